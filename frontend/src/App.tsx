@@ -62,6 +62,7 @@ import {
 import * as api from './lib/tauri';
 import { ForensicWorkspace } from './components/ForensicWorkspace';
 import { EduWorkspace } from './components/EduWorkspace';
+import { useDictation, DICTATION_HOLD_KEY } from './hooks/useDictation';
 import type { VaultStatus } from './lib/tauri';
 
 // ============================================
@@ -83,6 +84,11 @@ interface AppState {
   noteStartTime: string | null; // ISO timestamp when note editing started
 }
 
+/** Signed-in clinician, recorded as the editor of note revisions */
+function currentEditor(): string | undefined {
+  return localStorage.getItem('evidify_clinician_name') || undefined;
+}
+
 // ============================================
 // Beta Feature Showcase
 // ============================================
@@ -707,8 +713,8 @@ export default function App() {
           onViewNote={(note) => setState(s => ({ ...s, screen: 'done', currentNote: note }))}
           onReviewNote={async (note) => {
             try {
-              // Re-analyze for ethics issues
-              const ethics = await api.analyzeEthics(note.content);
+              // Re-analyze for ethics issues, anchoring detections to the saved note
+              const ethics = await api.analyzeNoteEthics(note.id);
               setState(s => ({ ...s, screen: 'review', currentNote: note, ethicsAnalysis: ethics }));
             } catch (err) {
               console.error('Failed to analyze note:', err);
@@ -1435,6 +1441,8 @@ function DashboardScreen({
   const [showNewClient, setShowNewClient] = useState(false);
   const [newClientName, setNewClientName] = useState('');
   const [creating, setCreating] = useState(false);
+  // One key per client being created, reused if the create is retried
+  const createClientKey = React.useRef(api.newIdempotencyKey());
   const [quickCaptureClient, setQuickCaptureClient] = useState<string>('');
   const [showSettings, setShowSettings] = useState(false);
   const [showShowcase, setShowShowcase] = useState(() => {
@@ -1452,12 +1460,17 @@ function DashboardScreen({
     if (!newClientName.trim()) return;
     setCreating(true);
     try {
-      await api.createClient(newClientName.trim());
+      await api.createClient(newClientName.trim(), createClientKey.current);
+      createClientKey.current = api.newIdempotencyKey();
       setNewClientName('');
       setShowNewClient(false);
       onRefresh();
     } catch (err) {
       console.error(err);
+      // The name changed after a create that did go through; the next try is a new client
+      if (String(err).includes(api.IDEMPOTENCY_KEY_REUSED)) {
+        createClientKey.current = api.newIdempotencyKey();
+      }
     }
     setCreating(false);
   }
@@ -1746,12 +1759,38 @@ function ClientDetailScreen({
     notes: client.notes || '',
   });
   const [saving, setSaving] = useState(false);
+  const [exportingBinder, setExportingBinder] = useState(false);
 
   async function handleRefresh() {
     setRefreshing(true);
     await onRefresh();
     setRefreshing(false);
   }
+
+  async function handleExportBinder() {
+    const outputPath = await api.pickSavePath(
+      `${client.display_name} audit binder.pdf`, 'PDF Documents', 'pdf'
+    );
+    if (!outputPath) return;
+    const request: api.BinderRequest = { scope: { kind: 'client', client_id: client.id } };
+    setExportingBinder(true);
+    try {
+      let summary: api.BinderSummary;
+      try {
+        summary = await api.exportAuditBinder(request, outputPath);
+      } catch (err) {
+        // Cloud-synced and removable destinations need an explicit override
+        if (!window.confirm(`${String(err)}\n\nExport to this location anyway?`)) return;
+        summary = await api.exportAuditBinder(request, outputPath, true);
+      }
+      alert(`Audit binder saved: ${summary.page_count} pages, ${summary.first_bates} to ${summary.last_bates}`);
+    } catch (err) {
+      console.error('Audit binder export failed:', err);
+      alert('Audit binder export failed: ' + String(err));
+    } finally {
+      setExportingBinder(false);
+    }
+  }
   
   async function handleSaveProfile() {
     setSaving(true);
@@ -1822,6 +1861,15 @@ function ClientDetailScreen({
             >
               {refreshing ? <Loader2 className="w-4 h-4 animate-spin" /> : '↻'}
             </button>
+            <button
+              onClick={handleExportBinder}
+              disabled={exportingBinder}
+              className="flex items-center gap-1 text-sm text-slate-400 hover:text-white disabled:opacity-50 transition-colors"
+              title="Export the chart as a print-ready audit binder (PDF)"
+            >
+              {exportingBinder ? <Loader2 className="w-4 h-4 animate-spin" /> : <FileText className="w-4 h-4" />}
+              Audit Binder
+            </button>
             <div className={`flex items-center gap-2 px-3 py-1 rounded-full text-sm ${
               ollamaStatus?.available ? 'bg-green-500/20 text-green-400' : 'bg-yellow-500/20 text-yellow-400'
             }`}>
@@ -2291,6 +2339,8 @@ function CaptureScreen({
   const [structuredContent, setStructuredContent] = useState<string | null>(null);
   const [isStructuring, setIsStructuring] = useState(false);
   const [noteType, setNoteType] = useState<api.NoteType>('progress');
+  // Reused when Generate is retried, so a lost response doesn't create the note twice
+  const createNoteKey = React.useRef(api.newIdempotencyKey());
   
   // Session timing (#17 - Time tracking)
   const [sessionStartTime] = useState(() => new Date().toISOString());
@@ -2378,23 +2428,24 @@ function CaptureScreen({
     try {
       // Get content with MSE/Risk appended
       const finalContent = getFinalContent();
-      
-      // Analyze ethics first
-      const ethics = await api.analyzeEthics(finalContent);
 
       // Create note - use structured content if available
       let note = await api.createNote(
         client?.id || 'demo',
         new Date().toISOString().split('T')[0],
         noteType,
-        finalContent
+        finalContent,
+        createNoteKey.current
       );
       
       // If we have structured content, save it to the database
       if (structuredContent) {
-        note = await api.updateStructuredNote(note.id, structuredContent);
+        note = await api.updateStructuredNote(note.id, structuredContent, currentEditor());
       }
       
+      // Analyze the saved note so its detections are anchored for signing
+      const ethics = await api.analyzeNoteEthics(note.id);
+      
       // Record time metrics (#17 - Time tracking for ProvenNote)
       try {
         const endTime = new Date().toISOString();
@@ -2416,6 +2467,10 @@ function CaptureScreen({
       onComplete(note, ethics);
     } catch (err) {
       console.error(err);
+      // Content changed after a create that did go through; the next try is a new note
+      if (String(err).includes(api.IDEMPOTENCY_KEY_REUSED)) {
+        createNoteKey.current = api.newIdempotencyKey();
+      }
       setGenerating(false);
     }
   }
@@ -2426,6 +2481,16 @@ function CaptureScreen({
     setShowVoice(false);
     setUsedVoice(true); // Track voice usage for metrics
   }
+
+  // Hold-to-talk / global hotkey dictation; the note has no id until generated
+  const dictation = useDictation({
+    onTranscript: (transcript) => {
+      if (!transcript.text.trim()) return;
+      setContent(prev => prev ? prev + '\n\n' + transcript.text : transcript.text);
+      setStructuredContent(null);
+      setUsedVoice(true);
+    },
+  });
   
   function handleUseStructured() {
     if (structuredContent) {
@@ -2515,12 +2580,24 @@ function CaptureScreen({
               setContent(e.target.value);
               setStructuredContent(null); // Clear structured on edit
             }}
+            onKeyDown={dictation.handleKeyDown}
+            onKeyUp={dictation.handleKeyUp}
             placeholder="Enter your session notes here. Use natural language - the system will structure it for you.
 
 Example: pt seems more anxious today, talked about work stress. not sleeping well. mentioned having some dark thoughts last week but says she's fine now. did cbt work on catastrophizing, assigned breathing exercises. f/u 2 weeks."
             className="w-full bg-transparent resize-none focus:outline-none min-h-[300px] text-lg"
             autoFocus
           />
+          <div className="flex items-center gap-2 text-xs text-slate-500 mt-2">
+            <Mic className={`w-3 h-3 ${dictation.phase === 'idle' ? '' : 'text-blue-400'}`} />
+            {dictation.phase === 'listening' && <span className="text-blue-400">Listening…</span>}
+            {dictation.phase === 'transcribing' && <span className="text-blue-400">Transcribing…</span>}
+            {dictation.phase === 'idle' && (
+              dictation.error
+                ? <span className="text-red-400">Dictation failed: {dictation.error}</span>
+                : <span>Hold {DICTATION_HOLD_KEY} to dictate</span>
+            )}
+          </div>
         </div>
         
         {/* Structured MSE Input */}
@@ -3192,7 +3269,7 @@ function DoneScreen({
     if (!editContent.trim()) return;
     setSaving(true);
     try {
-      const updated = await api.updateNoteContent(currentNote.id, editContent);
+      const updated = await api.updateNoteContent(currentNote.id, editContent, currentEditor());
       setCurrentNote(updated);
       setIsEditing(false);
     } catch (err) {
@@ -3207,7 +3284,7 @@ function DoneScreen({
     if (!amendmentText.trim() || !amendmentReason.trim()) return;
     setSaving(true);
     try {
-      const updated = await api.amendNote(currentNote.id, amendmentText, amendmentReason);
+      const updated = await api.amendNote(currentNote.id, amendmentText, amendmentReason, currentEditor());
       setCurrentNote(updated);
       setIsAmending(false);
       setAmendmentText('');
@@ -4906,7 +4983,11 @@ export function DocumentsPanel({
     setOcrProcessing(docId);
     try {
       const result = await api.processDocumentOcr(docId);
-      alert(result);
+      const confidence = result.mean_confidence === null ? 'no words recognized' : `${Math.round(result.mean_confidence)}% confidence`;
+      alert(
+        `OCR complete: ${result.word_count} words (${result.language}, ${confidence})` +
+        (result.low_confidence ? '\n\nLow confidence - check the text against the original scan.' : '')
+      );
       await loadDocuments(); // Refresh to show OCR status
     } catch (err) {
       console.error('OCR failed:', err);
@@ -5906,7 +5987,7 @@ export function CompletionCheckPanel({
       
       if (newContent !== currentContent) {
         // Update the note content
-        await api.updateNoteContent(noteId, newContent);
+        await api.updateNoteContent(noteId, newContent, currentEditor());
         setAppliedSuggestions(prev => new Set(prev).add(index));
         onSuggestionApplied(newContent);
       } else {
@@ -6482,8 +6563,7 @@ export function DeidentificationPanel({
     setExporting(true);
     setExportSuccess(null);
     try {
-      const data = await api.exportDeidentifiedCase(noteId, format, true);
-      const fileName = `deidentified_case.${format}`;
+      const exported = await api.exportDeidentifiedCase(noteId, format, true);
       
       const filterNames: Record<string, string> = {
         'txt': 'Text Files',
@@ -6491,8 +6571,11 @@ export function DeidentificationPanel({
         'pdf': 'PDF Documents'
       };
       
-      const saved = await api.saveFile(data, fileName, filterNames[format], format);
+      const saved = await api.saveFile(exported.document, exported.filename, filterNames[format], format);
       if (saved) {
+        // The certificate travels with the export so recipients can check it
+        const certificate = Array.from(new TextEncoder().encode(exported.certificate.content));
+        await api.saveFile(certificate, exported.certificate.filename, 'Certificates', 'json');
         setExportSuccess(format.toUpperCase());
         setTimeout(() => setExportSuccess(null), 3000);
       }
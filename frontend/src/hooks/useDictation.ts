@@ -0,0 +1,92 @@
+/**
+ * useDictation.ts
+ *
+ * React hook for in-process dictation into a note editor. Holding the
+ * dictation key captures audio; releasing it transcribes the clip. Transcripts
+ * from the global hotkey arrive through the same events.
+ *
+ * The backend emits `dictation-state` while capturing/transcribing and
+ * `dictation-transcript` with the text once a clip is transcribed.
+ */
+
+import { useState, useCallback, useEffect, useRef } from 'react';
+import type { KeyboardEvent } from 'react';
+import {
+  dictationKeyDown,
+  dictationKeyUp,
+  onDictationState,
+  onDictationTranscript,
+} from '../lib/tauri';
+import type { DictationPhase, DictationTranscript } from '../lib/tauri';
+
+/** Key held for hold-to-talk in the editor */
+export const DICTATION_HOLD_KEY = 'F8';
+
+/**
+ * Configuration options for the dictation hook.
+ */
+export interface UseDictationOptions {
+  /** Note being edited; undefined while the note has not been created yet */
+  noteId?: string;
+  /** Called with each transcript addressed to this editor */
+  onTranscript: (transcript: DictationTranscript) => void;
+}
+
+/**
+ * Return value of the dictation hook.
+ */
+export interface UseDictationReturn {
+  /** Current capture phase */
+  phase: DictationPhase;
+  /** Why the last dictation ended without a transcript */
+  error: string | null;
+  /** Attach to the editor's onKeyDown */
+  handleKeyDown: (e: KeyboardEvent) => void;
+  /** Attach to the editor's onKeyUp */
+  handleKeyUp: (e: KeyboardEvent) => void;
+}
+
+export function useDictation({ noteId, onTranscript }: UseDictationOptions): UseDictationReturn {
+  const [phase, setPhase] = useState<DictationPhase>('idle');
+  const [error, setError] = useState<string | null>(null);
+
+  // Keep the latest callback/note without re-subscribing on every render
+  const onTranscriptRef = useRef(onTranscript);
+  onTranscriptRef.current = onTranscript;
+  const noteIdRef = useRef(noteId);
+  noteIdRef.current = noteId;
+
+  useEffect(() => {
+    const unlisteners = [
+      onDictationState(status => {
+        setPhase(status.phase);
+        setError(status.error);
+      }),
+      onDictationTranscript(transcript => {
+        // A transcript without a note goes to whichever editor is open
+        if (transcript.note_id === null || transcript.note_id === noteIdRef.current) {
+          onTranscriptRef.current(transcript);
+        }
+      }),
+    ];
+    return () => {
+      unlisteners.forEach(unlisten => unlisten.then(fn => fn()));
+    };
+  }, []);
+
+  const handleKeyDown = useCallback((e: KeyboardEvent) => {
+    if (e.key !== DICTATION_HOLD_KEY) return;
+    e.preventDefault();
+    // Auto-repeat fires keydown while held; capture already started
+    if (e.repeat) return;
+    dictationKeyDown(noteIdRef.current).catch(err => setError(String(err)));
+  }, []);
+
+  const handleKeyUp = useCallback((e: KeyboardEvent) => {
+    if (e.key !== DICTATION_HOLD_KEY) return;
+    e.preventDefault();
+    dictationKeyUp().catch(err => setError(String(err)));
+  }, []);
+
+  return { phase, error, handleKeyDown, handleKeyUp };
+}
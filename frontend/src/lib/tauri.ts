@@ -4,6 +4,7 @@
 import { invoke } from '@tauri-apps/api/tauri';
 import { save } from '@tauri-apps/api/dialog';
 import { writeBinaryFile } from '@tauri-apps/api/fs';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
 
 // ============================================
 // Types
@@ -232,12 +233,30 @@ export async function vaultDeleteDb(): Promise<void> {
   return deleteVaultDb(true);
 }
 
+// ============================================
+// Idempotency
+// ============================================
+
+/** Error prefix returned when a key is reused for a different request */
+export const IDEMPOTENCY_KEY_REUSED = 'IDEMPOTENCY_KEY_REUSED';
+
+/**
+ * Key for one logical create/submit. Pass the same key when retrying that
+ * action so the backend replays the first result instead of running twice.
+ */
+export function newIdempotencyKey(): string {
+  return crypto.randomUUID();
+}
+
 // ============================================
 // Client API
 // ============================================
 
-export async function createClient(displayName: string): Promise<Client> {
-  return invoke('create_client', { displayName });
+export async function createClient(
+  displayName: string,
+  idempotencyKey: string = newIdempotencyKey()
+): Promise<Client> {
+  return invoke('create_client', { displayName, idempotencyKey });
 }
 
 export async function getClient(id: string): Promise<Client> {
@@ -260,9 +279,10 @@ export async function createNote(
   clientId: string,
   sessionDate: string,
   noteType: NoteType,
-  content: string
+  content: string,
+  idempotencyKey: string = newIdempotencyKey()
 ): Promise<Note> {
-  return invoke('create_note', { clientId, sessionDate, noteType, content });
+  return invoke('create_note', { clientId, sessionDate, noteType, content, idempotencyKey });
 }
 
 export async function getNote(id: string): Promise<Note> {
@@ -273,12 +293,12 @@ export async function listNotes(clientId?: string): Promise<Note[]> {
   return invoke('list_notes', { clientId });
 }
 
-export async function updateNote(id: string, content: string): Promise<Note> {
-  return invoke('update_note', { id, content });
+export async function updateNote(id: string, content: string, editor?: string): Promise<Note> {
+  return invoke('update_note', { id, content, editor });
 }
 
-export async function updateStructuredNote(id: string, structuredNote: string): Promise<Note> {
-  return invoke('update_structured_note', { id, structuredNote });
+export async function updateStructuredNote(id: string, structuredNote: string, editor?: string): Promise<Note> {
+  return invoke('update_structured_note', { id, structuredNote, editor });
 }
 
 export async function signNote(id: string, attestations: string): Promise<Note> {
@@ -297,6 +317,32 @@ export async function analyzeEthics(content: string): Promise<EthicsAnalysis> {
   return invoke('analyze_ethics', { content });
 }
 
+/**
+ * Analyze a saved note and anchor its detections to the analyzed text, so
+ * later edits carry them over or require re-analysis before signing
+ */
+export async function analyzeNoteEthics(noteId: string, lookbackDays?: number): Promise<EthicsAnalysis> {
+  return invoke('analyze_note_ethics', { noteId, lookbackDays });
+}
+
+export interface NoteDetectionState {
+  note_id: string;
+  detections: { id: string; pattern_id: string; severity: DetectionSeverity; match_start: number; match_end: number }[];
+  /** Hash of the note text the detections are anchored to */
+  content_hash: string;
+  /** An edit removed or rewrote flagged text; re-analyze before signing */
+  reanalyze_required: boolean;
+  /** Detection IDs dropped by edits since the last analysis */
+  invalidated: string[];
+  analyzed_at: number;
+  updated_at: number;
+}
+
+/** Anchored detections for a note, or null if it was never analyzed */
+export async function getNoteDetectionState(noteId: string): Promise<NoteDetectionState | null> {
+  return invoke('get_note_detection_state', { noteId });
+}
+
 // ============================================
 // AI API
 // ============================================
@@ -941,12 +987,12 @@ export async function getClientVisitCountSince(clientId: string, sinceDate: stri
 // Note Editing
 // ============================================
 
-export async function updateNoteContent(noteId: string, content: string): Promise<Note> {
-  return invoke('update_note_content', { noteId, content });
+export async function updateNoteContent(noteId: string, content: string, editor?: string): Promise<Note> {
+  return invoke('update_note_content', { noteId, content, editor });
 }
 
-export async function amendNote(noteId: string, amendmentText: string, reason: string): Promise<Note> {
-  return invoke('amend_note', { noteId, amendmentText, reason });
+export async function amendNote(noteId: string, amendmentText: string, reason: string, editor?: string): Promise<Note> {
+  return invoke('amend_note', { noteId, amendmentText, reason, editor });
 }
 
 // ============================================
@@ -1518,35 +1564,6 @@ export interface EhrTargetInfo {
   description: string;
 }
 
-export interface ExportableNote {
-  id: string;
-  client_id: string;
-  client_name: string;
-  session_date: string;
-  note_type: string;
-  content: string;
-  signed_at: string | null;
-  signed_by: string | null;
-  word_count: number;
-  amendments: EhrAmendment[];
-  attestations: EhrAttestationRecord[];
-}
-
-export interface EhrAmendment {
-  created_at: string;
-  reason: string;
-  content: string;
-  signed_by: string;
-}
-
-export interface EhrAttestationRecord {
-  detection_id: string;
-  detection_title: string;
-  response: string;
-  explanation: string | null;
-  attested_at: string;
-}
-
 export interface EhrExportResult {
   success: boolean;
   target: EhrTarget;
@@ -1562,16 +1579,17 @@ export async function getEhrTargets(): Promise<EhrTargetInfo[]> {
   return invoke('get_ehr_targets');
 }
 
-/** Export note to EHR format */
+/** Export a note to EHR format; the backend renders it from the vault */
 export async function exportToEhr(
-  note: ExportableNote,
+  noteId: string,
   target: EhrTarget,
   outputDir: string,
   includeAmendments: boolean,
-  includeSignature: boolean
+  includeSignature: boolean,
+  signedBy?: string
 ): Promise<EhrExportResult> {
   return invoke('export_to_ehr', { 
-    note, target, outputDir, includeAmendments, includeSignature 
+    noteId, target, outputDir, includeAmendments, includeSignature, signedBy 
   });
 }
 
@@ -1886,9 +1904,28 @@ export async function runDatabaseOptimization(): Promise<void> {
 // OCR Processing
 // ============================================
 
-/** Run OCR on a document and extract text */
-export async function processDocumentOcr(documentId: string): Promise<string> {
-  return invoke('process_document_ocr', { documentId });
+export type OcrProfile = 'printed' | 'handwriting';
+
+export interface OcrResult {
+  text: string;
+  /** Tesseract language string used for the final pass (e.g. "spa+eng") */
+  language: string;
+  detected_language: string | null;
+  profile: OcrProfile;
+  /** Mean word confidence 0-100; null when no words were recognized */
+  mean_confidence: number | null;
+  word_count: number;
+  low_confidence_words: number;
+  low_confidence: boolean;
+}
+
+/** Run OCR on a document and store its searchable text. Omit `languages` (or pass ["auto"]) to detect. */
+export async function processDocumentOcr(
+  documentId: string,
+  languages?: string[],
+  profile?: OcrProfile
+): Promise<OcrResult> {
+  return invoke('process_document_ocr', { documentId, languages, profile });
 }
 
 /** Check if Tesseract OCR is available */
@@ -2118,8 +2155,12 @@ export async function listTrainees(supervisorId: string): Promise<Trainee[]> {
 }
 
 /** Submit a note for supervisor review */
-export async function submitNoteForReview(noteId: string, traineeId: string): Promise<void> {
-  return invoke('submit_note_for_review', { noteId, traineeId });
+export async function submitNoteForReview(
+  noteId: string,
+  traineeId: string,
+  idempotencyKey: string = newIdempotencyKey()
+): Promise<void> {
+  return invoke('submit_note_for_review', { noteId, traineeId, idempotencyKey });
 }
 
 /** Get pending reviews for a supervisor */
@@ -2133,9 +2174,10 @@ export async function addReviewComment(
   supervisorId: string,
   commentType: string,
   text: string,
-  section?: string
+  section?: string,
+  idempotencyKey: string = newIdempotencyKey()
 ): Promise<ReviewComment> {
-  return invoke('add_review_comment', { noteId, supervisorId, commentType, text, section });
+  return invoke('add_review_comment', { noteId, supervisorId, commentType, text, section, idempotencyKey });
 }
 
 /** Complete a review */
@@ -2145,7 +2187,8 @@ export async function completeReview(
   status: 'approved' | 'needs_revision' | 'rejected',
   overallFeedback?: string,
   clinicalAccuracyScore?: number,
-  documentationQualityScore?: number
+  documentationQualityScore?: number,
+  idempotencyKey: string = newIdempotencyKey()
 ): Promise<SupervisorReview> {
   return invoke('complete_review', { 
     noteId, 
@@ -2153,7 +2196,8 @@ export async function completeReview(
     status, 
     overallFeedback,
     clinicalAccuracyScore,
-    documentationQualityScore
+    documentationQualityScore,
+    idempotencyKey
   });
 }
 
@@ -2236,6 +2280,18 @@ export interface DeidentificationAudit {
   exported_at: number | null;
 }
 
+export interface DeidentificationCertificateFile {
+  filename: string;
+  content: string;
+  certificate_sha256: string;
+}
+
+export interface DeidentifiedCaseExport {
+  filename: string;
+  document: number[];
+  certificate: DeidentificationCertificateFile;
+}
+
 export interface ConsultationDraft {
   id: string;
   title: string;
@@ -2284,10 +2340,18 @@ export async function exportDeidentifiedCase(
   noteId: string,
   format: 'pdf' | 'docx' | 'txt',
   includeAudit: boolean = true
-): Promise<number[]> {
+): Promise<DeidentifiedCaseExport> {
   return invoke('export_deidentified_case', { noteId, format, includeAudit });
 }
 
+/** Check a de-identification certificate; defaults to this install's key */
+export async function verifyDeidentificationCertificate(
+  certificateJson: string,
+  expectedFingerprint?: string
+): Promise<boolean> {
+  return invoke('verify_deidentification_certificate', { certificateJson, expectedFingerprint });
+}
+
 /** Create a consultation draft from a note */
 export async function createConsultationDraft(
   noteId: string,
@@ -2343,10 +2407,112 @@ export async function getTraineePendingReviews(traineeId: string): Promise<Pendi
   return invoke('get_trainee_pending_reviews', { traineeId });
 }
 
+// ============================================
+// Dictation (global hotkey / hold-to-talk)
+// ============================================
+
+export type DictationPhase = 'idle' | 'listening' | 'transcribing';
+export type DictationTrigger = 'hotkey' | 'hold';
+
+/** `dictation-state` event payload */
+export interface DictationStatus {
+  phase: DictationPhase;
+  trigger: DictationTrigger | null;
+  note_id: string | null;
+  error: string | null;
+}
+
+/** `dictation-transcript` event payload */
+export interface DictationTranscript {
+  note_id: string | null;
+  trigger: DictationTrigger;
+  text: string;
+  segments: TranscriptSegment[];
+  audio_ms: number;
+  risk_detected: string[];
+}
+
+/** Editor key down: start capturing for noteId (the focused note if omitted) */
+export async function dictationKeyDown(noteId?: string): Promise<void> {
+  return invoke('dictation_key_down', { noteId });
+}
+
+/** Editor key up: stop and transcribe; false if nothing was being captured */
+export async function dictationKeyUp(): Promise<boolean> {
+  return invoke('dictation_key_up');
+}
+
+export function onDictationState(callback: (status: DictationStatus) => void): Promise<UnlistenFn> {
+  return listen<DictationStatus>('dictation-state', event => callback(event.payload));
+}
+
+export function onDictationTranscript(
+  callback: (transcript: DictationTranscript) => void
+): Promise<UnlistenFn> {
+  return listen<DictationTranscript>('dictation-transcript', event => callback(event.payload));
+}
+
+// ============================================
+// Chart Audit Binder
+// ============================================
+
+export type BinderScope =
+  | { kind: 'client'; client_id: string }
+  | { kind: 'sample'; note_ids: string[] };
+
+export interface BinderRequest {
+  scope: BinderScope;
+  start_date?: string;
+  end_date?: string;
+  title?: string;
+  bates_prefix?: string;
+  first_bates_number?: number;
+}
+
+export interface BinderEntry {
+  tab: 'notes' | 'treatment_plans' | 'consents' | 'audit_trail';
+  record_id: string;
+  title: string;
+  page: number;
+  bates: string;
+}
+
+export interface BinderSummary {
+  id: string;
+  output_path: string;
+  page_count: number;
+  first_bates: string;
+  last_bates: string;
+  sha256: string;
+  entries: BinderEntry[];
+}
+
+/** Build the audit binder PDF at outputPath; the export is audited */
+export async function exportAuditBinder(
+  request: BinderRequest,
+  outputPath: string,
+  userOverride?: boolean
+): Promise<BinderSummary> {
+  return invoke('export_audit_binder', { request, outputPath, userOverride });
+}
+
 // ============================================
 // File Save Helper (uses Tauri native dialog)
 // ============================================
 
+/** Ask where to save a file the backend writes itself; null if cancelled */
+export async function pickSavePath(
+  defaultFileName: string,
+  filterName: string,
+  extension: string
+): Promise<string | null> {
+  const filePath = await save({
+    defaultPath: defaultFileName,
+    filters: [{ name: filterName, extensions: [extension] }]
+  });
+  return typeof filePath === 'string' ? filePath : null;
+}
+
 export async function saveFile(
   data: number[],
   defaultFileName: string,
@@ -0,0 +1,291 @@
+// Chart Access History - who/what touched one client's chart
+//
+// Built from the audit chain rather than a table of its own, so the
+// history carries the log's integrity guarantees and every event can be
+// checked against `verify_audit_chain` by its entry hash. An entry belongs
+// to a client's chart when its resource is:
+// - the client (chart access, edits, retrievals, prep sheets, holds)
+// - one of the client's notes
+// - an export traceable to the client: a note export, a client letter,
+//   or an EHR delivery of one of their notes
+//
+// Exports audited under a one-off id (encrypted de-identified bundles)
+// carry no link back to the client and are not listed. Entries for notes
+// that were hard-deleted can no longer be attributed either.
+
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::audit::{self, AuditError};
+use crate::models::{AuditEntry, AuditEventType, AuditOutcome, AuditResourceType};
+
+/// What an access did to the chart
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AccessCategory {
+    /// Chart opened, including reason-for-access and break-glass
+    Viewed,
+    /// Client or note created, edited, signed, trashed or purged
+    Modified,
+    /// Content left the vault, or an export of it was followed up
+    Exported,
+    /// Notes pulled into a search or AI context
+    Retrieved,
+    /// Pre-session prep sheet produced
+    PrepSheet,
+    /// Local AI analysis or ethics detection over the chart
+    Analyzed,
+    /// Retention, legal hold and authorship bookkeeping
+    Administrative,
+}
+
+impl AccessCategory {
+    pub fn of(event_type: AuditEventType) -> Self {
+        use AuditEventType::*;
+        match event_type {
+            ChartAccessed | EmergencyAccessActivated | EmergencyAccessRead
+            | EmergencyAccessReviewed => AccessCategory::Viewed,
+            NoteCreated | NoteUpdated | NoteSigned | NoteDeleted | NoteTrashed | NoteRestored
+            | ClientCreated | ClientUpdated | ClientTrashed | ClientRestored
+            | ClientPurged => AccessCategory::Modified,
+            NoteExported | ExportCreated | ExportPresenceChecked | ClientLetterExported
+            | EhrDeliveryQueued | EhrDeliverySent | EhrDeliveryFailed
+            | EhrDeliveryAcknowledged => AccessCategory::Exported,
+            NotesRetrieved | SearchExecuted => AccessCategory::Retrieved,
+            PrepSheetGenerated => AccessCategory::PrepSheet,
+            AiAnalysisRun | EthicsDetectionTriggered | EthicsDetectionResolved
+            | FormulationGenerated => AccessCategory::Analyzed,
+            _ => AccessCategory::Administrative,
+        }
+    }
+}
+
+/// Whether an entry records chart content actually leaving the vault,
+/// i.e. belongs in an accounting of disclosures
+pub fn is_disclosure(entry: &AuditEntry) -> bool {
+    matches!(entry.outcome, AuditOutcome::Success)
+        && matches!(
+            entry.event_type,
+            AuditEventType::NoteExported
+                | AuditEventType::ExportCreated
+                | AuditEventType::ClientLetterExported
+                | AuditEventType::EhrDeliverySent
+        )
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChartAccessEvent {
+    pub sequence: i64,
+    /// Unix ms
+    pub timestamp: i64,
+    pub category: AccessCategory,
+    pub event_type: AuditEventType,
+    pub resource_type: AuditResourceType,
+    pub resource_id: String,
+    pub outcome: AuditOutcome,
+    /// The entry's path class: access reason, export destination class,
+    /// retrieval kind, EHR target...
+    pub detail: Option<String>,
+    pub disclosure: bool,
+    /// For checking the event against the verified chain
+    pub entry_hash: String,
+}
+
+impl From<AuditEntry> for ChartAccessEvent {
+    fn from(entry: AuditEntry) -> Self {
+        ChartAccessEvent {
+            disclosure: is_disclosure(&entry),
+            category: AccessCategory::of(entry.event_type),
+            sequence: entry.sequence,
+            timestamp: entry.timestamp,
+            event_type: entry.event_type,
+            resource_type: entry.resource_type,
+            resource_id: entry.resource_id,
+            outcome: entry.outcome,
+            detail: entry.path_class,
+            entry_hash: entry.entry_hash,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChartAccessHistory {
+    pub client_id: String,
+    /// Unix ms bounds the history was limited to, if any
+    pub since: Option<i64>,
+    pub until: Option<i64>,
+    /// Oldest first
+    pub events: Vec<ChartAccessEvent>,
+    pub disclosure_count: usize,
+}
+
+/// Audit entries touching `client_id`'s chart with timestamps in
+/// [since, until], oldest first
+pub fn chart_entries(
+    conn: &Connection,
+    client_id: &str,
+    since: Option<i64>,
+    until: Option<i64>,
+) -> Result<Vec<AuditEntry>, AuditError> {
+    let mut stmt = conn.prepare(
+        "SELECT id, timestamp, sequence, event_type, resource_type, resource_id,
+         outcome, detection_ids, path_class, path_hash, previous_hash, entry_hash
+         FROM audit_log
+         WHERE (?2 IS NULL OR timestamp >= ?2)
+           AND (?3 IS NULL OR timestamp <= ?3)
+           AND (
+                (resource_type = 'client' AND resource_id = ?1)
+             OR (resource_type IN ('note', 'export')
+                 AND resource_id IN (SELECT id FROM notes WHERE client_id = ?1))
+             OR (resource_type = 'export' AND (
+                    resource_id IN (SELECT id FROM client_letters WHERE client_id = ?1)
+                 OR resource_id IN (SELECT d.id FROM ehr_deliveries d
+                                    JOIN notes n ON n.id = d.note_id
+                                    WHERE n.client_id = ?1)))
+           )
+         ORDER BY sequence ASC"
+    )?;
+    let rows = stmt.query_map(params![client_id, since, until], audit::map_entry_row)?;
+    rows.collect::<Result<Vec<_>, _>>().map_err(AuditError::from)
+}
+
+pub fn chart_access_history(
+    conn: &Connection,
+    client_id: &str,
+    since: Option<i64>,
+    until: Option<i64>,
+) -> Result<ChartAccessHistory, AuditError> {
+    let events: Vec<ChartAccessEvent> = chart_entries(conn, client_id, since, until)?
+        .into_iter()
+        .map(ChartAccessEvent::from)
+        .collect();
+    Ok(ChartAccessHistory {
+        client_id: client_id.to_string(),
+        since,
+        until,
+        disclosure_count: events.iter().filter(|e| e.disclosure).count(),
+        events,
+    })
+}
+
+/// Audit a retrieval of `note_ids` once per client whose notes were pulled,
+/// so a cross-client search shows up in every chart it touched
+pub fn log_retrieval(conn: &Connection, kind: &str, note_ids: &[&str]) -> Result<(), AuditError> {
+    let distinct: BTreeSet<&str> = note_ids.iter().copied().collect();
+    let mut stmt = conn.prepare("SELECT client_id FROM notes WHERE id = ?1")?;
+    let mut per_client: BTreeMap<String, usize> = BTreeMap::new();
+    for note_id in distinct {
+        let client_id: String = match stmt.query_row([note_id], |row| row.get(0)) {
+            Ok(id) => id,
+            Err(rusqlite::Error::QueryReturnedNoRows) => continue,
+            Err(e) => return Err(e.into()),
+        };
+        *per_client.entry(client_id).or_default() += 1;
+    }
+    for (client_id, count) in per_client {
+        audit::log_notes_retrieved(conn, &client_id, kind, count)?;
+    }
+    Ok(())
+}
+
+/// Audit results that carry only a client id (global search hits on
+/// clients, documents and drafts), once per client with its hit count
+pub fn log_client_retrieval(conn: &Connection, kind: &str, client_ids: &[&str]) -> Result<(), AuditError> {
+    let mut per_client: BTreeMap<&str, usize> = BTreeMap::new();
+    for client_id in client_ids {
+        *per_client.entry(client_id).or_default() += 1;
+    }
+    for (client_id, count) in per_client {
+        audit::log_notes_retrieved(conn, client_id, kind, count)?;
+    }
+    Ok(())
+}
+
+// ============================================
+// Tauri Commands
+// ============================================
+
+use tauri::State;
+use crate::commands::AppState;
+
+/// Everything that touched a client's chart, for an accounting of
+/// disclosures or a "who opened this" review. Bounds are Unix ms. Viewing
+/// the history is itself audited, after it is read.
+#[tauri::command]
+pub fn get_chart_access_history(
+    state: State<AppState>,
+    policy_state: State<'_, crate::policy::PolicyState>,
+    client_id: String,
+    since: Option<i64>,
+    until: Option<i64>,
+) -> Result<ChartAccessHistory, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.get_client(&client_id).map_err(|e| format!("{}", e))?;
+    crate::commands::ensure_chart_access(&vault, &policy_state, &client_id)?;
+    let conn = vault.get_connection().map_err(|e| format!("{}", e))?;
+    let history = chart_access_history(conn, &client_id, since, until).map_err(|e| format!("{}", e))?;
+    audit::log_notes_retrieved(conn, &client_id, "access_history", history.events.len())
+        .map_err(|e| format!("Audit write failed: {}", e))?;
+    Ok(history)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chart_access_history() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE audit_log (id TEXT PRIMARY KEY, timestamp INTEGER NOT NULL, sequence INTEGER NOT NULL,
+                 event_type TEXT NOT NULL, resource_type TEXT NOT NULL, resource_id TEXT NOT NULL,
+                 outcome TEXT NOT NULL, detection_ids TEXT, path_class TEXT, path_hash TEXT,
+                 previous_hash TEXT NOT NULL, entry_hash TEXT NOT NULL);
+             CREATE TABLE notes (id TEXT PRIMARY KEY, client_id TEXT NOT NULL);
+             CREATE TABLE client_letters (id TEXT PRIMARY KEY, client_id TEXT NOT NULL);
+             CREATE TABLE ehr_deliveries (id TEXT PRIMARY KEY, note_id TEXT NOT NULL);
+             INSERT INTO notes VALUES ('n1', 'c1'), ('n2', 'c1'), ('n3', 'c2');
+             INSERT INTO client_letters VALUES ('l1', 'c1');
+             INSERT INTO ehr_deliveries VALUES ('d1', 'n2'), ('d2', 'n3');",
+        ).unwrap();
+
+        use crate::models::AccessReason;
+        audit::log_chart_access(&conn, "c1", AccessReason::ContinuityOfCare, None).unwrap();
+        audit::log_event(&conn, AuditEventType::NoteUpdated, AuditResourceType::Note, "n1", AuditOutcome::Success, None).unwrap();
+        audit::log_export_event(&conn, "n1", AuditOutcome::Success, "safe", "hash").unwrap();
+        audit::log_client_letter_export(&conn, "l1", "r1", "hash").unwrap();
+        audit::log_ehr_delivery(&conn, AuditEventType::EhrDeliveryFailed, "d1", AuditOutcome::Failure, "epic", 1, None).unwrap();
+        audit::log_ehr_delivery(&conn, AuditEventType::EhrDeliverySent, "d2", AuditOutcome::Success, "epic", 1, None).unwrap();
+        audit::log_prep_sheet(&conn, "c1", false).unwrap();
+        log_retrieval(&conn, "rag_query", &["n1", "n2", "n3", "n1", "gone"]).unwrap();
+
+        let history = chart_access_history(&conn, "c1", None, None).unwrap();
+        let categories: Vec<AccessCategory> = history.events.iter().map(|e| e.category).collect();
+        assert_eq!(categories, [
+            AccessCategory::Viewed,
+            AccessCategory::Modified,
+            AccessCategory::Exported,
+            AccessCategory::Exported,
+            AccessCategory::Exported,
+            AccessCategory::PrepSheet,
+            AccessCategory::Retrieved,
+        ]);
+        assert_eq!(history.disclosure_count, 2);
+        assert_eq!(history.events[6].detail.as_deref(), Some("retrieval:rag_query:2"));
+
+        // The other client sees only its own delivery and retrieval
+        let other = chart_access_history(&conn, "c2", None, None).unwrap();
+        assert_eq!(other.events.len(), 2);
+        assert_eq!(other.disclosure_count, 1);
+        assert_eq!(other.events[1].detail.as_deref(), Some("retrieval:rag_query:1"));
+
+        let first = history.events[0].timestamp;
+        assert!(chart_access_history(&conn, "c1", None, Some(first - 1)).unwrap().events.is_empty());
+        assert_eq!(chart_access_history(&conn, "c1", Some(first), None).unwrap().events.len(), 7);
+
+        log_client_retrieval(&conn, "global_search", &["c2", "c2"]).unwrap();
+        let other = chart_access_history(&conn, "c2", None, None).unwrap();
+        assert_eq!(other.events.last().unwrap().detail.as_deref(), Some("retrieval:global_search:2"));
+    }
+}
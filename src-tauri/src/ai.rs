@@ -14,9 +14,9 @@
 // - ONLY localhost/127.0.0.1 connections allowed
 // - URL validation prevents accidental external connections
 
-use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
+use std::collections::HashMap;
 use std::net::IpAddr;
 
 use crate::models::{OllamaStatus, NoteType};
@@ -105,20 +105,14 @@ pub async fn check_status() -> OllamaStatus {
         };
     }
     
-    let client = match Client::builder()
+    let client = crate::performance::HTTP_CLIENT.get();
+    
+    match client
+        .get(format!("{}/api/tags", OLLAMA_BASE_URL))
         .timeout(std::time::Duration::from_secs(5))
-        .build() 
+        .send()
+        .await
     {
-        Ok(c) => c,
-        Err(e) => return OllamaStatus {
-            available: false,
-            authenticated: false,  // Always false - we don't authenticate
-            models: vec![],
-            error: Some(e.to_string()),
-        },
-    };
-    
-    match client.get(format!("{}/api/tags", OLLAMA_BASE_URL)).send().await {
         Ok(response) => {
             if let Ok(tags) = response.json::<TagsResponse>().await {
                 let models: Vec<String> = tags.models
@@ -187,10 +181,7 @@ struct GenerateResponse {
 
 /// Generic Ollama call for arbitrary prompts
 pub async fn call_ollama(model: &str, prompt: &str) -> Result<String, AIError> {
-    let client = Client::builder()
-        .timeout(std::time::Duration::from_secs(120))
-        .build()
-        .map_err(|e| AIError::RequestFailed(e.to_string()))?;
+    let client = crate::performance::HTTP_CLIENT.get();
     
     let request = GenerateRequest {
         model: model.to_string(),
@@ -205,6 +196,7 @@ pub async fn call_ollama(model: &str, prompt: &str) -> Result<String, AIError> {
     
     let response = client
         .post(format!("{}/api/generate", OLLAMA_BASE_URL))
+        .timeout(std::time::Duration::from_secs(120))
         .json(&request)
         .send()
         .await
@@ -237,10 +229,7 @@ pub async fn structure_note(
     // Build prompt with PHI (local processing only)
     let prompt = build_structuring_prompt(raw_input, note_type);
     
-    let client = Client::builder()
-        .timeout(std::time::Duration::from_secs(120))
-        .build()
-        .map_err(|e| AIError::RequestFailed(e.to_string()))?;
+    let client = crate::performance::HTTP_CLIENT.get();
     
     let request = GenerateRequest {
         model: model.to_string(),
@@ -258,6 +247,7 @@ pub async fn structure_note(
     
     let response = client
         .post(format!("{}/api/generate", OLLAMA_BASE_URL))
+        .timeout(std::time::Duration::from_secs(120))
         .json(&request)
         .send()
         .await
@@ -414,6 +404,7 @@ RULES:
 6. Maintain clinical terminology as written
 7. Use the exact section headers provided below
 8. For Risk Assessment: Default to "Denied" only if explicitly stated; otherwise "Not assessed"
+9. Copy bracketed placeholders such as [PERSON_1] or [DATE_2] exactly as written
 
 SESSION NOTE:
 {input}
@@ -429,6 +420,33 @@ STRUCTURED OUTPUT:
     )
 }
 
+/// Version of `completion_check_prompt`; bump it when the prompt changes so
+/// cached checks made with the old wording are not reused
+pub const COMPLETION_CHECK_PROMPT_VERSION: &str = "1";
+
+/// Prompt for the AI note completion check (JSON response expected)
+pub fn completion_check_prompt(note_content: &str) -> String {
+    format!(r#"Analyze this clinical progress note for completeness and quality. Check for:
+1. Missing required fields (subjective, objective, assessment, plan)
+2. Vague or ambiguous language
+3. Compliance issues (missing dates, unclear interventions, no diagnosis reference)
+
+Return a JSON object with:
+{{
+  "is_complete": boolean,
+  "overall_score": 0.0-1.0,
+  "missing_fields": [{{"field_name": "string", "importance": "required|recommended|optional", "description": "string"}}],
+  "vague_sections": [{{"section": "string", "problematic_text": "string", "suggestion": "string"}}],
+  "compliance_issues": [{{"issue_type": "string", "description": "string", "severity": "warning|error"}}],
+  "suggestions": ["string"]
+}}
+
+Note content:
+{}
+
+Return ONLY the JSON object, no other text."#, note_content)
+}
+
 /// Generate clinical formulation from multiple notes
 pub async fn generate_formulation(
     model: &str,
@@ -442,10 +460,7 @@ pub async fn generate_formulation(
     
     let prompt = build_formulation_prompt(notes, formulation_type);
     
-    let client = Client::builder()
-        .timeout(std::time::Duration::from_secs(180))
-        .build()
-        .map_err(|e| AIError::RequestFailed(e.to_string()))?;
+    let client = crate::performance::HTTP_CLIENT.get();
     
     let request = GenerateRequest {
         model: model.to_string(),
@@ -462,6 +477,7 @@ pub async fn generate_formulation(
     
     let response = client
         .post(format!("{}/api/generate", OLLAMA_BASE_URL))
+        .timeout(std::time::Duration::from_secs(180))
         .json(&request)
         .send()
         .await
@@ -529,6 +545,173 @@ FORMULATION:
     )
 }
 
+// ============================================
+// Prompt Scrubbing (PHI guardrail)
+// ============================================
+//
+// Even a local model should see no more PHI than the task needs. When the
+// policy enables it, direct identifiers are swapped for stable placeholders
+// ([PERSON_1], [DATE_1], [ID_1]) before the prompt is built, and swapped back
+// in the model output. The mapping lives only in memory for the one call.
+
+/// Which identifier classes to pseudonymize
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct ScrubOptions {
+    pub names: bool,
+    pub dates: bool,
+    /// Phone, email, SSN, record/account numbers, addresses, URLs, etc.
+    pub identifiers: bool,
+}
+
+impl From<&crate::policy::AiPolicy> for ScrubOptions {
+    fn from(policy: &crate::policy::AiPolicy) -> Self {
+        ScrubOptions {
+            names: policy.scrub_names,
+            dates: policy.scrub_dates,
+            identifiers: policy.scrub_identifiers,
+        }
+    }
+}
+
+/// Placeholder -> original text. Holds PHI: never serialized or logged.
+#[derive(Debug, Default)]
+pub struct ScrubMapping {
+    entries: Vec<(String, String)>,
+}
+
+impl ScrubMapping {
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+    
+    /// Substitute originals back; returns the text and how many placeholders were restored
+    pub fn restore(&self, text: &str) -> (String, usize) {
+        let mut restored = text.to_string();
+        let mut count = 0;
+        for (placeholder, original) in &self.entries {
+            let hits = restored.matches(placeholder.as_str()).count();
+            if hits > 0 {
+                restored = restored.replace(placeholder.as_str(), original);
+                count += hits;
+            }
+        }
+        (restored, count)
+    }
+}
+
+/// Provenance of an AI-generated draft (no PHI: counts and settings only)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AiProvenance {
+    pub model: String,
+    pub prompt_scrubbed: bool,
+    pub scrub_options: Option<ScrubOptions>,
+    /// Identifiers pseudonymized, by Safe Harbor category code
+    pub scrubbed_counts: HashMap<String, usize>,
+    pub placeholders_restored: usize,
+    /// Placeholders the model dropped or mangled (originals not re-inserted)
+    pub placeholders_missing: usize,
+    pub policy_version: String,
+    pub generated_at: String,
+}
+
+fn placeholder_label(category: &crate::deidentify::IdentifierCategory) -> &'static str {
+    use crate::deidentify::IdentifierCategory;
+    match category {
+        IdentifierCategory::Name => "PERSON",
+        IdentifierCategory::Date => "DATE",
+        _ => "ID",
+    }
+}
+
+/// Replace selected identifiers with placeholders. The same original text
+/// always maps to the same placeholder so the model can track references.
+pub fn scrub_prompt(text: &str, options: &ScrubOptions) -> (String, ScrubMapping, HashMap<String, usize>) {
+    use crate::deidentify::{DeidentificationEngine, IdentifierCategory};
+    
+    let detected = DeidentificationEngine::new(false, None).deidentify(text).identifiers_found;
+    
+    let mut mapping = ScrubMapping::default();
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    let mut next_index: HashMap<&'static str, usize> = HashMap::new();
+    let mut scrubbed = String::with_capacity(text.len());
+    let mut cursor = 0;
+    
+    // identifiers_found is sorted by position and non-overlapping
+    for id in detected {
+        let selected = match id.category {
+            IdentifierCategory::Name => options.names,
+            IdentifierCategory::Date => options.dates,
+            _ => options.identifiers,
+        };
+        if !selected || id.start_pos < cursor || text.get(id.start_pos..id.end_pos).is_none() {
+            continue;
+        }
+        
+        let original = &text[id.start_pos..id.end_pos];
+        let placeholder = match mapping.entries.iter().find(|(_, o)| o == original) {
+            Some((p, _)) => p.clone(),
+            None => {
+                let label = placeholder_label(&id.category);
+                let n = next_index.entry(label).or_insert(0);
+                *n += 1;
+                let p = format!("[{}_{}]", label, n);
+                mapping.entries.push((p.clone(), original.to_string()));
+                p
+            }
+        };
+        
+        scrubbed.push_str(&text[cursor..id.start_pos]);
+        scrubbed.push_str(&placeholder);
+        cursor = id.end_pos;
+        *counts.entry(id.category.code().to_string()).or_insert(0) += 1;
+    }
+    scrubbed.push_str(&text[cursor..]);
+    
+    // Longest placeholders first so [PERSON_1] never clobbers part of [PERSON_10]
+    mapping.entries.sort_by_key(|(p, _)| std::cmp::Reverse(p.len()));
+    
+    (scrubbed, mapping, counts)
+}
+
+/// Structure a note, pseudonymizing the input first when `scrub` is set
+pub async fn structure_note_guarded(
+    model: &str,
+    raw_input: &str,
+    note_type: NoteType,
+    scrub: Option<ScrubOptions>,
+    policy_version: &str,
+) -> Result<(String, AiProvenance), AIError> {
+    let (input, mapping, scrubbed_counts) = match &scrub {
+        Some(options) => scrub_prompt(raw_input, options),
+        None => (raw_input.to_string(), ScrubMapping::default(), HashMap::new()),
+    };
+    
+    if !mapping.is_empty() {
+        log::info!("AI prompt scrubbed: {} distinct identifiers pseudonymized", mapping.len());
+    }
+    
+    let output = structure_note(model, &input, note_type).await?;
+    let (structured, placeholders_restored) = mapping.restore(&output);
+    let placeholders_missing = mapping.entries.iter()
+        .filter(|(p, _)| !output.contains(p.as_str()))
+        .count();
+    
+    Ok((structured, AiProvenance {
+        model: model.to_string(),
+        prompt_scrubbed: scrub.is_some(),
+        scrub_options: scrub,
+        scrubbed_counts,
+        placeholders_restored,
+        placeholders_missing,
+        policy_version: policy_version.to_string(),
+        generated_at: chrono::Utc::now().to_rfc3339(),
+    }))
+}
+
 // ============================================
 // Embeddings (local, no Ollama needed)
 // ============================================
@@ -551,10 +734,7 @@ pub async fn generate_answer(
         return Err(AIError::ModelNotAllowed(model.to_string()));
     }
     
-    let client = Client::builder()
-        .timeout(std::time::Duration::from_secs(120))
-        .build()
-        .map_err(|e| AIError::RequestFailed(e.to_string()))?;
+    let client = crate::performance::HTTP_CLIENT.get();
     
     let request = GenerateRequest {
         model: model.to_string(),
@@ -571,6 +751,7 @@ pub async fn generate_answer(
     
     let response = client
         .post(format!("{}/api/generate", OLLAMA_BASE_URL))
+        .timeout(std::time::Duration::from_secs(120))
         .json(&request)
         .send()
         .await
@@ -607,3 +788,33 @@ pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
     
     dot / (norm_a * norm_b)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_scrub_and_restore_round_trip() {
+        let options = ScrubOptions { names: true, dates: true, identifiers: true };
+        let text = "Mr. Alvarez, phone 555-123-4567, seen 01/15/2024. Follow up with Mr. Alvarez.";
+        let (scrubbed, mapping, counts) = scrub_prompt(text, &options);
+        
+        assert!(!scrubbed.contains("Alvarez"));
+        assert!(!scrubbed.contains("555-123-4567"));
+        assert!(!scrubbed.contains("01/15/2024"));
+        assert_eq!(scrubbed.matches("[PERSON_1]").count(), 2);
+        assert_eq!(counts.get("A"), Some(&2));
+        
+        let (restored, count) = mapping.restore(&scrubbed);
+        assert_eq!(restored, text);
+        assert_eq!(count, 4);
+    }
+    
+    #[test]
+    fn test_scrub_respects_options() {
+        let options = ScrubOptions { names: false, dates: true, identifiers: false };
+        let (scrubbed, _, _) = scrub_prompt("Mr. Alvarez, seen 01/15/2024", &options);
+        assert!(scrubbed.contains("Alvarez"));
+        assert!(scrubbed.contains("[DATE_1]"));
+    }
+}
@@ -0,0 +1,308 @@
+// AI Evaluation Harness
+//
+// Runs the structuring and completion-check pipelines over a bundled
+// corpus of synthetic notes (no PHI) and scores the output against the
+// sections and gaps each case is known to contain. A model or prompt
+// change is gated by comparing its report with the previous one.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::models::{CompletionCheckResult, NoteType};
+
+/// Synthetic corpus compiled into the binary
+const CORPUS_JSON: &str = include_str!("../eval/synthetic_notes.json");
+
+// ============================================
+// Corpus
+// ============================================
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct EvalCorpus {
+    pub version: String,
+    pub cases: Vec<EvalCase>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct EvalCase {
+    pub id: String,
+    pub note_type: String,
+    pub raw_input: String,
+    /// Section header -> phrases that must appear in that section
+    pub expected_sections: BTreeMap<String, Vec<String>>,
+    /// Fields a completion check should report as missing
+    #[serde(default)]
+    pub expected_missing: Vec<String>,
+}
+
+pub fn load_corpus() -> Result<EvalCorpus, String> {
+    serde_json::from_str(CORPUS_JSON).map_err(|e| format!("Invalid evaluation corpus: {}", e))
+}
+
+// ============================================
+// Scoring
+// ============================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CaseScore {
+    pub case_id: String,
+    /// Expected section headers present in the output (0.0 - 1.0)
+    pub section_recall: f32,
+    /// Expected phrases found in the right section (0.0 - 1.0)
+    pub phrase_recall: f32,
+    /// Completion check flagged exactly the expected gaps (required fields only)
+    pub completion_correct: bool,
+    /// Headers or phrases that were not found, for triage
+    pub misses: Vec<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct EvalThresholds {
+    pub min_section_recall: f32,
+    pub min_phrase_recall: f32,
+    pub min_completion_accuracy: f32,
+    /// Allowed drop against the baseline before it counts as a regression
+    pub regression_tolerance: f32,
+}
+
+impl Default for EvalThresholds {
+    fn default() -> Self {
+        Self {
+            min_section_recall: 0.9,
+            min_phrase_recall: 0.75,
+            min_completion_accuracy: 0.75,
+            regression_tolerance: 0.05,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvalReport {
+    pub model: String,
+    pub corpus_version: String,
+    pub run_at: String,
+    pub cases: Vec<CaseScore>,
+    pub section_recall: f32,
+    pub phrase_recall: f32,
+    pub completion_accuracy: f32,
+    /// Threshold failures and regressions against the baseline
+    pub failures: Vec<String>,
+    pub passed: bool,
+}
+
+/// Split structured output into (HEADER, body) pairs on `**HEADER:**` lines
+pub fn extract_sections(output: &str) -> Vec<(String, String)> {
+    let mut sections: Vec<(String, String)> = Vec::new();
+    for line in output.lines() {
+        let trimmed = line.trim();
+        let header = trimmed
+            .strip_prefix("**")
+            .and_then(|rest| rest.split_once(":**").or_else(|| rest.split_once("**")))
+            .map(|(name, after)| (name.trim_end_matches(':').trim().to_uppercase(), after.trim()));
+
+        match header {
+            Some((name, after)) if !name.is_empty() => sections.push((name, after.to_string())),
+            _ => {
+                if let Some((_, body)) = sections.last_mut() {
+                    body.push('\n');
+                    body.push_str(trimmed);
+                }
+            }
+        }
+    }
+    sections
+}
+
+fn ratio(hit: usize, total: usize) -> f32 {
+    if total == 0 { 1.0 } else { hit as f32 / total as f32 }
+}
+
+/// Score one case from the structuring output and the parsed completion check
+pub fn score_case(case: &EvalCase, structured: &str, completion: Option<&CompletionCheckResult>) -> CaseScore {
+    let sections = extract_sections(structured);
+    let mut misses = Vec::new();
+    let (mut headers_found, mut phrases_found, mut phrase_total) = (0, 0, 0);
+
+    for (header, phrases) in &case.expected_sections {
+        let body = sections.iter()
+            .find(|(name, _)| name.starts_with(&header.to_uppercase()))
+            .map(|(_, body)| body.to_lowercase());
+        phrase_total += phrases.len();
+
+        let Some(body) = body else {
+            misses.push(format!("section {}", header));
+            continue;
+        };
+        headers_found += 1;
+        for phrase in phrases {
+            if body.contains(&phrase.to_lowercase()) {
+                phrases_found += 1;
+            } else {
+                misses.push(format!("{}: {}", header, phrase));
+            }
+        }
+    }
+
+    let completion_correct = completion.map(|result| {
+        let flagged: Vec<String> = result.missing_fields.iter()
+            .filter(|f| f.importance == "required")
+            .map(|f| f.field_name.to_lowercase())
+            .collect();
+        let expected_flagged = case.expected_missing.iter()
+            .all(|e| flagged.iter().any(|f| f.contains(&e.to_lowercase())));
+        let no_false_alarm = !case.expected_missing.is_empty() || flagged.is_empty();
+        expected_flagged && no_false_alarm
+    }).unwrap_or(false);
+
+    CaseScore {
+        case_id: case.id.clone(),
+        section_recall: ratio(headers_found, case.expected_sections.len()),
+        phrase_recall: ratio(phrases_found, phrase_total),
+        completion_correct,
+        misses,
+        error: None,
+    }
+}
+
+/// Aggregate case scores and apply thresholds and the baseline gate
+pub fn build_report(
+    model: &str,
+    corpus_version: &str,
+    cases: Vec<CaseScore>,
+    thresholds: &EvalThresholds,
+    baseline: Option<&EvalReport>,
+) -> EvalReport {
+    let n = cases.len().max(1) as f32;
+    let section_recall = cases.iter().map(|c| c.section_recall).sum::<f32>() / n;
+    let phrase_recall = cases.iter().map(|c| c.phrase_recall).sum::<f32>() / n;
+    let completion_accuracy = cases.iter().filter(|c| c.completion_correct).count() as f32 / n;
+
+    let mut failures = Vec::new();
+    let metrics = [
+        ("section recall", section_recall, thresholds.min_section_recall, baseline.map(|b| b.section_recall)),
+        ("phrase recall", phrase_recall, thresholds.min_phrase_recall, baseline.map(|b| b.phrase_recall)),
+        ("completion accuracy", completion_accuracy, thresholds.min_completion_accuracy, baseline.map(|b| b.completion_accuracy)),
+    ];
+    for (name, value, minimum, previous) in metrics {
+        if value < minimum {
+            failures.push(format!("{} {:.2} is below the minimum {:.2}", name, value, minimum));
+        }
+        if let Some(previous) = previous {
+            if previous - value > thresholds.regression_tolerance {
+                failures.push(format!("{} regressed from {:.2} to {:.2}", name, previous, value));
+            }
+        }
+    }
+    for case in cases.iter().filter(|c| c.error.is_some()) {
+        failures.push(format!("case {} failed to run", case.case_id));
+    }
+
+    EvalReport {
+        model: model.to_string(),
+        corpus_version: corpus_version.to_string(),
+        run_at: chrono::Utc::now().to_rfc3339(),
+        passed: failures.is_empty(),
+        cases,
+        section_recall,
+        phrase_recall,
+        completion_accuracy,
+        failures,
+    }
+}
+
+// ============================================
+// Runner
+// ============================================
+
+/// Run every corpus case through the live pipelines
+pub async fn run_evaluation(
+    model: &str,
+    thresholds: &EvalThresholds,
+    baseline: Option<&EvalReport>,
+) -> Result<EvalReport, String> {
+    let corpus = load_corpus()?;
+    let mut scores = Vec::new();
+
+    for case in &corpus.cases {
+        let note_type = NoteType::from_str(&case.note_type);
+        let structured = match crate::ai::structure_note(model, &case.raw_input, note_type).await {
+            Ok(output) => output,
+            Err(e) => {
+                let mut score = score_case(case, "", None);
+                score.error = Some(e.to_string());
+                scores.push(score);
+                continue;
+            }
+        };
+
+        let completion = crate::ai::call_ollama(model, &crate::ai::completion_check_prompt(&case.raw_input))
+            .await
+            .ok()
+            .and_then(|response| serde_json::from_str::<CompletionCheckResult>(&response).ok());
+
+        scores.push(score_case(case, &structured, completion.as_ref()));
+    }
+
+    Ok(build_report(model, &corpus.version, scores, thresholds, baseline))
+}
+
+// ============================================
+// Tauri Commands
+// ============================================
+
+/// Internal: evaluate a model/prompt combination against the synthetic
+/// corpus. Pass the previous report as `baseline` to gate on regressions.
+#[tauri::command]
+pub async fn run_ai_evaluation(
+    model: String,
+    thresholds: Option<EvalThresholds>,
+    baseline: Option<EvalReport>,
+) -> Result<EvalReport, String> {
+    run_evaluation(&model, &thresholds.unwrap_or_default(), baseline.as_ref()).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::MissingField;
+
+    #[test]
+    fn test_corpus_loads() {
+        let corpus = load_corpus().unwrap();
+        assert!(!corpus.cases.is_empty());
+        assert!(corpus.cases.iter().all(|c| !c.expected_sections.is_empty()));
+    }
+
+    #[test]
+    fn test_score_case_and_regression_gate() {
+        let case = load_corpus().unwrap().cases.into_iter()
+            .find(|c| c.id == "progress-missing-plan")
+            .unwrap();
+        let structured = "**SUBJECTIVE:**\nHard week after argument with sister.\n\n**RISK ASSESSMENT:**\n- Suicidal Ideation: Passive ideation\n\n**PLAN:**\nNot documented";
+        let completion = CompletionCheckResult {
+            is_complete: false,
+            overall_score: 0.5,
+            missing_fields: ["Plan", "Assessment"].iter().map(|f| MissingField {
+                field_name: f.to_string(),
+                importance: "required".to_string(),
+                description: String::new(),
+            }).collect(),
+            vague_sections: vec![],
+            compliance_issues: vec![],
+            suggestions: vec![],
+        };
+
+        let score = score_case(&case, structured, Some(&completion));
+        assert!((score.section_recall - 2.0 / 3.0).abs() < 1e-6);
+        assert!(score.completion_correct);
+        assert_eq!(score.misses, vec!["section INTERVENTIONS".to_string()]);
+
+        let thresholds = EvalThresholds { min_section_recall: 0.5, min_phrase_recall: 0.5, ..Default::default() };
+        let baseline = build_report("m", "v", vec![CaseScore { section_recall: 1.0, ..score.clone() }], &thresholds, None);
+        assert!(baseline.passed);
+        let report = build_report("m", "v", vec![score], &thresholds, Some(&baseline));
+        assert!(!report.passed);
+        assert!(report.failures.iter().any(|f| f.starts_with("section recall regressed")));
+    }
+}
@@ -9,6 +9,7 @@
 // 2. Inconsistency detectors (structured-to-structured)
 // 3. Trajectory summaries (symptom/treatment trends)
 // 4. Hypothesis engine (differential suggestions)
+// 5. Early-warning rules (thresholds defined in policy)
 //
 // CRITICAL: All outputs must include evidence_fields[] for traceability.
 // LLM (if used) only for phrasing, never for adding facts.
@@ -164,6 +165,9 @@ pub struct TelehealthContext {
 pub struct PatientFeatureStore {
     pub client_id: String,
     pub sessions: Vec<SessionFeatures>,
+    /// Dates (YYYY-MM-DD) of scheduled sessions the client missed
+    #[serde(default)]
+    pub missed_sessions: Vec<String>,
     pub updated_at: i64,
 }
 
@@ -192,6 +196,7 @@ impl PatientFeatureStore {
         PatientFeatureStore {
             client_id: client_id.to_string(),
             sessions: Vec::new(),
+            missed_sessions: Vec::new(),
             updated_at: Utc::now().timestamp_millis(),
         }
     }
@@ -209,6 +214,24 @@ impl PatientFeatureStore {
         self.updated_at = Utc::now().timestamp_millis();
     }
     
+    /// Record a missed (no-show or late-cancelled) session
+    pub fn record_missed_session(&mut self, session_date: &str) {
+        if !self.missed_sessions.iter().any(|d| d == session_date) {
+            self.missed_sessions.push(session_date.to_string());
+            self.missed_sessions.sort();
+        }
+        self.updated_at = Utc::now().timestamp_millis();
+    }
+    
+    /// Missed sessions after the last attended one
+    pub fn missed_session_streak(&self) -> Vec<&str> {
+        let last_attended = self.sessions.iter().map(|s| s.session_date.as_str()).max().unwrap_or("");
+        self.missed_sessions.iter()
+            .map(|d| d.as_str())
+            .filter(|d| *d > last_attended)
+            .collect()
+    }
+    
     /// Get sessions in time window (last N sessions)
     pub fn last_n_sessions(&self, n: usize) -> &[SessionFeatures] {
         let start = self.sessions.len().saturating_sub(n);
@@ -925,6 +948,154 @@ pub fn run_deep_analysis(store: &PatientFeatureStore) -> DeepAnalysisResult {
     }
 }
 
+// ============================================
+// Early-Warning Rules
+// ============================================
+
+use crate::policy::{AlertSeverity, EarlyWarningCondition, EarlyWarningPolicy, EarlyWarningRule};
+
+/// A fired early-warning rule, for the dashboard and prep sheet
+#[derive(Debug, Clone, Serialize)]
+pub struct EarlyWarningAlert {
+    pub alert_id: String,
+    pub client_id: String,
+    pub rule_id: String,
+    pub title: String,
+    pub severity: AlertSeverity,
+    pub description: String,
+    pub suggested_action: Option<String>,
+    /// Session date (or missed date) that triggered the alert
+    pub triggered_on: String,
+    pub evidence: EvidenceTrail,
+}
+
+impl EarlyWarningAlert {
+    /// Prep-sheet form of the alert
+    pub fn to_safety_alert(&self) -> crate::models::SafetyAlert {
+        crate::models::SafetyAlert {
+            alert_type: self.rule_id.clone(),
+            last_flagged: self.triggered_on.clone(),
+            severity: match self.severity {
+                AlertSeverity::Info => "low",
+                AlertSeverity::Warning => "moderate",
+                AlertSeverity::Critical => "high",
+            }.to_string(),
+            details: format!("{}: {}", self.title, self.description),
+        }
+    }
+}
+
+/// "PHQ-9", "phq9" and "PHQ 9" name the same measure
+fn measure_key(name: &str) -> String {
+    name.chars().filter(|c| c.is_ascii_alphanumeric()).collect::<String>().to_ascii_lowercase()
+}
+
+/// Scores of one measure in session order, with the scoring session
+fn measure_series<'a>(sessions: &'a [SessionFeatures], measure: &str) -> Vec<(&'a SessionFeatures, i32)> {
+    let key = measure_key(measure);
+    sessions.iter()
+        .filter_map(|s| {
+            s.measure_scores.iter()
+                .find(|(name, _)| measure_key(name) == key)
+                .map(|(_, score)| (s, *score))
+        })
+        .collect()
+}
+
+/// Evaluate one rule; Some when it fires on the store's current state
+fn evaluate_rule(store: &PatientFeatureStore, rule: &EarlyWarningRule) -> Option<EarlyWarningAlert> {
+    let sessions = &store.sessions;
+    let (description, triggered_on, evidence_fields, source_session_ids, time_window) = match &rule.condition {
+        EarlyWarningCondition::MeasureIncrease { measure, min_increase, window_sessions } => {
+            let series = measure_series(sessions, measure);
+            let (latest, latest_score) = *series.last()?;
+            let window = &series[series.len() - 1 - (*window_sessions).min(series.len() - 1)..series.len() - 1];
+            let (baseline, baseline_score) = *window.iter().min_by_key(|(_, score)| *score)?;
+            if latest_score - baseline_score < *min_increase {
+                return None;
+            }
+            (
+                format!("{} rose from {} ({}) to {} ({})", measure, baseline_score, baseline.session_date, latest_score, latest.session_date),
+                latest.session_date.clone(),
+                vec![format!("measure_scores.{}", measure)],
+                vec![baseline.note_id.clone(), latest.note_id.clone()],
+                Some(format!("last {} scored sessions", window.len() + 1)),
+            )
+        }
+        EarlyWarningCondition::MeasureAtLeast { measure, threshold } => {
+            let (latest, score) = *measure_series(sessions, measure).last()?;
+            if score < *threshold {
+                return None;
+            }
+            (
+                format!("{} of {} on {} is at or above {}", measure, score, latest.session_date, threshold),
+                latest.session_date.clone(),
+                vec![format!("measure_scores.{}", measure)],
+                vec![latest.note_id.clone()],
+                None,
+            )
+        }
+        EarlyWarningCondition::ConsecutiveRiskSessions { sessions: count, risk_types } => {
+            if *count == 0 || sessions.len() < *count {
+                return None;
+            }
+            let recent = &sessions[sessions.len() - count..];
+            let flagged = |s: &SessionFeatures| s.risk_types_present.iter()
+                .any(|r| risk_types.is_empty() || risk_types.contains(r));
+            if !recent.iter().all(flagged) {
+                return None;
+            }
+            (
+                format!("Risk flagged in each of the last {} sessions", count),
+                recent.last()?.session_date.clone(),
+                vec!["risk_types_present".to_string()],
+                recent.iter().map(|s| s.note_id.clone()).collect(),
+                Some(format!("last {} sessions", count)),
+            )
+        }
+        EarlyWarningCondition::MissedSessionStreak { min_streak } => {
+            let streak = store.missed_session_streak();
+            if streak.is_empty() || streak.len() < *min_streak {
+                return None;
+            }
+            (
+                format!("{} missed sessions since the last attended session", streak.len()),
+                streak.last()?.to_string(),
+                vec!["missed_sessions".to_string()],
+                vec![],
+                Some(format!("since {}", streak[0])),
+            )
+        }
+    };
+    
+    Some(EarlyWarningAlert {
+        alert_id: format!("{}-{}-{}", rule.id, store.client_id, triggered_on),
+        client_id: store.client_id.clone(),
+        rule_id: rule.id.clone(),
+        title: rule.title.clone(),
+        severity: rule.severity,
+        description,
+        suggested_action: rule.suggested_action.clone(),
+        triggered_on,
+        evidence: EvidenceTrail {
+            evidence_fields,
+            source_session_ids,
+            rule_ids: vec![rule.id.clone()],
+            time_window,
+        },
+    })
+}
+
+/// Alerts from every enabled rule in `policy`, most severe first
+pub fn evaluate_early_warnings(store: &PatientFeatureStore, policy: &EarlyWarningPolicy) -> Vec<EarlyWarningAlert> {
+    let mut alerts: Vec<_> = policy.rules.iter()
+        .filter(|rule| rule.enabled)
+        .filter_map(|rule| evaluate_rule(store, rule))
+        .collect();
+    alerts.sort_by(|a, b| b.severity.cmp(&a.severity));
+    alerts
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -981,4 +1152,37 @@ mod tests {
         let findings = detect_inconsistencies(&store);
         assert!(findings.iter().any(|f| f.finding_id.contains("INC-001")));
     }
+    
+    #[test]
+    fn test_default_early_warning_rules() {
+        let session = |n: usize, phq9: i32, risk: bool| SessionFeatures {
+            note_id: format!("note-{}", n),
+            session_date: format!("2026-01-{:02}", n),
+            timestamp: n as i64,
+            symptom_domains: vec![],
+            symptom_severities: HashMap::new(),
+            interventions_used: vec![],
+            homework_assigned: false,
+            risk_types_present: if risk { vec![RiskType::SelfHarm] } else { vec![] },
+            highest_risk_level: None,
+            diagnoses: vec![],
+            measure_scores: HashMap::from([("PHQ9".to_string(), phq9)]),
+            is_telehealth: false,
+            patient_state: None,
+        };
+        let policy = EarlyWarningPolicy::default();
+        let mut store = PatientFeatureStore::new("c1");
+        store.sessions = vec![session(1, 12, false), session(2, 8, false), session(3, 12, true)];
+        assert!(evaluate_early_warnings(&store, &policy).is_empty());
+        
+        store.sessions.push(session(4, 13, true));
+        store.record_missed_session("2026-01-02");
+        store.record_missed_session("2026-01-11");
+        store.record_missed_session("2026-01-18");
+        let alerts = evaluate_early_warnings(&store, &policy);
+        let ids: Vec<&str> = alerts.iter().map(|a| a.rule_id.as_str()).collect();
+        assert_eq!(ids, vec!["EW-002", "EW-001", "EW-003"]);
+        assert_eq!(alerts[1].evidence.source_session_ids, vec!["note-2", "note-4"]);
+        assert_eq!(alerts[2].description, "2 missed sessions since the last attended session");
+    }
 }
@@ -39,6 +39,8 @@ pub struct QuickPickOption {
     pub label: String,
     pub description: String,
     pub requires_note: bool,
+    /// Fill-in template; the rendered text becomes the response note
+    pub template: Option<QuickPickTemplate>,
 }
 
 /// Attestation request from frontend
@@ -87,24 +89,28 @@ pub fn get_quick_picks(category: &Category, severity: &Severity) -> Vec<QuickPic
             label: "Addressed in Note".to_string(),
             description: "This issue is documented and addressed in the session note".to_string(),
             requires_note: false,
+            template: None,
         },
         QuickPickOption {
             response: AttestationResponse::NotClinicallyRelevant,
             label: "Not Clinically Relevant".to_string(),
             description: "After review, this is not clinically significant in this context".to_string(),
             requires_note: true,
+            template: None,
         },
         QuickPickOption {
             response: AttestationResponse::WillAddressNextSession,
             label: "Will Address Next Session".to_string(),
             description: "Flagged for follow-up in the next scheduled session".to_string(),
             requires_note: false,
+            template: None,
         },
         QuickPickOption {
             response: AttestationResponse::ConsultedSupervisor,
             label: "Consulted Supervisor".to_string(),
             description: "Discussed with clinical supervisor for guidance".to_string(),
             requires_note: true,
+            template: None,
         },
     ];
     
@@ -116,6 +122,7 @@ pub fn get_quick_picks(category: &Category, severity: &Severity) -> Vec<QuickPic
                 label: "Safety Plan Completed".to_string(),
                 description: "Safety assessment conducted and plan documented".to_string(),
                 requires_note: false,
+                template: None,
             });
         }
         Category::HomicidalIdeation => {
@@ -124,6 +131,7 @@ pub fn get_quick_picks(category: &Category, severity: &Severity) -> Vec<QuickPic
                 label: "Duty to Warn Assessed".to_string(),
                 description: "Tarasoff duty evaluated and appropriate action taken".to_string(),
                 requires_note: true,
+                template: None,
             });
         }
         Category::ChildAbuse | Category::ElderAbuse => {
@@ -132,6 +140,7 @@ pub fn get_quick_picks(category: &Category, severity: &Severity) -> Vec<QuickPic
                 label: "Mandated Report Filed".to_string(),
                 description: "Required report submitted to appropriate agency".to_string(),
                 requires_note: true,
+                template: None,
             });
         }
         _ => {}
@@ -145,6 +154,245 @@ pub fn get_quick_picks(category: &Category, severity: &Severity) -> Vec<QuickPic
     options
 }
 
+// ============================================
+// Quick-Pick Templates
+// ============================================
+//
+// Templated quick picks carry required fill-ins ("Safety plan reviewed;
+// next review on {next_review}") so the attestation records specifics
+// rather than a generic phrase. Templates are configured per category in
+// the attestation policy; the values are validated before the rendered
+// text is accepted as the response note. Where a category has templates
+// for a response, a response note for it must be one of them, filled in.
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "options", rename_all = "snake_case")]
+pub enum TemplateFieldKind {
+    Text,
+    /// YYYY-MM-DD, today or earlier (assessed on, filed on)
+    Date,
+    /// YYYY-MM-DD, today or later (next review, follow-up)
+    FutureDate,
+    Number,
+    Choice(Vec<String>),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateField {
+    /// Placeholder name, used as `{name}` in the template
+    pub name: String,
+    pub label: String,
+    pub kind: TemplateFieldKind,
+    #[serde(default = "default_true")]
+    pub required: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuickPickTemplate {
+    pub id: String,
+    pub category: Category,
+    pub response: AttestationResponse,
+    pub label: String,
+    pub template: String,
+    pub fields: Vec<TemplateField>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TemplateFieldError {
+    pub field: String,
+    pub message: String,
+}
+
+lazy_static::lazy_static! {
+    static ref PLACEHOLDER: regex::Regex = regex::Regex::new(r"\{([a-z][a-z0-9_]*)\}").unwrap();
+}
+
+fn template(id: &str, category: Category, label: &str, text: &str, fields: Vec<TemplateField>) -> QuickPickTemplate {
+    QuickPickTemplate {
+        id: id.to_string(),
+        category,
+        response: AttestationResponse::AddressedInNote,
+        label: label.to_string(),
+        template: text.to_string(),
+        fields,
+    }
+}
+
+fn field(name: &str, label: &str, kind: TemplateFieldKind) -> TemplateField {
+    TemplateField { name: name.to_string(), label: label.to_string(), kind, required: true }
+}
+
+/// Templates shipped in the default attestation policy
+pub fn default_quick_pick_templates() -> Vec<QuickPickTemplate> {
+    let safety_plan = |id: &str, category| template(
+        id, category,
+        "Safety Plan Reviewed",
+        "Safety plan reviewed with client; means restriction discussed ({means}); next review on {next_review}.",
+        vec![
+            field("means", "Means restriction", TemplateFieldKind::Choice(vec![
+                "agreed".to_string(), "declined".to_string(), "not applicable".to_string(),
+            ])),
+            field("next_review", "Next review", TemplateFieldKind::FutureDate),
+        ],
+    );
+    vec![
+        safety_plan("si-safety-plan", Category::SuicidalIdeation),
+        safety_plan("self-harm-safety-plan", Category::SelfHarm),
+        template(
+            "hi-duty-to-warn", Category::HomicidalIdeation,
+            "Duty to Warn Assessed",
+            "Duty to warn assessed on {assessed_on}; outcome: {outcome}.",
+            vec![
+                field("assessed_on", "Assessed on", TemplateFieldKind::Date),
+                field("outcome", "Outcome", TemplateFieldKind::Choice(vec![
+                    "no identifiable victim".to_string(),
+                    "victim notified".to_string(),
+                    "law enforcement notified".to_string(),
+                ])),
+            ],
+        ),
+        template(
+            "child-abuse-report", Category::ChildAbuse,
+            "Mandated Report Filed",
+            "Report filed with {agency} on {filed_on}; reference {reference}.",
+            vec![
+                field("agency", "Agency", TemplateFieldKind::Text),
+                field("filed_on", "Filed on", TemplateFieldKind::Date),
+                field("reference", "Report reference", TemplateFieldKind::Text),
+            ],
+        ),
+        template(
+            "elder-abuse-report", Category::ElderAbuse,
+            "Mandated Report Filed",
+            "Report filed with {agency} on {filed_on}; reference {reference}.",
+            vec![
+                field("agency", "Agency", TemplateFieldKind::Text),
+                field("filed_on", "Filed on", TemplateFieldKind::Date),
+                field("reference", "Report reference", TemplateFieldKind::Text),
+            ],
+        ),
+    ]
+}
+
+/// Static quick picks with the category's templates first
+pub fn get_quick_picks_with_templates(
+    category: &Category,
+    severity: &Severity,
+    templates: &[QuickPickTemplate],
+) -> Vec<QuickPickOption> {
+    let mut options: Vec<QuickPickOption> = templates.iter()
+        .filter(|t| t.category == *category)
+        .map(|t| QuickPickOption {
+            response: t.response.clone(),
+            label: t.label.clone(),
+            description: t.template.clone(),
+            requires_note: true,
+            template: Some(t.clone()),
+        })
+        .collect();
+    options.extend(get_quick_picks(category, severity));
+    options
+}
+
+/// Validate `values` against the template's fields and render the note
+/// text. Every failing field is reported, not just the first.
+pub fn render_quick_pick(
+    template: &QuickPickTemplate,
+    values: &HashMap<String, String>,
+    today: chrono::NaiveDate,
+) -> Result<String, Vec<TemplateFieldError>> {
+    let mut errors = Vec::new();
+    let mut error = |field: &TemplateField, message: &str| errors.push(TemplateFieldError {
+        field: field.name.clone(),
+        message: format!("{} {}", field.label, message),
+    });
+
+    for field in &template.fields {
+        let value = values.get(&field.name).map(|v| v.trim()).unwrap_or("");
+        if value.is_empty() {
+            if field.required {
+                error(field, "is required");
+            }
+            continue;
+        }
+        match &field.kind {
+            TemplateFieldKind::Text => {}
+            TemplateFieldKind::Date | TemplateFieldKind::FutureDate => {
+                match chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+                    Err(_) => error(field, "must be a date (YYYY-MM-DD)"),
+                    Ok(date) if field.kind == TemplateFieldKind::FutureDate && date < today => {
+                        error(field, "must be today or later")
+                    }
+                    Ok(date) if field.kind == TemplateFieldKind::Date && date > today => {
+                        error(field, "cannot be in the future")
+                    }
+                    Ok(_) => {}
+                }
+            }
+            TemplateFieldKind::Number => {
+                if value.parse::<f64>().is_err() {
+                    error(field, "must be a number");
+                }
+            }
+            TemplateFieldKind::Choice(options) => {
+                if !options.iter().any(|o| o.eq_ignore_ascii_case(value)) {
+                    error(field, &format!("must be one of: {}", options.join(", ")));
+                }
+            }
+        }
+    }
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    let rendered = PLACEHOLDER.replace_all(&template.template, |caps: &regex::Captures| {
+        values.get(&caps[1]).map(|v| v.trim().to_string()).unwrap_or_default()
+    });
+    Ok(rendered.into_owned())
+}
+
+/// Whether `note` is `template` rendered from valid values
+pub fn fills_template(template: &QuickPickTemplate, note: &str, today: chrono::NaiveDate) -> bool {
+    // Known tokens become captures; everything else must match literally
+    let mut pattern = String::from("(?s)^");
+    let mut names = Vec::new();
+    let mut last = 0;
+    for caps in PLACEHOLDER.captures_iter(&template.template) {
+        let (Some(token), Some(name)) = (caps.get(0), caps.get(1)) else { continue };
+        if !template.fields.iter().any(|f| f.name == name.as_str()) {
+            continue;
+        }
+        pattern.push_str(&regex::escape(&template.template[last..token.start()]));
+        pattern.push_str("(.*?)");
+        names.push(name.as_str().to_string());
+        last = token.end();
+    }
+    pattern.push_str(&regex::escape(&template.template[last..]));
+    pattern.push('$');
+    
+    let Some(caps) = regex::Regex::new(&pattern).ok().and_then(|re| re.captures(note.trim())) else {
+        return false;
+    };
+    let values: HashMap<String, String> = names.into_iter()
+        .enumerate()
+        .map(|(i, name)| (name, caps.get(i + 1).map(|m| m.as_str().to_string()).unwrap_or_default()))
+        .collect();
+    render_quick_pick(template, &values, today).map(|text| text == note.trim()).unwrap_or(false)
+}
+
+/// Template `{field}` tokens left in a response note, i.e. a template
+/// accepted without filling it in. Other braces are ordinary text.
+pub fn unfilled_placeholders(text: &str, templates: &[QuickPickTemplate]) -> Vec<String> {
+    PLACEHOLDER.captures_iter(text)
+        .map(|c| c[1].to_string())
+        .filter(|name| templates.iter().any(|t| t.fields.iter().any(|f| &f.name == name)))
+        .collect()
+}
+
 // ============================================
 // Detection Consolidation
 // ============================================
@@ -306,18 +554,10 @@ fn build_attestation_summary(attestations: &[Attestation]) -> String {
     let mut summary = format!("{} attestation(s) recorded:\n", attestations.len());
     
     for att in attestations {
-        let response_str = match att.response {
-            AttestationResponse::AddressedInNote => "Addressed in note",
-            AttestationResponse::NotClinicallyRelevant => "Not clinically relevant",
-            AttestationResponse::WillAddressNextSession => "Will address next session",
-            AttestationResponse::ConsultedSupervisor => "Consulted supervisor",
-            AttestationResponse::DocumentedElsewhere => "Documented elsewhere",
-        };
-        
         summary.push_str(&format!(
             "- {}: {} ({})\n",
             att.detection_id,
-            response_str,
+            att.response.label(),
             chrono::DateTime::from_timestamp_millis(att.attested_at)
                 .map(|dt| dt.format("%Y-%m-%d %H:%M").to_string())
                 .unwrap_or_default()
@@ -340,6 +580,8 @@ pub fn validate_attestation(
     detection: &Detection,
     response: &AttestationResponse,
     response_note: &Option<String>,
+    templates: &[QuickPickTemplate],
+    today: chrono::NaiveDate,
 ) -> Result<(), String> {
     // Critical detections cannot use "not clinically relevant"
     if detection.severity == Severity::Critical {
@@ -348,6 +590,21 @@ pub fn validate_attestation(
         }
     }
     
+    if let Some(note) = response_note.as_deref().filter(|n| !n.trim().is_empty()) {
+        let unfilled = unfilled_placeholders(note, templates);
+        if !unfilled.is_empty() {
+            return Err(format!("Response note has unfilled template fields: {}", unfilled.join(", ")));
+        }
+        
+        let applicable: Vec<&QuickPickTemplate> = templates.iter()
+            .filter(|t| t.category == detection.category && t.response == *response)
+            .collect();
+        if !applicable.is_empty() && !applicable.iter().any(|t| fills_template(t, note, today)) {
+            let labels: Vec<&str> = applicable.iter().map(|t| t.label.as_str()).collect();
+            return Err(format!("Response note must use a quick pick template: {}", labels.join(", ")));
+        }
+    }
+    
     // Some responses require notes
     match response {
         AttestationResponse::NotClinicallyRelevant => {
@@ -437,6 +694,61 @@ mod tests {
         assert_eq!(groups.len(), 2);
     }
     
+    #[test]
+    fn test_quick_pick_template_rendering() {
+        let today = chrono::NaiveDate::from_ymd_opt(2024, 6, 1).unwrap();
+        let templates = default_quick_pick_templates();
+        let plan = templates.iter().find(|t| t.id == "si-safety-plan").unwrap();
+        
+        let picks = get_quick_picks_with_templates(&Category::SuicidalIdeation, &Severity::High, &templates);
+        assert_eq!(picks[0].template.as_ref().map(|t| t.id.as_str()), Some("si-safety-plan"));
+        
+        let mut values = HashMap::new();
+        values.insert("means".to_string(), "maybe".to_string());
+        values.insert("next_review".to_string(), "2024-05-01".to_string());
+        let errors = render_quick_pick(plan, &values, today).unwrap_err();
+        assert_eq!(errors.len(), 2);
+        
+        values.insert("means".to_string(), "Agreed".to_string());
+        values.insert("next_review".to_string(), "2024-06-15".to_string());
+        let text = render_quick_pick(plan, &values, today).unwrap();
+        assert_eq!(text, "Safety plan reviewed with client; means restriction discussed (Agreed); next review on 2024-06-15.");
+        assert!(unfilled_placeholders(&text, &templates).is_empty());
+        assert_eq!(unfilled_placeholders(&plan.template, &templates), vec!["means", "next_review"]);
+        assert!(unfilled_placeholders("Client uses {braces} in email", &templates).is_empty());
+        
+        // Report dates are in the past
+        let report = templates.iter().find(|t| t.id == "child-abuse-report").unwrap();
+        let mut values = HashMap::new();
+        values.insert("agency".to_string(), "County CPS".to_string());
+        values.insert("filed_on".to_string(), "2024-06-02".to_string());
+        values.insert("reference".to_string(), "R-1".to_string());
+        let errors = render_quick_pick(report, &values, today).unwrap_err();
+        assert_eq!(errors[0].field, "filed_on");
+    }
+    
+    #[test]
+    fn test_templated_category_rejects_free_text() {
+        let today = chrono::NaiveDate::from_ymd_opt(2024, 6, 1).unwrap();
+        let templates = default_quick_pick_templates();
+        let detection = mock_detection(Category::SuicidalIdeation, Severity::High);
+        let validate = |note: &str| validate_attestation(
+            &detection, &AttestationResponse::AddressedInNote, &Some(note.to_string()), &templates, today,
+        );
+        
+        assert!(validate("Talked about it.").is_err());
+        assert!(validate("Safety plan reviewed with client; means restriction discussed (agreed); next review on 2024-05-01.").is_err());
+        assert!(validate("Safety plan reviewed with client; means restriction discussed (agreed); next review on 2024-06-15.").is_ok());
+        
+        // Documented in the note body itself
+        assert!(validate_attestation(&detection, &AttestationResponse::AddressedInNote, &None, &templates, today).is_ok());
+        
+        // No template for this response: free text is the documentation
+        assert!(validate_attestation(
+            &detection, &AttestationResponse::ConsultedSupervisor, &Some("Discussed with Dr. Lee {urgent}".to_string()), &templates, today,
+        ).is_ok());
+    }
+    
     #[test]
     fn test_quick_picks() {
         let picks = get_quick_picks(&Category::SuicidalIdeation, &Severity::Critical);
@@ -453,6 +765,8 @@ mod tests {
             &detection,
             &AttestationResponse::NotClinicallyRelevant,
             &None,
+            &[],
+            chrono::Utc::now().date_naive(),
         );
         assert!(result.is_err());
         
@@ -461,6 +775,8 @@ mod tests {
             &detection,
             &AttestationResponse::AddressedInNote,
             &None,
+            &[],
+            chrono::Utc::now().date_naive(),
         );
         assert!(result.is_ok());
     }
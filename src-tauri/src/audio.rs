@@ -0,0 +1,453 @@
+// Audio Device Module
+//
+// Input device enumeration and live level metering for the pre-session
+// mic check. Levels are computed from the raw sample stream and only the
+// RMS/peak numbers leave this module - no samples are buffered, written
+// to disk or forwarded to the UI.
+//
+// Short captures for push-to-dictate (see dictation.rs) are buffered in
+// memory only and handed straight to the transcriber.
+
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::{FromSample, Sample, SizedSample};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use tauri::State;
+use thiserror::Error;
+
+/// Event name for streamed level readings
+pub const INPUT_LEVEL_EVENT: &str = "audio-input-level";
+
+/// How often a level reading is emitted to the UI
+const LEVEL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Monitoring stops on its own after this long if the UI never calls stop
+const MAX_MONITOR_DURATION: Duration = Duration::from_secs(120);
+
+/// Floor for dBFS so silence reports a finite number
+const MIN_DBFS: f32 = -96.0;
+
+#[derive(Error, Debug)]
+pub enum AudioError {
+    #[error("No input device available")]
+    NoInputDevice,
+
+    #[error("Input device not found: {0}")]
+    DeviceNotFound(String),
+
+    #[error("Unsupported sample format: {0}")]
+    UnsupportedFormat(String),
+
+    #[error("Audio device error: {0}")]
+    Device(String),
+}
+
+// ============================================
+// Devices
+// ============================================
+
+/// Input device as presented in the mic picker
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AudioDevice {
+    /// Stable within a session; cpal exposes no persistent id, so this is the device name
+    pub id: String,
+    pub name: String,
+    pub is_default: bool,
+    pub sample_rate: Option<u32>,
+    pub channels: Option<u16>,
+}
+
+/// Enumerate input devices on the default host
+pub fn list_input_devices() -> Result<Vec<AudioDevice>, AudioError> {
+    let host = cpal::default_host();
+    let default_name = host.default_input_device().and_then(|d| d.name().ok());
+
+    let devices = host
+        .input_devices()
+        .map_err(|e| AudioError::Device(e.to_string()))?;
+
+    let mut result = Vec::new();
+    for device in devices {
+        let name = match device.name() {
+            Ok(name) => name,
+            Err(e) => {
+                log::warn!("Skipping unnamed input device: {}", e);
+                continue;
+            }
+        };
+        let config = device.default_input_config().ok();
+
+        result.push(AudioDevice {
+            id: name.clone(),
+            is_default: default_name.as_deref() == Some(name.as_str()),
+            name,
+            sample_rate: config.as_ref().map(|c| c.sample_rate().0),
+            channels: config.as_ref().map(|c| c.channels()),
+        });
+    }
+
+    Ok(result)
+}
+
+/// Resolve a device id (or the default device when none is given)
+fn find_input_device(device_id: Option<&str>) -> Result<cpal::Device, AudioError> {
+    let host = cpal::default_host();
+
+    match device_id {
+        None => host.default_input_device().ok_or(AudioError::NoInputDevice),
+        Some(id) => host
+            .input_devices()
+            .map_err(|e| AudioError::Device(e.to_string()))?
+            .find(|d| d.name().map(|n| n == id).unwrap_or(false))
+            .ok_or_else(|| AudioError::DeviceNotFound(id.to_string())),
+    }
+}
+
+// ============================================
+// Level Metering
+// ============================================
+
+/// One level reading emitted to the UI
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InputLevel {
+    pub device_id: String,
+    /// Root-mean-square amplitude, 0.0 - 1.0
+    pub rms: f32,
+    /// Peak absolute amplitude, 0.0 - 1.0
+    pub peak: f32,
+    /// RMS in dBFS (0 = full scale, floored at -96)
+    pub dbfs: f32,
+    /// True if any sample hit full scale during the interval
+    pub clipping: bool,
+    pub timestamp: String,
+}
+
+/// Running sums between level emissions
+#[derive(Debug, Default)]
+struct LevelAccumulator {
+    sum_squares: f64,
+    peak: f32,
+    count: u64,
+}
+
+impl LevelAccumulator {
+    fn add(&mut self, sample: f32) {
+        self.sum_squares += (sample as f64) * (sample as f64);
+        self.peak = self.peak.max(sample.abs());
+        self.count += 1;
+    }
+
+    /// Produce (rms, peak) and reset for the next interval
+    fn take(&mut self) -> (f32, f32) {
+        let rms = if self.count == 0 {
+            0.0
+        } else {
+            (self.sum_squares / self.count as f64).sqrt() as f32
+        };
+        let peak = self.peak;
+        *self = LevelAccumulator::default();
+        (rms.min(1.0), peak.min(1.0))
+    }
+}
+
+/// Root-mean-square of a sample buffer
+pub fn rms(samples: &[f32]) -> f32 {
+    let mut acc = LevelAccumulator::default();
+    samples.iter().for_each(|s| acc.add(*s));
+    acc.take().0
+}
+
+/// Convert a linear amplitude to dBFS, floored at MIN_DBFS
+pub fn to_dbfs(amplitude: f32) -> f32 {
+    if amplitude <= 0.0 {
+        return MIN_DBFS;
+    }
+    (20.0 * amplitude.log10()).max(MIN_DBFS)
+}
+
+fn build_level_stream<T>(
+    device: &cpal::Device,
+    config: &cpal::StreamConfig,
+    levels: Arc<Mutex<LevelAccumulator>>,
+) -> Result<cpal::Stream, AudioError>
+where
+    T: SizedSample,
+    f32: FromSample<T>,
+{
+    device
+        .build_input_stream(
+            config,
+            move |data: &[T], _: &cpal::InputCallbackInfo| {
+                if let Ok(mut acc) = levels.lock() {
+                    data.iter().for_each(|s| acc.add(f32::from_sample(*s)));
+                }
+            },
+            |e| log::warn!("Input level stream error: {}", e),
+            None,
+        )
+        .map_err(|e| AudioError::Device(e.to_string()))
+}
+
+/// Open the device and emit an InputLevel every LEVEL_INTERVAL until stopped.
+/// Runs on its own thread: cpal streams are not Send.
+fn run_level_monitor(
+    device: cpal::Device,
+    device_id: String,
+    window: tauri::Window,
+    stop: Arc<AtomicBool>,
+    max_duration: Duration,
+) -> Result<(), AudioError> {
+    let supported = device
+        .default_input_config()
+        .map_err(|e| AudioError::Device(e.to_string()))?;
+    let config = supported.config();
+    let levels = Arc::new(Mutex::new(LevelAccumulator::default()));
+
+    let stream = match supported.sample_format() {
+        cpal::SampleFormat::F32 => build_level_stream::<f32>(&device, &config, levels.clone())?,
+        cpal::SampleFormat::I16 => build_level_stream::<i16>(&device, &config, levels.clone())?,
+        cpal::SampleFormat::U16 => build_level_stream::<u16>(&device, &config, levels.clone())?,
+        cpal::SampleFormat::I32 => build_level_stream::<i32>(&device, &config, levels.clone())?,
+        other => return Err(AudioError::UnsupportedFormat(format!("{:?}", other))),
+    };
+    stream.play().map_err(|e| AudioError::Device(e.to_string()))?;
+
+    let started = Instant::now();
+    while !stop.load(Ordering::Relaxed) && started.elapsed() < max_duration {
+        std::thread::sleep(LEVEL_INTERVAL);
+
+        let (rms, peak) = match levels.lock() {
+            Ok(mut acc) => acc.take(),
+            Err(_) => break,
+        };
+        let _ = window.emit(
+            INPUT_LEVEL_EVENT,
+            InputLevel {
+                device_id: device_id.clone(),
+                rms,
+                peak,
+                dbfs: to_dbfs(rms),
+                clipping: peak >= 0.999,
+                timestamp: chrono::Utc::now().to_rfc3339(),
+            },
+        );
+    }
+
+    drop(stream);
+    Ok(())
+}
+
+// ============================================
+// Dictation Capture
+// ============================================
+
+/// Samples recorded for one dictation, as delivered by the device
+pub struct CapturedAudio {
+    /// Interleaved when `channels` > 1
+    pub samples: Vec<f32>,
+    pub sample_rate: u32,
+    pub channels: u16,
+}
+
+impl CapturedAudio {
+    pub fn duration_ms(&self) -> i64 {
+        let frames = self.samples.len() / self.channels.max(1) as usize;
+        (frames as f64 * 1000.0 / self.sample_rate.max(1) as f64) as i64
+    }
+
+    /// Mono at 16 kHz, as Whisper expects
+    pub fn into_whisper_input(self) -> Vec<f32> {
+        let mono = downmix(&self.samples, self.channels);
+        crate::voice::resample_to_16k(&mono, self.sample_rate)
+    }
+}
+
+/// Average interleaved channels into one
+pub fn downmix(samples: &[f32], channels: u16) -> Vec<f32> {
+    let channels = channels.max(1) as usize;
+    if channels == 1 {
+        return samples.to_vec();
+    }
+    samples
+        .chunks(channels)
+        .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32)
+        .collect()
+}
+
+fn build_capture_stream<T>(
+    device: &cpal::Device,
+    config: &cpal::StreamConfig,
+    buffer: Arc<Mutex<Vec<f32>>>,
+) -> Result<cpal::Stream, AudioError>
+where
+    T: SizedSample,
+    f32: FromSample<T>,
+{
+    device
+        .build_input_stream(
+            config,
+            move |data: &[T], _: &cpal::InputCallbackInfo| {
+                if let Ok(mut buf) = buffer.lock() {
+                    buf.extend(data.iter().map(|s| f32::from_sample(*s)));
+                }
+            },
+            |e| log::warn!("Dictation capture stream error: {}", e),
+            None,
+        )
+        .map_err(|e| AudioError::Device(e.to_string()))
+}
+
+/// Record from a device (default input if None) until `should_stop`
+/// returns true or `max_duration` passes. Blocks; call it from its own
+/// thread, since cpal streams are not Send.
+pub fn capture_until(
+    device_id: Option<&str>,
+    should_stop: impl Fn() -> bool,
+    max_duration: Duration,
+) -> Result<CapturedAudio, AudioError> {
+    let device = find_input_device(device_id)?;
+    let supported = device
+        .default_input_config()
+        .map_err(|e| AudioError::Device(e.to_string()))?;
+    let config = supported.config();
+    let buffer = Arc::new(Mutex::new(Vec::new()));
+
+    let stream = match supported.sample_format() {
+        cpal::SampleFormat::F32 => build_capture_stream::<f32>(&device, &config, buffer.clone())?,
+        cpal::SampleFormat::I16 => build_capture_stream::<i16>(&device, &config, buffer.clone())?,
+        cpal::SampleFormat::U16 => build_capture_stream::<u16>(&device, &config, buffer.clone())?,
+        cpal::SampleFormat::I32 => build_capture_stream::<i32>(&device, &config, buffer.clone())?,
+        other => return Err(AudioError::UnsupportedFormat(format!("{:?}", other))),
+    };
+    stream.play().map_err(|e| AudioError::Device(e.to_string()))?;
+
+    let started = Instant::now();
+    while !should_stop() && started.elapsed() < max_duration {
+        std::thread::sleep(Duration::from_millis(20));
+    }
+    drop(stream);
+
+    let samples = std::mem::take(&mut *buffer.lock().map_err(|_| AudioError::Device("capture buffer poisoned".to_string()))?);
+    Ok(CapturedAudio {
+        samples,
+        sample_rate: config.sample_rate.0,
+        channels: config.channels,
+    })
+}
+
+// ============================================
+// State
+// ============================================
+
+struct LevelMonitor {
+    device_id: String,
+    stop: Arc<AtomicBool>,
+    handle: JoinHandle<()>,
+}
+
+impl LevelMonitor {
+    fn stop(self) {
+        self.stop.store(true, Ordering::Relaxed);
+        let _ = self.handle.join();
+    }
+}
+
+/// At most one mic check runs at a time
+#[derive(Default)]
+pub struct AudioState {
+    monitor: Mutex<Option<LevelMonitor>>,
+}
+
+// ============================================
+// Tauri Commands
+// ============================================
+
+#[tauri::command]
+pub fn list_audio_devices() -> Result<Vec<AudioDevice>, String> {
+    list_input_devices().map_err(|e| e.to_string())
+}
+
+/// Start streaming `audio-input-level` events for a device (default input if omitted).
+/// Replaces any monitor already running. Returns the id of the device being metered.
+#[tauri::command]
+pub fn monitor_input_level(
+    window: tauri::Window,
+    state: State<'_, AudioState>,
+    device_id: Option<String>,
+    max_seconds: Option<u64>,
+) -> Result<String, String> {
+    let mut slot = state.monitor.lock().map_err(|_| "Audio state poisoned")?;
+    if let Some(existing) = slot.take() {
+        existing.stop();
+    }
+
+    let device = find_input_device(device_id.as_deref()).map_err(|e| e.to_string())?;
+    let resolved_id = device.name().map_err(|e| e.to_string())?;
+    let max_duration = max_seconds
+        .map(Duration::from_secs)
+        .unwrap_or(MAX_MONITOR_DURATION)
+        .min(MAX_MONITOR_DURATION);
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let thread_stop = stop.clone();
+    let thread_id = resolved_id.clone();
+    let handle = std::thread::spawn(move || {
+        if let Err(e) = run_level_monitor(device, thread_id, window, thread_stop, max_duration) {
+            log::warn!("Input level monitor failed: {}", e);
+        }
+    });
+
+    *slot = Some(LevelMonitor {
+        device_id: resolved_id.clone(),
+        stop,
+        handle,
+    });
+
+    Ok(resolved_id)
+}
+
+/// Stop the running mic check, if any. Returns the device that was being metered.
+#[tauri::command]
+pub fn stop_input_level_monitor(state: State<'_, AudioState>) -> Result<Option<String>, String> {
+    let mut slot = state.monitor.lock().map_err(|_| "Audio state poisoned")?;
+    Ok(slot.take().map(|monitor| {
+        let device_id = monitor.device_id.clone();
+        monitor.stop();
+        device_id
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rms_and_dbfs() {
+        assert_eq!(rms(&[]), 0.0);
+        assert!((rms(&[0.5, -0.5, 0.5, -0.5]) - 0.5).abs() < 1e-6);
+        assert!((to_dbfs(1.0) - 0.0).abs() < 1e-4);
+        assert!((to_dbfs(0.5) + 6.0206).abs() < 1e-3);
+        assert_eq!(to_dbfs(0.0), MIN_DBFS);
+    }
+
+    #[test]
+    fn test_accumulator_resets_between_intervals() {
+        let mut acc = LevelAccumulator::default();
+        acc.add(1.0);
+        acc.add(-0.25);
+        let (_, peak) = acc.take();
+        assert_eq!(peak, 1.0);
+        assert_eq!(acc.take(), (0.0, 0.0));
+    }
+
+    #[test]
+    fn test_downmix_and_duration() {
+        assert_eq!(downmix(&[0.5, 0.25, -0.75, 1.0, 0.5, 0.0], 3), vec![0.0, 0.5]);
+        let captured = CapturedAudio { samples: vec![0.0; 96_000], sample_rate: 48_000, channels: 2 };
+        assert_eq!(captured.duration_ms(), 1000);
+        assert_eq!(captured.into_whisper_input().len(), 16_000);
+    }
+}
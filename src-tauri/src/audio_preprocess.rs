@@ -0,0 +1,320 @@
+// Audio Preprocessing
+//
+// Optional cleanup applied to dictation audio before it reaches Whisper.
+// Car and phone-mic recordings carry steady road/fan/line noise and
+// arrive far quieter than Whisper expects; both cost words. Two stages:
+//
+// - Denoise: spectral noise gate. The noise floor is learned per frequency
+//   bin from the quietest frames of the recording itself, then each frame
+//   is attenuated where it does not rise above that floor. Stationary noise
+//   only - speech-shaped noise (TV, other talkers) passes through.
+// - Loudness: gated RMS normalization to a target level with a soft
+//   limiter, so quiet dictations are lifted without clipping peaks.
+//
+// Everything runs in-process; no external tools are required.
+
+use realfft::RealFftPlanner;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::audio::{rms, to_dbfs};
+
+/// Analysis frame length in seconds (~32 ms, rounded up to a power of two)
+const FRAME_SECONDS: f32 = 0.032;
+
+/// Fraction of frames treated as noise-only when learning the floor
+const NOISE_FRAME_FRACTION: f32 = 0.1;
+
+/// Noise is over-subtracted by this factor to suppress residual hiss
+const OVER_SUBTRACTION: f32 = 1.5;
+
+/// Never attenuate a bin by more than this (linear, ~-20 dB); deeper
+/// gating produces "musical noise" artifacts that hurt recognition
+const GAIN_FLOOR: f32 = 0.1;
+
+/// Weight of the previous frame's gain, to avoid frame-to-frame flutter
+const GAIN_SMOOTHING: f32 = 0.4;
+
+/// Loudness is measured over blocks of this length
+const LOUDNESS_BLOCK_SECONDS: f32 = 0.4;
+
+/// Blocks quieter than this are silence and do not count toward loudness
+const SILENCE_GATE_DBFS: f32 = -50.0;
+
+/// Largest boost applied to a quiet recording
+const MAX_GAIN_DB: f32 = 30.0;
+
+/// Samples above this are soft-limited instead of clipped
+const LIMITER_THRESHOLD: f32 = 0.9;
+
+/// Per-recording preprocessing switches
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct PreprocessOptions {
+    #[serde(default = "default_true")]
+    pub denoise: bool,
+    #[serde(default = "default_true")]
+    pub normalize_loudness: bool,
+    /// Target speech level in dBFS
+    #[serde(default = "default_target_dbfs")]
+    pub target_dbfs: f32,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_target_dbfs() -> f32 {
+    -20.0
+}
+
+impl Default for PreprocessOptions {
+    fn default() -> Self {
+        Self {
+            denoise: true,
+            normalize_loudness: true,
+            target_dbfs: default_target_dbfs(),
+        }
+    }
+}
+
+/// Run the enabled stages over mono samples
+pub fn preprocess(samples: &[f32], sample_rate: u32, options: &PreprocessOptions) -> Vec<f32> {
+    let mut out = if options.denoise {
+        denoise(samples, sample_rate)
+    } else {
+        samples.to_vec()
+    };
+    if options.normalize_loudness {
+        normalize_loudness(&mut out, sample_rate, options.target_dbfs);
+    }
+    out
+}
+
+// ============================================
+// Denoise
+// ============================================
+
+/// Periodic sqrt-Hann window; applied on analysis and synthesis it sums
+/// to one at 50% overlap, so unmodified frames reconstruct exactly
+fn sqrt_hann(len: usize) -> Vec<f32> {
+    (0..len)
+        .map(|i| {
+            let hann = 0.5 - 0.5 * (2.0 * std::f32::consts::PI * i as f32 / len as f32).cos();
+            hann.sqrt()
+        })
+        .collect()
+}
+
+/// Spectral noise gate over mono samples
+pub fn denoise(samples: &[f32], sample_rate: u32) -> Vec<f32> {
+    let frame_len = ((sample_rate as f32 * FRAME_SECONDS) as usize).next_power_of_two().max(64);
+    let hop = frame_len / 2;
+    if samples.len() < frame_len * 2 {
+        return samples.to_vec();
+    }
+
+    let mut planner = RealFftPlanner::<f32>::new();
+    let forward = planner.plan_fft_forward(frame_len);
+    let inverse = planner.plan_fft_inverse(frame_len);
+    let window = sqrt_hann(frame_len);
+
+    // Pad so every sample is covered by two frames
+    let mut padded = vec![0.0f32; hop];
+    padded.extend_from_slice(samples);
+    padded.resize(padded.len() + frame_len, 0.0);
+    let frame_count = (padded.len() - frame_len) / hop + 1;
+
+    let mut input = forward.make_input_vec();
+    let mut spectra = Vec::with_capacity(frame_count);
+    for f in 0..frame_count {
+        let start = f * hop;
+        for (i, x) in input.iter_mut().enumerate() {
+            *x = padded[start + i] * window[i];
+        }
+        let mut spectrum = forward.make_output_vec();
+        // Buffer lengths come from the plan, so this cannot fail
+        forward.process(&mut input, &mut spectrum).expect("fft length");
+        spectra.push(spectrum);
+    }
+
+    // Noise floor: mean power per bin over the quietest frames
+    let bins = spectra[0].len();
+    let mut by_energy: Vec<(usize, f32)> = spectra.iter()
+        .enumerate()
+        .map(|(i, s)| (i, s.iter().map(|c| c.norm_sqr()).sum::<f32>()))
+        .collect();
+    by_energy.sort_by(|a, b| a.1.total_cmp(&b.1));
+    let noise_frames = ((frame_count as f32 * NOISE_FRAME_FRACTION) as usize).max(1);
+    let mut noise = vec![0.0f32; bins];
+    for (idx, _) in by_energy.iter().take(noise_frames) {
+        for (n, c) in noise.iter_mut().zip(&spectra[*idx]) {
+            *n += c.norm_sqr() / noise_frames as f32;
+        }
+    }
+
+    let mut output = vec![0.0f32; padded.len()];
+    let mut previous_gain = vec![1.0f32; bins];
+    let mut time = inverse.make_output_vec();
+    for (f, spectrum) in spectra.iter_mut().enumerate() {
+        for ((c, n), prev) in spectrum.iter_mut().zip(&noise).zip(previous_gain.iter_mut()) {
+            let power = c.norm_sqr();
+            let gain = if power > 0.0 {
+                ((power - OVER_SUBTRACTION * n) / power).max(GAIN_FLOOR * GAIN_FLOOR).sqrt()
+            } else {
+                GAIN_FLOOR
+            };
+            let gain = (1.0 - GAIN_SMOOTHING) * gain + GAIN_SMOOTHING * *prev;
+            *prev = gain;
+            *c *= gain;
+        }
+        // DC and Nyquist bins must stay real for the inverse transform
+        spectrum[0].im = 0.0;
+        spectrum[bins - 1].im = 0.0;
+        inverse.process(spectrum, &mut time).expect("fft length");
+
+        let start = f * hop;
+        for (i, x) in time.iter().enumerate() {
+            // realfft's inverse is unnormalized
+            output[start + i] += x * window[i] / frame_len as f32;
+        }
+    }
+
+    output[hop..hop + samples.len()].to_vec()
+}
+
+// ============================================
+// Loudness
+// ============================================
+
+/// Speech loudness in dBFS: mean power of non-silent blocks
+pub fn measure_loudness(samples: &[f32], sample_rate: u32) -> Option<f32> {
+    let block = ((sample_rate as f32 * LOUDNESS_BLOCK_SECONDS) as usize).max(1);
+    let powers: Vec<f32> = samples.chunks(block)
+        .map(rms)
+        .filter(|r| to_dbfs(*r) > SILENCE_GATE_DBFS)
+        .map(|r| r * r)
+        .collect();
+    if powers.is_empty() {
+        return None;
+    }
+    let mean_power = powers.iter().sum::<f32>() / powers.len() as f32;
+    Some(to_dbfs(mean_power.sqrt()))
+}
+
+/// Scale to the target level, soft-limiting anything that would clip.
+/// Silent recordings are left alone.
+pub fn normalize_loudness(samples: &mut [f32], sample_rate: u32, target_dbfs: f32) {
+    let Some(measured) = measure_loudness(samples, sample_rate) else {
+        return;
+    };
+    let gain = 10f32.powf((target_dbfs - measured).min(MAX_GAIN_DB) / 20.0);
+    let headroom = 1.0 - LIMITER_THRESHOLD;
+    for s in samples.iter_mut() {
+        let x = *s * gain;
+        let magnitude = x.abs();
+        *s = if magnitude > LIMITER_THRESHOLD {
+            x.signum() * (LIMITER_THRESHOLD + headroom * ((magnitude - LIMITER_THRESHOLD) / headroom).tanh())
+        } else {
+            x
+        };
+    }
+}
+
+// ============================================
+// WAV Files
+// ============================================
+
+/// Preprocess a mono/stereo WAV file and write the result as 16-bit mono
+pub fn preprocess_wav(input: &Path, output: &Path, options: &PreprocessOptions) -> Result<(), String> {
+    let mut reader = hound::WavReader::open(input).map_err(|e| format!("Cannot read WAV: {}", e))?;
+    let spec = reader.spec();
+
+    let interleaved: Vec<f32> = match spec.sample_format {
+        hound::SampleFormat::Float => reader.samples::<f32>().collect::<Result<_, _>>(),
+        hound::SampleFormat::Int => {
+            let scale = (1i64 << (spec.bits_per_sample - 1)) as f32;
+            reader.samples::<i32>().map(|s| s.map(|v| v as f32 / scale)).collect::<Result<_, _>>()
+        }
+    }.map_err(|e| format!("Cannot read WAV samples: {}", e))?;
+
+    let channels = spec.channels.max(1) as usize;
+    let mono: Vec<f32> = interleaved.chunks(channels)
+        .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32)
+        .collect();
+
+    let processed = preprocess(&mono, spec.sample_rate, options);
+
+    let out_spec = hound::WavSpec {
+        channels: 1,
+        sample_rate: spec.sample_rate,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut writer = hound::WavWriter::create(output, out_spec).map_err(|e| format!("Cannot write WAV: {}", e))?;
+    for s in processed {
+        writer.write_sample((s.clamp(-1.0, 1.0) * i16::MAX as f32) as i16)
+            .map_err(|e| format!("Cannot write WAV: {}", e))?;
+    }
+    writer.finalize().map_err(|e| format!("Cannot write WAV: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deterministic white noise in [-amplitude, amplitude]
+    fn noise(len: usize, amplitude: f32) -> Vec<f32> {
+        let mut state: u32 = 0x1234_5678;
+        (0..len).map(|_| {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            (state as f32 / u32::MAX as f32 * 2.0 - 1.0) * amplitude
+        }).collect()
+    }
+
+    fn tone(len: usize, sample_rate: u32, amplitude: f32) -> Vec<f32> {
+        (0..len)
+            .map(|i| (2.0 * std::f32::consts::PI * 440.0 * i as f32 / sample_rate as f32).sin() * amplitude)
+            .collect()
+    }
+
+    #[test]
+    fn test_denoise_keeps_speech_band_and_cuts_floor() {
+        let rate = 16000;
+        // One second of noise alone, then one second of tone over the same noise
+        let mut signal = noise(rate as usize * 2, 0.05);
+        let clean_tone = tone(rate as usize, rate, 0.3);
+        for (s, t) in signal[rate as usize..].iter_mut().zip(&clean_tone) {
+            *s += t;
+        }
+
+        let out = denoise(&signal, rate);
+        assert_eq!(out.len(), signal.len());
+
+        let noise_only_before = rms(&signal[1000..15000]);
+        let noise_only_after = rms(&out[1000..15000]);
+        assert!(noise_only_after < noise_only_before * 0.5);
+
+        let tone_after = rms(&out[17000..31000]);
+        assert!((tone_after - rms(&clean_tone)).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_loudness_normalization() {
+        let rate = 16000;
+        let mut quiet = tone(rate as usize, rate, 0.01);
+        normalize_loudness(&mut quiet, rate, -20.0);
+        let level = measure_loudness(&quiet, rate).unwrap();
+        assert!((level + 20.0).abs() < 0.5);
+
+        // Hot input is limited rather than clipped
+        let mut hot = tone(rate as usize, rate, 0.9);
+        normalize_loudness(&mut hot, rate, 0.0);
+        assert!(hot.iter().all(|s| s.abs() <= 1.0));
+
+        let mut silence = vec![0.0f32; 1000];
+        normalize_loudness(&mut silence, rate, -20.0);
+        assert!(silence.iter().all(|s| *s == 0.0));
+    }
+}
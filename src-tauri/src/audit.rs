@@ -6,9 +6,9 @@
 // - Path hashes only (no full file paths - PHI risk)
 // - Hash-chained for integrity verification
 
-use rusqlite::{Connection, params};
+use rusqlite::{Connection, params, OptionalExtension};
 use crate::crypto;
-use crate::models::{AuditEntry, AuditEventType, AuditResourceType, AuditOutcome};
+use crate::models::{AccessReason, AuditEntry, AuditEventType, AuditResourceType, AuditOutcome};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -60,6 +60,519 @@ pub fn log_export_event(
     )
 }
 
+/// Log a passphrase-protected disclosure (encrypted export bundle)
+///
+/// The one-time passphrase is shown to the clinician and never stored;
+/// its SHA-256 goes in path_hash so a recipient's copy can later be
+/// matched to this entry without the audit log being able to open it.
+pub fn log_encrypted_disclosure(
+    conn: &Connection,
+    resource_id: &str,
+    passphrase_hash: &str,
+) -> Result<AuditEntry, AuditError> {
+    log_event_with_path(
+        conn,
+        AuditEventType::ExportCreated,
+        AuditResourceType::Export,
+        resource_id,
+        AuditOutcome::Success,
+        None,
+        Some("encrypted_zip"),
+        Some(passphrase_hash),
+    )
+}
+
+/// Log issuance of a de-identification certificate. path_hash carries
+/// the certificate's SHA-256 so a copy presented later can be matched to
+/// the export it was issued for.
+pub fn log_deidentification_certificate(
+    conn: &Connection,
+    certificate_id: &str,
+    certificate_sha256: &str,
+) -> Result<AuditEntry, AuditError> {
+    log_event_with_path(
+        conn,
+        AuditEventType::ExportCreated,
+        AuditResourceType::Export,
+        certificate_id,
+        AuditOutcome::Success,
+        None,
+        Some("deid_certificate"),
+        Some(certificate_sha256),
+    )
+}
+
+/// Log a reason-for-access on a restricted chart
+///
+/// The reason code goes in path_class ("reason:<code>"). Any free-text
+/// justification stays in the vault; only its SHA-256 enters the chain so
+/// the stored text can later be shown to be the one given at access time.
+pub fn log_chart_access(
+    conn: &Connection,
+    client_id: &str,
+    reason: AccessReason,
+    justification_hash: Option<&str>,
+) -> Result<AuditEntry, AuditError> {
+    let reason_class = format!("reason:{}", reason.as_str());
+    log_event_with_path(
+        conn,
+        AuditEventType::ChartAccessed,
+        AuditResourceType::Client,
+        client_id,
+        AuditOutcome::Success,
+        None,
+        Some(&reason_class),
+        justification_hash,
+    )
+}
+
+/// Log a break-glass event. path_class carries the session
+/// ("emergency:<session id>") so reads correlate with their activation;
+/// path_hash carries the justification hash on activation.
+pub fn log_emergency_access(
+    conn: &Connection,
+    event_type: AuditEventType,
+    resource_type: AuditResourceType,
+    resource_id: &str,
+    outcome: AuditOutcome,
+    session_id: &str,
+    justification_hash: Option<&str>,
+) -> Result<AuditEntry, AuditError> {
+    let session_class = format!("emergency:{}", session_id);
+    log_event_with_path(
+        conn,
+        event_type,
+        resource_type,
+        resource_id,
+        outcome,
+        None,
+        Some(&session_class),
+        justification_hash,
+    )
+}
+
+/// Log a change to the break-glass credential. path_class carries what
+/// happened ("emergency_credential:set", ":replaced", or ":rejected" when
+/// a replacement was refused for want of the current credential).
+pub fn log_emergency_credential_change(
+    conn: &Connection,
+    transition: &str,
+    outcome: AuditOutcome,
+) -> Result<AuditEntry, AuditError> {
+    let credential_class = format!("emergency_credential:{}", transition);
+    log_event_with_path(
+        conn,
+        AuditEventType::SettingsChanged,
+        AuditResourceType::Settings,
+        "emergency_access_credential",
+        outcome,
+        None,
+        Some(&credential_class),
+        None,
+    )
+}
+
+/// Log a change of the row ID scheme; path_class is "id_scheme:<scheme>"
+pub fn log_id_scheme_change(conn: &Connection, scheme: &str) -> Result<AuditEntry, AuditError> {
+    let scheme_class = format!("id_scheme:{}", scheme);
+    log_event_with_path(
+        conn,
+        AuditEventType::SettingsChanged,
+        AuditResourceType::Settings,
+        crate::ids::ID_SCHEME_SETTING,
+        AuditOutcome::Success,
+        None,
+        Some(&scheme_class),
+        None,
+    )
+}
+
+/// Log a legal hold or destruction event on a client's chart. path_class
+/// carries the hold id or destruction counts; path_hash the matter hash
+/// or the destruction manifest hash.
+pub fn log_retention_event(
+    conn: &Connection,
+    event_type: AuditEventType,
+    client_id: &str,
+    detail: &str,
+    hash: &str,
+) -> Result<AuditEntry, AuditError> {
+    log_event_with_path(
+        conn,
+        event_type,
+        AuditResourceType::Client,
+        client_id,
+        AuditOutcome::Success,
+        None,
+        Some(detail),
+        Some(hash),
+    )
+}
+
+/// Log a change to retention settings: the practice schedule (resource
+/// Settings) or one client's jurisdiction and discharge date (resource
+/// Client). path_class names the setting; path_hash is a hash of the new
+/// value, so the entry shows what changed without holding the dates.
+pub fn log_retention_setting_change(
+    conn: &Connection,
+    resource_type: AuditResourceType,
+    resource_id: &str,
+    setting: &str,
+    value_hash: &str,
+) -> Result<AuditEntry, AuditError> {
+    log_event_with_path(
+        conn,
+        AuditEventType::SettingsChanged,
+        resource_type,
+        resource_id,
+        AuditOutcome::Success,
+        None,
+        Some(setting),
+        Some(value_hash),
+    )
+}
+
+/// Log export of a client visit-summary letter. path_class names the
+/// release of information it went out under ("release:<id>"); path_hash
+/// is the letter's content hash.
+pub fn log_client_letter_export(
+    conn: &Connection,
+    letter_id: &str,
+    release_id: &str,
+    content_hash: &str,
+) -> Result<AuditEntry, AuditError> {
+    let release_class = format!("release:{}", release_id);
+    log_event_with_path(
+        conn,
+        AuditEventType::ClientLetterExported,
+        AuditResourceType::Export,
+        letter_id,
+        AuditOutcome::Success,
+        None,
+        Some(&release_class),
+        Some(content_hash),
+    )
+}
+
+/// Log a status change of a queued EHR delivery. path_class carries the
+/// target and attempt ("ehr:<target>:attempt:<n>"); path_hash the
+/// delivered file's hash once there is one.
+pub fn log_ehr_delivery(
+    conn: &Connection,
+    event_type: AuditEventType,
+    delivery_id: &str,
+    outcome: AuditOutcome,
+    target: &str,
+    attempt: u32,
+    content_hash: Option<&str>,
+) -> Result<AuditEntry, AuditError> {
+    let delivery_class = format!("ehr:{}:attempt:{}", target, attempt);
+    log_event_with_path(
+        conn,
+        event_type,
+        AuditResourceType::Export,
+        delivery_id,
+        outcome,
+        None,
+        Some(&delivery_class),
+        content_hash,
+    )
+}
+
+/// Log a change to a note's authorship. path_class carries the author's
+/// role ("author:<role>"); path_hash the contribution hash when one was
+/// signed. Author names stay out of the log.
+pub fn log_note_author(
+    conn: &Connection,
+    event_type: AuditEventType,
+    note_id: &str,
+    role: &str,
+    contribution_hash: Option<&str>,
+) -> Result<AuditEntry, AuditError> {
+    let author_class = format!("author:{}", role);
+    log_event_with_path(
+        conn,
+        event_type,
+        AuditResourceType::Note,
+        note_id,
+        AuditOutcome::Success,
+        None,
+        Some(&author_class),
+        contribution_hash,
+    )
+}
+
+/// Log a contribution signature refused because the author's signing
+/// credential did not match
+pub fn log_contribution_signature_refused(
+    conn: &Connection,
+    note_id: &str,
+    role: &str,
+) -> Result<AuditEntry, AuditError> {
+    let author_class = format!("author:{}", role);
+    log_event_with_path(
+        conn,
+        AuditEventType::NoteContributionSigned,
+        AuditResourceType::Note,
+        note_id,
+        AuditOutcome::Blocked,
+        None,
+        Some(&author_class),
+        None,
+    )
+}
+
+/// Log a change to an author's signing credential. path_class carries
+/// what happened ("author_credential:set", ":replaced" or ":rejected").
+pub fn log_author_credential_change(
+    conn: &Connection,
+    author_id: &str,
+    transition: &str,
+    outcome: AuditOutcome,
+) -> Result<AuditEntry, AuditError> {
+    let credential_class = format!("author_credential:{}", transition);
+    log_event_with_path(
+        conn,
+        AuditEventType::SettingsChanged,
+        AuditResourceType::Settings,
+        author_id,
+        outcome,
+        None,
+        Some(&credential_class),
+        None,
+    )
+}
+
+/// Log an amendment to a signed note. path_class carries the size of the
+/// change ("amendment:sections:<n>:+<added>:-<removed>"); path_hash the
+/// hash of its deterministic change summary. A model-written summary shown
+/// at review is logged by `log_change_summary`.
+pub fn log_note_amendment(
+    conn: &Connection,
+    note_id: &str,
+    sections: usize,
+    lines_added: usize,
+    lines_removed: usize,
+    summary_hash: &str,
+) -> Result<AuditEntry, AuditError> {
+    let change_class = format!("amendment:sections:{}:+{}:-{}", sections, lines_added, lines_removed);
+    log_event_with_path(
+        conn,
+        AuditEventType::NoteUpdated,
+        AuditResourceType::Note,
+        note_id,
+        AuditOutcome::Success,
+        None,
+        Some(&change_class),
+        Some(summary_hash),
+    )
+}
+
+/// Log a local-model summary of a note's changes being shown. path_class
+/// carries the revisions compared ("change_summary:<from>:<to>"); path_hash
+/// the hash of the summary text, so a summary quoted later can be matched
+/// to the entry without the log holding it.
+pub fn log_change_summary(
+    conn: &Connection,
+    note_id: &str,
+    from_rev: usize,
+    to_rev: usize,
+    summary_hash: &str,
+) -> Result<AuditEntry, AuditError> {
+    let summary_class = format!("change_summary:{}:{}", from_rev, to_rev);
+    log_event_with_path(
+        conn,
+        AuditEventType::AiAnalysisRun,
+        AuditResourceType::Note,
+        note_id,
+        AuditOutcome::Success,
+        None,
+        Some(&summary_class),
+        Some(summary_hash),
+    )
+}
+
+/// Log a note or client moving into, out of, or being purged from the
+/// trash. path_class carries the transition ("trash:trashed",
+/// "trash:restored", "trash:purged", "trash:purged:expired").
+pub fn log_trash_event(
+    conn: &Connection,
+    event_type: AuditEventType,
+    resource_type: AuditResourceType,
+    resource_id: &str,
+    transition: &str,
+) -> Result<AuditEntry, AuditError> {
+    let trash_class = format!("trash:{}", transition);
+    log_event_with_path(
+        conn,
+        event_type,
+        resource_type,
+        resource_id,
+        AuditOutcome::Success,
+        None,
+        Some(&trash_class),
+        None,
+    )
+}
+
+/// Log the result of a follow-up presence check on an exported file
+///
+/// path_class carries the finding ("presence:present" / "presence:absent");
+/// path_hash repeats the export entry's hash so the two can be correlated.
+pub fn log_export_presence(
+    conn: &Connection,
+    resource_id: &str,
+    present: bool,
+    path_hash: &str,
+) -> Result<AuditEntry, AuditError> {
+    let finding = if present { "presence:present" } else { "presence:absent" };
+    log_event_with_path(
+        conn,
+        AuditEventType::ExportPresenceChecked,
+        AuditResourceType::Export,
+        resource_id,
+        AuditOutcome::Success,
+        None,
+        Some(finding),
+        Some(path_hash),
+    )
+}
+
+/// path_class of the entry anchoring an audit pack's Merkle root
+pub const AUDIT_PACK_ROOT_CLASS: &str = "audit_pack:merkle_root";
+
+/// Anchor an audit pack's Merkle root in the chain. path_hash carries the
+/// root so a disclosure built from the pack can be checked against this
+/// entry rather than against whatever root the pack itself claims.
+pub fn log_audit_pack_root(
+    conn: &Connection,
+    pack_id: &str,
+    merkle_root: &str,
+) -> Result<AuditEntry, AuditError> {
+    log_event_with_path(
+        conn,
+        AuditEventType::ExportCreated,
+        AuditResourceType::Export,
+        pack_id,
+        AuditOutcome::Success,
+        None,
+        Some(AUDIT_PACK_ROOT_CLASS),
+        Some(merkle_root),
+    )
+}
+
+/// Log a hashed-identifier client lookup. Only the kind and whether
+/// anything matched are recorded: the submitted hash is a dictionary-
+/// attackable stand-in for the identifier and stays out of the log.
+pub fn log_client_hash_lookup(
+    conn: &Connection,
+    kind: &str,
+    matched: bool,
+) -> Result<AuditEntry, AuditError> {
+    let finding = format!("lookup:{}:{}", kind, if matched { "found" } else { "not_found" });
+    log_event_with_path(
+        conn,
+        AuditEventType::ClientHashLookup,
+        AuditResourceType::Client,
+        "hash-lookup",
+        AuditOutcome::Success,
+        None,
+        Some(&finding),
+        None,
+    )
+}
+
+/// Log a client's notes being pulled into a search or AI context.
+/// path_class carries the kind and how many notes were retrieved
+/// ("retrieval:<kind>:<n>"); the note ids and query stay out of the log.
+pub fn log_notes_retrieved(
+    conn: &Connection,
+    client_id: &str,
+    kind: &str,
+    note_count: usize,
+) -> Result<AuditEntry, AuditError> {
+    let retrieval_class = format!("retrieval:{}:{}", kind, note_count);
+    log_event_with_path(
+        conn,
+        AuditEventType::NotesRetrieved,
+        AuditResourceType::Client,
+        client_id,
+        AuditOutcome::Success,
+        None,
+        Some(&retrieval_class),
+        None,
+    )
+}
+
+/// Log a prep sheet being produced for a client. path_class records
+/// whether it was built or served from the derived cache.
+pub fn log_prep_sheet(
+    conn: &Connection,
+    client_id: &str,
+    cached: bool,
+) -> Result<AuditEntry, AuditError> {
+    let source = if cached { "prep_sheet:cached" } else { "prep_sheet:built" };
+    log_event_with_path(
+        conn,
+        AuditEventType::PrepSheetGenerated,
+        AuditResourceType::Client,
+        client_id,
+        AuditOutcome::Success,
+        None,
+        Some(source),
+        None,
+    )
+}
+
+/// Log a dry run of a proposed policy bundle. The resource is the
+/// bundle; path_class carries its version, the days replayed and how many
+/// decisions would have gone the other way.
+pub fn log_policy_simulation(
+    conn: &Connection,
+    bundle_id: &str,
+    bundle_version: &str,
+    since_days: u32,
+    newly_blocked: usize,
+    newly_allowed: usize,
+) -> Result<AuditEntry, AuditError> {
+    let detail = format!(
+        "policy_simulation:v{}:{}d:blocked={}:allowed={}",
+        bundle_version, since_days, newly_blocked, newly_allowed
+    );
+    log_event_with_path(
+        conn,
+        AuditEventType::PolicySimulated,
+        AuditResourceType::Settings,
+        bundle_id,
+        AuditOutcome::Success,
+        None,
+        Some(&detail),
+        None,
+    )
+}
+
+/// Log a group supervision session being recorded. Cases discussed are
+/// counted, never named; path_class carries attendance and the case count.
+pub fn log_group_supervision(
+    conn: &Connection,
+    session_id: &str,
+    present: usize,
+    absent: usize,
+    cases: usize,
+) -> Result<AuditEntry, AuditError> {
+    let detail = format!("group_supervision:present={}:absent={}:cases={}", present, absent, cases);
+    log_event_with_path(
+        conn,
+        AuditEventType::GroupSupervisionRecorded,
+        AuditResourceType::Vault,
+        session_id,
+        AuditOutcome::Success,
+        None,
+        Some(&detail),
+        None,
+    )
+}
+
 /// Internal: log event with optional path info
 fn log_event_with_path(
     conn: &Connection,
@@ -71,9 +584,50 @@ fn log_event_with_path(
     path_class: Option<&str>,
     path_hash: Option<&str>,
 ) -> Result<AuditEntry, AuditError> {
-    let id = uuid::Uuid::new_v4().to_string();
-    let timestamp = chrono::Utc::now().timestamp_millis();
-    
+    append_entry(
+        conn,
+        crate::ids::new_id(),
+        chrono::Utc::now().timestamp_millis(),
+        event_type,
+        resource_type,
+        resource_id,
+        outcome,
+        detection_ids,
+        path_class,
+        path_hash,
+    )
+}
+
+/// Test fixtures: chain an entry with a caller-chosen id and timestamp
+#[cfg(test)]
+pub(crate) fn append_fixture_entry(
+    conn: &Connection,
+    id: String,
+    timestamp: i64,
+    event_type: AuditEventType,
+    resource_type: AuditResourceType,
+    resource_id: &str,
+    outcome: AuditOutcome,
+) -> Result<AuditEntry, AuditError> {
+    append_entry(conn, id, timestamp, event_type, resource_type, resource_id, outcome, None, None, None)
+}
+
+/// Internal: chain and insert an entry with a caller-chosen id and
+/// timestamp. Everything outside test fixtures goes through
+/// `log_event_with_path`, which takes both from the clock and RNG.
+#[allow(clippy::too_many_arguments)]
+fn append_entry(
+    conn: &Connection,
+    id: String,
+    timestamp: i64,
+    event_type: AuditEventType,
+    resource_type: AuditResourceType,
+    resource_id: &str,
+    outcome: AuditOutcome,
+    detection_ids: Option<&[String]>,
+    path_class: Option<&str>,
+    path_hash: Option<&str>,
+) -> Result<AuditEntry, AuditError> {
     let (previous_hash, sequence) = get_last_entry_info(conn)?;
     
     // Build entry data for hashing (no PHI - only IDs, enums, hashes)
@@ -140,6 +694,33 @@ fn get_last_entry_info(conn: &Connection) -> Result<(String, i64), AuditError> {
     }
 }
 
+/// Log an audit slice leaving or entering this install. path_class
+/// carries the direction and (for imports) the verification result;
+/// path_hash is the exporting install's key fingerprint.
+pub fn log_audit_slice(
+    conn: &Connection,
+    slice_id: &str,
+    imported: bool,
+    verified: bool,
+    exporter_fingerprint: &str,
+) -> Result<AuditEntry, AuditError> {
+    let (event_type, finding) = match (imported, verified) {
+        (false, _) => (AuditEventType::AuditSliceExported, "slice:exported"),
+        (true, true) => (AuditEventType::AuditSliceImported, "slice:verified"),
+        (true, false) => (AuditEventType::AuditSliceImported, "slice:rejected"),
+    };
+    log_event_with_path(
+        conn,
+        event_type,
+        AuditResourceType::Vault,
+        slice_id,
+        if verified { AuditOutcome::Success } else { AuditOutcome::Failure },
+        None,
+        Some(finding),
+        Some(exporter_fingerprint),
+    )
+}
+
 /// Get audit log entries
 pub fn get_entries(
     conn: &Connection,
@@ -175,6 +756,146 @@ pub fn get_entries(
     rows.collect::<Result<Vec<_>, _>>().map_err(AuditError::from)
 }
 
+/// Entries with timestamps in [start_ms, end_ms], oldest first. Contiguous
+/// by sequence, so the result verifies as a chain segment.
+pub fn get_entries_between(
+    conn: &Connection,
+    start_ms: i64,
+    end_ms: i64,
+) -> Result<Vec<AuditEntry>, AuditError> {
+    let mut stmt = conn.prepare(
+        "SELECT id, timestamp, sequence, event_type, resource_type, resource_id, 
+         outcome, detection_ids, path_class, path_hash, previous_hash, entry_hash 
+         FROM audit_log
+         WHERE sequence BETWEEN
+             (SELECT MIN(sequence) FROM audit_log WHERE timestamp >= ?1)
+             AND (SELECT MAX(sequence) FROM audit_log WHERE timestamp <= ?2)
+         ORDER BY sequence ASC"
+    )?;
+    
+    let rows = stmt.query_map(params![start_ms, end_ms], map_entry_row)?;
+    rows.collect::<Result<Vec<_>, _>>().map_err(AuditError::from)
+}
+
+/// Entries about one client's chart - the client, its notes (trashed
+/// notes aside) and its documents - with timestamps in [start_ms, end_ms],
+/// oldest first
+pub fn get_chart_entries(
+    conn: &Connection,
+    client_id: &str,
+    start_ms: i64,
+    end_ms: i64,
+) -> Result<Vec<AuditEntry>, AuditError> {
+    let mut stmt = conn.prepare(
+        "SELECT id, timestamp, sequence, event_type, resource_type, resource_id, 
+         outcome, detection_ids, path_class, path_hash, previous_hash, entry_hash 
+         FROM audit_log
+         WHERE timestamp BETWEEN ?2 AND ?3
+           AND (resource_id = ?1
+                OR resource_id IN (SELECT id FROM notes WHERE client_id = ?1 AND deleted_at IS NULL)
+                OR resource_id IN (SELECT id FROM client_documents WHERE client_id = ?1))
+         ORDER BY sequence ASC"
+    )?;
+    
+    let rows = stmt.query_map(params![client_id, start_ms, end_ms], map_entry_row)?;
+    rows.collect::<Result<Vec<_>, _>>().map_err(AuditError::from)
+}
+
+/// Entries from `from_sequence` to the head, oldest first
+pub fn get_entries_from(conn: &Connection, from_sequence: i64) -> Result<Vec<AuditEntry>, AuditError> {
+    let mut stmt = conn.prepare(
+        "SELECT id, timestamp, sequence, event_type, resource_type, resource_id, 
+         outcome, detection_ids, path_class, path_hash, previous_hash, entry_hash 
+         FROM audit_log WHERE sequence >= ?1 ORDER BY sequence ASC"
+    )?;
+    
+    let rows = stmt.query_map([from_sequence], map_entry_row)?;
+    rows.collect::<Result<Vec<_>, _>>().map_err(AuditError::from)
+}
+
+/// Entries with the given entry hashes, oldest first; unknown hashes are skipped
+pub fn get_entries_by_hash(conn: &Connection, entry_hashes: &[&str]) -> Result<Vec<AuditEntry>, AuditError> {
+    let mut stmt = conn.prepare(
+        "SELECT id, timestamp, sequence, event_type, resource_type, resource_id, 
+         outcome, detection_ids, path_class, path_hash, previous_hash, entry_hash 
+         FROM audit_log WHERE entry_hash = ?1"
+    )?;
+    let mut entries = Vec::new();
+    for hash in entry_hashes {
+        if let Some(entry) = stmt.query_row([hash], map_entry_row).optional()? {
+            entries.push(entry);
+        }
+    }
+    entries.sort_by_key(|e| e.sequence);
+    Ok(entries)
+}
+
+/// The entry anchoring `pack_id`'s Merkle root, if that pack was generated here
+pub fn get_audit_pack_root(conn: &Connection, pack_id: &str) -> Result<Option<AuditEntry>, AuditError> {
+    conn.query_row(
+        "SELECT id, timestamp, sequence, event_type, resource_type, resource_id, 
+         outcome, detection_ids, path_class, path_hash, previous_hash, entry_hash 
+         FROM audit_log WHERE resource_id = ?1 AND path_class = ?2
+         ORDER BY sequence DESC LIMIT 1",
+        params![pack_id, AUDIT_PACK_ROOT_CLASS],
+        map_entry_row,
+    ).optional().map_err(AuditError::from)
+}
+
+pub(crate) fn map_entry_row(row: &rusqlite::Row) -> rusqlite::Result<AuditEntry> {
+    Ok(AuditEntry {
+        id: row.get(0)?,
+        timestamp: row.get(1)?,
+        sequence: row.get(2)?,
+        event_type: parse_event_type(&row.get::<_, String>(3)?),
+        resource_type: parse_resource_type(&row.get::<_, String>(4)?),
+        resource_id: row.get(5)?,
+        outcome: parse_outcome(&row.get::<_, String>(6)?),
+        detection_ids: row.get::<_, Option<String>>(7)?
+            .map(|j| serde_json::from_str(&j).unwrap_or_default()),
+        path_class: row.get(8)?,
+        path_hash: row.get(9)?,
+        previous_hash: row.get(10)?,
+        entry_hash: row.get(11)?,
+    })
+}
+
+/// The string an entry's hash is computed over (no PHI - only IDs, enums, hashes)
+pub fn entry_hash_data(entry: &AuditEntry) -> String {
+    format!(
+        "{}|{}|{}|{:?}|{:?}|{}|{:?}|{}|{}",
+        entry.id, entry.timestamp, entry.sequence,
+        entry.event_type, entry.resource_type,
+        entry.resource_id, entry.outcome,
+        entry.path_class.as_deref().unwrap_or(""),
+        entry.path_hash.as_deref().unwrap_or("")
+    )
+}
+
+/// Verify a contiguous run of entries (oldest first): every hash
+/// recomputes, sequences are consecutive, and each entry links to the one
+/// before it. The first entry's predecessor is outside the segment, so its
+/// link is only checked when it claims to start the chain.
+pub fn verify_segment(entries: &[AuditEntry]) -> Result<(), AuditError> {
+    for (i, entry) in entries.iter().enumerate() {
+        let computed = crypto::hash_chain_entry(&entry.previous_hash, entry_hash_data(entry).as_bytes());
+        
+        if !crypto::digests_match(&computed, &entry.entry_hash) {
+            return Err(AuditError::HashMismatch { index: i });
+        }
+        
+        let linked = match i {
+            0 => entry.sequence != 1 || entry.previous_hash == "genesis",
+            _ => entry.sequence == entries[i - 1].sequence + 1
+                && crypto::digests_match(&entry.previous_hash, &entries[i - 1].entry_hash),
+        };
+        if !linked {
+            return Err(AuditError::ChainBroken { index: i });
+        }
+    }
+    Ok(())
+}
+
 /// Verify audit chain integrity
 pub fn verify_chain(conn: &Connection) -> Result<bool, AuditError> {
     let mut stmt = conn.prepare(
@@ -183,23 +904,7 @@ pub fn verify_chain(conn: &Connection) -> Result<bool, AuditError> {
          FROM audit_log ORDER BY sequence ASC"
     )?;
     
-    let rows = stmt.query_map([], |row| {
-        Ok(AuditEntry {
-            id: row.get(0)?,
-            timestamp: row.get(1)?,
-            sequence: row.get(2)?,
-            event_type: parse_event_type(&row.get::<_, String>(3)?),
-            resource_type: parse_resource_type(&row.get::<_, String>(4)?),
-            resource_id: row.get(5)?,
-            outcome: parse_outcome(&row.get::<_, String>(6)?),
-            detection_ids: row.get::<_, Option<String>>(7)?
-                .map(|j| serde_json::from_str(&j).unwrap_or_default()),
-            path_class: row.get(8)?,
-            path_hash: row.get(9)?,
-            previous_hash: row.get(10)?,
-            entry_hash: row.get(11)?,
-        })
-    })?;
+    let rows = stmt.query_map([], map_entry_row)?;
     
     let entries: Vec<AuditEntry> = rows.collect::<Result<Vec<_>, _>>()?;
     
@@ -213,26 +918,7 @@ pub fn verify_chain(conn: &Connection) -> Result<bool, AuditError> {
     }
     
     // Verify chain
-    for (i, entry) in entries.iter().enumerate() {
-        let entry_data = format!(
-            "{}|{}|{}|{:?}|{:?}|{}|{:?}|{}|{}",
-            entry.id, entry.timestamp, entry.sequence,
-            entry.event_type, entry.resource_type,
-            entry.resource_id, entry.outcome,
-            entry.path_class.as_deref().unwrap_or(""),
-            entry.path_hash.as_deref().unwrap_or("")
-        );
-        
-        let computed = crypto::hash_chain_entry(&entry.previous_hash, entry_data.as_bytes());
-        
-        if computed != entry.entry_hash {
-            return Err(AuditError::HashMismatch { index: i });
-        }
-        
-        if i > 0 && entry.previous_hash != entries[i - 1].entry_hash {
-            return Err(AuditError::ChainBroken { index: i });
-        }
-    }
+    verify_segment(&entries)?;
     
     Ok(true)
 }
@@ -256,6 +942,39 @@ fn parse_event_type(s: &str) -> AuditEventType {
         "vaultunlocked" => AuditEventType::VaultUnlocked,
         "vaultlocked" => AuditEventType::VaultLocked,
         "passphrasechanged" => AuditEventType::PassphraseChanged,
+        "trustedrecipientadded" => AuditEventType::TrustedRecipientAdded,
+        "trustedrecipientrevoked" => AuditEventType::TrustedRecipientRevoked,
+        "consultationdraftsealed" => AuditEventType::ConsultationDraftSealed,
+        "chartaccessed" => AuditEventType::ChartAccessed,
+        "exportpresencechecked" => AuditEventType::ExportPresenceChecked,
+        "clienthashlookup" => AuditEventType::ClientHashLookup,
+        "auditsliceexported" => AuditEventType::AuditSliceExported,
+        "auditsliceimported" => AuditEventType::AuditSliceImported,
+        "emergencyaccessactivated" => AuditEventType::EmergencyAccessActivated,
+        "emergencyaccessread" => AuditEventType::EmergencyAccessRead,
+        "emergencyaccessreviewed" => AuditEventType::EmergencyAccessReviewed,
+        "legalholdplaced" => AuditEventType::LegalHoldPlaced,
+        "legalholdreleased" => AuditEventType::LegalHoldReleased,
+        "recordsdestroyed" => AuditEventType::RecordsDestroyed,
+        "clientletterexported" => AuditEventType::ClientLetterExported,
+        "ehrdeliveryqueued" => AuditEventType::EhrDeliveryQueued,
+        "ehrdeliverysent" => AuditEventType::EhrDeliverySent,
+        "ehrdeliveryfailed" => AuditEventType::EhrDeliveryFailed,
+        "ehrdeliveryacknowledged" => AuditEventType::EhrDeliveryAcknowledged,
+        "noteauthoradded" => AuditEventType::NoteAuthorAdded,
+        "notesectionattributed" => AuditEventType::NoteSectionAttributed,
+        "notecontributionsigned" => AuditEventType::NoteContributionSigned,
+        "backupcreated" => AuditEventType::BackupCreated,
+        "backuprestored" => AuditEventType::BackupRestored,
+        "notesretrieved" => AuditEventType::NotesRetrieved,
+        "prepsheetgenerated" => AuditEventType::PrepSheetGenerated,
+        "notetrashed" => AuditEventType::NoteTrashed,
+        "noterestored" => AuditEventType::NoteRestored,
+        "clienttrashed" => AuditEventType::ClientTrashed,
+        "clientrestored" => AuditEventType::ClientRestored,
+        "clientpurged" => AuditEventType::ClientPurged,
+        "policysimulated" => AuditEventType::PolicySimulated,
+        "groupsupervisionrecorded" => AuditEventType::GroupSupervisionRecorded,
         _ => AuditEventType::NoteCreated,
     }
 }
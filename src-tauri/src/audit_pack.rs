@@ -5,6 +5,7 @@
 // - Attestation records
 // - Chain-of-custody verification
 // - Export certificate
+// - Environment fingerprint (which build and rules produced the pack)
 //
 // All outputs are verifiable and tamper-evident.
 
@@ -14,6 +15,11 @@ use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use thiserror::Error;
 
+use crate::policy::OrganizationPolicy;
+
+/// 1.1 added metadata.environment
+pub const AUDIT_PACK_SCHEMA_VERSION: &str = "1.1";
+
 #[derive(Error, Debug)]
 pub enum AuditPackError {
     #[error("Vault not available")]
@@ -87,6 +93,14 @@ pub struct AuditPackConfig {
     
     /// Output format
     pub output_format: AuditPackFormat,
+    
+    /// Split output into parts no larger than this (e.g. e-filing limits)
+    #[serde(default)]
+    pub max_part_bytes: Option<u64>,
+    
+    /// Include a documentation completeness scorecard per client
+    #[serde(default)]
+    pub include_scorecards: bool,
 }
 
 impl Default for AuditPackConfig {
@@ -103,6 +117,8 @@ impl Default for AuditPackConfig {
             include_chain_verification: true,
             redact_client_names: false,
             output_format: AuditPackFormat::Pdf,
+            max_part_bytes: None,
+            include_scorecards: false,
         }
     }
 }
@@ -172,6 +188,10 @@ pub struct AuditPackContents {
     
     /// Audit log extract (PHI-minimal)
     pub audit_log: Vec<AuditLogEntry>,
+    
+    /// Documentation completeness per client (chart audits)
+    #[serde(default)]
+    pub scorecards: Vec<crate::metrics::DocumentationScorecard>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -258,6 +278,13 @@ pub struct AuditPackMetadata {
     pub total_attestations: u32,
     /// Generation duration (ms)
     pub generation_time_ms: u64,
+    /// Merkle root over the pack's notes and audit entries (for selective disclosure)
+    #[serde(default)]
+    pub merkle_root: Option<String>,
+    /// Build and rules that produced the pack; absent on packs from
+    /// before schema 1.1
+    #[serde(default)]
+    pub environment: Option<EnvironmentFingerprint>,
 }
 
 /// Export certificate for audit pack
@@ -277,6 +304,9 @@ pub struct ExportCertificate {
     pub destination_hash: String,
     /// Certificate signature
     pub signature: String,
+    /// Present when the output was split into parts
+    #[serde(default)]
+    pub split_manifest: Option<SplitManifest>,
 }
 
 // ============================================
@@ -287,11 +317,19 @@ pub struct ExportCertificate {
 pub struct AuditPackGenerator {
     /// Configuration
     config: AuditPackConfig,
+    /// Recorded in every generated pack
+    environment: EnvironmentFingerprint,
 }
 
 impl AuditPackGenerator {
     pub fn new(config: AuditPackConfig) -> Self {
-        Self { config }
+        Self { config, environment: EnvironmentFingerprint::current(None) }
+    }
+    
+    /// Also fingerprint the active policy's rules
+    pub fn with_policy(mut self, policy: &OrganizationPolicy) -> Self {
+        self.environment = EnvironmentFingerprint::current(Some(policy));
+        self
     }
     
     /// Generate audit pack from vault data
@@ -333,7 +371,7 @@ impl AuditPackGenerator {
             .map(|(id, count)| ClientSummary {
                 id: id.clone(),
                 display_name: if self.config.redact_client_names {
-                    format!("Client-{}", &id[..8])
+                    format!("Client-{}", crate::ids::short_id(&id))
                 } else {
                     id
                 },
@@ -371,6 +409,11 @@ impl AuditPackGenerator {
             vec![]
         };
         
+        let merkle_root = caseload_merkle_root(
+            notes.iter().map(|n| (n.id.as_str(), n.content_hash.as_str())),
+            audit_log.iter().map(|e| e.entry_hash.as_str()),
+        );
+        
         let pack = AuditPack {
             id: uuid::Uuid::new_v4().to_string(),
             generated_at: Utc::now(),
@@ -386,6 +429,7 @@ impl AuditPackGenerator {
                 amendments: amendments.clone(),
                 attestations: attestations.clone(),
                 audit_log,
+                scorecards: Vec::new(),
             },
             chain_verification: if self.config.include_chain_verification {
                 chain_verification
@@ -394,11 +438,13 @@ impl AuditPackGenerator {
             },
             metadata: AuditPackMetadata {
                 app_version: env!("CARGO_PKG_VERSION").to_string(),
-                schema_version: "1.0".to_string(),
+                schema_version: AUDIT_PACK_SCHEMA_VERSION.to_string(),
                 total_notes: notes.len() as u32,
                 total_amendments: amendments.len() as u32,
                 total_attestations: attestations.len() as u32,
                 generation_time_ms: start_time.elapsed().as_millis() as u64,
+                merkle_root,
+                environment: Some(self.environment.clone()),
             },
         };
         
@@ -428,23 +474,21 @@ impl AuditPackGenerator {
         let content_hash = sha256_hex(content.as_bytes());
         
         // Write file
-        let output_path = match self.config.output_format {
-            AuditPackFormat::Json => {
-                let path = destination.join(format!("audit-pack-{}.json", pack.id));
-                std::fs::write(&path, &content)?;
-                path
-            }
-            AuditPackFormat::Pdf => {
-                // Would generate PDF here - for now, write JSON
-                let path = destination.join(format!("audit-pack-{}.json", pack.id));
-                std::fs::write(&path, &content)?;
-                path
+        let file_name = match self.config.output_format {
+            AuditPackFormat::Json => format!("audit-pack-{}.json", pack.id),
+            // Would generate PDF here - for now, write JSON
+            AuditPackFormat::Pdf => format!("audit-pack-{}.json", pack.id),
+            // Would create ZIP archive here - for now, write JSON
+            AuditPackFormat::Zip => format!("audit-pack-{}.json", pack.id),
+        };
+        let output_path = destination.join(&file_name);
+        let split_manifest = match self.config.max_part_bytes {
+            Some(limit) if content.len() as u64 > limit => {
+                Some(write_split_output(content.as_bytes(), destination, &file_name, limit)?)
             }
-            AuditPackFormat::Zip => {
-                // Would create ZIP archive here - for now, write JSON
-                let path = destination.join(format!("audit-pack-{}.json", pack.id));
-                std::fs::write(&path, &content)?;
-                path
+            _ => {
+                std::fs::write(&output_path, &content)?;
+                None
             }
         };
         
@@ -457,6 +501,7 @@ impl AuditPackGenerator {
             destination_class: dest_class,
             destination_hash: sha256_hex(destination.to_string_lossy().as_bytes()),
             signature: String::new(),  // Would sign with vault key
+            split_manifest,
         };
         
         // Write certificate
@@ -471,6 +516,592 @@ impl AuditPackGenerator {
     }
 }
 
+// ============================================
+// Selective Disclosure (Merkle proofs)
+// ============================================
+//
+// Leaves are every note (sorted by id) followed by every audit entry in
+// chain order. A single-note pack carries inclusion proofs for that note
+// and its audit events against the caseload root, so a subpoena for one
+// client doesn't require handing over the rest of the caseload.
+
+const LEAF_PREFIX: u8 = 0x00;
+const NODE_PREFIX: u8 = 0x01;
+
+/// Which side the sibling sits on when folding a proof
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SiblingSide {
+    Left,
+    Right,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProofStep {
+    /// Sibling node hash (hex)
+    pub sibling: String,
+    pub side: SiblingSide,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InclusionProof {
+    /// Position of the leaf in the caseload tree
+    pub leaf_index: usize,
+    /// Leaf hash (hex)
+    pub leaf_hash: String,
+    /// Path from leaf to root
+    pub path: Vec<ProofStep>,
+}
+
+/// Binary Merkle tree with domain-separated leaves and nodes.
+/// An odd node at the end of a level is promoted unchanged.
+pub struct MerkleTree {
+    levels: Vec<Vec<[u8; 32]>>,
+}
+
+impl MerkleTree {
+    pub fn build(leaves: Vec<[u8; 32]>) -> Self {
+        let mut levels = vec![leaves];
+        while levels.last().map(|l| l.len() > 1).unwrap_or(false) {
+            let current = levels.last().unwrap();
+            let next: Vec<[u8; 32]> = current
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => node_hash(left, right),
+                    [single] => *single,
+                    _ => unreachable!(),
+                })
+                .collect();
+            levels.push(next);
+        }
+        Self { levels }
+    }
+    
+    pub fn root(&self) -> Option<String> {
+        self.levels.last().and_then(|l| l.first()).map(hex::encode)
+    }
+    
+    pub fn proof(&self, leaf_index: usize) -> Option<InclusionProof> {
+        let leaf = *self.levels.first()?.get(leaf_index)?;
+        let mut path = Vec::new();
+        let mut index = leaf_index;
+        
+        for level in &self.levels[..self.levels.len() - 1] {
+            let sibling_index = index ^ 1;
+            if let Some(sibling) = level.get(sibling_index) {
+                path.push(ProofStep {
+                    sibling: hex::encode(sibling),
+                    side: if sibling_index < index { SiblingSide::Left } else { SiblingSide::Right },
+                });
+            }
+            index /= 2;
+        }
+        
+        Some(InclusionProof {
+            leaf_index,
+            leaf_hash: hex::encode(leaf),
+            path,
+        })
+    }
+}
+
+fn leaf_hash(data: &str) -> [u8; 32] {
+    use sha2::{Sha256, Digest};
+    let mut hasher = Sha256::new();
+    hasher.update([LEAF_PREFIX]);
+    hasher.update(data.as_bytes());
+    hasher.finalize().into()
+}
+
+fn node_hash(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    use sha2::{Sha256, Digest};
+    let mut hasher = Sha256::new();
+    hasher.update([NODE_PREFIX]);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+/// Leaf for a note: binds the note id to its content hash
+pub fn note_leaf(note_id: &str, content_hash: &str) -> [u8; 32] {
+    leaf_hash(&format!("note|{}|{}", note_id, content_hash))
+}
+
+/// Leaf for an audit entry: the entry hash already commits to the whole chain
+pub fn audit_leaf(entry_hash: &str) -> [u8; 32] {
+    leaf_hash(&format!("audit|{}", entry_hash))
+}
+
+/// Build caseload leaves in the canonical order (notes by id, then audit entries in chain order)
+fn caseload_leaves<'a>(
+    notes: impl Iterator<Item = (&'a str, &'a str)>,
+    audit_entry_hashes: impl Iterator<Item = &'a str>,
+) -> (Vec<[u8; 32]>, Vec<&'a str>, usize) {
+    let mut notes: Vec<(&str, &str)> = notes.collect();
+    notes.sort_by(|a, b| a.0.cmp(b.0));
+    
+    let note_ids: Vec<&str> = notes.iter().map(|(id, _)| *id).collect();
+    let mut leaves: Vec<[u8; 32]> = notes.iter().map(|(id, hash)| note_leaf(id, hash)).collect();
+    let note_count = leaves.len();
+    leaves.extend(audit_entry_hashes.map(audit_leaf));
+    
+    (leaves, note_ids, note_count)
+}
+
+/// Merkle root over notes and audit entries
+pub fn caseload_merkle_root<'a>(
+    notes: impl Iterator<Item = (&'a str, &'a str)>,
+    audit_entry_hashes: impl Iterator<Item = &'a str>,
+) -> Option<String> {
+    let (leaves, _, _) = caseload_leaves(notes, audit_entry_hashes);
+    MerkleTree::build(leaves).root()
+}
+
+/// Fold a proof up to a root and compare
+pub fn verify_inclusion(leaf: &[u8; 32], proof: &InclusionProof, root: &str) -> bool {
+    if !crate::crypto::digests_match(&hex::encode(leaf), &proof.leaf_hash) {
+        return false;
+    }
+    
+    let mut current = *leaf;
+    for step in &proof.path {
+        let sibling: [u8; 32] = match hex::decode(&step.sibling).ok().and_then(|b| b.try_into().ok()) {
+            Some(s) => s,
+            None => return false,
+        };
+        current = match step.side {
+            SiblingSide::Left => node_hash(&sibling, &current),
+            SiblingSide::Right => node_hash(&current, &sibling),
+        };
+    }
+    
+    crate::crypto::digests_match(&hex::encode(current), root)
+}
+
+/// Audit event disclosed alongside a note, with its proof
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DisclosedAuditEvent {
+    /// Full chain entry so the verifier can recompute `entry_hash`
+    pub entry: crate::models::AuditEntry,
+    pub proof: InclusionProof,
+}
+
+/// Mini audit pack proving one note against the root of the audit pack
+/// it was drawn from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SelectiveDisclosurePack {
+    pub id: String,
+    pub generated_at: DateTime<Utc>,
+    /// Audit pack whose `merkle_root` the proofs run to
+    pub pack_id: String,
+    pub note: AuditNote,
+    pub note_proof: InclusionProof,
+    pub audit_events: Vec<DisclosedAuditEvent>,
+    /// The pack's root over its notes and audit log extract
+    pub merkle_root: String,
+    pub leaf_count: usize,
+    /// Hash of the newest audit entry covered by the root
+    pub chain_head_hash: String,
+    /// Chain entry written when the pack was generated, naming its root
+    pub anchor: crate::models::AuditEntry,
+    pub schema_version: String,
+}
+
+/// Build a single-note disclosure against `pack`'s own Merkle root.
+/// The pack must still hash to the root it claims and `anchor` (the chain
+/// entry written when it was generated) must name that root.
+/// `audit_entries` supplies the full chain entries behind the pack's
+/// audit log extract; events without one are left out.
+pub fn build_disclosure(
+    pack: &AuditPack,
+    note_id: &str,
+    content: Option<String>,
+    anchor: crate::models::AuditEntry,
+    audit_entries: &[crate::models::AuditEntry],
+) -> Result<SelectiveDisclosurePack, AuditPackError> {
+    let fail = |msg: &str| AuditPackError::ChainVerificationFailed(msg.to_string());
+    let contents = &pack.contents;
+    let merkle_root = pack.metadata.merkle_root.clone()
+        .ok_or_else(|| fail("Audit pack has no Merkle root"))?;
+    if anchor.path_hash.as_deref() != Some(merkle_root.as_str()) || anchor.resource_id != pack.id {
+        return Err(fail("Audit pack root does not match the root anchored when it was generated"));
+    }
+    
+    let (leaves, note_ids, note_count) = caseload_leaves(
+        contents.notes.iter().map(|n| (n.id.as_str(), n.content_hash.as_str())),
+        contents.audit_log.iter().map(|e| e.entry_hash.as_str()),
+    );
+    let leaf_count = leaves.len();
+    let tree = MerkleTree::build(leaves);
+    if tree.root().as_deref() != Some(merkle_root.as_str()) {
+        return Err(fail("Audit pack contents do not hash to its Merkle root"));
+    }
+    
+    let note_index = note_ids.iter().position(|id| *id == note_id)
+        .ok_or(AuditPackError::NoNotesFound)?;
+    let note_proof = tree.proof(note_index).ok_or(AuditPackError::NoNotesFound)?;
+    let mut note = contents.notes.iter().find(|n| n.id == note_id)
+        .cloned()
+        .ok_or(AuditPackError::NoNotesFound)?;
+    note.content = content;
+    
+    let audit_events = contents.audit_log.iter().enumerate()
+        .filter(|(_, e)| e.resource_id == note_id)
+        .filter_map(|(i, e)| {
+            let entry = audit_entries.iter().find(|full| full.entry_hash == e.entry_hash)?;
+            tree.proof(note_count + i).map(|proof| DisclosedAuditEvent {
+                entry: entry.clone(),
+                proof,
+            })
+        })
+        .collect();
+    
+    Ok(SelectiveDisclosurePack {
+        id: uuid::Uuid::new_v4().to_string(),
+        generated_at: Utc::now(),
+        pack_id: pack.id.clone(),
+        note,
+        note_proof,
+        audit_events,
+        merkle_root,
+        leaf_count,
+        chain_head_hash: contents.audit_log.last()
+            .map(|e| e.entry_hash.clone())
+            .unwrap_or_else(|| "genesis".to_string()),
+        anchor,
+        schema_version: "1.1".to_string(),
+    })
+}
+
+/// Verify every proof in a disclosure pack against `expected_root`, the
+/// root the recipient holds from the full pack or the discloser's audit
+/// log. A pack carrying any other root is rejected however consistent it
+/// is internally. Audit entry hashes are recomputed from the disclosed fields.
+pub fn verify_disclosure(pack: &SelectiveDisclosurePack, expected_root: &str) -> Result<(), AuditPackError> {
+    let fail = |msg: String| Err(AuditPackError::ChainVerificationFailed(msg));
+    
+    if !crate::crypto::digests_match(&pack.merkle_root, expected_root) {
+        return fail("Disclosure root does not match the expected root".to_string());
+    }
+    let anchor = &pack.anchor;
+    let anchor_data = crate::audit::entry_hash_data(anchor);
+    if anchor.path_class.as_deref() != Some(crate::audit::AUDIT_PACK_ROOT_CLASS)
+        || anchor.resource_id != pack.pack_id
+        || !crate::crypto::digests_match(anchor.path_hash.as_deref().unwrap_or(""), expected_root)
+        || !crate::crypto::digests_match(&crate::crypto::hash_chain_entry(&anchor.previous_hash, anchor_data.as_bytes()), &anchor.entry_hash)
+    {
+        return fail("Anchor entry does not record this root".to_string());
+    }
+    
+    if let Some(content) = &pack.note.content {
+        if !crate::crypto::digests_match(&crate::crypto::hash_content(content.as_bytes()), &pack.note.content_hash) {
+            return fail("Note content does not match its content hash".to_string());
+        }
+    }
+    
+    let leaf = note_leaf(&pack.note.id, &pack.note.content_hash);
+    if !verify_inclusion(&leaf, &pack.note_proof, expected_root) {
+        return fail("Note is not included under the Merkle root".to_string());
+    }
+    
+    for event in &pack.audit_events {
+        let e = &event.entry;
+        if e.resource_id != pack.note.id {
+            return fail(format!("Audit entry {} does not belong to this note", e.sequence));
+        }
+        let entry_data = crate::audit::entry_hash_data(e);
+        if !crate::crypto::digests_match(&crate::crypto::hash_chain_entry(&e.previous_hash, entry_data.as_bytes()), &e.entry_hash) {
+            return fail(format!("Audit entry {} hash mismatch", e.sequence));
+        }
+        if !verify_inclusion(&audit_leaf(&e.entry_hash), &event.proof, expected_root) {
+            return fail(format!("Audit entry {} is not included under the Merkle root", e.sequence));
+        }
+    }
+    
+    Ok(())
+}
+
+// ============================================
+// Split Output
+// ============================================
+//
+// Court e-filing systems cap attachment size (commonly 25 MB). Large
+// outputs are cut into sequentially numbered parts, and a manifest binds
+// the parts together: each part's SHA-256, a Merkle root over the parts
+// in order, and the hash of the reassembled whole. Concatenating the parts
+// in index order reproduces the original file byte for byte.
+
+/// One part of a split output
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SplitPart {
+    /// 1-based position
+    pub index: u32,
+    pub file_name: String,
+    pub size_bytes: u64,
+    pub sha256: String,
+}
+
+/// Top-level manifest for a split output
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SplitManifest {
+    /// Name of the file the parts reassemble into
+    pub original_file_name: String,
+    pub total_size_bytes: u64,
+    /// SHA-256 of the reassembled file
+    pub content_hash: String,
+    pub max_part_bytes: u64,
+    pub parts: Vec<SplitPart>,
+    /// Merkle root over the parts in order; changes if any part is
+    /// swapped, dropped or reordered
+    pub parts_root: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Leaf for a part: binds its position to its hash
+fn part_leaf(index: u32, sha256: &str) -> [u8; 32] {
+    leaf_hash(&format!("part|{}|{}", index, sha256))
+}
+
+fn parts_root(parts: &[SplitPart]) -> String {
+    MerkleTree::build(parts.iter().map(|p| part_leaf(p.index, &p.sha256)).collect())
+        .root()
+        .unwrap_or_default()
+}
+
+/// Part file name: `<name>.part003-of-012`
+fn part_file_name(original: &str, index: u32, count: u32) -> String {
+    let width = count.to_string().len().max(3);
+    format!("{}.part{:0w$}-of-{:0w$}", original, index, count, w = width)
+}
+
+/// Part size limit in bytes from decimal MB, refusing sizes that overflow
+pub fn max_part_bytes(max_part_mb: Option<u64>) -> Result<Option<u64>, String> {
+    max_part_mb.map(|mb| {
+        mb.checked_mul(1_000_000).ok_or_else(|| format!("Part size limit of {} MB is too large", mb))
+    }).transpose()
+}
+
+/// Cut `content` into parts of at most `max_part_bytes` and build the manifest
+pub fn split_content<'a>(
+    content: &'a [u8],
+    original_file_name: &str,
+    max_part_bytes: u64,
+) -> Result<(SplitManifest, Vec<&'a [u8]>), AuditPackError> {
+    if max_part_bytes == 0 {
+        return Err(AuditPackError::Serialization("Part size limit must be greater than zero".to_string()));
+    }
+    let chunks: Vec<&[u8]> = content.chunks(max_part_bytes as usize).collect();
+    let count = chunks.len() as u32;
+    let parts: Vec<SplitPart> = chunks.iter().enumerate()
+        .map(|(i, chunk)| SplitPart {
+            index: i as u32 + 1,
+            file_name: part_file_name(original_file_name, i as u32 + 1, count),
+            size_bytes: chunk.len() as u64,
+            sha256: sha256_hex(chunk),
+        })
+        .collect();
+    
+    let manifest = SplitManifest {
+        original_file_name: original_file_name.to_string(),
+        total_size_bytes: content.len() as u64,
+        content_hash: sha256_hex(content),
+        max_part_bytes,
+        parts_root: parts_root(&parts),
+        parts,
+        created_at: Utc::now(),
+    };
+    Ok((manifest, chunks))
+}
+
+/// Write the parts and `<name>.manifest.json` into `destination`
+pub fn write_split_output(
+    content: &[u8],
+    destination: &Path,
+    original_file_name: &str,
+    max_part_bytes: u64,
+) -> Result<SplitManifest, AuditPackError> {
+    let (manifest, chunks) = split_content(content, original_file_name, max_part_bytes)?;
+    for (part, chunk) in manifest.parts.iter().zip(chunks) {
+        std::fs::write(destination.join(&part.file_name), chunk)?;
+    }
+    
+    let manifest_json = serde_json::to_string_pretty(&manifest)
+        .map_err(|e| AuditPackError::Serialization(e.to_string()))?;
+    std::fs::write(destination.join(format!("{}.manifest.json", original_file_name)), manifest_json)?;
+    
+    log::info!("Output split into {} parts", manifest.parts.len());
+    Ok(manifest)
+}
+
+/// Check every part against the manifest and return the reassembled bytes
+pub fn reassemble_split(manifest: &SplitManifest, parts_dir: &Path) -> Result<Vec<u8>, AuditPackError> {
+    if parts_root(&manifest.parts) != manifest.parts_root {
+        return Err(AuditPackError::ChainVerificationFailed("Manifest parts list does not match its root".to_string()));
+    }
+    
+    let mut content = Vec::with_capacity(manifest.total_size_bytes as usize);
+    for (expected_index, part) in (1..).zip(&manifest.parts) {
+        if part.index != expected_index {
+            return Err(AuditPackError::ChainVerificationFailed(format!("Part {} is out of sequence", part.index)));
+        }
+        let data = std::fs::read(parts_dir.join(&part.file_name))?;
+        if sha256_hex(&data) != part.sha256 {
+            return Err(AuditPackError::ChainVerificationFailed(format!("Part {} hash mismatch", part.index)));
+        }
+        content.extend_from_slice(&data);
+    }
+    
+    if !crate::crypto::digests_match(&sha256_hex(&content), &manifest.content_hash) {
+        return Err(AuditPackError::ChainVerificationFailed("Reassembled content hash mismatch".to_string()));
+    }
+    Ok(content)
+}
+
+// ============================================
+// Environment Fingerprint
+// ============================================
+//
+// Reviewers need to know which implementation produced a pack: hashes
+// from another canonicalization release or another rule set are not
+// comparable. The fingerprint names the app, canonicalization crate and
+// rule packs, and self_sha256 hashes those fields together so an edit
+// that forgot to update it stands out. It is a self-hash, not a signature:
+// anyone editing the fingerprint can recompute it, so it catches accidents,
+// not forgery; the pack's anchored Merkle root is what shows the pack is
+// the one this install produced. Verifying compares a pack's
+// fingerprint with this install's and reports each difference as a
+// warning; skew does not make a pack invalid.
+
+/// Rule pack name for the active policy's early-warning rules
+pub const EARLY_WARNING_RULE_PACK: &str = "early-warning-rules";
+
+/// CI build identifier, if the build set EVIDIFY_BUILD_ID
+const BUILD_ID: Option<&str> = option_env!("EVIDIFY_BUILD_ID");
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RulePackHash {
+    pub name: String,
+    pub version: String,
+    /// SHA-256 of the pack's canonical JSON
+    pub sha256: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EnvironmentFingerprint {
+    pub app_version: String,
+    pub canonicalization_version: String,
+    pub rule_packs: Vec<RulePackHash>,
+    /// Target OS and architecture of the build
+    pub os: String,
+    pub arch: String,
+    /// "release" or "debug"
+    pub build_profile: String,
+    pub build_id: Option<String>,
+    /// SHA-256 over the canonical JSON of every other field. Unkeyed:
+    /// detects accidental edits only.
+    #[serde(alias = "build_attestation_sha256")]
+    pub self_sha256: String,
+}
+
+impl EnvironmentFingerprint {
+    /// This build; includes the policy's early-warning rules when given
+    pub fn current(policy: Option<&OrganizationPolicy>) -> Self {
+        let mut rule_packs = vec![RulePackHash {
+            name: crate::ethics::RULE_PACK_NAME.to_string(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            sha256: crate::ethics::rule_pack_sha256(),
+        }];
+        if let Some(policy) = policy {
+            let rules = serde_json::to_value(&policy.early_warning_policy.rules).unwrap_or_default();
+            rule_packs.push(RulePackHash {
+                name: EARLY_WARNING_RULE_PACK.to_string(),
+                version: policy.version.clone(),
+                sha256: evidify_canonicalization::canonical_sha256(&rules),
+            });
+        }
+        let mut fingerprint = Self {
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            canonicalization_version: evidify_canonicalization::VERSION.to_string(),
+            rule_packs,
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            build_profile: if cfg!(debug_assertions) { "debug" } else { "release" }.to_string(),
+            build_id: BUILD_ID.map(str::to_string),
+            self_sha256: String::new(),
+        };
+        fingerprint.self_sha256 = fingerprint.self_hash();
+        fingerprint
+    }
+    
+    /// Hash of the fingerprint with self_sha256 blanked
+    fn self_hash(&self) -> String {
+        let mut unsigned = self.clone();
+        unsigned.self_sha256 = String::new();
+        evidify_canonicalization::canonical_sha256(&serde_json::to_value(&unsigned).unwrap_or_default())
+    }
+}
+
+/// One way the producing environment differs from the verifying one
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VersionSkewWarning {
+    /// "fingerprint", "app", "canonicalization", "build" or a rule pack name
+    pub component: String,
+    /// Value recorded in the pack
+    pub pack: Option<String>,
+    /// Value in this install
+    pub verifier: Option<String>,
+    pub message: String,
+}
+
+/// Differences between the environment that produced a pack and `current`
+pub fn check_environment(
+    recorded: Option<&EnvironmentFingerprint>,
+    current: &EnvironmentFingerprint,
+) -> Vec<VersionSkewWarning> {
+    let warn = |component: &str, pack: Option<&String>, verifier: Option<&String>, message: String| VersionSkewWarning {
+        component: component.to_string(),
+        pack: pack.cloned(),
+        verifier: verifier.cloned(),
+        message,
+    };
+    
+    let Some(recorded) = recorded else {
+        return vec![warn("fingerprint", None, None,
+            "Pack has no environment fingerprint; the producing version cannot be determined".to_string())];
+    };
+    
+    let mut warnings = Vec::new();
+    if recorded.self_sha256 != recorded.self_hash() {
+        warnings.push(warn("fingerprint", Some(&recorded.self_sha256), None,
+            "Fingerprint does not match its self-hash; it may have been edited".to_string()));
+    }
+    if recorded.app_version != current.app_version {
+        warnings.push(warn("app", Some(&recorded.app_version), Some(&current.app_version),
+            format!("Pack was generated by Evidify {}; this is {}", recorded.app_version, current.app_version)));
+    }
+    if recorded.canonicalization_version != current.canonicalization_version {
+        warnings.push(warn("canonicalization", Some(&recorded.canonicalization_version), Some(&current.canonicalization_version),
+            format!("Canonicalization {} produced the pack's hashes; this verifier uses {}",
+                recorded.canonicalization_version, current.canonicalization_version)));
+    }
+    if (&recorded.build_profile, &recorded.build_id) != (&current.build_profile, &current.build_id) {
+        let describe = |f: &EnvironmentFingerprint| format!("{} {}", f.build_profile, f.build_id.as_deref().unwrap_or("(no build id)"));
+        warnings.push(warn("build", Some(&describe(recorded)), Some(&describe(current)),
+            "Pack was produced by a different build".to_string()));
+    }
+    
+    for pack_rules in &recorded.rule_packs {
+        match current.rule_packs.iter().find(|r| r.name == pack_rules.name) {
+            Some(ours) if ours.sha256 == pack_rules.sha256 => {}
+            Some(ours) => warnings.push(warn(&pack_rules.name, Some(&pack_rules.sha256), Some(&ours.sha256),
+                format!("Rule pack {} differs (pack {} vs this install {})", pack_rules.name, pack_rules.version, ours.version))),
+            None => warnings.push(warn(&pack_rules.name, Some(&pack_rules.sha256), None,
+                format!("Rule pack {} is not loaded in this install", pack_rules.name))),
+        }
+    }
+    warnings
+}
+
 // ============================================
 // Helper Functions
 // ============================================
@@ -533,11 +1164,13 @@ fn sha256_hex(data: &[u8]) -> String {
 
 use tauri::State;
 use crate::commands::AppState;
+use crate::policy::PolicyState;
 
 /// Generate audit pack
 #[tauri::command]
 pub async fn generate_audit_pack(
     state: State<'_, AppState>,
+    policy_state: State<'_, PolicyState>,
     config: AuditPackConfig,
 ) -> Result<AuditPack, String> {
     let vault = state.vault.lock().map_err(|e| e.to_string())?;
@@ -581,26 +1214,128 @@ pub async fn generate_audit_pack(
     // Chain verification not implemented yet - placeholder
     let chain_verification: Option<ChainVerification> = None;
     
-    let generator = AuditPackGenerator::new(config);
-    generator.generate(
+    let include_scorecards = config.include_scorecards;
+    let policy = policy_state.engine.read().map_err(|e| e.to_string())?.get_policy().clone();
+    let generator = AuditPackGenerator::new(config).with_policy(&policy);
+    let mut pack = generator.generate(
         notes,
         amendments,
         attestations,
         audit_log,
         chain_verification,
         "current_user",  // Would get from vault
-    ).map_err(|e| e.to_string())
+    ).map_err(|e| e.to_string())?;
+    
+    if include_scorecards {
+        pack.contents.scorecards = pack.clients.iter()
+            .map(|c| vault.get_client_documentation_scorecard(&c.id))
+            .collect::<Result<_, _>>()
+            .map_err(|e| e.to_string())?;
+    }
+    
+    // Anchor the root so disclosures drawn from this pack can be checked
+    // against the chain rather than against the pack's own claim
+    if let Some(root) = &pack.metadata.merkle_root {
+        let conn = vault.get_connection().map_err(|e| e.to_string())?;
+        crate::audit::log_audit_pack_root(conn, &pack.id, root).map_err(|e| e.to_string())?;
+    }
+    
+    Ok(pack)
+}
+
+/// Generate a selective-disclosure pack for one note of an audit pack
+/// generated here. The proofs run to that pack's anchored Merkle root.
+#[tauri::command]
+pub fn generate_note_disclosure(
+    state: State<'_, AppState>,
+    pack: AuditPack,
+    note_id: String,
+    include_content: bool,
+) -> Result<SelectiveDisclosurePack, String> {
+    let vault = state.vault.lock().map_err(|e| e.to_string())?;
+    
+    if !vault.is_unlocked() {
+        return Err("Vault is not unlocked".to_string());
+    }
+    let conn = vault.get_connection().map_err(|e| e.to_string())?;
+    
+    let anchor = crate::audit::get_audit_pack_root(conn, &pack.id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Audit pack {} was not generated by this vault", pack.id))?;
+    
+    let packed = pack.contents.notes.iter().find(|n| n.id == note_id)
+        .ok_or_else(|| format!("Note not found in audit pack: {}", note_id))?;
+    let content = match (include_content, &packed.content) {
+        (false, _) => None,
+        (true, Some(content)) => Some(content.clone()),
+        (true, None) => {
+            let note = vault.get_note(&note_id).map_err(|e| e.to_string())?;
+            if note.content_hash != packed.content_hash {
+                return Err("Note has changed since the audit pack was generated".to_string());
+            }
+            Some(note.raw_input)
+        }
+    };
+    
+    let event_hashes: Vec<&str> = pack.contents.audit_log.iter()
+        .filter(|e| e.resource_id == note_id)
+        .map(|e| e.entry_hash.as_str())
+        .collect();
+    let audit_entries = crate::audit::get_entries_by_hash(conn, &event_hashes).map_err(|e| e.to_string())?;
+    
+    let disclosure = build_disclosure(&pack, &note_id, content, anchor, &audit_entries)
+        .map_err(|e| e.to_string())?;
+    
+    let _ = crate::audit::log_event(
+        conn,
+        crate::models::AuditEventType::NoteExported,
+        crate::models::AuditResourceType::Note,
+        &note_id,
+        crate::models::AuditOutcome::Success,
+        None,
+    );
+    
+    Ok(disclosure)
+}
+
+/// Verify a selective-disclosure pack (e.g. one received from another
+/// practice) against the root the recipient was given for the full pack
+#[tauri::command]
+pub fn verify_note_disclosure(pack: SelectiveDisclosurePack, expected_root: String) -> Result<bool, String> {
+    match verify_disclosure(&pack, &expected_root) {
+        Ok(()) => Ok(true),
+        Err(AuditPackError::ChainVerificationFailed(reason)) => {
+            log::warn!("Disclosure verification failed: {}", reason);
+            Ok(false)
+        }
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Compare the environment that produced `pack` with this install.
+/// Empty when versions and rule packs match.
+#[tauri::command]
+pub fn verify_audit_pack_environment(
+    policy_state: State<'_, PolicyState>,
+    pack: AuditPack,
+) -> Result<Vec<VersionSkewWarning>, String> {
+    let engine = policy_state.engine.read().map_err(|e| e.to_string())?;
+    let current = EnvironmentFingerprint::current(Some(engine.get_policy()));
+    Ok(check_environment(pack.metadata.environment.as_ref(), &current))
 }
 
-/// Export audit pack to file
+/// Export audit pack to file. `max_part_mb` splits the output into parts
+/// under that size (decimal MB, matching how e-filing limits are stated).
 #[tauri::command]
 pub async fn export_audit_pack(
     pack: AuditPack,
     destination: String,
     format: AuditPackFormat,
+    max_part_mb: Option<u64>,
 ) -> Result<ExportCertificate, String> {
     let config = AuditPackConfig {
         output_format: format,
+        max_part_bytes: max_part_bytes(max_part_mb)?,
         ..Default::default()
     };
     
@@ -625,4 +1360,157 @@ mod tests {
         let hash = sha256_hex(b"test");
         assert_eq!(hash.len(), 64);
     }
+    
+    #[test]
+    fn test_max_part_bytes_overflow() {
+        assert_eq!(max_part_bytes(None), Ok(None));
+        assert_eq!(max_part_bytes(Some(25)), Ok(Some(25_000_000)));
+        assert!(max_part_bytes(Some(u64::MAX / 1_000)).is_err());
+    }
+    
+    #[test]
+    fn test_split_and_reassemble() {
+        let dir = std::env::temp_dir().join(format!("evidify-split-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let content: Vec<u8> = (0..2500u32).map(|i| (i % 251) as u8).collect();
+        
+        let manifest = write_split_output(&content, &dir, "pack.json", 1000).unwrap();
+        assert_eq!(manifest.parts.len(), 3);
+        assert_eq!(manifest.parts[0].file_name, "pack.json.part001-of-003");
+        assert!(manifest.parts.iter().all(|p| p.size_bytes <= 1000));
+        assert_eq!(reassemble_split(&manifest, &dir).unwrap(), content);
+        
+        // Reordering parts in the manifest breaks the binding root
+        let mut reordered = manifest.clone();
+        reordered.parts.swap(0, 1);
+        assert!(reassemble_split(&reordered, &dir).is_err());
+        
+        // A modified part is caught
+        std::fs::write(dir.join(&manifest.parts[2].file_name), b"tampered").unwrap();
+        assert!(reassemble_split(&manifest, &dir).is_err());
+        
+        std::fs::remove_dir_all(&dir).ok();
+    }
+    
+    #[test]
+    fn test_merkle_proofs_all_sizes() {
+        for size in 1..=9 {
+            let leaves: Vec<[u8; 32]> = (0..size).map(|i| audit_leaf(&format!("entry-{}", i))).collect();
+            let tree = MerkleTree::build(leaves.clone());
+            let root = tree.root().unwrap();
+            
+            for (i, leaf) in leaves.iter().enumerate() {
+                let proof = tree.proof(i).unwrap();
+                assert!(verify_inclusion(leaf, &proof, &root), "size {} leaf {}", size, i);
+                assert!(!verify_inclusion(&audit_leaf("forged"), &proof, &root));
+            }
+        }
+    }
+    
+    #[test]
+    fn test_environment_skew_warnings() {
+        let policy = OrganizationPolicy::default();
+        let current = EnvironmentFingerprint::current(Some(&policy));
+        assert_eq!(current.self_sha256.len(), 64);
+        assert!(check_environment(Some(&current), &current).is_empty());
+        assert_eq!(check_environment(None, &current)[0].component, "fingerprint");
+        
+        // An older release with different rules, honestly recorded
+        let mut older = current.clone();
+        older.app_version = "4.1.0".to_string();
+        older.canonicalization_version = "1.0.0".to_string();
+        older.rule_packs[0].sha256 = "0".repeat(64);
+        older.self_sha256 = older.self_hash();
+        let components: Vec<String> = check_environment(Some(&older), &current).into_iter().map(|w| w.component).collect();
+        assert_eq!(components, vec!["app", "canonicalization", crate::ethics::RULE_PACK_NAME]);
+        
+        // Packs written before the rename still read
+        let renamed = serde_json::to_string(&older).unwrap().replace("\"self_sha256\"", "\"build_attestation_sha256\"");
+        assert_eq!(serde_json::from_str::<EnvironmentFingerprint>(&renamed).unwrap(), older);
+        
+        // Editing a field without the self-hash is flagged
+        let mut edited = current.clone();
+        edited.app_version = "9.9.9".to_string();
+        assert_eq!(check_environment(Some(&edited), &current)[0].component, "fingerprint");
+    }
+    
+    fn disclosure_fixture() -> (AuditPack, crate::models::AuditEntry) {
+        let note = |id: &str, content: &str| AuditNote {
+            id: id.to_string(),
+            client_id: "client-1".to_string(),
+            note_type: "Progress".to_string(),
+            created_at: Utc::now(),
+            signed_at: None,
+            signed_by: None,
+            content: Some(content.to_string()),
+            content_hash: crate::crypto::hash_content(content.as_bytes()),
+            has_amendments: false,
+            has_attestations: false,
+        };
+        let config = AuditPackConfig {
+            start_date: Utc::now() - chrono::Duration::days(1),
+            end_date: Utc::now() + chrono::Duration::days(1),
+            ..Default::default()
+        };
+        let pack = AuditPackGenerator::new(config).generate(
+            vec![note("note-a", "First"), note("note-b", "Session note content"), note("note-c", "Third")],
+            vec![], vec![], vec![], None, "tester",
+        ).unwrap();
+        
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE audit_log (id TEXT PRIMARY KEY, timestamp INTEGER, sequence INTEGER, event_type TEXT,
+                 resource_type TEXT, resource_id TEXT, outcome TEXT, detection_ids TEXT, path_class TEXT,
+                 path_hash TEXT, previous_hash TEXT, entry_hash TEXT)",
+        ).unwrap();
+        let root = pack.metadata.merkle_root.clone().unwrap();
+        let anchor = crate::audit::log_audit_pack_root(&conn, &pack.id, &root).unwrap();
+        (pack, anchor)
+    }
+    
+    #[test]
+    fn test_disclosure_detects_tampering() {
+        let (pack, anchor) = disclosure_fixture();
+        let root = pack.metadata.merkle_root.clone().unwrap();
+        
+        let mut disclosure = build_disclosure(&pack, "note-b", Some("Session note content".to_string()), anchor, &[])
+            .unwrap();
+        assert_eq!(disclosure.merkle_root, root);
+        assert!(verify_disclosure(&disclosure, &root).is_ok());
+        
+        disclosure.note.content = Some("Altered content".to_string());
+        assert!(verify_disclosure(&disclosure, &root).is_err());
+    }
+    
+    #[test]
+    fn test_forged_pack_fails_disclosure() {
+        let (pack, anchor) = disclosure_fixture();
+        let anchored_root = pack.metadata.merkle_root.clone().unwrap();
+        
+        // Altered note with the root recomputed: internally consistent
+        let mut forged = pack.clone();
+        forged.contents.notes[1].content_hash = crate::crypto::hash_content(b"Forged content");
+        forged.metadata.merkle_root = caseload_merkle_root(
+            forged.contents.notes.iter().map(|n| (n.id.as_str(), n.content_hash.as_str())),
+            std::iter::empty(),
+        );
+        let forged_root = forged.metadata.merkle_root.clone().unwrap();
+        
+        // Not anchored, so no disclosure can be built from it here
+        assert!(build_disclosure(&forged, "note-b", None, anchor.clone(), &[]).is_err());
+        
+        // Nor does one hand-built around the forged root verify against the anchored root
+        let mut disclosure = build_disclosure(&pack, "note-b", None, anchor, &[]).unwrap();
+        let tree = MerkleTree::build(forged.contents.notes.iter().map(|n| note_leaf(&n.id, &n.content_hash)).collect());
+        disclosure.note.content_hash = forged.contents.notes[1].content_hash.clone();
+        disclosure.note_proof = tree.proof(1).unwrap();
+        disclosure.merkle_root = forged_root.clone();
+        assert!(verify_disclosure(&disclosure, &forged_root).is_err());
+        assert!(verify_disclosure(&disclosure, &anchored_root).is_err());
+        
+        // A pack whose contents no longer hash to its root is refused too
+        let mut tampered = pack.clone();
+        tampered.contents.notes[1].content_hash = crate::crypto::hash_content(b"Forged content");
+        assert!(build_disclosure(&tampered, "note-b", None, disclosure.anchor.clone(), &[]).is_err());
+    }
 }
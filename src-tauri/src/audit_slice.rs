@@ -0,0 +1,342 @@
+// Audit Slice Module
+//
+// Signed excerpts of the audit log that move between Evidify installs.
+// When a trainee changes practices, the previous install exports the
+// audit entries covering the supervision period, signed with its install
+// key. The new supervisor imports the slice, pinning the exporting
+// install's key fingerprint (obtained out of band), and the vault keeps
+// the verified slice as externally attested evidence. Imported entries
+// never join the local chain: they are stored beside it, and the import
+// itself is an event on the local chain.
+//
+// A slice carries only the trainee's own entries: events on the notes
+// they submitted for review and on those notes' charts. Practice-wide
+// events, other clinicians' notes and client lookup hashes stay behind.
+// So the chain can still be checked, the slice lists the sequence and
+// hash of every entry in the period; omitted entries appear there only
+// as hashes.
+
+use serde::{Deserialize, Serialize};
+
+use crate::crypto::signing::{self, InstallSigningKey};
+use crate::models::AuditEntry;
+
+/// Slice layout version; covered by the signature
+pub const SLICE_FORMAT_VERSION: u8 = 2;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditSlice {
+    pub format_version: u8,
+    pub slice_id: String,
+    pub exported_at: i64,
+    /// Practice name of the exporting install, as it chose to present itself
+    pub exporter_label: String,
+    /// Base64 Ed25519 install key
+    pub exporter_public_key: String,
+    /// Trainee whose entries the slice carries, as named on the exporting install
+    pub trainee_name: String,
+    pub purpose: String,
+    /// Requested period (ms); entries are the contiguous run covering it
+    pub period_start: i64,
+    pub period_end: i64,
+    /// The trainee's entries, oldest first
+    pub entries: Vec<AuditEntry>,
+    /// Every entry in the period, kept or not, by sequence and hash
+    pub chain: Vec<ChainLink>,
+    /// Base64 signature over `signing_payload`
+    #[serde(default)]
+    pub signature: String,
+}
+
+/// One position in the exporting install's chain
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChainLink {
+    pub sequence: i64,
+    pub entry_hash: String,
+}
+
+/// Split a contiguous run of entries into the ones `keep` accepts and
+/// links for the whole run
+pub fn redact_run(run: Vec<AuditEntry>, keep: impl Fn(&AuditEntry) -> bool) -> (Vec<AuditEntry>, Vec<ChainLink>) {
+    let chain = run.iter()
+        .map(|e| ChainLink { sequence: e.sequence, entry_hash: e.entry_hash.clone() })
+        .collect();
+    (run.into_iter().filter(|e| keep(e)).collect(), chain)
+}
+
+/// Check the links run without gaps and each kept entry hashes to its link
+/// and follows the link before it
+pub fn verify_redacted_run(entries: &[AuditEntry], chain: &[ChainLink]) -> Result<(), String> {
+    if chain.windows(2).any(|w| w[1].sequence != w[0].sequence + 1) {
+        return Err("chain links are not contiguous".to_string());
+    }
+    let first = chain.first().map(|l| l.sequence).unwrap_or_default();
+    for entry in entries {
+        let computed = crate::crypto::hash_chain_entry(
+            &entry.previous_hash,
+            crate::audit::entry_hash_data(entry).as_bytes(),
+        );
+        let position = usize::try_from(entry.sequence - first).ok()
+            .filter(|&i| i < chain.len())
+            .ok_or_else(|| format!("entry {} is outside the chain", entry.sequence))?;
+        if !crate::crypto::digests_match(&computed, &entry.entry_hash)
+            || !crate::crypto::digests_match(&entry.entry_hash, &chain[position].entry_hash) {
+            return Err(format!("entry {} does not match its hash", entry.sequence));
+        }
+        let linked = match position {
+            0 => entry.sequence != 1 || entry.previous_hash == "genesis",
+            _ => crate::crypto::digests_match(&entry.previous_hash, &chain[position - 1].entry_hash),
+        };
+        if !linked {
+            return Err(format!("entry {} does not follow the entry before it", entry.sequence));
+        }
+    }
+    Ok(())
+}
+
+impl AuditSlice {
+    /// Bytes covered by the signature: the slice with the signature blanked
+    pub fn signing_payload(&self) -> Result<Vec<u8>, String> {
+        let unsigned = AuditSlice { signature: String::new(), ..self.clone() };
+        serde_json::to_vec(&unsigned).map_err(|e| e.to_string())
+    }
+
+    pub fn sign(mut self, key: &InstallSigningKey) -> Result<Self, String> {
+        self.exporter_public_key = key.public_key_base64();
+        self.signature = key.sign(&self.signing_payload()?);
+        Ok(self)
+    }
+}
+
+/// Public half of the install key, for sharing with whoever will verify
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstallSigningIdentity {
+    pub public_key: String,
+    pub fingerprint: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SliceVerification {
+    pub slice_id: String,
+    pub exporter_fingerprint: String,
+    /// Slice was signed by the key the importer expected
+    pub key_pinned: bool,
+    pub signature_valid: bool,
+    pub chain_valid: bool,
+    pub entry_count: usize,
+    pub errors: Vec<String>,
+}
+
+impl SliceVerification {
+    pub fn verified(&self) -> bool {
+        self.key_pinned && self.signature_valid && self.chain_valid && self.errors.is_empty()
+    }
+}
+
+/// Imported slice as stored in the vault
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExternalAuditRecord {
+    pub id: String,
+    pub slice_id: String,
+    pub exporter_label: String,
+    pub exporter_fingerprint: String,
+    pub purpose: String,
+    pub period_start: i64,
+    pub period_end: i64,
+    pub entry_count: u32,
+    pub imported_at: i64,
+}
+
+/// Check a slice against the fingerprint the importer was given for the
+/// exporting install. All checks run so the report lists every failure.
+pub fn verify_slice(slice: &AuditSlice, expected_fingerprint: &str) -> SliceVerification {
+    let mut errors = Vec::new();
+
+    if slice.format_version != SLICE_FORMAT_VERSION {
+        errors.push(format!("Unsupported slice format version {}", slice.format_version));
+    }
+    if slice.entries.is_empty() {
+        errors.push("Slice contains no audit entries".to_string());
+    }
+
+    let exporter_fingerprint = signing::fingerprint(&slice.exporter_public_key).unwrap_or_default();
+    let expected = expected_fingerprint.trim().to_lowercase().replace([':', ' '], "");
+    let key_pinned = !exporter_fingerprint.is_empty()
+        && crate::crypto::digests_match(&exporter_fingerprint, &expected);
+    if !key_pinned {
+        errors.push("Slice was not signed by the expected install key".to_string());
+    }
+
+    let signature_valid = slice.signing_payload()
+        .and_then(|payload| {
+            signing::verify(&slice.exporter_public_key, &payload, &slice.signature).map_err(|e| e.to_string())
+        })
+        .map_err(|e| errors.push(e))
+        .is_ok();
+
+    let chain_valid = verify_redacted_run(&slice.entries, &slice.chain)
+        .map_err(|e| errors.push(format!("Audit chain: {}", e)))
+        .is_ok();
+
+    SliceVerification {
+        slice_id: slice.slice_id.clone(),
+        exporter_fingerprint,
+        key_pinned,
+        signature_valid,
+        chain_valid,
+        entry_count: slice.entries.len(),
+        errors,
+    }
+}
+
+// ============================================
+// Tauri Commands
+// ============================================
+
+use tauri::State;
+use crate::commands::AppState;
+
+/// This install's public signing key and fingerprint
+#[tauri::command]
+pub fn get_install_signing_identity(state: State<'_, AppState>) -> Result<InstallSigningIdentity, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.install_signing_identity().map_err(|e| format!("{}", e))
+}
+
+/// Signed slice of one trainee's audit entries between two timestamps (ms)
+#[tauri::command]
+pub fn export_audit_slice(
+    state: State<'_, AppState>,
+    trainee_id: String,
+    period_start: i64,
+    period_end: i64,
+    purpose: String,
+) -> Result<AuditSlice, String> {
+    if period_end < period_start {
+        return Err("Period end is before period start".to_string());
+    }
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.export_audit_slice(&trainee_id, period_start, period_end, &purpose).map_err(|e| format!("{}", e))
+}
+
+/// Verify a slice from another install and store it if it checks out
+#[tauri::command]
+pub fn import_audit_slice(
+    state: State<'_, AppState>,
+    slice: AuditSlice,
+    expected_fingerprint: String,
+) -> Result<ExternalAuditRecord, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.import_audit_slice(&slice, &expected_fingerprint).map_err(|e| format!("{}", e))
+}
+
+#[tauri::command]
+pub fn list_external_audit_slices(state: State<'_, AppState>) -> Result<Vec<ExternalAuditRecord>, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.list_external_audit_slices().map_err(|e| format!("{}", e))
+}
+
+/// A stored slice as received, for re-verification or inclusion in a pack
+#[tauri::command]
+pub fn get_external_audit_slice(state: State<'_, AppState>, id: String) -> Result<AuditSlice, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.get_external_audit_slice(&id).map_err(|e| format!("{}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{AuditEventType, AuditOutcome, AuditResourceType};
+
+    fn chained_entries(count: i64) -> Vec<AuditEntry> {
+        let mut previous = "genesis".to_string();
+        (1..=count).map(|sequence| {
+            let mut entry = AuditEntry {
+                id: format!("e{}", sequence),
+                timestamp: 1_700_000_000_000 + sequence,
+                sequence,
+                event_type: AuditEventType::NoteSigned,
+                resource_type: AuditResourceType::Note,
+                resource_id: "note-1".to_string(),
+                outcome: AuditOutcome::Success,
+                detection_ids: None,
+                path_class: None,
+                path_hash: None,
+                previous_hash: previous.clone(),
+                entry_hash: String::new(),
+            };
+            entry.entry_hash = crate::crypto::hash_chain_entry(
+                &entry.previous_hash,
+                crate::audit::entry_hash_data(&entry).as_bytes(),
+            );
+            previous = entry.entry_hash.clone();
+            entry
+        }).collect()
+    }
+
+    fn signed_slice(key: &InstallSigningKey) -> AuditSlice {
+        let entries = chained_entries(3);
+        let chain = redact_run(entries.clone(), |_| true).1;
+        AuditSlice {
+            format_version: SLICE_FORMAT_VERSION,
+            slice_id: "slice-1".to_string(),
+            exported_at: 1_700_000_100_000,
+            exporter_label: "Riverside Psychology".to_string(),
+            exporter_public_key: String::new(),
+            trainee_name: "Intern A".to_string(),
+            purpose: "supervision history".to_string(),
+            period_start: 1_700_000_000_000,
+            period_end: 1_700_000_000_010,
+            entries,
+            chain,
+            signature: String::new(),
+        }.sign(key).unwrap()
+    }
+
+    #[test]
+    fn test_slice_verifies_against_pinned_key() {
+        let key = InstallSigningKey::generate();
+        let fingerprint = signing::fingerprint(&key.public_key_base64()).unwrap();
+        let slice = signed_slice(&key);
+
+        let report = verify_slice(&slice, &fingerprint.to_uppercase());
+        assert!(report.verified(), "{:?}", report.errors);
+        assert_eq!(report.entry_count, 3);
+
+        // A slice re-signed by some other key is not the install we pinned
+        let impostor = signed_slice(&InstallSigningKey::generate());
+        let report = verify_slice(&impostor, &fingerprint);
+        assert!(report.signature_valid && !report.key_pinned && !report.verified());
+    }
+
+    #[test]
+    fn test_tampered_slice_is_rejected() {
+        let key = InstallSigningKey::generate();
+        let fingerprint = signing::fingerprint(&key.public_key_base64()).unwrap();
+
+        let mut slice = signed_slice(&key);
+        slice.entries[1].outcome = AuditOutcome::Failure;
+        let report = verify_slice(&slice, &fingerprint);
+        assert!(!report.signature_valid && !report.chain_valid);
+
+        // Dropping a link and re-signing still breaks the chain
+        let mut slice = signed_slice(&key);
+        slice.chain.remove(1);
+        let slice = slice.sign(&key).unwrap();
+        let report = verify_slice(&slice, &fingerprint);
+        assert!(report.signature_valid && !report.chain_valid);
+    }
+
+    #[test]
+    fn test_redacted_entries_still_verify_against_the_chain() {
+        let (kept, chain) = redact_run(chained_entries(5), |e| e.sequence % 2 == 0);
+        assert_eq!(kept.iter().map(|e| e.sequence).collect::<Vec<_>>(), [2, 4]);
+        assert_eq!(chain.len(), 5);
+        assert!(verify_redacted_run(&kept, &chain).is_ok());
+
+        // An omitted entry's hash can't be swapped out from under a kept one
+        let mut tampered = chain.clone();
+        tampered[2].entry_hash = "0".repeat(64);
+        assert!(verify_redacted_run(&kept, &tampered).is_err());
+    }
+}
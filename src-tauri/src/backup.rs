@@ -0,0 +1,541 @@
+// Encrypted Backup and Restore
+//
+// A backup is one file a clinician can keep on an external drive: the
+// SQLCipher database (already encrypted with the vault key) together with
+// the keychain's salt and wrapped vault key, sealed again under a key
+// derived from a separate backup passphrase. Restoring on a new machine
+// needs both the backup passphrase and the vault passphrase that was in
+// use when the backup was made.
+//
+// Layout:
+//   EVIDIFY-BACKUP\n
+//   <header JSON>\n          (authenticated as associated data)
+//   <AES-256-GCM ciphertext> of [u32 manifest length][manifest JSON][database]
+//
+// The header records the ciphertext's SHA-256, so a damaged file is caught
+// before any key derivation; the manifest records the database's SHA-256,
+// checked again after decryption. Nothing is replaced until every check
+// passes and the restored database opens.
+
+use aes_gcm::{
+    aead::{AeadInPlace, KeyInit},
+    Aes256Gcm, Nonce,
+};
+use base64::Engine;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::Path;
+use thiserror::Error;
+
+use crate::crypto::{self, VaultKey, WrappedVaultKey, KEK};
+use crate::vault::{Vault, VaultError};
+
+/// First line of every backup file
+pub const BACKUP_MAGIC: &[u8] = b"EVIDIFY-BACKUP\n";
+
+/// Header and payload layout version
+pub const BACKUP_FORMAT_VERSION: u8 = 1;
+
+#[derive(Error, Debug)]
+pub enum BackupError {
+    #[error("Not an Evidify backup")]
+    NotABackup,
+
+    #[error("Unsupported backup format version {0}")]
+    UnsupportedVersion(u8),
+
+    #[error("Backup is damaged: {0}")]
+    Corrupt(String),
+
+    #[error("Invalid backup passphrase")]
+    InvalidBackupPassphrase,
+
+    #[error("The vault passphrase does not open this backup")]
+    InvalidVaultPassphrase,
+
+    #[error("Vault error: {0}")]
+    Vault(#[from] VaultError),
+
+    #[error("Crypto error: {0}")]
+    Crypto(#[from] crypto::CryptoError),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Readable without the passphrase
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupHeader {
+    pub format_version: u8,
+    pub backup_id: String,
+    pub created_at: i64,
+    pub app_version: String,
+    /// Salt for the backup passphrase KEK (base64)
+    pub kdf_salt: String,
+    /// Random data key wrapped under the backup passphrase KEK (base64)
+    pub wrapped_data_key: String,
+    pub nonce: String,
+    pub ciphertext_sha256: String,
+    pub ciphertext_len: u64,
+}
+
+/// Sealed inside the backup
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct BackupManifest {
+    /// Keychain entries of the vault at backup time (base64)
+    vault_salt: String,
+    vault_wrapped_key: String,
+    database_sha256: String,
+    database_len: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BackupInfo {
+    pub backup_id: String,
+    pub created_at: i64,
+    pub size_bytes: u64,
+    /// SHA-256 of the whole backup file
+    pub file_sha256: String,
+    pub database_sha256: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RestoreReport {
+    pub backup_id: String,
+    pub backup_created_at: i64,
+    pub database_sha256: String,
+    /// Where the replaced database was moved, if there was one
+    pub previous_database: Option<String>,
+    /// Its keychain entries, kept beside it so it can still be opened
+    pub previous_keychain: Option<String>,
+}
+
+fn b64(bytes: &[u8]) -> String {
+    base64::engine::general_purpose::STANDARD.encode(bytes)
+}
+
+fn unb64(field: &str, encoded: &str) -> Result<Vec<u8>, BackupError> {
+    base64::engine::general_purpose::STANDARD
+        .decode(encoded)
+        .map_err(|_| BackupError::Corrupt(format!("{} is not base64", field)))
+}
+
+fn salt_from(field: &str, encoded: &str) -> Result<[u8; 16], BackupError> {
+    unb64(field, encoded)?
+        .try_into()
+        .map_err(|_| BackupError::Corrupt(format!("{} is not 16 bytes", field)))
+}
+
+// ============================================
+// Archive Format
+// ============================================
+
+/// A sealed backup: the magic line and header, then the ciphertext. Kept
+/// as two buffers so the database is never copied to join them.
+struct SealedArchive {
+    header: BackupHeader,
+    head: Vec<u8>,
+    ciphertext: Vec<u8>,
+}
+
+impl SealedArchive {
+    fn len(&self) -> usize {
+        self.head.len() + self.ciphertext.len()
+    }
+
+    fn sha256(&self) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(&self.head);
+        hasher.update(&self.ciphertext);
+        hex::encode(hasher.finalize())
+    }
+}
+
+/// Seal `database` and the vault's keychain entries under
+/// `backup_passphrase`. The database buffer becomes the ciphertext: it is
+/// prefixed with the manifest and encrypted in place.
+fn seal_archive(
+    backup_id: &str,
+    mut database: Vec<u8>,
+    vault_salt: &[u8; 16],
+    vault_wrapped: &WrappedVaultKey,
+    backup_passphrase: &str,
+) -> Result<SealedArchive, BackupError> {
+    let manifest = BackupManifest {
+        vault_salt: b64(vault_salt),
+        vault_wrapped_key: b64(&vault_wrapped.to_bytes()),
+        database_sha256: crypto::hash_sha256(&database),
+        database_len: database.len() as u64,
+    };
+    let manifest_json = serde_json::to_vec(&manifest).map_err(|e| BackupError::Corrupt(e.to_string()))?;
+    let mut prefix = Vec::with_capacity(4 + manifest_json.len());
+    prefix.extend_from_slice(&(manifest_json.len() as u32).to_be_bytes());
+    prefix.extend_from_slice(&manifest_json);
+    // Room for the prefix and the 16-byte GCM tag, so neither reallocates
+    database.reserve_exact(prefix.len() + 16);
+    database.splice(0..0, prefix);
+    let mut buffer = database;
+
+    let kdf_salt = crypto::generate_salt();
+    let data_key = VaultKey::generate();
+    let wrapped_data_key = KEK::derive(backup_passphrase, &kdf_salt)?.wrap(&data_key)?;
+    let mut nonce = [0u8; 12];
+    rand::rngs::OsRng.fill_bytes(&mut nonce);
+
+    // The ciphertext hash goes in the header, which is the associated
+    // data, so the header is final before encrypting. GCM adds a 16-byte tag.
+    let ciphertext_len = buffer.len() as u64 + 16;
+    let cipher = Aes256Gcm::new_from_slice(&data_key.0).map_err(|_| crypto::CryptoError::InvalidKeyLength)?;
+    let mut header = BackupHeader {
+        format_version: BACKUP_FORMAT_VERSION,
+        backup_id: backup_id.to_string(),
+        created_at: chrono::Utc::now().timestamp(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        kdf_salt: b64(&kdf_salt),
+        wrapped_data_key: b64(&wrapped_data_key.to_bytes()),
+        nonce: b64(&nonce),
+        ciphertext_sha256: String::new(),
+        ciphertext_len,
+    };
+    cipher
+        .encrypt_in_place(Nonce::from_slice(&nonce), &associated_data(&header)?, &mut buffer)
+        .map_err(|e| crypto::CryptoError::Encryption(e.to_string()))?;
+    header.ciphertext_sha256 = crypto::hash_sha256(&buffer);
+
+    let header_json = serde_json::to_vec(&header).map_err(|e| BackupError::Corrupt(e.to_string()))?;
+    let mut head = Vec::with_capacity(BACKUP_MAGIC.len() + header_json.len() + 1);
+    head.extend_from_slice(BACKUP_MAGIC);
+    head.extend_from_slice(&header_json);
+    head.push(b'\n');
+    Ok(SealedArchive { header, head, ciphertext: buffer })
+}
+
+/// The header as authenticated: everything but the ciphertext hash
+fn associated_data(header: &BackupHeader) -> Result<Vec<u8>, BackupError> {
+    let mut unhashed = header.clone();
+    unhashed.ciphertext_sha256 = String::new();
+    serde_json::to_vec(&unhashed).map_err(|e| BackupError::Corrupt(e.to_string()))
+}
+
+/// Split a backup file into its header and ciphertext, checking the
+/// ciphertext hash; no passphrase needed
+pub fn read_header(archive: &[u8]) -> Result<(BackupHeader, &[u8]), BackupError> {
+    let rest = archive.strip_prefix(BACKUP_MAGIC).ok_or(BackupError::NotABackup)?;
+    let newline = rest.iter().position(|&b| b == b'\n').ok_or(BackupError::NotABackup)?;
+    let header: BackupHeader = serde_json::from_slice(&rest[..newline])
+        .map_err(|e| BackupError::Corrupt(format!("header: {}", e)))?;
+    if header.format_version != BACKUP_FORMAT_VERSION {
+        return Err(BackupError::UnsupportedVersion(header.format_version));
+    }
+    let ciphertext = &rest[newline + 1..];
+    if ciphertext.len() as u64 != header.ciphertext_len {
+        return Err(BackupError::Corrupt(format!(
+            "{} bytes of data, header says {}",
+            ciphertext.len(),
+            header.ciphertext_len
+        )));
+    }
+    if !crypto::digests_match(&crypto::hash_sha256(ciphertext), &header.ciphertext_sha256) {
+        return Err(BackupError::Corrupt("data does not match its hash".to_string()));
+    }
+    Ok((header, ciphertext))
+}
+
+/// Decrypt a backup: its header, manifest and database bytes. The
+/// archive buffer is decrypted in place and becomes the database.
+fn open_archive(
+    mut archive: Vec<u8>,
+    backup_passphrase: &str,
+) -> Result<(BackupHeader, BackupManifest, Vec<u8>), BackupError> {
+    let (header, ciphertext) = read_header(&archive)?;
+    let ciphertext_start = archive.len() - ciphertext.len();
+    let kdf_salt = salt_from("kdf_salt", &header.kdf_salt)?;
+    let wrapped_data_key = WrappedVaultKey::from_bytes(&unb64("wrapped_data_key", &header.wrapped_data_key)?)?;
+    let data_key = KEK::derive(backup_passphrase, &kdf_salt)?
+        .unwrap(&wrapped_data_key)
+        .map_err(|_| BackupError::InvalidBackupPassphrase)?;
+    let nonce = unb64("nonce", &header.nonce)?;
+    if nonce.len() != 12 {
+        return Err(BackupError::Corrupt("nonce is not 12 bytes".to_string()));
+    }
+
+    let cipher = Aes256Gcm::new_from_slice(&data_key.0).map_err(|_| crypto::CryptoError::InvalidKeyLength)?;
+    archive.drain(..ciphertext_start);
+    let mut plaintext = archive;
+    cipher
+        .decrypt_in_place(Nonce::from_slice(&nonce), &associated_data(&header)?, &mut plaintext)
+        .map_err(|_| BackupError::Corrupt("authentication failed".to_string()))?;
+
+    let manifest_len = plaintext.get(..4)
+        .map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]) as usize)
+        .ok_or_else(|| BackupError::Corrupt("payload too short".to_string()))?;
+    let manifest_end = 4 + manifest_len;
+    let manifest: BackupManifest = plaintext.get(4..manifest_end)
+        .ok_or_else(|| BackupError::Corrupt("payload too short".to_string()))
+        .and_then(|m| serde_json::from_slice(m).map_err(|e| BackupError::Corrupt(format!("manifest: {}", e))))?;
+    plaintext.drain(..manifest_end);
+    let database = plaintext;
+    if database.len() as u64 != manifest.database_len
+        || !crypto::digests_match(&crypto::hash_sha256(&database), &manifest.database_sha256)
+    {
+        return Err(BackupError::Corrupt("database does not match its hash".to_string()));
+    }
+    Ok((header, manifest, database))
+}
+
+/// SHA-256 of a file, read in chunks
+fn file_sha256(path: &Path) -> std::io::Result<String> {
+    use std::io::Read;
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut chunk = vec![0u8; 1 << 16];
+    loop {
+        let n = file.read(&mut chunk)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&chunk[..n]);
+    }
+    Ok(hex::encode(hasher.finalize()))
+}
+
+// ============================================
+// Backup and Restore
+// ============================================
+
+/// Write an encrypted backup of the (unlocked) vault to `path`. The
+/// database is held in memory once: it is encrypted in place and written
+/// straight from that buffer.
+pub fn create_backup(vault: &Vault, path: &Path, backup_passphrase: &str) -> Result<BackupInfo, BackupError> {
+    let database = vault.database_bytes()?;
+    let database_sha256 = crypto::hash_sha256(&database);
+    let vault_salt = crypto::retrieve_salt()?;
+    let vault_wrapped = crypto::retrieve_wrapped_key()?;
+    let backup_id = crate::ids::new_id();
+    let archive = seal_archive(&backup_id, database, &vault_salt, &vault_wrapped, backup_passphrase)?;
+    let archive_sha256 = archive.sha256();
+
+    // Write beside the target, then rename, so a failed write never leaves
+    // a truncated file under the backup's name
+    let partial = path.with_extension("partial");
+    {
+        use std::io::Write;
+        let mut file = std::fs::File::create(&partial)?;
+        file.write_all(&archive.head)?;
+        file.write_all(&archive.ciphertext)?;
+        file.sync_all()?;
+    }
+    let written = file_sha256(&partial);
+    if !written.map(|hash| crypto::digests_match(&hash, &archive_sha256)).unwrap_or(false) {
+        std::fs::remove_file(&partial).ok();
+        return Err(BackupError::Corrupt("written backup does not read back".to_string()));
+    }
+    std::fs::rename(&partial, path)?;
+
+    log::info!("Backup {} written ({} bytes)", backup_id, archive.len());
+    Ok(BackupInfo {
+        backup_id,
+        created_at: archive.header.created_at,
+        size_bytes: archive.len() as u64,
+        file_sha256: archive_sha256,
+        database_sha256,
+    })
+}
+
+/// Replace the vault with the backup at `path`. The backup is decrypted
+/// and every hash checked, and the restored database must open with
+/// `vault_passphrase`, before anything is replaced. The current database
+/// is kept beside the new one with its keychain entries; the vault is left
+/// unlocked on the restored data.
+pub fn restore_backup(
+    vault: &mut Vault,
+    path: &Path,
+    backup_passphrase: &str,
+    vault_passphrase: &str,
+) -> Result<RestoreReport, BackupError> {
+    let archive = std::fs::read(path)?;
+    let (header, manifest, database) = open_archive(archive, backup_passphrase)?;
+    let vault_salt = salt_from("vault_salt", &manifest.vault_salt)?;
+    let vault_wrapped = WrappedVaultKey::from_bytes(&unb64("vault_wrapped_key", &manifest.vault_wrapped_key)?)?;
+
+    let staged = vault.stage_restored_database(&database)?;
+    drop(database);
+    if let Err(e) = Vault::check_database_opens(&staged, vault_passphrase, &vault_salt, &vault_wrapped) {
+        std::fs::remove_file(&staged).ok();
+        return Err(match e {
+            VaultError::InvalidPassphrase => BackupError::InvalidVaultPassphrase,
+            other => other.into(),
+        });
+    }
+
+    let previous = vault.replace_database(&staged, &vault_salt, &vault_wrapped)?;
+    vault.unlock(vault_passphrase)?;
+    log::info!("Vault restored from backup {}", header.backup_id);
+
+    Ok(RestoreReport {
+        backup_id: header.backup_id,
+        backup_created_at: header.created_at,
+        database_sha256: manifest.database_sha256,
+        previous_keychain: previous.as_deref()
+            .map(Vault::set_aside_credentials_path)
+            .filter(|p| p.exists())
+            .map(|p| p.to_string_lossy().into_owned()),
+        previous_database: previous.map(|p| p.to_string_lossy().into_owned()),
+    })
+}
+
+// ============================================
+// Tauri Commands
+// ============================================
+
+use tauri::State;
+use crate::commands::AppState;
+use crate::models::{AuditEventType, AuditOutcome, AuditResourceType};
+
+/// Back up the vault to `path`, encrypted under `backup_passphrase`
+#[tauri::command]
+pub fn create_vault_backup(
+    state: State<AppState>,
+    policy_state: State<'_, crate::policy::PolicyState>,
+    path: String,
+    backup_passphrase: String,
+    user_override: Option<bool>,
+) -> Result<BackupInfo, String> {
+    crate::commands::validate_export_path(path.clone(), None, user_override)?;
+    {
+        let engine = policy_state.engine.read().map_err(|e| e.to_string())?;
+        crate::passphrase::enforce(&backup_passphrase, &engine.get_policy().passphrase_policy)?;
+    }
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    let result = create_backup(&vault, Path::new(&path), &backup_passphrase);
+
+    if let Ok(conn) = vault.get_connection() {
+        let _ = crate::audit::log_event(
+            conn,
+            AuditEventType::BackupCreated,
+            AuditResourceType::Vault,
+            result.as_ref().map(|info| info.backup_id.as_str()).unwrap_or("backup"),
+            if result.is_ok() { AuditOutcome::Success } else { AuditOutcome::Failure },
+            None,
+        );
+    }
+    result.map_err(|e| e.to_string())
+}
+
+/// Check a backup file's integrity without the passphrase
+#[tauri::command]
+pub fn inspect_vault_backup(path: String) -> Result<BackupHeader, String> {
+    let archive = std::fs::read(&path).map_err(|e| e.to_string())?;
+    read_header(&archive).map(|(header, _)| header).map_err(|e| e.to_string())
+}
+
+/// Replace the vault with a backup; see `restore_backup`
+#[tauri::command]
+pub fn restore_vault_backup(
+    state: State<AppState>,
+    policy_state: State<'_, crate::policy::PolicyState>,
+    replica_state: State<'_, crate::replica::ReplicaState>,
+    path: String,
+    backup_passphrase: String,
+    vault_passphrase: String,
+) -> Result<RestoreReport, String> {
+    let mut vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    crate::commands::ensure_residency(&vault, &policy_state)?;
+    let report = restore_backup(&mut vault, Path::new(&path), &backup_passphrase, &vault_passphrase)
+        .map_err(|e| e.to_string())?;
+
+    // Recorded in the restored vault's own chain
+    if let Ok(conn) = vault.get_connection() {
+        let _ = crate::audit::log_event(
+            conn,
+            AuditEventType::BackupRestored,
+            AuditResourceType::Vault,
+            &report.backup_id,
+            AuditOutcome::Success,
+            None,
+        );
+    }
+    drop(vault);
+    replica_state.clear();
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_archive_round_trip() {
+        let database = b"SQLite format 3\0 pretend this is encrypted".repeat(50);
+        let salt = crypto::generate_salt();
+        let wrapped = KEK::derive("vault pass", &salt).unwrap().wrap(&VaultKey::generate()).unwrap();
+        let sealed = seal_archive("b-1", database.clone(), &salt, &wrapped, "backup pass").unwrap();
+        let archive = [sealed.head.as_slice(), sealed.ciphertext.as_slice()].concat();
+        assert_eq!(crypto::hash_sha256(&archive), sealed.sha256());
+
+        let (header, manifest, restored) = open_archive(archive.clone(), "backup pass").unwrap();
+        assert_eq!(header.backup_id, "b-1");
+        assert_eq!(restored, database);
+        assert_eq!(salt_from("vault_salt", &manifest.vault_salt).unwrap(), salt);
+        assert_eq!(unb64("k", &manifest.vault_wrapped_key).unwrap(), wrapped.to_bytes());
+
+        assert!(matches!(open_archive(archive.clone(), "wrong pass"), Err(BackupError::InvalidBackupPassphrase)));
+
+        // Any flipped byte is caught by the header hash, before key derivation
+        let mut damaged = archive.clone();
+        let last = damaged.len() - 1;
+        damaged[last] ^= 1;
+        assert!(matches!(read_header(&damaged), Err(BackupError::Corrupt(_))));
+
+        // A header edited along with its hash still fails authentication
+        let (mut edited, ciphertext) = read_header(&archive).map(|(h, c)| (h, c.to_vec())).unwrap();
+        edited.created_at += 1;
+        let mut forged = BACKUP_MAGIC.to_vec();
+        forged.extend_from_slice(&serde_json::to_vec(&edited).unwrap());
+        forged.push(b'\n');
+        forged.extend_from_slice(&ciphertext);
+        assert!(matches!(open_archive(forged, "backup pass"), Err(BackupError::Corrupt(_))));
+
+        assert!(matches!(read_header(b"not a backup"), Err(BackupError::NotABackup)));
+    }
+
+    #[test]
+    fn test_restore_round_trip() {
+        use crate::models::NoteType;
+        use crate::vault::testing::{fixture_key, FixtureBuilder};
+
+        let fixture = FixtureBuilder::new("restore")
+            .client("Client A")
+            .signed_note("2024-04-01", NoteType::Progress, "Reviewed sleep diary.")
+            .note("2024-04-08", NoteType::Progress, "Draft follow-up.")
+            .build()
+            .unwrap();
+        let dir = std::env::temp_dir().join(format!("evidify-restore-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let source = dir.join("source.db");
+        drop(fixture.vault.open_snapshot(&source).unwrap());
+
+        let salt = crypto::generate_salt();
+        let wrapped = KEK::derive("vault pass", &salt).unwrap().wrap(&fixture_key("restore")).unwrap();
+        let sealed = seal_archive("b-2", std::fs::read(&source).unwrap(), &salt, &wrapped, "backup pass").unwrap();
+        let archive = [sealed.head.as_slice(), sealed.ciphertext.as_slice()].concat();
+
+        let (_, manifest, database) = open_archive(archive, "backup pass").unwrap();
+        let vault_salt = salt_from("vault_salt", &manifest.vault_salt).unwrap();
+        let vault_wrapped = WrappedVaultKey::from_bytes(&unb64("k", &manifest.vault_wrapped_key).unwrap()).unwrap();
+        let staged = Vault::new(dir.clone()).stage_restored_database(&database).unwrap();
+        Vault::check_database_opens(&staged, "vault pass", &vault_salt, &vault_wrapped).unwrap();
+        assert!(matches!(
+            Vault::check_database_opens(&staged, "wrong pass", &vault_salt, &vault_wrapped),
+            Err(VaultError::InvalidPassphrase)
+        ));
+
+        // Keychain entries kept beside a set-aside database still open it
+        Vault::write_set_aside_credentials(&staged, &vault_salt, &vault_wrapped).unwrap();
+        let (kept_salt, kept_wrapped) = Vault::read_set_aside_credentials(&staged).unwrap();
+        Vault::check_database_opens(&staged, "vault pass", &kept_salt, &kept_wrapped).unwrap();
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,911 @@
+// Chart Audit Binder
+//
+// On-site payer audits want the chart as one printed binder. This builds
+// it as a single PDF, per client or per audit sample (a list of notes,
+// possibly across clients): a cover page, a table of contents whose lines
+// link to their pages, and tabbed sections - notes, treatment plans,
+// consents, audit trail extract - each opening with a divider page and
+// marked by a tab on the right edge of every page. Every page carries a
+// Bates-style number (EV-000001, ...) running without gaps across the
+// whole binder, and the PDF outline mirrors the tabs.
+//
+// Records come from the same gathering as records-request responses, so
+// only finalized notes are included. The date range limits notes and the
+// audit extract; treatment plans and consents are included whatever their
+// date, since the ones in effect often predate the audited period, and a
+// sample's notes are the ones named. Uploaded files without extracted
+// text are listed by name and hash, not reproduced. Writing the binder is
+// audited as an export to its destination, like a records response.
+
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+
+use crate::legal_export::{CandidateRecord, RecordCategory, DOCUMENT_NUMBER_PREFIX};
+
+// ============================================
+// Types
+// ============================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BinderTab {
+    /// Progress, intake, crisis and discharge notes
+    Notes,
+    TreatmentPlans,
+    Consents,
+    AuditTrail,
+}
+
+impl BinderTab {
+    pub const ALL: [BinderTab; 4] = [BinderTab::Notes, BinderTab::TreatmentPlans, BinderTab::Consents, BinderTab::AuditTrail];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            BinderTab::Notes => "Notes",
+            BinderTab::TreatmentPlans => "Treatment plans",
+            BinderTab::Consents => "Consents",
+            BinderTab::AuditTrail => "Audit trail",
+        }
+    }
+
+    /// Tab for a record category; None for records the binder leaves out
+    pub fn for_category(category: RecordCategory) -> Option<Self> {
+        match category {
+            RecordCategory::ProgressNotes
+            | RecordCategory::Assessments
+            | RecordCategory::CrisisRecords
+            | RecordCategory::DischargeSummaries => Some(BinderTab::Notes),
+            RecordCategory::TreatmentPlans => Some(BinderTab::TreatmentPlans),
+            RecordCategory::Consents => Some(BinderTab::Consents),
+            RecordCategory::AuditTrail => Some(BinderTab::AuditTrail),
+            RecordCategory::OtherDocuments => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum BinderScope {
+    /// One client's whole chart
+    Client { client_id: String },
+    /// The sampled notes, with their clients' plans and consents
+    Sample { note_ids: Vec<String> },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BinderRequest {
+    pub scope: BinderScope,
+    /// Inclusive; open-ended when None
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+    /// Cover title; "Chart Audit Binder" when None
+    pub title: Option<String>,
+    /// Defaults to the production-number prefix
+    pub bates_prefix: Option<String>,
+    /// Number on the cover page; defaults to 1
+    pub first_bates_number: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BinderRecord {
+    pub tab: BinderTab,
+    pub client_name: String,
+    pub title: String,
+    pub record_id: String,
+    pub record_date: NaiveDate,
+    /// Last date covered, for records spanning a period (audit months)
+    pub period_end: Option<NaiveDate>,
+    /// Text reproduced in the binder; None for files without extracted text
+    pub content: Option<String>,
+    pub content_sha256: String,
+    pub attachment: Option<String>,
+}
+
+/// Everything that goes into the PDF
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BinderContents {
+    pub title: String,
+    /// Practice letterhead for the cover
+    pub letterhead: Vec<String>,
+    pub clients: Vec<String>,
+    /// "Complete chart" or "Audit sample of N notes"
+    pub scope: String,
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+    pub generated_at: DateTime<Utc>,
+    pub bates_prefix: String,
+    pub first_bates_number: u32,
+    pub records: Vec<BinderRecord>,
+}
+
+/// Where a record landed in the binder
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BinderEntry {
+    pub tab: BinderTab,
+    pub record_id: String,
+    pub title: String,
+    /// 1-based page of the PDF
+    pub page: usize,
+    pub bates: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BinderSummary {
+    /// Resource id of the binder's export audit entry
+    pub id: String,
+    pub output_path: String,
+    pub page_count: usize,
+    pub first_bates: String,
+    pub last_bates: String,
+    pub sha256: String,
+    pub entries: Vec<BinderEntry>,
+}
+
+pub struct RenderedBinder {
+    pub pdf: Vec<u8>,
+    pub page_count: usize,
+    pub entries: Vec<BinderEntry>,
+}
+
+// ============================================
+// Layout
+// ============================================
+
+const PAGE_WIDTH: f32 = 612.0;
+const PAGE_HEIGHT: f32 = 792.0;
+const MARGIN: f32 = 54.0;
+const TEXT_TOP: f32 = 738.0;
+const TEXT_BOTTOM: f32 = 72.0;
+const FOOTER_Y: f32 = 36.0;
+/// Width of the tab strip on the right edge
+const TAB_WIDTH: f32 = 26.0;
+const TAB_HEIGHT: f32 = 110.0;
+/// Characters per line of 10pt Helvetica between the margin and the tabs
+const BODY_WRAP: usize = 90;
+/// Characters per line of 7pt Courier (4.2pt per character)
+const AUDIT_WRAP: usize = 118;
+const TOC_LINES_PER_PAGE: usize = 40;
+const TOC_LEADING: f32 = 15.0;
+
+#[derive(Clone, Copy)]
+enum Font {
+    Regular,
+    Bold,
+    Mono,
+}
+
+impl Font {
+    fn resource(&self) -> &'static str {
+        match self {
+            Font::Regular => "F1",
+            Font::Bold => "F2",
+            Font::Mono => "F3",
+        }
+    }
+}
+
+/// `s` as a PDF literal string in WinAnsiEncoding; characters it lacks
+/// become '?'
+fn pdf_string(s: &str) -> Vec<u8> {
+    let mut out = vec![b'('];
+    for c in s.chars() {
+        let byte = match c {
+            '\u{2018}' => 0x91,
+            '\u{2019}' => 0x92,
+            '\u{201C}' => 0x93,
+            '\u{201D}' => 0x94,
+            '\u{2022}' => 0x95,
+            '\u{2013}' => 0x96,
+            '\u{2014}' => 0x97,
+            '\u{2026}' => 0x85,
+            '\t' => b' ',
+            c if (c as u32) < 0x20 => continue,
+            c if (c as u32) < 0x7F || (0xA0..=0xFF).contains(&(c as u32)) => c as u8,
+            _ => b'?',
+        };
+        if matches!(byte, b'(' | b')' | b'\\') {
+            out.push(b'\\');
+        }
+        out.push(byte);
+    }
+    out.push(b')');
+    out
+}
+
+/// Greedy word wrap at `width` characters; words longer than a line are split
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    for source in text.lines() {
+        let mut line = String::new();
+        for word in source.split_whitespace() {
+            let mut word = word;
+            while word.chars().count() > width {
+                if !line.is_empty() {
+                    lines.push(std::mem::take(&mut line));
+                }
+                let split = word.char_indices().nth(width).map(|(i, _)| i).unwrap_or(word.len());
+                lines.push(word[..split].to_string());
+                word = &word[split..];
+            }
+            if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > width {
+                lines.push(std::mem::take(&mut line));
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(word);
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+fn truncate(s: &str, max: usize) -> String {
+    if s.chars().count() <= max {
+        s.to_string()
+    } else {
+        format!("{}...", s.chars().take(max - 3).collect::<String>())
+    }
+}
+
+#[derive(Default)]
+struct Page {
+    content: Vec<u8>,
+    /// Link rectangles and the page index they go to
+    links: Vec<([f32; 4], usize)>,
+}
+
+impl Page {
+    fn ops(&mut self, ops: &str) {
+        self.content.extend_from_slice(ops.as_bytes());
+        self.content.push(b'\n');
+    }
+
+    fn text(&mut self, font: Font, size: f32, x: f32, y: f32, s: &str) {
+        self.content.extend_from_slice(format!("BT /{} {} Tf {} {} Td ", font.resource(), size, x, y).as_bytes());
+        self.content.extend_from_slice(&pdf_string(s));
+        self.content.extend_from_slice(b" Tj ET\n");
+    }
+
+    /// Dark tab on the right edge, lower for each later tab, with its
+    /// number and label running up the strip
+    fn tab(&mut self, index: usize, tab: BinderTab) {
+        let top = TEXT_TOP - index as f32 * (TAB_HEIGHT + 8.0);
+        let bottom = top - TAB_HEIGHT;
+        self.ops(&format!("0.25 g {} {} {} {} re f", PAGE_WIDTH - TAB_WIDTH, bottom, TAB_WIDTH, TAB_HEIGHT));
+        self.content.extend_from_slice(
+            format!("BT 1 g /F2 9 Tf 0 1 -1 0 {} {} Tm ", PAGE_WIDTH - 10.0, bottom + 8.0).as_bytes(),
+        );
+        self.content.extend_from_slice(&pdf_string(&format!("{}  {}", index + 1, tab.label())));
+        self.content.extend_from_slice(b" Tj ET 0 g\n");
+    }
+}
+
+/// Bookmark for a tab (or the contents), with its records as children
+struct OutlineItem {
+    title: String,
+    page: usize,
+    children: Vec<(String, usize)>,
+}
+
+/// Text flowing down pages, starting a new page when one fills
+struct Flow {
+    pages: Vec<Page>,
+    y: f32,
+    tab: Option<(usize, BinderTab)>,
+}
+
+impl Flow {
+    fn page(&mut self) -> &mut Page {
+        self.pages.last_mut().expect("flow has a page")
+    }
+
+    fn new_page(&mut self) {
+        let mut page = Page::default();
+        if let Some((index, tab)) = self.tab {
+            page.tab(index, tab);
+        }
+        self.pages.push(page);
+        self.y = TEXT_TOP;
+    }
+
+    fn line(&mut self, font: Font, size: f32, s: &str) {
+        if self.y < TEXT_BOTTOM {
+            self.new_page();
+        }
+        let y = self.y;
+        self.page().text(font, size, MARGIN, y, s);
+        self.y -= size * 1.35;
+    }
+
+    fn wrapped(&mut self, font: Font, size: f32, width: usize, text: &str) {
+        for line in wrap(text, width) {
+            self.line(font, size, &line);
+        }
+    }
+
+    fn rule(&mut self) {
+        let y = self.y + 4.0;
+        let right = PAGE_WIDTH - MARGIN;
+        self.page().ops(&format!("0.5 w {} {} m {} {} l S", MARGIN, y, right, y));
+        self.y -= 8.0;
+    }
+}
+
+fn period(start: Option<NaiveDate>, end: Option<NaiveDate>) -> String {
+    match (start, end) {
+        (Some(start), Some(end)) => format!("{} through {}", start, end),
+        (Some(start), None) => format!("From {}", start),
+        (None, Some(end)) => format!("Through {}", end),
+        (None, None) => "All dates".to_string(),
+    }
+}
+
+fn write_record(flow: &mut Flow, record: &BinderRecord) {
+    flow.line(Font::Bold, 12.0, &truncate(&record.title, 80));
+    let date = match record.period_end {
+        Some(end) => format!("{} through {}", record.record_date, end),
+        None => record.record_date.to_string(),
+    };
+    flow.line(Font::Regular, 9.0, &format!("Client: {}    Date: {}    Record: {}", record.client_name, date, record.record_id));
+    flow.line(Font::Regular, 9.0, &format!("SHA-256: {}", record.content_sha256));
+    flow.rule();
+    match (&record.content, record.tab) {
+        (Some(content), BinderTab::AuditTrail) => flow.wrapped(Font::Mono, 7.0, AUDIT_WRAP, content),
+        (Some(content), _) => flow.wrapped(Font::Regular, 10.0, BODY_WRAP, content),
+        (None, _) => flow.wrapped(Font::Regular, 10.0, BODY_WRAP, &format!(
+            "Native file {} is not reproduced in this binder. It is produced separately and matches the SHA-256 above.",
+            record.attachment.as_deref().unwrap_or(&record.record_id),
+        )),
+    }
+}
+
+fn write_cover(page: &mut Page, contents: &BinderContents, tabs: &[(BinderTab, Vec<&BinderRecord>)], bates_range: &str) {
+    let mut y = TEXT_TOP;
+    for line in &contents.letterhead {
+        page.text(Font::Regular, 10.0, MARGIN, y, line);
+        y -= 13.0;
+    }
+    page.text(Font::Bold, 22.0, MARGIN, 560.0, &truncate(&contents.title, 44));
+
+    let plural = |n: usize| if n == 1 { "" } else { "s" };
+    let mut lines = vec![
+        (Font::Regular, format!("Client{}: {}", plural(contents.clients.len()), contents.clients.join(", "))),
+        (Font::Regular, contents.scope.clone()),
+        (Font::Regular, format!("Period: {}", period(contents.start_date, contents.end_date))),
+        (Font::Regular, format!("Generated: {}", contents.generated_at.format("%Y-%m-%d %H:%M UTC"))),
+        (Font::Regular, format!("Page numbers: {}", bates_range)),
+        (Font::Regular, String::new()),
+    ];
+    lines.extend(tabs.iter().enumerate().map(|(i, (tab, records))| {
+        (Font::Bold, format!("Tab {}  {} ({} record{})", i + 1, tab.label(), records.len(), plural(records.len())))
+    }));
+    for (i, (font, text)) in lines.iter().enumerate() {
+        page.text(*font, 12.0, MARGIN, 520.0 - i as f32 * 18.0, &truncate(text, 80));
+    }
+    page.text(Font::Regular, 9.0, MARGIN, 110.0, "CONFIDENTIAL: contains protected health information. Produced for audit review only;");
+    page.text(Font::Regular, 9.0, MARGIN, 98.0, "do not redisclose without authorization.");
+}
+
+/// Lay out and write the binder PDF. Deterministic for the same contents.
+pub fn render_binder(contents: &BinderContents) -> RenderedBinder {
+    let mut records: Vec<&BinderRecord> = contents.records.iter().collect();
+    records.sort_by(|a, b| {
+        (a.tab, &a.client_name, a.record_date, &a.title, &a.record_id)
+            .cmp(&(b.tab, &b.client_name, b.record_date, &b.title, &b.record_id))
+    });
+    let tabs: Vec<(BinderTab, Vec<&BinderRecord>)> = BinderTab::ALL.iter()
+        .map(|tab| (*tab, records.iter().copied().filter(|r| r.tab == *tab).collect::<Vec<_>>()))
+        .filter(|(_, records)| !records.is_empty())
+        .collect();
+    let several_clients = contents.clients.len() > 1;
+
+    // Cover and contents pages first; filled in once page numbers are known
+    let toc_pages = (tabs.len() + records.len()).div_ceil(TOC_LINES_PER_PAGE).max(1);
+    let mut flow = Flow {
+        pages: (0..1 + toc_pages).map(|_| Page::default()).collect(),
+        y: TEXT_TOP,
+        tab: None,
+    };
+    // (text, indented, target page)
+    let mut toc: Vec<(String, bool, usize)> = Vec::new();
+    let mut outline: Vec<OutlineItem> = Vec::new();
+    let mut placed: Vec<(&BinderRecord, usize)> = Vec::new();
+
+    for (index, (tab, tab_records)) in tabs.iter().enumerate() {
+        flow.tab = Some((index, *tab));
+        flow.new_page();
+        let divider = flow.pages.len() - 1;
+        let page = flow.page();
+        page.text(Font::Bold, 28.0, MARGIN, 560.0, &format!("TAB {}", index + 1));
+        page.text(Font::Bold, 20.0, MARGIN, 526.0, tab.label());
+        page.text(Font::Regular, 11.0, MARGIN, 500.0, &format!("{} record{}", tab_records.len(), if tab_records.len() == 1 { "" } else { "s" }));
+        toc.push((format!("Tab {}  {}", index + 1, tab.label()), false, divider));
+
+        let mut children = Vec::new();
+        for record in tab_records {
+            flow.new_page();
+            let first = flow.pages.len() - 1;
+            write_record(&mut flow, record);
+            let label = if several_clients {
+                format!("{}  {} ({})", record.record_date, record.title, record.client_name)
+            } else {
+                format!("{}  {}", record.record_date, record.title)
+            };
+            toc.push((label.clone(), true, first));
+            children.push((label, first));
+            placed.push((record, first));
+        }
+        outline.push(OutlineItem { title: format!("Tab {}: {}", index + 1, tab.label()), page: divider, children });
+    }
+
+    let page_count = flow.pages.len();
+    let bates = |page: usize| format!("{}-{:06}", contents.bates_prefix, contents.first_bates_number as usize + page);
+    let bates_range = format!("{} through {} ({} pages)", bates(0), bates(page_count - 1), page_count);
+    let mut pages = flow.pages;
+
+    write_cover(&mut pages[0], contents, &tabs, &bates_range);
+
+    for (n, chunk) in toc.chunks(TOC_LINES_PER_PAGE).enumerate() {
+        let page = &mut pages[1 + n];
+        let mut y = TEXT_TOP;
+        if n == 0 {
+            page.text(Font::Bold, 16.0, MARGIN, y, "Table of Contents");
+            y -= 24.0;
+        }
+        page.text(Font::Bold, 9.0, 440.0, y, "Bates");
+        page.text(Font::Bold, 9.0, 520.0, y, "Page");
+        y -= TOC_LEADING;
+        for (text, indented, target) in chunk {
+            let (font, x, max) = if *indented { (Font::Regular, MARGIN + 18.0, 62) } else { (Font::Bold, MARGIN, 64) };
+            page.text(font, 10.0, x, y, &truncate(text, max));
+            page.text(Font::Regular, 10.0, 440.0, y, &bates(*target));
+            page.text(Font::Regular, 10.0, 520.0, y, &(target + 1).to_string());
+            page.links.push(([MARGIN, y - 4.0, PAGE_WIDTH - MARGIN, y + 11.0], *target));
+            y -= TOC_LEADING;
+        }
+    }
+    if toc.is_empty() {
+        pages[1].text(Font::Regular, 11.0, MARGIN, TEXT_TOP - 40.0, "No records in scope.");
+    }
+
+    let footer_title = truncate(&contents.title, 50);
+    for (i, page) in pages.iter_mut().enumerate() {
+        page.text(Font::Regular, 8.0, MARGIN, FOOTER_Y, &footer_title);
+        page.text(Font::Regular, 8.0, 270.0, FOOTER_Y, &format!("Page {} of {}", i + 1, page_count));
+        page.text(Font::Bold, 9.0, PAGE_WIDTH - MARGIN - 70.0, FOOTER_Y, &bates(i));
+    }
+
+    let entries = placed.into_iter().map(|(record, page)| BinderEntry {
+        tab: record.tab,
+        record_id: record.record_id.clone(),
+        title: record.title.clone(),
+        page: page + 1,
+        bates: bates(page),
+    }).collect();
+    let mut outline_items = vec![OutlineItem { title: "Table of contents".to_string(), page: 1, children: Vec::new() }];
+    outline_items.extend(outline);
+
+    RenderedBinder {
+        pdf: write_pdf(&pages, &outline_items, contents),
+        page_count,
+        entries,
+    }
+}
+
+// ============================================
+// PDF Objects
+// ============================================
+
+/// Objects numbered from 1 in the order reserved
+#[derive(Default)]
+struct PdfObjects {
+    bodies: Vec<Vec<u8>>,
+}
+
+impl PdfObjects {
+    fn reserve(&mut self) -> usize {
+        self.bodies.push(Vec::new());
+        self.bodies.len()
+    }
+
+    fn set(&mut self, id: usize, body: Vec<u8>) {
+        self.bodies[id - 1] = body;
+    }
+
+    fn add(&mut self, body: Vec<u8>) -> usize {
+        let id = self.reserve();
+        self.set(id, body);
+        id
+    }
+
+    fn finish(self, root: usize, info: usize) -> Vec<u8> {
+        let mut pdf = b"%PDF-1.4\n%\xe2\xe3\xcf\xd3\n".to_vec();
+        let mut offsets = Vec::with_capacity(self.bodies.len());
+        for (i, body) in self.bodies.iter().enumerate() {
+            offsets.push(pdf.len());
+            pdf.extend_from_slice(format!("{} 0 obj\n", i + 1).as_bytes());
+            pdf.extend_from_slice(body);
+            pdf.extend_from_slice(b"\nendobj\n");
+        }
+        let xref_offset = pdf.len();
+        pdf.extend_from_slice(format!("xref\n0 {}\n0000000000 65535 f \n", offsets.len() + 1).as_bytes());
+        for offset in offsets {
+            pdf.extend_from_slice(format!("{:010} 00000 n \n", offset).as_bytes());
+        }
+        pdf.extend_from_slice(format!(
+            "trailer << /Size {} /Root {} 0 R /Info {} 0 R >>\nstartxref\n{}\n%%EOF",
+            self.bodies.len() + 1, root, info, xref_offset,
+        ).as_bytes());
+        pdf
+    }
+}
+
+fn dict(parts: &[&[u8]]) -> Vec<u8> {
+    parts.concat()
+}
+
+fn write_pdf(pages: &[Page], outline: &[OutlineItem], contents: &BinderContents) -> Vec<u8> {
+    let mut objects = PdfObjects::default();
+    let catalog = objects.reserve();
+    let pages_root = objects.reserve();
+    let outlines_root = objects.reserve();
+    let info = objects.add(dict(&[
+        b"<< /Title ", &pdf_string(&contents.title),
+        b" /Producer (Evidify) /CreationDate ",
+        &pdf_string(&contents.generated_at.format("D:%Y%m%d%H%M%SZ").to_string()), b" >>",
+    ]));
+    let fonts: Vec<usize> = ["Helvetica", "Helvetica-Bold", "Courier"].iter()
+        .map(|name| objects.add(format!("<< /Type /Font /Subtype /Type1 /BaseFont /{} /Encoding /WinAnsiEncoding >>", name).into_bytes()))
+        .collect();
+    let page_ids: Vec<usize> = pages.iter().map(|_| objects.reserve()).collect();
+
+    for (page, id) in pages.iter().zip(&page_ids) {
+        let content = objects.add(dict(&[
+            format!("<< /Length {} >>\nstream\n", page.content.len()).as_bytes(),
+            &page.content,
+            b"\nendstream",
+        ]));
+        let annots: Vec<String> = page.links.iter().map(|(rect, target)| {
+            let annot = objects.add(format!(
+                "<< /Type /Annot /Subtype /Link /Rect [{} {} {} {}] /Border [0 0 0] /Dest [{} 0 R /Fit] >>",
+                rect[0], rect[1], rect[2], rect[3], page_ids[*target],
+            ).into_bytes());
+            format!("{} 0 R", annot)
+        }).collect();
+        let annots = if annots.is_empty() { String::new() } else { format!(" /Annots [{}]", annots.join(" ")) };
+        objects.set(*id, format!(
+            "<< /Type /Page /Parent {} 0 R /MediaBox [0 0 {} {}] /Resources << /Font << /F1 {} 0 R /F2 {} 0 R /F3 {} 0 R >> >> /Contents {} 0 R{} >>",
+            pages_root, PAGE_WIDTH, PAGE_HEIGHT, fonts[0], fonts[1], fonts[2], content, annots,
+        ).into_bytes());
+    }
+
+    let kids: Vec<String> = page_ids.iter().map(|id| format!("{} 0 R", id)).collect();
+    objects.set(pages_root, format!("<< /Type /Pages /Kids [{}] /Count {} >>", kids.join(" "), page_ids.len()).into_bytes());
+
+    // Outline: one item per tab, with its records as children
+    let item_ids: Vec<(usize, Vec<usize>)> = outline.iter()
+        .map(|item| (objects.reserve(), item.children.iter().map(|_| objects.reserve()).collect()))
+        .collect();
+    let sibling = |ids: &[usize], i: usize| {
+        let prev = if i > 0 { format!(" /Prev {} 0 R", ids[i - 1]) } else { String::new() };
+        let next = ids.get(i + 1).map(|id| format!(" /Next {} 0 R", id)).unwrap_or_default();
+        format!("{}{}", prev, next)
+    };
+    let top_ids: Vec<usize> = item_ids.iter().map(|(id, _)| *id).collect();
+    let mut visible = top_ids.len();
+    for (i, (item, (id, child_ids))) in outline.iter().zip(&item_ids).enumerate() {
+        for (j, ((child_title, child_page), child_id)) in item.children.iter().zip(child_ids).enumerate() {
+            objects.set(*child_id, dict(&[
+                b"<< /Title ", &pdf_string(child_title),
+                format!(" /Parent {} 0 R{} /Dest [{} 0 R /Fit] >>", id, sibling(child_ids, j), page_ids[*child_page]).as_bytes(),
+            ]));
+        }
+        let nested = match (child_ids.first(), child_ids.last()) {
+            (Some(first), Some(last)) => format!(" /First {} 0 R /Last {} 0 R /Count {}", first, last, child_ids.len()),
+            _ => String::new(),
+        };
+        visible += child_ids.len();
+        objects.set(*id, dict(&[
+            b"<< /Title ", &pdf_string(&item.title),
+            format!(" /Parent {} 0 R{}{} /Dest [{} 0 R /Fit] >>", outlines_root, sibling(&top_ids, i), nested, page_ids[item.page]).as_bytes(),
+        ]));
+    }
+    objects.set(outlines_root, format!(
+        "<< /Type /Outlines /First {} 0 R /Last {} 0 R /Count {} >>",
+        top_ids[0], top_ids[top_ids.len() - 1], visible,
+    ).into_bytes());
+    objects.set(catalog, format!(
+        "<< /Type /Catalog /Pages {} 0 R /Outlines {} 0 R /PageMode /UseOutlines >>",
+        pages_root, outlines_root,
+    ).into_bytes());
+
+    objects.finish(catalog, info)
+}
+
+// ============================================
+// Gathering
+// ============================================
+
+fn in_range(request: &BinderRequest, first: NaiveDate, last: NaiveDate) -> bool {
+    request.start_date.map(|start| last >= start).unwrap_or(true)
+        && request.end_date.map(|end| first <= end).unwrap_or(true)
+}
+
+/// The request's period as inclusive UTC milliseconds, for audit queries
+fn period_ms(request: &BinderRequest) -> (i64, i64) {
+    let midnight = |date: NaiveDate| date.and_hms_opt(0, 0, 0).map(|t| t.and_utc().timestamp_millis());
+    let start = request.start_date.and_then(midnight).unwrap_or(0);
+    let end = request.end_date
+        .and_then(|end| end.succ_opt())
+        .and_then(midnight)
+        .map(|next| next - 1)
+        .unwrap_or(i64::MAX);
+    (start, end)
+}
+
+/// Clients whose charts the binder draws on, in order of first appearance
+fn binder_clients(vault: &crate::vault::Vault, scope: &BinderScope) -> Result<Vec<String>, String> {
+    match scope {
+        BinderScope::Client { client_id } => Ok(vec![client_id.clone()]),
+        BinderScope::Sample { note_ids } => {
+            if note_ids.is_empty() {
+                return Err("Audit sample has no notes".to_string());
+            }
+            let mut clients = Vec::new();
+            for note_id in note_ids {
+                let note = vault.get_note(note_id).map_err(|e| format!("{}", e))?;
+                if !clients.contains(&note.client_id) {
+                    clients.push(note.client_id);
+                }
+            }
+            Ok(clients)
+        }
+    }
+}
+
+/// Client names and records for a binder. Sampled notes must be finalized.
+fn gather_binder_records(
+    vault: &crate::vault::Vault,
+    request: &BinderRequest,
+    client_ids: &[String],
+) -> Result<(Vec<String>, Vec<BinderRecord>), String> {
+    let sample: Option<HashSet<&String>> = match &request.scope {
+        BinderScope::Client { .. } => None,
+        BinderScope::Sample { note_ids } => Some(note_ids.iter().collect()),
+    };
+
+    let conn = vault.get_connection().map_err(|e| format!("{}", e))?;
+    let (start_ms, end_ms) = period_ms(request);
+    let mut names = Vec::new();
+    let mut records = Vec::new();
+    for client_id in client_ids {
+        let client_name = vault.get_client(client_id).map_err(|e| format!("{}", e))?.display_name;
+        let (candidates, _) = crate::legal_export::gather_chart_records(vault, client_id)?;
+
+        let mut included: Vec<CandidateRecord> = Vec::new();
+        for candidate in candidates {
+            let keep = match BinderTab::for_category(candidate.category) {
+                Some(BinderTab::Notes) => match &sample {
+                    Some(sample) => sample.contains(&candidate.record_id),
+                    None => in_range(request, candidate.record_date, candidate.record_date),
+                },
+                Some(_) => true,
+                None => false,
+            };
+            if keep {
+                included.push(candidate);
+            }
+        }
+
+        // The whole chart's history in the period for a client binder; for
+        // a sample, only what happened to the records in it
+        let sampled: Option<HashSet<&String>> = sample.as_ref()
+            .map(|_| included.iter().map(|c| &c.record_id).collect());
+        let chart_entries = crate::audit::get_chart_entries(conn, client_id, start_ms, end_ms)
+            .map_err(|e| format!("{}", e))?;
+        let mut months: BTreeMap<NaiveDate, Vec<String>> = BTreeMap::new();
+        for entry in chart_entries.iter()
+            .filter(|e| sampled.as_ref().map_or(true, |ids| ids.contains(&e.resource_id)))
+        {
+            let Some((at, line)) = crate::legal_export::audit_line(entry) else { continue };
+            let month = at.date_naive().with_day(1).unwrap_or(at.date_naive());
+            months.entry(month).or_default().push(line);
+        }
+
+        for candidate in included {
+            records.push(BinderRecord {
+                tab: BinderTab::for_category(candidate.category).unwrap_or(BinderTab::Notes),
+                client_name: client_name.clone(),
+                title: candidate.title,
+                record_id: candidate.record_id,
+                record_date: candidate.record_date,
+                period_end: candidate.period_end,
+                content: candidate.content,
+                content_sha256: candidate.content_sha256,
+                attachment: candidate.attachment,
+            });
+        }
+        for (month, lines) in months {
+            let period_end = month.checked_add_months(chrono::Months::new(1))
+                .and_then(|next| next.pred_opt())
+                .unwrap_or(month);
+            let content = lines.join("\n");
+            records.push(BinderRecord {
+                tab: BinderTab::AuditTrail,
+                client_name: client_name.clone(),
+                title: format!("Audit trail, {}", month.format("%B %Y")),
+                record_id: format!("audit-{}", month.format("%Y-%m")),
+                record_date: month,
+                period_end: Some(period_end),
+                content_sha256: crate::crypto::hash_sha256(content.as_bytes()),
+                content: Some(content),
+                attachment: None,
+            });
+        }
+        names.push(client_name);
+    }
+
+    if let Some(sample) = &sample {
+        if let Some(missing) = sample.iter().find(|id| !records.iter().any(|r| r.record_id == id.as_str())) {
+            return Err(format!("Note {} is not finalized; drafts are not part of the record", missing));
+        }
+    }
+    Ok((names, records))
+}
+
+// ============================================
+// Tauri Commands
+// ============================================
+
+use tauri::State;
+use crate::commands::AppState;
+
+/// Build the audit binder PDF and write it to `output_path`
+#[tauri::command]
+pub fn export_audit_binder(
+    state: State<AppState>,
+    policy_state: State<crate::policy::PolicyState>,
+    request: BinderRequest,
+    output_path: String,
+    user_override: Option<bool>,
+) -> Result<BinderSummary, String> {
+    crate::commands::validate_export_path(output_path.clone(), None, user_override)?;
+    if let (Some(start), Some(end)) = (request.start_date, request.end_date) {
+        if start > end {
+            return Err("Binder period ends before it starts".to_string());
+        }
+    }
+
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    let client_ids = binder_clients(&vault, &request.scope)?;
+    for client_id in &client_ids {
+        crate::commands::ensure_chart_access(&vault, &policy_state, client_id)?;
+    }
+    let (clients, records) = gather_binder_records(&vault, &request, &client_ids)?;
+    if records.is_empty() {
+        return Err("No finalized records in scope".to_string());
+    }
+    let letterhead = vault.get_practice_profile().map_err(|e| format!("{}", e))?
+        .map(|p| p.letterhead_lines())
+        .unwrap_or_default();
+
+    let contents = BinderContents {
+        title: request.title.clone().filter(|t| !t.trim().is_empty()).unwrap_or_else(|| "Chart Audit Binder".to_string()),
+        letterhead,
+        clients,
+        scope: match &request.scope {
+            BinderScope::Client { .. } => "Complete chart".to_string(),
+            BinderScope::Sample { note_ids } => format!("Audit sample of {} note{}", note_ids.len(), if note_ids.len() == 1 { "" } else { "s" }),
+        },
+        start_date: request.start_date,
+        end_date: request.end_date,
+        generated_at: Utc::now(),
+        bates_prefix: request.bates_prefix.clone().unwrap_or_else(|| DOCUMENT_NUMBER_PREFIX.to_string()),
+        first_bates_number: request.first_bates_number.unwrap_or(1),
+        records,
+    };
+    let rendered = render_binder(&contents);
+    std::fs::write(&output_path, &rendered.pdf).map_err(|e| e.to_string())?;
+    let record_ids: Vec<&str> = contents.records.iter().map(|r| r.record_id.as_str()).collect();
+    crate::commands::log_note_retrieval(&vault, "audit_binder", &record_ids)?;
+    let id = crate::ids::new_id();
+    vault.record_export_destination(
+        &id,
+        &output_path,
+        crate::commands::path_class_name(std::path::Path::new(&output_path)),
+        user_override.unwrap_or(false),
+        None,
+    ).map_err(|e| format!("{}", e))?;
+
+    let bates = |page: usize| format!("{}-{:06}", contents.bates_prefix, contents.first_bates_number as usize + page);
+    Ok(BinderSummary {
+        id,
+        output_path,
+        page_count: rendered.page_count,
+        first_bates: bates(0),
+        last_bates: bates(rendered.page_count - 1),
+        sha256: crate::crypto::hash_sha256(&rendered.pdf),
+        entries: rendered.entries,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(tab: BinderTab, title: &str, date: &str, content: Option<&str>) -> BinderRecord {
+        BinderRecord {
+            tab,
+            client_name: "Client A".to_string(),
+            title: title.to_string(),
+            record_id: format!("rec-{}", title.to_lowercase().replace(' ', "-")),
+            record_date: NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap(),
+            period_end: None,
+            content: content.map(|c| c.to_string()),
+            content_sha256: "ab".repeat(32),
+            attachment: content.is_none().then(|| "consent.pdf".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_render_binder() {
+        let long_note = "Client reported improved sleep (7 hours) and fewer panic episodes. ".repeat(120);
+        let contents = BinderContents {
+            title: "Payer audit".to_string(),
+            letterhead: vec!["Practice".to_string()],
+            clients: vec!["Client A".to_string()],
+            scope: "Complete chart".to_string(),
+            start_date: None,
+            end_date: None,
+            generated_at: DateTime::parse_from_rfc3339("2024-06-01T12:00:00Z").unwrap().with_timezone(&Utc),
+            bates_prefix: "EV".to_string(),
+            first_bates_number: 101,
+            records: vec![
+                record(BinderTab::Consents, "Informed consent", "2024-01-02", None),
+                record(BinderTab::Notes, "Progress note", "2024-02-01", Some(&long_note)),
+                record(BinderTab::Notes, "Intake", "2024-01-05", Some("Initial assessment.")),
+            ],
+        };
+        let rendered = render_binder(&contents);
+        let pdf = String::from_utf8_lossy(&rendered.pdf);
+
+        // Cover, contents, two dividers, three records (the long note spans pages)
+        assert!(rendered.page_count > 7);
+        assert_eq!(pdf.matches("/Type /Page ").count(), rendered.page_count);
+        assert_eq!(pdf.matches("/Subtype /Link").count(), 5);
+        for page in 0..rendered.page_count {
+            assert!(pdf.contains(&format!("(EV-{:06})", 101 + page)));
+        }
+        // Sorted by tab, then date; first record follows cover, contents and divider
+        assert_eq!(rendered.entries[0].record_id, "rec-intake");
+        assert_eq!(rendered.entries[0].page, 4);
+        assert_eq!(rendered.entries[0].bates, "EV-000104");
+        assert_eq!(rendered.entries[2].tab, BinderTab::Consents);
+
+        // Every xref offset points at its object
+        let bytes = &rendered.pdf;
+        let xref = pdf.rfind("xref\n").unwrap();
+        for (i, line) in pdf[xref..].lines().skip(3).take_while(|l| l.ends_with(" n ")).enumerate() {
+            let offset: usize = line[..10].parse().unwrap();
+            assert!(bytes[offset..].starts_with(format!("{} 0 obj", i + 1).as_bytes()));
+        }
+        assert_eq!(render_binder(&contents).pdf, rendered.pdf);
+
+        let request = BinderRequest {
+            scope: BinderScope::Client { client_id: "c".to_string() },
+            start_date: NaiveDate::from_ymd_opt(2024, 1, 1),
+            end_date: NaiveDate::from_ymd_opt(2024, 1, 31),
+            title: None,
+            bates_prefix: None,
+            first_bates_number: None,
+        };
+        assert_eq!(period_ms(&request), (1_704_067_200_000, 1_706_745_599_999));
+
+        assert_eq!(wrap("one two three", 7), vec!["one two", "three"]);
+        assert_eq!(wrap("abcdefghij", 4), vec!["abcd", "efgh", "ij"]);
+        assert_eq!(wrap("a\n\nb", 10), vec!["a", "", "b"]);
+        assert_eq!(pdf_string("f(x) \u{2014} caf\u{e9} \u{4e2d}"), b"(f\\(x\\) \x97 caf\xe9 ?)".to_vec());
+    }
+}
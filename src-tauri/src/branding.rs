@@ -0,0 +1,217 @@
+// Practice Branding Module
+//
+// Practice profile (name, address, NPI, logo) stored in the vault and
+// rendered as the letterhead of PDF/DOCX note exports and the cover of
+// legal reports. Without a profile, exports keep the plain
+// "Generated by Evidify" footer.
+
+use image::imageops::FilterType;
+use image::{DynamicImage, GenericImageView, ImageOutputFormat, Rgb, RgbImage};
+use serde::{Deserialize, Serialize};
+use std::io::Cursor;
+
+/// Largest logo upload accepted before decoding
+pub const MAX_LOGO_BYTES: usize = 5 * 1024 * 1024;
+
+/// Stored logos are downscaled to fit this box (px)
+const LOGO_MAX_WIDTH: u32 = 600;
+const LOGO_MAX_HEIGHT: u32 = 200;
+
+/// Rendered logo height on the page (points / DOCX EMU derive from this)
+const LOGO_RENDER_HEIGHT_PT: u32 = 40;
+
+/// Footer used when no practice profile is configured
+pub const DEFAULT_FOOTER: &str = "Generated by Evidify";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PracticeProfile {
+    pub name: String,
+    /// Address as printed, one line per entry
+    #[serde(default)]
+    pub address_lines: Vec<String>,
+    /// Type 2 (organizational) or type 1 NPI
+    pub npi: Option<String>,
+    pub phone: Option<String>,
+    /// Set by the vault; the logo bytes are fetched separately
+    #[serde(default)]
+    pub has_logo: bool,
+}
+
+impl PracticeProfile {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("Practice name is required".to_string());
+        }
+        if let Some(npi) = self.npi.as_deref().filter(|n| !n.trim().is_empty()) {
+            if !is_valid_npi(npi.trim()) {
+                return Err(format!("'{}' is not a valid 10-digit NPI", npi));
+            }
+        }
+        Ok(())
+    }
+
+    /// Letterhead lines in print order
+    pub fn letterhead_lines(&self) -> Vec<String> {
+        let mut lines = vec![self.name.clone()];
+        lines.extend(self.address_lines.iter().filter(|l| !l.trim().is_empty()).cloned());
+        let mut contact = Vec::new();
+        if let Some(phone) = self.phone.as_deref().filter(|p| !p.is_empty()) {
+            contact.push(format!("Tel: {}", phone));
+        }
+        if let Some(npi) = self.npi.as_deref().filter(|n| !n.is_empty()) {
+            contact.push(format!("NPI: {}", npi));
+        }
+        if !contact.is_empty() {
+            lines.push(contact.join(" | "));
+        }
+        lines
+    }
+}
+
+/// Footer text for an export: the practice name when branded
+pub fn footer_text(profile: Option<&PracticeProfile>) -> String {
+    match profile {
+        Some(p) => p.name.clone(),
+        None => DEFAULT_FOOTER.to_string(),
+    }
+}
+
+/// NPI check digit: Luhn over the 9-digit base prefixed with 80840
+pub fn is_valid_npi(npi: &str) -> bool {
+    if npi.len() != 10 || !npi.chars().all(|c| c.is_ascii_digit()) {
+        return false;
+    }
+    let digits: Vec<u32> = format!("80840{}", npi).chars().filter_map(|c| c.to_digit(10)).collect();
+    let sum: u32 = digits.iter().rev().enumerate().map(|(i, &d)| {
+        if i % 2 == 1 {
+            let doubled = d * 2;
+            if doubled > 9 { doubled - 9 } else { doubled }
+        } else {
+            d
+        }
+    }).sum();
+    sum.is_multiple_of(10)
+}
+
+// ============================================
+// Logo
+// ============================================
+
+/// Logo as stored: baseline JPEG so PDFs can embed it without re-encoding
+#[derive(Debug, Clone)]
+pub struct PracticeLogo {
+    pub jpeg: Vec<u8>,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl PracticeLogo {
+    /// Rendered size in points at the letterhead height
+    pub fn render_size_pt(&self) -> (u32, u32) {
+        let width = (self.width * LOGO_RENDER_HEIGHT_PT / self.height.max(1)).max(1);
+        (width, LOGO_RENDER_HEIGHT_PT)
+    }
+}
+
+/// Downscale an upload and re-encode as JPEG, flattening transparency onto
+/// white (PDF DCT images have no alpha channel)
+pub fn normalize_logo(data: &[u8]) -> Result<PracticeLogo, String> {
+    if data.len() > MAX_LOGO_BYTES {
+        return Err(format!("Logo is larger than {} MB", MAX_LOGO_BYTES / (1024 * 1024)));
+    }
+    let img = image::load_from_memory(data).map_err(|e| format!("Unsupported image: {}", e))?;
+    let img = if img.width() > LOGO_MAX_WIDTH || img.height() > LOGO_MAX_HEIGHT {
+        img.resize(LOGO_MAX_WIDTH, LOGO_MAX_HEIGHT, FilterType::Triangle)
+    } else {
+        img
+    };
+    let (width, height) = img.dimensions();
+
+    let rgba = img.to_rgba8();
+    let flattened = RgbImage::from_fn(width, height, |x, y| {
+        let p = rgba.get_pixel(x, y).0;
+        let alpha = p[3] as u32;
+        let blend = |c: u8| ((c as u32 * alpha + 255 * (255 - alpha)) / 255) as u8;
+        Rgb([blend(p[0]), blend(p[1]), blend(p[2])])
+    });
+
+    let mut out = Cursor::new(Vec::new());
+    DynamicImage::ImageRgb8(flattened)
+        .write_to(&mut out, ImageOutputFormat::Jpeg(90))
+        .map_err(|e| e.to_string())?;
+    Ok(PracticeLogo { jpeg: out.into_inner(), width, height })
+}
+
+// ============================================
+// Rendering Helpers
+// ============================================
+
+/// PDF image XObject for the logo as object `obj_num`
+pub fn pdf_logo_object(logo: &PracticeLogo, obj_num: u32) -> Vec<u8> {
+    let mut obj = format!(
+        "{} 0 obj << /Type /XObject /Subtype /Image /Width {} /Height {} /ColorSpace /DeviceRGB /BitsPerComponent 8 /Filter /DCTDecode /Length {} >>\nstream\n",
+        obj_num, logo.width, logo.height, logo.jpeg.len()
+    ).into_bytes();
+    obj.extend_from_slice(&logo.jpeg);
+    obj.extend_from_slice(b"\nendstream\nendobj\n");
+    obj
+}
+
+/// Content-stream operators drawing `/Logo` in the top-right corner of a
+/// US Letter page
+pub fn pdf_logo_draw(logo: &PracticeLogo) -> String {
+    let (w, h) = logo.render_size_pt();
+    let x = 612 - 50 - w;
+    let y = 792 - 36 - h;
+    format!("q {} 0 0 {} {} {} cm /Logo Do Q\n", w, h, x, y)
+}
+
+/// DOCX inline drawing referencing image relationship `rel_id`
+pub fn docx_logo_drawing(logo: &PracticeLogo, rel_id: &str) -> String {
+    // 12700 EMU per point
+    let (w, h) = logo.render_size_pt();
+    let (cx, cy) = (w as u64 * 12700, h as u64 * 12700);
+    format!(
+        r#"<w:r><w:drawing><wp:inline><wp:extent cx="{cx}" cy="{cy}"/><wp:docPr id="1" name="Logo"/><a:graphic><a:graphicData uri="http://schemas.openxmlformats.org/drawingml/2006/picture"><pic:pic><pic:nvPicPr><pic:cNvPr id="1" name="logo.jpeg"/><pic:cNvPicPr/></pic:nvPicPr><pic:blipFill><a:blip r:embed="{rel_id}"/><a:stretch><a:fillRect/></a:stretch></pic:blipFill><pic:spPr><a:xfrm><a:off x="0" y="0"/><a:ext cx="{cx}" cy="{cy}"/></a:xfrm><a:prstGeom prst="rect"><a:avLst/></a:prstGeom></pic:spPr></pic:pic></a:graphicData></a:graphic></wp:inline></w:drawing></w:r>"#
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_npi_validation() {
+        // CMS example NPI
+        assert!(is_valid_npi("1234567893"));
+        assert!(!is_valid_npi("1234567890"));
+        assert!(!is_valid_npi("12345"));
+
+        let mut profile = PracticeProfile {
+            name: "Riverside Psychology".to_string(),
+            address_lines: vec!["12 Main St".to_string(), "Austin, TX 78701".to_string()],
+            npi: Some("1234567893".to_string()),
+            phone: None,
+            has_logo: false,
+        };
+        assert!(profile.validate().is_ok());
+        assert_eq!(profile.letterhead_lines().last().unwrap(), "NPI: 1234567893");
+        profile.npi = Some("1234567890".to_string());
+        assert!(profile.validate().is_err());
+    }
+
+    #[test]
+    fn test_logo_is_flattened_to_jpeg() {
+        let img = DynamicImage::ImageRgba8(image::RgbaImage::from_pixel(1200, 300, image::Rgba([0, 0, 0, 0])));
+        let mut png = Cursor::new(Vec::new());
+        img.write_to(&mut png, ImageOutputFormat::Png).unwrap();
+
+        let logo = normalize_logo(&png.into_inner()).unwrap();
+        assert_eq!((logo.width, logo.height), (600, 150));
+        assert_eq!(&logo.jpeg[..2], &[0xFF, 0xD8]);
+        // Transparent pixels become white, not black
+        let decoded = image::load_from_memory(&logo.jpeg).unwrap().to_rgb8();
+        assert!(decoded.get_pixel(10, 10).0[0] > 240);
+        assert_eq!(logo.render_size_pt(), (160, 40));
+    }
+}
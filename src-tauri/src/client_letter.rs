@@ -0,0 +1,463 @@
+// Client Letter Module
+//
+// Plain-language visit summaries for clients who ask for a copy of their
+// care. A letter starts from a signed note: the clinician fills (or
+// accepts pre-filled) "what we discussed / what we practiced / next steps"
+// fields, the template renders them, and the draft can optionally be
+// rewritten by the local model. Every version is run through the
+// readability/jargon check; nothing is exported until the clinician
+// finalizes it.
+//
+// Letters are their own document type with their own content hash, bound
+// to the hash of the note they summarize. Export is gated on a release of
+// information (ROI) on file for the client that covers visit summaries,
+// and each export writes an audit entry naming the release used.
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::readability::{self, ReadabilityReport};
+
+/// Document type recorded for letters in the vault and audit trail
+pub const LETTER_DOCUMENT_TYPE: &str = "client_visit_summary";
+
+// ============================================
+// Types
+// ============================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LetterSource {
+    /// Rendered from the template fields
+    Template,
+    /// Rewritten by the local model
+    LocalRewrite,
+    /// Edited by hand after either of the above
+    Edited,
+}
+
+impl LetterSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LetterSource::Template => "template",
+            LetterSource::LocalRewrite => "local_rewrite",
+            LetterSource::Edited => "edited",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Self {
+        match s {
+            "local_rewrite" => LetterSource::LocalRewrite,
+            "edited" => LetterSource::Edited,
+            _ => LetterSource::Template,
+        }
+    }
+}
+
+/// Template fields, in the clinician's words
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LetterFields {
+    pub what_we_discussed: String,
+    pub what_we_practiced: String,
+    pub next_steps: String,
+    pub next_appointment: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClientLetter {
+    pub id: String,
+    pub note_id: String,
+    pub client_id: String,
+    pub document_type: String,
+    pub body: String,
+    pub source: LetterSource,
+    /// Content hash of the note when the letter was drafted
+    pub note_hash: String,
+    /// SHA-256 of the body; set when the letter is finalized
+    pub content_hash: Option<String>,
+    pub finalized_at: Option<i64>,
+    pub finalized_by: Option<String>,
+    pub last_exported_at: Option<i64>,
+    pub created_at: i64,
+    pub updated_at: i64,
+    /// Recomputed from the body on every read
+    pub readability: ReadabilityReport,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReleaseScope {
+    VisitSummaries,
+    FullRecord,
+}
+
+impl ReleaseScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReleaseScope::VisitSummaries => "visit_summaries",
+            ReleaseScope::FullRecord => "full_record",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Self {
+        match s {
+            "full_record" => ReleaseScope::FullRecord,
+            _ => ReleaseScope::VisitSummaries,
+        }
+    }
+}
+
+/// Release of information on file for a client
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReleaseAuthorization {
+    pub id: String,
+    pub client_id: String,
+    /// Who may receive the records ("Client" for the client's own copy)
+    pub recipient: String,
+    pub scope: ReleaseScope,
+    pub signed_on: NaiveDate,
+    pub expires_on: Option<NaiveDate>,
+    pub revoked_at: Option<i64>,
+    pub created_at: i64,
+}
+
+impl ReleaseAuthorization {
+    /// Whether this release allows sending a visit summary on `today`
+    pub fn permits_visit_summary(&self, today: NaiveDate) -> Result<(), String> {
+        if self.revoked_at.is_some() {
+            return Err("Release was revoked".to_string());
+        }
+        if today < self.signed_on {
+            return Err(format!("Release is not effective until {}", self.signed_on));
+        }
+        if let Some(expires) = self.expires_on {
+            if today > expires {
+                return Err(format!("Release expired {}", expires));
+            }
+        }
+        // Both scopes cover visit summaries; the check is here so a
+        // narrower scope added later has to decide explicitly
+        match self.scope {
+            ReleaseScope::VisitSummaries | ReleaseScope::FullRecord => Ok(()),
+        }
+    }
+}
+
+/// Rendered letter handed back for saving or sending
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedLetter {
+    pub letter_id: String,
+    pub filename: String,
+    pub content: String,
+    pub content_hash: String,
+    pub release_id: String,
+    pub audit_entry_id: String,
+}
+
+// ============================================
+// Drafting
+// ============================================
+
+/// Note headings that feed each template field (matched case-insensitively
+/// at the start of a line, with or without a trailing colon)
+const DISCUSSED_HEADINGS: &[&str] = &["subjective", "session focus", "presenting concerns", "data"];
+const PRACTICED_HEADINGS: &[&str] = &["interventions", "intervention", "skills"];
+const NEXT_STEPS_HEADINGS: &[&str] = &["plan", "next steps", "homework"];
+
+/// Text under the first of `headings` found in the note, up to the next
+/// heading-looking line
+fn section(note_text: &str, headings: &[&str]) -> String {
+    let is_heading = |line: &str| {
+        let trimmed = line.trim().trim_start_matches('#').trim();
+        let label = trimmed.split(':').next().unwrap_or("").trim();
+        (trimmed.ends_with(':') || (trimmed.contains(':') && label.split_whitespace().count() <= 3))
+            && !label.is_empty()
+            && label.len() < 40
+    };
+
+    let mut lines = note_text.lines();
+    while let Some(line) = lines.next() {
+        let trimmed = line.trim().trim_start_matches('#').trim();
+        let label = trimmed.split(':').next().unwrap_or("").trim().to_lowercase();
+        if !headings.contains(&label.as_str()) {
+            continue;
+        }
+        let mut out: Vec<String> = Vec::new();
+        if let Some((_, rest)) = trimmed.split_once(':') {
+            if !rest.trim().is_empty() {
+                out.push(rest.trim().to_string());
+            }
+        }
+        for next in lines.by_ref() {
+            if is_heading(next) {
+                break;
+            }
+            if !next.trim().is_empty() {
+                out.push(next.trim().to_string());
+            }
+        }
+        return out.join(" ");
+    }
+    String::new()
+}
+
+/// Starting values for the template fields from the note's sections. These
+/// are the clinician's words, not the client's - the check on the rendered
+/// letter flags what still needs plain-language wording.
+pub fn prefill_fields(note_text: &str) -> LetterFields {
+    LetterFields {
+        what_we_discussed: section(note_text, DISCUSSED_HEADINGS),
+        what_we_practiced: section(note_text, PRACTICED_HEADINGS),
+        next_steps: section(note_text, NEXT_STEPS_HEADINGS),
+        next_appointment: None,
+    }
+}
+
+fn display_date(date: &str) -> String {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map(|d| d.format("%B %-d, %Y").to_string())
+        .unwrap_or_else(|_| date.to_string())
+}
+
+/// Render the template. Empty fields are left out rather than printed blank.
+pub fn render_letter(client_name: &str, session_date: &str, fields: &LetterFields, clinician: Option<&str>) -> String {
+    let mut out = String::new();
+    out.push_str(&format!("Dear {},\n\n", client_name));
+    out.push_str(&format!("Thank you for meeting with me on {}. Here is a short summary of our visit.\n\n", display_date(session_date)));
+
+    let parts = [
+        ("What we talked about", &fields.what_we_discussed),
+        ("What we practiced", &fields.what_we_practiced),
+        ("Next steps", &fields.next_steps),
+    ];
+    for (heading, text) in parts {
+        if !text.trim().is_empty() {
+            out.push_str(&format!("{}\n{}\n\n", heading, text.trim()));
+        }
+    }
+    if let Some(next) = fields.next_appointment.as_deref().filter(|s| !s.trim().is_empty()) {
+        out.push_str(&format!("Our next appointment is {}.\n\n", next.trim()));
+    }
+
+    out.push_str("If anything here does not match what you remember, or you have questions, please let me know at our next visit.\n\n");
+    out.push_str("Sincerely,\n");
+    out.push_str(clinician.unwrap_or("Your clinician"));
+    out.push('\n');
+    out
+}
+
+/// Prompt for the optional local-model rewrite. The model may only reword:
+/// no new facts, no diagnoses, no risk details beyond what is there.
+pub fn rewrite_prompt(body: &str, target_grade: f64) -> String {
+    format!(
+        r#"Rewrite the following letter to a therapy client in plain, warm language at or below a grade {:.0} reading level.
+
+RULES:
+1. Keep every fact and date exactly as given; do not add new information
+2. Do not add diagnoses, test results, or clinical terms
+3. Replace abbreviations and clinical jargon with everyday words
+4. Keep the greeting, the headings and the closing
+5. Output only the rewritten letter
+
+LETTER:
+{}
+"#,
+        target_grade, body
+    )
+}
+
+/// Hash recorded for a finalized letter
+pub fn letter_hash(body: &str) -> String {
+    crate::crypto::hash_sha256(body.as_bytes())
+}
+
+/// Readability of a letter body against the default client-facing target
+pub fn check(body: &str) -> ReadabilityReport {
+    readability::analyze(body, readability::DEFAULT_TARGET_GRADE)
+}
+
+/// Text written on export: practice letterhead, the letter, and a footer
+/// carrying the letter's hash so a returned copy can be matched
+pub fn render_export(letter: &ClientLetter, practice: Option<&crate::branding::PracticeProfile>) -> String {
+    let mut out = String::new();
+    if let Some(practice) = practice {
+        for line in practice.letterhead_lines() {
+            out.push_str(&line);
+            out.push('\n');
+        }
+        out.push('\n');
+    }
+    out.push_str(&letter.body);
+    out.push_str(&format!(
+        "\n---\n{} | Visit summary | Ref: {}\n",
+        crate::branding::footer_text(practice),
+        letter.content_hash.as_deref().map(|h| &h[..12.min(h.len())]).unwrap_or("")
+    ));
+    out
+}
+
+// ============================================
+// Tauri Commands
+// ============================================
+
+use tauri::State;
+use crate::commands::AppState;
+
+/// Template fields pre-filled from a signed note's sections
+#[tauri::command]
+pub fn prefill_client_letter(state: State<AppState>, note_id: String) -> Result<LetterFields, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    let note = vault.get_note(&note_id).map_err(|e| format!("{}", e))?;
+    Ok(prefill_fields(note.structured_note.as_ref().unwrap_or(&note.raw_input)))
+}
+
+/// Draft a visit summary letter from a signed note
+#[tauri::command]
+pub fn create_client_letter(
+    state: State<AppState>,
+    note_id: String,
+    fields: LetterFields,
+) -> Result<ClientLetter, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.create_client_letter(&note_id, &fields).map_err(|e| format!("{}", e))
+}
+
+/// Replace a draft letter's text with the clinician's edit
+#[tauri::command]
+pub fn update_client_letter(state: State<AppState>, letter_id: String, body: String) -> Result<ClientLetter, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.update_client_letter(&letter_id, &body, LetterSource::Edited).map_err(|e| format!("{}", e))
+}
+
+/// Reword a draft letter with the local model. The result replaces the
+/// draft; the clinician still reviews and finalizes it.
+#[tauri::command]
+pub async fn rewrite_client_letter(
+    state: State<'_, AppState>,
+    letter_id: String,
+    model: String,
+) -> Result<ClientLetter, String> {
+    let body = {
+        let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+        let letter = vault.get_client_letter(&letter_id).map_err(|e| format!("{}", e))?;
+        if letter.finalized_at.is_some() {
+            return Err("Letter is already finalized".to_string());
+        }
+        letter.body
+    };
+
+    let rewritten = crate::ai::generate_answer(&model, &rewrite_prompt(&body, readability::DEFAULT_TARGET_GRADE))
+        .await
+        .map_err(|e| format!("Rewrite failed: {}", e))?;
+    if rewritten.trim().is_empty() {
+        return Err("Model returned an empty letter".to_string());
+    }
+
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.update_client_letter(&letter_id, rewritten.trim(), LetterSource::LocalRewrite)
+        .map_err(|e| format!("{}", e))
+}
+
+/// Lock the letter's text and record its hash
+#[tauri::command]
+pub fn finalize_client_letter(
+    state: State<AppState>,
+    letter_id: String,
+    finalized_by: String,
+) -> Result<ClientLetter, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.finalize_client_letter(&letter_id, &finalized_by).map_err(|e| format!("{}", e))
+}
+
+#[tauri::command]
+pub fn list_client_letters(state: State<AppState>, client_id: String) -> Result<Vec<ClientLetter>, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.list_client_letters(&client_id).map_err(|e| format!("{}", e))
+}
+
+#[tauri::command]
+pub fn record_release_authorization(
+    state: State<AppState>,
+    client_id: String,
+    recipient: String,
+    scope: ReleaseScope,
+    signed_on: NaiveDate,
+    expires_on: Option<NaiveDate>,
+) -> Result<ReleaseAuthorization, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.record_release_authorization(&client_id, &recipient, scope, signed_on, expires_on)
+        .map_err(|e| format!("{}", e))
+}
+
+#[tauri::command]
+pub fn revoke_release_authorization(state: State<AppState>, release_id: String) -> Result<ReleaseAuthorization, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.revoke_release_authorization(&release_id).map_err(|e| format!("{}", e))
+}
+
+#[tauri::command]
+pub fn list_release_authorizations(state: State<AppState>, client_id: String) -> Result<Vec<ReleaseAuthorization>, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.list_release_authorizations(&client_id).map_err(|e| format!("{}", e))
+}
+
+/// Export a finalized letter under a release on file for the client
+#[tauri::command]
+pub fn export_client_letter(
+    state: State<AppState>,
+    letter_id: String,
+    release_id: String,
+) -> Result<ExportedLetter, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.export_client_letter(&letter_id, &release_id, chrono::Utc::now().date_naive())
+        .map_err(|e| format!("{}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_prefill_and_render() {
+        let note = "Subjective: Client reports better sleep this week.\n\
+                    Feels less worried at work.\n\
+                    Interventions:\n\
+                    Practiced box breathing.\n\
+                    Plan: Continue breathing daily; f/u in two weeks.\n";
+        let fields = prefill_fields(note);
+        assert_eq!(fields.what_we_discussed, "Client reports better sleep this week. Feels less worried at work.");
+        assert_eq!(fields.what_we_practiced, "Practiced box breathing.");
+        assert_eq!(fields.next_steps, "Continue breathing daily; f/u in two weeks.");
+
+        let body = render_letter("Alex", "2024-03-05", &fields, Some("Dr. Rivera"));
+        assert!(body.starts_with("Dear Alex,"));
+        assert!(body.contains("March 5, 2024"));
+        assert!(body.contains("What we practiced\nPracticed box breathing."));
+        assert!(!body.contains("Our next appointment"));
+        // Clinical shorthand carried over from the note is flagged
+        assert!(check(&body).jargon.iter().any(|j| j.term == "f/u"));
+    }
+
+    #[test]
+    fn test_release_gating() {
+        let mut release = ReleaseAuthorization {
+            id: "r1".to_string(),
+            client_id: "c1".to_string(),
+            recipient: "Client".to_string(),
+            scope: ReleaseScope::VisitSummaries,
+            signed_on: date("2024-01-10"),
+            expires_on: Some(date("2024-12-31")),
+            revoked_at: None,
+            created_at: 0,
+        };
+        assert!(release.permits_visit_summary(date("2024-06-01")).is_ok());
+        assert!(release.permits_visit_summary(date("2024-01-09")).is_err());
+        assert!(release.permits_visit_summary(date("2025-01-01")).is_err());
+        release.revoked_at = Some(1);
+        assert!(release.permits_visit_summary(date("2024-06-01")).is_err());
+    }
+}
@@ -0,0 +1,506 @@
+// Cohort Module
+//
+// Client groups for program-based practices (IOP, DBT skills groups,
+// closed psychoeducation groups). A cohort has a name, a program label and
+// a weekly schedule; membership is a dated history rather than a flag, so
+// a client who joins late, steps out and returns is only counted for the
+// sessions held while they were enrolled.
+//
+// Recording a cohort session fans one shared narrative out into a Group
+// note per attending member, each with that member's individual section,
+// and records attendance for everyone enrolled. The shared narrative is
+// copied into every member's chart, so it must not name other members.
+// Attendance rolls up into per-member rates and billable session counts.
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+/// Share of the scheduled session a member must attend for it to count
+/// toward billing
+pub const BILLABLE_ATTENDANCE_FRACTION: f64 = 0.5;
+
+/// Consecutive missed sessions after which a member is flagged for
+/// outreach in progress reporting
+pub const ABSENCE_FLAG_THRESHOLD: u32 = 3;
+
+// ============================================
+// Types
+// ============================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CohortSchedule {
+    /// 1 = Monday ... 7 = Sunday
+    pub weekdays: Vec<u8>,
+    /// Local start time, "HH:MM"
+    pub start_time: String,
+    pub duration_minutes: u32,
+}
+
+impl CohortSchedule {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.weekdays.is_empty() || self.weekdays.iter().any(|d| !(1..=7).contains(d)) {
+            return Err("Schedule weekdays must be 1 (Monday) through 7 (Sunday)".to_string());
+        }
+        if chrono::NaiveTime::parse_from_str(&self.start_time, "%H:%M").is_err() {
+            return Err(format!("Invalid start time '{}' (expected HH:MM)", self.start_time));
+        }
+        if self.duration_minutes == 0 {
+            return Err("Session duration must be greater than zero".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Cohort {
+    pub id: String,
+    pub name: String,
+    /// Program label, e.g. "IOP" or "DBT skills"
+    pub program: String,
+    pub schedule: CohortSchedule,
+    pub active_members: u32,
+    pub created_at: i64,
+    pub archived_at: Option<i64>,
+}
+
+/// One enrollment period. `left_on` is the first date the client is no
+/// longer a member; None while enrolled.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CohortMembership {
+    pub id: String,
+    pub cohort_id: String,
+    pub client_id: String,
+    pub joined_on: String,
+    pub left_on: Option<String>,
+    pub left_reason: Option<String>,
+}
+
+impl CohortMembership {
+    /// Enrolled on `date` (YYYY-MM-DD; ISO dates compare as strings)
+    pub fn is_active_on(&self, date: &str) -> bool {
+        self.joined_on.as_str() <= date && self.left_on.as_deref().map(|l| date < l).unwrap_or(true)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AttendanceStatus {
+    Present,
+    Late,
+    LeftEarly,
+    Absent,
+    Excused,
+}
+
+impl AttendanceStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AttendanceStatus::Present => "present",
+            AttendanceStatus::Late => "late",
+            AttendanceStatus::LeftEarly => "left_early",
+            AttendanceStatus::Absent => "absent",
+            AttendanceStatus::Excused => "excused",
+        }
+    }
+
+    pub fn parse(s: &str) -> Self {
+        match s {
+            "present" => AttendanceStatus::Present,
+            "late" => AttendanceStatus::Late,
+            "left_early" => AttendanceStatus::LeftEarly,
+            "excused" => AttendanceStatus::Excused,
+            _ => AttendanceStatus::Absent,
+        }
+    }
+
+    /// Member was in the room for some of the session
+    pub fn attended(&self) -> bool {
+        matches!(self, AttendanceStatus::Present | AttendanceStatus::Late | AttendanceStatus::LeftEarly)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CohortSession {
+    pub id: String,
+    pub cohort_id: String,
+    pub session_date: String,
+    pub duration_minutes: u32,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttendanceRecord {
+    pub session_id: String,
+    pub client_id: String,
+    pub status: AttendanceStatus,
+    /// Minutes in the room; None means the full session for attended statuses
+    pub minutes_attended: Option<u32>,
+    /// Group note written for this member, if they attended
+    pub note_id: Option<String>,
+}
+
+/// Per-member input when recording a session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemberSessionInput {
+    pub client_id: String,
+    pub status: AttendanceStatus,
+    #[serde(default)]
+    pub minutes_attended: Option<u32>,
+    /// This member's participation, response and plan
+    #[serde(default)]
+    pub individual_content: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CohortSessionRecord {
+    pub session: CohortSession,
+    pub attendance: Vec<AttendanceRecord>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemberAttendance {
+    pub client_id: String,
+    /// Sessions held while the client was enrolled
+    pub eligible_sessions: u32,
+    pub attended: u32,
+    pub excused: u32,
+    pub absent: u32,
+    /// Eligible sessions with no attendance recorded
+    pub unrecorded: u32,
+    pub billable_sessions: u32,
+    pub billable_minutes: u32,
+    /// attended / (eligible - excused); None when nothing was expected
+    pub attendance_rate: Option<f64>,
+    /// Missed sessions in a row, counting back from the latest
+    pub consecutive_absences: u32,
+    pub needs_outreach: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CohortAttendanceReport {
+    pub cohort_id: String,
+    pub period_start: String,
+    pub period_end: String,
+    pub sessions_held: u32,
+    pub members: Vec<MemberAttendance>,
+    pub mean_attendance_rate: Option<f64>,
+}
+
+// ============================================
+// Session Fan-out
+// ============================================
+
+pub fn validate_date(date: &str) -> Result<(), String> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map(|_| ())
+        .map_err(|_| format!("Invalid date '{}' (expected YYYY-MM-DD)", date))
+}
+
+/// Member display names that appear in the shared narrative. Every name
+/// returned would be disclosed in the other members' charts.
+pub fn names_in_shared_content(shared: &str, member_names: &[String]) -> Vec<String> {
+    let shared = shared.to_lowercase();
+    member_names.iter()
+        .filter(|name| {
+            let name = name.trim().to_lowercase();
+            !name.is_empty() && shared
+                .match_indices(&name)
+                .any(|(at, m)| {
+                    let before = shared[..at].chars().next_back();
+                    let after = shared[at + m.len()..].chars().next();
+                    !before.map(char::is_alphanumeric).unwrap_or(false)
+                        && !after.map(char::is_alphanumeric).unwrap_or(false)
+                })
+        })
+        .cloned()
+        .collect()
+}
+
+/// Note body for one member: the shared group narrative followed by the
+/// member's individual section
+pub fn compose_member_note(cohort: &Cohort, shared: &str, individual: &str) -> String {
+    let mut note = format!(
+        "Group: {} ({})\n\nGroup session:\n{}",
+        cohort.name, cohort.program, shared.trim()
+    );
+    if !individual.trim().is_empty() {
+        note.push_str("\n\nIndividual participation:\n");
+        note.push_str(individual.trim());
+    }
+    note
+}
+
+// ============================================
+// Attendance Metrics
+// ============================================
+
+fn round_rate(rate: f64) -> f64 {
+    (rate * 1000.0).round() / 1000.0
+}
+
+/// Attendance per member over `sessions` (already limited to the period)
+pub fn compute_attendance(
+    cohort_id: &str,
+    period_start: &str,
+    period_end: &str,
+    sessions: &[CohortSession],
+    memberships: &[CohortMembership],
+    attendance: &[AttendanceRecord],
+) -> CohortAttendanceReport {
+    let mut sessions: Vec<&CohortSession> = sessions.iter().collect();
+    sessions.sort_by(|a, b| a.session_date.cmp(&b.session_date).then(a.created_at.cmp(&b.created_at)));
+
+    let mut client_ids: Vec<&str> = memberships.iter().map(|m| m.client_id.as_str()).collect();
+    client_ids.sort();
+    client_ids.dedup();
+
+    let members: Vec<MemberAttendance> = client_ids.into_iter()
+        .map(|client_id| {
+            let mut row = MemberAttendance {
+                client_id: client_id.to_string(),
+                eligible_sessions: 0,
+                attended: 0,
+                excused: 0,
+                absent: 0,
+                unrecorded: 0,
+                billable_sessions: 0,
+                billable_minutes: 0,
+                attendance_rate: None,
+                consecutive_absences: 0,
+                needs_outreach: false,
+            };
+
+            for session in &sessions {
+                let enrolled = memberships.iter()
+                    .any(|m| m.client_id == client_id && m.is_active_on(&session.session_date));
+                if !enrolled {
+                    continue;
+                }
+                row.eligible_sessions += 1;
+
+                let record = attendance.iter()
+                    .find(|a| a.session_id == session.id && a.client_id == client_id);
+                match record.map(|r| r.status) {
+                    Some(status) if status.attended() => {
+                        row.attended += 1;
+                        row.consecutive_absences = 0;
+                        let minutes = record
+                            .and_then(|r| r.minutes_attended)
+                            .unwrap_or(session.duration_minutes)
+                            .min(session.duration_minutes);
+                        if minutes as f64 >= session.duration_minutes as f64 * BILLABLE_ATTENDANCE_FRACTION {
+                            row.billable_sessions += 1;
+                            row.billable_minutes += minutes;
+                        }
+                    }
+                    Some(AttendanceStatus::Excused) => row.excused += 1,
+                    Some(_) => {
+                        row.absent += 1;
+                        row.consecutive_absences += 1;
+                    }
+                    None => row.unrecorded += 1,
+                }
+            }
+
+            let expected = row.eligible_sessions - row.excused;
+            row.attendance_rate = (expected > 0).then(|| round_rate(row.attended as f64 / expected as f64));
+            row.needs_outreach = row.consecutive_absences >= ABSENCE_FLAG_THRESHOLD;
+            row
+        })
+        .filter(|row| row.eligible_sessions > 0)
+        .collect();
+
+    let rates: Vec<f64> = members.iter().filter_map(|m| m.attendance_rate).collect();
+    let mean_attendance_rate = (!rates.is_empty())
+        .then(|| round_rate(rates.iter().sum::<f64>() / rates.len() as f64));
+
+    CohortAttendanceReport {
+        cohort_id: cohort_id.to_string(),
+        period_start: period_start.to_string(),
+        period_end: period_end.to_string(),
+        sessions_held: sessions.len() as u32,
+        members,
+        mean_attendance_rate,
+    }
+}
+
+// ============================================
+// Tauri Commands
+// ============================================
+
+use tauri::State;
+use crate::commands::AppState;
+
+#[tauri::command]
+pub fn create_cohort(
+    state: State<'_, AppState>,
+    name: String,
+    program: String,
+    schedule: CohortSchedule,
+) -> Result<Cohort, String> {
+    schedule.validate()?;
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.create_cohort(&name, &program, &schedule).map_err(|e| format!("{}", e))
+}
+
+#[tauri::command]
+pub fn list_cohorts(state: State<'_, AppState>, include_archived: Option<bool>) -> Result<Vec<Cohort>, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.list_cohorts(include_archived.unwrap_or(false)).map_err(|e| format!("{}", e))
+}
+
+#[tauri::command]
+pub fn archive_cohort(state: State<'_, AppState>, cohort_id: String) -> Result<(), String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.archive_cohort(&cohort_id).map_err(|e| format!("{}", e))
+}
+
+#[tauri::command]
+pub fn add_cohort_member(
+    state: State<'_, AppState>,
+    cohort_id: String,
+    client_id: String,
+    joined_on: String,
+) -> Result<CohortMembership, String> {
+    validate_date(&joined_on)?;
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.add_cohort_member(&cohort_id, &client_id, &joined_on).map_err(|e| format!("{}", e))
+}
+
+#[tauri::command]
+pub fn end_cohort_membership(
+    state: State<'_, AppState>,
+    cohort_id: String,
+    client_id: String,
+    left_on: String,
+    reason: Option<String>,
+) -> Result<CohortMembership, String> {
+    validate_date(&left_on)?;
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.end_cohort_membership(&cohort_id, &client_id, &left_on, reason.as_deref())
+        .map_err(|e| format!("{}", e))
+}
+
+/// Membership history; current members only unless `include_past`
+#[tauri::command]
+pub fn list_cohort_members(
+    state: State<'_, AppState>,
+    cohort_id: String,
+    include_past: Option<bool>,
+) -> Result<Vec<CohortMembership>, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.list_cohort_memberships(&cohort_id, include_past.unwrap_or(false)).map_err(|e| format!("{}", e))
+}
+
+/// Record a session: attendance for every member, a Group note for each
+/// member who attended
+#[tauri::command]
+pub fn record_cohort_session(
+    state: State<'_, AppState>,
+    cohort_id: String,
+    session_date: String,
+    shared_content: String,
+    members: Vec<MemberSessionInput>,
+    duration_minutes: Option<u32>,
+) -> Result<CohortSessionRecord, String> {
+    validate_date(&session_date)?;
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.record_cohort_session(&cohort_id, &session_date, &shared_content, &members, duration_minutes)
+        .map_err(|e| format!("{}", e))
+}
+
+#[tauri::command]
+pub fn get_cohort_attendance(
+    state: State<'_, AppState>,
+    cohort_id: String,
+    period_start: String,
+    period_end: String,
+) -> Result<CohortAttendanceReport, String> {
+    validate_date(&period_start)?;
+    validate_date(&period_end)?;
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.get_cohort_attendance_report(&cohort_id, &period_start, &period_end).map_err(|e| format!("{}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn membership(client_id: &str, joined_on: &str, left_on: Option<&str>) -> CohortMembership {
+        CohortMembership {
+            id: format!("m-{}-{}", client_id, joined_on),
+            cohort_id: "c1".to_string(),
+            client_id: client_id.to_string(),
+            joined_on: joined_on.to_string(),
+            left_on: left_on.map(str::to_string),
+            left_reason: None,
+        }
+    }
+
+    fn session(id: &str, date: &str) -> CohortSession {
+        CohortSession {
+            id: id.to_string(),
+            cohort_id: "c1".to_string(),
+            session_date: date.to_string(),
+            duration_minutes: 180,
+            created_at: 0,
+        }
+    }
+
+    fn record(session_id: &str, client_id: &str, status: AttendanceStatus, minutes: Option<u32>) -> AttendanceRecord {
+        AttendanceRecord {
+            session_id: session_id.to_string(),
+            client_id: client_id.to_string(),
+            status,
+            minutes_attended: minutes,
+            note_id: None,
+        }
+    }
+
+    #[test]
+    fn test_attendance_follows_membership_history() {
+        let sessions = vec![
+            session("s1", "2026-03-02"),
+            session("s2", "2026-03-04"),
+            session("s3", "2026-03-06"),
+            session("s4", "2026-03-09"),
+        ];
+        // b joins for the second session, steps out, and returns for the last
+        let memberships = vec![
+            membership("a", "2026-03-01", None),
+            membership("b", "2026-03-04", Some("2026-03-06")),
+            membership("b", "2026-03-09", None),
+        ];
+        let attendance = vec![
+            record("s1", "a", AttendanceStatus::Present, None),
+            record("s2", "a", AttendanceStatus::LeftEarly, Some(60)),
+            record("s3", "a", AttendanceStatus::Excused, None),
+            record("s4", "a", AttendanceStatus::Late, Some(150)),
+            record("s2", "b", AttendanceStatus::Present, None),
+            record("s4", "b", AttendanceStatus::Absent, None),
+        ];
+
+        let report = compute_attendance("c1", "2026-03-01", "2026-03-31", &sessions, &memberships, &attendance);
+        assert_eq!(report.sessions_held, 4);
+
+        let a = &report.members[0];
+        assert_eq!((a.eligible_sessions, a.attended, a.excused), (4, 3, 1));
+        assert_eq!(a.attendance_rate, Some(1.0));
+        // Leaving after an hour of three is not billable
+        assert_eq!((a.billable_sessions, a.billable_minutes), (2, 330));
+
+        let b = &report.members[1];
+        assert_eq!((b.eligible_sessions, b.attended, b.absent), (2, 1, 1));
+        assert_eq!(b.attendance_rate, Some(0.5));
+        assert_eq!(report.mean_attendance_rate, Some(0.75));
+    }
+
+    #[test]
+    fn test_member_names_in_shared_content() {
+        let names = vec!["Ann".to_string(), "Joe Smith".to_string()];
+        let found = names_in_shared_content("Group discussed planning. joe smith shared an example.", &names);
+        assert_eq!(found, vec!["Joe Smith".to_string()]);
+        // Substrings of other words are not names
+        assert!(names_in_shared_content("Members practiced annual goal setting.", &names).is_empty());
+    }
+}
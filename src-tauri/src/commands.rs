@@ -38,14 +38,54 @@ pub fn vault_exists(state: State<AppState>) -> bool {
 }
 
 #[tauri::command]
-pub fn create_vault(state: State<AppState>, passphrase: String) -> Result<(), String> {
+pub fn create_vault(
+    app: tauri::AppHandle,
+    state: State<AppState>,
+    policy_state: State<'_, crate::policy::PolicyState>,
+    passphrase: String,
+) -> Result<(), String> {
     let mut vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
-    vault.create(&passphrase).map_err(|e| format!("{}", e))
+    ensure_residency(&vault, &policy_state)?;
+    {
+        let engine = policy_state.engine.read().map_err(|e| e.to_string())?;
+        crate::passphrase::enforce(&passphrase, &engine.get_policy().passphrase_policy)?;
+    }
+    vault.create(&passphrase).map_err(|e| format!("{}", e))?;
+    start_background_workers(&app);
+    Ok(())
+}
+
+/// Threads that need the unlocked vault start with the first unlock
+/// instead of at launch; the startup report lists them as a deferred phase
+fn start_background_workers(app: &tauri::AppHandle) {
+    static STARTED: std::sync::Once = std::sync::Once::new();
+    STARTED.call_once(|| crate::performance::time_deferred_phase("background_workers", || {
+        crate::maintenance::start_scheduler(app);
+        crate::ehr_export::start_delivery_worker(app);
+    }));
+}
+
+/// Refuse vault access when the residency policy blocks its location
+pub(crate) fn ensure_residency(vault: &Vault, policy_state: &crate::policy::PolicyState) -> Result<(), String> {
+    let policy = {
+        let engine = policy_state.engine.read().map_err(|e| e.to_string())?;
+        engine.get_policy().residency_policy.clone()
+    };
+    match crate::residency::check_data_dir(vault.data_dir(), &policy).blocker() {
+        Some(reason) => Err(reason),
+        None => Ok(()),
+    }
 }
 
 #[tauri::command]
-pub fn unlock_vault(state: State<AppState>, passphrase: String) -> Result<(), String> {
+pub fn unlock_vault(
+    app: tauri::AppHandle,
+    state: State<AppState>,
+    policy_state: State<'_, crate::policy::PolicyState>,
+    passphrase: String,
+) -> Result<(), String> {
     let mut vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    ensure_residency(&vault, &policy_state)?;
     vault.unlock(&passphrase).map_err(|e| format!("{}", e))?;
     
     // Log vault unlock event
@@ -60,11 +100,59 @@ pub fn unlock_vault(state: State<AppState>, passphrase: String) -> Result<(), St
         );
     }
     
+    // Follow-up checks on overridden exports that came due while locked
+    if let Err(e) = vault.run_due_presence_checks() {
+        log::warn!("Export presence checks failed: {}", e);
+    }
+    
+    start_background_workers(&app);
     Ok(())
 }
 
+/// Change the vault passphrase. With `rotate_key`, the vault key is
+/// replaced too and the database re-encrypted under it.
+#[tauri::command]
+pub fn change_passphrase(
+    state: State<AppState>,
+    policy_state: State<'_, crate::policy::PolicyState>,
+    replica_state: State<'_, crate::replica::ReplicaState>,
+    old_passphrase: String,
+    new_passphrase: String,
+    rotate_key: Option<bool>,
+) -> Result<crate::vault::PassphraseChange, String> {
+    let mut vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    {
+        let engine = policy_state.engine.read().map_err(|e| e.to_string())?;
+        crate::passphrase::enforce(&new_passphrase, &engine.get_policy().passphrase_policy)?;
+    }
+    let rotate_key = rotate_key.unwrap_or(false);
+    let result = vault.change_passphrase(&old_passphrase, &new_passphrase, rotate_key);
+    
+    if let Ok(conn) = vault.get_connection() {
+        let _ = audit::log_event(
+            conn,
+            AuditEventType::PassphraseChanged,
+            AuditResourceType::Vault,
+            if rotate_key { "vault_key" } else { "vault" },
+            if result.is_ok() { AuditOutcome::Success } else { AuditOutcome::Failure },
+            None,
+        );
+    }
+    let change = result.map_err(|e| format!("{}", e))?;
+    
+    // The replica is a copy under the previous key
+    if change.key_rotated {
+        drop(vault);
+        replica_state.clear();
+    }
+    Ok(change)
+}
+
 #[tauri::command]
-pub fn lock_vault(state: State<AppState>) -> Result<(), String> {
+pub fn lock_vault(
+    state: State<AppState>,
+    replica_state: State<'_, crate::replica::ReplicaState>,
+) -> Result<(), String> {
     let mut vault = state.vault.lock().map_err(|_| "Vault mutex poisoned".to_string())?;
     
     // Log vault lock event before locking
@@ -80,6 +168,10 @@ pub fn lock_vault(state: State<AppState>) -> Result<(), String> {
     }
     
     vault.lock();
+    // After releasing the vault: a refresh holds the replica lock while it
+    // waits for the vault
+    drop(vault);
+    replica_state.clear();
     Ok(())
 }
 
@@ -187,9 +279,16 @@ pub fn vault_delete_db(state: State<AppState>, confirm: bool) -> Result<(), Stri
 // ============================================
 
 #[tauri::command]
-pub fn create_client(state: State<AppState>, display_name: String) -> Result<Client, String> {
+pub fn create_client(
+    state: State<AppState>,
+    display_name: String,
+    idempotency_key: Option<String>,
+) -> Result<Client, String> {
     let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned".to_string())?;
-    vault.create_client(&display_name).map_err(|e| format!("{e}"))
+    let args = serde_json::json!({ "display_name": &display_name });
+    crate::idempotency::run_once(&vault, idempotency_key.as_deref(), "create_client", args, || {
+        vault.create_client(&display_name).map_err(|e| format!("{e}"))
+    })
 }
 
 #[tauri::command]
@@ -199,8 +298,13 @@ pub fn list_clients(state: State<AppState>) -> Result<Vec<Client>, String> {
 }
 
 #[tauri::command]
-pub fn get_client(state: State<AppState>, id: String) -> Result<Client, String> {
+pub fn get_client(
+    state: State<AppState>,
+    policy_state: State<'_, crate::policy::PolicyState>,
+    id: String,
+) -> Result<Client, String> {
     let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned".to_string())?;
+    ensure_chart_access(&vault, &policy_state, &id)?;
     vault.get_client(&id).map_err(|e| format!("{e}"))
 }
 
@@ -215,6 +319,182 @@ pub fn update_client(
     vault.update_client(&client).map_err(|e| format!("{e}"))
 }
 
+// ============================================
+// Reason-for-Access
+// ============================================
+
+/// Error prefix the UI matches on to show the reason-for-access prompt
+pub const ACCESS_REASON_REQUIRED: &str = "ACCESS_REASON_REQUIRED";
+
+// Every command that returns chart content goes through one of these
+// guards: single reads are refused, lists and searches drop the charts
+// that need a stated reason.
+
+fn access_policy(policy_state: &crate::policy::PolicyState) -> Result<crate::policy::AccessPolicy, String> {
+    let engine = policy_state.engine.read().map_err(|e| e.to_string())?;
+    Ok(engine.get_policy().access_policy.clone())
+}
+
+/// Block chart reads of restricted clients unless a reason is on record
+pub(crate) fn ensure_chart_access(
+    vault: &Vault,
+    policy_state: &crate::policy::PolicyState,
+    client_id: &str,
+) -> Result<(), String> {
+    let policy = access_policy(policy_state)?;
+    if !vault.chart_access_allowed(client_id, &policy).map_err(|e| format!("{e}"))? {
+        return Err(format!(
+            "{}: this chart is restricted; state a reason for access to open it",
+            ACCESS_REASON_REQUIRED
+        ));
+    }
+    Ok(())
+}
+
+/// Audit note content returned to the UI or written out, per client
+pub(crate) fn log_note_retrieval(vault: &Vault, kind: &str, note_ids: &[&str]) -> Result<(), String> {
+    let conn = vault.get_connection().map_err(|e| format!("{e}"))?;
+    crate::access_history::log_retrieval(conn, kind, note_ids)
+        .map_err(|e| format!("Audit write failed: {e}"))
+}
+
+/// A note, if its chart may be read
+pub(crate) fn read_note_checked(
+    vault: &Vault,
+    policy_state: &crate::policy::PolicyState,
+    note_id: &str,
+) -> Result<Note, String> {
+    let note = vault.get_note(note_id).map_err(|e| format!("{e}"))?;
+    ensure_chart_access(vault, policy_state, &note.client_id)?;
+    Ok(note)
+}
+
+/// `items` without those belonging to charts that need a stated reason
+pub(crate) fn retain_accessible_charts<T>(
+    vault: &Vault,
+    policy_state: &crate::policy::PolicyState,
+    mut items: Vec<T>,
+    client_of: impl Fn(&T) -> Option<&str>,
+) -> Result<Vec<T>, String> {
+    let policy = access_policy(policy_state)?;
+    let restricted = vault.restricted_chart_ids(&policy).map_err(|e| format!("{e}"))?;
+    if !restricted.is_empty() {
+        items.retain(|item| client_of(item).map_or(true, |id| !restricted.contains(id)));
+    }
+    Ok(items)
+}
+
+/// Whether opening this client's chart will need a stated reason
+#[tauri::command]
+pub fn chart_access_required(
+    state: State<AppState>,
+    policy_state: State<'_, crate::policy::PolicyState>,
+    client_id: String,
+) -> Result<bool, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned".to_string())?;
+    Ok(ensure_chart_access(&vault, &policy_state, &client_id).is_err())
+}
+
+/// Record a reason for opening a restricted chart (audited)
+#[tauri::command]
+pub fn record_chart_access_reason(
+    state: State<AppState>,
+    policy_state: State<'_, crate::policy::PolicyState>,
+    client_id: String,
+    reason: AccessReason,
+    justification: Option<String>,
+) -> Result<ChartAccessGrant, String> {
+    let ttl_minutes = {
+        let engine = policy_state.engine.read().map_err(|e| e.to_string())?;
+        engine.get_policy().access_policy.reason_ttl_minutes
+    };
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned".to_string())?;
+    vault.record_chart_access(&client_id, reason, justification.as_deref(), ttl_minutes)
+        .map_err(|e| format!("{e}"))
+}
+
+// ============================================
+// Emergency Access (break-glass)
+// ============================================
+
+/// Default break-glass session length when the caller doesn't ask for one
+const EMERGENCY_ACCESS_DEFAULT_MINUTES: u32 = 60;
+
+/// Set the credential covering clinicians use to open emergency access.
+/// Replacing it requires the current one.
+#[tauri::command]
+pub fn set_emergency_access_credential(
+    state: State<AppState>,
+    credential: String,
+    current_credential: Option<String>,
+) -> Result<(), String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned".to_string())?;
+    vault.set_emergency_access_credential(&credential, current_credential.as_deref()).map_err(|e| format!("{e}"))
+}
+
+/// Open time-limited read access to a client's recent notes (audited,
+/// flagged for review)
+#[tauri::command]
+pub fn activate_emergency_access(
+    state: State<AppState>,
+    client_id: String,
+    covering_clinician: String,
+    credential: String,
+    justification: String,
+    ttl_minutes: Option<u32>,
+) -> Result<EmergencyAccessSession, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned".to_string())?;
+    vault.activate_emergency_access(
+        &client_id,
+        &covering_clinician,
+        &credential,
+        &justification,
+        ttl_minutes.unwrap_or(EMERGENCY_ACCESS_DEFAULT_MINUTES),
+    ).map_err(|e| format!("{e}"))
+}
+
+/// Recent notes visible under an active emergency session
+#[tauri::command]
+pub fn read_emergency_access_notes(
+    state: State<AppState>,
+    session_id: String,
+) -> Result<Vec<Note>, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned".to_string())?;
+    vault.read_emergency_access_notes(&session_id).map_err(|e| format!("{e}"))
+}
+
+#[tauri::command]
+pub fn end_emergency_access(
+    state: State<AppState>,
+    session_id: String,
+) -> Result<EmergencyAccessSession, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned".to_string())?;
+    vault.end_emergency_access(&session_id).map_err(|e| format!("{e}"))
+}
+
+#[tauri::command]
+pub fn list_emergency_access_sessions(
+    state: State<AppState>,
+    pending_only: Option<bool>,
+) -> Result<Vec<EmergencyAccessSession>, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned".to_string())?;
+    vault.list_emergency_access_sessions(pending_only.unwrap_or(false)).map_err(|e| format!("{e}"))
+}
+
+/// Record the post-hoc review of an emergency access session
+#[tauri::command]
+pub fn review_emergency_access(
+    state: State<AppState>,
+    session_id: String,
+    reviewer: String,
+    appropriate: bool,
+    note: Option<String>,
+) -> Result<EmergencyAccessSession, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned".to_string())?;
+    vault.review_emergency_access(&session_id, &reviewer, appropriate, note.as_deref())
+        .map_err(|e| format!("{e}"))
+}
+
 // ============================================
 // Note Commands
 // ============================================
@@ -226,76 +506,182 @@ pub fn create_note(
     session_date: String,
     note_type: String,
     content: String,
+    idempotency_key: Option<String>,
 ) -> Result<Note, String> {
     let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned".to_string())?;
-    let note_type = NoteType::from_str(&note_type);
-    vault.create_note(&client_id, &session_date, note_type, &content)
-        .map_err(|e| format!("{e}"))
+    let args = serde_json::json!({
+        "client_id": &client_id, "session_date": &session_date, "note_type": &note_type, "content": &content,
+    });
+    crate::idempotency::run_once(&vault, idempotency_key.as_deref(), "create_note", args, || {
+        // Agency-defined types are stored as their base type plus a name reference
+        let custom_type = if crate::note_types::is_builtin(&note_type) {
+            None
+        } else {
+            vault.get_note_type_definition(&note_type).map_err(|e| format!("{e}"))?
+        };
+        let base_type = custom_type.as_ref()
+            .map(|def| def.base_type)
+            .unwrap_or_else(|| NoteType::from_str(&note_type));
+        
+        let note = vault.create_note(&client_id, &session_date, base_type, &content)
+            .map_err(|e| format!("{e}"))?;
+        if let Some(def) = custom_type {
+            vault.assign_note_type(&note.id, &def.name).map_err(|e| format!("{e}"))?;
+        }
+        Ok(note)
+    })
 }
 
 #[tauri::command]
-pub fn get_note(state: State<AppState>, id: String) -> Result<Note, String> {
+pub fn get_note(
+    state: State<AppState>,
+    policy_state: State<'_, crate::policy::PolicyState>,
+    id: String,
+) -> Result<Note, String> {
     let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned".to_string())?;
-    vault.get_note(&id).map_err(|e| format!("{e}"))
+    read_note_checked(&vault, &policy_state, &id)
 }
 
 #[tauri::command]
-pub fn list_notes(state: State<AppState>, client_id: Option<String>) -> Result<Vec<Note>, String> {
+pub fn list_notes(
+    state: State<AppState>,
+    policy_state: State<'_, crate::policy::PolicyState>,
+    client_id: Option<String>,
+) -> Result<Vec<Note>, String> {
     let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned".to_string())?;
-    vault.list_notes(client_id.as_deref()).map_err(|e| format!("{e}"))
+    if let Some(ref client_id) = client_id {
+        ensure_chart_access(&vault, &policy_state, client_id)?;
+    }
+    let notes = vault.list_notes(client_id.as_deref()).map_err(|e| format!("{e}"))?;
+    retain_accessible_charts(&vault, &policy_state, notes, |n| Some(n.client_id.as_str()))
 }
 
 #[tauri::command]
-pub fn update_note(state: State<AppState>, id: String, content: String) -> Result<Note, String> {
+pub fn update_note(
+    state: State<AppState>,
+    id: String,
+    content: String,
+    editor: Option<String>,
+) -> Result<Note, String> {
     let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned".to_string())?;
-    vault.update_note(&id, &content).map_err(|e| format!("{e}"))
+    vault.update_note(&id, &content, editor.as_deref()).map_err(|e| format!("{e}"))
 }
 
 #[tauri::command]
 pub fn update_structured_note(
     state: State<AppState>, 
     id: String, 
-    structured_note: String
+    structured_note: String,
+    editor: Option<String>,
 ) -> Result<Note, String> {
     let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned".to_string())?;
-    vault.update_note_structured(&id, &structured_note).map_err(|e| format!("{e}"))
+    vault.update_note_structured(&id, &structured_note, editor.as_deref()).map_err(|e| format!("{e}"))
 }
 
 #[tauri::command]
-pub fn sign_note(state: State<AppState>, id: String, attestations: String) -> Result<Note, String> {
+pub fn sign_note(
+    state: State<AppState>,
+    policy_state: State<'_, crate::policy::PolicyState>,
+    id: String,
+    attestations: String,
+) -> Result<Note, String> {
     let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned".to_string())?;
     
     // Get the note first to validate
     let note = vault.get_note(&id).map_err(|e| format!("{e}"))?;
-    
-    // Basic completion validation - require minimum content
     let content = note.structured_note.as_ref().unwrap_or(&note.raw_input);
-    let word_count = content.split_whitespace().count();
+    let parsed: Vec<crate::models::Attestation> = serde_json::from_str(&attestations)
+        .map_err(|e| format!("Invalid attestations: {}", e))?;
     
-    if word_count < 20 {
-        return Err("Cannot sign: Note is too short (minimum 20 words required for defensibility)".to_string());
+    // Organization policy: required attestations present, responses acceptable
+    // (the part of this gate policy simulations replay)
+    {
+        let engine = policy_state.engine.read().map_err(|e| e.to_string())?;
+        let blockers = crate::policy::policy_sign_blockers(&engine, &note.detection_ids, &parsed);
+        if !blockers.is_empty() {
+            return Err(format!("Cannot sign: {}", blockers.join("; ")));
+        }
     }
     
-    // Check for SOAP note critical sections
-    let content_lower = content.to_lowercase();
-    let has_assessment = content_lower.contains("assessment") || content_lower.contains("a:") || content_lower.contains("a (");
-    let has_plan = content_lower.contains("plan") || content_lower.contains("p:") || content_lower.contains("p (");
+    // Attestations must refer to detections anchored in the text being signed
+    if let Some(anchors) = vault.get_note_detection_state(&id).map_err(|e| format!("{e}"))? {
+        let current = crate::crypto::digests_match(&anchors.content_hash, &crate::crypto::hash_sha256(content.as_bytes()));
+        if anchors.reanalyze_required || !current {
+            return Err("Cannot sign: the note changed after ethics analysis; re-analyze before signing".to_string());
+        }
+        if let Some(orphan) = parsed.iter().find(|a| !anchors.detections.iter().any(|d| d.id == a.detection_id)) {
+            return Err(format!(
+                "Cannot sign: attestation for {} refers to text no longer in the note", orphan.detection_id
+            ));
+        }
+    }
     
-    if !has_assessment {
-        return Err("Cannot sign: Note is missing Assessment section (required for clinical defensibility)".to_string());
+    // Telehealth patient outside licensed jurisdictions
+    if let Some(check) = vault.get_licensure_check(&id).map_err(|e| format!("{e}"))? {
+        if let Some(blocker) = check.sign_blocker() {
+            return Err(format!("Cannot sign: {}", blocker));
+        }
     }
     
-    if !has_plan {
-        return Err("Cannot sign: Note is missing Plan section (required for clinical defensibility)".to_string());
+    // Every note passes the baseline; agency-defined types add their own rules
+    let mut failures = crate::note_types::baseline_sign_gate(content);
+    if let Some(type_name) = vault.get_note_type_name(&id).map_err(|e| format!("{e}"))? {
+        let def = vault.get_note_type_definition(&type_name).map_err(|e| format!("{e}"))?
+            .ok_or_else(|| format!("Cannot sign: note type '{}' no longer exists", type_name))?;
+        let has_mse = vault.get_note_mse(&id).map_err(|e| format!("{e}"))?.is_some();
+        failures.extend(crate::note_types::check_sign_gate(&def, content, has_mse));
+    }
+    if !failures.is_empty() {
+        return Err(format!("Cannot sign: {}", failures.join("; ")));
     }
     
     vault.sign_note(&id, &attestations).map_err(|e| format!("{e}"))
 }
 
+/// Take the advisory edit lock for a window. If another window holds it,
+/// `acquired` is false and `lock` names the current holder so the UI can warn.
 #[tauri::command]
-pub fn export_note(state: State<AppState>, id: String, format: String) -> Result<String, String> {
+pub fn acquire_note_lock(
+    state: State<AppState>,
+    note_id: String,
+    holder_id: String,
+    holder_label: Option<String>,
+    ttl_secs: Option<i64>,
+    force: Option<bool>,
+) -> Result<NoteLockOutcome, String> {
     let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned".to_string())?;
-    let note = vault.get_note(&id).map_err(|e| format!("{e}"))?;
+    vault.acquire_note_lock(&note_id, &holder_id, holder_label.as_deref(), ttl_secs, force.unwrap_or(false))
+        .map_err(|e| format!("{e}"))
+}
+
+#[tauri::command]
+pub fn release_note_lock(state: State<AppState>, note_id: String, holder_id: String) -> Result<bool, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned".to_string())?;
+    vault.release_note_lock(&note_id, &holder_id).map_err(|e| format!("{e}"))
+}
+
+/// Drop every lock a window holds (call on window close)
+#[tauri::command]
+pub fn release_note_locks_for_holder(state: State<AppState>, holder_id: String) -> Result<usize, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned".to_string())?;
+    vault.release_note_locks_for_holder(&holder_id).map_err(|e| format!("{e}"))
+}
+
+#[tauri::command]
+pub fn get_note_lock(state: State<AppState>, note_id: String) -> Result<Option<NoteLock>, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned".to_string())?;
+    vault.get_note_lock(&note_id).map_err(|e| format!("{e}"))
+}
+
+#[tauri::command]
+pub fn export_note(
+    state: State<AppState>,
+    policy_state: State<'_, crate::policy::PolicyState>,
+    id: String,
+    format: String,
+) -> Result<String, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned".to_string())?;
+    let note = read_note_checked(&vault, &policy_state, &id)?;
     
     match format.as_str() {
         "text" => Ok(format_note_text(&note)),
@@ -355,6 +741,131 @@ pub fn analyze_ethics(content: String) -> EthicsAnalysis {
     ethics::analyze(&content)
 }
 
+/// Ethics analysis that also flags required documentation that is missing,
+/// given the client's open risk events and the note type
+#[tauri::command]
+pub fn analyze_ethics_with_context(
+    state: State<AppState>,
+    content: String,
+    client_id: String,
+    note_type: Option<String>,
+    note_id: Option<String>,
+    lookback_days: Option<i64>,
+) -> Result<EthicsAnalysis, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned".to_string())?;
+    let context = ethics::ClientContext {
+        note_type: note_type.as_deref().map(NoteType::from_str),
+        active_risk_events: client_risk_events(&vault, &client_id, note_id.as_deref(), lookback_days.unwrap_or(90))?,
+    };
+    Ok(ethics::analyze_with_context(&content, &context))
+}
+
+/// Analyze a saved note and anchor its detections to the analyzed text, so
+/// later edits either carry them over or require re-analysis
+#[tauri::command]
+pub fn analyze_note_ethics(
+    state: State<AppState>,
+    note_id: String,
+    lookback_days: Option<i64>,
+) -> Result<EthicsAnalysis, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned".to_string())?;
+    let note = vault.get_note(&note_id).map_err(|e| format!("{e}"))?;
+    let context = ethics::ClientContext {
+        note_type: Some(note.note_type),
+        active_risk_events: client_risk_events(&vault, &note.client_id, Some(&note.id), lookback_days.unwrap_or(90))?,
+    };
+    let analysis = ethics::analyze_with_context(Vault::analyzed_text(&note), &context);
+    vault.record_note_detections(&note.id, &analysis.stored_detections).map_err(|e| format!("{e}"))?;
+    Ok(analysis)
+}
+
+/// Anchored detections for a note and whether it needs re-analysis
+#[tauri::command]
+pub fn get_note_detection_state(
+    state: State<AppState>,
+    note_id: String,
+) -> Result<Option<crate::models::NoteDetectionState>, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned".to_string())?;
+    vault.get_note_detection_state(&note_id).map_err(|e| format!("{e}"))
+}
+
+/// Why a detection on a note fired: the pattern and words matched, the
+/// exclusions checked, and the policy the rule cites
+#[tauri::command]
+pub fn explain_detection(
+    state: State<AppState>,
+    policy_state: State<'_, crate::policy::PolicyState>,
+    detection_id: String,
+    note_id: String,
+) -> Result<ethics::DetectionExplanation, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned".to_string())?;
+    let note = read_note_checked(&vault, &policy_state, &note_id)?;
+    let detection = vault.get_note_detection_state(&note_id).map_err(|e| format!("{e}"))?
+        .and_then(|s| s.detections.into_iter().find(|d| d.id == detection_id))
+        .ok_or_else(|| format!("Detection {} not found on note {}", detection_id, note_id))?;
+    let explanation = ethics::explain_detection(&detection, Vault::analyzed_text(&note))
+        .ok_or_else(|| format!("Rule {} is no longer in the rule pack", detection.pattern_id))?;
+    log_note_retrieval(&vault, "explain_detection", &[note_id.as_str()])?;
+    Ok(explanation)
+}
+
+/// Session and collateral runs of a note's text, for marking collateral
+/// passages in the editor
+#[tauri::command]
+pub fn get_note_sources(
+    state: State<AppState>,
+    policy_state: State<'_, crate::policy::PolicyState>,
+    note_id: String,
+) -> Result<Vec<ethics::SourceSegment>, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned".to_string())?;
+    let note = read_note_checked(&vault, &policy_state, &note_id)?;
+    log_note_retrieval(&vault, "note_sources", &[note_id.as_str()])?;
+    Ok(ethics::source_segments(Vault::analyzed_text(&note)))
+}
+
+/// Risk events from the client's recent notes (detections and structured MSEs),
+/// excluding the note currently being analyzed
+fn client_risk_events(
+    vault: &Vault,
+    client_id: &str,
+    exclude_note_id: Option<&str>,
+    lookback_days: i64,
+) -> Result<Vec<ethics::RiskEvent>, String> {
+    let cutoff = (chrono::Utc::now() - chrono::Duration::days(lookback_days)).format("%Y-%m-%d").to_string();
+    let recent = |note_id: &str, session_date: &str| {
+        Some(note_id) != exclude_note_id && session_date >= cutoff.as_str()
+    };
+    
+    let mut events = Vec::new();
+    for note in vault.list_notes(Some(client_id)).map_err(|e| format!("{e}"))? {
+        if recent(&note.id, &note.session_date) {
+            events.extend(ethics::risk_events_from_detections(&note.detection_ids, &note.id, &note.session_date));
+        }
+    }
+    
+    for mse in vault.get_client_mse_history(client_id).map_err(|e| format!("{e}"))? {
+        if !recent(&mse.note_id, &mse.session_date) {
+            continue;
+        }
+        let kinds = [
+            (mse.exam.suicidal_ideation, ethics::RiskEventKind::SuicidalIdeation),
+            (mse.exam.homicidal_ideation, ethics::RiskEventKind::HomicidalIdeation),
+        ];
+        for (ideation, kind) in kinds {
+            if ideation != crate::mse::Ideation::Denied {
+                events.push(ethics::RiskEvent {
+                    kind,
+                    source_note_id: mse.note_id.clone(),
+                    session_date: mse.session_date.clone(),
+                    source: "mse".to_string(),
+                });
+            }
+        }
+    }
+    
+    Ok(events)
+}
+
 #[tauri::command]
 pub fn resolve_detection(
     state: State<AppState>,
@@ -385,6 +896,49 @@ pub fn resolve_detection(
     Ok(())
 }
 
+/// Number of earlier notes the risk trend is measured against
+const RISK_TREND_LOOKBACK: usize = 5;
+
+/// Structured risk score for a note (safety / integrity / privacy) with
+/// trend against the client's previous notes
+#[tauri::command]
+pub fn compute_note_risk_summary(
+    state: State<AppState>,
+    note_id: String,
+) -> Result<ethics::NoteRiskSummary, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned".to_string())?;
+    let note = vault.get_note(&note_id).map_err(|e| format!("{e}"))?;
+    
+    let mut previous: Vec<Note> = vault.list_notes(Some(&note.client_id)).map_err(|e| format!("{e}"))?
+        .into_iter()
+        .filter(|n| n.id != note.id
+            && (n.session_date.as_str(), n.created_at) < (note.session_date.as_str(), note.created_at))
+        .collect();
+    previous.sort_by(|a, b| (b.session_date.as_str(), b.created_at).cmp(&(a.session_date.as_str(), a.created_at)));
+    let previous_totals: Vec<u32> = previous.iter()
+        .take(RISK_TREND_LOOKBACK)
+        .map(|n| ethics::risk_total(&n.detection_ids))
+        .collect();
+    
+    Ok(ethics::summarize_note_risk(&note.id, &note.detection_ids, &note.attestations, &previous_totals))
+}
+
+/// Admin report: how often each rule's detections are dismissed vs acted on,
+/// with suggested severity adjustments
+#[tauri::command]
+pub fn get_severity_calibration_report(
+    state: State<AppState>,
+    min_samples: Option<usize>,
+) -> Result<ethics::CalibrationReport, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned".to_string())?;
+    let notes = vault.list_notes(None).map_err(|e| format!("{e}"))?;
+    let attestations: Vec<Attestation> = notes.into_iter()
+        .flat_map(|n| n.attestations)
+        .collect();
+    
+    Ok(ethics::calibration_report(&attestations, min_samples.unwrap_or(20)))
+}
+
 // ============================================
 // AI Commands
 // ============================================
@@ -406,6 +960,37 @@ pub async fn structure_note_ai(
         .map_err(|e| format!("{e}"))
 }
 
+/// Structured draft plus how it was produced
+#[derive(serde::Serialize)]
+pub struct GuardedStructuring {
+    pub structured_note: String,
+    pub provenance: ai::AiProvenance,
+}
+
+/// Structure a note with the policy's prompt-scrubbing guardrail applied.
+/// Returns provenance so the caller can store it with the draft.
+#[tauri::command]
+pub async fn structure_note_ai_guarded(
+    policy_state: State<'_, crate::policy::PolicyState>,
+    model: String,
+    content: String,
+    note_type: String,
+) -> Result<GuardedStructuring, String> {
+    let (scrub, policy_version) = {
+        let engine = policy_state.engine.read().map_err(|e| e.to_string())?;
+        let policy = engine.get_policy();
+        let scrub = policy.ai_policy.scrub_prompts.then(|| ai::ScrubOptions::from(&policy.ai_policy));
+        (scrub, policy.version.clone())
+    };
+    
+    let note_type = NoteType::from_str(&note_type);
+    let (structured_note, provenance) = ai::structure_note_guarded(&model, &content, note_type, scrub, &policy_version)
+        .await
+        .map_err(|e| format!("{e}"))?;
+    
+    Ok(GuardedStructuring { structured_note, provenance })
+}
+
 #[tauri::command]
 pub fn embed_text(text: String) -> Vec<f32> {
     ai::embed_text_local(&text)
@@ -578,35 +1163,183 @@ pub fn validate_export_path(
     }
 }
 
-// ============================================
-// Voice Commands
-// ============================================
-
-#[tauri::command]
-pub fn list_whisper_models() -> Vec<voice::WhisperModelInfo> {
-    let models_dir = std::path::PathBuf::from("models");
-    voice::VoiceCapture::list_models(&models_dir)
-}
-
-#[tauri::command]
-pub fn get_transcript_text(state: State<AppState>) -> String {
-    // In production, we'd have voice state in AppState
-    // For now, return placeholder
-    "Transcript text would appear here during recording.".to_string()
-}
-
-/// Transcribe audio buffer and return segments
+/// Pre-flight report for a per-client export: scope, size, time and any
+/// policy blockers. Nothing is exported.
 #[tauri::command]
-pub fn transcribe_audio(
-    audio_data: Vec<f32>,
-    sample_rate: u32,
-) -> Result<Vec<voice::TranscriptSegment>, String> {
-    // Resample to 16kHz if needed
-    let audio = if sample_rate != 16000 {
+pub fn estimate_export(
+    state: State<AppState>,
+    policy_state: State<'_, crate::policy::PolicyState>,
+    perf_state: State<'_, crate::performance::PerformanceState>,
+    client_id: String,
+    options: Option<export::ClientExportOptions>,
+) -> Result<export::ExportEstimate, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    let options = options.unwrap_or_default();
+    build_export_estimate(&vault, &policy_state, perf_state.pending_tasks(), &client_id, &options)
+}
+
+fn build_export_estimate(
+    vault: &Vault,
+    policy_state: &crate::policy::PolicyState,
+    queued_tasks: usize,
+    client_id: &str,
+    options: &export::ClientExportOptions,
+) -> Result<export::ExportEstimate, String> {
+    vault.get_client(client_id).map_err(|e| format!("{}", e))?;
+    let scope = vault.get_client_export_scope(client_id, options).map_err(|e| format!("{}", e))?;
+    
+    let mut blockers = Vec::new();
+    let mut warnings = Vec::new();
+    {
+        let engine = policy_state.engine.read().map_err(|e| e.to_string())?;
+        let policy = engine.get_policy();
+        let export_policy = &policy.export_policy;
+        
+        if !export_policy.allowed_formats.iter().any(|f| f.eq_ignore_ascii_case(&options.format)) {
+            blockers.push(format!("Format '{}' is not allowed by organization policy", options.format));
+        }
+        if let Some(limit) = export_policy.audit_pack_required_above {
+            if scope.note_count > limit as i64 && !options.include_audit_trail {
+                blockers.push(format!("Exports of more than {} notes must include the audit trail", limit));
+            }
+        }
+        if !vault.chart_access_allowed(client_id, &policy.access_policy).map_err(|e| format!("{}", e))? {
+            blockers.push(format!("{}: state a reason for access to this restricted chart", ACCESS_REASON_REQUIRED));
+        }
+        
+        if let Some(destination) = &options.destination {
+            if export_policy.blocked_paths.iter().any(|p| destination.starts_with(p.as_str())) {
+                blockers.push("Destination is on the organization's blocked path list".to_string());
+            } else {
+                let path = std::path::Path::new(destination);
+                let destination_class = match export::classify_path(path).classification {
+                    PathClassification::Safe => "safe",
+                    PathClassification::CloudSync => "cloud_sync",
+                    PathClassification::NetworkShare => "network_share",
+                    PathClassification::RemovableMedia => "removable",
+                    PathClassification::Unknown => "unknown",
+                };
+                match engine.check_export(destination_class) {
+                    crate::policy::PolicyDecision::Allow => {}
+                    crate::policy::PolicyDecision::Warn { message } => warnings.push(message),
+                    crate::policy::PolicyDecision::Block { reason } => blockers.push(reason),
+                    crate::policy::PolicyDecision::RequireApproval { approver } => {
+                        blockers.push(format!("Destination requires approval by {}", approver))
+                    }
+                }
+            }
+        } else {
+            warnings.push("No destination chosen yet; destination policy not checked".to_string());
+        }
+    }
+    
+    if scope.draft_note_count > 0 {
+        warnings.push(format!("{} unsigned draft note(s) will be included", scope.draft_note_count));
+    }
+    if scope.note_count + scope.document_count == 0 {
+        warnings.push("Nothing to export for the selected range".to_string());
+    }
+    
+    let audit_bytes = scope.audit_event_count * export::AUDIT_ENTRY_BYTES;
+    let total_bytes = scope.note_bytes + scope.document_bytes + audit_bytes;
+    
+    Ok(export::ExportEstimate {
+        client_id: client_id.to_string(),
+        note_count: scope.note_count,
+        draft_note_count: scope.draft_note_count,
+        document_count: scope.document_count,
+        audit_event_count: scope.audit_event_count,
+        note_bytes: scope.note_bytes,
+        document_bytes: scope.document_bytes,
+        total_bytes,
+        estimated_seconds: export::estimate_export_seconds(scope.note_count, total_bytes, &options.format.to_lowercase()),
+        queued_tasks,
+        blockers,
+        warnings,
+    })
+}
+
+/// Audit a completed export. When the destination was allowed by user
+/// override and is removable or cloud-synced, `check_after_days` schedules
+/// a later check on whether the file is still there.
+#[tauri::command]
+pub fn record_export_destination(
+    state: State<AppState>,
+    resource_id: String,
+    path: String,
+    user_override: bool,
+    check_after_days: Option<u32>,
+) -> Result<Option<ExportPresenceCheck>, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    let path_class = path_class_name(std::path::Path::new(&path));
+    vault.record_export_destination(&resource_id, &path, path_class, user_override, check_after_days)
+        .map_err(|e| format!("{}", e))
+}
+
+/// The path class recorded in export audit entries
+pub(crate) fn path_class_name(path: &std::path::Path) -> &'static str {
+    match export::classify_path(path).classification {
+        PathClassification::Safe => "safe",
+        PathClassification::CloudSync => "cloud_sync",
+        PathClassification::NetworkShare => "network_share",
+        PathClassification::RemovableMedia => "removable",
+        PathClassification::Unknown => "unknown",
+    }
+}
+
+/// Run presence checks that are due now
+#[tauri::command]
+pub fn run_export_presence_checks(state: State<AppState>) -> Result<Vec<ExportPresenceCheck>, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.run_due_presence_checks().map_err(|e| format!("{}", e))
+}
+
+#[tauri::command]
+pub fn list_export_presence_checks(state: State<AppState>) -> Result<Vec<ExportPresenceCheck>, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.list_export_presence_checks().map_err(|e| format!("{}", e))
+}
+
+// ============================================
+// Voice Commands
+// ============================================
+
+/// Whisper manifest annotated for this machine (installed, RAM fit, expected
+/// speed) with a hardware-based recommendation
+#[tauri::command]
+pub fn list_whisper_models(english_only: Option<bool>) -> voice::WhisperModelCatalog {
+    voice::build_model_catalog(
+        &voice::get_models_directory(),
+        voice::probe_hardware(),
+        english_only.unwrap_or(true),
+    )
+}
+
+#[tauri::command]
+pub fn get_transcript_text(state: State<AppState>) -> String {
+    // In production, we'd have voice state in AppState
+    // For now, return placeholder
+    "Transcript text would appear here during recording.".to_string()
+}
+
+/// Transcribe audio buffer and return segments. `preprocessing` enables
+/// denoise/loudness cleanup for this recording (off when omitted).
+#[tauri::command]
+pub fn transcribe_audio(
+    audio_data: Vec<f32>,
+    sample_rate: u32,
+    preprocessing: Option<crate::audio_preprocess::PreprocessOptions>,
+) -> Result<Vec<voice::TranscriptSegment>, String> {
+    // Resample to 16kHz if needed
+    let audio = if sample_rate != 16000 {
         voice::resample_to_16k(&audio_data, sample_rate)
     } else {
         audio_data
     };
+    let audio = match preprocessing {
+        Some(options) => crate::audio_preprocess::preprocess(&audio, 16000, &options),
+        None => audio,
+    };
     
     // In production, use WhisperContext
     // For now, return placeholder
@@ -772,6 +1505,7 @@ pub async fn download_whisper_model(model_name: String) -> Result<String, String
 pub async fn transcribe_audio_base64(
     audio_data: String,
     format: String,
+    preprocessing: Option<crate::audio_preprocess::PreprocessOptions>,
 ) -> Result<String, String> {
     use std::io::Write;
     
@@ -810,6 +1544,13 @@ pub async fn transcribe_audio_base64(
         return Err(format!("ffmpeg conversion failed: {}", String::from_utf8_lossy(&ffmpeg_output.stderr)));
     }
     
+    if let Some(options) = preprocessing {
+        if let Err(e) = crate::audio_preprocess::preprocess_wav(&wav_path, &wav_path, &options) {
+            let _ = std::fs::remove_file(&wav_path);
+            return Err(format!("Audio preprocessing failed: {}", e));
+        }
+    }
+    
     // Find whisper command
     let whisper_cmd = ["whisper-cpp", "whisper", "main"]
         .iter()
@@ -945,7 +1686,8 @@ pub fn index_note_for_search(
     let content = note.structured_note.as_ref()
         .unwrap_or(&note.raw_input);
     
-    rag::index_note(conn, &note_id, content)
+    let keys = vault.vector_keyring().map_err(|e| format!("{e}"))?;
+    rag::index_note(conn, &keys, &note_id, content)
         .map_err(|e| format!("{e}"))
 }
 
@@ -959,8 +1701,13 @@ pub fn search_notes_semantic(
     let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned".to_string())?;
     let conn = vault.get_connection().map_err(|e| format!("{e}"))?;
     
-    rag::search_similar(conn, &query, limit, client_id.as_deref())
-        .map_err(|e| format!("{e}"))
+    let keys = vault.vector_keyring().map_err(|e| format!("{e}"))?;
+    let results = rag::search_similar(conn, &keys, &query, limit, client_id.as_deref())
+        .map_err(|e| format!("{e}"))?;
+    let note_ids: Vec<&str> = results.iter().map(|r| r.note_id.as_str()).collect();
+    crate::access_history::log_retrieval(conn, "semantic_search", &note_ids)
+        .map_err(|e| format!("Audit write failed: {e}"))?;
+    Ok(results)
 }
 
 #[tauri::command]
@@ -977,8 +1724,13 @@ pub async fn rag_query_notes(
     
     // For now, run synchronously - RAG queries are quick enough
     // A proper fix would involve connection pooling or async-safe DB access
-    rag::rag_query_sync(conn, &question, client_id.as_deref(), &model)
-        .map_err(|e| format!("{e}"))
+    let keys = vault.vector_keyring().map_err(|e| format!("{e}"))?;
+    let answer = rag::rag_query_sync(conn, &keys, &question, client_id.as_deref(), &model)
+        .map_err(|e| format!("{e}"))?;
+    let note_ids: Vec<&str> = answer.sources.iter().map(|s| s.note_id.as_str()).collect();
+    crate::access_history::log_retrieval(conn, "rag_query", &note_ids)
+        .map_err(|e| format!("Audit write failed: {e}"))?;
+    Ok(answer)
 }
 
 #[tauri::command]
@@ -994,19 +1746,16 @@ pub fn reindex_all_notes_for_search(state: State<AppState>) -> Result<usize, Str
     let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned".to_string())?;
     let conn = vault.get_connection().map_err(|e| format!("{e}"))?;
     
-    rag::reindex_all_notes(conn).map_err(|e| format!("{e}"))
+    let keys = vault.vector_keyring().map_err(|e| format!("{e}"))?;
+    rag::reindex_all_notes(conn, &keys).map_err(|e| format!("{e}"))
 }
 
 // ============================================
 // Attestation Commands
 // ============================================
 
-#[tauri::command]
-pub fn get_quick_picks(
-    category: String,
-    severity: String,
-) -> Vec<attestation::QuickPickOption> {
-    let cat = match category.to_lowercase().as_str() {
+fn parse_detection_category(category: &str) -> ethics::Category {
+    match category.to_lowercase().as_str() {
         "suicidalideation" | "suicidal_ideation" => ethics::Category::SuicidalIdeation,
         "homicidalideation" | "homicidal_ideation" => ethics::Category::HomicidalIdeation,
         "selfharm" | "self_harm" => ethics::Category::SelfHarm,
@@ -1018,7 +1767,21 @@ pub fn get_quick_picks(
         "billing" => ethics::Category::Billing,
         "integrity" => ethics::Category::Documentation, // map integrity to documentation
         _ => ethics::Category::ClinicalRisk,
-    };
+    }
+}
+
+fn quick_pick_templates(policy_state: &crate::policy::PolicyState) -> Result<Vec<attestation::QuickPickTemplate>, String> {
+    let engine = policy_state.engine.read().map_err(|e| e.to_string())?;
+    Ok(engine.get_policy().attestation_policy.quick_pick_templates.clone())
+}
+
+#[tauri::command]
+pub fn get_quick_picks(
+    policy_state: State<'_, crate::policy::PolicyState>,
+    category: String,
+    severity: String,
+) -> Result<Vec<attestation::QuickPickOption>, String> {
+    let cat = parse_detection_category(&category);
     
     // Handle both Severity (Critical/High/Medium/Low) and DetectionSeverity (attest/flag/coach)
     let sev = match severity.to_lowercase().as_str() {
@@ -1029,7 +1792,24 @@ pub fn get_quick_picks(
         _ => ethics::Severity::Low,
     };
     
-    attestation::get_quick_picks(&cat, &sev)
+    let templates = quick_pick_templates(&policy_state)?;
+    Ok(attestation::get_quick_picks_with_templates(&cat, &sev, &templates))
+}
+
+/// Validate fill-ins for a templated quick pick and return the response
+/// note text. Field errors come back as JSON so the form can mark each one.
+#[tauri::command]
+pub fn render_quick_pick_template(
+    policy_state: State<'_, crate::policy::PolicyState>,
+    template_id: String,
+    values: HashMap<String, String>,
+) -> Result<String, String> {
+    let template = quick_pick_templates(&policy_state)?
+        .into_iter()
+        .find(|t| t.id == template_id)
+        .ok_or_else(|| format!("Unknown quick pick template: {}", template_id))?;
+    attestation::render_quick_pick(&template, &values, chrono::Local::now().date_naive())
+        .map_err(|errors| serde_json::to_string(&errors).unwrap_or_default())
 }
 
 #[tauri::command]
@@ -1046,6 +1826,7 @@ pub fn consolidate_detections(
 
 #[tauri::command]
 pub fn validate_attestation(
+    policy_state: State<'_, crate::policy::PolicyState>,
     detection_json: String,
     response: String,
     response_note: Option<String>,
@@ -1057,7 +1838,8 @@ pub fn validate_attestation(
     
     let resp = parse_attestation_response(&response)?;
     
-    attestation::validate_attestation(&detection, &resp, &response_note)
+    let templates = quick_pick_templates(&policy_state)?;
+    attestation::validate_attestation(&detection, &resp, &response_note, &templates, chrono::Local::now().date_naive())
 }
 
 #[tauri::command]
@@ -1121,35 +1903,77 @@ pub fn record_session_metrics(
     metrics::record_session(conn, &metrics).map_err(|e| format!("{e}"))
 }
 
+/// Usage metrics (no PHI) from the reporting snapshot, so the scan doesn't
+/// hold the vault; cached until the snapshot is replaced
+fn dashboard_metrics(
+    state: &AppState,
+    replica_state: &crate::replica::ReplicaState,
+    perf_state: &crate::performance::PerformanceState,
+    days: i32,
+) -> Result<metrics::DashboardMetrics, String> {
+    let days_param = days.to_string();
+    replica_state.with_cached_replica(state, perf_state, "dashboard_metrics", &[&days_param], |replica| {
+        Ok(metrics::calculate_dashboard_metrics(replica.get_connection()?, days)?)
+    })
+}
+
 #[tauri::command]
 pub fn get_dashboard_metrics(
     state: State<AppState>,
+    replica_state: State<'_, crate::replica::ReplicaState>,
+    perf_state: State<'_, crate::performance::PerformanceState>,
     days: i32,
 ) -> Result<metrics::DashboardMetrics, String> {
-    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned".to_string())?;
-    let conn = vault.get_connection().map_err(|e| format!("{e}"))?;
-    
-    // Ensure metrics table exists
-    metrics::init_metrics_table(conn).map_err(|e| format!("{e}"))?;
-    
-    metrics::calculate_dashboard_metrics(conn, days).map_err(|e| format!("{e}"))
+    dashboard_metrics(&state, &replica_state, &perf_state, days)
 }
 
 #[tauri::command]
 pub fn get_metrics_report(
     state: State<AppState>,
+    replica_state: State<'_, crate::replica::ReplicaState>,
+    perf_state: State<'_, crate::performance::PerformanceState>,
     days: i32,
 ) -> Result<metrics::MetricsReport, String> {
-    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned".to_string())?;
-    let conn = vault.get_connection().map_err(|e| format!("{e}"))?;
+    let dashboard = dashboard_metrics(&state, &replica_state, &perf_state, days)?;
     
-    // Ensure metrics table exists
-    metrics::init_metrics_table(conn).map_err(|e| format!("{e}"))?;
+    Ok(metrics::generate_report(&dashboard, days))
+}
+
+/// Private wellness panel: after-hours / weekend documentation load.
+/// Computed on demand from local session timestamps; nothing is stored or logged.
+#[tauri::command]
+pub fn get_wellness_indicators(
+    state: State<AppState>,
+    days: Option<i64>,
+    work_hours: Option<metrics::WorkHours>,
+) -> Result<metrics::WellnessIndicators, String> {
+    let days = days.unwrap_or(28).clamp(7, 365);
+    let hours = work_hours.unwrap_or_default();
+    if hours.start_hour >= hours.end_hour || hours.end_hour > 24 {
+        return Err("Work hours must satisfy 0 <= start < end <= 24".to_string());
+    }
     
-    let dashboard = metrics::calculate_dashboard_metrics(conn, days)
-        .map_err(|e| format!("{e}"))?;
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned".to_string())?;
+    let since = (chrono::Utc::now() - chrono::Duration::days(days)).timestamp();
+    let sessions: Vec<(i64, i64)> = vault.get_session_metrics(since)
+        .map_err(|e| format!("{e}"))?
+        .iter()
+        .map(|m| (m.start_time, m.end_time))
+        .collect();
     
-    Ok(metrics::generate_report(&dashboard, days))
+    let today = chrono::Local::now().date_naive();
+    Ok(metrics::compute_wellness(&sessions, &hours, &chrono::Local, today, days))
+}
+
+/// Per-client documentation completeness: signing, attestation, and
+/// treatment plan / consent / assessment currency
+#[tauri::command]
+pub fn get_client_documentation_scorecard(
+    state: State<AppState>,
+    client_id: String,
+) -> Result<metrics::DocumentationScorecard, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.get_client_documentation_scorecard(&client_id).map_err(|e| format!("{}", e))
 }
 
 // ============================================
@@ -1258,6 +2082,50 @@ pub fn generate_hypotheses(
     Ok(analysis::generate_hypotheses(&store))
 }
 
+#[tauri::command]
+pub fn record_missed_session(
+    store_json: String,
+    session_date: String,
+) -> Result<analysis::PatientFeatureStore, String> {
+    let mut store: analysis::PatientFeatureStore = serde_json::from_str(&store_json)
+        .map_err(|e| format!("{e}"))?;
+    
+    store.record_missed_session(&session_date);
+    Ok(store)
+}
+
+/// Early-warning alerts for one client under the active policy's rules
+#[tauri::command]
+pub fn evaluate_early_warnings(
+    policy_state: State<'_, crate::policy::PolicyState>,
+    store_json: String,
+) -> Result<Vec<analysis::EarlyWarningAlert>, String> {
+    let store: analysis::PatientFeatureStore = serde_json::from_str(&store_json)
+        .map_err(|e| format!("{e}"))?;
+    let engine = policy_state.engine.read().map_err(|e| e.to_string())?;
+    
+    Ok(analysis::evaluate_early_warnings(&store, &engine.get_policy().early_warning_policy))
+}
+
+/// Dashboard alert list across the caseload, most severe first
+#[tauri::command]
+pub fn get_caseload_early_warnings(
+    policy_state: State<'_, crate::policy::PolicyState>,
+    stores_json: Vec<String>,
+) -> Result<Vec<analysis::EarlyWarningAlert>, String> {
+    let engine = policy_state.engine.read().map_err(|e| e.to_string())?;
+    let policy = &engine.get_policy().early_warning_policy;
+    
+    let mut alerts = Vec::new();
+    for store_json in &stores_json {
+        let store: analysis::PatientFeatureStore = serde_json::from_str(store_json)
+            .map_err(|e| format!("{e}"))?;
+        alerts.extend(analysis::evaluate_early_warnings(&store, policy));
+    }
+    alerts.sort_by(|a, b| b.severity.cmp(&a.severity));
+    Ok(alerts)
+}
+
 // ============================================
 // Cross-Client Search
 // ============================================
@@ -1271,6 +2139,41 @@ pub fn search_clients(
     vault.search_clients(&query).map_err(|e| format!("{}", e))
 }
 
+/// Global search bar: clients, notes, document OCR text and consultation drafts in one ranked list.
+/// The query itself is never written to the audit log.
+#[tauri::command]
+pub fn search_everything(
+    state: State<AppState>,
+    policy_state: State<'_, crate::policy::PolicyState>,
+    query: String,
+    limit: Option<usize>,
+) -> Result<Vec<crate::models::GlobalSearchResult>, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    let results = vault.search_everything(&query, limit.unwrap_or(50)).map_err(|e| format!("{}", e))?;
+    let results = retain_accessible_charts(&vault, &policy_state, results, |r| r.client_id.as_deref())?;
+    
+    let conn = vault.get_connection().map_err(|e| format!("{}", e))?;
+    let _ = audit::log_event(
+        conn,
+        AuditEventType::SearchExecuted,
+        AuditResourceType::Vault,
+        "global_search",
+        AuditOutcome::Success,
+        None,
+    );
+    let client_ids: Vec<&str> = results.iter().filter_map(|r| r.client_id.as_deref()).collect();
+    crate::access_history::log_client_retrieval(conn, "global_search", &client_ids)
+        .map_err(|e| format!("Audit write failed: {}", e))?;
+    // Note excerpts are note content shown to the user
+    let note_ids: Vec<&str> = results.iter()
+        .filter(|r| r.entity_type == crate::models::SearchEntityType::Note)
+        .map(|r| r.entity_id.as_str())
+        .collect();
+    log_note_retrieval(&vault, "global_search", &note_ids)?;
+    
+    Ok(results)
+}
+
 #[tauri::command]
 pub fn get_client_last_visit(
     state: State<AppState>,
@@ -1299,9 +2202,10 @@ pub fn update_note_content(
     state: State<AppState>,
     note_id: String,
     content: String,
+    editor: Option<String>,
 ) -> Result<crate::models::Note, String> {
     let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
-    vault.update_note(&note_id, &content).map_err(|e| format!("{}", e))
+    vault.update_note(&note_id, &content, editor.as_deref()).map_err(|e| format!("{}", e))
 }
 
 #[tauri::command]
@@ -1310,9 +2214,72 @@ pub fn amend_note(
     note_id: String,
     amendment_text: String,
     reason: String,
+    editor: Option<String>,
 ) -> Result<crate::models::Note, String> {
     let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
-    vault.amend_note(&note_id, &amendment_text, &reason).map_err(|e| format!("{}", e))
+    vault.amend_note(&note_id, &amendment_text, &reason, editor.as_deref()).map_err(|e| format!("{}", e))
+}
+
+// ============================================
+// Trash
+// ============================================
+
+#[tauri::command]
+pub fn trash_note(state: State<AppState>, note_id: String) -> Result<(), String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.trash_note(&note_id).map_err(|e| format!("{}", e))
+}
+
+#[tauri::command]
+pub fn restore_note(state: State<AppState>, note_id: String) -> Result<crate::models::Note, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.restore_note(&note_id).map_err(|e| format!("{}", e))
+}
+
+#[tauri::command]
+pub fn purge_note(
+    state: State<AppState>,
+    replica_state: State<'_, crate::replica::ReplicaState>,
+    note_id: String,
+) -> Result<(), String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.purge_note(&note_id).map_err(|e| format!("{}", e))?;
+    // The reporting snapshot still holds the purged records
+    drop(vault);
+    replica_state.clear();
+    Ok(())
+}
+
+#[tauri::command]
+pub fn trash_client(state: State<AppState>, client_id: String) -> Result<(), String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.trash_client(&client_id).map_err(|e| format!("{}", e))
+}
+
+#[tauri::command]
+pub fn restore_client(state: State<AppState>, client_id: String) -> Result<crate::models::Client, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.restore_client(&client_id).map_err(|e| format!("{}", e))
+}
+
+#[tauri::command]
+pub fn purge_client(
+    state: State<AppState>,
+    replica_state: State<'_, crate::replica::ReplicaState>,
+    client_id: String,
+) -> Result<(), String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.purge_client(&client_id).map_err(|e| format!("{}", e))?;
+    // The reporting snapshot still holds the purged records
+    drop(vault);
+    replica_state.clear();
+    Ok(())
+}
+
+#[tauri::command]
+pub fn list_trash(state: State<AppState>) -> Result<Vec<crate::models::TrashedItem>, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.list_trash().map_err(|e| format!("{}", e))
 }
 
 // ============================================
@@ -1328,6 +2295,100 @@ pub fn get_treatment_progress(
     vault.get_treatment_progress(&client_id).map_err(|e| format!("{}", e))
 }
 
+// ============================================
+// Mental Status Exam
+// ============================================
+
+#[tauri::command]
+pub fn record_note_mse(
+    state: State<AppState>,
+    note_id: String,
+    exam: crate::mse::MentalStatusExam,
+) -> Result<crate::mse::NoteMse, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.record_note_mse(&note_id, &exam).map_err(|e| format!("{}", e))
+}
+
+#[tauri::command]
+pub fn update_note_mse(
+    state: State<AppState>,
+    note_id: String,
+    exam: crate::mse::MentalStatusExam,
+) -> Result<crate::mse::NoteMse, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.update_note_mse(&note_id, &exam).map_err(|e| format!("{}", e))
+}
+
+#[tauri::command]
+pub fn get_note_mse(
+    state: State<AppState>,
+    note_id: String,
+) -> Result<Option<crate::mse::NoteMse>, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.get_note_mse(&note_id).map_err(|e| format!("{}", e))
+}
+
+/// MSE severity trend for the treatment-progress view
+#[tauri::command]
+pub fn get_mse_trend(
+    state: State<AppState>,
+    client_id: String,
+) -> Result<crate::mse::MseTrend, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    let history = vault.get_client_mse_history(&client_id).map_err(|e| format!("{}", e))?;
+    Ok(crate::mse::build_trend(&client_id, &history))
+}
+
+// ============================================
+// Note Type Registry
+// ============================================
+
+/// Built-in note types followed by agency-defined ones
+#[tauri::command]
+pub fn list_note_types(
+    state: State<AppState>,
+) -> Result<Vec<crate::note_types::NoteTypeDefinition>, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    let mut types = crate::note_types::builtin_definitions();
+    types.extend(vault.list_note_type_definitions().map_err(|e| format!("{}", e))?);
+    Ok(types)
+}
+
+#[tauri::command]
+pub fn save_note_type(
+    state: State<AppState>,
+    definition: crate::note_types::NoteTypeDefinition,
+) -> Result<crate::note_types::NoteTypeDefinition, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.save_note_type_definition(&definition).map_err(|e| format!("{}", e))
+}
+
+#[tauri::command]
+pub fn delete_note_type(state: State<AppState>, name: String) -> Result<(), String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.delete_note_type_definition(&name).map_err(|e| format!("{}", e))
+}
+
+/// Definition a note was written against (built-in template if it has no custom type)
+#[tauri::command]
+pub fn get_note_type_for_note(
+    state: State<AppState>,
+    note_id: String,
+) -> Result<crate::note_types::NoteTypeDefinition, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    if let Some(name) = vault.get_note_type_name(&note_id).map_err(|e| format!("{}", e))? {
+        if let Some(def) = vault.get_note_type_definition(&name).map_err(|e| format!("{}", e))? {
+            return Ok(def);
+        }
+    }
+    
+    let note = vault.get_note(&note_id).map_err(|e| format!("{}", e))?;
+    crate::note_types::builtin_definitions()
+        .into_iter()
+        .find(|d| d.base_type == note.note_type)
+        .ok_or_else(|| "Unknown note type".to_string())
+}
+
 // ============================================
 // Document Management
 // ============================================
@@ -1356,39 +2417,230 @@ pub fn upload_document(
 }
 
 #[tauri::command]
-pub fn list_documents(
+pub fn list_documents(
+    state: State<AppState>,
+    client_id: String,
+) -> Result<Vec<crate::vault::ClientDocument>, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.list_documents(&client_id).map_err(|e| format!("{}", e))
+}
+
+#[tauri::command]
+pub fn get_document_data(
+    state: State<AppState>,
+    policy_state: State<'_, crate::policy::PolicyState>,
+    document_id: String,
+) -> Result<Vec<u8>, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    let client_id = vault.get_document_client_id(&document_id).map_err(|e| format!("{}", e))?;
+    ensure_chart_access(&vault, &policy_state, &client_id)?;
+    vault.get_document_data(&document_id).map_err(|e| format!("{}", e))
+}
+
+#[tauri::command]
+pub fn delete_document(
+    state: State<AppState>,
+    document_id: String,
+) -> Result<(), String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.delete_document(&document_id).map_err(|e| format!("{}", e))
+}
+
+// ============================================
+// Support Lookup
+// ============================================
+
+/// Salt support tooling uses to compute lookup hashes
+#[tauri::command]
+pub fn get_client_lookup_salt(state: State<AppState>) -> Result<String, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.client_lookup_salt().map_err(|e| format!("{}", e))
+}
+
+/// "Is this client in the vault?" from a salted hash, without reading PHI
+#[tauri::command]
+pub fn lookup_client_by_hash(
+    state: State<AppState>,
+    kind: crate::models::ClientIdentifierKind,
+    identifier_hash: String,
+) -> Result<crate::models::ClientHashLookupResult, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.lookup_client_by_hash(kind, &identifier_hash).map_err(|e| format!("{}", e))
+}
+
+// ============================================
+// Licensure
+// ============================================
+
+#[tauri::command]
+pub fn add_clinician_license(
+    state: State<AppState>,
+    kind: crate::licensure::LicenseKind,
+    jurisdiction: Option<String>,
+    license_number: String,
+    expires_on: Option<String>,
+) -> Result<crate::licensure::ClinicianLicense, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.add_clinician_license(kind, jurisdiction.as_deref(), &license_number, expires_on.as_deref())
+        .map_err(|e| format!("{}", e))
+}
+
+#[tauri::command]
+pub fn list_clinician_licenses(
+    state: State<AppState>,
+) -> Result<Vec<crate::licensure::ClinicianLicense>, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.list_clinician_licenses().map_err(|e| format!("{}", e))
+}
+
+#[tauri::command]
+pub fn remove_clinician_license(state: State<AppState>, id: String) -> Result<bool, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.remove_clinician_license(&id).map_err(|e| format!("{}", e))
+}
+
+/// Record where a telehealth patient was located and check licensure
+#[tauri::command]
+pub fn record_telehealth_location(
+    state: State<AppState>,
+    policy_state: State<'_, crate::policy::PolicyState>,
+    note_id: String,
+    patient_location: String,
+) -> Result<crate::licensure::LicensureCheck, String> {
+    let policy = {
+        let engine = policy_state.engine.read().map_err(|e| e.to_string())?;
+        engine.get_policy().licensure_policy.clone()
+    };
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.record_telehealth_location(&note_id, &patient_location, &policy)
+        .map_err(|e| format!("{}", e))
+}
+
+#[tauri::command]
+pub fn attest_telehealth_jurisdiction(
+    state: State<AppState>,
+    note_id: String,
+    statement: String,
+) -> Result<crate::licensure::LicensureCheck, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.attest_licensure_check(&note_id, &statement).map_err(|e| format!("{}", e))
+}
+
+#[tauri::command]
+pub fn get_licensure_check(
+    state: State<AppState>,
+    note_id: String,
+) -> Result<Option<crate::licensure::LicensureCheck>, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.get_licensure_check(&note_id).map_err(|e| format!("{}", e))
+}
+
+// ============================================
+// Practice Profile
+// ============================================
+
+#[tauri::command]
+pub fn get_practice_profile(state: State<AppState>) -> Result<Option<crate::branding::PracticeProfile>, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.get_practice_profile().map_err(|e| format!("{}", e))
+}
+
+#[tauri::command]
+pub fn set_practice_profile(
+    state: State<AppState>,
+    profile: crate::branding::PracticeProfile,
+) -> Result<crate::branding::PracticeProfile, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.set_practice_profile(&profile).map_err(|e| format!("{}", e))
+}
+
+#[tauri::command]
+pub fn set_practice_logo(state: State<AppState>, data: Vec<u8>) -> Result<(), String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.set_practice_logo(&data).map_err(|e| format!("{}", e))
+}
+
+/// Stored logo (JPEG) for the settings preview
+#[tauri::command]
+pub fn get_practice_logo(state: State<AppState>) -> Result<Option<Vec<u8>>, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    let logo = vault.get_practice_logo().map_err(|e| format!("{}", e))?;
+    Ok(logo.map(|l| l.jpeg))
+}
+
+#[tauri::command]
+pub fn remove_practice_logo(state: State<AppState>) -> Result<bool, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.remove_practice_logo().map_err(|e| format!("{}", e))
+}
+
+// ============================================
+// Client Photo
+// ============================================
+
+#[tauri::command]
+pub fn set_client_photo(
+    state: State<AppState>,
+    client_id: String,
+    data: Vec<u8>,
+) -> Result<crate::photo::ClientPhoto, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    let photo = vault.set_client_photo(&client_id, &data).map_err(|e| format!("{}", e))?;
+    
+    if let Ok(conn) = vault.get_connection() {
+        audit::log_event(conn, AuditEventType::ClientUpdated, AuditResourceType::Client, &client_id, AuditOutcome::Success, None).ok();
+    }
+    Ok(photo)
+}
+
+#[tauri::command]
+pub fn get_client_photo_info(
     state: State<AppState>,
     client_id: String,
-) -> Result<Vec<crate::vault::ClientDocument>, String> {
+) -> Result<Option<crate::photo::ClientPhoto>, String> {
     let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
-    vault.list_documents(&client_id).map_err(|e| format!("{}", e))
+    vault.get_client_photo_info(&client_id).map_err(|e| format!("{}", e))
 }
 
+/// Original photo for the waiting-room view; never use for exports
 #[tauri::command]
-pub fn get_document_data(
+pub fn get_client_photo(
     state: State<AppState>,
-    document_id: String,
-) -> Result<Vec<u8>, String> {
+    policy_state: State<'_, crate::policy::PolicyState>,
+    client_id: String,
+) -> Result<Option<Vec<u8>>, String> {
     let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
-    vault.get_document_data(&document_id).map_err(|e| format!("{}", e))
+    ensure_chart_access(&vault, &policy_state, &client_id)?;
+    vault.get_client_photo_for_display(&client_id).map_err(|e| format!("{}", e))
 }
 
+/// Photo for inclusion in an export: omitted (default) or blurred
 #[tauri::command]
-pub fn delete_document(
+pub fn get_client_photo_for_export(
     state: State<AppState>,
-    document_id: String,
-) -> Result<(), String> {
+    client_id: String,
+    mode: Option<crate::photo::PhotoExportMode>,
+) -> Result<Option<Vec<u8>>, String> {
     let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
-    vault.delete_document(&document_id).map_err(|e| format!("{}", e))
+    vault.get_client_photo_for_export(&client_id, mode.unwrap_or_default())
+        .map_err(|e| format!("{}", e))
+}
+
+#[tauri::command]
+pub fn remove_client_photo(state: State<AppState>, client_id: String) -> Result<bool, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.remove_client_photo(&client_id).map_err(|e| format!("{}", e))
 }
 
 #[tauri::command]
 pub fn search_documents(
     state: State<AppState>,
+    policy_state: State<'_, crate::policy::PolicyState>,
     query: String,
 ) -> Result<Vec<crate::vault::ClientDocument>, String> {
     let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
-    vault.search_documents(&query).map_err(|e| format!("{}", e))
+    let documents = vault.search_documents(&query).map_err(|e| format!("{}", e))?;
+    retain_accessible_charts(&vault, &policy_state, documents, |d| Some(d.client_id.as_str()))
 }
 
 #[tauri::command]
@@ -1413,6 +2665,15 @@ pub fn get_storage_stats(
     vault.get_storage_stats().map_err(|e| format!("{}", e))
 }
 
+/// Client/note/document/review counts for the dashboard in a single call
+#[tauri::command]
+pub fn get_dashboard_snapshot(
+    state: State<AppState>,
+) -> Result<crate::vault::DashboardSnapshot, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.get_dashboard_snapshot().map_err(|e| format!("{}", e))
+}
+
 #[tauri::command]
 pub fn optimize_database(
     state: State<AppState>,
@@ -1425,65 +2686,40 @@ pub fn optimize_database(
 // OCR Processing
 // ============================================
 
-/// Run OCR on a document and update its searchable text
+/// Run OCR on a document and update its searchable text. `languages` are
+/// Tesseract codes (omit or ["auto"] to detect); `profile` selects the
+/// printed (default) or handwriting profile.
 #[tauri::command]
 pub async fn process_document_ocr(
     state: State<'_, AppState>,
     document_id: String,
-) -> Result<String, String> {
+    languages: Option<Vec<String>>,
+    profile: Option<crate::ocr::OcrProfile>,
+) -> Result<crate::ocr::OcrResult, String> {
+    let languages = crate::ocr::parse_languages(languages.as_deref())?;
+    
     // Get document data
     let data = {
         let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
         vault.get_document_data(&document_id).map_err(|e| format!("{}", e))?
     };
     
-    // Write to temp file
-    let temp_dir = std::env::temp_dir();
-    let temp_path = temp_dir.join(format!("evidify_ocr_{}.tmp", uuid::Uuid::new_v4()));
-    
-    std::fs::write(&temp_path, &data)
-        .map_err(|e| format!("Failed to write temp file: {}", e))?;
-    
-    // Run tesseract OCR
-    let output_base = temp_dir.join(format!("evidify_ocr_out_{}", uuid::Uuid::new_v4()));
-    
-    let result = std::process::Command::new("tesseract")
-        .args([
-            temp_path.to_str().unwrap(),
-            output_base.to_str().unwrap(),
-            "-l", "eng",  // English
-            "--psm", "1", // Automatic page segmentation with OSD
-        ])
-        .output();
-    
-    // Clean up input temp file
-    let _ = std::fs::remove_file(&temp_path);
-    
-    let output = result.map_err(|e| {
-        format!("Tesseract not installed or failed: {}. Install with: brew install tesseract", e)
-    })?;
-    
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("OCR failed: {}", stderr));
-    }
-    
-    // Read output text file
-    let output_txt = output_base.with_extension("txt");
-    let ocr_text = std::fs::read_to_string(&output_txt)
-        .map_err(|e| format!("Failed to read OCR output: {}", e))?;
-    
-    // Clean up output file
-    let _ = std::fs::remove_file(&output_txt);
+    let result = crate::ocr::recognize(&data, languages, profile.unwrap_or_default())?;
     
-    // Update document with OCR text
+    // Update document with OCR text and confidence
     {
         let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
-        vault.update_document_ocr(&document_id, &ocr_text)
+        vault.record_document_ocr(&document_id, &result)
             .map_err(|e| format!("{}", e))?;
     }
     
-    Ok(format!("OCR complete: {} characters extracted", ocr_text.len()))
+    Ok(result)
+}
+
+/// Tesseract language packs available for OCR
+#[tauri::command]
+pub fn list_ocr_languages() -> Vec<String> {
+    crate::ocr::installed_languages()
 }
 
 /// Check if OCR (Tesseract) is available
@@ -1500,13 +2736,47 @@ pub fn check_ocr_available() -> bool {
 // Pre-Session Prep Sheet
 // ============================================
 
+/// Prep sheet for a client. With the client's feature store, early-warning
+/// alerts are added as safety alerts and focus suggestions.
 #[tauri::command]
 pub fn generate_prep_sheet(
     state: State<AppState>,
+    policy_state: State<'_, crate::policy::PolicyState>,
     client_id: String,
+    store_json: Option<String>,
 ) -> Result<crate::models::PrepSheet, String> {
+    let mut sheet = {
+        let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+        vault.generate_prep_sheet(&client_id).map_err(|e| format!("{}", e))?
+    };
+    
+    if let Some(store_json) = store_json {
+        let store: analysis::PatientFeatureStore = serde_json::from_str(&store_json)
+            .map_err(|e| format!("{e}"))?;
+        let engine = policy_state.engine.read().map_err(|e| e.to_string())?;
+        let alerts = analysis::evaluate_early_warnings(&store, &engine.get_policy().early_warning_policy);
+        if !alerts.is_empty() {
+            sheet.focus_suggestions.retain(|s| s != "Continue current treatment approach");
+        }
+        for (i, alert) in alerts.iter().enumerate() {
+            sheet.safety_alerts.push(alert.to_safety_alert());
+            if let Some(action) = &alert.suggested_action {
+                sheet.focus_suggestions.insert(i.min(sheet.focus_suggestions.len()), format!("{} ({})", action, alert.title));
+            }
+        }
+    }
+    Ok(sheet)
+}
+
+/// Drop cached derived artifacts of one kind ("prep_sheet", "completion_check")
+/// or, with no kind, all of them
+#[tauri::command]
+pub fn clear_derived_cache(
+    state: State<AppState>,
+    kind: Option<String>,
+) -> Result<usize, String> {
     let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
-    vault.generate_prep_sheet(&client_id).map_err(|e| format!("{}", e))
+    vault.clear_derived_cache(kind.as_deref()).map_err(|e| format!("{}", e))
 }
 
 // ============================================
@@ -1519,39 +2789,34 @@ pub async fn check_note_completion(
     note_id: String,
     model: String,
 ) -> Result<crate::models::CompletionCheckResult, String> {
-    // Get note content
-    let note_content = {
+    // Get note content; reuse a previous AI check of the same content, prompt and model
+    let versions = format!("prompt={};model={}", crate::ai::COMPLETION_CHECK_PROMPT_VERSION, model);
+    let (note_content, content_hash) = {
         let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
         let note = vault.get_note(&note_id).map_err(|e| format!("{}", e))?;
-        note.raw_input.clone()
+        if let Some(cached) = vault.cache_get("completion_check", &note_id, &note.content_hash, &versions)
+            .map_err(|e| format!("{}", e))?
+        {
+            return Ok(cached);
+        }
+        (note.raw_input, note.content_hash)
     };
     
     // Use AI to check completeness
-    let prompt = format!(r#"Analyze this clinical progress note for completeness and quality. Check for:
-1. Missing required fields (subjective, objective, assessment, plan)
-2. Vague or ambiguous language
-3. Compliance issues (missing dates, unclear interventions, no diagnosis reference)
-
-Return a JSON object with:
-{{
-  "is_complete": boolean,
-  "overall_score": 0.0-1.0,
-  "missing_fields": [{{"field_name": "string", "importance": "required|recommended|optional", "description": "string"}}],
-  "vague_sections": [{{"section": "string", "problematic_text": "string", "suggestion": "string"}}],
-  "compliance_issues": [{{"issue_type": "string", "description": "string", "severity": "warning|error"}}],
-  "suggestions": ["string"]
-}}
-
-Note content:
-{}
-
-Return ONLY the JSON object, no other text."#, note_content);
+    let prompt = crate::ai::completion_check_prompt(&note_content);
 
     let response = crate::ai::call_ollama(&model, &prompt).await
         .map_err(|e| format!("AI check failed: {}", e))?;
     
-    // Parse response
-    let result: crate::models::CompletionCheckResult = serde_json::from_str(&response)
+    // Parse response (only model output is cached; the rule-based fallback is cheap)
+    let parsed: Result<crate::models::CompletionCheckResult, _> = serde_json::from_str(&response);
+    if let Ok(ref result) = parsed {
+        let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+        if let Err(e) = vault.cache_put("completion_check", &note_id, &content_hash, &versions, result) {
+            log::warn!("Failed to cache completion check: {}", e);
+        }
+    }
+    let result = parsed
         .unwrap_or_else(|_| {
             // Fallback: basic rule-based check
             let content_lower = note_content.to_lowercase();
@@ -1618,6 +2883,11 @@ pub fn export_note_to_file(
     let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
     let note = vault.get_note(&note_id).map_err(|e| format!("{}", e))?;
     let client = vault.get_client(&note.client_id).map_err(|e| format!("{}", e))?;
+    let practice = vault.get_practice_profile().map_err(|e| format!("{}", e))?;
+    let logo = match practice {
+        Some(ref p) if p.has_logo => vault.get_practice_logo().map_err(|e| format!("{}", e))?,
+        _ => None,
+    };
     
     match format.as_str() {
         "txt" => {
@@ -1636,21 +2906,34 @@ pub fn export_note_to_file(
         }
         "pdf" => {
             // Generate PDF using simple text-based approach
-            let pdf_content = generate_note_pdf(&note, &client, include_header)?;
+            let pdf_content = generate_note_pdf(&note, &client, include_header, practice.as_ref(), logo.as_ref())?;
             Ok(pdf_content)
         }
         "docx" => {
             // Generate DOCX
-            let docx_content = generate_note_docx(&note, &client, include_header)?;
+            let docx_content = generate_note_docx(&note, &client, include_header, practice.as_ref(), logo.as_ref())?;
             Ok(docx_content)
         }
         _ => Err(format!("Unsupported format: {}", format)),
     }
 }
 
-fn generate_note_pdf(note: &crate::models::Note, client: &crate::models::Client, include_header: bool) -> Result<Vec<u8>, String> {
+fn generate_note_pdf(
+    note: &crate::models::Note,
+    client: &crate::models::Client,
+    include_header: bool,
+    practice: Option<&crate::branding::PracticeProfile>,
+    logo: Option<&crate::branding::PracticeLogo>,
+) -> Result<Vec<u8>, String> {
     // Build text content
     let mut body = String::new();
+    if let Some(practice) = practice {
+        for line in practice.letterhead_lines() {
+            body.push_str(&line);
+            body.push('\n');
+        }
+        body.push('\n');
+    }
     if include_header {
         body.push_str("CLINICAL PROGRESS NOTE\n\n");
         body.push_str(&format!("Client: {}\n", client.display_name));
@@ -1660,7 +2943,7 @@ fn generate_note_pdf(note: &crate::models::Note, client: &crate::models::Client,
         body.push_str("---\n\n");
     }
     body.push_str(&note.raw_input);
-    body.push_str(&format!("\n\n---\nGenerated by Evidify | Hash: {}", &note.content_hash[..12]));
+    body.push_str(&format!("\n\n---\n{} | Hash: {}", crate::branding::footer_text(practice), &note.content_hash[..12]));
     
     // Split into lines and create text positioning commands
     let mut text_commands = String::new();
@@ -1679,53 +2962,75 @@ fn generate_note_pdf(note: &crate::models::Note, client: &crate::models::Client,
         y_pos = 0;
     }
     
-    let stream_content = format!("BT\n/F1 11 Tf\n{}ET", text_commands);
+    let logo_draw = logo.map(crate::branding::pdf_logo_draw).unwrap_or_default();
+    let stream_content = format!("{}BT\n/F1 11 Tf\n{}ET", logo_draw, text_commands);
     let stream_length = stream_content.len();
+    let xobjects = if logo.is_some() { " /XObject << /Logo 6 0 R >>" } else { "" };
     
     let mut pdf = Vec::new();
+    let mut offsets = Vec::new();
     
     pdf.extend_from_slice(b"%PDF-1.4\n%\xe2\xe3\xcf\xd3\n");
-    let obj1_offset = pdf.len();
+    offsets.push(pdf.len());
     pdf.extend_from_slice(b"1 0 obj << /Type /Catalog /Pages 2 0 R >> endobj\n");
-    let obj2_offset = pdf.len();
+    offsets.push(pdf.len());
     pdf.extend_from_slice(b"2 0 obj << /Type /Pages /Kids [3 0 R] /Count 1 >> endobj\n");
-    let obj3_offset = pdf.len();
-    pdf.extend_from_slice(b"3 0 obj << /Type /Page /Parent 2 0 R /MediaBox [0 0 612 792] /Contents 4 0 R /Resources << /Font << /F1 5 0 R >> >> >> endobj\n");
-    let obj4_offset = pdf.len();
+    offsets.push(pdf.len());
+    let obj3 = format!("3 0 obj << /Type /Page /Parent 2 0 R /MediaBox [0 0 612 792] /Contents 4 0 R /Resources << /Font << /F1 5 0 R >>{} >> >> endobj\n", xobjects);
+    pdf.extend_from_slice(obj3.as_bytes());
+    offsets.push(pdf.len());
     let obj4 = format!("4 0 obj << /Length {} >>\nstream\n{}\nendstream\nendobj\n", stream_length, stream_content);
     pdf.extend_from_slice(obj4.as_bytes());
-    let obj5_offset = pdf.len();
+    offsets.push(pdf.len());
     pdf.extend_from_slice(b"5 0 obj << /Type /Font /Subtype /Type1 /BaseFont /Helvetica >> endobj\n");
+    if let Some(logo) = logo {
+        offsets.push(pdf.len());
+        pdf.extend_from_slice(&crate::branding::pdf_logo_object(logo, 6));
+    }
     
     let xref_offset = pdf.len();
-    let xref = format!(
-        "xref\n0 6\n0000000000 65535 f \n{:010} 00000 n \n{:010} 00000 n \n{:010} 00000 n \n{:010} 00000 n \n{:010} 00000 n \n",
-        obj1_offset, obj2_offset, obj3_offset, obj4_offset, obj5_offset
-    );
+    let mut xref = format!("xref\n0 {}\n0000000000 65535 f \n", offsets.len() + 1);
+    for offset in &offsets {
+        xref.push_str(&format!("{:010} 00000 n \n", offset));
+    }
     pdf.extend_from_slice(xref.as_bytes());
     
-    let trailer = format!("trailer << /Size 6 /Root 1 0 R >>\nstartxref\n{}\n%%EOF", xref_offset);
+    let trailer = format!("trailer << /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF", offsets.len() + 1, xref_offset);
     pdf.extend_from_slice(trailer.as_bytes());
     
     Ok(pdf)
 }
-fn generate_note_docx(note: &crate::models::Note, client: &crate::models::Client, include_header: bool) -> Result<Vec<u8>, String> {
+fn generate_note_docx(
+    note: &crate::models::Note,
+    client: &crate::models::Client,
+    include_header: bool,
+    practice: Option<&crate::branding::PracticeProfile>,
+    logo: Option<&crate::branding::PracticeLogo>,
+) -> Result<Vec<u8>, String> {
     use std::io::{Write, Cursor};
-    use zip::write::FileOptions;
+    use zip::write::SimpleFileOptions;
     use zip::ZipWriter;
     
     let mut buffer = Cursor::new(Vec::new());
     let mut zip = ZipWriter::new(&mut buffer);
-    let options = FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
     
     // [Content_Types].xml
     zip.start_file("[Content_Types].xml", options).map_err(|e| e.to_string())?;
-    zip.write_all(br#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+    let header_type = if practice.is_some() {
+        r#"
+  <Default Extension="jpeg" ContentType="image/jpeg"/>
+  <Override PartName="/word/header1.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.header+xml"/>"#
+    } else {
+        ""
+    };
+    let content_types = format!(r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
 <Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">
   <Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>
   <Default Extension="xml" ContentType="application/xml"/>
-  <Override PartName="/word/document.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.document.main+xml"/>
-</Types>"#).map_err(|e| e.to_string())?;
+  <Override PartName="/word/document.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.document.main+xml"/>{}
+</Types>"#, header_type);
+    zip.write_all(content_types.as_bytes()).map_err(|e| e.to_string())?;
     
     // _rels/.rels
     zip.start_file("_rels/.rels", options).map_err(|e| e.to_string())?;
@@ -1734,6 +3039,40 @@ fn generate_note_docx(note: &crate::models::Note, client: &crate::models::Client
   <Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="word/document.xml"/>
 </Relationships>"#).map_err(|e| e.to_string())?;
     
+    // Letterhead goes in a page header so it repeats on every page
+    let mut section_props = String::new();
+    if let Some(practice) = practice {
+        zip.start_file("word/_rels/document.xml.rels", options).map_err(|e| e.to_string())?;
+        zip.write_all(br#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+  <Relationship Id="rIdHeader1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/header" Target="header1.xml"/>
+</Relationships>"#).map_err(|e| e.to_string())?;
+        
+        let mut header = String::new();
+        if let Some(logo) = logo {
+            zip.start_file("word/media/logo.jpeg", options).map_err(|e| e.to_string())?;
+            zip.write_all(&logo.jpeg).map_err(|e| e.to_string())?;
+            zip.start_file("word/_rels/header1.xml.rels", options).map_err(|e| e.to_string())?;
+            zip.write_all(br#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+  <Relationship Id="rIdLogo" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/image" Target="media/logo.jpeg"/>
+</Relationships>"#).map_err(|e| e.to_string())?;
+            header.push_str(&format!(r#"<w:p><w:pPr><w:jc w:val="right"/></w:pPr>{}</w:p>"#, crate::branding::docx_logo_drawing(logo, "rIdLogo")));
+        }
+        for (i, line) in practice.letterhead_lines().iter().enumerate() {
+            let bold = if i == 0 { "<w:b/>" } else { "" };
+            header.push_str(&format!(r#"<w:p><w:r><w:rPr>{}<w:sz w:val="18"/></w:rPr><w:t>{}</w:t></w:r></w:p>"#, bold, escape_xml(line)));
+        }
+        
+        zip.start_file("word/header1.xml", options).map_err(|e| e.to_string())?;
+        let header_xml = format!(r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<w:hdr xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships" xmlns:wp="http://schemas.openxmlformats.org/drawingml/2006/wordprocessingDrawing" xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:pic="http://schemas.openxmlformats.org/drawingml/2006/picture">
+  {}
+</w:hdr>"#, header);
+        zip.write_all(header_xml.as_bytes()).map_err(|e| e.to_string())?;
+        section_props.push_str(r#"<w:sectPr><w:headerReference w:type="default" r:id="rIdHeader1"/></w:sectPr>"#);
+    }
+    
     // Build document content
     let mut paragraphs = String::new();
     
@@ -1757,20 +3096,20 @@ fn generate_note_docx(note: &crate::models::Note, client: &crate::models::Client
     
     // Footer
     paragraphs.push_str(r#"<w:p><w:r><w:t>---</w:t></w:r></w:p>"#);
-    paragraphs.push_str(&format!(r#"<w:p><w:r><w:rPr><w:sz w:val="18"/></w:rPr><w:t>Generated by Evidify | Hash: {}</w:t></w:r></w:p>"#, &note.content_hash[..12]));
+    paragraphs.push_str(&format!(r#"<w:p><w:r><w:rPr><w:sz w:val="18"/></w:rPr><w:t>{} | Hash: {}</w:t></w:r></w:p>"#,
+        escape_xml(&crate::branding::footer_text(practice)), &note.content_hash[..12]));
     
     // word/document.xml
     zip.start_file("word/document.xml", options).map_err(|e| e.to_string())?;
     let doc = format!(r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
-<w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main">
+<w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships">
   <w:body>
-    {}
+    {}{}
   </w:body>
-</w:document>"#, paragraphs);
+</w:document>"#, paragraphs, section_props);
     zip.write_all(doc.as_bytes()).map_err(|e| e.to_string())?;
     
     zip.finish().map_err(|e| e.to_string())?;
-    drop(zip);
     
     Ok(buffer.into_inner())
 }
@@ -1813,19 +3152,47 @@ pub fn submit_note_for_review(
     state: State<AppState>,
     note_id: String,
     trainee_id: String,
+    idempotency_key: Option<String>,
 ) -> Result<(), String> {
     let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
-    vault.submit_note_for_review(&note_id, &trainee_id)
-        .map_err(|e| format!("{}", e))
+    let args = serde_json::json!({ "note_id": &note_id, "trainee_id": &trainee_id });
+    crate::idempotency::run_once(&vault, idempotency_key.as_deref(), "submit_note_for_review", args, || {
+        vault.submit_note_for_review(&note_id, &trainee_id)
+            .map_err(|e| format!("{}", e))
+    })
 }
 
+/// Pending reviews. A supervisor may ask for the blinded list; when the
+/// supervision policy requires blind review it cannot be turned off.
 #[tauri::command]
 pub fn get_pending_reviews(
     state: State<AppState>,
+    policy_state: State<'_, crate::policy::PolicyState>,
     supervisor_id: String,
+    blinded: Option<bool>,
 ) -> Result<Vec<crate::models::PendingReview>, String> {
+    let required = {
+        let engine = policy_state.engine.read().map_err(|e| e.to_string())?;
+        engine.get_policy().supervision_policy.blind_review
+    };
+    let blinded = required || blinded.unwrap_or(false);
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    if blinded {
+        vault.get_pending_reviews_blinded(&supervisor_id).map_err(|e| format!("{}", e))
+    } else {
+        vault.get_pending_reviews(&supervisor_id).map_err(|e| format!("{}", e))
+    }
+}
+
+/// Note for blinded documentation-quality review
+#[tauri::command]
+pub fn get_blinded_review_note(
+    state: State<AppState>,
+    note_id: String,
+    supervisor_id: String,
+) -> Result<crate::supervision::BlindedNote, String> {
     let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
-    vault.get_pending_reviews(&supervisor_id).map_err(|e| format!("{}", e))
+    vault.get_blinded_review_note(&note_id, &supervisor_id).map_err(|e| format!("{}", e))
 }
 
 #[tauri::command]
@@ -1836,13 +3203,21 @@ pub fn add_review_comment(
     comment_type: String,
     text: String,
     section: Option<String>,
+    idempotency_key: Option<String>,
 ) -> Result<crate::models::ReviewComment, String> {
     let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
-    vault.add_review_comment(&note_id, &supervisor_id, &comment_type, &text, section.as_deref())
-        .map_err(|e| format!("{}", e))
+    let args = serde_json::json!({
+        "note_id": &note_id, "supervisor_id": &supervisor_id, "comment_type": &comment_type,
+        "text": &text, "section": &section,
+    });
+    crate::idempotency::run_once(&vault, idempotency_key.as_deref(), "add_review_comment", args, || {
+        vault.add_review_comment(&note_id, &supervisor_id, &comment_type, &text, section.as_deref())
+            .map_err(|e| format!("{}", e))
+    })
 }
 
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub fn complete_review(
     state: State<AppState>,
     note_id: String,
@@ -1851,25 +3226,33 @@ pub fn complete_review(
     overall_feedback: Option<String>,
     clinical_accuracy_score: Option<i32>,
     documentation_quality_score: Option<i32>,
+    idempotency_key: Option<String>,
 ) -> Result<crate::models::SupervisorReview, String> {
     let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
-    vault.complete_review(
-        &note_id,
-        &supervisor_id,
-        &status,
-        overall_feedback.as_deref(),
-        clinical_accuracy_score,
-        documentation_quality_score,
-    ).map_err(|e| format!("{}", e))
+    let args = serde_json::json!({
+        "note_id": &note_id, "supervisor_id": &supervisor_id, "status": &status,
+        "overall_feedback": &overall_feedback, "clinical_accuracy_score": clinical_accuracy_score,
+        "documentation_quality_score": documentation_quality_score,
+    });
+    crate::idempotency::run_once(&vault, idempotency_key.as_deref(), "complete_review", args, || {
+        vault.complete_review(
+            &note_id,
+            &supervisor_id,
+            &status,
+            overall_feedback.as_deref(),
+            clinical_accuracy_score,
+            documentation_quality_score,
+        ).map_err(|e| format!("{}", e))
+    })
 }
 
 #[tauri::command]
 pub fn get_supervisor_dashboard(
     state: State<AppState>,
+    replica_state: State<'_, crate::replica::ReplicaState>,
     supervisor_id: String,
 ) -> Result<crate::models::SupervisorDashboard, String> {
-    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
-    vault.get_supervisor_dashboard(&supervisor_id).map_err(|e| format!("{}", e))
+    replica_state.with_replica(&state, |replica| replica.get_supervisor_dashboard(&supervisor_id))
 }
 
 /// Get pending reviews for a specific trainee
@@ -1891,6 +3274,143 @@ pub fn get_note_reviews(
     vault.get_note_reviews(&note_id).map_err(|e| format!("{}", e))
 }
 
+/// Trainee records what they learned from a completed review
+#[tauri::command]
+pub fn save_review_reflection(
+    state: State<AppState>,
+    review_id: String,
+    trainee_id: String,
+    what_i_learned: String,
+    what_i_will_change: String,
+    idempotency_key: Option<String>,
+) -> Result<crate::models::ReviewReflection, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    let args = serde_json::json!({
+        "review_id": &review_id, "trainee_id": &trainee_id,
+        "what_i_learned": &what_i_learned, "what_i_will_change": &what_i_will_change,
+    });
+    crate::idempotency::run_once(&vault, idempotency_key.as_deref(), "save_review_reflection", args, || {
+        vault.save_review_reflection(&review_id, &trainee_id, &what_i_learned, &what_i_will_change)
+            .map_err(|e| format!("{}", e))
+    })
+}
+
+#[tauri::command]
+pub fn get_review_reflection(
+    state: State<AppState>,
+    review_id: String,
+) -> Result<Option<crate::models::ReviewReflection>, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.get_review_reflection(&review_id).map_err(|e| format!("{}", e))
+}
+
+#[tauri::command]
+pub fn list_trainee_reflections(
+    state: State<AppState>,
+    trainee_id: String,
+) -> Result<Vec<crate::models::ReviewReflection>, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.list_trainee_reflections(&trainee_id).map_err(|e| format!("{}", e))
+}
+
+#[tauri::command]
+pub fn acknowledge_review_reflection(
+    state: State<AppState>,
+    reflection_id: String,
+    supervisor_id: String,
+) -> Result<(), String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.acknowledge_review_reflection(&reflection_id, &supervisor_id)
+        .map_err(|e| format!("{}", e))
+}
+
+#[tauri::command]
+pub fn get_trainee_competency_report(
+    state: State<AppState>,
+    trainee_id: String,
+) -> Result<crate::models::TraineeCompetencyReport, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.get_trainee_competency_report(&trainee_id).map_err(|e| format!("{}", e))
+}
+
+/// Competency report rendered as Markdown for the training program
+#[tauri::command]
+pub fn export_trainee_competency_report(
+    state: State<AppState>,
+    trainee_id: String,
+) -> Result<String, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    let report = vault.get_trainee_competency_report(&trainee_id).map_err(|e| format!("{}", e))?;
+    Ok(crate::supervision::format_competency_report(&report))
+}
+
+#[tauri::command]
+pub fn get_supervision_quality_report(
+    state: State<AppState>,
+    supervisor_id: String,
+) -> Result<crate::models::SupervisionQualityReport, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.get_supervision_quality_report(&supervisor_id).map_err(|e| format!("{}", e))
+}
+
+/// Quality trend report rendered as Markdown for accreditation files
+#[tauri::command]
+pub fn export_supervision_quality_report(
+    state: State<AppState>,
+    supervisor_id: String,
+) -> Result<String, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    let report = vault.get_supervision_quality_report(&supervisor_id).map_err(|e| format!("{}", e))?;
+    Ok(crate::supervision::format_quality_report(&report))
+}
+
+#[tauri::command]
+pub fn record_group_supervision(
+    state: State<AppState>,
+    session: crate::models::NewGroupSupervisionSession,
+) -> Result<crate::models::GroupSupervisionSession, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.record_group_supervision(&session).map_err(|e| format!("{}", e))
+}
+
+#[tauri::command]
+pub fn list_group_supervision_sessions(
+    state: State<AppState>,
+    supervisor_id: String,
+    from: Option<String>,
+    to: Option<String>,
+) -> Result<Vec<crate::models::GroupSupervisionSession>, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.list_group_supervision_sessions(&supervisor_id, from.as_deref(), to.as_deref())
+        .map_err(|e| format!("{}", e))
+}
+
+#[tauri::command]
+pub fn get_group_supervision_log(
+    state: State<AppState>,
+    supervisor_id: String,
+    from: Option<String>,
+    to: Option<String>,
+) -> Result<crate::models::GroupSupervisionLog, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.get_group_supervision_log(&supervisor_id, from.as_deref(), to.as_deref())
+        .map_err(|e| format!("{}", e))
+}
+
+/// Group supervision log rendered as Markdown for accreditation files
+#[tauri::command]
+pub fn export_group_supervision_log(
+    state: State<AppState>,
+    supervisor_id: String,
+    from: Option<String>,
+    to: Option<String>,
+) -> Result<String, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    let log = vault.get_group_supervision_log(&supervisor_id, from.as_deref(), to.as_deref())
+        .map_err(|e| format!("{}", e))?;
+    Ok(crate::supervision::format_group_supervision_log(&log))
+}
+
 // ============================================
 // HIPAA Safe Harbor De-identification
 // ============================================
@@ -1905,6 +3425,17 @@ pub fn deidentify_text(
     Ok(engine.deidentify(&text))
 }
 
+/// De-identify a Markdown or HTML document, keeping its structure
+#[tauri::command]
+pub fn deidentify_document(
+    text: String,
+    format: String,
+    use_ai: bool,
+) -> Result<crate::deidentify::DeidentificationResult, String> {
+    let engine = crate::deidentify::DeidentificationEngine::new(use_ai, None);
+    Ok(engine.deidentify_document(&text, crate::deidentify::DocumentFormat::from_name(&format)))
+}
+
 /// De-identify a note and return preview comparison
 #[tauri::command]
 pub fn deidentify_note(
@@ -1953,23 +3484,122 @@ pub fn get_deidentification_audits(
         .map_err(|e| format!("{}", e))
 }
 
-/// Export de-identified case with audit certificate
+/// De-identified case document plus the signed certificate it cites
+#[derive(serde::Serialize)]
+pub struct DeidentifiedCaseExport {
+    pub filename: String,
+    pub document: Vec<u8>,
+    pub certificate: crate::deidentify::CertificateFile,
+}
+
+/// Export de-identified case with its standalone certificate
 #[tauri::command]
 pub fn export_deidentified_case(
     state: State<AppState>,
     note_id: String,
     format: String,  // "pdf", "docx", "txt"
     include_audit: bool,
-) -> Result<Vec<u8>, String> {
+) -> Result<DeidentifiedCaseExport, String> {
     let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
-    
+    let (document, certificate) = render_deidentified_case(&vault, &note_id, &format, include_audit)?;
+    let stem = format!("deidentified-case-{}", &certificate.certificate_id[..8]);
+    Ok(DeidentifiedCaseExport {
+        filename: format!("{}.{}", stem, format),
+        document,
+        certificate: certificate.to_file(&stem)?,
+    })
+}
+
+/// Check a de-identification certificate's hash and signatures against
+/// an install key fingerprint; without one, this install's own key
+#[tauri::command]
+pub fn verify_deidentification_certificate(
+    state: State<AppState>,
+    certificate_json: String,
+    expected_fingerprint: Option<String>,
+) -> Result<bool, String> {
+    let certificate: crate::deidentify::DeidentificationCertificate = serde_json::from_str(&certificate_json)
+        .map_err(|e| format!("Not a de-identification certificate: {}", e))?;
+    let expected_fingerprint = match expected_fingerprint {
+        Some(fingerprint) => fingerprint,
+        None => {
+            let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+            vault.install_signing_identity().map_err(|e| format!("{}", e))?.fingerprint
+        }
+    };
+    match certificate.verify(&expected_fingerprint) {
+        Ok(()) => Ok(true),
+        Err(reason) => {
+            log::warn!("De-identification certificate failed verification: {}", reason);
+            Ok(false)
+        }
+    }
+}
+
+/// Encrypted de-identified case bundle, safe to attach to an email
+#[derive(serde::Serialize)]
+pub struct EncryptedCaseExport {
+    pub filename: String,
+    pub archive: Vec<u8>,
+    /// Shown once; never stored. Share with the recipient out-of-band.
+    pub passphrase: String,
+    pub archive_sha256: String,
+    /// Certificate inside the archive, for the recipient to check against
+    pub certificate_sha256: String,
+}
+
+/// Export a de-identified case as an AES-256 encrypted ZIP.
+/// A fresh passphrase is generated per export and only its hash is audited.
+#[tauri::command]
+pub fn export_deidentified_case_encrypted(
+    state: State<AppState>,
+    note_id: String,
+    format: String,  // "pdf", "docx", "txt"
+    include_audit: bool,
+) -> Result<EncryptedCaseExport, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    let (document, certificate) = render_deidentified_case(&vault, &note_id, &format, include_audit)?;
+    
+    let export_id = uuid::Uuid::new_v4().to_string();
+    let short_id = &export_id[..8];
+    let stem = format!("deidentified-case-{}", short_id);
+    let entry_name = format!("{}.{}", stem, format);
+    let certificate_file = certificate.to_file(&stem)?;
+    let passphrase = export::generate_archive_passphrase();
+    let archive = export::build_encrypted_zip(&[
+        (entry_name.as_str(), document.as_slice()),
+        (certificate_file.filename.as_str(), certificate_file.content.as_bytes()),
+    ], &passphrase)?;
+    
+    let conn = vault.get_connection().map_err(|e| format!("{}", e))?;
+    audit::log_encrypted_disclosure(conn, &export_id, &crate::crypto::hash_sha256(passphrase.as_bytes()))
+        .map_err(|e| format!("Failed to audit disclosure: {}", e))?;
+    
+    Ok(EncryptedCaseExport {
+        filename: format!("deidentified-case-{}.zip", short_id),
+        archive_sha256: crate::crypto::hash_sha256(&archive),
+        archive,
+        passphrase,
+        certificate_sha256: certificate_file.certificate_sha256,
+    })
+}
+
+fn render_deidentified_case(
+    vault: &Vault,
+    note_id: &str,
+    format: &str,
+    include_audit: bool,
+) -> Result<(Vec<u8>, crate::deidentify::DeidentificationCertificate), String> {
     // Get note
-    let note = vault.get_note(&note_id).map_err(|e| format!("{}", e))?;
+    let note = vault.get_note(note_id).map_err(|e| format!("{}", e))?;
     let client = vault.get_client(&note.client_id).map_err(|e| format!("{}", e))?;
+    log_note_retrieval(vault, "deidentified_export", &[note_id])?;
     
     // De-identify
     let engine = crate::deidentify::DeidentificationEngine::new(false, None);
     let result = engine.deidentify(&note.raw_input);
+    let certificate = vault.issue_deidentification_certificate(&result, format, false)
+        .map_err(|e| format!("{}", e))?;
     
     // Build content
     let mut content = String::new();
@@ -1979,7 +3609,8 @@ pub fn export_deidentified_case(
     content.push_str(&format!("Case Type: {}\n", note.note_type));
     content.push_str(&format!("Session Period: [DATE REDACTED]\n"));
     content.push_str(&format!("De-identification Method: HIPAA Safe Harbor\n"));
-    content.push_str(&format!("Regulation: 45 CFR 164.514(b)(2)\n\n"));
+    content.push_str(&format!("Regulation: 45 CFR 164.514(b)(2)\n"));
+    content.push_str(&format!("Certificate: {} (SHA-256 {})\n\n", certificate.certificate_id, certificate.certificate_sha256));
     content.push_str("───────────────────────────────────────────────────────────\n");
     content.push_str("                    CASE CONTENT\n");
     content.push_str("───────────────────────────────────────────────────────────\n\n");
@@ -2026,24 +3657,21 @@ pub fn export_deidentified_case(
         content.push_str("\n");
         content.push_str("This document has been de-identified per HIPAA Safe Harbor\n");
         content.push_str("method. All 18 identifier categories have been reviewed and\n");
-        content.push_str("applicable identifiers removed or generalized.\n\n");
+        content.push_str("applicable identifiers removed or generalized. The signed\n");
+        content.push_str("certificate file accompanying this document carries the\n");
+        content.push_str("hashes above in full.\n\n");
         content.push_str("Generated by Evidify | evidify.ai\n");
     }
     
-    match format.as_str() {
-        "txt" => Ok(content.into_bytes()),
-        "pdf" => {
-            // Simple PDF generation
-            let pdf = generate_deidentified_pdf(&content)?;
-            Ok(pdf)
-        }
-        "docx" => {
-            // Generate DOCX
-            let docx = generate_deidentified_docx(&content)?;
-            Ok(docx)
-        }
-        _ => Err(format!("Unsupported format: {}", format)),
-    }
+    let document = match format {
+        "txt" => content.into_bytes(),
+        // Simple PDF generation
+        "pdf" => generate_deidentified_pdf(&content)?,
+        // Generate DOCX
+        "docx" => generate_deidentified_docx(&content)?,
+        _ => return Err(format!("Unsupported format: {}", format)),
+    };
+    Ok((document, certificate))
 }
 
 fn generate_deidentified_pdf(content: &str) -> Result<Vec<u8>, String> {
@@ -2086,12 +3714,12 @@ startxref
 
 fn generate_deidentified_docx(content: &str) -> Result<Vec<u8>, String> {
     use std::io::{Write, Cursor};
-    use zip::write::FileOptions;
+    use zip::write::SimpleFileOptions;
     use zip::ZipWriter;
     
     let mut buffer = Cursor::new(Vec::new());
     let mut zip = ZipWriter::new(&mut buffer);
-    let options = FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
     
     // [Content_Types].xml
     zip.start_file("[Content_Types].xml", options).map_err(|e| e.to_string())?;
@@ -2134,7 +3762,6 @@ fn generate_deidentified_docx(content: &str) -> Result<Vec<u8>, String> {
     zip.write_all(doc.as_bytes()).map_err(|e| e.to_string())?;
     
     zip.finish().map_err(|e| e.to_string())?;
-    drop(zip);
     
     Ok(buffer.into_inner())
 }
@@ -2222,6 +3849,48 @@ pub fn delete_consultation_draft(
     vault.delete_consultation_draft(&draft_id).map_err(|e| format!("{}", e))
 }
 
+/// Register a consultant's X25519 public key (base64)
+#[tauri::command]
+pub fn add_trusted_recipient(
+    state: State<AppState>,
+    label: String,
+    public_key: String,
+) -> Result<crate::deidentify::TrustedRecipient, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.add_trusted_recipient(&label, &public_key).map_err(|e| format!("{}", e))
+}
+
+/// List trusted recipient keys
+#[tauri::command]
+pub fn list_trusted_recipients(
+    state: State<AppState>,
+    include_revoked: Option<bool>,
+) -> Result<Vec<crate::deidentify::TrustedRecipient>, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.list_trusted_recipients(include_revoked.unwrap_or(false)).map_err(|e| format!("{}", e))
+}
+
+/// Revoke a trusted recipient key
+#[tauri::command]
+pub fn revoke_trusted_recipient(
+    state: State<AppState>,
+    recipient_id: String,
+) -> Result<(), String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.revoke_trusted_recipient(&recipient_id).map_err(|e| format!("{}", e))
+}
+
+/// Encrypt a consultation draft for a trusted recipient
+#[tauri::command]
+pub fn seal_consultation_draft(
+    state: State<AppState>,
+    draft_id: String,
+    recipient_id: String,
+) -> Result<crate::crypto::envelope::SealedEnvelope, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.seal_consultation_draft(&draft_id, &recipient_id).map_err(|e| format!("{}", e))
+}
+
 // =============================
 // Forensic annotation commands
 // =============================
@@ -5,14 +5,20 @@
 // - KEK wraps Vault Key (stored in OS keychain)
 // - Vault Key opens SQLCipher database
 // - Passphrase REQUIRED every session to derive KEK
+//
+// Wrapped keys carry a leading algorithm byte so the wrapping scheme can
+// change (e.g. AES-KW, XChaCha20-Poly1305) without stranding existing
+// vaults: unwrap accepts every known version, and unlock re-wraps keys
+// that are not on the current one.
 
 use argon2::{Argon2, Params, Version};
 use aes_gcm::{
-    aead::{Aead, KeyInit},
+    aead::{Aead, KeyInit, Payload},
     Aes256Gcm, Nonce,
 };
 use sha2::{Digest, Sha256};
 use rand::RngCore;
+use subtle::ConstantTimeEq;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -34,6 +40,9 @@ pub enum CryptoError {
     
     #[error("Key unwrap failed - invalid passphrase")]
     UnwrapFailed,
+    
+    #[error("Unsupported key wrap algorithm: {0}")]
+    UnsupportedAlgorithm(u8),
 }
 
 // ============================================
@@ -69,33 +78,40 @@ impl KEK {
         Ok(KEK(key))
     }
     
-    /// Wrap a vault key for storage
+    /// Wrap a vault key for storage with the current algorithm
     pub fn wrap(&self, vault_key: &VaultKey) -> Result<WrappedVaultKey, CryptoError> {
+        let algorithm = WrapAlgorithm::CURRENT;
         let cipher = Aes256Gcm::new_from_slice(&self.0)
             .map_err(|_| CryptoError::InvalidKeyLength)?;
         
-        let mut nonce_bytes = [0u8; 12];
-        rand::rngs::OsRng.fill_bytes(&mut nonce_bytes);
-        let nonce = Nonce::from_slice(&nonce_bytes);
+        let mut nonce = vec![0u8; algorithm.nonce_len()];
+        rand::rngs::OsRng.fill_bytes(&mut nonce);
         
-        let ciphertext = cipher.encrypt(nonce, vault_key.0.as_ref())
+        let aad = algorithm.associated_data();
+        let ciphertext = cipher.encrypt(Nonce::from_slice(&nonce), Payload { msg: vault_key.0.as_ref(), aad: &aad })
             .map_err(|e| CryptoError::Encryption(e.to_string()))?;
         
         Ok(WrappedVaultKey {
+            algorithm,
             ciphertext,
-            nonce: nonce_bytes,
+            nonce,
         })
     }
     
-    /// Unwrap a vault key from storage
+    /// Unwrap a vault key from storage (any supported algorithm)
     pub fn unwrap(&self, wrapped: &WrappedVaultKey) -> Result<VaultKey, CryptoError> {
-        let cipher = Aes256Gcm::new_from_slice(&self.0)
-            .map_err(|_| CryptoError::InvalidKeyLength)?;
-        
-        let nonce = Nonce::from_slice(&wrapped.nonce);
-        
-        let plaintext = cipher.decrypt(nonce, wrapped.ciphertext.as_ref())
-            .map_err(|_| CryptoError::UnwrapFailed)?;
+        let plaintext = match wrapped.algorithm {
+            WrapAlgorithm::LegacyAes256Gcm | WrapAlgorithm::Aes256GcmV1 => {
+                let cipher = Aes256Gcm::new_from_slice(&self.0)
+                    .map_err(|_| CryptoError::InvalidKeyLength)?;
+                if wrapped.nonce.len() != wrapped.algorithm.nonce_len() {
+                    return Err(CryptoError::UnwrapFailed);
+                }
+                let aad = wrapped.algorithm.associated_data();
+                cipher.decrypt(Nonce::from_slice(&wrapped.nonce), Payload { msg: &wrapped.ciphertext, aad: &aad })
+                    .map_err(|_| CryptoError::UnwrapFailed)?
+            }
+        };
         
         if plaintext.len() != 32 {
             return Err(CryptoError::InvalidKeyLength);
@@ -137,33 +153,91 @@ impl Drop for VaultKey {
     }
 }
 
+/// Key wrapping scheme. The byte value is the version prefix of the
+/// serialized wrapped key; new schemes get a new value and an arm in
+/// `KEK::unwrap`, and `CURRENT` moves to them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WrapAlgorithm {
+    /// Pre-versioning keychain entries: nonce || ciphertext, no prefix, no AAD
+    LegacyAes256Gcm = 0,
+    /// AES-256-GCM with the version byte bound as associated data
+    Aes256GcmV1 = 1,
+}
+
+impl WrapAlgorithm {
+    pub const CURRENT: WrapAlgorithm = WrapAlgorithm::Aes256GcmV1;
+    
+    pub fn from_byte(b: u8) -> Result<Self, CryptoError> {
+        match b {
+            1 => Ok(WrapAlgorithm::Aes256GcmV1),
+            other => Err(CryptoError::UnsupportedAlgorithm(other)),
+        }
+    }
+    
+    fn nonce_len(self) -> usize {
+        match self {
+            WrapAlgorithm::LegacyAes256Gcm | WrapAlgorithm::Aes256GcmV1 => 12,
+        }
+    }
+    
+    /// Binds the version into the tag so a wrapped key cannot be
+    /// relabelled as an older scheme
+    fn associated_data(self) -> Vec<u8> {
+        match self {
+            WrapAlgorithm::LegacyAes256Gcm => Vec::new(),
+            v => format!("evidify-wrap|{}", v as u8).into_bytes(),
+        }
+    }
+}
+
+/// Legacy entries are exactly nonce (12) + key (32) + GCM tag (16)
+const LEGACY_WRAPPED_LEN: usize = 12 + 32 + 16;
+
 /// Wrapped Vault Key - safe to store in keychain
 #[derive(Clone)]
 pub struct WrappedVaultKey {
+    pub algorithm: WrapAlgorithm,
     pub ciphertext: Vec<u8>,
-    pub nonce: [u8; 12],
+    pub nonce: Vec<u8>,
 }
 
 impl WrappedVaultKey {
-    /// Serialize for keychain storage
+    /// Serialize for keychain storage: version || nonce || ciphertext
     pub fn to_bytes(&self) -> Vec<u8> {
-        let mut bytes = Vec::with_capacity(12 + self.ciphertext.len());
+        let mut bytes = Vec::with_capacity(1 + self.nonce.len() + self.ciphertext.len());
+        if self.algorithm != WrapAlgorithm::LegacyAes256Gcm {
+            bytes.push(self.algorithm as u8);
+        }
         bytes.extend_from_slice(&self.nonce);
         bytes.extend_from_slice(&self.ciphertext);
         bytes
     }
     
-    /// Deserialize from keychain storage
+    /// Deserialize from keychain storage, accepting the unversioned
+    /// legacy layout
     pub fn from_bytes(bytes: &[u8]) -> Result<Self, CryptoError> {
-        if bytes.len() < 12 {
+        let (algorithm, rest) = if bytes.len() == LEGACY_WRAPPED_LEN {
+            (WrapAlgorithm::LegacyAes256Gcm, bytes)
+        } else {
+            let (&version, rest) = bytes.split_first().ok_or(CryptoError::InvalidKeyLength)?;
+            (WrapAlgorithm::from_byte(version)?, rest)
+        };
+        
+        let nonce_len = algorithm.nonce_len();
+        if rest.len() < nonce_len {
             return Err(CryptoError::InvalidKeyLength);
         }
         
-        let mut nonce = [0u8; 12];
-        nonce.copy_from_slice(&bytes[0..12]);
-        let ciphertext = bytes[12..].to_vec();
-        
-        Ok(WrappedVaultKey { ciphertext, nonce })
+        Ok(WrappedVaultKey {
+            algorithm,
+            nonce: rest[..nonce_len].to_vec(),
+            ciphertext: rest[nonce_len..].to_vec(),
+        })
+    }
+    
+    /// True when unlock should re-wrap this key with the current algorithm
+    pub fn needs_rewrap(&self) -> bool {
+        self.algorithm != WrapAlgorithm::CURRENT
     }
 }
 
@@ -187,6 +261,24 @@ pub fn hash_sha256(data: &[u8]) -> String {
     hex::encode(hasher.finalize())
 }
 
+/// Slow (Argon2id, same cost as the KEK) hash of a secondary secret such
+/// as the emergency access credential, as hex
+pub fn hash_secret(secret: &str, salt: &[u8; 16]) -> Result<String, CryptoError> {
+    let key = KEK::derive(secret, salt)?;
+    Ok(hex::encode(key.0))
+}
+
+/// Constant-time equality for MACs, hashes and other secret-derived
+/// values. Length is not secret and short-circuits.
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && bool::from(a.ct_eq(b))
+}
+
+/// Constant-time comparison of two encoded (hex/base64) digests
+pub fn digests_match(a: &str, b: &str) -> bool {
+    constant_time_eq(a.as_bytes(), b.as_bytes())
+}
+
 /// Alias for hash_sha256 - hash arbitrary content
 pub fn hash_content(data: &[u8]) -> String {
     hash_sha256(data)
@@ -207,6 +299,24 @@ pub fn hash_path(path: &str, salt: &[u8]) -> String {
     hex::encode(hasher.finalize())
 }
 
+/// Salted hash of a client identifier for support lookups. Values are
+/// normalized first so "(555) 123-4567" and "555.123.4567" hash alike;
+/// the kind is hashed in so a phone hash never matches a DOB hash.
+pub fn hash_client_identifier(salt: &[u8], kind: crate::models::ClientIdentifierKind, value: &str) -> String {
+    use crate::models::ClientIdentifierKind;
+    let normalized = match kind {
+        ClientIdentifierKind::Phone => value.chars().filter(|c| c.is_ascii_digit()).collect(),
+        ClientIdentifierKind::ClientId => value.trim().to_string(),
+        _ => value.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase(),
+    };
+    let mut hasher = Sha256::new();
+    hasher.update(salt);
+    hasher.update(kind.as_str().as_bytes());
+    hasher.update(b":");
+    hasher.update(normalized.as_bytes());
+    hex::encode(hasher.finalize())
+}
+
 // ============================================
 // Keychain Integration
 // ============================================
@@ -214,6 +324,8 @@ pub fn hash_path(path: &str, salt: &[u8]) -> String {
 const KEYCHAIN_SERVICE: &str = "com.evidify.vault";
 const KEYCHAIN_WRAPPED_KEY: &str = "wrapped_vault_key";
 const KEYCHAIN_SALT: &str = "kdf_salt";
+/// Salt and wrapped key of a passphrase change in progress, in one entry
+const KEYCHAIN_PENDING: &str = "pending_credentials";
 
 /// Store wrapped vault key in OS keychain
 pub fn store_wrapped_key(wrapped: &WrappedVaultKey) -> Result<(), CryptoError> {
@@ -304,9 +416,44 @@ pub fn clear_keychain() -> Result<(), CryptoError> {
         .map_err(|e| CryptoError::Keychain(e.to_string()))?;
     let _ = salt_entry.delete_password(); // Ignore if not found
     
+    clear_pending_credentials();
     Ok(())
 }
 
+/// Stage a new salt and wrapped key in a single entry before the two
+/// real entries are overwritten one at a time. If the change is cut short
+/// between those writes, unlock finishes it from here.
+pub fn stage_credentials(salt: &[u8; 16], wrapped: &WrappedVaultKey) -> Result<(), CryptoError> {
+    let entry = keyring::Entry::new(KEYCHAIN_SERVICE, KEYCHAIN_PENDING)
+        .map_err(|e| CryptoError::Keychain(e.to_string()))?;
+    
+    let mut bytes = salt.to_vec();
+    bytes.extend_from_slice(&wrapped.to_bytes());
+    let encoded = base64::Engine::encode(&base64::engine::general_purpose::STANDARD, &bytes);
+    
+    entry.set_password(&encoded)
+        .map_err(|e| CryptoError::Keychain(e.to_string()))
+}
+
+/// Credentials staged by `stage_credentials`, if a change did not finish
+pub fn retrieve_pending_credentials() -> Option<([u8; 16], WrappedVaultKey)> {
+    let entry = keyring::Entry::new(KEYCHAIN_SERVICE, KEYCHAIN_PENDING).ok()?;
+    let encoded = entry.get_password().ok()?;
+    let bytes = base64::Engine::decode(&base64::engine::general_purpose::STANDARD, &encoded).ok()?;
+    if bytes.len() <= 16 {
+        return None;
+    }
+    let mut salt = [0u8; 16];
+    salt.copy_from_slice(&bytes[..16]);
+    Some((salt, WrappedVaultKey::from_bytes(&bytes[16..]).ok()?))
+}
+
+pub fn clear_pending_credentials() {
+    if let Ok(entry) = keyring::Entry::new(KEYCHAIN_SERVICE, KEYCHAIN_PENDING) {
+        let _ = entry.delete_password(); // Ignore if not found
+    }
+}
+
 // ============================================
 // Token Generation
 // ============================================
@@ -317,10 +464,390 @@ pub fn generate_token(len: usize) -> String {
     hex::encode(&bytes)
 }
 
+// ============================================
+// Envelope Encryption (consultation sharing)
+// ============================================
+
+/// Envelope encryption for handing de-identified material to an outside
+/// consultant. Each envelope uses a fresh ephemeral X25519 key; the shared
+/// secret is expanded with HKDF-SHA256 into a one-time AES-256-GCM key, so
+/// only the holder of the recipient's private key can open it.
+pub mod envelope {
+    use super::CryptoError;
+    use aes_gcm::{
+        aead::{Aead, KeyInit, Payload},
+        Aes256Gcm, Nonce,
+    };
+    use base64::Engine;
+    use hkdf::Hkdf;
+    use rand::RngCore;
+    use serde::{Deserialize, Serialize};
+    use sha2::{Digest, Sha256};
+    use x25519_dalek::{EphemeralSecret, PublicKey, StaticSecret};
+    use zeroize::Zeroizing;
+
+    pub const ENVELOPE_VERSION: u8 = 1;
+    const HKDF_INFO: &[u8] = b"evidify-envelope-v1";
+
+    /// Sealed payload, safe to write to disk or hand to a transport
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct SealedEnvelope {
+        pub version: u8,
+        pub recipient_fingerprint: String,
+        pub content_type: String,          // Bound into the AEAD associated data
+        pub ephemeral_public_key: String,  // base64
+        pub nonce: String,                 // base64
+        pub ciphertext: String,            // base64
+    }
+
+    /// Recipient key pair - generated by the consultant, never by the sender
+    pub struct RecipientKeyPair {
+        secret: StaticSecret,
+        public: PublicKey,
+    }
+
+    impl RecipientKeyPair {
+        pub fn generate() -> Self {
+            let secret = StaticSecret::random_from_rng(rand::rngs::OsRng);
+            let public = PublicKey::from(&secret);
+            RecipientKeyPair { secret, public }
+        }
+
+        pub fn from_secret_bytes(bytes: [u8; 32]) -> Self {
+            let secret = StaticSecret::from(bytes);
+            let public = PublicKey::from(&secret);
+            RecipientKeyPair { secret, public }
+        }
+
+        pub fn public_key(&self) -> [u8; 32] {
+            self.public.to_bytes()
+        }
+
+        pub fn public_key_base64(&self) -> String {
+            encode(self.public.as_bytes())
+        }
+    }
+
+    /// Decode and validate a base64 X25519 public key
+    pub fn parse_public_key(encoded: &str) -> Result<[u8; 32], CryptoError> {
+        let bytes = decode(encoded.trim())?;
+        if bytes.len() != 32 {
+            return Err(CryptoError::InvalidKeyLength);
+        }
+        let mut key = [0u8; 32];
+        key.copy_from_slice(&bytes);
+        if key.iter().all(|b| *b == 0) {
+            return Err(CryptoError::Encryption("All-zero public key".to_string()));
+        }
+        Ok(key)
+    }
+
+    /// Short, human-comparable fingerprint (first 16 bytes of SHA-256, hex)
+    pub fn fingerprint(public_key: &[u8; 32]) -> String {
+        let digest = Sha256::digest(public_key);
+        hex::encode(&digest[..16])
+    }
+
+    /// Encrypt `plaintext` for the holder of `recipient_public_key`
+    pub fn seal(
+        recipient_public_key: &[u8; 32],
+        content_type: &str,
+        plaintext: &[u8],
+    ) -> Result<SealedEnvelope, CryptoError> {
+        let recipient = PublicKey::from(*recipient_public_key);
+        let ephemeral = EphemeralSecret::random_from_rng(rand::rngs::OsRng);
+        let ephemeral_public = PublicKey::from(&ephemeral);
+
+        let shared = ephemeral.diffie_hellman(&recipient);
+        if !shared.was_contributory() {
+            return Err(CryptoError::Encryption("Recipient key is a low-order point".to_string()));
+        }
+
+        let key = derive_key(shared.as_bytes(), ephemeral_public.as_bytes(), recipient_public_key)?;
+        let cipher = Aes256Gcm::new_from_slice(key.as_ref())
+            .map_err(|_| CryptoError::InvalidKeyLength)?;
+
+        let mut nonce_bytes = [0u8; 12];
+        rand::rngs::OsRng.fill_bytes(&mut nonce_bytes);
+
+        let recipient_fingerprint = fingerprint(recipient_public_key);
+        let aad = associated_data(ENVELOPE_VERSION, content_type, &recipient_fingerprint);
+        let ciphertext = cipher
+            .encrypt(Nonce::from_slice(&nonce_bytes), Payload { msg: plaintext, aad: &aad })
+            .map_err(|e| CryptoError::Encryption(e.to_string()))?;
+
+        Ok(SealedEnvelope {
+            version: ENVELOPE_VERSION,
+            recipient_fingerprint,
+            content_type: content_type.to_string(),
+            ephemeral_public_key: encode(ephemeral_public.as_bytes()),
+            nonce: encode(&nonce_bytes),
+            ciphertext: encode(&ciphertext),
+        })
+    }
+
+    /// Decrypt an envelope with the recipient's key pair
+    pub fn open(recipient: &RecipientKeyPair, envelope: &SealedEnvelope) -> Result<Vec<u8>, CryptoError> {
+        if envelope.version != ENVELOPE_VERSION {
+            return Err(CryptoError::Decryption(format!(
+                "Unsupported envelope version {}",
+                envelope.version
+            )));
+        }
+
+        let recipient_public = recipient.public_key();
+        if !super::digests_match(&envelope.recipient_fingerprint, &fingerprint(&recipient_public)) {
+            return Err(CryptoError::Decryption("Envelope was sealed for a different recipient".to_string()));
+        }
+
+        let ephemeral_bytes = parse_public_key(&envelope.ephemeral_public_key)?;
+        let shared = recipient.secret.diffie_hellman(&PublicKey::from(ephemeral_bytes));
+        if !shared.was_contributory() {
+            return Err(CryptoError::Decryption("Ephemeral key is a low-order point".to_string()));
+        }
+
+        let key = derive_key(shared.as_bytes(), &ephemeral_bytes, &recipient_public)?;
+        let cipher = Aes256Gcm::new_from_slice(key.as_ref())
+            .map_err(|_| CryptoError::InvalidKeyLength)?;
+
+        let nonce = decode(&envelope.nonce)?;
+        if nonce.len() != 12 {
+            return Err(CryptoError::Decryption("Invalid nonce length".to_string()));
+        }
+        let ciphertext = decode(&envelope.ciphertext)?;
+        let aad = associated_data(envelope.version, &envelope.content_type, &envelope.recipient_fingerprint);
+
+        cipher
+            .decrypt(Nonce::from_slice(&nonce), Payload { msg: &ciphertext, aad: &aad })
+            .map_err(|_| CryptoError::Decryption("Envelope authentication failed".to_string()))
+    }
+
+    fn derive_key(
+        shared_secret: &[u8; 32],
+        ephemeral_public: &[u8; 32],
+        recipient_public: &[u8; 32],
+    ) -> Result<Zeroizing<[u8; 32]>, CryptoError> {
+        let mut salt = [0u8; 64];
+        salt[..32].copy_from_slice(ephemeral_public);
+        salt[32..].copy_from_slice(recipient_public);
+
+        let hk = Hkdf::<Sha256>::new(Some(&salt), shared_secret);
+        let mut key = Zeroizing::new([0u8; 32]);
+        hk.expand(HKDF_INFO, key.as_mut())
+            .map_err(|e| CryptoError::KeyDerivation(e.to_string()))?;
+        Ok(key)
+    }
+
+    fn associated_data(version: u8, content_type: &str, recipient_fingerprint: &str) -> Vec<u8> {
+        format!("evidify-envelope|{}|{}|{}", version, content_type, recipient_fingerprint).into_bytes()
+    }
+
+    fn encode(bytes: &[u8]) -> String {
+        base64::engine::general_purpose::STANDARD.encode(bytes)
+    }
+
+    fn decode(encoded: &str) -> Result<Vec<u8>, CryptoError> {
+        base64::engine::general_purpose::STANDARD
+            .decode(encoded)
+            .map_err(|e| CryptoError::Decryption(e.to_string()))
+    }
+}
+
+// ============================================
+// Embedding Vector Encryption
+// ============================================
+
+/// Application-layer encryption for embedding vectors. SQLCipher protects
+/// the file at rest, but anything holding the open connection can read raw
+/// vectors and attempt inversion. Vectors are instead sealed with a key per
+/// client ("scope") expanded from the vault key, so a query only derives
+/// and decrypts the partitions it actually searches.
+pub mod vectors {
+    use super::{CryptoError, VaultKey};
+    use aes_gcm::{
+        aead::{Aead, KeyInit, Payload},
+        Aes256Gcm, Nonce,
+    };
+    use hkdf::Hkdf;
+    use rand::RngCore;
+    use sha2::Sha256;
+    use zeroize::Zeroizing;
+
+    pub const VECTOR_FORMAT_VERSION: u8 = 1;
+    const KEYRING_INFO: &[u8] = b"evidify-vectors-v1";
+
+    /// Root of the vector key hierarchy, derived once per unlock
+    pub struct VectorKeyring(Zeroizing<[u8; 32]>);
+
+    /// Key for one client's vectors
+    pub struct ScopeKey {
+        scope: String,
+        cipher: Aes256Gcm,
+    }
+
+    impl VectorKeyring {
+        pub fn from_vault_key(vault_key: &VaultKey) -> Result<Self, CryptoError> {
+            let hk = Hkdf::<Sha256>::new(None, &vault_key.0);
+            let mut root = Zeroizing::new([0u8; 32]);
+            hk.expand(KEYRING_INFO, root.as_mut())
+                .map_err(|e| CryptoError::KeyDerivation(e.to_string()))?;
+            Ok(VectorKeyring(root))
+        }
+
+        /// Key for `scope` (the client ID). Scopes never share a key, so a
+        /// leaked partition key exposes one client's vectors only.
+        pub fn scope_key(&self, scope: &str) -> Result<ScopeKey, CryptoError> {
+            let hk = Hkdf::<Sha256>::new(Some(scope.as_bytes()), self.0.as_ref());
+            let mut key = Zeroizing::new([0u8; 32]);
+            hk.expand(KEYRING_INFO, key.as_mut())
+                .map_err(|e| CryptoError::KeyDerivation(e.to_string()))?;
+            let cipher = Aes256Gcm::new_from_slice(key.as_ref())
+                .map_err(|_| CryptoError::InvalidKeyLength)?;
+            Ok(ScopeKey { scope: scope.to_string(), cipher })
+        }
+    }
+
+    impl ScopeKey {
+        pub fn scope(&self) -> &str {
+            &self.scope
+        }
+
+        /// Seal a serialized vector for row `row_id`.
+        /// Layout: version (1) || nonce (12) || ciphertext+tag
+        pub fn seal(&self, row_id: &str, plaintext: &[u8]) -> Result<Vec<u8>, CryptoError> {
+            let mut nonce_bytes = [0u8; 12];
+            rand::rngs::OsRng.fill_bytes(&mut nonce_bytes);
+
+            let aad = associated_data(VECTOR_FORMAT_VERSION, &self.scope, row_id);
+            let ciphertext = self.cipher
+                .encrypt(Nonce::from_slice(&nonce_bytes), Payload { msg: plaintext, aad: &aad })
+                .map_err(|e| CryptoError::Encryption(e.to_string()))?;
+
+            let mut sealed = Vec::with_capacity(1 + 12 + ciphertext.len());
+            sealed.push(VECTOR_FORMAT_VERSION);
+            sealed.extend_from_slice(&nonce_bytes);
+            sealed.extend_from_slice(&ciphertext);
+            Ok(sealed)
+        }
+
+        /// Open a vector sealed by `seal`. Fails if the blob was moved to
+        /// another row or client, or was sealed under a different vault key.
+        pub fn open(&self, row_id: &str, sealed: &[u8]) -> Result<Vec<u8>, CryptoError> {
+            if sealed.len() < 1 + 12 + 16 {
+                return Err(CryptoError::Decryption("Sealed vector too short".to_string()));
+            }
+            if sealed[0] != VECTOR_FORMAT_VERSION {
+                return Err(CryptoError::Decryption(format!(
+                    "Unsupported vector format version {}",
+                    sealed[0]
+                )));
+            }
+
+            let aad = associated_data(sealed[0], &self.scope, row_id);
+            self.cipher
+                .decrypt(Nonce::from_slice(&sealed[1..13]), Payload { msg: &sealed[13..], aad: &aad })
+                .map_err(|_| CryptoError::Decryption("Vector authentication failed".to_string()))
+        }
+    }
+
+    fn associated_data(version: u8, scope: &str, row_id: &str) -> Vec<u8> {
+        format!("evidify-vector|{}|{}|{}", version, scope, row_id).into_bytes()
+    }
+}
+
+// ============================================
+// Install Signing Key (Ed25519)
+// ============================================
+
+/// Per-install Ed25519 identity for signing material that leaves the
+/// vault (audit slices). The secret lives inside the encrypted vault; the
+/// public key is shared out of band and pinned by whoever verifies.
+pub mod signing {
+    use super::CryptoError;
+    use base64::Engine;
+    use ed25519_dalek::{Signature, Signer, SigningKey, VerifyingKey};
+
+    pub struct InstallSigningKey(SigningKey);
+
+    impl InstallSigningKey {
+        pub fn generate() -> Self {
+            InstallSigningKey(SigningKey::generate(&mut rand::rngs::OsRng))
+        }
+
+        pub fn from_secret_bytes(bytes: [u8; 32]) -> Self {
+            InstallSigningKey(SigningKey::from_bytes(&bytes))
+        }
+
+        pub fn secret_bytes(&self) -> [u8; 32] {
+            self.0.to_bytes()
+        }
+
+        pub fn public_key_base64(&self) -> String {
+            base64::engine::general_purpose::STANDARD.encode(self.0.verifying_key().as_bytes())
+        }
+
+        /// Base64 signature over `message`
+        pub fn sign(&self, message: &[u8]) -> String {
+            base64::engine::general_purpose::STANDARD.encode(self.0.sign(message).to_bytes())
+        }
+    }
+
+    fn parse_public_key(encoded: &str) -> Result<VerifyingKey, CryptoError> {
+        let bytes: [u8; 32] = base64::engine::general_purpose::STANDARD
+            .decode(encoded.trim())
+            .ok()
+            .and_then(|b| b.try_into().ok())
+            .ok_or(CryptoError::InvalidKeyLength)?;
+        VerifyingKey::from_bytes(&bytes).map_err(|e| CryptoError::Decryption(e.to_string()))
+    }
+
+    /// Fingerprint of a base64 public key, same format as envelope keys
+    pub fn fingerprint(public_key: &str) -> Result<String, CryptoError> {
+        Ok(super::envelope::fingerprint(parse_public_key(public_key)?.as_bytes()))
+    }
+
+    /// Verify a base64 signature; strict verification rejects malleable
+    /// and small-order-key signatures
+    pub fn verify(public_key: &str, message: &[u8], signature: &str) -> Result<(), CryptoError> {
+        let key = parse_public_key(public_key)?;
+        let sig_bytes: [u8; 64] = base64::engine::general_purpose::STANDARD
+            .decode(signature.trim())
+            .ok()
+            .and_then(|b| b.try_into().ok())
+            .ok_or_else(|| CryptoError::Decryption("Malformed signature".to_string()))?;
+        key.verify_strict(message, &Signature::from_bytes(&sig_bytes))
+            .map_err(|_| CryptoError::Decryption("Signature verification failed".to_string()))?;
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     
+    #[test]
+    fn test_client_identifier_hash_normalizes() {
+        use crate::models::ClientIdentifierKind;
+        let salt = [7u8; 16];
+        assert_eq!(
+            hash_client_identifier(&salt, ClientIdentifierKind::Phone, "(555) 123-4567"),
+            hash_client_identifier(&salt, ClientIdentifierKind::Phone, "555.123.4567"),
+        );
+        assert_eq!(
+            hash_client_identifier(&salt, ClientIdentifierKind::Email, " Jane@Example.org "),
+            hash_client_identifier(&salt, ClientIdentifierKind::Email, "jane@example.org"),
+        );
+        // Same value under a different kind or salt does not collide
+        assert_ne!(
+            hash_client_identifier(&salt, ClientIdentifierKind::DisplayName, "5551234567"),
+            hash_client_identifier(&salt, ClientIdentifierKind::Phone, "5551234567"),
+        );
+        assert_ne!(
+            hash_client_identifier(&[8u8; 16], ClientIdentifierKind::Phone, "5551234567"),
+            hash_client_identifier(&salt, ClientIdentifierKind::Phone, "5551234567"),
+        );
+    }
+    
     #[test]
     fn test_key_wrap_unwrap() {
         let passphrase = "test_passphrase_123";
@@ -353,4 +880,78 @@ mod tests {
         let kek2 = KEK::derive("wrong_passphrase", &salt).unwrap();
         assert!(kek2.unwrap(&wrapped).is_err());
     }
+    
+    #[test]
+    fn test_wrapped_key_versioning() {
+        let salt = generate_salt();
+        let kek = KEK::derive("passphrase", &salt).unwrap();
+        let vault_key = VaultKey::generate();
+        
+        let wrapped = kek.wrap(&vault_key).unwrap();
+        let bytes = wrapped.to_bytes();
+        assert_eq!(bytes[0], WrapAlgorithm::CURRENT as u8);
+        let parsed = WrappedVaultKey::from_bytes(&bytes).unwrap();
+        assert!(!parsed.needs_rewrap());
+        assert_eq!(kek.unwrap(&parsed).unwrap().as_hex(), vault_key.as_hex());
+        
+        // Unversioned entry written before the version byte existed
+        let cipher = Aes256Gcm::new_from_slice(&kek.0).unwrap();
+        let nonce = [9u8; 12];
+        let mut legacy = nonce.to_vec();
+        legacy.extend(cipher.encrypt(Nonce::from_slice(&nonce), vault_key.0.as_ref()).unwrap());
+        let parsed = WrappedVaultKey::from_bytes(&legacy).unwrap();
+        assert_eq!(parsed.algorithm, WrapAlgorithm::LegacyAes256Gcm);
+        assert!(parsed.needs_rewrap());
+        assert_eq!(kek.unwrap(&parsed).unwrap().as_hex(), vault_key.as_hex());
+        
+        // Relabelling a v1 key as legacy fails authentication; unknown versions are refused
+        let mut relabelled = WrappedVaultKey::from_bytes(&bytes).unwrap();
+        relabelled.algorithm = WrapAlgorithm::LegacyAes256Gcm;
+        assert!(kek.unwrap(&relabelled).is_err());
+        let mut unknown = bytes.clone();
+        unknown[0] = 0x7f;
+        assert!(matches!(WrappedVaultKey::from_bytes(&unknown), Err(CryptoError::UnsupportedAlgorithm(0x7f))));
+    }
+    
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(digests_match(&hash_sha256(b"a"), &hash_sha256(b"a")));
+        assert!(!digests_match(&hash_sha256(b"a"), &hash_sha256(b"b")));
+        assert!(!constant_time_eq(b"abc", b"abcd"));
+    }
+    
+    #[test]
+    fn test_envelope_round_trip() {
+        let recipient = envelope::RecipientKeyPair::generate();
+        let sealed = envelope::seal(&recipient.public_key(), "consultation_draft", b"de-identified text").unwrap();
+        
+        let opened = envelope::open(&recipient, &sealed).unwrap();
+        assert_eq!(opened, b"de-identified text");
+        
+        // A different recipient cannot open it
+        let other = envelope::RecipientKeyPair::generate();
+        assert!(envelope::open(&other, &sealed).is_err());
+    }
+    
+    #[test]
+    fn test_envelope_rejects_tampered_content_type() {
+        let recipient = envelope::RecipientKeyPair::generate();
+        let mut sealed = envelope::seal(&recipient.public_key(), "consultation_draft", b"payload").unwrap();
+        sealed.content_type = "other".to_string();
+        assert!(envelope::open(&recipient, &sealed).is_err());
+    }
+    
+    #[test]
+    fn test_vector_seal_is_scoped() {
+        let keyring = vectors::VectorKeyring::from_vault_key(&VaultKey([3u8; 32])).unwrap();
+        let alice = keyring.scope_key("client-a").unwrap();
+        let sealed = alice.seal("row-1", &[1, 2, 3, 4]).unwrap();
+        assert_eq!(alice.open("row-1", &sealed).unwrap(), vec![1, 2, 3, 4]);
+        
+        // Another client's key, another row, or another vault cannot open it
+        assert!(keyring.scope_key("client-b").unwrap().open("row-1", &sealed).is_err());
+        assert!(alice.open("row-2", &sealed).is_err());
+        let other_vault = vectors::VectorKeyring::from_vault_key(&VaultKey([4u8; 32])).unwrap();
+        assert!(other_vault.scope_key("client-a").unwrap().open("row-1", &sealed).is_err());
+    }
 }
@@ -160,6 +160,17 @@ pub struct ConsultationDraft {
     pub updated_at: i64,
 }
 
+/// Consultant public key the clinician has explicitly chosen to trust
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrustedRecipient {
+    pub id: String,
+    pub label: String,
+    pub public_key: String,   // base64 X25519
+    pub fingerprint: String,
+    pub created_at: i64,
+    pub revoked_at: Option<i64>,
+}
+
 // ============================================
 // Regex Patterns for 18 Identifiers
 // ============================================
@@ -175,8 +186,19 @@ lazy_static! {
         r"(?i)\d{1,5}\s+[\w\s]{1,30}\s+(street|st|avenue|ave|road|rd|boulevard|blvd|drive|dr|lane|ln|way|court|ct|place|pl|circle|cir)\b"
     ).unwrap();
     
-    static ref CITY_STATE: Regex = Regex::new(
-        r"(?i)\b[A-Z][a-z]+(\s+[A-Z][a-z]+)?,\s*[A-Z]{2}\b"
+    // "City, ST" / "City, State" - only when the second part is a real state,
+    // so the city can be dropped and the state (permitted) kept
+    static ref CITY_STATE: Regex = {
+        let mut states: Vec<&str> = US_STATES.iter().flat_map(|(code, name)| [*code, *name]).collect();
+        states.sort_by_key(|s| std::cmp::Reverse(s.len()));
+        Regex::new(&format!(
+            r"(?i)\b([A-Z][a-z]+(?:\s+[A-Z][a-z]+)?),\s*({})\b",
+            states.join("|")
+        )).unwrap()
+    };
+    
+    static ref COUNTY: Regex = Regex::new(
+        r"\b[A-Z][a-z]+(?:\s+[A-Z][a-z]+)?\s+(County|Parish|Borough)\b"
     ).unwrap();
     
     static ref ZIP_CODE: Regex = Regex::new(
@@ -301,11 +323,44 @@ lazy_static! {
         "carol", "amanda", "melissa", "deborah", "stephanie", "rebecca", "sharon", "laura", "cynthia", "kathleen"
     ];
     
-    // Restricted ZIP codes (population <= 20,000)
-    static ref RESTRICTED_ZIPS: Vec<&'static str> = vec![
-        "036", "059", "063", "102", "203", "556", "692", "790", "821", "823", 
-        "830", "831", "878", "879", "884", "890", "893"
-    ];
+}
+
+/// 3-digit ZIP prefixes whose combined population is 20,000 or fewer.
+/// Safe Harbor requires these to be reported as "000". Union of the HHS
+/// lists derived from the 2000 and 2010 Census, so a prefix restricted in
+/// either vintage stays restricted.
+pub const RESTRICTED_ZIP3: &[&str] = &[
+    "036", "059", "063", "102", "203", "205", "369", "556", "692", "753",
+    "772", "790", "821", "823", "830", "831", "878", "879", "884", "890",
+    "893",
+];
+
+/// State-level geography is permitted under Safe Harbor and is kept
+const US_STATES: &[(&str, &str)] = &[
+    ("AL", "Alabama"), ("AK", "Alaska"), ("AZ", "Arizona"), ("AR", "Arkansas"),
+    ("CA", "California"), ("CO", "Colorado"), ("CT", "Connecticut"), ("DE", "Delaware"),
+    ("DC", "District of Columbia"), ("FL", "Florida"), ("GA", "Georgia"), ("HI", "Hawaii"),
+    ("ID", "Idaho"), ("IL", "Illinois"), ("IN", "Indiana"), ("IA", "Iowa"),
+    ("KS", "Kansas"), ("KY", "Kentucky"), ("LA", "Louisiana"), ("ME", "Maine"),
+    ("MD", "Maryland"), ("MA", "Massachusetts"), ("MI", "Michigan"), ("MN", "Minnesota"),
+    ("MS", "Mississippi"), ("MO", "Missouri"), ("MT", "Montana"), ("NE", "Nebraska"),
+    ("NV", "Nevada"), ("NH", "New Hampshire"), ("NJ", "New Jersey"), ("NM", "New Mexico"),
+    ("NY", "New York"), ("NC", "North Carolina"), ("ND", "North Dakota"), ("OH", "Ohio"),
+    ("OK", "Oklahoma"), ("OR", "Oregon"), ("PA", "Pennsylvania"), ("RI", "Rhode Island"),
+    ("SC", "South Carolina"), ("SD", "South Dakota"), ("TN", "Tennessee"), ("TX", "Texas"),
+    ("UT", "Utah"), ("VT", "Vermont"), ("VA", "Virginia"), ("WA", "Washington"),
+    ("WV", "West Virginia"), ("WI", "Wisconsin"), ("WY", "Wyoming"), ("PR", "Puerto Rico"),
+];
+
+/// Safe Harbor 3-digit ZIP: the first three digits, or "000" when the
+/// prefix covers 20,000 people or fewer
+pub fn safe_harbor_zip3(zip: &str) -> String {
+    let prefix: String = zip.chars().take_while(|c| c.is_ascii_digit()).take(3).collect();
+    if prefix.len() < 3 || RESTRICTED_ZIP3.contains(&prefix.as_str()) {
+        "000".to_string()
+    } else {
+        prefix
+    }
 }
 
 // ============================================
@@ -349,9 +404,9 @@ impl DeidentificationEngine {
         // Remove overlapping detections (keep highest confidence)
         identifiers = Self::remove_overlaps(identifiers);
         
-        // Apply replacements
+        // Apply replacements back to front so earlier offsets stay valid
         let mut deidentified = text.to_string();
-        for id in &identifiers {
+        for id in identifiers.iter().rev() {
             // Use safe replacement to avoid UTF-8 boundary panics
             Self::safe_replace_range(&mut deidentified, id.start_pos, id.end_pos, &id.replacement);
         }
@@ -565,28 +620,38 @@ impl DeidentificationEngine {
             });
         }
         
-        // City, State patterns
-        for cap in CITY_STATE.find_iter(text) {
+        // City, State patterns - generalize to state level (states are permitted)
+        for cap in CITY_STATE.captures_iter(text) {
+            let (Some(full), Some(city), Some(state)) = (cap.get(0), cap.get(1), cap.get(2)) else {
+                continue;
+            };
+            results.push(DetectedIdentifier {
+                category: IdentifierCategory::Geographic,
+                original_text: full.as_str().to_string(),
+                start_pos: city.start(),
+                end_pos: full.end(),
+                replacement: format!("[CITY], {}", state.as_str()),
+                confidence: 0.85,
+                detection_method: "regex".to_string(),
+            });
+        }
+        
+        // Counties are subdivisions smaller than a state
+        for cap in COUNTY.find_iter(text) {
             results.push(DetectedIdentifier {
                 category: IdentifierCategory::Geographic,
                 original_text: cap.as_str().to_string(),
                 start_pos: cap.start(),
                 end_pos: cap.end(),
-                replacement: "[CITY, STATE]".to_string(),
-                confidence: 0.85,
+                replacement: "[COUNTY]".to_string(),
+                confidence: 0.9,
                 detection_method: "regex".to_string(),
             });
         }
         
-        // ZIP codes - check for restricted ZIPs
+        // ZIP codes - truncate to 3 digits, zeroing restricted prefixes
         for cap in ZIP_CODE.find_iter(text) {
-            let zip = cap.as_str();
-            let first_three = &zip[..3];
-            let replacement = if RESTRICTED_ZIPS.contains(&first_three) {
-                "[ZIP 000XX]".to_string()  // Must set to 000 for small populations
-            } else {
-                format!("[ZIP {}XX]", first_three)  // Keep first 3 digits if pop > 20k
-            };
+            let replacement = format!("[ZIP {}XX]", safe_harbor_zip3(cap.as_str()));
             
             results.push(DetectedIdentifier {
                 category: IdentifierCategory::Geographic,
@@ -949,6 +1014,461 @@ impl DeidentificationEngine {
     }
 }
 
+// ============================================
+// Structure-Preserving De-identification
+// ============================================
+//
+// Markdown and HTML are de-identified in place: markup bytes are masked
+// with spaces (same byte length, so offsets carry over), the masked text
+// runs through the normal detectors, and each replacement is written back
+// into the text bytes only. Headings, lists, emphasis and tags survive.
+// HTML text is matched with character references decoded (`&#74;ohn` is
+// "John"); each decoded byte remembers the source range it came from.
+
+/// Input format for `deidentify_document`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DocumentFormat {
+    PlainText,
+    Markdown,
+    Html,
+}
+
+impl DocumentFormat {
+    pub fn from_name(s: &str) -> Self {
+        match s.to_lowercase().as_str() {
+            "markdown" | "md" => DocumentFormat::Markdown,
+            "html" | "htm" => DocumentFormat::Html,
+            _ => DocumentFormat::PlainText,
+        }
+    }
+}
+
+lazy_static! {
+    static ref MD_LINE_PREFIX: Regex = Regex::new(
+        r"^[ \t]*(?:>[ \t]?)*(?:#{1,6}[ \t]+|(?:[-*+]|\d{1,9}[.)])[ \t]+(?:\[[ xX]\][ \t]+)?)?"
+    ).unwrap();
+    static ref MD_RULE_OR_FENCE: Regex = Regex::new(
+        r"^[ \t]*(?:```|~~~|(?:[-*_][ \t]*){3,}$|\|?[ \t]*:?-{3,}:?[ \t]*(?:\|[ \t]*:?-{3,}:?[ \t]*)*\|?[ \t]*$)"
+    ).unwrap();
+    static ref MD_LINK: Regex = Regex::new(r"!?\[[^\]\n]*\]\([^)\n]*\)").unwrap();
+}
+
+fn mark(mask: &mut [bool], start: usize, end: usize) {
+    mask[start..end].iter_mut().for_each(|m| *m = true);
+}
+
+/// Byte mask of Markdown syntax (true = markup)
+fn markdown_markup(text: &str) -> Vec<bool> {
+    let mut mask = vec![false; text.len()];
+    let mut in_code_block = false;
+    let mut offset = 0;
+    
+    for line in text.split_inclusive('\n') {
+        let body = line.trim_end_matches(['\n', '\r']);
+        let line_start = offset;
+        offset += line.len();
+        
+        if let Some(m) = MD_RULE_OR_FENCE.find(body) {
+            let trimmed = body.trim_start();
+            if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+                in_code_block = !in_code_block;
+                mark(&mut mask, line_start, line_start + body.len());
+                continue;
+            }
+            if !in_code_block && m.end() == body.len() {
+                mark(&mut mask, line_start, line_start + body.len());
+                continue;
+            }
+        }
+        if in_code_block {
+            continue;
+        }
+        
+        let prefix_len = MD_LINE_PREFIX.find(body).map(|m| m.end()).unwrap_or(0);
+        mark(&mut mask, line_start, line_start + prefix_len);
+        
+        // Link/image brackets are markup; link text and URL stay text
+        for link in MD_LINK.find_iter(body) {
+            let s = link.as_str();
+            let open = if s.starts_with('!') { 2 } else { 1 };
+            let close = s.find("](").unwrap_or(0);
+            mark(&mut mask, line_start + link.start(), line_start + link.start() + open);
+            mark(&mut mask, line_start + link.start() + close, line_start + link.start() + close + 2);
+            mark(&mut mask, line_start + link.end() - 1, line_start + link.end());
+        }
+        
+        // Emphasis, strikethrough, inline code and table pipes. Single
+        // underscores are left alone: they're common inside words.
+        let bytes = body.as_bytes();
+        let mut i = prefix_len;
+        while i < bytes.len() {
+            let run = match bytes[i] {
+                b'*' | b'`' | b'|' => 1,
+                b'_' | b'~' if bytes.get(i + 1) == Some(&bytes[i]) => 2,
+                _ => 0,
+            };
+            if run > 0 && (i == 0 || bytes[i - 1] != b'\\') {
+                mark(&mut mask, line_start + i, line_start + i + run);
+                i += run;
+            } else {
+                i += 1;
+            }
+        }
+    }
+    
+    mask
+}
+
+/// Byte mask of HTML markup (true = markup). Attribute values, quoted or
+/// not, and comment bodies count as text: alt/title/href can carry identifiers.
+fn html_markup(text: &str) -> Vec<bool> {
+    let bytes = text.as_bytes();
+    let mut mask = vec![false; bytes.len()];
+    let mut i = 0;
+    
+    while i < bytes.len() {
+        if text[i..].starts_with("<!--") {
+            let close = text[i + 4..].find("-->").map(|p| i + 4 + p);
+            mark(&mut mask, i, i + 4);
+            match close {
+                Some(c) => {
+                    mark(&mut mask, c, c + 3);
+                    i = c + 3;
+                }
+                None => break,
+            }
+            continue;
+        }
+        
+        let opens_tag = bytes[i] == b'<'
+            && bytes.get(i + 1).map(|b| b.is_ascii_alphabetic() || *b == b'/' || *b == b'!').unwrap_or(false);
+        if !opens_tag {
+            i += 1;
+            continue;
+        }
+        
+        // Inside a tag: everything is markup except attribute values
+        let mut quote: Option<u8> = None;
+        let mut after_equals = false;
+        let mut unquoted = false;
+        while i < bytes.len() {
+            let b = bytes[i];
+            if let Some(q) = quote {
+                if b == q {
+                    quote = None;
+                    mask[i] = true;
+                }
+                i += 1;
+                continue;
+            }
+            if unquoted {
+                if !(b.is_ascii_whitespace() || b == b'>') {
+                    i += 1;
+                    continue;
+                }
+                unquoted = false;
+            }
+            match b {
+                b'"' | b'\'' => {
+                    quote = Some(b);
+                    after_equals = false;
+                }
+                b'=' => after_equals = true,
+                b'>' => {
+                    mask[i] = true;
+                    i += 1;
+                    break;
+                }
+                _ if b.is_ascii_whitespace() => {}
+                _ if after_equals => {
+                    // Unquoted value runs to the next space or '>'
+                    unquoted = true;
+                    after_equals = false;
+                    i += 1;
+                    continue;
+                }
+                _ => after_equals = false,
+            }
+            mask[i] = true;
+            i += 1;
+        }
+    }
+    
+    mask
+}
+
+/// Decode the HTML character reference at the start of `s`, returning the
+/// character and the reference's byte length
+fn decode_char_ref(s: &str) -> Option<(char, usize)> {
+    let rest = s.strip_prefix('&')?;
+    if let Some(num) = rest.strip_prefix('#') {
+        let (digits, radix, prefix_len) = match num.strip_prefix(['x', 'X']) {
+            Some(hex) => (hex, 16, 3),
+            None => (num, 10, 2),
+        };
+        let len = digits.chars().take_while(|c| c.is_digit(radix)).count();
+        if len == 0 || len > 8 {
+            return None;
+        }
+        let ch = char::from_u32(u32::from_str_radix(&digits[..len], radix).ok()?)?;
+        // Browsers decode numeric references without the semicolon too
+        let semicolon = usize::from(digits[len..].starts_with(';'));
+        return Some((ch, prefix_len + len + semicolon));
+    }
+    let name_len = rest.find(';')?;
+    let ch = match &rest[..name_len] {
+        "amp" => '&',
+        "lt" => '<',
+        "gt" => '>',
+        "quot" => '"',
+        "apos" => '\'',
+        "nbsp" => ' ',
+        _ => return None,
+    };
+    Some((ch, name_len + 2))
+}
+
+/// HTML text to run the detectors over: markup blanked, character
+/// references decoded. Also returns the source byte range of each output byte.
+fn html_detection_text(text: &str, mask: &[bool]) -> (String, Vec<(usize, usize)>) {
+    let mut out = String::with_capacity(text.len());
+    let mut origin = Vec::with_capacity(text.len());
+    let mut i = 0;
+    while i < text.len() {
+        let (ch, len) = if mask[i] {
+            (' ', 1)
+        } else if let Some(decoded) = decode_char_ref(&text[i..]) {
+            decoded
+        } else {
+            let ch = text[i..].chars().next().unwrap_or(' ');
+            (ch, ch.len_utf8())
+        };
+        out.push(ch);
+        origin.resize(out.len(), (i, i + len));
+        i += len;
+    }
+    (out, origin)
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+impl DeidentificationEngine {
+    /// De-identify a Markdown or HTML document, replacing identifiers in
+    /// text only so the document structure is kept
+    pub fn deidentify_document(&self, text: &str, format: DocumentFormat) -> DeidentificationResult {
+        let mask = match format {
+            DocumentFormat::PlainText => return self.deidentify(text),
+            DocumentFormat::Markdown => markdown_markup(text),
+            DocumentFormat::Html => html_markup(text),
+        };
+        
+        let mut result = if format == DocumentFormat::Html {
+            let (decoded, origin) = html_detection_text(text, &mask);
+            let mut result = self.deidentify(&decoded);
+            for id in result.identifiers_found.iter_mut() {
+                if id.start_pos < id.end_pos && id.end_pos <= origin.len() {
+                    id.start_pos = origin[id.start_pos].0;
+                    id.end_pos = origin[id.end_pos - 1].1;
+                }
+            }
+            result
+        } else {
+            // Same byte length as `text`, so detector offsets index both.
+            // Markup is always masked a whole character at a time.
+            let masked = text.bytes().zip(&mask).map(|(b, hidden)| if *hidden { b' ' } else { b }).collect();
+            let Ok(masked) = String::from_utf8(masked) else {
+                return self.deidentify(text);
+            };
+            self.deidentify(&masked)
+        };
+        
+        let mut output = text.to_string();
+        for id in result.identifiers_found.iter_mut().rev() {
+            if id.end_pos > text.len() || !text.is_char_boundary(id.start_pos) || !text.is_char_boundary(id.end_pos) {
+                continue;
+            }
+            
+            // Text runs inside the identifier span, separated by markup
+            let mut runs: Vec<(usize, usize)> = Vec::new();
+            for (pos, hidden) in mask.iter().enumerate().take(id.end_pos).skip(id.start_pos) {
+                if *hidden {
+                    continue;
+                }
+                match runs.last_mut() {
+                    Some(run) if run.1 == pos => run.1 = pos + 1,
+                    _ => runs.push((pos, pos + 1)),
+                }
+            }
+            let Some(target) = runs.iter().enumerate().max_by_key(|(i, r)| (r.1 - r.0, std::cmp::Reverse(*i))).map(|(i, _)| i) else {
+                continue;
+            };
+            
+            let replacement = if format == DocumentFormat::Html {
+                escape_html(&id.replacement)
+            } else {
+                id.replacement.clone()
+            };
+            
+            let mut span = String::new();
+            let mut cursor = id.start_pos;
+            for (i, (start, end)) in runs.iter().enumerate() {
+                span.push_str(&text[cursor..*start]);
+                if i == target {
+                    span.push_str(&replacement);
+                }
+                cursor = *end;
+            }
+            span.push_str(&text[cursor..id.end_pos]);
+            
+            output.replace_range(id.start_pos..id.end_pos, &span);
+            id.original_text = text[id.start_pos..id.end_pos].to_string();
+        }
+        
+        result.original_hash = Self::compute_hash(text);
+        result.deidentified_hash = Self::compute_hash(&output);
+        result.deidentified_text = output;
+        result
+    }
+}
+
+// ============================================
+// Certificate Artifact
+// ============================================
+//
+// Every de-identified export ships with a standalone JSON certificate:
+// method, per-category counts, text hashes and engine version. It is
+// hashed over its canonical JSON (sorted keys, minified) with
+// certificate_sha256 blanked and signatures empty, signed over the same
+// bytes with the install key, and the exported document cites it by that
+// hash. Neither the certificate nor its hashes carry any identifier text.
+
+pub const DEID_CERTIFICATE_SCHEMA_VERSION: &str = "evidify.deid_certificate.v1";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeidentificationCertificate {
+    pub schema_version: String,
+    pub certificate_id: String,
+    /// RFC 3339, UTC
+    pub issued_at: String,
+    /// "hipaa_safe_harbor"
+    pub method: String,
+    pub regulation: String,
+    pub engine: String,
+    pub engine_version: String,
+    pub ai_enhanced: bool,
+    /// "txt", "pdf", "docx", ...
+    pub export_format: String,
+    pub original_sha256: String,
+    pub deidentified_sha256: String,
+    /// Safe Harbor category code ("A".."R", "AI") to identifiers removed
+    pub category_counts: std::collections::BTreeMap<String, i32>,
+    pub identifiers_removed: usize,
+    pub safe_harbor_compliant: bool,
+    pub certificate_sha256: String,
+    pub signatures: Vec<CertificateSignature>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CertificateSignature {
+    pub algorithm: String,
+    pub key_fingerprint: String,
+    pub public_key: String,
+    pub signature: String,
+}
+
+/// Certificate ready to save next to the export
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CertificateFile {
+    pub filename: String,
+    pub content: String,
+    pub certificate_sha256: String,
+}
+
+impl DeidentificationCertificate {
+    /// Unsealed certificate for `result`
+    pub fn build(result: &DeidentificationResult, export_format: &str, ai_enhanced: bool) -> Self {
+        DeidentificationCertificate {
+            schema_version: DEID_CERTIFICATE_SCHEMA_VERSION.to_string(),
+            certificate_id: uuid::Uuid::new_v4().to_string(),
+            issued_at: chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+            method: "hipaa_safe_harbor".to_string(),
+            regulation: "45 CFR 164.514(b)(2)".to_string(),
+            engine: "evidify-deidentify".to_string(),
+            engine_version: env!("CARGO_PKG_VERSION").to_string(),
+            ai_enhanced,
+            export_format: export_format.to_string(),
+            original_sha256: result.original_hash.clone(),
+            deidentified_sha256: result.deidentified_hash.clone(),
+            category_counts: result.category_counts.iter().map(|(k, v)| (k.clone(), *v)).collect(),
+            identifiers_removed: result.identifiers_found.len(),
+            safe_harbor_compliant: result.safe_harbor_compliant,
+            certificate_sha256: String::new(),
+            signatures: Vec::new(),
+        }
+    }
+
+    /// Canonical bytes covered by certificate_sha256 and the signatures
+    pub fn signing_payload(&self) -> Result<Vec<u8>, String> {
+        let mut unsigned = self.clone();
+        unsigned.certificate_sha256 = String::new();
+        unsigned.signatures.clear();
+        let value = serde_json::to_value(&unsigned).map_err(|e| e.to_string())?;
+        evidify_canonicalization::try_canonical_bytes(&value).map_err(|e| e.to_string())
+    }
+
+    /// Fill in certificate_sha256 and sign with the install key
+    pub fn seal(mut self, key: &crate::crypto::signing::InstallSigningKey) -> Result<Self, String> {
+        let payload = self.signing_payload()?;
+        let public_key = key.public_key_base64();
+        self.certificate_sha256 = evidify_canonicalization::sha256_hex(&payload);
+        self.signatures = vec![CertificateSignature {
+            algorithm: "ed25519".to_string(),
+            key_fingerprint: crate::crypto::signing::fingerprint(&public_key).map_err(|e| e.to_string())?,
+            public_key,
+            signature: key.sign(&payload),
+        }];
+        Ok(self)
+    }
+
+    /// Check the hash and every signature, and that one of them is by the
+    /// install key with `expected_fingerprint`. The embedded public keys
+    /// alone prove nothing: anyone can re-seal an edited certificate with
+    /// a key of their own. Err names the first failure.
+    pub fn verify(&self, expected_fingerprint: &str) -> Result<(), String> {
+        let payload = self.signing_payload()?;
+        if !crate::crypto::digests_match(&self.certificate_sha256, &evidify_canonicalization::sha256_hex(&payload)) {
+            return Err("Certificate hash does not match its contents".to_string());
+        }
+        if self.signatures.is_empty() {
+            return Err("Certificate is not signed".to_string());
+        }
+        let expected = expected_fingerprint.trim().to_lowercase().replace([':', ' '], "");
+        let mut pinned = false;
+        for sig in &self.signatures {
+            crate::crypto::signing::verify(&sig.public_key, &payload, &sig.signature)
+                .map_err(|e| format!("Signature by {}: {}", sig.key_fingerprint, e))?;
+            let fingerprint = crate::crypto::signing::fingerprint(&sig.public_key).map_err(|e| e.to_string())?;
+            pinned |= crate::crypto::digests_match(&fingerprint, &expected);
+        }
+        if !pinned {
+            return Err("Certificate was not signed by the expected install key".to_string());
+        }
+        Ok(())
+    }
+
+    /// Pretty-printed file named after the export it accompanies
+    pub fn to_file(&self, export_stem: &str) -> Result<CertificateFile, String> {
+        Ok(CertificateFile {
+            filename: format!("{}.deid-certificate.json", export_stem),
+            content: serde_json::to_string_pretty(self).map_err(|e| e.to_string())?,
+            certificate_sha256: self.certificate_sha256.clone(),
+        })
+    }
+}
+
 // ============================================
 // AI-Enhanced Detection (Ollama Integration)
 // ============================================
@@ -1015,7 +1535,7 @@ Return ONLY the JSON array, no other text. If none found, return []"#, text);
 #[cfg(test)]
 mod tests {
     use super::*;
-    
+
     #[test]
     fn test_phone_detection() {
         let engine = DeidentificationEngine::new(false, None);
@@ -1072,4 +1592,89 @@ mod tests {
         
         assert!(result.deidentified_text.contains("90+"));
     }
+    
+    #[test]
+    fn test_markdown_structure_preserved() {
+        let engine = DeidentificationEngine::new(false, None);
+        let text = "## Contact\n- Phone: **555-123-4567**\n- Seen by Dr. **Smith**.\n\n| Field | Value |\n|---|---|\n| Email | john.doe@email.com |\n";
+        let result = engine.deidentify_document(text, DocumentFormat::Markdown);
+        
+        assert!(result.deidentified_text.starts_with("## Contact\n- Phone: **[PHONE]**\n"));
+        assert!(result.deidentified_text.contains("- Seen by **[NAME]**."));
+        assert!(result.deidentified_text.contains("|---|---|\n| Email | [EMAIL] |"));
+        assert!(!result.deidentified_text.contains("Smith"));
+    }
+    
+    #[test]
+    fn test_html_text_and_attributes_deidentified() {
+        let engine = DeidentificationEngine::new(false, None);
+        let text = r#"<h2>Intake</h2><ul><li>SSN: <b>123-45-6789</b></li></ul><a href="mailto:john.doe@email.com">email</a>"#;
+        let result = engine.deidentify_document(text, DocumentFormat::Html);
+        
+        assert_eq!(
+            result.deidentified_text,
+            r#"<h2>Intake</h2><ul><li>SSN: <b>[SSN]</b></li></ul><a href="mailto:[EMAIL]">email</a>"#
+        );
+    }
+    
+    #[test]
+    fn test_html_entities_and_unquoted_attributes_deidentified() {
+        let engine = DeidentificationEngine::new(false, None);
+        let text = "<p>Call 555&#45;123-4567 or j&#x6F;hn.doe&#64;email.com</p><img alt=jane.roe@email.com src=scan.png>";
+        let result = engine.deidentify_document(text, DocumentFormat::Html);
+        
+        assert_eq!(
+            result.deidentified_text,
+            "<p>Call [PHONE] or [EMAIL]</p><img alt=[EMAIL] src=scan.png>"
+        );
+        assert!(result.identifiers_found.iter().any(|id| id.original_text == "j&#x6F;hn.doe&#64;email.com"));
+    }
+    
+    #[test]
+    fn test_geographic_generalization() {
+        assert_eq!(safe_harbor_zip3("62701-1234"), "627");
+        assert_eq!(safe_harbor_zip3("82301"), "000");
+        
+        let engine = DeidentificationEngine::new(false, None);
+        let text = "Moved from Springfield, IL 62701 to Cook County, then Albany, New York.";
+        let result = engine.deidentify(text);
+        
+        assert!(result.deidentified_text.contains("[CITY], IL [ZIP 627XX]"));
+        assert!(result.deidentified_text.contains("[CITY], New York"));
+        assert!(result.deidentified_text.contains("[COUNTY]"));
+        assert!(!result.deidentified_text.contains("Springfield"));
+        assert!(!result.deidentified_text.contains("Albany"));
+        
+        // Typed without capitals
+        let result = engine.deidentify("moved from springfield, il in march");
+        assert!(result.deidentified_text.contains("[CITY], il"));
+        assert!(!result.deidentified_text.contains("springfield"));
+    }
+    
+    #[test]
+    fn test_certificate_seal_and_verify() {
+        let engine = DeidentificationEngine::new(false, None);
+        let result = engine.deidentify("Jane Smith called from 555-123-4567 on 03/05/2024.");
+        let key = crate::crypto::signing::InstallSigningKey::generate();
+        let fingerprint = crate::crypto::signing::fingerprint(&key.public_key_base64()).unwrap();
+        let cert = DeidentificationCertificate::build(&result, "txt", false).seal(&key).unwrap();
+        
+        assert_eq!(cert.certificate_sha256.len(), 64);
+        assert!(cert.verify(&fingerprint).is_ok());
+        let file = cert.to_file("deidentified-case-1234").unwrap();
+        assert!(!file.content.contains("Jane") && !file.content.contains("555-123"));
+        let parsed: DeidentificationCertificate = serde_json::from_str(&file.content).unwrap();
+        assert!(parsed.verify(&fingerprint).is_ok());
+        
+        let mut tampered = cert.clone();
+        tampered.identifiers_removed += 1;
+        assert!(tampered.verify(&fingerprint).is_err());
+        
+        // Edited and re-sealed with another key: internally consistent, but
+        // not by this install
+        let forger = crate::crypto::signing::InstallSigningKey::generate();
+        let forged = tampered.seal(&forger).unwrap();
+        assert!(forged.verify(&crate::crypto::signing::fingerprint(&forger.public_key_base64()).unwrap()).is_ok());
+        assert!(forged.verify(&fingerprint).is_err());
+    }
 }
@@ -0,0 +1,413 @@
+// Dictation Module - Push-to-Dictate
+//
+// Hold a key, speak, release: the clip is captured on a background thread
+// (audio::capture_until), transcribed in process (voice::WhisperContext)
+// and sent to the UI as a `dictation-transcript` event, which the editor
+// inserts into the focused note. Audio stays in memory and is dropped once
+// transcribed.
+//
+// Two triggers:
+// - The global hotkey works while the app is in the background. Tauri's
+//   shortcut manager reports presses only, so it toggles: the first press
+//   starts, the next press (after the key-repeat guard) stops.
+// - dictation_key_down / dictation_key_up, called from the note editor's
+//   key handlers, give true hold-to-talk while the window has focus.
+//
+// Dictation only starts with the vault unlocked, stops if the vault locks
+// mid-capture, and discards the audio instead of transcribing it then.
+//
+// The Whisper model is loaded on first use and kept for later clips, so
+// only the first dictation after launch (or after the model changes) pays
+// the load time.
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, TryLockError};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+
+use crate::audio::{self, AudioError, CapturedAudio};
+use crate::commands::AppState;
+use crate::voice::{self, TranscriptSegment, VoiceError, WhisperConfig, WhisperContext};
+
+/// Event name for phase changes (listening, transcribing, idle)
+pub const DICTATION_STATE_EVENT: &str = "dictation-state";
+
+/// Event name for finished transcripts
+pub const DICTATION_TRANSCRIPT_EVENT: &str = "dictation-transcript";
+
+/// Hotkey presses this soon after a capture starts are key auto-repeat
+const KEY_REPEAT_GUARD: Duration = Duration::from_millis(400);
+
+/// Clips shorter than this are accidental taps and are not transcribed
+const MIN_CLIP_MS: i64 = 250;
+
+/// Upper bound for DictationConfig::max_seconds
+const MAX_CAPTURE_SECONDS: u64 = 600;
+
+// ============================================
+// Types
+// ============================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DictationConfig {
+    /// Register the global hotkey
+    pub enabled: bool,
+    /// Tauri accelerator, e.g. "CmdOrCtrl+Shift+Space"
+    pub hotkey: String,
+    /// Input device id from list_audio_devices; default input when None
+    pub device_id: Option<String>,
+    /// Whisper model file; WhisperConfig's default model when None
+    pub model_path: Option<PathBuf>,
+    /// A capture stops and is transcribed on its own after this long
+    pub max_seconds: u64,
+}
+
+impl Default for DictationConfig {
+    fn default() -> Self {
+        DictationConfig {
+            enabled: false,
+            hotkey: "CmdOrCtrl+Shift+Space".to_string(),
+            device_id: None,
+            model_path: None,
+            max_seconds: 120,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DictationPhase {
+    Idle,
+    Listening,
+    Transcribing,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DictationTrigger {
+    /// Global hotkey (toggle)
+    Hotkey,
+    /// Editor key down / key up
+    Hold,
+}
+
+/// `dictation-state` payload
+#[derive(Debug, Clone, Serialize)]
+pub struct DictationStatus {
+    pub phase: DictationPhase,
+    pub trigger: Option<DictationTrigger>,
+    /// Note the editor asked to dictate into; None means the focused note
+    pub note_id: Option<String>,
+    /// Why the last dictation ended without a transcript
+    pub error: Option<String>,
+}
+
+/// `dictation-transcript` payload
+#[derive(Debug, Clone, Serialize)]
+pub struct DictationTranscript {
+    pub note_id: Option<String>,
+    pub trigger: DictationTrigger,
+    pub text: String,
+    pub segments: Vec<TranscriptSegment>,
+    pub audio_ms: i64,
+    /// Risk-phrase detections in the dictated text, for the editor to flag
+    pub risk_detected: Vec<String>,
+}
+
+// ============================================
+// State
+// ============================================
+
+struct Capture {
+    started: Instant,
+    stop: Arc<AtomicBool>,
+}
+
+/// At most one capture at a time; a new one may start while the previous
+/// clip is still transcribing
+#[derive(Default)]
+pub struct DictationState {
+    config: Mutex<DictationConfig>,
+    capture: Mutex<Option<Capture>>,
+    /// Hotkey currently registered with the OS
+    registered: Mutex<Option<String>>,
+    /// Loaded model, reused while the configured model path is unchanged
+    model: Mutex<Option<(PathBuf, Arc<WhisperContext>)>>,
+}
+
+impl DictationState {
+    /// The model at `path`, loading it unless it is already loaded
+    fn model(&self, path: PathBuf) -> Result<Arc<WhisperContext>, VoiceError> {
+        let mut cached = self.model.lock()
+            .map_err(|_| VoiceError::Transcription("Dictation state poisoned".to_string()))?;
+        if let Some((loaded, context)) = cached.as_ref() {
+            if *loaded == path {
+                return Ok(context.clone());
+            }
+        }
+        let context = Arc::new(WhisperContext::new(path.clone())?);
+        *cached = Some((path, context.clone()));
+        Ok(context)
+    }
+}
+
+// ============================================
+// Capture and Transcription
+// ============================================
+
+#[derive(Debug, PartialEq, Eq)]
+enum PressAction {
+    Start,
+    Stop,
+    Ignore,
+}
+
+/// What a hotkey press means, given when the current capture started
+fn press_action(listening_since: Option<Instant>, now: Instant) -> PressAction {
+    match listening_since {
+        None => PressAction::Start,
+        Some(started) if now.duration_since(started) < KEY_REPEAT_GUARD => PressAction::Ignore,
+        Some(_) => PressAction::Stop,
+    }
+}
+
+fn emit_status(
+    app: &AppHandle,
+    phase: DictationPhase,
+    trigger: Option<DictationTrigger>,
+    note_id: Option<String>,
+    error: Option<String>,
+) {
+    let _ = app.emit_all(DICTATION_STATE_EVENT, DictationStatus { phase, trigger, note_id, error });
+}
+
+fn vault_unlocked(app: &AppHandle) -> bool {
+    app.state::<AppState>().vault.lock().map(|v| v.is_unlocked()).unwrap_or(false)
+}
+
+/// Non-blocking check for the capture loop; a vault busy with another
+/// command is still unlocked
+fn vault_locked_now(app: &AppHandle) -> bool {
+    match app.state::<AppState>().vault.try_lock() {
+        Ok(vault) => !vault.is_unlocked(),
+        Err(TryLockError::WouldBlock) => false,
+        Err(TryLockError::Poisoned(_)) => true,
+    }
+}
+
+/// Start capturing on a background thread
+fn begin(app: &AppHandle, trigger: DictationTrigger, note_id: Option<String>) -> Result<(), String> {
+    if !vault_unlocked(app) {
+        return Err("Unlock the vault to dictate".to_string());
+    }
+    let state = app.state::<DictationState>();
+    let config = state.config.lock().map_err(|_| "Dictation state poisoned")?.clone();
+    let mut slot = state.capture.lock().map_err(|_| "Dictation state poisoned")?;
+    if slot.is_some() {
+        return Err("Dictation already in progress".to_string());
+    }
+
+    let stop = Arc::new(AtomicBool::new(false));
+    *slot = Some(Capture { started: Instant::now(), stop: stop.clone() });
+    drop(slot);
+    emit_status(app, DictationPhase::Listening, Some(trigger), note_id.clone(), None);
+
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let captured = audio::capture_until(
+            config.device_id.as_deref(),
+            || stop.load(Ordering::Relaxed) || vault_locked_now(&app),
+            Duration::from_secs(config.max_seconds),
+        );
+        // Free the slot if the capture ended on its own (time limit, vault lock)
+        if let Ok(mut slot) = app.state::<DictationState>().capture.lock() {
+            if slot.as_ref().is_some_and(|c| Arc::ptr_eq(&c.stop, &stop)) {
+                *slot = None;
+            }
+        }
+        finish(&app, &config, trigger, note_id, captured);
+    });
+    Ok(())
+}
+
+/// Stop the running capture, if any; its thread goes on to transcribe.
+/// Returns whether a capture was stopped.
+fn release(app: &AppHandle) -> Result<bool, String> {
+    let state = app.state::<DictationState>();
+    let mut slot = state.capture.lock().map_err(|_| "Dictation state poisoned")?;
+    match slot.take() {
+        Some(capture) => {
+            capture.stop.store(true, Ordering::Relaxed);
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+fn transcribe(
+    app: &AppHandle,
+    config: &DictationConfig,
+    captured: CapturedAudio,
+) -> Result<Vec<TranscriptSegment>, VoiceError> {
+    let model_path = config.model_path.clone().unwrap_or_else(|| WhisperConfig::default().model_path);
+    let model = app.state::<DictationState>().model(model_path)?;
+    let mut segments = model.transcribe(&captured.into_whisper_input())?;
+    segments.iter_mut().for_each(voice::check_segment_for_risks);
+    Ok(segments)
+}
+
+/// Transcribe a finished capture and hand the text to the UI
+fn finish(
+    app: &AppHandle,
+    config: &DictationConfig,
+    trigger: DictationTrigger,
+    note_id: Option<String>,
+    captured: Result<CapturedAudio, AudioError>,
+) {
+    let idle = |error: Option<String>| emit_status(app, DictationPhase::Idle, Some(trigger), note_id.clone(), error);
+
+    let captured = match captured {
+        Ok(captured) => captured,
+        Err(e) => return idle(Some(e.to_string())),
+    };
+    if !vault_unlocked(app) {
+        return idle(Some("Vault locked during dictation; audio discarded".to_string()));
+    }
+    let audio_ms = captured.duration_ms();
+    if audio_ms < MIN_CLIP_MS {
+        return idle(None);
+    }
+
+    emit_status(app, DictationPhase::Transcribing, Some(trigger), note_id.clone(), None);
+    let segments = match transcribe(app, config, captured) {
+        Ok(segments) => segments,
+        Err(e) => return idle(Some(e.to_string())),
+    };
+    // The vault may have locked while Whisper ran
+    if !vault_unlocked(app) {
+        return idle(Some("Vault locked during dictation; transcript discarded".to_string()));
+    }
+
+    let text = segments.iter().map(|s| s.text.trim()).filter(|t| !t.is_empty()).collect::<Vec<_>>().join(" ");
+    let risk_detected = segments.iter().filter_map(|s| s.risk_detected.clone()).collect();
+    let _ = app.emit_all(DICTATION_TRANSCRIPT_EVENT, DictationTranscript {
+        note_id: note_id.clone(),
+        trigger,
+        text,
+        segments,
+        audio_ms,
+        risk_detected,
+    });
+    idle(None);
+}
+
+// ============================================
+// Global Hotkey
+// ============================================
+
+fn on_hotkey(app: &AppHandle) {
+    let listening_since = app
+        .state::<DictationState>()
+        .capture
+        .lock()
+        .ok()
+        .and_then(|slot| slot.as_ref().map(|c| c.started));
+    let outcome = match press_action(listening_since, Instant::now()) {
+        PressAction::Start => begin(app, DictationTrigger::Hotkey, None),
+        PressAction::Stop => release(app).map(|_| ()),
+        PressAction::Ignore => Ok(()),
+    };
+    if let Err(e) = outcome {
+        emit_status(app, DictationPhase::Idle, Some(DictationTrigger::Hotkey), None, Some(e));
+    }
+}
+
+/// Replace the registered hotkey with `config`'s (none when disabled)
+fn register_hotkey(app: &AppHandle, state: &DictationState, config: &DictationConfig) -> Result<(), String> {
+    use tauri::GlobalShortcutManager;
+
+    let mut registered = state.registered.lock().map_err(|_| "Dictation state poisoned")?;
+    let mut manager = app.global_shortcut_manager();
+    if let Some(previous) = registered.take() {
+        manager.unregister(&previous).map_err(|e| format!("Could not release {}: {}", previous, e))?;
+    }
+    if config.enabled {
+        let handle = app.clone();
+        manager
+            .register(&config.hotkey, move || on_hotkey(&handle))
+            .map_err(|e| format!("Could not register {}: {}", config.hotkey, e))?;
+        *registered = Some(config.hotkey.clone());
+    }
+    Ok(())
+}
+
+// ============================================
+// Tauri Commands
+// ============================================
+
+use tauri::State;
+
+/// Apply dictation settings and (re)register the global hotkey.
+/// Returns the settings as applied.
+#[tauri::command]
+pub fn configure_dictation(
+    app: AppHandle,
+    state: State<'_, DictationState>,
+    mut config: DictationConfig,
+) -> Result<DictationConfig, String> {
+    config.max_seconds = config.max_seconds.clamp(1, MAX_CAPTURE_SECONDS);
+    register_hotkey(&app, &state, &config)?;
+    *state.config.lock().map_err(|_| "Dictation state poisoned")? = config.clone();
+    Ok(config)
+}
+
+#[tauri::command]
+pub fn get_dictation_config(state: State<'_, DictationState>) -> Result<DictationConfig, String> {
+    Ok(state.config.lock().map_err(|_| "Dictation state poisoned")?.clone())
+}
+
+/// Editor key down: start capturing for `note_id` (the focused note if omitted)
+#[tauri::command]
+pub fn dictation_key_down(app: AppHandle, note_id: Option<String>) -> Result<(), String> {
+    begin(&app, DictationTrigger::Hold, note_id)
+}
+
+/// Editor key up: stop capturing and transcribe. Returns false if nothing
+/// was being captured.
+#[tauri::command]
+pub fn dictation_key_up(app: AppHandle) -> Result<bool, String> {
+    release(&app)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hotkey_press_toggles_past_repeat_guard() {
+        let start = Instant::now();
+        assert_eq!(press_action(None, start), PressAction::Start);
+        assert_eq!(press_action(Some(start), start + Duration::from_millis(50)), PressAction::Ignore);
+        assert_eq!(press_action(Some(start), start + KEY_REPEAT_GUARD), PressAction::Stop);
+    }
+
+    #[test]
+    fn test_model_is_loaded_once_per_path() {
+        let dir = std::env::temp_dir().join(format!("evidify-dictation-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let (small, large) = (dir.join("small.bin"), dir.join("large.bin"));
+        std::fs::write(&small, b"model").unwrap();
+        std::fs::write(&large, b"model").unwrap();
+
+        let state = DictationState::default();
+        let first = state.model(small.clone()).unwrap();
+        assert!(Arc::ptr_eq(&first, &state.model(small.clone()).unwrap()));
+        // Changing the configured model loads the new one
+        assert!(!Arc::ptr_eq(&first, &state.model(large).unwrap()));
+        assert!(state.model(dir.join("missing.bin")).is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -11,9 +11,10 @@
 // - PDF (universal)
 // - DOCX (universal)
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -57,6 +58,20 @@ pub enum EhrTarget {
 }
 
 impl EhrTarget {
+    /// Lowercase identifier, as the frontend names targets
+    pub fn id(&self) -> &'static str {
+        match self {
+            EhrTarget::SimplePractice => "simplepractice",
+            EhrTarget::TherapyNotes => "therapynotes",
+            EhrTarget::JaneApp => "janeapp",
+            EhrTarget::PracticeFusion => "practicefusion",
+            EhrTarget::Epic => "epic",
+            EhrTarget::Pdf => "pdf",
+            EhrTarget::Docx => "docx",
+            EhrTarget::PlainText => "plaintext",
+        }
+    }
+    
     pub fn file_extension(&self) -> &'static str {
         match self {
             EhrTarget::SimplePractice => "csv",
@@ -114,6 +129,28 @@ pub struct ExportableNote {
     pub word_count: u32,
     pub amendments: Vec<Amendment>,
     pub attestations: Vec<AttestationRecord>,
+    /// Authors of a multi-author note who signed their contribution; filled
+    /// in from the vault at export
+    #[serde(default)]
+    pub co_signers: Vec<CoSigner>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CoSigner {
+    pub name: String,
+    pub credentials: Option<String>,
+    pub role: String,
+    pub signed_at: DateTime<Utc>,
+    pub contribution_sha256: String,
+}
+
+impl CoSigner {
+    fn display(&self) -> String {
+        match &self.credentials {
+            Some(credentials) => format!("{}, {} ({})", self.name, credentials, self.role.replace('_', "-")),
+            None => format!("{} ({})", self.name, self.role.replace('_', "-")),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -148,6 +185,10 @@ pub struct ExportOptions {
     pub custom_header: Option<String>,
     /// Custom footer text
     pub custom_footer: Option<String>,
+    /// Name on the signature block (the vault records when a note was
+    /// signed, not by whom)
+    #[serde(default)]
+    pub signed_by: Option<String>,
 }
 
 impl Default for ExportOptions {
@@ -159,6 +200,7 @@ impl Default for ExportOptions {
             include_signature: true,
             custom_header: None,
             custom_footer: None,
+            signed_by: None,
         }
     }
 }
@@ -173,6 +215,9 @@ pub struct ExportResult {
     pub notes_exported: u32,
     pub export_time_ms: u64,
     pub instructions: String,
+    /// Outbound queue entry, for exports sent through the queue
+    #[serde(default)]
+    pub delivery_id: Option<String>,
 }
 
 // ============================================
@@ -207,7 +252,7 @@ impl EhrExporter {
             sanitize_filename(&note.client_name),
             note.session_date,
             note.note_type,
-            note.id[..8].to_string(),
+            crate::ids::short_id(&note.id),
             options.target.file_extension()
         );
         
@@ -225,6 +270,7 @@ impl EhrExporter {
             notes_exported: 1,
             export_time_ms,
             instructions: Self::get_import_instructions(options.target),
+            delivery_id: None,
         })
     }
     
@@ -253,6 +299,7 @@ impl EhrExporter {
                 notes_exported: notes.len() as u32,
                 export_time_ms: start.elapsed().as_millis() as u64,
                 instructions: Self::get_import_instructions(options.target),
+                delivery_id: None,
             });
         }
         
@@ -270,6 +317,7 @@ impl EhrExporter {
             notes_exported: notes.len() as u32,
             export_time_ms: start.elapsed().as_millis() as u64,
             instructions: Self::get_import_instructions(options.target),
+            delivery_id: None,
         })
     }
     
@@ -352,6 +400,14 @@ impl EhrExporter {
                     at.format("%Y-%m-%d at %H:%M")
                 ));
             }
+            for co in &note.co_signers {
+                output.push_str(&format!(
+                    "Co-signed by {} on {} (contribution SHA-256 {})\n",
+                    co.display(),
+                    co.signed_at.format("%Y-%m-%d at %H:%M"),
+                    co.contribution_sha256
+                ));
+            }
         }
         
         Ok(output)
@@ -408,6 +464,14 @@ impl EhrExporter {
                     at.format("%Y-%m-%d at %H:%M")
                 ));
             }
+            for co in &note.co_signers {
+                html.push_str(&format!(
+                    "<p>Co-signed by <strong>{}</strong> on {} <small>(contribution SHA-256 {})</small></p>\n",
+                    html_escape(&co.display()),
+                    co.signed_at.format("%Y-%m-%d at %H:%M"),
+                    co.contribution_sha256
+                ));
+            }
             html.push_str("</div>\n");
         }
         
@@ -449,6 +513,14 @@ impl EhrExporter {
             xml.push_str("    </assignedAuthor>\n");
             xml.push_str("  </author>\n");
         }
+        for co in &note.co_signers {
+            xml.push_str("  <author>\n");
+            xml.push_str(&format!("    <time value=\"{}\"/>\n", co.signed_at.format("%Y%m%d%H%M%S")));
+            xml.push_str("    <assignedAuthor>\n");
+            xml.push_str(&format!("      <assignedPerson><name>{}</name></assignedPerson>\n", xml_escape(&co.display())));
+            xml.push_str("    </assignedAuthor>\n");
+            xml.push_str("  </author>\n");
+        }
         
         // Body with note content
         xml.push_str("  <component>\n");
@@ -545,6 +617,244 @@ impl EhrExporter {
     }
 }
 
+// ============================================
+// Outbound Delivery Queue
+// ============================================
+//
+// Every EHR export is queued in the vault (ehr_deliveries) before it is
+// written. The queue holds the note id, not the note: each attempt renders
+// the note from the vault as it stands then (exportable_note), so the queue
+// is not a second copy of the chart and a purged note is never sent. A failed write (import folder offline, share unmounted) is
+// retried with exponential backoff by a background worker until it
+// succeeds or DELIVERY_MAX_ATTEMPTS is reached; the clinician can retry
+// by hand after that. A sent delivery stays unreconciled until someone
+// confirms the EHR imported it. Every status change is audited; the
+// audit entry carries the target and attempt, never the path or error
+// text (both may name the client).
+//
+//   pending -> sent -> acknowledged
+//      |  ^
+//      v  |  (automatic retry, or manual retry once attempts run out)
+//     failed
+
+pub const DELIVERY_MAX_ATTEMPTS: u32 = 6;
+const DELIVERY_BASE_BACKOFF_SECS: i64 = 30;
+const DELIVERY_MAX_BACKOFF_SECS: i64 = 60 * 60;
+/// How often the worker looks for due deliveries
+const DELIVERY_WORKER_TICK: std::time::Duration = std::time::Duration::from_secs(30);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeliveryStatus {
+    Pending,
+    Sent,
+    Failed,
+    Acknowledged,
+}
+
+impl DeliveryStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DeliveryStatus::Pending => "pending",
+            DeliveryStatus::Sent => "sent",
+            DeliveryStatus::Failed => "failed",
+            DeliveryStatus::Acknowledged => "acknowledged",
+        }
+    }
+    
+    pub fn from_str(s: &str) -> Self {
+        match s {
+            "sent" => DeliveryStatus::Sent,
+            "failed" => DeliveryStatus::Failed,
+            "acknowledged" => DeliveryStatus::Acknowledged,
+            _ => DeliveryStatus::Pending,
+        }
+    }
+}
+
+/// One queued export and where it stands. The note payload stays in the
+/// vault and is not returned here.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EhrDelivery {
+    pub id: String,
+    pub note_id: String,
+    pub target: EhrTarget,
+    pub status: DeliveryStatus,
+    pub attempts: u32,
+    /// Unix seconds; None when no automatic attempt is scheduled
+    pub next_attempt_at: Option<i64>,
+    pub last_error: Option<String>,
+    pub file_path: Option<String>,
+    /// SHA-256 of the file as written
+    pub content_sha256: Option<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+    pub sent_at: Option<i64>,
+    pub acknowledged_at: Option<i64>,
+    pub acknowledged_by: Option<String>,
+    /// Identifier the EHR gave the imported document, if any
+    pub ehr_reference: Option<String>,
+}
+
+/// Wait before the retry following `attempts` failed attempts
+pub fn retry_delay_secs(attempts: u32) -> i64 {
+    let doublings = attempts.saturating_sub(1).min(16);
+    (DELIVERY_BASE_BACKOFF_SECS << doublings).min(DELIVERY_MAX_BACKOFF_SECS)
+}
+
+/// When to retry after the `attempts`-th failure, or None to stop
+pub fn next_attempt_after_failure(attempts: u32, now: i64) -> Option<i64> {
+    (attempts < DELIVERY_MAX_ATTEMPTS).then(|| now + retry_delay_secs(attempts))
+}
+
+pub fn parse_target(target: &str) -> Result<EhrTarget, String> {
+    match target {
+        "simplepractice" => Ok(EhrTarget::SimplePractice),
+        "therapynotes" => Ok(EhrTarget::TherapyNotes),
+        "janeapp" => Ok(EhrTarget::JaneApp),
+        "pdf" => Ok(EhrTarget::Pdf),
+        "docx" => Ok(EhrTarget::Docx),
+        "plaintext" => Ok(EhrTarget::PlainText),
+        _ => Err(format!("Unknown EHR target: {}", target)),
+    }
+}
+
+/// Authors whose signature covers the note's current text
+pub fn co_signers(vault: &crate::vault::Vault, note_id: &str) -> Result<Vec<CoSigner>, crate::vault::VaultError> {
+    let authorship = vault.get_note_authorship(note_id)?;
+    Ok(authorship.authors.iter()
+        .filter(|author| author.has_signed(&authorship.text_sha256))
+        .filter_map(|author| Some(CoSigner {
+            name: author.author_name.clone(),
+            credentials: author.credentials.clone(),
+            role: author.role.as_str().to_string(),
+            signed_at: DateTime::from_timestamp_millis(author.signed_at?)?,
+            contribution_sha256: author.contribution_sha256.clone()?,
+        }))
+        .collect())
+}
+
+/// The note as it now stands in the vault, ready to export. Amendment blocks
+/// become `amendments`, signed by whoever amended the note where the
+/// revision records it.
+pub fn exportable_note(
+    vault: &crate::vault::Vault,
+    note_id: &str,
+    signed_by: Option<&str>,
+) -> Result<ExportableNote, crate::vault::VaultError> {
+    let note = vault.get_note(note_id)?;
+    let client = vault.get_client(&note.client_id)?;
+    let (body, blocks) = crate::note_diff::amendment_blocks(&note.raw_input);
+    let amended_by: Vec<Option<String>> = if blocks.is_empty() {
+        Vec::new()
+    } else {
+        vault.get_note_revisions(note_id)?.into_iter()
+            .filter(|revision| revision.superseded_by.as_deref() == Some("amendment"))
+            .map(|revision| revision.editor)
+            .collect()
+    };
+    let updated_at = DateTime::from_timestamp_millis(note.updated_at).unwrap_or_default();
+    let amendments = blocks.iter().enumerate().map(|(i, block)| Amendment {
+        created_at: block.amended_at
+            .and_then(|at| NaiveDateTime::parse_from_str(at, "%Y-%m-%d %H:%M:%S UTC").ok())
+            .map(|at| at.and_utc())
+            .unwrap_or(updated_at),
+        reason: block.reason.unwrap_or_default().to_string(),
+        content: block.body.to_string(),
+        signed_by: amended_by.get(i).cloned().flatten()
+            .or_else(|| signed_by.map(str::to_string))
+            .unwrap_or_default(),
+    }).collect();
+    let attestations = note.attestations.iter().map(|attestation| AttestationRecord {
+        detection_id: attestation.detection_id.clone(),
+        detection_title: crate::ethics::detection_title(&attestation.detection_id)
+            .unwrap_or(&attestation.detection_id)
+            .to_string(),
+        response: attestation.response.label().to_string(),
+        explanation: attestation.response_note.clone(),
+        attested_at: DateTime::from_timestamp_millis(attestation.attested_at).unwrap_or_default(),
+    }).collect();
+
+    Ok(ExportableNote {
+        co_signers: co_signers(vault, note_id)?,
+        content: body.to_string(),
+        id: note.id,
+        client_id: note.client_id,
+        client_name: client.display_name,
+        session_date: note.session_date,
+        note_type: note.note_type.to_string(),
+        signed_at: note.signed_at.and_then(DateTime::from_timestamp_millis),
+        signed_by: signed_by.map(str::to_string),
+        word_count: note.word_count.max(0) as u32,
+        amendments,
+        attestations,
+    })
+}
+
+/// Make one attempt at a queued delivery and record the outcome. A note
+/// that can no longer be rendered (purged, trashed) fails the attempt.
+pub fn attempt_delivery(
+    vault: &crate::vault::Vault,
+    delivery_id: &str,
+) -> Result<(EhrDelivery, Option<ExportResult>), crate::vault::VaultError> {
+    let (note_id, options, output_dir) = vault.ehr_delivery_payload(delivery_id)?;
+    let written = exportable_note(vault, &note_id, options.signed_by.as_deref())
+        .map_err(|e| EhrExportError::ExportFailed(e.to_string()))
+        .and_then(|note| EhrExporter::export_note(&note, &options, Path::new(&output_dir)))
+        .and_then(|result| {
+            let bytes = std::fs::read(result.file_path.as_deref().unwrap_or_default())?;
+            Ok((result, crate::crypto::hash_sha256(&bytes)))
+        });
+    match written {
+        Ok((result, sha256)) => {
+            let path = result.file_path.clone().unwrap_or_default();
+            let delivery = vault.record_ehr_delivery_attempt(delivery_id, Ok((&path, &sha256)))?;
+            Ok((delivery, Some(result)))
+        }
+        Err(e) => {
+            log::warn!("EHR delivery {} failed: {}", delivery_id, e);
+            let delivery = vault.record_ehr_delivery_attempt(delivery_id, Err(&e.to_string()))?;
+            Ok((delivery, None))
+        }
+    }
+}
+
+static DELIVERY_WORKER_STARTED: AtomicBool = AtomicBool::new(false);
+
+/// Background thread that retries due deliveries. Started with the first
+/// unlock (commands::start_background_workers); later calls do nothing.
+pub fn start_delivery_worker(app: &tauri::AppHandle) {
+    use tauri::Manager;
+    
+    if DELIVERY_WORKER_STARTED.swap(true, Ordering::AcqRel) {
+        return;
+    }
+    let app = app.clone();
+    std::thread::spawn(move || loop {
+        std::thread::sleep(DELIVERY_WORKER_TICK);
+        
+        let state = app.state::<crate::commands::AppState>();
+        let Ok(vault) = state.vault.try_lock() else {
+            continue;
+        };
+        if !vault.is_unlocked() {
+            continue;
+        }
+        let due = match vault.due_ehr_deliveries(Utc::now().timestamp()) {
+            Ok(due) => due,
+            Err(e) => {
+                log::warn!("EHR delivery queue unreadable: {}", e);
+                continue;
+            }
+        };
+        for delivery in due {
+            if let Err(e) = attempt_delivery(&vault, &delivery.id) {
+                log::warn!("EHR delivery {} not attempted: {}", delivery.id, e);
+            }
+        }
+    });
+}
+
 // ============================================
 // Helper Functions
 // ============================================
@@ -595,32 +905,96 @@ pub struct EhrTargetInfo {
     pub description: &'static str,
 }
 
-/// Export note to EHR format
+/// Export a note to EHR format. The export is queued first; if this attempt
+/// fails it stays queued and is retried in the background.
 #[tauri::command]
 pub async fn export_to_ehr(
-    note: ExportableNote,
+    state: tauri::State<'_, crate::commands::AppState>,
+    policy_state: tauri::State<'_, crate::policy::PolicyState>,
+    note_id: String,
     target: String,
     output_dir: String,
     include_amendments: bool,
     include_signature: bool,
+    signed_by: Option<String>,
 ) -> Result<ExportResult, String> {
-    let target = match target.as_str() {
-        "simplepractice" => EhrTarget::SimplePractice,
-        "therapynotes" => EhrTarget::TherapyNotes,
-        "janeapp" => EhrTarget::JaneApp,
-        "pdf" => EhrTarget::Pdf,
-        "docx" => EhrTarget::Docx,
-        "plaintext" => EhrTarget::PlainText,
-        _ => return Err(format!("Unknown EHR target: {}", target)),
-    };
-    
     let options = ExportOptions {
-        target,
+        target: parse_target(&target)?,
         include_amendments,
         include_signature,
+        signed_by,
         ..Default::default()
     };
     
-    EhrExporter::export_note(&note, &options, Path::new(&output_dir))
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    crate::commands::read_note_checked(&vault, &policy_state, &note_id)?;
+    let queued = vault.enqueue_ehr_delivery(&note_id, &options, &output_dir).map_err(|e| e.to_string())?;
+    match attempt_delivery(&vault, &queued.id).map_err(|e| e.to_string())? {
+        (delivery, Some(result)) => Ok(ExportResult { delivery_id: Some(delivery.id), ..result }),
+        (delivery, None) => Err(format!(
+            "Export failed ({}); delivery {} is queued and will be retried",
+            delivery.last_error.unwrap_or_default(), delivery.id
+        )),
+    }
+}
+
+#[tauri::command]
+pub fn list_ehr_deliveries(
+    state: tauri::State<'_, crate::commands::AppState>,
+) -> Result<Vec<EhrDelivery>, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.list_ehr_deliveries(false).map_err(|e| e.to_string())
+}
+
+/// Reconciliation view: deliveries the EHR has not confirmed (pending,
+/// failed or sent), oldest first
+#[tauri::command]
+pub fn get_unreconciled_ehr_deliveries(
+    state: tauri::State<'_, crate::commands::AppState>,
+) -> Result<Vec<EhrDelivery>, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.list_ehr_deliveries(true).map_err(|e| e.to_string())
+}
+
+/// Record that the EHR imported a sent delivery
+#[tauri::command]
+pub fn acknowledge_ehr_delivery(
+    state: tauri::State<'_, crate::commands::AppState>,
+    delivery_id: String,
+    acknowledged_by: String,
+    ehr_reference: Option<String>,
+) -> Result<EhrDelivery, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.acknowledge_ehr_delivery(&delivery_id, &acknowledged_by, ehr_reference.as_deref())
         .map_err(|e| e.to_string())
 }
+
+/// Requeue a failed delivery (or resend a sent one the EHR never got)
+/// and attempt it now
+#[tauri::command]
+pub fn retry_ehr_delivery(
+    state: tauri::State<'_, crate::commands::AppState>,
+    delivery_id: String,
+) -> Result<EhrDelivery, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.requeue_ehr_delivery(&delivery_id).map_err(|e| e.to_string())?;
+    attempt_delivery(&vault, &delivery_id).map(|(delivery, _)| delivery).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_delivery_backoff() {
+        assert_eq!(retry_delay_secs(1), 30);
+        assert_eq!(retry_delay_secs(2), 60);
+        assert_eq!(retry_delay_secs(5), 480);
+        assert_eq!(retry_delay_secs(40), DELIVERY_MAX_BACKOFF_SECS);
+        assert_eq!(next_attempt_after_failure(1, 1_000), Some(1_030));
+        assert_eq!(next_attempt_after_failure(DELIVERY_MAX_ATTEMPTS, 1_000), None);
+        for status in [DeliveryStatus::Pending, DeliveryStatus::Sent, DeliveryStatus::Failed, DeliveryStatus::Acknowledged] {
+            assert_eq!(DeliveryStatus::from_str(status.as_str()), status);
+        }
+    }
+}
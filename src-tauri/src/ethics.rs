@@ -6,8 +6,9 @@
 // - This prevents PHI leakage into logs/support bundles
 
 use regex::Regex;
+use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
-use crate::models::{EthicsDetection, EthicsAnalysis, StoredDetection, DetectionSeverity};
+use crate::models::{Attestation, AttestationResponse, EthicsDetection, EthicsAnalysis, StoredDetection, DetectionSeverity, InformationSource, NoteType, SourceKind, SubjectAttribution, SubjectRole};
 
 // ============================================
 // Re-exported Types for Other Modules
@@ -850,8 +851,10 @@ fn normalize_text(text: &str) -> String {
 /// - StoredDetection list (for database, no evidence)
 pub fn analyze(text: &str) -> EthicsAnalysis {
     let normalized = normalize_text(text);
+    let content_hash = crate::crypto::hash_sha256(text.as_bytes());
     let mut detections = Vec::new();
     let mut stored_detections = Vec::new();
+    let segments = source_segments(&normalized);
     
     for pattern_def in PATTERNS.iter() {
         // Check exclusions
@@ -876,10 +879,14 @@ pub fn analyze(text: &str) -> EthicsAnalysis {
                         severity: pattern_def.severity,
                         match_start: m.start(),
                         match_end: m.end(),
+                        content_hash: Some(content_hash.clone()),
+                        span_hash: Some(crate::crypto::hash_sha256(m.as_str().as_bytes())),
                     });
                     
                     // Full detection with evidence (for display)
                     let evidence = extract_context(text, m.start(), m.end(), 50);
+                    let attribution = attribute_subject(&normalized, m.start());
+                    let source = source_at(&segments, m.start());
                     
                     detections.push(EthicsDetection {
                         id: detection_id,
@@ -888,9 +895,11 @@ pub fn analyze(text: &str) -> EthicsAnalysis {
                         title: pattern_def.title.to_string(),
                         description: pattern_def.description.to_string(),
                         evidence,
-                        suggestion: pattern_def.suggestion.to_string(),
+                        suggestion: cited_suggestion(pattern_def, &source),
                         policy_ref: pattern_def.policy_ref.map(|s| s.to_string()),
                         requires_attestation: pattern_def.severity == DetectionSeverity::Attest,
+                        attribution: Some(attribution),
+                        source: Some(source),
                     });
                     
                     break; // One detection per pattern type
@@ -931,9 +940,16 @@ fn extract_context(text: &str, start: usize, end: usize, context_size: usize) ->
 
 /// Reconstruct detections with evidence from stored detections and note content
 pub fn hydrate_detections(stored: &[StoredDetection], note_content: &str) -> Vec<EthicsDetection> {
+    // Offsets were taken on normalized text
+    let normalized = normalize_text(note_content);
+    let segments = source_segments(&normalized);
     stored.iter().filter_map(|sd| {
-        let pattern_def = PATTERNS.iter().find(|p| p.id == sd.pattern_id)?;
+        let Some(pattern_def) = PATTERNS.iter().find(|p| p.id == sd.pattern_id) else {
+            // Absence detections have no span to reconstruct
+            return ABSENCE_RULES.iter().find(|r| r.id == sd.pattern_id).map(|rule| rule.detection(sd.id.clone()));
+        };
         let evidence = sd.get_evidence(note_content, 50);
+        let source = source_at(&segments, sd.match_start);
         
         Some(EthicsDetection {
             id: sd.id.clone(),
@@ -942,17 +958,1051 @@ pub fn hydrate_detections(stored: &[StoredDetection], note_content: &str) -> Vec
             title: pattern_def.title.to_string(),
             description: pattern_def.description.to_string(),
             evidence,
-            suggestion: pattern_def.suggestion.to_string(),
+            suggestion: cited_suggestion(pattern_def, &source),
             policy_ref: pattern_def.policy_ref.map(|s| s.to_string()),
             requires_attestation: sd.severity == DetectionSeverity::Attest,
+            attribution: (sd.match_start <= normalized.len())
+                .then(|| attribute_subject(&normalized, sd.match_start)),
+            source: Some(source),
         })
     }).collect()
 }
 
+// ============================================
+// Rule Pack Identity
+// ============================================
+
+/// Name of the built-in detection rules in audit pack fingerprints
+pub const RULE_PACK_NAME: &str = "ethics-detections";
+
+/// SHA-256 over the canonical JSON of every detection rule (patterns and
+/// absence rules: ids, severities, regexes). Changes whenever a rule
+/// does, so packs record exactly which rules produced their detections.
+pub fn rule_pack_sha256() -> String {
+    let patterns: Vec<serde_json::Value> = PATTERNS.iter().map(|p| serde_json::json!({
+        "id": p.id,
+        "category": p.category,
+        "severity": p.severity,
+        "patterns": p.patterns,
+        "exclusions": p.exclusions,
+    })).collect();
+    let absence: Vec<serde_json::Value> = ABSENCE_RULES.iter().map(|r| serde_json::json!({
+        "id": r.id,
+        "category": r.category,
+        "severity": r.severity,
+        "trigger": match &r.trigger {
+            AbsenceTrigger::ActiveRisk(kind) => serde_json::json!({ "active_risk": kind }),
+            AbsenceTrigger::NoteType(note_type) => serde_json::json!({ "note_type": note_type }),
+        },
+        "required": r.required,
+    })).collect();
+    evidify_canonicalization::canonical_sha256(&serde_json::json!({
+        "patterns": patterns,
+        "absence_rules": absence,
+    }))
+}
+
+// ============================================
+// Detection Anchoring
+// ============================================
+//
+// Stored detections are offsets into the text that was analyzed, and the
+// clinician may keep editing before attesting. Each detection carries the
+// hash of that text and of its matched span. After an edit, a detection
+// whose span is still present (same pattern, same matched words) moves to
+// the span's new offsets and keeps its ID, so its attestation still
+// applies. Absence findings have no span: they carry over unless the edit
+// added the documentation they asked for, which resolves them. Anything
+// else - the span was rewritten or removed, a detection stored before
+// anchoring existed - is dropped and the note needs re-analysis before
+// signing.
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReanchorOutcome {
+    pub detections: Vec<StoredDetection>,
+    /// IDs whose offsets moved
+    pub reanchored: Vec<String>,
+    /// IDs that no longer point at their text
+    pub invalidated: Vec<String>,
+    /// Absence findings whose required documentation the edit added
+    pub resolved: Vec<String>,
+}
+
+impl ReanchorOutcome {
+    pub fn reanalyze_required(&self) -> bool {
+        !self.invalidated.is_empty()
+    }
+}
+
+/// Offsets of `sd`'s span in `normalized`, if the same words still match
+/// its pattern (nearest to the old position wins)
+fn locate_span(sd: &StoredDetection, normalized: &str) -> Option<(usize, usize)> {
+    let span_hash = sd.span_hash.as_deref()?;
+    let pattern_def = PATTERNS.iter().find(|p| p.id == sd.pattern_id)?;
+    let same_span = |s: &str| crate::crypto::digests_match(&crate::crypto::hash_sha256(s.as_bytes()), span_hash);
+
+    if normalized.get(sd.match_start..sd.match_end).map(same_span).unwrap_or(false) {
+        return Some((sd.match_start, sd.match_end));
+    }
+    pattern_def.patterns.iter()
+        .filter_map(|p| PATTERN_REGEXES.get(p))
+        .flat_map(|re| re.find_iter(normalized).map(|m| (m.start(), m.end(), m.as_str().to_string())).collect::<Vec<_>>())
+        .filter(|(_, _, s)| same_span(s))
+        .min_by_key(|(start, _, _)| start.abs_diff(sd.match_start))
+        .map(|(start, end, _)| (start, end))
+}
+
+/// Carry detections analyzed on an earlier version over to `new_text`
+pub fn reanchor_detections(stored: &[StoredDetection], new_text: &str) -> ReanchorOutcome {
+    let content_hash = crate::crypto::hash_sha256(new_text.as_bytes());
+    let normalized = normalize_text(new_text);
+    let mut outcome = ReanchorOutcome {
+        detections: Vec::new(),
+        reanchored: Vec::new(),
+        invalidated: Vec::new(),
+        resolved: Vec::new(),
+    };
+
+    for sd in stored {
+        if sd.content_hash.as_deref() == Some(content_hash.as_str()) {
+            outcome.detections.push(sd.clone());
+            continue;
+        }
+        if let Some(rule) = ABSENCE_RULES.iter().find(|r| r.id == sd.pattern_id) {
+            if rule_checks(&rule.required, &normalized).iter().any(|c| c.matched) {
+                outcome.resolved.push(sd.id.clone());
+            } else {
+                outcome.detections.push(StoredDetection { content_hash: Some(content_hash.clone()), ..sd.clone() });
+            }
+            continue;
+        }
+        match locate_span(sd, &normalized) {
+            Some((start, end)) => {
+                if start != sd.match_start {
+                    outcome.reanchored.push(sd.id.clone());
+                }
+                outcome.detections.push(StoredDetection {
+                    match_start: start,
+                    match_end: end,
+                    content_hash: Some(content_hash.clone()),
+                    ..sd.clone()
+                });
+            }
+            None => outcome.invalidated.push(sd.id.clone()),
+        }
+    }
+    outcome
+}
+
+// ============================================
+// Detection Explanation
+// ============================================
+//
+// "Why did this fire?" for the clinician: which of the rule's patterns
+// matched, the words it matched, the exclusions that were checked and did
+// not apply, and the policy the rule cites. Patterns are shown in a
+// readable form rather than as raw regex.
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleCheck {
+    /// Readable form of the regex
+    pub pattern: String,
+    /// Whether it matches the note's current text
+    pub matched: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MatchedSpan {
+    pub start: usize,
+    pub end: usize,
+    pub text: String,
+    /// The text at the stored offsets is still the text that was flagged
+    pub current: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DetectionExplanation {
+    pub detection_id: String,
+    pub rule_id: String,
+    /// "pattern" (language present) or "absence" (documentation missing)
+    pub rule_kind: String,
+    pub category: String,
+    pub severity: DetectionSeverity,
+    pub title: String,
+    pub description: String,
+    /// Pattern that produced the match; None for absence rules
+    pub matched_pattern: Option<String>,
+    pub matched_span: Option<MatchedSpan>,
+    /// Session or collateral source of the matched text (pattern rules)
+    pub source: Option<InformationSource>,
+    /// Exclusions evaluated before the rule fired (pattern rules)
+    pub exclusions: Vec<RuleCheck>,
+    /// Documentation the rule looked for and did not find (absence rules)
+    pub required_documentation: Vec<RuleCheck>,
+    pub policy_ref: Option<String>,
+    pub rule_pack_sha256: String,
+}
+
+lazy_static::lazy_static! {
+    /// Detection patterns, compiled once for explanations and re-anchoring
+    static ref PATTERN_REGEXES: HashMap<&'static str, Regex> = PATTERNS.iter()
+        .flat_map(|p| p.patterns.iter())
+        .filter_map(|p| Regex::new(p).ok().map(|re| (*p, re)))
+        .collect();
+    static ref PATTERN_GAP: Regex = Regex::new(r"\.\{\d*,?\d*\}|\.[*+]|\\s[*+]?").unwrap();
+}
+
+/// Regex syntax stripped down to the words it looks for:
+/// `(?i)\b(dark thoughts?|dark place)\b` -> `(dark thoughts?|dark place)`
+pub fn describe_pattern(pattern: &str) -> String {
+    let text = pattern.replace("(?i)", "").replace("(?:", "(").replace("(?-i:", "(").replace("\\b", "");
+    let text = PATTERN_GAP.replace_all(&text, " … ");
+    let text = text.replace("\\w*", "…").replace("\\w+", "…").replace('\\', "");
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn rule_checks(patterns: &[&'static str], normalized: &str) -> Vec<RuleCheck> {
+    patterns.iter().map(|p| RuleCheck {
+        pattern: describe_pattern(p),
+        matched: REQUIRED_REGEXES.get(p).is_some_and(|re| re.is_match(normalized)),
+    }).collect()
+}
+
+/// Explain a stored detection against the note text it is anchored to.
+/// None if the rule no longer exists.
+pub fn explain_detection(sd: &StoredDetection, note_text: &str) -> Option<DetectionExplanation> {
+    let normalized = normalize_text(note_text);
+    let rule_pack_sha256 = rule_pack_sha256();
+
+    if let Some(rule) = ABSENCE_RULES.iter().find(|r| r.id == sd.pattern_id) {
+        return Some(DetectionExplanation {
+            detection_id: sd.id.clone(),
+            rule_id: rule.id.to_string(),
+            rule_kind: "absence".to_string(),
+            category: rule.category.to_string(),
+            severity: sd.severity,
+            title: rule.title.to_string(),
+            description: rule.description.to_string(),
+            matched_pattern: None,
+            matched_span: None,
+            source: None,
+            exclusions: Vec::new(),
+            required_documentation: rule_checks(&rule.required, &normalized),
+            policy_ref: rule.policy_ref.map(|s| s.to_string()),
+            rule_pack_sha256,
+        });
+    }
+
+    let pattern_def = PATTERNS.iter().find(|p| p.id == sd.pattern_id)?;
+    let span = normalized.get(sd.match_start..sd.match_end);
+    // The pattern that matched exactly these offsets, else any that
+    // matches the span's words
+    let matched_pattern = pattern_def.patterns.iter()
+        .find(|p| PATTERN_REGEXES.get(*p).is_some_and(|re| {
+            re.find_iter(&normalized).any(|m| m.start() == sd.match_start && m.end() == sd.match_end)
+        }))
+        .or_else(|| pattern_def.patterns.iter().find(|p| {
+            span.is_some_and(|s| PATTERN_REGEXES.get(*p).is_some_and(|re| re.is_match(s)))
+        }));
+
+    Some(DetectionExplanation {
+        detection_id: sd.id.clone(),
+        rule_id: pattern_def.id.to_string(),
+        rule_kind: "pattern".to_string(),
+        category: pattern_def.category.to_string(),
+        severity: sd.severity,
+        title: pattern_def.title.to_string(),
+        description: pattern_def.description.to_string(),
+        matched_pattern: matched_pattern.map(|p| describe_pattern(p)),
+        matched_span: span.map(|s| MatchedSpan {
+            start: sd.match_start,
+            end: sd.match_end,
+            text: s.to_string(),
+            current: sd.span_hash.as_deref().is_some_and(|h| {
+                crate::crypto::digests_match(&crate::crypto::hash_sha256(s.as_bytes()), h)
+            }),
+        }),
+        source: Some(source_at(&source_segments(&normalized), sd.match_start)),
+        exclusions: rule_checks(&pattern_def.exclusions, &normalized),
+        required_documentation: Vec::new(),
+        policy_ref: pattern_def.policy_ref.map(|s| s.to_string()),
+        rule_pack_sha256,
+    })
+}
+
+// ============================================
+// Role Attribution
+// ============================================
+//
+// "Her ex said he'd hurt her" matches the same violence patterns as a
+// client's own threat. Each pattern detection is tagged with whose speech
+// or action the sentence describes, from cues in the sentence up to the
+// match: a named relation acting as the subject, clinician voice
+// ("writer", "I", intervention verbs), or client voice ("client", "pt",
+// subject-less "Reports..."). Heuristic by design - it routes attention
+// (duty-to-warn target vs client risk), the clinician still decides.
+
+lazy_static::lazy_static! {
+    static ref THIRD_PARTY_SUBJECT: Regex = Regex::new(
+        r"(?i)\b(?:her|his|their|client'?s|pt'?s|patient'?s|the|a|an)\s+(ex(?:-?(?:husband|wife|boyfriend|girlfriend|partner))?|husband|wife|boyfriend|girlfriend|partner|spouse|fianc[eé]e?|mother|father|mom|dad|stepfather|stepmother|brother|sister|son|daughter|uncle|aunt|cousin|grandmother|grandfather|friend|roommate|coworker|co-worker|boss|supervisor|neighbor|landlord|teacher|classmate)\b(?:\s+\w+){0,3}?\s+(?:said|says|told|tells|stated|states|threatened|threatens|texted|yelled|screamed|warned|reportedly|has been|was|is|wants|will|would|plans|planned|tried|keeps)\b"
+    ).unwrap();
+    static ref CLINICIAN_SUBJECT: Regex = Regex::new(
+        r"(?i)^\s*(?:this\s+)?(?:writer|clinician|therapist|counselor|provider|I|we)\b|^\s*(?:discussed|provided|reviewed|explored|educated|assessed|processed|encouraged|recommended|completed|administered|consulted)\b"
+    ).unwrap();
+    static ref CLIENT_SUBJECT: Regex = Regex::new(
+        r"(?i)^\s*(?:the\s+)?(?:client|pt|patient|cl|member)\b|^\s*(?:reports?|reported|states?|stated|endorses?|endorsed|denies|denied|describes?|described|shares?|shared|disclosed|admits?|admitted|expressed)\b"
+    ).unwrap();
+}
+
+/// Start of the sentence containing `offset`
+fn sentence_start(text: &str, offset: usize) -> usize {
+    text[..offset]
+        .rfind(['.', '!', '?', '\n'])
+        .map(|i| i + 1)
+        .unwrap_or(0)
+}
+
+/// Attribute the sentence around a match offset to client, third party or clinician
+pub fn attribute_subject(text: &str, match_start: usize) -> SubjectAttribution {
+    let match_start = (0..=match_start.min(text.len())).rev()
+        .find(|i| text.is_char_boundary(*i))
+        .unwrap_or(0);
+    let lead = &text[sentence_start(text, match_start)..match_start];
+    
+    if let Some(caps) = THIRD_PARTY_SUBJECT.captures(lead) {
+        return SubjectAttribution {
+            role: SubjectRole::ThirdParty,
+            relation: caps.get(1).map(|r| r.as_str().to_lowercase()),
+            confidence: 0.8,
+            cue: caps.get(0).map(|c| c.as_str().trim().to_string()).unwrap_or_default(),
+        };
+    }
+    if let Some(m) = CLINICIAN_SUBJECT.find(lead) {
+        return SubjectAttribution {
+            role: SubjectRole::Clinician,
+            relation: None,
+            confidence: 0.7,
+            cue: m.as_str().trim().to_string(),
+        };
+    }
+    if let Some(m) = CLIENT_SUBJECT.find(lead) {
+        return SubjectAttribution {
+            role: SubjectRole::Client,
+            relation: None,
+            confidence: 0.8,
+            cue: m.as_str().trim().to_string(),
+        };
+    }
+    SubjectAttribution {
+        role: SubjectRole::Unknown,
+        relation: None,
+        confidence: 0.0,
+        cue: String::new(),
+    }
+}
+
+// ============================================
+// Information Source
+// ============================================
+//
+// A parent's call or a school report is collateral information, not a
+// client disclosure, and a mandatory report has to say where a concern
+// came from. Clinicians mark collateral with a heading line that starts
+// with "Collateral" ("Collateral (school report):", "## Collateral
+// contact - mother by phone"). Everything from that line to the next
+// section heading is collateral; the rest of the note is session content.
+// Pattern detections carry the source of the text they matched, and
+// mandatory-reporting suggestions cite it.
+
+/// Rules whose suggestions cite the information source
+const MANDATORY_REPORTING_RULES: &[&str] = &["safety-abuse-child", "safety-abuse-elder", "safety-abuse-vulnerable"];
+
+/// Labels that end a collateral block when used as "Label:" headings
+const SECTION_HEADINGS: &[&str] = &[
+    "subjective", "objective", "assessment", "plan", "data", "response",
+    "intervention", "interventions", "session", "session content", "session notes",
+    "presenting problem", "presenting concerns", "mental status", "mse",
+    "risk", "risk assessment", "safety", "safety plan", "diagnosis", "goals",
+    "progress", "homework", "next steps", "summary", "behavior", "client report",
+];
+
+/// A run of note text with one information source
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SourceSegment {
+    pub start: usize,
+    pub end: usize,
+    pub source: InformationSource,
+}
+
+fn session_source() -> InformationSource {
+    InformationSource { kind: SourceKind::Session, description: None }
+}
+
+/// Label of a heading line: the text after markdown `#`s, or before the
+/// colon of a "Label:" line. None for ordinary lines.
+fn heading_label(line: &str) -> Option<&str> {
+    let line = line.trim();
+    let label = match line.strip_prefix('#') {
+        Some(rest) => rest.trim_start_matches('#').trim_end_matches(':'),
+        None => line.split_once(':')?.0,
+    };
+    Some(label.trim_matches(|c: char| c == '*' || c == '_' || c.is_whitespace()))
+}
+
+/// Source a heading line switches to, or None if the line is not a heading
+fn heading_source(line: &str) -> Option<InformationSource> {
+    let markdown = line.trim_start().starts_with('#');
+    let label = heading_label(line)?;
+    let lower = label.to_lowercase();
+    if let Some(rest) = lower.strip_prefix("collateral") {
+        if !rest.is_empty() && !rest.starts_with(|c: char| !c.is_alphanumeric()) {
+            return None;
+        }
+        // "Collateral contact - mother by phone" -> "mother by phone"
+        let rest = &label["collateral".len()..];
+        let rest = rest.trim_start();
+        let rest = ["contact", "information", "info", "report"].iter()
+            .find(|w| rest.to_lowercase().starts_with(*w)
+                && !rest[w.len()..].starts_with(|c: char| c.is_alphanumeric()))
+            .map(|w| &rest[w.len()..])
+            .unwrap_or(rest);
+        let description = rest.trim_matches(|c: char| "-–—(),:".contains(c) || c.is_whitespace());
+        return Some(InformationSource {
+            kind: SourceKind::Collateral,
+            description: (!description.is_empty()).then(|| description.to_string()),
+        });
+    }
+    let all_caps = label.chars().any(|c| c.is_alphabetic())
+        && !label.chars().any(|c| c.is_lowercase());
+    (markdown || all_caps || SECTION_HEADINGS.contains(&lower.as_str())).then(session_source)
+}
+
+/// Split a note into session and collateral runs covering the whole text.
+/// Offsets are byte offsets into `text` (pass normalized text to line up
+/// with detection offsets).
+pub fn source_segments(text: &str) -> Vec<SourceSegment> {
+    let mut segments = Vec::new();
+    let mut current = SourceSegment { start: 0, end: 0, source: session_source() };
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        if let Some(source) = heading_source(line) {
+            if source != current.source {
+                current.end = offset;
+                let next = SourceSegment { start: offset, end: offset, source };
+                let done = std::mem::replace(&mut current, next);
+                if done.end > done.start {
+                    segments.push(done);
+                }
+            }
+        }
+        offset += line.len();
+    }
+    current.end = text.len();
+    if current.end > current.start || segments.is_empty() {
+        segments.push(current);
+    }
+    segments
+}
+
+/// Source of the text at `offset`
+pub fn source_at(segments: &[SourceSegment], offset: usize) -> InformationSource {
+    segments.iter()
+        .find(|s| s.start <= offset && offset < s.end)
+        .map(|s| s.source.clone())
+        .unwrap_or_else(session_source)
+}
+
+/// A rule's suggestion; mandatory-reporting rules also say where the
+/// information came from, since the report has to
+fn cited_suggestion(pattern_def: &DetectionPattern, source: &InformationSource) -> String {
+    if !MANDATORY_REPORTING_RULES.contains(&pattern_def.id) {
+        return pattern_def.suggestion.to_string();
+    }
+    let citation = match (source.kind, source.description.as_deref()) {
+        (SourceKind::Session, _) => "Source: direct session content.".to_string(),
+        (SourceKind::Collateral, description) => format!(
+            "Source: collateral contact{}, not a client disclosure. Document who reported it, their relationship to the client, when and how, and whether the client was asked directly.",
+            description.map(|d| format!(" ({})", d)).unwrap_or_default(),
+        ),
+    };
+    format!("{} {}", pattern_def.suggestion, citation)
+}
+
+// ============================================
+// Documentation by Exception (absence detection)
+// ============================================
+//
+// The pattern rules above flag language that IS present. These rules flag
+// required documentation that is MISSING given what we know about the client:
+// e.g. an active suicide-risk event with no risk assessment in today's note.
+
+/// Kind of open risk carried forward from earlier sessions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RiskEventKind {
+    SuicidalIdeation,
+    HomicidalIdeation,
+    Abuse,
+}
+
+/// A risk signal from a prior note (detection or structured MSE)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RiskEvent {
+    pub kind: RiskEventKind,
+    pub source_note_id: String,
+    pub session_date: String,
+    pub source: String,            // "detection" or "mse"
+}
+
+/// What the detector knows about the client beyond the note text
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ClientContext {
+    pub note_type: Option<NoteType>,
+    #[serde(default)]
+    pub active_risk_events: Vec<RiskEvent>,
+}
+
+enum AbsenceTrigger {
+    ActiveRisk(RiskEventKind),
+    NoteType(NoteType),
+}
+
+struct AbsenceRule {
+    id: &'static str,
+    category: &'static str,
+    severity: DetectionSeverity,
+    trigger: AbsenceTrigger,
+    /// Any match means the required documentation is present
+    required: Vec<&'static str>,
+    title: &'static str,
+    description: &'static str,
+    suggestion: &'static str,
+    policy_ref: Option<&'static str>,
+}
+
+impl AbsenceRule {
+    fn applies(&self, ctx: &ClientContext) -> bool {
+        match &self.trigger {
+            AbsenceTrigger::ActiveRisk(kind) => ctx.active_risk_events.iter().any(|e| e.kind == *kind),
+            AbsenceTrigger::NoteType(note_type) => ctx.note_type == Some(*note_type),
+        }
+    }
+    
+    fn detection(&self, id: String) -> EthicsDetection {
+        EthicsDetection {
+            id,
+            severity: self.severity,
+            category: self.category.to_string(),
+            title: self.title.to_string(),
+            description: self.description.to_string(),
+            evidence: "Required documentation not found in note".to_string(),
+            suggestion: self.suggestion.to_string(),
+            policy_ref: self.policy_ref.map(|s| s.to_string()),
+            requires_attestation: self.severity == DetectionSeverity::Attest,
+            attribution: None,
+            source: None,
+        }
+    }
+}
+
+lazy_static::lazy_static! {
+    static ref ABSENCE_RULES: Vec<AbsenceRule> = vec![
+        AbsenceRule {
+            id: "absence-si-assessment",
+            category: "safety",
+            severity: DetectionSeverity::Attest,
+            trigger: AbsenceTrigger::ActiveRisk(RiskEventKind::SuicidalIdeation),
+            required: vec![
+                r"(?i)\b(suicid\w*|(?-i:SI)|risk assessment|safety plan\w*|C-?SSRS|columbia|protective factors|lethal means|means restriction)\b",
+            ],
+            title: "Suicide Risk Not Reassessed",
+            description: "Client has an active suicide-risk event but this note contains no risk assessment",
+            suggestion: "Document current ideation, plan, intent, protective factors and safety plan review, or attest why reassessment was not indicated",
+            policy_ref: Some("Clinical standard of care"),
+        },
+        AbsenceRule {
+            id: "absence-hi-assessment",
+            category: "safety",
+            severity: DetectionSeverity::Attest,
+            trigger: AbsenceTrigger::ActiveRisk(RiskEventKind::HomicidalIdeation),
+            required: vec![
+                r"(?i)\b(homicid\w*|(?-i:HI)|violence risk|threat assessment|duty to (warn|protect)|intended victim)\b",
+            ],
+            title: "Violence Risk Not Reassessed",
+            description: "Client has an active violence-risk event but this note contains no risk assessment",
+            suggestion: "Document current homicidal ideation, identifiable victim, intent and any duty-to-protect actions",
+            policy_ref: Some("Tarasoff / state duty-to-protect statutes"),
+        },
+        AbsenceRule {
+            id: "absence-abuse-followup",
+            category: "safety",
+            severity: DetectionSeverity::Flag,
+            trigger: AbsenceTrigger::ActiveRisk(RiskEventKind::Abuse),
+            required: vec![
+                r"(?i)\b(report(ed|ing)?|(?-i:CPS|APS)|child protective|adult protective|mandated|hotline|follow(ed)?[- ]?up)\b",
+            ],
+            title: "Abuse Concern Without Follow-up",
+            description: "An abuse concern was recorded for this client but this note does not document reporting or follow-up",
+            suggestion: "Document report status (agency, date, reference) or the clinical follow-up on the prior concern",
+            policy_ref: Some("State mandatory reporting requirements"),
+        },
+        AbsenceRule {
+            id: "absence-crisis-disposition",
+            category: "documentation",
+            severity: DetectionSeverity::Flag,
+            trigger: AbsenceTrigger::NoteType(NoteType::Crisis),
+            required: vec![
+                r"(?i)\b(safety plan\w*|disposition|level of care|hospitali[sz]\w*|referr\w*|emergency (department|room)|(?-i:ED|ER)|mobile crisis)\b",
+            ],
+            title: "Crisis Note Missing Disposition",
+            description: "Crisis note does not document a safety plan, disposition or level-of-care decision",
+            suggestion: "Document the disposition (safety plan, referral, level of care) and who was involved",
+            policy_ref: None,
+        },
+        AbsenceRule {
+            id: "absence-intake-risk-screen",
+            category: "documentation",
+            severity: DetectionSeverity::Flag,
+            trigger: AbsenceTrigger::NoteType(NoteType::Intake),
+            required: vec![
+                r"(?i)\b(suicid\w*|(?-i:SI|HI)|homicid\w*|risk (screen\w*|assessment)|self[- ]harm)\b",
+            ],
+            title: "Intake Missing Risk Screening",
+            description: "Intake assessment does not document a suicide/violence risk screen",
+            suggestion: "Document risk screening results, including denials",
+            policy_ref: Some("Clinical standard of care"),
+        },
+    ];
+    
+    /// Absence-rule patterns, compiled once
+    static ref REQUIRED_REGEXES: HashMap<&'static str, Regex> = ABSENCE_RULES.iter()
+        .flat_map(|r| r.required.iter())
+        .filter_map(|p| Regex::new(p).ok().map(|re| (*p, re)))
+        .collect();
+}
+
+/// Map prior-note detections onto the risk events they imply
+pub fn risk_event_kind_for_pattern(pattern_id: &str) -> Option<RiskEventKind> {
+    match pattern_id {
+        "safety-si-euphemism" | "safety-si-rehearsal" | "safety-means-access" => Some(RiskEventKind::SuicidalIdeation),
+        "safety-hi-threat" | "safety-duty-warn" | "safety-threat-escalation" => Some(RiskEventKind::HomicidalIdeation),
+        "safety-abuse-child" | "safety-abuse-elder" | "safety-abuse-vulnerable" => Some(RiskEventKind::Abuse),
+        _ => None,
+    }
+}
+
+/// Risk events implied by a prior note's detection ids
+pub fn risk_events_from_detections(detection_ids: &[String], note_id: &str, session_date: &str) -> Vec<RiskEvent> {
+    let mut kinds: Vec<RiskEventKind> = Vec::new();
+    for kind in detection_ids.iter().filter_map(|id| risk_event_kind_for_pattern(pattern_id_of(id))) {
+        if !kinds.contains(&kind) {
+            kinds.push(kind);
+        }
+    }
+    
+    kinds.into_iter().map(|kind| RiskEvent {
+        kind,
+        source_note_id: note_id.to_string(),
+        session_date: session_date.to_string(),
+        source: "detection".to_string(),
+    }).collect()
+}
+
+/// Whether `rule_id` is an absence rule (fires on missing documentation,
+/// given client context) rather than a text pattern
+pub fn is_absence_rule(rule_id: &str) -> bool {
+    ABSENCE_RULES.iter().any(|r| r.id == rule_id)
+}
+
+/// Pattern analysis plus absence rules driven by client context
+pub fn analyze_with_context(text: &str, ctx: &ClientContext) -> EthicsAnalysis {
+    let mut analysis = analyze(text);
+    let normalized = normalize_text(text);
+    
+    for rule in ABSENCE_RULES.iter().filter(|r| r.applies(ctx)) {
+        let documented = rule.required.iter().any(|req| {
+            REQUIRED_REGEXES.get(req).is_some_and(|re| re.is_match(&normalized))
+        });
+        if documented {
+            continue;
+        }
+        
+        // No span: the finding is about the whole note
+        let detection_id = format!("{}-0", rule.id);
+        analysis.stored_detections.push(StoredDetection {
+            id: detection_id.clone(),
+            pattern_id: rule.id.to_string(),
+            severity: rule.severity,
+            match_start: 0,
+            match_end: 0,
+            content_hash: Some(crate::crypto::hash_sha256(text.as_bytes())),
+            span_hash: None,
+        });
+        analysis.detections.push(rule.detection(detection_id));
+    }
+    
+    analysis.attest_count = analysis.detections.iter().filter(|d| d.severity == DetectionSeverity::Attest).count();
+    analysis.flag_count = analysis.detections.iter().filter(|d| d.severity == DetectionSeverity::Flag).count();
+    analysis.coach_count = analysis.detections.iter().filter(|d| d.severity == DetectionSeverity::Coach).count();
+    analysis
+}
+
+// ============================================
+// Severity Calibration
+// ============================================
+
+/// Per-rule calibration derived from historical attestations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleCalibration {
+    pub pattern_id: String,
+    pub category: String,
+    pub current_severity: DetectionSeverity,
+    pub attestation_count: usize,
+    pub not_relevant_count: usize,
+    pub not_relevant_rate: f64,
+    pub acted_on_rate: f64,                          // addressed in note / consulted / next session
+    pub monthly: Vec<CalibrationPeriod>,
+    pub suggested_severity: Option<DetectionSeverity>,
+    pub rationale: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalibrationPeriod {
+    pub period: String,  // YYYY-MM
+    pub attestation_count: usize,
+    pub not_relevant_rate: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalibrationReport {
+    pub generated_at: i64,
+    pub attestations_analyzed: usize,
+    pub min_samples: usize,
+    pub rules: Vec<RuleCalibration>,
+}
+
+/// Dismissal rate at or above which a rule is suggested for downgrade
+const DOWNGRADE_NOT_RELEVANT_RATE: f64 = 0.9;
+/// Action rate at or above which a low-severity rule is suggested for upgrade
+const UPGRADE_ACTED_ON_RATE: f64 = 0.8;
+
+/// Pattern id from a detection id (`{pattern_id}-{offset}`)
+pub(crate) fn pattern_id_of(detection_id: &str) -> &str {
+    match detection_id.rsplit_once('-') {
+        Some((prefix, offset)) if !offset.is_empty() && offset.chars().all(|c| c.is_ascii_digit()) => prefix,
+        _ => detection_id,
+    }
+}
+
+/// Correlate detection rules with attestation responses and suggest severity adjustments.
+/// Suggestions are advisory for admins; nothing is changed automatically.
+pub fn calibration_report(attestations: &[Attestation], min_samples: usize) -> CalibrationReport {
+    use std::collections::BTreeMap;
+    
+    let mut by_rule: BTreeMap<&str, Vec<&Attestation>> = BTreeMap::new();
+    for att in attestations {
+        by_rule.entry(pattern_id_of(&att.detection_id)).or_default().push(att);
+    }
+    
+    let rules = by_rule.into_iter().filter_map(|(pattern_id, atts)| {
+        let pattern_def = PATTERNS.iter().find(|p| p.id == pattern_id)?;
+        
+        let is_not_relevant = |a: &Attestation| a.response == AttestationResponse::NotClinicallyRelevant;
+        let is_acted_on = |a: &Attestation| matches!(
+            a.response,
+            AttestationResponse::AddressedInNote
+                | AttestationResponse::ConsultedSupervisor
+                | AttestationResponse::WillAddressNextSession
+        );
+        
+        let total = atts.len();
+        let not_relevant_count = atts.iter().filter(|a| is_not_relevant(a)).count();
+        let not_relevant_rate = not_relevant_count as f64 / total as f64;
+        let acted_on_rate = atts.iter().filter(|a| is_acted_on(a)).count() as f64 / total as f64;
+        
+        let mut months: BTreeMap<String, (usize, usize)> = BTreeMap::new();
+        for att in &atts {
+            let period = chrono::DateTime::from_timestamp_millis(att.attested_at)
+                .map(|d| d.format("%Y-%m").to_string())
+                .unwrap_or_else(|| "unknown".to_string());
+            let entry = months.entry(period).or_insert((0, 0));
+            entry.0 += 1;
+            if is_not_relevant(att) {
+                entry.1 += 1;
+            }
+        }
+        let monthly = months.into_iter()
+            .map(|(period, (count, nr))| CalibrationPeriod {
+                period,
+                attestation_count: count,
+                not_relevant_rate: nr as f64 / count as f64,
+            })
+            .collect();
+        
+        let (suggested_severity, rationale) = suggest_severity(
+            pattern_def, total, min_samples, not_relevant_rate, acted_on_rate,
+        );
+        
+        Some(RuleCalibration {
+            pattern_id: pattern_id.to_string(),
+            category: pattern_def.category.to_string(),
+            current_severity: pattern_def.severity,
+            attestation_count: total,
+            not_relevant_count,
+            not_relevant_rate,
+            acted_on_rate,
+            monthly,
+            suggested_severity,
+            rationale,
+        })
+    }).collect();
+    
+    CalibrationReport {
+        generated_at: chrono::Utc::now().timestamp_millis(),
+        attestations_analyzed: attestations.len(),
+        min_samples,
+        rules,
+    }
+}
+
+fn suggest_severity(
+    pattern_def: &DetectionPattern,
+    total: usize,
+    min_samples: usize,
+    not_relevant_rate: f64,
+    acted_on_rate: f64,
+) -> (Option<DetectionSeverity>, String) {
+    if total < min_samples {
+        return (None, format!("Insufficient data ({} of {} attestations)", total, min_samples));
+    }
+    
+    let pct = |r: f64| (r * 100.0).round();
+    
+    if not_relevant_rate >= DOWNGRADE_NOT_RELEVANT_RATE {
+        // Safety rules are never suggested below Attest - a dismissal rate alone
+        // doesn't justify reducing scrutiny of risk language
+        if pattern_def.category == "safety" {
+            return (None, format!(
+                "{}% marked not clinically relevant; safety rule - review patterns/exclusions rather than severity",
+                pct(not_relevant_rate)
+            ));
+        }
+        let lower = match pattern_def.severity {
+            DetectionSeverity::Attest => Some(DetectionSeverity::Flag),
+            DetectionSeverity::Flag => Some(DetectionSeverity::Coach),
+            DetectionSeverity::Coach => None,
+        };
+        let rationale = match lower {
+            Some(_) => format!("{}% marked not clinically relevant - consider downgrading", pct(not_relevant_rate)),
+            None => format!("{}% marked not clinically relevant - consider retiring the rule", pct(not_relevant_rate)),
+        };
+        return (lower, rationale);
+    }
+    
+    if acted_on_rate >= UPGRADE_ACTED_ON_RATE {
+        let higher = match pattern_def.severity {
+            DetectionSeverity::Coach => Some(DetectionSeverity::Flag),
+            DetectionSeverity::Flag => Some(DetectionSeverity::Attest),
+            DetectionSeverity::Attest => None,
+        };
+        if higher.is_some() {
+            return (higher, format!("{}% led to clinical action - consider upgrading", pct(acted_on_rate)));
+        }
+    }
+    
+    (None, "Severity appears well calibrated".to_string())
+}
+
+// ============================================
+// Note Risk Summary
+// ============================================
+//
+// Dashboards and caseload triage need a glanceable number per note, not
+// the raw detection list. Detections are bucketed into three dimensions
+// and weighted by severity; the note's total is compared against the
+// client's recent notes to show direction.
+
+/// Dimension a detection contributes to in the risk summary
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RiskDimension {
+    Safety,
+    Integrity,
+    Privacy,
+}
+
+impl RiskDimension {
+    pub fn for_category(category: &str) -> RiskDimension {
+        match category {
+            "safety" => RiskDimension::Safety,
+            "privacy" | "security" | "telehealth" => RiskDimension::Privacy,
+            // documentation, integrity, boundary, legal, ethics
+            _ => RiskDimension::Integrity,
+        }
+    }
+}
+
+/// Score for one dimension (0-10, saturating)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DimensionScore {
+    pub score: u32,
+    pub detections: usize,
+    pub highest: Option<DetectionSeverity>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NoteRiskSummary {
+    pub note_id: String,
+    pub safety: DimensionScore,
+    pub integrity: DimensionScore,
+    pub privacy: DimensionScore,
+    /// Sum of the dimension scores (0-30)
+    pub total: u32,
+    /// "none", "low", "moderate", "high"
+    pub level: String,
+    /// Attest-level detections without an attestation
+    pub unattested: usize,
+    /// Mean total over the client's previous notes, if any
+    pub baseline: Option<f64>,
+    /// "rising", "stable", "falling", "no_history"
+    pub trend: String,
+    pub compared_notes: usize,
+}
+
+/// Maximum score per dimension
+const DIMENSION_SCORE_CAP: u32 = 10;
+/// Change against the baseline needed to call a trend
+const RISK_TREND_DELTA: f64 = 2.0;
+
+fn severity_weight(severity: DetectionSeverity) -> u32 {
+    match severity {
+        DetectionSeverity::Attest => 5,
+        DetectionSeverity::Flag => 2,
+        DetectionSeverity::Coach => 1,
+    }
+}
+
+/// Category and severity of the rule behind a stored detection id
+fn detection_rule(detection_id: &str) -> Option<(&'static str, DetectionSeverity)> {
+    let pattern_id = pattern_id_of(detection_id);
+    PATTERNS.iter().find(|p| p.id == pattern_id).map(|p| (p.category, p.severity))
+        .or_else(|| ABSENCE_RULES.iter().find(|r| r.id == pattern_id).map(|r| (r.category, r.severity)))
+}
+
+/// Title of the rule behind a stored detection id
+pub fn detection_title(detection_id: &str) -> Option<&'static str> {
+    let pattern_id = pattern_id_of(detection_id);
+    PATTERNS.iter().find(|p| p.id == pattern_id).map(|p| p.title)
+        .or_else(|| ABSENCE_RULES.iter().find(|r| r.id == pattern_id).map(|r| r.title))
+}
+
+/// Per-dimension scores for a note's detection ids; unknown ids are ignored
+pub fn score_detections(detection_ids: &[String]) -> (DimensionScore, DimensionScore, DimensionScore) {
+    let (mut safety, mut integrity, mut privacy) =
+        (DimensionScore::default(), DimensionScore::default(), DimensionScore::default());
+    
+    for (category, severity) in detection_ids.iter().filter_map(|id| detection_rule(id)) {
+        let dim = match RiskDimension::for_category(category) {
+            RiskDimension::Safety => &mut safety,
+            RiskDimension::Integrity => &mut integrity,
+            RiskDimension::Privacy => &mut privacy,
+        };
+        dim.score = (dim.score + severity_weight(severity)).min(DIMENSION_SCORE_CAP);
+        dim.detections += 1;
+        // DetectionSeverity orders Attest first
+        dim.highest = Some(dim.highest.map_or(severity, |h| h.min(severity)));
+    }
+    
+    (safety, integrity, privacy)
+}
+
+/// Total score for a note, used as the trend baseline for later notes
+pub fn risk_total(detection_ids: &[String]) -> u32 {
+    let (safety, integrity, privacy) = score_detections(detection_ids);
+    safety.score + integrity.score + privacy.score
+}
+
+/// Summarize a note's detections. `previous_totals` are the risk totals of
+/// the client's earlier notes (most recent first, already limited).
+pub fn summarize_note_risk(
+    note_id: &str,
+    detection_ids: &[String],
+    attestations: &[Attestation],
+    previous_totals: &[u32],
+) -> NoteRiskSummary {
+    let (safety, integrity, privacy) = score_detections(detection_ids);
+    let total = safety.score + integrity.score + privacy.score;
+    
+    let level = if total == 0 {
+        "none"
+    } else if safety.highest == Some(DetectionSeverity::Attest) || total >= 10 {
+        "high"
+    } else if total >= 4 {
+        "moderate"
+    } else {
+        "low"
+    };
+    
+    let unattested = detection_ids.iter()
+        .filter(|id| matches!(detection_rule(id), Some((_, DetectionSeverity::Attest))))
+        .filter(|id| !attestations.iter().any(|a| &a.detection_id == *id))
+        .count();
+    
+    let baseline = (!previous_totals.is_empty())
+        .then(|| previous_totals.iter().map(|t| *t as f64).sum::<f64>() / previous_totals.len() as f64);
+    let trend = match baseline {
+        None => "no_history",
+        Some(b) if total as f64 - b >= RISK_TREND_DELTA => "rising",
+        Some(b) if b - total as f64 >= RISK_TREND_DELTA => "falling",
+        Some(_) => "stable",
+    };
+    
+    NoteRiskSummary {
+        note_id: note_id.to_string(),
+        safety,
+        integrity,
+        privacy,
+        total,
+        level: level.to_string(),
+        unattested,
+        baseline,
+        trend: trend.to_string(),
+        compared_notes: previous_totals.len(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     
+    #[test]
+    fn test_note_risk_summary() {
+        let ids = vec![
+            "safety-si-euphemism-12".to_string(),
+            "privacy-phi-email-40".to_string(),
+            "unknown-rule-3".to_string(),
+        ];
+        let attested = vec![Attestation {
+            detection_id: "safety-si-euphemism-12".to_string(),
+            response: AttestationResponse::AddressedInNote,
+            response_note: None,
+            attested_at: 0,
+        }];
+        
+        let summary = summarize_note_risk("n1", &ids, &attested, &[0, 1]);
+        assert_eq!(summary.safety.detections, 1);
+        assert_eq!(summary.safety.highest, Some(DetectionSeverity::Attest));
+        assert_eq!(summary.privacy.detections, 1);
+        assert_eq!(summary.integrity.score, 0);
+        assert_eq!(summary.level, "high");
+        assert_eq!(summary.unattested, 1);
+        assert_eq!(summary.trend, "rising");
+        
+        let first = summarize_note_risk("n0", &[], &[], &[]);
+        assert_eq!(first.level, "none");
+        assert_eq!(first.trend, "no_history");
+    }
+    
     #[test]
     fn test_note_6_integrity() {
         let text = "they don't want it in writing";
@@ -995,6 +2045,53 @@ mod tests {
         assert!(!analysis.detections.iter().any(|d| d.id.starts_with("safety-hi")));
     }
     
+    #[test]
+    fn test_role_attribution() {
+        let third = analyze("Client is scared. Her ex said he's going to kill her if she leaves.");
+        let d = third.detections.iter().find(|d| d.id.starts_with("safety-hi-threat")).unwrap();
+        let attribution = d.attribution.as_ref().unwrap();
+        assert_eq!(attribution.role, SubjectRole::ThirdParty);
+        assert_eq!(attribution.relation.as_deref(), Some("ex"));
+        
+        let client = analyze("Client stated he is going to kill his neighbor.");
+        let d = client.detections.iter().find(|d| d.id.starts_with("safety-hi-threat")).unwrap();
+        assert_eq!(d.attribution.as_ref().unwrap().role, SubjectRole::Client);
+        
+        // Hydration from stored offsets gives the same answer
+        let hydrated = hydrate_detections(&third.stored_detections, "Client is scared. Her ex said he's going to kill her if she leaves.");
+        let d = hydrated.iter().find(|d| d.id.starts_with("safety-hi-threat")).unwrap();
+        assert_eq!(d.attribution.as_ref().unwrap().role, SubjectRole::ThirdParty);
+    }
+    
+    #[test]
+    fn test_collateral_source() {
+        let text = "Subjective:\nClient discussed work stress.\n\nCollateral (school report):\nTeacher noticed a bruise with no explanation and child is afraid to go home.\n\nPlan:\nFollow up next week.";
+        let segments = source_segments(text);
+        assert_eq!(segments.len(), 3);
+        assert_eq!(segments[1].source.kind, SourceKind::Collateral);
+        assert_eq!(segments[1].source.description.as_deref(), Some("school report"));
+        assert!(text[segments[1].start..].starts_with("Collateral"));
+        assert!(text[segments[2].start..].starts_with("Plan:"));
+        
+        let analysis = analyze(text);
+        let d = analysis.detections.iter().find(|d| d.id.starts_with("safety-abuse-child")).unwrap();
+        assert_eq!(d.source.as_ref().unwrap().kind, SourceKind::Collateral);
+        assert!(d.suggestion.contains("collateral contact (school report)"));
+        let hydrated = hydrate_detections(&analysis.stored_detections, text);
+        let d = hydrated.iter().find(|d| d.id.starts_with("safety-abuse-child")).unwrap();
+        assert_eq!(d.source.as_ref().unwrap().kind, SourceKind::Collateral);
+        
+        let session = analyze("Client is afraid to go home because dad hits him.");
+        let d = session.detections.iter().find(|d| d.id.starts_with("safety-abuse-child")).unwrap();
+        assert_eq!(d.source.as_ref().unwrap().kind, SourceKind::Session);
+        assert!(d.suggestion.ends_with("Source: direct session content."));
+        
+        let heading = heading_source("## Collateral contact - mother by phone").unwrap();
+        assert_eq!(heading.description.as_deref(), Some("mother by phone"));
+        assert_eq!(heading_source("Collaterally, she agreed: yes"), None);
+        assert_eq!(heading_source("Mother reports: bruises"), None);
+    }
+    
     #[test]
     fn test_stored_detection_evidence_reconstruction() {
         let text = "The client said they want to power down for a while and not be around.";
@@ -1006,4 +2103,133 @@ mod tests {
             assert!(!evidence.is_empty());
         }
     }
+    
+    #[test]
+    fn test_calibration_suggests_downgrade() {
+        let now = chrono::Utc::now().timestamp_millis();
+        let att = |id: &str, response| Attestation {
+            detection_id: id.to_string(),
+            response,
+            response_note: None,
+            attested_at: now,
+        };
+        
+        let mut attestations: Vec<Attestation> = (0..19)
+            .map(|i| att(&format!("security-egress-{}", i), AttestationResponse::NotClinicallyRelevant))
+            .collect();
+        attestations.push(att("security-egress-7", AttestationResponse::AddressedInNote));
+        // Safety rule with the same dismissal rate must not be downgraded
+        attestations.extend((0..10).map(|i| att(&format!("safety-si-euphemism-{}", i), AttestationResponse::NotClinicallyRelevant)));
+        
+        let report = calibration_report(&attestations, 10);
+        let egress = report.rules.iter().find(|r| r.pattern_id == "security-egress").unwrap();
+        assert_eq!(egress.attestation_count, 20);
+        assert!(egress.not_relevant_rate >= 0.9);
+        assert!(egress.suggested_severity.is_some());
+        
+        let si = report.rules.iter().find(|r| r.pattern_id == "safety-si-euphemism").unwrap();
+        assert!(si.suggested_severity.is_none());
+    }
+    
+    #[test]
+    fn test_absence_of_risk_assessment_flags() {
+        let ctx = ClientContext {
+            note_type: Some(NoteType::Progress),
+            active_risk_events: risk_events_from_detections(
+                &["safety-si-rehearsal-42".to_string()],
+                "prior-note",
+                "2024-03-01",
+            ),
+        };
+        assert_eq!(ctx.active_risk_events.len(), 1);
+        
+        let silent = analyze_with_context("Discussed work stress and sleep hygiene.", &ctx);
+        assert!(silent.detections.iter().any(|d| d.id == "absence-si-assessment-0"));
+        assert_eq!(silent.attest_count, 1);
+        
+        let assessed = analyze_with_context("Denies SI; safety plan reviewed.", &ctx);
+        assert!(!assessed.detections.iter().any(|d| d.id.starts_with("absence-")));
+        
+        // Lowercase "hi"/"si" are words, not the acronyms
+        let greeting = analyze_with_context("Client said hi and si to the interpreter.", &ctx);
+        assert!(greeting.detections.iter().any(|d| d.id == "absence-si-assessment-0"));
+        
+        let no_context = analyze_with_context("Discussed work stress.", &ClientContext::default());
+        assert!(no_context.detections.is_empty());
+        
+        let hydrated = hydrate_detections(&silent.stored_detections, "Discussed work stress and sleep hygiene.");
+        assert_eq!(hydrated.len(), 1);
+    }
+    
+    #[test]
+    fn test_detections_reanchor_after_edits() {
+        let text = "Client wants to 'power down for a while'. Discussed coping.";
+        let analysis = analyze(text);
+        let si = analysis.stored_detections.iter().find(|d| d.id.starts_with("safety-si")).unwrap().clone();
+        
+        // Text added before the flagged phrase: same detection ID, new offsets
+        let edited = format!("Session held by video. {}", text);
+        let outcome = reanchor_detections(&analysis.stored_detections, &edited);
+        assert!(!outcome.reanalyze_required());
+        assert_eq!(outcome.reanchored, vec![si.id.clone()]);
+        let moved = outcome.detections.iter().find(|d| d.id == si.id).unwrap();
+        assert_eq!(moved.match_start, si.match_start + "Session held by video. ".len());
+        let hydrated = hydrate_detections(&outcome.detections, &edited);
+        assert!(hydrated.iter().find(|d| d.id == si.id).unwrap().evidence.contains("power down"));
+        
+        // Flagged phrase removed: the detection no longer exists
+        let outcome = reanchor_detections(&analysis.stored_detections, "Client is tired. Discussed coping.");
+        assert!(outcome.reanalyze_required());
+        assert!(outcome.invalidated.contains(&si.id));
+        assert!(outcome.detections.iter().all(|d| d.id != si.id));
+    }
+    
+    #[test]
+    fn test_absence_findings_survive_edits_until_documented() {
+        let ctx = ClientContext {
+            note_type: Some(NoteType::Progress),
+            active_risk_events: risk_events_from_detections(
+                &["safety-si-rehearsal-42".to_string()],
+                "prior-note",
+                "2024-03-01",
+            ),
+        };
+        let analysis = analyze_with_context("Discussed work stress.", &ctx);
+        let absence = analysis.stored_detections.iter().find(|d| d.pattern_id == "absence-si-assessment").unwrap();
+        
+        let outcome = reanchor_detections(&analysis.stored_detections, "Discussed work stress and sleep.");
+        assert!(!outcome.reanalyze_required());
+        assert!(outcome.detections.iter().any(|d| d.id == absence.id));
+        
+        let outcome = reanchor_detections(&analysis.stored_detections, "Discussed work stress. Safety plan reviewed.");
+        assert!(!outcome.reanalyze_required());
+        assert_eq!(outcome.resolved, vec![absence.id.clone()]);
+        assert!(outcome.detections.iter().all(|d| d.id != absence.id));
+    }
+    
+    #[test]
+    fn test_explain_detection() {
+        let text = "Client said she has been in a dark place since the move.";
+        let analysis = analyze(text);
+        let sd = analysis.stored_detections.iter().find(|d| d.pattern_id == "safety-si-euphemism").unwrap();
+        let explanation = explain_detection(sd, text).unwrap();
+        assert_eq!(explanation.rule_kind, "pattern");
+        assert_eq!(explanation.matched_pattern.as_deref(), Some("(dark thoughts?|dark place)"));
+        let span = explanation.matched_span.unwrap();
+        assert_eq!(span.text, "dark place");
+        assert!(span.current);
+        assert_eq!(explanation.exclusions.len(), 2);
+        assert!(explanation.exclusions.iter().all(|c| !c.matched && !c.pattern.contains("(?i)")));
+        assert_eq!(explanation.policy_ref.as_deref(), Some("Clinical standard of care"));
+        
+        assert_eq!(describe_pattern(r"(?i)\b(know (exactly )?how|planned).{0,15}(do it)\b"), "(know (exactly )?how|planned) … (do it)");
+        
+        let ctx = ClientContext { note_type: Some(NoteType::Intake), active_risk_events: Vec::new() };
+        let intake = analyze_with_context("Discussed work stress.", &ctx);
+        let absence = intake.stored_detections.iter().find(|d| d.pattern_id == "absence-intake-risk-screen").unwrap();
+        let explanation = explain_detection(absence, "Discussed work stress.").unwrap();
+        assert_eq!(explanation.rule_kind, "absence");
+        assert!(explanation.matched_span.is_none());
+        assert!(explanation.required_documentation.iter().all(|c| !c.matched));
+    }
 }
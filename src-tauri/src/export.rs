@@ -471,10 +471,175 @@ pub enum ExportDecision {
     Blocked { reason: String, can_override: bool },
 }
 
+// ============================================
+// Encrypted Archives
+// ============================================
+
+/// Alphabet for generated archive passphrases: no 0/O, 1/l/I, so a
+/// passphrase read aloud over the phone survives transcription.
+const PASSPHRASE_ALPHABET: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz23456789";
+const PASSPHRASE_GROUPS: usize = 6;
+const PASSPHRASE_GROUP_LEN: usize = 4;
+
+/// Generate a one-time passphrase for an encrypted export bundle.
+///
+/// Six dash-separated groups of four characters (~140 bits). It is shown to
+/// the clinician once and never stored; only its hash reaches the audit log.
+pub fn generate_archive_passphrase() -> String {
+    use rand::Rng;
+    let mut rng = rand::rngs::OsRng;
+    (0..PASSPHRASE_GROUPS)
+        .map(|_| {
+            (0..PASSPHRASE_GROUP_LEN)
+                .map(|_| PASSPHRASE_ALPHABET[rng.gen_range(0..PASSPHRASE_ALPHABET.len())] as char)
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Build a ZIP archive with every entry encrypted using WinZip AES-256.
+///
+/// AES-256 ZIPs open natively in macOS Archive Utility, 7-Zip and modern
+/// Windows, so the recipient needs no Evidify tooling.
+pub fn build_encrypted_zip(entries: &[(&str, &[u8])], passphrase: &str) -> Result<Vec<u8>, String> {
+    use std::io::{Cursor, Write};
+    use zip::write::SimpleFileOptions;
+
+    if passphrase.is_empty() {
+        return Err("Archive passphrase must not be empty".to_string());
+    }
+
+    let mut buffer = Cursor::new(Vec::new());
+    {
+        let mut zip = zip::ZipWriter::new(&mut buffer);
+        let options = SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Deflated)
+            .with_aes_encryption(zip::AesMode::Aes256, passphrase);
+
+        for (name, data) in entries {
+            zip.start_file(*name, options).map_err(|e| e.to_string())?;
+            zip.write_all(data).map_err(|e| e.to_string())?;
+        }
+        zip.finish().map_err(|e| e.to_string())?;
+    }
+
+    Ok(buffer.into_inner())
+}
+
+// ============================================
+// Per-client Export Pre-flight
+// ============================================
+
+/// What a per-client export should include
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ClientExportOptions {
+    #[serde(default = "default_true")]
+    pub include_notes: bool,
+    #[serde(default = "default_true")]
+    pub include_documents: bool,
+    #[serde(default)]
+    pub include_audit_trail: bool,
+    /// Leave drafts out of the export
+    #[serde(default)]
+    pub signed_only: bool,
+    /// Inclusive session/document date range (YYYY-MM-DD)
+    #[serde(default)]
+    pub date_from: Option<String>,
+    #[serde(default)]
+    pub date_to: Option<String>,
+    #[serde(default = "default_export_format")]
+    pub format: String,
+    /// Destination folder, if already chosen
+    #[serde(default)]
+    pub destination: Option<String>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_export_format() -> String {
+    "pdf".to_string()
+}
+
+impl Default for ClientExportOptions {
+    fn default() -> Self {
+        Self {
+            include_notes: true,
+            include_documents: true,
+            include_audit_trail: false,
+            signed_only: false,
+            date_from: None,
+            date_to: None,
+            format: default_export_format(),
+            destination: None,
+        }
+    }
+}
+
+/// Scope, size and blockers for a per-client export, computed before it runs
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ExportEstimate {
+    pub client_id: String,
+    pub note_count: i64,
+    pub draft_note_count: i64,
+    pub document_count: i64,
+    pub audit_event_count: i64,
+    pub note_bytes: i64,
+    pub document_bytes: i64,
+    pub total_bytes: i64,
+    pub estimated_seconds: u64,
+    /// Background tasks already queued when the estimate was made
+    pub queued_tasks: usize,
+    /// Reasons the export cannot start as configured
+    pub blockers: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+impl ExportEstimate {
+    pub fn can_start(&self) -> bool {
+        self.blockers.is_empty()
+    }
+}
+
+/// Sustained encrypt-and-write throughput for export bundles
+const EXPORT_BYTES_PER_SECOND: u64 = 8 * 1024 * 1024;
+/// Average serialized size of one audit entry
+pub const AUDIT_ENTRY_BYTES: i64 = 400;
+
+/// Per-note render cost by output format
+fn note_render_millis(format: &str) -> u64 {
+    match format {
+        "pdf" => 60,
+        "docx" => 35,
+        _ => 2,
+    }
+}
+
+/// Rough wall-clock time for an export, rounded up to whole seconds
+pub fn estimate_export_seconds(note_count: i64, total_bytes: i64, format: &str) -> u64 {
+    let render_ms = note_count.max(0) as u64 * note_render_millis(format);
+    let write_ms = total_bytes.max(0) as u64 * 1000 / EXPORT_BYTES_PER_SECOND;
+    (render_ms + write_ms).div_ceil(1000)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     
+    #[test]
+    fn test_export_time_estimate() {
+        assert_eq!(estimate_export_seconds(0, 0, "pdf"), 0);
+        // 100 PDFs at 60ms + 16 MiB at 8 MiB/s
+        assert_eq!(estimate_export_seconds(100, 16 * 1024 * 1024, "pdf"), 8);
+        assert_eq!(estimate_export_seconds(100, 0, "json"), 1);
+        
+        let options: ClientExportOptions = serde_json::from_str(r#"{"signed_only": true}"#).unwrap();
+        assert!(options.include_notes && options.include_documents && options.signed_only);
+        assert_eq!(options.format, "pdf");
+    }
+    
     #[test]
     fn test_pattern_detection() {
         let path = Path::new("/Users/test/Dropbox/Documents");
@@ -501,4 +666,25 @@ mod tests {
             _ => panic!("Should be blocked"),
         }
     }
+    
+    #[test]
+    fn test_encrypted_zip_requires_passphrase() {
+        use std::io::{Cursor, Read};
+
+        let passphrase = generate_archive_passphrase();
+        assert_eq!(passphrase.len(), PASSPHRASE_GROUPS * (PASSPHRASE_GROUP_LEN + 1) - 1);
+
+        let bytes = build_encrypted_zip(&[("case.txt", b"de-identified case")], &passphrase).unwrap();
+        let mut archive = zip::ZipArchive::new(Cursor::new(bytes)).unwrap();
+
+        assert!(archive.by_index_decrypt(0, b"wrong-passphrase").is_err());
+
+        let mut contents = String::new();
+        archive
+            .by_index_decrypt(0, passphrase.as_bytes())
+            .unwrap()
+            .read_to_string(&mut contents)
+            .unwrap();
+        assert_eq!(contents, "de-identified case");
+    }
 }
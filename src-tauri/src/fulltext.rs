@@ -0,0 +1,330 @@
+// Full-Text Search - FTS5 index over note bodies
+//
+// `notes_fts` is an external-content FTS5 table over `notes` (raw_input and
+// structured_note), kept in sync by triggers, so the text is stored once
+// and the index only holds tokens. It is keyed by the notes table's rowid,
+// which VACUUM may renumber (notes has a TEXT primary key); anything that
+// vacuums the vault calls `rebuild_index` afterwards.
+//
+// Queries typed in the search box are translated rather than passed to
+// MATCH, so FTS5 operators and stray quotes are never a syntax error:
+// - bare words must all match
+// - "quoted text" matches as a phrase
+// - a trailing * matches any word with that prefix (also on a phrase)
+
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+/// Marks a match in `snippet()` output; stripped before returning
+const MATCH_OPEN: char = '\u{2}';
+const MATCH_CLOSE: char = '\u{3}';
+
+/// Tokens of context in a snippet
+const SNIPPET_TOKENS: i32 = 24;
+
+/// Index table, sync triggers. Safe to run on every unlock.
+pub const SCHEMA: &str = r#"
+    CREATE VIRTUAL TABLE IF NOT EXISTS notes_fts USING fts5(
+        raw_input,
+        structured_note,
+        content = 'notes',
+        content_rowid = 'rowid',
+        tokenize = 'unicode61 remove_diacritics 2'
+    );
+
+    CREATE TRIGGER IF NOT EXISTS trg_notes_fts_insert AFTER INSERT ON notes BEGIN
+        INSERT INTO notes_fts (rowid, raw_input, structured_note)
+            VALUES (NEW.rowid, NEW.raw_input, NEW.structured_note);
+    END;
+    CREATE TRIGGER IF NOT EXISTS trg_notes_fts_delete AFTER DELETE ON notes BEGIN
+        INSERT INTO notes_fts (notes_fts, rowid, raw_input, structured_note)
+            VALUES ('delete', OLD.rowid, OLD.raw_input, OLD.structured_note);
+    END;
+    CREATE TRIGGER IF NOT EXISTS trg_notes_fts_update AFTER UPDATE OF raw_input, structured_note ON notes BEGIN
+        INSERT INTO notes_fts (notes_fts, rowid, raw_input, structured_note)
+            VALUES ('delete', OLD.rowid, OLD.raw_input, OLD.structured_note);
+        INSERT INTO notes_fts (rowid, raw_input, structured_note)
+            VALUES (NEW.rowid, NEW.raw_input, NEW.structured_note);
+    END;
+"#;
+
+/// Create the index if needed, filling it from existing notes when new
+pub fn ensure_index(conn: &Connection) -> rusqlite::Result<()> {
+    let existed = conn.query_row(
+        "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'notes_fts'",
+        [],
+        |_| Ok(()),
+    ).optional()?.is_some();
+    conn.execute_batch(SCHEMA)?;
+    if !existed {
+        rebuild_index(conn)?;
+    }
+    Ok(())
+}
+
+/// Re-read every note into the index (after VACUUM, or to repair drift)
+pub fn rebuild_index(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute("INSERT INTO notes_fts (notes_fts) VALUES ('rebuild')", [])?;
+    Ok(())
+}
+
+/// Merge the index segments. Deleting a note only records a delete
+/// marker over its tokens; after a purge or records destruction this
+/// drops them for good.
+pub fn optimize_index(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute("INSERT INTO notes_fts (notes_fts) VALUES ('optimize')", [])?;
+    Ok(())
+}
+
+// ============================================
+// Query Translation
+// ============================================
+
+/// FTS5 string literal
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('"', "\"\""))
+}
+
+/// Translate a search-box query into an FTS5 MATCH expression, or None
+/// if it has nothing to search for
+pub fn fts5_query(input: &str) -> Option<String> {
+    let mut terms = Vec::new();
+    let mut chars = input.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+
+        let (text, quoted) = if c == '"' {
+            chars.next();
+            let phrase: String = chars.by_ref().take_while(|&c| c != '"').collect();
+            (phrase, true)
+        } else {
+            let mut word = String::new();
+            while let Some(&c) = chars.peek() {
+                if c.is_whitespace() || c == '"' {
+                    break;
+                }
+                word.push(c);
+                chars.next();
+            }
+            (word, false)
+        };
+
+        // A phrase takes its prefix marker after the closing quote
+        let mut prefix = false;
+        let mut text = text.trim().to_string();
+        if quoted {
+            if chars.peek() == Some(&'*') {
+                chars.next();
+                prefix = true;
+            }
+        } else if text.ends_with('*') {
+            text = text.trim_end_matches('*').to_string();
+            prefix = true;
+        }
+
+        // Only words and numbers are indexed; a term of punctuation
+        // alone would match nothing
+        if !text.chars().any(char::is_alphanumeric) {
+            continue;
+        }
+        terms.push(if prefix { format!("{}*", quote(&text)) } else { quote(&text) });
+    }
+
+    (!terms.is_empty()).then(|| terms.join(" "))
+}
+
+// ============================================
+// Search
+// ============================================
+
+/// Matched span in a snippet, in characters (Unicode scalar values)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HighlightSpan {
+    pub start: usize,
+    pub end: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FulltextHit {
+    pub note_id: String,
+    pub client_id: String,
+    pub session_date: String,
+    pub note_type: String,
+    /// Excerpt around the best-matching part of the note
+    pub snippet: String,
+    /// Matched terms within `snippet`
+    pub highlights: Vec<HighlightSpan>,
+    /// BM25 relevance; lower is better
+    pub rank: f64,
+}
+
+/// Strip the match markers from `snippet()` output, recording where they were
+fn parse_snippet(marked: &str) -> (String, Vec<HighlightSpan>) {
+    let mut text = String::with_capacity(marked.len());
+    let mut highlights = Vec::new();
+    let mut len = 0;
+    let mut open = None;
+    for c in marked.chars() {
+        match c {
+            MATCH_OPEN => open = Some(len),
+            MATCH_CLOSE => {
+                if let Some(start) = open.take() {
+                    highlights.push(HighlightSpan { start, end: len });
+                }
+            }
+            _ => {
+                text.push(c);
+                len += 1;
+            }
+        }
+    }
+    (text, highlights)
+}
+
+/// Notes matching `query` (see `fts5_query`), best first. Trashed notes,
+/// and notes of trashed clients, stay indexed, so restoring one needs no
+/// reindex, but are not returned.
+pub fn search_notes(
+    conn: &Connection,
+    query: &str,
+    client_id: Option<&str>,
+    limit: usize,
+) -> rusqlite::Result<Vec<FulltextHit>> {
+    let Some(expression) = fts5_query(query) else {
+        return Ok(vec![]);
+    };
+
+    let mut stmt = conn.prepare(
+        "SELECT n.id, n.client_id, n.session_date, n.note_type,
+                snippet(notes_fts, -1, ?4, ?5, '...', ?6),
+                bm25(notes_fts)
+         FROM notes_fts
+         JOIN notes n ON n.rowid = notes_fts.rowid
+         WHERE notes_fts MATCH ?1
+           AND n.deleted_at IS NULL
+           AND n.client_id NOT IN (SELECT id FROM clients WHERE deleted_at IS NOT NULL)
+           AND (?2 IS NULL OR n.client_id = ?2)
+         ORDER BY bm25(notes_fts)
+         LIMIT ?3"
+    )?;
+    let hits = stmt.query_map(
+        params![
+            expression,
+            client_id,
+            limit as i64,
+            MATCH_OPEN.to_string(),
+            MATCH_CLOSE.to_string(),
+            SNIPPET_TOKENS,
+        ],
+        |row| {
+            let (snippet, highlights) = parse_snippet(&row.get::<_, String>(4)?);
+            Ok(FulltextHit {
+                note_id: row.get(0)?,
+                client_id: row.get(1)?,
+                session_date: row.get(2)?,
+                note_type: row.get(3)?,
+                snippet,
+                highlights,
+                rank: row.get(5)?,
+            })
+        },
+    )?.collect::<Result<Vec<_>, _>>()?;
+    Ok(hits)
+}
+
+// ============================================
+// Tauri Commands
+// ============================================
+
+use tauri::State;
+use crate::commands::AppState;
+
+/// Keyword search over note bodies: all words must match, "quoted text"
+/// is a phrase, word* is a prefix
+#[tauri::command]
+pub fn search_notes_fulltext(
+    state: State<AppState>,
+    policy_state: State<'_, crate::policy::PolicyState>,
+    query: String,
+    client_id: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<FulltextHit>, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    if let Some(ref client_id) = client_id {
+        crate::commands::ensure_chart_access(&vault, &policy_state, client_id)?;
+    }
+    let conn = vault.get_connection().map_err(|e| format!("{}", e))?;
+    let hits = search_notes(conn, &query, client_id.as_deref(), limit.unwrap_or(50).min(500))
+        .map_err(|e| format!("{}", e))?;
+    let hits = crate::commands::retain_accessible_charts(&vault, &policy_state, hits, |h| Some(h.client_id.as_str()))?;
+    let note_ids: Vec<&str> = hits.iter().map(|h| h.note_id.as_str()).collect();
+    crate::commands::log_note_retrieval(&vault, "fulltext_search", &note_ids)?;
+    Ok(hits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fulltext_search() {
+        assert_eq!(fts5_query("sleep  hygiene"), Some(r#""sleep" "hygiene""#.to_string()));
+        assert_eq!(fts5_query(r#""safety plan"* anx* NEAR("#), Some(r#""safety plan"* "anx"* "NEAR(""#.to_string()));
+        assert_eq!(fts5_query(r#"say "hi"#), Some(r#""say" "hi""#.to_string()));
+        assert_eq!(fts5_query(" * -- \"\" "), None);
+
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE clients (id TEXT PRIMARY KEY, deleted_at INTEGER);
+             INSERT INTO clients VALUES ('c1', NULL), ('c2', NULL);
+             CREATE TABLE notes (id TEXT PRIMARY KEY, client_id TEXT NOT NULL, session_date TEXT NOT NULL,
+                                 note_type TEXT NOT NULL, raw_input TEXT NOT NULL, structured_note TEXT,
+                                 deleted_at INTEGER);
+             INSERT INTO notes VALUES ('n0', 'c1', '2024-01-01', 'progress', 'Existing note about insomnia', NULL, NULL);",
+        ).unwrap();
+        ensure_index(&conn).unwrap();
+        assert_eq!(search_notes(&conn, "insomnia", None, 10).unwrap().len(), 1);
+
+        conn.execute_batch(
+            "INSERT INTO notes VALUES ('n1', 'c1', '2024-02-01', 'progress',
+                 'Client reviewed the safety plan and reported anxiety at work.', NULL, NULL);
+             INSERT INTO notes VALUES ('n2', 'c2', '2024-02-02', 'intake',
+                 'Plan for safety reviewed.', 'Café visit; anxious before meetings.', NULL);",
+        ).unwrap();
+
+        let phrase = search_notes(&conn, r#""safety plan""#, None, 10).unwrap();
+        assert_eq!(phrase.iter().map(|h| h.note_id.as_str()).collect::<Vec<_>>(), ["n1"]);
+        let hit = &phrase[0];
+        let chars: Vec<char> = hit.snippet.chars().collect();
+        let marked: Vec<String> = hit.highlights.iter()
+            .map(|s| chars[s.start..s.end].iter().collect())
+            .collect();
+        assert_eq!(marked, ["safety plan"]);
+
+        // Prefix, diacritics folded, structured note searched, client filter
+        assert_eq!(search_notes(&conn, "anx*", None, 10).unwrap().len(), 2);
+        assert_eq!(search_notes(&conn, "anx*", Some("c2"), 10).unwrap()[0].note_id, "n2");
+        assert_eq!(search_notes(&conn, "cafe", None, 10).unwrap()[0].note_id, "n2");
+        conn.execute("UPDATE clients SET deleted_at = 1 WHERE id = 'c2'", []).unwrap();
+        assert!(search_notes(&conn, "cafe", None, 10).unwrap().is_empty());
+        conn.execute("UPDATE clients SET deleted_at = NULL WHERE id = 'c2'", []).unwrap();
+
+        conn.execute("UPDATE notes SET deleted_at = 1 WHERE id = 'n0'", []).unwrap();
+        assert!(search_notes(&conn, "insomnia", None, 10).unwrap().is_empty());
+
+        conn.execute("UPDATE notes SET raw_input = 'Rewritten' WHERE id = 'n1'", []).unwrap();
+        conn.execute("DELETE FROM notes WHERE id = 'n2'", []).unwrap();
+        assert!(search_notes(&conn, "safety", None, 10).unwrap().is_empty());
+        assert_eq!(search_notes(&conn, "rewritten", None, 10).unwrap()[0].note_id, "n1");
+        optimize_index(&conn).unwrap();
+        assert!(search_notes(&conn, "safety", None, 10).unwrap().is_empty());
+
+        conn.execute("VACUUM", []).unwrap();
+        rebuild_index(&conn).unwrap();
+        assert_eq!(search_notes(&conn, "rewritten", None, 10).unwrap()[0].note_id, "n1");
+    }
+}
@@ -0,0 +1,159 @@
+// Idempotency Module
+//
+// The webview retries a command when the response doesn't arrive in time,
+// and a retry of a create is a second create - duplicate clients and
+// duplicate review submissions have come from exactly that. Mutating
+// commands accept an optional idempotency key: the first call with a key
+// runs and its result is stored (in the vault, for a TTL) alongside a hash
+// of the request and of the result; a replay with the same key and request
+// gets the stored result back without running again. Reusing a key for a
+// different request is an error rather than a silent replay.
+//
+// Failures are not stored, so retrying a failed call runs it again. A
+// stored result can carry note or client content, so purging a note or
+// destroying a chart also drops the results that name it.
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::vault::Vault;
+
+/// How long a processed key is remembered
+pub const IDEMPOTENCY_KEY_TTL_HOURS: i64 = 24;
+
+/// Error prefix the UI matches on when a key is reused for another request
+pub const IDEMPOTENCY_KEY_REUSED: &str = "IDEMPOTENCY_KEY_REUSED";
+
+/// A processed key as stored in the vault
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IdempotencyRecord {
+    pub key: String,
+    pub command: String,
+    /// SHA-256 of the canonical request arguments
+    pub request_hash: String,
+    pub result_json: String,
+    /// SHA-256 of result_json, checked before a replay is returned
+    pub result_hash: String,
+    pub created_at: i64,
+    pub expires_at: i64,
+}
+
+/// Hash of a command's arguments over their canonical form, so argument
+/// order doesn't matter.
+pub fn request_hash(command: &str, args: &serde_json::Value) -> String {
+    let body = evidify_canonicalization::try_canonical_bytes(&serde_json::json!({ "command": command, "args": args }))
+        .unwrap_or_default();
+    crate::crypto::hash_sha256(&body)
+}
+
+/// The stored result for a replay, or None if the record has expired.
+/// Errors if the key was used for a different command or request, or if
+/// the stored result no longer matches its hash.
+pub fn replay<T: DeserializeOwned>(
+    record: &IdempotencyRecord,
+    command: &str,
+    request_hash: &str,
+    now_ms: i64,
+) -> Result<Option<T>, String> {
+    if record.expires_at <= now_ms {
+        return Ok(None);
+    }
+    if record.command != command || !crate::crypto::digests_match(&record.request_hash, request_hash) {
+        return Err(format!(
+            "{}: idempotency key {} was already used for a different request",
+            IDEMPOTENCY_KEY_REUSED, record.key
+        ));
+    }
+    if !crate::crypto::digests_match(&crate::crypto::hash_sha256(record.result_json.as_bytes()), &record.result_hash) {
+        return Err(format!("Stored result for idempotency key {} failed its integrity check", record.key));
+    }
+    serde_json::from_str(&record.result_json)
+        .map(Some)
+        .map_err(|e| format!("Stored result for idempotency key {} is unreadable: {}", record.key, e))
+}
+
+/// Run `f` once per key. Without a key this is just `f()`. The caller
+/// holds the vault lock for the whole call, so a retry arriving while the
+/// first attempt is still running waits and then replays.
+pub fn run_once<T, F>(
+    vault: &Vault,
+    key: Option<&str>,
+    command: &str,
+    args: serde_json::Value,
+    f: F,
+) -> Result<T, String>
+where
+    T: Serialize + DeserializeOwned,
+    F: FnOnce() -> Result<T, String>,
+{
+    let Some(key) = key.map(str::trim).filter(|k| !k.is_empty()) else {
+        return f();
+    };
+
+    let now = chrono::Utc::now().timestamp_millis();
+    let hash = request_hash(command, &args);
+    if let Some(record) = vault.get_idempotency_record(key).map_err(|e| format!("{}", e))? {
+        if let Some(result) = replay(&record, command, &hash, now)? {
+            log::info!("Replayed {} for idempotency key {}", command, key);
+            return Ok(result);
+        }
+    }
+
+    let result = f()?;
+    let result_json = serde_json::to_string(&result).map_err(|e| e.to_string())?;
+    let record = IdempotencyRecord {
+        key: key.to_string(),
+        command: command.to_string(),
+        request_hash: hash,
+        result_hash: crate::crypto::hash_sha256(result_json.as_bytes()),
+        result_json,
+        created_at: now,
+        expires_at: now + IDEMPOTENCY_KEY_TTL_HOURS * 3_600_000,
+    };
+    // The mutation already happened; failing to remember it only costs
+    // protection against a later retry, so don't fail the call
+    if let Err(e) = vault.store_idempotency_record(&record) {
+        log::error!("Failed to store idempotency key for {}: {}", command, e);
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(args: serde_json::Value, result: &str) -> IdempotencyRecord {
+        IdempotencyRecord {
+            key: "k-1".to_string(),
+            command: "create_client".to_string(),
+            request_hash: request_hash("create_client", &args),
+            result_json: result.to_string(),
+            result_hash: crate::crypto::hash_sha256(result.as_bytes()),
+            created_at: 0,
+            expires_at: 1_000,
+        }
+    }
+
+    #[test]
+    fn test_replay_returns_stored_result_only_for_same_request() {
+        let args = serde_json::json!({ "display_name": "J.D.", "status": "active" });
+        let stored = record(args.clone(), r#"{"id":"client-1"}"#);
+        let reordered = serde_json::json!({ "status": "active", "display_name": "J.D." });
+
+        let replayed: Option<serde_json::Value> =
+            replay(&stored, "create_client", &request_hash("create_client", &reordered), 500).unwrap();
+        assert_eq!(replayed.unwrap()["id"], "client-1");
+
+        let other = request_hash("create_client", &serde_json::json!({ "display_name": "K.L." }));
+        let err = replay::<serde_json::Value>(&stored, "create_client", &other, 500).unwrap_err();
+        assert!(err.starts_with(IDEMPOTENCY_KEY_REUSED));
+
+        // Expired keys run again
+        assert!(replay::<serde_json::Value>(&stored, "create_client", &stored.request_hash, 1_000)
+            .unwrap()
+            .is_none());
+
+        let mut tampered = stored.clone();
+        tampered.result_json = r#"{"id":"client-2"}"#.to_string();
+        assert!(replay::<serde_json::Value>(&tampered, "create_client", &stored.request_hash, 500).is_err());
+    }
+}
@@ -0,0 +1,188 @@
+// Row Identifiers
+//
+// Primary keys for new vault rows come from here. Random (v4) UUIDs land
+// all over the B-tree indexes, so inserts touch cold pages and scans in
+// creation order jump around the file. UUIDv7 keys begin with a
+// millisecond timestamp and sort in creation order. Which one new rows
+// get is a per-vault setting (settings key 'id_scheme'), applied on
+// unlock; v4 stays the default.
+//
+// Clients and notes always get v4 whatever the setting. Their IDs leave
+// the vault in exports, file names and audit packs, and a v7 ID there
+// would tell anyone holding it when the chart was opened or the session
+// note written.
+//
+// Existing v4 rows are never rewritten, so both kinds live side by side:
+// nothing may read a time or an order out of an ID without checking its
+// version (`timestamp_ms`), and a v7 ID's leading characters are the
+// clock, not a unique prefix (`short_id`).
+
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::Mutex;
+use uuid::Uuid;
+
+/// Settings key holding the scheme
+pub const ID_SCHEME_SETTING: &str = "id_scheme";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IdScheme {
+    /// Random UUIDs, as every release before this setting
+    #[default]
+    V4,
+    /// Time-ordered UUIDs (RFC 9562)
+    V7,
+}
+
+impl IdScheme {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IdScheme::V4 => "v4",
+            IdScheme::V7 => "v7",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "v4" => Some(IdScheme::V4),
+            "v7" => Some(IdScheme::V7),
+            _ => None,
+        }
+    }
+}
+
+static SCHEME: AtomicU8 = AtomicU8::new(0);
+
+/// Last (milliseconds, counter) handed out, so v7 IDs from this process
+/// strictly increase even within one millisecond
+static LAST_V7: Mutex<(u64, u16)> = Mutex::new((0, 0));
+
+const V7_COUNTER_MAX: u16 = 0x0FFF;
+/// Counters start at a random value below this, leaving room to count up
+const V7_COUNTER_SEED_MASK: u16 = 0x03FF;
+
+pub fn set_scheme(scheme: IdScheme) {
+    SCHEME.store(scheme as u8, Ordering::Relaxed);
+}
+
+pub fn scheme() -> IdScheme {
+    match SCHEME.load(Ordering::Relaxed) {
+        1 => IdScheme::V7,
+        _ => IdScheme::V4,
+    }
+}
+
+/// ID for a new row under the current scheme
+pub fn new_id() -> String {
+    match scheme() {
+        IdScheme::V4 => Uuid::new_v4().to_string(),
+        IdScheme::V7 => new_v7().to_string(),
+    }
+}
+
+pub fn new_v7() -> Uuid {
+    let now = chrono::Utc::now().timestamp_millis().max(0) as u64;
+    let (ms, counter) = {
+        let mut last = LAST_V7.lock().unwrap_or_else(|e| e.into_inner());
+        next_v7_parts(&mut last, now, rand::random())
+    };
+    v7_from_parts(ms, counter, rand::random())
+}
+
+/// Advance (milliseconds, counter). A new millisecond reseeds the counter;
+/// an exhausted counter, or a clock that went backwards, borrows from the
+/// timestamp instead.
+fn next_v7_parts(last: &mut (u64, u16), now: u64, seed: u16) -> (u64, u16) {
+    if now > last.0 {
+        *last = (now, seed & V7_COUNTER_SEED_MASK);
+    } else if last.1 < V7_COUNTER_MAX {
+        last.1 += 1;
+    } else {
+        *last = (last.0 + 1, seed & V7_COUNTER_SEED_MASK);
+    }
+    *last
+}
+
+/// 48-bit timestamp, version 7, 12-bit counter, variant, 62 random bits
+fn v7_from_parts(ms: u64, counter: u16, random: [u8; 8]) -> Uuid {
+    let mut bytes = [0u8; 16];
+    bytes[..6].copy_from_slice(&ms.to_be_bytes()[2..]);
+    bytes[6] = 0x70 | ((counter >> 8) as u8 & 0x0F);
+    bytes[7] = counter as u8;
+    bytes[8..].copy_from_slice(&random);
+    bytes[8] = (bytes[8] & 0x3F) | 0x80;
+    Uuid::from_bytes(bytes)
+}
+
+/// Creation time (Unix milliseconds) carried by a v7 ID; None for v4 and
+/// anything else
+pub fn timestamp_ms(id: &str) -> Option<i64> {
+    let uuid = Uuid::parse_str(id).ok()?;
+    if uuid.get_version_num() != 7 {
+        return None;
+    }
+    let mut ms = [0u8; 8];
+    ms[2..].copy_from_slice(&uuid.as_bytes()[..6]);
+    Some(u64::from_be_bytes(ms) as i64)
+}
+
+/// Eight characters to tell rows apart in file names: the start of a v4
+/// ID (as before v7 existed), the random tail of a v7 one
+pub fn short_id(id: &str) -> &str {
+    if timestamp_ms(id).is_some() {
+        &id[id.len() - 8..]
+    } else {
+        &id[..8.min(id.len())]
+    }
+}
+
+// ============================================
+// Tauri Commands
+// ============================================
+
+use tauri::State;
+use crate::commands::AppState;
+
+#[tauri::command]
+pub fn get_id_scheme(state: State<AppState>) -> Result<IdScheme, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.get_id_scheme().map_err(|e| format!("{}", e))
+}
+
+/// Choose the ID scheme for rows created from now on; existing rows keep
+/// their IDs
+#[tauri::command]
+pub fn set_id_scheme(state: State<AppState>, scheme: IdScheme) -> Result<IdScheme, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.set_id_scheme(scheme).map_err(|e| format!("{}", e))?;
+    Ok(scheme)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_v7_ids() {
+        let now = 1_717_171_717_171;
+        let mut last = (0, 0);
+        let first = next_v7_parts(&mut last, now, 0xFFFF);
+        assert_eq!(first, (now, V7_COUNTER_SEED_MASK));
+        assert_eq!(next_v7_parts(&mut last, now, 0), (now, V7_COUNTER_SEED_MASK + 1));
+        last.1 = V7_COUNTER_MAX;
+        assert_eq!(next_v7_parts(&mut last, now - 5, 7), (now + 1, 7));
+
+        let a = v7_from_parts(now, 0x0ABC, [0xFF; 8]).to_string();
+        let b = v7_from_parts(now, 0x0ABD, [0x00; 8]).to_string();
+        assert!(a < b);
+        assert_eq!(&a[14..15], "7");
+        assert_eq!(timestamp_ms(&a), Some(now as i64));
+        assert_ne!(short_id(&a), &a[..8]);
+
+        let v4 = Uuid::new_v4().to_string();
+        assert_eq!(timestamp_ms(&v4), None);
+        assert_eq!(short_id(&v4), &v4[..8]);
+        assert_eq!(IdScheme::from_str(IdScheme::V7.as_str()), Some(IdScheme::V7));
+    }
+}
@@ -0,0 +1,169 @@
+// JSON Schema Validation
+//
+// Checks documents against the schemas we publish under
+// verification/schemas before they are written. Supports the subset of
+// draft 2020-12 those schemas use: type (single or list), const, enum,
+// required, properties, additionalProperties (boolean), items, minLength,
+// minimum, pattern and local "#/$defs/..." references. Other keywords are
+// ignored, so a schema that starts using one needs it added here first.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SchemaViolation {
+    /// JSON Pointer of the offending value ("" for the document root)
+    pub path: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for SchemaViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let at = if self.path.is_empty() { "/" } else { self.path.as_str() };
+        write!(f, "{}: {}", at, self.message)
+    }
+}
+
+/// Every place `instance` breaks `schema`; empty when it is valid
+pub fn validate(schema: &Value, instance: &Value) -> Vec<SchemaViolation> {
+    let mut out = Vec::new();
+    check(schema, schema, instance, &mut String::new(), &mut out);
+    out
+}
+
+fn type_matches(name: &str, value: &Value) -> bool {
+    match name {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64(),
+        _ => false,
+    }
+}
+
+fn check(root: &Value, schema: &Value, value: &Value, path: &mut String, out: &mut Vec<SchemaViolation>) {
+    let mut fail = |path: &str, message: String| out.push(SchemaViolation { path: path.to_string(), message });
+
+    if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+        match reference.strip_prefix("#/$defs/").and_then(|name| root.get("$defs")?.get(name)) {
+            Some(target) => check(root, target, value, path, out),
+            None => fail(path, format!("unresolvable $ref {}", reference)),
+        }
+        return;
+    }
+
+    let types: Vec<&str> = match schema.get("type") {
+        Some(Value::String(t)) => vec![t.as_str()],
+        Some(Value::Array(ts)) => ts.iter().filter_map(Value::as_str).collect(),
+        _ => Vec::new(),
+    };
+    if !types.is_empty() && !types.iter().any(|t| type_matches(t, value)) {
+        fail(path, format!("expected {}", types.join(" or ")));
+        return;
+    }
+    if let Some(expected) = schema.get("const") {
+        if value != expected {
+            fail(path, format!("must be {}", expected));
+        }
+    }
+    if let Some(allowed) = schema.get("enum").and_then(Value::as_array) {
+        if !allowed.contains(value) {
+            fail(path, format!("{} is not one of the allowed values", value));
+        }
+    }
+
+    match value {
+        Value::String(s) => {
+            if let Some(min) = schema.get("minLength").and_then(Value::as_u64) {
+                if (s.chars().count() as u64) < min {
+                    fail(path, format!("shorter than {} characters", min));
+                }
+            }
+            if let Some(pattern) = schema.get("pattern").and_then(Value::as_str) {
+                match Regex::new(pattern) {
+                    Ok(re) if !re.is_match(s) => fail(path, format!("does not match {}", pattern)),
+                    Ok(_) => {}
+                    Err(e) => fail(path, format!("invalid pattern in schema: {}", e)),
+                }
+            }
+        }
+        Value::Number(n) => {
+            if let (Some(min), Some(v)) = (schema.get("minimum").and_then(Value::as_f64), n.as_f64()) {
+                if v < min {
+                    fail(path, format!("less than minimum {}", min));
+                }
+            }
+        }
+        Value::Array(items) => {
+            if let Some(item_schema) = schema.get("items") {
+                for (i, item) in items.iter().enumerate() {
+                    let len = path.len();
+                    path.push_str(&format!("/{}", i));
+                    check(root, item_schema, item, path, out);
+                    path.truncate(len);
+                }
+            }
+        }
+        Value::Object(map) => {
+            for key in schema.get("required").and_then(Value::as_array).into_iter().flatten().filter_map(Value::as_str) {
+                if !map.contains_key(key) {
+                    fail(path, format!("missing required property {:?}", key));
+                }
+            }
+            let properties = schema.get("properties").and_then(Value::as_object);
+            let closed = schema.get("additionalProperties") == Some(&Value::Bool(false));
+            for (key, child) in map {
+                let len = path.len();
+                path.push('/');
+                path.push_str(&key.replace('~', "~0").replace('/', "~1"));
+                match properties.and_then(|p| p.get(key)) {
+                    Some(child_schema) => check(root, child_schema, child, path, out),
+                    None if closed => out.push(SchemaViolation {
+                        path: path.clone(),
+                        message: "property not allowed by schema".to_string(),
+                    }),
+                    None => {}
+                }
+                path.truncate(len);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_validate_reports_paths() {
+        let schema = json!({
+            "type": "object",
+            "additionalProperties": false,
+            "required": ["id", "items"],
+            "properties": {
+                "id": {"type": "string", "pattern": "^[a-f0-9]{4}$"},
+                "kind": {"type": "string", "enum": ["a", "b"]},
+                "items": {"type": "array", "items": {"$ref": "#/$defs/item"}}
+            },
+            "$defs": {
+                "item": {"type": ["integer", "null"], "minimum": 0}
+            }
+        });
+
+        assert!(validate(&schema, &json!({"id": "beef", "items": [1, null]})).is_empty());
+
+        let violations = validate(&schema, &json!({"id": "BEEF", "kind": "c", "items": [1, -2, "x"], "extra": 1}));
+        let mut paths: Vec<&str> = violations.iter().map(|v| v.path.as_str()).collect();
+        paths.sort_unstable();
+        assert_eq!(paths, vec!["/extra", "/id", "/items/1", "/items/2", "/kind"]);
+
+        let missing = validate(&schema, &json!({"id": "beef"}));
+        assert_eq!(missing[0].to_string(), "/: missing required property \"items\"");
+    }
+}
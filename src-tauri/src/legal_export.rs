@@ -8,9 +8,14 @@
 // - PDF report with chain verification
 // - JSON for technical analysis
 // - CSV timeline for legal review
+//
+// Reports are deterministic: the same request, entries and as-of time
+// always produce byte-identical output, so a regenerated report can be
+// compared to the original by hash.
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::path::Path;
 
 // ============================================
@@ -53,6 +58,9 @@ pub struct LegalReportRequest {
     pub requested_by: String,
     /// Case reference number
     pub case_reference: Option<String>,
+    /// Point in time the report speaks as of; used for every timestamp the
+    /// report itself carries instead of the wall clock
+    pub as_of: DateTime<Utc>,
 }
 
 /// Legal report output
@@ -192,13 +200,25 @@ pub struct CertificationStatement {
 pub struct LegalReportGenerator;
 
 impl LegalReportGenerator {
-    /// Generate a legal report
+    /// Generate a legal report. Output depends only on the inputs, so
+    /// regenerating over identical data yields an identical report.
     pub fn generate(
         request: &LegalReportRequest,
-        entries: Vec<AuditEntry>,
-        verification: ChainVerificationResult,
-    ) -> LegalReport {
-        let now = Utc::now();
+        mut entries: Vec<AuditEntry>,
+        mut verification: ChainVerificationResult,
+    ) -> Result<LegalReport, String> {
+        let as_of = request.as_of;
+        
+        // Stable ordering regardless of how the entries were fetched
+        entries.sort_by(|a, b| {
+            a.timestamp.cmp(&b.timestamp).then_with(|| a.id.cmp(&b.id))
+        });
+        verification.gaps.sort_by(|a, b| {
+            a.before_timestamp.cmp(&b.before_timestamp)
+                .then_with(|| a.before_hash.cmp(&b.before_hash))
+        });
+        
+        let id = Self::report_id(request, &entries, &verification)?;
         
         // Calculate summary statistics
         let summary = Self::calculate_summary(&entries);
@@ -215,7 +235,7 @@ impl LegalReportGenerator {
                 request.end_date.format("%Y-%m-%d")
             ),
             certified_by: request.requested_by.clone(),
-            certified_at: now,
+            certified_at: as_of,
             signature: String::new(), // Would be actual signature
         };
         
@@ -234,10 +254,10 @@ impl LegalReportGenerator {
             ),
         };
         
-        LegalReport {
-            id: uuid::Uuid::new_v4().to_string(),
+        Ok(LegalReport {
+            id,
             report_type: request.report_type,
-            generated_at: now,
+            generated_at: as_of,
             title,
             case_reference: request.case_reference.clone(),
             date_range: DateRange {
@@ -248,7 +268,23 @@ impl LegalReportGenerator {
             entries,
             chain_verification: verification,
             certification,
-        }
+        })
+    }
+    
+    /// Content-derived report ID: hash of the canonical request, entries and
+    /// verification, so the same inputs always get the same ID
+    fn report_id(
+        request: &LegalReportRequest,
+        entries: &[AuditEntry],
+        verification: &ChainVerificationResult,
+    ) -> Result<String, String> {
+        let inputs = serde_json::json!({
+            "request": request,
+            "entries": entries,
+            "verification": verification,
+        });
+        let bytes = canonical_json_bytes(&inputs).map_err(|e| format!("Cannot derive report ID: {e}"))?;
+        Ok(format!("LR-{}", &sha256_hex(&bytes)[..32]))
     }
     
     /// Calculate summary statistics
@@ -297,13 +333,18 @@ impl LegalReportGenerator {
         }
     }
     
-    /// Format report as PDF-ready HTML
-    pub fn format_html(report: &LegalReport) -> String {
+    /// Format report as PDF-ready HTML. With a practice profile the report
+    /// opens with a letterhead cover page.
+    pub fn format_html(
+        report: &LegalReport,
+        practice: Option<&crate::branding::PracticeProfile>,
+        logo: Option<&crate::branding::PracticeLogo>,
+    ) -> String {
         let mut html = String::new();
         
         html.push_str("<!DOCTYPE html>\n<html>\n<head>\n");
         html.push_str("<meta charset=\"UTF-8\">\n");
-        html.push_str(&format!("<title>{}</title>\n", report.title));
+        html.push_str(&format!("<title>{}</title>\n", escape_html(&report.title)));
         html.push_str("<style>\n");
         html.push_str("body { font-family: 'Times New Roman', serif; max-width: 8.5in; margin: 0.75in auto; font-size: 11pt; line-height: 1.4; }\n");
         html.push_str("h1 { font-size: 16pt; text-align: center; border-bottom: 2px solid black; padding-bottom: 10px; }\n");
@@ -317,16 +358,32 @@ impl LegalReportGenerator {
         html.push_str(".verification.invalid { background: #ffebee; border-color: #f44336; }\n");
         html.push_str(".certification { margin-top: 40px; padding-top: 20px; border-top: 2px solid black; }\n");
         html.push_str(".page-break { page-break-before: always; }\n");
+        html.push_str(".cover { text-align: center; padding-top: 1.5in; }\n");
+        html.push_str(".cover .letterhead { margin-bottom: 1.5in; }\n");
+        html.push_str(".cover .letterhead img { max-height: 60px; }\n");
         html.push_str("@media print { body { margin: 0; } }\n");
         html.push_str("</style>\n</head>\n<body>\n");
         
+        // Cover page
+        if let Some(practice) = practice {
+            html.push_str("<div class=\"cover\">\n");
+            html.push_str(&letterhead_html(practice, logo));
+            html.push_str(&format!("<h1>{}</h1>\n", escape_html(&report.title)));
+            if let Some(ref case_ref) = report.case_reference {
+                html.push_str(&format!("<p><strong>Case Reference:</strong> {}</p>\n", escape_html(case_ref)));
+            }
+            html.push_str(&format!("<p><strong>Prepared by:</strong> {}</p>\n", escape_html(&report.certification.certified_by)));
+            html.push_str(&format!("<p><strong>Report ID:</strong> {}</p>\n", escape_html(&report.id)));
+            html.push_str("</div>\n<div class=\"page-break\"></div>\n");
+        }
+        
         // Header
-        html.push_str(&format!("<h1>{}</h1>\n", report.title));
+        html.push_str(&format!("<h1>{}</h1>\n", escape_html(&report.title)));
         html.push_str("<div class=\"header-info\">\n");
-        html.push_str(&format!("<p><strong>Report ID:</strong> {}</p>\n", report.id));
+        html.push_str(&format!("<p><strong>Report ID:</strong> {}</p>\n", escape_html(&report.id)));
         html.push_str(&format!("<p><strong>Generated:</strong> {}</p>\n", report.generated_at.format("%Y-%m-%d %H:%M:%S UTC")));
         if let Some(ref case_ref) = report.case_reference {
-            html.push_str(&format!("<p><strong>Case Reference:</strong> {}</p>\n", case_ref));
+            html.push_str(&format!("<p><strong>Case Reference:</strong> {}</p>\n", escape_html(case_ref)));
         }
         html.push_str(&format!("<p><strong>Period:</strong> {} to {}</p>\n",
             report.date_range.start.format("%Y-%m-%d"),
@@ -387,7 +444,7 @@ impl LegalReportGenerator {
         html.push_str("<div class=\"certification\">\n");
         html.push_str("<h2>Certification</h2>\n");
         html.push_str(&format!("<p>{}</p>\n", report.certification.statement));
-        html.push_str(&format!("<p><strong>Certified by:</strong> {}</p>\n", report.certification.certified_by));
+        html.push_str(&format!("<p><strong>Certified by:</strong> {}</p>\n", escape_html(&report.certification.certified_by)));
         html.push_str(&format!("<p><strong>Date:</strong> {}</p>\n", report.certification.certified_at.format("%Y-%m-%d %H:%M:%S UTC")));
         html.push_str("</div>\n");
         
@@ -418,18 +475,403 @@ impl LegalReportGenerator {
         csv
     }
     
-    /// Format report as JSON
+    /// Format report as JSON (keys sorted, so output is canonical)
     pub fn format_json(report: &LegalReport) -> Result<String, serde_json::Error> {
-        serde_json::to_string_pretty(report)
+        serde_json::to_string_pretty(&serde_json::to_value(report)?)
+    }
+    
+    /// SHA-256 of the report's canonical JSON; matches across regenerations
+    pub fn report_sha256(report: &LegalReport) -> Result<String, serde_json::Error> {
+        let value = serde_json::to_value(report)?;
+        let bytes = canonical_json_bytes(&value).map_err(serde::ser::Error::custom)?;
+        Ok(sha256_hex(&bytes))
     }
 }
 
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Practice letterhead block: logo, then name in bold and address lines
+fn letterhead_html(
+    practice: &crate::branding::PracticeProfile,
+    logo: Option<&crate::branding::PracticeLogo>,
+) -> String {
+    let mut html = String::from("<div class=\"letterhead\">\n");
+    if let Some(logo) = logo {
+        let encoded = base64::Engine::encode(&base64::engine::general_purpose::STANDARD, &logo.jpeg);
+        html.push_str(&format!("<img src=\"data:image/jpeg;base64,{}\" alt=\"\">\n", encoded));
+    }
+    for (i, line) in practice.letterhead_lines().iter().enumerate() {
+        if i == 0 {
+            html.push_str(&format!("<p><strong>{}</strong></p>\n", escape_html(line)));
+        } else {
+            html.push_str(&format!("<p>{}</p>\n", escape_html(line)));
+        }
+    }
+    html.push_str("</div>\n");
+    html
+}
+
+/// Canonical JSON bytes (sorted keys, minified) for content-derived IDs.
+/// Errors rather than hashing a lossy encoding, e.g. of a non-finite float.
+fn canonical_json_bytes(value: &serde_json::Value) -> Result<Vec<u8>, evidify_canonicalization::CanonicalizeError> {
+    evidify_canonicalization::try_canonical_bytes(value)
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    hex::encode(Sha256::digest(bytes))
+}
+
+// ============================================
+// Records Request Responses
+// ============================================
+//
+// A records request or set of interrogatories lists numbered items, each
+// asking for categories of records over a period ("all progress notes
+// from 2023-01-01 through 2023-06-30"). The assembler maps each category
+// onto the record types the vault holds, numbers every responsive record
+// once (EV-000001, ...) even when several items call for it, and writes a
+// response per item with an objection placeholder for counsel to complete
+// or strike. Only finalized notes are produced - drafts are not part of
+// the record.
+
+/// Prefix for production numbers
+pub const DOCUMENT_NUMBER_PREFIX: &str = "EV";
+
+/// Text left in every item response until counsel replaces or removes it
+pub const OBJECTION_PLACEHOLDER: &str = "[Objection, if any, to be completed or struck by counsel]";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordCategory {
+    /// Progress, phone and group notes
+    ProgressNotes,
+    /// Intake notes and assessment-type notes or documents
+    Assessments,
+    CrisisRecords,
+    TreatmentPlans,
+    /// Consents, authorizations and releases
+    Consents,
+    DischargeSummaries,
+    /// Uploaded documents not classified above
+    OtherDocuments,
+    /// Chart access, signing and export history
+    AuditTrail,
+}
+
+impl RecordCategory {
+    pub fn label(&self) -> &'static str {
+        match self {
+            RecordCategory::ProgressNotes => "Progress notes",
+            RecordCategory::Assessments => "Assessments",
+            RecordCategory::CrisisRecords => "Crisis records",
+            RecordCategory::TreatmentPlans => "Treatment plans",
+            RecordCategory::Consents => "Consents and authorizations",
+            RecordCategory::DischargeSummaries => "Discharge summaries",
+            RecordCategory::OtherDocuments => "Other documents",
+            RecordCategory::AuditTrail => "Audit trail",
+        }
+    }
+}
+
+/// Category of a note by its agency type name, falling back to the base type
+pub fn category_for_note(note_type: crate::models::NoteType, type_name: Option<&str>) -> RecordCategory {
+    use crate::models::NoteType;
+    
+    let name = type_name.unwrap_or_default().to_lowercase();
+    if name.contains("treatment plan") {
+        return RecordCategory::TreatmentPlans;
+    }
+    if name.contains("assessment") || name.contains("evaluation") {
+        return RecordCategory::Assessments;
+    }
+    if name.contains("consent") {
+        return RecordCategory::Consents;
+    }
+    match note_type {
+        NoteType::Intake => RecordCategory::Assessments,
+        NoteType::Crisis => RecordCategory::CrisisRecords,
+        NoteType::Termination => RecordCategory::DischargeSummaries,
+        NoteType::Progress | NoteType::Phone | NoteType::Group => RecordCategory::ProgressNotes,
+    }
+}
+
+/// Category of an uploaded document from its type, filename and description
+pub fn category_for_document(file_type: &str, filename: &str, description: Option<&str>) -> RecordCategory {
+    let text = format!("{} {} {}", file_type, filename, description.unwrap_or_default())
+        .to_lowercase()
+        .replace(['_', '-'], " ");
+    let has = |terms: &[&str]| terms.iter().any(|t| text.contains(t));
+    
+    if has(&["treatment plan"]) {
+        RecordCategory::TreatmentPlans
+    } else if has(&["consent", "authorization", "release of information"]) {
+        RecordCategory::Consents
+    } else if has(&["assessment", "evaluation", "intake"]) {
+        RecordCategory::Assessments
+    } else if has(&["discharge"]) {
+        RecordCategory::DischargeSummaries
+    } else {
+        RecordCategory::OtherDocuments
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RequestItem {
+    /// As numbered in the request ("3", "Interrogatory 7")
+    pub number: String,
+    pub description: String,
+    pub categories: Vec<RecordCategory>,
+    /// Inclusive; open-ended when None
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordsRequest {
+    pub client_id: String,
+    pub case_reference: Option<String>,
+    pub requesting_party: String,
+    pub received_on: NaiveDate,
+    pub items: Vec<RequestItem>,
+}
+
+impl RecordsRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.items.is_empty() {
+            return Err("Records request has no items".to_string());
+        }
+        for (i, item) in self.items.iter().enumerate() {
+            if item.number.trim().is_empty() {
+                return Err(format!("Item {} has no number", i + 1));
+            }
+            if self.items[..i].iter().any(|other| other.number == item.number) {
+                return Err(format!("Item number '{}' appears more than once", item.number));
+            }
+            if item.categories.is_empty() {
+                return Err(format!("Item '{}' names no record categories", item.number));
+            }
+            if let (Some(start), Some(end)) = (item.start_date, item.end_date) {
+                if start > end {
+                    return Err(format!("Item '{}' ends before it starts", item.number));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A record that may be responsive, gathered from the vault
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CandidateRecord {
+    pub category: RecordCategory,
+    pub record_id: String,
+    pub record_date: NaiveDate,
+    /// Last date covered, for records spanning a period (audit months)
+    pub period_end: Option<NaiveDate>,
+    pub title: String,
+    /// Text produced in the package; None for files produced natively
+    pub content: Option<String>,
+    pub content_sha256: String,
+    /// Original filename of a document produced as a native file
+    pub attachment: Option<String>,
+}
+
+impl CandidateRecord {
+    fn responsive_to(&self, item: &RequestItem) -> bool {
+        let last = self.period_end.unwrap_or(self.record_date);
+        item.categories.contains(&self.category)
+            && item.start_date.map(|start| last >= start).unwrap_or(true)
+            && item.end_date.map(|end| self.record_date <= end).unwrap_or(true)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResponsiveDocument {
+    pub document_number: String,
+    #[serde(flatten)]
+    pub record: CandidateRecord,
+    /// Request item numbers this document answers
+    pub items: Vec<String>,
+    /// Reading level and jargon of the produced text, so a plain-language
+    /// cover letter can go with records released to the client
+    #[serde(default)]
+    pub readability: Option<crate::readability::ReadabilityReport>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ItemResponse {
+    pub number: String,
+    pub description: String,
+    pub document_numbers: Vec<String>,
+    pub objection: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordsResponsePackage {
+    pub id: String,
+    pub generated_at: DateTime<Utc>,
+    pub request: RecordsRequest,
+    pub responses: Vec<ItemResponse>,
+    pub documents: Vec<ResponsiveDocument>,
+}
+
+/// Number the responsive records and answer each item. Deterministic for
+/// the same request, records and as-of time.
+pub fn build_records_response(
+    request: &RecordsRequest,
+    mut candidates: Vec<CandidateRecord>,
+    as_of: DateTime<Utc>,
+) -> Result<RecordsResponsePackage, String> {
+    request.validate()?;
+    candidates.sort_by(|a, b| {
+        a.record_date.cmp(&b.record_date)
+            .then_with(|| a.category.cmp(&b.category))
+            .then_with(|| a.record_id.cmp(&b.record_id))
+    });
+    
+    let documents: Vec<ResponsiveDocument> = candidates.into_iter()
+        .filter_map(|record| {
+            let items: Vec<String> = request.items.iter()
+                .filter(|item| record.responsive_to(item))
+                .map(|item| item.number.clone())
+                .collect();
+            (!items.is_empty()).then_some((record, items))
+        })
+        .enumerate()
+        .map(|(i, (record, items))| ResponsiveDocument {
+            document_number: format!("{}-{:06}", DOCUMENT_NUMBER_PREFIX, i + 1),
+            readability: record.content.as_deref()
+                .map(|text| crate::readability::analyze(text, crate::readability::DEFAULT_TARGET_GRADE)),
+            record,
+            items,
+        })
+        .collect();
+    
+    let responses = request.items.iter()
+        .map(|item| ItemResponse {
+            number: item.number.clone(),
+            description: item.description.clone(),
+            document_numbers: documents.iter()
+                .filter(|d| d.items.contains(&item.number))
+                .map(|d| d.document_number.clone())
+                .collect(),
+            objection: OBJECTION_PLACEHOLDER.to_string(),
+        })
+        .collect();
+    
+    let inputs = serde_json::json!({ "request": request, "documents": documents, "as_of": as_of });
+    let bytes = canonical_json_bytes(&inputs).map_err(|e| format!("Cannot derive package ID: {e}"))?;
+    Ok(RecordsResponsePackage {
+        id: format!("RR-{}", &sha256_hex(&bytes)[..32]),
+        generated_at: as_of,
+        request: request.clone(),
+        responses,
+        documents,
+    })
+}
+
+/// Response, production index and produced records as PDF-ready HTML
+pub fn format_records_response_html(
+    package: &RecordsResponsePackage,
+    practice: Option<&crate::branding::PracticeProfile>,
+    logo: Option<&crate::branding::PracticeLogo>,
+) -> String {
+    let request = &package.request;
+    let mut html = String::new();
+    
+    html.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"UTF-8\">\n");
+    html.push_str("<title>Response to Records Request</title>\n<style>\n");
+    html.push_str("body { font-family: 'Times New Roman', serif; max-width: 8.5in; margin: 0.75in auto; font-size: 11pt; line-height: 1.4; }\n");
+    html.push_str("h1 { font-size: 16pt; text-align: center; border-bottom: 2px solid black; padding-bottom: 10px; }\n");
+    html.push_str("h2 { font-size: 13pt; margin-top: 20px; }\n");
+    html.push_str("table { width: 100%; border-collapse: collapse; font-size: 9pt; }\n");
+    html.push_str("th, td { border: 1px solid #ccc; padding: 4px; text-align: left; vertical-align: top; }\n");
+    html.push_str(".objection { font-style: italic; color: #666; }\n");
+    html.push_str(".doc-header { border-bottom: 1px solid black; margin-bottom: 10px; }\n");
+    html.push_str(".doc-body { white-space: pre-wrap; font-size: 10pt; }\n");
+    html.push_str(".hash { font-family: monospace; font-size: 8pt; color: #999; }\n");
+    html.push_str(".letterhead { text-align: center; margin-bottom: 30px; }\n");
+    html.push_str(".letterhead img { max-height: 60px; }\n");
+    html.push_str(".page-break { page-break-before: always; }\n");
+    html.push_str("@media print { body { margin: 0; } }\n");
+    html.push_str("</style>\n</head>\n<body>\n");
+    
+    if let Some(practice) = practice {
+        html.push_str(&letterhead_html(practice, logo));
+    }
+    html.push_str("<h1>Response to Records Request</h1>\n");
+    if let Some(ref case_ref) = request.case_reference {
+        html.push_str(&format!("<p><strong>Case Reference:</strong> {}</p>\n", escape_html(case_ref)));
+    }
+    html.push_str(&format!("<p><strong>Requesting Party:</strong> {}</p>\n", escape_html(&request.requesting_party)));
+    html.push_str(&format!("<p><strong>Request Received:</strong> {}</p>\n", request.received_on.format("%Y-%m-%d")));
+    html.push_str(&format!("<p><strong>Response ID:</strong> {}</p>\n", package.id));
+    html.push_str(&format!("<p><strong>Prepared:</strong> {}</p>\n", package.generated_at.format("%Y-%m-%d %H:%M:%S UTC")));
+    
+    html.push_str("<h2>Responses</h2>\n");
+    for response in &package.responses {
+        html.push_str(&format!(
+            "<h3>Request No. {}</h3>\n<p>{}</p>\n<p class=\"objection\">{}</p>\n",
+            escape_html(&response.number), escape_html(&response.description), escape_html(&response.objection)
+        ));
+        let answer = match (response.document_numbers.first(), response.document_numbers.last()) {
+            (Some(first), Some(last)) if first == last => format!("Responsive document produced: {}.", first),
+            (Some(_), Some(_)) => format!(
+                "Responsive documents produced: {}.", response.document_numbers.join(", ")
+            ),
+            _ => "No responsive documents were located after a diligent search.".to_string(),
+        };
+        html.push_str(&format!("<p><strong>Response:</strong> {}</p>\n", answer));
+    }
+    
+    html.push_str("<div class=\"page-break\"></div>\n<h2>Production Index</h2>\n");
+    html.push_str("<table>\n<tr><th>Document</th><th>Date</th><th>Type</th><th>Description</th><th>Responsive To</th><th>SHA-256</th></tr>\n");
+    for doc in &package.documents {
+        html.push_str(&format!(
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td class=\"hash\">{}</td></tr>\n",
+            doc.document_number,
+            doc.record.record_date.format("%Y-%m-%d"),
+            doc.record.category.label(),
+            escape_html(&doc.record.title),
+            escape_html(&doc.items.join(", ")),
+            &doc.record.content_sha256[..16.min(doc.record.content_sha256.len())],
+        ));
+    }
+    html.push_str("</table>\n");
+    
+    for doc in &package.documents {
+        html.push_str("<div class=\"page-break\"></div>\n<div class=\"doc-header\">\n");
+        html.push_str(&format!(
+            "<p><strong>{}</strong> &mdash; {} &mdash; {}</p>\n",
+            doc.document_number, escape_html(&doc.record.title), doc.record.record_date.format("%Y-%m-%d")
+        ));
+        html.push_str("</div>\n");
+        if let Some(ref filename) = doc.record.attachment {
+            html.push_str(&format!(
+                "<p>Produced as native file: {}_{}</p>\n", doc.document_number, escape_html(filename)
+            ));
+        }
+        if let Some(ref content) = doc.record.content {
+            html.push_str(&format!("<div class=\"doc-body\">{}</div>\n", escape_html(content)));
+        }
+    }
+    
+    html.push_str("</body>\n</html>");
+    html
+}
+
 // ============================================
 // Tauri Commands
 // ============================================
 
 /// Generate legal audit report
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn generate_legal_report(
     report_type: String,
     client_id: Option<String>,
@@ -438,6 +880,7 @@ pub async fn generate_legal_report(
     case_reference: Option<String>,
     requested_by: String,
     include_technical: bool,
+    as_of: Option<String>,
 ) -> Result<LegalReport, String> {
     let start = DateTime::parse_from_rfc3339(&start_date)
         .map_err(|e| e.to_string())?
@@ -445,6 +888,14 @@ pub async fn generate_legal_report(
     let end = DateTime::parse_from_rfc3339(&end_date)
         .map_err(|e| e.to_string())?
         .with_timezone(&Utc);
+    // Default to the end of the period so re-running the same request
+    // reproduces the same report
+    let as_of = match as_of {
+        Some(ts) => DateTime::parse_from_rfc3339(&ts)
+            .map_err(|e| e.to_string())?
+            .with_timezone(&Utc),
+        None => end,
+    };
     
     let report_type = match report_type.as_str() {
         "full" => LegalReportType::FullAudit,
@@ -465,6 +916,7 @@ pub async fn generate_legal_report(
         include_verification: true,
         requested_by,
         case_reference,
+        as_of,
     };
     
     // Mock data for now - would fetch from audit log
@@ -475,28 +927,537 @@ pub async fn generate_legal_report(
         first_hash: "0".repeat(64),
         last_hash: "0".repeat(64),
         gaps: vec![],
-        verified_at: Utc::now(),
+        verified_at: as_of,
         method: "SHA-256 hash chain".to_string(),
     };
     
-    Ok(LegalReportGenerator::generate(&request, entries, verification))
+    LegalReportGenerator::generate(&request, entries, verification)
 }
 
-/// Export legal report to file
+/// Export legal report to file. With `max_part_mb` set and the report
+/// larger than that, numbered parts and a manifest are written next to
+/// `output_path` and the manifest path is returned.
 #[tauri::command]
 pub async fn export_legal_report(
+    state: tauri::State<'_, crate::commands::AppState>,
     report: LegalReport,
     format: String,
     output_path: String,
+    max_part_mb: Option<u64>,
 ) -> Result<String, String> {
+    let (practice, logo) = {
+        let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+        let practice = vault.get_practice_profile().map_err(|e| e.to_string())?;
+        let logo = match practice {
+            Some(ref p) if p.has_logo => vault.get_practice_logo().map_err(|e| e.to_string())?,
+            _ => None,
+        };
+        (practice, logo)
+    };
+    
     let content = match format.as_str() {
-        "html" | "pdf" => LegalReportGenerator::format_html(&report),
+        "html" | "pdf" => LegalReportGenerator::format_html(&report, practice.as_ref(), logo.as_ref()),
         "csv" => LegalReportGenerator::format_csv(&report),
         "json" => LegalReportGenerator::format_json(&report).map_err(|e| e.to_string())?,
         _ => return Err("Unknown format".to_string()),
     };
     
-    std::fs::write(&output_path, &content).map_err(|e| e.to_string())?;
+    write_export(content.as_bytes(), &output_path, max_part_mb)
+}
+
+/// Finalized notes, documents and per-month audit trail (up to `as_of`)
+/// for one client. Drafts and reviewed-but-unsigned notes are not part of
+/// the record.
+fn gather_records_candidates(
+    vault: &crate::vault::Vault,
+    client_id: &str,
+    as_of: DateTime<Utc>,
+) -> Result<Vec<CandidateRecord>, String> {
+    use std::collections::BTreeMap;
+    
+    let (mut candidates, _) = gather_chart_records(vault, client_id)?;
+    
+    // One audit-trail record per calendar month, so date ranges select
+    // whole months rather than scattering single events through the index
+    let conn = vault.get_connection().map_err(|e| format!("{}", e))?;
+    let entries = crate::audit::get_chart_entries(conn, client_id, 0, as_of.timestamp_millis())
+        .map_err(|e| format!("{}", e))?;
+    let mut months: BTreeMap<NaiveDate, Vec<String>> = BTreeMap::new();
+    for entry in &entries {
+        let Some((at, line)) = audit_line(entry) else { continue };
+        let month = at.date_naive().with_day(1).unwrap_or(at.date_naive());
+        months.entry(month).or_default().push(line);
+    }
+    for (month, lines) in months {
+        let period_end = month.checked_add_months(chrono::Months::new(1))
+            .and_then(|next| next.pred_opt())
+            .unwrap_or(month);
+        let content = lines.join("\n");
+        candidates.push(CandidateRecord {
+            category: RecordCategory::AuditTrail,
+            record_id: format!("audit-{}", month.format("%Y-%m")),
+            record_date: month,
+            period_end: Some(period_end),
+            title: format!("Audit trail, {}", month.format("%B %Y")),
+            content_sha256: sha256_hex(content.as_bytes()),
+            content: Some(content),
+            attachment: None,
+        });
+    }
+    
+    Ok(candidates)
+}
+
+/// One audit entry as a line of an audit-trail record, with its time
+pub(crate) fn audit_line(entry: &crate::models::AuditEntry) -> Option<(DateTime<Utc>, String)> {
+    let at = DateTime::from_timestamp_millis(entry.timestamp)?;
+    Some((at, format!(
+        "{}  #{}  {:?}  {:?} {}  {:?}  {}",
+        at.format("%Y-%m-%d %H:%M:%S UTC"),
+        entry.sequence,
+        entry.event_type,
+        entry.resource_type,
+        entry.resource_id,
+        entry.outcome,
+        entry.entry_hash,
+    )))
+}
+
+/// Finalized notes and uploaded documents of one client, and the IDs of
+/// the client and of every note and document in the chart (drafts
+/// included) for selecting its audit entries
+pub(crate) fn gather_chart_records(
+    vault: &crate::vault::Vault,
+    client_id: &str,
+) -> Result<(Vec<CandidateRecord>, std::collections::HashSet<String>), String> {
+    use crate::models::NoteStatus;
+    use std::collections::HashSet;
+    
+    let mut candidates = Vec::new();
+    let mut resource_ids: HashSet<String> = HashSet::from([client_id.to_string()]);
+    
+    for note in vault.list_notes(Some(client_id)).map_err(|e| format!("{}", e))? {
+        resource_ids.insert(note.id.clone());
+        if !matches!(note.status, NoteStatus::Signed | NoteStatus::Amended | NoteStatus::Exported) {
+            continue;
+        }
+        let Ok(record_date) = NaiveDate::parse_from_str(&note.session_date, "%Y-%m-%d") else {
+            log::warn!("Skipping note {} with unparseable session date", note.id);
+            continue;
+        };
+        let type_name = vault.get_note_type_name(&note.id).map_err(|e| format!("{}", e))?;
+        let content = crate::vault::Vault::analyzed_text(&note).to_string();
+        candidates.push(CandidateRecord {
+            category: category_for_note(note.note_type, type_name.as_deref()),
+            record_id: note.id.clone(),
+            record_date,
+            period_end: None,
+            title: type_name.unwrap_or_else(|| format!("{} note", note.note_type.format_name())),
+            content_sha256: sha256_hex(content.as_bytes()),
+            content: Some(content),
+            attachment: None,
+        });
+    }
+    
+    for doc in vault.list_documents(client_id).map_err(|e| format!("{}", e))? {
+        resource_ids.insert(doc.id.clone());
+        let record_date = doc.document_date.as_deref()
+            .and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+            .or_else(|| DateTime::from_timestamp(doc.created_at, 0).map(|t| t.date_naive()));
+        let Some(record_date) = record_date else { continue };
+        candidates.push(CandidateRecord {
+            category: category_for_document(&doc.file_type, &doc.filename, doc.description.as_deref()),
+            record_id: doc.id.clone(),
+            record_date,
+            period_end: None,
+            title: doc.description.clone().unwrap_or_else(|| doc.filename.clone()),
+            content: doc.ocr_text.clone(),
+            content_sha256: doc.content_hash.clone(),
+            attachment: Some(doc.filename.clone()),
+        });
+    }
+    
+    Ok((candidates, resource_ids))
+}
+
+/// Write `content` to `output_path`, or as numbered parts plus a manifest
+/// when it exceeds `max_part_mb`. Returns the path written.
+fn write_export(content: &[u8], output_path: &str, max_part_mb: Option<u64>) -> Result<String, String> {
+    if let Some(limit) = crate::audit_pack::max_part_bytes(max_part_mb)? {
+        if content.len() as u64 > limit {
+            let path = Path::new(output_path);
+            let file_name = path.file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .ok_or_else(|| "Output path has no file name".to_string())?;
+            let dir = path.parent().unwrap_or_else(|| Path::new("."));
+            crate::audit_pack::write_split_output(content, dir, &file_name, limit)
+                .map_err(|e| e.to_string())?;
+            return Ok(dir.join(format!("{}.manifest.json", file_name)).to_string_lossy().to_string());
+        }
+    }
+    
+    std::fs::write(output_path, content).map_err(|e| e.to_string())?;
+    
+    Ok(output_path.to_string())
+}
+
+/// Assemble the response to a records request for one client's chart
+#[tauri::command]
+pub fn assemble_records_response(
+    state: tauri::State<'_, crate::commands::AppState>,
+    policy_state: tauri::State<'_, crate::policy::PolicyState>,
+    request: RecordsRequest,
+    as_of: Option<String>,
+) -> Result<RecordsResponsePackage, String> {
+    request.validate()?;
+    let as_of = match as_of {
+        Some(ts) => DateTime::parse_from_rfc3339(&ts)
+            .map_err(|e| e.to_string())?
+            .with_timezone(&Utc),
+        None => Utc::now(),
+    };
+    
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    crate::commands::ensure_chart_access(&vault, &policy_state, &request.client_id)?;
+    let candidates = gather_records_candidates(&vault, &request.client_id, as_of)?;
+    
+    build_records_response(&request, candidates, as_of)
+}
+
+/// Every produced record must still be in the chart exactly as it was
+/// assembled; the package comes back from the UI and is not trusted
+fn verify_against_chart(
+    vault: &crate::vault::Vault,
+    package: &RecordsResponsePackage,
+) -> Result<(), String> {
+    let current: std::collections::HashMap<String, CandidateRecord> =
+        gather_records_candidates(vault, &package.request.client_id, package.generated_at)?
+            .into_iter()
+            .map(|c| (c.record_id.clone(), c))
+            .collect();
+    for doc in &package.documents {
+        match current.get(&doc.record.record_id) {
+            Some(record) if *record == doc.record => {}
+            _ => return Err(format!("{} no longer matches the chart; reassemble", doc.document_number)),
+        }
+    }
+    Ok(())
+}
+
+/// Export an assembled records response. Documents produced natively are
+/// copied, prefixed with their production number, into `<stem>_native/`
+/// next to `output_path`.
+#[tauri::command]
+pub async fn export_records_response(
+    state: tauri::State<'_, crate::commands::AppState>,
+    policy_state: tauri::State<'_, crate::policy::PolicyState>,
+    package: RecordsResponsePackage,
+    format: String,
+    output_path: String,
+    max_part_mb: Option<u64>,
+    user_override: Option<bool>,
+) -> Result<String, String> {
+    crate::commands::validate_export_path(output_path.clone(), None, user_override)?;
     
-    Ok(output_path)
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    crate::commands::ensure_chart_access(&vault, &policy_state, &package.request.client_id)?;
+    verify_against_chart(&vault, &package)?;
+    let practice = vault.get_practice_profile().map_err(|e| e.to_string())?;
+    let logo = match practice {
+        Some(ref p) if p.has_logo => vault.get_practice_logo().map_err(|e| e.to_string())?,
+        _ => None,
+    };
+    
+    let content = match format.as_str() {
+        "html" | "pdf" => format_records_response_html(&package, practice.as_ref(), logo.as_ref()),
+        "json" => serde_json::to_string_pretty(&package).map_err(|e| e.to_string())?,
+        _ => return Err("Unknown format".to_string()),
+    };
+    
+    let natives: Vec<&ResponsiveDocument> = package.documents.iter()
+        .filter(|d| d.record.attachment.is_some())
+        .collect();
+    if !natives.is_empty() {
+        let path = Path::new(&output_path);
+        let stem = path.file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .ok_or_else(|| "Output path has no file name".to_string())?;
+        let native_dir = path.parent().unwrap_or_else(|| Path::new(".")).join(format!("{}_native", stem));
+        std::fs::create_dir_all(&native_dir).map_err(|e| e.to_string())?;
+        for doc in natives {
+            let filename = doc.record.attachment.as_deref().unwrap_or_default();
+            let data = vault.get_document_data(&doc.record.record_id).map_err(|e| format!("{}", e))?;
+            if sha256_hex(&data) != doc.record.content_sha256 {
+                return Err(format!("{} no longer matches the assembled response; reassemble", doc.document_number));
+            }
+            let safe_name = Path::new(filename).file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| doc.record.record_id.clone());
+            std::fs::write(native_dir.join(format!("{}_{}", doc.document_number, safe_name)), data)
+                .map_err(|e| e.to_string())?;
+        }
+    }
+    
+    let written = write_export(content.as_bytes(), &output_path, max_part_mb)?;
+    let record_ids: Vec<&str> = package.documents.iter().map(|d| d.record.record_id.as_str()).collect();
+    crate::commands::log_note_retrieval(&vault, "records_response", &record_ids)?;
+    vault.record_export_destination(
+        &package.id,
+        &output_path,
+        crate::commands::path_class_name(Path::new(&output_path)),
+        user_override.unwrap_or(false),
+        None,
+    ).map_err(|e| format!("{}", e))?;
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ts(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    fn entry(id: &str, at: &str) -> AuditEntry {
+        AuditEntry {
+            id: id.to_string(),
+            timestamp: ts(at),
+            event_type: "note.signed".to_string(),
+            category: "note".to_string(),
+            description: "Note signed".to_string(),
+            resource_type: "note".to_string(),
+            resource_id: "note-1".to_string(),
+            user_id: "user".to_string(),
+            outcome: "success".to_string(),
+            entry_hash: "a".repeat(64),
+            previous_hash: "0".repeat(64),
+            technical: None,
+        }
+    }
+
+    #[test]
+    fn test_report_is_reproducible() {
+        let request = LegalReportRequest {
+            report_type: LegalReportType::FullAudit,
+            client_id: None,
+            start_date: ts("2024-01-01T00:00:00Z"),
+            end_date: ts("2024-02-01T00:00:00Z"),
+            note_ids: None,
+            include_technical: false,
+            include_verification: true,
+            requested_by: "Dr. Test".to_string(),
+            case_reference: Some("CASE-1".to_string()),
+            as_of: ts("2024-02-01T00:00:00Z"),
+        };
+        let verification = ChainVerificationResult {
+            valid: true,
+            entries_verified: 2,
+            first_hash: "0".repeat(64),
+            last_hash: "a".repeat(64),
+            gaps: vec![],
+            verified_at: request.as_of,
+            method: "SHA-256 hash chain".to_string(),
+        };
+
+        let a = entry("e1", "2024-01-05T10:00:00Z");
+        let b = entry("e2", "2024-01-06T10:00:00Z");
+        let first = LegalReportGenerator::generate(&request, vec![a.clone(), b.clone()], verification.clone()).unwrap();
+        let second = LegalReportGenerator::generate(&request, vec![b, a], verification).unwrap();
+
+        assert_eq!(first.entries[0].id, "e1");
+        assert_eq!(first.id, second.id);
+        assert_eq!(
+            LegalReportGenerator::format_json(&first).unwrap(),
+            LegalReportGenerator::format_json(&second).unwrap()
+        );
+        assert_eq!(
+            LegalReportGenerator::report_sha256(&first).unwrap(),
+            LegalReportGenerator::report_sha256(&second).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_cover_page_fields_are_escaped() {
+        let request = LegalReportRequest {
+            report_type: LegalReportType::FullAudit,
+            client_id: None,
+            start_date: ts("2024-01-01T00:00:00Z"),
+            end_date: ts("2024-02-01T00:00:00Z"),
+            note_ids: None,
+            include_technical: false,
+            include_verification: true,
+            requested_by: "Dr. A & <b>B</b>".to_string(),
+            case_reference: Some("<script>alert(1)</script>".to_string()),
+            as_of: ts("2024-02-01T00:00:00Z"),
+        };
+        let verification = ChainVerificationResult {
+            valid: true,
+            entries_verified: 1,
+            first_hash: "0".repeat(64),
+            last_hash: "a".repeat(64),
+            gaps: vec![],
+            verified_at: request.as_of,
+            method: "SHA-256 hash chain".to_string(),
+        };
+        let report = LegalReportGenerator::generate(&request, vec![entry("e1", "2024-01-05T10:00:00Z")], verification).unwrap();
+        let practice = crate::branding::PracticeProfile {
+            name: "Riverside Psychology".to_string(),
+            address_lines: vec![],
+            npi: None,
+            phone: None,
+            has_logo: false,
+        };
+
+        let html = LegalReportGenerator::format_html(&report, Some(&practice), None);
+        assert!(!html.contains("<script>"));
+        assert!(html.contains("<p><strong>Case Reference:</strong> &lt;script&gt;alert(1)&lt;/script&gt;</p>"));
+        assert!(html.contains("<p><strong>Prepared by:</strong> Dr. A &amp; &lt;b&gt;B&lt;/b&gt;</p>"));
+    }
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    fn record(category: RecordCategory, id: &str, on: &str) -> CandidateRecord {
+        CandidateRecord {
+            category,
+            record_id: id.to_string(),
+            record_date: date(on),
+            period_end: None,
+            title: id.to_string(),
+            content: Some(format!("content of {}", id)),
+            content_sha256: sha256_hex(id.as_bytes()),
+            attachment: None,
+        }
+    }
+
+    #[test]
+    fn test_records_response_numbers_each_document_once() {
+        let request = RecordsRequest {
+            client_id: "client-1".to_string(),
+            case_reference: Some("CASE-1".to_string()),
+            requesting_party: "Counsel".to_string(),
+            received_on: date("2024-07-01"),
+            items: vec![
+                RequestItem {
+                    number: "1".to_string(),
+                    description: "All progress notes, first half of 2024".to_string(),
+                    categories: vec![RecordCategory::ProgressNotes],
+                    start_date: Some(date("2024-01-01")),
+                    end_date: Some(date("2024-06-30")),
+                },
+                RequestItem {
+                    number: "2".to_string(),
+                    description: "All clinical records from March 2024".to_string(),
+                    categories: vec![RecordCategory::ProgressNotes, RecordCategory::AuditTrail],
+                    start_date: Some(date("2024-03-15")),
+                    end_date: Some(date("2024-03-31")),
+                },
+                RequestItem {
+                    number: "3".to_string(),
+                    description: "All treatment plans".to_string(),
+                    categories: vec![RecordCategory::TreatmentPlans],
+                    start_date: None,
+                    end_date: None,
+                },
+            ],
+        };
+        let mut audit = record(RecordCategory::AuditTrail, "audit-2024-03", "2024-03-01");
+        audit.period_end = Some(date("2024-03-31"));
+        let mut note_a = record(RecordCategory::ProgressNotes, "note-a", "2024-02-01");
+        note_a.content = Some("Pt presents with anhedonia; f/u in two weeks.".to_string());
+        let candidates = vec![
+            record(RecordCategory::ProgressNotes, "note-b", "2024-03-20"),
+            note_a,
+            record(RecordCategory::ProgressNotes, "note-late", "2024-08-01"),
+            audit,
+        ];
+        let as_of = ts("2024-07-02T00:00:00Z");
+
+        let package = build_records_response(&request, candidates.clone(), as_of).unwrap();
+        let numbered: Vec<(&str, &str)> = package.documents.iter()
+            .map(|d| (d.document_number.as_str(), d.record.record_id.as_str()))
+            .collect();
+        assert_eq!(numbered, vec![
+            ("EV-000001", "note-a"),
+            ("EV-000002", "audit-2024-03"),
+            ("EV-000003", "note-b"),
+        ]);
+        assert_eq!(package.responses[0].document_numbers, vec!["EV-000001", "EV-000003"]);
+        // The March audit month overlaps the item's range even though it starts before it
+        assert_eq!(package.responses[1].document_numbers, vec!["EV-000002", "EV-000003"]);
+        assert!(package.responses[2].document_numbers.is_empty());
+        assert_eq!(package.responses[2].objection, OBJECTION_PLACEHOLDER);
+        let jargon: Vec<&str> = package.documents[0].readability.as_ref().unwrap().jargon.iter()
+            .map(|j| j.term.as_str())
+            .collect();
+        assert_eq!(jargon, ["Pt", "presents with", "anhedonia", "f/u"]);
+
+        let mut reversed = candidates;
+        reversed.reverse();
+        assert_eq!(build_records_response(&request, reversed, as_of).unwrap().id, package.id);
+
+        let html = format_records_response_html(&package, None, None);
+        assert!(html.contains("No responsive documents were located"));
+    }
+
+    #[test]
+    fn test_records_response_export_rechecks_the_chart() {
+        use crate::models::NoteType;
+        let fixture = crate::vault::testing::FixtureBuilder::new("records-response")
+            .client("Client A")
+            .signed_note("2024-03-01", NoteType::Progress, "Discussed sleep and work stress.")
+            .build()
+            .unwrap();
+        let request = RecordsRequest {
+            client_id: fixture.clients[0].id.clone(),
+            case_reference: None,
+            requesting_party: "Counsel".to_string(),
+            received_on: date("2024-07-01"),
+            items: vec![RequestItem {
+                number: "1".to_string(),
+                description: "All clinical records".to_string(),
+                categories: vec![RecordCategory::ProgressNotes, RecordCategory::AuditTrail],
+                start_date: None,
+                end_date: None,
+            }],
+        };
+        let as_of = Utc::now();
+        let candidates = gather_records_candidates(&fixture.vault, &request.client_id, as_of).unwrap();
+        assert!(candidates.iter().any(|c| c.category == RecordCategory::AuditTrail));
+        let mut package = build_records_response(&request, candidates, as_of).unwrap();
+        verify_against_chart(&fixture.vault, &package).unwrap();
+
+        let note = package.documents.iter_mut()
+            .find(|d| d.record.category == RecordCategory::ProgressNotes)
+            .unwrap();
+        note.record.content = Some("Altered text.".to_string());
+        note.record.content_sha256 = sha256_hex(b"Altered text.");
+        assert!(verify_against_chart(&fixture.vault, &package).unwrap_err().contains("reassemble"));
+    }
+
+    #[test]
+    fn test_records_request_validation() {
+        let item = RequestItem {
+            number: "1".to_string(),
+            description: "Notes".to_string(),
+            categories: vec![RecordCategory::ProgressNotes],
+            start_date: Some(date("2024-06-01")),
+            end_date: Some(date("2024-01-01")),
+        };
+        let mut request = RecordsRequest {
+            client_id: "client-1".to_string(),
+            case_reference: None,
+            requesting_party: "Counsel".to_string(),
+            received_on: date("2024-07-01"),
+            items: vec![item.clone()],
+        };
+        assert!(request.validate().is_err());
+
+        request.items[0].start_date = None;
+        request.items.push(item);
+        request.items[1].end_date = None;
+        assert!(request.validate().unwrap_err().contains("more than once"));
+    }
 }
@@ -0,0 +1,239 @@
+// Licensure Registry
+//
+// Telehealth is governed by where the patient is sitting, not where the
+// clinician is. The clinician records their state licenses and PSYPACT
+// authority (APIT) here; when a telehealth note records the patient's
+// location, the location is checked against that registry and the result
+// is stored with the note. What happens to an uncovered session (block
+// signing or require an attestation) is set by the organization policy.
+
+use serde::{Deserialize, Serialize};
+
+use crate::policy::{LicensureAction, LicensurePolicy};
+
+// ============================================
+// Registry
+// ============================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LicenseKind {
+    /// Full license issued by one jurisdiction
+    StateLicense,
+    /// PSYPACT Authority to Practice Interjurisdictional Telepsychology;
+    /// covers every compact state listed in the licensure policy
+    PsypactApit,
+}
+
+impl LicenseKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LicenseKind::StateLicense => "state_license",
+            LicenseKind::PsypactApit => "psypact_apit",
+        }
+    }
+    
+    pub fn from_name(s: &str) -> Self {
+        match s {
+            "psypact_apit" => LicenseKind::PsypactApit,
+            _ => LicenseKind::StateLicense,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClinicianLicense {
+    pub id: String,
+    pub kind: LicenseKind,
+    /// Two-letter code for state licenses; ignored for APIT
+    pub jurisdiction: Option<String>,
+    pub license_number: String,
+    /// YYYY-MM-DD; the license covers sessions on or before this date
+    pub expires_on: Option<String>,
+    pub created_at: i64,
+}
+
+impl ClinicianLicense {
+    fn valid_on(&self, session_date: &str) -> bool {
+        // ISO dates compare correctly as strings
+        self.expires_on.as_deref().is_none_or(|exp| session_date <= exp)
+    }
+}
+
+const US_JURISDICTIONS: &[(&str, &str)] = &[
+    ("AL", "ALABAMA"), ("AK", "ALASKA"), ("AZ", "ARIZONA"), ("AR", "ARKANSAS"),
+    ("CA", "CALIFORNIA"), ("CO", "COLORADO"), ("CT", "CONNECTICUT"), ("DE", "DELAWARE"),
+    ("DC", "DISTRICT OF COLUMBIA"), ("FL", "FLORIDA"), ("GA", "GEORGIA"), ("HI", "HAWAII"),
+    ("ID", "IDAHO"), ("IL", "ILLINOIS"), ("IN", "INDIANA"), ("IA", "IOWA"),
+    ("KS", "KANSAS"), ("KY", "KENTUCKY"), ("LA", "LOUISIANA"), ("ME", "MAINE"),
+    ("MD", "MARYLAND"), ("MA", "MASSACHUSETTS"), ("MI", "MICHIGAN"), ("MN", "MINNESOTA"),
+    ("MS", "MISSISSIPPI"), ("MO", "MISSOURI"), ("MT", "MONTANA"), ("NE", "NEBRASKA"),
+    ("NV", "NEVADA"), ("NH", "NEW HAMPSHIRE"), ("NJ", "NEW JERSEY"), ("NM", "NEW MEXICO"),
+    ("NY", "NEW YORK"), ("NC", "NORTH CAROLINA"), ("ND", "NORTH DAKOTA"), ("OH", "OHIO"),
+    ("OK", "OKLAHOMA"), ("OR", "OREGON"), ("PA", "PENNSYLVANIA"), ("RI", "RHODE ISLAND"),
+    ("SC", "SOUTH CAROLINA"), ("SD", "SOUTH DAKOTA"), ("TN", "TENNESSEE"), ("TX", "TEXAS"),
+    ("UT", "UTAH"), ("VT", "VERMONT"), ("VA", "VIRGINIA"), ("WA", "WASHINGTON"),
+    ("WV", "WEST VIRGINIA"), ("WI", "WISCONSIN"), ("WY", "WYOMING"), ("PR", "PUERTO RICO"),
+];
+
+/// Normalize "tx", "Texas" or " TX " to "TX". Unknown values are returned
+/// upper-cased so non-US locations still compare consistently.
+pub fn normalize_jurisdiction(location: &str) -> String {
+    let upper = location.trim().to_uppercase();
+    US_JURISDICTIONS.iter()
+        .find(|(code, name)| *code == upper || *name == upper)
+        .map(|(code, _)| code.to_string())
+        .unwrap_or(upper)
+}
+
+// ============================================
+// Telehealth Check
+// ============================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LicensureStatus {
+    /// A state license for the patient's location
+    Licensed,
+    /// Covered by APIT in a PSYPACT state
+    Psypact,
+    /// No current license or compact authority covers the location
+    OutOfJurisdiction,
+}
+
+/// Result of checking one telehealth note, stored with the note
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LicensureCheck {
+    pub note_id: String,
+    pub patient_location: String,
+    pub session_date: String,
+    pub status: LicensureStatus,
+    /// License that covers the session, if any
+    pub covered_by: Option<String>,
+    /// Policy action applied when not covered
+    pub action: LicensureAction,
+    pub message: String,
+    /// Clinician's statement when the policy allows signing with one
+    pub attestation: Option<String>,
+    pub attested_at: Option<i64>,
+    pub checked_at: i64,
+}
+
+impl LicensureCheck {
+    /// Reason the note cannot be signed yet, if any
+    pub fn sign_blocker(&self) -> Option<String> {
+        if self.status != LicensureStatus::OutOfJurisdiction {
+            return None;
+        }
+        match self.action {
+            LicensureAction::Allow => None,
+            LicensureAction::Block => Some(self.message.clone()),
+            LicensureAction::RequireAttestation if self.attestation.is_none() => {
+                Some(format!("{} - attest to the jurisdiction basis before signing", self.message))
+            }
+            LicensureAction::RequireAttestation => None,
+        }
+    }
+}
+
+/// Check a patient location against the clinician's licenses on the session date
+pub fn check_location(
+    note_id: &str,
+    patient_location: &str,
+    session_date: &str,
+    licenses: &[ClinicianLicense],
+    policy: &LicensurePolicy,
+) -> LicensureCheck {
+    let location = normalize_jurisdiction(patient_location);
+    let current: Vec<&ClinicianLicense> = licenses.iter().filter(|l| l.valid_on(session_date)).collect();
+
+    let state_license = current.iter().find(|l| {
+        l.kind == LicenseKind::StateLicense
+            && l.jurisdiction.as_deref().map(normalize_jurisdiction).as_deref() == Some(location.as_str())
+    });
+    let apit = current.iter()
+        .find(|l| l.kind == LicenseKind::PsypactApit)
+        .filter(|_| policy.psypact_states.iter().any(|s| normalize_jurisdiction(s) == location));
+
+    let (status, covered_by, message) = match (state_license, apit) {
+        (Some(l), _) => (
+            LicensureStatus::Licensed,
+            Some(l.license_number.clone()),
+            format!("Licensed in {}", location),
+        ),
+        (None, Some(l)) => (
+            LicensureStatus::Psypact,
+            Some(l.license_number.clone()),
+            format!("{} is covered by PSYPACT APIT", location),
+        ),
+        (None, None) => (
+            LicensureStatus::OutOfJurisdiction,
+            None,
+            format!("No current license or PSYPACT authority covers a patient located in {}", location),
+        ),
+    };
+
+    LicensureCheck {
+        note_id: note_id.to_string(),
+        patient_location: location,
+        session_date: session_date.to_string(),
+        status,
+        covered_by,
+        action: policy.out_of_jurisdiction,
+        message,
+        attestation: None,
+        attested_at: None,
+        checked_at: chrono::Utc::now().timestamp(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn license(kind: LicenseKind, jurisdiction: Option<&str>, number: &str, expires_on: Option<&str>) -> ClinicianLicense {
+        ClinicianLicense {
+            id: number.to_string(),
+            kind,
+            jurisdiction: jurisdiction.map(String::from),
+            license_number: number.to_string(),
+            expires_on: expires_on.map(String::from),
+            created_at: 0,
+        }
+    }
+
+    #[test]
+    fn test_state_license_and_psypact_coverage() {
+        let policy = LicensurePolicy::default();
+        let licenses = vec![
+            license(LicenseKind::StateLicense, Some("TX"), "TX-123", Some("2025-06-30")),
+            license(LicenseKind::PsypactApit, None, "APIT-9", None),
+        ];
+
+        let check = check_location("n1", "Texas", "2025-01-10", &licenses, &policy);
+        assert_eq!(check.status, LicensureStatus::Licensed);
+        assert_eq!(check.covered_by.as_deref(), Some("TX-123"));
+
+        // Expired state license, but Texas is a compact state
+        let check = check_location("n1", "tx", "2025-07-01", &licenses, &policy);
+        assert_eq!(check.status, LicensureStatus::Psypact);
+
+        let check = check_location("n1", "CA", "2025-01-10", &licenses, &policy);
+        assert_eq!(check.status, LicensureStatus::OutOfJurisdiction);
+        assert!(check.sign_blocker().is_some());
+    }
+
+    #[test]
+    fn test_sign_blocker_follows_policy() {
+        let mut policy = LicensurePolicy::default();
+        let mut check = check_location("n1", "NY", "2025-01-10", &[], &policy);
+        assert_eq!(check.action, LicensureAction::RequireAttestation);
+        check.attestation = Some("Patient temporarily in NY; emergency continuity of care".to_string());
+        assert!(check.sign_blocker().is_none());
+
+        policy.out_of_jurisdiction = LicensureAction::Block;
+        let mut check = check_location("n1", "NY", "2025-01-10", &[], &policy);
+        check.attestation = Some("attested".to_string());
+        assert!(check.sign_blocker().is_some());
+    }
+}
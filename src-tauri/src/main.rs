@@ -33,6 +33,36 @@ mod ehr_export;
 mod legal_export;
 mod performance;
 mod deidentify;
+mod mse;
+mod note_types;
+mod audio;
+mod ai_eval;
+mod photo;
+mod licensure;
+mod audio_preprocess;
+mod branding;
+mod readability;
+mod residency;
+mod audit_slice;
+mod ocr;
+mod cohort;
+mod maintenance;
+mod idempotency;
+mod retention;
+mod client_letter;
+mod reanalysis;
+mod json_schema;
+mod note_export;
+mod dictation;
+mod passphrase;
+mod replica;
+mod note_authors;
+mod ids;
+mod binder;
+mod backup;
+mod fulltext;
+mod access_history;
+mod note_diff;
 
 use std::sync::Mutex;
 use tauri::Manager;
@@ -40,21 +70,35 @@ use commands::AppState;
 use vault::Vault;
 
 fn main() {
+    performance::start_startup_clock();
     env_logger::init();
     
     tauri::Builder::default()
         .setup(|app| {
             // Initialize vault directory
-            let app_dir = app.path_resolver()
-                .app_data_dir()
-                .expect("Failed to get app data directory");
-            
-            std::fs::create_dir_all(&app_dir).ok();
+            let app_dir = performance::time_phase("data_dir", || {
+                let app_dir = app.path_resolver()
+                    .app_data_dir()
+                    .expect("Failed to get app data directory");
+                std::fs::create_dir_all(&app_dir).ok();
+                app_dir
+            });
             
             log::info!("Evidify starting, data dir: {:?}", app_dir);
             
+            // Residency check against the default policy; create/unlock
+            // re-check once an organization policy is loaded
+            let vault_dir = performance::time_phase("residency", || {
+                let vault_dir = residency::resolve_data_dir(&app_dir);
+                let check = residency::check_data_dir(&vault_dir, &policy::DataResidencyPolicy::default());
+                if check.status != residency::ResidencyStatus::Compliant {
+                    log::warn!("Vault residency: {}", check.message);
+                }
+                vault_dir
+            });
+            
             // Create vault instance
-            let vault = Vault::new(app_dir);
+            let vault = performance::time_phase("vault", || Vault::new(vault_dir));
             
             // Manage app state
             app.manage(AppState {
@@ -81,7 +125,28 @@ fn main() {
             app.manage(time_tracking::TimeTrackerState::default());
             
             // Manage performance state
-            app.manage(performance::PerformanceState::default());
+            app.manage(performance::PerformanceState::load(&app_dir));
+            
+            // Manage audio device state (mic check)
+            app.manage(audio::AudioState::default());
+            
+            // Push-to-dictate (hotkey registered once the UI applies its settings)
+            app.manage(dictation::DictationState::default());
+            
+            // Snapshot that metrics and dashboards read instead of the live vault
+            app.manage(replica::ReplicaState::default());
+            
+            // Overnight maintenance (optimize, index cleanup, audit checkpoint, backup)
+            // (the scheduler thread starts on unlock)
+            app.manage(maintenance::MaintenanceState::default());
+            
+            // Background ethics re-analysis of signed notes after rule updates
+            app.manage(reanalysis::ReanalysisState::default());
+            
+            // HTTP clients, the background worker and RAG tables are
+            // deferred and built on first use, after the unlock screen;
+            // the maintenance and EHR delivery threads start on unlock
+            performance::mark_setup_complete();
             
             Ok(())
         })
@@ -91,15 +156,33 @@ fn main() {
             commands::create_vault,
             commands::unlock_vault,
             commands::lock_vault,
+            commands::change_passphrase,
+            backup::create_vault_backup,
+            backup::inspect_vault_backup,
+            backup::restore_vault_backup,
             commands::vault_status,
             commands::vault_clear_stale_keychain,
             commands::vault_delete_db,
+            residency::check_data_residency,
+            residency::relocate_vault,
             
             // Client commands
             commands::create_client,
             commands::list_clients,
             commands::get_client,
             commands::update_client,
+            commands::get_client_lookup_salt,
+            commands::lookup_client_by_hash,
+            
+            // Reason-for-access
+            commands::chart_access_required,
+            commands::record_chart_access_reason,
+            commands::set_emergency_access_credential,
+            commands::activate_emergency_access,
+            commands::read_emergency_access_notes,
+            commands::end_emergency_access,
+            commands::list_emergency_access_sessions,
+            commands::review_emergency_access,
             
             // Note commands
             commands::create_note,
@@ -108,21 +191,50 @@ fn main() {
             commands::update_note,
             commands::update_structured_note,
             commands::sign_note,
+            note_authors::get_note_authorship,
+            note_authors::add_note_author,
+            note_authors::attribute_note_section,
+            note_authors::set_author_signing_credential,
+            note_authors::sign_note_contribution,
+            ids::get_id_scheme,
+            ids::set_id_scheme,
+            commands::add_clinician_license,
+            commands::list_clinician_licenses,
+            commands::remove_clinician_license,
+            commands::record_telehealth_location,
+            commands::attest_telehealth_jurisdiction,
+            commands::get_licensure_check,
             commands::export_note,
+            commands::acquire_note_lock,
+            commands::release_note_lock,
+            commands::release_note_locks_for_holder,
+            commands::get_note_lock,
             
             // Ethics commands
             commands::analyze_ethics,
+            commands::analyze_ethics_with_context,
+            commands::analyze_note_ethics,
+            commands::get_note_detection_state,
+            commands::explain_detection,
+            commands::get_note_sources,
             commands::resolve_detection,
+            commands::get_severity_calibration_report,
+            commands::compute_note_risk_summary,
             
             // AI commands
             commands::check_ollama,
             commands::structure_note_ai,
+            commands::structure_note_ai_guarded,
             commands::embed_text,
             commands::search_notes,
             
             // Export commands
             commands::classify_export_path,
             commands::validate_export_path,
+            commands::estimate_export,
+            commands::record_export_destination,
+            commands::run_export_presence_checks,
+            commands::list_export_presence_checks,
             
             // Voice commands
             commands::list_whisper_models,
@@ -142,6 +254,7 @@ fn main() {
             
             // Attestation commands
             commands::get_quick_picks,
+            commands::render_quick_pick_template,
             commands::consolidate_detections,
             commands::validate_attestation,
             commands::check_attestation_completeness,
@@ -151,12 +264,28 @@ fn main() {
             commands::record_session_metrics,
             commands::get_dashboard_metrics,
             commands::get_metrics_report,
+            replica::refresh_reporting_replica,
+            replica::get_reporting_replica_status,
+            commands::get_wellness_indicators,
+            commands::get_client_documentation_scorecard,
             
             // Recording commands
             commands::evaluate_recording_policy,
             commands::start_recording_session,
             commands::get_default_recording_policy,
             
+            // Audio device commands
+            audio::list_audio_devices,
+            audio::monitor_input_level,
+            audio::stop_input_level_monitor,
+            // Push-to-dictate
+            dictation::configure_dictation,
+            dictation::get_dictation_config,
+            dictation::dictation_key_down,
+            dictation::dictation_key_up,
+            // Vault passphrase strength
+            passphrase::check_passphrase_strength,
+            
             // Deep Analysis commands
             commands::create_patient_feature_store,
             commands::add_session_to_feature_store,
@@ -164,43 +293,85 @@ fn main() {
             commands::detect_inconsistencies,
             commands::analyze_trajectories,
             commands::generate_hypotheses,
+            commands::record_missed_session,
+            commands::evaluate_early_warnings,
+            commands::get_caseload_early_warnings,
             
             // Cross-Client Search
             commands::search_clients,
+            commands::search_everything,
+            fulltext::search_notes_fulltext,
             commands::get_client_last_visit,
             commands::get_client_visit_count_since,
             
             // Note Editing
             commands::update_note_content,
             commands::amend_note,
+            note_diff::summarize_note_changes,
+            note_diff::get_note_history,
+            
+            // Trash
+            commands::trash_note,
+            commands::restore_note,
+            commands::purge_note,
+            commands::trash_client,
+            commands::restore_client,
+            commands::purge_client,
+            commands::list_trash,
             
             // Treatment Progress
             commands::get_treatment_progress,
             
+            // Mental Status Exam
+            commands::record_note_mse,
+            commands::update_note_mse,
+            commands::get_note_mse,
+            commands::get_mse_trend,
+            
+            // Note type registry
+            commands::list_note_types,
+            commands::save_note_type,
+            commands::delete_note_type,
+            commands::get_note_type_for_note,
+            
             // Document Management
             commands::upload_document,
             commands::list_documents,
             commands::get_document_data,
             commands::delete_document,
             commands::search_documents,
+            commands::get_practice_profile,
+            commands::set_practice_profile,
+            commands::set_practice_logo,
+            commands::get_practice_logo,
+            commands::remove_practice_logo,
+            commands::set_client_photo,
+            commands::get_client_photo_info,
+            commands::get_client_photo,
+            commands::get_client_photo_for_export,
+            commands::remove_client_photo,
             commands::update_document_ocr,
             
             // Storage Management
             commands::get_storage_stats,
+            commands::get_dashboard_snapshot,
             commands::optimize_database,
             
             // OCR Processing
             commands::process_document_ocr,
             commands::check_ocr_available,
+            commands::list_ocr_languages,
             
             // Voice/Whisper
             commands::download_whisper_model,
             
             // Pre-Session Prep Sheet
             commands::generate_prep_sheet,
+            commands::clear_derived_cache,
             
             // AI Completion Check
             commands::check_note_completion,
+            ai_eval::run_ai_evaluation,
             
             // Export
             commands::export_note_to_file,
@@ -210,15 +381,29 @@ fn main() {
             commands::list_trainees,
             commands::submit_note_for_review,
             commands::get_pending_reviews,
+            commands::get_blinded_review_note,
             commands::add_review_comment,
             commands::complete_review,
             commands::get_supervisor_dashboard,
             commands::get_trainee_pending_reviews,
             commands::get_note_reviews,
+            commands::save_review_reflection,
+            commands::get_review_reflection,
+            commands::list_trainee_reflections,
+            commands::acknowledge_review_reflection,
+            commands::get_trainee_competency_report,
+            commands::export_trainee_competency_report,
+            commands::get_supervision_quality_report,
+            commands::export_supervision_quality_report,
+            commands::record_group_supervision,
+            commands::list_group_supervision_sessions,
+            commands::get_group_supervision_log,
+            commands::export_group_supervision_log,
             
             // Audit commands
             commands::get_audit_log,
             commands::verify_audit_chain,
+            access_history::get_chart_access_history,
             
             // Clipboard commands
             clipboard::clipboard_copy,
@@ -232,6 +417,7 @@ fn main() {
             policy::load_policy_from_file,
             policy::check_export_policy,
             policy::check_attestation_policy,
+            policy::simulate_policy,
             policy::get_policy_version,
             
             // Supervision commands
@@ -239,6 +425,8 @@ fn main() {
             supervision::add_feedback_annotation,
             supervision::get_note_annotations,
             supervision::cosign_note,
+            supervision::get_cosign_escalations,
+            supervision::get_cosign_sla_report,
             supervision::get_cosignature,
             supervision::check_cosign_required,
             supervision::update_competency_rating,
@@ -252,37 +440,119 @@ fn main() {
             // Audit Pack commands
             audit_pack::generate_audit_pack,
             audit_pack::export_audit_pack,
+            audit_pack::generate_note_disclosure,
+            audit_pack::verify_note_disclosure,
+            audit_pack::verify_audit_pack_environment,
+            audit_slice::get_install_signing_identity,
+            audit_slice::export_audit_slice,
+            audit_slice::import_audit_slice,
+            audit_slice::list_external_audit_slices,
+            audit_slice::get_external_audit_slice,
             
             // Time Tracking commands
             time_tracking::record_time_metrics,
             time_tracking::get_time_metrics,
             time_tracking::get_efficiency_score,
+            time_tracking::start_timer,
+            time_tracking::record_timer_activity,
+            time_tracking::pause_timer,
+            time_tracking::resume_timer,
+            time_tracking::stop_timer,
+            time_tracking::get_active_timers,
             
             // EHR Export commands
             ehr_export::get_ehr_targets,
             ehr_export::export_to_ehr,
+            ehr_export::list_ehr_deliveries,
+            ehr_export::get_unreconciled_ehr_deliveries,
+            ehr_export::acknowledge_ehr_delivery,
+            ehr_export::retry_ehr_delivery,
             
             // Legal Export commands
             legal_export::generate_legal_report,
             legal_export::export_legal_report,
+            legal_export::assemble_records_response,
+            legal_export::export_records_response,
+            binder::export_audit_binder,
+            readability::analyze_readability,
+            
+            // Cohort (group program) commands
+            cohort::create_cohort,
+            cohort::list_cohorts,
+            cohort::archive_cohort,
+            cohort::add_cohort_member,
+            cohort::end_cohort_membership,
+            cohort::list_cohort_members,
+            cohort::record_cohort_session,
+            cohort::get_cohort_attendance,
+            
+            // Maintenance commands
+            maintenance::record_user_activity,
+            maintenance::run_maintenance_now,
+            maintenance::get_maintenance_schedule,
+            maintenance::set_maintenance_schedule,
+            maintenance::get_maintenance_health,
+            maintenance::list_maintenance_runs,
+            
+            // Retention and legal hold commands
+            retention::get_retention_schedule,
+            retention::set_retention_schedule,
+            retention::set_client_retention_profile,
+            retention::get_destruction_eligibility_report,
+            retention::destroy_client_records,
+            retention::list_destruction_certificates,
+            retention::place_legal_hold,
+            retention::release_legal_hold,
+            retention::list_legal_holds,
+            
+            // Client visit-summary letters
+            client_letter::prefill_client_letter,
+            client_letter::create_client_letter,
+            client_letter::update_client_letter,
+            client_letter::rewrite_client_letter,
+            client_letter::finalize_client_letter,
+            client_letter::list_client_letters,
+            client_letter::record_release_authorization,
+            client_letter::revoke_release_authorization,
+            client_letter::list_release_authorizations,
+            client_letter::export_client_letter,
+            
+            // Ethics re-analysis (advisory findings on signed notes)
+            reanalysis::reanalyze_notes,
+            reanalysis::get_reanalysis_run,
+            reanalysis::list_reanalysis_runs,
+            reanalysis::list_advisory_findings,
+            
+            // Machine-readable note export (note_export_v1)
+            note_export::export_note_json,
+            note_export::get_note_export_schema,
             
             // Performance commands
             performance::get_performance_stats,
+            performance::mark_unlock_screen_ready,
             performance::clear_caches,
+            performance::set_memory_budget,
             performance::get_notes_paginated,
             
             // De-identification commands (HIPAA Safe Harbor)
             commands::deidentify_text,
+            commands::deidentify_document,
             commands::deidentify_note,
             commands::detect_contextual_identifiers,
             commands::save_deidentification_audit,
             commands::get_deidentification_audits,
             commands::export_deidentified_case,
+            commands::export_deidentified_case_encrypted,
+            commands::verify_deidentification_certificate,
             commands::create_consultation_draft,
             commands::list_consultation_drafts,
             commands::get_consultation_draft,
             commands::update_consultation_draft,
             commands::delete_consultation_draft,
+            commands::add_trusted_recipient,
+            commands::list_trusted_recipients,
+            commands::revoke_trusted_recipient,
+            commands::seal_consultation_draft,
 
             // Forensic UI support commands (annotations / promotion)
             commands::forensic_list_annotations,
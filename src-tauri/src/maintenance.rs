@@ -0,0 +1,493 @@
+// Maintenance Module
+//
+// One orchestrated maintenance job instead of ad-hoc VACUUM calls: expired
+// trash purge, database optimize, search index cleanup, an audit chain
+// checkpoint and a vault backup, run in that order during a configurable overnight window once
+// the clinician has been idle long enough. Each task's outcome is stored
+// with the run so the maintenance health view can show what last ran,
+// what failed and what is overdue.
+//
+// The scheduler starts with the first unlock and only runs while the vault
+// is unlocked (every task needs the database key). It never waits on the
+// vault lock - if a command holds it, the tick is skipped.
+
+use chrono::{NaiveDateTime, NaiveTime};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
+
+use crate::vault::Vault;
+
+/// How often the scheduler checks whether a run is due
+const SCHEDULER_TICK: std::time::Duration = std::time::Duration::from_secs(5 * 60);
+
+/// A task with no success in this long is reported as overdue
+pub const OVERDUE_AFTER_HOURS: i64 = 72;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MaintenanceTask {
+    /// Purge notes and clients past the trash retention window
+    PurgeTrash,
+    /// PRAGMA optimize + ANALYZE, VACUUM when enough pages are free
+    Optimize,
+    /// Drop orphaned embeddings and derived-cache entries from older rules
+    SearchIndex,
+    /// Verify the audit chain since the last checkpoint and record a new one
+    AuditCheckpoint,
+    /// Hash-verified copy of the encrypted vault database
+    Backup,
+}
+
+impl MaintenanceTask {
+    /// Run order: purged rows are reclaimed by the optimize that follows;
+    /// backups are taken after the database is compacted and the audit
+    /// chain is known to be intact
+    pub const ALL: [MaintenanceTask; 5] = [
+        MaintenanceTask::PurgeTrash,
+        MaintenanceTask::Optimize,
+        MaintenanceTask::SearchIndex,
+        MaintenanceTask::AuditCheckpoint,
+        MaintenanceTask::Backup,
+    ];
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskStatus {
+    Ok,
+    Skipped,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskResult {
+    pub task: MaintenanceTask,
+    pub status: TaskStatus,
+    pub detail: String,
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MaintenanceTrigger {
+    Scheduled,
+    Manual,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MaintenanceRun {
+    pub id: String,
+    pub trigger: MaintenanceTrigger,
+    pub started_at: i64,
+    pub finished_at: i64,
+    pub tasks: Vec<TaskResult>,
+}
+
+impl MaintenanceRun {
+    pub fn succeeded(&self) -> bool {
+        self.tasks.iter().all(|t| t.status != TaskStatus::Failed)
+    }
+
+    /// Whether trash was purged, leaving the reporting replica stale
+    pub fn purged_trash(&self) -> bool {
+        self.tasks.iter().any(|t| t.task == MaintenanceTask::PurgeTrash && t.status == TaskStatus::Ok)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MaintenanceSchedule {
+    pub enabled: bool,
+    /// Local time window, "HH:MM"; may wrap past midnight
+    pub window_start: String,
+    pub window_end: String,
+    /// Minutes without user activity before a scheduled run starts
+    pub idle_minutes: u32,
+    /// Minimum hours between scheduled runs
+    pub min_interval_hours: u32,
+    /// Backups kept; older ones are deleted after a successful backup
+    pub keep_backups: u32,
+}
+
+impl Default for MaintenanceSchedule {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            window_start: "01:00".to_string(),
+            window_end: "05:00".to_string(),
+            idle_minutes: 30,
+            min_interval_hours: 20,
+            keep_backups: 7,
+        }
+    }
+}
+
+impl MaintenanceSchedule {
+    pub fn validate(&self) -> Result<(), String> {
+        for time in [&self.window_start, &self.window_end] {
+            NaiveTime::parse_from_str(time, "%H:%M")
+                .map_err(|_| format!("Invalid window time '{}' (expected HH:MM)", time))?;
+        }
+        if self.window_start == self.window_end {
+            return Err("Maintenance window start and end must differ".to_string());
+        }
+        if self.keep_backups == 0 {
+            return Err("At least one backup must be kept".to_string());
+        }
+        Ok(())
+    }
+
+    fn in_window(&self, time: NaiveTime) -> bool {
+        let (Ok(start), Ok(end)) = (
+            NaiveTime::parse_from_str(&self.window_start, "%H:%M"),
+            NaiveTime::parse_from_str(&self.window_end, "%H:%M"),
+        ) else {
+            return false;
+        };
+        if start < end {
+            start <= time && time < end
+        } else {
+            time >= start || time < end
+        }
+    }
+
+    /// Whether a scheduled run should start now. Times are Unix seconds;
+    /// `now_local` is the same instant as wall-clock time.
+    pub fn is_due(&self, now: i64, now_local: NaiveDateTime, last_run_at: Option<i64>, last_activity_at: i64) -> bool {
+        self.enabled
+            && self.in_window(now_local.time())
+            && now - last_activity_at >= self.idle_minutes as i64 * 60
+            && last_run_at.map(|at| now - at >= self.min_interval_hours as i64 * 3600).unwrap_or(true)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskHealth {
+    pub task: MaintenanceTask,
+    pub last_status: Option<TaskStatus>,
+    pub last_run_at: Option<i64>,
+    pub last_success_at: Option<i64>,
+    pub last_detail: Option<String>,
+    pub overdue: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MaintenanceHealth {
+    pub schedule: MaintenanceSchedule,
+    pub running: bool,
+    pub last_run: Option<MaintenanceRun>,
+    pub tasks: Vec<TaskHealth>,
+    /// No task failed on its last run and none is overdue
+    pub healthy: bool,
+}
+
+/// Per-task health from recent runs (any order)
+pub fn task_health(runs: &[MaintenanceRun], now: i64) -> Vec<TaskHealth> {
+    MaintenanceTask::ALL.iter()
+        .map(|&task| {
+            let mut attempts: Vec<(i64, &TaskResult)> = runs.iter()
+                .flat_map(|run| run.tasks.iter().filter(|t| t.task == task).map(move |t| (run.started_at, t)))
+                .collect();
+            attempts.sort_by_key(|(at, _)| std::cmp::Reverse(*at));
+
+            let last = attempts.first();
+            let last_success_at = attempts.iter()
+                .find(|(_, t)| t.status == TaskStatus::Ok)
+                .map(|(at, _)| *at);
+            TaskHealth {
+                task,
+                last_status: last.map(|(_, t)| t.status),
+                last_run_at: last.map(|(at, _)| *at),
+                last_success_at,
+                last_detail: last.map(|(_, t)| t.detail.clone()),
+                overdue: last_success_at.map(|at| now - at > OVERDUE_AFTER_HOURS * 3600).unwrap_or(true),
+            }
+        })
+        .collect()
+}
+
+// ============================================
+// Orchestration
+// ============================================
+
+/// Run `tasks` in order against an unlocked vault. A failing task does not
+/// stop the ones after it. The run is stored before it is returned.
+pub fn run_maintenance(
+    vault: &Vault,
+    tasks: &[MaintenanceTask],
+    trigger: MaintenanceTrigger,
+    schedule: &MaintenanceSchedule,
+) -> Result<MaintenanceRun, String> {
+    if !vault.is_unlocked() {
+        return Err("Unlock the vault to run maintenance".to_string());
+    }
+    let started_at = chrono::Utc::now().timestamp();
+    let mut results = Vec::with_capacity(tasks.len());
+
+    for &task in MaintenanceTask::ALL.iter().filter(|t| tasks.contains(t)) {
+        // Rotation would replace good backups with a copy of a chain that
+        // just failed verification
+        let chain_failed = results.iter().any(|r: &TaskResult| {
+            r.task == MaintenanceTask::AuditCheckpoint && r.status == TaskStatus::Failed
+        });
+        if task == MaintenanceTask::Backup && chain_failed {
+            results.push(TaskResult {
+                task,
+                status: TaskStatus::Skipped,
+                detail: "Audit chain failed verification; existing backups kept".to_string(),
+                duration_ms: 0,
+            });
+            continue;
+        }
+
+        let started = std::time::Instant::now();
+        let outcome = match task {
+            MaintenanceTask::PurgeTrash => vault.maintenance_purge_trash(),
+            MaintenanceTask::Optimize => vault.maintenance_optimize(),
+            MaintenanceTask::SearchIndex => vault.maintenance_search_index(),
+            MaintenanceTask::AuditCheckpoint => vault.maintenance_audit_checkpoint(),
+            MaintenanceTask::Backup => vault.maintenance_backup(schedule.keep_backups as usize),
+        };
+        let (status, detail) = match outcome {
+            Ok(detail) => (TaskStatus::Ok, detail),
+            Err(e) => {
+                log::warn!("Maintenance task {:?} failed: {}", task, e);
+                (TaskStatus::Failed, e.to_string())
+            }
+        };
+        results.push(TaskResult {
+            task,
+            status,
+            detail,
+            duration_ms: started.elapsed().as_millis() as u64,
+        });
+    }
+
+    let run = MaintenanceRun {
+        id: uuid::Uuid::new_v4().to_string(),
+        trigger,
+        started_at,
+        finished_at: chrono::Utc::now().timestamp(),
+        tasks: results,
+    };
+    vault.record_maintenance_run(&run).map_err(|e| e.to_string())?;
+    log::info!("Maintenance run finished ({} tasks, ok: {})", run.tasks.len(), run.succeeded());
+    Ok(run)
+}
+
+/// Activity and in-progress tracking for the scheduler
+pub struct MaintenanceState {
+    last_activity_at: AtomicI64,
+    running: AtomicBool,
+    scheduler_started: AtomicBool,
+}
+
+impl Default for MaintenanceState {
+    fn default() -> Self {
+        Self {
+            last_activity_at: AtomicI64::new(chrono::Utc::now().timestamp()),
+            running: AtomicBool::new(false),
+            scheduler_started: AtomicBool::new(false),
+        }
+    }
+}
+
+impl MaintenanceState {
+    pub fn record_activity(&self) {
+        self.last_activity_at.store(chrono::Utc::now().timestamp(), Ordering::Relaxed);
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::Relaxed)
+    }
+
+    /// Run `f` unless another run is in progress
+    fn exclusive<T>(&self, f: impl FnOnce() -> Result<T, String>) -> Result<T, String> {
+        if self.running.swap(true, Ordering::AcqRel) {
+            return Err("Maintenance is already running".to_string());
+        }
+        let result = f();
+        self.running.store(false, Ordering::Release);
+        result
+    }
+}
+
+/// Background thread that starts a scheduled run when one is due. Started
+/// on the first unlock, not at launch; later calls do nothing.
+pub fn start_scheduler(app: &tauri::AppHandle) {
+    use tauri::Manager;
+
+    if app.state::<MaintenanceState>().scheduler_started.swap(true, Ordering::AcqRel) {
+        return;
+    }
+    let app = app.clone();
+    std::thread::spawn(move || loop {
+        std::thread::sleep(SCHEDULER_TICK);
+
+        let state = app.state::<AppState>();
+        let maintenance = app.state::<MaintenanceState>();
+        let Ok(vault) = state.vault.try_lock() else {
+            continue;
+        };
+        if !vault.is_unlocked() || maintenance.is_running() {
+            continue;
+        }
+
+        let schedule = vault.maintenance_schedule().unwrap_or_default();
+        let last_run_at = vault.list_maintenance_runs(1).ok()
+            .and_then(|runs| runs.first().map(|r| r.started_at));
+        let now = chrono::Utc::now();
+        let due = schedule.is_due(
+            now.timestamp(),
+            now.with_timezone(&chrono::Local).naive_local(),
+            last_run_at,
+            maintenance.last_activity_at.load(Ordering::Relaxed),
+        );
+        if due {
+            let result = maintenance.exclusive(|| {
+                run_maintenance(&vault, &MaintenanceTask::ALL, MaintenanceTrigger::Scheduled, &schedule)
+            });
+            drop(vault);
+            match result {
+                Ok(run) if run.purged_trash() => app.state::<crate::replica::ReplicaState>().clear(),
+                Ok(_) => {}
+                Err(e) => log::warn!("Scheduled maintenance did not run: {}", e),
+            }
+        }
+    });
+}
+
+// ============================================
+// Tauri Commands
+// ============================================
+
+use tauri::State;
+use crate::commands::AppState;
+
+/// Called by the UI (debounced) on user input so runs wait for idle time
+#[tauri::command]
+pub fn record_user_activity(maintenance: State<'_, MaintenanceState>) {
+    maintenance.record_activity();
+}
+
+/// Run maintenance now; all tasks unless a subset is given
+#[tauri::command]
+pub fn run_maintenance_now(
+    state: State<'_, AppState>,
+    maintenance: State<'_, MaintenanceState>,
+    replica_state: State<'_, crate::replica::ReplicaState>,
+    tasks: Option<Vec<MaintenanceTask>>,
+) -> Result<MaintenanceRun, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    let schedule = vault.maintenance_schedule().map_err(|e| format!("{}", e))?;
+    let tasks = tasks.unwrap_or_else(|| MaintenanceTask::ALL.to_vec());
+    let run = maintenance.exclusive(|| run_maintenance(&vault, &tasks, MaintenanceTrigger::Manual, &schedule))?;
+    drop(vault);
+    if run.purged_trash() {
+        replica_state.clear();
+    }
+    Ok(run)
+}
+
+#[tauri::command]
+pub fn get_maintenance_schedule(state: State<'_, AppState>) -> Result<MaintenanceSchedule, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.maintenance_schedule().map_err(|e| format!("{}", e))
+}
+
+#[tauri::command]
+pub fn set_maintenance_schedule(state: State<'_, AppState>, schedule: MaintenanceSchedule) -> Result<(), String> {
+    schedule.validate()?;
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.set_maintenance_schedule(&schedule).map_err(|e| format!("{}", e))
+}
+
+/// Last outcome per task, overdue tasks and the most recent run
+#[tauri::command]
+pub fn get_maintenance_health(
+    state: State<'_, AppState>,
+    maintenance: State<'_, MaintenanceState>,
+) -> Result<MaintenanceHealth, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    let schedule = vault.maintenance_schedule().map_err(|e| format!("{}", e))?;
+    let runs = vault.list_maintenance_runs(30).map_err(|e| format!("{}", e))?;
+    let tasks = task_health(&runs, chrono::Utc::now().timestamp());
+    let healthy = tasks.iter().all(|t| !t.overdue && t.last_status != Some(TaskStatus::Failed));
+
+    Ok(MaintenanceHealth {
+        schedule,
+        running: maintenance.is_running(),
+        last_run: runs.into_iter().next(),
+        tasks,
+        healthy,
+    })
+}
+
+#[tauri::command]
+pub fn list_maintenance_runs(state: State<'_, AppState>, limit: Option<u32>) -> Result<Vec<MaintenanceRun>, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.list_maintenance_runs(limit.unwrap_or(20)).map_err(|e| format!("{}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn at(h: u32, m: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 3, 10).unwrap().and_hms_opt(h, m, 0).unwrap()
+    }
+
+    #[test]
+    fn test_due_only_in_window_when_idle() {
+        let schedule = MaintenanceSchedule {
+            window_start: "23:00".to_string(),
+            window_end: "04:00".to_string(),
+            ..Default::default()
+        };
+        let now = 1_800_000_000;
+        let idle_since = now - 3600;
+
+        // Window wraps past midnight
+        assert!(schedule.is_due(now, at(2, 30), None, idle_since));
+        assert!(schedule.is_due(now, at(23, 15), None, idle_since));
+        assert!(!schedule.is_due(now, at(12, 0), None, idle_since));
+
+        // Recent activity or a recent run holds it back
+        assert!(!schedule.is_due(now, at(2, 30), None, now - 60));
+        assert!(!schedule.is_due(now, at(2, 30), Some(now - 3 * 3600), idle_since));
+        assert!(schedule.is_due(now, at(2, 30), Some(now - 24 * 3600), idle_since));
+
+        let disabled = MaintenanceSchedule { enabled: false, ..schedule };
+        assert!(!disabled.is_due(now, at(2, 30), None, idle_since));
+    }
+
+    #[test]
+    fn test_task_health_tracks_last_success() {
+        let now = 1_800_000_000;
+        let result = |task, status| TaskResult { task, status, detail: String::new(), duration_ms: 1 };
+        let run = |started_at, tasks| MaintenanceRun {
+            id: format!("run-{}", started_at),
+            trigger: MaintenanceTrigger::Scheduled,
+            started_at,
+            finished_at: started_at + 60,
+            tasks,
+        };
+        let runs = vec![
+            run(now - 3600, vec![
+                result(MaintenanceTask::Optimize, TaskStatus::Ok),
+                result(MaintenanceTask::Backup, TaskStatus::Failed),
+            ]),
+            run(now - 24 * 3600, vec![result(MaintenanceTask::Backup, TaskStatus::Ok)]),
+        ];
+
+        let health = task_health(&runs, now);
+        let backup = health.iter().find(|h| h.task == MaintenanceTask::Backup).unwrap();
+        assert_eq!(backup.last_status, Some(TaskStatus::Failed));
+        assert_eq!(backup.last_success_at, Some(now - 24 * 3600));
+        assert!(!backup.overdue);
+
+        // Never run at all
+        let checkpoint = health.iter().find(|h| h.task == MaintenanceTask::AuditCheckpoint).unwrap();
+        assert!(checkpoint.overdue && checkpoint.last_status.is_none());
+    }
+}
@@ -51,7 +51,7 @@ pub struct SessionMetrics {
 }
 
 /// Aggregate metrics for dashboard
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DashboardMetrics {
     pub period_start: i64,
     pub period_end: i64,
@@ -82,7 +82,7 @@ pub struct DashboardMetrics {
     pub volume_trend: Vec<TrendPoint>,        // Notes per day over time
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TrendPoint {
     pub date: String,
     pub value: f32,
@@ -389,6 +389,317 @@ pub fn generate_report(dashboard: &DashboardMetrics, days: i32) -> MetricsReport
     }
 }
 
+// ============================================
+// Clinician Wellness (private)
+// ============================================
+//
+// Burnout indicators derived from session timestamps only. Computed on
+// demand for the clinician's own wellness panel: never stored, never
+// included in reports, SIEM forwarding or audit packs.
+
+/// What counts as the clinician's working day (local time)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkHours {
+    pub start_hour: u32,           // inclusive, 0-23
+    pub end_hour: u32,             // exclusive, 1-24
+}
+
+impl Default for WorkHours {
+    fn default() -> Self {
+        WorkHours { start_hour: 8, end_hour: 18 }
+    }
+}
+
+/// Late-night window (local time) flagged separately from ordinary after-hours work
+const LATE_NIGHT_START_HOUR: u32 = 22;
+const LATE_NIGHT_END_HOUR: u32 = 5;
+
+/// Per-week documentation load
+#[derive(Debug, Clone, Serialize)]
+pub struct WellnessWeek {
+    pub week_start: String,        // Monday, YYYY-MM-DD
+    pub total_minutes: f64,
+    pub after_hours_minutes: f64,
+    pub weekend_minutes: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WellnessIndicators {
+    pub period_days: i64,
+    pub total_sessions: u32,
+    pub total_minutes: f64,
+    pub after_hours_sessions: u32, // outside work hours on a weekday
+    pub after_hours_minutes: f64,
+    pub weekend_sessions: u32,
+    pub weekend_minutes: f64,
+    pub late_night_sessions: u32,
+    /// Share of documentation minutes done after hours or on weekends (0.0 - 1.0)
+    pub off_hours_share: f64,
+    /// Consecutive days, ending today or yesterday, with off-hours documentation
+    pub current_streak_days: u32,
+    pub longest_streak_days: u32,
+    pub weeks: Vec<WellnessWeek>,
+    pub level: String,             // "healthy", "elevated", "high"
+}
+
+/// Compute wellness indicators from (start, end) unix-second pairs,
+/// bucketed in the given timezone (the device's local zone in production).
+pub fn compute_wellness<Tz: chrono::TimeZone>(
+    sessions: &[(i64, i64)],
+    hours: &WorkHours,
+    tz: &Tz,
+    today: chrono::NaiveDate,
+    period_days: i64,
+) -> WellnessIndicators {
+    use chrono::{Datelike, Timelike, Weekday};
+    use std::collections::{BTreeMap, BTreeSet};
+
+    let mut total_minutes = 0.0;
+    let mut after_hours_sessions = 0;
+    let mut after_hours_minutes = 0.0;
+    let mut weekend_sessions = 0;
+    let mut weekend_minutes = 0.0;
+    let mut late_night_sessions = 0;
+    let mut off_hours_days = BTreeSet::new();
+    let mut weeks: BTreeMap<chrono::NaiveDate, WellnessWeek> = BTreeMap::new();
+
+    for &(start, end) in sessions {
+        let local = match tz.timestamp_opt(start, 0).single() {
+            Some(t) => t,
+            None => continue,
+        };
+        let minutes = (end - start).max(0) as f64 / 60.0;
+        let date = local.date_naive();
+        let hour = local.hour();
+
+        let weekend = matches!(date.weekday(), Weekday::Sat | Weekday::Sun);
+        let after_hours = !weekend && !(hours.start_hour..hours.end_hour).contains(&hour);
+
+        total_minutes += minutes;
+        if weekend {
+            weekend_sessions += 1;
+            weekend_minutes += minutes;
+        }
+        if after_hours {
+            after_hours_sessions += 1;
+            after_hours_minutes += minutes;
+        }
+        if !(LATE_NIGHT_END_HOUR..LATE_NIGHT_START_HOUR).contains(&hour) {
+            late_night_sessions += 1;
+        }
+        if weekend || after_hours {
+            off_hours_days.insert(date);
+        }
+
+        let week_start = date - Duration::days(date.weekday().num_days_from_monday() as i64);
+        let week = weeks.entry(week_start).or_insert_with(|| WellnessWeek {
+            week_start: week_start.format("%Y-%m-%d").to_string(),
+            total_minutes: 0.0,
+            after_hours_minutes: 0.0,
+            weekend_minutes: 0.0,
+        });
+        week.total_minutes += minutes;
+        if after_hours {
+            week.after_hours_minutes += minutes;
+        }
+        if weekend {
+            week.weekend_minutes += minutes;
+        }
+    }
+
+    let (current_streak_days, longest_streak_days) = off_hours_streaks(&off_hours_days, today);
+    let off_hours_share = if total_minutes > 0.0 {
+        (after_hours_minutes + weekend_minutes) / total_minutes
+    } else {
+        0.0
+    };
+
+    let level = if off_hours_share >= 0.30 || current_streak_days >= 7 {
+        "high"
+    } else if off_hours_share >= 0.15 || current_streak_days >= 3 {
+        "elevated"
+    } else {
+        "healthy"
+    };
+
+    WellnessIndicators {
+        period_days,
+        total_sessions: sessions.len() as u32,
+        total_minutes,
+        after_hours_sessions,
+        after_hours_minutes,
+        weekend_sessions,
+        weekend_minutes,
+        late_night_sessions,
+        off_hours_share,
+        current_streak_days,
+        longest_streak_days,
+        weeks: weeks.into_values().collect(),
+        level: level.to_string(),
+    }
+}
+
+/// (current, longest) runs of consecutive days; the current run may end
+/// yesterday so a streak isn't reset just because today hasn't happened yet.
+fn off_hours_streaks(days: &std::collections::BTreeSet<chrono::NaiveDate>, today: chrono::NaiveDate) -> (u32, u32) {
+    let mut longest = 0;
+    let mut run = 0;
+    let mut previous: Option<chrono::NaiveDate> = None;
+
+    for &day in days {
+        run = match previous {
+            Some(p) if day - p == Duration::days(1) => run + 1,
+            _ => 1,
+        };
+        longest = longest.max(run);
+        previous = Some(day);
+    }
+
+    let current = match previous {
+        Some(last) if today - last <= Duration::days(1) => run,
+        _ => 0,
+    };
+
+    (current, longest)
+}
+
+// ============================================
+// Documentation Completeness (per client)
+// ============================================
+//
+// Chart-level scorecard: signed vs unsigned notes, unattested detections,
+// and how recently the treatment plan, consent and assessment were
+// documented. Unlike the usage metrics above this is about one client's
+// record, so it is computed from the vault on demand and travels only in
+// chart audits (audit packs), never in usage reports.
+
+/// Treatment plans are reviewed quarterly
+pub const TREATMENT_PLAN_REVIEW_DAYS: i64 = 90;
+/// Informed consent is renewed annually
+pub const CONSENT_RENEWAL_DAYS: i64 = 365;
+/// Formal (re)assessment at least every six months
+pub const ASSESSMENT_INTERVAL_DAYS: i64 = 180;
+/// Items due within this many days are flagged before they lapse
+const DUE_SOON_DAYS: i64 = 14;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScorecardStatus {
+    Current,
+    DueSoon,
+    Overdue,
+    Missing,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScorecardItem {
+    pub item: String,               // "treatment_plan", "consent", "assessment"
+    pub status: ScorecardStatus,
+    pub last_documented: Option<String>, // YYYY-MM-DD
+    pub due_date: Option<String>,
+    pub interval_days: i64,
+}
+
+/// Per-note facts the scorecard needs (no content)
+#[derive(Debug, Clone)]
+pub struct ScorecardNote {
+    pub session_date: chrono::NaiveDate,
+    pub signed: bool,
+    pub detection_count: usize,
+    pub attested_count: usize,
+}
+
+/// Inputs gathered from the vault for one client
+#[derive(Debug, Clone, Default)]
+pub struct ScorecardInputs {
+    pub notes: Vec<ScorecardNote>,
+    pub last_treatment_plan: Option<chrono::NaiveDate>,
+    pub last_consent: Option<chrono::NaiveDate>,
+    pub last_assessment: Option<chrono::NaiveDate>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocumentationScorecard {
+    pub client_id: String,
+    pub generated_at: i64,
+    pub total_notes: u32,
+    pub signed_notes: u32,
+    pub unsigned_notes: u32,
+    /// Age of the oldest unsigned note, by session date
+    pub oldest_unsigned_days: Option<i64>,
+    pub notes_with_detections: u32,
+    /// Notes whose every detection has an attestation
+    pub notes_fully_attested: u32,
+    pub unattested_detections: u32,
+    pub items: Vec<ScorecardItem>,
+    /// Share of checks passing (0.0 - 1.0): signing, attestation and each
+    /// currency item count equally; due-soon items still pass
+    pub completeness: f64,
+}
+
+fn currency_item(item: &str, last: Option<chrono::NaiveDate>, interval_days: i64, today: chrono::NaiveDate) -> ScorecardItem {
+    let due = last.map(|d| d + Duration::days(interval_days));
+    let status = match due {
+        None => ScorecardStatus::Missing,
+        Some(due) if due < today => ScorecardStatus::Overdue,
+        Some(due) if due - today <= Duration::days(DUE_SOON_DAYS) => ScorecardStatus::DueSoon,
+        Some(_) => ScorecardStatus::Current,
+    };
+    ScorecardItem {
+        item: item.to_string(),
+        status,
+        last_documented: last.map(|d| d.format("%Y-%m-%d").to_string()),
+        due_date: due.map(|d| d.format("%Y-%m-%d").to_string()),
+        interval_days,
+    }
+}
+
+/// Score one client's chart as of `today`
+pub fn compute_scorecard(client_id: &str, inputs: &ScorecardInputs, today: chrono::NaiveDate) -> DocumentationScorecard {
+    let signed_notes = inputs.notes.iter().filter(|n| n.signed).count() as u32;
+    let unsigned_notes = inputs.notes.len() as u32 - signed_notes;
+    let oldest_unsigned_days = inputs.notes.iter()
+        .filter(|n| !n.signed)
+        .map(|n| (today - n.session_date).num_days())
+        .max();
+
+    let with_detections: Vec<&ScorecardNote> = inputs.notes.iter().filter(|n| n.detection_count > 0).collect();
+    let notes_fully_attested = with_detections.iter().filter(|n| n.attested_count >= n.detection_count).count() as u32;
+    let unattested_detections = with_detections.iter()
+        .map(|n| n.detection_count.saturating_sub(n.attested_count) as u32)
+        .sum();
+
+    let items = vec![
+        currency_item("treatment_plan", inputs.last_treatment_plan, TREATMENT_PLAN_REVIEW_DAYS, today),
+        currency_item("consent", inputs.last_consent, CONSENT_RENEWAL_DAYS, today),
+        currency_item("assessment", inputs.last_assessment, ASSESSMENT_INTERVAL_DAYS, today),
+    ];
+
+    let mut passed = items.iter()
+        .filter(|i| matches!(i.status, ScorecardStatus::Current | ScorecardStatus::DueSoon))
+        .count();
+    if unsigned_notes == 0 {
+        passed += 1;
+    }
+    if unattested_detections == 0 {
+        passed += 1;
+    }
+
+    DocumentationScorecard {
+        client_id: client_id.to_string(),
+        generated_at: Utc::now().timestamp(),
+        total_notes: inputs.notes.len() as u32,
+        signed_notes,
+        unsigned_notes,
+        oldest_unsigned_days,
+        notes_with_detections: with_detections.len() as u32,
+        notes_fully_attested,
+        unattested_detections,
+        completeness: passed as f64 / (items.len() + 2) as f64,
+        items,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -399,4 +710,65 @@ mod tests {
         assert_eq!(b.industry_avg_note_time_ms, 900_000);
         assert_eq!(b.typing_speed_wpm, 40);
     }
+    
+    #[test]
+    fn test_wellness_after_hours_and_streaks() {
+        use chrono::{NaiveDate, TimeZone};
+        
+        let tz = chrono::FixedOffset::west_opt(5 * 3600).unwrap();
+        let at = |d: u32, h: u32| tz.with_ymd_and_hms(2024, 3, d, h, 0, 0).unwrap().timestamp();
+        
+        // Mon 4th 10:00 (in hours), Tue-Thu 5th-7th 21:00, Sat 9th 23:00 (30 min each)
+        let sessions: Vec<(i64, i64)> = [(4, 10), (5, 21), (6, 21), (7, 21), (9, 23)]
+            .iter()
+            .map(|&(d, h)| (at(d, h), at(d, h) + 1800))
+            .collect();
+        
+        let today = NaiveDate::from_ymd_opt(2024, 3, 10).unwrap();
+        let w = compute_wellness(&sessions, &WorkHours::default(), &tz, today, 30);
+        
+        assert_eq!(w.after_hours_sessions, 3);
+        assert_eq!(w.weekend_sessions, 1);
+        assert_eq!(w.late_night_sessions, 1);
+        assert_eq!(w.longest_streak_days, 3);
+        assert_eq!(w.current_streak_days, 1);
+        assert!((w.off_hours_share - 0.8).abs() < 1e-9);
+        assert_eq!(w.level, "high");
+        assert_eq!(w.weeks.len(), 1);
+        assert_eq!(w.weeks[0].week_start, "2024-03-04");
+    }
+    
+    #[test]
+    fn test_documentation_scorecard() {
+        use chrono::NaiveDate;
+        
+        let day = |m: u32, d: u32| NaiveDate::from_ymd_opt(2024, m, d).unwrap();
+        let note = |date, signed, detection_count, attested_count| ScorecardNote {
+            session_date: date, signed, detection_count, attested_count,
+        };
+        let today = day(6, 1);
+        
+        let inputs = ScorecardInputs {
+            notes: vec![
+                note(day(5, 1), true, 2, 2),
+                note(day(5, 20), false, 3, 1),
+                note(day(5, 27), false, 0, 0),
+            ],
+            last_treatment_plan: Some(day(3, 10)),  // due 6/8: due soon
+            last_consent: Some(day(1, 15)),
+            last_assessment: None,
+        };
+        let card = compute_scorecard("c1", &inputs, today);
+        
+        assert_eq!((card.signed_notes, card.unsigned_notes), (1, 2));
+        assert_eq!(card.oldest_unsigned_days, Some(12));
+        assert_eq!((card.notes_with_detections, card.notes_fully_attested), (2, 1));
+        assert_eq!(card.unattested_detections, 2);
+        
+        let status: Vec<ScorecardStatus> = card.items.iter().map(|i| i.status).collect();
+        assert_eq!(status, vec![ScorecardStatus::DueSoon, ScorecardStatus::Current, ScorecardStatus::Missing]);
+        assert_eq!(card.items[0].due_date.as_deref(), Some("2024-06-08"));
+        // Plan + consent pass; unsigned notes, unattested detections, missing assessment fail
+        assert!((card.completeness - 0.4).abs() < 1e-9);
+    }
 }
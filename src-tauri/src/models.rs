@@ -181,6 +181,69 @@ impl std::fmt::Display for NoteStatus {
     }
 }
 
+/// Advisory edit lock on a note (one editor window at a time)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NoteLock {
+    pub note_id: String,
+    pub holder_id: String,         // window / pane instance id
+    pub holder_label: Option<String>,
+    pub acquired_at: i64,
+    pub expires_at: i64,           // refreshed by re-acquiring (heartbeat)
+}
+
+/// Result of an acquire attempt: our lock, or the lock we collided with
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NoteLockOutcome {
+    pub acquired: bool,
+    pub lock: NoteLock,
+}
+
+/// A saved version of a note's content. Every overwrite stores the
+/// version it replaces; the note row itself is the latest revision.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NoteRevision {
+    pub note_id: String,
+    /// 0 for the first version
+    pub revision: usize,
+    #[serde(rename = "content")]
+    pub raw_input: String,
+    pub structured_note: Option<String>,
+    pub content_hash: String,
+    pub status: NoteStatus,
+    /// When this version was written (the note's updated_at at the time)
+    pub saved_at: i64,
+    /// When it was replaced; None for the current version
+    pub superseded_at: Option<i64>,
+    /// What replaced it: "edit", "structure" or "amendment"
+    pub superseded_by: Option<String>,
+    /// Who made that change; None for the current version and for
+    /// amendments made before editors were recorded
+    pub editor: Option<String>,
+}
+
+// ============================================
+// Trash
+// ============================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrashItemKind {
+    Note,
+    Client,
+}
+
+/// A soft-deleted note or client, restorable until `purge_after`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrashedItem {
+    pub kind: TrashItemKind,
+    pub id: String,
+    pub client_id: String,
+    /// Client display name, or the note's session date and type
+    pub label: String,
+    pub deleted_at: i64,
+    pub purge_after: i64,
+}
+
 // ============================================
 // Attestation
 // ============================================
@@ -203,6 +266,18 @@ pub enum AttestationResponse {
     DocumentedElsewhere,
 }
 
+impl AttestationResponse {
+    pub fn label(&self) -> &'static str {
+        match self {
+            AttestationResponse::AddressedInNote => "Addressed in note",
+            AttestationResponse::NotClinicallyRelevant => "Not clinically relevant",
+            AttestationResponse::WillAddressNextSession => "Will address next session",
+            AttestationResponse::ConsultedSupervisor => "Consulted supervisor",
+            AttestationResponse::DocumentedElsewhere => "Documented elsewhere",
+        }
+    }
+}
+
 // ============================================
 // Ethics Detection (v3: stored without evidence)
 // ============================================
@@ -215,6 +290,13 @@ pub struct StoredDetection {
     pub severity: DetectionSeverity,
     pub match_start: usize,
     pub match_end: usize,
+    /// SHA-256 of the analyzed note text; offsets are only meaningful
+    /// against this version
+    #[serde(default)]
+    pub content_hash: Option<String>,
+    /// SHA-256 of the matched (normalized) span, for re-anchoring after edits
+    #[serde(default)]
+    pub span_hash: Option<String>,
 }
 
 impl StoredDetection {
@@ -258,6 +340,52 @@ pub struct EthicsDetection {
     pub suggestion: String,
     pub policy_ref: Option<String>,
     pub requires_attestation: bool,
+    /// Whose words/actions the flagged text describes (None for absence rules)
+    #[serde(default)]
+    pub attribution: Option<SubjectAttribution>,
+    /// Whether the flagged text came from the session or a collateral
+    /// contact (None for absence rules)
+    #[serde(default)]
+    pub source: Option<InformationSource>,
+}
+
+/// Who a detection is about
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SubjectRole {
+    Client,
+    ThirdParty,
+    /// Clinician's own statement or action ("writer discussed...")
+    Clinician,
+    Unknown,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubjectAttribution {
+    pub role: SubjectRole,
+    /// Relationship named for a third party ("ex", "mother"); never a name
+    pub relation: Option<String>,
+    /// 0.0 - 1.0
+    pub confidence: f32,
+    /// Which cue decided the role, for review
+    pub cue: String,
+}
+
+/// Where a passage of a note came from
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SourceKind {
+    /// Said or observed in the session itself
+    Session,
+    /// Reported by someone other than the client (parent call, school report)
+    Collateral,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct InformationSource {
+    pub kind: SourceKind,
+    /// Who or what, as written in the collateral heading ("mother, by phone")
+    pub description: Option<String>,
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
@@ -277,6 +405,21 @@ pub struct EthicsAnalysis {
     pub coach_count: usize,
 }
 
+/// Stored detections for a note and whether they still describe its text
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NoteDetectionState {
+    pub note_id: String,
+    pub detections: Vec<StoredDetection>,
+    /// Hash of the note text the detections are anchored to
+    pub content_hash: String,
+    /// An edit removed or rewrote flagged text; re-analyze before signing
+    pub reanalyze_required: bool,
+    /// Detection IDs dropped by edits since the last analysis
+    pub invalidated: Vec<String>,
+    pub analyzed_at: i64,
+    pub updated_at: i64,
+}
+
 // ============================================
 // Audit Log (PHI-impossible)
 // ============================================
@@ -317,6 +460,39 @@ pub enum AuditEventType {
     VaultUnlocked,
     VaultLocked,
     PassphraseChanged,
+    TrustedRecipientAdded,
+    TrustedRecipientRevoked,
+    ConsultationDraftSealed,
+    ChartAccessed,
+    ExportPresenceChecked,
+    ClientHashLookup,
+    AuditSliceExported,
+    AuditSliceImported,
+    EmergencyAccessActivated,
+    EmergencyAccessRead,
+    EmergencyAccessReviewed,
+    LegalHoldPlaced,
+    LegalHoldReleased,
+    RecordsDestroyed,
+    ClientLetterExported,
+    EhrDeliveryQueued,
+    EhrDeliverySent,
+    EhrDeliveryFailed,
+    EhrDeliveryAcknowledged,
+    NoteAuthorAdded,
+    NoteSectionAttributed,
+    NoteContributionSigned,
+    BackupCreated,
+    BackupRestored,
+    NotesRetrieved,
+    PrepSheetGenerated,
+    NoteTrashed,
+    NoteRestored,
+    ClientTrashed,
+    ClientRestored,
+    ClientPurged,
+    PolicySimulated,
+    GroupSupervisionRecorded,
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
@@ -337,6 +513,127 @@ pub enum AuditOutcome {
     Blocked,
 }
 
+/// Client field a support lookup hash was computed over
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClientIdentifierKind {
+    ClientId,
+    DisplayName,
+    DateOfBirth,
+    Phone,
+    Email,
+}
+
+impl ClientIdentifierKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ClientIdentifierKind::ClientId => "client_id",
+            ClientIdentifierKind::DisplayName => "display_name",
+            ClientIdentifierKind::DateOfBirth => "date_of_birth",
+            ClientIdentifierKind::Phone => "phone",
+            ClientIdentifierKind::Email => "email",
+        }
+    }
+    
+    /// The client's value for this field, if set
+    pub fn value_of<'a>(&self, client: &'a Client) -> Option<&'a str> {
+        match self {
+            ClientIdentifierKind::ClientId => Some(&client.id),
+            ClientIdentifierKind::DisplayName => Some(&client.display_name),
+            ClientIdentifierKind::DateOfBirth => client.date_of_birth.as_deref(),
+            ClientIdentifierKind::Phone => client.phone.as_deref(),
+            ClientIdentifierKind::Email => client.email.as_deref(),
+        }
+    }
+}
+
+/// Answer to a hashed-identifier lookup: existence and count only
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClientHashLookupResult {
+    pub kind: ClientIdentifierKind,
+    pub exists: bool,
+    pub match_count: usize,
+}
+
+/// Stated reason for opening a restricted (closed/archived) chart
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AccessReason {
+    ContinuityOfCare,
+    RecordsRequest,
+    QualityReview,
+    Supervision,
+    LegalOrCompliance,
+    Other,
+}
+
+impl AccessReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AccessReason::ContinuityOfCare => "continuity_of_care",
+            AccessReason::RecordsRequest => "records_request",
+            AccessReason::QualityReview => "quality_review",
+            AccessReason::Supervision => "supervision",
+            AccessReason::LegalOrCompliance => "legal_or_compliance",
+            AccessReason::Other => "other",
+        }
+    }
+}
+
+/// A recorded reason-for-access; covers reads of the chart until expires_at
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChartAccessGrant {
+    pub id: String,
+    pub client_id: String,
+    pub audit_entry_id: String,
+    pub reason: AccessReason,
+    /// Free-text detail, required for `Other`; kept in the vault, hash-bound to the audit entry
+    pub justification: Option<String>,
+    pub created_at: i64,
+    pub expires_at: i64,
+}
+
+/// A break-glass session: a covering clinician's time-limited read access
+/// to one client's recent notes, pending review after the fact
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmergencyAccessSession {
+    pub id: String,
+    pub client_id: String,
+    pub covering_clinician: String,
+    /// Kept in the vault, hash-bound to the activation audit entry
+    pub justification: String,
+    pub audit_entry_id: String,
+    pub activated_at: i64,
+    pub expires_at: i64,
+    /// Set when ended before expiry
+    pub ended_at: Option<i64>,
+    /// Notes read under this session
+    pub access_count: u32,
+    /// "pending", "appropriate", "inappropriate"
+    pub review_status: String,
+    pub reviewed_by: Option<String>,
+    pub reviewed_at: Option<i64>,
+    pub review_note: Option<String>,
+}
+
+/// Follow-up check on whether an exported file is still at its destination
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportPresenceCheck {
+    pub id: String,
+    pub resource_id: String,
+    /// The ExportCreated entry this check follows up on
+    pub export_audit_entry_id: String,
+    pub path_class: String,
+    /// Same salted hash as the export entry, so the two correlate in the log
+    pub path_hash: String,
+    pub exported_at: i64,
+    pub due_at: i64,
+    /// "scheduled", "present", "absent"
+    pub status: String,
+    pub checked_at: Option<i64>,
+    pub check_audit_entry_id: Option<String>,
+}
+
 // ============================================
 // AI / Ollama
 // ============================================
@@ -372,6 +669,30 @@ pub struct SearchResult {
     pub score: f32,
 }
 
+/// Entity kinds returned by the global search bar
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchEntityType {
+    Client,
+    Note,
+    Document,
+    ConsultationDraft,
+}
+
+/// One ranked hit from `search_everything`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GlobalSearchResult {
+    pub entity_type: SearchEntityType,
+    pub entity_id: String,
+    pub client_id: Option<String>,
+    pub title: String,
+    pub excerpt: String,
+    pub score: f32,                // 0.0 - 1.0, fraction of query terms matched (+ title boost)
+    /// Document matched only in OCR text below the confidence threshold
+    #[serde(default)]
+    pub low_confidence: bool,
+}
+
 // ============================================
 // Export
 // ============================================
@@ -580,6 +901,39 @@ pub struct SupervisorReview {
     pub created_at: i64,
 }
 
+/// Trainee's reflective-practice entry on a completed review
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewReflection {
+    pub id: String,
+    pub review_id: String,
+    pub note_id: String,
+    pub trainee_id: String,
+    pub what_i_learned: String,
+    pub what_i_will_change: String,
+    pub created_at: i64,
+    pub updated_at: i64,
+    /// Set when the supervisor has read it; the entry is locked from then on
+    pub acknowledged_at: Option<i64>,
+    pub acknowledged_by: Option<String>,
+}
+
+/// Competency report for a trainee: review outcomes plus reflections
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TraineeCompetencyReport {
+    pub trainee: Trainee,
+    pub generated_at: String,
+    pub reviews_completed: i32,
+    pub approved: i32,
+    pub needs_revision: i32,
+    pub rejected: i32,
+    pub avg_clinical_accuracy: Option<f32>,
+    pub avg_documentation_quality: Option<f32>,
+    /// Share of completed reviews with a reflection (0.0 - 1.0)
+    pub reflection_rate: Option<f32>,
+    pub reflections: Vec<ReviewReflection>,
+    pub supervision_hours: TraineeSupervisionHours,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ReviewComment {
     pub id: String,
@@ -616,8 +970,53 @@ pub struct SupervisorDashboard {
 pub struct TraineeSummary {
     pub trainee: Trainee,
     pub pending_notes: i32,
+    /// Rolling mean of both review scores over the most recent reviews
     pub avg_quality_score: Option<f32>,
     pub last_submission: Option<String>,
+    pub quality_trend: QualityTrend,
+}
+
+/// Scores from one completed review (input to trend analytics)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewScore {
+    pub trainee_id: String,
+    pub completed_at: i64,
+    pub clinical_accuracy: Option<i32>,
+    pub documentation_quality: Option<i32>,
+}
+
+/// Review scores for one calendar month, with change from the prior
+/// month that had reviews
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MonthlyQuality {
+    pub month: String,  // YYYY-MM
+    pub reviews: i32,
+    pub avg_clinical_accuracy: Option<f32>,
+    pub avg_documentation_quality: Option<f32>,
+    pub clinical_accuracy_delta: Option<f32>,
+    pub documentation_quality_delta: Option<f32>,
+}
+
+/// Quality trend for one trainee across completed reviews
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QualityTrend {
+    pub trainee_id: String,
+    pub reviews_completed: i32,
+    /// Averages over the last `window` scored reviews
+    pub window: usize,
+    pub rolling_clinical_accuracy: Option<f32>,
+    pub rolling_documentation_quality: Option<f32>,
+    /// Oldest month first
+    pub monthly: Vec<MonthlyQuality>,
+}
+
+/// Quality trends for all of a supervisor's trainees, for accreditation
+/// reporting
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SupervisionQualityReport {
+    pub supervisor_id: String,
+    pub generated_at: String,
+    pub trainees: Vec<TraineeSummary>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -636,3 +1035,67 @@ pub struct SupervisorActivity {
     pub description: String,
     pub timestamp: i64,
 }
+
+/// Group supervision session to record. Cases are given as client IDs and
+/// stored only as the supervisor's client pseudonyms.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewGroupSupervisionSession {
+    pub supervisor_id: String,
+    pub session_date: String,  // YYYY-MM-DD
+    pub duration_minutes: i32,
+    /// Trainee IDs present
+    pub present: Vec<String>,
+    /// Trainee IDs expected but absent
+    #[serde(default)]
+    pub absent: Vec<String>,
+    #[serde(default)]
+    pub topics: Vec<String>,
+    #[serde(default)]
+    pub case_client_ids: Vec<String>,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupSupervisionAttendee {
+    pub trainee_id: String,
+    pub trainee_name: String,
+    pub present: bool,
+}
+
+/// Group supervision session: one supervisor, several trainees
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupSupervisionSession {
+    pub id: String,
+    pub supervisor_id: String,
+    pub session_date: String,  // YYYY-MM-DD
+    pub duration_minutes: i32,
+    pub attendees: Vec<GroupSupervisionAttendee>,
+    pub topics: Vec<String>,
+    /// Client pseudonyms (see `supervision::client_pseudonym`)
+    pub cases_discussed: Vec<String>,
+    pub notes: Option<String>,
+    pub created_at: i64,
+}
+
+/// Group supervision credited to one trainee
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TraineeSupervisionHours {
+    pub trainee_id: String,
+    pub trainee_name: String,
+    pub group_sessions_attended: i32,
+    pub group_sessions_missed: i32,
+    pub group_hours: f32,
+}
+
+/// Group supervision sessions and credited hours over a period, for
+/// accreditation reporting
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupSupervisionLog {
+    pub supervisor_id: String,
+    pub generated_at: String,
+    pub from: Option<String>,
+    pub to: Option<String>,
+    /// Oldest first
+    pub sessions: Vec<GroupSupervisionSession>,
+    pub hours: Vec<TraineeSupervisionHours>,
+}
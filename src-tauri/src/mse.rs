@@ -0,0 +1,401 @@
+// Mental Status Exam Module
+//
+// Structured MSE capture attached to notes. Free-text MSEs can't be
+// trended, so each domain is an enumerated value with an ordinal
+// severity (0 = within normal limits, 3 = most impaired) that the
+// treatment-progress view can chart session over session.
+
+use serde::{Deserialize, Serialize};
+
+// ============================================
+// Domain Values
+// ============================================
+
+/// Implemented by every enumerated MSE domain so trends can be charted
+pub trait Severity {
+    /// 0 = within normal limits, 3 = most impaired
+    fn severity(&self) -> u8;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Appearance {
+    WellGroomed,
+    Casual,
+    Disheveled,
+    Bizarre,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Psychomotor {
+    Normal,
+    Restless,
+    Agitated,
+    Retarded,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Speech {
+    Normal,
+    Soft,
+    Slowed,
+    Pressured,
+    Mute,
+}
+
+/// Client's self-reported mood
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Mood {
+    Euthymic,
+    Anxious,
+    Irritable,
+    Depressed,
+    Euphoric,
+}
+
+/// Clinician-observed affect
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Affect {
+    Full,
+    Constricted,
+    Blunted,
+    Labile,
+    Flat,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ThoughtProcess {
+    LinearGoalDirected,
+    Circumstantial,
+    Tangential,
+    FlightOfIdeas,
+    LooseAssociations,
+}
+
+/// Suicidal / homicidal ideation level
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Ideation {
+    Denied,
+    Passive,
+    ActiveNoPlan,
+    ActiveWithPlan,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Perception {
+    NoDisturbance,
+    Illusions,
+    Hallucinations,
+    CommandHallucinations,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Rating {
+    Good,
+    Fair,
+    Limited,
+    Poor,
+}
+
+impl Severity for Appearance {
+    fn severity(&self) -> u8 {
+        match self {
+            Appearance::WellGroomed => 0,
+            Appearance::Casual => 0,
+            Appearance::Disheveled => 2,
+            Appearance::Bizarre => 3,
+        }
+    }
+}
+
+impl Severity for Psychomotor {
+    fn severity(&self) -> u8 {
+        match self {
+            Psychomotor::Normal => 0,
+            Psychomotor::Restless => 1,
+            Psychomotor::Agitated => 2,
+            Psychomotor::Retarded => 2,
+        }
+    }
+}
+
+impl Severity for Speech {
+    fn severity(&self) -> u8 {
+        match self {
+            Speech::Normal => 0,
+            Speech::Soft => 1,
+            Speech::Slowed => 1,
+            Speech::Pressured => 2,
+            Speech::Mute => 3,
+        }
+    }
+}
+
+impl Severity for Mood {
+    fn severity(&self) -> u8 {
+        match self {
+            Mood::Euthymic => 0,
+            Mood::Anxious => 1,
+            Mood::Irritable => 1,
+            Mood::Depressed => 2,
+            Mood::Euphoric => 2,
+        }
+    }
+}
+
+impl Severity for Affect {
+    fn severity(&self) -> u8 {
+        match self {
+            Affect::Full => 0,
+            Affect::Constricted => 1,
+            Affect::Blunted => 2,
+            Affect::Labile => 2,
+            Affect::Flat => 3,
+        }
+    }
+}
+
+impl Severity for ThoughtProcess {
+    fn severity(&self) -> u8 {
+        match self {
+            ThoughtProcess::LinearGoalDirected => 0,
+            ThoughtProcess::Circumstantial => 1,
+            ThoughtProcess::Tangential => 2,
+            ThoughtProcess::FlightOfIdeas => 3,
+            ThoughtProcess::LooseAssociations => 3,
+        }
+    }
+}
+
+impl Severity for Ideation {
+    fn severity(&self) -> u8 {
+        match self {
+            Ideation::Denied => 0,
+            Ideation::Passive => 1,
+            Ideation::ActiveNoPlan => 2,
+            Ideation::ActiveWithPlan => 3,
+        }
+    }
+}
+
+impl Severity for Perception {
+    fn severity(&self) -> u8 {
+        match self {
+            Perception::NoDisturbance => 0,
+            Perception::Illusions => 1,
+            Perception::Hallucinations => 2,
+            Perception::CommandHallucinations => 3,
+        }
+    }
+}
+
+impl Severity for Rating {
+    fn severity(&self) -> u8 {
+        match self {
+            Rating::Good => 0,
+            Rating::Fair => 1,
+            Rating::Limited => 2,
+            Rating::Poor => 3,
+        }
+    }
+}
+
+// ============================================
+// Exam
+// ============================================
+
+/// Structured mental status exam for one session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MentalStatusExam {
+    pub appearance: Appearance,
+    pub psychomotor: Psychomotor,
+    pub speech: Speech,
+    pub mood: Mood,
+    pub affect: Affect,
+    pub thought_process: ThoughtProcess,
+    pub suicidal_ideation: Ideation,
+    pub homicidal_ideation: Ideation,
+    pub perception: Perception,
+    /// Oriented to person, place, time, situation (0-4)
+    pub orientation: u8,
+    pub insight: Rating,
+    pub judgment: Rating,
+    /// Optional clinician comment (kept free-text, not trended)
+    #[serde(default)]
+    pub comments: Option<String>,
+}
+
+impl MentalStatusExam {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.orientation > 4 {
+            return Err("Orientation must be between 0 and 4".to_string());
+        }
+        Ok(())
+    }
+
+    /// Sum of domain severities; higher = more impaired presentation
+    pub fn total_severity(&self) -> u32 {
+        let domains: [&dyn Severity; 11] = [
+            &self.appearance,
+            &self.psychomotor,
+            &self.speech,
+            &self.mood,
+            &self.affect,
+            &self.thought_process,
+            &self.suicidal_ideation,
+            &self.homicidal_ideation,
+            &self.perception,
+            &self.insight,
+            &self.judgment,
+        ];
+        let disorientation = 4u32.saturating_sub(self.orientation as u32);
+        domains.iter().map(|d| d.severity() as u32).sum::<u32>() + disorientation
+    }
+}
+
+/// MSE as stored against a note
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NoteMse {
+    pub note_id: String,
+    pub client_id: String,
+    pub session_date: String,
+    pub exam: MentalStatusExam,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+// ============================================
+// Trends
+// ============================================
+
+/// One charted session in the MSE trend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MseTrendPoint {
+    pub note_id: String,
+    pub session_date: String,
+    pub mood: u8,
+    pub affect: u8,
+    pub thought_process: u8,
+    pub suicidal_ideation: u8,
+    pub insight: u8,
+    pub judgment: u8,
+    pub total_severity: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MseTrend {
+    pub client_id: String,
+    pub points: Vec<MseTrendPoint>,
+    /// "improving", "stable", "worsening", "insufficient_data"
+    pub direction: String,
+}
+
+/// Build a trend from exams ordered oldest first
+pub fn build_trend(client_id: &str, exams: &[NoteMse]) -> MseTrend {
+    let points: Vec<MseTrendPoint> = exams
+        .iter()
+        .map(|m| MseTrendPoint {
+            note_id: m.note_id.clone(),
+            session_date: m.session_date.clone(),
+            mood: m.exam.mood.severity(),
+            affect: m.exam.affect.severity(),
+            thought_process: m.exam.thought_process.severity(),
+            suicidal_ideation: m.exam.suicidal_ideation.severity(),
+            insight: m.exam.insight.severity(),
+            judgment: m.exam.judgment.severity(),
+            total_severity: m.exam.total_severity(),
+        })
+        .collect();
+
+    let direction = trend_direction(&points);
+
+    MseTrend {
+        client_id: client_id.to_string(),
+        points,
+        direction,
+    }
+}
+
+/// Compare the mean severity of the earlier and later halves of the series
+fn trend_direction(points: &[MseTrendPoint]) -> String {
+    if points.len() < 3 {
+        return "insufficient_data".to_string();
+    }
+
+    let mid = points.len() / 2;
+    let mean = |slice: &[MseTrendPoint]| {
+        slice.iter().map(|p| p.total_severity as f64).sum::<f64>() / slice.len() as f64
+    };
+    let delta = mean(&points[mid..]) - mean(&points[..mid]);
+
+    if delta <= -1.0 {
+        "improving".to_string()
+    } else if delta >= 1.0 {
+        "worsening".to_string()
+    } else {
+        "stable".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exam(mood: Mood, affect: Affect) -> MentalStatusExam {
+        MentalStatusExam {
+            appearance: Appearance::Casual,
+            psychomotor: Psychomotor::Normal,
+            speech: Speech::Normal,
+            mood,
+            affect,
+            thought_process: ThoughtProcess::LinearGoalDirected,
+            suicidal_ideation: Ideation::Denied,
+            homicidal_ideation: Ideation::Denied,
+            perception: Perception::NoDisturbance,
+            orientation: 4,
+            insight: Rating::Good,
+            judgment: Rating::Good,
+            comments: None,
+        }
+    }
+
+    fn stored(date: &str, exam: MentalStatusExam) -> NoteMse {
+        NoteMse {
+            note_id: format!("note-{}", date),
+            client_id: "client-1".to_string(),
+            session_date: date.to_string(),
+            exam,
+            created_at: 0,
+            updated_at: 0,
+        }
+    }
+
+    #[test]
+    fn test_total_severity() {
+        assert_eq!(exam(Mood::Euthymic, Affect::Full).total_severity(), 0);
+
+        let mut impaired = exam(Mood::Depressed, Affect::Flat);
+        impaired.orientation = 3;
+        assert_eq!(impaired.total_severity(), 2 + 3 + 1);
+    }
+
+    #[test]
+    fn test_trend_direction() {
+        let exams = vec![
+            stored("2024-01-01", exam(Mood::Depressed, Affect::Flat)),
+            stored("2024-01-08", exam(Mood::Depressed, Affect::Blunted)),
+            stored("2024-01-15", exam(Mood::Anxious, Affect::Constricted)),
+            stored("2024-01-22", exam(Mood::Euthymic, Affect::Full)),
+        ];
+        assert_eq!(build_trend("client-1", &exams).direction, "improving");
+        assert_eq!(build_trend("client-1", &exams[..2]).direction, "insufficient_data");
+    }
+}
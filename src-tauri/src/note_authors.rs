@@ -0,0 +1,336 @@
+// Multi-Author Notes
+//
+// Co-therapy sessions and trainee notes with supervisor additions have more
+// than one author. Each author is registered on the note with a role, and
+// sections (by heading) are attributed to whoever wrote them; sections
+// nobody claimed belong to the primary author.
+//
+// An author's contribution hash is the canonical SHA-256 of
+// {author_id, sections: {heading: SHA-256 of the section text}} over the
+// note as it stands. Signing a contribution records that hash and the hash
+// of the whole analyzed text. Each author signs with their own signing
+// credential, so one author can't sign for another. The note itself can't be
+// signed until every author has signed the current text (Vault::sign_note
+// enforces this): an edit, or a change of attribution, after someone signed
+// means they sign again.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+
+// ============================================
+// Types
+// ============================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuthorRole {
+    /// Owns every section nobody else claimed. At most one per note.
+    Primary,
+    CoTherapist,
+    Intern,
+    Supervisor,
+}
+
+impl AuthorRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuthorRole::Primary => "primary",
+            AuthorRole::CoTherapist => "co_therapist",
+            AuthorRole::Intern => "intern",
+            AuthorRole::Supervisor => "supervisor",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "primary" => Some(AuthorRole::Primary),
+            "co_therapist" => Some(AuthorRole::CoTherapist),
+            "intern" => Some(AuthorRole::Intern),
+            "supervisor" => Some(AuthorRole::Supervisor),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NoteAuthor {
+    pub author_id: String,
+    pub author_name: String,
+    pub credentials: Option<String>,
+    pub role: AuthorRole,
+    pub added_at: i64,
+    pub signed_at: Option<i64>,
+    /// SHA-256 of the analyzed text the author signed
+    pub signed_text_sha256: Option<String>,
+    /// Contribution hash at signing
+    pub contribution_sha256: Option<String>,
+}
+
+impl NoteAuthor {
+    /// True if the author's signature covers the text with this hash
+    pub fn has_signed(&self, text_sha256: &str) -> bool {
+        self.signed_text_sha256
+            .as_deref()
+            .is_some_and(|signed| crate::crypto::digests_match(signed, text_sha256))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SectionAttribution {
+    /// Normalized heading (see `section_key`)
+    pub section: String,
+    pub author_id: String,
+    pub attributed_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthorContribution {
+    pub author_id: String,
+    pub sections: Vec<String>,
+    pub contribution_sha256: String,
+}
+
+/// Who wrote what, and whose signature the note is waiting for
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NoteAuthorship {
+    pub note_id: String,
+    pub text_sha256: String,
+    pub authors: Vec<NoteAuthor>,
+    pub sections: Vec<SectionAttribution>,
+    pub contributions: Vec<AuthorContribution>,
+    /// Author ids without a signature on the current text
+    pub awaiting_signature: Vec<String>,
+    /// Every author has signed (trivially true for single-author notes)
+    pub complete: bool,
+}
+
+// ============================================
+// Sections and Contributions
+// ============================================
+
+/// Heading as stored: lowercase, markdown decoration and trailing colon removed
+pub fn section_key(heading: &str) -> String {
+    heading
+        .trim()
+        .trim_start_matches(|c: char| c == '#' || c == '*' || c.is_whitespace())
+        .trim_end_matches(|c: char| c == '*' || c == ':' || c.is_whitespace())
+        .to_lowercase()
+}
+
+/// Heading of a "Label:" line (label of at most three words)
+fn heading_of(line: &str) -> Option<String> {
+    let (label, _) = line.split_once(':')?;
+    let key = section_key(label);
+    (!key.is_empty() && key.len() < 40 && key.split_whitespace().count() <= 3).then_some(key)
+}
+
+/// Note text split into (section key, body). Text before the first heading
+/// is the "" section; a repeated heading continues its earlier section.
+pub fn split_sections(text: &str) -> BTreeMap<String, String> {
+    let mut sections: BTreeMap<String, String> = BTreeMap::new();
+    let mut current = String::new();
+    for line in text.lines() {
+        let body = match heading_of(line) {
+            Some(key) => {
+                current = key;
+                line.split_once(':').map(|(_, rest)| rest).unwrap_or("")
+            }
+            None => line,
+        };
+        let body = body.trim();
+        if body.is_empty() {
+            continue;
+        }
+        let section = sections.entry(current.clone()).or_default();
+        if !section.is_empty() {
+            section.push('\n');
+        }
+        section.push_str(body);
+    }
+    sections
+}
+
+/// Each author's sections and contribution hash over `text`. A claimed
+/// section missing from the text hashes as null.
+pub fn contributions(
+    text: &str,
+    authors: &[NoteAuthor],
+    attributions: &[SectionAttribution],
+) -> Vec<AuthorContribution> {
+    let present = split_sections(text);
+    let primary = authors.iter().find(|a| a.role == AuthorRole::Primary).map(|a| a.author_id.as_str());
+
+    authors.iter().map(|author| {
+        let mut owned: Vec<String> = attributions.iter()
+            .filter(|s| s.author_id == author.author_id)
+            .map(|s| s.section.clone())
+            .collect();
+        if primary == Some(author.author_id.as_str()) {
+            owned.extend(present.keys().filter(|key| !attributions.iter().any(|s| &s.section == *key)).cloned());
+        }
+        owned.sort();
+        owned.dedup();
+
+        let hashes: Map<String, Value> = owned.iter().map(|section| {
+            let hash = present.get(section).map(|body| Value::String(crate::crypto::hash_sha256(body.as_bytes())));
+            (section.clone(), hash.unwrap_or(Value::Null))
+        }).collect();
+        AuthorContribution {
+            author_id: author.author_id.clone(),
+            contribution_sha256: evidify_canonicalization::canonical_sha256(&json!({
+                "author_id": author.author_id,
+                "sections": hashes,
+            })),
+            sections: owned,
+        }
+    }).collect()
+}
+
+pub fn authorship(
+    note_id: &str,
+    text: &str,
+    authors: Vec<NoteAuthor>,
+    sections: Vec<SectionAttribution>,
+) -> NoteAuthorship {
+    let text_sha256 = crate::crypto::hash_sha256(text.as_bytes());
+    let awaiting_signature: Vec<String> = authors.iter()
+        .filter(|a| !a.has_signed(&text_sha256))
+        .map(|a| a.author_id.clone())
+        .collect();
+    NoteAuthorship {
+        note_id: note_id.to_string(),
+        contributions: contributions(text, &authors, &sections),
+        complete: awaiting_signature.is_empty(),
+        awaiting_signature,
+        text_sha256,
+        authors,
+        sections,
+    }
+}
+
+impl NoteAuthorship {
+    /// Names of the authors still to sign, for the sign gate's message
+    pub fn awaiting_names(&self) -> Vec<&str> {
+        self.authors.iter()
+            .filter(|a| self.awaiting_signature.contains(&a.author_id))
+            .map(|a| a.author_name.as_str())
+            .collect()
+    }
+}
+
+// ============================================
+// Tauri Commands
+// ============================================
+
+use tauri::State;
+use crate::commands::AppState;
+
+#[tauri::command]
+pub fn get_note_authorship(state: State<AppState>, note_id: String) -> Result<NoteAuthorship, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.get_note_authorship(&note_id).map_err(|e| format!("{}", e))
+}
+
+/// Register a co-author on an unsigned note
+#[tauri::command]
+pub fn add_note_author(
+    state: State<AppState>,
+    note_id: String,
+    author_id: String,
+    author_name: String,
+    credentials: Option<String>,
+    role: String,
+) -> Result<NoteAuthorship, String> {
+    let role = AuthorRole::from_str(&role).ok_or_else(|| format!("Unknown author role: {}", role))?;
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.add_note_author(&note_id, &author_id, &author_name, credentials.as_deref(), role)
+        .map_err(|e| format!("{}", e))
+}
+
+/// Attribute a section (by heading) to one of the note's authors
+#[tauri::command]
+pub fn attribute_note_section(
+    state: State<AppState>,
+    note_id: String,
+    section: String,
+    author_id: String,
+) -> Result<NoteAuthorship, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.attribute_note_section(&note_id, &section, &author_id).map_err(|e| format!("{}", e))
+}
+
+/// Set (or, given the current one, replace) an author's signing credential
+#[tauri::command]
+pub fn set_author_signing_credential(
+    state: State<AppState>,
+    author_id: String,
+    credential: String,
+    current: Option<String>,
+) -> Result<(), String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.set_author_signing_credential(&author_id, &credential, current.as_deref())
+        .map_err(|e| format!("{}", e))
+}
+
+/// Sign one author's contribution to the note as it stands; the author
+/// enters their own signing credential
+#[tauri::command]
+pub fn sign_note_contribution(
+    state: State<AppState>,
+    note_id: String,
+    author_id: String,
+    credential: String,
+) -> Result<NoteAuthorship, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.sign_note_contribution(&note_id, &author_id, &credential).map_err(|e| format!("{}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn author(id: &str, role: AuthorRole) -> NoteAuthor {
+        NoteAuthor {
+            author_id: id.to_string(),
+            author_name: id.to_uppercase(),
+            credentials: None,
+            role,
+            added_at: 0,
+            signed_at: None,
+            signed_text_sha256: None,
+            contribution_sha256: None,
+        }
+    }
+
+    #[test]
+    fn test_contributions_and_signatures() {
+        let text = "Group session, two facilitators.\n## Subjective: reports better sleep\nStill anxious.\nAssessment:\nImproving.\nPlan: continue weekly";
+        let sections = split_sections(text);
+        assert_eq!(sections.keys().collect::<Vec<_>>(), ["", "assessment", "plan", "subjective"]);
+        assert_eq!(sections["subjective"], "reports better sleep\nStill anxious.");
+
+        let authors = vec![author("lead", AuthorRole::Primary), author("intern", AuthorRole::Intern)];
+        let claimed = vec![SectionAttribution { section: "assessment".into(), author_id: "intern".into(), attributed_at: 0 }];
+        let before = contributions(text, &authors, &claimed);
+        assert_eq!(before[0].sections, ["", "plan", "subjective"]);
+        assert_eq!(before[1].sections, ["assessment"]);
+
+        // Editing the intern's section changes only the intern's hash
+        let edited = text.replace("Improving.", "Improving steadily.");
+        let after = contributions(&edited, &authors, &claimed);
+        assert_eq!(after[0].contribution_sha256, before[0].contribution_sha256);
+        assert_ne!(after[1].contribution_sha256, before[1].contribution_sha256);
+
+        // Both must sign the current text
+        let mut signed = authors.clone();
+        signed[0].signed_text_sha256 = Some(crate::crypto::hash_sha256(text.as_bytes()));
+        let state = authorship("n1", text, signed.clone(), claimed.clone());
+        assert!(!state.complete);
+        assert_eq!(state.awaiting_names(), ["INTERN"]);
+        signed[1].signed_text_sha256 = Some(crate::crypto::hash_sha256(text.as_bytes()));
+        assert!(authorship("n1", text, signed.clone(), claimed.clone()).complete);
+        assert_eq!(authorship("n1", &edited, signed, claimed).awaiting_signature.len(), 2);
+        assert!(authorship("n1", text, Vec::new(), Vec::new()).complete);
+    }
+}
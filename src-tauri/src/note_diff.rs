@@ -0,0 +1,484 @@
+// Note Change Summaries
+//
+// A concise "what changed" between two revisions of a note, for the
+// supervisor review screen and amendment audit entries. The deterministic
+// diff is always computed: sections are matched by key (see
+// `note_authors::split_sections`) and the lines of a changed section are
+// diffed by longest common subsequence. When a local model is named it is
+// asked for a short clinical reading of that diff; if it is unavailable or
+// returns nothing, the deterministic summary stands.
+//
+// Revisions come from the note_revisions table, which holds every version
+// an edit, restructure or amendment replaced, and who made the change.
+// Notes amended before that table existed are backfilled from the
+// amendment blocks `amend_note` appends (revision 0 is the text as signed,
+// revision n the text after the n-th amendment), so amendment numbering is
+// the same either way.
+//
+// Diffs are over the note content (raw_input), which is what the content
+// hash covers; a restructure shows up as `structured_changed` on its step.
+// Amendment blocks are diffed apart from the sections, as one "amendment"
+// change holding their bodies without the marker and reason lines.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+use crate::models::NoteRevision;
+use crate::note_authors::split_sections;
+
+/// Start of each block `amend_note` appends to a signed note
+pub const AMENDMENT_MARKER: &str = "\n\n--- AMENDMENT (";
+
+/// Changed lines quoted to the model per section
+const PROMPT_LINES_PER_SECTION: usize = 12;
+
+// ============================================
+// Types
+// ============================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SectionChangeKind {
+    Added,
+    Removed,
+    Modified,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SectionChange {
+    /// Section key; "" is text before the first heading
+    pub section: String,
+    pub kind: SectionChangeKind,
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SummarySource {
+    LocalModel,
+    Deterministic,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NoteChangeSummary {
+    pub note_id: String,
+    pub from_rev: usize,
+    pub to_rev: usize,
+    pub from_hash: String,
+    pub to_hash: String,
+    /// By section key, removed sections last
+    pub sections: Vec<SectionChange>,
+    pub lines_added: usize,
+    pub lines_removed: usize,
+    pub summary: String,
+    pub source: SummarySource,
+}
+
+/// One step in a note's history: what changed from one revision to the next
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RevisionStep {
+    pub from_rev: usize,
+    pub to_rev: usize,
+    /// "edit", "structure" or "amendment"
+    pub change: Option<String>,
+    pub changed_at: Option<i64>,
+    pub changed_by: Option<String>,
+    pub sections: Vec<SectionChange>,
+    pub structured_changed: bool,
+    pub summary: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NoteHistory {
+    pub note_id: String,
+    /// Oldest first; the last is the current version
+    pub revisions: Vec<NoteRevision>,
+    /// revisions[i] -> revisions[i + 1]
+    pub steps: Vec<RevisionStep>,
+}
+
+// ============================================
+// Revisions
+// ============================================
+
+/// History with a diff for every consecutive pair of revisions
+pub fn history(note_id: &str, revisions: Vec<NoteRevision>) -> NoteHistory {
+    let steps = revisions.windows(2).map(|pair| {
+        let (from, to) = (&pair[0], &pair[1]);
+        let sections = diff_sections(&from.raw_input, &to.raw_input);
+        RevisionStep {
+            from_rev: from.revision,
+            to_rev: to.revision,
+            change: from.superseded_by.clone(),
+            changed_at: from.superseded_at,
+            changed_by: from.editor.clone(),
+            summary: deterministic_summary(&sections),
+            structured_changed: from.structured_note != to.structured_note,
+            sections,
+        }
+    }).collect();
+    NoteHistory { note_id: note_id.to_string(), revisions, steps }
+}
+
+/// A note's revisions, oldest first, from the amendment blocks in its text
+pub fn amendment_revisions(raw_input: &str) -> Vec<&str> {
+    let mut revisions: Vec<&str> = raw_input
+        .match_indices(AMENDMENT_MARKER)
+        .map(|(at, _)| &raw_input[..at])
+        .collect();
+    revisions.push(raw_input);
+    revisions
+}
+
+// ============================================
+// Diff
+// ============================================
+
+/// Lines only in `old` and lines only in `new`, by longest common subsequence
+pub fn line_diff(old: &[&str], new: &[&str]) -> (Vec<String>, Vec<String>) {
+    let (n, m) = (old.len(), new.len());
+    let mut lcs = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let (mut removed, mut added) = (Vec::new(), Vec::new());
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if old[i] == new[j] {
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            removed.push(old[i].to_string());
+            i += 1;
+        } else {
+            added.push(new[j].to_string());
+            j += 1;
+        }
+    }
+    removed.extend(old[i..].iter().map(|l| l.to_string()));
+    added.extend(new[j..].iter().map(|l| l.to_string()));
+    (removed, added)
+}
+
+/// Section key under which appended amendment blocks are reported
+pub const AMENDMENT_SECTION: &str = "amendment";
+
+/// One block appended by `amend_note`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AmendmentBlock<'a> {
+    /// As written in the marker line ("%Y-%m-%d %H:%M:%S UTC")
+    pub amended_at: Option<&'a str>,
+    pub reason: Option<&'a str>,
+    /// The amendment text; the whole block if it cannot be parsed
+    pub body: &'a str,
+}
+
+/// The text before any amendment blocks, and each block in order
+pub fn amendment_blocks(text: &str) -> (&str, Vec<AmendmentBlock<'_>>) {
+    let starts: Vec<usize> = text.match_indices(AMENDMENT_MARKER).map(|(at, _)| at).collect();
+    let Some(&first) = starts.first() else {
+        return (text, Vec::new());
+    };
+    let blocks = starts.iter().enumerate().map(|(i, &start)| {
+        let block = &text[start..starts.get(i + 1).copied().unwrap_or(text.len())];
+        AmendmentBlock {
+            amended_at: block[AMENDMENT_MARKER.len()..].split_once(") ---").map(|(at, _)| at),
+            reason: block.find("\nReason: ")
+                .and_then(|at| block[at + "\nReason: ".len()..].lines().next()),
+            body: block.find("\nAmended: ")
+                .and_then(|at| block[at..].find("\n\n").map(|end| &block[at + end + 2..]))
+                .unwrap_or(block),
+        }
+    }).collect();
+    (&text[..first], blocks)
+}
+
+/// The text before any amendment blocks, and the body of each block with
+/// its marker, reason and timestamp lines removed
+fn split_amendments(text: &str) -> (&str, Vec<&str>) {
+    let (before, blocks) = amendment_blocks(text);
+    (before, blocks.iter().map(|block| block.body).collect())
+}
+
+/// Per-section changes from `from` to `to`. Amendment blocks are reported
+/// together as one "amendment" change holding their bodies.
+pub fn diff_sections(from: &str, to: &str) -> Vec<SectionChange> {
+    let (from, from_amendments) = split_amendments(from);
+    let (to, to_amendments) = split_amendments(to);
+    let mut changes = diff_section_bodies(from, to);
+    
+    let shared = from_amendments.iter().zip(&to_amendments).take_while(|(a, b)| a == b).count();
+    let old_lines: Vec<&str> = from_amendments[shared..].iter().flat_map(|b| b.lines()).collect();
+    let new_lines: Vec<&str> = to_amendments[shared..].iter().flat_map(|b| b.lines()).collect();
+    let kind = match (old_lines.is_empty(), new_lines.is_empty()) {
+        (true, true) => return changes,
+        (true, false) => SectionChangeKind::Added,
+        (false, true) => SectionChangeKind::Removed,
+        (false, false) => SectionChangeKind::Modified,
+    };
+    let (removed, added) = line_diff(&old_lines, &new_lines);
+    changes.push(SectionChange { section: AMENDMENT_SECTION.to_string(), kind, added, removed });
+    changes
+}
+
+fn diff_section_bodies(from: &str, to: &str) -> Vec<SectionChange> {
+    let before = split_sections(from);
+    let after = split_sections(to);
+    let mut changes = Vec::new();
+
+    for (section, body) in &after {
+        match before.get(section) {
+            None => changes.push(SectionChange {
+                section: section.clone(),
+                kind: SectionChangeKind::Added,
+                added: body.lines().map(str::to_string).collect(),
+                removed: vec![],
+            }),
+            Some(old) if old != body => {
+                let old_lines: Vec<&str> = old.lines().collect();
+                let new_lines: Vec<&str> = body.lines().collect();
+                let (removed, added) = line_diff(&old_lines, &new_lines);
+                changes.push(SectionChange {
+                    section: section.clone(),
+                    kind: SectionChangeKind::Modified,
+                    added,
+                    removed,
+                });
+            }
+            Some(_) => {}
+        }
+    }
+    let kept: BTreeSet<&String> = after.keys().collect();
+    for (section, body) in before.iter().filter(|(key, _)| !kept.contains(key)) {
+        changes.push(SectionChange {
+            section: section.clone(),
+            kind: SectionChangeKind::Removed,
+            added: vec![],
+            removed: body.lines().map(str::to_string).collect(),
+        });
+    }
+    changes
+}
+
+fn section_label(section: &str) -> &str {
+    if section.is_empty() { "opening text" } else { section }
+}
+
+fn line_count(n: usize) -> String {
+    format!("{} line{}", n, if n == 1 { "" } else { "s" })
+}
+
+/// One-paragraph summary of the changes, without quoting note content
+pub fn deterministic_summary(changes: &[SectionChange]) -> String {
+    if changes.is_empty() {
+        return "No changes.".to_string();
+    }
+    let parts: Vec<String> = changes.iter().map(|c| {
+        let label = section_label(&c.section);
+        match c.kind {
+            SectionChangeKind::Added => format!("{} added ({})", label, line_count(c.added.len())),
+            SectionChangeKind::Removed => format!("{} removed ({})", label, line_count(c.removed.len())),
+            SectionChangeKind::Modified => format!(
+                "{} edited (+{} / -{})", label, c.added.len(), line_count(c.removed.len())
+            ),
+        }
+    }).collect();
+    format!(
+        "{} section{} changed: {}.",
+        changes.len(),
+        if changes.len() == 1 { "" } else { "s" },
+        parts.join("; ")
+    )
+}
+
+/// Deterministic summary of revision `from_rev` -> `to_rev`
+pub fn summarize(note_id: &str, from_rev: usize, to_rev: usize, from: &str, to: &str) -> NoteChangeSummary {
+    let sections = diff_sections(from, to);
+    NoteChangeSummary {
+        note_id: note_id.to_string(),
+        from_rev,
+        to_rev,
+        from_hash: crate::crypto::hash_sha256(from.as_bytes()),
+        to_hash: crate::crypto::hash_sha256(to.as_bytes()),
+        lines_added: sections.iter().map(|s| s.added.len()).sum(),
+        lines_removed: sections.iter().map(|s| s.removed.len()).sum(),
+        summary: deterministic_summary(&sections),
+        source: SummarySource::Deterministic,
+        sections,
+    }
+}
+
+/// Prompt asking the local model for the clinical meaning of a diff. Only
+/// the changed lines are sent, never the whole note.
+pub fn summary_prompt(changes: &NoteChangeSummary) -> String {
+    let mut diff = String::new();
+    for change in &changes.sections {
+        diff.push_str(&format!("[{}]\n", section_label(&change.section)));
+        for line in change.removed.iter().take(PROMPT_LINES_PER_SECTION) {
+            diff.push_str(&format!("- {}\n", line));
+        }
+        for line in change.added.iter().take(PROMPT_LINES_PER_SECTION) {
+            diff.push_str(&format!("+ {}\n", line));
+        }
+    }
+    format!(
+        r#"Summarize the changes between two versions of a clinical note for a supervisor reviewing them.
+
+RULES:
+1. At most four sentences
+2. Name the sections touched and what the change means clinically (e.g. risk level, plan, diagnosis)
+3. Only describe what the changed lines say; do not speculate
+4. Output only the summary
+
+CHANGES (- removed, + added):
+{}"#,
+        diff
+    )
+}
+
+// ============================================
+// Tauri Commands
+// ============================================
+
+use tauri::State;
+use crate::commands::AppState;
+
+/// Summarize what changed between two revisions of a note. With a model
+/// the summary is written by the local LLM; otherwise, or if the model
+/// fails, it is the deterministic section diff.
+#[tauri::command]
+pub async fn summarize_note_changes(
+    state: State<'_, AppState>,
+    policy_state: State<'_, crate::policy::PolicyState>,
+    note_id: String,
+    from_rev: usize,
+    to_rev: usize,
+    model: Option<String>,
+) -> Result<NoteChangeSummary, String> {
+    let mut changes = {
+        let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+        crate::commands::read_note_checked(&vault, &policy_state, &note_id)?;
+        let stored = vault.get_note_revisions(&note_id).map_err(|e| format!("{}", e))?;
+        let revisions: Vec<&str> = stored.iter().map(|r| r.raw_input.as_str()).collect();
+        if from_rev >= to_rev || to_rev >= revisions.len() {
+            return Err(format!(
+                "Revisions must satisfy from < to < {} for this note", revisions.len()
+            ));
+        }
+        summarize(&note_id, from_rev, to_rev, revisions[from_rev], revisions[to_rev])
+    };
+
+    if let Some(model) = model.filter(|_| !changes.sections.is_empty()) {
+        match crate::ai::generate_answer(&model, &summary_prompt(&changes)).await {
+            Ok(text) if !text.trim().is_empty() => {
+                changes.summary = text.trim().to_string();
+                changes.source = SummarySource::LocalModel;
+            }
+            Ok(_) => log::warn!("Model returned an empty change summary; using the section diff"),
+            Err(e) => log::warn!("Change summary model call failed ({}); using the section diff", e),
+        }
+    }
+    if changes.source == SummarySource::LocalModel {
+        let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+        let conn = vault.get_connection().map_err(|e| format!("{}", e))?;
+        crate::audit::log_change_summary(
+            conn, &note_id, from_rev, to_rev, &crate::crypto::hash_sha256(changes.summary.as_bytes()),
+        ).map_err(|e| format!("Audit write failed: {}", e))?;
+    }
+    Ok(changes)
+}
+
+/// Every saved version of a note with what changed at each step, so a
+/// clinician can show exactly what changed before signing. Prior versions
+/// are note content, so reading them is audited as a retrieval.
+#[tauri::command]
+pub fn get_note_history(
+    state: State<AppState>,
+    policy_state: State<'_, crate::policy::PolicyState>,
+    note_id: String,
+) -> Result<NoteHistory, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    crate::commands::read_note_checked(&vault, &policy_state, &note_id)?;
+    let revisions = vault.get_note_revisions(&note_id).map_err(|e| format!("{}", e))?;
+    crate::commands::log_note_retrieval(&vault, "note_history", &[note_id.as_str()])?;
+    Ok(history(&note_id, revisions))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_note_change_summary() {
+        let (removed, added) = line_diff(&["a", "b", "c"], &["a", "c", "d"]);
+        assert_eq!(removed, ["b"]);
+        assert_eq!(added, ["d"]);
+
+        let signed = "Subjective: Reports poor sleep.\nPlan: Weekly CBT-I.\nRisk: Denies SI.";
+        let amended = format!(
+            "{}\n\n--- AMENDMENT (2024-03-02 10:00:00 UTC) ---\nReason: late entry\nAmended: 2024-03-02 10:00:00 UTC\n\nPassive SI disclosed after session.",
+            signed
+        );
+        let revisions = amendment_revisions(&amended);
+        assert_eq!(revisions, [signed, amended.as_str()]);
+        assert_eq!(amendment_revisions(signed), [signed]);
+
+        let changes = summarize("n1", 0, 1, revisions[0], revisions[1]);
+        // The block's marker, reason and timestamp lines are not note content
+        assert_eq!(changes.sections.iter().map(|s| s.section.as_str()).collect::<Vec<_>>(), [AMENDMENT_SECTION]);
+        assert_eq!(changes.sections[0].kind, SectionChangeKind::Added);
+        assert_eq!(changes.sections[0].added, ["Passive SI disclosed after session."]);
+        assert_eq!((changes.lines_added, changes.lines_removed), (1, 0));
+        assert_eq!(changes.summary, "1 section changed: amendment added (1 line).");
+
+        let twice = format!(
+            "{}\n\n--- AMENDMENT (2024-03-05 09:00:00 UTC) ---\nReason: correction\nAmended: 2024-03-05 09:00:00 UTC\n\nPlan: Add sleep diary.",
+            amended
+        );
+        let changes = summarize("n1", 1, 2, &amended, &twice);
+        assert_eq!(changes.sections.len(), 1);
+        assert_eq!(changes.sections[0].added, ["Plan: Add sleep diary."]);
+        assert_eq!(summarize("n1", 0, 2, signed, &twice).lines_added, 2);
+
+        let edited = "Subjective: Reports poor sleep.\nPlan: Biweekly CBT-I.\nAdd sleep diary.";
+        let changes = summarize("n1", 0, 1, signed, edited);
+        assert_eq!(changes.summary, "2 sections changed: plan edited (+2 / -1 line); risk removed (1 line).");
+        assert_eq!(changes.sections[1].removed, ["Denies SI."]);
+        assert!(summary_prompt(&changes).contains("+ Add sleep diary."));
+        assert!(!summary_prompt(&changes).contains("poor sleep"));
+
+        assert_eq!(summarize("n1", 0, 1, signed, signed).summary, "No changes.");
+
+        let revision = |revision, raw: &str, structured: Option<&str>, superseded_by: Option<&str>| NoteRevision {
+            note_id: "n1".to_string(),
+            revision,
+            raw_input: raw.to_string(),
+            structured_note: structured.map(str::to_string),
+            content_hash: crate::crypto::hash_sha256(raw.as_bytes()),
+            status: crate::models::NoteStatus::Draft,
+            saved_at: revision as i64,
+            superseded_at: superseded_by.map(|_| revision as i64 + 1),
+            superseded_by: superseded_by.map(str::to_string),
+            editor: superseded_by.map(|_| "Dr. Reyes".to_string()),
+        };
+        let log = history("n1", vec![
+            revision(0, signed, None, Some("structure")),
+            revision(1, signed, Some("S: poor sleep"), Some("edit")),
+            revision(2, edited, Some("S: poor sleep"), None),
+        ]);
+        assert_eq!(log.steps.len(), 2);
+        assert!(log.steps[0].structured_changed && log.steps[0].sections.is_empty());
+        assert_eq!(log.steps[1].change.as_deref(), Some("edit"));
+        assert_eq!(log.steps[1].changed_at, Some(2));
+        assert_eq!(log.steps[1].changed_by.as_deref(), Some("Dr. Reyes"));
+        assert_eq!(log.steps[1].summary, changes.summary);
+    }
+}
@@ -0,0 +1,320 @@
+// Note Export Module (note_export_v1)
+//
+// Machine-readable export of one note for research pipelines and the
+// TypeScript verifier, so neither has to scrape PDFs. The document follows
+// the published schema (verification/schemas/evidify.note_export.v1.schema.json)
+// and is validated against it before it is handed back for writing.
+//
+// The client appears only as an id stub and detections carry offsets, not
+// evidence text. hashes.payload_sha256 and the install signature both
+// cover the canonical JSON of the document (evidify_canonicalization,
+// the same bytes the verifiers hash) with payload_sha256 blanked and
+// signatures empty.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::crypto::signing::{self, InstallSigningKey};
+use crate::json_schema::{self, SchemaViolation};
+use crate::models::{Attestation, Client, DetectionSeverity, Note, StoredDetection};
+use crate::note_authors::NoteAuthorship;
+
+pub const NOTE_EXPORT_SCHEMA_VERSION: &str = "evidify.note_export.v1";
+
+/// The published schema, compiled in so the app validates against exactly
+/// what verifiers are given
+pub const NOTE_EXPORT_SCHEMA: &str = include_str!("../../verification/schemas/evidify.note_export.v1.schema.json");
+
+lazy_static::lazy_static! {
+    static ref SCHEMA: Value = serde_json::from_str(NOTE_EXPORT_SCHEMA)
+        .expect("note_export_v1 schema is valid JSON");
+}
+
+// ============================================
+// Types
+// ============================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NoteExportV1 {
+    pub schema_version: String,
+    pub export_id: String,
+    /// RFC 3339, UTC
+    pub exported_at: String,
+    pub generator: String,
+    pub note: ExportNote,
+    pub client: ExportClientStub,
+    pub attestations: Vec<Attestation>,
+    pub detections: Vec<ExportDetection>,
+    /// Co-authors of a multi-author note; omitted for single-author notes
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub authors: Vec<ExportAuthor>,
+    pub hashes: ExportHashes,
+    pub signatures: Vec<ExportSignature>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportNote {
+    pub id: String,
+    pub note_type: String,
+    pub session_date: String,
+    pub status: String,
+    pub content: String,
+    pub structured_content: Option<String>,
+    pub word_count: i32,
+    pub created_at: i64,
+    pub updated_at: i64,
+    pub signed_at: Option<i64>,
+}
+
+/// Identifier only; no name or contact details leave in this format
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportClientStub {
+    pub id: String,
+    pub status: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportDetection {
+    pub id: String,
+    pub pattern_id: String,
+    /// Unknown for notes whose detections were recorded before anchoring
+    pub severity: Option<DetectionSeverity>,
+    pub match_start: Option<usize>,
+    pub match_end: Option<usize>,
+}
+
+/// One author's share of the note. The contribution hash can be recomputed
+/// from the content (see note_authors).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportAuthor {
+    pub author_id: String,
+    pub role: String,
+    pub sections: Vec<String>,
+    pub contribution_sha256: String,
+    pub signed_at: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportHashes {
+    pub algorithm: String,
+    pub content_sha256: String,
+    pub payload_sha256: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportSignature {
+    pub algorithm: String,
+    pub key_fingerprint: String,
+    pub public_key: String,
+    pub signature: String,
+}
+
+/// Validated export, ready to save
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NoteExportFile {
+    pub filename: String,
+    pub content: String,
+    pub payload_sha256: String,
+}
+
+// ============================================
+// Building and Sealing
+// ============================================
+
+/// Unsealed export document. `anchored` are the note's anchored
+/// detections when it has them; otherwise detections are listed from the
+/// recorded ids without offsets. Authors are listed for multi-author notes.
+pub fn build(
+    note: &Note,
+    client: &Client,
+    anchored: Option<&[StoredDetection]>,
+    authorship: &NoteAuthorship,
+    exported_at: chrono::DateTime<chrono::Utc>,
+) -> NoteExportV1 {
+    let detections = match anchored {
+        Some(stored) => stored.iter().map(|d| ExportDetection {
+            id: d.id.clone(),
+            pattern_id: d.pattern_id.clone(),
+            severity: Some(d.severity),
+            match_start: Some(d.match_start),
+            match_end: Some(d.match_end),
+        }).collect(),
+        None => note.detection_ids.iter().map(|id| ExportDetection {
+            id: id.clone(),
+            pattern_id: crate::ethics::pattern_id_of(id).to_string(),
+            severity: None,
+            match_start: None,
+            match_end: None,
+        }).collect(),
+    };
+
+    let authors = authorship.authors.iter().zip(&authorship.contributions).map(|(author, contribution)| ExportAuthor {
+        author_id: author.author_id.clone(),
+        role: author.role.as_str().to_string(),
+        sections: contribution.sections.clone(),
+        contribution_sha256: contribution.contribution_sha256.clone(),
+        signed_at: author.signed_at.filter(|_| author.has_signed(&authorship.text_sha256)),
+    }).collect();
+
+    NoteExportV1 {
+        schema_version: NOTE_EXPORT_SCHEMA_VERSION.to_string(),
+        export_id: uuid::Uuid::new_v4().to_string(),
+        exported_at: exported_at.to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+        generator: concat!("evidify ", env!("CARGO_PKG_VERSION")).to_string(),
+        note: ExportNote {
+            id: note.id.clone(),
+            note_type: note.note_type.to_string(),
+            session_date: note.session_date.clone(),
+            status: note.status.to_string().to_lowercase(),
+            content: note.raw_input.clone(),
+            structured_content: note.structured_note.clone(),
+            word_count: note.word_count,
+            created_at: note.created_at,
+            updated_at: note.updated_at,
+            signed_at: note.signed_at,
+        },
+        client: ExportClientStub {
+            id: client.id.clone(),
+            status: client.status.clone(),
+        },
+        attestations: note.attestations.clone(),
+        detections,
+        authors,
+        hashes: ExportHashes {
+            algorithm: "sha256".to_string(),
+            content_sha256: note.content_hash.clone(),
+            payload_sha256: String::new(),
+        },
+        signatures: Vec::new(),
+    }
+}
+
+/// Bytes covered by payload_sha256 and the signatures
+pub fn signing_payload(doc: &NoteExportV1) -> Result<Vec<u8>, String> {
+    let mut unsigned = doc.clone();
+    unsigned.hashes.payload_sha256 = String::new();
+    unsigned.signatures.clear();
+    let value = serde_json::to_value(&unsigned).map_err(|e| e.to_string())?;
+    evidify_canonicalization::try_canonical_bytes(&value).map_err(|e| e.to_string())
+}
+
+/// Fill in payload_sha256 and sign with the install key
+pub fn seal(mut doc: NoteExportV1, key: &InstallSigningKey) -> Result<NoteExportV1, String> {
+    let payload = signing_payload(&doc)?;
+    let public_key = key.public_key_base64();
+    doc.hashes.payload_sha256 = crate::crypto::hash_sha256(&payload);
+    doc.signatures = vec![ExportSignature {
+        algorithm: "ed25519".to_string(),
+        key_fingerprint: signing::fingerprint(&public_key).map_err(|e| e.to_string())?,
+        public_key,
+        signature: key.sign(&payload),
+    }];
+    Ok(doc)
+}
+
+/// Schema violations in `doc`; empty when it can be written
+pub fn validate(doc: &NoteExportV1) -> Result<Vec<SchemaViolation>, String> {
+    let value = serde_json::to_value(doc).map_err(|e| e.to_string())?;
+    Ok(json_schema::validate(&SCHEMA, &value))
+}
+
+/// Validated, pretty-printed file for a sealed export
+pub fn render(doc: &NoteExportV1) -> Result<NoteExportFile, String> {
+    let violations = validate(doc)?;
+    if !violations.is_empty() {
+        let listed: Vec<String> = violations.iter().map(|v| v.to_string()).collect();
+        return Err(format!("Export does not match {}: {}", NOTE_EXPORT_SCHEMA_VERSION, listed.join("; ")));
+    }
+    Ok(NoteExportFile {
+        filename: format!("note-{}-{}.json", doc.note.session_date, crate::ids::short_id(&doc.note.id)),
+        content: serde_json::to_string_pretty(doc).map_err(|e| e.to_string())?,
+        payload_sha256: doc.hashes.payload_sha256.clone(),
+    })
+}
+
+// ============================================
+// Tauri Commands
+// ============================================
+
+use tauri::State;
+use crate::commands::AppState;
+
+/// note_export_v1 document for a note, validated against the published schema
+#[tauri::command]
+pub fn export_note_json(
+    state: State<AppState>,
+    policy_state: State<'_, crate::policy::PolicyState>,
+    note_id: String,
+) -> Result<NoteExportFile, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    crate::commands::read_note_checked(&vault, &policy_state, &note_id)?;
+    vault.export_note_v1(&note_id).map_err(|e| format!("{}", e))
+}
+
+/// The schema exports are validated against, for downstream consumers
+#[tauri::command]
+pub fn get_note_export_schema() -> String {
+    NOTE_EXPORT_SCHEMA.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{AttestationResponse, NoteStatus, NoteType};
+
+    fn fixtures() -> (Note, Client) {
+        let note = Note {
+            id: "9f0c2a51-0000-4000-8000-000000000001".to_string(),
+            client_id: "c1".to_string(),
+            session_date: "2024-03-05".to_string(),
+            note_type: NoteType::Progress,
+            raw_input: "Client denies SI.".to_string(),
+            structured_note: None,
+            word_count: 3,
+            status: NoteStatus::Signed,
+            detection_ids: vec!["safety-si-euphemism-7".to_string()],
+            attestations: vec![Attestation {
+                detection_id: "safety-si-euphemism-7".to_string(),
+                response: AttestationResponse::AddressedInNote,
+                response_note: None,
+                attested_at: 2,
+            }],
+            content_hash: crate::crypto::hash_sha256(b"Client denies SI."),
+            signed_at: Some(2),
+            created_at: 1,
+            updated_at: 2,
+        };
+        let client: Client = serde_json::from_value(serde_json::json!({
+            "id": "c1", "display_name": "Jane Doe", "status": "active",
+            "session_count": 1, "created_at": 0, "updated_at": 0
+        })).unwrap();
+        (note, client)
+    }
+
+    fn single_author(note: &Note) -> NoteAuthorship {
+        crate::note_authors::authorship(&note.id, &note.raw_input, Vec::new(), Vec::new())
+    }
+
+    #[test]
+    fn test_sealed_export_validates_and_verifies() {
+        let (note, client) = fixtures();
+        let key = InstallSigningKey::generate();
+        let doc = seal(build(&note, &client, None, &single_author(&note), chrono::Utc::now()), &key).unwrap();
+
+        assert!(validate(&doc).unwrap().is_empty());
+        let file = render(&doc).unwrap();
+        assert!(!file.content.contains("Jane Doe"));
+        assert_eq!(doc.detections[0].pattern_id, "safety-si-euphemism");
+
+        let payload = signing_payload(&doc).unwrap();
+        assert_eq!(doc.hashes.payload_sha256, crate::crypto::hash_sha256(&payload));
+        signing::verify(&doc.signatures[0].public_key, &payload, &doc.signatures[0].signature).unwrap();
+
+        // Unsealed documents and tampered fields are caught before writing
+        let unsealed = build(&note, &client, None, &single_author(&note), chrono::Utc::now());
+        assert!(validate(&unsealed).unwrap().iter().any(|v| v.path == "/hashes/payload_sha256"));
+        let mut bad = doc.clone();
+        bad.note.session_date = "March 5".to_string();
+        assert!(render(&bad).is_err());
+    }
+}
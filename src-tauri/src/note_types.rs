@@ -0,0 +1,332 @@
+// Note Type Registry
+//
+// NoteType is the fixed set of built-in formats. Agencies add their own
+// types ("Crisis Contact Note", "Group Note - DBT Skills", ...) as data:
+// a definition with sections, required labelled fields and sign-gate
+// rules, stored in the vault and referenced from notes by name. Every
+// custom type sits on a built-in base type so analysis, export and
+// billing code that matches on NoteType keeps working unchanged.
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::NoteType;
+
+// ============================================
+// Definitions
+// ============================================
+
+/// One section of a note template
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SectionDefinition {
+    /// Stable key (snake_case)
+    pub key: String,
+    /// Heading shown in the editor and matched when signing
+    pub title: String,
+    #[serde(default)]
+    pub required: bool,
+    /// Other headings accepted for this section (e.g. "A:" for Assessment)
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    /// Placeholder guidance for the editor
+    #[serde(default)]
+    pub prompt: Option<String>,
+}
+
+/// A labelled single-line field ("Contact method: phone")
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FieldDefinition {
+    pub key: String,
+    pub label: String,
+}
+
+/// Checks run before a note of this type can be signed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "rule", rename_all = "snake_case")]
+pub enum SignGateRule {
+    /// Note must have at least this many words
+    MinWords { count: usize },
+    /// Every section marked `required` must have a heading in the note
+    RequireSections,
+    /// Every required field must appear with a non-empty value
+    RequireFields,
+    /// A structured MSE must be attached
+    RequireMse,
+}
+
+/// A note type, built-in or agency-defined
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NoteTypeDefinition {
+    /// Unique name notes refer to (lowercase slug)
+    pub name: String,
+    pub display_name: String,
+    /// Built-in type this one is stored and analysed as
+    pub base_type: NoteType,
+    pub sections: Vec<SectionDefinition>,
+    #[serde(default)]
+    pub required_fields: Vec<FieldDefinition>,
+    #[serde(default)]
+    pub sign_gate: Vec<SignGateRule>,
+    /// Built-in types are listed for the picker but can't be edited;
+    /// their sign checks live in `sign_note`
+    #[serde(default)]
+    pub builtin: bool,
+}
+
+impl NoteTypeDefinition {
+    pub fn validate(&self) -> Result<(), String> {
+        let valid_name = !self.name.is_empty()
+            && self.name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '-');
+        if !valid_name {
+            return Err("Note type name must be a lowercase slug (a-z, 0-9, '_' or '-')".to_string());
+        }
+        if is_builtin(&self.name) {
+            return Err(format!("'{}' is a built-in note type", self.name));
+        }
+        if self.display_name.trim().is_empty() {
+            return Err("Note type display name is required".to_string());
+        }
+        if self.sections.is_empty() {
+            return Err("Note type needs at least one section".to_string());
+        }
+
+        let mut keys: Vec<&str> = self.sections.iter().map(|s| s.key.as_str())
+            .chain(self.required_fields.iter().map(|f| f.key.as_str()))
+            .collect();
+        keys.sort_unstable();
+        if keys.windows(2).any(|w| w[0] == w[1]) {
+            return Err("Section and field keys must be unique".to_string());
+        }
+        Ok(())
+    }
+}
+
+fn section(key: &str, title: &str, required: bool) -> SectionDefinition {
+    SectionDefinition {
+        key: key.to_string(),
+        title: title.to_string(),
+        required,
+        aliases: vec![],
+        prompt: None,
+    }
+}
+
+/// Templates for the built-in NoteType variants
+pub fn builtin_definitions() -> Vec<NoteTypeDefinition> {
+    let builtin = |note_type: NoteType, sections: Vec<SectionDefinition>| NoteTypeDefinition {
+        name: note_type.to_string(),
+        display_name: note_type.format_name().to_string(),
+        base_type: note_type,
+        sections,
+        required_fields: vec![],
+        sign_gate: vec![],
+        builtin: true,
+    };
+
+    vec![
+        builtin(NoteType::Progress, vec![
+            section("subjective", "Subjective", false),
+            section("objective", "Objective", false),
+            section("assessment", "Assessment", true),
+            section("plan", "Plan", true),
+        ]),
+        builtin(NoteType::Intake, vec![
+            section("presenting_problem", "Presenting Problem", true),
+            section("history", "History", false),
+            section("risk_assessment", "Risk Assessment", true),
+            section("diagnosis", "Diagnostic Impression", false),
+            section("plan", "Plan", true),
+        ]),
+        builtin(NoteType::Crisis, vec![
+            section("situation", "Situation", true),
+            section("risk_assessment", "Risk Assessment", true),
+            section("intervention", "Intervention", true),
+            section("disposition", "Disposition", true),
+        ]),
+        builtin(NoteType::Phone, vec![
+            section("reason", "Reason for Contact", true),
+            section("summary", "Summary", true),
+            section("plan", "Plan", false),
+        ]),
+        builtin(NoteType::Group, vec![
+            section("group_focus", "Group Focus", true),
+            section("participation", "Participation", true),
+            section("plan", "Plan", false),
+        ]),
+        builtin(NoteType::Termination, vec![
+            section("course_of_treatment", "Course of Treatment", true),
+            section("status_at_discharge", "Status at Discharge", true),
+            section("recommendations", "Recommendations", true),
+        ]),
+    ]
+}
+
+pub fn is_builtin(name: &str) -> bool {
+    builtin_definitions().iter().any(|d| d.name == name)
+}
+
+// ============================================
+// Sign Gate
+// ============================================
+
+/// Heading text of a line with markdown/bullet decoration removed
+fn heading_text(line: &str) -> String {
+    line.trim()
+        .trim_start_matches(|c: char| c == '#' || c == '*' || c == '-' || c.is_whitespace())
+        .to_lowercase()
+}
+
+/// True if some line starts with the section title (or an alias)
+pub fn section_present(content: &str, section: &SectionDefinition) -> bool {
+    let names: Vec<String> = std::iter::once(&section.title)
+        .chain(section.aliases.iter())
+        .map(|n| n.to_lowercase())
+        .collect();
+    content
+        .lines()
+        .map(heading_text)
+        .any(|line| names.iter().any(|n| line.starts_with(n.as_str())))
+}
+
+/// Value of a "Label: value" line, if present and non-empty
+pub fn field_value<'a>(content: &'a str, field: &FieldDefinition) -> Option<&'a str> {
+    let label = field.label.to_lowercase();
+    content.lines().find_map(|line| {
+        let (name, value) = line.split_once(':')?;
+        let value = value.trim();
+        (heading_text(name) == label && !value.is_empty()).then_some(value)
+    })
+}
+
+/// Words every note needs before it can be signed
+pub const BASELINE_MIN_WORDS: usize = 20;
+
+/// Checks every note passes before signing, whatever its type; a custom
+/// type's `sign_gate` adds to these and cannot waive them
+pub fn baseline_sign_gate(content: &str) -> Vec<String> {
+    let mut failures = Vec::new();
+    
+    let words = content.split_whitespace().count();
+    if words < BASELINE_MIN_WORDS {
+        failures.push(format!(
+            "Note is too short (minimum {} words required for defensibility)", BASELINE_MIN_WORDS
+        ));
+    }
+    
+    // SOAP critical sections
+    let lower = content.to_lowercase();
+    if !(lower.contains("assessment") || lower.contains("a:") || lower.contains("a (")) {
+        failures.push("Note is missing Assessment section (required for clinical defensibility)".to_string());
+    }
+    if !(lower.contains("plan") || lower.contains("p:") || lower.contains("p (")) {
+        failures.push("Note is missing Plan section (required for clinical defensibility)".to_string());
+    }
+    
+    failures
+}
+
+/// Reasons the note can't be signed yet; empty means the gate passes
+pub fn check_sign_gate(def: &NoteTypeDefinition, content: &str, has_mse: bool) -> Vec<String> {
+    let mut failures = Vec::new();
+
+    for rule in &def.sign_gate {
+        match rule {
+            SignGateRule::MinWords { count } => {
+                let words = content.split_whitespace().count();
+                if words < *count {
+                    failures.push(format!("Note is too short ({} of {} words)", words, count));
+                }
+            }
+            SignGateRule::RequireSections => {
+                for section in def.sections.iter().filter(|s| s.required) {
+                    if !section_present(content, section) {
+                        failures.push(format!("Missing {} section", section.title));
+                    }
+                }
+            }
+            SignGateRule::RequireFields => {
+                for field in &def.required_fields {
+                    if field_value(content, field).is_none() {
+                        failures.push(format!("Missing {} field", field.label));
+                    }
+                }
+            }
+            SignGateRule::RequireMse => {
+                if !has_mse {
+                    failures.push("Structured mental status exam is required".to_string());
+                }
+            }
+        }
+    }
+
+    failures
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn crisis_contact() -> NoteTypeDefinition {
+        NoteTypeDefinition {
+            name: "crisis_contact".to_string(),
+            display_name: "Crisis Contact Note".to_string(),
+            base_type: NoteType::Crisis,
+            sections: vec![
+                section("risk", "Risk Assessment", true),
+                section("safety_plan", "Safety Plan", true),
+                section("notes", "Notes", false),
+            ],
+            required_fields: vec![FieldDefinition {
+                key: "contact_method".to_string(),
+                label: "Contact method".to_string(),
+            }],
+            sign_gate: vec![
+                SignGateRule::MinWords { count: 5 },
+                SignGateRule::RequireSections,
+                SignGateRule::RequireFields,
+                SignGateRule::RequireMse,
+            ],
+            builtin: false,
+        }
+    }
+
+    #[test]
+    fn test_validate_definition() {
+        assert!(crisis_contact().validate().is_ok());
+
+        let mut clash = crisis_contact();
+        clash.name = "crisis".to_string();
+        assert!(clash.validate().is_err());
+
+        let mut bad_name = crisis_contact();
+        bad_name.name = "Crisis Contact".to_string();
+        assert!(bad_name.validate().is_err());
+    }
+
+    #[test]
+    fn test_sign_gate() {
+        let def = crisis_contact();
+        let complete = "Contact method: phone\n## Risk Assessment\nDenies SI.\n## Safety Plan\nReviewed coping steps.";
+        assert!(check_sign_gate(&def, complete, true).is_empty());
+
+        let incomplete = "Contact method:\n## Risk Assessment\nDenies SI and HI today.";
+        let failures = check_sign_gate(&def, incomplete, false);
+        assert_eq!(failures.len(), 3);
+        assert!(failures.iter().any(|f| f.contains("Safety Plan")));
+        assert!(failures.iter().any(|f| f.contains("Contact method")));
+    }
+
+    #[test]
+    fn test_baseline_applies_without_custom_rules() {
+        let mut def = crisis_contact();
+        def.sign_gate.clear();
+        let short = "## Risk Assessment\nDenies SI.\n## Safety Plan\nReviewed.";
+        assert!(check_sign_gate(&def, short, false).is_empty());
+        assert_eq!(baseline_sign_gate(short).len(), 1);
+
+        let long = "Client called after a difficult night. Reviewed coping steps together and agreed to \
+                    check in tomorrow morning by phone with the on-call clinician.";
+        let failures = baseline_sign_gate(long);
+        assert_eq!(failures.len(), 2);
+        assert!(failures.iter().all(|f| !f.contains("too short")));
+    }
+}
@@ -0,0 +1,307 @@
+// OCR Module
+//
+// Tesseract runs for uploaded documents. Callers pick the languages or
+// let a first English pass detect them, and choose between the default
+// printed-text profile and a handwriting profile for scanned intake forms.
+// Each run records the mean word confidence from Tesseract's TSV output,
+// so search can flag matches that sit in poorly recognized text.
+
+use image::GenericImageView;
+use serde::{Deserialize, Serialize};
+use std::io::Cursor;
+use std::path::Path;
+
+/// Mean word confidence (0-100) below which a document's OCR text is
+/// treated as unreliable
+pub const LOW_CONFIDENCE_THRESHOLD: f64 = 60.0;
+
+/// Handwriting scans narrower than this are upscaled before recognition
+const HANDWRITING_MIN_WIDTH: u32 = 2000;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OcrProfile {
+    #[default]
+    Printed,
+    Handwriting,
+}
+
+impl OcrProfile {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OcrProfile::Printed => "printed",
+            OcrProfile::Handwriting => "handwriting",
+        }
+    }
+
+    fn tesseract_args(&self) -> &'static [&'static str] {
+        match self {
+            // Automatic page segmentation with OSD
+            OcrProfile::Printed => &["--oem", "1", "--psm", "1"],
+            // Forms are one uniform block; keep the spacing between fields
+            OcrProfile::Handwriting => &["--oem", "1", "--psm", "6", "-c", "preserve_interword_spaces=1"],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OcrResult {
+    pub text: String,
+    /// Tesseract language string used for the final pass (e.g. "spa+eng")
+    pub language: String,
+    /// Set when the language came from detection rather than the caller
+    pub detected_language: Option<String>,
+    pub profile: OcrProfile,
+    /// Mean word confidence 0-100; None when no words were recognized
+    pub mean_confidence: Option<f64>,
+    pub word_count: usize,
+    pub low_confidence_words: usize,
+    /// Mean confidence under LOW_CONFIDENCE_THRESHOLD, or nothing
+    /// recognized; the text should be checked against the scan
+    pub low_confidence: bool,
+}
+
+fn is_low_confidence(mean_confidence: Option<f64>) -> bool {
+    mean_confidence.map(|c| c < LOW_CONFIDENCE_THRESHOLD).unwrap_or(true)
+}
+
+// ============================================
+// Languages
+// ============================================
+
+/// Stopwords per Tesseract language code, for detection from a first pass
+const STOPWORDS: &[(&str, &[&str])] = &[
+    ("eng", &["the", "and", "of", "to", "is", "in", "that", "with", "for", "was"]),
+    ("spa", &["el", "la", "de", "que", "y", "en", "los", "las", "por", "con"]),
+    ("fra", &["le", "la", "les", "et", "des", "est", "une", "dans", "pour", "que"]),
+    ("deu", &["der", "die", "und", "das", "ist", "nicht", "mit", "ein", "den", "zu"]),
+    ("por", &["o", "os", "de", "que", "e", "do", "da", "em", "um", "para"]),
+    ("ita", &["il", "di", "che", "e", "la", "per", "non", "una", "sono", "gli"]),
+];
+
+/// Minimum stopword hits before a detection is trusted
+const MIN_STOPWORD_HITS: usize = 5;
+
+lazy_static::lazy_static! {
+    static ref LANGUAGE_CODE: regex::Regex = regex::Regex::new(r"^[a-z]{3}(_[a-z]+)?$").unwrap();
+}
+
+/// Validate caller-supplied codes; `None`, empty or `["auto"]` means detect
+pub fn parse_languages(languages: Option<&[String]>) -> Result<Option<Vec<String>>, String> {
+    let languages: Vec<String> = languages.unwrap_or_default()
+        .iter()
+        .map(|l| l.trim().to_lowercase())
+        .filter(|l| !l.is_empty())
+        .collect();
+    if languages.is_empty() || languages == ["auto"] {
+        return Ok(None);
+    }
+    if let Some(bad) = languages.iter().find(|l| !LANGUAGE_CODE.is_match(l)) {
+        return Err(format!("'{}' is not a Tesseract language code (e.g. eng, spa, chi_sim)", bad));
+    }
+    Ok(Some(languages))
+}
+
+/// Most likely language of `text` by stopword frequency
+pub fn detect_language(text: &str) -> Option<&'static str> {
+    let words: Vec<String> = text
+        .split(|c: char| !c.is_alphabetic())
+        .filter(|w| !w.is_empty())
+        .map(|w| w.to_lowercase())
+        .collect();
+
+    STOPWORDS.iter()
+        .map(|(code, stopwords)| {
+            let hits = words.iter().filter(|w| stopwords.contains(&w.as_str())).count();
+            (*code, hits)
+        })
+        .filter(|(_, hits)| *hits >= MIN_STOPWORD_HITS)
+        .max_by_key(|(_, hits)| *hits)
+        .map(|(code, _)| code)
+}
+
+/// Language packs Tesseract has installed
+pub fn installed_languages() -> Vec<String> {
+    std::process::Command::new("tesseract")
+        .arg("--list-langs")
+        .output()
+        .map(|o| {
+            // stdout on recent versions, stderr on some older builds
+            let listing = format!("{}{}", String::from_utf8_lossy(&o.stdout), String::from_utf8_lossy(&o.stderr));
+            listing.lines()
+                .map(str::trim)
+                .filter(|l| LANGUAGE_CODE.is_match(l) && *l != "osd")
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+// ============================================
+// Recognition
+// ============================================
+
+/// (mean confidence, words, words below threshold) from Tesseract TSV
+pub fn parse_tsv_confidence(tsv: &str) -> (Option<f64>, usize, usize) {
+    let confidences: Vec<f64> = tsv.lines()
+        .skip(1)
+        .filter_map(|line| {
+            let cols: Vec<&str> = line.split('\t').collect();
+            // level 5 = word; conf -1 marks non-text blocks
+            if cols.len() < 12 || cols[0] != "5" || cols[11].trim().is_empty() {
+                return None;
+            }
+            cols[10].parse::<f64>().ok().filter(|c| *c >= 0.0)
+        })
+        .collect();
+
+    if confidences.is_empty() {
+        return (None, 0, 0);
+    }
+    let mean = confidences.iter().sum::<f64>() / confidences.len() as f64;
+    let low = confidences.iter().filter(|c| **c < LOW_CONFIDENCE_THRESHOLD).count();
+    (Some((mean * 10.0).round() / 10.0), confidences.len(), low)
+}
+
+/// Grayscale, contrast-stretched and (if small) upscaled PNG for the
+/// handwriting profile. None when `data` is not a decodable image (PDFs go
+/// to Tesseract as-is).
+pub fn prepare_handwriting_image(data: &[u8]) -> Option<Vec<u8>> {
+    let img = image::load_from_memory(data).ok()?;
+    let (width, height) = img.dimensions();
+    let img = if width < HANDWRITING_MIN_WIDTH {
+        let scale = HANDWRITING_MIN_WIDTH as f64 / width.max(1) as f64;
+        img.resize(HANDWRITING_MIN_WIDTH, (height as f64 * scale) as u32, image::imageops::FilterType::CatmullRom)
+    } else {
+        img
+    };
+
+    let mut gray = img.to_luma8();
+    let (lo, hi) = gray.pixels().fold((u8::MAX, u8::MIN), |(lo, hi), p| (lo.min(p.0[0]), hi.max(p.0[0])));
+    if hi > lo {
+        let range = (hi - lo) as u32;
+        for p in gray.pixels_mut() {
+            p.0[0] = ((p.0[0] - lo) as u32 * 255 / range) as u8;
+        }
+    }
+
+    let mut out = Cursor::new(Vec::new());
+    image::DynamicImage::ImageLuma8(gray)
+        .write_to(&mut out, image::ImageOutputFormat::Png)
+        .ok()?;
+    Some(out.into_inner())
+}
+
+/// One Tesseract pass producing text and TSV
+fn run_tesseract(input: &Path, languages: &str, profile: OcrProfile) -> Result<(String, String), String> {
+    let output_base = std::env::temp_dir().join(format!("evidify_ocr_out_{}", uuid::Uuid::new_v4()));
+
+    let result = std::process::Command::new("tesseract")
+        .arg(input)
+        .arg(&output_base)
+        .args(["-l", languages])
+        .args(profile.tesseract_args())
+        .args(["txt", "tsv"])
+        .output();
+
+    let txt_path = output_base.with_extension("txt");
+    let tsv_path = output_base.with_extension("tsv");
+    let read = || -> Result<(String, String), String> {
+        let output = result.map_err(|e| {
+            format!("Tesseract not installed or failed: {}. Install with: brew install tesseract", e)
+        })?;
+        if !output.status.success() {
+            return Err(format!("OCR failed: {}", String::from_utf8_lossy(&output.stderr)));
+        }
+        let text = std::fs::read_to_string(&txt_path)
+            .map_err(|e| format!("Failed to read OCR output: {}", e))?;
+        Ok((text, std::fs::read_to_string(&tsv_path).unwrap_or_default()))
+    };
+    let outcome = read();
+
+    let _ = std::fs::remove_file(&txt_path);
+    let _ = std::fs::remove_file(&tsv_path);
+    outcome
+}
+
+/// Recognize `data` with the given languages (None = detect) and profile
+pub fn recognize(data: &[u8], languages: Option<Vec<String>>, profile: OcrProfile) -> Result<OcrResult, String> {
+    let prepared = match profile {
+        OcrProfile::Handwriting => prepare_handwriting_image(data),
+        OcrProfile::Printed => None,
+    };
+    let temp_path = std::env::temp_dir().join(format!("evidify_ocr_{}.tmp", uuid::Uuid::new_v4()));
+    std::fs::write(&temp_path, prepared.as_deref().unwrap_or(data))
+        .map_err(|e| format!("Failed to write temp file: {}", e))?;
+
+    let outcome = (|| -> Result<(String, String, String, Option<String>), String> {
+        if let Some(languages) = languages {
+            let language = languages.join("+");
+            let (text, tsv) = run_tesseract(&temp_path, &language, profile)?;
+            return Ok((text, tsv, language, None));
+        }
+
+        // Detect from an English pass; re-run when another installed language wins
+        let (text, tsv) = run_tesseract(&temp_path, "eng", profile)?;
+        match detect_language(&text) {
+            Some(code) if code != "eng" && installed_languages().iter().any(|l| l == code) => {
+                let language = format!("{}+eng", code);
+                let (text, tsv) = run_tesseract(&temp_path, &language, profile)?;
+                Ok((text, tsv, language, Some(code.to_string())))
+            }
+            detected => Ok((text, tsv, "eng".to_string(), detected.map(str::to_string))),
+        }
+    })();
+    let _ = std::fs::remove_file(&temp_path);
+
+    let (text, tsv, language, detected_language) = outcome?;
+    let (mean_confidence, word_count, low_confidence_words) = parse_tsv_confidence(&tsv);
+    Ok(OcrResult {
+        text,
+        language,
+        detected_language,
+        profile,
+        mean_confidence,
+        word_count,
+        low_confidence_words,
+        low_confidence: is_low_confidence(mean_confidence),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_language_and_codes() {
+        assert_eq!(
+            detect_language("El paciente llegó a la cita con su madre y habló de los problemas en la escuela por la mañana."),
+            Some("spa")
+        );
+        assert_eq!(
+            detect_language("The client reported that the medication was helpful and that sleep is improving in the evenings."),
+            Some("eng")
+        );
+        assert_eq!(detect_language("Name: ____ DOB: ____"), None);
+
+        assert_eq!(parse_languages(None).unwrap(), None);
+        assert_eq!(parse_languages(Some(&["auto".to_string()])).unwrap(), None);
+        assert_eq!(
+            parse_languages(Some(&["SPA".to_string(), "chi_sim".to_string()])).unwrap(),
+            Some(vec!["spa".to_string(), "chi_sim".to_string()])
+        );
+        assert!(parse_languages(Some(&["eng; rm -rf".to_string()])).is_err());
+    }
+
+    #[test]
+    fn test_tsv_confidence() {
+        let tsv = "level\tpage_num\tblock_num\tpar_num\tline_num\tword_num\tleft\ttop\twidth\theight\tconf\ttext\n\
+                   1\t1\t0\t0\t0\t0\t0\t0\t800\t600\t-1\t\n\
+                   5\t1\t1\t1\t1\t1\t10\t10\t50\t20\t96.5\tClient\n\
+                   5\t1\t1\t1\t1\t2\t70\t10\t50\t20\t91.0\tname\n\
+                   5\t1\t1\t1\t1\t3\t130\t10\t50\t20\t31.5\tJ0hn\n\
+                   5\t1\t1\t1\t1\t4\t190\t10\t50\t20\t-1\t\n";
+        assert_eq!(parse_tsv_confidence(tsv), (Some(73.0), 3, 1));
+        assert_eq!(parse_tsv_confidence("level\tconf\n"), (None, 0, 0));
+    }
+}
@@ -0,0 +1,464 @@
+// Passphrase Strength Module
+//
+// Estimates how many guesses a vault passphrase would take, zxcvbn-style:
+// the passphrase is split into the cheapest sequence of patterns an
+// attacker would try (common passwords and words from a bundled list,
+// including l33t spellings, repeats, sequences, keyboard runs, years) and
+// whatever is left is brute-forced by character class. The list ships
+// with the app, so the check is offline; the passphrase never leaves this
+// module and nothing about it is logged.
+//
+// Scores follow zxcvbn's scale: 0 too guessable, 1 very guessable,
+// 2 somewhat guessable, 3 safely unguessable, 4 very unguessable. The
+// passphrase policy (policy.rs) sets what vault creation enforces; an
+// install without one configured only gets the score as advice.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::policy::PassphrasePolicy;
+
+/// Common passwords and base words, most common first
+const COMMON_PASSWORDS: &str = include_str!("../data/common_passwords.txt");
+
+/// Error prefix the UI matches on to show strength feedback
+pub const PASSPHRASE_TOO_WEAK: &str = "PASSPHRASE_TOO_WEAK";
+
+/// Offline guessing rate against the Argon2id-wrapped vault key on
+/// dedicated hardware
+const OFFLINE_GUESSES_PER_SECOND: f64 = 1e4;
+
+/// Longest token looked up in the word list
+const MAX_WORD_LEN: usize = 32;
+
+const KEYBOARD_ROWS: &[&str] = &["1234567890", "qwertyuiop", "asdfghjkl", "zxcvbnm"];
+
+lazy_static::lazy_static! {
+    static ref RANKED: HashMap<&'static str, usize> = COMMON_PASSWORDS
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .enumerate()
+        .map(|(i, word)| (word, i + 1))
+        .collect();
+}
+
+// ============================================
+// Types
+// ============================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PatternKind {
+    /// On the bundled common-password list
+    Dictionary,
+    Repeat,
+    Sequence,
+    Keyboard,
+    Year,
+    BruteForce,
+}
+
+/// One segment of the estimate. Offsets are in characters; the matched
+/// text itself is not returned.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PatternMatch {
+    pub kind: PatternKind,
+    pub start: usize,
+    pub end: usize,
+    pub guesses_log10: f64,
+    /// Dictionary matches spelled with l33t substitutions
+    #[serde(default)]
+    pub l33t: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StrengthFeedback {
+    pub warning: Option<String>,
+    pub suggestions: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PassphraseStrength {
+    /// 0 (too guessable) to 4 (very unguessable)
+    pub score: u8,
+    pub guesses_log10: f64,
+    /// At OFFLINE_GUESSES_PER_SECOND
+    pub crack_time_seconds: f64,
+    pub crack_time_display: String,
+    /// In characters
+    pub length: usize,
+    /// The whole passphrase is on the common-password list
+    pub common_password: bool,
+    pub patterns: Vec<PatternMatch>,
+    pub feedback: StrengthFeedback,
+}
+
+/// Strength plus what the active policy makes of it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PassphraseCheck {
+    pub strength: PassphraseStrength,
+    pub meets_policy: bool,
+    pub violations: Vec<String>,
+}
+
+// ============================================
+// Matching
+// ============================================
+
+/// Guesses to brute-force one character of this class
+fn cardinality(c: char) -> f64 {
+    if c.is_ascii_digit() {
+        10.0
+    } else if c.is_ascii_lowercase() || c.is_ascii_uppercase() {
+        26.0
+    } else if c.is_ascii() {
+        33.0
+    } else {
+        100.0
+    }
+}
+
+fn unleet(c: char) -> Option<char> {
+    Some(match c {
+        '4' | '@' => 'a',
+        '3' => 'e',
+        '0' => 'o',
+        '1' | '!' => 'i',
+        '5' | '$' => 's',
+        '7' => 't',
+        _ => return None,
+    })
+}
+
+/// Dictionary match for `token`, trying it as typed and with l33t undone
+fn dictionary_match(token: &[char]) -> Option<(f64, bool)> {
+    let lower: String = token.iter().flat_map(|c| c.to_lowercase()).collect();
+    let uppers = token.iter().filter(|c| c.is_uppercase()).count();
+    let case_variations = match uppers {
+        0 => 1.0,
+        n if n == token.len() || (n == 1 && token[0].is_uppercase()) => 2.0,
+        n => 2f64.powi(n.min(10) as i32),
+    };
+
+    if let Some(rank) = RANKED.get(lower.as_str()) {
+        return Some(((*rank as f64 * case_variations).log10(), false));
+    }
+    let substitutions = lower.chars().filter(|c| unleet(*c).is_some()).count();
+    if substitutions == 0 {
+        return None;
+    }
+    let unleeted: String = lower.chars().map(|c| unleet(c).unwrap_or(c)).collect();
+    RANKED.get(unleeted.as_str()).map(|rank| {
+        let l33t_variations = 2f64.powi(substitutions.min(10) as i32);
+        ((*rank as f64 * case_variations * l33t_variations).log10(), true)
+    })
+}
+
+/// Every non-brute-force match in `chars`, as (start, end, kind, guesses_log10, l33t)
+fn find_matches(chars: &[char]) -> Vec<PatternMatch> {
+    let n = chars.len();
+    let lower: Vec<char> = chars.iter().map(|c| c.to_ascii_lowercase()).collect();
+    let mut matches = Vec::new();
+    let mut push = |kind, start, end, guesses: f64, l33t| {
+        matches.push(PatternMatch { kind, start, end, guesses_log10: guesses.max(1.0).log10(), l33t });
+    };
+
+    for i in 0..n {
+        for j in (i + 3)..=n.min(i + MAX_WORD_LEN) {
+            if let Some((log10, l33t)) = dictionary_match(&chars[i..j]) {
+                push(PatternKind::Dictionary, i, j, 10f64.powf(log10), l33t);
+            }
+        }
+    }
+
+    // Repeats, sequences and keyboard runs: maximal runs of length >= 3
+    let mut i = 0;
+    while i < n {
+        let mut j = i + 1;
+        while j < n && chars[j] == chars[i] {
+            j += 1;
+        }
+        if j - i >= 3 {
+            push(PatternKind::Repeat, i, j, cardinality(chars[i]) * (j - i) as f64, false);
+        }
+        i = j;
+    }
+
+    for delta in [1i32, -1] {
+        let mut i = 0;
+        while i + 1 < n {
+            let mut j = i + 1;
+            while j < n && lower[j] as i32 - lower[j - 1] as i32 == delta {
+                j += 1;
+            }
+            if j - i >= 3 {
+                let obvious = matches!(lower[i], 'a' | 'z' | '0' | '1' | '9');
+                let start_guesses = if obvious { 4.0 } else { cardinality(lower[i]) };
+                let direction = if delta < 0 { 2.0 } else { 1.0 };
+                push(PatternKind::Sequence, i, j, start_guesses * direction * (j - i) as f64, false);
+            }
+            i = j;
+        }
+    }
+
+    let text: String = lower.iter().collect();
+    for row in KEYBOARD_ROWS {
+        let reversed: String = row.chars().rev().collect();
+        for run in [row.to_string(), reversed] {
+            for len in 4..=run.len() {
+                for window in run.as_bytes().windows(len) {
+                    let needle = std::str::from_utf8(window).unwrap_or_default();
+                    for (byte_start, _) in text.match_indices(needle) {
+                        let start = text[..byte_start].chars().count();
+                        push(PatternKind::Keyboard, start, start + len, 40.0 * len as f64, false);
+                    }
+                }
+            }
+        }
+    }
+
+    for i in 0..n.saturating_sub(3) {
+        let token: String = chars[i..i + 4].iter().collect();
+        if let Ok(year) = token.parse::<u32>() {
+            if (1900..=2039).contains(&year) {
+                push(PatternKind::Year, i, i + 4, 140.0, false);
+            }
+        }
+    }
+
+    matches
+}
+
+/// Cheapest cover of `chars` by matches and brute-forced runs
+fn cheapest_cover(chars: &[char]) -> (f64, Vec<PatternMatch>) {
+    let n = chars.len();
+    let candidates = find_matches(chars);
+    // best[k]: (log10 guesses, previous cut, match index or None for brute force)
+    let mut best: Vec<(f64, usize, Option<usize>)> = vec![(f64::INFINITY, 0, None); n + 1];
+    best[0].0 = 0.0;
+    for k in 1..=n {
+        let brute = best[k - 1].0 + cardinality(chars[k - 1]).log10();
+        best[k] = (brute, k - 1, None);
+        for (idx, m) in candidates.iter().enumerate().filter(|(_, m)| m.end == k) {
+            let total = best[m.start].0 + m.guesses_log10;
+            if total < best[k].0 {
+                best[k] = (total, m.start, Some(idx));
+            }
+        }
+    }
+
+    // Walk back, merging adjacent brute-force characters into one segment
+    let mut segments: Vec<PatternMatch> = Vec::new();
+    let mut k = n;
+    while k > 0 {
+        let (_, prev, idx) = best[k];
+        match idx {
+            Some(idx) => segments.push(candidates[idx].clone()),
+            None => {
+                let guesses = cardinality(chars[prev]).log10();
+                match segments.last_mut() {
+                    Some(last) if last.kind == PatternKind::BruteForce && last.start == k => {
+                        last.start = prev;
+                        last.guesses_log10 += guesses;
+                    }
+                    _ => segments.push(PatternMatch {
+                        kind: PatternKind::BruteForce,
+                        start: prev,
+                        end: k,
+                        guesses_log10: guesses,
+                        l33t: false,
+                    }),
+                }
+            }
+        }
+        k = prev;
+    }
+    segments.reverse();
+
+    // An attacker also has to guess how the pieces combine
+    let ordering = (1..=segments.len()).map(|i| (i as f64).log10()).sum::<f64>();
+    (best[n].0 + ordering, segments)
+}
+
+// ============================================
+// Estimation
+// ============================================
+
+fn score_for(guesses_log10: f64) -> u8 {
+    match guesses_log10 {
+        g if g < 3.0 => 0,
+        g if g < 6.0 => 1,
+        g if g < 8.0 => 2,
+        g if g < 10.0 => 3,
+        _ => 4,
+    }
+}
+
+fn display_time(seconds: f64) -> String {
+    const UNITS: &[(f64, &str)] = &[
+        (60.0, "second"),
+        (60.0, "minute"),
+        (24.0, "hour"),
+        (30.0, "day"),
+        (12.0, "month"),
+        (100.0, "year"),
+    ];
+    if seconds < 1.0 {
+        return "less than a second".to_string();
+    }
+    let mut value = seconds;
+    for (per_next, unit) in UNITS {
+        if value < *per_next {
+            let n = value.round() as u64;
+            return format!("{} {}{}", n, unit, if n == 1 { "" } else { "s" });
+        }
+        value /= per_next;
+    }
+    "centuries".to_string()
+}
+
+fn feedback_for(strength: &PassphraseStrength) -> StrengthFeedback {
+    let mut feedback = StrengthFeedback::default();
+    if strength.score >= 3 {
+        return feedback;
+    }
+
+    let dominant = strength.patterns.iter()
+        .filter(|p| p.kind != PatternKind::BruteForce)
+        .max_by_key(|p| p.end - p.start);
+    feedback.warning = if strength.common_password {
+        Some("This is a commonly used password and appears in breach lists".to_string())
+    } else {
+        dominant.map(|p| match p.kind {
+            PatternKind::Dictionary if p.l33t => "Predictable substitutions like '@' for 'a' don't add much",
+            PatternKind::Dictionary => "Common words and passwords are easy to guess",
+            PatternKind::Repeat => "Repeated characters like \"aaa\" are easy to guess",
+            PatternKind::Sequence => "Sequences like \"abc\" or \"6543\" are easy to guess",
+            PatternKind::Keyboard => "Rows of adjacent keys are easy to guess",
+            PatternKind::Year => "Years are easy to guess",
+            PatternKind::BruteForce => "",
+        }.to_string())
+    };
+
+    feedback.suggestions.push("Use four or more uncommon words, or a longer phrase only you would know".to_string());
+    if strength.length < 16 {
+        feedback.suggestions.push("Add another word or two; length helps more than symbols".to_string());
+    }
+    if strength.patterns.iter().any(|p| matches!(p.kind, PatternKind::Sequence | PatternKind::Keyboard | PatternKind::Repeat)) {
+        feedback.suggestions.push("Avoid sequences, repeated characters and keyboard rows".to_string());
+    }
+    if strength.patterns.iter().any(|p| p.kind == PatternKind::Year) {
+        feedback.suggestions.push("Avoid years and dates associated with you".to_string());
+    }
+    feedback
+}
+
+/// Strength estimate for a passphrase
+pub fn estimate(passphrase: &str) -> PassphraseStrength {
+    let chars: Vec<char> = passphrase.chars().collect();
+    let (guesses_log10, patterns) = cheapest_cover(&chars);
+    let common_password = patterns.len() == 1
+        && patterns[0].kind == PatternKind::Dictionary
+        && patterns[0].end - patterns[0].start == chars.len();
+    let crack_time_seconds = 10f64.powf(guesses_log10) / OFFLINE_GUESSES_PER_SECOND;
+
+    let mut strength = PassphraseStrength {
+        score: if common_password { 0 } else { score_for(guesses_log10) },
+        guesses_log10,
+        crack_time_seconds,
+        crack_time_display: display_time(crack_time_seconds),
+        length: chars.len(),
+        common_password,
+        patterns,
+        feedback: StrengthFeedback::default(),
+    };
+    strength.feedback = feedback_for(&strength);
+    strength
+}
+
+/// Estimate and judge against `policy`
+pub fn check(passphrase: &str, policy: &PassphrasePolicy) -> PassphraseCheck {
+    let strength = estimate(passphrase);
+    let mut violations = Vec::new();
+    if strength.length < policy.min_length {
+        violations.push(format!("Use at least {} characters", policy.min_length));
+    }
+    if policy.reject_common && strength.common_password {
+        violations.push("This passphrase is on the common-password list".to_string());
+    }
+    if strength.score < policy.min_score {
+        violations.push(format!("Strength {} of 4 is below the required {}", strength.score, policy.min_score));
+    }
+    PassphraseCheck {
+        meets_policy: violations.is_empty(),
+        strength,
+        violations,
+    }
+}
+
+/// Err with PASSPHRASE_TOO_WEAK and the violations unless `passphrase` meets `policy`
+pub fn enforce(passphrase: &str, policy: &PassphrasePolicy) -> Result<(), String> {
+    let result = check(passphrase, policy);
+    if result.meets_policy {
+        Ok(())
+    } else {
+        Err(format!("{}: {}", PASSPHRASE_TOO_WEAK, result.violations.join("; ")))
+    }
+}
+
+// ============================================
+// Tauri Commands
+// ============================================
+
+use tauri::State;
+use crate::policy::PolicyState;
+
+/// Live strength feedback for the create-vault and change-passphrase forms
+#[tauri::command]
+pub fn check_passphrase_strength(
+    policy_state: State<'_, PolicyState>,
+    passphrase: String,
+) -> Result<PassphraseCheck, String> {
+    let engine = policy_state.engine.read().map_err(|e| e.to_string())?;
+    Ok(check(&passphrase, &engine.get_policy().passphrase_policy))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate_scores() {
+        for weak in ["password", "P@ssw0rd", "qwerty123", "aaaaaaaa", "abcdefgh", "Therapy2025"] {
+            let strength = estimate(weak);
+            assert!(strength.score <= 1, "{} scored {}", weak, strength.score);
+            assert!(strength.feedback.warning.is_some(), "{}", weak);
+        }
+        assert!(estimate("password").common_password);
+        assert!(estimate("P@ssw0rd").common_password);
+
+        for strong in ["violet-harbor-quantum-pickle", "Tq9#vLm2!xZr&8pW"] {
+            let strength = estimate(strong);
+            assert!(strength.score >= 3, "{} scored {}", strong, strength.score);
+        }
+    }
+
+    #[test]
+    fn test_policy_check() {
+        let policy = PassphrasePolicy::recommended();
+        let weak = check("letmein", &policy);
+        assert!(!weak.meets_policy);
+        assert_eq!(weak.violations.len(), 3);
+        assert!(enforce("letmein", &policy).unwrap_err().starts_with(PASSPHRASE_TOO_WEAK));
+        assert!(enforce("violet-harbor-quantum-pickle", &policy).is_ok());
+
+        // Unconfigured: feedback only
+        let unconfigured = check("letmein", &PassphrasePolicy::default());
+        assert!(unconfigured.meets_policy && unconfigured.strength.score <= 1);
+
+        // A configured section without fields gets the recommended minimum
+        let configured: PassphrasePolicy = serde_json::from_str("{}").unwrap();
+        assert!(!check("letmein", &configured).meets_policy);
+    }
+}
@@ -1,23 +1,67 @@
 // Performance Optimization Module
 //
 // This module provides performance enhancements for Evidify:
-// - Query result caching with TTL
+// - Query result caching with TTL and LRU eviction within memory budgets
+//   (reporting queries on the replica snapshot; budgets persist across runs)
 // - Lazy loading for large datasets
 // - Background indexing
 // - Connection pooling for SQLite
 // - Memory-efficient batch operations
+// - Startup phase profiling and deferred initialization
 //
 // Sprint 4 - Performance Optimization
 
 use chrono::{DateTime, Duration, Utc};
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use std::collections::HashMap;
-use std::sync::{Arc, RwLock};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+use std::time::Instant;
 use tauri::State;
 
 // ============================================
 // Cache Implementation
 // ============================================
+//
+// Every cache has a budget in entries and in bytes. Entry sizes are
+// estimates (`CacheWeight`): close enough to keep the caches from growing
+// without bound, not an allocator-exact count. When either limit would be
+// exceeded, expired entries go first, then the least recently used.
+
+/// Approximate heap footprint of a cached value
+pub trait CacheWeight {
+    fn weight_bytes(&self) -> usize;
+}
+
+impl CacheWeight for String {
+    fn weight_bytes(&self) -> usize {
+        std::mem::size_of::<String>() + self.capacity()
+    }
+}
+
+impl CacheWeight for serde_json::Value {
+    fn weight_bytes(&self) -> usize {
+        use serde_json::Value;
+        let own = std::mem::size_of::<Value>();
+        match self {
+            Value::Null | Value::Bool(_) | Value::Number(_) => own,
+            Value::String(s) => own + s.capacity(),
+            Value::Array(items) => own + items.iter().map(|v| v.weight_bytes()).sum::<usize>(),
+            Value::Object(map) => {
+                own + map.iter()
+                    .map(|(k, v)| std::mem::size_of::<String>() + k.capacity() + v.weight_bytes())
+                    .sum::<usize>()
+            }
+        }
+    }
+}
+
+/// Limits for one cache
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CacheBudget {
+    pub max_entries: usize,
+    pub max_bytes: usize,
+}
 
 /// Generic cache entry with TTL
 #[derive(Clone)]
@@ -25,6 +69,10 @@ struct CacheEntry<T: Clone> {
     value: T,
     created_at: DateTime<Utc>,
     ttl_seconds: i64,
+    /// Key plus value, as counted against the byte budget
+    bytes: usize,
+    /// Access clock value at the last get or set
+    last_used: u64,
 }
 
 impl<T: Clone> CacheEntry<T> {
@@ -33,39 +81,59 @@ impl<T: Clone> CacheEntry<T> {
     }
 }
 
-/// LRU cache with TTL support
-pub struct TtlCache<T: Clone> {
+/// LRU cache with TTL support and an entry/byte budget
+pub struct TtlCache<T: Clone + CacheWeight> {
     entries: HashMap<String, CacheEntry<T>>,
-    max_entries: usize,
+    budget: CacheBudget,
     default_ttl: i64,
+    bytes: usize,
+    clock: u64,
     hits: u64,
     misses: u64,
+    evictions: u64,
 }
 
-impl<T: Clone> TtlCache<T> {
+impl<T: Clone + CacheWeight> TtlCache<T> {
+    /// No byte limit; see `with_budget`
     pub fn new(max_entries: usize, default_ttl_seconds: i64) -> Self {
+        Self::with_budget(CacheBudget { max_entries, max_bytes: usize::MAX }, default_ttl_seconds)
+    }
+
+    pub fn with_budget(budget: CacheBudget, default_ttl_seconds: i64) -> Self {
         Self {
             entries: HashMap::new(),
-            max_entries,
+            budget,
             default_ttl: default_ttl_seconds,
+            bytes: 0,
+            clock: 0,
             hits: 0,
             misses: 0,
+            evictions: 0,
         }
     }
 
+    fn tick(&mut self) -> u64 {
+        self.clock += 1;
+        self.clock
+    }
+
     pub fn get(&mut self, key: &str) -> Option<T> {
-        if let Some(entry) = self.entries.get(key) {
-            if entry.is_expired() {
-                self.entries.remove(key);
-                self.misses += 1;
-                None
-            } else {
+        let now = self.tick();
+        match self.entries.get_mut(key) {
+            Some(entry) if !entry.is_expired() => {
+                entry.last_used = now;
                 self.hits += 1;
                 Some(entry.value.clone())
             }
-        } else {
-            self.misses += 1;
-            None
+            Some(_) => {
+                self.remove(key);
+                self.misses += 1;
+                None
+            }
+            None => {
+                self.misses += 1;
+                None
+            }
         }
     }
 
@@ -73,58 +141,112 @@ impl<T: Clone> TtlCache<T> {
         self.set_with_ttl(key, value, self.default_ttl);
     }
 
+    /// A value larger than the whole byte budget is not cached
     pub fn set_with_ttl(&mut self, key: String, value: T, ttl_seconds: i64) {
-        // Evict expired entries if at capacity
-        if self.entries.len() >= self.max_entries {
-            self.evict_expired();
+        self.remove(&key);
+        let bytes = key.len() + value.weight_bytes();
+        if bytes > self.budget.max_bytes || self.budget.max_entries == 0 {
+            return;
         }
 
-        // If still at capacity, remove oldest
-        if self.entries.len() >= self.max_entries {
-            if let Some(oldest_key) = self.find_oldest_key() {
-                self.entries.remove(&oldest_key);
-            }
+        let fits = |cache: &Self| {
+            cache.entries.len() < cache.budget.max_entries
+                && cache.bytes + bytes <= cache.budget.max_bytes
+        };
+        if !fits(self) {
+            self.evict_expired();
+        }
+        while !fits(self) {
+            self.evict_lru();
         }
 
+        let last_used = self.tick();
+        self.bytes += bytes;
         self.entries.insert(
             key,
             CacheEntry {
                 value,
                 created_at: Utc::now(),
                 ttl_seconds,
+                bytes,
+                last_used,
             },
         );
     }
 
     pub fn invalidate(&mut self, key: &str) {
-        self.entries.remove(key);
+        self.remove(key);
     }
 
     pub fn invalidate_prefix(&mut self, prefix: &str) {
         self.entries.retain(|k, _| !k.starts_with(prefix));
+        self.recount();
     }
 
     pub fn clear(&mut self) {
         self.entries.clear();
+        self.bytes = 0;
+    }
+
+    /// Apply a new budget, evicting down to it at once
+    pub fn set_budget(&mut self, budget: CacheBudget) {
+        self.budget = budget;
+        self.evict_expired();
+        while self.entries.len() > budget.max_entries || self.bytes > budget.max_bytes {
+            self.evict_lru();
+        }
+    }
+
+    /// Evict least recently used entries until at most `max_bytes` remain;
+    /// returns the bytes freed. The budget itself is unchanged.
+    pub fn shrink_to(&mut self, max_bytes: usize) -> usize {
+        let before = self.bytes;
+        self.evict_expired();
+        while self.bytes > max_bytes {
+            self.evict_lru();
+        }
+        before - self.bytes
+    }
+
+    fn remove(&mut self, key: &str) {
+        if let Some(entry) = self.entries.remove(key) {
+            self.bytes -= entry.bytes;
+        }
+    }
+
+    fn recount(&mut self) {
+        self.bytes = self.entries.values().map(|e| e.bytes).sum();
     }
 
     fn evict_expired(&mut self) {
         self.entries.retain(|_, entry| !entry.is_expired());
+        self.recount();
     }
 
-    fn find_oldest_key(&self) -> Option<String> {
-        self.entries
+    fn evict_lru(&mut self) {
+        let lru = self.entries
             .iter()
-            .min_by_key(|(_, entry)| entry.created_at)
-            .map(|(key, _)| key.clone())
+            .min_by_key(|(_, entry)| entry.last_used)
+            .map(|(key, _)| key.clone());
+        if let Some(key) = lru {
+            self.remove(&key);
+            self.evictions += 1;
+        }
+    }
+
+    pub fn bytes(&self) -> usize {
+        self.bytes
     }
 
     pub fn stats(&self) -> CacheStats {
         CacheStats {
             entries: self.entries.len(),
-            max_entries: self.max_entries,
+            max_entries: self.budget.max_entries,
+            bytes: self.bytes,
+            max_bytes: self.budget.max_bytes,
             hits: self.hits,
             misses: self.misses,
+            evictions: self.evictions,
             hit_rate: if self.hits + self.misses > 0 {
                 (self.hits as f64) / ((self.hits + self.misses) as f64)
             } else {
@@ -138,8 +260,12 @@ impl<T: Clone> TtlCache<T> {
 pub struct CacheStats {
     pub entries: usize,
     pub max_entries: usize,
+    pub bytes: usize,
+    pub max_bytes: usize,
     pub hits: u64,
     pub misses: u64,
+    /// Entries dropped to stay within budget (expiry and clears not counted)
+    pub evictions: u64,
     pub hit_rate: f64,
 }
 
@@ -155,18 +281,30 @@ pub struct CachedQueryResult {
     pub query_time_ms: u64,
 }
 
+impl CacheWeight for CachedQueryResult {
+    fn weight_bytes(&self) -> usize {
+        std::mem::size_of::<Self>() - std::mem::size_of::<serde_json::Value>() + self.data.weight_bytes()
+    }
+}
+
 /// Query cache for expensive operations
 pub struct QueryCache {
     cache: TtlCache<CachedQueryResult>,
 }
 
 impl QueryCache {
+    pub const DEFAULT_BUDGET: CacheBudget = CacheBudget {
+        max_entries: 100,
+        max_bytes: 64 * 1024 * 1024,
+    };
+
     pub fn new() -> Self {
+        Self::with_budget(Self::DEFAULT_BUDGET)
+    }
+
+    pub fn with_budget(budget: CacheBudget) -> Self {
         Self {
-            cache: TtlCache::new(
-                100,  // Max 100 cached queries
-                60,   // 60 second TTL
-            ),
+            cache: TtlCache::with_budget(budget, 60), // 60 second TTL
         }
     }
 
@@ -186,6 +324,18 @@ impl QueryCache {
         self.cache.clear();
     }
 
+    pub fn set_budget(&mut self, budget: CacheBudget) {
+        self.cache.set_budget(budget);
+    }
+
+    pub fn shrink_to(&mut self, max_bytes: usize) -> usize {
+        self.cache.shrink_to(max_bytes)
+    }
+
+    pub fn bytes(&self) -> usize {
+        self.cache.bytes()
+    }
+
     pub fn stats(&self) -> CacheStats {
         self.cache.stats()
     }
@@ -408,13 +558,53 @@ where
 // Memory Monitoring
 // ============================================
 
+/// Resident memory the app should stay under (8GB clinic machines)
+pub const MEMORY_TARGET_MB: f64 = 500.0;
+
+/// Budget file in the app data directory; the budget is a property of the
+/// machine, so it is read before any vault is unlocked
+const BUDGET_FILE: &str = "memory_budget.json";
+
+/// Process target and the cache budgets that serve it
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MemoryBudget {
+    pub target_mb: f64,
+    pub query_cache: CacheBudget,
+}
+
+impl Default for MemoryBudget {
+    fn default() -> Self {
+        Self {
+            target_mb: MEMORY_TARGET_MB,
+            query_cache: QueryCache::DEFAULT_BUDGET,
+        }
+    }
+}
+
+impl MemoryBudget {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(self.target_mb.is_finite() && self.target_mb > 0.0) {
+            return Err("Memory target must be a positive number of MB".to_string());
+        }
+        if self.query_cache.max_bytes as f64 > self.target_mb * 1024.0 * 1024.0 {
+            return Err("Query cache budget exceeds the memory target".to_string());
+        }
+        Ok(())
+    }
+}
+
 /// Memory usage statistics
 #[derive(Debug, Clone, Serialize)]
 pub struct MemoryStats {
     pub heap_used_mb: f64,
     pub rss_mb: f64,
     pub cache_entries: usize,
+    /// Estimated bytes held by all caches
+    pub cache_bytes: usize,
     pub pending_tasks: usize,
+    pub target_mb: f64,
+    /// Unknown (false) where RSS cannot be read
+    pub over_target: bool,
 }
 
 #[cfg(target_os = "macos")]
@@ -435,8 +625,7 @@ pub fn get_memory_stats() -> MemoryStats {
     MemoryStats {
         heap_used_mb: 0.0, // Would need allocator stats
         rss_mb: rss_kb / 1024.0,
-        cache_entries: 0,
-        pending_tasks: 0,
+        ..MemoryStats::default()
     }
 }
 
@@ -455,8 +644,7 @@ pub fn get_memory_stats() -> MemoryStats {
             MemoryStats {
                 heap_used_mb: (pmc.WorkingSetSize as f64) / (1024.0 * 1024.0),
                 rss_mb: (pmc.WorkingSetSize as f64) / (1024.0 * 1024.0),
-                cache_entries: 0,
-                pending_tasks: 0,
+                ..MemoryStats::default()
             }
         } else {
             MemoryStats::default()
@@ -475,8 +663,7 @@ pub fn get_memory_stats() -> MemoryStats {
             return MemoryStats {
                 heap_used_mb: 0.0,
                 rss_mb: (rss_pages * page_size) / (1024.0 * 1024.0),
-                cache_entries: 0,
-                pending_tasks: 0,
+                ..MemoryStats::default()
             };
         }
     }
@@ -489,26 +676,273 @@ impl Default for MemoryStats {
             heap_used_mb: 0.0,
             rss_mb: 0.0,
             cache_entries: 0,
+            cache_bytes: 0,
             pending_tasks: 0,
+            target_mb: MEMORY_TARGET_MB,
+            over_target: false,
         }
     }
 }
 
+// ============================================
+// Startup Profiling
+// ============================================
+//
+// Setup phases are timed as they run; heavy state (HTTP clients, the
+// background worker, RAG lookup tables) is wrapped in `Deferred` and built
+// on first use, with that cost recorded as a deferred phase instead of
+// landing on the path to the unlock screen. Threads that need the vault
+// (maintenance scheduler, EHR delivery) start on the first unlock and are
+// recorded the same way.
+
+/// Budget from process start to the unlock screen
+pub const STARTUP_TARGET_MS: u64 = 2000;
+
+static PROCESS_START: OnceLock<Instant> = OnceLock::new();
+static STARTUP_LOG: Mutex<StartupLog> = Mutex::new(StartupLog::new());
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StartupPhase {
+    pub name: String,
+    /// Offset from process start
+    pub started_ms: u64,
+    pub duration_ms: u64,
+    /// Built lazily on first use rather than during setup
+    pub deferred: bool,
+}
+
+struct StartupLog {
+    phases: Vec<StartupPhase>,
+    setup_complete_ms: Option<u64>,
+    unlock_screen_ms: Option<u64>,
+}
+
+impl StartupLog {
+    const fn new() -> Self {
+        Self { phases: Vec::new(), setup_complete_ms: None, unlock_screen_ms: None }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StartupReport {
+    pub phases: Vec<StartupPhase>,
+    pub setup_complete_ms: Option<u64>,
+    /// Reported by the UI once the unlock screen is shown
+    pub unlock_screen_ms: Option<u64>,
+    pub target_ms: u64,
+    pub within_target: Option<bool>,
+    /// Slowest eager phase, the first place to look when over budget
+    pub slowest_phase: Option<String>,
+}
+
+/// Milliseconds since `start_startup_clock` (or the first profiling call)
+fn since_start() -> u64 {
+    PROCESS_START.get_or_init(Instant::now).elapsed().as_millis() as u64
+}
+
+/// Call first thing in `main` so offsets are from process start
+pub fn start_startup_clock() {
+    since_start();
+}
+
+fn record_phase(name: &str, started_ms: u64, duration_ms: u64, deferred: bool) {
+    if let Ok(mut log) = STARTUP_LOG.lock() {
+        log.phases.push(StartupPhase { name: name.to_string(), started_ms, duration_ms, deferred });
+    }
+}
+
+/// Run one setup step and record how long it took
+pub fn time_phase<T>(name: &str, f: impl FnOnce() -> T) -> T {
+    timed(name, false, f)
+}
+
+/// Run work moved off the launch path (e.g. on unlock) and record it as deferred
+pub fn time_deferred_phase<T>(name: &str, f: impl FnOnce() -> T) -> T {
+    timed(name, true, f)
+}
+
+fn timed<T>(name: &str, deferred: bool, f: impl FnOnce() -> T) -> T {
+    let started_ms = since_start();
+    let start = Instant::now();
+    let value = f();
+    record_phase(name, started_ms, start.elapsed().as_millis() as u64, deferred);
+    value
+}
+
+pub fn mark_setup_complete() {
+    let now = since_start();
+    if let Ok(mut log) = STARTUP_LOG.lock() {
+        log.setup_complete_ms.get_or_insert(now);
+    }
+}
+
+/// Only the first call counts; later unlock screens (after lock) are not startup
+pub fn mark_unlock_screen() -> u64 {
+    let now = since_start();
+    STARTUP_LOG.lock()
+        .map(|mut log| *log.unlock_screen_ms.get_or_insert(now))
+        .unwrap_or(now)
+}
+
+pub fn startup_report() -> StartupReport {
+    let (phases, setup_complete_ms, unlock_screen_ms) = STARTUP_LOG.lock()
+        .map(|log| (log.phases.clone(), log.setup_complete_ms, log.unlock_screen_ms))
+        .unwrap_or_default();
+    let slowest_phase = phases.iter()
+        .filter(|p| !p.deferred)
+        .max_by_key(|p| p.duration_ms)
+        .map(|p| p.name.clone());
+    
+    StartupReport {
+        within_target: unlock_screen_ms.map(|ms| ms <= STARTUP_TARGET_MS),
+        phases,
+        setup_complete_ms,
+        unlock_screen_ms,
+        target_ms: STARTUP_TARGET_MS,
+        slowest_phase,
+    }
+}
+
+/// State built on first access instead of at startup
+pub struct Deferred<T> {
+    name: &'static str,
+    cell: OnceLock<T>,
+    init: fn() -> T,
+}
+
+impl<T> Deferred<T> {
+    pub const fn new(name: &'static str, init: fn() -> T) -> Self {
+        Self { name, cell: OnceLock::new(), init }
+    }
+    
+    pub fn get(&self) -> &T {
+        self.cell.get_or_init(|| {
+            let started_ms = since_start();
+            let start = Instant::now();
+            let value = (self.init)();
+            record_phase(self.name, started_ms, start.elapsed().as_millis() as u64, true);
+            value
+        })
+    }
+    
+    /// Without forcing initialization
+    pub fn get_if_initialized(&self) -> Option<&T> {
+        self.cell.get()
+    }
+}
+
+/// Shared HTTP client (AI, SIEM). Building one loads the TLS root store,
+/// so it is created on the first request, not during setup. Callers set
+/// per-request timeouts.
+pub static HTTP_CLIENT: Deferred<reqwest::Client> = Deferred::new("http_client", reqwest::Client::new);
+
 // ============================================
 // Performance State
 // ============================================
 
 pub struct PerformanceState {
     pub query_cache: RwLock<QueryCache>,
-    pub background: BackgroundProcessor,
+    pub budget: RwLock<MemoryBudget>,
+    /// Worker thread starts with the first queued task
+    pub background: Deferred<BackgroundProcessor>,
+    /// Where `set_memory_budget` saves the budget; None keeps it in memory
+    budget_file: Option<PathBuf>,
 }
 
 impl Default for PerformanceState {
     fn default() -> Self {
+        Self::with_budget(MemoryBudget::default(), None)
+    }
+}
+
+impl PerformanceState {
+    fn with_budget(budget: MemoryBudget, budget_file: Option<PathBuf>) -> Self {
         Self {
-            query_cache: RwLock::new(QueryCache::new()),
-            background: BackgroundProcessor::new(),
+            query_cache: RwLock::new(QueryCache::with_budget(budget.query_cache)),
+            budget: RwLock::new(budget),
+            background: Deferred::new("background_processor", BackgroundProcessor::new),
+            budget_file,
+        }
+    }
+
+    /// State with the budget last saved in `app_dir`. A missing file means
+    /// the defaults; an unreadable or invalid one is logged and ignored.
+    pub fn load(app_dir: &Path) -> Self {
+        let path = app_dir.join(BUDGET_FILE);
+        let budget = match std::fs::read_to_string(&path) {
+            Ok(json) => serde_json::from_str::<MemoryBudget>(&json)
+                .map_err(|e| e.to_string())
+                .and_then(|budget| budget.validate().map(|()| budget))
+                .unwrap_or_else(|e| {
+                    log::warn!("Ignoring saved memory budget: {}", e);
+                    MemoryBudget::default()
+                }),
+            Err(_) => MemoryBudget::default(),
+        };
+        Self::with_budget(budget, Some(path))
+    }
+
+    /// Validate, save and apply a budget; caches over it are evicted down
+    /// to it immediately
+    pub fn apply_budget(&self, budget: MemoryBudget) -> Result<(), String> {
+        budget.validate()?;
+        if let Some(path) = &self.budget_file {
+            let json = serde_json::to_string_pretty(&budget).map_err(|e| e.to_string())?;
+            std::fs::write(path, json).map_err(|e| format!("Cannot save memory budget: {}", e))?;
+        }
+        self.query_cache.write().map_err(|e| e.to_string())?.set_budget(budget.query_cache);
+        *self.budget.write().map_err(|e| e.to_string())? = budget;
+        Ok(())
+    }
+
+    /// Cached result of `query` under `key`, running it on a miss. Keys
+    /// must change whenever the data behind them does; entries are not
+    /// invalidated on writes. Each insert checks the process against its
+    /// memory target, so caches are trimmed as they grow and not only when
+    /// the UI polls `get_performance_stats`.
+    pub fn cached<T: Serialize + DeserializeOwned>(
+        &self,
+        key: &str,
+        query: impl FnOnce() -> Result<T, String>,
+    ) -> Result<T, String> {
+        let hit = self.query_cache.write().map_err(|e| e.to_string())?.get(key);
+        if let Some(value) = hit.and_then(|hit| serde_json::from_value(hit.data).ok()) {
+            return Ok(value);
+        }
+
+        let start = Instant::now();
+        let value = query()?;
+        let data = serde_json::to_value(&value).map_err(|e| e.to_string())?;
+        let result = CachedQueryResult {
+            row_count: data.as_array().map_or(1, |rows| rows.len()),
+            data,
+            query_time_ms: start.elapsed().as_millis() as u64,
+        };
+        self.query_cache.write().map_err(|e| e.to_string())?.set(key.to_string(), result);
+        self.relieve_memory_pressure(get_memory_stats().rss_mb)?;
+        Ok(value)
+    }
+
+    /// Pending task count without starting the worker
+    pub fn pending_tasks(&self) -> usize {
+        self.background.get_if_initialized().map(|b| b.pending_count()).unwrap_or(0)
+    }
+
+    /// Over the process target, halve what the caches hold (least recently
+    /// used first). Returns the bytes freed.
+    pub fn relieve_memory_pressure(&self, rss_mb: f64) -> Result<usize, String> {
+        let target_mb = self.budget.read().map_err(|e| e.to_string())?.target_mb;
+        if rss_mb <= target_mb {
+            return Ok(0);
         }
+        let mut cache = self.query_cache.write().map_err(|e| e.to_string())?;
+        let half = cache.bytes() / 2;
+        let freed = cache.shrink_to(half);
+        log::warn!(
+            "Resident memory {:.0} MB over {:.0} MB target; freed {} cached bytes",
+            rss_mb, target_mb, freed
+        );
+        Ok(freed)
     }
 }
 
@@ -516,28 +950,35 @@ impl Default for PerformanceState {
 // Tauri Commands
 // ============================================
 
-/// Get performance statistics
+/// Get performance statistics. Caches are also trimmed here when the
+/// process is over its memory target (as on every cache insert).
 #[tauri::command]
 pub fn get_performance_stats(
     state: State<'_, PerformanceState>,
 ) -> Result<PerformanceStats, String> {
+    let memory = get_memory_stats();
+    state.relieve_memory_pressure(memory.rss_mb)?;
+
     let cache_stats = state
         .query_cache
         .read()
         .map_err(|e| e.to_string())?
         .stats();
-    
-    let memory = get_memory_stats();
-    let cache_entries = cache_stats.entries;
-    
+    let budget = *state.budget.read().map_err(|e| e.to_string())?;
+
     Ok(PerformanceStats {
-        cache: cache_stats,
         memory: MemoryStats {
-            cache_entries,
-            pending_tasks: state.background.pending_count(),
+            cache_entries: cache_stats.entries,
+            cache_bytes: cache_stats.bytes,
+            pending_tasks: state.pending_tasks(),
+            target_mb: budget.target_mb,
+            over_target: memory.rss_mb > budget.target_mb,
             ..memory
         },
-        pending_background_tasks: state.background.pending_count(),
+        cache: cache_stats,
+        budget,
+        pending_background_tasks: state.pending_tasks(),
+        startup: startup_report(),
     })
 }
 
@@ -545,7 +986,31 @@ pub fn get_performance_stats(
 pub struct PerformanceStats {
     pub cache: CacheStats,
     pub memory: MemoryStats,
+    pub budget: MemoryBudget,
     pub pending_background_tasks: usize,
+    pub startup: StartupReport,
+}
+
+/// Change the memory target and cache budgets. The budget is saved and
+/// applies again on the next start; caches over their new budget are
+/// evicted down to it immediately.
+#[tauri::command]
+pub fn set_memory_budget(
+    state: State<'_, PerformanceState>,
+    budget: MemoryBudget,
+) -> Result<MemoryBudget, String> {
+    state.apply_budget(budget)?;
+    Ok(budget)
+}
+
+/// Called by the UI when the unlock screen is first shown
+#[tauri::command]
+pub fn mark_unlock_screen_ready() -> StartupReport {
+    let elapsed = mark_unlock_screen();
+    if elapsed > STARTUP_TARGET_MS {
+        log::warn!("Startup took {} ms (target {} ms)", elapsed, STARTUP_TARGET_MS);
+    }
+    startup_report()
 }
 
 /// Clear all caches
@@ -626,6 +1091,83 @@ mod tests {
         assert_eq!(stats.misses, 1);
     }
 
+    #[test]
+    fn test_cache_budget_evicts_lru() {
+        let value = "x".repeat(100);
+        let entry_bytes = "a".len() + value.weight_bytes();
+        let budget = CacheBudget { max_entries: 10, max_bytes: entry_bytes * 2 };
+        let mut cache: TtlCache<String> = TtlCache::with_budget(budget, 60);
+
+        cache.set("a".to_string(), value.clone());
+        cache.set("b".to_string(), value.clone());
+        assert!(cache.get("a").is_some()); // b is now least recently used
+        cache.set("c".to_string(), value.clone());
+        assert_eq!(cache.get("b"), None);
+        assert!(cache.get("a").is_some() && cache.get("c").is_some());
+
+        let stats = cache.stats();
+        assert_eq!((stats.entries, stats.bytes, stats.evictions), (2, entry_bytes * 2, 1));
+
+        // Replacing a key does not double-count it
+        cache.set("c".to_string(), value.clone());
+        assert_eq!(cache.bytes(), entry_bytes * 2);
+
+        // Larger than the whole budget: not cached, nothing evicted
+        cache.set("d".to_string(), "x".repeat(1000));
+        assert_eq!((cache.get("d"), cache.stats().entries), (None, 2));
+
+        assert_eq!(cache.shrink_to(entry_bytes), entry_bytes);
+        cache.set_budget(CacheBudget { max_entries: 0, max_bytes: usize::MAX });
+        assert_eq!((cache.stats().entries, cache.bytes()), (0, 0));
+
+        assert!(MemoryBudget::default().validate().is_ok());
+        assert!(MemoryBudget { target_mb: 0.0, ..MemoryBudget::default() }.validate().is_err());
+    }
+
+    #[test]
+    fn test_cached_query_runs_once_per_key() {
+        let state = PerformanceState::default();
+        let mut runs = 0;
+        for _ in 0..2 {
+            let rows: Vec<u32> = state.cached("notes:count:a", || { runs += 1; Ok(vec![1, 2]) }).unwrap();
+            assert_eq!(rows, vec![1, 2]);
+        }
+        assert_eq!(runs, 1);
+
+        let stats = state.query_cache.read().unwrap().stats();
+        assert_eq!((stats.entries, stats.hits, stats.misses), (1, 1, 1));
+        assert!(stats.bytes > 0);
+
+        // Failures are not cached
+        assert!(state.cached::<u32>("notes:count:b", || Err("locked".to_string())).is_err());
+        assert_eq!(state.query_cache.read().unwrap().stats().entries, 1);
+    }
+
+    #[test]
+    fn test_memory_budget_is_saved_and_reloaded() {
+        let dir = std::env::temp_dir().join(format!("evidify-budget-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let budget = MemoryBudget {
+            target_mb: 300.0,
+            query_cache: CacheBudget { max_entries: 5, max_bytes: 1024 },
+        };
+
+        PerformanceState::load(&dir).apply_budget(budget).unwrap();
+        let reloaded = PerformanceState::load(&dir);
+        assert_eq!(*reloaded.budget.read().unwrap(), budget);
+        assert_eq!(reloaded.query_cache.read().unwrap().stats().max_entries, 5);
+
+        // An invalid budget is refused and leaves the saved one in place
+        assert!(reloaded.apply_budget(MemoryBudget { target_mb: -1.0, ..budget }).is_err());
+        assert_eq!(*PerformanceState::load(&dir).budget.read().unwrap(), budget);
+
+        // A corrupt file falls back to the defaults
+        std::fs::write(dir.join(BUDGET_FILE), "{").unwrap();
+        assert_eq!(*PerformanceState::load(&dir).budget.read().unwrap(), MemoryBudget::default());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
     #[test]
     fn test_pagination() {
         let req = PaginationRequest {
@@ -660,4 +1202,28 @@ mod tests {
         let key = cache_key("notes", "list", &["client123", "signed"]);
         assert_eq!(key, "notes:list:client123:signed");
     }
+    
+    #[test]
+    fn test_deferred_init_is_recorded_once() {
+        static CALLS: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+        fn build() -> u32 {
+            CALLS.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            7
+        }
+        static VALUE: Deferred<u32> = Deferred::new("test_deferred_value", build);
+        
+        assert!(VALUE.get_if_initialized().is_none());
+        assert_eq!(*VALUE.get(), 7);
+        assert_eq!(*VALUE.get(), 7);
+        assert_eq!(CALLS.load(std::sync::atomic::Ordering::SeqCst), 1);
+        
+        time_phase("test_eager_phase", || ());
+        time_deferred_phase("test_unlock_phase", || ());
+        let report = startup_report();
+        assert!(report.phases.iter().any(|p| p.name == "test_unlock_phase" && p.deferred));
+        let deferred: Vec<_> = report.phases.iter().filter(|p| p.name == "test_deferred_value").collect();
+        assert_eq!(deferred.len(), 1);
+        assert!(deferred[0].deferred);
+        assert!(report.slowest_phase.is_some());
+    }
 }
@@ -0,0 +1,121 @@
+// Client Photo Module
+//
+// Optional client photo for waiting-room identification. Photos live in
+// their own vault table, not with client documents, so no document or
+// note export picks them up by accident. The only way to get photo bytes
+// for an export is `photo_for_export`, which either drops the photo or
+// returns a blurred copy; original bytes are only served for in-app display.
+
+use image::imageops::FilterType;
+use image::{DynamicImage, GenericImageView, ImageOutputFormat};
+use serde::{Deserialize, Serialize};
+use std::io::Cursor;
+
+/// Largest upload accepted before decoding
+pub const MAX_PHOTO_BYTES: usize = 10 * 1024 * 1024;
+
+/// Stored photos are downscaled to fit this box (px)
+const STORED_MAX_DIMENSION: u32 = 512;
+
+/// Blurred exports keep this many blocks along the long edge; at this size
+/// no face is recognizable, so no face detection is needed to be safe
+const BLUR_BLOCKS: u32 = 8;
+
+/// Photo metadata (bytes are fetched separately)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClientPhoto {
+    pub client_id: String,
+    pub mime_type: String,
+    pub width: u32,
+    pub height: u32,
+    pub file_size: i64,
+    pub content_hash: String,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+/// How a photo is treated when it reaches an export
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PhotoExportMode {
+    #[default]
+    Exclude,
+    Blur,
+}
+
+fn decode(data: &[u8]) -> Result<DynamicImage, String> {
+    if data.len() > MAX_PHOTO_BYTES {
+        return Err(format!("Photo is larger than {} MB", MAX_PHOTO_BYTES / (1024 * 1024)));
+    }
+    image::load_from_memory(data).map_err(|e| format!("Unsupported image: {}", e))
+}
+
+fn encode_png(img: &DynamicImage) -> Result<Vec<u8>, String> {
+    let mut out = Cursor::new(Vec::new());
+    img.write_to(&mut out, ImageOutputFormat::Png).map_err(|e| e.to_string())?;
+    Ok(out.into_inner())
+}
+
+/// Downscale and re-encode an upload as PNG. Re-encoding also drops EXIF
+/// (camera GPS, device ids) that the original file may carry.
+/// Returns (png bytes, width, height).
+pub fn normalize_photo(data: &[u8]) -> Result<(Vec<u8>, u32, u32), String> {
+    let img = decode(data)?;
+    let img = if img.width() > STORED_MAX_DIMENSION || img.height() > STORED_MAX_DIMENSION {
+        img.resize(STORED_MAX_DIMENSION, STORED_MAX_DIMENSION, FilterType::Triangle)
+    } else {
+        img
+    };
+    let (width, height) = img.dimensions();
+    Ok((encode_png(&img)?, width, height))
+}
+
+/// Pixelate the whole image down to a few blocks, keeping its dimensions
+pub fn blur_photo(data: &[u8]) -> Result<Vec<u8>, String> {
+    let img = decode(data)?;
+    let (width, height) = img.dimensions();
+    let small = img.resize(BLUR_BLOCKS, BLUR_BLOCKS, FilterType::Triangle);
+    let blurred = small.resize_exact(width, height, FilterType::Nearest).blur(width.max(height) as f32 / 32.0);
+    encode_png(&blurred)
+}
+
+/// The single exit point for photo bytes into any export
+pub fn photo_for_export(data: &[u8], mode: PhotoExportMode) -> Result<Option<Vec<u8>>, String> {
+    match mode {
+        PhotoExportMode::Exclude => Ok(None),
+        PhotoExportMode::Blur => blur_photo(data).map(Some),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgb, RgbImage};
+
+    fn checkerboard(size: u32) -> Vec<u8> {
+        let img = RgbImage::from_fn(size, size, |x, y| {
+            if (x / 4 + y / 4) % 2 == 0 { Rgb([255, 255, 255]) } else { Rgb([0, 0, 0]) }
+        });
+        encode_png(&DynamicImage::ImageRgb8(img)).unwrap()
+    }
+
+    #[test]
+    fn test_normalize_downscales() {
+        let (png, width, height) = normalize_photo(&checkerboard(1024)).unwrap();
+        assert_eq!((width, height), (512, 512));
+        assert_eq!(image::load_from_memory(&png).unwrap().dimensions(), (512, 512));
+        assert!(normalize_photo(b"not an image").is_err());
+    }
+
+    #[test]
+    fn test_export_excludes_or_blurs() {
+        let original = checkerboard(64);
+        assert!(photo_for_export(&original, PhotoExportMode::Exclude).unwrap().is_none());
+
+        let blurred = photo_for_export(&original, PhotoExportMode::Blur).unwrap().unwrap();
+        let img = image::load_from_memory(&blurred).unwrap().to_luma8();
+        assert_eq!(img.dimensions(), (64, 64));
+        // The 4px checkerboard averages out to mid-grey
+        assert!(img.pixels().all(|p| (64..=192).contains(&p.0[0])));
+    }
+}
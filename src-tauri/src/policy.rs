@@ -9,6 +9,9 @@
 // - Recording consent rules
 // - Supervision requirements
 // - Retention policies
+// - Early-warning (deterioration) rules
+// - Vault passphrase strength
+// - Dry-run simulation of a proposed bundle against recent decisions
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -81,6 +84,30 @@ pub struct OrganizationPolicy {
     /// Data retention rules
     pub retention_policy: RetentionPolicy,
     
+    /// Local AI guardrails
+    #[serde(default)]
+    pub ai_policy: AiPolicy,
+    
+    /// Reason-for-access on sensitive chart reads
+    #[serde(default)]
+    pub access_policy: AccessPolicy,
+    
+    /// Telehealth licensure (patient location) checks
+    #[serde(default)]
+    pub licensure_policy: LicensurePolicy,
+    
+    /// Where the vault itself may be stored
+    #[serde(default)]
+    pub residency_policy: DataResidencyPolicy,
+    
+    /// Relapse/deterioration alerts over the feature store
+    #[serde(default)]
+    pub early_warning_policy: EarlyWarningPolicy,
+    
+    /// Minimum vault passphrase strength; none unless configured
+    #[serde(default)]
+    pub passphrase_policy: PassphrasePolicy,
+    
     /// Custom policy extensions
     pub custom_rules: HashMap<String, serde_json::Value>,
 }
@@ -100,6 +127,12 @@ impl Default for OrganizationPolicy {
             recording_policy: RecordingPolicy::default(),
             supervision_policy: SupervisionPolicy::default(),
             retention_policy: RetentionPolicy::default(),
+            ai_policy: AiPolicy::default(),
+            access_policy: AccessPolicy::default(),
+            licensure_policy: LicensurePolicy::default(),
+            residency_policy: DataResidencyPolicy::default(),
+            early_warning_policy: EarlyWarningPolicy::default(),
+            passphrase_policy: PassphrasePolicy::default(),
             custom_rules: HashMap::new(),
         }
     }
@@ -181,6 +214,10 @@ pub struct AttestationPolicy {
     
     /// Require explanation for "not relevant"
     pub require_explanation_for_not_relevant: bool,
+    
+    /// Fill-in quick picks offered per detection category
+    #[serde(default = "crate::attestation::default_quick_pick_templates")]
+    pub quick_pick_templates: Vec<crate::attestation::QuickPickTemplate>,
 }
 
 impl Default for AttestationPolicy {
@@ -204,6 +241,7 @@ impl Default for AttestationPolicy {
             attestation_timeout: 0,
             allow_not_relevant: true,
             require_explanation_for_not_relevant: true,
+            quick_pick_templates: crate::attestation::default_quick_pick_templates(),
         }
     }
 }
@@ -264,6 +302,22 @@ pub struct SupervisionPolicy {
     
     /// Competency tracking enabled
     pub competency_tracking_enabled: bool,
+    
+    /// Days before the SLA deadline at which a cosign is flagged "due soon"
+    #[serde(default = "default_cosign_due_soon_days")]
+    pub cosign_due_soon_days: u32,
+    
+    /// Raise a local notification when a cosign becomes overdue
+    #[serde(default)]
+    pub notify_overdue_cosigns: bool,
+    
+    /// Present notes to supervisors with client identifiers pseudonymized
+    #[serde(default)]
+    pub blind_review: bool,
+}
+
+fn default_cosign_due_soon_days() -> u32 {
+    1
 }
 
 impl Default for SupervisionPolicy {
@@ -276,6 +330,9 @@ impl Default for SupervisionPolicy {
             max_review_delay_hours: 72,
             review_high_risk_notes: true,
             competency_tracking_enabled: true,
+            cosign_due_soon_days: default_cosign_due_soon_days(),
+            notify_overdue_cosigns: false,
+            blind_review: false,
         }
     }
 }
@@ -318,6 +375,327 @@ impl Default for RetentionPolicy {
     }
 }
 
+/// Local AI guardrails
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AiPolicy {
+    /// Pseudonymize identifiers in prompts before they reach the model
+    #[serde(default)]
+    pub scrub_prompts: bool,
+    
+    #[serde(default = "default_true")]
+    pub scrub_names: bool,
+    
+    #[serde(default = "default_true")]
+    pub scrub_dates: bool,
+    
+    /// Phone, email, SSN, record numbers, addresses, URLs
+    #[serde(default = "default_true")]
+    pub scrub_identifiers: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for AiPolicy {
+    fn default() -> Self {
+        Self {
+            scrub_prompts: false,
+            scrub_names: true,
+            scrub_dates: true,
+            scrub_identifiers: true,
+        }
+    }
+}
+
+/// Reason-for-access (break-the-glass style) controls
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccessPolicy {
+    /// Require a stated reason before opening charts of restricted clients
+    #[serde(default)]
+    pub require_reason: bool,
+    
+    /// Client statuses that count as restricted
+    #[serde(default = "default_restricted_statuses")]
+    pub restricted_statuses: Vec<String>,
+    
+    /// How long one stated reason covers further reads of the same chart
+    #[serde(default = "default_reason_ttl_minutes")]
+    pub reason_ttl_minutes: u32,
+}
+
+fn default_restricted_statuses() -> Vec<String> {
+    vec!["closed".to_string(), "archived".to_string()]
+}
+
+fn default_reason_ttl_minutes() -> u32 {
+    30
+}
+
+impl Default for AccessPolicy {
+    fn default() -> Self {
+        Self {
+            require_reason: false,
+            restricted_statuses: default_restricted_statuses(),
+            reason_ttl_minutes: default_reason_ttl_minutes(),
+        }
+    }
+}
+
+impl AccessPolicy {
+    /// Whether reading this client's chart needs a stated reason
+    pub fn requires_reason(&self, client_status: &str) -> bool {
+        self.require_reason
+            && self.restricted_statuses.iter().any(|s| s.eq_ignore_ascii_case(client_status))
+    }
+}
+
+/// What happens when the vault is stored somewhere the policy disallows
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResidencyAction {
+    Allow,
+    /// Start normally but show the residency warning
+    Warn,
+    /// Refuse to create or unlock the vault until it is relocated
+    Block,
+}
+
+/// Vault storage location controls
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DataResidencyPolicy {
+    #[serde(default = "default_residency_warn")]
+    pub cloud_sync: ResidencyAction,
+    #[serde(default = "default_residency_warn")]
+    pub network_share: ResidencyAction,
+    #[serde(default = "default_residency_warn")]
+    pub removable_media: ResidencyAction,
+    #[serde(default = "default_residency_allow")]
+    pub unknown: ResidencyAction,
+    
+    /// If set, the vault must live under one of these directories
+    /// (e.g. the encrypted internal volume)
+    #[serde(default)]
+    pub approved_locations: Vec<String>,
+    
+    /// Action when `approved_locations` is set and the vault is elsewhere
+    #[serde(default = "default_residency_block")]
+    pub outside_approved: ResidencyAction,
+}
+
+fn default_residency_allow() -> ResidencyAction {
+    ResidencyAction::Allow
+}
+
+fn default_residency_warn() -> ResidencyAction {
+    ResidencyAction::Warn
+}
+
+fn default_residency_block() -> ResidencyAction {
+    ResidencyAction::Block
+}
+
+impl Default for DataResidencyPolicy {
+    fn default() -> Self {
+        Self {
+            cloud_sync: default_residency_warn(),
+            network_share: default_residency_warn(),
+            removable_media: default_residency_warn(),
+            unknown: default_residency_allow(),
+            approved_locations: vec![],
+            outside_approved: default_residency_block(),
+        }
+    }
+}
+
+/// What happens when a telehealth patient is outside licensed jurisdictions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LicensureAction {
+    /// Record the check only
+    Allow,
+    /// Allow signing once the clinician attests to the basis for practice
+    RequireAttestation,
+    /// Refuse to sign the note
+    Block,
+}
+
+/// Interstate telehealth controls
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LicensurePolicy {
+    #[serde(default = "default_out_of_jurisdiction")]
+    pub out_of_jurisdiction: LicensureAction,
+    
+    /// Jurisdictions where PSYPACT APIT is accepted. Membership changes as
+    /// states join the compact, so organizations keep this list current.
+    #[serde(default = "default_psypact_states")]
+    pub psypact_states: Vec<String>,
+}
+
+fn default_out_of_jurisdiction() -> LicensureAction {
+    LicensureAction::RequireAttestation
+}
+
+fn default_psypact_states() -> Vec<String> {
+    [
+        "AL", "AZ", "AR", "CO", "CT", "DE", "DC", "GA", "ID", "IL", "IN", "KS",
+        "KY", "ME", "MD", "MN", "MO", "NE", "NV", "NH", "NJ", "NC", "OH", "OK",
+        "PA", "RI", "SC", "TN", "TX", "UT", "VT", "VA", "WA", "WV", "WI", "WY",
+    ].iter().map(|s| s.to_string()).collect()
+}
+
+impl Default for LicensurePolicy {
+    fn default() -> Self {
+        Self {
+            out_of_jurisdiction: default_out_of_jurisdiction(),
+            psypact_states: default_psypact_states(),
+        }
+    }
+}
+
+/// How prominently an early-warning alert is shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertSeverity {
+    Info,
+    Warning,
+    Critical,
+}
+
+/// What an early-warning rule looks for in a client's feature store
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum EarlyWarningCondition {
+    /// Latest score of `measure` is at least `min_increase` above the
+    /// lowest score in the `window_sessions` scored sessions before it
+    MeasureIncrease {
+        measure: String,
+        min_increase: i32,
+        window_sessions: usize,
+    },
+    /// Latest score of `measure` is at or above `threshold`
+    MeasureAtLeast { measure: String, threshold: i32 },
+    /// The last `sessions` sessions each carry a risk flag (of one of
+    /// `risk_types`, or any type when empty)
+    ConsecutiveRiskSessions {
+        sessions: usize,
+        #[serde(default)]
+        risk_types: Vec<crate::analysis::RiskType>,
+    },
+    /// At least `min_streak` missed sessions since the last attended one
+    MissedSessionStreak { min_streak: usize },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EarlyWarningRule {
+    /// Stable id shown on alerts and in evidence trails, e.g. "EW-001"
+    pub id: String,
+    pub title: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    pub severity: AlertSeverity,
+    pub condition: EarlyWarningCondition,
+    /// Shown on the prep sheet when the rule fires
+    #[serde(default)]
+    pub suggested_action: Option<String>,
+}
+
+/// Early-warning rules; organizations tune thresholds here instead of in code
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EarlyWarningPolicy {
+    #[serde(default = "default_early_warning_rules")]
+    pub rules: Vec<EarlyWarningRule>,
+}
+
+fn default_early_warning_rules() -> Vec<EarlyWarningRule> {
+    vec![
+        EarlyWarningRule {
+            id: "EW-001".to_string(),
+            title: "PHQ-9 increase of 5 or more".to_string(),
+            enabled: true,
+            severity: AlertSeverity::Warning,
+            condition: EarlyWarningCondition::MeasureIncrease {
+                measure: "PHQ-9".to_string(),
+                min_increase: 5,
+                window_sessions: 4,
+            },
+            suggested_action: Some("Review depressive symptoms and current treatment plan".to_string()),
+        },
+        EarlyWarningRule {
+            id: "EW-002".to_string(),
+            title: "Risk flagged in consecutive sessions".to_string(),
+            enabled: true,
+            severity: AlertSeverity::Critical,
+            condition: EarlyWarningCondition::ConsecutiveRiskSessions {
+                sessions: 2,
+                risk_types: vec![],
+            },
+            suggested_action: Some("Reassess risk and review the safety plan".to_string()),
+        },
+        EarlyWarningRule {
+            id: "EW-003".to_string(),
+            title: "Missed session streak".to_string(),
+            enabled: true,
+            severity: AlertSeverity::Warning,
+            condition: EarlyWarningCondition::MissedSessionStreak { min_streak: 2 },
+            suggested_action: Some("Reach out to re-engage the client".to_string()),
+        },
+    ]
+}
+
+impl Default for EarlyWarningPolicy {
+    fn default() -> Self {
+        Self {
+            rules: default_early_warning_rules(),
+        }
+    }
+}
+
+/// What a new vault passphrase must meet (see passphrase.rs for scoring).
+/// Fields left out of a configured policy take the recommended values.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PassphrasePolicy {
+    /// zxcvbn-style score, 0-4
+    #[serde(default = "default_min_passphrase_score")]
+    pub min_score: u8,
+    #[serde(default = "default_min_passphrase_length")]
+    pub min_length: usize,
+    /// Refuse passphrases on the bundled common-password list
+    #[serde(default = "default_true")]
+    pub reject_common: bool,
+}
+
+fn default_min_passphrase_score() -> u8 {
+    2
+}
+
+fn default_min_passphrase_length() -> usize {
+    10
+}
+
+impl PassphrasePolicy {
+    /// The minimum an organization gets by adding a passphrase policy
+    pub fn recommended() -> Self {
+        Self {
+            min_score: default_min_passphrase_score(),
+            min_length: default_min_passphrase_length(),
+            reject_common: true,
+        }
+    }
+}
+
+/// No minimum, so installs without an organization policy still get
+/// strength feedback but are never blocked by it
+impl Default for PassphrasePolicy {
+    fn default() -> Self {
+        Self {
+            min_score: 0,
+            min_length: 0,
+            reject_common: false,
+        }
+    }
+}
+
 // ============================================
 // Policy Engine
 // ============================================
@@ -336,8 +714,13 @@ pub struct PolicyEngine {
 
 impl PolicyEngine {
     pub fn new() -> Self {
+        Self::with_policy(OrganizationPolicy::default())
+    }
+    
+    /// Engine over a given bundle, e.g. a proposed one under simulation
+    pub fn with_policy(policy: OrganizationPolicy) -> Self {
         Self {
-            active_policy: OrganizationPolicy::default(),
+            active_policy: policy,
             policy_path: None,
             last_sync: None,
         }
@@ -481,6 +864,213 @@ pub struct PolicyVersionInfo {
     pub last_sync: Option<DateTime<Utc>>,
 }
 
+// ============================================
+// Policy Simulation
+// ============================================
+//
+// Replays recorded decisions against a proposed bundle without touching
+// the active engine: exports by their audited destination class, and
+// signed notes by their detections and attestations. Every decision is
+// evaluated under both bundles; only those whose outcome differs are
+// reported.
+//
+// The sign gate replayed is the policy part of the real one:
+// commands::sign_note refuses a note through `policy_sign_blockers`, and
+// the replay uses the same checks. The rest of that gate (length and
+// sections, note-type rules, detection anchors, licensure, co-author
+// signatures) does not depend on the bundle, so it is not replayed; a note
+// it would have stopped is still counted as signed.
+
+/// Days of history replayed when the caller doesn't say
+pub const DEFAULT_SIMULATION_DAYS: u32 = 90;
+
+/// Export destination classes written to the audit log by export commands
+const AUDITED_DESTINATION_CLASSES: [&str; 5] = ["safe", "cloud_sync", "network_share", "removable", "unknown"];
+
+/// An audited export, as replayed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedExport {
+    pub resource_id: String,
+    pub at: i64,
+    pub destination_class: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SimulatedDecisionKind {
+    Export,
+    SignGate,
+    Attestation,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimulatedChange {
+    pub kind: SimulatedDecisionKind,
+    /// Export resource or note id
+    pub resource_id: String,
+    /// Detection the decision was about (sign gates and attestations)
+    pub detection_id: Option<String>,
+    pub at: i64,
+    pub current: String,
+    pub proposed: String,
+    /// The proposed bundle is stricter here
+    pub stricter: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolicySimulation {
+    pub proposed_id: String,
+    pub proposed_version: String,
+    /// Unix ms; decisions at or after this were replayed
+    pub since: i64,
+    pub exports_replayed: usize,
+    /// Signed notes whose policy sign gate was replayed (see module notes
+    /// for the parts of the gate that are not)
+    pub signatures_replayed: usize,
+    pub attestations_replayed: usize,
+    pub changes: Vec<SimulatedChange>,
+    pub newly_blocked: usize,
+    pub newly_allowed: usize,
+    /// The bundle's expiry has passed; loading it would fail
+    pub bundle_expired: bool,
+}
+
+/// Outcome label and severity (0 = allow .. 3 = block)
+fn export_outcome(decision: &PolicyDecision) -> (&'static str, u8) {
+    match decision {
+        PolicyDecision::Allow => ("allow", 0),
+        PolicyDecision::Warn { .. } => ("warn", 1),
+        PolicyDecision::RequireApproval { .. } => ("require_approval", 2),
+        PolicyDecision::Block { .. } => ("block", 3),
+    }
+}
+
+/// Whether signing would have been blocked for want of an attestation
+fn sign_gate_outcome(engine: &PolicyEngine, detection_id: &str, attested: bool) -> (&'static str, u8) {
+    if !attested && engine.check_attestation(detection_id) == AttestationRequirement::Required {
+        ("blocked: required attestation missing", 3)
+    } else {
+        ("allow", 0)
+    }
+}
+
+/// Whether an attestation would have been accepted
+fn attestation_outcome(policy: &AttestationPolicy, attestation: &crate::models::Attestation) -> (&'static str, u8) {
+    if attestation.response != crate::models::AttestationResponse::NotClinicallyRelevant {
+        return ("accept", 0);
+    }
+    if !policy.allow_not_relevant {
+        ("reject: not-relevant dismissals not allowed", 3)
+    } else if policy.require_explanation_for_not_relevant
+        && attestation.response_note.as_deref().map(str::trim).unwrap_or("").is_empty()
+    {
+        ("reject: explanation required", 3)
+    } else {
+        ("accept", 0)
+    }
+}
+
+/// Why the policy refuses to let a note with `detection_ids` be signed
+/// with `attestations`: required attestations missing, and attestations
+/// the policy does not accept. Empty if signing may go ahead.
+pub fn policy_sign_blockers(
+    engine: &PolicyEngine,
+    detection_ids: &[String],
+    attestations: &[crate::models::Attestation],
+) -> Vec<String> {
+    let missing = detection_ids.iter()
+        .filter(|id| {
+            let attested = attestations.iter().any(|a| &a.detection_id == *id);
+            sign_gate_outcome(engine, id, attested).1 > 0
+        })
+        .map(|id| format!("{} requires an attestation", id));
+    let rejected = attestations.iter().filter_map(|attestation| {
+        let (outcome, rank) = attestation_outcome(&engine.get_policy().attestation_policy, attestation);
+        (rank > 0).then(|| format!(
+            "attestation for {} not accepted ({})",
+            attestation.detection_id,
+            outcome.trim_start_matches("reject: ")
+        ))
+    });
+    missing.chain(rejected).collect()
+}
+
+/// Replay `exports` and the sign gates and attestations of `signed_notes`
+/// under `active` and `proposed`
+pub fn simulate(
+    active: &OrganizationPolicy,
+    proposed: &OrganizationPolicy,
+    since: i64,
+    exports: &[RecordedExport],
+    signed_notes: &[crate::models::Note],
+) -> PolicySimulation {
+    let current = PolicyEngine::with_policy(active.clone());
+    let candidate = PolicyEngine::with_policy(proposed.clone());
+    let mut changes = Vec::new();
+    let mut push = |kind, resource_id: &str, detection_id: Option<&str>, at, (was, was_rank): (&str, u8), (now, now_rank): (&str, u8)| {
+        if was != now {
+            changes.push(SimulatedChange {
+                kind,
+                resource_id: resource_id.to_string(),
+                detection_id: detection_id.map(str::to_string),
+                at,
+                current: was.to_string(),
+                proposed: now.to_string(),
+                stricter: now_rank > was_rank,
+            });
+        }
+    };
+
+    let exports: Vec<&RecordedExport> = exports.iter()
+        .filter(|e| e.at >= since && AUDITED_DESTINATION_CLASSES.contains(&e.destination_class.as_str()))
+        .collect();
+    for export in &exports {
+        push(
+            SimulatedDecisionKind::Export, &export.resource_id, None, export.at,
+            export_outcome(&current.check_export(&export.destination_class)),
+            export_outcome(&candidate.check_export(&export.destination_class)),
+        );
+    }
+
+    let signed: Vec<&crate::models::Note> = signed_notes.iter()
+        .filter(|n| n.signed_at.is_some_and(|at| at >= since))
+        .collect();
+    let mut attestations_replayed = 0;
+    for note in &signed {
+        let at = note.signed_at.unwrap_or_default();
+        for detection_id in &note.detection_ids {
+            let attested = note.attestations.iter().any(|a| &a.detection_id == detection_id);
+            push(
+                SimulatedDecisionKind::SignGate, &note.id, Some(detection_id), at,
+                sign_gate_outcome(&current, detection_id, attested),
+                sign_gate_outcome(&candidate, detection_id, attested),
+            );
+        }
+        for attestation in &note.attestations {
+            attestations_replayed += 1;
+            push(
+                SimulatedDecisionKind::Attestation, &note.id, Some(&attestation.detection_id), attestation.attested_at,
+                attestation_outcome(&active.attestation_policy, attestation),
+                attestation_outcome(&proposed.attestation_policy, attestation),
+            );
+        }
+    }
+
+    let newly_blocked = changes.iter().filter(|c| c.stricter).count();
+    PolicySimulation {
+        proposed_id: proposed.id.clone(),
+        proposed_version: proposed.version.clone(),
+        since,
+        exports_replayed: exports.len(),
+        signatures_replayed: signed.len(),
+        attestations_replayed,
+        newly_allowed: changes.len() - newly_blocked,
+        newly_blocked,
+        changes,
+        bundle_expired: proposed.expires_at.is_some_and(|at| at < Utc::now()),
+    }
+}
+
 // ============================================
 // Tauri Commands
 // ============================================
@@ -548,6 +1138,48 @@ pub fn get_policy_version(
     Ok(engine.get_version_info())
 }
 
+/// Dry-run a proposed bundle: replay the last `since_days` of exports,
+/// sign gates and attestations under it and report what would have gone
+/// differently. The active policy is not changed.
+#[tauri::command]
+pub fn simulate_policy(
+    state: State<'_, crate::commands::AppState>,
+    policy_state: State<'_, PolicyState>,
+    bundle: OrganizationPolicy,
+    since_days: Option<u32>,
+) -> Result<PolicySimulation, String> {
+    let since_days = since_days.unwrap_or(DEFAULT_SIMULATION_DAYS);
+    let now = Utc::now().timestamp_millis();
+    let since = now - since_days as i64 * 86_400_000;
+
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    let conn = vault.get_connection().map_err(|e| format!("{}", e))?;
+    let exports: Vec<RecordedExport> = crate::audit::get_entries_between(conn, since, now)
+        .map_err(|e| format!("{}", e))?
+        .into_iter()
+        .filter(|e| matches!(e.event_type, crate::models::AuditEventType::ExportCreated))
+        .filter_map(|e| Some(RecordedExport {
+            destination_class: e.path_class?,
+            resource_id: e.resource_id,
+            at: e.timestamp,
+        }))
+        .collect();
+    let signed_notes: Vec<crate::models::Note> = vault.list_notes(None).map_err(|e| format!("{}", e))?
+        .into_iter()
+        .filter(|n| n.signed_at.is_some_and(|at| at >= since))
+        .collect();
+
+    let simulation = {
+        let engine = policy_state.engine.read().map_err(|e| e.to_string())?;
+        simulate(engine.get_policy(), &bundle, since, &exports, &signed_notes)
+    };
+    crate::audit::log_policy_simulation(
+        conn, &simulation.proposed_id, &simulation.proposed_version, since_days,
+        simulation.newly_blocked, simulation.newly_allowed,
+    ).map_err(|e| format!("Audit write failed: {}", e))?;
+    Ok(simulation)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -590,4 +1222,95 @@ mod tests {
             AttestationRequirement::Optional
         );
     }
+    
+    #[test]
+    fn test_access_reason_requirement() {
+        let mut access = AccessPolicy::default();
+        assert!(!access.requires_reason("closed"));
+        
+        access.require_reason = true;
+        assert!(access.requires_reason("Closed"));
+        assert!(access.requires_reason("archived"));
+        assert!(!access.requires_reason("active"));
+    }
+    
+    #[test]
+    fn test_simulate_policy() {
+        use crate::models::{Attestation, AttestationResponse, Note, NoteStatus, NoteType};
+        
+        let active = OrganizationPolicy::default();
+        let mut proposed = active.clone();
+        proposed.version = "2.0".to_string();
+        proposed.export_policy.cloud_sync = ExportAction::Block;
+        proposed.export_policy.removable_media = ExportAction::Allow;
+        proposed.attestation_policy.required_attestations.push("safety-si-passive".to_string());
+        proposed.attestation_policy.allow_not_relevant = false;
+        
+        let export = |id: &str, at, class: &str| RecordedExport {
+            resource_id: id.to_string(),
+            at,
+            destination_class: class.to_string(),
+        };
+        let exports = [
+            export("e1", 100, "cloud_sync"),
+            export("e2", 100, "removable"),
+            export("e3", 100, "safe"),
+            export("e4", 100, "encrypted_zip"),
+            export("e5", 1, "cloud_sync"),
+        ];
+        let note = Note {
+            id: "n1".to_string(),
+            client_id: "c1".to_string(),
+            session_date: "2026-01-01".to_string(),
+            note_type: NoteType::Progress,
+            raw_input: String::new(),
+            structured_note: None,
+            word_count: 0,
+            status: NoteStatus::Signed,
+            detection_ids: vec!["safety-si-direct".to_string(), "safety-si-passive".to_string()],
+            attestations: vec![Attestation {
+                detection_id: "safety-si-direct".to_string(),
+                response: AttestationResponse::NotClinicallyRelevant,
+                response_note: Some("Quoting a film".to_string()),
+                attested_at: 150,
+            }],
+            content_hash: String::new(),
+            signed_at: Some(200),
+            created_at: 100,
+            updated_at: 200,
+        };
+        
+        let replayed = note.clone();
+        let sim = simulate(&active, &proposed, 50, &exports, &[note]);
+        assert_eq!((sim.exports_replayed, sim.signatures_replayed, sim.attestations_replayed), (3, 1, 1));
+        assert_eq!(sim.proposed_version, "2.0");
+        assert!(!sim.bundle_expired);
+        
+        let kinds: Vec<(SimulatedDecisionKind, &str, bool)> = sim.changes.iter()
+            .map(|c| (c.kind, c.proposed.as_str(), c.stricter))
+            .collect();
+        assert_eq!(kinds, [
+            (SimulatedDecisionKind::Export, "block", true),
+            (SimulatedDecisionKind::Export, "allow", false),
+            (SimulatedDecisionKind::SignGate, "blocked: required attestation missing", true),
+            (SimulatedDecisionKind::Attestation, "reject: not-relevant dismissals not allowed", true),
+        ]);
+        assert_eq!(sim.changes[2].detection_id.as_deref(), Some("safety-si-passive"));
+        assert_eq!((sim.newly_blocked, sim.newly_allowed), (3, 1));
+        
+        // Nothing changes against itself
+        assert!(simulate(&active, &active, 0, &exports, &[]).changes.is_empty());
+        
+        // The real sign gate refuses what the replay reports as blocked
+        let blockers = policy_sign_blockers(
+            &PolicyEngine::with_policy(proposed), &replayed.detection_ids, &replayed.attestations,
+        );
+        assert_eq!(blockers, [
+            "safety-si-passive requires an attestation",
+            "attestation for safety-si-direct not accepted (not-relevant dismissals not allowed)",
+        ]);
+        assert!(policy_sign_blockers(
+            &PolicyEngine::with_policy(active), &replayed.detection_ids, &replayed.attestations,
+        ).is_empty());
+    }
 }
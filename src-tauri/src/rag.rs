@@ -5,7 +5,8 @@
 //
 // Architecture:
 // 1. Embedding generation via local ONNX model (all-MiniLM-L6-v2)
-// 2. Vector storage in SQLCipher vault
+// 2. Vector storage in SQLCipher vault, each vector additionally sealed
+//    with its client's scope key so queries decrypt only what they search
 // 3. HNSW-style approximate nearest neighbor search
 // 4. RAG prompts to LLM with retrieved context
 
@@ -14,6 +15,7 @@ use rusqlite::{Connection, params};
 use thiserror::Error;
 
 use crate::ai;
+use crate::crypto::{self, vectors::{ScopeKey, VectorKeyring}};
 
 #[derive(Error, Debug)]
 pub enum RAGError {
@@ -28,6 +30,9 @@ pub enum RAGError {
     
     #[error("Model not loaded")]
     ModelNotLoaded,
+    
+    #[error("Vector encryption error: {0}")]
+    Crypto(#[from] crypto::CryptoError),
 }
 
 /// Safely slice a string respecting UTF-8 character boundaries
@@ -195,13 +200,9 @@ fn generate_ollama_embedding_sync(text: &str) -> Result<Vec<f32>, RAGError> {
     Ok(result)
 }
 
-/// Generate TF-IDF style embedding from text
-/// Uses word frequencies and position weighting
-fn generate_tfidf_embedding(text: &str) -> Result<Vec<f32>, RAGError> {
-    use std::collections::HashMap;
-    
-    // Common clinical/psychology terms with weights
-    let domain_terms: HashMap<&str, f32> = [
+/// Common clinical/psychology terms with weights
+fn build_domain_terms() -> std::collections::HashMap<&'static str, f32> {
+    [
         // Mental health terms
         ("anxiety", 2.0), ("depression", 2.0), ("mood", 1.5), ("affect", 1.5),
         ("suicidal", 3.0), ("ideation", 2.5), ("trauma", 2.0), ("ptsd", 2.0),
@@ -220,7 +221,19 @@ fn generate_tfidf_embedding(text: &str) -> Result<Vec<f32>, RAGError> {
         // Relationships
         ("family", 1.3), ("relationship", 1.3), ("conflict", 1.5), ("support", 1.2),
         ("isolation", 1.5), ("attachment", 1.5),
-    ].iter().cloned().collect();
+    ].iter().cloned().collect()
+}
+
+/// Built on the first embedding rather than at startup
+static DOMAIN_TERMS: crate::performance::Deferred<std::collections::HashMap<&'static str, f32>> =
+    crate::performance::Deferred::new("rag_domain_terms", build_domain_terms);
+
+/// Generate TF-IDF style embedding from text
+/// Uses word frequencies and position weighting
+fn generate_tfidf_embedding(text: &str) -> Result<Vec<f32>, RAGError> {
+    use std::collections::HashMap;
+    
+    let domain_terms = DOMAIN_TERMS.get();
     
     let text_lower = text.to_lowercase();
     let words: Vec<&str> = text_lower
@@ -361,40 +374,106 @@ pub struct TextChunk {
 // Vector Storage & Search
 // ============================================
 
-/// Store embedding in vault
+/// Store embedding in vault, sealed under the note's client scope key
 pub fn store_embedding(
     conn: &Connection,
+    scope_key: &ScopeKey,
     note_id: &str,
     chunk: &TextChunk,
     embedding: &[f32],
 ) -> Result<String, RAGError> {
-    let id = uuid::Uuid::new_v4().to_string();
+    let id = crate::ids::new_id();
     let now = chrono::Utc::now().timestamp_millis();
     
-    // Serialize embedding to bytes
+    // Serialize embedding to bytes, then seal bound to this row
     let vector_bytes: Vec<u8> = embedding
         .iter()
         .flat_map(|f| f.to_le_bytes())
         .collect();
+    let sealed = scope_key.seal(&id, &vector_bytes)?;
     
     conn.execute(
-        "INSERT INTO embeddings (id, note_id, chunk_index, chunk_start, chunk_end, vector, model_id, created_at)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        "INSERT INTO embeddings (id, note_id, chunk_index, chunk_start, chunk_end, vector, model_id, created_at, key_scope)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
         params![
             &id,
             note_id,
             chunk.index,
             chunk.start as i32,
             chunk.end as i32,
-            vector_bytes,
+            sealed,
             EMBEDDING_MODEL_ID,
-            now
+            now,
+            scope_key.scope()
         ],
     )?;
     
     Ok(id)
 }
 
+/// Seal vectors stored before per-client encryption (NULL `key_scope`).
+/// Runs on unlock; rows whose note is gone can't be scoped and are dropped,
+/// so no plaintext vector outlives the migration.
+pub fn seal_legacy_vectors(conn: &Connection, keys: &VectorKeyring) -> Result<usize, RAGError> {
+    conn.execute(
+        "DELETE FROM embeddings WHERE key_scope IS NULL AND note_id NOT IN (SELECT id FROM notes)",
+        [],
+    )?;
+    let legacy: Vec<(String, Vec<u8>, String)> = {
+        let mut stmt = conn.prepare(
+            "SELECT e.id, e.vector, n.client_id FROM embeddings e
+             JOIN notes n ON e.note_id = n.id
+             WHERE e.key_scope IS NULL",
+        )?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?;
+        rows.collect::<Result<_, _>>()?
+    };
+    if legacy.is_empty() {
+        return Ok(0);
+    }
+    
+    let mut scope_keys: HashMap<String, ScopeKey> = HashMap::new();
+    let tx = conn.unchecked_transaction()?;
+    for (id, vector, client_id) in &legacy {
+        if !scope_keys.contains_key(client_id) {
+            scope_keys.insert(client_id.clone(), keys.scope_key(client_id)?);
+        }
+        let sealed = scope_keys[client_id].seal(id, vector)?;
+        tx.execute(
+            "UPDATE embeddings SET vector = ?1, key_scope = ?2 WHERE id = ?3",
+            params![sealed, client_id, id],
+        )?;
+    }
+    tx.commit()?;
+    
+    Ok(legacy.len())
+}
+
+/// Re-encrypt every sealed vector from `old` keys to `new` (vault key
+/// rotation). All or nothing: any vector that fails to open aborts the
+/// transaction.
+pub fn reseal_vectors(conn: &Connection, old: &VectorKeyring, new: &VectorKeyring) -> Result<usize, RAGError> {
+    let sealed: Vec<(String, Vec<u8>, String)> = {
+        let mut stmt = conn.prepare("SELECT id, vector, key_scope FROM embeddings WHERE key_scope IS NOT NULL")?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?;
+        rows.collect::<Result<_, _>>()?
+    };
+    
+    let mut scope_keys: HashMap<String, (ScopeKey, ScopeKey)> = HashMap::new();
+    let tx = conn.unchecked_transaction()?;
+    for (id, vector, scope) in &sealed {
+        if !scope_keys.contains_key(scope) {
+            scope_keys.insert(scope.clone(), (old.scope_key(scope)?, new.scope_key(scope)?));
+        }
+        let (old_key, new_key) = &scope_keys[scope];
+        let resealed = new_key.seal(id, &old_key.open(id, vector)?)?;
+        tx.execute("UPDATE embeddings SET vector = ?1 WHERE id = ?2", params![resealed, id])?;
+    }
+    tx.commit()?;
+    
+    Ok(sealed.len())
+}
+
 /// Delete embeddings for a note (e.g., when note is updated)
 pub fn delete_note_embeddings(conn: &Connection, note_id: &str) -> Result<usize, RAGError> {
     let count = conn.execute(
@@ -407,28 +486,135 @@ pub fn delete_note_embeddings(conn: &Connection, note_id: &str) -> Result<usize,
 /// Search for similar content across all notes
 pub fn search_similar(
     conn: &Connection,
+    keys: &VectorKeyring,
     query: &str,
     limit: usize,
     client_id: Option<&str>,
 ) -> Result<Vec<SearchResult>, RAGError> {
     // Generate query embedding
     let query_embedding = generate_embedding(query)?;
+    let embedding_rows = match client_id {
+        Some(id) => load_embedding_rows(conn, Some(id))?,
+        // Without a client, open only the partitions whose notes the
+        // full-text index ties to the query
+        None => {
+            let mut rows = Vec::new();
+            for partition in query_partitions(conn, query)? {
+                rows.extend(load_embedding_rows(conn, Some(&partition))?);
+            }
+            rows
+        }
+    };
+    
+    // Scope keys are derived lazily, once per partition loaded above
+    let mut scope_keys: HashMap<String, ScopeKey> = HashMap::new();
     
-    // Build query based on whether we're filtering by client
+    // Calculate similarity scores and rank
+    let mut results: Vec<(f32, SearchResult)> = Vec::new();
+    
+    for row in embedding_rows {
+        
+        // Open and deserialize embedding
+        let embedding = match &row.key_scope {
+            Some(scope) => {
+                if !scope_keys.contains_key(scope) {
+                    scope_keys.insert(scope.clone(), keys.scope_key(scope)?);
+                }
+                match scope_keys[scope].open(&row.id, &row.vector) {
+                    Ok(bytes) => bytes_to_embedding(&bytes),
+                    // One damaged row shouldn't take the whole search down;
+                    // reindexing the note rewrites it
+                    Err(e) => {
+                        log::warn!("Skipping embedding {} that failed to open: {}", row.id, e);
+                        continue;
+                    }
+                }
+            }
+            // Never read as plaintext; sealing on unlock or reindexing the
+            // note fixes a row that missed the migration
+            None => {
+                log::warn!("Skipping embedding {} that was never sealed", row.id);
+                continue;
+            }
+        };
+        
+        // Calculate cosine similarity
+        let score = cosine_similarity(&query_embedding, &embedding);
+        
+        // Extract chunk text from raw_input (safely handling UTF-8 boundaries)
+        let chunk_text = safe_string_slice(
+            &row.raw_input,
+            row.chunk_start as usize,
+            row.chunk_end as usize
+        );
+        
+        results.push((score, SearchResult {
+            note_id: row.note_id,
+            chunk_text,
+            score,
+            note_date: Some(row.session_date),
+            note_type: Some(row.note_type),
+            client_id: Some(row.client_id),
+        }));
+    }
+    
+    // Sort by score descending
+    results.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+    
+    // Take top N
+    let top_results: Vec<SearchResult> = results
+        .into_iter()
+        .take(limit)
+        .map(|(_, r)| r)
+        .collect();
+    
+    if top_results.is_empty() {
+        return Err(RAGError::NoResults);
+    }
+    
+    Ok(top_results)
+}
+
+/// Clients with a note the full-text index matches on any key term
+fn query_partitions(conn: &Connection, question: &str) -> Result<Vec<String>, RAGError> {
+    let terms = query_terms(question);
+    if terms.is_empty() {
+        return Ok(Vec::new());
+    }
+    let mut stmt = conn.prepare(
+        "SELECT DISTINCT n.client_id FROM notes n
+         JOIN clients c ON c.id = n.client_id
+         WHERE n.rowid IN (SELECT rowid FROM notes_fts WHERE notes_fts MATCH ?1)
+           AND n.deleted_at IS NULL AND c.deleted_at IS NULL"
+    )?;
+    let partitions = stmt.query_map(params![fts_expression(&terms)], |row| row.get(0))?
+        .collect::<Result<_, _>>()?;
+    Ok(partitions)
+}
+
+/// All indexed chunks, optionally limited to one client
+fn load_embedding_rows(conn: &Connection, client_id: Option<&str>) -> Result<Vec<EmbeddingRow>, RAGError> {
+    // Build query based on whether we're filtering by client. Trashed
+    // notes, and notes of trashed clients, are never retrieved.
     let sql = if client_id.is_some() {
         r#"
         SELECT e.note_id, e.chunk_start, e.chunk_end, e.vector,
-               n.session_date, n.note_type, n.client_id, n.raw_input
+               n.session_date, n.note_type, n.client_id, n.raw_input,
+               e.id, e.key_scope
         FROM embeddings e
         JOIN notes n ON e.note_id = n.id
-        WHERE n.client_id = ?1
+        JOIN clients c ON c.id = n.client_id
+        WHERE n.client_id = ?1 AND n.deleted_at IS NULL AND c.deleted_at IS NULL
         "#
     } else {
         r#"
         SELECT e.note_id, e.chunk_start, e.chunk_end, e.vector,
-               n.session_date, n.note_type, n.client_id, n.raw_input
+               n.session_date, n.note_type, n.client_id, n.raw_input,
+               e.id, e.key_scope
         FROM embeddings e
         JOIN notes n ON e.note_id = n.id
+        JOIN clients c ON c.id = n.client_id
+        WHERE n.deleted_at IS NULL AND c.deleted_at IS NULL
         "#
     };
     
@@ -447,6 +633,8 @@ pub fn search_similar(
             note_type: row.get(5)?,
             client_id: row.get(6)?,
             raw_input: row.get(7)?,
+            id: row.get(8)?,
+            key_scope: row.get(9)?,
         })
     };
     
@@ -462,49 +650,7 @@ pub fn search_similar(
         }
     }
     
-    // Calculate similarity scores and rank
-    let mut results: Vec<(f32, SearchResult)> = Vec::new();
-    
-    for row in embedding_rows {
-        
-        // Deserialize embedding
-        let embedding = bytes_to_embedding(&row.vector);
-        
-        // Calculate cosine similarity
-        let score = cosine_similarity(&query_embedding, &embedding);
-        
-        // Extract chunk text from raw_input (safely handling UTF-8 boundaries)
-        let chunk_text = safe_string_slice(
-            &row.raw_input,
-            row.chunk_start as usize,
-            row.chunk_end as usize
-        );
-        
-        results.push((score, SearchResult {
-            note_id: row.note_id,
-            chunk_text,
-            score,
-            note_date: Some(row.session_date),
-            note_type: Some(row.note_type),
-            client_id: Some(row.client_id),
-        }));
-    }
-    
-    // Sort by score descending
-    results.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
-    
-    // Take top N
-    let top_results: Vec<SearchResult> = results
-        .into_iter()
-        .take(limit)
-        .map(|(_, r)| r)
-        .collect();
-    
-    if top_results.is_empty() {
-        return Err(RAGError::NoResults);
-    }
-    
-    Ok(top_results)
+    Ok(embedding_rows)
 }
 
 struct EmbeddingRow {
@@ -516,6 +662,9 @@ struct EmbeddingRow {
     note_type: String,
     client_id: String,
     raw_input: String,
+    id: String,
+    /// Client whose scope key sealed `vector`; None for legacy plaintext
+    key_scope: Option<String>,
 }
 
 fn bytes_to_embedding(bytes: &[u8]) -> Vec<f32> {
@@ -545,6 +694,201 @@ pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
     dot / (norm_a * norm_b)
 }
 
+// ============================================
+// Query Routing
+// ============================================
+
+/// Retrieval strategy chosen from the question's wording
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryRoute {
+    /// Specific fact: full-text term matches, small context
+    FactualLookup,
+    /// "When/how has X changed": date-ordered retrieval across sessions
+    Timeline,
+    /// Overview: broad context, one passage per session
+    Summary,
+}
+
+impl QueryRoute {
+    /// (chunks retrieved, context token budget)
+    fn budget(&self) -> (usize, usize) {
+        match self {
+            QueryRoute::FactualLookup => (4, 800),
+            QueryRoute::Timeline => (30, 3000),
+            QueryRoute::Summary => (15, 3000),
+        }
+    }
+    
+    fn instruction(&self) -> &'static str {
+        match self {
+            QueryRoute::FactualLookup => "Answer briefly with the specific fact and the session it comes from.",
+            QueryRoute::Timeline => "Context is in session order. Answer chronologically, one line per relevant session, noting changes between sessions.",
+            QueryRoute::Summary => "Give a concise overview across all sessions provided, grouping related themes.",
+        }
+    }
+}
+
+lazy_static::lazy_static! {
+    static ref TIMELINE_CUES: regex::Regex = regex::Regex::new(
+        r"(?i)\b(when|timeline|over time|since|chronolog\w*|history of|first time|last time|how (?:has|have|did) .* (?:change|progress|evolve)\w*|trend\w*|sequence|across sessions|session by session)\b"
+    ).unwrap();
+    static ref SUMMARY_CUES: regex::Regex = regex::Regex::new(
+        r"(?i)\b(summar\w*|overview|overall|recap|main themes?|key points|tell me about|big picture|in general)\b"
+    ).unwrap();
+}
+
+/// Pick a route for a question. Timeline cues win over summary cues:
+/// "summarize how sleep changed" needs session order.
+pub fn classify_query(question: &str) -> QueryRoute {
+    if TIMELINE_CUES.is_match(question) {
+        QueryRoute::Timeline
+    } else if SUMMARY_CUES.is_match(question) {
+        QueryRoute::Summary
+    } else {
+        QueryRoute::FactualLookup
+    }
+}
+
+const QUERY_STOPWORDS: &[&str] = &[
+    "a", "an", "and", "are", "at", "did", "do", "does", "for", "from", "has", "have", "how",
+    "in", "is", "it", "of", "on", "or", "the", "their", "they", "this", "to", "was", "were",
+    "what", "which", "who", "with", "client", "client's", "patient", "notes",
+];
+
+fn query_terms(question: &str) -> Vec<String> {
+    question
+        .split(|c: char| !c.is_alphanumeric() && c != '\'' && c != '-')
+        .map(|w| w.to_lowercase())
+        .filter(|w| w.len() > 2 && !QUERY_STOPWORDS.contains(&w.as_str()))
+        .collect()
+}
+
+/// notes_fts expression matching any of `terms` as a word prefix
+fn fts_expression(terms: &[String]) -> String {
+    terms.iter()
+        .map(|t| format!("\"{}\"*", t.replace('"', "\"\"")))
+        .collect::<Vec<_>>()
+        .join(" OR ")
+}
+
+/// Term retrieval through the full-text index: chunks of the notes that
+/// notes_fts matches on any key term (as a word prefix), ranked by the
+/// share of terms the chunk contains, newest first on ties
+pub fn search_exact(
+    conn: &Connection,
+    question: &str,
+    limit: usize,
+    client_id: Option<&str>,
+) -> Result<Vec<SearchResult>, RAGError> {
+    let terms = query_terms(question);
+    if terms.is_empty() {
+        return Err(RAGError::NoResults);
+    }
+    let expression = fts_expression(&terms);
+    
+    let mut stmt = conn.prepare(
+        "SELECT e.note_id, e.chunk_start, e.chunk_end, n.session_date, n.note_type, n.client_id, n.raw_input
+         FROM embeddings e
+         JOIN notes n ON e.note_id = n.id
+         JOIN clients c ON c.id = n.client_id
+         WHERE n.rowid IN (SELECT rowid FROM notes_fts WHERE notes_fts MATCH ?1)
+           AND n.deleted_at IS NULL AND c.deleted_at IS NULL
+           AND (?2 IS NULL OR n.client_id = ?2)"
+    )?;
+    let rows = stmt.query_map(params![expression, client_id], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, i32>(1)?,
+            row.get::<_, i32>(2)?,
+            row.get::<_, String>(3)?,
+            row.get::<_, String>(4)?,
+            row.get::<_, String>(5)?,
+            row.get::<_, String>(6)?,
+        ))
+    })?.collect::<Result<Vec<_>, _>>()?;
+    
+    let mut results: Vec<SearchResult> = rows
+        .into_iter()
+        .filter_map(|(note_id, chunk_start, chunk_end, session_date, note_type, client_id, raw_input)| {
+            let chunk_text = safe_string_slice(&raw_input, chunk_start as usize, chunk_end as usize);
+            let lower = chunk_text.to_lowercase();
+            let matched = terms.iter().filter(|t| lower.contains(t.as_str())).count();
+            (matched > 0).then(|| SearchResult {
+                note_id,
+                chunk_text,
+                score: matched as f32 / terms.len() as f32,
+                note_date: Some(session_date),
+                note_type: Some(note_type),
+                client_id: Some(client_id),
+            })
+        })
+        .collect();
+    
+    results.sort_by(|a, b| {
+        b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| b.note_date.cmp(&a.note_date))
+    });
+    results.truncate(limit);
+    
+    if results.is_empty() {
+        return Err(RAGError::NoResults);
+    }
+    Ok(results)
+}
+
+/// Sessions a timeline keeps: as many 100-word chunks as fit the route's
+/// 3000-token context
+const TIMELINE_MAX_SESSIONS: usize = 16;
+
+/// Shape retrieved chunks for the route: timelines keep the best chunk
+/// of each of the most recent sessions, in date order; summaries keep one
+/// chunk per session so more sessions fit the budget; factual lookups are
+/// left as ranked.
+pub fn arrange_for_route(route: QueryRoute, results: Vec<SearchResult>) -> Vec<SearchResult> {
+    if route == QueryRoute::FactualLookup {
+        return results;
+    }
+    
+    // Results arrive best-first, so the first chunk seen per note is its best
+    let mut seen = std::collections::HashSet::new();
+    let mut per_note: Vec<SearchResult> = results
+        .into_iter()
+        .filter(|r| seen.insert(r.note_id.clone()))
+        .collect();
+    
+    if route == QueryRoute::Timeline {
+        // Cut to the most recent sessions before ordering oldest-first;
+        // the context budget would otherwise drop the newest ones
+        per_note.sort_by(|a, b| b.note_date.cmp(&a.note_date));
+        per_note.truncate(TIMELINE_MAX_SESSIONS);
+        per_note.reverse();
+    }
+    per_note
+}
+
+/// Route the question and retrieve its context chunks
+fn retrieve_routed(
+    conn: &Connection,
+    keys: &VectorKeyring,
+    question: &str,
+    client_id: Option<&str>,
+) -> Result<(QueryRoute, Vec<SearchResult>, usize), RAGError> {
+    let route = classify_query(question);
+    let (limit, max_tokens) = route.budget();
+    
+    let results = match route {
+        // Fall back to similarity when no chunk has the terms
+        QueryRoute::FactualLookup => match search_exact(conn, question, limit, client_id) {
+            Err(RAGError::NoResults) => search_similar(conn, keys, question, limit, client_id)?,
+            other => other?,
+        },
+        QueryRoute::Timeline | QueryRoute::Summary => search_similar(conn, keys, question, limit, client_id)?,
+    };
+    
+    Ok((route, arrange_for_route(route, results), max_tokens))
+}
+
 // ============================================
 // RAG Query Pipeline
 // ============================================
@@ -586,6 +930,11 @@ pub fn build_rag_context_with_profile(query: &str, results: Vec<SearchResult>, m
 
 /// Generate RAG prompt with retrieved context
 pub fn build_rag_prompt(context: &RAGContext, question: &str) -> String {
+    build_routed_prompt(context, question, None)
+}
+
+/// RAG prompt with a route-specific answering instruction
+pub fn build_routed_prompt(context: &RAGContext, question: &str, route: Option<QueryRoute>) -> String {
     let mut prompt = String::from(
         r#"You are a clinical documentation assistant. Answer the question based on the provided context from past session notes and client profile information.
 
@@ -617,6 +966,9 @@ RULES:
         prompt.push_str("\n");
     }
     
+    if let Some(route) = route {
+        prompt.push_str(&format!("\n{}\n", route.instruction()));
+    }
     prompt.push_str(&format!("\nQUESTION: {}\n\nANSWER:", question));
     
     prompt
@@ -625,12 +977,12 @@ RULES:
 /// Execute RAG query: search + generate answer
 pub async fn rag_query(
     conn: &Connection,
+    keys: &VectorKeyring,
     question: &str,
     client_id: Option<&str>,
     model: &str,
 ) -> Result<RAGAnswer, RAGError> {
-    // Search for relevant chunks - increased to 10 for better coverage
-    let results = search_similar(conn, question, 10, client_id)?;
+    let (route, results, max_tokens) = retrieve_routed(conn, keys, question, client_id)?;
     
     // Get client profile if client_id is provided
     let client_profile = if let Some(cid) = client_id {
@@ -639,11 +991,11 @@ pub async fn rag_query(
         None
     };
     
-    // Build context with profile (limit to ~3000 tokens for better answers)
-    let context = build_rag_context_with_profile(question, results, 3000, client_profile);
+    // Build context with profile within the route's budget
+    let context = build_rag_context_with_profile(question, results, max_tokens, client_profile);
     
     // Generate RAG prompt
-    let prompt = build_rag_prompt(&context, question);
+    let prompt = build_routed_prompt(&context, question, Some(route));
     
     // Call LLM with generate_answer (not structure_note!)
     let answer = ai::generate_answer(model, &prompt)
@@ -657,6 +1009,7 @@ pub async fn rag_query(
             note_date: r.note_date.clone(),
             relevance: r.score,
         }).collect(),
+        route,
     })
 }
 
@@ -706,12 +1059,12 @@ fn get_client_profile_text(conn: &Connection, client_id: &str) -> Result<String,
 /// Synchronous version of rag_query for use in Tauri commands
 pub fn rag_query_sync(
     conn: &Connection,
+    keys: &VectorKeyring,
     question: &str,
     client_id: Option<&str>,
     model: &str,
 ) -> Result<RAGAnswer, RAGError> {
-    // Search for relevant chunks
-    let results = search_similar(conn, question, 5, client_id)?;
+    let (route, results, max_tokens) = retrieve_routed(conn, keys, question, client_id)?;
     
     // Get client profile if client_id is provided
     let client_profile = if let Some(cid) = client_id {
@@ -720,11 +1073,11 @@ pub fn rag_query_sync(
         None
     };
     
-    // Build context with profile (limit to ~2000 tokens)
-    let context = build_rag_context_with_profile(question, results, 2000, client_profile);
+    // Build context with profile within the route's budget
+    let context = build_rag_context_with_profile(question, results, max_tokens, client_profile);
     
     // Generate RAG prompt
-    let prompt = build_rag_prompt(&context, question);
+    let prompt = build_routed_prompt(&context, question, Some(route));
     
     // Call LLM synchronously using tokio's block_in_place
     let answer = tokio::task::block_in_place(|| {
@@ -740,6 +1093,7 @@ pub fn rag_query_sync(
             note_date: r.note_date.clone(),
             relevance: r.score,
         }).collect(),
+        route,
     })
 }
 
@@ -747,6 +1101,8 @@ pub fn rag_query_sync(
 pub struct RAGAnswer {
     pub answer: String,
     pub sources: Vec<RAGSource>,
+    /// Retrieval route the question was sent down
+    pub route: QueryRoute,
 }
 
 #[derive(Debug, Clone, serde::Serialize)]
@@ -763,12 +1119,21 @@ pub struct RAGSource {
 /// Index a note for RAG search
 pub fn index_note(
     conn: &Connection,
+    keys: &VectorKeyring,
     note_id: &str,
     content: &str,
 ) -> Result<usize, RAGError> {
     // Delete existing embeddings
     delete_note_embeddings(conn, note_id)?;
     
+    // Vectors are sealed under the note's client partition
+    let client_id: String = conn.query_row(
+        "SELECT client_id FROM notes WHERE id = ?1",
+        params![note_id],
+        |row| row.get(0),
+    )?;
+    let scope_key = keys.scope_key(&client_id)?;
+    
     // Chunk the content
     let chunks = chunk_text(content, 100, 20); // 100 words per chunk, 20 word overlap
     
@@ -776,7 +1141,7 @@ pub fn index_note(
     let mut count = 0;
     for chunk in chunks {
         let embedding = generate_embedding(&chunk.text)?;
-        store_embedding(conn, note_id, &chunk, &embedding)?;
+        store_embedding(conn, &scope_key, note_id, &chunk, &embedding)?;
         count += 1;
     }
     
@@ -785,7 +1150,7 @@ pub fn index_note(
 }
 
 /// Reindex all notes (e.g., after model update)
-pub fn reindex_all_notes(conn: &Connection) -> Result<usize, RAGError> {
+pub fn reindex_all_notes(conn: &Connection, keys: &VectorKeyring) -> Result<usize, RAGError> {
     // Get all notes
     let mut stmt = conn.prepare("SELECT id, raw_input FROM notes")?;
     let notes: Vec<(String, String)> = stmt
@@ -795,7 +1160,7 @@ pub fn reindex_all_notes(conn: &Connection) -> Result<usize, RAGError> {
     
     let mut total = 0;
     for (note_id, content) in notes {
-        total += index_note(conn, &note_id, &content)?;
+        total += index_note(conn, keys, &note_id, &content)?;
     }
     
     log::info!("Reindexed all notes: {} total chunks", total);
@@ -863,6 +1228,110 @@ mod tests {
         assert_eq!(emb1, emb2);
     }
     
+    #[test]
+    fn test_seal_legacy_vectors_leaves_no_plaintext() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE notes (id TEXT PRIMARY KEY, client_id TEXT);
+             CREATE TABLE embeddings (id TEXT PRIMARY KEY, note_id TEXT, vector BLOB, key_scope TEXT);
+             INSERT INTO notes VALUES ('n1', 'c1');
+             INSERT INTO embeddings VALUES ('e1', 'n1', x'0000803f', NULL), ('orphan', 'gone', x'0000803f', NULL);",
+        ).unwrap();
+        let keys = VectorKeyring::from_vault_key(&crypto::VaultKey::generate()).unwrap();
+        
+        assert_eq!(seal_legacy_vectors(&conn, &keys).unwrap(), 1);
+        let rows: Vec<(String, Option<String>)> = conn.prepare("SELECT id, key_scope FROM embeddings").unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?))).unwrap()
+            .collect::<Result<_, _>>().unwrap();
+        assert_eq!(rows, vec![("e1".to_string(), Some("c1".to_string()))]);
+    }
+    
+    #[test]
+    fn test_reseal_vectors() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("CREATE TABLE embeddings (id TEXT PRIMARY KEY, vector BLOB, key_scope TEXT)").unwrap();
+        let old = VectorKeyring::from_vault_key(&crypto::VaultKey::generate()).unwrap();
+        let new = VectorKeyring::from_vault_key(&crypto::VaultKey::generate()).unwrap();
+        let sealed = old.scope_key("client-a").unwrap().seal("e1", b"vector").unwrap();
+        conn.execute("INSERT INTO embeddings VALUES ('e1', ?1, 'client-a')", params![sealed]).unwrap();
+        
+        assert_eq!(reseal_vectors(&conn, &old, &new).unwrap(), 1);
+        let stored: Vec<u8> = conn.query_row("SELECT vector FROM embeddings", [], |r| r.get(0)).unwrap();
+        assert_eq!(new.scope_key("client-a").unwrap().open("e1", &stored).unwrap(), b"vector");
+        
+        // Under the wrong old keys nothing changes
+        assert!(reseal_vectors(&conn, &old, &new).is_err());
+        let after: Vec<u8> = conn.query_row("SELECT vector FROM embeddings", [], |r| r.get(0)).unwrap();
+        assert_eq!(after, stored);
+    }
+    
+    #[test]
+    fn test_load_embedding_rows_skips_trash() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE clients (id TEXT PRIMARY KEY, deleted_at INTEGER);
+             CREATE TABLE notes (id TEXT PRIMARY KEY, client_id TEXT, session_date TEXT, note_type TEXT,
+                 raw_input TEXT, deleted_at INTEGER);
+             CREATE TABLE embeddings (id TEXT PRIMARY KEY, note_id TEXT, chunk_start INTEGER, chunk_end INTEGER,
+                 vector BLOB, key_scope TEXT);
+             INSERT INTO clients VALUES ('c1', NULL), ('c2', 1);
+             INSERT INTO notes VALUES ('n1', 'c1', '2024-01-01', 'progress', 'kept', NULL),
+                 ('n2', 'c1', '2024-01-08', 'progress', 'trashed', 1),
+                 ('n3', 'c2', '2024-01-15', 'progress', 'client trashed', NULL);
+             INSERT INTO embeddings VALUES ('e1', 'n1', 0, 4, x'00', NULL), ('e2', 'n2', 0, 7, x'00', NULL),
+                 ('e3', 'n3', 0, 14, x'00', NULL);",
+        ).unwrap();
+        
+        for client_id in [None, Some("c1")] {
+            let rows = load_embedding_rows(&conn, client_id).unwrap();
+            let ids: Vec<&str> = rows.iter().map(|r| r.note_id.as_str()).collect();
+            assert_eq!(ids, ["n1"]);
+        }
+        assert!(load_embedding_rows(&conn, Some("c2")).unwrap().is_empty());
+    }
+    
+    #[test]
+    fn test_search_similar_skips_unreadable_vectors() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE clients (id TEXT PRIMARY KEY, deleted_at INTEGER);
+             CREATE TABLE notes (id TEXT PRIMARY KEY, client_id TEXT, session_date TEXT, note_type TEXT,
+                 raw_input TEXT, structured_note TEXT, deleted_at INTEGER);
+             CREATE TABLE embeddings (id TEXT PRIMARY KEY, note_id TEXT, chunk_index INTEGER, chunk_start INTEGER,
+                 chunk_end INTEGER, vector BLOB, model_id TEXT, created_at INTEGER, key_scope TEXT);
+             INSERT INTO clients VALUES ('c1', NULL), ('c2', NULL);
+             INSERT INTO notes VALUES ('n1', 'c1', '2024-01-01', 'progress', 'Improved sleep', NULL, NULL),
+                 ('n2', 'c1', '2024-01-08', 'progress', 'Worse sleep', NULL, NULL),
+                 ('n3', 'c1', '2024-01-15', 'progress', 'Sleep unchanged', NULL, NULL),
+                 ('n4', 'c2', '2024-01-02', 'progress', 'Appetite stable', NULL, NULL);",
+        ).unwrap();
+        crate::fulltext::ensure_index(&conn).unwrap();
+        let keys = VectorKeyring::from_vault_key(&crypto::VaultKey::generate()).unwrap();
+        let store = |client: &str, note: &str, text: &str| {
+            let chunk = TextChunk { text: text.to_string(), start: 0, end: text.len(), index: 0 };
+            store_embedding(&conn, &keys.scope_key(client).unwrap(), note, &chunk, &generate_embedding(text).unwrap())
+                .unwrap();
+        };
+        store("c1", "n1", "Improved sleep");
+        store("c2", "n4", "Appetite stable");
+        conn.execute(
+            "INSERT INTO embeddings VALUES ('bad', 'n2', 0, 0, 11, x'00112233', 'test', 0, 'c1')", [],
+        ).unwrap();
+        // Legacy plaintext vector that missed sealing
+        let legacy: Vec<u8> = generate_embedding("Sleep unchanged").unwrap().iter().flat_map(|f| f.to_le_bytes()).collect();
+        conn.execute(
+            "INSERT INTO embeddings VALUES ('legacy', 'n3', 0, 0, 15, ?1, 'test', 0, NULL)", params![legacy],
+        ).unwrap();
+        
+        // c2 has no note about sleep, so its partition is never opened
+        let results = search_similar(&conn, &keys, "sleep", 10, None).unwrap();
+        let ids: Vec<&str> = results.iter().map(|r| r.note_id.as_str()).collect();
+        assert_eq!(ids, ["n1"]);
+        
+        let results = search_similar(&conn, &keys, "sleep", 10, Some("c2")).unwrap();
+        assert_eq!(results[0].note_id, "n4");
+    }
+    
     #[test]
     fn test_cosine_similarity() {
         let a = vec![1.0, 0.0, 0.0];
@@ -872,4 +1341,63 @@ mod tests {
         assert!((cosine_similarity(&a, &b) - 1.0).abs() < 0.001);
         assert!(cosine_similarity(&a, &c).abs() < 0.001);
     }
+    
+    #[test]
+    fn test_query_routing() {
+        assert_eq!(classify_query("What medication dose is she on?"), QueryRoute::FactualLookup);
+        assert_eq!(classify_query("How has his sleep changed since March?"), QueryRoute::Timeline);
+        assert_eq!(classify_query("When did panic attacks first come up?"), QueryRoute::Timeline);
+        assert_eq!(classify_query("Give me an overview of treatment"), QueryRoute::Summary);
+        
+        let result = |note: &str, date: &str, score: f32| SearchResult {
+            note_id: note.to_string(),
+            chunk_text: String::new(),
+            score,
+            note_date: Some(date.to_string()),
+            note_type: None,
+            client_id: None,
+        };
+        let ranked = vec![
+            result("n2", "2024-03-01", 0.9),
+            result("n1", "2024-01-15", 0.8),
+            result("n2", "2024-03-01", 0.7),
+        ];
+        let timeline = arrange_for_route(QueryRoute::Timeline, ranked.clone());
+        let order: Vec<&str> = timeline.iter().map(|r| r.note_id.as_str()).collect();
+        assert_eq!(order, vec!["n1", "n2"]);
+        assert_eq!(timeline[1].score, 0.9);
+        assert_eq!(arrange_for_route(QueryRoute::FactualLookup, ranked).len(), 3);
+        
+        // A long history keeps its latest sessions, still oldest first
+        let history: Vec<SearchResult> = (1..=TIMELINE_MAX_SESSIONS + 4)
+            .map(|i| result(&format!("n{}", i), &format!("2024-01-{:02}", i), 1.0 / i as f32))
+            .collect();
+        let timeline = arrange_for_route(QueryRoute::Timeline, history);
+        assert_eq!(timeline.len(), TIMELINE_MAX_SESSIONS);
+        assert_eq!(timeline[0].note_date.as_deref(), Some("2024-01-05"));
+        assert_eq!(timeline.last().unwrap().note_date.as_deref(), Some("2024-01-20"));
+    }
+    
+    #[test]
+    fn test_search_exact_uses_fulltext_index() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE clients (id TEXT PRIMARY KEY, deleted_at INTEGER);
+             CREATE TABLE notes (id TEXT PRIMARY KEY, client_id TEXT NOT NULL, session_date TEXT NOT NULL,
+                 note_type TEXT NOT NULL, raw_input TEXT NOT NULL, structured_note TEXT, deleted_at INTEGER);
+             CREATE TABLE embeddings (id TEXT PRIMARY KEY, note_id TEXT, chunk_start INTEGER, chunk_end INTEGER,
+                 vector BLOB, key_scope TEXT);
+             INSERT INTO clients VALUES ('c1', NULL);
+             INSERT INTO notes VALUES ('n1', 'c1', '2024-01-01', 'progress', 'Sertraline dose raised to 100mg', NULL, NULL),
+                 ('n2', 'c1', '2024-01-08', 'progress', 'Sleeping poorly this week', NULL, NULL);
+             INSERT INTO embeddings VALUES ('e1', 'n1', 0, 31, x'00', NULL), ('e2', 'n2', 0, 25, x'00', NULL);",
+        ).unwrap();
+        crate::fulltext::ensure_index(&conn).unwrap();
+        
+        let hits = search_exact(&conn, "What sertraline dose?", 4, None).unwrap();
+        assert_eq!(hits.iter().map(|r| r.note_id.as_str()).collect::<Vec<_>>(), ["n1"]);
+        assert_eq!(hits[0].score, 1.0);
+        assert_eq!(search_exact(&conn, "sleep", 4, Some("c1")).unwrap()[0].note_id, "n2");
+        assert!(matches!(search_exact(&conn, "lithium", 4, None), Err(RAGError::NoResults)));
+    }
 }
@@ -0,0 +1,227 @@
+// Readability Module
+//
+// Reading level and jargon checks for text that leaves the practice for
+// the client (records-request summaries, portal letters). Scores are
+// Flesch Reading Ease and Flesch-Kincaid grade; jargon is matched against
+// a fixed list of clinical terms and abbreviations, each with a
+// plain-language suggestion. Nothing is rewritten automatically - the
+// clinician decides what to change before export.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// Grade level client-facing text should not exceed unless overridden
+/// (patient-education guidance is 6th-8th grade)
+pub const DEFAULT_TARGET_GRADE: f64 = 8.0;
+
+/// Sentences longer than this many words are listed for splitting
+const LONG_SENTENCE_WORDS: usize = 25;
+
+// ============================================
+// Types
+// ============================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JargonFlag {
+    /// Term as it appears in the text
+    pub term: String,
+    /// Byte offsets into the analyzed text
+    pub start: usize,
+    pub end: usize,
+    pub suggestion: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReadabilityReport {
+    pub word_count: usize,
+    pub sentence_count: usize,
+    pub syllable_count: usize,
+    /// 0-100, higher is easier
+    pub flesch_reading_ease: f64,
+    pub flesch_kincaid_grade: f64,
+    pub target_grade: f64,
+    pub meets_target: bool,
+    pub jargon: Vec<JargonFlag>,
+    /// Sentences over the long-sentence threshold, as written
+    pub long_sentences: Vec<String>,
+}
+
+// ============================================
+// Jargon
+// ============================================
+
+/// (pattern, plain-language suggestion). Patterns are matched
+/// case-insensitively on word boundaries; multi-word terms come first so
+/// "flat affect" wins over any shorter overlap.
+const JARGON: &[(&str, &str)] = &[
+    ("psychomotor agitation", "restlessness"),
+    ("psychomotor retardation", "slowed movement and speech"),
+    ("cognitive distortions?", "unhelpful thinking patterns"),
+    ("(?:flat|blunted|constricted|labile) affect", "how your emotions showed during the session"),
+    ("suicidal ideation", "thoughts of suicide"),
+    ("homicidal ideation", "thoughts of harming others"),
+    ("behavioral activation", "planning activities that lift your mood"),
+    ("presents with", "shows"),
+    ("anhedonia", "loss of interest or pleasure"),
+    ("dysphoric", "low or unhappy"),
+    ("euthymic", "steady mood"),
+    ("ideation", "thoughts"),
+    ("hypervigilance", "always feeling on guard"),
+    ("rumination", "going over the same worries again and again"),
+    ("psychoeducation", "learning about the condition"),
+    ("comorbid(?:ity)?", "occurring together"),
+    ("etiology", "cause"),
+    ("prognosis", "expected outlook"),
+    ("somatic", "physical"),
+    ("titrat(?:e|ed|ion)", "adjust the dose step by step"),
+    ("contraindicated", "not recommended"),
+    ("modality", "type of treatment"),
+    ("sequelae", "after-effects"),
+    ("remission", "symptoms have eased"),
+    ("pt", "you"),
+    ("hx", "history"),
+    ("dx", "diagnosis"),
+    ("tx", "treatment"),
+    ("sx", "symptoms"),
+    ("f/u", "follow-up"),
+    ("prn", "as needed"),
+    ("si/hi", "thoughts of harming yourself or others"),
+];
+
+lazy_static::lazy_static! {
+    static ref JARGON_PATTERNS: Vec<(Regex, &'static str)> = JARGON.iter()
+        .map(|(pattern, suggestion)| {
+            (Regex::new(&format!(r"(?i)\b{}\b", pattern)).unwrap(), *suggestion)
+        })
+        .collect();
+    static ref WORD: Regex = Regex::new(r"[A-Za-z][A-Za-z'\-]*").unwrap();
+    static ref VOWEL_GROUP: Regex = Regex::new(r"[aeiouy]+").unwrap();
+}
+
+/// Jargon matches, earliest first, without overlaps
+pub fn find_jargon(text: &str) -> Vec<JargonFlag> {
+    let mut flags: Vec<JargonFlag> = Vec::new();
+    for (re, suggestion) in JARGON_PATTERNS.iter() {
+        for m in re.find_iter(text) {
+            if flags.iter().any(|f| m.start() < f.end && f.start < m.end()) {
+                continue;
+            }
+            flags.push(JargonFlag {
+                term: m.as_str().to_string(),
+                start: m.start(),
+                end: m.end(),
+                suggestion: suggestion.to_string(),
+            });
+        }
+    }
+    flags.sort_by_key(|f| f.start);
+    flags
+}
+
+// ============================================
+// Scoring
+// ============================================
+
+/// Vowel-group syllable estimate; adequate for grade-level scoring
+pub fn count_syllables(word: &str) -> usize {
+    let word = word.to_lowercase();
+    let word = word.trim_matches(|c: char| !c.is_ascii_alphabetic());
+    if word.len() <= 3 {
+        return 1;
+    }
+    let mut count = VOWEL_GROUP.find_iter(word).count();
+    // Silent trailing e ("hope"), but not "-le" ("table")
+    if word.ends_with('e') && !word.ends_with("le") && count > 1 {
+        count -= 1;
+    }
+    count.max(1)
+}
+
+fn split_sentences(text: &str) -> Vec<&str> {
+    text.split(['.', '!', '?', '\n'])
+        .map(str::trim)
+        .filter(|s| WORD.is_match(s))
+        .collect()
+}
+
+/// Score `text` against `target_grade`
+pub fn analyze(text: &str, target_grade: f64) -> ReadabilityReport {
+    let sentences = split_sentences(text);
+    let words: Vec<&str> = WORD.find_iter(text).map(|m| m.as_str()).collect();
+    let word_count = words.len();
+    let sentence_count = sentences.len().max(1);
+    let syllable_count: usize = words.iter().map(|w| count_syllables(w)).sum();
+
+    let (reading_ease, grade) = if word_count == 0 {
+        (100.0, 0.0)
+    } else {
+        let words_per_sentence = word_count as f64 / sentence_count as f64;
+        let syllables_per_word = syllable_count as f64 / word_count as f64;
+        (
+            206.835 - 1.015 * words_per_sentence - 84.6 * syllables_per_word,
+            (0.39 * words_per_sentence + 11.8 * syllables_per_word - 15.59).max(0.0),
+        )
+    };
+
+    let long_sentences = sentences.iter()
+        .filter(|s| WORD.find_iter(s).count() > LONG_SENTENCE_WORDS)
+        .map(|s| s.to_string())
+        .collect();
+
+    ReadabilityReport {
+        word_count,
+        sentence_count: sentences.len(),
+        syllable_count,
+        flesch_reading_ease: (reading_ease * 10.0).round() / 10.0,
+        flesch_kincaid_grade: (grade * 10.0).round() / 10.0,
+        target_grade,
+        meets_target: grade <= target_grade,
+        jargon: find_jargon(text),
+        long_sentences,
+    }
+}
+
+// ============================================
+// Tauri Commands
+// ============================================
+
+/// Readability and jargon check for a client-facing draft
+#[tauri::command]
+pub fn analyze_readability(text: String, target_grade: Option<f64>) -> Result<ReadabilityReport, String> {
+    Ok(analyze(&text, target_grade.unwrap_or(DEFAULT_TARGET_GRADE)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grade_level_scoring() {
+        let plain = analyze("We met today. You said you slept well. We will meet again next week.", DEFAULT_TARGET_GRADE);
+        assert_eq!(plain.sentence_count, 3);
+        assert!(plain.meets_target);
+        assert!(plain.flesch_reading_ease > 80.0);
+
+        let dense = analyze(
+            "Client presents with persistent dysphoric mood, significant anhedonia, and intermittent \
+             psychomotor retardation, consistent with a comorbid presentation necessitating \
+             continued psychotherapeutic intervention and medication titration.",
+            DEFAULT_TARGET_GRADE,
+        );
+        assert!(!dense.meets_target);
+        assert!(dense.flesch_kincaid_grade > plain.flesch_kincaid_grade);
+        assert_eq!(dense.long_sentences.len(), 0);
+    }
+
+    #[test]
+    fn test_jargon_flags_with_suggestions() {
+        let text = "Pt denies SI/HI. Flat affect noted; f/u in two weeks.";
+        let flags = find_jargon(text);
+        let terms: Vec<&str> = flags.iter().map(|f| f.term.as_str()).collect();
+        assert_eq!(terms, vec!["Pt", "SI/HI", "Flat affect", "f/u"]);
+        assert_eq!(&text[flags[1].start..flags[1].end], "SI/HI");
+        assert_eq!(flags[3].suggestion, "follow-up");
+        // Plain words containing a jargon abbreviation are not flagged
+        assert!(find_jargon("The tx of the text was fine").iter().all(|f| f.term == "tx"));
+    }
+}
@@ -0,0 +1,424 @@
+// Ethics Re-analysis Module
+//
+// When the detection rules change (a new app version ships an updated
+// pattern pack), admins want to know what older signed notes would flag
+// now. A re-analysis run goes through the signed and amended notes in a
+// session-date range in the background, runs the current text patterns
+// over each, and stores what differs from the note's recorded detections
+// as advisory findings. Signed notes are never touched: their recorded
+// detections, attestations and content hash stay as they were at signing.
+//
+// Absence rules are left out on both sides - they depend on the client's
+// risk history at the time of writing, which a later pass cannot replay.
+//
+// The job takes the vault lock one batch at a time so the app stays
+// usable while it runs. If the vault is locked mid-run the run stops; it
+// is marked interrupted the next time a run is started.
+
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::ethics;
+use crate::models::{DetectionSeverity, Note, StoredDetection};
+use crate::vault::{Vault, VaultError};
+
+/// Notes analyzed per vault lock
+const REANALYSIS_BATCH: usize = 25;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReanalysisRange {
+    /// Inclusive session-date bounds, YYYY-MM-DD
+    pub from_date: Option<String>,
+    pub to_date: Option<String>,
+    pub client_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FindingChange {
+    /// Flagged by the current rules, not recorded on the note
+    New,
+    /// Recorded on the note, no longer flagged by the current rules
+    Cleared,
+}
+
+impl FindingChange {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FindingChange::New => "new",
+            FindingChange::Cleared => "cleared",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Self {
+        match s {
+            "cleared" => FindingChange::Cleared,
+            _ => FindingChange::New,
+        }
+    }
+}
+
+/// Advisory only: stored beside the note, never merged into it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdvisoryFinding {
+    pub id: String,
+    pub run_id: String,
+    pub note_id: String,
+    pub client_id: String,
+    pub session_date: String,
+    pub pattern_id: String,
+    pub change: FindingChange,
+    /// Severity under the current rules (new findings only)
+    pub severity: Option<DetectionSeverity>,
+    /// Match offsets in the analyzed text (new findings only)
+    pub match_start: Option<usize>,
+    pub match_end: Option<usize>,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunStatus {
+    Running,
+    Completed,
+    Failed,
+}
+
+impl RunStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RunStatus::Running => "running",
+            RunStatus::Completed => "completed",
+            RunStatus::Failed => "failed",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Self {
+        match s {
+            "running" => RunStatus::Running,
+            "completed" => RunStatus::Completed,
+            _ => RunStatus::Failed,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PatternChange {
+    pub pattern_id: String,
+    pub severity: Option<DetectionSeverity>,
+    /// Notes where the pattern is newly flagged
+    pub new_in_notes: usize,
+    /// Notes where a recorded detection of the pattern no longer fires
+    pub cleared_in_notes: usize,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReanalysisReport {
+    pub notes_scanned: usize,
+    pub notes_with_new_findings: usize,
+    pub notes_with_cleared_findings: usize,
+    pub new_attest: usize,
+    pub new_flag: usize,
+    pub new_coach: usize,
+    /// Most newly-flagged patterns first
+    pub patterns: Vec<PatternChange>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReanalysisRun {
+    pub id: String,
+    /// Rules the notes were re-analyzed with
+    pub rules_version: String,
+    pub range: ReanalysisRange,
+    pub status: RunStatus,
+    pub notes_total: usize,
+    pub notes_scanned: usize,
+    pub error: Option<String>,
+    pub started_at: i64,
+    pub finished_at: Option<i64>,
+    /// Set when the run completes
+    pub report: Option<ReanalysisReport>,
+}
+
+// ============================================
+// Comparison
+// ============================================
+
+/// Differences between a note's recorded detections and a fresh analysis
+/// of its text, by pattern. One finding per pattern per note.
+pub fn note_findings(run_id: &str, note: &Note, current: &[StoredDetection], now: i64) -> Vec<AdvisoryFinding> {
+    let mut recorded: Vec<&str> = note.detection_ids.iter()
+        .map(|id| ethics::pattern_id_of(id))
+        .filter(|p| !ethics::is_absence_rule(p))
+        .collect();
+    recorded.sort_unstable();
+    recorded.dedup();
+
+    let mut seen: Vec<&str> = Vec::new();
+    let mut findings = Vec::new();
+    let finding = |pattern_id: &str, change, detection: Option<&StoredDetection>| AdvisoryFinding {
+        id: uuid::Uuid::new_v4().to_string(),
+        run_id: run_id.to_string(),
+        note_id: note.id.clone(),
+        client_id: note.client_id.clone(),
+        session_date: note.session_date.clone(),
+        pattern_id: pattern_id.to_string(),
+        change,
+        severity: detection.map(|d| d.severity),
+        match_start: detection.map(|d| d.match_start),
+        match_end: detection.map(|d| d.match_end),
+        created_at: now,
+    };
+
+    for detection in current.iter().filter(|d| !ethics::is_absence_rule(&d.pattern_id)) {
+        let pattern_id = detection.pattern_id.as_str();
+        if seen.contains(&pattern_id) {
+            continue;
+        }
+        seen.push(pattern_id);
+        if recorded.binary_search(&pattern_id).is_err() {
+            findings.push(finding(pattern_id, FindingChange::New, Some(detection)));
+        }
+    }
+    for pattern_id in recorded.into_iter().filter(|p| !seen.contains(p)) {
+        findings.push(finding(pattern_id, FindingChange::Cleared, None));
+    }
+    findings
+}
+
+/// Summary of a run's findings over `notes_scanned` notes
+pub fn summarize(findings: &[AdvisoryFinding], notes_scanned: usize) -> ReanalysisReport {
+    let notes_with = |change: FindingChange| {
+        let mut notes: Vec<&str> = findings.iter()
+            .filter(|f| f.change == change)
+            .map(|f| f.note_id.as_str())
+            .collect();
+        notes.sort_unstable();
+        notes.dedup();
+        notes.len()
+    };
+    let new_with = |severity: DetectionSeverity| {
+        findings.iter().filter(|f| f.change == FindingChange::New && f.severity == Some(severity)).count()
+    };
+
+    let mut patterns: Vec<PatternChange> = Vec::new();
+    for f in findings {
+        let index = match patterns.iter().position(|p| p.pattern_id == f.pattern_id) {
+            Some(i) => i,
+            None => {
+                patterns.push(PatternChange {
+                    pattern_id: f.pattern_id.clone(),
+                    severity: None,
+                    new_in_notes: 0,
+                    cleared_in_notes: 0,
+                });
+                patterns.len() - 1
+            }
+        };
+        let entry = &mut patterns[index];
+        entry.severity = entry.severity.or(f.severity);
+        match f.change {
+            FindingChange::New => entry.new_in_notes += 1,
+            FindingChange::Cleared => entry.cleared_in_notes += 1,
+        }
+    }
+    patterns.sort_by(|a, b| {
+        b.new_in_notes.cmp(&a.new_in_notes)
+            .then(b.cleared_in_notes.cmp(&a.cleared_in_notes))
+            .then(a.pattern_id.cmp(&b.pattern_id))
+    });
+
+    ReanalysisReport {
+        notes_scanned,
+        notes_with_new_findings: notes_with(FindingChange::New),
+        notes_with_cleared_findings: notes_with(FindingChange::Cleared),
+        new_attest: new_with(DetectionSeverity::Attest),
+        new_flag: new_with(DetectionSeverity::Flag),
+        new_coach: new_with(DetectionSeverity::Coach),
+        patterns,
+    }
+}
+
+// ============================================
+// Background Job
+// ============================================
+
+/// At most one run at a time
+#[derive(Default)]
+pub struct ReanalysisState {
+    running: AtomicBool,
+}
+
+/// Analyze `note_ids` in batches, storing findings and progress as it
+/// goes, then record the outcome
+fn run_job(app: tauri::AppHandle, run_id: String, note_ids: Vec<String>) {
+    use tauri::Manager;
+
+    let state = app.state::<AppState>();
+    let mut scanned = 0;
+    let outcome = (|| -> Result<(), String> {
+        for batch in note_ids.chunks(REANALYSIS_BATCH) {
+            let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+            if !vault.is_unlocked() {
+                return Err("Vault was locked during re-analysis".to_string());
+            }
+            let now = chrono::Utc::now().timestamp_millis();
+            let mut findings = Vec::new();
+            for note_id in batch {
+                let note = match vault.get_note(note_id) {
+                    Ok(note) => note,
+                    // Trashed or purged since the run was planned
+                    Err(VaultError::NotFound(_)) => continue,
+                    Err(e) => return Err(format!("{}", e)),
+                };
+                let analysis = ethics::analyze(Vault::analyzed_text(&note));
+                findings.extend(note_findings(&run_id, &note, &analysis.stored_detections, now));
+            }
+            scanned += batch.len();
+            vault.record_reanalysis_batch(&run_id, &findings, scanned)
+                .map_err(|e| format!("{}", e))?;
+        }
+        Ok(())
+    })();
+
+    match state.vault.lock() {
+        Ok(vault) if vault.is_unlocked() => {
+            let result = match &outcome {
+                Ok(()) => vault.complete_reanalysis_run(&run_id).map(|_| ()),
+                Err(e) => vault.fail_reanalysis_run(&run_id, e),
+            };
+            if let Err(e) = result {
+                log::warn!("Could not record re-analysis outcome: {}", e);
+            }
+        }
+        _ => log::warn!("Re-analysis stopped after {} notes; vault unavailable", scanned),
+    }
+    if let Err(e) = outcome {
+        log::warn!("Re-analysis run failed: {}", e);
+    }
+    app.state::<ReanalysisState>().running.store(false, Ordering::Release);
+}
+
+// ============================================
+// Tauri Commands
+// ============================================
+
+use tauri::State;
+use crate::commands::AppState;
+
+/// Start re-analyzing signed notes in `range` with the current rules.
+/// Returns the run as started; poll `get_reanalysis_run` for progress and
+/// the report.
+#[tauri::command]
+pub fn reanalyze_notes(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    reanalysis: State<'_, ReanalysisState>,
+    range: ReanalysisRange,
+) -> Result<ReanalysisRun, String> {
+    if reanalysis.running.swap(true, Ordering::AcqRel) {
+        return Err("A re-analysis run is already in progress".to_string());
+    }
+    let started = (|| {
+        let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+        vault.interrupt_stale_reanalysis_runs().map_err(|e| format!("{}", e))?;
+        let note_ids = vault.list_signed_note_ids(&range).map_err(|e| format!("{}", e))?;
+        let run = vault.start_reanalysis_run(&range, note_ids.len()).map_err(|e| format!("{}", e))?;
+        Ok::<_, String>((run, note_ids))
+    })();
+    let (run, note_ids) = match started {
+        Ok(started) => started,
+        Err(e) => {
+            reanalysis.running.store(false, Ordering::Release);
+            return Err(e);
+        }
+    };
+
+    let run_id = run.id.clone();
+    std::thread::spawn(move || run_job(app, run_id, note_ids));
+    Ok(run)
+}
+
+#[tauri::command]
+pub fn get_reanalysis_run(state: State<'_, AppState>, run_id: String) -> Result<ReanalysisRun, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.get_reanalysis_run(&run_id).map_err(|e| format!("{}", e))
+}
+
+#[tauri::command]
+pub fn list_reanalysis_runs(state: State<'_, AppState>, limit: Option<u32>) -> Result<Vec<ReanalysisRun>, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.list_reanalysis_runs(limit.unwrap_or(20)).map_err(|e| format!("{}", e))
+}
+
+/// Findings from a run, optionally for one note
+#[tauri::command]
+pub fn list_advisory_findings(
+    state: State<'_, AppState>,
+    run_id: String,
+    note_id: Option<String>,
+) -> Result<Vec<AdvisoryFinding>, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.list_advisory_findings(&run_id, note_id.as_deref()).map_err(|e| format!("{}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{NoteStatus, NoteType};
+
+    fn note(detection_ids: &[&str]) -> Note {
+        Note {
+            id: "n1".to_string(),
+            client_id: "c1".to_string(),
+            session_date: "2024-02-01".to_string(),
+            note_type: NoteType::Progress,
+            raw_input: String::new(),
+            structured_note: None,
+            word_count: 0,
+            status: NoteStatus::Signed,
+            detection_ids: detection_ids.iter().map(|s| s.to_string()).collect(),
+            attestations: vec![],
+            content_hash: String::new(),
+            signed_at: Some(1),
+            created_at: 0,
+            updated_at: 0,
+        }
+    }
+
+    fn detection(pattern_id: &str, severity: DetectionSeverity, start: usize) -> StoredDetection {
+        StoredDetection {
+            id: format!("{}-{}", pattern_id, start),
+            pattern_id: pattern_id.to_string(),
+            severity,
+            match_start: start,
+            match_end: start + 4,
+            content_hash: None,
+            span_hash: None,
+        }
+    }
+
+    #[test]
+    fn test_findings_compare_by_pattern() {
+        let note = note(&["safety-si-euphemism-10", "doc-vague-12", "doc-vague-40"]);
+        let current = vec![
+            detection("safety-si-euphemism", DetectionSeverity::Attest, 11),
+            detection("safety-means-access", DetectionSeverity::Attest, 30),
+            detection("safety-means-access", DetectionSeverity::Attest, 60),
+        ];
+        let findings = note_findings("run", &note, &current, 5);
+
+        let changes: Vec<(&str, FindingChange)> = findings.iter().map(|f| (f.pattern_id.as_str(), f.change)).collect();
+        assert_eq!(changes, vec![("safety-means-access", FindingChange::New), ("doc-vague", FindingChange::Cleared)]);
+        assert_eq!(findings[0].match_start, Some(30));
+        assert_eq!(findings[1].severity, None);
+
+        let report = summarize(&findings, 3);
+        assert_eq!(report.notes_scanned, 3);
+        assert_eq!(report.notes_with_new_findings, 1);
+        assert_eq!(report.notes_with_cleared_findings, 1);
+        assert_eq!(report.new_attest, 1);
+        assert_eq!(report.patterns[0].pattern_id, "safety-means-access");
+        assert_eq!(report.patterns[0].severity, Some(DetectionSeverity::Attest));
+    }
+}
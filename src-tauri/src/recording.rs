@@ -365,7 +365,7 @@ impl DestructionCertificate {
     pub fn verify_integrity(&self) -> bool {
         let expected = self.calculate_integrity_hash();
         // Use constant-time comparison to prevent timing attacks
-        self.integrity_hash == expected
+        crate::crypto::digests_match(&self.integrity_hash, &expected)
     }
     
     /// Generate patient-friendly confirmation text
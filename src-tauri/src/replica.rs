@@ -0,0 +1,226 @@
+// Reporting Replica
+//
+// Metrics and caseload dashboards scan whole tables. Run against the live
+// vault they hold the vault mutex for the length of the scan, and note
+// saves queue behind them. Instead they read a snapshot: an encrypted,
+// read-only copy of the database kept under <data_dir>/replica. The vault
+// mutex is held only to look up the database file and key; the file is
+// then copied, still encrypted, by a separate connection holding a read
+// transaction (SnapshotSource::copy_to), so saves wait at most for the
+// copy to finish before committing. Queries on the copy hold only the
+// replica's own lock.
+//
+// The snapshot is refreshed when a reporting query finds it older than
+// REPLICA_MAX_AGE_SECS, or on demand (refresh_reporting_replica, e.g.
+// after the user asks for fresh numbers). It is dropped, and its file
+// deleted, when the vault locks and whenever records are purged or
+// destroyed, so a purged note never outlives its purge in the replica.
+//
+// Because a snapshot never changes, a report computed on one can be kept in
+// the query cache under the snapshot's id (with_cached_replica): it is
+// recomputed only once a newer snapshot replaces it.
+
+use chrono::{DateTime, Utc};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::commands::AppState;
+use crate::performance::{cache_key, PerformanceState};
+use crate::vault::{Vault, VaultError};
+
+/// Reporting queries refresh a snapshot older than this
+pub const REPLICA_MAX_AGE_SECS: i64 = 120;
+
+/// Subdirectory of the data directory holding the snapshot file
+const REPLICA_DIR: &str = "replica";
+
+// ============================================
+// Types
+// ============================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplicaStatus {
+    pub available: bool,
+    /// Unix seconds
+    pub refreshed_at: Option<i64>,
+    pub age_seconds: Option<i64>,
+    pub refresh_ms: Option<u64>,
+}
+
+struct Replica {
+    /// Distinguishes this snapshot from every other; keys cached reports
+    id: String,
+    vault: Vault,
+    path: PathBuf,
+    refreshed_at: DateTime<Utc>,
+    refresh_ms: u64,
+}
+
+impl Drop for Replica {
+    fn drop(&mut self) {
+        // Close the connection before deleting the file (Windows refuses
+        // to delete open files)
+        drop(std::mem::replace(&mut self.vault, Vault::new(PathBuf::new())));
+        if let Err(e) = std::fs::remove_file(&self.path) {
+            log::warn!("Reporting replica not deleted: {}", e);
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct ReplicaState {
+    replica: Mutex<Option<Replica>>,
+}
+
+// ============================================
+// Snapshot Management
+// ============================================
+
+fn is_fresh(refreshed_at: DateTime<Utc>, now: DateTime<Utc>, max_age_secs: i64) -> bool {
+    (now - refreshed_at).num_seconds() < max_age_secs
+}
+
+/// Remove snapshot files left behind by a crash
+fn clear_replica_dir(dir: &Path) {
+    let Ok(entries) = std::fs::read_dir(dir) else { return };
+    for path in entries.filter_map(|e| e.ok().map(|e| e.path())) {
+        if path.extension().is_some_and(|ext| ext == "db") {
+            std::fs::remove_file(&path).ok();
+        }
+    }
+}
+
+fn take_snapshot(app: &AppState) -> Result<Replica, String> {
+    let started = std::time::Instant::now();
+    let source = {
+        let vault = app.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+        let conn = vault.get_connection().map_err(|e| format!("{}", e))?;
+        // Reporting queries expect this table even before the first session is recorded
+        crate::metrics::init_metrics_table(conn).map_err(|e| format!("{}", e))?;
+        vault.snapshot_source().map_err(|e| format!("{}", e))?
+    };
+
+    let dir = source.data_dir().join(REPLICA_DIR);
+    clear_replica_dir(&dir);
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Cannot create replica directory: {}", e))?;
+    let id = uuid::Uuid::new_v4().to_string();
+    let path = dir.join(format!("reporting-{}.db", id));
+    let snapshot = source.copy_to(&path).map_err(|e| format!("{}", e))?;
+
+    Ok(Replica {
+        id,
+        vault: snapshot,
+        path,
+        refreshed_at: Utc::now(),
+        refresh_ms: started.elapsed().as_millis() as u64,
+    })
+}
+
+impl ReplicaState {
+    /// Run `query` on the snapshot, taking a new one first if there is
+    /// none or it is older than REPLICA_MAX_AGE_SECS
+    pub fn with_replica<T>(
+        &self,
+        app: &AppState,
+        query: impl FnOnce(&Vault) -> Result<T, VaultError>,
+    ) -> Result<T, String> {
+        self.with_fresh(app, |replica| query(&replica.vault).map_err(|e| format!("{}", e)))
+    }
+
+    /// `with_replica`, with the result kept in the query cache for the life
+    /// of the snapshot. Only for reports without PHI: entries outlive a
+    /// purge until evicted.
+    pub fn with_cached_replica<T: Serialize + DeserializeOwned>(
+        &self,
+        app: &AppState,
+        perf: &PerformanceState,
+        operation: &str,
+        params: &[&str],
+        query: impl FnOnce(&Vault) -> Result<T, VaultError>,
+    ) -> Result<T, String> {
+        self.with_fresh(app, |replica| {
+            let key = cache_key("replica", operation, &[&[replica.id.as_str()], params].concat());
+            perf.cached(&key, || query(&replica.vault).map_err(|e| format!("{}", e)))
+        })
+    }
+
+    fn with_fresh<T>(
+        &self,
+        app: &AppState,
+        f: impl FnOnce(&Replica) -> Result<T, String>,
+    ) -> Result<T, String> {
+        let mut slot = self.replica.lock().map_err(|_| "Replica mutex poisoned")?;
+        let fresh = slot.as_ref().is_some_and(|r| is_fresh(r.refreshed_at, Utc::now(), REPLICA_MAX_AGE_SECS));
+        if !fresh {
+            // Drop (and delete) the old snapshot before copying the new one
+            *slot = None;
+            *slot = Some(take_snapshot(app)?);
+        }
+        f(slot.as_ref().ok_or("Reporting replica unavailable")?)
+    }
+
+    pub fn refresh(&self, app: &AppState) -> Result<ReplicaStatus, String> {
+        let mut slot = self.replica.lock().map_err(|_| "Replica mutex poisoned")?;
+        *slot = None;
+        *slot = Some(take_snapshot(app)?);
+        Ok(status_of(slot.as_ref()))
+    }
+
+    /// Drop the snapshot; called when the vault locks and after purges.
+    /// Callers must not hold the vault mutex (with_replica takes the
+    /// replica lock before the vault's).
+    pub fn clear(&self) {
+        if let Ok(mut slot) = self.replica.lock() {
+            *slot = None;
+        }
+    }
+
+    pub fn status(&self) -> Result<ReplicaStatus, String> {
+        let slot = self.replica.lock().map_err(|_| "Replica mutex poisoned")?;
+        Ok(status_of(slot.as_ref()))
+    }
+}
+
+fn status_of(replica: Option<&Replica>) -> ReplicaStatus {
+    ReplicaStatus {
+        available: replica.is_some(),
+        refreshed_at: replica.map(|r| r.refreshed_at.timestamp()),
+        age_seconds: replica.map(|r| (Utc::now() - r.refreshed_at).num_seconds()),
+        refresh_ms: replica.map(|r| r.refresh_ms),
+    }
+}
+
+// ============================================
+// Tauri Commands
+// ============================================
+
+use tauri::State;
+
+/// Take a fresh reporting snapshot now
+#[tauri::command]
+pub fn refresh_reporting_replica(
+    state: State<'_, AppState>,
+    replica_state: State<'_, ReplicaState>,
+) -> Result<ReplicaStatus, String> {
+    replica_state.refresh(&state)
+}
+
+/// How old the numbers on reporting screens are
+#[tauri::command]
+pub fn get_reporting_replica_status(replica_state: State<'_, ReplicaState>) -> Result<ReplicaStatus, String> {
+    replica_state.status()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replica_freshness() {
+        let taken = Utc::now();
+        assert!(is_fresh(taken, taken + chrono::Duration::seconds(REPLICA_MAX_AGE_SECS - 1), REPLICA_MAX_AGE_SECS));
+        assert!(!is_fresh(taken, taken + chrono::Duration::seconds(REPLICA_MAX_AGE_SECS), REPLICA_MAX_AGE_SECS));
+        assert!(!status_of(None).available);
+    }
+}
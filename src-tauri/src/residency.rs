@@ -0,0 +1,290 @@
+// Data Residency Module
+//
+// Checks where the vault lives against the organization's residency
+// policy, using the same classifier as export destinations: a vault in a
+// OneDrive/iCloud-synced Documents folder, on a network share or on a USB
+// stick can be warned about or refused. The check runs at startup and
+// again before create/unlock, so a policy loaded after startup applies.
+//
+// Relocation copies the vault database to a new directory, verifies the
+// copy by hash, and records the new location in a pointer file in the
+// app data directory (which itself never moves).
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::models::PathClassification;
+use crate::policy::{DataResidencyPolicy, ResidencyAction};
+
+/// Pointer file in the app data directory naming a relocated vault
+const LOCATION_FILE: &str = "vault_location.json";
+
+/// Vault database files moved by relocation (SQLCipher main file + journals)
+const VAULT_FILES: &[&str] = &["vault.db", "vault.db-journal", "vault.db-wal", "vault.db-shm"];
+
+/// Directories of vault copies moved with it: maintenance backups and the
+/// reporting replica. Both hold the whole chart, so leaving them behind
+/// would leave it at the location the policy moved it away from.
+const VAULT_DIRS: &[&str] = &["backups", "replica"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResidencyStatus {
+    Compliant,
+    Warning,
+    Blocked,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResidencyCheck {
+    pub data_dir: String,
+    pub classification: PathClassification,
+    pub status: ResidencyStatus,
+    pub message: String,
+    pub checked_at: i64,
+}
+
+impl ResidencyCheck {
+    /// Error for create/unlock when the policy blocks this location
+    pub fn blocker(&self) -> Option<String> {
+        (self.status == ResidencyStatus::Blocked).then(|| {
+            format!("{} Relocate the vault to an approved location to continue.", self.message)
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct VaultLocation {
+    data_dir: PathBuf,
+    relocated_at: i64,
+}
+
+/// Directory the vault lives in: the relocated path if one is recorded,
+/// otherwise the app data directory
+pub fn resolve_data_dir(app_dir: &Path) -> PathBuf {
+    std::fs::read_to_string(app_dir.join(LOCATION_FILE))
+        .ok()
+        .and_then(|json| serde_json::from_str::<VaultLocation>(&json).ok())
+        .map(|loc| loc.data_dir)
+        .unwrap_or_else(|| app_dir.to_path_buf())
+}
+
+/// Evaluate an already-classified location against the policy
+pub fn evaluate(
+    data_dir: &Path,
+    classification: PathClassification,
+    reason: &str,
+    policy: &DataResidencyPolicy,
+) -> ResidencyCheck {
+    let approved = policy.approved_locations.iter().any(|root| data_dir.starts_with(root));
+
+    let (action, message) = if !policy.approved_locations.is_empty() && !approved {
+        (policy.outside_approved, "Vault is outside the approved storage locations.".to_string())
+    } else {
+        let action = match classification {
+            PathClassification::Safe => ResidencyAction::Allow,
+            PathClassification::CloudSync => policy.cloud_sync,
+            PathClassification::NetworkShare => policy.network_share,
+            PathClassification::RemovableMedia => policy.removable_media,
+            PathClassification::Unknown => policy.unknown,
+        };
+        let message = match classification {
+            PathClassification::Safe => "Vault location meets the residency policy.".to_string(),
+            _ => format!("Vault location is not local storage: {}.", reason),
+        };
+        (action, message)
+    };
+
+    let status = match action {
+        ResidencyAction::Allow => ResidencyStatus::Compliant,
+        ResidencyAction::Warn => ResidencyStatus::Warning,
+        ResidencyAction::Block => ResidencyStatus::Blocked,
+    };
+
+    ResidencyCheck {
+        data_dir: data_dir.display().to_string(),
+        classification,
+        status,
+        message,
+        checked_at: chrono::Utc::now().timestamp(),
+    }
+}
+
+/// Classify `data_dir` with the export-path classifier and evaluate it
+pub fn check_data_dir(data_dir: &Path, policy: &DataResidencyPolicy) -> ResidencyCheck {
+    let classified = crate::export::classify_path(data_dir);
+    evaluate(&classified.canonical_path, classified.classification, &classified.reason, policy)
+}
+
+/// Move the vault database, its backups and replica from `current_dir`
+/// to `target_dir` and point the app at it. The vault must be locked. Originals are removed only
+/// after the copies hash-match.
+pub fn relocate(
+    app_dir: &Path,
+    current_dir: &Path,
+    target_dir: &Path,
+    policy: &DataResidencyPolicy,
+) -> Result<ResidencyCheck, String> {
+    std::fs::create_dir_all(target_dir).map_err(|e| format!("Cannot create target directory: {}", e))?;
+    let check = check_data_dir(target_dir, policy);
+    if let Some(reason) = check.blocker() {
+        return Err(reason);
+    }
+    if target_dir.join("vault.db").exists() {
+        return Err("Target directory already contains a vault".to_string());
+    }
+
+    let mut names: Vec<PathBuf> = VAULT_FILES.iter().map(PathBuf::from).collect();
+    for dir in VAULT_DIRS {
+        let Ok(entries) = std::fs::read_dir(current_dir.join(dir)) else { continue };
+        for entry in entries.filter_map(|e| e.ok()).filter(|e| e.path().is_file()) {
+            names.push(Path::new(dir).join(entry.file_name()));
+        }
+        std::fs::create_dir_all(target_dir.join(dir)).map_err(|e| format!("Cannot create {}: {}", dir, e))?;
+    }
+
+    let mut copied = Vec::new();
+    for name in &names {
+        let src = current_dir.join(name);
+        if !src.exists() {
+            continue;
+        }
+        let dst = target_dir.join(name);
+        let name = name.display();
+        let result = std::fs::copy(&src, &dst)
+            .map_err(|e| format!("Copy of {} failed: {}", name, e))
+            .and_then(|_| {
+                let a = std::fs::read(&src).map_err(|e| e.to_string())?;
+                let b = std::fs::read(&dst).map_err(|e| e.to_string())?;
+                if crate::crypto::hash_sha256(&a) == crate::crypto::hash_sha256(&b) {
+                    Ok(())
+                } else {
+                    Err(format!("Copy of {} does not match the original", name))
+                }
+            });
+        if let Err(e) = result {
+            for done in copied.iter().chain(std::iter::once(&dst)) {
+                std::fs::remove_file(done).ok();
+            }
+            return Err(e);
+        }
+        copied.push(dst);
+    }
+    if !target_dir.join("vault.db").exists() {
+        for done in &copied {
+            std::fs::remove_file(done).ok();
+        }
+        return Err("No vault database found to relocate".to_string());
+    }
+
+    let location = VaultLocation {
+        data_dir: target_dir.to_path_buf(),
+        relocated_at: chrono::Utc::now().timestamp(),
+    };
+    let json = serde_json::to_string_pretty(&location).map_err(|e| e.to_string())?;
+    std::fs::write(app_dir.join(LOCATION_FILE), json).map_err(|e| e.to_string())?;
+
+    for name in &names {
+        std::fs::remove_file(current_dir.join(name)).ok();
+    }
+    for dir in VAULT_DIRS {
+        // Only if now empty
+        std::fs::remove_dir(current_dir.join(dir)).ok();
+    }
+    log::info!("Vault relocated to {:?}", target_dir);
+    Ok(check)
+}
+
+// ============================================
+// Tauri Commands
+// ============================================
+
+use tauri::State;
+use crate::commands::AppState;
+use crate::policy::PolicyState;
+
+fn residency_policy(policy_state: &PolicyState) -> Result<DataResidencyPolicy, String> {
+    let engine = policy_state.engine.read().map_err(|e| e.to_string())?;
+    Ok(engine.get_policy().residency_policy.clone())
+}
+
+/// Current vault location against the active residency policy
+#[tauri::command]
+pub fn check_data_residency(
+    state: State<'_, AppState>,
+    policy_state: State<'_, PolicyState>,
+) -> Result<ResidencyCheck, String> {
+    let policy = residency_policy(&policy_state)?;
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    Ok(check_data_dir(vault.data_dir(), &policy))
+}
+
+/// Move the (locked) vault to `target_dir`
+#[tauri::command]
+pub fn relocate_vault(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    policy_state: State<'_, PolicyState>,
+    target_dir: String,
+) -> Result<ResidencyCheck, String> {
+    let policy = residency_policy(&policy_state)?;
+    let app_dir = app.path_resolver()
+        .app_data_dir()
+        .ok_or_else(|| "App data directory unavailable".to_string())?;
+    let mut vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    if vault.is_unlocked() {
+        return Err("Lock the vault before relocating it".to_string());
+    }
+
+    let target = PathBuf::from(target_dir);
+    let check = relocate(&app_dir, vault.data_dir(), &target, &policy)?;
+    *vault = crate::vault::Vault::new(target);
+    Ok(check)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_policy_actions_by_location() {
+        let mut policy = DataResidencyPolicy::default();
+        let dir = Path::new("/Users/dr/OneDrive/Evidify");
+
+        let check = evaluate(dir, PathClassification::CloudSync, "OneDrive", &policy);
+        assert_eq!(check.status, ResidencyStatus::Warning);
+        assert!(check.blocker().is_none());
+
+        policy.cloud_sync = ResidencyAction::Block;
+        let check = evaluate(dir, PathClassification::CloudSync, "OneDrive", &policy);
+        assert!(check.blocker().is_some());
+
+        // Approved roots take precedence over classification
+        policy.approved_locations = vec!["/Volumes/Secure".to_string()];
+        let check = evaluate(Path::new("/Users/dr/Library/Evidify"), PathClassification::Safe, "", &policy);
+        assert_eq!(check.status, ResidencyStatus::Blocked);
+        let check = evaluate(Path::new("/Volumes/Secure/Evidify"), PathClassification::Safe, "", &policy);
+        assert_eq!(check.status, ResidencyStatus::Compliant);
+    }
+
+    #[test]
+    fn test_relocate_moves_vault_and_records_pointer() {
+        let root = std::env::temp_dir().join(format!("evidify-residency-{}", uuid::Uuid::new_v4()));
+        let app_dir = root.join("app");
+        let target = root.join("secure");
+        std::fs::create_dir_all(&app_dir).unwrap();
+        std::fs::write(app_dir.join("vault.db"), b"sqlcipher bytes").unwrap();
+        std::fs::create_dir_all(app_dir.join("backups")).unwrap();
+        std::fs::write(app_dir.join("backups/vault-20240301T020000Z.db"), b"backup bytes").unwrap();
+
+        assert_eq!(resolve_data_dir(&app_dir), app_dir);
+        relocate(&app_dir, &app_dir, &target, &DataResidencyPolicy::default()).unwrap();
+
+        assert_eq!(resolve_data_dir(&app_dir), target);
+        assert_eq!(std::fs::read(target.join("vault.db")).unwrap(), b"sqlcipher bytes");
+        assert_eq!(std::fs::read(target.join("backups/vault-20240301T020000Z.db")).unwrap(), b"backup bytes");
+        assert!(!app_dir.join("vault.db").exists());
+        assert!(!app_dir.join("backups").exists());
+        std::fs::remove_dir_all(&root).ok();
+    }
+}
@@ -0,0 +1,425 @@
+// Retention Module
+//
+// Record retention and destruction. Each jurisdiction has a rule: keep
+// records N years after discharge, and for clients treated as minors, at
+// least M years past the age of majority as well - whichever is later. A
+// client's discharge date is the recorded one if set, otherwise the last
+// termination note, otherwise (for closed or archived clients) the last
+// session. Clients still in care are never eligible.
+//
+// Destruction is explicit and clinician-authorized: the eligibility report
+// only lists what may be destroyed. Destroying a chart writes an audit
+// entry over a manifest of what was removed and issues a certificate of
+// destruction bound to that entry. A legal hold freezes both destruction
+// and edits to the chart until it is released.
+
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+/// Client statuses that mean care has ended
+pub const DISCHARGED_STATUSES: &[&str] = &["closed", "archived", "discharged"];
+
+/// Status and display name a destroyed chart keeps, so audit entries still resolve
+pub const DESTROYED_STATUS: &str = "destroyed";
+
+/// What destroy_client_records does, as stated on the certificate
+pub const DESTRUCTION_METHOD: &str = "Deleted from the encrypted vault database with secure_delete: \
+notes, documents and everything derived from them, the search index entries, cached analyses, \
+chart access grants, emergency access sessions and cohort memberships and attendance. \
+Earlier maintenance backups removed and the reporting snapshot discarded.";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RetentionRule {
+    /// Jurisdiction code as used for licensure ("CA", "NY") or "default"
+    pub jurisdiction: String,
+    pub years_after_discharge: u32,
+    /// For clients discharged before the age of majority: also keep until
+    /// this many years after reaching it
+    pub years_after_majority: u32,
+    pub age_of_majority: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetentionSchedule {
+    /// Rule for clients with no jurisdiction, or one with no rule of its own
+    pub default_jurisdiction: String,
+    pub rules: Vec<RetentionRule>,
+}
+
+impl Default for RetentionSchedule {
+    fn default() -> Self {
+        Self {
+            default_jurisdiction: "default".to_string(),
+            rules: vec![RetentionRule {
+                jurisdiction: "default".to_string(),
+                years_after_discharge: 7,
+                years_after_majority: 3,
+                age_of_majority: 18,
+            }],
+        }
+    }
+}
+
+impl RetentionSchedule {
+    pub fn validate(&self) -> Result<(), String> {
+        for (i, rule) in self.rules.iter().enumerate() {
+            if rule.jurisdiction.trim().is_empty() {
+                return Err(format!("Rule {} has no jurisdiction", i + 1));
+            }
+            if self.rules[..i].iter().any(|r| r.jurisdiction.eq_ignore_ascii_case(&rule.jurisdiction)) {
+                return Err(format!("Jurisdiction '{}' has more than one rule", rule.jurisdiction));
+            }
+            if rule.years_after_discharge == 0 || rule.years_after_discharge > 100 {
+                return Err(format!("Retention for '{}' must be 1-100 years", rule.jurisdiction));
+            }
+            if !(14..=25).contains(&rule.age_of_majority) {
+                return Err(format!("Age of majority for '{}' must be 14-25", rule.jurisdiction));
+            }
+        }
+        if self.rule_named(&self.default_jurisdiction).is_none() {
+            return Err(format!("No rule for the default jurisdiction '{}'", self.default_jurisdiction));
+        }
+        Ok(())
+    }
+
+    fn rule_named(&self, jurisdiction: &str) -> Option<&RetentionRule> {
+        self.rules.iter().find(|r| r.jurisdiction.eq_ignore_ascii_case(jurisdiction))
+    }
+
+    /// The jurisdiction's rule, falling back to the default
+    pub fn rule_for(&self, jurisdiction: Option<&str>) -> Option<&RetentionRule> {
+        jurisdiction
+            .map(crate::licensure::normalize_jurisdiction)
+            .and_then(|j| self.rule_named(&j))
+            .or_else(|| self.rule_named(&self.default_jurisdiction))
+    }
+}
+
+/// Per-client retention inputs the chart doesn't otherwise hold
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ClientRetentionProfile {
+    pub client_id: String,
+    pub jurisdiction: Option<String>,
+    pub discharged_on: Option<NaiveDate>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DischargeSource {
+    Recorded,
+    TerminationNote,
+    LastSession,
+}
+
+/// Dates the chart supplies for working out discharge
+#[derive(Debug, Clone, Default)]
+pub struct ChartDates {
+    pub client_status: String,
+    pub date_of_birth: Option<NaiveDate>,
+    pub last_termination_note: Option<NaiveDate>,
+    pub last_session: Option<NaiveDate>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetentionStatus {
+    pub client_id: String,
+    pub jurisdiction: String,
+    pub discharged_on: Option<NaiveDate>,
+    pub discharge_source: Option<DischargeSource>,
+    pub retain_until: Option<NaiveDate>,
+    pub on_legal_hold: bool,
+    pub eligible_for_destruction: bool,
+    /// Why the chart is or isn't eligible
+    pub reason: String,
+}
+
+fn add_years(date: NaiveDate, years: u32) -> NaiveDate {
+    // Feb 29 rolls to Feb 28 in non-leap target years
+    date.with_year(date.year() + years as i32)
+        .or_else(|| NaiveDate::from_ymd_opt(date.year() + years as i32, date.month(), 28))
+        .unwrap_or(date)
+}
+
+/// Last day records must be kept: the later of discharge + the rule's
+/// years, and (if discharged as a minor) majority + the minors' years
+pub fn retain_until(rule: &RetentionRule, discharged_on: NaiveDate, date_of_birth: Option<NaiveDate>) -> NaiveDate {
+    let after_discharge = add_years(discharged_on, rule.years_after_discharge);
+    match date_of_birth {
+        Some(dob) => {
+            let majority = add_years(dob, rule.age_of_majority);
+            if discharged_on < majority {
+                after_discharge.max(add_years(majority, rule.years_after_majority))
+            } else {
+                after_discharge
+            }
+        }
+        None => after_discharge,
+    }
+}
+
+/// Retention status of one chart on `today`
+pub fn evaluate(
+    schedule: &RetentionSchedule,
+    profile: &ClientRetentionProfile,
+    dates: &ChartDates,
+    on_legal_hold: bool,
+    today: NaiveDate,
+) -> RetentionStatus {
+    let rule = schedule.rule_for(profile.jurisdiction.as_deref());
+    let jurisdiction = rule.map(|r| r.jurisdiction.clone()).unwrap_or_default();
+    let discharged = DISCHARGED_STATUSES.iter().any(|s| s.eq_ignore_ascii_case(&dates.client_status));
+
+    let discharge = profile.discharged_on.map(|d| (d, DischargeSource::Recorded))
+        .or_else(|| dates.last_termination_note.map(|d| (d, DischargeSource::TerminationNote)))
+        .or_else(|| dates.last_session.filter(|_| discharged).map(|d| (d, DischargeSource::LastSession)));
+
+    let mut status = RetentionStatus {
+        client_id: profile.client_id.clone(),
+        jurisdiction,
+        discharged_on: discharge.map(|(d, _)| d),
+        discharge_source: discharge.map(|(_, s)| s),
+        retain_until: None,
+        on_legal_hold,
+        eligible_for_destruction: false,
+        reason: String::new(),
+    };
+
+    let (Some(rule), Some((discharged_on, _))) = (rule, discharge) else {
+        status.reason = if rule.is_none() {
+            "No retention rule applies".to_string()
+        } else {
+            "Client has not been discharged".to_string()
+        };
+        return status;
+    };
+    if !discharged && profile.discharged_on.is_none() {
+        status.reason = "Client is still in care".to_string();
+        return status;
+    }
+
+    let until = retain_until(rule, discharged_on, dates.date_of_birth);
+    status.retain_until = Some(until);
+    status.reason = if on_legal_hold {
+        "Under legal hold".to_string()
+    } else if today <= until {
+        format!("Retain until {}", until)
+    } else {
+        status.eligible_for_destruction = true;
+        format!("Retention period ended {}", until)
+    };
+    status
+}
+
+/// A litigation hold on one client's chart
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LegalHold {
+    pub id: String,
+    pub client_id: String,
+    /// Matter or case reference the hold is for
+    pub matter: String,
+    pub placed_by: String,
+    pub placed_at: i64,
+    pub audit_entry_id: String,
+    pub released_at: Option<i64>,
+    pub released_by: Option<String>,
+}
+
+/// Certificate of destruction for one chart. `certificate_sha256` covers
+/// every other field, and `audit_entry_hash` ties it to the chain.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DestructionCertificate {
+    pub id: String,
+    pub client_id: String,
+    pub jurisdiction: String,
+    pub discharged_on: NaiveDate,
+    pub retain_until: NaiveDate,
+    pub destroyed_at: i64,
+    pub authorized_by: String,
+    pub method: String,
+    pub notes_destroyed: u32,
+    pub documents_destroyed: u32,
+    /// SHA-256 over the sorted "kind:id:content_hash" lines of what was removed
+    pub manifest_sha256: String,
+    pub audit_entry_id: String,
+    pub audit_entry_hash: String,
+    pub certificate_sha256: String,
+}
+
+impl DestructionCertificate {
+    /// Hash of the certificate with `certificate_sha256` blanked
+    pub fn compute_sha256(&self) -> String {
+        let mut unsigned = self.clone();
+        unsigned.certificate_sha256 = String::new();
+        let json = serde_json::to_value(&unsigned).unwrap_or_default();
+        crate::crypto::hash_sha256(&serde_json::to_vec(&json).unwrap_or_default())
+    }
+}
+
+/// Hash of a destruction manifest; lines are sorted so order doesn't matter
+pub fn manifest_sha256(mut lines: Vec<String>) -> String {
+    lines.sort();
+    crate::crypto::hash_sha256(lines.join("\n").as_bytes())
+}
+
+// ============================================
+// Tauri Commands
+// ============================================
+
+use tauri::State;
+use crate::commands::AppState;
+
+#[tauri::command]
+pub fn get_retention_schedule(state: State<AppState>) -> Result<RetentionSchedule, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.retention_schedule().map_err(|e| format!("{}", e))
+}
+
+#[tauri::command]
+pub fn set_retention_schedule(state: State<AppState>, schedule: RetentionSchedule) -> Result<(), String> {
+    schedule.validate()?;
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.set_retention_schedule(&schedule).map_err(|e| format!("{}", e))
+}
+
+/// Record a client's jurisdiction and/or discharge date for retention
+#[tauri::command]
+pub fn set_client_retention_profile(
+    state: State<AppState>,
+    profile: ClientRetentionProfile,
+) -> Result<RetentionStatus, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.set_client_retention_profile(&profile).map_err(|e| format!("{}", e))?;
+    vault.retention_status(&profile.client_id, chrono::Utc::now().date_naive())
+        .map_err(|e| format!("{}", e))
+}
+
+/// Retention status of every chart; with `eligible_only`, just those that
+/// may be destroyed today
+#[tauri::command]
+pub fn get_destruction_eligibility_report(
+    state: State<AppState>,
+    eligible_only: Option<bool>,
+) -> Result<Vec<RetentionStatus>, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    let report = vault.destruction_eligibility_report(chrono::Utc::now().date_naive())
+        .map_err(|e| format!("{}", e))?;
+    Ok(report.into_iter()
+        .filter(|s| !eligible_only.unwrap_or(false) || s.eligible_for_destruction)
+        .collect())
+}
+
+/// Destroy an eligible chart and issue its certificate of destruction
+#[tauri::command]
+pub fn destroy_client_records(
+    state: State<AppState>,
+    replica_state: State<'_, crate::replica::ReplicaState>,
+    client_id: String,
+    authorized_by: String,
+) -> Result<DestructionCertificate, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    let certificate = vault.destroy_client_records(&client_id, &authorized_by, chrono::Utc::now().date_naive())
+        .map_err(|e| format!("{}", e))?;
+    // The reporting snapshot still holds the chart; the next query takes a new one
+    drop(vault);
+    replica_state.clear();
+    Ok(certificate)
+}
+
+#[tauri::command]
+pub fn list_destruction_certificates(state: State<AppState>) -> Result<Vec<DestructionCertificate>, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.list_destruction_certificates().map_err(|e| format!("{}", e))
+}
+
+#[tauri::command]
+pub fn place_legal_hold(
+    state: State<AppState>,
+    client_id: String,
+    matter: String,
+    placed_by: String,
+) -> Result<LegalHold, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.place_legal_hold(&client_id, &matter, &placed_by).map_err(|e| format!("{}", e))
+}
+
+#[tauri::command]
+pub fn release_legal_hold(
+    state: State<AppState>,
+    hold_id: String,
+    released_by: String,
+) -> Result<LegalHold, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.release_legal_hold(&hold_id, &released_by).map_err(|e| format!("{}", e))
+}
+
+/// Holds, newest first; with `client_id`, only that client's
+#[tauri::command]
+pub fn list_legal_holds(
+    state: State<AppState>,
+    client_id: Option<String>,
+    active_only: Option<bool>,
+) -> Result<Vec<LegalHold>, String> {
+    let vault = state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+    vault.list_legal_holds(client_id.as_deref(), active_only.unwrap_or(false))
+        .map_err(|e| format!("{}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_minor_retention_extends_past_majority() {
+        let schedule = RetentionSchedule::default();
+        let profile = ClientRetentionProfile {
+            client_id: "c1".to_string(),
+            jurisdiction: None,
+            discharged_on: None,
+        };
+        let dates = ChartDates {
+            client_status: "closed".to_string(),
+            date_of_birth: Some(date("2010-02-28")),
+            last_termination_note: Some(date("2015-06-01")),
+            last_session: Some(date("2015-06-01")),
+        };
+
+        // Discharged at 5: majority 2028-02-28 + 3 years beats 2015 + 7
+        let status = evaluate(&schedule, &profile, &dates, false, date("2030-01-01"));
+        assert_eq!(status.discharge_source, Some(DischargeSource::TerminationNote));
+        assert_eq!(status.retain_until, Some(date("2031-02-28")));
+        assert!(!status.eligible_for_destruction);
+
+        assert!(evaluate(&schedule, &profile, &dates, false, date("2031-03-01")).eligible_for_destruction);
+        let held = evaluate(&schedule, &profile, &dates, true, date("2031-03-01"));
+        assert!(!held.eligible_for_destruction);
+        assert!(held.on_legal_hold);
+    }
+
+    #[test]
+    fn test_active_clients_are_never_eligible() {
+        let mut schedule = RetentionSchedule::default();
+        schedule.rules.push(RetentionRule {
+            jurisdiction: "CA".to_string(),
+            years_after_discharge: 10,
+            years_after_majority: 1,
+            age_of_majority: 18,
+        });
+        assert!(schedule.validate().is_ok());
+        assert_eq!(schedule.rule_for(Some("California")).unwrap().years_after_discharge, 10);
+        assert_eq!(schedule.rule_for(Some("Nowhere")).unwrap().jurisdiction, "default");
+
+        let profile = ClientRetentionProfile { client_id: "c2".to_string(), ..Default::default() };
+        let dates = ChartDates {
+            client_status: "active".to_string(),
+            last_session: Some(date("2001-01-01")),
+            ..Default::default()
+        };
+        let status = evaluate(&schedule, &profile, &dates, false, date("2030-01-01"));
+        assert!(!status.eligible_for_destruction);
+        assert_eq!(status.retain_until, None);
+    }
+}
@@ -228,7 +228,7 @@ impl SiemForwarder {
         Self {
             config,
             buffer: VecDeque::new(),
-            client: reqwest::Client::new(),
+            client: crate::performance::HTTP_CLIENT.get().clone(),
             last_flush: None,
             failed_count: 0,
             sent_count: 0,
@@ -206,6 +206,105 @@ pub struct CoSignature {
     pub conditions: Option<String>,
     /// Cryptographic signature
     pub signature: String,
+    /// SLA in force when the note was co-signed (hours)
+    #[serde(default)]
+    pub sla_hours: u32,
+    /// Co-signed within the SLA (training-program reporting); None when the
+    /// note never entered the review queue, so no delay could be measured
+    #[serde(default)]
+    pub met_sla: Option<bool>,
+    /// Content hash of the true note at co-signature time
+    #[serde(default)]
+    pub note_hash: String,
+    /// Supervisor reviewed the pseudonymized view
+    #[serde(default)]
+    pub blinded: bool,
+}
+
+/// Co-signature SLA, derived from the organization supervision policy
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct CosignSla {
+    /// Hours allowed between supervisee signature and co-signature
+    pub sla_hours: u32,
+    /// Hours before the deadline at which a cosign is "due soon"
+    pub due_soon_hours: u32,
+    /// Raise local notifications for overdue cosigns
+    pub notify_overdue: bool,
+}
+
+impl CosignSla {
+    pub fn from_policy(policy: &crate::policy::SupervisionPolicy) -> Self {
+        Self {
+            sla_hours: policy.max_review_delay_hours,
+            due_soon_hours: policy.cosign_due_soon_days * 24,
+            notify_overdue: policy.notify_overdue_cosigns,
+        }
+    }
+    
+    /// Escalation level for a cosign pending `hours_pending` hours
+    pub fn level(&self, hours_pending: f64) -> EscalationLevel {
+        let sla = self.sla_hours as f64;
+        if hours_pending > sla {
+            EscalationLevel::Overdue
+        } else if hours_pending >= sla - self.due_soon_hours as f64 {
+            EscalationLevel::DueSoon
+        } else {
+            EscalationLevel::OnTrack
+        }
+    }
+}
+
+impl Default for CosignSla {
+    fn default() -> Self {
+        Self::from_policy(&crate::policy::SupervisionPolicy::default())
+    }
+}
+
+/// Escalation state of a pending co-signature
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EscalationLevel {
+    OnTrack,
+    DueSoon,
+    Overdue,
+}
+
+/// Pending co-signature with its SLA status
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CosignEscalation {
+    /// Note ID
+    pub note_id: String,
+    /// Supervisee who created the note
+    pub supervisee_id: String,
+    /// Supervisee name
+    pub supervisee_name: String,
+    /// When the note was signed by supervisee
+    pub signed_at: DateTime<Utc>,
+    /// Co-signature deadline
+    pub due_at: DateTime<Utc>,
+    /// Hours since supervisee signature
+    pub hours_pending: f64,
+    /// Escalation level
+    pub level: EscalationLevel,
+}
+
+/// Cosign SLA compliance summary for training-program reporting
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CosignSlaReport {
+    /// Co-signatures with a measurable review delay
+    pub total_cosigned: u32,
+    /// Co-signed within SLA
+    pub met_sla: u32,
+    /// Co-signed after SLA
+    pub missed_sla: u32,
+    /// Co-signed notes that never entered the review queue (excluded above)
+    #[serde(default)]
+    pub not_applicable: u32,
+    /// met / total (0.0 - 1.0); None when nothing has been co-signed
+    pub compliance_rate: Option<f64>,
+    /// Mean review delay (hours)
+    pub average_delay_hours: Option<f64>,
+    /// Currently pending cosigns past SLA
+    pub currently_overdue: u32,
 }
 
 /// Competency tracking record
@@ -369,6 +468,115 @@ impl SupervisionManager {
         self.cosignatures.get(note_id)
     }
     
+    /// Build a co-signature for a queued note, computing review delay and SLA compliance
+    pub fn prepare_cosignature(
+        &self,
+        note_id: &str,
+        supervisor_id: &str,
+        supervisor_name: &str,
+        supervisor_credentials: &str,
+        conditions: Option<String>,
+        sla: &CosignSla,
+    ) -> CoSignature {
+        let now = Utc::now();
+        let queued_delay_hours = self.review_queue.iter()
+            .find(|item| item.note_id == note_id)
+            .map(|item| (now - item.signed_at).num_seconds() as f64 / 3600.0);
+        let review_delay_hours = queued_delay_hours.unwrap_or(0.0);
+        
+        CoSignature {
+            note_id: note_id.to_string(),
+            supervisor_id: supervisor_id.to_string(),
+            supervisor_name: supervisor_name.to_string(),
+            supervisor_credentials: supervisor_credentials.to_string(),
+            signed_at: now,
+            review_delay_hours,
+            conditions,
+            signature: String::new(),  // Would generate cryptographic signature
+            sla_hours: sla.sla_hours,
+            met_sla: queued_delay_hours.map(|hours| hours <= sla.sla_hours as f64),
+            note_hash: String::new(),  // Filled from the vault by the caller
+            blinded: false,
+        }
+    }
+    
+    /// Pending cosigns for a supervisor that are due soon or overdue, most urgent first
+    pub fn cosign_escalations(
+        &self,
+        supervisor_id: &str,
+        sla: &CosignSla,
+        now: DateTime<Utc>,
+    ) -> Vec<CosignEscalation> {
+        let mut escalations: Vec<CosignEscalation> = self.get_review_queue(supervisor_id)
+            .into_iter()
+            .filter(|item| !self.cosignatures.contains_key(&item.note_id))
+            .filter_map(|item| {
+                let hours_pending = (now - item.signed_at).num_seconds() as f64 / 3600.0;
+                let level = sla.level(hours_pending);
+                if level == EscalationLevel::OnTrack {
+                    return None;
+                }
+                Some(CosignEscalation {
+                    note_id: item.note_id.clone(),
+                    supervisee_id: item.supervisee_id.clone(),
+                    supervisee_name: item.supervisee_name.clone(),
+                    signed_at: item.signed_at,
+                    due_at: item.signed_at + chrono::Duration::hours(sla.sla_hours as i64),
+                    hours_pending,
+                    level,
+                })
+            })
+            .collect();
+        
+        escalations.sort_by(|a, b| {
+            b.hours_pending.partial_cmp(&a.hours_pending).unwrap_or(std::cmp::Ordering::Equal)
+        });
+        escalations
+    }
+    
+    /// SLA compliance across recorded co-signatures (optionally for one supervisor)
+    pub fn cosign_sla_report(
+        &self,
+        supervisor_id: Option<&str>,
+        sla: &CosignSla,
+        now: DateTime<Utc>,
+    ) -> CosignSlaReport {
+        let (cosigns, unmeasured): (Vec<&CoSignature>, Vec<&CoSignature>) = self.cosignatures.values()
+            .filter(|c| supervisor_id.map(|id| c.supervisor_id == id).unwrap_or(true))
+            .partition(|c| c.met_sla.is_some());
+        
+        let total = cosigns.len() as u32;
+        let met = cosigns.iter().filter(|c| c.met_sla == Some(true)).count() as u32;
+        let average_delay_hours = if cosigns.is_empty() {
+            None
+        } else {
+            Some(cosigns.iter().map(|c| c.review_delay_hours).sum::<f64>() / cosigns.len() as f64)
+        };
+        
+        let currently_overdue = match supervisor_id {
+            Some(id) => self.cosign_escalations(id, sla, now).iter()
+                .filter(|e| e.level == EscalationLevel::Overdue)
+                .count() as u32,
+            None => self.review_queue.iter()
+                .filter(|item| !self.cosignatures.contains_key(&item.note_id))
+                .filter(|item| {
+                    let hours = (now - item.signed_at).num_seconds() as f64 / 3600.0;
+                    sla.level(hours) == EscalationLevel::Overdue
+                })
+                .count() as u32,
+        };
+        
+        CosignSlaReport {
+            total_cosigned: total,
+            met_sla: met,
+            missed_sla: total - met,
+            not_applicable: unmeasured.len() as u32,
+            compliance_rate: if total == 0 { None } else { Some(met as f64 / total as f64) },
+            average_delay_hours,
+            currently_overdue,
+        }
+    }
+    
     /// Check if note requires co-signature
     pub fn requires_cosignature(&self, supervisee_id: &str) -> bool {
         self.get_supervisor(supervisee_id)
@@ -486,6 +694,305 @@ pub struct SuperviseeCompetencySummary {
     pub areas_needing_attention: Vec<String>,
 }
 
+// ============================================
+// Trainee Competency Report
+// ============================================
+
+/// Render the competency report (review outcomes + reflective practice) as
+/// Markdown for the training program's file
+pub fn format_competency_report(report: &crate::models::TraineeCompetencyReport) -> String {
+    let mut out = String::new();
+    let score = |v: Option<f32>| v.map(|s| format!("{:.1} / 5", s)).unwrap_or_else(|| "n/a".to_string());
+    
+    out.push_str(&format!("# Competency Report: {}\n\n", report.trainee.name));
+    out.push_str(&format!("Generated: {}  \nTraining start: {}\n\n", report.generated_at, report.trainee.start_date));
+    
+    out.push_str("## Supervised Reviews\n\n");
+    out.push_str(&format!("- Completed reviews: {}\n", report.reviews_completed));
+    out.push_str(&format!("- Approved: {} | Needs revision: {} | Rejected: {}\n",
+        report.approved, report.needs_revision, report.rejected));
+    out.push_str(&format!("- Clinical accuracy: {}\n", score(report.avg_clinical_accuracy)));
+    out.push_str(&format!("- Documentation quality: {}\n\n", score(report.avg_documentation_quality)));
+    
+    out.push_str("## Reflective Practice\n\n");
+    match report.reflection_rate {
+        Some(rate) => out.push_str(&format!("{} reflection(s) on {} completed review(s) ({:.0}%)\n\n",
+            report.reflections.len(), report.reviews_completed, rate * 100.0)),
+        None => out.push_str("No completed reviews yet.\n\n"),
+    }
+    
+    for reflection in &report.reflections {
+        let date = DateTime::from_timestamp(reflection.created_at, 0)
+            .map(|dt| dt.format("%Y-%m-%d").to_string())
+            .unwrap_or_default();
+        out.push_str(&format!("### {} (review {})\n\n", date, reflection.review_id));
+        out.push_str(&format!("**What I learned:** {}\n\n", reflection.what_i_learned));
+        out.push_str(&format!("**What I will change:** {}\n\n", reflection.what_i_will_change));
+        if reflection.acknowledged_at.is_some() {
+            out.push_str("_Acknowledged by supervisor_\n\n");
+        }
+    }
+    
+    let hours = &report.supervision_hours;
+    out.push_str("## Group Supervision\n\n");
+    out.push_str(&format!("- Sessions attended: {} (missed {})\n", hours.group_sessions_attended, hours.group_sessions_missed));
+    out.push_str(&format!("- Hours: {:.1}\n", hours.group_hours));
+    
+    out
+}
+
+// ============================================
+// Review Quality Trends
+// ============================================
+
+/// Number of most recent scored reviews in the rolling averages
+pub const QUALITY_ROLLING_WINDOW: usize = 10;
+
+/// Rolling averages and month-by-month scores for one trainee. `scores`
+/// may be in any order; reviews without a score are counted but do not
+/// move the averages.
+pub fn quality_trend(trainee_id: &str, scores: &[crate::models::ReviewScore], window: usize) -> crate::models::QualityTrend {
+    let mut mine: Vec<&crate::models::ReviewScore> = scores.iter()
+        .filter(|s| s.trainee_id == trainee_id)
+        .collect();
+    mine.sort_by_key(|s| s.completed_at);
+    
+    let mean = |values: Vec<i32>| -> Option<f32> {
+        (!values.is_empty()).then(|| values.iter().sum::<i32>() as f32 / values.len() as f32)
+    };
+    let rolling = |pick: fn(&crate::models::ReviewScore) -> Option<i32>| {
+        let mut recent: Vec<i32> = mine.iter().rev().filter_map(|s| pick(s)).take(window).collect();
+        recent.reverse();
+        mean(recent)
+    };
+    
+    let mut months: Vec<(String, Vec<&crate::models::ReviewScore>)> = Vec::new();
+    for score in &mine {
+        let month = DateTime::from_timestamp(score.completed_at, 0)
+            .map(|dt| dt.format("%Y-%m").to_string())
+            .unwrap_or_default();
+        match months.last_mut() {
+            Some((m, bucket)) if *m == month => bucket.push(*score),
+            _ => months.push((month, vec![*score])),
+        }
+    }
+    
+    let mut monthly: Vec<crate::models::MonthlyQuality> = Vec::with_capacity(months.len());
+    for (month, bucket) in months {
+        let avg_clinical_accuracy = mean(bucket.iter().filter_map(|s| s.clinical_accuracy).collect());
+        let avg_documentation_quality = mean(bucket.iter().filter_map(|s| s.documentation_quality).collect());
+        let delta = |current: Option<f32>, prior: Option<f32>| current.zip(prior).map(|(c, p)| c - p);
+        let (prior_clinical, prior_documentation) = monthly.last()
+            .map(|p| (p.avg_clinical_accuracy, p.avg_documentation_quality))
+            .unwrap_or((None, None));
+        monthly.push(crate::models::MonthlyQuality {
+            month,
+            reviews: bucket.len() as i32,
+            avg_clinical_accuracy,
+            avg_documentation_quality,
+            clinical_accuracy_delta: delta(avg_clinical_accuracy, prior_clinical),
+            documentation_quality_delta: delta(avg_documentation_quality, prior_documentation),
+        });
+    }
+    
+    crate::models::QualityTrend {
+        trainee_id: trainee_id.to_string(),
+        reviews_completed: mine.len() as i32,
+        window,
+        rolling_clinical_accuracy: rolling(|s| s.clinical_accuracy),
+        rolling_documentation_quality: rolling(|s| s.documentation_quality),
+        monthly,
+    }
+}
+
+/// Render the supervision quality report as Markdown for training-program
+/// accreditation files
+pub fn format_quality_report(report: &crate::models::SupervisionQualityReport) -> String {
+    let mut out = String::new();
+    let score = |v: Option<f32>| v.map(|s| format!("{:.1}", s)).unwrap_or_else(|| "n/a".to_string());
+    let delta = |v: Option<f32>| v.map(|d| format!("{:+.1}", d)).unwrap_or_else(|| "-".to_string());
+    
+    out.push_str("# Supervision Quality Report\n\n");
+    out.push_str(&format!("Supervisor: {}  \nGenerated: {}\n\n", report.supervisor_id, report.generated_at));
+    
+    for summary in &report.trainees {
+        let trend = &summary.quality_trend;
+        out.push_str(&format!("## {}\n\n", summary.trainee.name));
+        out.push_str(&format!("- Completed reviews: {}\n", trend.reviews_completed));
+        out.push_str(&format!("- Clinical accuracy (last {}): {}\n", trend.window, score(trend.rolling_clinical_accuracy)));
+        out.push_str(&format!("- Documentation quality (last {}): {}\n\n", trend.window, score(trend.rolling_documentation_quality)));
+        
+        if trend.monthly.is_empty() {
+            out.push_str("No completed reviews yet.\n\n");
+            continue;
+        }
+        out.push_str("| Month | Reviews | Clinical accuracy | Change | Documentation quality | Change |\n");
+        out.push_str("|---|---|---|---|---|---|\n");
+        for month in &trend.monthly {
+            out.push_str(&format!("| {} | {} | {} | {} | {} | {} |\n",
+                month.month, month.reviews,
+                score(month.avg_clinical_accuracy), delta(month.clinical_accuracy_delta),
+                score(month.avg_documentation_quality), delta(month.documentation_quality_delta)));
+        }
+        out.push('\n');
+    }
+    
+    out
+}
+
+// ============================================
+// Group Supervision
+// ============================================
+
+/// Longest group session accepted, in minutes
+pub const MAX_GROUP_SESSION_MINUTES: i32 = 8 * 60;
+
+/// Check a group session before it is recorded
+pub fn validate_group_session(session: &crate::models::NewGroupSupervisionSession) -> Result<(), String> {
+    chrono::NaiveDate::parse_from_str(&session.session_date, "%Y-%m-%d")
+        .map_err(|_| format!("Session date must be YYYY-MM-DD, got {:?}", session.session_date))?;
+    if !(1..=MAX_GROUP_SESSION_MINUTES).contains(&session.duration_minutes) {
+        return Err(format!("Duration must be 1-{} minutes", MAX_GROUP_SESSION_MINUTES));
+    }
+    if session.present.is_empty() {
+        return Err("A group session needs at least one trainee present".to_string());
+    }
+    if let Some(both) = session.absent.iter().find(|id| session.present.contains(id)) {
+        return Err(format!("Trainee {} is listed as both present and absent", both));
+    }
+    Ok(())
+}
+
+/// Group supervision credited to each trainee across `sessions`, by name.
+/// Only sessions attended count toward hours.
+pub fn group_supervision_hours(sessions: &[crate::models::GroupSupervisionSession]) -> Vec<crate::models::TraineeSupervisionHours> {
+    let mut by_trainee: HashMap<&str, crate::models::TraineeSupervisionHours> = HashMap::new();
+    for session in sessions {
+        for attendee in &session.attendees {
+            let hours = by_trainee.entry(&attendee.trainee_id).or_insert_with(|| crate::models::TraineeSupervisionHours {
+                trainee_id: attendee.trainee_id.clone(),
+                trainee_name: attendee.trainee_name.clone(),
+                ..Default::default()
+            });
+            if attendee.present {
+                hours.group_sessions_attended += 1;
+                hours.group_hours += session.duration_minutes as f32 / 60.0;
+            } else {
+                hours.group_sessions_missed += 1;
+            }
+        }
+    }
+    let mut hours: Vec<_> = by_trainee.into_values().collect();
+    hours.sort_by(|a, b| a.trainee_name.cmp(&b.trainee_name).then_with(|| a.trainee_id.cmp(&b.trainee_id)));
+    hours
+}
+
+/// Render the group supervision log as Markdown for training-program
+/// accreditation files
+pub fn format_group_supervision_log(log: &crate::models::GroupSupervisionLog) -> String {
+    let mut out = String::new();
+    let list = |items: &[String]| if items.is_empty() { "none".to_string() } else { items.join(", ") };
+    
+    out.push_str("# Group Supervision Log\n\n");
+    out.push_str(&format!("Supervisor: {}  \nPeriod: {} to {}  \nGenerated: {}\n\n",
+        log.supervisor_id,
+        log.from.as_deref().unwrap_or("start"),
+        log.to.as_deref().unwrap_or("present"),
+        log.generated_at));
+    
+    out.push_str("## Hours by Trainee\n\n");
+    if log.hours.is_empty() {
+        out.push_str("No group sessions in this period.\n\n");
+    } else {
+        out.push_str("| Trainee | Sessions attended | Sessions missed | Hours |\n");
+        out.push_str("|---|---|---|---|\n");
+        for hours in &log.hours {
+            out.push_str(&format!("| {} | {} | {} | {:.1} |\n",
+                hours.trainee_name, hours.group_sessions_attended, hours.group_sessions_missed, hours.group_hours));
+        }
+        out.push('\n');
+    }
+    
+    out.push_str("## Sessions\n\n");
+    for session in &log.sessions {
+        let (present, absent): (Vec<_>, Vec<_>) = session.attendees.iter().partition(|a| a.present);
+        let names = |attendees: Vec<&crate::models::GroupSupervisionAttendee>| {
+            list(&attendees.into_iter().map(|a| a.trainee_name.clone()).collect::<Vec<_>>())
+        };
+        out.push_str(&format!("### {} ({} min)\n\n", session.session_date, session.duration_minutes));
+        out.push_str(&format!("- Present: {}\n", names(present)));
+        out.push_str(&format!("- Absent: {}\n", names(absent)));
+        out.push_str(&format!("- Topics: {}\n", list(&session.topics)));
+        out.push_str(&format!("- Cases discussed: {}\n\n", list(&session.cases_discussed)));
+        if let Some(notes) = session.notes.as_deref().filter(|n| !n.trim().is_empty()) {
+            out.push_str(&format!("{}\n\n", notes.trim()));
+        }
+    }
+    
+    out
+}
+
+// ============================================
+// Blinded Review
+// ============================================
+
+/// Note as shown in blinded review. The client's name is replaced with a
+/// stable pseudonym and the text goes through the de-identification
+/// engine; the note ID and true content hash are kept so comments and the
+/// co-signature still attach to the real note.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlindedNote {
+    pub note_id: String,
+    pub client_pseudonym: String,
+    pub note_type: String,
+    pub content: String,
+    pub identifiers_removed: usize,
+    /// Hash of the unblinded note; pass back when co-signing
+    pub note_hash: String,
+}
+
+/// Pseudonym for a client as seen by one supervisor. Stable across that
+/// supervisor's reviews so patterns in one client's notes stay visible,
+/// but not linkable across supervisors.
+pub fn client_pseudonym(supervisor_id: &str, client_id: &str) -> String {
+    let digest = crate::crypto::hash_sha256(format!("blind-review:{}:{}", supervisor_id, client_id).as_bytes());
+    format!("Client {}", digest[..6].to_uppercase())
+}
+
+/// Build the blinded view of a note for `supervisor_id`
+pub fn blind_note(
+    note: &crate::models::Note,
+    client: &crate::models::Client,
+    supervisor_id: &str,
+) -> BlindedNote {
+    let pseudonym = client_pseudonym(supervisor_id, &client.id);
+    let text = note.structured_note.as_deref().unwrap_or(&note.raw_input);
+    let deid = crate::deidentify::DeidentificationEngine::new(false, None).deidentify(text);
+    
+    // The engine's name detection is pattern-based; the client's own name
+    // parts are known here, so replace whatever it missed
+    let mut content = deid.deidentified_text;
+    let mut extra = 0;
+    for part in std::iter::once(client.display_name.as_str()).chain(client.display_name.split_whitespace()) {
+        if part.chars().filter(|c| c.is_alphanumeric()).count() < 2 {
+            continue;
+        }
+        if let Ok(re) = regex::Regex::new(&format!(r"(?i)\b{}\b", regex::escape(part))) {
+            extra += re.find_iter(&content).count();
+            content = re.replace_all(&content, pseudonym.as_str()).into_owned();
+        }
+    }
+    
+    BlindedNote {
+        note_id: note.id.clone(),
+        client_pseudonym: pseudonym,
+        note_type: note.note_type.to_string(),
+        content,
+        identifiers_removed: deid.identifiers_found.len() + extra,
+        note_hash: note.content_hash.clone(),
+    }
+}
+
 // ============================================
 // Tauri Commands
 // ============================================
@@ -561,33 +1068,93 @@ pub fn get_note_annotations(
         .collect())
 }
 
-/// Co-sign a note
+/// Active cosign SLA from the organization policy
+fn current_cosign_sla(policy_state: &crate::policy::PolicyState) -> Result<CosignSla, String> {
+    let engine = policy_state.engine.read().map_err(|e| e.to_string())?;
+    Ok(CosignSla::from_policy(&engine.get_policy().supervision_policy))
+}
+
+/// Co-sign a note. The co-signature records the note's true content hash;
+/// `reviewed_note_hash` (from a blinded review) must still match it. It is
+/// marked blinded only if the vault served this supervisor the blinded
+/// view of that same content.
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub fn cosign_note(
     state: State<'_, SupervisionState>,
+    policy_state: State<'_, crate::policy::PolicyState>,
+    app_state: State<'_, crate::commands::AppState>,
     note_id: String,
     supervisor_id: String,
     supervisor_name: String,
     supervisor_credentials: String,
     conditions: Option<String>,
+    reviewed_note_hash: Option<String>,
 ) -> Result<CoSignature, String> {
+    let sla = current_cosign_sla(&policy_state)?;
+    let (note_hash, blinded) = {
+        let vault = app_state.vault.lock().map_err(|_| "Vault mutex poisoned")?;
+        let note_hash = vault.get_note(&note_id).map_err(|e| e.to_string())?.content_hash;
+        let blinded = vault.reviewed_blinded(&note_id, &supervisor_id, &note_hash).map_err(|e| e.to_string())?;
+        (note_hash, blinded)
+    };
+    if let Some(ref reviewed) = reviewed_note_hash {
+        if !crate::crypto::digests_match(reviewed, &note_hash) {
+            return Err("Note changed after it was reviewed; reload before co-signing".to_string());
+        }
+    }
     let mut manager = state.manager.write().map_err(|e| e.to_string())?;
     
-    let cosignature = CoSignature {
-        note_id,
-        supervisor_id,
-        supervisor_name,
-        supervisor_credentials,
-        signed_at: Utc::now(),
-        review_delay_hours: 0.0,  // Would calculate from note signed_at
+    let mut cosignature = manager.prepare_cosignature(
+        &note_id,
+        &supervisor_id,
+        &supervisor_name,
+        &supervisor_credentials,
         conditions,
-        signature: String::new(),  // Would generate cryptographic signature
-    };
+        &sla,
+    );
+    cosignature.note_hash = note_hash;
+    cosignature.blinded = blinded;
     
     manager.add_cosignature(cosignature.clone()).map_err(|e| e.to_string())?;
     Ok(cosignature)
 }
 
+/// Get due-soon and overdue cosigns for the supervisor dashboard.
+/// When the policy enables notifications, emits `cosign-overdue` for the UI to raise a local notification.
+#[tauri::command]
+pub fn get_cosign_escalations(
+    window: tauri::Window,
+    state: State<'_, SupervisionState>,
+    policy_state: State<'_, crate::policy::PolicyState>,
+    supervisor_id: String,
+) -> Result<Vec<CosignEscalation>, String> {
+    let sla = current_cosign_sla(&policy_state)?;
+    let manager = state.manager.read().map_err(|e| e.to_string())?;
+    let escalations = manager.cosign_escalations(&supervisor_id, &sla, Utc::now());
+    
+    let overdue: Vec<&CosignEscalation> = escalations.iter()
+        .filter(|e| e.level == EscalationLevel::Overdue)
+        .collect();
+    if sla.notify_overdue && !overdue.is_empty() {
+        let _ = window.emit("cosign-overdue", overdue.len());
+    }
+    
+    Ok(escalations)
+}
+
+/// Get cosign SLA compliance report
+#[tauri::command]
+pub fn get_cosign_sla_report(
+    state: State<'_, SupervisionState>,
+    policy_state: State<'_, crate::policy::PolicyState>,
+    supervisor_id: Option<String>,
+) -> Result<CosignSlaReport, String> {
+    let sla = current_cosign_sla(&policy_state)?;
+    let manager = state.manager.read().map_err(|e| e.to_string())?;
+    Ok(manager.cosign_sla_report(supervisor_id.as_deref(), &sla, Utc::now()))
+}
+
 /// Get co-signature for a note
 #[tauri::command]
 pub fn get_cosignature(
@@ -683,4 +1250,260 @@ mod tests {
         // Urgent should be first
         assert_eq!(manager.review_queue[0].note_id, "2");
     }
+    
+    #[test]
+    fn test_competency_report_includes_reflections() {
+        let report = crate::models::TraineeCompetencyReport {
+            trainee: crate::models::Trainee {
+                id: "t1".to_string(),
+                name: "Trainee 1".to_string(),
+                email: None,
+                supervisor_id: "super1".to_string(),
+                start_date: "2024-09-01".to_string(),
+                status: "active".to_string(),
+                notes_submitted: 2,
+                notes_approved: 1,
+                created_at: 0,
+            },
+            generated_at: "2024-10-01 09:00".to_string(),
+            reviews_completed: 2,
+            approved: 1,
+            needs_revision: 1,
+            rejected: 0,
+            avg_clinical_accuracy: Some(4.0),
+            avg_documentation_quality: None,
+            reflection_rate: Some(0.5),
+            reflections: vec![crate::models::ReviewReflection {
+                id: "r1".to_string(),
+                review_id: "rev1".to_string(),
+                note_id: "n1".to_string(),
+                trainee_id: "t1".to_string(),
+                what_i_learned: "Document the risk rationale".to_string(),
+                what_i_will_change: "Add a safety plan section".to_string(),
+                created_at: 1_727_000_000,
+                updated_at: 1_727_000_000,
+                acknowledged_at: Some(1_727_100_000),
+                acknowledged_by: Some("super1".to_string()),
+            }],
+            supervision_hours: crate::models::TraineeSupervisionHours {
+                trainee_id: "t1".to_string(),
+                trainee_name: "Trainee 1".to_string(),
+                group_sessions_attended: 3,
+                group_sessions_missed: 1,
+                group_hours: 4.5,
+            },
+        };
+        
+        let md = format_competency_report(&report);
+        assert!(md.contains("Trainee 1"));
+        assert!(md.contains("(50%)"));
+        assert!(md.contains("Document the risk rationale"));
+        assert!(md.contains("Documentation quality: n/a"));
+        assert!(md.contains("Acknowledged by supervisor"));
+        assert!(md.contains("Sessions attended: 3 (missed 1)"));
+    }
+    
+    #[test]
+    fn test_group_supervision_hours_and_log() {
+        use crate::models::{GroupSupervisionAttendee, GroupSupervisionSession, NewGroupSupervisionSession};
+        let attendee = |id: &str, present: bool| GroupSupervisionAttendee {
+            trainee_id: id.to_string(),
+            trainee_name: format!("Trainee {}", id),
+            present,
+        };
+        let session = |date: &str, minutes: i32, attendees: Vec<GroupSupervisionAttendee>| GroupSupervisionSession {
+            id: date.to_string(),
+            supervisor_id: "super1".to_string(),
+            session_date: date.to_string(),
+            duration_minutes: minutes,
+            attendees,
+            topics: vec!["Safety planning".to_string()],
+            cases_discussed: vec![client_pseudonym("super1", "c1")],
+            notes: None,
+            created_at: 0,
+        };
+        let sessions = vec![
+            session("2024-10-01", 90, vec![attendee("a", true), attendee("b", false)]),
+            session("2024-10-08", 60, vec![attendee("a", true), attendee("b", true)]),
+        ];
+        
+        let hours = group_supervision_hours(&sessions);
+        assert_eq!(hours.len(), 2);
+        assert_eq!((hours[0].group_sessions_attended, hours[0].group_hours), (2, 2.5));
+        assert_eq!((hours[1].group_sessions_attended, hours[1].group_sessions_missed, hours[1].group_hours), (1, 1, 1.0));
+        
+        let log = crate::models::GroupSupervisionLog {
+            supervisor_id: "super1".to_string(),
+            generated_at: "2024-11-01 09:00".to_string(),
+            from: Some("2024-10-01".to_string()),
+            to: None,
+            sessions,
+            hours,
+        };
+        let md = format_group_supervision_log(&log);
+        assert!(md.contains("| Trainee a | 2 | 0 | 2.5 |"));
+        assert!(md.contains("- Absent: Trainee b"));
+        assert!(md.contains(&client_pseudonym("super1", "c1")));
+        assert!(!md.contains("c1,") && !md.contains("c1\n"));
+        
+        let mut input = NewGroupSupervisionSession {
+            supervisor_id: "super1".to_string(),
+            session_date: "2024-10-15".to_string(),
+            duration_minutes: 90,
+            present: vec!["a".to_string()],
+            absent: vec!["b".to_string()],
+            topics: vec![],
+            case_client_ids: vec![],
+            notes: None,
+        };
+        assert!(validate_group_session(&input).is_ok());
+        input.absent.push("a".to_string());
+        assert!(validate_group_session(&input).is_err());
+        input.absent.clear();
+        input.session_date = "15/10/2024".to_string();
+        assert!(validate_group_session(&input).is_err());
+    }
+    
+    #[test]
+    fn test_quality_trend_rolling_and_monthly() {
+        use crate::models::ReviewScore;
+        let score = |trainee: &str, date: &str, clinical: Option<i32>, docs: Option<i32>| ReviewScore {
+            trainee_id: trainee.to_string(),
+            completed_at: chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap()
+                .and_hms_opt(12, 0, 0).unwrap().and_utc().timestamp(),
+            clinical_accuracy: clinical,
+            documentation_quality: docs,
+        };
+        let scores = vec![
+            score("t1", "2024-03-10", Some(4), Some(5)),
+            score("t1", "2024-02-01", Some(2), Some(3)),
+            score("t1", "2024-02-20", Some(3), None),
+            score("t2", "2024-03-01", Some(1), Some(1)),
+        ];
+        
+        let trend = quality_trend("t1", &scores, 2);
+        assert_eq!(trend.reviews_completed, 3);
+        // Last two scored reviews only
+        assert_eq!(trend.rolling_clinical_accuracy, Some(3.5));
+        assert_eq!(trend.rolling_documentation_quality, Some(4.0));
+        
+        assert_eq!(trend.monthly.len(), 2);
+        assert_eq!(trend.monthly[0].month, "2024-02");
+        assert_eq!(trend.monthly[0].avg_clinical_accuracy, Some(2.5));
+        assert_eq!(trend.monthly[0].clinical_accuracy_delta, None);
+        assert_eq!(trend.monthly[1].clinical_accuracy_delta, Some(1.5));
+        assert_eq!(trend.monthly[1].documentation_quality_delta, Some(2.0));
+        
+        let empty = quality_trend("t3", &scores, 2);
+        assert_eq!(empty.reviews_completed, 0);
+        assert!(empty.rolling_clinical_accuracy.is_none());
+    }
+    
+    #[test]
+    fn test_cosign_sla_escalation() {
+        let mut manager = SupervisionManager::new();
+        let now = Utc::now();
+        let sla = CosignSla { sla_hours: 7 * 24, due_soon_hours: 24, notify_overdue: false };
+        
+        manager.create_relationship(SupervisionRelationship {
+            id: "rel1".to_string(),
+            supervisor_id: "super1".to_string(),
+            supervisor_name: "Dr. Supervisor".to_string(),
+            supervisor_credentials: "PhD".to_string(),
+            supervisee_id: "sup1".to_string(),
+            supervisee_name: "Trainee 1".to_string(),
+            supervisee_level: CredentialLevel::Intern,
+            start_date: now,
+            end_date: None,
+            cosign_required: true,
+            review_high_risk: true,
+            max_review_hours: 72,
+            active: true,
+        }).unwrap();
+        
+        for (id, days) in [("fresh", 1), ("soon", 6), ("late", 9)] {
+            manager.add_to_review_queue(ReviewQueueItem {
+                note_id: id.to_string(),
+                client_name: "Client".to_string(),
+                note_type: "Progress".to_string(),
+                supervisee_id: "sup1".to_string(),
+                supervisee_name: "Trainee 1".to_string(),
+                signed_at: now - chrono::Duration::days(days),
+                hours_pending: (days * 24) as f64,
+                priority: ReviewPriority::Normal,
+                has_risk_flags: false,
+                detection_count: 0,
+                is_overdue: false,
+            });
+        }
+        
+        let escalations = manager.cosign_escalations("super1", &sla, now);
+        assert_eq!(escalations.len(), 2);
+        assert_eq!(escalations[0].note_id, "late");
+        assert_eq!(escalations[0].level, EscalationLevel::Overdue);
+        assert_eq!(escalations[1].level, EscalationLevel::DueSoon);
+        
+        let late = manager.prepare_cosignature("late", "super1", "Dr. Supervisor", "PhD", None, &sla);
+        assert_eq!(late.met_sla, Some(false));
+        manager.add_cosignature(late).unwrap();
+        let fresh = manager.prepare_cosignature("fresh", "super1", "Dr. Supervisor", "PhD", None, &sla);
+        assert_eq!(fresh.met_sla, Some(true));
+        manager.add_cosignature(fresh).unwrap();
+        
+        // Never queued: no delay to measure, so it is neither met nor missed
+        let unqueued = manager.prepare_cosignature("unqueued", "super1", "Dr. Supervisor", "PhD", None, &sla);
+        assert_eq!(unqueued.met_sla, None);
+        manager.add_cosignature(unqueued).unwrap();
+        
+        let report = manager.cosign_sla_report(Some("super1"), &sla, now);
+        assert_eq!(report.total_cosigned, 2);
+        assert_eq!(report.met_sla, 1);
+        assert_eq!(report.not_applicable, 1);
+        assert_eq!(report.compliance_rate, Some(0.5));
+        assert_eq!(report.currently_overdue, 0);
+    }
+    
+    #[test]
+    fn test_blind_note_pseudonymizes_client() {
+        let client = crate::models::Client {
+            id: "c1".to_string(),
+            display_name: "Maria Gonzalez".to_string(),
+            status: "active".to_string(),
+            session_count: 0,
+            created_at: 0,
+            updated_at: 0,
+            date_of_birth: None,
+            phone: None,
+            email: None,
+            emergency_contact: None,
+            insurance_info: None,
+            diagnosis_codes: None,
+            treatment_start_date: None,
+            referring_provider: None,
+            notes: None,
+        };
+        let note = crate::models::Note {
+            id: "n1".to_string(),
+            client_id: "c1".to_string(),
+            session_date: "2024-03-01".to_string(),
+            note_type: crate::models::NoteType::Progress,
+            raw_input: "Maria reported better sleep. Gonzalez family attended.".to_string(),
+            structured_note: None,
+            word_count: 7,
+            status: crate::models::NoteStatus::Signed,
+            detection_ids: vec![],
+            attestations: vec![],
+            content_hash: "abc123".to_string(),
+            signed_at: None,
+            created_at: 0,
+            updated_at: 0,
+        };
+        
+        let blinded = blind_note(&note, &client, "super1");
+        assert!(!blinded.content.contains("Maria"));
+        assert!(!blinded.content.contains("Gonzalez"));
+        assert_eq!(blinded.note_hash, "abc123");
+        assert_eq!(blinded.client_pseudonym, client_pseudonym("super1", "c1"));
+        assert_ne!(blinded.client_pseudonym, client_pseudonym("super2", "c1"));
+    }
 }
@@ -323,26 +323,190 @@ impl Default for TimeTracker {
     }
 }
 
+// ============================================
+// Session Timers
+// ============================================
+//
+// Start/end timestamps count every coffee break and phone call as
+// documentation time. A session timer instead runs while the clinician
+// works: the editor reports activity (with the note in focus) as they
+// type, gaps longer than TIMER_IDLE_THRESHOLD_MS count as idle, and
+// explicit pauses are excluded. Time between two activity reports goes to
+// the note that was in focus, so one sitting spent on several notes is
+// split between them. Several timers can run at once (one per window).
+
+/// Activity gaps longer than this are idle; only the threshold itself
+/// counts as active
+pub const TIMER_IDLE_THRESHOLD_MS: i64 = 2 * 60 * 1000;
+
+/// Active time attributed to one note within a timer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NoteTime {
+    pub note_id: String,
+    pub active_ms: i64,
+    /// Unix ms of the first and last activity on the note
+    pub first_active_at: i64,
+    pub last_active_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionTimer {
+    pub id: String,
+    /// Unix ms
+    pub started_at: i64,
+    /// Set while paused
+    pub paused_at: Option<i64>,
+    /// Note receiving time until the next activity report
+    pub note_id: Option<String>,
+    pub active_ms: i64,
+    pub idle_ms: i64,
+    pub paused_ms: i64,
+    /// Active time with no note in focus
+    pub unattributed_ms: i64,
+    pub notes: Vec<NoteTime>,
+    /// End of the last interval accounted for
+    #[serde(skip)]
+    last_mark: i64,
+}
+
+/// What a stopped timer measured; wall_ms = active_ms + idle_ms + paused_ms
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimerSummary {
+    pub timer_id: String,
+    pub started_at: i64,
+    pub stopped_at: i64,
+    pub wall_ms: i64,
+    pub active_ms: i64,
+    pub idle_ms: i64,
+    pub paused_ms: i64,
+    pub unattributed_ms: i64,
+    pub notes: Vec<NoteTime>,
+}
+
+impl SessionTimer {
+    pub fn start(id: String, now: i64, note_id: Option<String>) -> Self {
+        SessionTimer {
+            id,
+            started_at: now,
+            paused_at: None,
+            note_id,
+            active_ms: 0,
+            idle_ms: 0,
+            paused_ms: 0,
+            unattributed_ms: 0,
+            notes: Vec::new(),
+            last_mark: now,
+        }
+    }
+    
+    /// Account for the time since the last mark: active up to the idle
+    /// threshold, idle beyond it
+    fn close_interval(&mut self, now: i64) {
+        let gap = (now - self.last_mark).max(0);
+        let active = gap.min(TIMER_IDLE_THRESHOLD_MS);
+        self.active_ms += active;
+        self.idle_ms += gap - active;
+        self.last_mark = self.last_mark.max(now);
+        
+        match &self.note_id {
+            Some(note_id) if active > 0 => {
+                let entry = match self.notes.iter_mut().position(|n| &n.note_id == note_id) {
+                    Some(i) => &mut self.notes[i],
+                    None => {
+                        self.notes.push(NoteTime {
+                            note_id: note_id.clone(),
+                            active_ms: 0,
+                            first_active_at: now - active,
+                            last_active_at: now,
+                        });
+                        self.notes.last_mut().expect("just pushed")
+                    }
+                };
+                entry.active_ms += active;
+                entry.last_active_at = now;
+            }
+            Some(_) => {}
+            None => self.unattributed_ms += active,
+        }
+    }
+    
+    /// The clinician did something; `note_id` is the note now in focus
+    /// (None keeps the current one). Resumes a paused timer.
+    pub fn activity(&mut self, now: i64, note_id: Option<String>) {
+        if self.paused_at.is_some() {
+            self.resume(now);
+        } else {
+            self.close_interval(now);
+        }
+        if note_id.is_some() {
+            self.note_id = note_id;
+        }
+    }
+    
+    pub fn pause(&mut self, now: i64) -> Result<(), String> {
+        if self.paused_at.is_some() {
+            return Err(format!("Timer {} is already paused", self.id));
+        }
+        self.close_interval(now);
+        self.paused_at = Some(now);
+        Ok(())
+    }
+    
+    pub fn resume(&mut self, now: i64) {
+        if let Some(paused_at) = self.paused_at.take() {
+            self.paused_ms += (now - paused_at).max(0);
+            self.last_mark = self.last_mark.max(now);
+        }
+    }
+    
+    pub fn stop(mut self, now: i64) -> TimerSummary {
+        self.resume(now);
+        self.close_interval(now);
+        TimerSummary {
+            timer_id: self.id,
+            started_at: self.started_at,
+            stopped_at: now,
+            wall_ms: now - self.started_at,
+            active_ms: self.active_ms,
+            idle_ms: self.idle_ms,
+            paused_ms: self.paused_ms,
+            unattributed_ms: self.unattributed_ms,
+            notes: self.notes,
+        }
+    }
+}
+
 // ============================================
 // Tauri Commands
 // ============================================
 
 use tauri::State;
-use std::sync::RwLock;
+use std::sync::{Mutex, RwLock};
 use crate::commands::AppState;
 
 pub struct TimeTrackerState {
     pub tracker: RwLock<TimeTracker>,
+    /// Running and paused session timers by id
+    pub timers: Mutex<HashMap<String, SessionTimer>>,
 }
 
 impl Default for TimeTrackerState {
     fn default() -> Self {
         Self {
             tracker: RwLock::new(TimeTracker::new()),
+            timers: Mutex::new(HashMap::new()),
         }
     }
 }
 
+impl TimeTrackerState {
+    fn with_timer<T>(&self, timer_id: &str, f: impl FnOnce(&mut SessionTimer) -> Result<T, String>) -> Result<T, String> {
+        let mut timers = self.timers.lock().map_err(|_| "Timer mutex poisoned")?;
+        let timer = timers.get_mut(timer_id).ok_or_else(|| format!("No timer {}", timer_id))?;
+        f(timer)
+    }
+}
+
 /// Record session timing - persists to vault database
 #[tauri::command]
 pub fn record_time_metrics(
@@ -374,6 +538,7 @@ pub fn record_time_metrics(
             &method,
             word_count as i32,
             ai_assisted,
+            None,
         ).map_err(|e| e.to_string())?;
         
         log::info!("Recorded session metric: note={}, method={}, duration={}s", 
@@ -385,6 +550,141 @@ pub fn record_time_metrics(
     Ok(())
 }
 
+/// Start a session timer, optionally with a note already in focus
+#[tauri::command]
+pub fn start_timer(
+    state: State<'_, TimeTrackerState>,
+    note_id: Option<String>,
+) -> Result<SessionTimer, String> {
+    let timer = SessionTimer::start(uuid::Uuid::new_v4().to_string(), Utc::now().timestamp_millis(), note_id);
+    let mut timers = state.timers.lock().map_err(|_| "Timer mutex poisoned")?;
+    timers.insert(timer.id.clone(), timer.clone());
+    Ok(timer)
+}
+
+/// Editor activity (throttled keystrokes, focus changes) on a timer
+#[tauri::command]
+pub fn record_timer_activity(
+    state: State<'_, TimeTrackerState>,
+    timer_id: String,
+    note_id: Option<String>,
+) -> Result<SessionTimer, String> {
+    let now = Utc::now().timestamp_millis();
+    state.with_timer(&timer_id, |timer| {
+        timer.activity(now, note_id);
+        Ok(timer.clone())
+    })
+}
+
+#[tauri::command]
+pub fn pause_timer(state: State<'_, TimeTrackerState>, timer_id: String) -> Result<SessionTimer, String> {
+    let now = Utc::now().timestamp_millis();
+    state.with_timer(&timer_id, |timer| {
+        timer.pause(now)?;
+        Ok(timer.clone())
+    })
+}
+
+#[tauri::command]
+pub fn resume_timer(state: State<'_, TimeTrackerState>, timer_id: String) -> Result<SessionTimer, String> {
+    let now = Utc::now().timestamp_millis();
+    state.with_timer(&timer_id, |timer| {
+        timer.resume(now);
+        Ok(timer.clone())
+    })
+}
+
+#[tauri::command]
+pub fn get_active_timers(state: State<'_, TimeTrackerState>) -> Result<Vec<SessionTimer>, String> {
+    let timers = state.timers.lock().map_err(|_| "Timer mutex poisoned")?;
+    let mut list: Vec<SessionTimer> = timers.values().cloned().collect();
+    list.sort_by_key(|t| t.started_at);
+    Ok(list)
+}
+
+/// Record one session metric per note the timer attributed time to, in
+/// one transaction. Notes since deleted or trashed (or whose client was)
+/// are skipped. Returns how many were recorded.
+fn record_timer_summary(
+    vault: &crate::vault::Vault,
+    summary: &TimerSummary,
+    method: &str,
+    ai_assisted: bool,
+) -> Result<usize, String> {
+    let conn = vault.get_connection().map_err(|e| e.to_string())?;
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    let mut recorded = 0;
+    for note_time in &summary.notes {
+        let note = match vault.get_note(&note_time.note_id) {
+            Ok(note) => note,
+            Err(crate::vault::VaultError::NotFound(_)) => {
+                log::info!("Timer {}: note no longer in the chart; its time is not recorded", summary.timer_id);
+                continue;
+            }
+            Err(e) => return Err(e.to_string()),
+        };
+        let word_count = crate::vault::Vault::analyzed_text(&note).split_whitespace().count();
+        // Idle time is the timer's; share it by active time
+        let idle_ms = if summary.active_ms > 0 {
+            summary.idle_ms * note_time.active_ms / summary.active_ms
+        } else {
+            0
+        };
+        vault.record_session_metric(
+            &note.id,
+            &note.client_id,
+            note_time.first_active_at / 1000,
+            note_time.last_active_at / 1000,
+            method,
+            word_count as i32,
+            ai_assisted,
+            Some((note_time.active_ms / 1000, idle_ms / 1000)),
+        ).map_err(|e| e.to_string())?;
+        recorded += 1;
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(recorded)
+}
+
+/// Stop a timer and record one session metric per note it attributed
+/// time to, with the active (not wall-clock) time. The timer is taken out
+/// of the tracker before anything is written, so a second stop cannot
+/// record it twice, and put back if the metrics are not saved.
+#[tauri::command]
+pub fn stop_timer(
+    app_state: State<'_, AppState>,
+    tracker_state: State<'_, TimeTrackerState>,
+    timer_id: String,
+    method: String,
+    ai_assisted: bool,
+) -> Result<TimerSummary, String> {
+    let now = Utc::now().timestamp_millis();
+    // Not holding the timer lock while the vault is locked
+    let timer = tracker_state.timers.lock().map_err(|_| "Timer mutex poisoned")?
+        .remove(&timer_id)
+        .ok_or_else(|| format!("No timer {}", timer_id))?;
+    let summary = timer.clone().stop(now);
+    
+    let recorded = {
+        let vault = app_state.vault.lock().map_err(|e| e.to_string())?;
+        if vault.is_unlocked() {
+            record_timer_summary(&vault, &summary, &method, ai_assisted).map(Some)
+        } else {
+            Ok(None)
+        }
+    };
+    match recorded {
+        Ok(Some(count)) => log::info!("Timer {} stopped: {} note(s) recorded, {}s active, {}s idle, {}s paused",
+            timer_id, count, summary.active_ms / 1000, summary.idle_ms / 1000, summary.paused_ms / 1000),
+        Ok(None) => log::warn!("Vault not unlocked, cannot save session metrics"),
+        Err(e) => {
+            tracker_state.timers.lock().map_err(|_| "Timer mutex poisoned")?.insert(timer_id, timer);
+            return Err(e);
+        }
+    }
+    Ok(summary)
+}
+
 /// Get time metrics for period - reads from vault database
 #[tauri::command]
 pub fn get_time_metrics(
@@ -425,7 +725,7 @@ pub fn get_time_metrics(
     // Calculate aggregated metrics
     let total_notes = sessions.len() as u32;
     let total_time_seconds: u64 = sessions.iter()
-        .map(|s| (s.end_time - s.start_time).max(0) as u64)
+        .map(|s| s.duration_seconds())
         .sum();
     
     let avg_time_seconds = if total_notes > 0 {
@@ -437,7 +737,7 @@ pub fn get_time_metrics(
     let voice_sessions: Vec<_> = sessions.iter().filter(|s| s.method == "voice").collect();
     let voice_scribe_count = voice_sessions.len() as u32;
     let voice_total: u64 = voice_sessions.iter()
-        .map(|s| (s.end_time - s.start_time).max(0) as u64)
+        .map(|s| s.duration_seconds())
         .sum();
     let voice_scribe_avg_seconds = if voice_scribe_count > 0 {
         voice_total as f64 / voice_scribe_count as f64
@@ -448,7 +748,7 @@ pub fn get_time_metrics(
     let typed_sessions: Vec<_> = sessions.iter().filter(|s| s.method == "typed" || s.method == "manual").collect();
     let typed_count = typed_sessions.len() as u32;
     let typed_total: u64 = typed_sessions.iter()
-        .map(|s| (s.end_time - s.start_time).max(0) as u64)
+        .map(|s| s.duration_seconds())
         .sum();
     let typed_avg_seconds = if typed_count > 0 {
         typed_total as f64 / typed_count as f64
@@ -489,7 +789,7 @@ fn build_weekly_stats(sessions: &[crate::vault::SessionMetricRow]) -> Vec<Weekly
         let week_start = dt - Duration::days(dt.weekday().num_days_from_monday() as i64);
         let week_key = week_start.format("%Y-%m-%d").to_string();
         
-        let duration = (session.end_time - session.start_time).max(0) as u64;
+        let duration = session.duration_seconds();
         let entry = weeks.entry(week_key).or_insert((0, 0));
         entry.0 += 1;
         entry.1 += duration;
@@ -566,4 +866,51 @@ mod tests {
         assert_eq!(metrics.voice_scribe_count, 5);
         assert!(metrics.estimated_time_saved_seconds > 0);
     }
+    
+    #[test]
+    fn test_session_timer_idle_and_attribution() {
+        let min = 60 * 1000;
+        let mut timer = SessionTimer::start("t1".to_string(), 0, Some("note-a".to_string()));
+        timer.activity(min, None);
+        timer.activity(2 * min, Some("note-b".to_string()));  // note-a gets 2 min
+        timer.activity(12 * min, None);                       // 10-min gap: 2 active, 8 idle
+        timer.pause(13 * min).unwrap();
+        assert!(timer.pause(14 * min).is_err());
+        timer.activity(20 * min, None);                       // resumes; 7 min paused
+        let summary = timer.stop(21 * min);
+        
+        assert_eq!(summary.wall_ms, 21 * min);
+        assert_eq!((summary.active_ms, summary.idle_ms, summary.paused_ms), (6 * min, 8 * min, 7 * min));
+        assert_eq!(summary.wall_ms, summary.active_ms + summary.idle_ms + summary.paused_ms);
+        assert_eq!(summary.notes.len(), 2);
+        assert_eq!((summary.notes[0].note_id.as_str(), summary.notes[0].active_ms), ("note-a", 2 * min));
+        assert_eq!((summary.notes[1].note_id.as_str(), summary.notes[1].active_ms), ("note-b", 4 * min));
+        assert_eq!(summary.notes[1].last_active_at, 21 * min);
+        assert_eq!(summary.unattributed_ms, 0);
+    }
+    
+    #[test]
+    fn test_timer_summary_skips_notes_no_longer_in_the_chart() {
+        use crate::models::NoteType;
+        let fixture = crate::vault::testing::FixtureBuilder::new("timer")
+            .client("Client A")
+            .note("2024-03-01", NoteType::Progress, "Kept session.")
+            .note("2024-03-08", NoteType::Progress, "Trashed draft.")
+            .build()
+            .unwrap();
+        let vault = &fixture.vault;
+        vault.trash_note(&fixture.notes[1].id).unwrap();
+        
+        let min = 60 * 1000;
+        let mut timer = SessionTimer::start("t1".to_string(), 1_000 * min, Some(fixture.notes[0].id.clone()));
+        timer.activity(1_001 * min, Some(fixture.notes[1].id.clone()));
+        timer.activity(1_002 * min, Some("deleted-note".to_string()));
+        let summary = timer.stop(1_003 * min);
+        assert_eq!(summary.notes.len(), 3);
+        
+        assert_eq!(record_timer_summary(vault, &summary, "typed", false).unwrap(), 1);
+        let rows = vault.get_session_metrics(0).unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].note_id, fixture.notes[0].id);
+    }
 }
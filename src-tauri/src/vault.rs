@@ -19,7 +19,56 @@ use regex::Regex;
 use chrono::Datelike;
 
 use crate::crypto::{self, KEK, VaultKey, WrappedVaultKey};
-use crate::models::{Client, ClientSearchResult, Note, NoteStatus, NoteType, StoredDetection, TreatmentProgress, ProgressTheme};
+use crate::models::{AccessReason, ChartAccessGrant, Client, ExportPresenceCheck, ClientSearchResult, Note, EmergencyAccessSession, NoteLock, NoteLockOutcome, NoteStatus, NoteType, StoredDetection, TreatmentProgress, ProgressTheme};
+
+/// In-memory fixture vaults for tests
+#[cfg(test)]
+pub mod testing;
+
+/// Rule version stamped on cached artifacts built by deterministic code;
+/// a new app version invalidates them
+pub const DERIVED_RULES_VERSION: &str = concat!("rules=", env!("CARGO_PKG_VERSION"));
+
+/// Default note lock lifetime; the editor re-acquires as a heartbeat
+const NOTE_LOCK_TTL_SECS: i64 = 120;
+const NOTE_LOCK_MAX_TTL_SECS: i64 = 3600;
+
+/// Days a trashed note or client stays restorable before it is purged
+pub const TRASH_RETENTION_DAYS: i64 = 30;
+
+/// Status of a client row left behind by `purge_client`
+const TRASH_PURGED_STATUS: &str = "purged";
+
+/// Break-glass limits: session length cap, how far back notes are visible,
+/// and minimum lengths for the credential and justification
+pub const EMERGENCY_ACCESS_MAX_MINUTES: u32 = 240;
+pub const EMERGENCY_ACCESS_NOTE_WINDOW_DAYS: i64 = 90;
+const EMERGENCY_CREDENTIAL_MIN_CHARS: usize = 8;
+const AUTHOR_CREDENTIAL_MIN_CHARS: usize = 6;
+const EMERGENCY_JUSTIFICATION_MIN_CHARS: usize = 20;
+
+const EMERGENCY_SESSION_SELECT: &str =
+    "SELECT id, client_id, covering_clinician, justification, audit_entry_id, activated_at, expires_at,
+            ended_at, access_count, review_status, reviewed_by, reviewed_at, review_note
+     FROM emergency_access_sessions";
+
+fn map_emergency_session(row: &rusqlite::Row) -> rusqlite::Result<EmergencyAccessSession> {
+    Ok(EmergencyAccessSession {
+        id: row.get(0)?,
+        client_id: row.get(1)?,
+        covering_clinician: row.get(2)?,
+        justification: row.get(3)?,
+        audit_entry_id: row.get(4)?,
+        activated_at: row.get(5)?,
+        expires_at: row.get(6)?,
+        ended_at: row.get(7)?,
+        access_count: row.get(8)?,
+        review_status: row.get(9)?,
+        reviewed_by: row.get(10)?,
+        reviewed_at: row.get(11)?,
+        review_note: row.get(12)?,
+    })
+}
 
 /// Extract a number from a query string (for semantic search)
 fn extract_number(s: &str) -> Option<u32> {
@@ -27,6 +76,66 @@ fn extract_number(s: &str) -> Option<u32> {
     re.captures(s).and_then(|cap| cap.get(1).and_then(|m| m.as_str().parse().ok()))
 }
 
+/// Lowercased query terms used by the global search
+fn search_terms(query: &str) -> Vec<String> {
+    query
+        .to_lowercase()
+        .split_whitespace()
+        .filter(|w| w.len() >= 2)
+        .map(|w| w.to_string())
+        .collect()
+}
+
+/// `%term%` for `LIKE ... ESCAPE '\'`, so `%`, `_` and `\` in the term
+/// match themselves rather than acting as wildcards
+fn like_contains(term: &str) -> String {
+    let mut pattern = String::with_capacity(term.len() + 2);
+    pattern.push('%');
+    for c in term.chars() {
+        if matches!(c, '\\' | '%' | '_') {
+            pattern.push('\\');
+        }
+        pattern.push(c);
+    }
+    pattern.push('%');
+    pattern
+}
+
+/// Fraction of query terms present in `text` (case-insensitive)
+fn term_score(text: &str, terms: &[String]) -> f32 {
+    if terms.is_empty() {
+        return 0.0;
+    }
+    let lower = text.to_lowercase();
+    let hits = terms.iter().filter(|t| lower.contains(t.as_str())).count();
+    hits as f32 / terms.len() as f32
+}
+
+/// Short excerpt centred on the first matching term
+fn excerpt_around(text: &str, terms: &[String], radius: usize) -> String {
+    let lower = text.to_lowercase();
+    let first = terms.iter().filter_map(|t| lower.find(t.as_str())).min();
+    
+    let chars: Vec<char> = text.chars().collect();
+    let centre = match first {
+        // Lowercasing can change byte lengths, so map back via char count
+        Some(byte_idx) => lower[..byte_idx].chars().count().min(chars.len()),
+        None => 0,
+    };
+    let start = centre.saturating_sub(radius);
+    let end = (centre + radius).min(chars.len());
+    
+    let mut excerpt: String = chars[start..end].iter().collect();
+    excerpt = excerpt.split_whitespace().collect::<Vec<_>>().join(" ");
+    if start > 0 {
+        excerpt = format!("...{}", excerpt);
+    }
+    if end < chars.len() {
+        excerpt.push_str("...");
+    }
+    excerpt
+}
+
 #[derive(Error, Debug)]
 pub enum VaultError {
     #[error("Vault not initialized")]
@@ -85,6 +194,72 @@ pub enum VaultStateType {
     Unlocked,          // Currently unlocked
 }
 
+/// Outcome of `Vault::change_passphrase`
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct PassphraseChange {
+    pub key_rotated: bool,
+    pub vectors_resealed: usize,
+    /// Database backups re-encrypted under the new key
+    pub backups_rekeyed: usize,
+    /// Database backups that could not be re-encrypted and still open only
+    /// with the key in use before rotation
+    pub backups_under_previous_key: usize,
+}
+
+/// Database file and key of an unlocked vault, for copying it without
+/// holding the vault's connection (Vault::snapshot_source)
+pub struct SnapshotSource {
+    database: PathBuf,
+    key: VaultKey,
+    data_dir: PathBuf,
+}
+
+impl SnapshotSource {
+    pub fn data_dir(&self) -> &std::path::Path {
+        &self.data_dir
+    }
+    
+    /// Copy the database to `path` (which must not exist) and open the
+    /// copy read-only as its own Vault. A separate connection holds a read
+    /// transaction for the length of the copy, so writers wait only to
+    /// commit, and the pages are copied still encrypted.
+    pub fn copy_to(&self, path: &std::path::Path) -> Result<Vault, VaultError> {
+        let key = format!("x'{}'", self.key.as_hex());
+        let reader = Connection::open_with_flags(
+            &self.database,
+            rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY | rusqlite::OpenFlags::SQLITE_OPEN_NO_MUTEX,
+        )?;
+        reader.pragma_update(None, "key", &key)?;
+        reader.busy_timeout(std::time::Duration::from_secs(5))?;
+        reader.execute_batch("BEGIN")?;
+        // The first read takes the shared lock that keeps commits out
+        let copied = reader.query_row("SELECT count(*) FROM sqlite_master", [], |_| Ok(()))
+            .map_err(VaultError::from)
+            .and_then(|_| {
+                std::fs::copy(&self.database, path)
+                    .map_err(|e| VaultError::Internal(format!("Snapshot copy failed: {}", e)))
+            });
+        reader.execute_batch("COMMIT").ok();
+        drop(reader);
+        if let Err(e) = copied {
+            std::fs::remove_file(path).ok();
+            return Err(e);
+        }
+        
+        let snapshot = Connection::open_with_flags(
+            path,
+            rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY | rusqlite::OpenFlags::SQLITE_OPEN_NO_MUTEX,
+        )?;
+        snapshot.pragma_update(None, "key", &key)?;
+        snapshot.query_row("SELECT count(*) FROM sqlite_master", [], |_| Ok(()))?;
+        Ok(Vault {
+            conn: Some(snapshot),
+            vault_key: Some(VaultKey(self.key.0)),
+            data_dir: self.data_dir.clone(),
+        })
+    }
+}
+
 /// Vault state
 pub struct Vault {
     conn: Option<Connection>,
@@ -176,6 +351,11 @@ impl Vault {
         self.data_dir.join("vault.db")
     }
     
+    /// Directory holding the vault database
+    pub fn data_dir(&self) -> &std::path::Path {
+        &self.data_dir
+    }
+    
     /// Create a new vault with passphrase
     /// 
     /// TRANSACTIONAL: Database is created first, keychain entries stored only on success.
@@ -268,9 +448,55 @@ impl Vault {
         let salt = crypto::retrieve_salt()?;
         let wrapped = crypto::retrieve_wrapped_key()?;
         
-        // Derive KEK and unwrap vault key
-        let kek = KEK::derive(passphrase, &salt)?;
-        let vault_key = kek.unwrap(&wrapped)
+        let (kek, vault_key, conn, wrapped) = match self.open_with_credentials(passphrase, &salt, &wrapped) {
+            Ok((kek, vault_key, conn)) => {
+                // A staged change that never took effect
+                crypto::clear_pending_credentials();
+                (kek, vault_key, conn, wrapped)
+            }
+            Err(VaultError::InvalidPassphrase) => self.finish_pending_change(passphrase)?,
+            Err(e) => return Err(e),
+        };
+        
+        // Move keys wrapped with an older algorithm onto the current one.
+        // Best effort: the old entry still unlocks if the keychain write fails.
+        if wrapped.needs_rewrap() {
+            match kek.wrap(&vault_key).and_then(|w| crypto::store_wrapped_key(&w)) {
+                Ok(()) => log::info!("Vault key re-wrapped with {:?}", crypto::WrapAlgorithm::CURRENT),
+                Err(e) => log::warn!("Vault key re-wrap failed, keeping {:?}: {}", wrapped.algorithm, e),
+            }
+        }
+        
+        // Run migrations for schema updates on existing databases
+        self.run_migrations(&conn)?;
+        crate::ids::set_scheme(Self::stored_id_scheme(&conn));
+        
+        // Seal any vectors written before per-client encryption existed
+        match crypto::vectors::VectorKeyring::from_vault_key(&vault_key)
+            .map_err(|e| e.to_string())
+            .and_then(|keys| crate::rag::seal_legacy_vectors(&conn, &keys).map_err(|e| e.to_string()))
+        {
+            Ok(0) => {}
+            Ok(n) => log::info!("Sealed {} legacy embedding vectors", n),
+            Err(e) => log::warn!("Legacy embedding vectors left unsealed: {}", e),
+        }
+        
+        self.conn = Some(conn);
+        self.vault_key = Some(vault_key);
+        
+        log::info!("Vault unlocked successfully");
+        Ok(())
+    }
+    
+    /// Derive the KEK, unwrap the vault key and open the database with it
+    fn open_with_credentials(
+        &self,
+        passphrase: &str,
+        salt: &[u8; 16],
+        wrapped: &WrappedVaultKey,
+    ) -> Result<(KEK, VaultKey, Connection), VaultError> {
+        let kek = KEK::derive(passphrase, salt)?;
+        let vault_key = kek.unwrap(wrapped)
             .map_err(|_| VaultError::InvalidPassphrase)?;
         
         // Open encrypted database
@@ -280,15 +506,168 @@ impl Vault {
         // Verify we can read (will fail if wrong key)
         conn.query_row("SELECT count(*) FROM sqlite_master", [], |_| Ok(()))
             .map_err(|_| VaultError::InvalidPassphrase)?;
+        Ok((kek, vault_key, conn))
+    }
+    
+    /// Unlock with credentials staged by a passphrase change that was cut
+    /// short, and write them to the keychain so the change is complete
+    fn finish_pending_change(
+        &self,
+        passphrase: &str,
+    ) -> Result<(KEK, VaultKey, Connection, WrappedVaultKey), VaultError> {
+        let (salt, wrapped) = crypto::retrieve_pending_credentials().ok_or(VaultError::InvalidPassphrase)?;
+        let (kek, vault_key, conn) = self.open_with_credentials(passphrase, &salt, &wrapped)?;
+        match crypto::store_salt(&salt).and_then(|_| crypto::store_wrapped_key(&wrapped)) {
+            Ok(()) => {
+                crypto::clear_pending_credentials();
+                log::info!("Finished an interrupted passphrase change");
+            }
+            Err(e) => log::warn!("Interrupted passphrase change still pending: {}", e),
+        }
+        Ok((kek, vault_key, conn, wrapped))
+    }
+    
+    /// Change the passphrase: verify `old`, wrap the vault key under a KEK
+    /// derived from `new` and a fresh salt, and replace the keychain entries.
+    /// The new pair is staged in the keychain first, so an interrupted
+    /// change is finished by the next unlock with `new`.
+    /// 
+    /// With `rotate_key`, the vault key itself is replaced: the database and
+    /// the maintenance backups in `backups/` are re-encrypted (`PRAGMA
+    /// rekey`) and embedding vectors are resealed. A backup that cannot be
+    /// re-encrypted is counted in the result; it still needs the previous
+    /// key, which is no longer in the keychain.
+    pub fn change_passphrase(
+        &mut self,
+        old: &str,
+        new: &str,
+        rotate_key: bool,
+    ) -> Result<PassphraseChange, VaultError> {
+        let conn = self.conn()?;
+        let current_key = self.vault_key.as_ref().ok_or(VaultError::Locked)?;
+        
+        let old_salt = crypto::retrieve_salt()?;
+        let old_wrapped = crypto::retrieve_wrapped_key()?;
+        let unwrapped = KEK::derive(old, &old_salt)?.unwrap(&old_wrapped)
+            .map_err(|_| VaultError::InvalidPassphrase)?;
+        if !crypto::constant_time_eq(&unwrapped.0, &current_key.0) {
+            return Err(VaultError::InvalidPassphrase);
+        }
         
-        // Run migrations for schema updates on existing databases
-        self.run_migrations(&conn)?;
+        let new_key = if rotate_key { VaultKey::generate() } else { VaultKey(current_key.0) };
+        let salt = crypto::generate_salt();
+        let wrapped = KEK::derive(new, &salt)?.wrap(&new_key)?;
+        crypto::stage_credentials(&salt, &wrapped)?;
+        
+        let mut vectors_resealed = 0;
+        if rotate_key {
+            let rekeyed = conn.pragma_update(None, "rekey", &format!("x'{}'", new_key.as_hex()))
+                .and_then(|_| conn.query_row("SELECT count(*) FROM sqlite_master", [], |_| Ok(())));
+            if let Err(e) = rekeyed {
+                crypto::clear_pending_credentials();
+                return Err(e.into());
+            }
+            
+            // Vector keys derive from the vault key. Embeddings are derived
+            // data, so if they cannot be resealed they are dropped and
+            // rebuilt by the next reindex.
+            let resealed = crypto::vectors::VectorKeyring::from_vault_key(current_key)
+                .and_then(|old_keys| {
+                    crypto::vectors::VectorKeyring::from_vault_key(&new_key).map(|new_keys| (old_keys, new_keys))
+                })
+                .map_err(|e| e.to_string())
+                .and_then(|(old_keys, new_keys)| {
+                    crate::rag::reseal_vectors(conn, &old_keys, &new_keys).map_err(|e| e.to_string())
+                });
+            match resealed {
+                Ok(n) => vectors_resealed = n,
+                Err(e) => {
+                    log::warn!("Embedding vectors not resealed, dropping them for reindex: {}", e);
+                    conn.execute("DELETE FROM embeddings", [])?;
+                }
+            }
+        }
         
-        self.conn = Some(conn);
-        self.vault_key = Some(vault_key);
+        let stored = crypto::store_salt(&salt).and_then(|_| crypto::store_wrapped_key(&wrapped));
+        if let Err(e) = stored {
+            if rotate_key {
+                // The database is under the new key now; the staged entry is
+                // the only way back in, and the next unlock will finish with it
+                self.vault_key = Some(new_key);
+                return Err(VaultError::Internal(format!(
+                    "Vault re-keyed but the keychain update failed ({}); unlock with the new passphrase to finish",
+                    e
+                )));
+            }
+            let restored = crypto::store_salt(&old_salt).and_then(|_| crypto::store_wrapped_key(&old_wrapped));
+            if restored.is_ok() {
+                crypto::clear_pending_credentials();
+            }
+            return Err(e.into());
+        }
+        crypto::clear_pending_credentials();
         
-        log::info!("Vault unlocked successfully");
-        Ok(())
+        let (backups_rekeyed, backups_under_previous_key) = if rotate_key {
+            Self::rekey_backups(&self.data_dir.join("backups"), current_key, &new_key)
+        } else {
+            (0, 0)
+        };
+        self.vault_key = Some(new_key);
+        
+        log::info!("Vault passphrase changed{}", if rotate_key { " and key rotated" } else { "" });
+        Ok(PassphraseChange {
+            key_rotated: rotate_key,
+            vectors_resealed,
+            backups_rekeyed,
+            backups_under_previous_key,
+        })
+    }
+    
+    /// Maintenance backups (`vault-<ts>.db`) in `dir`, oldest first
+    fn list_maintenance_backups(dir: &std::path::Path) -> std::io::Result<Vec<PathBuf>> {
+        let mut backups: Vec<PathBuf> = std::fs::read_dir(dir)?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|p| {
+                p.file_name().and_then(|n| n.to_str())
+                    .map(|n| n.starts_with("vault-") && n.ends_with(".db"))
+                    .unwrap_or(false)
+            })
+            .collect();
+        // Timestamped names sort chronologically
+        backups.sort();
+        Ok(backups)
+    }
+    
+    /// Re-encrypt the maintenance backups in `dir` from `old` to `new`.
+    /// Returns (re-encrypted, left under `old`); a backup that fails is
+    /// left as it was.
+    fn rekey_backups(dir: &std::path::Path, old: &VaultKey, new: &VaultKey) -> (usize, usize) {
+        let backups = match Self::list_maintenance_backups(dir) {
+            Ok(backups) => backups,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return (0, 0),
+            Err(e) => {
+                log::warn!("Cannot list backups to re-key: {}", e);
+                return (0, 0);
+            }
+        };
+        
+        let (mut rekeyed, mut failed) = (0, 0);
+        for path in &backups {
+            let result = Connection::open(path).and_then(|conn| {
+                conn.pragma_update(None, "key", format!("x'{}'", old.as_hex()))?;
+                conn.query_row("SELECT count(*) FROM sqlite_master", [], |_| Ok(()))?;
+                conn.pragma_update(None, "rekey", format!("x'{}'", new.as_hex()))?;
+                conn.query_row("SELECT count(*) FROM sqlite_master", [], |_| Ok(()))
+            });
+            match result {
+                Ok(()) => rekeyed += 1,
+                Err(e) => {
+                    log::warn!("Backup {} left under the previous key: {}", path.display(), e);
+                    failed += 1;
+                }
+            }
+        }
+        (rekeyed, failed)
     }
     
     /// Lock vault (clear keys from memory)
@@ -308,6 +687,12 @@ impl Vault {
         self.conn.as_ref().ok_or(VaultError::Locked)
     }
     
+    /// Keys for sealing and opening embedding vectors (RAG index)
+    pub fn vector_keyring(&self) -> Result<crypto::vectors::VectorKeyring, VaultError> {
+        let vault_key = self.vault_key.as_ref().ok_or(VaultError::Locked)?;
+        Ok(crypto::vectors::VectorKeyring::from_vault_key(vault_key)?)
+    }
+    
     fn conn(&self) -> Result<&Connection, VaultError> {
         self.conn.as_ref().ok_or(VaultError::Locked)
     }
@@ -438,6 +823,7 @@ impl Vault {
             CREATE INDEX IF NOT EXISTS idx_embeddings_note ON embeddings(note_id);
             CREATE INDEX IF NOT EXISTS idx_audit_timestamp ON audit_log(timestamp);
             CREATE INDEX IF NOT EXISTS idx_audit_sequence ON audit_log(sequence);
+            CREATE INDEX IF NOT EXISTS idx_audit_resource ON audit_log(resource_id);
             CREATE INDEX IF NOT EXISTS idx_session_metrics_time ON session_metrics(start_time);
             CREATE INDEX IF NOT EXISTS idx_session_metrics_note ON session_metrics(note_id);
             CREATE INDEX IF NOT EXISTS idx_documents_client ON client_documents(client_id);
@@ -613,1959 +999,7780 @@ impl Vault {
             Err(e) => log::error!("Failed to create de-identification tables: {}", e),
         }
         
-        log::info!("Database migrations complete");
-        Ok(())
-    }
-    
-    // ============================================
-    // Client Operations
-    // ============================================
-    
-    pub fn create_client(&self, display_name: &str) -> Result<Client, VaultError> {
-        let conn = self.conn()?;
+        // Migration v4.2.8: Trusted recipient keys for consultation sharing
+        match conn.execute_batch(r#"
+            CREATE TABLE IF NOT EXISTS trusted_recipients (
+                id TEXT PRIMARY KEY,
+                label TEXT NOT NULL,
+                public_key TEXT NOT NULL,
+                fingerprint TEXT NOT NULL UNIQUE,
+                created_at INTEGER NOT NULL,
+                revoked_at INTEGER
+            );
+        "#) {
+            Ok(_) => log::info!("Trusted recipient table ready"),
+            Err(e) => log::error!("Failed to create trusted recipient table: {}", e),
+        }
         
-        let id = uuid::Uuid::new_v4().to_string();
-        let now = chrono::Utc::now().timestamp_millis();
+        // Migration v4.2.8: Structured mental status exams
+        match conn.execute_batch(r#"
+            CREATE TABLE IF NOT EXISTS mental_status_exams (
+                note_id TEXT PRIMARY KEY,
+                client_id TEXT NOT NULL,
+                session_date TEXT NOT NULL,
+                exam TEXT NOT NULL,              -- JSON MentalStatusExam
+                total_severity INTEGER NOT NULL,
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL,
+                
+                FOREIGN KEY (note_id) REFERENCES notes(id) ON DELETE CASCADE
+            );
+            
+            CREATE INDEX IF NOT EXISTS idx_mse_client_date ON mental_status_exams(client_id, session_date);
+        "#) {
+            Ok(_) => log::info!("Mental status exam table ready"),
+            Err(e) => log::error!("Failed to create mental status exam table: {}", e),
+        }
         
-        conn.execute(
-            "INSERT INTO clients (id, display_name, status, session_count, created_at, updated_at) 
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
-            params![&id, display_name, "active", 0i32, now, now],
-        )?;
+        // Migration v4.2.8: Advisory note edit locks
+        match conn.execute_batch(r#"
+            CREATE TABLE IF NOT EXISTS note_locks (
+                note_id TEXT PRIMARY KEY,
+                holder_id TEXT NOT NULL,
+                holder_label TEXT,
+                acquired_at INTEGER NOT NULL,
+                expires_at INTEGER NOT NULL,
+                
+                FOREIGN KEY (note_id) REFERENCES notes(id) ON DELETE CASCADE
+            );
+        "#) {
+            Ok(_) => log::info!("Note lock table ready"),
+            Err(e) => log::error!("Failed to create note lock table: {}", e),
+        }
         
-        Ok(Client {
-            id,
-            display_name: display_name.to_string(),
-            status: "active".to_string(),
-            session_count: 0,
-            created_at: now,
-            updated_at: now,
-            date_of_birth: None,
-            phone: None,
-            email: None,
-            emergency_contact: None,
-            insurance_info: None,
-            diagnosis_codes: None,
-            treatment_start_date: None,
-            referring_provider: None,
-            notes: None,
-        })
-    }
-    
-    pub fn list_clients(&self) -> Result<Vec<Client>, VaultError> {
-        let conn = self.conn()?;
+        // Migration v4.2.8: Trigger-maintained counters for dashboards
+        // (one indexed read instead of a COUNT(*) per widget under the vault lock)
+        match conn.execute_batch(r#"
+            CREATE TABLE IF NOT EXISTS vault_counters (
+                name TEXT PRIMARY KEY,
+                value INTEGER NOT NULL DEFAULT 0
+            );
+            
+            CREATE TRIGGER IF NOT EXISTS trg_counters_client_insert AFTER INSERT ON clients BEGIN
+                INSERT INTO vault_counters (name, value) VALUES ('clients', 1)
+                    ON CONFLICT(name) DO UPDATE SET value = value + 1;
+            END;
+            CREATE TRIGGER IF NOT EXISTS trg_counters_client_delete AFTER DELETE ON clients BEGIN
+                UPDATE vault_counters SET value = value - 1 WHERE name = 'clients';
+            END;
+            
+            CREATE TRIGGER IF NOT EXISTS trg_counters_note_insert AFTER INSERT ON notes BEGIN
+                INSERT INTO vault_counters (name, value) VALUES ('notes', 1)
+                    ON CONFLICT(name) DO UPDATE SET value = value + 1;
+                INSERT INTO vault_counters (name, value) VALUES ('notes_status:' || NEW.status, 1)
+                    ON CONFLICT(name) DO UPDATE SET value = value + 1;
+            END;
+            CREATE TRIGGER IF NOT EXISTS trg_counters_note_delete AFTER DELETE ON notes BEGIN
+                UPDATE vault_counters SET value = value - 1 WHERE name = 'notes';
+                UPDATE vault_counters SET value = value - 1 WHERE name = 'notes_status:' || OLD.status;
+            END;
+            CREATE TRIGGER IF NOT EXISTS trg_counters_note_status AFTER UPDATE OF status ON notes
+                WHEN OLD.status IS NOT NEW.status BEGIN
+                UPDATE vault_counters SET value = value - 1 WHERE name = 'notes_status:' || OLD.status;
+                INSERT INTO vault_counters (name, value) VALUES ('notes_status:' || NEW.status, 1)
+                    ON CONFLICT(name) DO UPDATE SET value = value + 1;
+            END;
+            
+            CREATE TRIGGER IF NOT EXISTS trg_counters_document_insert AFTER INSERT ON client_documents BEGIN
+                INSERT INTO vault_counters (name, value) VALUES ('documents', 1)
+                    ON CONFLICT(name) DO UPDATE SET value = value + 1;
+                INSERT INTO vault_counters (name, value) VALUES ('document_bytes', COALESCE(NEW.file_size, 0))
+                    ON CONFLICT(name) DO UPDATE SET value = value + COALESCE(NEW.file_size, 0);
+            END;
+            CREATE TRIGGER IF NOT EXISTS trg_counters_document_delete AFTER DELETE ON client_documents BEGIN
+                UPDATE vault_counters SET value = value - 1 WHERE name = 'documents';
+                UPDATE vault_counters SET value = value - COALESCE(OLD.file_size, 0) WHERE name = 'document_bytes';
+            END;
+            
+            CREATE TRIGGER IF NOT EXISTS trg_counters_review_insert AFTER INSERT ON note_reviews BEGIN
+                INSERT INTO vault_counters (name, value) VALUES ('reviews_status:' || NEW.status, 1)
+                    ON CONFLICT(name) DO UPDATE SET value = value + 1;
+            END;
+            CREATE TRIGGER IF NOT EXISTS trg_counters_review_delete AFTER DELETE ON note_reviews BEGIN
+                UPDATE vault_counters SET value = value - 1 WHERE name = 'reviews_status:' || OLD.status;
+            END;
+            CREATE TRIGGER IF NOT EXISTS trg_counters_review_status AFTER UPDATE OF status ON note_reviews
+                WHEN OLD.status IS NOT NEW.status BEGIN
+                UPDATE vault_counters SET value = value - 1 WHERE name = 'reviews_status:' || OLD.status;
+                INSERT INTO vault_counters (name, value) VALUES ('reviews_status:' || NEW.status, 1)
+                    ON CONFLICT(name) DO UPDATE SET value = value + 1;
+            END;
+            
+            CREATE VIEW IF NOT EXISTS dashboard_counts AS
+            SELECT
+                COALESCE((SELECT value FROM vault_counters WHERE name = 'clients'), 0) AS clients,
+                COALESCE((SELECT value FROM vault_counters WHERE name = 'notes'), 0) AS notes,
+                COALESCE((SELECT value FROM vault_counters WHERE name = 'notes_status:draft'), 0) AS draft_notes,
+                COALESCE((SELECT value FROM vault_counters WHERE name = 'notes_status:signed'), 0) AS signed_notes,
+                COALESCE((SELECT value FROM vault_counters WHERE name = 'notes_status:amended'), 0) AS amended_notes,
+                COALESCE((SELECT value FROM vault_counters WHERE name = 'documents'), 0) AS documents,
+                COALESCE((SELECT value FROM vault_counters WHERE name = 'document_bytes'), 0) AS document_bytes,
+                COALESCE((SELECT value FROM vault_counters WHERE name = 'reviews_status:pending'), 0) AS pending_reviews;
+        "#) {
+            Ok(_) => log::info!("Dashboard counters ready"),
+            Err(e) => log::error!("Failed to create dashboard counters: {}", e),
+        }
         
-        let mut stmt = conn.prepare(
-            "SELECT id, display_name, status, session_count, created_at, updated_at,
-                    date_of_birth, phone, email, emergency_contact, insurance_info,
-                    diagnosis_codes, treatment_start_date, referring_provider, notes
-             FROM clients ORDER BY display_name"
-        )?;
+        // Migration v4.2.8: Reason-for-access grants on restricted charts
+        match conn.execute_batch(r#"
+            CREATE TABLE IF NOT EXISTS chart_access_grants (
+                id TEXT PRIMARY KEY,
+                client_id TEXT NOT NULL,
+                audit_entry_id TEXT NOT NULL,
+                reason TEXT NOT NULL,
+                justification TEXT,
+                created_at INTEGER NOT NULL,
+                expires_at INTEGER NOT NULL,
+                FOREIGN KEY (client_id) REFERENCES clients(id)
+            );
+            
+            CREATE INDEX IF NOT EXISTS idx_chart_access_client ON chart_access_grants(client_id, expires_at);
+        "#) {
+            Ok(_) => log::info!("Chart access grants table ready"),
+            Err(e) => log::error!("Failed to create chart access grants table: {}", e),
+        }
         
-        let rows = stmt.query_map([], |row| {
-            Ok(Client {
-                id: row.get(0)?,
-                display_name: row.get(1)?,
-                status: row.get(2)?,
-                session_count: row.get(3)?,
-                created_at: row.get(4)?,
-                updated_at: row.get(5)?,
-                date_of_birth: row.get(6)?,
-                phone: row.get(7)?,
-                email: row.get(8)?,
-                emergency_contact: row.get(9)?,
-                insurance_info: row.get(10)?,
-                diagnosis_codes: row.get(11)?,
-                treatment_start_date: row.get(12)?,
-                referring_provider: row.get(13)?,
-                notes: row.get(14)?,
-            })
-        })?;
+        // Migration v4.2.8: Agency-defined note types
+        match conn.execute_batch(r#"
+            CREATE TABLE IF NOT EXISTS note_type_definitions (
+                name TEXT PRIMARY KEY,
+                definition TEXT NOT NULL,        -- JSON NoteTypeDefinition
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL
+            );
+            
+            CREATE TABLE IF NOT EXISTS note_type_assignments (
+                note_id TEXT PRIMARY KEY,
+                type_name TEXT NOT NULL,
+                FOREIGN KEY (note_id) REFERENCES notes(id),
+                FOREIGN KEY (type_name) REFERENCES note_type_definitions(name)
+            );
+            
+            CREATE INDEX IF NOT EXISTS idx_note_type_assignments_type ON note_type_assignments(type_name);
+        "#) {
+            Ok(_) => log::info!("Note type registry tables ready"),
+            Err(e) => log::error!("Failed to create note type registry tables: {}", e),
+        }
         
-        rows.collect::<Result<Vec<_>, _>>().map_err(VaultError::from)
-    }
-    
-    pub fn get_client(&self, id: &str) -> Result<Client, VaultError> {
-        let conn = self.conn()?;
+        // Migration v4.2.8: Derived-artifact cache
+        match conn.execute_batch(r#"
+            CREATE TABLE IF NOT EXISTS derived_cache (
+                kind TEXT NOT NULL,              -- "prep_sheet", "completion_check", ...
+                cache_key TEXT NOT NULL,         -- usually the note or client ID
+                source_hash TEXT NOT NULL,       -- fingerprint of the inputs
+                versions TEXT NOT NULL,          -- model/rule versions that produced it
+                payload TEXT NOT NULL,           -- JSON
+                created_at INTEGER NOT NULL,
+                last_hit_at INTEGER,
+                PRIMARY KEY (kind, cache_key)
+            );
+            
+            CREATE INDEX IF NOT EXISTS idx_derived_cache_key ON derived_cache(cache_key);
+        "#) {
+            Ok(_) => log::info!("Derived cache table ready"),
+            Err(e) => log::error!("Failed to create derived cache table: {}", e),
+        }
         
-        conn.query_row(
-            "SELECT id, display_name, status, session_count, created_at, updated_at,
-                    date_of_birth, phone, email, emergency_contact, insurance_info,
-                    diagnosis_codes, treatment_start_date, referring_provider, notes
-             FROM clients WHERE id = ?1",
-            params![id],
-            |row| Ok(Client {
-                id: row.get(0)?,
-                display_name: row.get(1)?,
-                status: row.get(2)?,
-                session_count: row.get(3)?,
-                created_at: row.get(4)?,
-                updated_at: row.get(5)?,
-                date_of_birth: row.get(6)?,
-                phone: row.get(7)?,
-                email: row.get(8)?,
-                emergency_contact: row.get(9)?,
-                insurance_info: row.get(10)?,
-                diagnosis_codes: row.get(11)?,
-                treatment_start_date: row.get(12)?,
-                referring_provider: row.get(13)?,
-                notes: row.get(14)?,
-            })
-        ).map_err(|_| VaultError::NotFound(format!("Client {}", id)))
-    }
-    
-    pub fn update_client(&self, client: &Client) -> Result<Client, VaultError> {
-        let conn = self.conn()?;
-        let now = chrono::Utc::now().timestamp_millis();
+        // Migration v4.2.8: Trainee reflections on completed reviews
+        match conn.execute_batch(r#"
+            CREATE TABLE IF NOT EXISTS review_reflections (
+                id TEXT PRIMARY KEY,
+                review_id TEXT NOT NULL UNIQUE,
+                note_id TEXT NOT NULL,
+                trainee_id TEXT NOT NULL,
+                what_i_learned TEXT NOT NULL,
+                what_i_will_change TEXT NOT NULL,
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL,
+                acknowledged_at INTEGER,
+                acknowledged_by TEXT,
+                
+                FOREIGN KEY (review_id) REFERENCES note_reviews(id) ON DELETE CASCADE,
+                FOREIGN KEY (trainee_id) REFERENCES trainees(id) ON DELETE CASCADE
+            );
+            
+            CREATE INDEX IF NOT EXISTS idx_review_reflections_trainee ON review_reflections(trainee_id);
+        "#) {
+            Ok(_) => log::info!("Review reflections table ready"),
+            Err(e) => log::error!("Failed to create review reflections table: {}", e),
+        }
         
-        conn.execute(
-            "UPDATE clients SET 
-                display_name = ?2,
-                status = ?3,
-                updated_at = ?4,
-                date_of_birth = ?5,
-                phone = ?6,
-                email = ?7,
-                emergency_contact = ?8,
-                insurance_info = ?9,
-                diagnosis_codes = ?10,
-                treatment_start_date = ?11,
-                referring_provider = ?12,
-                notes = ?13
-             WHERE id = ?1",
-            params![
-                &client.id,
-                &client.display_name,
-                &client.status,
-                now,
-                &client.date_of_birth,
-                &client.phone,
-                &client.email,
-                &client.emergency_contact,
-                &client.insurance_info,
-                &client.diagnosis_codes,
-                &client.treatment_start_date,
-                &client.referring_provider,
-                &client.notes,
-            ],
-        )?;
+        // Migration v4.2.8: Follow-up presence checks on overridden exports
+        match conn.execute_batch(r#"
+            CREATE TABLE IF NOT EXISTS export_presence_checks (
+                id TEXT PRIMARY KEY,
+                resource_id TEXT NOT NULL,
+                export_audit_entry_id TEXT NOT NULL,
+                path_class TEXT NOT NULL,
+                path_hash TEXT NOT NULL,
+                destination TEXT NOT NULL,  -- full path; stays in the vault, never in the audit log
+                exported_at INTEGER NOT NULL,
+                due_at INTEGER NOT NULL,
+                status TEXT NOT NULL DEFAULT 'scheduled',
+                checked_at INTEGER,
+                check_audit_entry_id TEXT
+            );
+            
+            CREATE INDEX IF NOT EXISTS idx_export_presence_due ON export_presence_checks(status, due_at);
+        "#) {
+            Ok(_) => log::info!("Export presence checks table ready"),
+            Err(e) => log::error!("Failed to create export presence checks table: {}", e),
+        }
         
-        self.get_client(&client.id)
-    }
-    
-    /// Helper function to map SQL results to ClientSearchResult
-    fn map_client_results<P: rusqlite::Params>(
-        &self,
-        stmt: &mut rusqlite::Statement,
-        params: P,
-        matched_fn: impl Fn(&Client) -> Vec<String>,
-    ) -> Result<Vec<ClientSearchResult>, VaultError> {
-        let rows = stmt.query_map(params, |row| {
-            Ok(Client {
-                id: row.get(0)?,
-                display_name: row.get(1)?,
-                status: row.get(2)?,
-                session_count: row.get(3)?,
-                created_at: row.get(4)?,
-                updated_at: row.get(5)?,
-                date_of_birth: row.get(6)?,
-                phone: row.get(7)?,
-                email: row.get(8)?,
-                emergency_contact: row.get(9)?,
-                insurance_info: row.get(10)?,
-                diagnosis_codes: row.get(11)?,
-                treatment_start_date: row.get(12)?,
-                referring_provider: row.get(13)?,
-                notes: row.get(14)?,
-            })
-        })?;
+        // Migration v4.2.8: Client photos (kept apart from documents so exports never include them)
+        match conn.execute_batch(r#"
+            CREATE TABLE IF NOT EXISTS client_photos (
+                client_id TEXT PRIMARY KEY,
+                mime_type TEXT NOT NULL,
+                width INTEGER NOT NULL,
+                height INTEGER NOT NULL,
+                file_size INTEGER NOT NULL,
+                content_hash TEXT NOT NULL,
+                encrypted_data BLOB NOT NULL,
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL,
+                
+                FOREIGN KEY (client_id) REFERENCES clients(id) ON DELETE CASCADE
+            );
+        "#) {
+            Ok(_) => log::info!("Client photos table ready"),
+            Err(e) => log::error!("Failed to create client photos table: {}", e),
+        }
         
-        let results: Vec<ClientSearchResult> = rows.flatten().map(|client| {
-            let matched_fields: Vec<(String, String)> = matched_fn(&client)
-                .into_iter()
-                .filter_map(|field| {
-                    let value = match field.as_str() {
-                        "date_of_birth" => client.date_of_birth.clone(),
-                        "created_at" => Some(format!("{}", client.created_at)),
-                        "treatment_start_date" => client.treatment_start_date.clone(),
-                        "session_count" => Some(format!("{}", client.session_count)),
-                        _ => None,
-                    };
-                    value.map(|v| (field, v))
-                })
-                .collect();
-            ClientSearchResult {
-                client,
-                matched_fields,
+        // Migration v4.2.8: Clinician licensure registry and telehealth location checks
+        match conn.execute_batch(r#"
+            CREATE TABLE IF NOT EXISTS clinician_licenses (
+                id TEXT PRIMARY KEY,
+                kind TEXT NOT NULL,
+                jurisdiction TEXT,
+                license_number TEXT NOT NULL,
+                expires_on TEXT,
+                created_at INTEGER NOT NULL
+            );
+            
+            CREATE TABLE IF NOT EXISTS note_licensure_checks (
+                note_id TEXT PRIMARY KEY,
+                check_json TEXT NOT NULL,
+                checked_at INTEGER NOT NULL,
+                
+                FOREIGN KEY (note_id) REFERENCES notes(id) ON DELETE CASCADE
+            );
+        "#) {
+            Ok(_) => log::info!("Licensure tables ready"),
+            Err(e) => log::error!("Failed to create licensure tables: {}", e),
+        }
+        
+        // Migration v4.2.8: Practice profile (letterhead) - single row
+        match conn.execute_batch(r#"
+            CREATE TABLE IF NOT EXISTS practice_profile (
+                id INTEGER PRIMARY KEY CHECK (id = 1),
+                profile_json TEXT NOT NULL,
+                logo_jpeg BLOB,
+                logo_width INTEGER,
+                logo_height INTEGER,
+                updated_at INTEGER NOT NULL
+            );
+        "#) {
+            Ok(_) => log::info!("Practice profile table ready"),
+            Err(e) => log::error!("Failed to create practice profile table: {}", e),
+        }
+        
+        // Migration v4.2.8: OCR confidence, language and profile per document
+        for (col_name, col_type) in [("ocr_confidence", "REAL"), ("ocr_language", "TEXT"), ("ocr_profile", "TEXT")] {
+            let sql = format!("ALTER TABLE client_documents ADD COLUMN {} {}", col_name, col_type);
+            if let Err(e) = conn.execute(&sql, []) {
+                log::debug!("Column {} already exists or migration failed: {}", col_name, e);
             }
-        }).collect();
+        }
         
-        Ok(results)
-    }
-    
-    /// Search across all clients by profile fields
-    /// Supports semantic queries like "older than 30", "high risk", "newest clients"
-    pub fn search_clients(&self, query: &str) -> Result<Vec<ClientSearchResult>, VaultError> {
-        let conn = self.conn()?;
-        let query_lower = query.to_lowercase();
+        // Migration v4.2.8: Audit slices imported from other installs
+        match conn.execute_batch(r#"
+            CREATE TABLE IF NOT EXISTS external_audit_slices (
+                id TEXT PRIMARY KEY,
+                slice_id TEXT NOT NULL UNIQUE,
+                exporter_label TEXT NOT NULL,
+                exporter_fingerprint TEXT NOT NULL,
+                purpose TEXT NOT NULL,
+                period_start INTEGER NOT NULL,
+                period_end INTEGER NOT NULL,
+                entry_count INTEGER NOT NULL,
+                slice_json TEXT NOT NULL,        -- AuditSlice as received (signed)
+                imported_at INTEGER NOT NULL
+            );
+        "#) {
+            Ok(_) => log::info!("External audit slice table ready"),
+            Err(e) => log::error!("Failed to create external audit slice table: {}", e),
+        }
         
-        // Check for semantic queries first
-        // Age queries: "older than X", "under X", "age > X"
-        if query_lower.contains("older than") || query_lower.contains("over ") || query_lower.contains("age >") {
-            let age = extract_number(&query_lower).unwrap_or(30);
-            let cutoff_year = chrono::Utc::now().year() - age as i32;
-            let cutoff_date = format!("{}-12-31", cutoff_year);
-            
-            let mut stmt = conn.prepare(
-                "SELECT id, display_name, status, session_count, created_at, updated_at,
-                        date_of_birth, phone, email, emergency_contact, insurance_info,
-                        diagnosis_codes, treatment_start_date, referring_provider, notes
-                 FROM clients 
-                 WHERE date_of_birth IS NOT NULL AND date_of_birth < ?1
-                 ORDER BY date_of_birth"
-            )?;
-            
-            return self.map_client_results(&mut stmt, rusqlite::params![cutoff_date], 
-                |_| vec!["date_of_birth".to_string()]);
+        // Migration v4.2.8: Cohorts (group programs) with dated membership and attendance
+        match conn.execute_batch(r#"
+            CREATE TABLE IF NOT EXISTS cohorts (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                program TEXT NOT NULL,
+                schedule_json TEXT NOT NULL,
+                created_at INTEGER NOT NULL,
+                archived_at INTEGER
+            );
+            CREATE TABLE IF NOT EXISTS cohort_memberships (
+                id TEXT PRIMARY KEY,
+                cohort_id TEXT NOT NULL REFERENCES cohorts(id),
+                client_id TEXT NOT NULL REFERENCES clients(id),
+                joined_on TEXT NOT NULL,         -- YYYY-MM-DD
+                left_on TEXT,                    -- first date no longer a member
+                left_reason TEXT,
+                created_at INTEGER NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_cohort_memberships_cohort ON cohort_memberships(cohort_id);
+            CREATE INDEX IF NOT EXISTS idx_cohort_memberships_client ON cohort_memberships(client_id);
+            CREATE TABLE IF NOT EXISTS cohort_sessions (
+                id TEXT PRIMARY KEY,
+                cohort_id TEXT NOT NULL REFERENCES cohorts(id),
+                session_date TEXT NOT NULL,
+                duration_minutes INTEGER NOT NULL,
+                created_at INTEGER NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_cohort_sessions_date ON cohort_sessions(cohort_id, session_date);
+            CREATE TABLE IF NOT EXISTS cohort_attendance (
+                session_id TEXT NOT NULL REFERENCES cohort_sessions(id),
+                client_id TEXT NOT NULL REFERENCES clients(id),
+                status TEXT NOT NULL,
+                minutes_attended INTEGER,
+                note_id TEXT REFERENCES notes(id),
+                PRIMARY KEY (session_id, client_id)
+            );
+        "#) {
+            Ok(_) => log::info!("Cohort tables ready"),
+            Err(e) => log::error!("Failed to create cohort tables: {}", e),
         }
         
-        if query_lower.contains("younger than") || query_lower.contains("under ") || query_lower.contains("age <") {
-            let age = extract_number(&query_lower).unwrap_or(30);
-            let cutoff_year = chrono::Utc::now().year() - age as i32;
-            let cutoff_date = format!("{}-01-01", cutoff_year);
-            
-            let mut stmt = conn.prepare(
-                "SELECT id, display_name, status, session_count, created_at, updated_at,
-                        date_of_birth, phone, email, emergency_contact, insurance_info,
-                        diagnosis_codes, treatment_start_date, referring_provider, notes
-                 FROM clients 
-                 WHERE date_of_birth IS NOT NULL AND date_of_birth > ?1
-                 ORDER BY date_of_birth DESC"
-            )?;
-            
-            return self.map_client_results(&mut stmt, rusqlite::params![cutoff_date],
-                |_| vec!["date_of_birth".to_string()]);
+        // Migration v4.2.8: Maintenance run history and audit chain checkpoints
+        match conn.execute_batch(r#"
+            CREATE TABLE IF NOT EXISTS maintenance_runs (
+                id TEXT PRIMARY KEY,
+                triggered_by TEXT NOT NULL,      -- scheduled / manual
+                started_at INTEGER NOT NULL,
+                finished_at INTEGER NOT NULL,
+                succeeded INTEGER NOT NULL,
+                tasks_json TEXT NOT NULL         -- per-task status, detail, duration
+            );
+            CREATE INDEX IF NOT EXISTS idx_maintenance_runs_started ON maintenance_runs(started_at);
+            CREATE TABLE IF NOT EXISTS audit_checkpoints (
+                sequence INTEGER PRIMARY KEY,
+                entry_hash TEXT NOT NULL,
+                created_at INTEGER NOT NULL
+            );
+        "#) {
+            Ok(_) => log::info!("Maintenance tables ready"),
+            Err(e) => log::error!("Failed to create maintenance tables: {}", e),
         }
         
-        // Tenure queries: "newest", "longest", "recent clients"
-        if query_lower.contains("newest") || query_lower.contains("recent client") || query_lower.contains("new client") {
-            let mut stmt = conn.prepare(
-                "SELECT id, display_name, status, session_count, created_at, updated_at,
-                        date_of_birth, phone, email, emergency_contact, insurance_info,
-                        diagnosis_codes, treatment_start_date, referring_provider, notes
-                 FROM clients ORDER BY created_at DESC LIMIT 10"
-            )?;
+        // Migration v4.2.8: Detections anchored to the note text they were found in
+        match conn.execute_batch(r#"
+            CREATE TABLE IF NOT EXISTS note_detection_anchors (
+                note_id TEXT PRIMARY KEY REFERENCES notes(id),
+                detections_json TEXT NOT NULL,   -- StoredDetection list (offsets + hashes, no text)
+                content_hash TEXT NOT NULL,
+                reanalyze_required INTEGER NOT NULL DEFAULT 0,
+                invalidated_json TEXT NOT NULL DEFAULT '[]',
+                analyzed_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL
+            );
+        "#) {
+            Ok(_) => log::info!("Detection anchor table ready"),
+            Err(e) => log::error!("Failed to create detection anchor table: {}", e),
+        }
+        
+        // Migration v4.2.8: Break-glass emergency access sessions
+        match conn.execute_batch(r#"
+            CREATE TABLE IF NOT EXISTS emergency_access_sessions (
+                id TEXT PRIMARY KEY,
+                client_id TEXT NOT NULL REFERENCES clients(id),
+                covering_clinician TEXT NOT NULL,
+                justification TEXT NOT NULL,
+                audit_entry_id TEXT NOT NULL,
+                activated_at INTEGER NOT NULL,
+                expires_at INTEGER NOT NULL,
+                ended_at INTEGER,
+                access_count INTEGER NOT NULL DEFAULT 0,
+                review_status TEXT NOT NULL DEFAULT 'pending',
+                reviewed_by TEXT,
+                reviewed_at INTEGER,
+                review_note TEXT
+            );
             
-            return self.map_client_results(&mut stmt, rusqlite::params![],
-                |_| vec!["created_at".to_string()]);
+            CREATE INDEX IF NOT EXISTS idx_emergency_access_review ON emergency_access_sessions(review_status, activated_at);
+        "#) {
+            Ok(_) => log::info!("Emergency access table ready"),
+            Err(e) => log::error!("Failed to create emergency access table: {}", e),
         }
         
-        if query_lower.contains("longest") || query_lower.contains("oldest client") || query_lower.contains("veteran") {
-            let mut stmt = conn.prepare(
-                "SELECT id, display_name, status, session_count, created_at, updated_at,
-                        date_of_birth, phone, email, emergency_contact, insurance_info,
-                        diagnosis_codes, treatment_start_date, referring_provider, notes
-                 FROM clients ORDER BY treatment_start_date ASC, created_at ASC LIMIT 10"
-            )?;
+        // Migration v4.2.8: Processed idempotency keys for retried commands
+        match conn.execute_batch(r#"
+            CREATE TABLE IF NOT EXISTS idempotency_keys (
+                key TEXT PRIMARY KEY,
+                command TEXT NOT NULL,
+                request_hash TEXT NOT NULL,
+                result_json TEXT NOT NULL,
+                result_hash TEXT NOT NULL,
+                created_at INTEGER NOT NULL,
+                expires_at INTEGER NOT NULL
+            );
             
-            return self.map_client_results(&mut stmt, rusqlite::params![],
-                |_| vec!["treatment_start_date".to_string()]);
+            CREATE INDEX IF NOT EXISTS idx_idempotency_expires ON idempotency_keys(expires_at);
+        "#) {
+            Ok(_) => log::info!("Idempotency key table ready"),
+            Err(e) => log::error!("Failed to create idempotency key table: {}", e),
         }
         
-        // Most sessions
-        if query_lower.contains("most session") || query_lower.contains("frequent") || query_lower.contains("active") {
-            let mut stmt = conn.prepare(
-                "SELECT id, display_name, status, session_count, created_at, updated_at,
-                        date_of_birth, phone, email, emergency_contact, insurance_info,
-                        diagnosis_codes, treatment_start_date, referring_provider, notes
-                 FROM clients ORDER BY session_count DESC LIMIT 10"
-            )?;
+        // Migration v4.2.8: Retention profiles, legal holds and destruction certificates
+        match conn.execute_batch(r#"
+            CREATE TABLE IF NOT EXISTS client_retention (
+                client_id TEXT PRIMARY KEY REFERENCES clients(id),
+                jurisdiction TEXT,
+                discharged_on TEXT,             -- YYYY-MM-DD
+                updated_at INTEGER NOT NULL
+            );
             
-            return self.map_client_results(&mut stmt, rusqlite::params![],
-                |_| vec!["session_count".to_string()]);
+            CREATE TABLE IF NOT EXISTS legal_holds (
+                id TEXT PRIMARY KEY,
+                client_id TEXT NOT NULL REFERENCES clients(id),
+                matter TEXT NOT NULL,
+                placed_by TEXT NOT NULL,
+                placed_at INTEGER NOT NULL,
+                audit_entry_id TEXT NOT NULL,
+                released_at INTEGER,
+                released_by TEXT
+            );
+            
+            CREATE INDEX IF NOT EXISTS idx_legal_holds_client ON legal_holds(client_id, released_at);
+            
+            CREATE TABLE IF NOT EXISTS destruction_certificates (
+                id TEXT PRIMARY KEY,
+                client_id TEXT NOT NULL,
+                certificate_json TEXT NOT NULL,
+                created_at INTEGER NOT NULL
+            );
+        "#) {
+            Ok(_) => log::info!("Retention tables ready"),
+            Err(e) => log::error!("Failed to create retention tables: {}", e),
         }
         
-        // Split query into individual words and filter out common words
-        let stop_words = ["the", "a", "an", "is", "are", "was", "were", "named", "called", "pts", "patients", "client", "clients", "find", "search", "for", "with", "who"];
-        let words: Vec<String> = query_lower
-            .split_whitespace()
-            .filter(|w| w.len() >= 2 && !stop_words.contains(&w.to_lowercase().as_str()))
-            .map(|w| format!("%{}%", w))
-            .collect();
+        // Migration v4.2.8: Active (timer-measured) time per session; NULL = start/end only
+        for col_name in ["active_seconds", "idle_seconds"] {
+            let sql = format!("ALTER TABLE session_metrics ADD COLUMN {} INTEGER", col_name);
+            if let Err(e) = conn.execute(&sql, []) {
+                log::debug!("Column {} already exists or migration failed: {}", col_name, e);
+            }
+        }
         
-        if words.is_empty() {
-            // If no valid search words, return all clients
-            let mut stmt = conn.prepare(
-                "SELECT id, display_name, status, session_count, created_at, updated_at,
-                        date_of_birth, phone, email, emergency_contact, insurance_info,
-                        diagnosis_codes, treatment_start_date, referring_provider, notes
-                 FROM clients ORDER BY display_name"
-            )?;
+        // Migration v4.2.8: Embedding vectors sealed per client (NULL scope = legacy plaintext)
+        if let Err(e) = conn.execute("ALTER TABLE embeddings ADD COLUMN key_scope TEXT", []) {
+            log::debug!("Column key_scope already exists or migration failed: {}", e);
+        }
+        if let Err(e) = conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_embeddings_scope ON embeddings(key_scope)", [],
+        ) {
+            log::error!("Failed to create embedding scope index: {}", e);
+        }
+        
+        // Migration v4.2.8: Client visit-summary letters and releases of information
+        match conn.execute_batch(r#"
+            CREATE TABLE IF NOT EXISTS client_letters (
+                id TEXT PRIMARY KEY,
+                note_id TEXT NOT NULL REFERENCES notes(id),
+                client_id TEXT NOT NULL REFERENCES clients(id),
+                body TEXT NOT NULL,
+                source TEXT NOT NULL,            -- template / local_rewrite / edited
+                note_hash TEXT NOT NULL,         -- note content hash at drafting
+                content_hash TEXT,               -- set on finalize
+                finalized_at INTEGER,
+                finalized_by TEXT,
+                last_exported_at INTEGER,
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL
+            );
             
-            let rows = stmt.query_map([], |row| {
-                Ok(Client {
-                    id: row.get(0)?,
-                    display_name: row.get(1)?,
-                    status: row.get(2)?,
-                    session_count: row.get(3)?,
-                    created_at: row.get(4)?,
-                    updated_at: row.get(5)?,
-                    date_of_birth: row.get(6)?,
-                    phone: row.get(7)?,
-                    email: row.get(8)?,
-                    emergency_contact: row.get(9)?,
-                    insurance_info: row.get(10)?,
-                    diagnosis_codes: row.get(11)?,
-                    treatment_start_date: row.get(12)?,
-                    referring_provider: row.get(13)?,
-                    notes: row.get(14)?,
-                })
-            })?;
+            CREATE INDEX IF NOT EXISTS idx_client_letters_client ON client_letters(client_id);
             
-            let clients: Vec<Client> = rows.collect::<Result<Vec<_>, _>>()?;
-            return Ok(clients.into_iter().map(|client| ClientSearchResult {
-                client,
-                matched_fields: vec![],
-            }).collect());
+            CREATE TABLE IF NOT EXISTS release_authorizations (
+                id TEXT PRIMARY KEY,
+                client_id TEXT NOT NULL REFERENCES clients(id),
+                recipient TEXT NOT NULL,
+                scope TEXT NOT NULL,             -- visit_summaries / full_record
+                signed_on TEXT NOT NULL,         -- YYYY-MM-DD
+                expires_on TEXT,
+                revoked_at INTEGER,
+                created_at INTEGER NOT NULL
+            );
+            
+            CREATE INDEX IF NOT EXISTS idx_release_authorizations_client ON release_authorizations(client_id);
+        "#) {
+            Ok(_) => log::info!("Client letter tables ready"),
+            Err(e) => log::error!("Failed to create client letter tables: {}", e),
         }
         
-        // For each word, build a combined search pattern
-        // Use a simpler approach: search for ANY word match
-        let search_pattern = words.join("%");
-        
-        let sql = "SELECT id, display_name, status, session_count, created_at, updated_at,
-                          date_of_birth, phone, email, emergency_contact, insurance_info,
-                          diagnosis_codes, treatment_start_date, referring_provider, notes
-                   FROM clients 
-                   WHERE LOWER(display_name) LIKE ?1
-                      OR LOWER(COALESCE(phone, '')) LIKE ?1
-                      OR LOWER(COALESCE(email, '')) LIKE ?1
-                      OR LOWER(COALESCE(insurance_info, '')) LIKE ?1
-                      OR LOWER(COALESCE(diagnosis_codes, '')) LIKE ?1
-                      OR LOWER(COALESCE(referring_provider, '')) LIKE ?1
-                      OR LOWER(COALESCE(notes, '')) LIKE ?1
-                      OR LOWER(COALESCE(emergency_contact, '')) LIKE ?1
-                   ORDER BY display_name";
-        
-        // Also try individual words
-        let mut all_clients: std::collections::HashSet<String> = std::collections::HashSet::new();
-        let mut result_clients: Vec<Client> = Vec::new();
+        // Migration v4.2.8: Ethics re-analysis runs and their advisory findings
+        match conn.execute_batch(r#"
+            CREATE TABLE IF NOT EXISTS reanalysis_runs (
+                id TEXT PRIMARY KEY,
+                rules_version TEXT NOT NULL,
+                range_json TEXT NOT NULL,
+                status TEXT NOT NULL,            -- running / completed / failed
+                notes_total INTEGER NOT NULL,
+                notes_scanned INTEGER NOT NULL DEFAULT 0,
+                error TEXT,
+                report_json TEXT,                -- set on completion
+                started_at INTEGER NOT NULL,
+                finished_at INTEGER
+            );
+            
+            CREATE TABLE IF NOT EXISTS advisory_findings (
+                id TEXT PRIMARY KEY,
+                run_id TEXT NOT NULL REFERENCES reanalysis_runs(id),
+                note_id TEXT NOT NULL REFERENCES notes(id),
+                client_id TEXT NOT NULL REFERENCES clients(id),
+                session_date TEXT NOT NULL,
+                pattern_id TEXT NOT NULL,
+                change_kind TEXT NOT NULL,       -- new / cleared
+                severity TEXT,
+                match_start INTEGER,
+                match_end INTEGER,
+                created_at INTEGER NOT NULL
+            );
+            
+            CREATE INDEX IF NOT EXISTS idx_advisory_findings_run ON advisory_findings(run_id, note_id);
+            CREATE INDEX IF NOT EXISTS idx_advisory_findings_client ON advisory_findings(client_id);
+        "#) {
+            Ok(_) => log::info!("Re-analysis tables ready"),
+            Err(e) => log::error!("Failed to create re-analysis tables: {}", e),
+        }
         
-        for word in &words {
-            let mut stmt = conn.prepare(sql)?;
-            let rows = stmt.query_map([word], |row| {
-                Ok(Client {
-                    id: row.get(0)?,
-                    display_name: row.get(1)?,
-                    status: row.get(2)?,
-                    session_count: row.get(3)?,
-                    created_at: row.get(4)?,
-                    updated_at: row.get(5)?,
-                    date_of_birth: row.get(6)?,
-                    phone: row.get(7)?,
-                    email: row.get(8)?,
-                    emergency_contact: row.get(9)?,
-                    insurance_info: row.get(10)?,
-                    diagnosis_codes: row.get(11)?,
-                    treatment_start_date: row.get(12)?,
-                    referring_provider: row.get(13)?,
-                    notes: row.get(14)?,
-                })
-            })?;
+        // Migration v4.2.8: Outbound EHR delivery queue
+        match conn.execute_batch(r#"
+            CREATE TABLE IF NOT EXISTS ehr_deliveries (
+                id TEXT PRIMARY KEY,
+                note_id TEXT NOT NULL,
+                target TEXT NOT NULL,
+                status TEXT NOT NULL,            -- pending / sent / failed / acknowledged
+                attempts INTEGER NOT NULL DEFAULT 0,
+                next_attempt_at INTEGER,         -- NULL: no automatic attempt scheduled
+                last_error TEXT,
+                file_path TEXT,
+                content_sha256 TEXT,
+                options_json TEXT NOT NULL,      -- the note is rendered at send time
+                output_dir TEXT NOT NULL,
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL,
+                sent_at INTEGER,
+                acknowledged_at INTEGER,
+                acknowledged_by TEXT,
+                ehr_reference TEXT
+            );
             
-            for client in rows.flatten() {
-                if !all_clients.contains(&client.id) {
-                    all_clients.insert(client.id.clone());
-                    result_clients.push(client);
-                }
-            }
+            CREATE INDEX IF NOT EXISTS idx_ehr_deliveries_status ON ehr_deliveries(status, next_attempt_at);
+        "#) {
+            Ok(_) => log::info!("EHR delivery queue ready"),
+            Err(e) => log::error!("Failed to create EHR delivery queue: {}", e),
+        }
+        // Earlier queues kept a plaintext copy of each note; drop it
+        if let Err(e) = conn.execute("ALTER TABLE ehr_deliveries DROP COLUMN note_json", []) {
+            log::debug!("Column ehr_deliveries.note_json already dropped or migration failed: {}", e);
         }
         
-        // Build results with match context
-        let query_words: Vec<String> = query
-            .to_lowercase()
-            .split_whitespace()
-            .filter(|w| w.len() >= 2 && !stop_words.contains(&w.to_lowercase().as_str()))
-            .map(|s| s.to_string())
-            .collect();
+        // Migration v4.2.9: Multi-author notes
+        match conn.execute_batch(r#"
+            CREATE TABLE IF NOT EXISTS note_authors (
+                note_id TEXT NOT NULL,
+                author_id TEXT NOT NULL,
+                author_name TEXT NOT NULL,
+                credentials TEXT,
+                role TEXT NOT NULL,              -- primary / co_therapist / intern / supervisor
+                added_at INTEGER NOT NULL,
+                signed_at INTEGER,
+                signed_text_sha256 TEXT,
+                contribution_sha256 TEXT,
+                PRIMARY KEY (note_id, author_id)
+            );
+            
+            -- Per-author secret a co-author enters to sign their contribution
+            CREATE TABLE IF NOT EXISTS author_signing_credentials (
+                author_id TEXT PRIMARY KEY,
+                salt TEXT NOT NULL,
+                hash TEXT NOT NULL,
+                updated_at INTEGER NOT NULL
+            );
+            
+            CREATE TABLE IF NOT EXISTS note_section_authors (
+                note_id TEXT NOT NULL,
+                section TEXT NOT NULL,           -- normalized heading
+                author_id TEXT NOT NULL,
+                attributed_at INTEGER NOT NULL,
+                PRIMARY KEY (note_id, section)
+            );
+        "#) {
+            Ok(_) => log::info!("Note authorship tables ready"),
+            Err(e) => log::error!("Failed to create note authorship tables: {}", e),
+        }
         
-        let results: Vec<ClientSearchResult> = result_clients.into_iter().map(|client| {
-            let mut matched_fields = Vec::new();
+        // Migration v4.2.9: Group supervision sessions and attendance
+        match conn.execute_batch(r#"
+            CREATE TABLE IF NOT EXISTS group_supervision_sessions (
+                id TEXT PRIMARY KEY,
+                supervisor_id TEXT NOT NULL,
+                session_date TEXT NOT NULL,      -- YYYY-MM-DD
+                duration_minutes INTEGER NOT NULL,
+                topics TEXT NOT NULL,            -- JSON array
+                cases_discussed TEXT NOT NULL,   -- JSON array of client pseudonyms
+                notes TEXT,
+                created_at INTEGER NOT NULL
+            );
             
-            for word in &query_words {
-                if client.display_name.to_lowercase().contains(word) {
-                    matched_fields.push(("name".to_string(), client.display_name.clone()));
-                    break;
-                }
-            }
-            if let Some(ref phone) = client.phone {
-                for word in &query_words {
-                    if phone.to_lowercase().contains(word) {
-                        matched_fields.push(("phone".to_string(), phone.clone()));
-                        break;
-                    }
-                }
-            }
-            if let Some(ref email) = client.email {
-                for word in &query_words {
-                    if email.to_lowercase().contains(word) {
-                        matched_fields.push(("email".to_string(), email.clone()));
-                        break;
-                    }
-                }
-            }
-            if let Some(ref insurance) = client.insurance_info {
-                for word in &query_words {
-                    if insurance.to_lowercase().contains(word) {
-                        matched_fields.push(("insurance".to_string(), insurance.clone()));
-                        break;
-                    }
-                }
-            }
-            if let Some(ref diagnosis) = client.diagnosis_codes {
-                for word in &query_words {
-                    if diagnosis.to_lowercase().contains(word) {
-                        matched_fields.push(("diagnosis".to_string(), diagnosis.clone()));
-                        break;
-                    }
-                }
-            }
-            if let Some(ref provider) = client.referring_provider {
-                for word in &query_words {
-                    if provider.to_lowercase().contains(word) {
-                        matched_fields.push(("referring_provider".to_string(), provider.clone()));
-                        break;
-                    }
-                }
-            }
-            if let Some(ref notes) = client.notes {
-                for word in &query_words {
-                    if notes.to_lowercase().contains(word) {
-                        matched_fields.push(("notes".to_string(), notes.clone()));
-                        break;
-                    }
-                }
+            CREATE TABLE IF NOT EXISTS group_supervision_attendance (
+                session_id TEXT NOT NULL,
+                trainee_id TEXT NOT NULL,
+                trainee_name TEXT NOT NULL,      -- as of the session
+                present INTEGER NOT NULL,
+                PRIMARY KEY (session_id, trainee_id),
+                FOREIGN KEY (session_id) REFERENCES group_supervision_sessions(id) ON DELETE CASCADE
+            );
+            
+            CREATE INDEX IF NOT EXISTS idx_group_supervision_supervisor
+                ON group_supervision_sessions(supervisor_id, session_date);
+            CREATE INDEX IF NOT EXISTS idx_group_supervision_trainee
+                ON group_supervision_attendance(trainee_id);
+        "#) {
+            Ok(_) => log::info!("Group supervision tables ready"),
+            Err(e) => log::error!("Failed to create group supervision tables: {}", e),
+        }
+        
+        // Migration v4.2.9: Soft delete (trash) for notes and clients
+        for table in ["notes", "clients"] {
+            let sql = format!("ALTER TABLE {} ADD COLUMN deleted_at INTEGER", table);
+            if let Err(e) = conn.execute(&sql, []) {
+                log::debug!("Column {}.deleted_at already exists or migration failed: {}", table, e);
             }
-            if let Some(ref emergency) = client.emergency_contact {
-                for word in &query_words {
-                    if emergency.to_lowercase().contains(word) {
-                        matched_fields.push(("emergency_contact".to_string(), emergency.clone()));
-                        break;
+        }
+        
+        // Migration v4.2.9: Prior versions of note content
+        match conn.execute_batch(r#"
+            CREATE TABLE IF NOT EXISTS note_revisions (
+                note_id TEXT NOT NULL REFERENCES notes(id),
+                revision INTEGER NOT NULL,       -- 0 = first version
+                raw_input TEXT NOT NULL,
+                structured_note TEXT,
+                content_hash TEXT NOT NULL,
+                status TEXT NOT NULL,
+                saved_at INTEGER NOT NULL,       -- the note's updated_at for this version
+                superseded_at INTEGER NOT NULL,
+                superseded_by TEXT NOT NULL,     -- edit / structure / amendment
+                editor TEXT,                     -- who made that change, when known
+                PRIMARY KEY (note_id, revision)
+            );
+        "#) {
+            Ok(_) => log::info!("Note revisions table ready"),
+            Err(e) => log::error!("Failed to create note revisions table: {}", e),
+        }
+        if let Err(e) = conn.execute("ALTER TABLE note_revisions ADD COLUMN editor TEXT", []) {
+            log::debug!("Column note_revisions.editor already exists or migration failed: {}", e);
+        }
+        let backfilled: bool = conn.query_row(
+            "SELECT COUNT(*) > 0 FROM settings WHERE key = 'amendment_revisions_backfilled'",
+            [],
+            |row| row.get(0),
+        ).unwrap_or(true);
+        if !backfilled {
+            match Self::backfill_amendment_revisions(conn) {
+                Ok(n) => {
+                    log::info!("Backfilled amendment revisions for {} notes", n);
+                    if let Err(e) = conn.execute(
+                        "INSERT OR REPLACE INTO settings (key, value) VALUES ('amendment_revisions_backfilled', '1')",
+                        [],
+                    ) {
+                        log::error!("Failed to record the amendment revision backfill: {}", e);
                     }
                 }
+                Err(e) => log::error!("Failed to backfill amendment revisions: {}", e),
             }
-            
-            ClientSearchResult {
-                client,
-                matched_fields,
+        }
+        
+        // Migration v4.2.9: Note content hash a supervisor last saw in blinded review
+        if let Err(e) = conn.execute("ALTER TABLE note_reviews ADD COLUMN blinded_note_hash TEXT", []) {
+            log::debug!("Column note_reviews.blinded_note_hash already exists or migration failed: {}", e);
+        }
+        
+        // Migration v4.2.9: FTS5 keyword index over note bodies
+        match crate::fulltext::ensure_index(conn) {
+            Ok(_) => log::info!("Note full-text index ready"),
+            Err(e) => log::error!("Failed to create note full-text index: {}", e),
+        }
+        
+        // Rebuild counters from the source tables on every unlock so any drift
+        // (e.g. rows written before the triggers existed) self-heals
+        match conn.execute_batch(r#"
+            BEGIN;
+            DELETE FROM vault_counters;
+            INSERT INTO vault_counters (name, value) SELECT 'clients', COUNT(*) FROM clients;
+            INSERT INTO vault_counters (name, value) SELECT 'notes', COUNT(*) FROM notes;
+            INSERT INTO vault_counters (name, value)
+                SELECT 'notes_status:' || status, COUNT(*) FROM notes GROUP BY status;
+            INSERT INTO vault_counters (name, value) SELECT 'documents', COUNT(*) FROM client_documents;
+            INSERT INTO vault_counters (name, value)
+                SELECT 'document_bytes', COALESCE(SUM(file_size), 0) FROM client_documents;
+            INSERT INTO vault_counters (name, value)
+                SELECT 'reviews_status:' || status, COUNT(*) FROM note_reviews GROUP BY status;
+            COMMIT;
+        "#) {
+            Ok(_) => log::info!("Dashboard counters rebuilt"),
+            Err(e) => {
+                conn.execute_batch("ROLLBACK;").ok();
+                log::error!("Failed to rebuild dashboard counters: {}", e)
             }
-        }).collect();
+        }
         
-        Ok(results)
+        log::info!("Database migrations complete");
+        Ok(())
+    }
+    
+    // ============================================
+    // Client Operations
+    // ============================================
+    
+    pub fn create_client(&self, display_name: &str) -> Result<Client, VaultError> {
+        let conn = self.conn()?;
+        
+        // Always v4; see ids.rs
+        let id = uuid::Uuid::new_v4().to_string();
+        let now = chrono::Utc::now().timestamp_millis();
+        
+        conn.execute(
+            "INSERT INTO clients (id, display_name, status, session_count, created_at, updated_at) 
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![&id, display_name, "active", 0i32, now, now],
+        )?;
+        
+        Ok(Client {
+            id,
+            display_name: display_name.to_string(),
+            status: "active".to_string(),
+            session_count: 0,
+            created_at: now,
+            updated_at: now,
+            date_of_birth: None,
+            phone: None,
+            email: None,
+            emergency_contact: None,
+            insurance_info: None,
+            diagnosis_codes: None,
+            treatment_start_date: None,
+            referring_provider: None,
+            notes: None,
+        })
+    }
+    
+    pub fn list_clients(&self) -> Result<Vec<Client>, VaultError> {
+        let conn = self.conn()?;
+        
+        let mut stmt = conn.prepare(
+            "SELECT id, display_name, status, session_count, created_at, updated_at,
+                    date_of_birth, phone, email, emergency_contact, insurance_info,
+                    diagnosis_codes, treatment_start_date, referring_provider, notes
+             FROM clients WHERE deleted_at IS NULL ORDER BY display_name"
+        )?;
+        
+        let rows = stmt.query_map([], |row| {
+            Ok(Client {
+                id: row.get(0)?,
+                display_name: row.get(1)?,
+                status: row.get(2)?,
+                session_count: row.get(3)?,
+                created_at: row.get(4)?,
+                updated_at: row.get(5)?,
+                date_of_birth: row.get(6)?,
+                phone: row.get(7)?,
+                email: row.get(8)?,
+                emergency_contact: row.get(9)?,
+                insurance_info: row.get(10)?,
+                diagnosis_codes: row.get(11)?,
+                treatment_start_date: row.get(12)?,
+                referring_provider: row.get(13)?,
+                notes: row.get(14)?,
+            })
+        })?;
+        
+        rows.collect::<Result<Vec<_>, _>>().map_err(VaultError::from)
+    }
+    
+    pub fn get_client(&self, id: &str) -> Result<Client, VaultError> {
+        let conn = self.conn()?;
+        
+        conn.query_row(
+            "SELECT id, display_name, status, session_count, created_at, updated_at,
+                    date_of_birth, phone, email, emergency_contact, insurance_info,
+                    diagnosis_codes, treatment_start_date, referring_provider, notes
+             FROM clients WHERE id = ?1 AND deleted_at IS NULL",
+            params![id],
+            |row| Ok(Client {
+                id: row.get(0)?,
+                display_name: row.get(1)?,
+                status: row.get(2)?,
+                session_count: row.get(3)?,
+                created_at: row.get(4)?,
+                updated_at: row.get(5)?,
+                date_of_birth: row.get(6)?,
+                phone: row.get(7)?,
+                email: row.get(8)?,
+                emergency_contact: row.get(9)?,
+                insurance_info: row.get(10)?,
+                diagnosis_codes: row.get(11)?,
+                treatment_start_date: row.get(12)?,
+                referring_provider: row.get(13)?,
+                notes: row.get(14)?,
+            })
+        ).map_err(|_| VaultError::NotFound(format!("Client {}", id)))
+    }
+    
+    pub fn update_client(&self, client: &Client) -> Result<Client, VaultError> {
+        let conn = self.conn()?;
+        let now = chrono::Utc::now().timestamp_millis();
+        
+        conn.execute(
+            "UPDATE clients SET 
+                display_name = ?2,
+                status = ?3,
+                updated_at = ?4,
+                date_of_birth = ?5,
+                phone = ?6,
+                email = ?7,
+                emergency_contact = ?8,
+                insurance_info = ?9,
+                diagnosis_codes = ?10,
+                treatment_start_date = ?11,
+                referring_provider = ?12,
+                notes = ?13
+             WHERE id = ?1",
+            params![
+                &client.id,
+                &client.display_name,
+                &client.status,
+                now,
+                &client.date_of_birth,
+                &client.phone,
+                &client.email,
+                &client.emergency_contact,
+                &client.insurance_info,
+                &client.diagnosis_codes,
+                &client.treatment_start_date,
+                &client.referring_provider,
+                &client.notes,
+            ],
+        )?;
+        
+        self.get_client(&client.id)
+    }
+    
+    /// Helper function to map SQL results to ClientSearchResult
+    fn map_client_results<P: rusqlite::Params>(
+        &self,
+        stmt: &mut rusqlite::Statement,
+        params: P,
+        matched_fn: impl Fn(&Client) -> Vec<String>,
+    ) -> Result<Vec<ClientSearchResult>, VaultError> {
+        let rows = stmt.query_map(params, |row| {
+            Ok(Client {
+                id: row.get(0)?,
+                display_name: row.get(1)?,
+                status: row.get(2)?,
+                session_count: row.get(3)?,
+                created_at: row.get(4)?,
+                updated_at: row.get(5)?,
+                date_of_birth: row.get(6)?,
+                phone: row.get(7)?,
+                email: row.get(8)?,
+                emergency_contact: row.get(9)?,
+                insurance_info: row.get(10)?,
+                diagnosis_codes: row.get(11)?,
+                treatment_start_date: row.get(12)?,
+                referring_provider: row.get(13)?,
+                notes: row.get(14)?,
+            })
+        })?;
+        
+        let results: Vec<ClientSearchResult> = rows.flatten().map(|client| {
+            let matched_fields: Vec<(String, String)> = matched_fn(&client)
+                .into_iter()
+                .filter_map(|field| {
+                    let value = match field.as_str() {
+                        "date_of_birth" => client.date_of_birth.clone(),
+                        "created_at" => Some(format!("{}", client.created_at)),
+                        "treatment_start_date" => client.treatment_start_date.clone(),
+                        "session_count" => Some(format!("{}", client.session_count)),
+                        _ => None,
+                    };
+                    value.map(|v| (field, v))
+                })
+                .collect();
+            ClientSearchResult {
+                client,
+                matched_fields,
+            }
+        }).collect();
+        
+        Ok(results)
+    }
+    
+    /// Search across all clients by profile fields
+    /// Supports semantic queries like "older than 30", "high risk", "newest clients"
+    pub fn search_clients(&self, query: &str) -> Result<Vec<ClientSearchResult>, VaultError> {
+        let conn = self.conn()?;
+        let query_lower = query.to_lowercase();
+        
+        // Check for semantic queries first
+        // Age queries: "older than X", "under X", "age > X"
+        if query_lower.contains("older than") || query_lower.contains("over ") || query_lower.contains("age >") {
+            let age = extract_number(&query_lower).unwrap_or(30);
+            let cutoff_year = chrono::Utc::now().year() - age as i32;
+            let cutoff_date = format!("{}-12-31", cutoff_year);
+            
+            let mut stmt = conn.prepare(
+                "SELECT id, display_name, status, session_count, created_at, updated_at,
+                        date_of_birth, phone, email, emergency_contact, insurance_info,
+                        diagnosis_codes, treatment_start_date, referring_provider, notes
+                 FROM clients 
+                 WHERE deleted_at IS NULL AND date_of_birth IS NOT NULL AND date_of_birth < ?1
+                 ORDER BY date_of_birth"
+            )?;
+            
+            return self.map_client_results(&mut stmt, rusqlite::params![cutoff_date], 
+                |_| vec!["date_of_birth".to_string()]);
+        }
+        
+        if query_lower.contains("younger than") || query_lower.contains("under ") || query_lower.contains("age <") {
+            let age = extract_number(&query_lower).unwrap_or(30);
+            let cutoff_year = chrono::Utc::now().year() - age as i32;
+            let cutoff_date = format!("{}-01-01", cutoff_year);
+            
+            let mut stmt = conn.prepare(
+                "SELECT id, display_name, status, session_count, created_at, updated_at,
+                        date_of_birth, phone, email, emergency_contact, insurance_info,
+                        diagnosis_codes, treatment_start_date, referring_provider, notes
+                 FROM clients 
+                 WHERE deleted_at IS NULL AND date_of_birth IS NOT NULL AND date_of_birth > ?1
+                 ORDER BY date_of_birth DESC"
+            )?;
+            
+            return self.map_client_results(&mut stmt, rusqlite::params![cutoff_date],
+                |_| vec!["date_of_birth".to_string()]);
+        }
+        
+        // Tenure queries: "newest", "longest", "recent clients"
+        if query_lower.contains("newest") || query_lower.contains("recent client") || query_lower.contains("new client") {
+            let mut stmt = conn.prepare(
+                "SELECT id, display_name, status, session_count, created_at, updated_at,
+                        date_of_birth, phone, email, emergency_contact, insurance_info,
+                        diagnosis_codes, treatment_start_date, referring_provider, notes
+                 FROM clients WHERE deleted_at IS NULL ORDER BY created_at DESC LIMIT 10"
+            )?;
+            
+            return self.map_client_results(&mut stmt, rusqlite::params![],
+                |_| vec!["created_at".to_string()]);
+        }
+        
+        if query_lower.contains("longest") || query_lower.contains("oldest client") || query_lower.contains("veteran") {
+            let mut stmt = conn.prepare(
+                "SELECT id, display_name, status, session_count, created_at, updated_at,
+                        date_of_birth, phone, email, emergency_contact, insurance_info,
+                        diagnosis_codes, treatment_start_date, referring_provider, notes
+                 FROM clients WHERE deleted_at IS NULL ORDER BY treatment_start_date ASC, created_at ASC LIMIT 10"
+            )?;
+            
+            return self.map_client_results(&mut stmt, rusqlite::params![],
+                |_| vec!["treatment_start_date".to_string()]);
+        }
+        
+        // Most sessions
+        if query_lower.contains("most session") || query_lower.contains("frequent") || query_lower.contains("active") {
+            let mut stmt = conn.prepare(
+                "SELECT id, display_name, status, session_count, created_at, updated_at,
+                        date_of_birth, phone, email, emergency_contact, insurance_info,
+                        diagnosis_codes, treatment_start_date, referring_provider, notes
+                 FROM clients WHERE deleted_at IS NULL ORDER BY session_count DESC LIMIT 10"
+            )?;
+            
+            return self.map_client_results(&mut stmt, rusqlite::params![],
+                |_| vec!["session_count".to_string()]);
+        }
+        
+        // Split query into individual words and filter out common words
+        let stop_words = ["the", "a", "an", "is", "are", "was", "were", "named", "called", "pts", "patients", "client", "clients", "find", "search", "for", "with", "who"];
+        let words: Vec<String> = query_lower
+            .split_whitespace()
+            .filter(|w| w.len() >= 2 && !stop_words.contains(&w.to_lowercase().as_str()))
+            .map(like_contains)
+            .collect();
+        
+        if words.is_empty() {
+            // If no valid search words, return all clients
+            let mut stmt = conn.prepare(
+                "SELECT id, display_name, status, session_count, created_at, updated_at,
+                        date_of_birth, phone, email, emergency_contact, insurance_info,
+                        diagnosis_codes, treatment_start_date, referring_provider, notes
+                 FROM clients WHERE deleted_at IS NULL ORDER BY display_name"
+            )?;
+            
+            let rows = stmt.query_map([], |row| {
+                Ok(Client {
+                    id: row.get(0)?,
+                    display_name: row.get(1)?,
+                    status: row.get(2)?,
+                    session_count: row.get(3)?,
+                    created_at: row.get(4)?,
+                    updated_at: row.get(5)?,
+                    date_of_birth: row.get(6)?,
+                    phone: row.get(7)?,
+                    email: row.get(8)?,
+                    emergency_contact: row.get(9)?,
+                    insurance_info: row.get(10)?,
+                    diagnosis_codes: row.get(11)?,
+                    treatment_start_date: row.get(12)?,
+                    referring_provider: row.get(13)?,
+                    notes: row.get(14)?,
+                })
+            })?;
+            
+            let clients: Vec<Client> = rows.collect::<Result<Vec<_>, _>>()?;
+            return Ok(clients.into_iter().map(|client| ClientSearchResult {
+                client,
+                matched_fields: vec![],
+            }).collect());
+        }
+        
+        // For each word, build a combined search pattern
+        // Use a simpler approach: search for ANY word match
+        let search_pattern = words.join("%");
+        
+        let sql = "SELECT id, display_name, status, session_count, created_at, updated_at,
+                          date_of_birth, phone, email, emergency_contact, insurance_info,
+                          diagnosis_codes, treatment_start_date, referring_provider, notes
+                   FROM clients 
+                   WHERE deleted_at IS NULL AND (
+                         LOWER(display_name) LIKE ?1 ESCAPE '\\'
+                      OR LOWER(COALESCE(phone, '')) LIKE ?1 ESCAPE '\\'
+                      OR LOWER(COALESCE(email, '')) LIKE ?1 ESCAPE '\\'
+                      OR LOWER(COALESCE(insurance_info, '')) LIKE ?1 ESCAPE '\\'
+                      OR LOWER(COALESCE(diagnosis_codes, '')) LIKE ?1 ESCAPE '\\'
+                      OR LOWER(COALESCE(referring_provider, '')) LIKE ?1 ESCAPE '\\'
+                      OR LOWER(COALESCE(notes, '')) LIKE ?1 ESCAPE '\\'
+                      OR LOWER(COALESCE(emergency_contact, '')) LIKE ?1 ESCAPE '\\')
+                   ORDER BY display_name";
+        
+        // Also try individual words
+        let mut all_clients: std::collections::HashSet<String> = std::collections::HashSet::new();
+        let mut result_clients: Vec<Client> = Vec::new();
+        
+        for word in &words {
+            let mut stmt = conn.prepare(sql)?;
+            let rows = stmt.query_map([word], |row| {
+                Ok(Client {
+                    id: row.get(0)?,
+                    display_name: row.get(1)?,
+                    status: row.get(2)?,
+                    session_count: row.get(3)?,
+                    created_at: row.get(4)?,
+                    updated_at: row.get(5)?,
+                    date_of_birth: row.get(6)?,
+                    phone: row.get(7)?,
+                    email: row.get(8)?,
+                    emergency_contact: row.get(9)?,
+                    insurance_info: row.get(10)?,
+                    diagnosis_codes: row.get(11)?,
+                    treatment_start_date: row.get(12)?,
+                    referring_provider: row.get(13)?,
+                    notes: row.get(14)?,
+                })
+            })?;
+            
+            for client in rows.flatten() {
+                if !all_clients.contains(&client.id) {
+                    all_clients.insert(client.id.clone());
+                    result_clients.push(client);
+                }
+            }
+        }
+        
+        // Build results with match context
+        let query_words: Vec<String> = query
+            .to_lowercase()
+            .split_whitespace()
+            .filter(|w| w.len() >= 2 && !stop_words.contains(&w.to_lowercase().as_str()))
+            .map(|s| s.to_string())
+            .collect();
+        
+        let results: Vec<ClientSearchResult> = result_clients.into_iter().map(|client| {
+            let mut matched_fields = Vec::new();
+            
+            for word in &query_words {
+                if client.display_name.to_lowercase().contains(word) {
+                    matched_fields.push(("name".to_string(), client.display_name.clone()));
+                    break;
+                }
+            }
+            if let Some(ref phone) = client.phone {
+                for word in &query_words {
+                    if phone.to_lowercase().contains(word) {
+                        matched_fields.push(("phone".to_string(), phone.clone()));
+                        break;
+                    }
+                }
+            }
+            if let Some(ref email) = client.email {
+                for word in &query_words {
+                    if email.to_lowercase().contains(word) {
+                        matched_fields.push(("email".to_string(), email.clone()));
+                        break;
+                    }
+                }
+            }
+            if let Some(ref insurance) = client.insurance_info {
+                for word in &query_words {
+                    if insurance.to_lowercase().contains(word) {
+                        matched_fields.push(("insurance".to_string(), insurance.clone()));
+                        break;
+                    }
+                }
+            }
+            if let Some(ref diagnosis) = client.diagnosis_codes {
+                for word in &query_words {
+                    if diagnosis.to_lowercase().contains(word) {
+                        matched_fields.push(("diagnosis".to_string(), diagnosis.clone()));
+                        break;
+                    }
+                }
+            }
+            if let Some(ref provider) = client.referring_provider {
+                for word in &query_words {
+                    if provider.to_lowercase().contains(word) {
+                        matched_fields.push(("referring_provider".to_string(), provider.clone()));
+                        break;
+                    }
+                }
+            }
+            if let Some(ref notes) = client.notes {
+                for word in &query_words {
+                    if notes.to_lowercase().contains(word) {
+                        matched_fields.push(("notes".to_string(), notes.clone()));
+                        break;
+                    }
+                }
+            }
+            if let Some(ref emergency) = client.emergency_contact {
+                for word in &query_words {
+                    if emergency.to_lowercase().contains(word) {
+                        matched_fields.push(("emergency_contact".to_string(), emergency.clone()));
+                        break;
+                    }
+                }
+            }
+            
+            ClientSearchResult {
+                client,
+                matched_fields,
+            }
+        }).collect();
+        
+        Ok(results)
+    }
+    
+    /// Get client's last visit date
+    pub fn get_client_last_visit(&self, client_id: &str) -> Result<Option<String>, VaultError> {
+        let conn = self.conn()?;
+        
+        let result: Option<String> = conn.query_row(
+            "SELECT session_date FROM notes WHERE client_id = ?1 ORDER BY created_at DESC LIMIT 1",
+            params![client_id],
+            |row| row.get(0)
+        ).optional()?;
+        
+        Ok(result)
+    }
+    
+    /// Get client visit count since a date
+    pub fn get_client_visit_count_since(&self, client_id: &str, since_date: &str) -> Result<i32, VaultError> {
+        let conn = self.conn()?;
+        
+        let count: i32 = conn.query_row(
+            "SELECT COUNT(*) FROM notes WHERE client_id = ?1 AND session_date >= ?2",
+            params![client_id, since_date],
+            |row| row.get(0)
+        )?;
+        
+        Ok(count)
+    }
+    
+    // ============================================
+    // Note Operations
+    // ============================================
+    
+    /// Sanitize note content by removing dangerous tokens
+    /// This strips QA/TRAP tokens, injection attempts, etc.
+    fn sanitize_note_content(content: &str) -> String {
+        let mut sanitized = content.to_string();
+        
+        // Remove entire lines containing QA/TRAP marker
+        let lines: Vec<&str> = sanitized.lines().collect();
+        let filtered_lines: Vec<&str> = lines.into_iter()
+            .filter(|line| {
+                let lower = line.to_lowercase();
+                !lower.contains("qa/trap") &&
+                !lower.contains("trap token") &&
+                !lower.contains("test token")
+            })
+            .collect();
+        sanitized = filtered_lines.join("\n");
+        
+        // Remove common injection patterns (case insensitive)
+        let dangerous_patterns = [
+            r"(?i)\.\.%2f",
+            r"(?i)%2e%2e",
+            r"(?i)javascript:",
+            r"(?i)file:///",
+            r"(?i)data:",
+            r"(?i)<script",
+            r"(?i)onclick=",
+            r"(?i)onerror=",
+            r"(?i)C:\\Users",
+            r"(?i)C:/Users",
+            r"(?i)\\x[0-9a-f]{2}",
+        ];
+        
+        for pattern_str in &dangerous_patterns {
+            if let Ok(re) = regex::Regex::new(pattern_str) {
+                sanitized = re.replace_all(&sanitized, "[REMOVED]").to_string();
+            }
+        }
+        
+        // Clean up any resulting empty lines or excessive whitespace
+        let lines: Vec<&str> = sanitized.lines()
+            .map(|l| l.trim_end())
+            .filter(|l| !l.is_empty() || true) // Keep structure
+            .collect();
+        
+        lines.join("\n").trim().to_string()
+    }
+    
+    pub fn create_note(
+        &self,
+        client_id: &str,
+        session_date: &str,
+        note_type: NoteType,
+        raw_input: &str,
+    ) -> Result<Note, VaultError> {
+        let conn = self.conn()?;
+        
+        // Sanitize content before saving
+        let sanitized_content = Self::sanitize_note_content(raw_input);
+        
+        // Always v4; see ids.rs
+        let id = uuid::Uuid::new_v4().to_string();
+        let now = chrono::Utc::now().timestamp_millis();
+        let content_hash = crypto::hash_sha256(sanitized_content.as_bytes());
+        let word_count = sanitized_content.split_whitespace().count() as i32;
+        
+        conn.execute(
+            "INSERT INTO notes (id, client_id, session_date, note_type, raw_input, word_count, 
+             status, content_hash, created_at, updated_at) 
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            params![
+                &id, client_id, session_date, note_type.to_string(), &sanitized_content,
+                word_count, "draft", &content_hash, now, now
+            ],
+        )?;
+        
+        // Update client session count
+        conn.execute(
+            "UPDATE clients SET session_count = session_count + 1, updated_at = ?1 WHERE id = ?2",
+            params![now, client_id],
+        )?;
+        
+        Ok(Note {
+            id,
+            client_id: client_id.to_string(),
+            session_date: session_date.to_string(),
+            note_type,
+            raw_input: sanitized_content,
+            structured_note: None,
+            word_count,
+            status: NoteStatus::Draft,
+            detection_ids: vec![],
+            attestations: vec![],
+            content_hash,
+            signed_at: None,
+            created_at: now,
+            updated_at: now,
+        })
+    }
+    
+    pub fn get_note(&self, id: &str) -> Result<Note, VaultError> {
+        let conn = self.conn()?;
+        
+        conn.query_row(
+            "SELECT id, client_id, session_date, note_type, raw_input, structured_note,
+             word_count, status, detection_ids, attestations, content_hash, signed_at, 
+             created_at, updated_at FROM notes WHERE id = ?1 AND deleted_at IS NULL
+             AND client_id NOT IN (SELECT id FROM clients WHERE deleted_at IS NOT NULL)",
+            params![id],
+            |row| {
+                let detection_ids_json: Option<String> = row.get(8)?;
+                let attestations_json: Option<String> = row.get(9)?;
+                
+                Ok(Note {
+                    id: row.get(0)?,
+                    client_id: row.get(1)?,
+                    session_date: row.get(2)?,
+                    note_type: NoteType::from_str(&row.get::<_, String>(3)?),
+                    raw_input: row.get(4)?,
+                    structured_note: row.get(5)?,
+                    word_count: row.get(6)?,
+                    status: NoteStatus::from_str(&row.get::<_, String>(7)?),
+                    detection_ids: detection_ids_json
+                        .map(|j| serde_json::from_str(&j).unwrap_or_default())
+                        .unwrap_or_default(),
+                    attestations: attestations_json
+                        .map(|j| serde_json::from_str(&j).unwrap_or_default())
+                        .unwrap_or_default(),
+                    content_hash: row.get(10)?,
+                    signed_at: row.get(11)?,
+                    created_at: row.get(12)?,
+                    updated_at: row.get(13)?,
+                })
+            }
+        ).map_err(|_| VaultError::NotFound(format!("Note {}", id)))
+    }
+    
+    pub fn list_notes(&self, client_id: Option<&str>) -> Result<Vec<Note>, VaultError> {
+        let conn = self.conn()?;
+        
+        let sql = match client_id {
+            Some(_) => "SELECT id, client_id, session_date, note_type, raw_input, structured_note,
+                        word_count, status, detection_ids, attestations, content_hash, signed_at,
+                        created_at, updated_at FROM notes WHERE client_id = ?1 AND deleted_at IS NULL
+                        AND client_id NOT IN (SELECT id FROM clients WHERE deleted_at IS NOT NULL)
+                        ORDER BY session_date DESC",
+            None => "SELECT id, client_id, session_date, note_type, raw_input, structured_note,
+                     word_count, status, detection_ids, attestations, content_hash, signed_at,
+                     created_at, updated_at FROM notes WHERE deleted_at IS NULL
+                     AND client_id NOT IN (SELECT id FROM clients WHERE deleted_at IS NOT NULL)
+                     ORDER BY session_date DESC",
+        };
+        
+        let mut stmt = conn.prepare(sql)?;
+        
+        let row_mapper = |row: &rusqlite::Row| {
+            let detection_ids_json: Option<String> = row.get(8)?;
+            let attestations_json: Option<String> = row.get(9)?;
+            
+            Ok(Note {
+                id: row.get(0)?,
+                client_id: row.get(1)?,
+                session_date: row.get(2)?,
+                note_type: NoteType::from_str(&row.get::<_, String>(3)?),
+                raw_input: row.get(4)?,
+                structured_note: row.get(5)?,
+                word_count: row.get(6)?,
+                status: NoteStatus::from_str(&row.get::<_, String>(7)?),
+                detection_ids: detection_ids_json
+                    .map(|j| serde_json::from_str(&j).unwrap_or_default())
+                    .unwrap_or_default(),
+                attestations: attestations_json
+                    .map(|j| serde_json::from_str(&j).unwrap_or_default())
+                    .unwrap_or_default(),
+                content_hash: row.get(10)?,
+                signed_at: row.get(11)?,
+                created_at: row.get(12)?,
+                updated_at: row.get(13)?,
+            })
+        };
+        
+        let rows = match client_id {
+            Some(cid) => stmt.query_map(params![cid], row_mapper)?,
+            None => stmt.query_map([], row_mapper)?,
+        };
+        
+        rows.collect::<Result<Vec<_>, _>>().map_err(VaultError::from)
+    }
+    
+    pub fn update_note(&self, id: &str, raw_input: &str, editor: Option<&str>) -> Result<Note, VaultError> {
+        let conn = self.conn()?;
+        self.ensure_note_not_trashed(id)?;
+        self.ensure_note_not_on_legal_hold(id)?;
+        let previous = self.get_note(id)?;
+        
+        // Sanitize content
+        let sanitized_content = Self::sanitize_note_content(raw_input);
+        
+        let now = chrono::Utc::now().timestamp_millis();
+        let content_hash = crypto::hash_sha256(sanitized_content.as_bytes());
+        let word_count = sanitized_content.split_whitespace().count() as i32;
+        
+        let tx = conn.unchecked_transaction()?;
+        if sanitized_content != previous.raw_input {
+            Self::record_note_revision(&tx, &previous, now, "edit", editor)?;
+        }
+        tx.execute(
+            "UPDATE notes SET raw_input = ?1, word_count = ?2, content_hash = ?3, updated_at = ?4 
+             WHERE id = ?5",
+            params![&sanitized_content, word_count, &content_hash, now, id],
+        )?;
+        tx.commit()?;
+        
+        self.invalidate_note_artifacts(id, &previous.client_id)?;
+        self.reanchor_note_detections(id)?;
+        self.get_note(id)
+    }
+    
+    pub fn update_note_structured(&self, id: &str, structured: &str, editor: Option<&str>) -> Result<Note, VaultError> {
+        let conn = self.conn()?;
+        self.ensure_note_not_trashed(id)?;
+        self.ensure_note_not_on_legal_hold(id)?;
+        
+        // Sanitize structured content too
+        let sanitized = Self::sanitize_note_content(structured);
+        
+        let now = chrono::Utc::now().timestamp_millis();
+        
+        let previous = self.get_note(id)?;
+        let tx = conn.unchecked_transaction()?;
+        if previous.structured_note.as_deref() != Some(sanitized.as_str()) {
+            Self::record_note_revision(&tx, &previous, now, "structure", editor)?;
+        }
+        tx.execute(
+            "UPDATE notes SET structured_note = ?1, updated_at = ?2 WHERE id = ?3",
+            params![&sanitized, now, id],
+        )?;
+        tx.commit()?;
+        
+        self.invalidate_note_artifacts(id, &previous.client_id)?;
+        self.reanchor_note_detections(id)?;
+        self.get_note(id)
+    }
+    
+    pub fn update_note_detections(&self, id: &str, detection_ids: &[String]) -> Result<(), VaultError> {
+        let conn = self.conn()?;
+        self.ensure_note_not_trashed(id)?;
+        let now = chrono::Utc::now().timestamp_millis();
+        let json = serde_json::to_string(detection_ids)
+            .map_err(|e| VaultError::Serialization(e.to_string()))?;
+        
+        conn.execute(
+            "UPDATE notes SET detection_ids = ?1, updated_at = ?2 WHERE id = ?3",
+            params![json, now, id],
+        )?;
+        
+        Ok(())
+    }
+    
+    pub fn sign_note(&self, id: &str, attestations_json: &str) -> Result<Note, VaultError> {
+        let conn = self.conn()?;
+        self.ensure_note_not_trashed(id)?;
+        // Multi-author notes: every author signs the current text first
+        let authorship = self.get_note_authorship(id)?;
+        if !authorship.complete {
+            return Err(VaultError::InvalidState(format!(
+                "Cannot sign: awaiting signatures from {}", authorship.awaiting_names().join(", ")
+            )));
+        }
+        let now = chrono::Utc::now().timestamp_millis();
+        
+        conn.execute(
+            "UPDATE notes SET status = 'signed', attestations = ?1, signed_at = ?2, updated_at = ?2 
+             WHERE id = ?3",
+            params![attestations_json, now, id],
+        )?;
+        
+        self.get_note(id)
+    }
+    
+    /// Amend a signed note - creates an amendment record and updates content
+    /// Signed notes cannot be directly edited, only amended
+    pub fn amend_note(&self, id: &str, amendment_text: &str, reason: &str, editor: Option<&str>) -> Result<Note, VaultError> {
+        let conn = self.conn()?;
+        self.ensure_note_not_trashed(id)?;
+        let note = self.get_note(id)?;
+        
+        // Only signed notes can be amended
+        if note.status != NoteStatus::Signed {
+            return Err(VaultError::InvalidState("Only signed notes can be amended".to_string()));
+        }
+        self.ensure_not_on_legal_hold(&note.client_id)?;
+        
+        let now = chrono::Utc::now().timestamp_millis();
+        let sanitized_amendment = Self::sanitize_note_content(amendment_text);
+        
+        // Format amendment with audit trail
+        let amendment_record = format!(
+            "\n\n--- AMENDMENT ({}) ---\nReason: {}\nAmended: {}\n\n{}",
+            chrono::DateTime::from_timestamp_millis(now)
+                .map(|dt| dt.format("%Y-%m-%d %H:%M:%S UTC").to_string())
+                .unwrap_or_else(|| "Unknown".to_string()),
+            reason,
+            chrono::DateTime::from_timestamp_millis(now)
+                .map(|dt| dt.format("%Y-%m-%d %H:%M:%S UTC").to_string())
+                .unwrap_or_else(|| "Unknown".to_string()),
+            sanitized_amendment
+        );
+        
+        // Append amendment to existing content
+        let new_content = format!("{}{}", note.raw_input, amendment_record);
+        let new_hash = crypto::hash_sha256(new_content.as_bytes());
+        let new_word_count = new_content.split_whitespace().count() as i32;
+        
+        let tx = conn.unchecked_transaction()?;
+        let from_rev = Self::record_note_revision(&tx, &note, now, "amendment", editor)?;
+        tx.execute(
+            "UPDATE notes SET raw_input = ?1, word_count = ?2, content_hash = ?3, updated_at = ?4, status = 'amended'
+             WHERE id = ?5",
+            params![&new_content, new_word_count, &new_hash, now, id],
+        )?;
+        tx.commit()?;
+        self.invalidate_note_artifacts(id, &note.client_id)?;
+        
+        // Log the amendment in audit, sized by its change summary
+        let changes = crate::note_diff::summarize(id, from_rev, from_rev + 1, &note.raw_input, &new_content);
+        crate::audit::log_note_amendment(
+            conn,
+            id,
+            changes.sections.len(),
+            changes.lines_added,
+            changes.lines_removed,
+            &crypto::hash_sha256(changes.summary.as_bytes()),
+        ).ok(); // Don't fail on audit error
+        
+        self.get_note(id)
+    }
+    
+    // ============================================
+    // Note Revisions
+    // ============================================
+    
+    /// Store `note` as it stands before `editor` overwrites it at `now`;
+    /// returns its revision number. Runs in the caller's transaction so the
+    /// revision and the overwrite land together.
+    fn record_note_revision(
+        tx: &rusqlite::Transaction,
+        note: &Note,
+        now: i64,
+        superseded_by: &str,
+        editor: Option<&str>,
+    ) -> Result<usize, VaultError> {
+        let revision: i64 = tx.query_row(
+            "SELECT COUNT(*) FROM note_revisions WHERE note_id = ?1",
+            [&note.id],
+            |row| row.get(0),
+        )?;
+        tx.execute(
+            "INSERT INTO note_revisions (note_id, revision, raw_input, structured_note, content_hash, status,
+             saved_at, superseded_at, superseded_by, editor)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            params![&note.id, revision, &note.raw_input, &note.structured_note, &note.content_hash,
+                    note.status.to_string().to_lowercase(), note.updated_at, now, superseded_by, editor],
+        )?;
+        Ok(revision as usize)
+    }
+    
+    /// Give notes amended before note_revisions existed a row per amendment
+    /// block, so revision numbers keep counting amendments (0 = as signed).
+    /// Block headers carry when each amendment was made; no editor was kept.
+    /// Only signed notes can have been amended: a draft that happens to
+    /// contain the marker text is left alone. Runs once, at migration.
+    fn backfill_amendment_revisions(conn: &Connection) -> Result<usize, VaultError> {
+        let notes: Vec<(String, String, Option<i64>, i64)> = conn.prepare(
+            "SELECT id, raw_input, signed_at, updated_at FROM notes
+             WHERE status IN ('signed', 'amended') AND instr(raw_input, ?1) > 0
+               AND NOT EXISTS (SELECT 1 FROM note_revisions r WHERE r.note_id = notes.id)"
+        )?.query_map([crate::note_diff::AMENDMENT_MARKER], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+        })?.collect::<Result<_, _>>()?;
+        
+        let tx = conn.unchecked_transaction()?;
+        for (note_id, raw_input, signed_at, updated_at) in &notes {
+            let versions = crate::note_diff::amendment_revisions(raw_input);
+            let mut saved_at = signed_at.unwrap_or(*updated_at);
+            for (revision, text) in versions[..versions.len() - 1].iter().enumerate() {
+                let header = &raw_input[text.len() + crate::note_diff::AMENDMENT_MARKER.len()..];
+                let superseded_at = header.get(..23)
+                    .and_then(|stamp| chrono::NaiveDateTime::parse_from_str(stamp, "%Y-%m-%d %H:%M:%S UTC").ok())
+                    .map(|dt| dt.and_utc().timestamp_millis())
+                    .unwrap_or(*updated_at);
+                tx.execute(
+                    "INSERT INTO note_revisions (note_id, revision, raw_input, content_hash, status,
+                     saved_at, superseded_at, superseded_by)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, 'amendment')",
+                    params![note_id, revision as i64, text, crypto::hash_sha256(text.as_bytes()),
+                            if revision == 0 { "signed" } else { "amended" }, saved_at, superseded_at],
+                )?;
+                saved_at = superseded_at;
+            }
+        }
+        tx.commit()?;
+        Ok(notes.len())
+    }
+    
+    /// Every version of a note, oldest first, ending with the current one
+    pub fn get_note_revisions(&self, note_id: &str) -> Result<Vec<crate::models::NoteRevision>, VaultError> {
+        let conn = self.conn()?;
+        let note = self.get_note(note_id)?;
+        
+        let mut stmt = conn.prepare(
+            "SELECT revision, raw_input, structured_note, content_hash, status, saved_at, superseded_at, superseded_by,
+                    editor
+             FROM note_revisions WHERE note_id = ?1 ORDER BY revision"
+        )?;
+        let mut revisions = stmt.query_map([note_id], |row| {
+            Ok(crate::models::NoteRevision {
+                note_id: note_id.to_string(),
+                revision: row.get::<_, i64>(0)? as usize,
+                raw_input: row.get(1)?,
+                structured_note: row.get(2)?,
+                content_hash: row.get(3)?,
+                status: NoteStatus::from_str(&row.get::<_, String>(4)?),
+                saved_at: row.get(5)?,
+                superseded_at: row.get(6)?,
+                superseded_by: row.get(7)?,
+                editor: row.get(8)?,
+            })
+        })?.collect::<Result<Vec<_>, _>>()?;
+        
+        revisions.push(crate::models::NoteRevision {
+            note_id: note.id,
+            revision: revisions.len(),
+            raw_input: note.raw_input,
+            structured_note: note.structured_note,
+            content_hash: note.content_hash,
+            status: note.status,
+            saved_at: note.updated_at,
+            superseded_at: None,
+            superseded_by: None,
+            editor: None,
+        });
+        Ok(revisions)
+    }
+    
+    // ============================================
+    // Trash
+    // ============================================
+    
+    /// Draft or reviewed notes can be trashed; signed ones are part of the
+    /// record and are amended instead
+    pub fn trash_note(&self, id: &str) -> Result<(), VaultError> {
+        let conn = self.conn()?;
+        let note = self.get_note(id)?;
+        self.ensure_not_on_legal_hold(&note.client_id)?;
+        if !matches!(note.status, NoteStatus::Draft | NoteStatus::Reviewed) {
+            return Err(VaultError::InvalidState("Signed notes can't be deleted; amend the note instead".to_string()));
+        }
+        
+        let trashed = conn.execute(
+            "UPDATE notes SET deleted_at = ?1 WHERE id = ?2 AND deleted_at IS NULL",
+            params![chrono::Utc::now().timestamp_millis(), id],
+        )?;
+        if trashed == 0 {
+            return Err(VaultError::InvalidState("Note is already in the trash".to_string()));
+        }
+        crate::audit::log_trash_event(
+            conn, crate::models::AuditEventType::NoteTrashed, crate::models::AuditResourceType::Note, id, "trashed",
+        ).map_err(|e| VaultError::Internal(format!("Audit write failed: {}", e)))?;
+        Ok(())
+    }
+    
+    pub fn restore_note(&self, id: &str) -> Result<Note, VaultError> {
+        let conn = self.conn()?;
+        let client_trashed: Option<bool> = conn.query_row(
+            "SELECT c.deleted_at IS NOT NULL FROM notes n JOIN clients c ON c.id = n.client_id
+             WHERE n.id = ?1 AND n.deleted_at IS NOT NULL",
+            [id],
+            |row| row.get(0),
+        ).optional()?;
+        match client_trashed {
+            None => return Err(VaultError::NotFound(format!("Trashed note {}", id))),
+            Some(true) => return Err(VaultError::InvalidState("Restore the client before restoring its notes".to_string())),
+            Some(false) => {}
+        }
+        
+        conn.execute("UPDATE notes SET deleted_at = NULL WHERE id = ?1", [id])?;
+        crate::audit::log_trash_event(
+            conn, crate::models::AuditEventType::NoteRestored, crate::models::AuditResourceType::Note, id, "restored",
+        ).map_err(|e| VaultError::Internal(format!("Audit write failed: {}", e)))?;
+        self.get_note(id)
+    }
+    
+    /// Trashed notes read as missing; changing one needs a restore first
+    fn ensure_note_not_trashed(&self, id: &str) -> Result<(), VaultError> {
+        let conn = self.conn()?;
+        let trashed: Option<bool> = conn.query_row(
+            "SELECT deleted_at IS NOT NULL FROM notes WHERE id = ?1",
+            [id],
+            |row| row.get(0),
+        ).optional()?;
+        if trashed == Some(true) {
+            return Err(VaultError::InvalidState("Note is in the trash; restore it before changing it".to_string()));
+        }
+        Ok(())
+    }
+    
+    /// Permanently delete a trashed note and everything derived from it
+    pub fn purge_note(&self, id: &str) -> Result<(), VaultError> {
+        self.purge_note_with(id, "purged")
+    }
+    
+    fn purge_note_with(&self, id: &str, transition: &str) -> Result<(), VaultError> {
+        let conn = self.conn()?;
+        let client_id: String = conn.query_row(
+            "SELECT client_id FROM notes WHERE id = ?1 AND deleted_at IS NOT NULL",
+            [id],
+            |row| row.get(0),
+        ).optional()?.ok_or_else(|| VaultError::NotFound(format!("Trashed note {}", id)))?;
+        self.ensure_not_on_legal_hold(&client_id)?;
+        
+        // Audited before anything is deleted, like records destruction
+        crate::audit::log_trash_event(
+            conn, crate::models::AuditEventType::NoteDeleted, crate::models::AuditResourceType::Note, id, transition,
+        ).map_err(|e| VaultError::Internal(format!("Audit write failed: {}", e)))?;
+        
+        let tx = conn.unchecked_transaction()?;
+        Self::delete_note_dependents(&tx, "note_id = ?1", id)?;
+        // A stored create_note result would otherwise replay the note's text
+        tx.execute("DELETE FROM idempotency_keys WHERE instr(result_json, ?1) > 0", [id])?;
+        tx.execute("DELETE FROM notes WHERE id = ?1", [id])?;
+        tx.execute("DELETE FROM derived_cache WHERE cache_key = ?1", [&client_id])?;
+        tx.execute(
+            "UPDATE clients SET session_count = MAX(session_count - 1, 0) WHERE id = ?1",
+            [&client_id],
+        )?;
+        tx.commit()?;
+        crate::fulltext::optimize_index(conn)?;
+        Ok(())
+    }
+    
+    /// Delete rows keyed to the notes matched by `filter` (over `note_id`, with ?1 bound to `param`)
+    fn delete_note_dependents(tx: &rusqlite::Transaction, filter: &str, param: &str) -> Result<(), VaultError> {
+        for table in ["embeddings", "note_detection_anchors", "note_type_assignments", "note_locks",
+                      "review_comments", "review_reflections", "note_reviews", "note_licensure_checks", "note_revisions",
+                      "note_authors", "note_section_authors", "advisory_findings", "client_letters",
+                      "ehr_deliveries", "mental_status_exams", "session_metrics"] {
+            tx.execute(&format!("DELETE FROM {} WHERE {}", table, filter), [param])?;
+        }
+        tx.execute(
+            &format!("DELETE FROM derived_cache WHERE cache_key IN (SELECT note_id FROM (SELECT id AS note_id FROM notes) WHERE {})", filter),
+            [param],
+        )?;
+        // Attendance outlives the note it was documented in, and the
+        // hash-only de-identification record outlives what it disclosed
+        for table in ["cohort_attendance", "deidentification_audits"] {
+            tx.execute(&format!("UPDATE {} SET note_id = NULL WHERE {}", table, filter), [param])?;
+        }
+        Ok(())
+    }
+    
+    /// Delete a client's notes and documents and everything derived from
+    /// them. The client row is left for the caller to scrub.
+    fn delete_client_chart(tx: &rusqlite::Transaction, client_id: &str) -> Result<(), VaultError> {
+        Self::delete_note_dependents(tx, "note_id IN (SELECT id FROM notes WHERE client_id = ?1)", client_id)?;
+        // Stored command results replay client and note content for up to
+        // a day; drop any that name this client or one of its notes
+        tx.execute(
+            "DELETE FROM idempotency_keys WHERE instr(result_json, ?1) > 0
+                OR EXISTS (SELECT 1 FROM notes n WHERE n.client_id = ?1 AND instr(idempotency_keys.result_json, n.id) > 0)",
+            [client_id],
+        )?;
+        for table in ["advisory_findings", "client_letters", "release_authorizations", "mental_status_exams",
+                      "session_metrics", "client_photos", "client_documents", "notes"] {
+            tx.execute(&format!("DELETE FROM {} WHERE client_id = ?1", table), [client_id])?;
+        }
+        tx.execute("DELETE FROM derived_cache WHERE cache_key = ?1", [client_id])?;
+        Ok(())
+    }
+    
+    /// Clients with signed notes leave through discharge and records
+    /// destruction once retention allows, not the trash
+    fn ensure_client_has_no_signed_notes(&self, client_id: &str) -> Result<(), VaultError> {
+        let conn = self.conn()?;
+        let signed: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM notes WHERE client_id = ?1 AND LOWER(status) NOT IN ('draft', 'reviewed')",
+            [client_id],
+            |row| row.get(0),
+        )?;
+        if signed > 0 {
+            return Err(VaultError::InvalidState(format!(
+                "Client has {} signed note(s); discharge the client and use records destruction instead", signed
+            )));
+        }
+        Ok(())
+    }
+    
+    /// Hide a client and their chart. Their notes stay with the client and
+    /// come back when it is restored.
+    pub fn trash_client(&self, id: &str) -> Result<(), VaultError> {
+        let conn = self.conn()?;
+        let trashed: Option<bool> = conn.query_row(
+            "SELECT deleted_at IS NOT NULL FROM clients WHERE id = ?1",
+            [id],
+            |row| row.get(0),
+        ).optional()?;
+        match trashed {
+            None => return Err(VaultError::NotFound(format!("Client {}", id))),
+            Some(true) => return Err(VaultError::InvalidState("Client is already in the trash".to_string())),
+            Some(false) => {}
+        }
+        self.ensure_not_on_legal_hold(id)?;
+        self.ensure_client_has_no_signed_notes(id)?;
+        
+        let trashed = conn.execute(
+            "UPDATE clients SET deleted_at = ?1 WHERE id = ?2 AND deleted_at IS NULL",
+            params![chrono::Utc::now().timestamp_millis(), id],
+        )?;
+        if trashed == 0 {
+            return Err(VaultError::InvalidState("Client is already in the trash".to_string()));
+        }
+        crate::audit::log_trash_event(
+            conn, crate::models::AuditEventType::ClientTrashed, crate::models::AuditResourceType::Client, id, "trashed",
+        ).map_err(|e| VaultError::Internal(format!("Audit write failed: {}", e)))?;
+        Ok(())
+    }
+    
+    pub fn restore_client(&self, id: &str) -> Result<Client, VaultError> {
+        let conn = self.conn()?;
+        let restored = conn.execute(
+            "UPDATE clients SET deleted_at = NULL WHERE id = ?1 AND deleted_at IS NOT NULL AND status != ?2",
+            params![id, TRASH_PURGED_STATUS],
+        )?;
+        if restored == 0 {
+            return Err(VaultError::NotFound(format!("Trashed client {}", id)));
+        }
+        crate::audit::log_trash_event(
+            conn, crate::models::AuditEventType::ClientRestored, crate::models::AuditResourceType::Client, id, "restored",
+        ).map_err(|e| VaultError::Internal(format!("Audit write failed: {}", e)))?;
+        self.get_client(id)
+    }
+    
+    /// Permanently delete a trashed client's chart. The client row stays as
+    /// a scrubbed tombstone so audit entries still resolve.
+    pub fn purge_client(&self, id: &str) -> Result<(), VaultError> {
+        self.purge_client_with(id, "purged")
+    }
+    
+    fn purge_client_with(&self, id: &str, transition: &str) -> Result<(), VaultError> {
+        let conn = self.conn()?;
+        let in_trash: bool = conn.query_row(
+            "SELECT COUNT(*) > 0 FROM clients WHERE id = ?1 AND deleted_at IS NOT NULL AND status != ?2",
+            params![id, TRASH_PURGED_STATUS],
+            |row| row.get(0),
+        )?;
+        if !in_trash {
+            return Err(VaultError::NotFound(format!("Trashed client {}", id)));
+        }
+        self.ensure_not_on_legal_hold(id)?;
+        self.ensure_client_has_no_signed_notes(id)?;
+        
+        crate::audit::log_trash_event(
+            conn, crate::models::AuditEventType::ClientPurged, crate::models::AuditResourceType::Client, id, transition,
+        ).map_err(|e| VaultError::Internal(format!("Audit write failed: {}", e)))?;
+        
+        let tx = conn.unchecked_transaction()?;
+        Self::delete_client_chart(&tx, id)?;
+        tx.execute(
+            "UPDATE clients SET display_name = ?2, status = ?3, session_count = 0, updated_at = ?4,
+                 date_of_birth = NULL, phone = NULL, email = NULL, emergency_contact = NULL, insurance_info = NULL,
+                 diagnosis_codes = NULL, treatment_start_date = NULL, referring_provider = NULL, notes = NULL
+             WHERE id = ?1",
+            params![id, format!("Purged record {}", crate::ids::short_id(id)), TRASH_PURGED_STATUS,
+                    chrono::Utc::now().timestamp_millis()],
+        )?;
+        tx.commit()?;
+        crate::fulltext::optimize_index(conn)?;
+        Ok(())
+    }
+    
+    /// Trashed notes and clients, most recently deleted first
+    pub fn list_trash(&self) -> Result<Vec<crate::models::TrashedItem>, VaultError> {
+        use crate::models::{TrashItemKind, TrashedItem};
+        
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT 'client', id, id, display_name, deleted_at FROM clients
+             WHERE deleted_at IS NOT NULL AND status != ?1
+             UNION ALL
+             SELECT 'note', id, client_id, session_date || ' ' || note_type, deleted_at FROM notes
+             WHERE deleted_at IS NOT NULL
+             ORDER BY 5 DESC"
+        )?;
+        let rows = stmt.query_map([TRASH_PURGED_STATUS], |row| {
+            let deleted_at: i64 = row.get(4)?;
+            Ok(TrashedItem {
+                kind: if row.get::<_, String>(0)? == "client" { TrashItemKind::Client } else { TrashItemKind::Note },
+                id: row.get(1)?,
+                client_id: row.get(2)?,
+                label: row.get(3)?,
+                deleted_at,
+                purge_after: deleted_at + TRASH_RETENTION_DAYS * 86_400_000,
+            })
+        })?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(VaultError::from)
+    }
+    
+    /// Purge everything whose retention window has passed. Items that can't
+    /// be purged yet (e.g. under legal hold) are left for the next run.
+    pub fn purge_expired_trash(&self, now_ms: i64) -> Result<(usize, usize), VaultError> {
+        let mut expired: Vec<_> = self.list_trash()?.into_iter().filter(|item| item.purge_after <= now_ms).collect();
+        // Notes first, so none is left pointing at a client purged before it
+        expired.sort_by_key(|item| item.kind == crate::models::TrashItemKind::Client);
+        
+        let (mut notes, mut clients) = (0, 0);
+        for item in expired {
+            let purged = match item.kind {
+                crate::models::TrashItemKind::Note => self.purge_note_with(&item.id, "purged:expired"),
+                crate::models::TrashItemKind::Client => self.purge_client_with(&item.id, "purged:expired"),
+            };
+            match (purged, item.kind) {
+                (Ok(()), crate::models::TrashItemKind::Note) => notes += 1,
+                (Ok(()), crate::models::TrashItemKind::Client) => clients += 1,
+                (Err(e), kind) => log::warn!("Could not purge trashed {:?} {}: {}", kind, item.id, e),
+            }
+        }
+        Ok((notes, clients))
+    }
+    
+    // ============================================
+    // Detection Anchors
+    // ============================================
+    
+    /// Text that ethics analysis and signing operate on
+    pub fn analyzed_text(note: &Note) -> &str {
+        note.structured_note.as_deref().unwrap_or(&note.raw_input)
+    }
+    
+    /// Store the detections from a fresh analysis of the note's current
+    /// text, clearing any re-analysis requirement
+    pub fn record_note_detections(
+        &self,
+        note_id: &str,
+        detections: &[StoredDetection],
+    ) -> Result<crate::models::NoteDetectionState, VaultError> {
+        let note = self.get_note(note_id)?;
+        let content_hash = crypto::hash_sha256(Self::analyzed_text(&note).as_bytes());
+        if let Some(stale) = detections.iter().find(|d| d.content_hash.as_deref() != Some(content_hash.as_str())) {
+            return Err(VaultError::InvalidState(format!(
+                "Detection {} was not produced from the note's current text", stale.id
+            )));
+        }
+        
+        let now = chrono::Utc::now().timestamp_millis();
+        let state = crate::models::NoteDetectionState {
+            note_id: note_id.to_string(),
+            detections: detections.to_vec(),
+            content_hash,
+            reanalyze_required: false,
+            invalidated: Vec::new(),
+            analyzed_at: now,
+            updated_at: now,
+        };
+        self.save_detection_state(&state)?;
+        Ok(state)
+    }
+    
+    pub fn get_note_detection_state(&self, note_id: &str) -> Result<Option<crate::models::NoteDetectionState>, VaultError> {
+        let conn = self.conn()?;
+        let row: Option<(String, String, bool, String, i64, i64)> = conn.query_row(
+            "SELECT detections_json, content_hash, reanalyze_required, invalidated_json, analyzed_at, updated_at
+             FROM note_detection_anchors WHERE note_id = ?1",
+            [note_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?, row.get(5)?)),
+        ).optional()?;
+        
+        row.map(|(detections_json, content_hash, reanalyze_required, invalidated_json, analyzed_at, updated_at)| {
+            Ok(crate::models::NoteDetectionState {
+                note_id: note_id.to_string(),
+                detections: serde_json::from_str(&detections_json)
+                    .map_err(|e| VaultError::Serialization(e.to_string()))?,
+                content_hash,
+                reanalyze_required,
+                invalidated: serde_json::from_str(&invalidated_json).unwrap_or_default(),
+                analyzed_at,
+                updated_at,
+            })
+        }).transpose()
+    }
+    
+    /// Move stored detections onto the note's edited text. Detections whose
+    /// text is gone are dropped and the note is marked for re-analysis.
+    fn reanchor_note_detections(&self, note_id: &str) -> Result<(), VaultError> {
+        let Some(mut state) = self.get_note_detection_state(note_id)? else {
+            return Ok(());
+        };
+        let note = self.get_note(note_id)?;
+        let text = Self::analyzed_text(&note);
+        let content_hash = crypto::hash_sha256(text.as_bytes());
+        if content_hash == state.content_hash {
+            return Ok(());
+        }
+        
+        let outcome = crate::ethics::reanchor_detections(&state.detections, text);
+        if !outcome.invalidated.is_empty() {
+            log::info!("Note {}: {} detection(s) invalidated by edit", note_id, outcome.invalidated.len());
+        }
+        if !outcome.resolved.is_empty() {
+            log::info!("Note {}: {} absence finding(s) resolved by edit", note_id, outcome.resolved.len());
+        }
+        state.reanalyze_required |= outcome.reanalyze_required();
+        state.invalidated.extend(outcome.invalidated);
+        state.detections = outcome.detections;
+        state.content_hash = content_hash;
+        state.updated_at = chrono::Utc::now().timestamp_millis();
+        self.save_detection_state(&state)
+    }
+    
+    fn save_detection_state(&self, state: &crate::models::NoteDetectionState) -> Result<(), VaultError> {
+        let conn = self.conn()?;
+        let detections_json = serde_json::to_string(&state.detections)
+            .map_err(|e| VaultError::Serialization(e.to_string()))?;
+        let invalidated_json = serde_json::to_string(&state.invalidated)
+            .map_err(|e| VaultError::Serialization(e.to_string()))?;
+        conn.execute(
+            "INSERT OR REPLACE INTO note_detection_anchors
+             (note_id, detections_json, content_hash, reanalyze_required, invalidated_json, analyzed_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                &state.note_id, detections_json, &state.content_hash, state.reanalyze_required,
+                invalidated_json, state.analyzed_at, state.updated_at
+            ],
+        )?;
+        
+        let ids: Vec<String> = state.detections.iter().map(|d| d.id.clone()).collect();
+        self.update_note_detections(&state.note_id, &ids)
+    }
+    
+    // ============================================
+    // Note Locks (advisory)
+    // ============================================
+    
+    /// Take (or refresh) the edit lock on a note for one window/pane.
+    ///
+    /// Locks are advisory: a second editor gets `acquired: false` plus the
+    /// current holder so the UI can warn, and may pass `force` to take over.
+    /// Re-acquiring with the same holder_id extends the expiry (heartbeat).
+    pub fn acquire_note_lock(
+        &self,
+        note_id: &str,
+        holder_id: &str,
+        holder_label: Option<&str>,
+        ttl_secs: Option<i64>,
+        force: bool,
+    ) -> Result<NoteLockOutcome, VaultError> {
+        let conn = self.conn()?;
+        self.get_note(note_id)?;
+        
+        let now = chrono::Utc::now().timestamp_millis();
+        let ttl_ms = ttl_secs.unwrap_or(NOTE_LOCK_TTL_SECS).clamp(10, NOTE_LOCK_MAX_TTL_SECS) * 1000;
+        
+        // Auto-expire abandoned locks (crashed window, sleep, etc.)
+        conn.execute("DELETE FROM note_locks WHERE expires_at <= ?1", params![now])?;
+        
+        let acquired_at = match self.get_note_lock(note_id)? {
+            Some(current) if current.holder_id == holder_id => current.acquired_at,
+            Some(current) if !force => {
+                return Ok(NoteLockOutcome { acquired: false, lock: current });
+            }
+            Some(current) => {
+                log::warn!("Note lock on {} taken over from holder {}", note_id, current.holder_id);
+                now
+            }
+            None => now,
+        };
+        
+        let lock = NoteLock {
+            note_id: note_id.to_string(),
+            holder_id: holder_id.to_string(),
+            holder_label: holder_label.map(|l| l.to_string()),
+            acquired_at,
+            expires_at: now + ttl_ms,
+        };
+        
+        conn.execute(
+            "INSERT OR REPLACE INTO note_locks (note_id, holder_id, holder_label, acquired_at, expires_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![&lock.note_id, &lock.holder_id, &lock.holder_label, lock.acquired_at, lock.expires_at],
+        )?;
+        
+        Ok(NoteLockOutcome { acquired: true, lock })
+    }
+    
+    /// Release a lock held by this holder. Returns false if it held none.
+    pub fn release_note_lock(&self, note_id: &str, holder_id: &str) -> Result<bool, VaultError> {
+        let conn = self.conn()?;
+        let removed = conn.execute(
+            "DELETE FROM note_locks WHERE note_id = ?1 AND holder_id = ?2",
+            params![note_id, holder_id],
+        )?;
+        Ok(removed > 0)
+    }
+    
+    /// Release every lock held by a holder (window closing)
+    pub fn release_note_locks_for_holder(&self, holder_id: &str) -> Result<usize, VaultError> {
+        let conn = self.conn()?;
+        let removed = conn.execute(
+            "DELETE FROM note_locks WHERE holder_id = ?1",
+            params![holder_id],
+        )?;
+        Ok(removed)
+    }
+    
+    /// Current unexpired lock on a note, if any
+    pub fn get_note_lock(&self, note_id: &str) -> Result<Option<NoteLock>, VaultError> {
+        let conn = self.conn()?;
+        let now = chrono::Utc::now().timestamp_millis();
+        
+        let lock = conn.query_row(
+            "SELECT note_id, holder_id, holder_label, acquired_at, expires_at
+             FROM note_locks WHERE note_id = ?1 AND expires_at > ?2",
+            params![note_id, now],
+            |row| Ok(NoteLock {
+                note_id: row.get(0)?,
+                holder_id: row.get(1)?,
+                holder_label: row.get(2)?,
+                acquired_at: row.get(3)?,
+                expires_at: row.get(4)?,
+            }),
+        ).optional()?;
+        
+        Ok(lock)
+    }
+    
+    // ============================================
+    // Reason-for-Access
+    // ============================================
+    
+    /// Record why a restricted chart is being opened. The audit entry is
+    /// written first; if it can't be, no grant is issued.
+    pub fn record_chart_access(
+        &self,
+        client_id: &str,
+        reason: AccessReason,
+        justification: Option<&str>,
+        ttl_minutes: u32,
+    ) -> Result<ChartAccessGrant, VaultError> {
+        let conn = self.conn()?;
+        self.get_client(client_id)?;
+        
+        let justification = justification.map(str::trim).filter(|j| !j.is_empty());
+        if reason == AccessReason::Other && justification.is_none() {
+            return Err(VaultError::Internal("A justification is required when the reason is 'other'".to_string()));
+        }
+        
+        let justification_hash = justification.map(|j| crypto::hash_sha256(j.as_bytes()));
+        let entry = crate::audit::log_chart_access(conn, client_id, reason, justification_hash.as_deref())
+            .map_err(|e| VaultError::Internal(format!("Audit write failed: {}", e)))?;
+        
+        let now = chrono::Utc::now().timestamp_millis();
+        let grant = ChartAccessGrant {
+            id: crate::ids::new_id(),
+            client_id: client_id.to_string(),
+            audit_entry_id: entry.id,
+            reason,
+            justification: justification.map(|j| j.to_string()),
+            created_at: now,
+            expires_at: now + ttl_minutes.max(1) as i64 * 60_000,
+        };
+        
+        conn.execute(
+            "INSERT INTO chart_access_grants (id, client_id, audit_entry_id, reason, justification, created_at, expires_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![&grant.id, &grant.client_id, &grant.audit_entry_id, reason.as_str(),
+                    &grant.justification, grant.created_at, grant.expires_at],
+        )?;
+        
+        Ok(grant)
+    }
+    
+    /// Whether an unexpired reason-for-access covers this client's chart
+    pub fn has_active_chart_access(&self, client_id: &str) -> Result<bool, VaultError> {
+        let conn = self.conn()?;
+        let now = chrono::Utc::now().timestamp_millis();
+        
+        let count: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM chart_access_grants WHERE client_id = ?1 AND expires_at > ?2",
+            params![client_id, now],
+            |row| row.get(0),
+        )?;
+        
+        Ok(count > 0)
+    }
+    
+    /// Whether this client's chart can be read now: the policy doesn't
+    /// restrict its status, or an unexpired reason covers it
+    pub fn chart_access_allowed(
+        &self,
+        client_id: &str,
+        policy: &crate::policy::AccessPolicy,
+    ) -> Result<bool, VaultError> {
+        if !policy.require_reason {
+            return Ok(true);
+        }
+        let client = self.get_client(client_id)?;
+        Ok(!policy.requires_reason(&client.status) || self.has_active_chart_access(client_id)?)
+    }
+    
+    /// Clients whose charts `chart_access_allowed` refuses right now, for
+    /// filtering list and search results in one query
+    pub fn restricted_chart_ids(
+        &self,
+        policy: &crate::policy::AccessPolicy,
+    ) -> Result<std::collections::HashSet<String>, VaultError> {
+        if !policy.require_reason {
+            return Ok(std::collections::HashSet::new());
+        }
+        let conn = self.conn()?;
+        let now = chrono::Utc::now().timestamp_millis();
+        
+        let mut stmt = conn.prepare(
+            "SELECT id, status FROM clients
+             WHERE id NOT IN (SELECT client_id FROM chart_access_grants WHERE expires_at > ?1)"
+        )?;
+        let rows = stmt.query_map(params![now], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?;
+        
+        let mut restricted = std::collections::HashSet::new();
+        for row in rows {
+            let (id, status) = row?;
+            if policy.requires_reason(&status) {
+                restricted.insert(id);
+            }
+        }
+        Ok(restricted)
+    }
+    
+    // ============================================
+    // Emergency Access (break-glass)
+    // ============================================
+    
+    /// Set the emergency access credential. It is separate from the vault
+    /// passphrase and given only to designated covering clinicians. Once
+    /// set, it can only be replaced by someone who knows it: `current` must
+    /// match, and a refused attempt is audited as blocked.
+    pub fn set_emergency_access_credential(&self, credential: &str, current: Option<&str>) -> Result<(), VaultError> {
+        let conn = self.conn()?;
+        if credential.chars().count() < EMERGENCY_CREDENTIAL_MIN_CHARS {
+            return Err(VaultError::InvalidState(format!(
+                "Emergency access credential must be at least {} characters", EMERGENCY_CREDENTIAL_MIN_CHARS
+            )));
+        }
+        
+        let replacing: bool = conn.query_row(
+            "SELECT COUNT(*) > 0 FROM settings WHERE key = 'emergency_access_credential'",
+            [],
+            |row| row.get(0),
+        )?;
+        if replacing {
+            let verified = match current {
+                Some(current) => self.verify_emergency_access_credential(current)?,
+                None => false,
+            };
+            if !verified {
+                crate::audit::log_emergency_credential_change(conn, "rejected", crate::models::AuditOutcome::Blocked)
+                    .map_err(|e| VaultError::Internal(format!("Audit write failed: {}", e)))?;
+                return Err(VaultError::InvalidState(
+                    "Enter the current emergency access credential to replace it".to_string()
+                ));
+            }
+        }
+        
+        let salt = crypto::generate_salt();
+        let hash = crypto::hash_secret(credential, &salt)?;
+        let json = serde_json::json!({ "salt": hex::encode(salt), "hash": hash }).to_string();
+        conn.execute(
+            "INSERT OR REPLACE INTO settings (key, value) VALUES ('emergency_access_credential', ?1)",
+            [&json],
+        )?;
+        crate::audit::log_emergency_credential_change(
+            conn,
+            if replacing { "replaced" } else { "set" },
+            crate::models::AuditOutcome::Success,
+        ).map_err(|e| VaultError::Internal(format!("Audit write failed: {}", e)))?;
+        Ok(())
+    }
+    
+    fn verify_emergency_access_credential(&self, credential: &str) -> Result<bool, VaultError> {
+        let conn = self.conn()?;
+        let json: Option<String> = conn.query_row(
+            "SELECT value FROM settings WHERE key = 'emergency_access_credential'",
+            [],
+            |row| row.get(0),
+        ).optional()?;
+        let Some(stored) = json.and_then(|j| serde_json::from_str::<serde_json::Value>(&j).ok()) else {
+            return Err(VaultError::InvalidState("Emergency access is not configured".to_string()));
+        };
+        
+        let salt: [u8; 16] = stored["salt"].as_str()
+            .and_then(|s| hex::decode(s).ok())
+            .and_then(|b| b.try_into().ok())
+            .ok_or_else(|| VaultError::Internal("Corrupt emergency access credential".to_string()))?;
+        let expected = stored["hash"].as_str().unwrap_or_default();
+        Ok(crypto::digests_match(&crypto::hash_secret(credential, &salt)?, expected))
+    }
+    
+    /// Open break-glass access to one client's recent notes. The credential
+    /// and a written justification are both required; a failed attempt is
+    /// audited as blocked. The activation entry is written before the
+    /// session exists, as with reason-for-access grants.
+    pub fn activate_emergency_access(
+        &self,
+        client_id: &str,
+        covering_clinician: &str,
+        credential: &str,
+        justification: &str,
+        ttl_minutes: u32,
+    ) -> Result<EmergencyAccessSession, VaultError> {
+        let conn = self.conn()?;
+        self.get_client(client_id)?;
+        
+        let covering_clinician = covering_clinician.trim();
+        let justification = justification.trim();
+        if covering_clinician.is_empty() {
+            return Err(VaultError::InvalidState("Name the covering clinician".to_string()));
+        }
+        if justification.chars().count() < EMERGENCY_JUSTIFICATION_MIN_CHARS {
+            return Err(VaultError::InvalidState(format!(
+                "Emergency access needs a justification of at least {} characters", EMERGENCY_JUSTIFICATION_MIN_CHARS
+            )));
+        }
+        
+        let id = crate::ids::new_id();
+        let justification_hash = crypto::hash_sha256(justification.as_bytes());
+        if !self.verify_emergency_access_credential(credential)? {
+            crate::audit::log_emergency_access(
+                conn,
+                crate::models::AuditEventType::EmergencyAccessActivated,
+                crate::models::AuditResourceType::Client,
+                client_id,
+                crate::models::AuditOutcome::Blocked,
+                &id,
+                Some(&justification_hash),
+            ).map_err(|e| VaultError::Internal(format!("Audit write failed: {}", e)))?;
+            return Err(VaultError::InvalidState("Emergency access credential is incorrect".to_string()));
+        }
+        
+        let entry = crate::audit::log_emergency_access(
+            conn,
+            crate::models::AuditEventType::EmergencyAccessActivated,
+            crate::models::AuditResourceType::Client,
+            client_id,
+            crate::models::AuditOutcome::Success,
+            &id,
+            Some(&justification_hash),
+        ).map_err(|e| VaultError::Internal(format!("Audit write failed: {}", e)))?;
+        
+        let now = chrono::Utc::now().timestamp_millis();
+        let ttl = ttl_minutes.clamp(1, EMERGENCY_ACCESS_MAX_MINUTES) as i64;
+        let session = EmergencyAccessSession {
+            id,
+            client_id: client_id.to_string(),
+            covering_clinician: covering_clinician.to_string(),
+            justification: justification.to_string(),
+            audit_entry_id: entry.id,
+            activated_at: now,
+            expires_at: now + ttl * 60_000,
+            ended_at: None,
+            access_count: 0,
+            review_status: "pending".to_string(),
+            reviewed_by: None,
+            reviewed_at: None,
+            review_note: None,
+        };
+        conn.execute(
+            "INSERT INTO emergency_access_sessions
+             (id, client_id, covering_clinician, justification, audit_entry_id, activated_at, expires_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![&session.id, &session.client_id, &session.covering_clinician, &session.justification,
+                    &session.audit_entry_id, session.activated_at, session.expires_at],
+        )?;
+        
+        Ok(session)
+    }
+    
+    pub fn get_emergency_access_session(&self, session_id: &str) -> Result<EmergencyAccessSession, VaultError> {
+        let conn = self.conn()?;
+        conn.query_row(
+            &format!("{} WHERE id = ?1", EMERGENCY_SESSION_SELECT),
+            [session_id],
+            map_emergency_session,
+        ).optional()?
+            .ok_or_else(|| VaultError::NotFound(format!("Emergency access session {}", session_id)))
+    }
+    
+    /// Sessions, newest first; with `pending_only`, those still awaiting review
+    pub fn list_emergency_access_sessions(&self, pending_only: bool) -> Result<Vec<EmergencyAccessSession>, VaultError> {
+        let conn = self.conn()?;
+        let filter = if pending_only { "WHERE review_status = 'pending'" } else { "" };
+        let mut stmt = conn.prepare(&format!(
+            "{} {} ORDER BY activated_at DESC", EMERGENCY_SESSION_SELECT, filter
+        ))?;
+        let sessions = stmt.query_map([], map_emergency_session)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(sessions)
+    }
+    
+    /// Non-draft notes from the last EMERGENCY_ACCESS_NOTE_WINDOW_DAYS under
+    /// an active session. Each note returned is audited individually.
+    pub fn read_emergency_access_notes(&self, session_id: &str) -> Result<Vec<Note>, VaultError> {
+        let session = self.get_emergency_access_session(session_id)?;
+        let now = chrono::Utc::now();
+        if session.ended_at.is_some() || session.expires_at <= now.timestamp_millis() {
+            return Err(VaultError::InvalidState("Emergency access session has ended".to_string()));
+        }
+        
+        let cutoff = (now - chrono::Duration::days(EMERGENCY_ACCESS_NOTE_WINDOW_DAYS))
+            .format("%Y-%m-%d").to_string();
+        let notes: Vec<Note> = self.list_notes(Some(&session.client_id))?
+            .into_iter()
+            .filter(|n| n.status != NoteStatus::Draft && n.session_date.as_str() >= cutoff.as_str())
+            .collect();
+        
+        let conn = self.conn()?;
+        for note in &notes {
+            crate::audit::log_emergency_access(
+                conn,
+                crate::models::AuditEventType::EmergencyAccessRead,
+                crate::models::AuditResourceType::Note,
+                &note.id,
+                crate::models::AuditOutcome::Success,
+                session_id,
+                None,
+            ).map_err(|e| VaultError::Internal(format!("Audit write failed: {}", e)))?;
+        }
+        conn.execute(
+            "UPDATE emergency_access_sessions SET access_count = access_count + ?1 WHERE id = ?2",
+            params![notes.len() as i64, session_id],
+        )?;
+        
+        Ok(notes)
+    }
+    
+    /// Close a session before it expires
+    pub fn end_emergency_access(&self, session_id: &str) -> Result<EmergencyAccessSession, VaultError> {
+        let conn = self.conn()?;
+        let now = chrono::Utc::now().timestamp_millis();
+        conn.execute(
+            "UPDATE emergency_access_sessions SET ended_at = ?1 WHERE id = ?2 AND ended_at IS NULL AND expires_at > ?1",
+            params![now, session_id],
+        )?;
+        self.get_emergency_access_session(session_id)
+    }
+    
+    /// Record the post-hoc review of a session. `appropriate` is the
+    /// reviewer's finding; a note is required when it was not. The
+    /// clinician who activated the session cannot review it.
+    pub fn review_emergency_access(
+        &self,
+        session_id: &str,
+        reviewer: &str,
+        appropriate: bool,
+        note: Option<&str>,
+    ) -> Result<EmergencyAccessSession, VaultError> {
+        let conn = self.conn()?;
+        let session = self.get_emergency_access_session(session_id)?;
+        if session.review_status != "pending" {
+            return Err(VaultError::InvalidState("Emergency access session already reviewed".to_string()));
+        }
+        let reviewer = reviewer.trim();
+        if reviewer.is_empty() {
+            return Err(VaultError::InvalidState("Name the reviewer".to_string()));
+        }
+        if reviewer.to_lowercase() == session.covering_clinician.trim().to_lowercase() {
+            return Err(VaultError::InvalidState(
+                "Emergency access must be reviewed by someone other than the clinician who used it".to_string()
+            ));
+        }
+        let note = note.map(str::trim).filter(|n| !n.is_empty());
+        if !appropriate && note.is_none() {
+            return Err(VaultError::InvalidState("Explain why the access was inappropriate".to_string()));
+        }
+        
+        let status = if appropriate { "appropriate" } else { "inappropriate" };
+        crate::audit::log_emergency_access(
+            conn,
+            crate::models::AuditEventType::EmergencyAccessReviewed,
+            crate::models::AuditResourceType::Client,
+            &session.client_id,
+            if appropriate { crate::models::AuditOutcome::Success } else { crate::models::AuditOutcome::Failure },
+            session_id,
+            note.map(|n| crypto::hash_sha256(n.as_bytes())).as_deref(),
+        ).map_err(|e| VaultError::Internal(format!("Audit write failed: {}", e)))?;
+        
+        conn.execute(
+            "UPDATE emergency_access_sessions
+             SET review_status = ?1, reviewed_by = ?2, reviewed_at = ?3, review_note = ?4
+             WHERE id = ?5",
+            params![status, reviewer, chrono::Utc::now().timestamp_millis(), note, session_id],
+        )?;
+        self.get_emergency_access_session(session_id)
+    }
+    
+    // ============================================
+    // Export Presence Checks
+    // ============================================
+    
+    /// Per-vault salt for audit path hashes, created on first use
+    fn path_hash_salt(&self) -> Result<Vec<u8>, VaultError> {
+        let conn = self.conn()?;
+        let existing: Option<String> = conn.query_row(
+            "SELECT value FROM settings WHERE key = 'audit_path_salt'",
+            [],
+            |row| row.get(0),
+        ).optional()?;
+        
+        if let Some(salt) = existing.and_then(|s| hex::decode(s).ok()) {
+            return Ok(salt);
+        }
+        let salt = crypto::generate_salt().to_vec();
+        conn.execute(
+            "INSERT OR REPLACE INTO settings (key, value) VALUES ('audit_path_salt', ?1)",
+            [hex::encode(&salt)],
+        )?;
+        Ok(salt)
+    }
+    
+    /// Per-vault salt for hashed client lookups. Support tooling needs it to
+    /// compute lookup hashes; it is kept apart from the path-hash salt so
+    /// handing it out does not expose audit path hashes.
+    pub fn client_lookup_salt(&self) -> Result<String, VaultError> {
+        let conn = self.conn()?;
+        let existing: Option<String> = conn.query_row(
+            "SELECT value FROM settings WHERE key = 'client_lookup_salt'",
+            [],
+            |row| row.get(0),
+        ).optional()?;
+        
+        if let Some(salt) = existing {
+            return Ok(salt);
+        }
+        let salt = hex::encode(crypto::generate_salt());
+        conn.execute(
+            "INSERT OR REPLACE INTO settings (key, value) VALUES ('client_lookup_salt', ?1)",
+            [&salt],
+        )?;
+        Ok(salt)
+    }
+    
+    /// Does any client have an identifier hashing to `identifier_hash`?
+    /// Answers existence/count only and audits the lookup.
+    pub fn lookup_client_by_hash(
+        &self,
+        kind: crate::models::ClientIdentifierKind,
+        identifier_hash: &str,
+    ) -> Result<crate::models::ClientHashLookupResult, VaultError> {
+        let identifier_hash = identifier_hash.trim().to_lowercase();
+        if identifier_hash.len() != 64 || !identifier_hash.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(VaultError::InvalidState("Lookup hash must be 64 hex characters (SHA-256)".to_string()));
+        }
+        
+        let salt = hex::decode(self.client_lookup_salt()?)
+            .map_err(|e| VaultError::Internal(e.to_string()))?;
+        let match_count = self.list_clients()?
+            .iter()
+            .filter_map(|client| kind.value_of(client))
+            .filter(|value| crypto::digests_match(&crypto::hash_client_identifier(&salt, kind, value), &identifier_hash))
+            .count();
+        
+        let conn = self.conn()?;
+        crate::audit::log_client_hash_lookup(conn, kind.as_str(), match_count > 0)
+            .map_err(|e| VaultError::Internal(e.to_string()))?;
+        
+        Ok(crate::models::ClientHashLookupResult {
+            kind,
+            exists: match_count > 0,
+            match_count,
+        })
+    }
+    
+    /// Audit an export to `destination`. Overridden exports to removable or
+    /// cloud-synced locations can schedule a presence check `check_after_days` later.
+    pub fn record_export_destination(
+        &self,
+        resource_id: &str,
+        destination: &str,
+        path_class: &str,
+        overridden: bool,
+        check_after_days: Option<u32>,
+    ) -> Result<Option<ExportPresenceCheck>, VaultError> {
+        let conn = self.conn()?;
+        let path_hash = crypto::hash_path(destination, &self.path_hash_salt()?);
+        
+        let entry = crate::audit::log_export_event(conn, resource_id, crate::models::AuditOutcome::Success, path_class, &path_hash)
+            .map_err(|e| VaultError::Internal(format!("Audit write failed: {}", e)))?;
+        
+        let monitored = matches!(path_class, "removable" | "cloud_sync");
+        let Some(days) = check_after_days.filter(|_| overridden && monitored) else {
+            return Ok(None);
+        };
+        
+        let now = chrono::Utc::now().timestamp_millis();
+        let check = ExportPresenceCheck {
+            id: crate::ids::new_id(),
+            resource_id: resource_id.to_string(),
+            export_audit_entry_id: entry.id,
+            path_class: path_class.to_string(),
+            path_hash,
+            exported_at: now,
+            due_at: now + days as i64 * 86_400_000,
+            status: "scheduled".to_string(),
+            checked_at: None,
+            check_audit_entry_id: None,
+        };
+        
+        conn.execute(
+            "INSERT INTO export_presence_checks
+             (id, resource_id, export_audit_entry_id, path_class, path_hash, destination, exported_at, due_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![&check.id, &check.resource_id, &check.export_audit_entry_id, &check.path_class,
+                    &check.path_hash, destination, check.exported_at, check.due_at],
+        )?;
+        
+        Ok(Some(check))
+    }
+    
+    /// Run every scheduled check that is due: look for the file and log
+    /// the finding. Returns the checks that ran.
+    pub fn run_due_presence_checks(&self) -> Result<Vec<ExportPresenceCheck>, VaultError> {
+        let conn = self.conn()?;
+        let now = chrono::Utc::now().timestamp_millis();
+        
+        let due: Vec<(ExportPresenceCheck, String)> = {
+            let mut stmt = conn.prepare(
+                "SELECT id, resource_id, export_audit_entry_id, path_class, path_hash,
+                        exported_at, due_at, status, checked_at, check_audit_entry_id, destination
+                 FROM export_presence_checks
+                 WHERE status = 'scheduled' AND due_at <= ?1
+                 ORDER BY due_at"
+            )?;
+            let rows = stmt.query_map([now], |row| Ok((Self::map_presence_row(row)?, row.get(10)?)))?;
+            rows.collect::<Result<Vec<_>, _>>()?
+        };
+        
+        let mut completed = Vec::new();
+        for (mut check, destination) in due {
+            let present = std::path::Path::new(&destination).exists();
+            let entry = crate::audit::log_export_presence(conn, &check.resource_id, present, &check.path_hash)
+                .map_err(|e| VaultError::Internal(format!("Audit write failed: {}", e)))?;
+            
+            check.status = if present { "present" } else { "absent" }.to_string();
+            check.checked_at = Some(now);
+            check.check_audit_entry_id = Some(entry.id);
+            
+            conn.execute(
+                "UPDATE export_presence_checks SET status = ?1, checked_at = ?2, check_audit_entry_id = ?3 WHERE id = ?4",
+                params![&check.status, now, &check.check_audit_entry_id, &check.id],
+            )?;
+            completed.push(check);
+        }
+        
+        Ok(completed)
+    }
+    
+    /// All presence checks, newest export first
+    pub fn list_export_presence_checks(&self) -> Result<Vec<ExportPresenceCheck>, VaultError> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, resource_id, export_audit_entry_id, path_class, path_hash,
+                    exported_at, due_at, status, checked_at, check_audit_entry_id
+             FROM export_presence_checks ORDER BY exported_at DESC"
+        )?;
+        let rows = stmt.query_map([], Self::map_presence_row)?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(VaultError::from)
+    }
+    
+    fn map_presence_row(row: &rusqlite::Row) -> rusqlite::Result<ExportPresenceCheck> {
+        Ok(ExportPresenceCheck {
+            id: row.get(0)?,
+            resource_id: row.get(1)?,
+            export_audit_entry_id: row.get(2)?,
+            path_class: row.get(3)?,
+            path_hash: row.get(4)?,
+            exported_at: row.get(5)?,
+            due_at: row.get(6)?,
+            status: row.get(7)?,
+            checked_at: row.get(8)?,
+            check_audit_entry_id: row.get(9)?,
+        })
+    }
+    
+    // ============================================
+    // Treatment Progress Analysis
+    // ============================================
+    
+    /// Analyze treatment progress for a client based on their session notes
+    pub fn get_treatment_progress(&self, client_id: &str) -> Result<TreatmentProgress, VaultError> {
+        let conn = self.conn()?;
+        let client = self.get_client(client_id)?;
+        
+        // Get all notes for client ordered by date (include ID for theme linking)
+        let mut stmt = conn.prepare(
+            "SELECT id, session_date, raw_input, created_at FROM notes 
+             WHERE client_id = ?1 
+             ORDER BY session_date ASC"
+        )?;
+        
+        let notes: Vec<(String, String, String, i64)> = stmt.query_map(params![client_id], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+        })?.filter_map(|r| r.ok()).collect();
+        
+        if notes.is_empty() {
+            return Ok(TreatmentProgress {
+                client_id: client_id.to_string(),
+                client_name: client.display_name,
+                total_sessions: 0,
+                date_range: None,
+                session_frequency: None,
+                themes: vec![],
+                risk_trajectory: Some("insufficient_data".to_string()),
+                ai_summary: None,
+            });
+        }
+        
+        let total_sessions = notes.len() as i32;
+        let first_date = notes.first().map(|(_, d, _, _)| d.clone());
+        let last_date = notes.last().map(|(_, d, _, _)| d.clone());
+        
+        // Calculate session frequency (average days between sessions)
+        let session_frequency = if notes.len() > 1 {
+            if let (Some(first), Some(last)) = (&first_date, &last_date) {
+                if let (Ok(first_dt), Ok(last_dt)) = (
+                    chrono::NaiveDate::parse_from_str(first, "%Y-%m-%d"),
+                    chrono::NaiveDate::parse_from_str(last, "%Y-%m-%d")
+                ) {
+                    let days = (last_dt - first_dt).num_days() as f64;
+                    Some(days / (notes.len() - 1) as f64)
+                } else {
+                    None
+                }
+            } else {
+                None
+            }
+        } else {
+            None
+        };
+        
+        // Analyze themes from note content (with note IDs)
+        let themes = self.extract_treatment_themes(&notes);
+        
+        // Determine risk trajectory based on note content patterns
+        let notes_for_risk: Vec<(String, String, i64)> = notes.iter()
+            .map(|(_, date, content, ts)| (date.clone(), content.clone(), *ts))
+            .collect();
+        let risk_trajectory = self.analyze_risk_trajectory(&notes_for_risk);
+        
+        Ok(TreatmentProgress {
+            client_id: client_id.to_string(),
+            client_name: client.display_name,
+            total_sessions,
+            date_range: first_date.zip(last_date),
+            session_frequency,
+            themes,
+            risk_trajectory: Some(risk_trajectory),
+            ai_summary: None, // Will be populated by AI call if requested
+        })
+    }
+    
+    /// Extract treatment themes from session notes (with note ID tracking)
+    fn extract_treatment_themes(&self, notes: &[(String, String, String, i64)]) -> Vec<ProgressTheme> {
+        // HashMap: theme -> (first_date, count, dates, note_ids)
+        let mut themes: std::collections::HashMap<String, (String, i32, Vec<String>, Vec<String>)> = std::collections::HashMap::new();
+        
+        // Common clinical themes to track
+        let theme_keywords = [
+            ("anxiety", vec!["anxiety", "anxious", "worry", "panic", "nervous"]),
+            ("depression", vec!["depression", "depressed", "sad", "hopeless", "low mood"]),
+            ("sleep", vec!["sleep", "insomnia", "tired", "fatigue", "rest"]),
+            ("relationships", vec!["relationship", "family", "partner", "spouse", "conflict"]),
+            ("work_stress", vec!["work", "job", "career", "boss", "coworker"]),
+            ("trauma", vec!["trauma", "ptsd", "flashback", "nightmare", "abuse"]),
+            ("suicidal_ideation", vec!["si", "suicidal", "suicide", "self-harm", "kill myself", "end my life"]),
+            ("substance_use", vec!["alcohol", "drinking", "drug", "substance", "using"]),
+            ("coping", vec!["coping", "skills", "breathing", "grounding", "mindfulness"]),
+            ("medication", vec!["medication", "med", "prescription", "dose", "side effect"]),
+        ];
+        
+        for (note_id, date, content, _) in notes {
+            let content_lower = content.to_lowercase();
+            
+            for (theme_name, keywords) in &theme_keywords {
+                let mentioned = keywords.iter().any(|kw| content_lower.contains(kw));
+                if mentioned {
+                    let entry = themes.entry(theme_name.to_string())
+                        .or_insert_with(|| (date.clone(), 0, vec![], vec![]));
+                    entry.1 += 1;
+                    entry.2.push(date.clone());
+                    // Add note_id if not already in list
+                    if !entry.3.contains(note_id) {
+                        entry.3.push(note_id.clone());
+                    }
+                }
+            }
+        }
+        
+        // Convert to ProgressTheme with trend analysis and note IDs
+        themes.into_iter().map(|(theme, (first_date, count, dates, note_ids))| {
+            let trend = self.calculate_theme_trend(&dates, notes.len());
+            ProgressTheme {
+                theme,
+                first_mentioned: first_date,
+                mention_count: count,
+                trend,
+                note_ids,
+            }
+        }).collect()
+    }
+    
+    /// Calculate trend for a theme based on when it appears in session sequence
+    fn calculate_theme_trend(&self, dates: &[String], total_sessions: usize) -> String {
+        if dates.len() < 2 {
+            return "insufficient_data".to_string();
+        }
+        
+        // Simple trend: compare first half vs second half mentions
+        let midpoint = total_sessions / 2;
+        let early_mentions = dates.iter().filter(|d| {
+            // Rough comparison - actual implementation would use proper date parsing
+            dates.iter().position(|x| x == *d).unwrap_or(0) < midpoint
+        }).count();
+        let late_mentions = dates.len() - early_mentions;
+        
+        if late_mentions == 0 && early_mentions > 0 {
+            "resolved".to_string()
+        } else if late_mentions > early_mentions * 2 {
+            "worsening".to_string()
+        } else if early_mentions > late_mentions * 2 {
+            "improving".to_string()
+        } else {
+            "stable".to_string()
+        }
+    }
+    
+    /// Analyze overall risk trajectory
+    fn analyze_risk_trajectory(&self, notes: &[(String, String, i64)]) -> String {
+        if notes.len() < 3 {
+            return "insufficient_data".to_string();
+        }
+        
+        let risk_keywords = ["suicidal", "si", "self-harm", "kill", "end my life", "hopeless", "worthless"];
+        
+        // Count risk mentions in first third vs last third
+        let third = notes.len() / 3;
+        let early_notes = &notes[..third];
+        let late_notes = &notes[notes.len() - third..];
+        
+        let early_risk = early_notes.iter()
+            .filter(|(_, content, _)| {
+                let lower = content.to_lowercase();
+                risk_keywords.iter().any(|kw| lower.contains(kw))
+            })
+            .count();
+            
+        let late_risk = late_notes.iter()
+            .filter(|(_, content, _)| {
+                let lower = content.to_lowercase();
+                risk_keywords.iter().any(|kw| lower.contains(kw))
+            })
+            .count();
+        
+        if late_risk == 0 && early_risk > 0 {
+            "improving".to_string()
+        } else if late_risk > early_risk {
+            "concerning".to_string()
+        } else if early_risk > late_risk {
+            "improving".to_string()
+        } else {
+            "stable".to_string()
+        }
+    }
+    
+    // ============================================
+    // Metrics
+    // ============================================
+    
+    pub fn get_counts(&self) -> Result<(i32, i32), VaultError> {
+        let snapshot = self.get_dashboard_snapshot()?;
+        Ok((snapshot.client_count as i32, snapshot.note_count as i32))
+    }
+    
+    /// All dashboard aggregates in one read of the trigger-maintained counters
+    pub fn get_dashboard_snapshot(&self) -> Result<DashboardSnapshot, VaultError> {
+        let conn = self.conn()?;
+        
+        let snapshot = conn.query_row(
+            "SELECT clients, notes, draft_notes, signed_notes, amended_notes,
+                    documents, document_bytes, pending_reviews
+             FROM dashboard_counts",
+            [],
+            |row| Ok(DashboardSnapshot {
+                client_count: row.get(0)?,
+                note_count: row.get(1)?,
+                draft_note_count: row.get(2)?,
+                signed_note_count: row.get(3)?,
+                amended_note_count: row.get(4)?,
+                document_count: row.get(5)?,
+                document_size_bytes: row.get(6)?,
+                pending_review_count: row.get(7)?,
+                generated_at: chrono::Utc::now().timestamp_millis(),
+            }),
+        )?;
+        
+        Ok(snapshot)
+    }
+    
+    /// Counts and sizes of what a per-client export would contain
+    pub fn get_client_export_scope(
+        &self,
+        client_id: &str,
+        options: &crate::export::ClientExportOptions,
+    ) -> Result<ClientExportScope, VaultError> {
+        let conn = self.conn()?;
+        let from = options.date_from.as_deref().unwrap_or("");
+        let to = options.date_to.as_deref().unwrap_or("9999-12-31");
+        let mut scope = ClientExportScope::default();
+        
+        if options.include_notes {
+            let (notes, drafts, bytes): (i64, i64, i64) = conn.query_row(
+                "SELECT COUNT(*),
+                        COALESCE(SUM(status = 'draft'), 0),
+                        COALESCE(SUM(LENGTH(CAST(raw_input AS BLOB)) + COALESCE(LENGTH(CAST(structured_note AS BLOB)), 0)), 0)
+                 FROM notes
+                 WHERE client_id = ?1 AND session_date >= ?2 AND session_date <= ?3
+                   AND (?4 = 0 OR status != 'draft')",
+                rusqlite::params![client_id, from, to, options.signed_only as i32],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )?;
+            scope.note_count = notes;
+            scope.draft_note_count = drafts;
+            scope.note_bytes = bytes;
+        }
+        
+        if options.include_documents {
+            let (documents, bytes): (i64, i64) = conn.query_row(
+                "SELECT COUNT(*), COALESCE(SUM(file_size), 0)
+                 FROM client_documents
+                 WHERE client_id = ?1
+                   AND (document_date IS NULL OR (document_date >= ?2 AND document_date <= ?3))",
+                rusqlite::params![client_id, from, to],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )?;
+            scope.document_count = documents;
+            scope.document_bytes = bytes;
+        }
+        
+        if options.include_audit_trail {
+            scope.audit_event_count = conn.query_row(
+                "SELECT COUNT(*) FROM audit_log
+                 WHERE resource_id = ?1
+                    OR resource_id IN (SELECT id FROM notes WHERE client_id = ?1)",
+                [client_id],
+                |row| row.get(0),
+            )?;
+        }
+        
+        Ok(scope)
+    }
+    
+    // ============================================
+    // Session Metrics (Time Tracking)
+    // ============================================
+    
+    /// Record a session timing metric
+    pub fn record_session_metric(
+        &self,
+        note_id: &str,
+        client_id: &str,
+        start_time: i64,
+        end_time: i64,
+        method: &str,
+        word_count: i32,
+        ai_assisted: bool,
+        timed: Option<(i64, i64)>,
+    ) -> Result<String, VaultError> {
+        let conn = self.conn()?;
+        let id = crate::ids::new_id();
+        let now = chrono::Utc::now().timestamp();
+        let (active_seconds, idle_seconds) = timed.unzip();
+        
+        conn.execute(
+            "INSERT INTO session_metrics (id, note_id, client_id, start_time, end_time, method, word_count, ai_assisted, created_at,
+                                          active_seconds, idle_seconds)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            rusqlite::params![
+                id, note_id, client_id, start_time, end_time, method, word_count, ai_assisted as i32, now,
+                active_seconds, idle_seconds
+            ],
+        )?;
+        
+        Ok(id)
+    }
+    
+    /// Get session metrics for a time period
+    pub fn get_session_metrics(&self, since_timestamp: i64) -> Result<Vec<SessionMetricRow>, VaultError> {
+        let conn = self.conn()?;
+        
+        let mut stmt = conn.prepare(
+            "SELECT id, note_id, client_id, start_time, end_time, method, word_count, ai_assisted, created_at, active_seconds
+             FROM session_metrics
+             WHERE start_time >= ?1
+             ORDER BY start_time DESC"
+        )?;
+        
+        let rows = stmt.query_map([since_timestamp], |row| {
+            Ok(SessionMetricRow {
+                id: row.get(0)?,
+                note_id: row.get(1)?,
+                client_id: row.get(2)?,
+                start_time: row.get(3)?,
+                end_time: row.get(4)?,
+                method: row.get(5)?,
+                word_count: row.get(6)?,
+                ai_assisted: row.get::<_, i32>(7)? != 0,
+                created_at: row.get(8)?,
+                active_seconds: row.get(9)?,
+            })
+        })?;
+        
+        rows.collect::<Result<Vec<_>, _>>().map_err(VaultError::from)
+    }
+    
+    /// Get aggregated metrics summary
+    pub fn get_metrics_summary(&self, since_timestamp: i64) -> Result<MetricsSummary, VaultError> {
+        let conn = self.conn()?;
+        
+        let (total_notes, total_time, voice_count, typed_count, ai_assisted_count): (i32, i64, i32, i32, i32) = conn.query_row(
+            "SELECT 
+                COUNT(*) as total_notes,
+                COALESCE(SUM(COALESCE(active_seconds, end_time - start_time)), 0) as total_time,
+                COALESCE(SUM(CASE WHEN method = 'voice' THEN 1 ELSE 0 END), 0) as voice_count,
+                COALESCE(SUM(CASE WHEN method = 'typed' THEN 1 ELSE 0 END), 0) as typed_count,
+                COALESCE(SUM(ai_assisted), 0) as ai_assisted_count
+             FROM session_metrics
+             WHERE start_time >= ?1",
+            [since_timestamp],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?)),
+        )?;
+        
+        let avg_time = if total_notes > 0 { total_time as f64 / total_notes as f64 } else { 0.0 };
+        
+        // Industry benchmark is 15 minutes (900 seconds) per note
+        let benchmark_seconds = 900_i64;
+        let time_saved = (total_notes as i64 * benchmark_seconds) - total_time;
+        
+        Ok(MetricsSummary {
+            total_notes: total_notes as u32,
+            total_time_seconds: total_time as u64,
+            avg_time_seconds: avg_time,
+            voice_count: voice_count as u32,
+            typed_count: typed_count as u32,
+            ai_assisted_count: ai_assisted_count as u32,
+            estimated_time_saved_seconds: time_saved,
+        })
+    }
+    
+    // ============================================
+    // Document Management
+    // ============================================
+    
+    /// Upload a document for a client
+    pub fn upload_document(
+        &self,
+        client_id: &str,
+        filename: &str,
+        file_type: &str,
+        mime_type: &str,
+        data: &[u8],
+        description: Option<&str>,
+        document_date: Option<&str>,
+    ) -> Result<ClientDocument, VaultError> {
+        let conn = self.conn()?;
+        
+        // Generate ID and hash
+        let id = crate::ids::new_id();
+        use sha2::{Sha256, Digest};
+        let content_hash = format!("{:x}", Sha256::digest(data));
+        let now = chrono::Utc::now().timestamp();
+        let file_size = data.len() as i64;
+        
+        // Store encrypted data (SQLCipher handles encryption)
+        conn.execute(
+            "INSERT INTO client_documents 
+             (id, client_id, filename, file_type, mime_type, file_size, content_hash, encrypted_data, ocr_text, description, document_date, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+            rusqlite::params![
+                id, client_id, filename, file_type, mime_type, file_size, content_hash, 
+                data, None::<String>, description, document_date, now, now
+            ],
+        )?;
+        
+        Ok(ClientDocument {
+            id,
+            client_id: client_id.to_string(),
+            filename: filename.to_string(),
+            file_type: file_type.to_string(),
+            mime_type: mime_type.to_string(),
+            file_size,
+            content_hash,
+            ocr_text: None,
+            description: description.map(|s| s.to_string()),
+            document_date: document_date.map(|s| s.to_string()),
+            created_at: now,
+            updated_at: now,
+            ocr_confidence: None,
+            ocr_language: None,
+            ocr_profile: None,
+        })
+    }
+    
+    /// Get documents for a client
+    pub fn list_documents(&self, client_id: &str) -> Result<Vec<ClientDocument>, VaultError> {
+        let conn = self.conn()?;
+        
+        let mut stmt = conn.prepare(
+            "SELECT id, client_id, filename, file_type, mime_type, file_size, content_hash, 
+                    ocr_text, description, document_date, created_at, updated_at,
+                    ocr_confidence, ocr_language, ocr_profile
+             FROM client_documents
+             WHERE client_id = ?1
+             ORDER BY created_at DESC"
+        )?;
+        
+        let rows = stmt.query_map([client_id], |row| {
+            Ok(ClientDocument {
+                id: row.get(0)?,
+                client_id: row.get(1)?,
+                filename: row.get(2)?,
+                file_type: row.get(3)?,
+                mime_type: row.get(4)?,
+                file_size: row.get(5)?,
+                content_hash: row.get(6)?,
+                ocr_text: row.get(7)?,
+                description: row.get(8)?,
+                document_date: row.get(9)?,
+                created_at: row.get(10)?,
+                updated_at: row.get(11)?,
+                ocr_confidence: row.get(12)?,
+                ocr_language: row.get(13)?,
+                ocr_profile: row.get(14)?,
+            })
+        })?;
+        
+        rows.collect::<Result<Vec<_>, _>>().map_err(VaultError::from)
+    }
+    
+    /// Get document data
+    /// Client a document is filed under
+    pub fn get_document_client_id(&self, document_id: &str) -> Result<String, VaultError> {
+        let conn = self.conn()?;
+        
+        conn.query_row(
+            "SELECT client_id FROM client_documents WHERE id = ?1",
+            [document_id],
+            |row| row.get(0),
+        ).optional()?
+        .ok_or_else(|| VaultError::NotFound(format!("Document {}", document_id)))
+    }
+    
+    pub fn get_document_data(&self, document_id: &str) -> Result<Vec<u8>, VaultError> {
+        let conn = self.conn()?;
+        
+        let data: Vec<u8> = conn.query_row(
+            "SELECT encrypted_data FROM client_documents WHERE id = ?1",
+            [document_id],
+            |row| row.get(0),
+        )?;
+        
+        Ok(data)
+    }
+    
+    /// Update document OCR text (manual entry or correction: no confidence)
+    pub fn update_document_ocr(&self, document_id: &str, ocr_text: &str) -> Result<(), VaultError> {
+        let conn = self.conn()?;
+        let now = chrono::Utc::now().timestamp();
+        
+        conn.execute(
+            "UPDATE client_documents SET ocr_text = ?1, updated_at = ?2,
+                    ocr_confidence = NULL, ocr_language = NULL, ocr_profile = 'manual'
+             WHERE id = ?3",
+            rusqlite::params![ocr_text, now, document_id],
+        )?;
+        
+        Ok(())
+    }
+    
+    /// Store a Tesseract run's text with its confidence, language and profile
+    pub fn record_document_ocr(&self, document_id: &str, result: &crate::ocr::OcrResult) -> Result<(), VaultError> {
+        let conn = self.conn()?;
+        let now = chrono::Utc::now().timestamp();
+        
+        let updated = conn.execute(
+            "UPDATE client_documents SET ocr_text = ?1, updated_at = ?2,
+                    ocr_confidence = ?3, ocr_language = ?4, ocr_profile = ?5
+             WHERE id = ?6",
+            rusqlite::params![
+                &result.text, now, result.mean_confidence, &result.language,
+                result.profile.as_str(), document_id
+            ],
+        )?;
+        if updated == 0 {
+            return Err(VaultError::NotFound(format!("Document {}", document_id)));
+        }
+        Ok(())
+    }
+    
+    /// Delete a document
+    pub fn delete_document(&self, document_id: &str) -> Result<(), VaultError> {
+        let conn = self.conn()?;
+        let client_id: Option<String> = conn.query_row(
+            "SELECT client_id FROM client_documents WHERE id = ?1",
+            [document_id],
+            |row| row.get(0),
+        ).optional()?;
+        if let Some(client_id) = client_id {
+            self.ensure_not_on_legal_hold(&client_id)?;
+        }
+        conn.execute("DELETE FROM client_documents WHERE id = ?1", [document_id])?;
+        Ok(())
+    }
+    
+    // ============================================
+    // Licensure
+    // ============================================
+    
+    pub fn add_clinician_license(
+        &self,
+        kind: crate::licensure::LicenseKind,
+        jurisdiction: Option<&str>,
+        license_number: &str,
+        expires_on: Option<&str>,
+    ) -> Result<crate::licensure::ClinicianLicense, VaultError> {
+        let conn = self.conn()?;
+        let jurisdiction = match kind {
+            crate::licensure::LicenseKind::StateLicense => Some(crate::licensure::normalize_jurisdiction(
+                jurisdiction.ok_or_else(|| VaultError::InvalidState("State license requires a jurisdiction".to_string()))?,
+            )),
+            crate::licensure::LicenseKind::PsypactApit => None,
+        };
+        if let Some(date) = expires_on {
+            chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                .map_err(|_| VaultError::InvalidState(format!("Invalid expiry date: {}", date)))?;
+        }
+        
+        let license = crate::licensure::ClinicianLicense {
+            id: crate::ids::new_id(),
+            kind,
+            jurisdiction,
+            license_number: license_number.trim().to_string(),
+            expires_on: expires_on.map(String::from),
+            created_at: chrono::Utc::now().timestamp(),
+        };
+        conn.execute(
+            "INSERT INTO clinician_licenses (id, kind, jurisdiction, license_number, expires_on, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![&license.id, license.kind.as_str(), &license.jurisdiction,
+                    &license.license_number, &license.expires_on, license.created_at],
+        )?;
+        Ok(license)
+    }
+    
+    pub fn list_clinician_licenses(&self) -> Result<Vec<crate::licensure::ClinicianLicense>, VaultError> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, kind, jurisdiction, license_number, expires_on, created_at
+             FROM clinician_licenses ORDER BY kind, jurisdiction"
+        )?;
+        let rows = stmt.query_map([], |row| {
+            let kind: String = row.get(1)?;
+            Ok(crate::licensure::ClinicianLicense {
+                id: row.get(0)?,
+                kind: crate::licensure::LicenseKind::from_name(&kind),
+                jurisdiction: row.get(2)?,
+                license_number: row.get(3)?,
+                expires_on: row.get(4)?,
+                created_at: row.get(5)?,
+            })
+        })?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(VaultError::from)
+    }
+    
+    pub fn remove_clinician_license(&self, id: &str) -> Result<bool, VaultError> {
+        let conn = self.conn()?;
+        let removed = conn.execute("DELETE FROM clinician_licenses WHERE id = ?1", [id])?;
+        Ok(removed > 0)
+    }
+    
+    /// Check a telehealth note's patient location and store the result.
+    /// Re-recording replaces the previous check, including any attestation.
+    pub fn record_telehealth_location(
+        &self,
+        note_id: &str,
+        patient_location: &str,
+        policy: &crate::policy::LicensurePolicy,
+    ) -> Result<crate::licensure::LicensureCheck, VaultError> {
+        let note = self.get_note(note_id)?;
+        if note.status == NoteStatus::Signed {
+            return Err(VaultError::InvalidState("Cannot change the location of a signed note - amend instead".to_string()));
+        }
+        if patient_location.trim().is_empty() {
+            return Err(VaultError::InvalidState("Patient location is required".to_string()));
+        }
+        
+        let licenses = self.list_clinician_licenses()?;
+        let check = crate::licensure::check_location(note_id, patient_location, &note.session_date, &licenses, policy);
+        self.save_licensure_check(&check)?;
+        Ok(check)
+    }
+    
+    /// Record the clinician's attestation on an out-of-jurisdiction check
+    pub fn attest_licensure_check(&self, note_id: &str, statement: &str) -> Result<crate::licensure::LicensureCheck, VaultError> {
+        let mut check = self.get_licensure_check(note_id)?
+            .ok_or_else(|| VaultError::NotFound(format!("Licensure check for note {}", note_id)))?;
+        if check.status != crate::licensure::LicensureStatus::OutOfJurisdiction {
+            return Err(VaultError::InvalidState("Patient location is already covered".to_string()));
+        }
+        if statement.trim().is_empty() {
+            return Err(VaultError::InvalidState("Attestation statement is required".to_string()));
+        }
+        check.attestation = Some(statement.trim().to_string());
+        check.attested_at = Some(chrono::Utc::now().timestamp());
+        self.save_licensure_check(&check)?;
+        Ok(check)
+    }
+    
+    fn save_licensure_check(&self, check: &crate::licensure::LicensureCheck) -> Result<(), VaultError> {
+        let conn = self.conn()?;
+        let check_json = serde_json::to_string(check)
+            .map_err(|e| VaultError::Serialization(e.to_string()))?;
+        conn.execute(
+            "INSERT OR REPLACE INTO note_licensure_checks (note_id, check_json, checked_at) VALUES (?1, ?2, ?3)",
+            params![&check.note_id, check_json, check.checked_at],
+        )?;
+        
+        crate::audit::log_event(
+            conn,
+            crate::models::AuditEventType::NoteUpdated,
+            crate::models::AuditResourceType::Note,
+            &check.note_id,
+            crate::models::AuditOutcome::Success,
+            None,
+        ).ok();
+        Ok(())
+    }
+    
+    pub fn get_licensure_check(&self, note_id: &str) -> Result<Option<crate::licensure::LicensureCheck>, VaultError> {
+        let conn = self.conn()?;
+        let check_json: Option<String> = conn.query_row(
+            "SELECT check_json FROM note_licensure_checks WHERE note_id = ?1",
+            [note_id],
+            |row| row.get(0),
+        ).optional()?;
+        check_json
+            .map(|json| serde_json::from_str(&json).map_err(|e| VaultError::Serialization(e.to_string())))
+            .transpose()
+    }
+    
+    // ============================================
+    // Client Photo
+    // ============================================
+    
+    /// Set (or replace) the client's photo. The upload is normalized to a
+    /// downscaled PNG, which also strips EXIF.
+    pub fn set_client_photo(&self, client_id: &str, data: &[u8]) -> Result<crate::photo::ClientPhoto, VaultError> {
+        let conn = self.conn()?;
+        self.get_client(client_id)?;
+        
+        let (png, width, height) = crate::photo::normalize_photo(data).map_err(VaultError::InvalidState)?;
+        let now = chrono::Utc::now().timestamp();
+        let created_at: i64 = conn.query_row(
+            "SELECT created_at FROM client_photos WHERE client_id = ?1",
+            [client_id],
+            |row| row.get(0),
+        ).optional()?.unwrap_or(now);
+        
+        let photo = crate::photo::ClientPhoto {
+            client_id: client_id.to_string(),
+            mime_type: "image/png".to_string(),
+            width,
+            height,
+            file_size: png.len() as i64,
+            content_hash: crypto::hash_sha256(&png),
+            created_at,
+            updated_at: now,
+        };
+        
+        conn.execute(
+            "INSERT OR REPLACE INTO client_photos
+             (client_id, mime_type, width, height, file_size, content_hash, encrypted_data, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![&photo.client_id, &photo.mime_type, photo.width, photo.height, photo.file_size,
+                    &photo.content_hash, png, photo.created_at, photo.updated_at],
+        )?;
+        
+        Ok(photo)
+    }
+    
+    pub fn get_client_photo_info(&self, client_id: &str) -> Result<Option<crate::photo::ClientPhoto>, VaultError> {
+        let conn = self.conn()?;
+        conn.query_row(
+            "SELECT client_id, mime_type, width, height, file_size, content_hash, created_at, updated_at
+             FROM client_photos WHERE client_id = ?1",
+            [client_id],
+            |row| Ok(crate::photo::ClientPhoto {
+                client_id: row.get(0)?,
+                mime_type: row.get(1)?,
+                width: row.get(2)?,
+                height: row.get(3)?,
+                file_size: row.get(4)?,
+                content_hash: row.get(5)?,
+                created_at: row.get(6)?,
+                updated_at: row.get(7)?,
+            }),
+        ).optional().map_err(VaultError::from)
+    }
+    
+    /// Original photo bytes, for in-app display only. Export code must use
+    /// `get_client_photo_for_export`.
+    pub fn get_client_photo_for_display(&self, client_id: &str) -> Result<Option<Vec<u8>>, VaultError> {
+        let conn = self.conn()?;
+        conn.query_row(
+            "SELECT encrypted_data FROM client_photos WHERE client_id = ?1",
+            [client_id],
+            |row| row.get(0),
+        ).optional().map_err(VaultError::from)
+    }
+    
+    /// Photo as it may appear in an export: absent or blurred, never the original
+    pub fn get_client_photo_for_export(
+        &self,
+        client_id: &str,
+        mode: crate::photo::PhotoExportMode,
+    ) -> Result<Option<Vec<u8>>, VaultError> {
+        match self.get_client_photo_for_display(client_id)? {
+            Some(data) => crate::photo::photo_for_export(&data, mode).map_err(VaultError::Internal),
+            None => Ok(None),
+        }
+    }
+    
+    pub fn remove_client_photo(&self, client_id: &str) -> Result<bool, VaultError> {
+        let conn = self.conn()?;
+        let removed = conn.execute("DELETE FROM client_photos WHERE client_id = ?1", [client_id])?;
+        Ok(removed > 0)
+    }
+    
+    // ============================================
+    // Practice Profile
+    // ============================================
+    
+    pub fn get_practice_profile(&self) -> Result<Option<crate::branding::PracticeProfile>, VaultError> {
+        let conn = self.conn()?;
+        let row: Option<(String, bool)> = conn.query_row(
+            "SELECT profile_json, logo_jpeg IS NOT NULL FROM practice_profile WHERE id = 1",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        ).optional()?;
+        
+        match row {
+            Some((json, has_logo)) => {
+                let mut profile: crate::branding::PracticeProfile = serde_json::from_str(&json)
+                    .map_err(|e| VaultError::Serialization(e.to_string()))?;
+                profile.has_logo = has_logo;
+                Ok(Some(profile))
+            }
+            None => Ok(None),
+        }
+    }
+    
+    /// Save the practice profile; an existing logo is kept
+    pub fn set_practice_profile(&self, profile: &crate::branding::PracticeProfile) -> Result<crate::branding::PracticeProfile, VaultError> {
+        let conn = self.conn()?;
+        profile.validate().map_err(VaultError::InvalidState)?;
+        let json = serde_json::to_string(profile)
+            .map_err(|e| VaultError::Serialization(e.to_string()))?;
+        conn.execute(
+            "INSERT INTO practice_profile (id, profile_json, updated_at) VALUES (1, ?1, ?2)
+             ON CONFLICT(id) DO UPDATE SET profile_json = excluded.profile_json, updated_at = excluded.updated_at",
+            params![json, chrono::Utc::now().timestamp()],
+        )?;
+        self.get_practice_profile()?
+            .ok_or_else(|| VaultError::Internal("Practice profile not saved".to_string()))
+    }
+    
+    /// Store a logo for the letterhead. The profile must exist first.
+    pub fn set_practice_logo(&self, data: &[u8]) -> Result<(), VaultError> {
+        let conn = self.conn()?;
+        let logo = crate::branding::normalize_logo(data).map_err(VaultError::InvalidState)?;
+        let updated = conn.execute(
+            "UPDATE practice_profile SET logo_jpeg = ?1, logo_width = ?2, logo_height = ?3, updated_at = ?4 WHERE id = 1",
+            params![logo.jpeg, logo.width, logo.height, chrono::Utc::now().timestamp()],
+        )?;
+        if updated == 0 {
+            return Err(VaultError::InvalidState("Save the practice profile before adding a logo".to_string()));
+        }
+        Ok(())
+    }
+    
+    pub fn get_practice_logo(&self) -> Result<Option<crate::branding::PracticeLogo>, VaultError> {
+        let conn = self.conn()?;
+        conn.query_row(
+            "SELECT logo_jpeg, logo_width, logo_height FROM practice_profile WHERE id = 1 AND logo_jpeg IS NOT NULL",
+            [],
+            |row| Ok(crate::branding::PracticeLogo {
+                jpeg: row.get(0)?,
+                width: row.get(1)?,
+                height: row.get(2)?,
+            }),
+        ).optional().map_err(VaultError::from)
+    }
+    
+    pub fn remove_practice_logo(&self) -> Result<bool, VaultError> {
+        let conn = self.conn()?;
+        let removed = conn.execute(
+            "UPDATE practice_profile SET logo_jpeg = NULL, logo_width = NULL, logo_height = NULL WHERE id = 1 AND logo_jpeg IS NOT NULL",
+            [],
+        )?;
+        Ok(removed > 0)
+    }
+    
+    // ============================================
+    // External Audit Slices
+    // ============================================
+    
+    /// Ed25519 key identifying this install, created on first use
+    fn install_signing_key(&self) -> Result<crypto::signing::InstallSigningKey, VaultError> {
+        let conn = self.conn()?;
+        let existing: Option<String> = conn.query_row(
+            "SELECT value FROM settings WHERE key = 'install_signing_key'",
+            [],
+            |row| row.get(0),
+        ).optional()?;
+        
+        if let Some(secret) = existing {
+            let bytes: [u8; 32] = hex::decode(secret).ok()
+                .and_then(|b| b.try_into().ok())
+                .ok_or_else(|| VaultError::Internal("Stored install signing key is malformed".to_string()))?;
+            return Ok(crypto::signing::InstallSigningKey::from_secret_bytes(bytes));
+        }
+        let key = crypto::signing::InstallSigningKey::generate();
+        conn.execute(
+            "INSERT OR REPLACE INTO settings (key, value) VALUES ('install_signing_key', ?1)",
+            [hex::encode(key.secret_bytes())],
+        )?;
+        Ok(key)
+    }
+    
+    pub fn install_signing_identity(&self) -> Result<crate::audit_slice::InstallSigningIdentity, VaultError> {
+        let public_key = self.install_signing_key()?.public_key_base64();
+        Ok(crate::audit_slice::InstallSigningIdentity {
+            fingerprint: crypto::signing::fingerprint(&public_key)?,
+            public_key,
+        })
+    }
+    
+    /// Signed slice of one trainee's audit entries in [period_start,
+    /// period_end] (ms): events on the notes they submitted for review and
+    /// on those notes' charts. Everything else in the period goes out as
+    /// chain hashes only.
+    pub fn export_audit_slice(
+        &self,
+        trainee_id: &str,
+        period_start: i64,
+        period_end: i64,
+        purpose: &str,
+    ) -> Result<crate::audit_slice::AuditSlice, VaultError> {
+        use crate::models::{AuditEventType, AuditResourceType};
+        
+        let conn = self.conn()?;
+        let trainee_name: String = conn.query_row(
+            "SELECT name FROM trainees WHERE id = ?1",
+            [trainee_id],
+            |row| row.get(0),
+        ).optional()?
+            .ok_or_else(|| VaultError::NotFound(format!("Trainee {}", trainee_id)))?;
+        let mut stmt = conn.prepare(
+            "SELECT DISTINCT nr.note_id, n.client_id FROM note_reviews nr
+             JOIN notes n ON n.id = nr.note_id
+             WHERE nr.trainee_id = ?1"
+        )?;
+        let (note_ids, client_ids): (std::collections::HashSet<String>, std::collections::HashSet<String>) = stmt
+            .query_map([trainee_id], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .unzip();
+        
+        let run = crate::audit::get_entries_between(conn, period_start, period_end)
+            .map_err(|e| VaultError::Internal(e.to_string()))?;
+        let (entries, chain) = crate::audit_slice::redact_run(run, |e| match e.resource_type {
+            AuditResourceType::Note => note_ids.contains(&e.resource_id),
+            AuditResourceType::Client => client_ids.contains(&e.resource_id)
+                && !matches!(e.event_type, AuditEventType::ClientHashLookup),
+            _ => false,
+        });
+        if entries.is_empty() {
+            return Err(VaultError::NotFound(format!("Audit entries for {} in the requested period", trainee_name)));
+        }
+        
+        let key = self.install_signing_key()?;
+        let exporter_label = self.get_practice_profile()?
+            .map(|p| p.name)
+            .unwrap_or_else(|| "Evidify".to_string());
+        let slice = crate::audit_slice::AuditSlice {
+            format_version: crate::audit_slice::SLICE_FORMAT_VERSION,
+            slice_id: crate::ids::new_id(),
+            exported_at: chrono::Utc::now().timestamp_millis(),
+            exporter_label,
+            exporter_public_key: String::new(),
+            trainee_name,
+            purpose: purpose.to_string(),
+            period_start,
+            period_end,
+            entries,
+            chain,
+            signature: String::new(),
+        }.sign(&key).map_err(VaultError::Serialization)?;
+        
+        let fingerprint = crypto::signing::fingerprint(&slice.exporter_public_key)?;
+        crate::audit::log_audit_slice(conn, &slice.slice_id, false, true, &fingerprint)
+            .map_err(|e| VaultError::Internal(e.to_string()))?;
+        Ok(slice)
+    }
+    
+    /// Verify a slice from another install against the fingerprint the
+    /// supervisor was given for it, and keep it as attested evidence.
+    /// Rejected slices are audited and not stored.
+    pub fn import_audit_slice(
+        &self,
+        slice: &crate::audit_slice::AuditSlice,
+        expected_fingerprint: &str,
+    ) -> Result<crate::audit_slice::ExternalAuditRecord, VaultError> {
+        let conn = self.conn()?;
+        let report = crate::audit_slice::verify_slice(slice, expected_fingerprint);
+        crate::audit::log_audit_slice(conn, &slice.slice_id, true, report.verified(), &report.exporter_fingerprint)
+            .map_err(|e| VaultError::Internal(e.to_string()))?;
+        if !report.verified() {
+            return Err(VaultError::InvalidState(format!(
+                "Audit slice failed verification: {}", report.errors.join("; ")
+            )));
+        }
+        
+        let record = crate::audit_slice::ExternalAuditRecord {
+            id: crate::ids::new_id(),
+            slice_id: slice.slice_id.clone(),
+            exporter_label: slice.exporter_label.clone(),
+            exporter_fingerprint: report.exporter_fingerprint,
+            purpose: slice.purpose.clone(),
+            period_start: slice.period_start,
+            period_end: slice.period_end,
+            entry_count: slice.entries.len() as u32,
+            imported_at: chrono::Utc::now().timestamp(),
+        };
+        let slice_json = serde_json::to_string(slice)
+            .map_err(|e| VaultError::Serialization(e.to_string()))?;
+        let inserted = conn.execute(
+            "INSERT OR IGNORE INTO external_audit_slices
+             (id, slice_id, exporter_label, exporter_fingerprint, purpose, period_start, period_end, entry_count, slice_json, imported_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            params![
+                &record.id, &record.slice_id, &record.exporter_label, &record.exporter_fingerprint,
+                &record.purpose, record.period_start, record.period_end, record.entry_count,
+                slice_json, record.imported_at
+            ],
+        )?;
+        if inserted == 0 {
+            return Err(VaultError::InvalidState(format!("Audit slice {} was already imported", slice.slice_id)));
+        }
+        Ok(record)
+    }
+    
+    pub fn list_external_audit_slices(&self) -> Result<Vec<crate::audit_slice::ExternalAuditRecord>, VaultError> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, slice_id, exporter_label, exporter_fingerprint, purpose, period_start, period_end, entry_count, imported_at
+             FROM external_audit_slices ORDER BY imported_at DESC"
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok(crate::audit_slice::ExternalAuditRecord {
+                id: row.get(0)?,
+                slice_id: row.get(1)?,
+                exporter_label: row.get(2)?,
+                exporter_fingerprint: row.get(3)?,
+                purpose: row.get(4)?,
+                period_start: row.get(5)?,
+                period_end: row.get(6)?,
+                entry_count: row.get(7)?,
+                imported_at: row.get(8)?,
+            })
+        })?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(VaultError::from)
+    }
+    
+    pub fn get_external_audit_slice(&self, id: &str) -> Result<crate::audit_slice::AuditSlice, VaultError> {
+        let conn = self.conn()?;
+        let json: String = conn.query_row(
+            "SELECT slice_json FROM external_audit_slices WHERE id = ?1",
+            [id],
+            |row| row.get(0),
+        ).optional()?
+            .ok_or_else(|| VaultError::NotFound(format!("Audit slice {}", id)))?;
+        serde_json::from_str(&json).map_err(|e| VaultError::Serialization(e.to_string()))
+    }
+    
+    /// Search documents by OCR text
+    pub fn search_documents(&self, query: &str) -> Result<Vec<ClientDocument>, VaultError> {
+        let conn = self.conn()?;
+        let search_pattern = like_contains(&query.to_lowercase());
+        
+        let mut stmt = conn.prepare(
+            "SELECT id, client_id, filename, file_type, mime_type, file_size, content_hash, 
+                    ocr_text, description, document_date, created_at, updated_at,
+                    ocr_confidence, ocr_language, ocr_profile
+             FROM client_documents
+             WHERE LOWER(ocr_text) LIKE ?1 ESCAPE '\\'
+                OR LOWER(filename) LIKE ?1 ESCAPE '\\'
+                OR LOWER(description) LIKE ?1 ESCAPE '\\'
+             ORDER BY created_at DESC"
+        )?;
+        
+        let rows = stmt.query_map([&search_pattern], |row| {
+            Ok(ClientDocument {
+                id: row.get(0)?,
+                client_id: row.get(1)?,
+                filename: row.get(2)?,
+                file_type: row.get(3)?,
+                mime_type: row.get(4)?,
+                file_size: row.get(5)?,
+                content_hash: row.get(6)?,
+                ocr_text: row.get(7)?,
+                description: row.get(8)?,
+                document_date: row.get(9)?,
+                created_at: row.get(10)?,
+                updated_at: row.get(11)?,
+                ocr_confidence: row.get(12)?,
+                ocr_language: row.get(13)?,
+                ocr_profile: row.get(14)?,
+            })
+        })?;
+        
+        rows.collect::<Result<Vec<_>, _>>().map_err(VaultError::from)
+    }
+    
+    // ============================================
+    // Note Export (note_export_v1)
+    // ============================================
+    
+    /// Sealed, schema-validated machine-readable export of a note, signed
+    /// with the install key. The export is audited; the note is not changed.
+    pub fn export_note_v1(&self, note_id: &str) -> Result<crate::note_export::NoteExportFile, VaultError> {
+        let conn = self.conn()?;
+        let note = self.get_note(note_id)?;
+        let client = self.get_client(&note.client_id)?;
+        let anchored = self.get_note_detection_state(note_id)?
+            .filter(|state| state.content_hash == crypto::hash_sha256(Self::analyzed_text(&note).as_bytes()))
+            .map(|state| state.detections);
+        
+        let authorship = self.get_note_authorship(note_id)?;
+        
+        let doc = crate::note_export::build(&note, &client, anchored.as_deref(), &authorship, chrono::Utc::now());
+        let doc = crate::note_export::seal(doc, &self.install_signing_key()?)
+            .map_err(VaultError::Serialization)?;
+        let file = crate::note_export::render(&doc).map_err(VaultError::InvalidState)?;
+        
+        crate::audit::log_event(
+            conn,
+            crate::models::AuditEventType::NoteExported,
+            crate::models::AuditResourceType::Note,
+            note_id,
+            crate::models::AuditOutcome::Success,
+            None,
+        ).map_err(|e| VaultError::Internal(e.to_string()))?;
+        Ok(file)
+    }
+    
+    // ============================================
+    // Global Search
+    // ============================================
+    
+    /// Keyword search over note text (raw input and structured note) via
+    /// the FTS5 index. Every hit matched all terms; BM25 relative to the
+    /// best hit orders them.
+    pub fn search_note_text(&self, query: &str, limit: usize) -> Result<Vec<crate::models::SearchResult>, VaultError> {
+        let conn = self.conn()?;
+        let hits = crate::fulltext::search_notes(conn, query, None, limit)?;
+        // BM25 is negative, more negative is better
+        let best = hits.first().map(|h| h.rank).unwrap_or(0.0);
+        
+        Ok(hits.into_iter().map(|hit| crate::models::SearchResult {
+            score: if best < 0.0 { (hit.rank / best).clamp(0.0, 1.0) as f32 } else { 1.0 },
+            note_id: hit.note_id,
+            client_id: hit.client_id,
+            session_date: hit.session_date,
+            excerpt: hit.snippet,
+        }).collect())
+    }
+    
+    /// Federated search across clients, notes, document OCR text and consultation drafts.
+    /// Results are ranked by the fraction of query terms matched, with a boost for title hits.
+    pub fn search_everything(&self, query: &str, limit: usize) -> Result<Vec<crate::models::GlobalSearchResult>, VaultError> {
+        use crate::models::{GlobalSearchResult, SearchEntityType};
+        
+        let terms = search_terms(query);
+        if terms.is_empty() {
+            return Ok(vec![]);
+        }
+        let mut results: Vec<GlobalSearchResult> = Vec::new();
+        
+        // Clients (reuses the semantic client search)
+        for hit in self.search_clients(query)? {
+            let name_score = term_score(&hit.client.display_name, &terms);
+            let field_score = if hit.matched_fields.is_empty() { 0.0 } else { 0.5 };
+            let excerpt = hit.matched_fields.iter()
+                .map(|(field, value)| format!("{}: {}", field, value))
+                .collect::<Vec<_>>()
+                .join("; ");
+            results.push(GlobalSearchResult {
+                entity_type: SearchEntityType::Client,
+                entity_id: hit.client.id.clone(),
+                client_id: Some(hit.client.id),
+                title: hit.client.display_name,
+                excerpt,
+                score: (name_score + field_score).min(1.0),
+                low_confidence: false,
+            });
+        }
+        
+        // Notes
+        for hit in self.search_note_text(query, limit)? {
+            results.push(GlobalSearchResult {
+                entity_type: SearchEntityType::Note,
+                entity_id: hit.note_id,
+                client_id: Some(hit.client_id),
+                title: format!("Session {}", hit.session_date),
+                excerpt: hit.excerpt,
+                score: hit.score,
+                low_confidence: false,
+            });
+        }
+        
+        // Documents (OCR text, filename, description)
+        for doc in self.search_documents(query)? {
+            let body = format!(
+                "{} {} {}",
+                doc.filename,
+                doc.description.as_deref().unwrap_or(""),
+                doc.ocr_text.as_deref().unwrap_or("")
+            );
+            let title_boost = if term_score(&doc.filename, &terms) > 0.0 { 0.2 } else { 0.0 };
+            // Flag hits that rest only on poorly recognized OCR text
+            let label = format!("{} {}", doc.filename, doc.description.as_deref().unwrap_or(""));
+            let low_confidence = term_score(&label, &terms) == 0.0
+                && doc.ocr_confidence.is_some_and(|c| c < crate::ocr::LOW_CONFIDENCE_THRESHOLD);
+            results.push(GlobalSearchResult {
+                entity_type: SearchEntityType::Document,
+                entity_id: doc.id,
+                client_id: Some(doc.client_id),
+                title: doc.filename,
+                excerpt: excerpt_around(doc.ocr_text.as_deref().unwrap_or(&body), &terms, 80),
+                score: (term_score(&body, &terms) + title_boost).min(1.0),
+                low_confidence,
+            });
+        }
+        
+        // Consultation drafts (already de-identified)
+        for draft in self.list_consultation_drafts()? {
+            let body = format!("{} {} {}", draft.title, draft.clinical_question, draft.deidentified_content);
+            let score = term_score(&body, &terms);
+            if score == 0.0 {
+                continue;
+            }
+            let title_boost = if term_score(&draft.title, &terms) > 0.0 { 0.2 } else { 0.0 };
+            results.push(GlobalSearchResult {
+                entity_type: SearchEntityType::ConsultationDraft,
+                entity_id: draft.id,
+                client_id: None,
+                title: draft.title,
+                excerpt: excerpt_around(&draft.deidentified_content, &terms, 80),
+                score: (score + title_boost).min(1.0),
+                low_confidence: false,
+            });
+        }
+        
+        results.sort_by(|a, b| {
+            b.score.partial_cmp(&a.score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.title.cmp(&b.title))
+        });
+        results.truncate(limit);
+        Ok(results)
+    }
+    
+    /// Get storage statistics
+    pub fn get_storage_stats(&self) -> Result<StorageStats, VaultError> {
+        let conn = self.conn()?;
+        
+        // Get database file size
+        let db_size = std::fs::metadata(&self.vault_path())
+            .map(|m| m.len() as i64)
+            .unwrap_or(0);
+        
+        // Count records
+        let counts = self.get_dashboard_snapshot()?;
+        let embedding_count: i64 = conn.query_row("SELECT COUNT(*) FROM embeddings", [], |row| row.get(0))?;
+        
+        Ok(StorageStats {
+            database_size_bytes: db_size,
+            note_count: counts.note_count as u32,
+            client_count: counts.client_count as u32,
+            document_count: counts.document_count as u32,
+            document_size_bytes: counts.document_size_bytes,
+            embedding_count: embedding_count as u32,
+        })
+    }
+    
+    /// Optimize database (VACUUM)
+    pub fn optimize_database(&self) -> Result<(), VaultError> {
+        let conn = self.conn()?;
+        conn.execute("VACUUM", [])?;
+        crate::fulltext::rebuild_index(conn)?;
+        conn.execute("ANALYZE", [])?;
+        Ok(())
+    }
+    
+    // ============================================
+    // Derived-Artifact Cache
+    // ============================================
+    
+    /// Cached artifact if it was built from the same inputs by the same
+    /// model/rule versions. A stale entry is dropped and None returned.
+    pub fn cache_get<T: serde::de::DeserializeOwned>(
+        &self,
+        kind: &str,
+        key: &str,
+        source_hash: &str,
+        versions: &str,
+    ) -> Result<Option<T>, VaultError> {
+        let conn = self.conn()?;
+        let row: Option<(String, String, String)> = conn.query_row(
+            "SELECT source_hash, versions, payload FROM derived_cache WHERE kind = ?1 AND cache_key = ?2",
+            params![kind, key],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        ).optional()?;
+        
+        let Some((cached_hash, cached_versions, payload)) = row else {
+            return Ok(None);
+        };
+        
+        if cached_hash != source_hash || cached_versions != versions {
+            self.invalidate_cache_entry(kind, key)?;
+            return Ok(None);
+        }
+        
+        match serde_json::from_str(&payload) {
+            Ok(value) => {
+                conn.execute(
+                    "UPDATE derived_cache SET last_hit_at = ?1 WHERE kind = ?2 AND cache_key = ?3",
+                    params![chrono::Utc::now().timestamp_millis(), kind, key],
+                )?;
+                Ok(Some(value))
+            }
+            Err(e) => {
+                // Shape changed between releases - treat as a miss
+                log::debug!("Discarding unreadable {} cache entry: {}", kind, e);
+                self.invalidate_cache_entry(kind, key)?;
+                Ok(None)
+            }
+        }
+    }
+    
+    /// Store (or replace) a derived artifact
+    pub fn cache_put<T: serde::Serialize>(
+        &self,
+        kind: &str,
+        key: &str,
+        source_hash: &str,
+        versions: &str,
+        value: &T,
+    ) -> Result<(), VaultError> {
+        let conn = self.conn()?;
+        let payload = serde_json::to_string(value)
+            .map_err(|e| VaultError::Serialization(e.to_string()))?;
+        
+        conn.execute(
+            "INSERT OR REPLACE INTO derived_cache
+             (kind, cache_key, source_hash, versions, payload, created_at, last_hit_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, NULL)",
+            params![kind, key, source_hash, versions, payload, chrono::Utc::now().timestamp_millis()],
+        )?;
+        Ok(())
+    }
+    
+    pub fn invalidate_cache_entry(&self, kind: &str, key: &str) -> Result<(), VaultError> {
+        let conn = self.conn()?;
+        conn.execute(
+            "DELETE FROM derived_cache WHERE kind = ?1 AND cache_key = ?2",
+            params![kind, key],
+        )?;
+        Ok(())
+    }
+    
+    /// Drop every artifact derived from a note or client, whatever its kind
+    pub fn invalidate_cache_key(&self, key: &str) -> Result<usize, VaultError> {
+        let conn = self.conn()?;
+        Ok(conn.execute("DELETE FROM derived_cache WHERE cache_key = ?1", [key])?)
+    }
+    
+    /// A note's own artifacts and its client's (the prep sheet reads every
+    /// note) go stale when its text changes
+    fn invalidate_note_artifacts(&self, note_id: &str, client_id: &str) -> Result<(), VaultError> {
+        self.invalidate_cache_key(note_id)?;
+        self.invalidate_cache_key(client_id)?;
+        Ok(())
+    }
+    
+    /// Drop all artifacts of one kind (e.g. after a model change), or everything
+    pub fn clear_derived_cache(&self, kind: Option<&str>) -> Result<usize, VaultError> {
+        let conn = self.conn()?;
+        let removed = match kind {
+            Some(kind) => conn.execute("DELETE FROM derived_cache WHERE kind = ?1", [kind])?,
+            None => conn.execute("DELETE FROM derived_cache", [])?,
+        };
+        Ok(removed)
+    }
+    
+    // ============================================
+    // Pre-Session Prep Sheet
+    // ============================================
+    
+    /// Prep sheet for a client, reused until the client or their notes change
+    /// (or the day rolls over - ages and day counts are relative to today)
+    pub fn generate_prep_sheet(&self, client_id: &str) -> Result<crate::models::PrepSheet, VaultError> {
+        let conn = self.conn()?;
+        let client = self.get_client(client_id)?;
+        
+        let (note_count, notes_updated): (i64, Option<i64>) = conn.query_row(
+            "SELECT COUNT(*), MAX(updated_at) FROM notes WHERE client_id = ?1",
+            [client_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        let source_hash = crypto::hash_sha256(format!(
+            "{}|{}|{}|{}",
+            client.updated_at,
+            note_count,
+            notes_updated.unwrap_or(0),
+            chrono::Utc::now().date_naive()
+        ).as_bytes());
+        
+        if let Some(sheet) = self.cache_get("prep_sheet", client_id, &source_hash, DERIVED_RULES_VERSION)? {
+            crate::audit::log_prep_sheet(conn, client_id, true)
+                .map_err(|e| VaultError::Internal(format!("Audit write failed: {}", e)))?;
+            return Ok(sheet);
+        }
+        
+        let sheet = self.build_prep_sheet(client_id)?;
+        self.cache_put("prep_sheet", client_id, &source_hash, DERIVED_RULES_VERSION, &sheet)?;
+        crate::audit::log_prep_sheet(conn, client_id, false)
+            .map_err(|e| VaultError::Internal(format!("Audit write failed: {}", e)))?;
+        Ok(sheet)
+    }
+    
+    fn build_prep_sheet(&self, client_id: &str) -> Result<crate::models::PrepSheet, VaultError> {
+        use crate::models::*;
+        
+        let conn = self.conn()?;
+        let client = self.get_client(client_id)?;
+        let now = chrono::Utc::now();
+        
+        // Get demographics
+        let age = client.date_of_birth.as_ref().and_then(|dob| {
+            chrono::NaiveDate::parse_from_str(dob, "%Y-%m-%d").ok().map(|d| {
+                let today = now.date_naive();
+                today.years_since(d).unwrap_or(0) as i32
+            })
+        });
+        
+        let treatment_duration = client.treatment_start_date.as_ref().and_then(|start| {
+            chrono::NaiveDate::parse_from_str(start, "%Y-%m-%d").ok().map(|d| {
+                (now.date_naive() - d).num_days() as i32
+            })
+        });
+        
+        // Get recent notes (last 5)
+        let mut stmt = conn.prepare(
+            "SELECT id, session_date, note_type, raw_input FROM notes 
+             WHERE client_id = ?1 ORDER BY session_date DESC LIMIT 5"
+        )?;
+        let notes: Vec<(String, String, String, String)> = stmt.query_map([client_id], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+        })?.filter_map(|r| r.ok()).collect();
+        
+        let last_session_date = notes.first().map(|(_, d, _, _)| d.clone());
+        let days_since_last = last_session_date.as_ref().and_then(|d| {
+            chrono::NaiveDate::parse_from_str(d, "%Y-%m-%d").ok().map(|date| {
+                (now.date_naive() - date).num_days() as i32
+            })
+        });
+        
+        // Build recent session summaries
+        let recent_sessions: Vec<RecentSessionSummary> = notes.iter().take(3).map(|(_, date, note_type, content)| {
+            let key_points = extract_key_points(content);
+            let mood_indicators = extract_mood_indicators(content);
+            let interventions = extract_interventions(content);
+            
+            RecentSessionSummary {
+                session_date: date.clone(),
+                note_type: note_type.clone(),
+                key_points,
+                mood_indicators,
+                interventions_used: interventions,
+            }
+        }).collect();
+        
+        // Get treatment themes
+        let progress = self.get_treatment_progress(client_id)?;
+        let active_themes: Vec<PrepTheme> = progress.themes.iter()
+            .filter(|t| t.trend != "resolved")
+            .map(|t| PrepTheme {
+                theme: t.theme.clone(),
+                trend: t.trend.clone(),
+                last_mentioned: t.first_mentioned.clone(),
+                clinical_note: None,
+            })
+            .collect();
+        
+        // Check for safety alerts in recent notes
+        let mut safety_alerts = Vec::new();
+        for (_, date, _, content) in &notes {
+            let content_lower = content.to_lowercase();
+            if content_lower.contains("suicidal") || content_lower.contains("si ") || content_lower.contains("suicide") {
+                safety_alerts.push(SafetyAlert {
+                    alert_type: "suicidal_ideation".to_string(),
+                    last_flagged: date.clone(),
+                    severity: "high".to_string(),
+                    details: "Suicidal ideation mentioned in recent note".to_string(),
+                });
+            }
+            if content_lower.contains("self-harm") || content_lower.contains("cutting") {
+                safety_alerts.push(SafetyAlert {
+                    alert_type: "self_harm".to_string(),
+                    last_flagged: date.clone(),
+                    severity: "high".to_string(),
+                    details: "Self-harm mentioned in recent note".to_string(),
+                });
+            }
+            if content_lower.contains("abuse") || content_lower.contains("violent") {
+                safety_alerts.push(SafetyAlert {
+                    alert_type: "abuse_concern".to_string(),
+                    last_flagged: date.clone(),
+                    severity: "moderate".to_string(),
+                    details: "Abuse or violence mentioned".to_string(),
+                });
+            }
+        }
+        safety_alerts.dedup_by(|a, b| a.alert_type == b.alert_type);
+        
+        // Suggest assessments based on themes
+        let mut suggested_assessments = Vec::new();
+        let theme_names: Vec<&str> = active_themes.iter().map(|t| t.theme.as_str()).collect();
+        
+        if theme_names.iter().any(|t| t.contains("depression")) {
+            suggested_assessments.push(AssessmentSuggestion {
+                assessment_name: "PHQ-9".to_string(),
+                reason: "Depression theme identified - consider screening".to_string(),
+                last_administered: None,
+                last_score: None,
+            });
+        }
+        if theme_names.iter().any(|t| t.contains("anxiety")) {
+            suggested_assessments.push(AssessmentSuggestion {
+                assessment_name: "GAD-7".to_string(),
+                reason: "Anxiety theme identified - consider screening".to_string(),
+                last_administered: None,
+                last_score: None,
+            });
+        }
+        if !safety_alerts.is_empty() {
+            suggested_assessments.push(AssessmentSuggestion {
+                assessment_name: "C-SSRS".to_string(),
+                reason: "Safety concerns flagged - consider risk assessment".to_string(),
+                last_administered: None,
+                last_score: None,
+            });
+        }
+        if theme_names.iter().any(|t| t.contains("trauma")) {
+            suggested_assessments.push(AssessmentSuggestion {
+                assessment_name: "PCL-5".to_string(),
+                reason: "Trauma theme identified - consider PTSD screening".to_string(),
+                last_administered: None,
+                last_score: None,
+            });
+        }
+        
+        // Generate focus suggestions
+        let mut focus_suggestions = Vec::new();
+        if days_since_last.map(|d| d > 14).unwrap_or(false) {
+            focus_suggestions.push("Consider checking in on progress since last session (extended gap)".to_string());
+        }
+        if !safety_alerts.is_empty() {
+            focus_suggestions.push("Review safety plan and assess current risk level".to_string());
+        }
+        for theme in &active_themes {
+            if theme.trend == "worsening" {
+                focus_suggestions.push(format!("Address {} - trend appears worsening", theme.theme.replace("_", " ")));
+            }
+        }
+        if focus_suggestions.is_empty() {
+            focus_suggestions.push("Continue current treatment approach".to_string());
+        }
+        
+        Ok(PrepSheet {
+            client_id: client_id.to_string(),
+            client_name: client.display_name,
+            generated_at: now.format("%Y-%m-%d %H:%M").to_string(),
+            demographics: PrepDemographics {
+                age,
+                treatment_duration_days: treatment_duration,
+                total_sessions: client.session_count,
+                last_session_date,
+                days_since_last_session: days_since_last,
+                diagnosis_codes: client.diagnosis_codes,
+            },
+            recent_sessions,
+            active_themes,
+            safety_alerts,
+            suggested_assessments,
+            focus_suggestions,
+            last_assessments: vec![],  // Would need separate assessment tracking
+        })
+    }
+    
+    // ============================================
+    // Supervisor Mode
+    // ============================================
+    
+    pub fn create_trainee(&self, name: &str, email: Option<&str>, supervisor_id: &str) -> Result<crate::models::Trainee, VaultError> {
+        let conn = self.conn()?;
+        let id = crate::ids::new_id();
+        let now = chrono::Utc::now();
+        
+        conn.execute(
+            "INSERT INTO trainees (id, name, email, supervisor_id, start_date, status, notes_submitted, notes_approved, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, 0, 0, ?7)",
+            rusqlite::params![id, name, email, supervisor_id, now.format("%Y-%m-%d").to_string(), "active", now.timestamp()],
+        )?;
+        
+        Ok(crate::models::Trainee {
+            id,
+            name: name.to_string(),
+            email: email.map(|s| s.to_string()),
+            supervisor_id: supervisor_id.to_string(),
+            start_date: now.format("%Y-%m-%d").to_string(),
+            status: "active".to_string(),
+            notes_submitted: 0,
+            notes_approved: 0,
+            created_at: now.timestamp(),
+        })
+    }
+    
+    pub fn list_trainees(&self, supervisor_id: &str) -> Result<Vec<crate::models::Trainee>, VaultError> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, name, email, supervisor_id, start_date, status, notes_submitted, notes_approved, created_at
+             FROM trainees WHERE supervisor_id = ?1"
+        )?;
+        
+        let trainees = stmt.query_map([supervisor_id], |row| {
+            Ok(crate::models::Trainee {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                email: row.get(2)?,
+                supervisor_id: row.get(3)?,
+                start_date: row.get(4)?,
+                status: row.get(5)?,
+                notes_submitted: row.get(6)?,
+                notes_approved: row.get(7)?,
+                created_at: row.get(8)?,
+            })
+        })?.filter_map(|r| r.ok()).collect();
+        
+        Ok(trainees)
+    }
+    
+    pub fn submit_note_for_review(&self, note_id: &str, trainee_id: &str) -> Result<(), VaultError> {
+        let conn = self.conn()?;
+        let now = chrono::Utc::now().timestamp();
+        let review_id = crate::ids::new_id();
+        
+        conn.execute(
+            "INSERT INTO note_reviews (id, note_id, trainee_id, status, submitted_at, created_at)
+             VALUES (?1, ?2, ?3, 'pending', ?4, ?5)",
+            rusqlite::params![review_id, note_id, trainee_id, now, now],
+        )?;
+        
+        // Update trainee stats
+        conn.execute(
+            "UPDATE trainees SET notes_submitted = notes_submitted + 1 WHERE id = ?1",
+            [trainee_id],
+        )?;
+        
+        Ok(())
+    }
+    
+    pub fn get_pending_reviews(&self, supervisor_id: &str) -> Result<Vec<crate::models::PendingReview>, VaultError> {
+        let conn = self.conn()?;
+        
+        let mut stmt = conn.prepare(
+            "SELECT nr.note_id, t.name, c.display_name, n.session_date, nr.submitted_at
+             FROM note_reviews nr
+             JOIN trainees t ON nr.trainee_id = t.id
+             JOIN notes n ON nr.note_id = n.id
+             JOIN clients c ON n.client_id = c.id
+             WHERE t.supervisor_id = ?1 AND nr.status = 'pending'
+             ORDER BY nr.submitted_at ASC"
+        )?;
+        
+        let now = chrono::Utc::now().timestamp();
+        let reviews = stmt.query_map([supervisor_id], |row| {
+            let submitted_at: i64 = row.get(4)?;
+            let days_pending = ((now - submitted_at) / 86400) as i32;
+            Ok(crate::models::PendingReview {
+                note_id: row.get(0)?,
+                trainee_name: row.get(1)?,
+                client_name: row.get(2)?,
+                session_date: row.get(3)?,
+                submitted_at: chrono::DateTime::from_timestamp(submitted_at, 0)
+                    .map(|dt| dt.format("%Y-%m-%d").to_string())
+                    .unwrap_or_default(),
+                days_pending,
+            })
+        })?.filter_map(|r| r.ok()).collect();
+        
+        Ok(reviews)
+    }
+    
+    /// Pending reviews with client names replaced by per-supervisor pseudonyms
+    pub fn get_pending_reviews_blinded(&self, supervisor_id: &str) -> Result<Vec<crate::models::PendingReview>, VaultError> {
+        let conn = self.conn()?;
+        let mut reviews = self.get_pending_reviews(supervisor_id)?;
+        for review in &mut reviews {
+            let client_id: String = conn.query_row(
+                "SELECT client_id FROM notes WHERE id = ?1",
+                [&review.note_id],
+                |row| row.get(0),
+            )?;
+            review.client_name = crate::supervision::client_pseudonym(supervisor_id, &client_id);
+        }
+        Ok(reviews)
+    }
+    
+    /// Blinded view of a note submitted to this supervisor for review
+    pub fn get_blinded_review_note(&self, note_id: &str, supervisor_id: &str) -> Result<crate::supervision::BlindedNote, VaultError> {
+        let conn = self.conn()?;
+        let assigned: bool = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM note_reviews nr JOIN trainees t ON nr.trainee_id = t.id
+                           WHERE nr.note_id = ?1 AND t.supervisor_id = ?2)",
+            params![note_id, supervisor_id],
+            |row| row.get(0),
+        )?;
+        if !assigned {
+            return Err(VaultError::NotFound(format!("No review of note {} for this supervisor", note_id)));
+        }
+        
+        let note = self.get_note(note_id)?;
+        let client = self.get_client(&note.client_id)?;
+        // Recorded now, so a co-signature can say whether the supervisor
+        // reviewed this content blinded without taking the client's word
+        conn.execute(
+            "UPDATE note_reviews SET blinded_note_hash = ?1
+             WHERE note_id = ?2 AND trainee_id IN (SELECT id FROM trainees WHERE supervisor_id = ?3)",
+            params![&note.content_hash, note_id, supervisor_id],
+        )?;
+        Ok(crate::supervision::blind_note(&note, &client, supervisor_id))
+    }
+    
+    /// Whether `supervisor_id`'s last view of the note in review was the
+    /// blinded one, of the content that now has `note_hash`
+    pub fn reviewed_blinded(&self, note_id: &str, supervisor_id: &str, note_hash: &str) -> Result<bool, VaultError> {
+        let conn = self.conn()?;
+        let hashes: Vec<Option<String>> = conn.prepare(
+            "SELECT nr.blinded_note_hash FROM note_reviews nr JOIN trainees t ON nr.trainee_id = t.id
+             WHERE nr.note_id = ?1 AND t.supervisor_id = ?2"
+        )?.query_map(params![note_id, supervisor_id], |row| row.get(0))?
+            .collect::<Result<_, _>>()?;
+        Ok(hashes.iter().flatten().any(|h| crypto::digests_match(h, note_hash)))
+    }
+    
+    /// Get pending reviews for a specific trainee
+    pub fn get_trainee_pending_reviews(&self, trainee_id: &str) -> Result<Vec<crate::models::PendingReview>, VaultError> {
+        let conn = self.conn()?;
+        
+        let mut stmt = conn.prepare(
+            "SELECT nr.note_id, t.name, c.display_name, n.session_date, nr.submitted_at
+             FROM note_reviews nr
+             JOIN trainees t ON nr.trainee_id = t.id
+             JOIN notes n ON nr.note_id = n.id
+             JOIN clients c ON n.client_id = c.id
+             WHERE nr.trainee_id = ?1 AND nr.status = 'pending'
+             ORDER BY nr.submitted_at ASC"
+        )?;
+        
+        let now = chrono::Utc::now().timestamp();
+        let reviews = stmt.query_map([trainee_id], |row| {
+            let submitted_at: i64 = row.get(4)?;
+            let days_pending = ((now - submitted_at) / 86400) as i32;
+            Ok(crate::models::PendingReview {
+                note_id: row.get(0)?,
+                trainee_name: row.get(1)?,
+                client_name: row.get(2)?,
+                session_date: row.get(3)?,
+                submitted_at: chrono::DateTime::from_timestamp(submitted_at, 0)
+                    .map(|dt| dt.format("%Y-%m-%d").to_string())
+                    .unwrap_or_default(),
+                days_pending,
+            })
+        })?.filter_map(|r| r.ok()).collect();
+        
+        Ok(reviews)
+    }
+    
+    pub fn add_review_comment(
+        &self,
+        note_id: &str,
+        supervisor_id: &str,
+        comment_type: &str,
+        text: &str,
+        section: Option<&str>,
+    ) -> Result<crate::models::ReviewComment, VaultError> {
+        let conn = self.conn()?;
+        let id = crate::ids::new_id();
+        let now = chrono::Utc::now().timestamp();
+        
+        conn.execute(
+            "INSERT INTO review_comments (id, note_id, supervisor_id, section, comment_type, text, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            rusqlite::params![id, note_id, supervisor_id, section, comment_type, text, now],
+        )?;
+        
+        Ok(crate::models::ReviewComment {
+            id,
+            section: section.map(|s| s.to_string()),
+            comment_type: comment_type.to_string(),
+            text: text.to_string(),
+            created_at: now,
+        })
+    }
+    
+    pub fn complete_review(
+        &self,
+        note_id: &str,
+        supervisor_id: &str,
+        status: &str,
+        overall_feedback: Option<&str>,
+        clinical_accuracy_score: Option<i32>,
+        documentation_quality_score: Option<i32>,
+    ) -> Result<crate::models::SupervisorReview, VaultError> {
+        let conn = self.conn()?;
+        let now = chrono::Utc::now();
+        let review_id: String = conn.query_row(
+            "SELECT id FROM note_reviews WHERE note_id = ?1 AND status = 'pending' LIMIT 1",
+            [note_id],
+            |row| row.get(0),
+        ).optional()?.unwrap_or_else(|| crate::ids::new_id());
+        
+        // Update the review record
+        conn.execute(
+            "UPDATE note_reviews SET status = ?1, completed_at = ?2, supervisor_id = ?3,
+             overall_feedback = ?4, clinical_accuracy_score = ?5, documentation_quality_score = ?6
+             WHERE note_id = ?7 AND status = 'pending'",
+            rusqlite::params![status, now.timestamp(), supervisor_id, overall_feedback, 
+                            clinical_accuracy_score, documentation_quality_score, note_id],
+        )?;
+        
+        // If approved, update trainee stats
+        if status == "approved" {
+            conn.execute(
+                "UPDATE trainees SET notes_approved = notes_approved + 1 
+                 WHERE id = (SELECT trainee_id FROM note_reviews WHERE note_id = ?1)",
+                [note_id],
+            )?;
+        }
+        
+        // Get comments for this review
+        let mut stmt = conn.prepare(
+            "SELECT id, section, comment_type, text, created_at FROM review_comments WHERE note_id = ?1"
+        )?;
+        let comments: Vec<crate::models::ReviewComment> = stmt.query_map([note_id], |row| {
+            Ok(crate::models::ReviewComment {
+                id: row.get(0)?,
+                section: row.get(1)?,
+                comment_type: row.get(2)?,
+                text: row.get(3)?,
+                created_at: row.get(4)?,
+            })
+        })?.filter_map(|r| r.ok()).collect();
+        
+        Ok(crate::models::SupervisorReview {
+            id: review_id,
+            note_id: note_id.to_string(),
+            supervisor_id: supervisor_id.to_string(),
+            supervisor_name: supervisor_id.to_string(),  // Would need lookup
+            review_date: now.format("%Y-%m-%d").to_string(),
+            status: status.to_string(),
+            comments,
+            overall_feedback: overall_feedback.map(|s| s.to_string()),
+            clinical_accuracy_score,
+            documentation_quality_score,
+            created_at: now.timestamp(),
+        })
+    }
+    
+    pub fn get_supervisor_dashboard(&self, supervisor_id: &str) -> Result<crate::models::SupervisorDashboard, VaultError> {
+        let pending_reviews = self.get_pending_reviews(supervisor_id)?;
+        let trainee_summaries = self.trainee_summaries(supervisor_id, &pending_reviews)?;
+        
+        Ok(crate::models::SupervisorDashboard {
+            supervisor_id: supervisor_id.to_string(),
+            trainees: trainee_summaries,
+            pending_reviews,
+            recent_activity: self.get_supervisor_activity(supervisor_id, 25)?,
+        })
+    }
+    
+    /// Per-trainee summaries with rolling quality scores and monthly trends
+    fn trainee_summaries(
+        &self,
+        supervisor_id: &str,
+        pending_reviews: &[crate::models::PendingReview],
+    ) -> Result<Vec<crate::models::TraineeSummary>, VaultError> {
+        let conn = self.conn()?;
+        let trainees = self.list_trainees(supervisor_id)?;
+        let scores = self.list_review_scores(supervisor_id)?;
+        
+        let mut summaries = Vec::with_capacity(trainees.len());
+        for t in trainees {
+            let quality_trend = crate::supervision::quality_trend(&t.id, &scores, crate::supervision::QUALITY_ROLLING_WINDOW);
+            let avg_quality_score = match (quality_trend.rolling_clinical_accuracy, quality_trend.rolling_documentation_quality) {
+                (Some(a), Some(b)) => Some((a + b) / 2.0),
+                (a, b) => a.or(b),
+            };
+            let last_submission: Option<i64> = conn.query_row(
+                "SELECT MAX(submitted_at) FROM note_reviews WHERE trainee_id = ?1",
+                [&t.id],
+                |row| row.get(0),
+            )?;
+            summaries.push(crate::models::TraineeSummary {
+                pending_notes: pending_reviews.iter().filter(|r| r.trainee_name == t.name).count() as i32,
+                avg_quality_score,
+                last_submission: last_submission
+                    .and_then(|ts| chrono::DateTime::from_timestamp(ts, 0))
+                    .map(|dt| dt.format("%Y-%m-%d").to_string()),
+                quality_trend,
+                trainee: t,
+            });
+        }
+        Ok(summaries)
+    }
+    
+    /// Scores of every completed review of this supervisor's trainees
+    fn list_review_scores(&self, supervisor_id: &str) -> Result<Vec<crate::models::ReviewScore>, VaultError> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT nr.trainee_id, nr.completed_at, nr.clinical_accuracy_score, nr.documentation_quality_score
+             FROM note_reviews nr
+             JOIN trainees t ON nr.trainee_id = t.id
+             WHERE t.supervisor_id = ?1 AND nr.status != 'pending' AND nr.completed_at IS NOT NULL
+             ORDER BY nr.completed_at ASC"
+        )?;
+        let scores = stmt.query_map([supervisor_id], |row| {
+            Ok(crate::models::ReviewScore {
+                trainee_id: row.get(0)?,
+                completed_at: row.get(1)?,
+                clinical_accuracy: row.get(2)?,
+                documentation_quality: row.get(3)?,
+            })
+        })?.collect::<Result<Vec<_>, _>>()?;
+        Ok(scores)
+    }
+    
+    /// Latest submissions, completed reviews and reflections across this
+    /// supervisor's trainees, newest first
+    pub fn get_supervisor_activity(&self, supervisor_id: &str, limit: usize) -> Result<Vec<crate::models::SupervisorActivity>, VaultError> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT 'submitted', t.name || ' submitted a note for review', nr.submitted_at
+               FROM note_reviews nr JOIN trainees t ON nr.trainee_id = t.id
+              WHERE t.supervisor_id = ?1
+             UNION ALL
+             SELECT 'reviewed', 'Review completed for ' || t.name || ': ' || nr.status, nr.completed_at
+               FROM note_reviews nr JOIN trainees t ON nr.trainee_id = t.id
+              WHERE t.supervisor_id = ?1 AND nr.completed_at IS NOT NULL
+             UNION ALL
+             SELECT 'reflection', t.name || ' added a reflection', rr.updated_at
+               FROM review_reflections rr JOIN trainees t ON rr.trainee_id = t.id
+              WHERE t.supervisor_id = ?1
+             ORDER BY 3 DESC
+             LIMIT ?2"
+        )?;
+        let activity = stmt.query_map(rusqlite::params![supervisor_id, limit as i64], |row| {
+            Ok(crate::models::SupervisorActivity {
+                activity_type: row.get(0)?,
+                description: row.get(1)?,
+                timestamp: row.get(2)?,
+            })
+        })?.collect::<Result<Vec<_>, _>>()?;
+        Ok(activity)
+    }
+    
+    /// Quality trends for all of a supervisor's trainees (accreditation export)
+    pub fn get_supervision_quality_report(&self, supervisor_id: &str) -> Result<crate::models::SupervisionQualityReport, VaultError> {
+        let pending_reviews = self.get_pending_reviews(supervisor_id)?;
+        Ok(crate::models::SupervisionQualityReport {
+            supervisor_id: supervisor_id.to_string(),
+            generated_at: chrono::Utc::now().format("%Y-%m-%d %H:%M").to_string(),
+            trainees: self.trainee_summaries(supervisor_id, &pending_reviews)?,
+        })
+    }
+    
+    pub fn get_note_reviews(&self, note_id: &str) -> Result<Vec<crate::models::SupervisorReview>, VaultError> {
+        let conn = self.conn()?;
+        
+        let mut stmt = conn.prepare(
+            "SELECT nr.id, nr.note_id, nr.supervisor_id, nr.status, nr.overall_feedback,
+                    nr.clinical_accuracy_score, nr.documentation_quality_score, nr.created_at, nr.completed_at
+             FROM note_reviews nr WHERE nr.note_id = ?1"
+        )?;
+        
+        let reviews = stmt.query_map([note_id], |row| {
+            let created_at: i64 = row.get(7)?;
+            Ok(crate::models::SupervisorReview {
+                id: row.get(0)?,
+                note_id: row.get(1)?,
+                supervisor_id: row.get::<_, Option<String>>(2)?.unwrap_or_default(),
+                supervisor_name: String::new(),
+                review_date: chrono::DateTime::from_timestamp(created_at, 0)
+                    .map(|dt| dt.format("%Y-%m-%d").to_string())
+                    .unwrap_or_default(),
+                status: row.get(3)?,
+                comments: vec![],
+                overall_feedback: row.get(4)?,
+                clinical_accuracy_score: row.get(5)?,
+                documentation_quality_score: row.get(6)?,
+                created_at,
+            })
+        })?.filter_map(|r| r.ok()).collect();
+        
+        Ok(reviews)
+    }
+    
+    /// Record (or revise) a trainee's reflection on one of their completed
+    /// reviews. Locked once the supervisor has acknowledged it.
+    pub fn save_review_reflection(
+        &self,
+        review_id: &str,
+        trainee_id: &str,
+        what_i_learned: &str,
+        what_i_will_change: &str,
+    ) -> Result<crate::models::ReviewReflection, VaultError> {
+        let conn = self.conn()?;
+        
+        let (note_id, review_trainee, status): (String, String, String) = conn.query_row(
+            "SELECT note_id, trainee_id, status FROM note_reviews WHERE id = ?1",
+            [review_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        ).optional()?.ok_or_else(|| VaultError::NotFound(format!("Review {}", review_id)))?;
+        
+        if review_trainee != trainee_id {
+            return Err(VaultError::InvalidState("Reflections can only be added by the reviewed trainee".to_string()));
+        }
+        if status == "pending" {
+            return Err(VaultError::InvalidState("Review is not complete yet".to_string()));
+        }
+        if what_i_learned.trim().is_empty() && what_i_will_change.trim().is_empty() {
+            return Err(VaultError::InvalidState("Reflection is empty".to_string()));
+        }
+        
+        let existing = self.get_review_reflection(review_id)?;
+        if existing.as_ref().map(|r| r.acknowledged_at.is_some()).unwrap_or(false) {
+            return Err(VaultError::InvalidState("Reflection was already acknowledged by the supervisor".to_string()));
+        }
+        
+        let now = chrono::Utc::now().timestamp();
+        let reflection = crate::models::ReviewReflection {
+            id: existing.as_ref().map(|r| r.id.clone()).unwrap_or_else(|| crate::ids::new_id()),
+            review_id: review_id.to_string(),
+            note_id,
+            trainee_id: trainee_id.to_string(),
+            what_i_learned: what_i_learned.trim().to_string(),
+            what_i_will_change: what_i_will_change.trim().to_string(),
+            created_at: existing.as_ref().map(|r| r.created_at).unwrap_or(now),
+            updated_at: now,
+            acknowledged_at: None,
+            acknowledged_by: None,
+        };
+        
+        conn.execute(
+            "INSERT OR REPLACE INTO review_reflections
+             (id, review_id, note_id, trainee_id, what_i_learned, what_i_will_change, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            rusqlite::params![reflection.id, reflection.review_id, reflection.note_id, reflection.trainee_id,
+                            reflection.what_i_learned, reflection.what_i_will_change,
+                            reflection.created_at, reflection.updated_at],
+        )?;
+        
+        Ok(reflection)
+    }
+    
+    pub fn get_review_reflection(&self, review_id: &str) -> Result<Option<crate::models::ReviewReflection>, VaultError> {
+        let conn = self.conn()?;
+        conn.query_row(
+            "SELECT id, review_id, note_id, trainee_id, what_i_learned, what_i_will_change,
+                    created_at, updated_at, acknowledged_at, acknowledged_by
+             FROM review_reflections WHERE review_id = ?1",
+            [review_id],
+            Self::map_reflection_row,
+        ).optional().map_err(VaultError::from)
+    }
+    
+    /// A trainee's reflections, newest first
+    pub fn list_trainee_reflections(&self, trainee_id: &str) -> Result<Vec<crate::models::ReviewReflection>, VaultError> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, review_id, note_id, trainee_id, what_i_learned, what_i_will_change,
+                    created_at, updated_at, acknowledged_at, acknowledged_by
+             FROM review_reflections WHERE trainee_id = ?1 ORDER BY created_at DESC"
+        )?;
+        let rows = stmt.query_map([trainee_id], Self::map_reflection_row)?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(VaultError::from)
+    }
+    
+    /// Supervisor marks a reflection as read
+    pub fn acknowledge_review_reflection(
+        &self,
+        reflection_id: &str,
+        supervisor_id: &str,
+    ) -> Result<(), VaultError> {
+        let conn = self.conn()?;
+        let updated = conn.execute(
+            "UPDATE review_reflections SET acknowledged_at = ?1, acknowledged_by = ?2
+             WHERE id = ?3 AND acknowledged_at IS NULL",
+            rusqlite::params![chrono::Utc::now().timestamp(), supervisor_id, reflection_id],
+        )?;
+        if updated == 0 {
+            return Err(VaultError::NotFound(format!("Unacknowledged reflection {}", reflection_id)));
+        }
+        Ok(())
+    }
+    
+    fn map_reflection_row(row: &rusqlite::Row) -> rusqlite::Result<crate::models::ReviewReflection> {
+        Ok(crate::models::ReviewReflection {
+            id: row.get(0)?,
+            review_id: row.get(1)?,
+            note_id: row.get(2)?,
+            trainee_id: row.get(3)?,
+            what_i_learned: row.get(4)?,
+            what_i_will_change: row.get(5)?,
+            created_at: row.get(6)?,
+            updated_at: row.get(7)?,
+            acknowledged_at: row.get(8)?,
+            acknowledged_by: row.get(9)?,
+        })
+    }
+    
+    /// Review outcomes and reflective-practice entries for one trainee
+    pub fn get_trainee_competency_report(&self, trainee_id: &str) -> Result<crate::models::TraineeCompetencyReport, VaultError> {
+        let conn = self.conn()?;
+        
+        let trainee = conn.query_row(
+            "SELECT id, name, email, supervisor_id, start_date, status, notes_submitted, notes_approved, created_at
+             FROM trainees WHERE id = ?1",
+            [trainee_id],
+            |row| Ok(crate::models::Trainee {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                email: row.get(2)?,
+                supervisor_id: row.get(3)?,
+                start_date: row.get(4)?,
+                status: row.get(5)?,
+                notes_submitted: row.get(6)?,
+                notes_approved: row.get(7)?,
+                created_at: row.get(8)?,
+            }),
+        ).optional()?.ok_or_else(|| VaultError::NotFound(format!("Trainee {}", trainee_id)))?;
+        
+        let (completed, approved, needs_revision, rejected, avg_accuracy, avg_quality):
+            (i32, i32, i32, i32, Option<f64>, Option<f64>) = conn.query_row(
+            "SELECT COUNT(*),
+                    COALESCE(SUM(status = 'approved'), 0),
+                    COALESCE(SUM(status = 'needs_revision'), 0),
+                    COALESCE(SUM(status = 'rejected'), 0),
+                    AVG(clinical_accuracy_score),
+                    AVG(documentation_quality_score)
+             FROM note_reviews WHERE trainee_id = ?1 AND status != 'pending'",
+            [trainee_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?, row.get(5)?)),
+        )?;
+        
+        let reflections = self.list_trainee_reflections(trainee_id)?;
+        
+        Ok(crate::models::TraineeCompetencyReport {
+            generated_at: chrono::Utc::now().format("%Y-%m-%d %H:%M").to_string(),
+            reviews_completed: completed,
+            approved,
+            needs_revision,
+            rejected,
+            avg_clinical_accuracy: avg_accuracy.map(|v| v as f32),
+            avg_documentation_quality: avg_quality.map(|v| v as f32),
+            reflection_rate: (completed > 0).then(|| reflections.len() as f32 / completed as f32),
+            reflections,
+            supervision_hours: self.trainee_supervision_hours(trainee_id, &trainee.name)?,
+            trainee,
+        })
+    }
+    
+    // ============================================
+    // Group Supervision
+    // ============================================
+    
+    /// Record a group supervision session. Every trainee listed must be
+    /// under this supervisor; cases are stored as the supervisor's client
+    /// pseudonyms, never as client IDs.
+    pub fn record_group_supervision(
+        &self,
+        input: &crate::models::NewGroupSupervisionSession,
+    ) -> Result<crate::models::GroupSupervisionSession, VaultError> {
+        crate::supervision::validate_group_session(input).map_err(VaultError::InvalidState)?;
+        let conn = self.conn()?;
+        
+        let names: std::collections::HashMap<String, String> = self.list_trainees(&input.supervisor_id)?
+            .into_iter()
+            .map(|t| (t.id, t.name))
+            .collect();
+        let mut attendees: Vec<crate::models::GroupSupervisionAttendee> = Vec::new();
+        for (ids, present) in [(&input.present, true), (&input.absent, false)] {
+            for id in ids {
+                let name = names.get(id).ok_or_else(|| {
+                    VaultError::NotFound(format!("Trainee {} under supervisor {}", id, input.supervisor_id))
+                })?;
+                if !attendees.iter().any(|a| &a.trainee_id == id) {
+                    attendees.push(crate::models::GroupSupervisionAttendee {
+                        trainee_id: id.clone(),
+                        trainee_name: name.clone(),
+                        present,
+                    });
+                }
+            }
+        }
+        
+        let topics: Vec<String> = input.topics.iter()
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty())
+            .collect();
+        let mut cases_discussed: Vec<String> = Vec::new();
+        for client_id in &input.case_client_ids {
+            // Only charts that exist (and are not in the trash) can be cases
+            self.get_client(client_id)?;
+            let pseudonym = crate::supervision::client_pseudonym(&input.supervisor_id, client_id);
+            if !cases_discussed.contains(&pseudonym) {
+                cases_discussed.push(pseudonym);
+            }
+        }
+        let notes = input.notes.as_deref().map(str::trim).filter(|n| !n.is_empty()).map(str::to_string);
+        
+        let id = crate::ids::new_id();
+        let now = chrono::Utc::now().timestamp();
+        let tx = conn.unchecked_transaction()?;
+        tx.execute(
+            "INSERT INTO group_supervision_sessions
+                (id, supervisor_id, session_date, duration_minutes, topics, cases_discussed, notes, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                id,
+                input.supervisor_id,
+                input.session_date,
+                input.duration_minutes,
+                serde_json::to_string(&topics).unwrap_or_default(),
+                serde_json::to_string(&cases_discussed).unwrap_or_default(),
+                notes,
+                now,
+            ],
+        )?;
+        for attendee in &attendees {
+            tx.execute(
+                "INSERT INTO group_supervision_attendance (session_id, trainee_id, trainee_name, present)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![id, attendee.trainee_id, attendee.trainee_name, attendee.present],
+            )?;
+        }
+        let present = attendees.iter().filter(|a| a.present).count();
+        crate::audit::log_group_supervision(&tx, &id, present, attendees.len() - present, cases_discussed.len())
+            .map_err(|e| VaultError::Internal(format!("Audit write failed: {}", e)))?;
+        tx.commit()?;
+        
+        Ok(crate::models::GroupSupervisionSession {
+            id,
+            supervisor_id: input.supervisor_id.clone(),
+            session_date: input.session_date.clone(),
+            duration_minutes: input.duration_minutes,
+            attendees,
+            topics,
+            cases_discussed,
+            notes,
+            created_at: now,
+        })
+    }
+    
+    /// A supervisor's group sessions with `session_date` in `from..=to`
+    /// (either bound optional), oldest first
+    pub fn list_group_supervision_sessions(
+        &self,
+        supervisor_id: &str,
+        from: Option<&str>,
+        to: Option<&str>,
+    ) -> Result<Vec<crate::models::GroupSupervisionSession>, VaultError> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, supervisor_id, session_date, duration_minutes, topics, cases_discussed, notes, created_at
+             FROM group_supervision_sessions
+             WHERE supervisor_id = ?1
+               AND (?2 IS NULL OR session_date >= ?2)
+               AND (?3 IS NULL OR session_date <= ?3)
+             ORDER BY session_date ASC, created_at ASC"
+        )?;
+        let mut sessions = stmt.query_map(params![supervisor_id, from, to], |row| {
+            let topics: String = row.get(4)?;
+            let cases: String = row.get(5)?;
+            Ok(crate::models::GroupSupervisionSession {
+                id: row.get(0)?,
+                supervisor_id: row.get(1)?,
+                session_date: row.get(2)?,
+                duration_minutes: row.get(3)?,
+                attendees: vec![],
+                topics: serde_json::from_str(&topics).unwrap_or_default(),
+                cases_discussed: serde_json::from_str(&cases).unwrap_or_default(),
+                notes: row.get(6)?,
+                created_at: row.get(7)?,
+            })
+        })?.collect::<Result<Vec<_>, _>>()?;
+        
+        let mut attendance = conn.prepare(
+            "SELECT trainee_id, trainee_name, present FROM group_supervision_attendance
+             WHERE session_id = ?1 ORDER BY present DESC, trainee_name ASC"
+        )?;
+        for session in &mut sessions {
+            session.attendees = attendance.query_map([&session.id], |row| {
+                Ok(crate::models::GroupSupervisionAttendee {
+                    trainee_id: row.get(0)?,
+                    trainee_name: row.get(1)?,
+                    present: row.get(2)?,
+                })
+            })?.collect::<Result<Vec<_>, _>>()?;
+        }
+        Ok(sessions)
+    }
+    
+    /// Group sessions and hours per trainee over a period (accreditation export)
+    pub fn get_group_supervision_log(
+        &self,
+        supervisor_id: &str,
+        from: Option<&str>,
+        to: Option<&str>,
+    ) -> Result<crate::models::GroupSupervisionLog, VaultError> {
+        let sessions = self.list_group_supervision_sessions(supervisor_id, from, to)?;
+        Ok(crate::models::GroupSupervisionLog {
+            supervisor_id: supervisor_id.to_string(),
+            generated_at: chrono::Utc::now().format("%Y-%m-%d %H:%M").to_string(),
+            from: from.map(str::to_string),
+            to: to.map(str::to_string),
+            hours: crate::supervision::group_supervision_hours(&sessions),
+            sessions,
+        })
+    }
+    
+    /// Group supervision credited to one trainee, all time
+    fn trainee_supervision_hours(&self, trainee_id: &str, trainee_name: &str) -> Result<crate::models::TraineeSupervisionHours, VaultError> {
+        let conn = self.conn()?;
+        let (attended, missed, minutes): (i32, i32, i64) = conn.query_row(
+            "SELECT COALESCE(SUM(a.present), 0),
+                    COALESCE(SUM(NOT a.present), 0),
+                    COALESCE(SUM(CASE WHEN a.present THEN s.duration_minutes ELSE 0 END), 0)
+             FROM group_supervision_attendance a
+             JOIN group_supervision_sessions s ON s.id = a.session_id
+             WHERE a.trainee_id = ?1",
+            [trainee_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )?;
+        Ok(crate::models::TraineeSupervisionHours {
+            trainee_id: trainee_id.to_string(),
+            trainee_name: trainee_name.to_string(),
+            group_sessions_attended: attended,
+            group_sessions_missed: missed,
+            group_hours: minutes as f32 / 60.0,
+        })
+    }
+    
+    // ============================================
+    // De-identification Audit Trail
+    // ============================================
+    
+    /// Sealed certificate for a de-identified export, signed with the
+    /// install key
+    pub fn issue_deidentification_certificate(
+        &self,
+        result: &crate::deidentify::DeidentificationResult,
+        export_format: &str,
+        ai_enhanced: bool,
+    ) -> Result<crate::deidentify::DeidentificationCertificate, VaultError> {
+        let certificate = crate::deidentify::DeidentificationCertificate::build(result, export_format, ai_enhanced)
+            .seal(&self.install_signing_key()?)
+            .map_err(VaultError::Serialization)?;
+        crate::audit::log_deidentification_certificate(self.conn()?, &certificate.certificate_id, &certificate.certificate_sha256)
+            .map_err(|e| VaultError::Internal(format!("Audit write failed: {}", e)))?;
+        Ok(certificate)
+    }
+    
+    pub fn save_deidentification_audit(
+        &self,
+        note_id: Option<&str>,
+        client_id: Option<&str>,
+        result: &crate::deidentify::DeidentificationResult,
+        ai_enhanced: bool,
+    ) -> Result<crate::deidentify::DeidentificationAudit, VaultError> {
+        let conn = self.conn()?;
+        let id = crate::ids::new_id();
+        let now = chrono::Utc::now().timestamp();
+        
+        // Build identifiers removed list
+        let identifiers_removed: Vec<crate::deidentify::AuditedIdentifier> = result.identifiers_found.iter()
+            .map(|i| crate::deidentify::AuditedIdentifier {
+                category_code: i.category.code().to_string(),
+                category_name: i.category.description().to_string(),
+                position: i.start_pos,
+                length: i.end_pos - i.start_pos,
+                replacement_type: "redact".to_string(),
+            })
+            .collect();
+        
+        let identifiers_json = serde_json::to_string(&identifiers_removed).unwrap_or_default();
+        let category_json = serde_json::to_string(&result.category_counts).unwrap_or_default();
+        
+        conn.execute(
+            "INSERT INTO deidentification_audits 
+             (id, note_id, client_id, original_hash, deidentified_hash, identifiers_removed, 
+              category_summary, method, ai_enhanced, user_verified, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            rusqlite::params![
+                id, note_id, client_id, 
+                result.original_hash, result.deidentified_hash,
+                identifiers_json, category_json,
+                "safe_harbor", ai_enhanced, false, now
+            ],
+        )?;
+        
+        Ok(crate::deidentify::DeidentificationAudit {
+            id,
+            note_id: note_id.map(|s| s.to_string()),
+            client_id: client_id.map(|s| s.to_string()),
+            original_hash: result.original_hash.clone(),
+            deidentified_hash: result.deidentified_hash.clone(),
+            identifiers_removed,
+            category_summary: result.category_counts.clone(),
+            method: "safe_harbor".to_string(),
+            ai_enhanced,
+            user_verified: false,
+            created_at: now,
+            exported_at: None,
+        })
+    }
+    
+    pub fn get_deidentification_audits(
+        &self,
+        note_id: Option<&str>,
+    ) -> Result<Vec<crate::deidentify::DeidentificationAudit>, VaultError> {
+        let conn = self.conn()?;
+        
+        let sql = match note_id {
+            Some(_) => "SELECT id, note_id, client_id, original_hash, deidentified_hash, 
+                        identifiers_removed, category_summary, method, ai_enhanced, 
+                        user_verified, created_at, exported_at
+                        FROM deidentification_audits WHERE note_id = ?1 ORDER BY created_at DESC",
+            None => "SELECT id, note_id, client_id, original_hash, deidentified_hash, 
+                     identifiers_removed, category_summary, method, ai_enhanced, 
+                     user_verified, created_at, exported_at
+                     FROM deidentification_audits ORDER BY created_at DESC",
+        };
+        
+        let mut stmt = conn.prepare(sql)?;
+        
+        let audits = if let Some(nid) = note_id {
+            stmt.query_map([nid], Self::map_audit_row)?
+        } else {
+            stmt.query_map([], Self::map_audit_row)?
+        };
+        
+        Ok(audits.filter_map(|r| r.ok()).collect())
+    }
+    
+    fn map_audit_row(row: &rusqlite::Row) -> rusqlite::Result<crate::deidentify::DeidentificationAudit> {
+        let identifiers_json: String = row.get(5)?;
+        let category_json: String = row.get(6)?;
+        
+        let identifiers: Vec<crate::deidentify::AuditedIdentifier> = 
+            serde_json::from_str(&identifiers_json).unwrap_or_default();
+        let categories: std::collections::HashMap<String, i32> = 
+            serde_json::from_str(&category_json).unwrap_or_default();
+        
+        Ok(crate::deidentify::DeidentificationAudit {
+            id: row.get(0)?,
+            note_id: row.get(1)?,
+            client_id: row.get(2)?,
+            original_hash: row.get(3)?,
+            deidentified_hash: row.get(4)?,
+            identifiers_removed: identifiers,
+            category_summary: categories,
+            method: row.get(7)?,
+            ai_enhanced: row.get(8)?,
+            user_verified: row.get(9)?,
+            created_at: row.get(10)?,
+            exported_at: row.get(11)?,
+        })
+    }
+    
+    // ============================================
+    // Consultation Draft Queue
+    // ============================================
+    
+    pub fn create_consultation_draft(
+        &self,
+        deidentified_content: &str,
+        title: &str,
+        clinical_question: &str,
+        specialties: &[String],
+        urgency: &str,
+        audit_id: &str,
+    ) -> Result<crate::deidentify::ConsultationDraft, VaultError> {
+        let conn = self.conn()?;
+        let id = crate::ids::new_id();
+        let now = chrono::Utc::now().timestamp();
+        let specialties_json = serde_json::to_string(specialties).unwrap_or_default();
+        
+        conn.execute(
+            "INSERT INTO consultation_drafts 
+             (id, title, deidentified_content, clinical_question, specialties, 
+              urgency, audit_id, status, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            rusqlite::params![
+                id, title, deidentified_content, clinical_question, specialties_json,
+                urgency, audit_id, "draft", now, now
+            ],
+        )?;
+        
+        Ok(crate::deidentify::ConsultationDraft {
+            id,
+            title: title.to_string(),
+            deidentified_content: deidentified_content.to_string(),
+            clinical_question: clinical_question.to_string(),
+            specialties: specialties.to_vec(),
+            urgency: urgency.to_string(),
+            audit_id: audit_id.to_string(),
+            status: "draft".to_string(),
+            created_at: now,
+            updated_at: now,
+        })
+    }
+    
+    pub fn list_consultation_drafts(&self) -> Result<Vec<crate::deidentify::ConsultationDraft>, VaultError> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, title, deidentified_content, clinical_question, specialties,
+                    urgency, audit_id, status, created_at, updated_at
+             FROM consultation_drafts ORDER BY updated_at DESC"
+        )?;
+        
+        let drafts = stmt.query_map([], |row| {
+            let specialties_json: String = row.get(4)?;
+            let specialties: Vec<String> = serde_json::from_str(&specialties_json).unwrap_or_default();
+            
+            Ok(crate::deidentify::ConsultationDraft {
+                id: row.get(0)?,
+                title: row.get(1)?,
+                deidentified_content: row.get(2)?,
+                clinical_question: row.get(3)?,
+                specialties,
+                urgency: row.get(5)?,
+                audit_id: row.get(6)?,
+                status: row.get(7)?,
+                created_at: row.get(8)?,
+                updated_at: row.get(9)?,
+            })
+        })?.filter_map(|r| r.ok()).collect();
+        
+        Ok(drafts)
+    }
+    
+    pub fn get_consultation_draft(&self, draft_id: &str) -> Result<crate::deidentify::ConsultationDraft, VaultError> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, title, deidentified_content, clinical_question, specialties,
+                    urgency, audit_id, status, created_at, updated_at
+             FROM consultation_drafts WHERE id = ?1"
+        )?;
+        
+        stmt.query_row([draft_id], |row| {
+            let specialties_json: String = row.get(4)?;
+            let specialties: Vec<String> = serde_json::from_str(&specialties_json).unwrap_or_default();
+            
+            Ok(crate::deidentify::ConsultationDraft {
+                id: row.get(0)?,
+                title: row.get(1)?,
+                deidentified_content: row.get(2)?,
+                clinical_question: row.get(3)?,
+                specialties,
+                urgency: row.get(5)?,
+                audit_id: row.get(6)?,
+                status: row.get(7)?,
+                created_at: row.get(8)?,
+                updated_at: row.get(9)?,
+            })
+        }).map_err(|e| VaultError::Database(e))
+    }
+    
+    pub fn update_consultation_draft(
+        &self,
+        draft_id: &str,
+        title: Option<&str>,
+        clinical_question: Option<&str>,
+        status: Option<&str>,
+    ) -> Result<(), VaultError> {
+        let conn = self.conn()?;
+        let now = chrono::Utc::now().timestamp();
+        
+        if let Some(t) = title {
+            conn.execute("UPDATE consultation_drafts SET title = ?1, updated_at = ?2 WHERE id = ?3",
+                        rusqlite::params![t, now, draft_id])?;
+        }
+        if let Some(q) = clinical_question {
+            conn.execute("UPDATE consultation_drafts SET clinical_question = ?1, updated_at = ?2 WHERE id = ?3",
+                        rusqlite::params![q, now, draft_id])?;
+        }
+        if let Some(s) = status {
+            conn.execute("UPDATE consultation_drafts SET status = ?1, updated_at = ?2 WHERE id = ?3",
+                        rusqlite::params![s, now, draft_id])?;
+        }
+        
+        Ok(())
+    }
+    
+    pub fn delete_consultation_draft(&self, draft_id: &str) -> Result<(), VaultError> {
+        let conn = self.conn()?;
+        conn.execute("DELETE FROM consultation_drafts WHERE id = ?1", [draft_id])?;
+        Ok(())
+    }
+    
+    // ============================================
+    // Mental Status Exam
+    // ============================================
+    
+    /// Attach a structured MSE to a note. Fails if the note already has one.
+    pub fn record_note_mse(
+        &self,
+        note_id: &str,
+        exam: &crate::mse::MentalStatusExam,
+    ) -> Result<crate::mse::NoteMse, VaultError> {
+        if self.get_note_mse(note_id)?.is_some() {
+            return Err(VaultError::InvalidState("Note already has a mental status exam".to_string()));
+        }
+        self.write_note_mse(note_id, exam, None)
+    }
+    
+    /// Replace the MSE already attached to a note
+    pub fn update_note_mse(
+        &self,
+        note_id: &str,
+        exam: &crate::mse::MentalStatusExam,
+    ) -> Result<crate::mse::NoteMse, VaultError> {
+        let existing = self.get_note_mse(note_id)?
+            .ok_or_else(|| VaultError::NotFound(format!("MSE for note {}", note_id)))?;
+        self.write_note_mse(note_id, exam, Some(existing.created_at))
+    }
+    
+    fn write_note_mse(
+        &self,
+        note_id: &str,
+        exam: &crate::mse::MentalStatusExam,
+        created_at: Option<i64>,
+    ) -> Result<crate::mse::NoteMse, VaultError> {
+        exam.validate().map_err(VaultError::InvalidState)?;
+        let note = self.get_note(note_id)?;
+        if note.status == NoteStatus::Signed {
+            return Err(VaultError::InvalidState("Cannot change the MSE of a signed note - amend instead".to_string()));
+        }
+        
+        let conn = self.conn()?;
+        let now = chrono::Utc::now().timestamp_millis();
+        let created_at = created_at.unwrap_or(now);
+        let exam_json = serde_json::to_string(exam)
+            .map_err(|e| VaultError::Serialization(e.to_string()))?;
+        
+        conn.execute(
+            "INSERT OR REPLACE INTO mental_status_exams
+             (note_id, client_id, session_date, exam, total_severity, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![note_id, &note.client_id, &note.session_date, exam_json, exam.total_severity(), created_at, now],
+        )?;
+        
+        crate::audit::log_event(
+            conn,
+            crate::models::AuditEventType::NoteUpdated,
+            crate::models::AuditResourceType::Note,
+            note_id,
+            crate::models::AuditOutcome::Success,
+            None,
+        ).ok();
+        
+        Ok(crate::mse::NoteMse {
+            note_id: note_id.to_string(),
+            client_id: note.client_id,
+            session_date: note.session_date,
+            exam: exam.clone(),
+            created_at,
+            updated_at: now,
+        })
+    }
+    
+    pub fn get_note_mse(&self, note_id: &str) -> Result<Option<crate::mse::NoteMse>, VaultError> {
+        let conn = self.conn()?;
+        conn.query_row(
+            "SELECT note_id, client_id, session_date, exam, created_at, updated_at
+             FROM mental_status_exams WHERE note_id = ?1",
+            [note_id],
+            Self::map_mse_row,
+        ).optional().map_err(VaultError::from)
+    }
+    
+    /// All MSEs for a client, oldest session first
+    pub fn get_client_mse_history(&self, client_id: &str) -> Result<Vec<crate::mse::NoteMse>, VaultError> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT note_id, client_id, session_date, exam, created_at, updated_at
+             FROM mental_status_exams WHERE client_id = ?1
+             ORDER BY session_date ASC, created_at ASC"
+        )?;
+        
+        let rows = stmt.query_map([client_id], Self::map_mse_row)?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(VaultError::from)
+    }
+    
+    fn map_mse_row(row: &rusqlite::Row) -> rusqlite::Result<crate::mse::NoteMse> {
+        let exam_json: String = row.get(3)?;
+        let exam = serde_json::from_str(&exam_json).map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(3, rusqlite::types::Type::Text, Box::new(e))
+        })?;
+        Ok(crate::mse::NoteMse {
+            note_id: row.get(0)?,
+            client_id: row.get(1)?,
+            session_date: row.get(2)?,
+            exam,
+            created_at: row.get(4)?,
+            updated_at: row.get(5)?,
+        })
+    }
+    
+    // ============================================
+    // Note Type Registry
+    // ============================================
+    
+    /// Create or replace an agency-defined note type
+    pub fn save_note_type_definition(
+        &self,
+        definition: &crate::note_types::NoteTypeDefinition,
+    ) -> Result<crate::note_types::NoteTypeDefinition, VaultError> {
+        definition.validate().map_err(VaultError::InvalidState)?;
+        let conn = self.conn()?;
+        let now = chrono::Utc::now().timestamp_millis();
+        
+        let mut stored = definition.clone();
+        stored.builtin = false;
+        let json = serde_json::to_string(&stored)
+            .map_err(|e| VaultError::Serialization(e.to_string()))?;
+        
+        conn.execute(
+            "INSERT INTO note_type_definitions (name, definition, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?3)
+             ON CONFLICT(name) DO UPDATE SET definition = excluded.definition, updated_at = excluded.updated_at",
+            params![&stored.name, json, now],
+        )?;
+        
+        crate::audit::log_event(
+            conn,
+            crate::models::AuditEventType::SettingsChanged,
+            crate::models::AuditResourceType::Settings,
+            &stored.name,
+            crate::models::AuditOutcome::Success,
+            None,
+        ).ok();
+        
+        Ok(stored)
+    }
+    
+    /// Agency-defined note types, by display name
+    pub fn list_note_type_definitions(&self) -> Result<Vec<crate::note_types::NoteTypeDefinition>, VaultError> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare("SELECT definition FROM note_type_definitions")?;
+        let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+        
+        let mut definitions = Vec::new();
+        for json in rows {
+            match serde_json::from_str::<crate::note_types::NoteTypeDefinition>(&json?) {
+                Ok(def) => definitions.push(def),
+                Err(e) => log::warn!("Skipping unreadable note type definition: {}", e),
+            }
+        }
+        definitions.sort_by(|a, b| a.display_name.cmp(&b.display_name));
+        Ok(definitions)
+    }
+    
+    pub fn get_note_type_definition(
+        &self,
+        name: &str,
+    ) -> Result<Option<crate::note_types::NoteTypeDefinition>, VaultError> {
+        let conn = self.conn()?;
+        let json: Option<String> = conn.query_row(
+            "SELECT definition FROM note_type_definitions WHERE name = ?1",
+            [name],
+            |row| row.get(0),
+        ).optional()?;
+        
+        json.map(|j| serde_json::from_str(&j).map_err(|e| VaultError::Serialization(e.to_string())))
+            .transpose()
+    }
+    
+    /// Remove a note type. Refused while any note still refers to it.
+    pub fn delete_note_type_definition(&self, name: &str) -> Result<(), VaultError> {
+        let conn = self.conn()?;
+        let in_use: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM note_type_assignments WHERE type_name = ?1",
+            [name],
+            |row| row.get(0),
+        )?;
+        if in_use > 0 {
+            return Err(VaultError::InvalidState(format!(
+                "Note type '{}' is used by {} note(s)", name, in_use
+            )));
+        }
+        
+        let removed = conn.execute("DELETE FROM note_type_definitions WHERE name = ?1", [name])?;
+        if removed == 0 {
+            return Err(VaultError::NotFound(format!("Note type {}", name)));
+        }
+        Ok(())
+    }
+    
+    /// Record which agency-defined type a note was written as
+    pub fn assign_note_type(&self, note_id: &str, type_name: &str) -> Result<(), VaultError> {
+        let conn = self.conn()?;
+        conn.execute(
+            "INSERT OR REPLACE INTO note_type_assignments (note_id, type_name) VALUES (?1, ?2)",
+            params![note_id, type_name],
+        )?;
+        Ok(())
+    }
+    
+    /// Agency-defined type of a note, if it has one (built-in types have none)
+    pub fn get_note_type_name(&self, note_id: &str) -> Result<Option<String>, VaultError> {
+        let conn = self.conn()?;
+        conn.query_row(
+            "SELECT type_name FROM note_type_assignments WHERE note_id = ?1",
+            [note_id],
+            |row| row.get(0),
+        ).optional().map_err(VaultError::from)
+    }
+    
+    // ============================================
+    // Retention and Legal Hold
+    // ============================================
+    
+    pub fn retention_schedule(&self) -> Result<crate::retention::RetentionSchedule, VaultError> {
+        let conn = self.conn()?;
+        let json: Option<String> = conn.query_row(
+            "SELECT value FROM settings WHERE key = 'retention_schedule'",
+            [],
+            |row| row.get(0),
+        ).optional()?;
+        Ok(json.and_then(|j| serde_json::from_str(&j).ok()).unwrap_or_default())
     }
     
-    /// Get client's last visit date
-    pub fn get_client_last_visit(&self, client_id: &str) -> Result<Option<String>, VaultError> {
+    pub fn set_retention_schedule(&self, schedule: &crate::retention::RetentionSchedule) -> Result<(), VaultError> {
         let conn = self.conn()?;
-        
-        let result: Option<String> = conn.query_row(
-            "SELECT session_date FROM notes WHERE client_id = ?1 ORDER BY created_at DESC LIMIT 1",
-            params![client_id],
-            |row| row.get(0)
+        let json = serde_json::to_string(schedule)
+            .map_err(|e| VaultError::Serialization(e.to_string()))?;
+        conn.execute(
+            "INSERT OR REPLACE INTO settings (key, value) VALUES ('retention_schedule', ?1)",
+            [&json],
+        )?;
+        crate::audit::log_retention_setting_change(
+            conn,
+            crate::models::AuditResourceType::Settings,
+            "retention_schedule",
+            "retention_schedule",
+            &crypto::hash_sha256(json.as_bytes()),
+        ).map_err(|e| VaultError::Internal(format!("Audit write failed: {}", e)))?;
+        Ok(())
+    }
+    
+    pub fn set_client_retention_profile(&self, profile: &crate::retention::ClientRetentionProfile) -> Result<(), VaultError> {
+        let conn = self.conn()?;
+        self.get_client(&profile.client_id)?;
+        let jurisdiction = profile.jurisdiction.as_deref()
+            .map(str::trim)
+            .filter(|j| !j.is_empty())
+            .map(crate::licensure::normalize_jurisdiction);
+        let discharged_on = profile.discharged_on.map(|d| d.format("%Y-%m-%d").to_string());
+        conn.execute(
+            "INSERT OR REPLACE INTO client_retention (client_id, jurisdiction, discharged_on, updated_at)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![&profile.client_id, jurisdiction, discharged_on, chrono::Utc::now().timestamp_millis()],
+        )?;
+        let value = format!("{}|{}", jurisdiction.unwrap_or_default(), discharged_on.unwrap_or_default());
+        crate::audit::log_retention_setting_change(
+            conn,
+            crate::models::AuditResourceType::Client,
+            &profile.client_id,
+            "retention_profile",
+            &crypto::hash_sha256(value.as_bytes()),
+        ).map_err(|e| VaultError::Internal(format!("Audit write failed: {}", e)))?;
+        Ok(())
+    }
+    
+    fn client_retention_profile(&self, client_id: &str) -> Result<crate::retention::ClientRetentionProfile, VaultError> {
+        let conn = self.conn()?;
+        let row: Option<(Option<String>, Option<String>)> = conn.query_row(
+            "SELECT jurisdiction, discharged_on FROM client_retention WHERE client_id = ?1",
+            [client_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
         ).optional()?;
-        
-        Ok(result)
+        let (jurisdiction, discharged_on) = row.unwrap_or_default();
+        Ok(crate::retention::ClientRetentionProfile {
+            client_id: client_id.to_string(),
+            jurisdiction,
+            discharged_on: discharged_on.and_then(|d| chrono::NaiveDate::parse_from_str(&d, "%Y-%m-%d").ok()),
+        })
     }
     
-    /// Get client visit count since a date
-    pub fn get_client_visit_count_since(&self, client_id: &str, since_date: &str) -> Result<i32, VaultError> {
+    pub fn retention_status(&self, client_id: &str, today: chrono::NaiveDate) -> Result<crate::retention::RetentionStatus, VaultError> {
         let conn = self.conn()?;
-        
-        let count: i32 = conn.query_row(
-            "SELECT COUNT(*) FROM notes WHERE client_id = ?1 AND session_date >= ?2",
-            params![client_id, since_date],
-            |row| row.get(0)
+        let client = self.get_client(client_id)?;
+        let profile = self.client_retention_profile(client_id)?;
+        let parse = |d: Option<String>| d.and_then(|d| chrono::NaiveDate::parse_from_str(&d, "%Y-%m-%d").ok());
+        
+        let (last_termination, last_session): (Option<String>, Option<String>) = conn.query_row(
+            "SELECT MAX(CASE WHEN note_type = 'termination' THEN session_date END), MAX(session_date)
+             FROM notes WHERE client_id = ?1",
+            [client_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
         )?;
+        let dates = crate::retention::ChartDates {
+            client_status: client.status.clone(),
+            date_of_birth: parse(client.date_of_birth.clone()),
+            last_termination_note: parse(last_termination),
+            last_session: parse(last_session),
+        };
         
-        Ok(count)
+        Ok(crate::retention::evaluate(
+            &self.retention_schedule()?,
+            &profile,
+            &dates,
+            self.has_active_legal_hold(client_id)?,
+            today,
+        ))
     }
     
-    // ============================================
-    // Note Operations
-    // ============================================
+    /// Retention status of every chart not already destroyed
+    pub fn destruction_eligibility_report(&self, today: chrono::NaiveDate) -> Result<Vec<crate::retention::RetentionStatus>, VaultError> {
+        self.list_clients()?
+            .into_iter()
+            .filter(|c| c.status != crate::retention::DESTROYED_STATUS)
+            .map(|c| self.retention_status(&c.id, today))
+            .collect()
+    }
     
-    /// Sanitize note content by removing dangerous tokens
-    /// This strips QA/TRAP tokens, injection attempts, etc.
-    fn sanitize_note_content(content: &str) -> String {
-        let mut sanitized = content.to_string();
-        
-        // Remove entire lines containing QA/TRAP marker
-        let lines: Vec<&str> = sanitized.lines().collect();
-        let filtered_lines: Vec<&str> = lines.into_iter()
-            .filter(|line| {
-                let lower = line.to_lowercase();
-                !lower.contains("qa/trap") &&
-                !lower.contains("trap token") &&
-                !lower.contains("test token")
-            })
-            .collect();
-        sanitized = filtered_lines.join("\n");
-        
-        // Remove common injection patterns (case insensitive)
-        let dangerous_patterns = [
-            r"(?i)\.\.%2f",
-            r"(?i)%2e%2e",
-            r"(?i)javascript:",
-            r"(?i)file:///",
-            r"(?i)data:",
-            r"(?i)<script",
-            r"(?i)onclick=",
-            r"(?i)onerror=",
-            r"(?i)C:\\Users",
-            r"(?i)C:/Users",
-            r"(?i)\\x[0-9a-f]{2}",
-        ];
-        
-        for pattern_str in &dangerous_patterns {
-            if let Ok(re) = regex::Regex::new(pattern_str) {
-                sanitized = re.replace_all(&sanitized, "[REMOVED]").to_string();
-            }
+    pub fn has_active_legal_hold(&self, client_id: &str) -> Result<bool, VaultError> {
+        let conn = self.conn()?;
+        let count: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM legal_holds WHERE client_id = ?1 AND released_at IS NULL",
+            [client_id],
+            |row| row.get(0),
+        )?;
+        Ok(count > 0)
+    }
+    
+    /// Refuse chart edits while a legal hold is in place
+    fn ensure_not_on_legal_hold(&self, client_id: &str) -> Result<(), VaultError> {
+        if self.has_active_legal_hold(client_id)? {
+            return Err(VaultError::InvalidState(
+                "This chart is under legal hold; edits and destruction are frozen until the hold is released".to_string()
+            ));
         }
-        
-        // Clean up any resulting empty lines or excessive whitespace
-        let lines: Vec<&str> = sanitized.lines()
-            .map(|l| l.trim_end())
-            .filter(|l| !l.is_empty() || true) // Keep structure
-            .collect();
-        
-        lines.join("\n").trim().to_string()
+        Ok(())
     }
     
-    pub fn create_note(
-        &self,
-        client_id: &str,
-        session_date: &str,
-        note_type: NoteType,
-        raw_input: &str,
-    ) -> Result<Note, VaultError> {
+    fn ensure_note_not_on_legal_hold(&self, note_id: &str) -> Result<(), VaultError> {
         let conn = self.conn()?;
+        let client_id: Option<String> = conn.query_row(
+            "SELECT client_id FROM notes WHERE id = ?1",
+            [note_id],
+            |row| row.get(0),
+        ).optional()?;
+        match client_id {
+            Some(client_id) => self.ensure_not_on_legal_hold(&client_id),
+            None => Ok(()),
+        }
+    }
+    
+    pub fn place_legal_hold(&self, client_id: &str, matter: &str, placed_by: &str) -> Result<crate::retention::LegalHold, VaultError> {
+        let conn = self.conn()?;
+        self.get_client(client_id)?;
+        let (matter, placed_by) = (matter.trim(), placed_by.trim());
+        if matter.is_empty() || placed_by.is_empty() {
+            return Err(VaultError::InvalidState("A legal hold needs a matter and who placed it".to_string()));
+        }
         
-        // Sanitize content before saving
-        let sanitized_content = Self::sanitize_note_content(raw_input);
-        
-        let id = uuid::Uuid::new_v4().to_string();
-        let now = chrono::Utc::now().timestamp_millis();
-        let content_hash = crypto::hash_sha256(sanitized_content.as_bytes());
-        let word_count = sanitized_content.split_whitespace().count() as i32;
+        let id = crate::ids::new_id();
+        let entry = crate::audit::log_retention_event(
+            conn,
+            crate::models::AuditEventType::LegalHoldPlaced,
+            client_id,
+            &format!("hold:{}", id),
+            &crypto::hash_sha256(matter.as_bytes()),
+        ).map_err(|e| VaultError::Internal(format!("Audit write failed: {}", e)))?;
         
+        let hold = crate::retention::LegalHold {
+            id,
+            client_id: client_id.to_string(),
+            matter: matter.to_string(),
+            placed_by: placed_by.to_string(),
+            placed_at: chrono::Utc::now().timestamp_millis(),
+            audit_entry_id: entry.id,
+            released_at: None,
+            released_by: None,
+        };
         conn.execute(
-            "INSERT INTO notes (id, client_id, session_date, note_type, raw_input, word_count, 
-             status, content_hash, created_at, updated_at) 
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
-            params![
-                &id, client_id, session_date, note_type.to_string(), &sanitized_content,
-                word_count, "draft", &content_hash, now, now
-            ],
+            "INSERT INTO legal_holds (id, client_id, matter, placed_by, placed_at, audit_entry_id)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![&hold.id, &hold.client_id, &hold.matter, &hold.placed_by, hold.placed_at, &hold.audit_entry_id],
         )?;
+        Ok(hold)
+    }
+    
+    pub fn release_legal_hold(&self, hold_id: &str, released_by: &str) -> Result<crate::retention::LegalHold, VaultError> {
+        let conn = self.conn()?;
+        let hold = self.get_legal_hold(hold_id)?;
+        if hold.released_at.is_some() {
+            return Err(VaultError::InvalidState("Legal hold already released".to_string()));
+        }
+        let released_by = released_by.trim();
+        if released_by.is_empty() {
+            return Err(VaultError::InvalidState("Name who is releasing the hold".to_string()));
+        }
         
-        // Update client session count
+        crate::audit::log_retention_event(
+            conn,
+            crate::models::AuditEventType::LegalHoldReleased,
+            &hold.client_id,
+            &format!("hold:{}", hold.id),
+            &crypto::hash_sha256(hold.matter.as_bytes()),
+        ).map_err(|e| VaultError::Internal(format!("Audit write failed: {}", e)))?;
         conn.execute(
-            "UPDATE clients SET session_count = session_count + 1, updated_at = ?1 WHERE id = ?2",
-            params![now, client_id],
+            "UPDATE legal_holds SET released_at = ?1, released_by = ?2 WHERE id = ?3",
+            params![chrono::Utc::now().timestamp_millis(), released_by, hold_id],
         )?;
-        
-        Ok(Note {
-            id,
-            client_id: client_id.to_string(),
-            session_date: session_date.to_string(),
-            note_type,
-            raw_input: sanitized_content,
-            structured_note: None,
-            word_count,
-            status: NoteStatus::Draft,
-            detection_ids: vec![],
-            attestations: vec![],
-            content_hash,
-            signed_at: None,
-            created_at: now,
-            updated_at: now,
-        })
+        self.get_legal_hold(hold_id)
     }
     
-    pub fn get_note(&self, id: &str) -> Result<Note, VaultError> {
+    fn get_legal_hold(&self, hold_id: &str) -> Result<crate::retention::LegalHold, VaultError> {
+        self.query_legal_holds("WHERE id = ?1", params![hold_id])?
+            .pop()
+            .ok_or_else(|| VaultError::NotFound(format!("Legal hold {}", hold_id)))
+    }
+    
+    pub fn list_legal_holds(&self, client_id: Option<&str>, active_only: bool) -> Result<Vec<crate::retention::LegalHold>, VaultError> {
+        let active = if active_only { " AND released_at IS NULL" } else { "" };
+        self.query_legal_holds(&format!("WHERE (?1 IS NULL OR client_id = ?1){}", active), params![client_id])
+    }
+    
+    fn query_legal_holds<P: rusqlite::Params>(&self, filter: &str, params: P) -> Result<Vec<crate::retention::LegalHold>, VaultError> {
         let conn = self.conn()?;
-        
-        conn.query_row(
-            "SELECT id, client_id, session_date, note_type, raw_input, structured_note,
-             word_count, status, detection_ids, attestations, content_hash, signed_at, 
-             created_at, updated_at FROM notes WHERE id = ?1",
-            params![id],
-            |row| {
-                let detection_ids_json: Option<String> = row.get(8)?;
-                let attestations_json: Option<String> = row.get(9)?;
-                
-                Ok(Note {
-                    id: row.get(0)?,
-                    client_id: row.get(1)?,
-                    session_date: row.get(2)?,
-                    note_type: NoteType::from_str(&row.get::<_, String>(3)?),
-                    raw_input: row.get(4)?,
-                    structured_note: row.get(5)?,
-                    word_count: row.get(6)?,
-                    status: NoteStatus::from_str(&row.get::<_, String>(7)?),
-                    detection_ids: detection_ids_json
-                        .map(|j| serde_json::from_str(&j).unwrap_or_default())
-                        .unwrap_or_default(),
-                    attestations: attestations_json
-                        .map(|j| serde_json::from_str(&j).unwrap_or_default())
-                        .unwrap_or_default(),
-                    content_hash: row.get(10)?,
-                    signed_at: row.get(11)?,
-                    created_at: row.get(12)?,
-                    updated_at: row.get(13)?,
-                })
-            }
-        ).map_err(|_| VaultError::NotFound(format!("Note {}", id)))
+        let mut stmt = conn.prepare(&format!(
+            "SELECT id, client_id, matter, placed_by, placed_at, audit_entry_id, released_at, released_by
+             FROM legal_holds {} ORDER BY placed_at DESC",
+            filter
+        ))?;
+        let holds = stmt.query_map(params, |row| Ok(crate::retention::LegalHold {
+            id: row.get(0)?,
+            client_id: row.get(1)?,
+            matter: row.get(2)?,
+            placed_by: row.get(3)?,
+            placed_at: row.get(4)?,
+            audit_entry_id: row.get(5)?,
+            released_at: row.get(6)?,
+            released_by: row.get(7)?,
+        }))?.collect::<Result<Vec<_>, _>>()?;
+        Ok(holds)
     }
     
-    pub fn list_notes(&self, client_id: Option<&str>) -> Result<Vec<Note>, VaultError> {
+    /// Destroy a chart whose retention period has ended. Note and document
+    /// content and everything derived from it are deleted; the client row
+    /// stays, scrubbed, so audit entries and the certificate still resolve.
+    /// The audit entry over the manifest is written before anything is
+    /// deleted, and the deletes run in one transaction.
+    pub fn destroy_client_records(
+        &self,
+        client_id: &str,
+        authorized_by: &str,
+        today: chrono::NaiveDate,
+    ) -> Result<crate::retention::DestructionCertificate, VaultError> {
         let conn = self.conn()?;
-        
-        let sql = match client_id {
-            Some(_) => "SELECT id, client_id, session_date, note_type, raw_input, structured_note,
-                        word_count, status, detection_ids, attestations, content_hash, signed_at,
-                        created_at, updated_at FROM notes WHERE client_id = ?1 ORDER BY session_date DESC",
-            None => "SELECT id, client_id, session_date, note_type, raw_input, structured_note,
-                     word_count, status, detection_ids, attestations, content_hash, signed_at,
-                     created_at, updated_at FROM notes ORDER BY session_date DESC",
+        let authorized_by = authorized_by.trim();
+        if authorized_by.is_empty() {
+            return Err(VaultError::InvalidState("Name who authorized the destruction".to_string()));
+        }
+        self.ensure_not_on_legal_hold(client_id)?;
+        let status = self.retention_status(client_id, today)?;
+        let (true, Some(discharged_on), Some(retain_until)) =
+            (status.eligible_for_destruction, status.discharged_on, status.retain_until) else {
+            return Err(VaultError::InvalidState(format!("Chart is not eligible for destruction: {}", status.reason)));
         };
         
-        let mut stmt = conn.prepare(sql)?;
+        let mut manifest = Vec::new();
+        let mut stmt = conn.prepare("SELECT id, content_hash FROM notes WHERE client_id = ?1")?;
+        for row in stmt.query_map([client_id], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))? {
+            let (id, hash) = row?;
+            manifest.push(format!("note:{}:{}", id, hash));
+        }
+        let notes_destroyed = manifest.len() as u32;
+        let mut stmt = conn.prepare("SELECT id, content_hash FROM client_documents WHERE client_id = ?1")?;
+        for row in stmt.query_map([client_id], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))? {
+            let (id, hash) = row?;
+            manifest.push(format!("document:{}:{}", id, hash));
+        }
+        let documents_destroyed = manifest.len() as u32 - notes_destroyed;
+        let manifest_sha256 = crate::retention::manifest_sha256(manifest);
         
-        let row_mapper = |row: &rusqlite::Row| {
-            let detection_ids_json: Option<String> = row.get(8)?;
-            let attestations_json: Option<String> = row.get(9)?;
-            
-            Ok(Note {
-                id: row.get(0)?,
-                client_id: row.get(1)?,
-                session_date: row.get(2)?,
-                note_type: NoteType::from_str(&row.get::<_, String>(3)?),
-                raw_input: row.get(4)?,
-                structured_note: row.get(5)?,
-                word_count: row.get(6)?,
-                status: NoteStatus::from_str(&row.get::<_, String>(7)?),
-                detection_ids: detection_ids_json
-                    .map(|j| serde_json::from_str(&j).unwrap_or_default())
-                    .unwrap_or_default(),
-                attestations: attestations_json
-                    .map(|j| serde_json::from_str(&j).unwrap_or_default())
-                    .unwrap_or_default(),
-                content_hash: row.get(10)?,
-                signed_at: row.get(11)?,
-                created_at: row.get(12)?,
-                updated_at: row.get(13)?,
-            })
-        };
+        let entry = crate::audit::log_retention_event(
+            conn,
+            crate::models::AuditEventType::RecordsDestroyed,
+            client_id,
+            &format!("destroyed:notes={},documents={}", notes_destroyed, documents_destroyed),
+            &manifest_sha256,
+        ).map_err(|e| VaultError::Internal(format!("Audit write failed: {}", e)))?;
         
-        let rows = match client_id {
-            Some(cid) => stmt.query_map(params![cid], row_mapper)?,
-            None => stmt.query_map([], row_mapper)?,
+        let now = chrono::Utc::now().timestamp_millis();
+        let mut certificate = crate::retention::DestructionCertificate {
+            id: crate::ids::new_id(),
+            client_id: client_id.to_string(),
+            jurisdiction: status.jurisdiction,
+            discharged_on,
+            retain_until,
+            destroyed_at: now,
+            authorized_by: authorized_by.to_string(),
+            method: crate::retention::DESTRUCTION_METHOD.to_string(),
+            notes_destroyed,
+            documents_destroyed,
+            manifest_sha256,
+            audit_entry_id: entry.id,
+            audit_entry_hash: entry.entry_hash,
+            certificate_sha256: String::new(),
         };
+        certificate.certificate_sha256 = certificate.compute_sha256();
+        let certificate_json = serde_json::to_string(&certificate)
+            .map_err(|e| VaultError::Serialization(e.to_string()))?;
         
-        rows.collect::<Result<Vec<_>, _>>().map_err(VaultError::from)
+        // Overwrite freed pages rather than leave the chart in the free list
+        let secure_delete: i64 = conn.query_row("PRAGMA secure_delete", [], |row| row.get(0))?;
+        conn.pragma_update(None, "secure_delete", true)?;
+        let destroyed = Self::delete_destroyed_chart(conn, client_id, &certificate, &certificate_json, now);
+        conn.pragma_update(None, "secure_delete", secure_delete)?;
+        destroyed?;
+        crate::fulltext::optimize_index(conn)?;
+        self.replace_backups_after_destruction();
+        
+        Ok(certificate)
     }
     
-    pub fn update_note(&self, id: &str, raw_input: &str) -> Result<Note, VaultError> {
-        let conn = self.conn()?;
-        
-        // Sanitize content
-        let sanitized_content = Self::sanitize_note_content(raw_input);
-        
-        let now = chrono::Utc::now().timestamp_millis();
-        let content_hash = crypto::hash_sha256(sanitized_content.as_bytes());
-        let word_count = sanitized_content.split_whitespace().count() as i32;
-        
-        conn.execute(
-            "UPDATE notes SET raw_input = ?1, word_count = ?2, content_hash = ?3, updated_at = ?4 
-             WHERE id = ?5",
-            params![&sanitized_content, word_count, &content_hash, now, id],
+    fn delete_destroyed_chart(
+        conn: &Connection,
+        client_id: &str,
+        certificate: &crate::retention::DestructionCertificate,
+        certificate_json: &str,
+        now: i64,
+    ) -> Result<(), VaultError> {
+        let tx = conn.unchecked_transaction()?;
+        Self::delete_client_chart(&tx, client_id)?;
+        for table in ["chart_access_grants", "emergency_access_sessions", "cohort_attendance", "cohort_memberships"] {
+            tx.execute(&format!("DELETE FROM {} WHERE client_id = ?1", table), [client_id])?;
+        }
+        tx.execute(
+            "UPDATE clients SET display_name = ?2, status = ?3, updated_at = ?4, date_of_birth = NULL,
+                 phone = NULL, email = NULL, emergency_contact = NULL, insurance_info = NULL,
+                 diagnosis_codes = NULL, treatment_start_date = NULL, referring_provider = NULL, notes = NULL
+             WHERE id = ?1",
+            params![client_id, format!("Destroyed record {}", crate::ids::short_id(&certificate.id)),
+                    crate::retention::DESTROYED_STATUS, now],
         )?;
-        
-        self.get_note(id)
+        tx.execute(
+            "INSERT INTO destruction_certificates (id, client_id, certificate_json, created_at) VALUES (?1, ?2, ?3, ?4)",
+            params![&certificate.id, client_id, certificate_json, now],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+    
+    pub fn list_destruction_certificates(&self) -> Result<Vec<crate::retention::DestructionCertificate>, VaultError> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare("SELECT certificate_json FROM destruction_certificates ORDER BY created_at DESC")?;
+        let certificates = stmt.query_map([], |row| row.get::<_, String>(0))?
+            .map(|json| {
+                let json = json?;
+                serde_json::from_str(&json).map_err(|e| VaultError::Serialization(e.to_string()))
+            })
+            .collect::<Result<Vec<_>, VaultError>>()?;
+        Ok(certificates)
+    }
+    
+    // ============================================
+    // Idempotency Keys
+    // ============================================
+    
+    pub fn get_idempotency_record(&self, key: &str) -> Result<Option<crate::idempotency::IdempotencyRecord>, VaultError> {
+        let conn = self.conn()?;
+        let record = conn.query_row(
+            "SELECT key, command, request_hash, result_json, result_hash, created_at, expires_at
+             FROM idempotency_keys WHERE key = ?1",
+            [key],
+            |row| Ok(crate::idempotency::IdempotencyRecord {
+                key: row.get(0)?,
+                command: row.get(1)?,
+                request_hash: row.get(2)?,
+                result_json: row.get(3)?,
+                result_hash: row.get(4)?,
+                created_at: row.get(5)?,
+                expires_at: row.get(6)?,
+            }),
+        ).optional()?;
+        Ok(record)
     }
     
-    pub fn update_note_structured(&self, id: &str, structured: &str) -> Result<Note, VaultError> {
+    /// Store a processed key, replacing an expired record for the same key
+    /// and dropping any others past their TTL
+    pub fn store_idempotency_record(&self, record: &crate::idempotency::IdempotencyRecord) -> Result<(), VaultError> {
         let conn = self.conn()?;
-        
-        // Sanitize structured content too
-        let sanitized = Self::sanitize_note_content(structured);
-        
-        let now = chrono::Utc::now().timestamp_millis();
-        
+        conn.execute("DELETE FROM idempotency_keys WHERE expires_at <= ?1", [record.created_at])?;
         conn.execute(
-            "UPDATE notes SET structured_note = ?1, updated_at = ?2 WHERE id = ?3",
-            params![&sanitized, now, id],
+            "INSERT OR REPLACE INTO idempotency_keys
+             (key, command, request_hash, result_json, result_hash, created_at, expires_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![&record.key, &record.command, &record.request_hash, &record.result_json,
+                    &record.result_hash, record.created_at, record.expires_at],
         )?;
-        
-        self.get_note(id)
+        Ok(())
     }
     
-    pub fn update_note_detections(&self, id: &str, detection_ids: &[String]) -> Result<(), VaultError> {
+    // ============================================
+    // Maintenance
+    // ============================================
+    
+    pub fn maintenance_schedule(&self) -> Result<crate::maintenance::MaintenanceSchedule, VaultError> {
         let conn = self.conn()?;
-        let now = chrono::Utc::now().timestamp_millis();
-        let json = serde_json::to_string(detection_ids)
+        let json: Option<String> = conn.query_row(
+            "SELECT value FROM settings WHERE key = 'maintenance_schedule'",
+            [],
+            |row| row.get(0),
+        ).optional()?;
+        Ok(json.and_then(|j| serde_json::from_str(&j).ok()).unwrap_or_default())
+    }
+    
+    pub fn set_maintenance_schedule(&self, schedule: &crate::maintenance::MaintenanceSchedule) -> Result<(), VaultError> {
+        let conn = self.conn()?;
+        let json = serde_json::to_string(schedule)
             .map_err(|e| VaultError::Serialization(e.to_string()))?;
-        
         conn.execute(
-            "UPDATE notes SET detection_ids = ?1, updated_at = ?2 WHERE id = ?3",
-            params![json, now, id],
+            "INSERT OR REPLACE INTO settings (key, value) VALUES ('maintenance_schedule', ?1)",
+            [&json],
         )?;
-        
         Ok(())
     }
     
-    pub fn sign_note(&self, id: &str, attestations_json: &str) -> Result<Note, VaultError> {
-        let conn = self.conn()?;
-        let now = chrono::Utc::now().timestamp_millis();
+    /// PRAGMA optimize and ANALYZE; VACUUM only when enough of the file is
+    /// free pages to be worth rewriting it
+    pub fn maintenance_optimize(&self) -> Result<String, VaultError> {
+        const VACUUM_FREE_PERCENT: i64 = 20;
         
-        conn.execute(
-            "UPDATE notes SET status = 'signed', attestations = ?1, signed_at = ?2, updated_at = ?2 
-             WHERE id = ?3",
-            params![attestations_json, now, id],
+        let conn = self.conn()?;
+        let pages: i64 = conn.query_row("PRAGMA page_count", [], |row| row.get(0))?;
+        let free: i64 = conn.query_row("PRAGMA freelist_count", [], |row| row.get(0))?;
+        conn.execute_batch("PRAGMA optimize; ANALYZE;")?;
+        
+        if pages > 0 && free * 100 / pages >= VACUUM_FREE_PERCENT {
+            conn.execute("VACUUM", [])?;
+            // VACUUM may renumber note rowids, which the index is keyed by
+            crate::fulltext::rebuild_index(conn)?;
+            Ok(format!("Analyzed and vacuumed; reclaimed {} of {} pages", free, pages))
+        } else {
+            Ok(format!("Analyzed; {} of {} pages free, vacuum not needed", free, pages))
+        }
+    }
+    
+    /// Permanently delete notes and clients whose trash retention window
+    /// has passed
+    pub fn maintenance_purge_trash(&self) -> Result<String, VaultError> {
+        let (notes, clients) = self.purge_expired_trash(chrono::Utc::now().timestamp_millis())?;
+        Ok(format!(
+            "Purged {} notes and {} clients trashed more than {} days ago",
+            notes, clients, TRASH_RETENTION_DAYS
+        ))
+    }
+    
+    /// Remove index rows that no longer match their sources: embeddings of
+    /// deleted notes and derived artifacts built by an older release
+    pub fn maintenance_search_index(&self) -> Result<String, VaultError> {
+        let conn = self.conn()?;
+        let embeddings = conn.execute(
+            "DELETE FROM embeddings WHERE note_id NOT IN (SELECT id FROM notes)",
+            [],
         )?;
-        
-        self.get_note(id)
+        // Only entries stamped with a rules version; model/prompt-versioned
+        // entries are checked against their own versions on read
+        let cached = conn.execute(
+            "DELETE FROM derived_cache
+             WHERE instr(';' || versions || ';', ';rules=') > 0
+               AND instr(';' || versions || ';', ';' || ?1 || ';') = 0",
+            [DERIVED_RULES_VERSION],
+        )?;
+        Ok(format!("Removed {} orphaned embeddings and {} stale cached artifacts", embeddings, cached))
     }
     
-    /// Amend a signed note - creates an amendment record and updates content
-    /// Signed notes cannot be directly edited, only amended
-    pub fn amend_note(&self, id: &str, amendment_text: &str, reason: &str) -> Result<Note, VaultError> {
+    /// Verify the audit chain from the last checkpoint to the head and
+    /// record the head as the new checkpoint. The checkpointed entry must
+    /// still carry the hash recorded for it, so rewriting history before a
+    /// checkpoint is caught without re-walking the whole log.
+    pub fn maintenance_audit_checkpoint(&self) -> Result<String, VaultError> {
         let conn = self.conn()?;
-        let note = self.get_note(id)?;
-        
-        // Only signed notes can be amended
-        if note.status != NoteStatus::Signed {
-            return Err(VaultError::InvalidState("Only signed notes can be amended".to_string()));
-        }
-        
-        let now = chrono::Utc::now().timestamp_millis();
-        let sanitized_amendment = Self::sanitize_note_content(amendment_text);
+        let last: Option<(i64, String)> = conn.query_row(
+            "SELECT sequence, entry_hash FROM audit_checkpoints ORDER BY sequence DESC LIMIT 1",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        ).optional()?;
         
-        // Format amendment with audit trail
-        let amendment_record = format!(
-            "\n\n--- AMENDMENT ({}) ---\nReason: {}\nAmended: {}\n\n{}",
-            chrono::DateTime::from_timestamp_millis(now)
-                .map(|dt| dt.format("%Y-%m-%d %H:%M:%S UTC").to_string())
-                .unwrap_or_else(|| "Unknown".to_string()),
-            reason,
-            chrono::DateTime::from_timestamp_millis(now)
-                .map(|dt| dt.format("%Y-%m-%d %H:%M:%S UTC").to_string())
-                .unwrap_or_else(|| "Unknown".to_string()),
-            sanitized_amendment
-        );
+        let entries = crate::audit::get_entries_from(conn, last.as_ref().map(|(seq, _)| *seq).unwrap_or(1))
+            .map_err(|e| VaultError::Internal(e.to_string()))?;
+        let Some(head) = entries.last() else {
+            return Ok("Audit log is empty; nothing to checkpoint".to_string());
+        };
         
-        // Append amendment to existing content
-        let new_content = format!("{}{}", note.raw_input, amendment_record);
-        let new_hash = crypto::hash_sha256(new_content.as_bytes());
-        let new_word_count = new_content.split_whitespace().count() as i32;
+        match &last {
+            Some((sequence, hash)) => {
+                let anchored = entries.first()
+                    .map(|e| e.sequence == *sequence && crypto::digests_match(&e.entry_hash, hash))
+                    .unwrap_or(false);
+                if !anchored {
+                    return Err(VaultError::InvalidState(format!(
+                        "Audit entry {} no longer matches its checkpoint", sequence
+                    )));
+                }
+            }
+            None if entries[0].previous_hash != "genesis" => {
+                return Err(VaultError::InvalidState("Audit log does not start at genesis".to_string()));
+            }
+            None => {}
+        }
+        crate::audit::verify_segment(&entries)
+            .map_err(|e| VaultError::InvalidState(format!("Audit chain: {}", e)))?;
         
+        if last.as_ref().map(|(seq, _)| *seq == head.sequence).unwrap_or(false) {
+            return Ok(format!("Verified; no new entries since checkpoint at sequence {}", head.sequence));
+        }
         conn.execute(
-            "UPDATE notes SET raw_input = ?1, word_count = ?2, content_hash = ?3, updated_at = ?4, status = 'amended'
-             WHERE id = ?5",
-            params![&new_content, new_word_count, &new_hash, now, id],
+            "INSERT INTO audit_checkpoints (sequence, entry_hash, created_at) VALUES (?1, ?2, ?3)",
+            params![head.sequence, &head.entry_hash, chrono::Utc::now().timestamp()],
         )?;
-        
-        // Log the amendment in audit
-        crate::audit::log_event(
-            &conn,
-            crate::models::AuditEventType::NoteUpdated,
-            crate::models::AuditResourceType::Note,
-            id,
-            crate::models::AuditOutcome::Success,
-            None, // No detection IDs for amendment
-        ).ok(); // Don't fail on audit error
-        
-        self.get_note(id)
+        Ok(format!("Verified {} entries; checkpoint at sequence {}", entries.len(), head.sequence))
     }
     
-    // ============================================
-    // Treatment Progress Analysis
-    // ============================================
-    
-    /// Analyze treatment progress for a client based on their session notes
-    pub fn get_treatment_progress(&self, client_id: &str) -> Result<TreatmentProgress, VaultError> {
+    /// Copy the (SQLCipher-encrypted) database to `backups/`, verify the
+    /// copy by hash and keep the newest `keep` backups. A backup opens with
+    /// the same passphrase as the vault it came from.
+    pub fn maintenance_backup(&self, keep: usize) -> Result<String, VaultError> {
         let conn = self.conn()?;
-        let client = self.get_client(client_id)?;
-        
-        // Get all notes for client ordered by date (include ID for theme linking)
-        let mut stmt = conn.prepare(
-            "SELECT id, session_date, raw_input, created_at FROM notes 
-             WHERE client_id = ?1 
-             ORDER BY session_date ASC"
-        )?;
-        
-        let notes: Vec<(String, String, String, i64)> = stmt.query_map(params![client_id], |row| {
-            Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
-        })?.filter_map(|r| r.ok()).collect();
-        
-        if notes.is_empty() {
-            return Ok(TreatmentProgress {
-                client_id: client_id.to_string(),
-                client_name: client.display_name,
-                total_sessions: 0,
-                date_range: None,
-                session_frequency: None,
-                themes: vec![],
-                risk_trajectory: Some("insufficient_data".to_string()),
-                ai_summary: None,
-            });
+        // Fold any WAL content into the main file so one file is a full copy
+        conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(())).optional()?;
+        
+        let dir = self.data_dir.join("backups");
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| VaultError::Internal(format!("Cannot create backup directory: {}", e)))?;
+        let name = format!("vault-{}.db", chrono::Utc::now().format("%Y%m%dT%H%M%SZ"));
+        let target = dir.join(&name);
+        
+        std::fs::copy(self.vault_path(), &target)
+            .map_err(|e| VaultError::Internal(format!("Backup copy failed: {}", e)))?;
+        let read = |path: &std::path::Path| {
+            std::fs::read(path).map_err(|e| VaultError::Internal(format!("Backup verification failed: {}", e)))
+        };
+        let original = crypto::hash_sha256(&read(&self.vault_path())?);
+        if !crypto::digests_match(&original, &crypto::hash_sha256(&read(&target)?)) {
+            std::fs::remove_file(&target).ok();
+            return Err(VaultError::Internal("Backup copy does not match the vault".to_string()));
         }
         
-        let total_sessions = notes.len() as i32;
-        let first_date = notes.first().map(|(_, d, _, _)| d.clone());
-        let last_date = notes.last().map(|(_, d, _, _)| d.clone());
+        let backups = Self::list_maintenance_backups(&dir)
+            .map_err(|e| VaultError::Internal(e.to_string()))?;
+        let excess = backups.len().saturating_sub(keep.max(1));
+        for old in &backups[..excess] {
+            std::fs::remove_file(old).ok();
+        }
         
-        // Calculate session frequency (average days between sessions)
-        let session_frequency = if notes.len() > 1 {
-            if let (Some(first), Some(last)) = (&first_date, &last_date) {
-                if let (Ok(first_dt), Ok(last_dt)) = (
-                    chrono::NaiveDate::parse_from_str(first, "%Y-%m-%d"),
-                    chrono::NaiveDate::parse_from_str(last, "%Y-%m-%d")
-                ) {
-                    let days = (last_dt - first_dt).num_days() as f64;
-                    Some(days / (notes.len() - 1) as f64)
-                } else {
-                    None
-                }
-            } else {
+        Ok(format!("Backed up to {} ({} kept)", name, backups.len() - excess))
+    }
+    
+    /// Maintenance backups taken before a records destruction still hold
+    /// the destroyed chart. Take a fresh one and remove the rest; if the
+    /// fresh backup fails they are removed anyway, since the chart must not
+    /// outlive its certificate.
+    fn replace_backups_after_destruction(&self) {
+        let dir = self.data_dir.join("backups");
+        let earlier = match Self::list_maintenance_backups(&dir) {
+            Ok(backups) => backups,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return,
+            Err(e) => {
+                log::warn!("Cannot list backups after records destruction: {}", e);
+                return;
+            }
+        };
+        let fresh = match self.maintenance_backup(usize::MAX) {
+            Ok(_) => Self::list_maintenance_backups(&dir).ok().and_then(|b| b.last().cloned()),
+            Err(e) => {
+                log::warn!("No backup taken after records destruction: {}", e);
                 None
             }
-        } else {
-            None
         };
-        
-        // Analyze themes from note content (with note IDs)
-        let themes = self.extract_treatment_themes(&notes);
-        
-        // Determine risk trajectory based on note content patterns
-        let notes_for_risk: Vec<(String, String, i64)> = notes.iter()
-            .map(|(_, date, content, ts)| (date.clone(), content.clone(), *ts))
-            .collect();
-        let risk_trajectory = self.analyze_risk_trajectory(&notes_for_risk);
-        
-        Ok(TreatmentProgress {
-            client_id: client_id.to_string(),
-            client_name: client.display_name,
-            total_sessions,
-            date_range: first_date.zip(last_date),
-            session_frequency,
-            themes,
-            risk_trajectory: Some(risk_trajectory),
-            ai_summary: None, // Will be populated by AI call if requested
-        })
-    }
-    
-    /// Extract treatment themes from session notes (with note ID tracking)
-    fn extract_treatment_themes(&self, notes: &[(String, String, String, i64)]) -> Vec<ProgressTheme> {
-        // HashMap: theme -> (first_date, count, dates, note_ids)
-        let mut themes: std::collections::HashMap<String, (String, i32, Vec<String>, Vec<String>)> = std::collections::HashMap::new();
-        
-        // Common clinical themes to track
-        let theme_keywords = [
-            ("anxiety", vec!["anxiety", "anxious", "worry", "panic", "nervous"]),
-            ("depression", vec!["depression", "depressed", "sad", "hopeless", "low mood"]),
-            ("sleep", vec!["sleep", "insomnia", "tired", "fatigue", "rest"]),
-            ("relationships", vec!["relationship", "family", "partner", "spouse", "conflict"]),
-            ("work_stress", vec!["work", "job", "career", "boss", "coworker"]),
-            ("trauma", vec!["trauma", "ptsd", "flashback", "nightmare", "abuse"]),
-            ("suicidal_ideation", vec!["si", "suicidal", "suicide", "self-harm", "kill myself", "end my life"]),
-            ("substance_use", vec!["alcohol", "drinking", "drug", "substance", "using"]),
-            ("coping", vec!["coping", "skills", "breathing", "grounding", "mindfulness"]),
-            ("medication", vec!["medication", "med", "prescription", "dose", "side effect"]),
-        ];
-        
-        for (note_id, date, content, _) in notes {
-            let content_lower = content.to_lowercase();
-            
-            for (theme_name, keywords) in &theme_keywords {
-                let mentioned = keywords.iter().any(|kw| content_lower.contains(kw));
-                if mentioned {
-                    let entry = themes.entry(theme_name.to_string())
-                        .or_insert_with(|| (date.clone(), 0, vec![], vec![]));
-                    entry.1 += 1;
-                    entry.2.push(date.clone());
-                    // Add note_id if not already in list
-                    if !entry.3.contains(note_id) {
-                        entry.3.push(note_id.clone());
-                    }
-                }
+        for old in earlier.iter().filter(|p| Some(*p) != fresh.as_ref()) {
+            if let Err(e) = std::fs::remove_file(old) {
+                log::warn!("Backup {} not removed after records destruction: {}", old.display(), e);
             }
         }
-        
-        // Convert to ProgressTheme with trend analysis and note IDs
-        themes.into_iter().map(|(theme, (first_date, count, dates, note_ids))| {
-            let trend = self.calculate_theme_trend(&dates, notes.len());
-            ProgressTheme {
-                theme,
-                first_mentioned: first_date,
-                mention_count: count,
-                trend,
-                note_ids,
-            }
-        }).collect()
     }
     
-    /// Calculate trend for a theme based on when it appears in session sequence
-    fn calculate_theme_trend(&self, dates: &[String], total_sessions: usize) -> String {
-        if dates.len() < 2 {
-            return "insufficient_data".to_string();
-        }
-        
-        // Simple trend: compare first half vs second half mentions
-        let midpoint = total_sessions / 2;
-        let early_mentions = dates.iter().filter(|d| {
-            // Rough comparison - actual implementation would use proper date parsing
-            dates.iter().position(|x| x == *d).unwrap_or(0) < midpoint
-        }).count();
-        let late_mentions = dates.len() - early_mentions;
-        
-        if late_mentions == 0 && early_mentions > 0 {
-            "resolved".to_string()
-        } else if late_mentions > early_mentions * 2 {
-            "worsening".to_string()
-        } else if early_mentions > late_mentions * 2 {
-            "improving".to_string()
-        } else {
-            "stable".to_string()
-        }
+    /// The (SQLCipher-encrypted) database file, with the WAL folded in
+    pub fn database_bytes(&self) -> Result<Vec<u8>, VaultError> {
+        let conn = self.conn()?;
+        conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(())).optional()?;
+        std::fs::read(self.vault_path())
+            .map_err(|e| VaultError::Internal(format!("Cannot read vault database: {}", e)))
     }
     
-    /// Analyze overall risk trajectory
-    fn analyze_risk_trajectory(&self, notes: &[(String, String, i64)]) -> String {
-        if notes.len() < 3 {
-            return "insufficient_data".to_string();
+    /// Write a database being restored beside the vault, for
+    /// `check_database_opens` and `replace_database`
+    pub fn stage_restored_database(&self, bytes: &[u8]) -> Result<PathBuf, VaultError> {
+        std::fs::create_dir_all(&self.data_dir)
+            .map_err(|e| VaultError::Internal(format!("Cannot create data directory: {}", e)))?;
+        let staged = self.data_dir.join("vault.db.restoring");
+        std::fs::write(&staged, bytes)
+            .map_err(|e| VaultError::Internal(format!("Cannot stage restored database: {}", e)))?;
+        Ok(staged)
+    }
+    
+    /// Check that the database at `path` opens with `passphrase` and the
+    /// given keychain entries, without touching the vault
+    pub fn check_database_opens(
+        path: &std::path::Path,
+        passphrase: &str,
+        salt: &[u8; 16],
+        wrapped: &WrappedVaultKey,
+    ) -> Result<(), VaultError> {
+        let vault_key = KEK::derive(passphrase, salt)?.unwrap(wrapped)
+            .map_err(|_| VaultError::InvalidPassphrase)?;
+        let conn = Connection::open(path)?;
+        conn.pragma_update(None, "key", &format!("x'{}'", vault_key.as_hex()))?;
+        conn.query_row("SELECT count(*) FROM notes", [], |_| Ok(()))
+            .map_err(|_| VaultError::InvalidPassphrase)?;
+        Ok(())
+    }
+    
+    /// Lock the vault and swap in a staged database with its keychain
+    /// entries. The current database is renamed `vault.db.pre-restore-<ts>`
+    /// and its path returned; its keychain entries are written beside it
+    /// (see `set_aside_credentials_path`) so it can still be opened once
+    /// the keychain holds the restored vault's. The entries are staged in
+    /// the keychain first, so if storing them fails the next unlock
+    /// finishes the swap.
+    pub fn replace_database(
+        &mut self,
+        staged: &std::path::Path,
+        salt: &[u8; 16],
+        wrapped: &WrappedVaultKey,
+    ) -> Result<Option<PathBuf>, VaultError> {
+        self.lock();
+        
+        let current = self.vault_path();
+        let suffix = format!("pre-restore-{}", chrono::Utc::now().format("%Y%m%dT%H%M%SZ"));
+        let previous_path = self.data_dir.join(format!("vault.db.{}", suffix));
+        if current.exists() {
+            // Without its keychain entries the database set aside could
+            // never be opened again, so keep them or don't restore
+            match crypto::retrieve_salt().and_then(|s| crypto::retrieve_wrapped_key().map(|w| (s, w))) {
+                Ok((old_salt, old_wrapped)) => {
+                    Self::write_set_aside_credentials(&previous_path, &old_salt, &old_wrapped)?;
+                }
+                Err(e) => log::warn!("Current vault has no keychain entries to keep ({}); setting it aside without them", e),
+            }
+        }
+        let set_aside_credentials = Self::set_aside_credentials_path(&previous_path);
+        if let Err(e) = crypto::stage_credentials(salt, wrapped) {
+            std::fs::remove_file(&set_aside_credentials).ok();
+            return Err(e.into());
         }
         
-        let risk_keywords = ["suicidal", "si", "self-harm", "kill", "end my life", "hopeless", "worthless"];
-        
-        // Count risk mentions in first third vs last third
-        let third = notes.len() / 3;
-        let early_notes = &notes[..third];
-        let late_notes = &notes[notes.len() - third..];
+        let previous = if current.exists() {
+            let previous = previous_path;
+            if let Err(e) = std::fs::rename(&current, &previous) {
+                std::fs::remove_file(&set_aside_credentials).ok();
+                crypto::clear_pending_credentials();
+                return Err(VaultError::Internal(format!("Cannot move current vault aside: {}", e)));
+            }
+            // WAL files belong to the database they were written for
+            for side in ["wal", "shm"] {
+                let file = self.data_dir.join(format!("vault.db-{}", side));
+                if file.exists() {
+                    std::fs::rename(&file, self.data_dir.join(format!("vault.db-{}.{}", side, suffix))).ok();
+                }
+            }
+            Some(previous)
+        } else {
+            None
+        };
         
-        let early_risk = early_notes.iter()
-            .filter(|(_, content, _)| {
-                let lower = content.to_lowercase();
-                risk_keywords.iter().any(|kw| lower.contains(kw))
-            })
-            .count();
-            
-        let late_risk = late_notes.iter()
-            .filter(|(_, content, _)| {
-                let lower = content.to_lowercase();
-                risk_keywords.iter().any(|kw| lower.contains(kw))
-            })
-            .count();
+        if let Err(e) = std::fs::rename(staged, &current) {
+            if let Some(previous) = &previous {
+                std::fs::rename(previous, &current).ok();
+                std::fs::remove_file(&set_aside_credentials).ok();
+            }
+            crypto::clear_pending_credentials();
+            return Err(VaultError::Internal(format!("Cannot move restored database into place: {}", e)));
+        }
         
-        if late_risk == 0 && early_risk > 0 {
-            "improving".to_string()
-        } else if late_risk > early_risk {
-            "concerning".to_string()
-        } else if early_risk > late_risk {
-            "improving".to_string()
-        } else {
-            "stable".to_string()
+        match crypto::store_salt(salt).and_then(|_| crypto::store_wrapped_key(wrapped)) {
+            Ok(()) => crypto::clear_pending_credentials(),
+            Err(e) => log::warn!("Restored vault keychain entries still pending: {}", e),
         }
+        Ok(previous)
     }
     
-    // ============================================
-    // Metrics
-    // ============================================
+    /// Where `replace_database` keeps the keychain entries of a database it
+    /// set aside: `<database>.keychain.json`
+    pub fn set_aside_credentials_path(database: &std::path::Path) -> PathBuf {
+        let mut name = database.as_os_str().to_owned();
+        name.push(".keychain.json");
+        PathBuf::from(name)
+    }
     
-    pub fn get_counts(&self) -> Result<(i32, i32), VaultError> {
+    pub(crate) fn write_set_aside_credentials(
+        database: &std::path::Path,
+        salt: &[u8; 16],
+        wrapped: &WrappedVaultKey,
+    ) -> Result<(), VaultError> {
+        let json = serde_json::json!({
+            "salt": hex::encode(salt),
+            "wrapped_key": hex::encode(wrapped.to_bytes()),
+        });
+        std::fs::write(Self::set_aside_credentials_path(database), json.to_string())
+            .map_err(|e| VaultError::Internal(format!("Cannot keep the current vault's keychain entries: {}", e)))
+    }
+    
+    /// Keychain entries kept beside a database set aside by a restore, for
+    /// `check_database_opens` or a later restore of that database
+    pub fn read_set_aside_credentials(
+        database: &std::path::Path,
+    ) -> Result<([u8; 16], WrappedVaultKey), VaultError> {
+        let text = std::fs::read_to_string(Self::set_aside_credentials_path(database))
+            .map_err(|e| VaultError::NotFound(format!("Keychain entries for {}: {}", database.display(), e)))?;
+        let json: serde_json::Value = serde_json::from_str(&text)
+            .map_err(|e| VaultError::Serialization(e.to_string()))?;
+        let field = |name: &str| {
+            json[name].as_str()
+                .and_then(|h| hex::decode(h).ok())
+                .ok_or_else(|| VaultError::Serialization(format!("Set-aside {} is missing or not hex", name)))
+        };
+        let salt: [u8; 16] = field("salt")?.try_into()
+            .map_err(|_| VaultError::Serialization("Set-aside salt is not 16 bytes".to_string()))?;
+        Ok((salt, WrappedVaultKey::from_bytes(&field("wrapped_key")?)?))
+    }
+    
+    /// Point-in-time copy of the vault at `path` (which must not exist),
+    /// encrypted with the vault key, opened read-only as its own Vault.
+    /// Queries on the copy neither wait for nor block this connection.
+    pub fn open_snapshot(&self, path: &std::path::Path) -> Result<Vault, VaultError> {
         let conn = self.conn()?;
+        let vault_key = self.vault_key.as_ref().ok_or(VaultError::Locked)?;
+        let key = format!("x'{}'", vault_key.as_hex());
+        
+        conn.execute("ATTACH DATABASE ?1 AS snapshot KEY ?2", params![path.to_string_lossy(), &key])?;
+        let exported = conn.query_row("SELECT sqlcipher_export('snapshot')", [], |_| Ok(()));
+        conn.execute("DETACH DATABASE snapshot", [])?;
+        if let Err(e) = exported {
+            std::fs::remove_file(path).ok();
+            return Err(e.into());
+        }
         
-        let client_count: i32 = conn.query_row(
-            "SELECT COUNT(*) FROM clients",
-            [],
-            |row| row.get(0),
-        )?;
-        
-        let note_count: i32 = conn.query_row(
-            "SELECT COUNT(*) FROM notes",
-            [],
-            |row| row.get(0),
+        let snapshot = Connection::open_with_flags(
+            path,
+            rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY | rusqlite::OpenFlags::SQLITE_OPEN_NO_MUTEX,
         )?;
-        
-        Ok((client_count, note_count))
+        snapshot.pragma_update(None, "key", &key)?;
+        snapshot.query_row("SELECT count(*) FROM sqlite_master", [], |_| Ok(()))?;
+        Ok(Vault {
+            conn: Some(snapshot),
+            vault_key: Some(VaultKey(vault_key.0)),
+            data_dir: self.data_dir.clone(),
+        })
     }
     
-    // ============================================
-    // Session Metrics (Time Tracking)
-    // ============================================
+    /// What a snapshot can be taken from without this connection: the
+    /// database file and its key. See SnapshotSource::copy_to.
+    pub fn snapshot_source(&self) -> Result<SnapshotSource, VaultError> {
+        self.conn()?;
+        let vault_key = self.vault_key.as_ref().ok_or(VaultError::Locked)?;
+        Ok(SnapshotSource {
+            database: self.vault_path(),
+            key: VaultKey(vault_key.0),
+            data_dir: self.data_dir.clone(),
+        })
+    }
     
-    /// Record a session timing metric
-    pub fn record_session_metric(
-        &self,
-        note_id: &str,
-        client_id: &str,
-        start_time: i64,
-        end_time: i64,
-        method: &str,
-        word_count: i32,
-        ai_assisted: bool,
-    ) -> Result<String, VaultError> {
+    pub fn record_maintenance_run(&self, run: &crate::maintenance::MaintenanceRun) -> Result<(), VaultError> {
         let conn = self.conn()?;
-        let id = uuid::Uuid::new_v4().to_string();
-        let now = chrono::Utc::now().timestamp();
-        
+        let tasks_json = serde_json::to_string(&run.tasks)
+            .map_err(|e| VaultError::Serialization(e.to_string()))?;
+        let trigger = serde_json::to_value(run.trigger)
+            .map_err(|e| VaultError::Serialization(e.to_string()))?;
         conn.execute(
-            "INSERT INTO session_metrics (id, note_id, client_id, start_time, end_time, method, word_count, ai_assisted, created_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
-            rusqlite::params![id, note_id, client_id, start_time, end_time, method, word_count, ai_assisted as i32, now],
+            "INSERT INTO maintenance_runs (id, triggered_by, started_at, finished_at, succeeded, tasks_json)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![&run.id, trigger.as_str(), run.started_at, run.finished_at, run.succeeded(), &tasks_json],
         )?;
-        
-        Ok(id)
+        Ok(())
     }
     
-    /// Get session metrics for a time period
-    pub fn get_session_metrics(&self, since_timestamp: i64) -> Result<Vec<SessionMetricRow>, VaultError> {
+    /// Most recent runs first
+    pub fn list_maintenance_runs(&self, limit: u32) -> Result<Vec<crate::maintenance::MaintenanceRun>, VaultError> {
         let conn = self.conn()?;
-        
         let mut stmt = conn.prepare(
-            "SELECT id, note_id, client_id, start_time, end_time, method, word_count, ai_assisted, created_at
-             FROM session_metrics
-             WHERE start_time >= ?1
-             ORDER BY start_time DESC"
+            "SELECT id, triggered_by, started_at, finished_at, tasks_json FROM maintenance_runs
+             ORDER BY started_at DESC LIMIT ?1"
         )?;
-        
-        let rows = stmt.query_map([since_timestamp], |row| {
-            Ok(SessionMetricRow {
+        let runs = stmt.query_map([limit], |row| {
+            let trigger: String = row.get(1)?;
+            let tasks_json: String = row.get(4)?;
+            Ok(crate::maintenance::MaintenanceRun {
                 id: row.get(0)?,
-                note_id: row.get(1)?,
-                client_id: row.get(2)?,
-                start_time: row.get(3)?,
-                end_time: row.get(4)?,
-                method: row.get(5)?,
-                word_count: row.get(6)?,
-                ai_assisted: row.get::<_, i32>(7)? != 0,
-                created_at: row.get(8)?,
+                trigger: serde_json::from_value(serde_json::Value::String(trigger))
+                    .unwrap_or(crate::maintenance::MaintenanceTrigger::Manual),
+                started_at: row.get(2)?,
+                finished_at: row.get(3)?,
+                tasks: serde_json::from_str(&tasks_json).unwrap_or_default(),
             })
-        })?;
-        
-        rows.collect::<Result<Vec<_>, _>>().map_err(VaultError::from)
+        })?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(runs)
     }
     
-    /// Get aggregated metrics summary
-    pub fn get_metrics_summary(&self, since_timestamp: i64) -> Result<MetricsSummary, VaultError> {
+    // ============================================
+    // Ethics Re-analysis
+    // ============================================
+    
+    /// Signed and amended notes in `range`, oldest session first
+    pub fn list_signed_note_ids(&self, range: &crate::reanalysis::ReanalysisRange) -> Result<Vec<String>, VaultError> {
         let conn = self.conn()?;
-        
-        let (total_notes, total_time, voice_count, typed_count, ai_assisted_count): (i32, i64, i32, i32, i32) = conn.query_row(
-            "SELECT 
-                COUNT(*) as total_notes,
-                COALESCE(SUM(end_time - start_time), 0) as total_time,
-                COALESCE(SUM(CASE WHEN method = 'voice' THEN 1 ELSE 0 END), 0) as voice_count,
-                COALESCE(SUM(CASE WHEN method = 'typed' THEN 1 ELSE 0 END), 0) as typed_count,
-                COALESCE(SUM(ai_assisted), 0) as ai_assisted_count
-             FROM session_metrics
-             WHERE start_time >= ?1",
-            [since_timestamp],
-            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?)),
+        let mut stmt = conn.prepare(
+            "SELECT id FROM notes
+             WHERE status IN ('signed', 'amended')
+               AND (?1 IS NULL OR session_date >= ?1)
+               AND (?2 IS NULL OR session_date <= ?2)
+               AND (?3 IS NULL OR client_id = ?3)
+             ORDER BY session_date, id"
         )?;
-        
-        let avg_time = if total_notes > 0 { total_time as f64 / total_notes as f64 } else { 0.0 };
-        
-        // Industry benchmark is 15 minutes (900 seconds) per note
-        let benchmark_seconds = 900_i64;
-        let time_saved = (total_notes as i64 * benchmark_seconds) - total_time;
-        
-        Ok(MetricsSummary {
-            total_notes: total_notes as u32,
-            total_time_seconds: total_time as u64,
-            avg_time_seconds: avg_time,
-            voice_count: voice_count as u32,
-            typed_count: typed_count as u32,
-            ai_assisted_count: ai_assisted_count as u32,
-            estimated_time_saved_seconds: time_saved,
-        })
+        let ids = stmt.query_map(params![&range.from_date, &range.to_date, &range.client_id], |row| row.get(0))?
+            .collect::<Result<Vec<String>, _>>()?;
+        Ok(ids)
     }
     
-    // ============================================
-    // Document Management
-    // ============================================
+    pub fn start_reanalysis_run(
+        &self,
+        range: &crate::reanalysis::ReanalysisRange,
+        notes_total: usize,
+    ) -> Result<crate::reanalysis::ReanalysisRun, VaultError> {
+        let conn = self.conn()?;
+        let run = crate::reanalysis::ReanalysisRun {
+            id: crate::ids::new_id(),
+            rules_version: DERIVED_RULES_VERSION.to_string(),
+            range: range.clone(),
+            status: crate::reanalysis::RunStatus::Running,
+            notes_total,
+            notes_scanned: 0,
+            error: None,
+            started_at: chrono::Utc::now().timestamp_millis(),
+            finished_at: None,
+            report: None,
+        };
+        let range_json = serde_json::to_string(range)
+            .map_err(|e| VaultError::Serialization(e.to_string()))?;
+        conn.execute(
+            "INSERT INTO reanalysis_runs (id, rules_version, range_json, status, notes_total, notes_scanned, started_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, 0, ?6)",
+            params![&run.id, &run.rules_version, &range_json, run.status.as_str(), notes_total as i64, run.started_at],
+        )?;
+        Ok(run)
+    }
     
-    /// Upload a document for a client
-    pub fn upload_document(
+    /// Store one batch's findings and the running count of notes scanned
+    pub fn record_reanalysis_batch(
         &self,
-        client_id: &str,
-        filename: &str,
-        file_type: &str,
-        mime_type: &str,
-        data: &[u8],
-        description: Option<&str>,
-        document_date: Option<&str>,
-    ) -> Result<ClientDocument, VaultError> {
+        run_id: &str,
+        findings: &[crate::reanalysis::AdvisoryFinding],
+        notes_scanned: usize,
+    ) -> Result<(), VaultError> {
         let conn = self.conn()?;
-        
-        // Generate ID and hash
-        let id = uuid::Uuid::new_v4().to_string();
-        use sha2::{Sha256, Digest};
-        let content_hash = format!("{:x}", Sha256::digest(data));
-        let now = chrono::Utc::now().timestamp();
-        let file_size = data.len() as i64;
-        
-        // Store encrypted data (SQLCipher handles encryption)
+        let tx = conn.unchecked_transaction()?;
+        for f in findings {
+            let severity = f.severity
+                .map(|s| serde_json::to_value(s).map_err(|e| VaultError::Serialization(e.to_string())))
+                .transpose()?;
+            tx.execute(
+                "INSERT INTO advisory_findings (id, run_id, note_id, client_id, session_date, pattern_id,
+                 change_kind, severity, match_start, match_end, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+                params![
+                    &f.id, &f.run_id, &f.note_id, &f.client_id, &f.session_date, &f.pattern_id,
+                    f.change.as_str(), severity.as_ref().and_then(|v| v.as_str()),
+                    f.match_start.map(|n| n as i64), f.match_end.map(|n| n as i64), f.created_at
+                ],
+            )?;
+        }
+        tx.execute(
+            "UPDATE reanalysis_runs SET notes_scanned = ?1 WHERE id = ?2",
+            params![notes_scanned as i64, run_id],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+    
+    /// Mark a run completed and store its summary report
+    pub fn complete_reanalysis_run(&self, run_id: &str) -> Result<crate::reanalysis::ReanalysisRun, VaultError> {
+        let conn = self.conn()?;
+        let run = self.get_reanalysis_run(run_id)?;
+        let findings = self.list_advisory_findings(run_id, None)?;
+        let report = crate::reanalysis::summarize(&findings, run.notes_scanned);
+        let report_json = serde_json::to_string(&report)
+            .map_err(|e| VaultError::Serialization(e.to_string()))?;
         conn.execute(
-            "INSERT INTO client_documents 
-             (id, client_id, filename, file_type, mime_type, file_size, content_hash, encrypted_data, ocr_text, description, document_date, created_at, updated_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
-            rusqlite::params![
-                id, client_id, filename, file_type, mime_type, file_size, content_hash, 
-                data, None::<String>, description, document_date, now, now
-            ],
+            "UPDATE reanalysis_runs SET status = 'completed', report_json = ?1, finished_at = ?2 WHERE id = ?3",
+            params![&report_json, chrono::Utc::now().timestamp_millis(), run_id],
         )?;
-        
-        Ok(ClientDocument {
-            id,
-            client_id: client_id.to_string(),
-            filename: filename.to_string(),
-            file_type: file_type.to_string(),
-            mime_type: mime_type.to_string(),
-            file_size,
-            content_hash,
-            ocr_text: None,
-            description: description.map(|s| s.to_string()),
-            document_date: document_date.map(|s| s.to_string()),
-            created_at: now,
-            updated_at: now,
+        self.get_reanalysis_run(run_id)
+    }
+    
+    pub fn fail_reanalysis_run(&self, run_id: &str, error: &str) -> Result<(), VaultError> {
+        self.conn()?.execute(
+            "UPDATE reanalysis_runs SET status = 'failed', error = ?1, finished_at = ?2 WHERE id = ?3",
+            params![error, chrono::Utc::now().timestamp_millis(), run_id],
+        )?;
+        Ok(())
+    }
+    
+    /// Runs still marked running when no job is (the vault was locked or
+    /// the app closed mid-run). Their findings so far are kept.
+    pub fn interrupt_stale_reanalysis_runs(&self) -> Result<usize, VaultError> {
+        Ok(self.conn()?.execute(
+            "UPDATE reanalysis_runs SET status = 'failed', error = 'Interrupted', finished_at = ?1
+             WHERE status = 'running'",
+            [chrono::Utc::now().timestamp_millis()],
+        )?)
+    }
+    
+    fn map_reanalysis_run(row: &rusqlite::Row) -> rusqlite::Result<crate::reanalysis::ReanalysisRun> {
+        let range_json: String = row.get(2)?;
+        let status: String = row.get(3)?;
+        let report_json: Option<String> = row.get(7)?;
+        Ok(crate::reanalysis::ReanalysisRun {
+            id: row.get(0)?,
+            rules_version: row.get(1)?,
+            range: serde_json::from_str(&range_json).unwrap_or_default(),
+            status: crate::reanalysis::RunStatus::from_str(&status),
+            notes_total: row.get::<_, i64>(4)? as usize,
+            notes_scanned: row.get::<_, i64>(5)? as usize,
+            error: row.get(6)?,
+            report: report_json.and_then(|j| serde_json::from_str(&j).ok()),
+            started_at: row.get(8)?,
+            finished_at: row.get(9)?,
         })
     }
     
-    /// Get documents for a client
-    pub fn list_documents(&self, client_id: &str) -> Result<Vec<ClientDocument>, VaultError> {
+    pub fn get_reanalysis_run(&self, run_id: &str) -> Result<crate::reanalysis::ReanalysisRun, VaultError> {
+        self.conn()?.query_row(
+            "SELECT id, rules_version, range_json, status, notes_total, notes_scanned, error, report_json,
+                    started_at, finished_at
+             FROM reanalysis_runs WHERE id = ?1",
+            [run_id],
+            Self::map_reanalysis_run,
+        ).optional()?
+            .ok_or_else(|| VaultError::NotFound(format!("Re-analysis run {}", run_id)))
+    }
+    
+    /// Most recent runs first
+    pub fn list_reanalysis_runs(&self, limit: u32) -> Result<Vec<crate::reanalysis::ReanalysisRun>, VaultError> {
         let conn = self.conn()?;
-        
         let mut stmt = conn.prepare(
-            "SELECT id, client_id, filename, file_type, mime_type, file_size, content_hash, 
-                    ocr_text, description, document_date, created_at, updated_at
-             FROM client_documents
-             WHERE client_id = ?1
-             ORDER BY created_at DESC"
+            "SELECT id, rules_version, range_json, status, notes_total, notes_scanned, error, report_json,
+                    started_at, finished_at
+             FROM reanalysis_runs ORDER BY started_at DESC LIMIT ?1"
         )?;
-        
-        let rows = stmt.query_map([client_id], |row| {
-            Ok(ClientDocument {
+        let runs = stmt.query_map([limit], Self::map_reanalysis_run)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(runs)
+    }
+    
+    pub fn list_advisory_findings(
+        &self,
+        run_id: &str,
+        note_id: Option<&str>,
+    ) -> Result<Vec<crate::reanalysis::AdvisoryFinding>, VaultError> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, run_id, note_id, client_id, session_date, pattern_id, change_kind, severity,
+                    match_start, match_end, created_at
+             FROM advisory_findings
+             WHERE run_id = ?1 AND (?2 IS NULL OR note_id = ?2)
+             ORDER BY session_date, note_id, change_kind, pattern_id"
+        )?;
+        let findings = stmt.query_map(params![run_id, note_id], |row| {
+            let change: String = row.get(6)?;
+            let severity: Option<String> = row.get(7)?;
+            Ok(crate::reanalysis::AdvisoryFinding {
                 id: row.get(0)?,
-                client_id: row.get(1)?,
-                filename: row.get(2)?,
-                file_type: row.get(3)?,
-                mime_type: row.get(4)?,
-                file_size: row.get(5)?,
-                content_hash: row.get(6)?,
-                ocr_text: row.get(7)?,
-                description: row.get(8)?,
-                document_date: row.get(9)?,
+                run_id: row.get(1)?,
+                note_id: row.get(2)?,
+                client_id: row.get(3)?,
+                session_date: row.get(4)?,
+                pattern_id: row.get(5)?,
+                change: crate::reanalysis::FindingChange::from_str(&change),
+                severity: severity.and_then(|s| serde_json::from_value(serde_json::Value::String(s)).ok()),
+                match_start: row.get::<_, Option<i64>>(8)?.map(|n| n as usize),
+                match_end: row.get::<_, Option<i64>>(9)?.map(|n| n as usize),
                 created_at: row.get(10)?,
-                updated_at: row.get(11)?,
             })
-        })?;
-        
-        rows.collect::<Result<Vec<_>, _>>().map_err(VaultError::from)
+        })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(findings)
+    }
+    
+    // ============================================
+    // Cohorts (group programs)
+    // ============================================
+    
+    fn map_cohort(row: &rusqlite::Row) -> rusqlite::Result<crate::cohort::Cohort> {
+        let schedule_json: String = row.get(3)?;
+        Ok(crate::cohort::Cohort {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            program: row.get(2)?,
+            schedule: serde_json::from_str(&schedule_json).map_err(|e| {
+                rusqlite::Error::FromSqlConversionFailure(3, rusqlite::types::Type::Text, Box::new(e))
+            })?,
+            active_members: row.get(4)?,
+            created_at: row.get(5)?,
+            archived_at: row.get(6)?,
+        })
+    }
+    
+    fn map_cohort_membership(row: &rusqlite::Row) -> rusqlite::Result<crate::cohort::CohortMembership> {
+        Ok(crate::cohort::CohortMembership {
+            id: row.get(0)?,
+            cohort_id: row.get(1)?,
+            client_id: row.get(2)?,
+            joined_on: row.get(3)?,
+            left_on: row.get(4)?,
+            left_reason: row.get(5)?,
+        })
     }
     
-    /// Get document data
-    pub fn get_document_data(&self, document_id: &str) -> Result<Vec<u8>, VaultError> {
-        let conn = self.conn()?;
-        
-        let data: Vec<u8> = conn.query_row(
-            "SELECT encrypted_data FROM client_documents WHERE id = ?1",
-            [document_id],
-            |row| row.get(0),
-        )?;
-        
-        Ok(data)
-    }
+    const COHORT_COLUMNS: &'static str = "c.id, c.name, c.program, c.schedule_json,
+        (SELECT COUNT(*) FROM cohort_memberships m WHERE m.cohort_id = c.id AND m.left_on IS NULL),
+        c.created_at, c.archived_at";
     
-    /// Update document OCR text
-    pub fn update_document_ocr(&self, document_id: &str, ocr_text: &str) -> Result<(), VaultError> {
+    pub fn create_cohort(
+        &self,
+        name: &str,
+        program: &str,
+        schedule: &crate::cohort::CohortSchedule,
+    ) -> Result<crate::cohort::Cohort, VaultError> {
         let conn = self.conn()?;
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(VaultError::InvalidState("Cohort name is required".to_string()));
+        }
+        let id = crate::ids::new_id();
         let now = chrono::Utc::now().timestamp();
+        let schedule_json = serde_json::to_string(schedule)
+            .map_err(|e| VaultError::Serialization(e.to_string()))?;
         
         conn.execute(
-            "UPDATE client_documents SET ocr_text = ?1, updated_at = ?2 WHERE id = ?3",
-            rusqlite::params![ocr_text, now, document_id],
+            "INSERT INTO cohorts (id, name, program, schedule_json, created_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![&id, name, program.trim(), &schedule_json, now],
         )?;
         
-        Ok(())
+        Ok(crate::cohort::Cohort {
+            id,
+            name: name.to_string(),
+            program: program.trim().to_string(),
+            schedule: schedule.clone(),
+            active_members: 0,
+            created_at: now,
+            archived_at: None,
+        })
     }
     
-    /// Delete a document
-    pub fn delete_document(&self, document_id: &str) -> Result<(), VaultError> {
+    pub fn get_cohort(&self, cohort_id: &str) -> Result<crate::cohort::Cohort, VaultError> {
         let conn = self.conn()?;
-        conn.execute("DELETE FROM client_documents WHERE id = ?1", [document_id])?;
-        Ok(())
+        conn.query_row(
+            &format!("SELECT {} FROM cohorts c WHERE c.id = ?1", Self::COHORT_COLUMNS),
+            [cohort_id],
+            Self::map_cohort,
+        ).optional()?
+            .ok_or_else(|| VaultError::NotFound(format!("Cohort {}", cohort_id)))
     }
     
-    /// Search documents by OCR text
-    pub fn search_documents(&self, query: &str) -> Result<Vec<ClientDocument>, VaultError> {
+    pub fn list_cohorts(&self, include_archived: bool) -> Result<Vec<crate::cohort::Cohort>, VaultError> {
         let conn = self.conn()?;
-        let search_pattern = format!("%{}%", query.to_lowercase());
-        
-        let mut stmt = conn.prepare(
-            "SELECT id, client_id, filename, file_type, mime_type, file_size, content_hash, 
-                    ocr_text, description, document_date, created_at, updated_at
-             FROM client_documents
-             WHERE LOWER(ocr_text) LIKE ?1 
-                OR LOWER(filename) LIKE ?1 
-                OR LOWER(description) LIKE ?1
-             ORDER BY created_at DESC"
-        )?;
-        
-        let rows = stmt.query_map([&search_pattern], |row| {
-            Ok(ClientDocument {
-                id: row.get(0)?,
-                client_id: row.get(1)?,
-                filename: row.get(2)?,
-                file_type: row.get(3)?,
-                mime_type: row.get(4)?,
-                file_size: row.get(5)?,
-                content_hash: row.get(6)?,
-                ocr_text: row.get(7)?,
-                description: row.get(8)?,
-                document_date: row.get(9)?,
-                created_at: row.get(10)?,
-                updated_at: row.get(11)?,
-            })
-        })?;
-        
-        rows.collect::<Result<Vec<_>, _>>().map_err(VaultError::from)
+        let filter = if include_archived { "" } else { "WHERE c.archived_at IS NULL" };
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM cohorts c {} ORDER BY c.name", Self::COHORT_COLUMNS, filter
+        ))?;
+        let cohorts = stmt.query_map([], Self::map_cohort)?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(cohorts)
     }
     
-    /// Get storage statistics
-    pub fn get_storage_stats(&self) -> Result<StorageStats, VaultError> {
+    /// Close a cohort to new sessions; history and notes are kept
+    pub fn archive_cohort(&self, cohort_id: &str) -> Result<(), VaultError> {
         let conn = self.conn()?;
+        let updated = conn.execute(
+            "UPDATE cohorts SET archived_at = ?1 WHERE id = ?2 AND archived_at IS NULL",
+            params![chrono::Utc::now().timestamp(), cohort_id],
+        )?;
+        if updated == 0 {
+            return Err(VaultError::NotFound(format!("Active cohort {}", cohort_id)));
+        }
+        Ok(())
+    }
+    
+    pub fn add_cohort_member(
+        &self,
+        cohort_id: &str,
+        client_id: &str,
+        joined_on: &str,
+    ) -> Result<crate::cohort::CohortMembership, VaultError> {
+        let cohort = self.get_cohort(cohort_id)?;
+        if cohort.archived_at.is_some() {
+            return Err(VaultError::InvalidState("Cohort is archived".to_string()));
+        }
+        self.get_client(client_id)?;
         
-        // Get database file size
-        let db_size = std::fs::metadata(&self.vault_path())
-            .map(|m| m.len() as i64)
-            .unwrap_or(0);
+        let history = self.list_cohort_memberships(cohort_id, true)?;
+        let periods: Vec<_> = history.iter().filter(|m| m.client_id == client_id).collect();
+        if periods.iter().any(|m| m.left_on.is_none()) {
+            return Err(VaultError::InvalidState("Client is already a member of this cohort".to_string()));
+        }
+        if periods.iter().any(|m| m.left_on.as_deref().is_some_and(|left| left > joined_on)) {
+            return Err(VaultError::InvalidState(
+                "Join date overlaps an earlier membership period".to_string()
+            ));
+        }
         
-        // Count records
-        let note_count: i64 = conn.query_row("SELECT COUNT(*) FROM notes", [], |row| row.get(0))?;
-        let client_count: i64 = conn.query_row("SELECT COUNT(*) FROM clients", [], |row| row.get(0))?;
-        let doc_count: i64 = conn.query_row("SELECT COUNT(*) FROM client_documents", [], |row| row.get(0)).unwrap_or(0);
-        let doc_size: i64 = conn.query_row("SELECT COALESCE(SUM(file_size), 0) FROM client_documents", [], |row| row.get(0)).unwrap_or(0);
-        let embedding_count: i64 = conn.query_row("SELECT COUNT(*) FROM embeddings", [], |row| row.get(0))?;
+        let conn = self.conn()?;
+        let id = crate::ids::new_id();
+        conn.execute(
+            "INSERT INTO cohort_memberships (id, cohort_id, client_id, joined_on, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![&id, cohort_id, client_id, joined_on, chrono::Utc::now().timestamp()],
+        )?;
         
-        Ok(StorageStats {
-            database_size_bytes: db_size,
-            note_count: note_count as u32,
-            client_count: client_count as u32,
-            document_count: doc_count as u32,
-            document_size_bytes: doc_size,
-            embedding_count: embedding_count as u32,
+        Ok(crate::cohort::CohortMembership {
+            id,
+            cohort_id: cohort_id.to_string(),
+            client_id: client_id.to_string(),
+            joined_on: joined_on.to_string(),
+            left_on: None,
+            left_reason: None,
         })
     }
     
-    /// Optimize database (VACUUM)
-    pub fn optimize_database(&self) -> Result<(), VaultError> {
+    /// Close the client's open membership period; `left_on` is the first
+    /// date they are no longer a member
+    pub fn end_cohort_membership(
+        &self,
+        cohort_id: &str,
+        client_id: &str,
+        left_on: &str,
+        reason: Option<&str>,
+    ) -> Result<crate::cohort::CohortMembership, VaultError> {
+        let mut membership = self.list_cohort_memberships(cohort_id, false)?
+            .into_iter()
+            .find(|m| m.client_id == client_id)
+            .ok_or_else(|| VaultError::NotFound("Current cohort membership".to_string()))?;
+        if left_on <= membership.joined_on.as_str() {
+            return Err(VaultError::InvalidState("Leave date must be after the join date".to_string()));
+        }
+        
         let conn = self.conn()?;
-        conn.execute("VACUUM", [])?;
-        conn.execute("ANALYZE", [])?;
-        Ok(())
+        conn.execute(
+            "UPDATE cohort_memberships SET left_on = ?1, left_reason = ?2 WHERE id = ?3",
+            params![left_on, reason, &membership.id],
+        )?;
+        
+        membership.left_on = Some(left_on.to_string());
+        membership.left_reason = reason.map(str::to_string);
+        Ok(membership)
     }
     
-    // ============================================
-    // Pre-Session Prep Sheet
-    // ============================================
+    pub fn list_cohort_memberships(
+        &self,
+        cohort_id: &str,
+        include_past: bool,
+    ) -> Result<Vec<crate::cohort::CohortMembership>, VaultError> {
+        let conn = self.conn()?;
+        let filter = if include_past { "" } else { "AND left_on IS NULL" };
+        let mut stmt = conn.prepare(&format!(
+            "SELECT id, cohort_id, client_id, joined_on, left_on, left_reason
+             FROM cohort_memberships WHERE cohort_id = ?1 {} ORDER BY joined_on, client_id",
+            filter
+        ))?;
+        let memberships = stmt.query_map([cohort_id], Self::map_cohort_membership)?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(memberships)
+    }
     
-    pub fn generate_prep_sheet(&self, client_id: &str) -> Result<crate::models::PrepSheet, VaultError> {
-        use crate::models::*;
+    /// Record one group session. Every member enrolled on `session_date`
+    /// needs an attendance entry; attending members each get a Group note
+    /// built from the shared narrative and their individual section.
+    pub fn record_cohort_session(
+        &self,
+        cohort_id: &str,
+        session_date: &str,
+        shared_content: &str,
+        members: &[crate::cohort::MemberSessionInput],
+        duration_minutes: Option<u32>,
+    ) -> Result<crate::cohort::CohortSessionRecord, VaultError> {
+        use crate::cohort::{AttendanceRecord, CohortSession, CohortSessionRecord};
+        
+        let cohort = self.get_cohort(cohort_id)?;
+        if cohort.archived_at.is_some() {
+            return Err(VaultError::InvalidState("Cohort is archived".to_string()));
+        }
+        
+        // Validate everything before writing anything
+        let enrolled: Vec<String> = self.list_cohort_memberships(cohort_id, true)?
+            .into_iter()
+            .filter(|m| m.is_active_on(session_date))
+            .map(|m| m.client_id)
+            .collect();
+        for (i, input) in members.iter().enumerate() {
+            if !enrolled.contains(&input.client_id) {
+                return Err(VaultError::InvalidState(format!(
+                    "Client {} is not enrolled in this cohort on {}", input.client_id, session_date
+                )));
+            }
+            if members[..i].iter().any(|m| m.client_id == input.client_id) {
+                return Err(VaultError::InvalidState(format!("Duplicate attendance for client {}", input.client_id)));
+            }
+        }
+        let missing = enrolled.iter().filter(|id| !members.iter().any(|m| &m.client_id == *id)).count();
+        if missing > 0 {
+            return Err(VaultError::InvalidState(format!(
+                "Attendance missing for {} enrolled member(s)", missing
+            )));
+        }
+        if members.iter().any(|m| m.status.attended()) && shared_content.trim().is_empty() {
+            return Err(VaultError::InvalidState("Shared group narrative is required".to_string()));
+        }
         
+        let names: Vec<String> = enrolled.iter()
+            .map(|id| self.get_client(id).map(|c| c.display_name))
+            .collect::<Result<_, _>>()?;
+        let disclosed = crate::cohort::names_in_shared_content(shared_content, &names);
+        if !disclosed.is_empty() {
+            return Err(VaultError::InvalidState(format!(
+                "Shared narrative names group members ({}); it is copied into every member's chart",
+                disclosed.join(", ")
+            )));
+        }
+        
+        let session = CohortSession {
+            id: crate::ids::new_id(),
+            cohort_id: cohort_id.to_string(),
+            session_date: session_date.to_string(),
+            duration_minutes: duration_minutes.unwrap_or(cohort.schedule.duration_minutes),
+            created_at: chrono::Utc::now().timestamp(),
+        };
+        // The session, member notes and attendance land together or not at all
         let conn = self.conn()?;
-        let client = self.get_client(client_id)?;
-        let now = chrono::Utc::now();
+        let tx = conn.unchecked_transaction()?;
+        tx.execute(
+            "INSERT INTO cohort_sessions (id, cohort_id, session_date, duration_minutes, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![&session.id, cohort_id, session_date, session.duration_minutes, session.created_at],
+        )?;
         
-        // Get demographics
-        let age = client.date_of_birth.as_ref().and_then(|dob| {
-            chrono::NaiveDate::parse_from_str(dob, "%Y-%m-%d").ok().map(|d| {
-                let today = now.date_naive();
-                today.years_since(d).unwrap_or(0) as i32
-            })
-        });
+        let mut attendance = Vec::with_capacity(members.len());
+        for input in members {
+            let note_id = if input.status.attended() {
+                let content = crate::cohort::compose_member_note(&cohort, shared_content, &input.individual_content);
+                Some(self.create_note(&input.client_id, session_date, NoteType::Group, &content)?.id)
+            } else {
+                None
+            };
+            let minutes_attended = input.minutes_attended.map(|m| m.min(session.duration_minutes));
+            
+            tx.execute(
+                "INSERT INTO cohort_attendance (session_id, client_id, status, minutes_attended, note_id)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![&session.id, &input.client_id, input.status.as_str(), minutes_attended, &note_id],
+            )?;
+            attendance.push(AttendanceRecord {
+                session_id: session.id.clone(),
+                client_id: input.client_id.clone(),
+                status: input.status,
+                minutes_attended,
+                note_id,
+            });
+        }
+        tx.commit()?;
         
-        let treatment_duration = client.treatment_start_date.as_ref().and_then(|start| {
-            chrono::NaiveDate::parse_from_str(start, "%Y-%m-%d").ok().map(|d| {
-                (now.date_naive() - d).num_days() as i32
-            })
-        });
+        Ok(CohortSessionRecord { session, attendance })
+    }
+    
+    /// Attendance, billable sessions and outreach flags for sessions held
+    /// between two dates (inclusive)
+    pub fn get_cohort_attendance_report(
+        &self,
+        cohort_id: &str,
+        period_start: &str,
+        period_end: &str,
+    ) -> Result<crate::cohort::CohortAttendanceReport, VaultError> {
+        use crate::cohort::{AttendanceRecord, AttendanceStatus, CohortSession};
+        
+        self.get_cohort(cohort_id)?;
+        let memberships = self.list_cohort_memberships(cohort_id, true)?;
+        let conn = self.conn()?;
         
-        // Get recent notes (last 5)
         let mut stmt = conn.prepare(
-            "SELECT id, session_date, note_type, raw_input FROM notes 
-             WHERE client_id = ?1 ORDER BY session_date DESC LIMIT 5"
+            "SELECT id, cohort_id, session_date, duration_minutes, created_at FROM cohort_sessions
+             WHERE cohort_id = ?1 AND session_date >= ?2 AND session_date <= ?3"
         )?;
-        let notes: Vec<(String, String, String, String)> = stmt.query_map([client_id], |row| {
-            Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
-        })?.filter_map(|r| r.ok()).collect();
-        
-        let last_session_date = notes.first().map(|(_, d, _, _)| d.clone());
-        let days_since_last = last_session_date.as_ref().and_then(|d| {
-            chrono::NaiveDate::parse_from_str(d, "%Y-%m-%d").ok().map(|date| {
-                (now.date_naive() - date).num_days() as i32
+        let sessions: Vec<CohortSession> = stmt.query_map(params![cohort_id, period_start, period_end], |row| {
+            Ok(CohortSession {
+                id: row.get(0)?,
+                cohort_id: row.get(1)?,
+                session_date: row.get(2)?,
+                duration_minutes: row.get(3)?,
+                created_at: row.get(4)?,
             })
-        });
-        
-        // Build recent session summaries
-        let recent_sessions: Vec<RecentSessionSummary> = notes.iter().take(3).map(|(_, date, note_type, content)| {
-            let key_points = extract_key_points(content);
-            let mood_indicators = extract_mood_indicators(content);
-            let interventions = extract_interventions(content);
-            
-            RecentSessionSummary {
-                session_date: date.clone(),
-                note_type: note_type.clone(),
-                key_points,
-                mood_indicators,
-                interventions_used: interventions,
-            }
-        }).collect();
+        })?
+            .filter_map(|r| r.ok())
+            .collect();
         
-        // Get treatment themes
-        let progress = self.get_treatment_progress(client_id)?;
-        let active_themes: Vec<PrepTheme> = progress.themes.iter()
-            .filter(|t| t.trend != "resolved")
-            .map(|t| PrepTheme {
-                theme: t.theme.clone(),
-                trend: t.trend.clone(),
-                last_mentioned: t.first_mentioned.clone(),
-                clinical_note: None,
+        let mut stmt = conn.prepare(
+            "SELECT a.session_id, a.client_id, a.status, a.minutes_attended, a.note_id
+             FROM cohort_attendance a JOIN cohort_sessions s ON s.id = a.session_id
+             WHERE s.cohort_id = ?1 AND s.session_date >= ?2 AND s.session_date <= ?3"
+        )?;
+        let attendance: Vec<AttendanceRecord> = stmt.query_map(params![cohort_id, period_start, period_end], |row| {
+            Ok(AttendanceRecord {
+                session_id: row.get(0)?,
+                client_id: row.get(1)?,
+                status: AttendanceStatus::parse(&row.get::<_, String>(2)?),
+                minutes_attended: row.get(3)?,
+                note_id: row.get(4)?,
             })
+        })?
+            .filter_map(|r| r.ok())
             .collect();
         
-        // Check for safety alerts in recent notes
-        let mut safety_alerts = Vec::new();
-        for (_, date, _, content) in &notes {
-            let content_lower = content.to_lowercase();
-            if content_lower.contains("suicidal") || content_lower.contains("si ") || content_lower.contains("suicide") {
-                safety_alerts.push(SafetyAlert {
-                    alert_type: "suicidal_ideation".to_string(),
-                    last_flagged: date.clone(),
-                    severity: "high".to_string(),
-                    details: "Suicidal ideation mentioned in recent note".to_string(),
-                });
-            }
-            if content_lower.contains("self-harm") || content_lower.contains("cutting") {
-                safety_alerts.push(SafetyAlert {
-                    alert_type: "self_harm".to_string(),
-                    last_flagged: date.clone(),
-                    severity: "high".to_string(),
-                    details: "Self-harm mentioned in recent note".to_string(),
-                });
-            }
-            if content_lower.contains("abuse") || content_lower.contains("violent") {
-                safety_alerts.push(SafetyAlert {
-                    alert_type: "abuse_concern".to_string(),
-                    last_flagged: date.clone(),
-                    severity: "moderate".to_string(),
-                    details: "Abuse or violence mentioned".to_string(),
-                });
-            }
-        }
-        safety_alerts.dedup_by(|a, b| a.alert_type == b.alert_type);
-        
-        // Suggest assessments based on themes
-        let mut suggested_assessments = Vec::new();
-        let theme_names: Vec<&str> = active_themes.iter().map(|t| t.theme.as_str()).collect();
-        
-        if theme_names.iter().any(|t| t.contains("depression")) {
-            suggested_assessments.push(AssessmentSuggestion {
-                assessment_name: "PHQ-9".to_string(),
-                reason: "Depression theme identified - consider screening".to_string(),
-                last_administered: None,
-                last_score: None,
-            });
-        }
-        if theme_names.iter().any(|t| t.contains("anxiety")) {
-            suggested_assessments.push(AssessmentSuggestion {
-                assessment_name: "GAD-7".to_string(),
-                reason: "Anxiety theme identified - consider screening".to_string(),
-                last_administered: None,
-                last_score: None,
-            });
-        }
-        if !safety_alerts.is_empty() {
-            suggested_assessments.push(AssessmentSuggestion {
-                assessment_name: "C-SSRS".to_string(),
-                reason: "Safety concerns flagged - consider risk assessment".to_string(),
-                last_administered: None,
-                last_score: None,
-            });
-        }
-        if theme_names.iter().any(|t| t.contains("trauma")) {
-            suggested_assessments.push(AssessmentSuggestion {
-                assessment_name: "PCL-5".to_string(),
-                reason: "Trauma theme identified - consider PTSD screening".to_string(),
-                last_administered: None,
-                last_score: None,
-            });
-        }
+        Ok(crate::cohort::compute_attendance(
+            cohort_id, period_start, period_end, &sessions, &memberships, &attendance,
+        ))
+    }
+    
+    // ============================================
+    // Documentation Scorecard
+    // ============================================
+    
+    /// Completeness scorecard for one client's chart. Treatment plans and
+    /// consents are recognized by agency note type name or by uploaded
+    /// documents whose type, filename or description names them;
+    /// assessments are intake notes, structured MSEs, or notes of an
+    /// "assessment" type.
+    pub fn get_client_documentation_scorecard(
+        &self,
+        client_id: &str,
+    ) -> Result<crate::metrics::DocumentationScorecard, VaultError> {
+        use chrono::NaiveDate;
         
-        // Generate focus suggestions
-        let mut focus_suggestions = Vec::new();
-        if days_since_last.map(|d| d > 14).unwrap_or(false) {
-            focus_suggestions.push("Consider checking in on progress since last session (extended gap)".to_string());
-        }
-        if !safety_alerts.is_empty() {
-            focus_suggestions.push("Review safety plan and assess current risk level".to_string());
-        }
-        for theme in &active_themes {
-            if theme.trend == "worsening" {
-                focus_suggestions.push(format!("Address {} - trend appears worsening", theme.theme.replace("_", " ")));
-            }
-        }
-        if focus_suggestions.is_empty() {
-            focus_suggestions.push("Continue current treatment approach".to_string());
-        }
+        self.get_client(client_id)?;
+        let parse_date = |s: &str| NaiveDate::parse_from_str(s.get(..10).unwrap_or(s), "%Y-%m-%d").ok();
         
-        Ok(PrepSheet {
-            client_id: client_id.to_string(),
-            client_name: client.display_name,
-            generated_at: now.format("%Y-%m-%d %H:%M").to_string(),
-            demographics: PrepDemographics {
-                age,
-                treatment_duration_days: treatment_duration,
-                total_sessions: client.session_count,
-                last_session_date,
-                days_since_last_session: days_since_last,
-                diagnosis_codes: client.diagnosis_codes,
-            },
-            recent_sessions,
-            active_themes,
-            safety_alerts,
-            suggested_assessments,
-            focus_suggestions,
-            last_assessments: vec![],  // Would need separate assessment tracking
-        })
+        let notes = self.list_notes(Some(client_id))?;
+        let conn = self.conn()?;
+        
+        // Latest note session date / document date whose type or label contains `pattern`
+        let latest_labeled = |pattern: &str| -> Result<Option<NaiveDate>, VaultError> {
+            let note_date: Option<String> = conn.query_row(
+                "SELECT MAX(n.session_date) FROM notes n
+                 JOIN note_type_assignments t ON t.note_id = n.id
+                 WHERE n.client_id = ?1 AND LOWER(REPLACE(t.type_name, '_', ' ')) LIKE ?2",
+                params![client_id, pattern],
+                |row| row.get(0),
+            )?;
+            let mut stmt = conn.prepare(
+                "SELECT document_date, created_at FROM client_documents
+                 WHERE client_id = ?1
+                   AND LOWER(REPLACE(file_type || ' ' || filename || ' ' || COALESCE(description, ''), '_', ' ')) LIKE ?2"
+            )?;
+            let doc_dates = stmt.query_map(params![client_id, pattern], |row| {
+                Ok((row.get::<_, Option<String>>(0)?, row.get::<_, i64>(1)?))
+            })?
+                .filter_map(|r| r.ok())
+                .filter_map(|(date, created_at)| {
+                    date.as_deref().and_then(parse_date).or_else(|| {
+                        chrono::DateTime::from_timestamp(created_at, 0).map(|t| t.date_naive())
+                    })
+                });
+            Ok(note_date.as_deref().and_then(parse_date).into_iter().chain(doc_dates).max())
+        };
+        
+        let last_treatment_plan = latest_labeled("%treatment plan%")?;
+        let last_consent = latest_labeled("%consent%")?;
+        
+        let last_mse: Option<String> = conn.query_row(
+            "SELECT MAX(session_date) FROM mental_status_exams WHERE client_id = ?1",
+            [client_id],
+            |row| row.get(0),
+        )?;
+        let last_intake = notes.iter()
+            .filter(|n| n.note_type == NoteType::Intake)
+            .filter_map(|n| parse_date(&n.session_date))
+            .max();
+        let last_assessment = [last_mse.as_deref().and_then(parse_date), last_intake, latest_labeled("%assessment%")?]
+            .into_iter()
+            .flatten()
+            .max();
+        
+        let notes = notes.iter()
+            .filter_map(|n| {
+                Some(crate::metrics::ScorecardNote {
+                    session_date: parse_date(&n.session_date)?,
+                    signed: !matches!(n.status, NoteStatus::Draft | NoteStatus::Reviewed),
+                    detection_count: n.detection_ids.len(),
+                    attested_count: n.detection_ids.iter()
+                        .filter(|id| n.attestations.iter().any(|a| &a.detection_id == *id))
+                        .count(),
+                })
+            })
+            .collect();
+        
+        let inputs = crate::metrics::ScorecardInputs {
+            notes,
+            last_treatment_plan,
+            last_consent,
+            last_assessment,
+        };
+        Ok(crate::metrics::compute_scorecard(client_id, &inputs, chrono::Local::now().date_naive()))
     }
     
     // ============================================
-    // Supervisor Mode
+    // Trusted Recipients (consultation sharing)
     // ============================================
     
-    pub fn create_trainee(&self, name: &str, email: Option<&str>, supervisor_id: &str) -> Result<crate::models::Trainee, VaultError> {
+    pub fn add_trusted_recipient(
+        &self,
+        label: &str,
+        public_key: &str,
+    ) -> Result<crate::deidentify::TrustedRecipient, VaultError> {
         let conn = self.conn()?;
-        let id = uuid::Uuid::new_v4().to_string();
-        let now = chrono::Utc::now();
+        let key = crate::crypto::envelope::parse_public_key(public_key)?;
+        let fingerprint = crate::crypto::envelope::fingerprint(&key);
+        
+        let existing: Option<String> = conn.query_row(
+            "SELECT id FROM trusted_recipients WHERE fingerprint = ?1 AND revoked_at IS NULL",
+            [&fingerprint],
+            |row| row.get(0),
+        ).optional()?;
+        if existing.is_some() {
+            return Err(VaultError::InvalidState("Recipient already trusted".to_string()));
+        }
+        
+        let id = crate::ids::new_id();
+        let now = chrono::Utc::now().timestamp();
+        let public_key = public_key.trim().to_string();
         
+        // A revoked key can be re-trusted; replace the old row
+        conn.execute("DELETE FROM trusted_recipients WHERE fingerprint = ?1", [&fingerprint])?;
         conn.execute(
-            "INSERT INTO trainees (id, name, email, supervisor_id, start_date, status, notes_submitted, notes_approved, created_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, 0, 0, ?7)",
-            rusqlite::params![id, name, email, supervisor_id, now.format("%Y-%m-%d").to_string(), "active", now.timestamp()],
+            "INSERT INTO trusted_recipients (id, label, public_key, fingerprint, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            rusqlite::params![id, label, public_key, fingerprint, now],
         )?;
         
-        Ok(crate::models::Trainee {
+        crate::audit::log_event(
+            conn,
+            crate::models::AuditEventType::TrustedRecipientAdded,
+            crate::models::AuditResourceType::Settings,
+            &id,
+            crate::models::AuditOutcome::Success,
+            None,
+        ).ok();
+        
+        Ok(crate::deidentify::TrustedRecipient {
             id,
-            name: name.to_string(),
-            email: email.map(|s| s.to_string()),
-            supervisor_id: supervisor_id.to_string(),
-            start_date: now.format("%Y-%m-%d").to_string(),
-            status: "active".to_string(),
-            notes_submitted: 0,
-            notes_approved: 0,
-            created_at: now.timestamp(),
+            label: label.to_string(),
+            public_key,
+            fingerprint,
+            created_at: now,
+            revoked_at: None,
         })
     }
     
-    pub fn list_trainees(&self, supervisor_id: &str) -> Result<Vec<crate::models::Trainee>, VaultError> {
+    pub fn list_trusted_recipients(&self, include_revoked: bool) -> Result<Vec<crate::deidentify::TrustedRecipient>, VaultError> {
         let conn = self.conn()?;
-        let mut stmt = conn.prepare(
-            "SELECT id, name, email, supervisor_id, start_date, status, notes_submitted, notes_approved, created_at
-             FROM trainees WHERE supervisor_id = ?1"
-        )?;
+        let sql = if include_revoked {
+            "SELECT id, label, public_key, fingerprint, created_at, revoked_at
+             FROM trusted_recipients ORDER BY label"
+        } else {
+            "SELECT id, label, public_key, fingerprint, created_at, revoked_at
+             FROM trusted_recipients WHERE revoked_at IS NULL ORDER BY label"
+        };
+        let mut stmt = conn.prepare(sql)?;
         
-        let trainees = stmt.query_map([supervisor_id], |row| {
-            Ok(crate::models::Trainee {
-                id: row.get(0)?,
-                name: row.get(1)?,
-                email: row.get(2)?,
-                supervisor_id: row.get(3)?,
-                start_date: row.get(4)?,
-                status: row.get(5)?,
-                notes_submitted: row.get(6)?,
-                notes_approved: row.get(7)?,
-                created_at: row.get(8)?,
-            })
-        })?.filter_map(|r| r.ok()).collect();
+        let recipients = stmt.query_map([], Self::map_trusted_recipient)?
+            .filter_map(|r| r.ok())
+            .collect();
         
-        Ok(trainees)
+        Ok(recipients)
     }
     
-    pub fn submit_note_for_review(&self, note_id: &str, trainee_id: &str) -> Result<(), VaultError> {
+    pub fn get_trusted_recipient(&self, recipient_id: &str) -> Result<crate::deidentify::TrustedRecipient, VaultError> {
+        let conn = self.conn()?;
+        conn.query_row(
+            "SELECT id, label, public_key, fingerprint, created_at, revoked_at
+             FROM trusted_recipients WHERE id = ?1",
+            [recipient_id],
+            Self::map_trusted_recipient,
+        ).optional()?
+            .ok_or_else(|| VaultError::NotFound(format!("Recipient {}", recipient_id)))
+    }
+    
+    pub fn revoke_trusted_recipient(&self, recipient_id: &str) -> Result<(), VaultError> {
         let conn = self.conn()?;
         let now = chrono::Utc::now().timestamp();
-        let review_id = uuid::Uuid::new_v4().to_string();
-        
-        conn.execute(
-            "INSERT INTO note_reviews (id, note_id, trainee_id, status, submitted_at, created_at)
-             VALUES (?1, ?2, ?3, 'pending', ?4, ?5)",
-            rusqlite::params![review_id, note_id, trainee_id, now, now],
+        let updated = conn.execute(
+            "UPDATE trusted_recipients SET revoked_at = ?1 WHERE id = ?2 AND revoked_at IS NULL",
+            rusqlite::params![now, recipient_id],
         )?;
+        if updated == 0 {
+            return Err(VaultError::NotFound(format!("Recipient {}", recipient_id)));
+        }
         
-        // Update trainee stats
+        crate::audit::log_event(
+            conn,
+            crate::models::AuditEventType::TrustedRecipientRevoked,
+            crate::models::AuditResourceType::Settings,
+            recipient_id,
+            crate::models::AuditOutcome::Success,
+            None,
+        ).ok();
+        
+        Ok(())
+    }
+    
+    /// Encrypt a consultation draft for a trusted recipient.
+    /// The draft itself is already de-identified; the envelope protects it in transit.
+    pub fn seal_consultation_draft(
+        &self,
+        draft_id: &str,
+        recipient_id: &str,
+    ) -> Result<crate::crypto::envelope::SealedEnvelope, VaultError> {
+        let draft = self.get_consultation_draft(draft_id)?;
+        let recipient = self.get_trusted_recipient(recipient_id)?;
+        if recipient.revoked_at.is_some() {
+            return Err(VaultError::InvalidState("Recipient key has been revoked".to_string()));
+        }
+        
+        let key = crate::crypto::envelope::parse_public_key(&recipient.public_key)?;
+        let payload = serde_json::to_vec(&draft)
+            .map_err(|e| VaultError::Serialization(e.to_string()))?;
+        let sealed = crate::crypto::envelope::seal(&key, "consultation_draft", &payload)?;
+        
+        let conn = self.conn()?;
+        crate::audit::log_event(
+            conn,
+            crate::models::AuditEventType::ConsultationDraftSealed,
+            crate::models::AuditResourceType::Export,
+            draft_id,
+            crate::models::AuditOutcome::Success,
+            None,
+        ).ok();
+        
+        Ok(sealed)
+    }
+    
+    fn map_trusted_recipient(row: &rusqlite::Row) -> rusqlite::Result<crate::deidentify::TrustedRecipient> {
+        Ok(crate::deidentify::TrustedRecipient {
+            id: row.get(0)?,
+            label: row.get(1)?,
+            public_key: row.get(2)?,
+            fingerprint: row.get(3)?,
+            created_at: row.get(4)?,
+            revoked_at: row.get(5)?,
+        })
+    }
+    
+    // ============================================
+    // Row Identifiers
+    // ============================================
+    
+    fn stored_id_scheme(conn: &Connection) -> crate::ids::IdScheme {
+        conn.query_row(
+            "SELECT value FROM settings WHERE key = ?1",
+            [crate::ids::ID_SCHEME_SETTING],
+            |row| row.get::<_, String>(0),
+        ).ok()
+            .and_then(|value| crate::ids::IdScheme::from_str(&value))
+            .unwrap_or_default()
+    }
+    
+    pub fn get_id_scheme(&self) -> Result<crate::ids::IdScheme, VaultError> {
+        Ok(Self::stored_id_scheme(self.conn()?))
+    }
+    
+    /// Scheme for IDs of rows created from now on
+    pub fn set_id_scheme(&self, scheme: crate::ids::IdScheme) -> Result<(), VaultError> {
+        let conn = self.conn()?;
         conn.execute(
-            "UPDATE trainees SET notes_submitted = notes_submitted + 1 WHERE id = ?1",
-            [trainee_id],
+            "INSERT OR REPLACE INTO settings (key, value) VALUES (?1, ?2)",
+            params![crate::ids::ID_SCHEME_SETTING, scheme.as_str()],
         )?;
-        
+        crate::ids::set_scheme(scheme);
+        crate::audit::log_id_scheme_change(conn, scheme.as_str())
+            .map_err(|e| VaultError::Internal(format!("Audit write failed: {}", e)))?;
+        log::info!("New rows will get {} IDs", scheme.as_str());
         Ok(())
     }
     
-    pub fn get_pending_reviews(&self, supervisor_id: &str) -> Result<Vec<crate::models::PendingReview>, VaultError> {
+    // ============================================
+    // Note Authors
+    // ============================================
+    
+    /// Authors, section attributions and signature state of a note. Notes
+    /// with no registered authors come back with empty lists and complete.
+    pub fn get_note_authorship(&self, note_id: &str) -> Result<crate::note_authors::NoteAuthorship, VaultError> {
         let conn = self.conn()?;
+        let note = self.get_note(note_id)?;
         
         let mut stmt = conn.prepare(
-            "SELECT nr.note_id, t.name, c.display_name, n.session_date, nr.submitted_at
-             FROM note_reviews nr
-             JOIN trainees t ON nr.trainee_id = t.id
-             JOIN notes n ON nr.note_id = n.id
-             JOIN clients c ON n.client_id = c.id
-             WHERE t.supervisor_id = ?1 AND nr.status = 'pending'
-             ORDER BY nr.submitted_at ASC"
+            "SELECT author_id, author_name, credentials, role, added_at, signed_at, signed_text_sha256, contribution_sha256
+             FROM note_authors WHERE note_id = ?1 ORDER BY added_at, author_id"
         )?;
+        let authors = stmt.query_map([note_id], |row| {
+            let role: String = row.get(3)?;
+            Ok(crate::note_authors::NoteAuthor {
+                author_id: row.get(0)?,
+                author_name: row.get(1)?,
+                credentials: row.get(2)?,
+                role: crate::note_authors::AuthorRole::from_str(&role)
+                    .unwrap_or(crate::note_authors::AuthorRole::CoTherapist),
+                added_at: row.get(4)?,
+                signed_at: row.get(5)?,
+                signed_text_sha256: row.get(6)?,
+                contribution_sha256: row.get(7)?,
+            })
+        })?.collect::<Result<Vec<_>, _>>()?;
         
-        let now = chrono::Utc::now().timestamp();
-        let reviews = stmt.query_map([supervisor_id], |row| {
-            let submitted_at: i64 = row.get(4)?;
-            let days_pending = ((now - submitted_at) / 86400) as i32;
-            Ok(crate::models::PendingReview {
-                note_id: row.get(0)?,
-                trainee_name: row.get(1)?,
-                client_name: row.get(2)?,
-                session_date: row.get(3)?,
-                submitted_at: chrono::DateTime::from_timestamp(submitted_at, 0)
-                    .map(|dt| dt.format("%Y-%m-%d").to_string())
-                    .unwrap_or_default(),
-                days_pending,
+        let mut stmt = conn.prepare(
+            "SELECT section, author_id, attributed_at FROM note_section_authors WHERE note_id = ?1 ORDER BY section"
+        )?;
+        let sections = stmt.query_map([note_id], |row| {
+            Ok(crate::note_authors::SectionAttribution {
+                section: row.get(0)?,
+                author_id: row.get(1)?,
+                attributed_at: row.get(2)?,
             })
-        })?.filter_map(|r| r.ok()).collect();
+        })?.collect::<Result<Vec<_>, _>>()?;
         
-        Ok(reviews)
+        Ok(crate::note_authors::authorship(note_id, Self::analyzed_text(&note), authors, sections))
     }
     
-    /// Get pending reviews for a specific trainee
-    pub fn get_trainee_pending_reviews(&self, trainee_id: &str) -> Result<Vec<crate::models::PendingReview>, VaultError> {
+    /// Authorship can change only before the note is signed
+    fn ensure_note_unsigned(&self, note_id: &str) -> Result<(), VaultError> {
+        let note = self.get_note(note_id)?;
+        if !matches!(note.status, NoteStatus::Draft | NoteStatus::Reviewed) {
+            return Err(VaultError::InvalidState(format!("Note {} is already signed", note_id)));
+        }
+        Ok(())
+    }
+    
+    pub fn add_note_author(
+        &self,
+        note_id: &str,
+        author_id: &str,
+        author_name: &str,
+        credentials: Option<&str>,
+        role: crate::note_authors::AuthorRole,
+    ) -> Result<crate::note_authors::NoteAuthorship, VaultError> {
+        use crate::note_authors::AuthorRole;
+        
         let conn = self.conn()?;
+        self.ensure_note_unsigned(note_id)?;
+        let (author_id, author_name) = (author_id.trim(), author_name.trim());
+        if author_id.is_empty() || author_name.is_empty() {
+            return Err(VaultError::InvalidState("Author id and name are required".to_string()));
+        }
+        let current = self.get_note_authorship(note_id)?;
+        if current.authors.iter().any(|a| a.author_id == author_id) {
+            return Err(VaultError::InvalidState(format!("{} is already an author of this note", author_name)));
+        }
+        if role == AuthorRole::Primary && current.authors.iter().any(|a| a.role == AuthorRole::Primary) {
+            return Err(VaultError::InvalidState("Note already has a primary author".to_string()));
+        }
         
-        let mut stmt = conn.prepare(
-            "SELECT nr.note_id, t.name, c.display_name, n.session_date, nr.submitted_at
-             FROM note_reviews nr
-             JOIN trainees t ON nr.trainee_id = t.id
-             JOIN notes n ON nr.note_id = n.id
-             JOIN clients c ON n.client_id = c.id
-             WHERE nr.trainee_id = ?1 AND nr.status = 'pending'
-             ORDER BY nr.submitted_at ASC"
+        conn.execute(
+            "INSERT INTO note_authors (note_id, author_id, author_name, credentials, role, added_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![note_id, author_id, author_name, credentials.map(str::trim).filter(|c| !c.is_empty()),
+                    role.as_str(), chrono::Utc::now().timestamp_millis()],
         )?;
+        crate::audit::log_note_author(
+            conn, crate::models::AuditEventType::NoteAuthorAdded, note_id, role.as_str(), None,
+        ).map_err(|e| VaultError::Internal(format!("Audit write failed: {}", e)))?;
+        self.get_note_authorship(note_id)
+    }
+    
+    /// Attribute a section to an author. Contributions change, so any
+    /// signatures already given are cleared.
+    pub fn attribute_note_section(
+        &self,
+        note_id: &str,
+        section: &str,
+        author_id: &str,
+    ) -> Result<crate::note_authors::NoteAuthorship, VaultError> {
+        let conn = self.conn()?;
+        self.ensure_note_unsigned(note_id)?;
+        let current = self.get_note_authorship(note_id)?;
+        let author = current.authors.iter().find(|a| a.author_id == author_id)
+            .ok_or_else(|| VaultError::NotFound(format!("Author {} on note {}", author_id, note_id)))?;
+        let section = crate::note_authors::section_key(section);
         
-        let now = chrono::Utc::now().timestamp();
-        let reviews = stmt.query_map([trainee_id], |row| {
-            let submitted_at: i64 = row.get(4)?;
-            let days_pending = ((now - submitted_at) / 86400) as i32;
-            Ok(crate::models::PendingReview {
-                note_id: row.get(0)?,
-                trainee_name: row.get(1)?,
-                client_name: row.get(2)?,
-                session_date: row.get(3)?,
-                submitted_at: chrono::DateTime::from_timestamp(submitted_at, 0)
-                    .map(|dt| dt.format("%Y-%m-%d").to_string())
-                    .unwrap_or_default(),
-                days_pending,
-            })
-        })?.filter_map(|r| r.ok()).collect();
+        conn.execute(
+            "INSERT INTO note_section_authors (note_id, section, author_id, attributed_at) VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(note_id, section) DO UPDATE SET author_id = excluded.author_id, attributed_at = excluded.attributed_at",
+            params![note_id, &section, author_id, chrono::Utc::now().timestamp_millis()],
+        )?;
+        conn.execute(
+            "UPDATE note_authors SET signed_at = NULL, signed_text_sha256 = NULL, contribution_sha256 = NULL
+             WHERE note_id = ?1",
+            [note_id],
+        )?;
+        crate::audit::log_note_author(
+            conn, crate::models::AuditEventType::NoteSectionAttributed, note_id, author.role.as_str(), None,
+        ).map_err(|e| VaultError::Internal(format!("Audit write failed: {}", e)))?;
+        self.get_note_authorship(note_id)
+    }
+    
+    /// Set the secret `author_id` enters to sign contributions. Once set it
+    /// can only be replaced by someone who knows it (`current`), as with
+    /// the emergency access credential.
+    pub fn set_author_signing_credential(
+        &self,
+        author_id: &str,
+        credential: &str,
+        current: Option<&str>,
+    ) -> Result<(), VaultError> {
+        let conn = self.conn()?;
+        let author_id = author_id.trim();
+        if author_id.is_empty() {
+            return Err(VaultError::InvalidState("Author id is required".to_string()));
+        }
+        if credential.chars().count() < AUTHOR_CREDENTIAL_MIN_CHARS {
+            return Err(VaultError::InvalidState(format!(
+                "Signing credential must be at least {} characters", AUTHOR_CREDENTIAL_MIN_CHARS
+            )));
+        }
         
-        Ok(reviews)
+        let replacing = self.author_signing_credential(author_id)?.is_some();
+        if replacing {
+            let verified = match current {
+                Some(current) => self.verify_author_signing_credential(author_id, current)?,
+                None => false,
+            };
+            if !verified {
+                crate::audit::log_author_credential_change(conn, author_id, "rejected", crate::models::AuditOutcome::Blocked)
+                    .map_err(|e| VaultError::Internal(format!("Audit write failed: {}", e)))?;
+                return Err(VaultError::InvalidState(
+                    "Enter the current signing credential to replace it".to_string()
+                ));
+            }
+        }
+        
+        let salt = crypto::generate_salt();
+        let hash = crypto::hash_secret(credential, &salt)?;
+        conn.execute(
+            "INSERT OR REPLACE INTO author_signing_credentials (author_id, salt, hash, updated_at)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![author_id, hex::encode(salt), hash, chrono::Utc::now().timestamp_millis()],
+        )?;
+        crate::audit::log_author_credential_change(
+            conn,
+            author_id,
+            if replacing { "replaced" } else { "set" },
+            crate::models::AuditOutcome::Success,
+        ).map_err(|e| VaultError::Internal(format!("Audit write failed: {}", e)))?;
+        Ok(())
+    }
+    
+    fn author_signing_credential(&self, author_id: &str) -> Result<Option<(String, String)>, VaultError> {
+        let conn = self.conn()?;
+        Ok(conn.query_row(
+            "SELECT salt, hash FROM author_signing_credentials WHERE author_id = ?1",
+            [author_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        ).optional()?)
+    }
+    
+    fn verify_author_signing_credential(&self, author_id: &str, credential: &str) -> Result<bool, VaultError> {
+        let Some((salt, expected)) = self.author_signing_credential(author_id)? else {
+            return Err(VaultError::InvalidState(format!("No signing credential set for author {}", author_id)));
+        };
+        let salt: [u8; 16] = hex::decode(&salt).ok()
+            .and_then(|b| b.try_into().ok())
+            .ok_or_else(|| VaultError::Internal("Corrupt signing credential".to_string()))?;
+        Ok(crypto::digests_match(&crypto::hash_secret(credential, &salt)?, &expected))
+    }
+    
+    /// Sign one author's contribution over the note's current text. The
+    /// author's own signing credential is required; a wrong one is audited
+    /// as blocked.
+    pub fn sign_note_contribution(
+        &self,
+        note_id: &str,
+        author_id: &str,
+        credential: &str,
+    ) -> Result<crate::note_authors::NoteAuthorship, VaultError> {
+        let conn = self.conn()?;
+        self.ensure_note_unsigned(note_id)?;
+        let current = self.get_note_authorship(note_id)?;
+        let author = current.authors.iter().find(|a| a.author_id == author_id)
+            .ok_or_else(|| VaultError::NotFound(format!("Author {} on note {}", author_id, note_id)))?;
+        if !self.verify_author_signing_credential(author_id, credential)? {
+            crate::audit::log_contribution_signature_refused(conn, note_id, author.role.as_str())
+                .map_err(|e| VaultError::Internal(format!("Audit write failed: {}", e)))?;
+            return Err(VaultError::InvalidState(format!("Signing credential for {} does not match", author.author_name)));
+        }
+        let contribution = current.contributions.iter().find(|c| c.author_id == author_id)
+            .ok_or_else(|| VaultError::Internal(format!("No contribution computed for {}", author_id)))?;
+        
+        conn.execute(
+            "UPDATE note_authors SET signed_at = ?1, signed_text_sha256 = ?2, contribution_sha256 = ?3
+             WHERE note_id = ?4 AND author_id = ?5",
+            params![chrono::Utc::now().timestamp_millis(), &current.text_sha256,
+                    &contribution.contribution_sha256, note_id, author_id],
+        )?;
+        crate::audit::log_note_author(
+            conn, crate::models::AuditEventType::NoteContributionSigned, note_id, author.role.as_str(),
+            Some(&contribution.contribution_sha256),
+        ).map_err(|e| VaultError::Internal(format!("Audit write failed: {}", e)))?;
+        self.get_note_authorship(note_id)
     }
     
-    pub fn add_review_comment(
+    // ============================================
+    // EHR Delivery Queue
+    // ============================================
+    
+    /// Queue a note for export to an EHR. The first attempt is due at once.
+    pub fn enqueue_ehr_delivery(
         &self,
         note_id: &str,
-        supervisor_id: &str,
-        comment_type: &str,
-        text: &str,
-        section: Option<&str>,
-    ) -> Result<crate::models::ReviewComment, VaultError> {
+        options: &crate::ehr_export::ExportOptions,
+        output_dir: &str,
+    ) -> Result<crate::ehr_export::EhrDelivery, VaultError> {
         let conn = self.conn()?;
-        let id = uuid::Uuid::new_v4().to_string();
+        self.get_note(note_id)?;
+        let id = crate::ids::new_id();
         let now = chrono::Utc::now().timestamp();
-        
+        let options_json = serde_json::to_string(options).map_err(|e| VaultError::Serialization(e.to_string()))?;
         conn.execute(
-            "INSERT INTO review_comments (id, note_id, supervisor_id, section, comment_type, text, created_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
-            rusqlite::params![id, note_id, supervisor_id, section, comment_type, text, now],
+            "INSERT INTO ehr_deliveries
+             (id, note_id, target, status, next_attempt_at, options_json, output_dir, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?5, ?5)",
+            params![&id, note_id, options.target.id(), crate::ehr_export::DeliveryStatus::Pending.as_str(),
+                    now, options_json, output_dir],
         )?;
-        
-        Ok(crate::models::ReviewComment {
-            id,
-            section: section.map(|s| s.to_string()),
-            comment_type: comment_type.to_string(),
-            text: text.to_string(),
-            created_at: now,
-        })
+        crate::audit::log_ehr_delivery(
+            conn, crate::models::AuditEventType::EhrDeliveryQueued, &id,
+            crate::models::AuditOutcome::Success, options.target.id(), 0, None,
+        ).map_err(|e| VaultError::Internal(format!("Audit write failed: {}", e)))?;
+        self.get_ehr_delivery(&id)
     }
     
-    pub fn complete_review(
+    /// What a delivery exports: the note id, the options and the output directory
+    pub fn ehr_delivery_payload(
         &self,
-        note_id: &str,
-        supervisor_id: &str,
-        status: &str,
-        overall_feedback: Option<&str>,
-        clinical_accuracy_score: Option<i32>,
-        documentation_quality_score: Option<i32>,
-    ) -> Result<crate::models::SupervisorReview, VaultError> {
+        delivery_id: &str,
+    ) -> Result<(String, crate::ehr_export::ExportOptions, String), VaultError> {
         let conn = self.conn()?;
-        let now = chrono::Utc::now();
-        let review_id = uuid::Uuid::new_v4().to_string();
+        let (note_id, options_json, output_dir): (String, String, String) = conn.query_row(
+            "SELECT note_id, options_json, output_dir FROM ehr_deliveries WHERE id = ?1",
+            [delivery_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        ).optional()?.ok_or_else(|| VaultError::NotFound(format!("Delivery {}", delivery_id)))?;
+        Ok((
+            note_id,
+            serde_json::from_str(&options_json).map_err(|e| VaultError::Serialization(e.to_string()))?,
+            output_dir,
+        ))
+    }
+    
+    /// Record one attempt: Ok((file path, file SHA-256)) or Err(error text).
+    /// A failure schedules the next attempt with backoff until attempts run out.
+    pub fn record_ehr_delivery_attempt(
+        &self,
+        delivery_id: &str,
+        outcome: Result<(&str, &str), &str>,
+    ) -> Result<crate::ehr_export::EhrDelivery, VaultError> {
+        use crate::ehr_export::DeliveryStatus;
+        use crate::models::{AuditEventType, AuditOutcome};
         
-        // Update the review record
+        let conn = self.conn()?;
+        let delivery = self.get_ehr_delivery(delivery_id)?;
+        if !matches!(delivery.status, DeliveryStatus::Pending | DeliveryStatus::Failed) {
+            return Err(VaultError::InvalidState(format!(
+                "Delivery {} is {}; nothing to attempt", delivery_id, delivery.status.as_str()
+            )));
+        }
+        let attempts = delivery.attempts + 1;
+        let now = chrono::Utc::now().timestamp();
+        let audit = match outcome {
+            Ok((file_path, content_sha256)) => {
+                conn.execute(
+                    "UPDATE ehr_deliveries SET status = ?1, attempts = ?2, next_attempt_at = NULL, last_error = NULL,
+                            file_path = ?3, content_sha256 = ?4, sent_at = ?5, updated_at = ?5
+                     WHERE id = ?6",
+                    params![DeliveryStatus::Sent.as_str(), attempts, file_path, content_sha256, now, delivery_id],
+                )?;
+                crate::audit::log_ehr_delivery(
+                    conn, AuditEventType::EhrDeliverySent, delivery_id, AuditOutcome::Success,
+                    delivery.target.id(), attempts, Some(content_sha256),
+                )
+            }
+            Err(error) => {
+                let next_attempt_at = crate::ehr_export::next_attempt_after_failure(attempts, now);
+                conn.execute(
+                    "UPDATE ehr_deliveries SET status = ?1, attempts = ?2, next_attempt_at = ?3, last_error = ?4, updated_at = ?5
+                     WHERE id = ?6",
+                    params![DeliveryStatus::Failed.as_str(), attempts, next_attempt_at, error, now, delivery_id],
+                )?;
+                crate::audit::log_ehr_delivery(
+                    conn, AuditEventType::EhrDeliveryFailed, delivery_id, AuditOutcome::Failure,
+                    delivery.target.id(), attempts, None,
+                )
+            }
+        };
+        audit.map_err(|e| VaultError::Internal(format!("Audit write failed: {}", e)))?;
+        self.get_ehr_delivery(delivery_id)
+    }
+    
+    /// Put a failed or sent delivery back in the queue, due now
+    pub fn requeue_ehr_delivery(&self, delivery_id: &str) -> Result<crate::ehr_export::EhrDelivery, VaultError> {
+        use crate::ehr_export::DeliveryStatus;
+        
+        let conn = self.conn()?;
+        let delivery = self.get_ehr_delivery(delivery_id)?;
+        if !matches!(delivery.status, DeliveryStatus::Failed | DeliveryStatus::Sent) {
+            return Err(VaultError::InvalidState(format!(
+                "Delivery {} is {}; only failed or sent deliveries can be retried", delivery_id, delivery.status.as_str()
+            )));
+        }
+        let now = chrono::Utc::now().timestamp();
         conn.execute(
-            "UPDATE note_reviews SET status = ?1, completed_at = ?2, supervisor_id = ?3,
-             overall_feedback = ?4, clinical_accuracy_score = ?5, documentation_quality_score = ?6
-             WHERE note_id = ?7 AND status = 'pending'",
-            rusqlite::params![status, now.timestamp(), supervisor_id, overall_feedback, 
-                            clinical_accuracy_score, documentation_quality_score, note_id],
+            "UPDATE ehr_deliveries SET status = ?1, next_attempt_at = ?2, updated_at = ?2 WHERE id = ?3",
+            params![DeliveryStatus::Pending.as_str(), now, delivery_id],
         )?;
+        crate::audit::log_ehr_delivery(
+            conn, crate::models::AuditEventType::EhrDeliveryQueued, delivery_id,
+            crate::models::AuditOutcome::Success, delivery.target.id(), delivery.attempts, None,
+        ).map_err(|e| VaultError::Internal(format!("Audit write failed: {}", e)))?;
+        self.get_ehr_delivery(delivery_id)
+    }
+    
+    /// Confirm the EHR imported a sent delivery
+    pub fn acknowledge_ehr_delivery(
+        &self,
+        delivery_id: &str,
+        acknowledged_by: &str,
+        ehr_reference: Option<&str>,
+    ) -> Result<crate::ehr_export::EhrDelivery, VaultError> {
+        use crate::ehr_export::DeliveryStatus;
         
-        // If approved, update trainee stats
-        if status == "approved" {
-            conn.execute(
-                "UPDATE trainees SET notes_approved = notes_approved + 1 
-                 WHERE id = (SELECT trainee_id FROM note_reviews WHERE note_id = ?1)",
-                [note_id],
-            )?;
+        let conn = self.conn()?;
+        let delivery = self.get_ehr_delivery(delivery_id)?;
+        if delivery.status != DeliveryStatus::Sent {
+            return Err(VaultError::InvalidState(format!(
+                "Delivery {} is {}; only sent deliveries can be acknowledged", delivery_id, delivery.status.as_str()
+            )));
         }
-        
-        // Get comments for this review
-        let mut stmt = conn.prepare(
-            "SELECT id, section, comment_type, text, created_at FROM review_comments WHERE note_id = ?1"
+        let acknowledged_by = acknowledged_by.trim();
+        if acknowledged_by.is_empty() {
+            return Err(VaultError::InvalidState("Name who confirmed the import".to_string()));
+        }
+        let now = chrono::Utc::now().timestamp();
+        conn.execute(
+            "UPDATE ehr_deliveries SET status = ?1, acknowledged_at = ?2, acknowledged_by = ?3, ehr_reference = ?4, updated_at = ?2
+             WHERE id = ?5",
+            params![DeliveryStatus::Acknowledged.as_str(), now, acknowledged_by,
+                    ehr_reference.map(str::trim).filter(|r| !r.is_empty()), delivery_id],
         )?;
-        let comments: Vec<crate::models::ReviewComment> = stmt.query_map([note_id], |row| {
-            Ok(crate::models::ReviewComment {
-                id: row.get(0)?,
-                section: row.get(1)?,
-                comment_type: row.get(2)?,
-                text: row.get(3)?,
-                created_at: row.get(4)?,
-            })
-        })?.filter_map(|r| r.ok()).collect();
-        
-        Ok(crate::models::SupervisorReview {
-            id: review_id,
-            note_id: note_id.to_string(),
-            supervisor_id: supervisor_id.to_string(),
-            supervisor_name: supervisor_id.to_string(),  // Would need lookup
-            review_date: now.format("%Y-%m-%d").to_string(),
-            status: status.to_string(),
-            comments,
-            overall_feedback: overall_feedback.map(|s| s.to_string()),
-            clinical_accuracy_score,
-            documentation_quality_score,
-            created_at: now.timestamp(),
-        })
+        crate::audit::log_ehr_delivery(
+            conn, crate::models::AuditEventType::EhrDeliveryAcknowledged, delivery_id,
+            crate::models::AuditOutcome::Success, delivery.target.id(), delivery.attempts,
+            delivery.content_sha256.as_deref(),
+        ).map_err(|e| VaultError::Internal(format!("Audit write failed: {}", e)))?;
+        self.get_ehr_delivery(delivery_id)
     }
     
-    pub fn get_supervisor_dashboard(&self, supervisor_id: &str) -> Result<crate::models::SupervisorDashboard, VaultError> {
-        let trainees = self.list_trainees(supervisor_id)?;
-        let pending_reviews = self.get_pending_reviews(supervisor_id)?;
-        
-        let trainee_summaries: Vec<crate::models::TraineeSummary> = trainees.into_iter().map(|t| {
-            crate::models::TraineeSummary {
-                pending_notes: pending_reviews.iter().filter(|r| r.trainee_name == t.name).count() as i32,
-                avg_quality_score: None,  // Would calculate from completed reviews
-                last_submission: None,
-                trainee: t,
-            }
-        }).collect();
-        
-        Ok(crate::models::SupervisorDashboard {
-            supervisor_id: supervisor_id.to_string(),
-            trainees: trainee_summaries,
-            pending_reviews,
-            recent_activity: vec![],
-        })
+    pub fn get_ehr_delivery(&self, delivery_id: &str) -> Result<crate::ehr_export::EhrDelivery, VaultError> {
+        self.query_ehr_deliveries("WHERE id = ?1", params![delivery_id])?
+            .pop()
+            .ok_or_else(|| VaultError::NotFound(format!("Delivery {}", delivery_id)))
     }
     
-    pub fn get_note_reviews(&self, note_id: &str) -> Result<Vec<crate::models::SupervisorReview>, VaultError> {
+    /// Deliveries whose next automatic attempt is due at `now` (Unix seconds)
+    pub fn due_ehr_deliveries(&self, now: i64) -> Result<Vec<crate::ehr_export::EhrDelivery>, VaultError> {
+        self.query_ehr_deliveries(
+            "WHERE status IN ('pending', 'failed') AND next_attempt_at IS NOT NULL AND next_attempt_at <= ?1
+             ORDER BY next_attempt_at",
+            params![now],
+        )
+    }
+    
+    /// All deliveries, newest first; or, for reconciliation, those not yet
+    /// acknowledged, oldest first
+    pub fn list_ehr_deliveries(&self, unreconciled_only: bool) -> Result<Vec<crate::ehr_export::EhrDelivery>, VaultError> {
+        if unreconciled_only {
+            self.query_ehr_deliveries("WHERE status != 'acknowledged' ORDER BY created_at", [])
+        } else {
+            self.query_ehr_deliveries("ORDER BY created_at DESC", [])
+        }
+    }
+    
+    fn query_ehr_deliveries<P: rusqlite::Params>(&self, filter: &str, params: P) -> Result<Vec<crate::ehr_export::EhrDelivery>, VaultError> {
         let conn = self.conn()?;
-        
-        let mut stmt = conn.prepare(
-            "SELECT nr.id, nr.note_id, nr.supervisor_id, nr.status, nr.overall_feedback,
-                    nr.clinical_accuracy_score, nr.documentation_quality_score, nr.created_at, nr.completed_at
-             FROM note_reviews nr WHERE nr.note_id = ?1"
-        )?;
-        
-        let reviews = stmt.query_map([note_id], |row| {
-            let created_at: i64 = row.get(7)?;
-            Ok(crate::models::SupervisorReview {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT id, note_id, options_json, status, attempts, next_attempt_at, last_error, file_path, content_sha256,
+                    created_at, updated_at, sent_at, acknowledged_at, acknowledged_by, ehr_reference
+             FROM ehr_deliveries {}",
+            filter
+        ))?;
+        let deliveries = stmt.query_map(params, |row| {
+            let options_json: String = row.get(2)?;
+            let options: crate::ehr_export::ExportOptions = serde_json::from_str(&options_json)
+                .map_err(|e| rusqlite::Error::FromSqlConversionFailure(2, rusqlite::types::Type::Text, Box::new(e)))?;
+            let status: String = row.get(3)?;
+            Ok(crate::ehr_export::EhrDelivery {
                 id: row.get(0)?,
                 note_id: row.get(1)?,
-                supervisor_id: row.get::<_, Option<String>>(2)?.unwrap_or_default(),
-                supervisor_name: String::new(),
-                review_date: chrono::DateTime::from_timestamp(created_at, 0)
-                    .map(|dt| dt.format("%Y-%m-%d").to_string())
-                    .unwrap_or_default(),
-                status: row.get(3)?,
-                comments: vec![],
-                overall_feedback: row.get(4)?,
-                clinical_accuracy_score: row.get(5)?,
-                documentation_quality_score: row.get(6)?,
-                created_at,
+                target: options.target,
+                status: crate::ehr_export::DeliveryStatus::from_str(&status),
+                attempts: row.get(4)?,
+                next_attempt_at: row.get(5)?,
+                last_error: row.get(6)?,
+                file_path: row.get(7)?,
+                content_sha256: row.get(8)?,
+                created_at: row.get(9)?,
+                updated_at: row.get(10)?,
+                sent_at: row.get(11)?,
+                acknowledged_at: row.get(12)?,
+                acknowledged_by: row.get(13)?,
+                ehr_reference: row.get(14)?,
             })
-        })?.filter_map(|r| r.ok()).collect();
-        
-        Ok(reviews)
+        })?.collect::<Result<Vec<_>, _>>()?;
+        Ok(deliveries)
     }
     
     // ============================================
-    // De-identification Audit Trail
+    // Client Letters (visit summaries) and Releases
     // ============================================
     
-    pub fn save_deidentification_audit(
+    /// Draft a visit summary letter from a signed note
+    pub fn create_client_letter(
         &self,
-        note_id: Option<&str>,
-        client_id: Option<&str>,
-        result: &crate::deidentify::DeidentificationResult,
-        ai_enhanced: bool,
-    ) -> Result<crate::deidentify::DeidentificationAudit, VaultError> {
+        note_id: &str,
+        fields: &crate::client_letter::LetterFields,
+    ) -> Result<crate::client_letter::ClientLetter, VaultError> {
         let conn = self.conn()?;
-        let id = uuid::Uuid::new_v4().to_string();
+        let note = self.get_note(note_id)?;
+        if !matches!(note.status, NoteStatus::Signed | NoteStatus::Amended) {
+            return Err(VaultError::InvalidState("Letters can only be drafted from signed notes".to_string()));
+        }
+        let client = self.get_client(&note.client_id)?;
+        let practice = self.get_practice_profile()?;
+        
+        let body = crate::client_letter::render_letter(
+            &client.display_name,
+            &note.session_date,
+            fields,
+            practice.as_ref().map(|p| p.name.as_str()),
+        );
+        let id = crate::ids::new_id();
         let now = chrono::Utc::now().timestamp();
-        
-        // Build identifiers removed list
-        let identifiers_removed: Vec<crate::deidentify::AuditedIdentifier> = result.identifiers_found.iter()
-            .map(|i| crate::deidentify::AuditedIdentifier {
-                category_code: i.category.code().to_string(),
-                category_name: i.category.description().to_string(),
-                position: i.start_pos,
-                length: i.end_pos - i.start_pos,
-                replacement_type: "redact".to_string(),
-            })
-            .collect();
-        
-        let identifiers_json = serde_json::to_string(&identifiers_removed).unwrap_or_default();
-        let category_json = serde_json::to_string(&result.category_counts).unwrap_or_default();
-        
         conn.execute(
-            "INSERT INTO deidentification_audits 
-             (id, note_id, client_id, original_hash, deidentified_hash, identifiers_removed, 
-              category_summary, method, ai_enhanced, user_verified, created_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
-            rusqlite::params![
-                id, note_id, client_id, 
-                result.original_hash, result.deidentified_hash,
-                identifiers_json, category_json,
-                "safe_harbor", ai_enhanced, false, now
-            ],
+            "INSERT INTO client_letters (id, note_id, client_id, body, source, note_hash, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?7)",
+            params![&id, note_id, &note.client_id, &body,
+                    crate::client_letter::LetterSource::Template.as_str(), &note.content_hash, now],
         )?;
-        
-        Ok(crate::deidentify::DeidentificationAudit {
-            id,
-            note_id: note_id.map(|s| s.to_string()),
-            client_id: client_id.map(|s| s.to_string()),
-            original_hash: result.original_hash.clone(),
-            deidentified_hash: result.deidentified_hash.clone(),
-            identifiers_removed,
-            category_summary: result.category_counts.clone(),
-            method: "safe_harbor".to_string(),
-            ai_enhanced,
-            user_verified: false,
-            created_at: now,
-            exported_at: None,
-        })
+        self.get_client_letter(&id)
     }
     
-    pub fn get_deidentification_audits(
+    /// Replace the text of a letter that has not been finalized
+    pub fn update_client_letter(
         &self,
-        note_id: Option<&str>,
-    ) -> Result<Vec<crate::deidentify::DeidentificationAudit>, VaultError> {
+        letter_id: &str,
+        body: &str,
+        source: crate::client_letter::LetterSource,
+    ) -> Result<crate::client_letter::ClientLetter, VaultError> {
         let conn = self.conn()?;
-        
-        let sql = match note_id {
-            Some(_) => "SELECT id, note_id, client_id, original_hash, deidentified_hash, 
-                        identifiers_removed, category_summary, method, ai_enhanced, 
-                        user_verified, created_at, exported_at
-                        FROM deidentification_audits WHERE note_id = ?1 ORDER BY created_at DESC",
-            None => "SELECT id, note_id, client_id, original_hash, deidentified_hash, 
-                     identifiers_removed, category_summary, method, ai_enhanced, 
-                     user_verified, created_at, exported_at
-                     FROM deidentification_audits ORDER BY created_at DESC",
-        };
-        
-        let mut stmt = conn.prepare(sql)?;
-        
-        let audits = if let Some(nid) = note_id {
-            stmt.query_map([nid], Self::map_audit_row)?
-        } else {
-            stmt.query_map([], Self::map_audit_row)?
-        };
-        
-        Ok(audits.filter_map(|r| r.ok()).collect())
+        let letter = self.get_client_letter(letter_id)?;
+        if letter.finalized_at.is_some() {
+            return Err(VaultError::InvalidState("Letter is already finalized".to_string()));
+        }
+        if body.trim().is_empty() {
+            return Err(VaultError::InvalidState("Letter is empty".to_string()));
+        }
+        conn.execute(
+            "UPDATE client_letters SET body = ?1, source = ?2, updated_at = ?3 WHERE id = ?4",
+            params![body, source.as_str(), chrono::Utc::now().timestamp(), letter_id],
+        )?;
+        self.get_client_letter(letter_id)
     }
     
-    fn map_audit_row(row: &rusqlite::Row) -> rusqlite::Result<crate::deidentify::DeidentificationAudit> {
-        let identifiers_json: String = row.get(5)?;
-        let category_json: String = row.get(6)?;
-        
-        let identifiers: Vec<crate::deidentify::AuditedIdentifier> = 
-            serde_json::from_str(&identifiers_json).unwrap_or_default();
-        let categories: std::collections::HashMap<String, i32> = 
-            serde_json::from_str(&category_json).unwrap_or_default();
+    /// Lock a letter's text and record its hash. Refused if the note it
+    /// summarizes has changed since the letter was drafted.
+    pub fn finalize_client_letter(&self, letter_id: &str, finalized_by: &str) -> Result<crate::client_letter::ClientLetter, VaultError> {
+        let conn = self.conn()?;
+        let letter = self.get_client_letter(letter_id)?;
+        if letter.finalized_at.is_some() {
+            return Err(VaultError::InvalidState("Letter is already finalized".to_string()));
+        }
+        let finalized_by = finalized_by.trim();
+        if finalized_by.is_empty() {
+            return Err(VaultError::InvalidState("Name who is finalizing the letter".to_string()));
+        }
+        let note = self.get_note(&letter.note_id)?;
+        if !crypto::digests_match(&note.content_hash, &letter.note_hash) {
+            return Err(VaultError::InvalidState("Note has changed since this letter was drafted".to_string()));
+        }
         
-        Ok(crate::deidentify::DeidentificationAudit {
-            id: row.get(0)?,
-            note_id: row.get(1)?,
-            client_id: row.get(2)?,
-            original_hash: row.get(3)?,
-            deidentified_hash: row.get(4)?,
-            identifiers_removed: identifiers,
-            category_summary: categories,
-            method: row.get(7)?,
-            ai_enhanced: row.get(8)?,
-            user_verified: row.get(9)?,
-            created_at: row.get(10)?,
-            exported_at: row.get(11)?,
-        })
+        conn.execute(
+            "UPDATE client_letters SET content_hash = ?1, finalized_at = ?2, finalized_by = ?3 WHERE id = ?4",
+            params![crate::client_letter::letter_hash(&letter.body), chrono::Utc::now().timestamp(), finalized_by, letter_id],
+        )?;
+        self.get_client_letter(letter_id)
+    }
+    
+    pub fn get_client_letter(&self, letter_id: &str) -> Result<crate::client_letter::ClientLetter, VaultError> {
+        self.query_client_letters("WHERE id = ?1", params![letter_id])?
+            .pop()
+            .ok_or_else(|| VaultError::NotFound(format!("Letter {}", letter_id)))
+    }
+    
+    pub fn list_client_letters(&self, client_id: &str) -> Result<Vec<crate::client_letter::ClientLetter>, VaultError> {
+        self.query_client_letters("WHERE client_id = ?1", params![client_id])
+    }
+    
+    fn query_client_letters<P: rusqlite::Params>(&self, filter: &str, params: P) -> Result<Vec<crate::client_letter::ClientLetter>, VaultError> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT id, note_id, client_id, body, source, note_hash, content_hash, finalized_at, finalized_by,
+                    last_exported_at, created_at, updated_at
+             FROM client_letters {} ORDER BY created_at DESC",
+            filter
+        ))?;
+        let letters = stmt.query_map(params, |row| {
+            let body: String = row.get(3)?;
+            let source: String = row.get(4)?;
+            Ok(crate::client_letter::ClientLetter {
+                id: row.get(0)?,
+                note_id: row.get(1)?,
+                client_id: row.get(2)?,
+                document_type: crate::client_letter::LETTER_DOCUMENT_TYPE.to_string(),
+                readability: crate::client_letter::check(&body),
+                body,
+                source: crate::client_letter::LetterSource::from_str(&source),
+                note_hash: row.get(5)?,
+                content_hash: row.get(6)?,
+                finalized_at: row.get(7)?,
+                finalized_by: row.get(8)?,
+                last_exported_at: row.get(9)?,
+                created_at: row.get(10)?,
+                updated_at: row.get(11)?,
+            })
+        })?.collect::<Result<Vec<_>, _>>()?;
+        Ok(letters)
     }
     
-    // ============================================
-    // Consultation Draft Queue
-    // ============================================
-    
-    pub fn create_consultation_draft(
+    pub fn record_release_authorization(
         &self,
-        deidentified_content: &str,
-        title: &str,
-        clinical_question: &str,
-        specialties: &[String],
-        urgency: &str,
-        audit_id: &str,
-    ) -> Result<crate::deidentify::ConsultationDraft, VaultError> {
+        client_id: &str,
+        recipient: &str,
+        scope: crate::client_letter::ReleaseScope,
+        signed_on: chrono::NaiveDate,
+        expires_on: Option<chrono::NaiveDate>,
+    ) -> Result<crate::client_letter::ReleaseAuthorization, VaultError> {
         let conn = self.conn()?;
-        let id = uuid::Uuid::new_v4().to_string();
-        let now = chrono::Utc::now().timestamp();
-        let specialties_json = serde_json::to_string(specialties).unwrap_or_default();
+        self.get_client(client_id)?;
+        let recipient = recipient.trim();
+        if recipient.is_empty() {
+            return Err(VaultError::InvalidState("A release needs a recipient".to_string()));
+        }
+        if expires_on.map(|e| e < signed_on).unwrap_or(false) {
+            return Err(VaultError::InvalidState("Release expires before it was signed".to_string()));
+        }
         
+        let release = crate::client_letter::ReleaseAuthorization {
+            id: crate::ids::new_id(),
+            client_id: client_id.to_string(),
+            recipient: recipient.to_string(),
+            scope,
+            signed_on,
+            expires_on,
+            revoked_at: None,
+            created_at: chrono::Utc::now().timestamp(),
+        };
         conn.execute(
-            "INSERT INTO consultation_drafts 
-             (id, title, deidentified_content, clinical_question, specialties, 
-              urgency, audit_id, status, created_at, updated_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
-            rusqlite::params![
-                id, title, deidentified_content, clinical_question, specialties_json,
-                urgency, audit_id, "draft", now, now
-            ],
+            "INSERT INTO release_authorizations (id, client_id, recipient, scope, signed_on, expires_on, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![&release.id, client_id, &release.recipient, scope.as_str(),
+                    signed_on.to_string(), expires_on.map(|d| d.to_string()), release.created_at],
         )?;
-        
-        Ok(crate::deidentify::ConsultationDraft {
-            id,
-            title: title.to_string(),
-            deidentified_content: deidentified_content.to_string(),
-            clinical_question: clinical_question.to_string(),
-            specialties: specialties.to_vec(),
-            urgency: urgency.to_string(),
-            audit_id: audit_id.to_string(),
-            status: "draft".to_string(),
-            created_at: now,
-            updated_at: now,
-        })
+        Ok(release)
     }
     
-    pub fn list_consultation_drafts(&self) -> Result<Vec<crate::deidentify::ConsultationDraft>, VaultError> {
+    pub fn revoke_release_authorization(&self, release_id: &str) -> Result<crate::client_letter::ReleaseAuthorization, VaultError> {
         let conn = self.conn()?;
-        let mut stmt = conn.prepare(
-            "SELECT id, title, deidentified_content, clinical_question, specialties,
-                    urgency, audit_id, status, created_at, updated_at
-             FROM consultation_drafts ORDER BY updated_at DESC"
+        let release = self.get_release_authorization(release_id)?;
+        if release.revoked_at.is_some() {
+            return Err(VaultError::InvalidState("Release already revoked".to_string()));
+        }
+        conn.execute(
+            "UPDATE release_authorizations SET revoked_at = ?1 WHERE id = ?2",
+            params![chrono::Utc::now().timestamp(), release_id],
         )?;
-        
-        let drafts = stmt.query_map([], |row| {
-            let specialties_json: String = row.get(4)?;
-            let specialties: Vec<String> = serde_json::from_str(&specialties_json).unwrap_or_default();
-            
-            Ok(crate::deidentify::ConsultationDraft {
-                id: row.get(0)?,
-                title: row.get(1)?,
-                deidentified_content: row.get(2)?,
-                clinical_question: row.get(3)?,
-                specialties,
-                urgency: row.get(5)?,
-                audit_id: row.get(6)?,
-                status: row.get(7)?,
-                created_at: row.get(8)?,
-                updated_at: row.get(9)?,
-            })
-        })?.filter_map(|r| r.ok()).collect();
-        
-        Ok(drafts)
+        self.get_release_authorization(release_id)
     }
     
-    pub fn get_consultation_draft(&self, draft_id: &str) -> Result<crate::deidentify::ConsultationDraft, VaultError> {
+    fn get_release_authorization(&self, release_id: &str) -> Result<crate::client_letter::ReleaseAuthorization, VaultError> {
+        self.query_release_authorizations("WHERE id = ?1", params![release_id])?
+            .pop()
+            .ok_or_else(|| VaultError::NotFound(format!("Release {}", release_id)))
+    }
+    
+    pub fn list_release_authorizations(&self, client_id: &str) -> Result<Vec<crate::client_letter::ReleaseAuthorization>, VaultError> {
+        self.query_release_authorizations("WHERE client_id = ?1", params![client_id])
+    }
+    
+    fn query_release_authorizations<P: rusqlite::Params>(&self, filter: &str, params: P) -> Result<Vec<crate::client_letter::ReleaseAuthorization>, VaultError> {
         let conn = self.conn()?;
-        let mut stmt = conn.prepare(
-            "SELECT id, title, deidentified_content, clinical_question, specialties,
-                    urgency, audit_id, status, created_at, updated_at
-             FROM consultation_drafts WHERE id = ?1"
-        )?;
-        
-        stmt.query_row([draft_id], |row| {
-            let specialties_json: String = row.get(4)?;
-            let specialties: Vec<String> = serde_json::from_str(&specialties_json).unwrap_or_default();
-            
-            Ok(crate::deidentify::ConsultationDraft {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT id, client_id, recipient, scope, signed_on, expires_on, revoked_at, created_at
+             FROM release_authorizations {} ORDER BY signed_on DESC",
+            filter
+        ))?;
+        let parse_date = |s: String| chrono::NaiveDate::parse_from_str(&s, "%Y-%m-%d")
+            .map_err(|e| rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, Box::new(e)));
+        let releases = stmt.query_map(params, |row| {
+            let scope: String = row.get(3)?;
+            let expires_on: Option<String> = row.get(5)?;
+            Ok(crate::client_letter::ReleaseAuthorization {
                 id: row.get(0)?,
-                title: row.get(1)?,
-                deidentified_content: row.get(2)?,
-                clinical_question: row.get(3)?,
-                specialties,
-                urgency: row.get(5)?,
-                audit_id: row.get(6)?,
-                status: row.get(7)?,
-                created_at: row.get(8)?,
-                updated_at: row.get(9)?,
+                client_id: row.get(1)?,
+                recipient: row.get(2)?,
+                scope: crate::client_letter::ReleaseScope::from_str(&scope),
+                signed_on: parse_date(row.get(4)?)?,
+                expires_on: expires_on.map(parse_date).transpose()?,
+                revoked_at: row.get(6)?,
+                created_at: row.get(7)?,
             })
-        }).map_err(|e| VaultError::Database(e))
+        })?.collect::<Result<Vec<_>, _>>()?;
+        Ok(releases)
     }
     
-    pub fn update_consultation_draft(
+    /// Render a finalized letter for the client under a release on file.
+    /// The audit entry names the release and carries the letter's hash.
+    pub fn export_client_letter(
         &self,
-        draft_id: &str,
-        title: Option<&str>,
-        clinical_question: Option<&str>,
-        status: Option<&str>,
-    ) -> Result<(), VaultError> {
+        letter_id: &str,
+        release_id: &str,
+        today: chrono::NaiveDate,
+    ) -> Result<crate::client_letter::ExportedLetter, VaultError> {
         let conn = self.conn()?;
-        let now = chrono::Utc::now().timestamp();
-        
-        if let Some(t) = title {
-            conn.execute("UPDATE consultation_drafts SET title = ?1, updated_at = ?2 WHERE id = ?3",
-                        rusqlite::params![t, now, draft_id])?;
-        }
-        if let Some(q) = clinical_question {
-            conn.execute("UPDATE consultation_drafts SET clinical_question = ?1, updated_at = ?2 WHERE id = ?3",
-                        rusqlite::params![q, now, draft_id])?;
-        }
-        if let Some(s) = status {
-            conn.execute("UPDATE consultation_drafts SET status = ?1, updated_at = ?2 WHERE id = ?3",
-                        rusqlite::params![s, now, draft_id])?;
+        let letter = self.get_client_letter(letter_id)?;
+        let Some(content_hash) = letter.content_hash.clone() else {
+            return Err(VaultError::InvalidState("Finalize the letter before exporting it".to_string()));
+        };
+        let release = self.get_release_authorization(release_id)?;
+        if release.client_id != letter.client_id {
+            return Err(VaultError::InvalidState("Release is for a different client".to_string()));
         }
+        release.permits_visit_summary(today).map_err(VaultError::InvalidState)?;
         
-        Ok(())
-    }
-    
-    pub fn delete_consultation_draft(&self, draft_id: &str) -> Result<(), VaultError> {
-        let conn = self.conn()?;
-        conn.execute("DELETE FROM consultation_drafts WHERE id = ?1", [draft_id])?;
-        Ok(())
+        let practice = self.get_practice_profile()?;
+        let content = crate::client_letter::render_export(&letter, practice.as_ref());
+        let entry = crate::audit::log_client_letter_export(conn, letter_id, release_id, &content_hash)
+            .map_err(|e| VaultError::Internal(format!("Audit write failed: {}", e)))?;
+        conn.execute(
+            "UPDATE client_letters SET last_exported_at = ?1 WHERE id = ?2",
+            params![chrono::Utc::now().timestamp(), letter_id],
+        )?;
+        
+        let session_date = self.get_note(&letter.note_id).map(|n| n.session_date).unwrap_or_default();
+        Ok(crate::client_letter::ExportedLetter {
+            letter_id: letter_id.to_string(),
+            filename: format!("visit-summary-{}.txt", session_date),
+            content,
+            content_hash,
+            release_id: release_id.to_string(),
+            audit_entry_id: entry.id,
+        })
     }
 }
 
@@ -2662,6 +8869,40 @@ pub struct ClientDocument {
     pub document_date: Option<String>,
     pub created_at: i64,
     pub updated_at: i64,
+    /// Mean OCR word confidence (0-100); None for manual text or no OCR
+    #[serde(default)]
+    pub ocr_confidence: Option<f64>,
+    /// Tesseract language string of the OCR run (e.g. "spa+eng")
+    #[serde(default)]
+    pub ocr_language: Option<String>,
+    /// "printed", "handwriting" or "manual"
+    #[serde(default)]
+    pub ocr_profile: Option<String>,
+}
+
+/// Dashboard aggregates (from the `dashboard_counts` view)
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct DashboardSnapshot {
+    pub client_count: i64,
+    pub note_count: i64,
+    pub draft_note_count: i64,
+    pub signed_note_count: i64,
+    pub amended_note_count: i64,
+    pub document_count: i64,
+    pub document_size_bytes: i64,
+    pub pending_review_count: i64,
+    pub generated_at: i64,
+}
+
+/// Raw counts behind a per-client export estimate
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct ClientExportScope {
+    pub note_count: i64,
+    pub draft_note_count: i64,
+    pub note_bytes: i64,
+    pub document_count: i64,
+    pub document_bytes: i64,
+    pub audit_event_count: i64,
 }
 
 /// Storage statistics
@@ -2687,6 +8928,16 @@ pub struct SessionMetricRow {
     pub word_count: i32,
     pub ai_assisted: bool,
     pub created_at: i64,
+    /// Time actually spent, from a session timer (idle and paused time
+    /// excluded); None for sessions recorded as start/end only
+    pub active_seconds: Option<i64>,
+}
+
+impl SessionMetricRow {
+    /// Active time when a timer measured it, else end minus start
+    pub fn duration_seconds(&self) -> u64 {
+        self.active_seconds.unwrap_or(self.end_time - self.start_time).max(0) as u64
+    }
 }
 
 /// Aggregated metrics summary
@@ -2707,3 +8958,1028 @@ pub type SharedVault = Arc<Mutex<Vault>>;
 pub fn create_shared_vault(data_dir: PathBuf) -> SharedVault {
     Arc::new(Mutex::new(Vault::new(data_dir)))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::testing::FixtureBuilder;
+    
+    /// `table:note_id` for every row, in any table with a `note_id`
+    /// column, that still points at one of `note_ids`
+    fn rows_referencing_notes(vault: &Vault, note_ids: &[&str]) -> Vec<String> {
+        let conn = vault.conn().unwrap();
+        let tables: Vec<String> = conn.prepare(
+            "SELECT m.name FROM sqlite_master m, pragma_table_info(m.name) c
+             WHERE m.type = 'table' AND c.name = 'note_id' ORDER BY m.name"
+        ).unwrap().query_map([], |row| row.get(0)).unwrap().collect::<Result<_, _>>().unwrap();
+        
+        let mut found = Vec::new();
+        for table in tables {
+            for id in note_ids {
+                let count: i64 = conn.query_row(
+                    &format!("SELECT COUNT(*) FROM {} WHERE note_id = ?1", table), [id], |row| row.get(0),
+                ).unwrap();
+                if count > 0 {
+                    found.push(format!("{}:{}", table, id));
+                }
+            }
+        }
+        found
+    }
+    
+    #[test]
+    fn test_destroy_client_records_leaves_no_note_rows() {
+        let fixture = FixtureBuilder::new("destruction")
+            .client("Client A")
+            .signed_note("2020-03-01", NoteType::Progress, "Discussed sleep and work stress.")
+            .note("2020-03-08", NoteType::Progress, "Draft follow-up.")
+            .build()
+            .unwrap();
+        let vault = &fixture.vault;
+        let conn = vault.conn().unwrap();
+        let client_id = &fixture.clients[0].id;
+        for note in &fixture.notes {
+            conn.execute(
+                "INSERT INTO ehr_deliveries (id, note_id, target, status, options_json, output_dir,
+                 created_at, updated_at) VALUES (?1, ?2, 'fhir', 'pending', '{}', 'out', 0, 0)",
+                params![format!("delivery-{}", note.id), &note.id],
+            ).unwrap();
+            conn.execute(
+                "INSERT INTO note_authors (note_id, author_id, author_name, role, added_at)
+                 VALUES (?1, 'author', 'Author', 'primary', 0)",
+                [&note.id],
+            ).unwrap();
+            conn.execute(
+                "INSERT INTO note_section_authors (note_id, section, author_id, attributed_at)
+                 VALUES (?1, 'plan', 'author', 0)",
+                [&note.id],
+            ).unwrap();
+            conn.execute(
+                "INSERT INTO note_licensure_checks (note_id, check_json, checked_at) VALUES (?1, '{}', 0)",
+                [&note.id],
+            ).unwrap();
+        }
+        vault.update_note(&fixture.notes[1].id, "Edited follow-up.", None).unwrap();
+        conn.execute("UPDATE clients SET status = 'discharged' WHERE id = ?1", [client_id]).unwrap();
+        vault.set_client_retention_profile(&crate::retention::ClientRetentionProfile {
+            client_id: client_id.clone(),
+            jurisdiction: Some("CA".to_string()),
+            discharged_on: None,
+        }).unwrap();
+        conn.execute(
+            "INSERT INTO chart_access_grants (id, client_id, audit_entry_id, reason, created_at, expires_at)
+             VALUES ('grant', ?1, 'entry', 'treatment', 0, 0)",
+            [client_id],
+        ).unwrap();
+        conn.execute(
+            "INSERT INTO cohort_memberships (id, cohort_id, client_id, joined_on, created_at)
+             VALUES ('member', 'cohort', ?1, '2020-01-01', 0)",
+            [client_id],
+        ).unwrap();
+        
+        let note_ids: Vec<&str> = fixture.notes.iter().map(|n| n.id.as_str()).collect();
+        assert!(rows_referencing_notes(vault, &note_ids).iter().any(|r| r.starts_with("note_revisions:")));
+        
+        let secure_delete_before: i64 = conn.query_row("PRAGMA secure_delete", [], |row| row.get(0)).unwrap();
+        let today = chrono::NaiveDate::from_ymd_opt(2100, 1, 1).unwrap();
+        let certificate = vault.destroy_client_records(client_id, "Records officer", today).unwrap();
+        assert_eq!(certificate.notes_destroyed, 2);
+        assert_eq!(rows_referencing_notes(vault, &note_ids), Vec::<String>::new());
+        let notes_left: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM notes WHERE client_id = ?1", [client_id], |row| row.get(0),
+        ).unwrap();
+        assert_eq!(notes_left, 0);
+        
+        assert_eq!(certificate.method, crate::retention::DESTRUCTION_METHOD);
+        for table in ["chart_access_grants", "cohort_memberships"] {
+            let left: i64 = conn.query_row(
+                &format!("SELECT COUNT(*) FROM {} WHERE client_id = ?1", table), [client_id], |row| row.get(0),
+            ).unwrap();
+            assert_eq!(left, 0, "{}", table);
+        }
+        let secure_delete: i64 = conn.query_row("PRAGMA secure_delete", [], |row| row.get(0)).unwrap();
+        assert_eq!(secure_delete, secure_delete_before);
+        let profile_changes: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM audit_log WHERE resource_id = ?1 AND path_class = 'retention_profile'",
+            [client_id],
+            |row| row.get(0),
+        ).unwrap();
+        assert_eq!(profile_changes, 1);
+    }
+    
+    fn trash_fixture() -> super::testing::Fixture {
+        FixtureBuilder::new("trash")
+            .client("Client A")
+            .signed_note("2024-03-01", NoteType::Progress, "Signed session.")
+            .note("2024-03-08", NoteType::Progress, "Draft follow-up.")
+            .client("Client B")
+            .note("2024-03-02", NoteType::Intake, "Draft intake.")
+            .build()
+            .unwrap()
+    }
+    
+    #[test]
+    fn test_trash_list_and_restore() {
+        let fixture = trash_fixture();
+        let vault = &fixture.vault;
+        let (signed, draft, other) = (&fixture.notes[0].id, &fixture.notes[1].id, &fixture.notes[2].id);
+        let (client_a, client_b) = (&fixture.clients[0].id, &fixture.clients[1].id);
+        
+        assert!(matches!(vault.trash_note(signed), Err(VaultError::InvalidState(_))));
+        assert!(matches!(vault.trash_client(client_a), Err(VaultError::InvalidState(_))));
+        
+        vault.trash_note(draft).unwrap();
+        assert!(matches!(vault.get_note(draft), Err(VaultError::NotFound(_))));
+        assert!(matches!(vault.update_note(draft, "Edited.", None), Err(VaultError::InvalidState(_))));
+        assert!(matches!(vault.sign_note(draft, "[]"), Err(VaultError::InvalidState(_))));
+        assert!(!vault.list_notes(Some(client_a)).unwrap().iter().any(|n| &n.id == draft));
+        
+        vault.trash_client(client_b).unwrap();
+        let trash = vault.list_trash().unwrap();
+        let ids: Vec<&str> = trash.iter().map(|item| item.id.as_str()).collect();
+        assert_eq!(ids.len(), 2);
+        assert!(ids.contains(&draft.as_str()) && ids.contains(&client_b.as_str()));
+        assert!(trash.iter().all(|item| item.purge_after == item.deleted_at + TRASH_RETENTION_DAYS * 86_400_000));
+        assert!(!vault.list_clients().unwrap().iter().any(|c| &c.id == client_b));
+        // A trashed client's chart reads as missing however it is reached
+        assert!(matches!(vault.get_client(client_b), Err(VaultError::NotFound(_))));
+        assert!(matches!(vault.get_note(other), Err(VaultError::NotFound(_))));
+        assert!(vault.list_notes(Some(client_b)).unwrap().is_empty());
+        assert!(vault.search_note_text("intake", 10).unwrap().is_empty());
+        assert!(matches!(vault.trash_client(client_b), Err(VaultError::InvalidState(_))));
+        
+        assert_eq!(vault.restore_note(draft).unwrap().id, *draft);
+        assert_eq!(vault.update_note(draft, "Edited follow-up.", None).unwrap().raw_input, "Edited follow-up.");
+        vault.restore_client(client_b).unwrap();
+        assert!(vault.list_trash().unwrap().is_empty());
+        assert_eq!(vault.get_note(other).unwrap().client_id, *client_b);
+        assert_eq!(vault.search_note_text("intake", 10).unwrap().len(), 1);
+    }
+    
+    #[test]
+    fn test_purge_expired_trash() {
+        let fixture = trash_fixture();
+        let vault = &fixture.vault;
+        let (draft, other) = (&fixture.notes[1].id, &fixture.notes[2].id);
+        let client_b = &fixture.clients[1].id;
+        
+        let now = chrono::Utc::now().timestamp_millis();
+        for (key, result) in [("k-note", draft), ("k-client", client_b), ("k-other", other), ("k-kept", &fixture.notes[0].id)] {
+            let result_json = serde_json::json!({ "id": result }).to_string();
+            vault.store_idempotency_record(&crate::idempotency::IdempotencyRecord {
+                key: key.to_string(),
+                command: "create_note".to_string(),
+                request_hash: String::new(),
+                result_hash: crypto::hash_sha256(result_json.as_bytes()),
+                result_json,
+                created_at: now,
+                expires_at: now + 3_600_000,
+            }).unwrap();
+        }
+        
+        vault.trash_note(draft).unwrap();
+        vault.trash_client(client_b).unwrap();
+        assert_eq!(vault.purge_expired_trash(now).unwrap(), (0, 0));
+        assert_eq!(vault.list_trash().unwrap().len(), 2);
+        
+        let later = now + (TRASH_RETENTION_DAYS + 1) * 86_400_000;
+        assert_eq!(vault.purge_expired_trash(later).unwrap(), (1, 1));
+        assert!(vault.list_trash().unwrap().is_empty());
+        
+        let conn = vault.conn().unwrap();
+        let notes_left: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM notes WHERE id IN (?1, ?2)", params![draft, other], |row| row.get(0),
+        ).unwrap();
+        assert_eq!(notes_left, 0);
+        let (name, status): (String, String) = conn.query_row(
+            "SELECT display_name, status FROM clients WHERE id = ?1", [client_b], |row| Ok((row.get(0)?, row.get(1)?)),
+        ).unwrap();
+        assert_eq!(status, TRASH_PURGED_STATUS);
+        assert_ne!(name, "Client B");
+        assert!(matches!(vault.restore_client(client_b), Err(VaultError::NotFound(_))));
+        
+        // Only the untouched note's stored result survives
+        for (key, kept) in [("k-note", false), ("k-client", false), ("k-other", false), ("k-kept", true)] {
+            assert_eq!(vault.get_idempotency_record(key).unwrap().is_some(), kept, "{}", key);
+        }
+    }
+    
+    #[test]
+    fn test_contribution_signatures_need_each_authors_credential() {
+        use crate::note_authors::AuthorRole;
+        
+        let today = chrono::Utc::now().format("%Y-%m-%d").to_string();
+        let fixture = FixtureBuilder::new("co-authors")
+            .client("Client A")
+            .note(&today, NoteType::Progress, "## Assessment\nStable.\n\n## Plan\nContinue weekly sessions.")
+            .build()
+            .unwrap();
+        let vault = &fixture.vault;
+        let note_id = &fixture.notes[0].id;
+        vault.add_note_author(note_id, "dr-a", "Dr. A", None, AuthorRole::Primary).unwrap();
+        vault.add_note_author(note_id, "dr-b", "Dr. B", None, AuthorRole::CoTherapist).unwrap();
+        vault.set_author_signing_credential("dr-a", "a-secret", None).unwrap();
+        vault.set_author_signing_credential("dr-b", "b-secret", None).unwrap();
+        assert!(matches!(
+            vault.set_author_signing_credential("dr-b", "taken-over", Some("a-secret")),
+            Err(VaultError::InvalidState(_))
+        ));
+        
+        // Nobody signs another author's contribution
+        assert!(matches!(vault.sign_note_contribution(note_id, "dr-b", "a-secret"), Err(VaultError::InvalidState(_))));
+        let refused = fixture.audit_entries().unwrap().into_iter()
+            .filter(|e| matches!(e.event_type, crate::models::AuditEventType::NoteContributionSigned))
+            .filter(|e| matches!(e.outcome, crate::models::AuditOutcome::Blocked))
+            .count();
+        assert_eq!(refused, 1);
+        
+        // The vault, not just the command, holds the note until everyone signed
+        vault.sign_note_contribution(note_id, "dr-a", "a-secret").unwrap();
+        match vault.sign_note(note_id, "[]") {
+            Err(VaultError::InvalidState(message)) => assert!(message.contains("Dr. B"), "{}", message),
+            other => panic!("expected the co-author gate, got {:?}", other.map(|n| n.status)),
+        }
+        let authorship = vault.sign_note_contribution(note_id, "dr-b", "b-secret").unwrap();
+        assert!(authorship.complete);
+        assert_eq!(vault.sign_note(note_id, "[]").unwrap().status, NoteStatus::Signed);
+    }
+    
+    #[test]
+    fn test_emergency_access_activate_read_review() {
+        let today = chrono::Utc::now().format("%Y-%m-%d").to_string();
+        let fixture = FixtureBuilder::new("emergency")
+            .client("Client A")
+            .signed_note(&today, NoteType::Progress, "Safety plan reviewed.")
+            .note(&today, NoteType::Progress, "Unsigned draft.")
+            .build()
+            .unwrap();
+        let vault = &fixture.vault;
+        let client_id = &fixture.clients[0].id;
+        let justification = "Client in crisis, primary clinician unreachable";
+        
+        // The credential can only be replaced by someone who knows it
+        vault.set_emergency_access_credential("first-credential", None).unwrap();
+        assert!(matches!(
+            vault.set_emergency_access_credential("hijacked-credential", None),
+            Err(VaultError::InvalidState(_))
+        ));
+        assert!(matches!(
+            vault.set_emergency_access_credential("hijacked-credential", Some("wrong-credential")),
+            Err(VaultError::InvalidState(_))
+        ));
+        vault.set_emergency_access_credential("second-credential", Some("first-credential")).unwrap();
+        let credential_changes: Vec<Option<String>> = fixture.audit_entries().unwrap().into_iter()
+            .filter(|e| e.resource_id == "emergency_access_credential")
+            .map(|e| e.path_class)
+            .collect();
+        assert_eq!(credential_changes, [
+            Some("emergency_credential:set".to_string()),
+            Some("emergency_credential:rejected".to_string()),
+            Some("emergency_credential:rejected".to_string()),
+            Some("emergency_credential:replaced".to_string()),
+        ]);
+        
+        assert!(matches!(
+            vault.activate_emergency_access(client_id, "Dr. Cover", "first-credential", justification, 30),
+            Err(VaultError::InvalidState(_))
+        ));
+        let session = vault.activate_emergency_access(client_id, "Dr. Cover", "second-credential", justification, 30)
+            .unwrap();
+        
+        let notes = vault.read_emergency_access_notes(&session.id).unwrap();
+        assert_eq!(notes.len(), 1);
+        assert_eq!(notes[0].id, fixture.notes[0].id);
+        assert_eq!(vault.get_emergency_access_session(&session.id).unwrap().access_count, 1);
+        
+        assert!(matches!(
+            vault.review_emergency_access(&session.id, " dr. cover ", true, None),
+            Err(VaultError::InvalidState(_))
+        ));
+        assert!(matches!(
+            vault.review_emergency_access(&session.id, "Dr. Review", false, None),
+            Err(VaultError::InvalidState(_))
+        ));
+        let reviewed = vault.review_emergency_access(&session.id, "Dr. Review", true, None).unwrap();
+        assert_eq!(reviewed.review_status, "appropriate");
+        assert_eq!(reviewed.reviewed_by.as_deref(), Some("Dr. Review"));
+        assert!(vault.list_emergency_access_sessions(true).unwrap().is_empty());
+        
+        vault.end_emergency_access(&session.id).unwrap();
+        assert!(matches!(vault.read_emergency_access_notes(&session.id), Err(VaultError::InvalidState(_))));
+    }
+    
+    #[test]
+    fn test_note_history_keeps_prior_versions() {
+        let fixture = trash_fixture();
+        let vault = &fixture.vault;
+        let (signed, draft) = (&fixture.notes[0].id, &fixture.notes[1].id);
+        
+        vault.update_note(draft, "Edited follow-up.", Some("Dr. Reyes")).unwrap();
+        vault.update_note(draft, "Edited follow-up again.", Some("Dr. Lin")).unwrap();
+        let log = crate::note_diff::history(draft, vault.get_note_revisions(draft).unwrap());
+        let texts: Vec<&str> = log.revisions.iter().map(|r| r.raw_input.as_str()).collect();
+        assert_eq!(texts, ["Draft follow-up.", "Edited follow-up.", "Edited follow-up again."]);
+        assert_eq!(log.revisions[0].content_hash, crypto::hash_sha256(b"Draft follow-up."));
+        let editors: Vec<Option<&str>> = log.steps.iter().map(|s| s.changed_by.as_deref()).collect();
+        assert_eq!(editors, [Some("Dr. Reyes"), Some("Dr. Lin")]);
+        assert!(log.steps.iter().all(|s| s.change.as_deref() == Some("edit")));
+        
+        // An amendment made before note_revisions existed is backfilled, so
+        // the next amendment is still revision 1 -> 2
+        let conn = vault.conn().unwrap();
+        let original = vault.get_note(signed).unwrap().raw_input;
+        let amended = format!(
+            "{}\n\n--- AMENDMENT (2024-03-02 10:00:00 UTC) ---\nReason: late entry\nAmended: 2024-03-02 10:00:00 UTC\n\nAdded.",
+            original
+        );
+        conn.execute("UPDATE notes SET raw_input = ?1 WHERE id = ?2", params![&amended, signed]).unwrap();
+        conn.execute("UPDATE notes SET raw_input = ?1 WHERE id = ?2", params![&amended, &fixture.notes[2].id]).unwrap();
+        // The draft quoting an amendment block is not backfilled
+        assert_eq!(Vault::backfill_amendment_revisions(conn).unwrap(), 1);
+        assert_eq!(Vault::backfill_amendment_revisions(conn).unwrap(), 0);
+        
+        vault.amend_note(signed, "Second addendum.", "clarification", Some("Dr. Reyes")).unwrap();
+        let revisions = vault.get_note_revisions(signed).unwrap();
+        assert_eq!(revisions.len(), 3);
+        assert_eq!(revisions[0].raw_input, original);
+        assert_eq!(revisions[0].status, NoteStatus::Signed);
+        assert_eq!(revisions[0].superseded_at, Some(1_709_373_600_000));
+        assert_eq!(revisions[0].editor, None);
+        assert_eq!(revisions[1].raw_input, amended);
+        assert_eq!(revisions[1].editor.as_deref(), Some("Dr. Reyes"));
+        assert!(revisions[2].raw_input.ends_with("Second addendum."));
+    }
+    
+    #[test]
+    fn test_restricted_chart_needs_reason() {
+        let fixture = FixtureBuilder::new("chart-access")
+            .client("Closed Client")
+            .note("2024-01-10", NoteType::Progress, "Discharge summary.")
+            .client("Active Client")
+            .build()
+            .unwrap();
+        let vault = &fixture.vault;
+        let (closed, active) = (&fixture.clients[0].id, &fixture.clients[1].id);
+        let mut client = vault.get_client(closed).unwrap();
+        client.status = "closed".to_string();
+        vault.update_client(&client).unwrap();
+        
+        let mut policy = crate::policy::AccessPolicy::default();
+        assert!(vault.chart_access_allowed(closed, &policy).unwrap());
+        assert!(vault.restricted_chart_ids(&policy).unwrap().is_empty());
+        
+        policy.require_reason = true;
+        assert!(!vault.chart_access_allowed(closed, &policy).unwrap());
+        assert!(vault.chart_access_allowed(active, &policy).unwrap());
+        assert_eq!(vault.restricted_chart_ids(&policy).unwrap().into_iter().collect::<Vec<_>>(), [closed.clone()]);
+        
+        vault.record_chart_access(closed, AccessReason::ContinuityOfCare, None, 30).unwrap();
+        assert!(vault.chart_access_allowed(closed, &policy).unwrap());
+        assert!(vault.restricted_chart_ids(&policy).unwrap().is_empty());
+    }
+    
+    #[test]
+    fn test_backup_opens_after_key_rotation() {
+        let fixture = FixtureBuilder::new("rotation")
+            .client("Client A")
+            .signed_note("2024-02-01", NoteType::Progress, "Reviewed coping plan.")
+            .build()
+            .unwrap();
+        let vault = &fixture.vault;
+        let dir = std::env::temp_dir().join(format!("evidify-rekey-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let backup = dir.join("vault-20240201T000000Z.db");
+        drop(vault.open_snapshot(&backup).unwrap());
+        
+        let old_key = vault.vault_key.as_ref().unwrap();
+        let new_key = VaultKey::generate();
+        assert_eq!(Vault::rekey_backups(&dir, old_key, &new_key), (1, 0));
+        
+        let salt = crypto::generate_salt();
+        let wrapped = KEK::derive("new passphrase", &salt).unwrap().wrap(&new_key).unwrap();
+        Vault::check_database_opens(&backup, "new passphrase", &salt, &wrapped).unwrap();
+        let old_wrapped = KEK::derive("old passphrase", &salt).unwrap().wrap(old_key).unwrap();
+        assert!(Vault::check_database_opens(&backup, "old passphrase", &salt, &old_wrapped).is_err());
+        
+        std::fs::remove_dir_all(&dir).ok();
+    }
+    
+    #[test]
+    fn test_audit_slice_carries_only_the_trainees_entries() {
+        let today = chrono::Utc::now().format("%Y-%m-%d").to_string();
+        let fixture = FixtureBuilder::new("audit-slice")
+            .client("Client A")
+            .note(&today, NoteType::Progress, "Trainee's session note.")
+            .client("Client B")
+            .note(&today, NoteType::Progress, "Another clinician's note.")
+            .build()
+            .unwrap();
+        let vault = &fixture.vault;
+        let trainee = vault.create_trainee("Intern A", None, "supervisor-1").unwrap();
+        vault.submit_note_for_review(&fixture.notes[0].id, &trainee.id).unwrap();
+        vault.update_note(&fixture.notes[0].id, "Trainee's revised note.", None).unwrap();
+        vault.lookup_client_by_hash(crate::models::ClientIdentifierKind::Phone, &crypto::hash_sha256(b"555-0100"))
+            .unwrap();
+        vault.set_id_scheme(crate::ids::IdScheme::V4).unwrap();
+        
+        let slice = vault.export_audit_slice(&trainee.id, 0, i64::MAX, "supervision history").unwrap();
+        assert_eq!(slice.trainee_name, "Intern A");
+        let trainee_chart = [fixture.clients[0].id.as_str(), fixture.notes[0].id.as_str()];
+        assert!(!slice.entries.is_empty());
+        assert!(slice.entries.iter().all(|e| trainee_chart.contains(&e.resource_id.as_str())));
+        assert!(slice.chain.len() > slice.entries.len());
+        let json = serde_json::to_string(&slice).unwrap();
+        for omitted in [fixture.clients[1].id.as_str(), fixture.notes[1].id.as_str(), "hash-lookup"] {
+            assert!(!json.contains(omitted), "{}", omitted);
+        }
+        
+        let fingerprint = vault.install_signing_identity().unwrap().fingerprint;
+        let record = vault.import_audit_slice(&slice, &fingerprint).unwrap();
+        assert_eq!(record.entry_count as usize, slice.entries.len());
+        assert!(matches!(vault.export_audit_slice("no-such-trainee", 0, i64::MAX, "x"), Err(VaultError::NotFound(_))));
+    }
+    
+    #[test]
+    fn test_client_hash_lookup_keeps_hash_out_of_audit() {
+        let fixture = FixtureBuilder::new("hash-lookup").client("Client A").build().unwrap();
+        let vault = &fixture.vault;
+        let hash = crypto::hash_sha256(b"555-0100");
+        
+        let result = vault.lookup_client_by_hash(crate::models::ClientIdentifierKind::Phone, &hash).unwrap();
+        assert!(!result.exists);
+        
+        let entry = fixture.audit_entries().unwrap().pop().unwrap();
+        assert!(matches!(entry.event_type, crate::models::AuditEventType::ClientHashLookup));
+        assert_eq!(entry.path_class.as_deref(), Some("lookup:phone:not_found"));
+        assert_eq!(entry.path_hash, None);
+        assert!(!serde_json::to_string(&entry).unwrap().contains(&hash));
+    }
+    
+    #[test]
+    fn test_derived_cache_follows_note_edits_and_purges() {
+        let fixture = trash_fixture();
+        let vault = &fixture.vault;
+        let (signed, draft, other) = (&fixture.notes[0].id, &fixture.notes[1].id, &fixture.notes[2].id);
+        let (client_a, client_b) = (&fixture.clients[0].id, &fixture.clients[1].id);
+        let cached = |key: &str| -> i64 {
+            vault.conn().unwrap().query_row(
+                "SELECT COUNT(*) FROM derived_cache WHERE cache_key = ?1", [key], |row| row.get(0),
+            ).unwrap()
+        };
+        for key in [signed, draft, other, client_a, client_b] {
+            vault.cache_put("completion_check", key, "hash", "v1", &"derived").unwrap();
+        }
+        
+        vault.update_note(draft, "Edited follow-up.", None).unwrap();
+        assert_eq!((cached(draft), cached(client_a), cached(signed)), (0, 0, 1));
+        
+        vault.amend_note(signed, "Corrected date.", "Typo", None).unwrap();
+        assert_eq!(cached(signed), 0);
+        
+        vault.cache_put("prep_sheet", client_a, "hash", "v1", &"derived").unwrap();
+        vault.cache_put("completion_check", draft, "hash", "v1", &"derived").unwrap();
+        vault.trash_note(draft).unwrap();
+        vault.purge_note(draft).unwrap();
+        assert_eq!((cached(draft), cached(client_a)), (0, 0));
+        
+        vault.trash_client(client_b).unwrap();
+        vault.purge_client(client_b).unwrap();
+        assert_eq!((cached(other), cached(client_b)), (0, 0));
+    }
+    
+    #[test]
+    fn test_search_index_maintenance_keeps_model_versioned_cache() {
+        let fixture = FixtureBuilder::new("cache-maintenance").client("Client A").build().unwrap();
+        let vault = &fixture.vault;
+        vault.cache_put("completion_check", "note-1", "hash", "prompt=1;model=llama3", &"derived").unwrap();
+        vault.cache_put("prep_sheet", "client-current", "hash", DERIVED_RULES_VERSION, &"derived").unwrap();
+        vault.cache_put("prep_sheet", "client-stale", "hash", "rules=0.0.1", &"derived").unwrap();
+        
+        vault.maintenance_search_index().unwrap();
+        let kept: Vec<String> = vault.conn().unwrap()
+            .prepare("SELECT cache_key FROM derived_cache ORDER BY cache_key").unwrap()
+            .query_map([], |row| row.get(0)).unwrap().collect::<Result<_, _>>().unwrap();
+        assert_eq!(kept, vec!["client-current", "note-1"]);
+    }
+    
+    #[test]
+    fn test_snapshot_copy_reads_without_the_vault_connection() {
+        let fixture = FixtureBuilder::new("snapshot-copy")
+            .client("Client A")
+            .note("2024-03-01", NoteType::Progress, "Session one.")
+            .signed_note("2024-03-08", NoteType::Progress, "Session two.")
+            .build()
+            .unwrap();
+        let dir = std::env::temp_dir().join(format!("evidify-snapshot-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let database = dir.join("vault.db");
+        drop(fixture.vault.open_snapshot(&database).unwrap());
+        
+        let source = SnapshotSource {
+            database,
+            key: VaultKey(fixture.vault.vault_key.as_ref().unwrap().0),
+            data_dir: dir.clone(),
+        };
+        let copy = source.copy_to(&dir.join("copy.db")).unwrap();
+        let count = |vault: &Vault| -> i64 {
+            vault.conn().unwrap().query_row("SELECT count(*) FROM notes", [], |row| row.get(0)).unwrap()
+        };
+        assert_eq!(count(&copy), 2);
+        assert_eq!(copy.data_dir(), dir.as_path());
+        // Read-only, like the replica it backs
+        assert!(copy.conn().unwrap().execute("DELETE FROM notes", []).is_err());
+        drop(copy);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+    
+    #[test]
+    fn test_v7_scheme_keeps_chart_ids_random_and_is_audited() {
+        let fixture = FixtureBuilder::new("id-scheme").client("Client A").build().unwrap();
+        let vault = &fixture.vault;
+        vault.set_id_scheme(crate::ids::IdScheme::V7).unwrap();
+        let client = vault.create_client("Client B");
+        let note = client.as_ref().ok()
+            .map(|c| vault.create_note(&c.id, "2024-03-01", NoteType::Progress, "Session."));
+        vault.set_id_scheme(crate::ids::IdScheme::V4).unwrap();
+        
+        let client = client.unwrap();
+        let note = note.unwrap().unwrap();
+        assert_eq!(uuid::Uuid::parse_str(&client.id).unwrap().get_version_num(), 4);
+        assert_eq!(uuid::Uuid::parse_str(&note.id).unwrap().get_version_num(), 4);
+        let changes: Vec<String> = vault.conn().unwrap()
+            .prepare("SELECT path_class FROM audit_log WHERE resource_id = 'id_scheme' ORDER BY sequence").unwrap()
+            .query_map([], |row| row.get(0)).unwrap().collect::<Result<_, _>>().unwrap();
+        assert_eq!(changes, vec!["id_scheme:v7", "id_scheme:v4"]);
+    }
+    
+    #[test]
+    fn test_blinded_review_is_recorded_against_the_content_seen() {
+        let fixture = FixtureBuilder::new("blinded-review")
+            .client("Client A")
+            .note("2024-03-01", NoteType::Progress, "Session with trainee.")
+            .build()
+            .unwrap();
+        let vault = &fixture.vault;
+        let note = &fixture.notes[0];
+        let trainee = vault.create_trainee("Trainee", None, "super1").unwrap();
+        vault.submit_note_for_review(&note.id, &trainee.id).unwrap();
+        
+        assert!(!vault.reviewed_blinded(&note.id, "super1", &note.content_hash).unwrap());
+        let view = vault.get_blinded_review_note(&note.id, "super1").unwrap();
+        assert!(vault.reviewed_blinded(&note.id, "super1", &view.note_hash).unwrap());
+        assert!(!vault.reviewed_blinded(&note.id, "super2", &view.note_hash).unwrap());
+        
+        // An edit after the blinded view is content the supervisor saw unblinded
+        let edited = vault.update_note(&note.id, "Session with trainee, edited.", None).unwrap();
+        assert!(!vault.reviewed_blinded(&note.id, "super1", &edited.content_hash).unwrap());
+    }
+    
+    #[test]
+    fn test_search_treats_like_wildcards_literally() {
+        let fixture = FixtureBuilder::new("like-escape")
+            .client("Client A")
+            .note("2024-03-01", NoteType::Progress, "Dose reduced by 50% this week.")
+            .note("2024-03-08", NoteType::Progress, "Dose reduced by 50 mg this week.")
+            .note("2024-03-15", NoteType::Progress, "Saved to shared\\notes for review.")
+            .note("2024-03-22", NoteType::Progress, "Saved to shared notes for review.")
+            .build()
+            .unwrap();
+        let vault = &fixture.vault;
+        let ids = |hits: Vec<crate::models::SearchResult>| -> Vec<String> {
+            hits.into_iter().map(|h| h.note_id).collect()
+        };
+        
+        assert_eq!(like_contains("50%_a\\b"), "%50\\%\\_a\\\\b%");
+        assert!(vault.search_everything("%%", 10).unwrap().is_empty());
+        assert!(vault.search_everything("__", 10).unwrap().is_empty());
+        
+        // Note text goes through the FTS5 index, where punctuation separates words
+        assert_eq!(ids(vault.search_note_text("\"50 mg\"", 10).unwrap()), [fixture.notes[1].id.clone()]);
+        assert_eq!(vault.search_note_text("dose 50", 10).unwrap().len(), 2);
+        assert_eq!(vault.search_note_text("shared\\notes", 10).unwrap().len(), 2);
+        
+        let hits = vault.search_everything("reduc*", 10).unwrap();
+        let notes: Vec<&crate::models::GlobalSearchResult> = hits.iter()
+            .filter(|h| h.entity_type == crate::models::SearchEntityType::Note)
+            .collect();
+        assert_eq!(notes.len(), 2);
+        assert!(notes.iter().all(|h| h.score > 0.0 && h.excerpt.contains("reduced")));
+    }
+    
+    #[test]
+    fn test_trusted_recipient_duplicate_and_retrust() {
+        let fixture = FixtureBuilder::new("recipients").client("Client A").build().unwrap();
+        let vault = &fixture.vault;
+        let key = crypto::envelope::RecipientKeyPair::generate().public_key_base64();
+        
+        let first = vault.add_trusted_recipient("Dr. Consult", &key).unwrap();
+        match vault.add_trusted_recipient("Dr. Consult again", &key) {
+            Err(VaultError::InvalidState(msg)) => assert_eq!(msg, "Recipient already trusted"),
+            other => panic!("expected InvalidState, got {:?}", other.map(|r| r.id)),
+        }
+        
+        vault.revoke_trusted_recipient(&first.id).unwrap();
+        let again = vault.add_trusted_recipient("Dr. Consult", &key).unwrap();
+        assert_eq!(again.fingerprint, first.fingerprint);
+    }
+    
+    #[test]
+    fn test_cohort_session_is_recorded_atomically() {
+        use crate::cohort::{AttendanceStatus, CohortSchedule, MemberSessionInput};
+        
+        let fixture = FixtureBuilder::new("cohort-session")
+            .client("Member A")
+            .client("Member B")
+            .build()
+            .unwrap();
+        let vault = &fixture.vault;
+        let schedule = CohortSchedule { weekdays: vec![2], start_time: "10:00".to_string(), duration_minutes: 90 };
+        let cohort = vault.create_cohort("IOP Tuesday", "IOP", &schedule).unwrap();
+        for client in &fixture.clients {
+            vault.add_cohort_member(&cohort.id, &client.id, "2024-03-01").unwrap();
+        }
+        let members: Vec<MemberSessionInput> = fixture.clients.iter().map(|c| MemberSessionInput {
+            client_id: c.id.clone(),
+            status: AttendanceStatus::Present,
+            minutes_attended: None,
+            individual_content: "Participated in skills practice.".to_string(),
+        }).collect();
+        
+        // Second member's attendance write fails after the first member's note was written
+        vault.conn().unwrap().execute_batch(&format!(
+            "CREATE TEMP TRIGGER fail_attendance BEFORE INSERT ON cohort_attendance
+             WHEN NEW.client_id = '{}' BEGIN SELECT RAISE(ABORT, 'attendance write failed'); END;",
+            fixture.clients[1].id
+        )).unwrap();
+        assert!(vault.record_cohort_session(&cohort.id, "2024-03-05", "Group practiced DBT skills.", &members, None).is_err());
+        let sessions: i64 = vault.conn().unwrap()
+            .query_row("SELECT COUNT(*) FROM cohort_sessions", [], |row| row.get(0)).unwrap();
+        assert_eq!(sessions, 0);
+        assert!(vault.list_notes(Some(&fixture.clients[0].id)).unwrap().is_empty());
+        
+        vault.conn().unwrap().execute_batch("DROP TRIGGER fail_attendance;").unwrap();
+        let record = vault.record_cohort_session(&cohort.id, "2024-03-05", "Group practiced DBT skills.", &members, None).unwrap();
+        assert_eq!(record.attendance.len(), 2);
+        assert!(record.attendance.iter().all(|a| a.note_id.is_some()));
+    }
+    
+    #[test]
+    fn test_group_supervision_validates_cases_and_is_audited() {
+        let fixture = FixtureBuilder::new("group-supervision")
+            .client("Case A")
+            .client("Case B")
+            .build()
+            .unwrap();
+        let vault = &fixture.vault;
+        let a = vault.create_trainee("Trainee A", None, "super1").unwrap();
+        let b = vault.create_trainee("Trainee B", None, "super1").unwrap();
+        let mut input = crate::models::NewGroupSupervisionSession {
+            supervisor_id: "super1".to_string(),
+            session_date: "2024-10-15".to_string(),
+            duration_minutes: 90,
+            present: vec![a.id.clone()],
+            absent: vec![b.id.clone()],
+            topics: vec!["Safety planning".to_string()],
+            case_client_ids: vec![fixture.clients[0].id.clone(), "no-such-client".to_string()],
+            notes: None,
+        };
+        let sessions = |vault: &Vault| -> i64 {
+            vault.conn().unwrap()
+                .query_row("SELECT COUNT(*) FROM group_supervision_sessions", [], |row| row.get(0)).unwrap()
+        };
+        
+        assert!(matches!(vault.record_group_supervision(&input), Err(VaultError::NotFound(_))));
+        vault.trash_client(&fixture.clients[1].id).unwrap();
+        input.case_client_ids = vec![fixture.clients[1].id.clone()];
+        assert!(matches!(vault.record_group_supervision(&input), Err(VaultError::NotFound(_))));
+        assert_eq!(sessions(vault), 0);
+        
+        input.case_client_ids = vec![fixture.clients[0].id.clone(), fixture.clients[0].id.clone()];
+        let session = vault.record_group_supervision(&input).unwrap();
+        assert_eq!(session.cases_discussed.len(), 1);
+        assert_eq!(sessions(vault), 1);
+        
+        let entries = crate::audit::get_entries(vault.conn().unwrap(), 100, 0).unwrap();
+        let entry = entries.iter()
+            .find(|e| matches!(e.event_type, crate::models::AuditEventType::GroupSupervisionRecorded))
+            .unwrap();
+        assert_eq!(entry.resource_id, session.id);
+        assert_eq!(entry.path_class.as_deref(), Some("group_supervision:present=1:absent=1:cases=1"));
+        assert!(entries.iter().all(|e| e.path_class.as_deref().map_or(true, |p| !p.contains(&fixture.clients[0].id))));
+    }
+    
+    #[test]
+    fn test_ehr_delivery_renders_the_current_note_at_send_time() {
+        let fixture = FixtureBuilder::new("ehr-delivery")
+            .client("Client A")
+            .signed_note("2024-03-01", NoteType::Progress, "Discussed sleep hygiene and work stress.")
+            .note("2024-03-08", NoteType::Progress, "Draft follow-up.")
+            .build()
+            .unwrap();
+        let vault = &fixture.vault;
+        let note_id = &fixture.notes[0].id;
+        let dir = std::env::temp_dir().join(format!("evidify-ehr-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let options = crate::ehr_export::ExportOptions {
+            target: crate::ehr_export::EhrTarget::PlainText,
+            signed_by: Some("Dr. Example".to_string()),
+            ..Default::default()
+        };
+        
+        let queued = vault.enqueue_ehr_delivery(note_id, &options, &dir.to_string_lossy()).unwrap();
+        let columns: Vec<String> = vault.conn().unwrap()
+            .prepare("SELECT name FROM pragma_table_info('ehr_deliveries')").unwrap()
+            .query_map([], |row| row.get(0)).unwrap()
+            .collect::<Result<_, _>>().unwrap();
+        assert!(!columns.iter().any(|c| c == "note_json"));
+        
+        // Amended after queueing: the delivery sends the amended note
+        vault.amend_note(note_id, "Sleep diary assigned.", "late entry", Some("Dr. Amender")).unwrap();
+        let (delivery, result) = crate::ehr_export::attempt_delivery(vault, &queued.id).unwrap();
+        assert_eq!(delivery.status, crate::ehr_export::DeliveryStatus::Sent);
+        let written = std::fs::read_to_string(result.unwrap().file_path.unwrap()).unwrap();
+        assert!(written.contains("Discussed sleep hygiene"));
+        assert!(written.contains("Sleep diary assigned."));
+        assert!(written.contains("Dr. Amender"));
+        
+        let note = crate::ehr_export::exportable_note(vault, note_id, None).unwrap();
+        assert_eq!(note.content, "Discussed sleep hygiene and work stress.");
+        assert_eq!(note.amendments.len(), 1);
+        assert_eq!(note.amendments[0].reason, "late entry");
+        
+        // A note gone from the chart fails the attempt instead of being sent
+        let draft = vault.enqueue_ehr_delivery(&fixture.notes[1].id, &options, &dir.to_string_lossy()).unwrap();
+        vault.trash_note(&fixture.notes[1].id).unwrap();
+        let (delivery, result) = crate::ehr_export::attempt_delivery(vault, &draft.id).unwrap();
+        assert!(result.is_none());
+        assert_eq!(delivery.status, crate::ehr_export::DeliveryStatus::Failed);
+        
+        std::fs::remove_dir_all(&dir).ok();
+    }
+    
+    #[test]
+    fn test_note_mse_persists_and_locks_on_sign() {
+        use crate::mse::*;
+        
+        let fixture = FixtureBuilder::new("mse")
+            .client("Client A")
+            .note("2024-03-08", NoteType::Progress, "Follow-up session.")
+            .note("2024-03-01", NoteType::Progress, "First session.")
+            .signed_note("2024-02-23", NoteType::Progress, "Signed session.")
+            .build()
+            .unwrap();
+        let vault = &fixture.vault;
+        let (later, earlier, signed) = (&fixture.notes[0].id, &fixture.notes[1].id, &fixture.notes[2].id);
+        let mut exam = MentalStatusExam {
+            appearance: Appearance::Casual,
+            psychomotor: Psychomotor::Normal,
+            speech: Speech::Normal,
+            mood: Mood::Depressed,
+            affect: Affect::Flat,
+            thought_process: ThoughtProcess::LinearGoalDirected,
+            suicidal_ideation: Ideation::Denied,
+            homicidal_ideation: Ideation::Denied,
+            perception: Perception::NoDisturbance,
+            orientation: 4,
+            insight: Rating::Good,
+            judgment: Rating::Good,
+            comments: Some("Tearful at times.".to_string()),
+        };
+        
+        let first = vault.record_note_mse(later, &exam).unwrap();
+        assert!(matches!(vault.record_note_mse(later, &exam), Err(VaultError::InvalidState(_))));
+        assert!(matches!(vault.update_note_mse(earlier, &exam), Err(VaultError::NotFound(_))));
+        assert!(matches!(vault.record_note_mse(signed, &exam), Err(VaultError::InvalidState(_))));
+        
+        exam.mood = Mood::Euthymic;
+        exam.affect = Affect::Full;
+        let updated = vault.update_note_mse(later, &exam).unwrap();
+        assert_eq!(updated.created_at, first.created_at);
+        let stored = vault.get_note_mse(later).unwrap().unwrap();
+        assert_eq!(stored.exam.total_severity(), exam.total_severity());
+        assert_eq!(stored.exam.comments.as_deref(), Some("Tearful at times."));
+        
+        exam.orientation = 5;
+        assert!(matches!(vault.record_note_mse(earlier, &exam), Err(VaultError::InvalidState(_))));
+        exam.orientation = 4;
+        vault.record_note_mse(earlier, &exam).unwrap();
+        let history = vault.get_client_mse_history(&fixture.clients[0].id).unwrap();
+        let dates: Vec<&str> = history.iter().map(|m| m.session_date.as_str()).collect();
+        assert_eq!(dates, ["2024-03-01", "2024-03-08"]);
+        
+        vault.sign_note(later, "[]").unwrap();
+        assert!(matches!(vault.update_note_mse(later, &exam), Err(VaultError::InvalidState(_))));
+    }
+    
+    #[test]
+    fn test_note_locks_collide_heartbeat_and_expire() {
+        let fixture = FixtureBuilder::new("note-locks")
+            .client("Client A")
+            .note("2024-03-01", NoteType::Progress, "Draft one.")
+            .note("2024-03-08", NoteType::Progress, "Draft two.")
+            .build()
+            .unwrap();
+        let vault = &fixture.vault;
+        let (note, other) = (&fixture.notes[0].id, &fixture.notes[1].id);
+        
+        let first = vault.acquire_note_lock(note, "window-a", Some("Main window"), None, false).unwrap();
+        assert!(first.acquired);
+        let blocked = vault.acquire_note_lock(note, "window-b", None, None, false).unwrap();
+        assert!(!blocked.acquired);
+        assert_eq!(blocked.lock.holder_label.as_deref(), Some("Main window"));
+        
+        // Heartbeat keeps the original acquisition time
+        let refreshed = vault.acquire_note_lock(note, "window-a", Some("Main window"), Some(600), false).unwrap();
+        assert_eq!(refreshed.lock.acquired_at, first.lock.acquired_at);
+        assert!(refreshed.lock.expires_at >= first.lock.expires_at);
+        
+        assert!(!vault.release_note_lock(note, "window-b").unwrap());
+        let taken = vault.acquire_note_lock(note, "window-b", None, None, true).unwrap();
+        assert!(taken.acquired);
+        assert_eq!(vault.get_note_lock(note).unwrap().unwrap().holder_id, "window-b");
+        
+        // An abandoned lock stops blocking once it expires
+        vault.conn().unwrap().execute("UPDATE note_locks SET expires_at = 0", []).unwrap();
+        assert!(vault.get_note_lock(note).unwrap().is_none());
+        assert!(vault.acquire_note_lock(note, "window-a", None, None, false).unwrap().acquired);
+        
+        vault.acquire_note_lock(other, "window-a", None, None, false).unwrap();
+        assert_eq!(vault.release_note_locks_for_holder("window-a").unwrap(), 2);
+        assert!(vault.get_note_lock(note).unwrap().is_none());
+        assert!(matches!(
+            vault.acquire_note_lock("no-such-note", "window-a", None, None, false),
+            Err(VaultError::NotFound(_))
+        ));
+    }
+    
+    #[test]
+    fn test_dashboard_counters_follow_writes_and_heal_drift() {
+        let fixture = FixtureBuilder::new("dashboard-counters")
+            .client("Client A")
+            .note("2024-03-01", NoteType::Progress, "Draft session.")
+            .signed_note("2024-03-08", NoteType::Progress, "Signed session.")
+            .client("Client B")
+            .build()
+            .unwrap();
+        let vault = &fixture.vault;
+        let counted = |sql: &str| -> i64 { vault.conn().unwrap().query_row(sql, [], |row| row.get(0)).unwrap() };
+        
+        let snapshot = vault.get_dashboard_snapshot().unwrap();
+        assert_eq!((snapshot.client_count, snapshot.note_count), (2, 2));
+        assert_eq!((snapshot.draft_note_count, snapshot.signed_note_count), (1, 1));
+        
+        vault.sign_note(&fixture.notes[0].id, "[]").unwrap();
+        let snapshot = vault.get_dashboard_snapshot().unwrap();
+        assert_eq!((snapshot.draft_note_count, snapshot.signed_note_count), (0, 2));
+        
+        vault.trash_client(&fixture.clients[1].id).unwrap();
+        vault.purge_client(&fixture.clients[1].id).unwrap();
+        assert_eq!(vault.get_dashboard_snapshot().unwrap().client_count, counted("SELECT COUNT(*) FROM clients"));
+        
+        // Drifted counters are rebuilt from the source tables on the next unlock
+        vault.conn().unwrap().execute("UPDATE vault_counters SET value = 99 WHERE name = 'notes'", []).unwrap();
+        assert_eq!(vault.get_dashboard_snapshot().unwrap().note_count, 99);
+        vault.run_migrations(vault.conn().unwrap()).unwrap();
+        let snapshot = vault.get_dashboard_snapshot().unwrap();
+        assert_eq!(snapshot.note_count, counted("SELECT COUNT(*) FROM notes"));
+        assert_eq!(snapshot.signed_note_count, counted("SELECT COUNT(*) FROM notes WHERE status = 'signed'"));
+    }
+    
+    #[test]
+    fn test_review_reflections_lock_once_acknowledged() {
+        let fixture = FixtureBuilder::new("reflections")
+            .client("Client A")
+            .note("2024-03-01", NoteType::Progress, "Trainee session one.")
+            .note("2024-03-08", NoteType::Progress, "Trainee session two.")
+            .build()
+            .unwrap();
+        let vault = &fixture.vault;
+        let trainee = vault.create_trainee("Trainee A", None, "super1").unwrap();
+        let (reviewed, pending) = (&fixture.notes[0].id, &fixture.notes[1].id);
+        vault.submit_note_for_review(reviewed, &trainee.id).unwrap();
+        vault.submit_note_for_review(pending, &trainee.id).unwrap();
+        let review_of = |note_id: &str| -> String {
+            vault.conn().unwrap()
+                .query_row("SELECT id FROM note_reviews WHERE note_id = ?1", [note_id], |row| row.get(0))
+                .unwrap()
+        };
+        let review_id = review_of(reviewed);
+        
+        assert!(matches!(
+            vault.save_review_reflection(&review_of(pending), &trainee.id, "Learned.", ""),
+            Err(VaultError::InvalidState(_))
+        ));
+        vault.complete_review(reviewed, "super1", "approved", Some("Good risk documentation."), Some(4), Some(5)).unwrap();
+        assert!(matches!(
+            vault.save_review_reflection(&review_id, "someone-else", "Learned.", ""),
+            Err(VaultError::InvalidState(_))
+        ));
+        assert!(matches!(vault.save_review_reflection(&review_id, &trainee.id, " ", ""), Err(VaultError::InvalidState(_))));
+        
+        let first = vault.save_review_reflection(&review_id, &trainee.id, " Name the means. ", "").unwrap();
+        assert_eq!(first.what_i_learned, "Name the means.");
+        let revised = vault.save_review_reflection(&review_id, &trainee.id, "Name the means.", "Ask about access.").unwrap();
+        assert_eq!((revised.id.as_str(), revised.created_at), (first.id.as_str(), first.created_at));
+        assert_eq!(vault.list_trainee_reflections(&trainee.id).unwrap().len(), 1);
+        
+        vault.acknowledge_review_reflection(&first.id, "super1").unwrap();
+        let stored = vault.get_review_reflection(&review_id).unwrap().unwrap();
+        assert_eq!(stored.acknowledged_by.as_deref(), Some("super1"));
+        assert_eq!(stored.what_i_will_change, "Ask about access.");
+        assert!(matches!(vault.acknowledge_review_reflection(&first.id, "super1"), Err(VaultError::NotFound(_))));
+        assert!(matches!(
+            vault.save_review_reflection(&review_id, &trainee.id, "Rewritten.", ""),
+            Err(VaultError::InvalidState(_))
+        ));
+        
+        let report = vault.get_trainee_competency_report(&trainee.id).unwrap();
+        assert_eq!(report.reviews_completed, 1);
+        assert_eq!(report.reflection_rate, Some(1.0));
+        assert_eq!(report.reflections.len(), 1);
+    }
+    
+    #[test]
+    fn test_export_presence_checks_run_once_when_due() {
+        let fixture = FixtureBuilder::new("presence-checks").client("Client A").build().unwrap();
+        let vault = &fixture.vault;
+        let dir = std::env::temp_dir().join(format!("evidify-presence-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let kept = dir.join("kept.json");
+        std::fs::write(&kept, b"{}").unwrap();
+        let removed = dir.join("removed.json");
+        
+        // Only overridden exports to monitored locations are followed up
+        assert!(vault.record_export_destination("export-0", kept.to_str().unwrap(), "removable", false, Some(7)).unwrap().is_none());
+        assert!(vault.record_export_destination("export-0", kept.to_str().unwrap(), "safe", true, Some(7)).unwrap().is_none());
+        let present = vault.record_export_destination("export-1", kept.to_str().unwrap(), "removable", true, Some(7)).unwrap().unwrap();
+        let absent = vault.record_export_destination("export-2", removed.to_str().unwrap(), "cloud_sync", true, Some(7)).unwrap().unwrap();
+        assert_eq!(present.due_at - present.exported_at, 7 * 86_400_000);
+        assert!(vault.run_due_presence_checks().unwrap().is_empty());
+        
+        vault.conn().unwrap().execute("UPDATE export_presence_checks SET due_at = 0", []).unwrap();
+        let ran = vault.run_due_presence_checks().unwrap();
+        let status = |id: &str| ran.iter().find(|c| c.id == id).unwrap().status.clone();
+        assert_eq!((status(&present.id), status(&absent.id)), ("present".to_string(), "absent".to_string()));
+        assert!(vault.run_due_presence_checks().unwrap().is_empty());
+        
+        let entries = crate::audit::get_entries(vault.conn().unwrap(), 100, 0).unwrap();
+        for check in vault.list_export_presence_checks().unwrap() {
+            let followed_up = entries.iter().find(|e| Some(&e.id) == check.check_audit_entry_id.as_ref()).unwrap();
+            let export = entries.iter().find(|e| e.id == check.export_audit_entry_id).unwrap();
+            assert_eq!(followed_up.path_hash, export.path_hash);
+        }
+        let dir_name = dir.to_string_lossy().to_string();
+        assert!(entries.iter().all(|e| e.path_class.as_deref().map_or(true, |p| !p.contains(&dir_name))));
+        
+        std::fs::remove_dir_all(&dir).ok();
+    }
+    
+    #[test]
+    fn test_supervisor_dashboard_scores_and_activity() {
+        let fixture = FixtureBuilder::new("supervisor-dashboard")
+            .client("Client A")
+            .note("2024-03-01", NoteType::Progress, "Session one.")
+            .note("2024-03-08", NoteType::Progress, "Session two.")
+            .note("2024-03-15", NoteType::Progress, "Session three.")
+            .note("2024-03-22", NoteType::Progress, "Other supervisor's trainee.")
+            .build()
+            .unwrap();
+        let vault = &fixture.vault;
+        let notes: Vec<&str> = fixture.notes.iter().map(|n| n.id.as_str()).collect();
+        let a = vault.create_trainee("Trainee A", None, "super1").unwrap();
+        let b = vault.create_trainee("Trainee B", None, "super1").unwrap();
+        let elsewhere = vault.create_trainee("Trainee C", None, "super2").unwrap();
+        
+        vault.submit_note_for_review(notes[0], &a.id).unwrap();
+        vault.submit_note_for_review(notes[1], &a.id).unwrap();
+        vault.submit_note_for_review(notes[2], &b.id).unwrap();
+        vault.submit_note_for_review(notes[3], &elsewhere.id).unwrap();
+        vault.complete_review(notes[0], "super1", "approved", None, Some(4), Some(2)).unwrap();
+        vault.complete_review(notes[1], "super1", "needs_revision", None, Some(2), None).unwrap();
+        vault.complete_review(notes[3], "super2", "approved", None, Some(1), Some(1)).unwrap();
+        let review_id: String = vault.conn().unwrap()
+            .query_row("SELECT id FROM note_reviews WHERE note_id = ?1", [notes[0]], |row| row.get(0))
+            .unwrap();
+        vault.save_review_reflection(&review_id, &a.id, "Document the rationale.", "").unwrap();
+        
+        let dashboard = vault.get_supervisor_dashboard("super1").unwrap();
+        assert_eq!(dashboard.trainees.len(), 2);
+        let summary = |id: &str| dashboard.trainees.iter().find(|t| t.trainee.id == id).unwrap();
+        let trend = &summary(&a.id).quality_trend;
+        assert_eq!(trend.reviews_completed, 2);
+        assert_eq!((trend.rolling_clinical_accuracy, trend.rolling_documentation_quality), (Some(3.0), Some(2.0)));
+        assert_eq!(summary(&a.id).avg_quality_score, Some(2.5));
+        assert!(summary(&a.id).last_submission.is_some());
+        assert_eq!((summary(&b.id).pending_notes, summary(&b.id).avg_quality_score), (1, None));
+        
+        let kinds = |kind: &str| dashboard.recent_activity.iter().filter(|e| e.activity_type == kind).count();
+        assert_eq!((kinds("submitted"), kinds("reviewed"), kinds("reflection")), (3, 2, 1));
+        assert!(dashboard.recent_activity.windows(2).all(|w| w[0].timestamp >= w[1].timestamp));
+        assert!(dashboard.recent_activity.iter().all(|e| !e.description.contains("Trainee C")));
+        assert_eq!(vault.get_supervisor_activity("super1", 2).unwrap().len(), 2);
+        
+        let report = vault.get_supervision_quality_report("super1").unwrap();
+        assert_eq!(report.trainees.len(), 2);
+        assert_eq!(vault.get_supervision_quality_report("super2").unwrap().trainees[0].avg_quality_score, Some(1.0));
+    }
+}
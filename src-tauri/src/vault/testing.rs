@@ -0,0 +1,365 @@
+// Vault Test Fixtures
+//
+// In-memory vaults for this crate's tests, compiled only under `cargo
+// test`. A fixture spec lists clients, their notes and any extra audit
+// events; the builder writes them into a keyed in-memory SQLCipher
+// database with the real schema and migrations.
+//
+// Everything is derived from the spec: ids are hashed from the seed, the
+// clock starts at `start_ms` and advances one step per row, and audit
+// entries are chained with those ids and timestamps. Building the same
+// spec twice gives byte-identical tables and audit chain, so golden
+// audit-chain fixtures can be regenerated and diffed.
+
+// Not every test uses every builder method
+#![allow(dead_code)]
+
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use super::{Vault, VaultError};
+use crate::audit;
+use crate::crypto::{self, VaultKey};
+use crate::models::{AuditEntry, AuditEventType, AuditOutcome, AuditResourceType, Client, Note, NoteType};
+
+/// Default fixture clock: 2023-11-14T22:13:20Z
+pub const DEFAULT_START_MS: i64 = 1_700_000_000_000;
+
+/// Milliseconds the fixture clock advances per row written
+pub const CLOCK_STEP_MS: i64 = 60_000;
+
+// ============================================
+// Fixture Spec
+// ============================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FixtureSpec {
+    /// Seeds the vault key and every generated id
+    pub seed: String,
+    #[serde(default = "default_start_ms")]
+    pub start_ms: i64,
+    #[serde(default)]
+    pub clients: Vec<ClientFixture>,
+    /// Appended to the audit chain after clients and notes
+    #[serde(default)]
+    pub audit_events: Vec<AuditFixture>,
+}
+
+fn default_start_ms() -> i64 {
+    DEFAULT_START_MS
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClientFixture {
+    pub display_name: String,
+    #[serde(default)]
+    pub notes: Vec<NoteFixture>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NoteFixture {
+    pub session_date: String,
+    pub note_type: NoteType,
+    pub raw_input: String,
+    #[serde(default)]
+    pub signed: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditFixture {
+    pub event_type: AuditEventType,
+    pub resource_type: AuditResourceType,
+    pub resource_id: String,
+    pub outcome: AuditOutcome,
+}
+
+// ============================================
+// Builder
+// ============================================
+
+/// Builds a [`Fixture`] from a spec, or from chained calls:
+///
+/// ```ignore
+/// let fixture = FixtureBuilder::new("ethics-tests")
+///     .client("Client A")
+///     .signed_note("2024-03-01", NoteType::Progress, "Session text")
+///     .build()?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct FixtureBuilder {
+    spec: FixtureSpec,
+}
+
+impl FixtureBuilder {
+    pub fn new(seed: &str) -> Self {
+        FixtureBuilder {
+            spec: FixtureSpec {
+                seed: seed.to_string(),
+                start_ms: DEFAULT_START_MS,
+                clients: Vec::new(),
+                audit_events: Vec::new(),
+            },
+        }
+    }
+
+    pub fn from_spec(spec: FixtureSpec) -> Self {
+        FixtureBuilder { spec }
+    }
+
+    /// Spec from fixture JSON (the format `Fixture::spec` serializes to)
+    pub fn from_json(json: &str) -> Result<Self, VaultError> {
+        serde_json::from_str(json)
+            .map(Self::from_spec)
+            .map_err(|e| VaultError::Serialization(e.to_string()))
+    }
+
+    pub fn start_ms(mut self, start_ms: i64) -> Self {
+        self.spec.start_ms = start_ms;
+        self
+    }
+
+    pub fn client(mut self, display_name: &str) -> Self {
+        self.spec.clients.push(ClientFixture {
+            display_name: display_name.to_string(),
+            notes: Vec::new(),
+        });
+        self
+    }
+
+    /// Draft note for the most recently added client
+    pub fn note(self, session_date: &str, note_type: NoteType, raw_input: &str) -> Self {
+        self.push_note(session_date, note_type, raw_input, false)
+    }
+
+    /// Signed note for the most recently added client
+    pub fn signed_note(self, session_date: &str, note_type: NoteType, raw_input: &str) -> Self {
+        self.push_note(session_date, note_type, raw_input, true)
+    }
+
+    fn push_note(mut self, session_date: &str, note_type: NoteType, raw_input: &str, signed: bool) -> Self {
+        let client = self.spec.clients.last_mut()
+            .expect("fixture note added before any client");
+        client.notes.push(NoteFixture {
+            session_date: session_date.to_string(),
+            note_type,
+            raw_input: raw_input.to_string(),
+            signed,
+        });
+        self
+    }
+
+    pub fn audit_event(
+        mut self,
+        event_type: AuditEventType,
+        resource_type: AuditResourceType,
+        resource_id: &str,
+        outcome: AuditOutcome,
+    ) -> Self {
+        self.spec.audit_events.push(AuditFixture {
+            event_type,
+            resource_type,
+            resource_id: resource_id.to_string(),
+            outcome,
+        });
+        self
+    }
+
+    pub fn build(self) -> Result<Fixture, VaultError> {
+        let spec = self.spec;
+        let vault = Vault::open_in_memory(&spec.seed)?;
+        let mut seeder = Seeder { conn: vault.conn()?, seed: &spec.seed, now: spec.start_ms, next: 0 };
+
+        let mut clients = Vec::with_capacity(spec.clients.len());
+        let mut notes = Vec::new();
+        for client_spec in &spec.clients {
+            let client_id = seeder.insert_client(&client_spec.display_name)?;
+            for note_spec in &client_spec.notes {
+                notes.push(seeder.insert_note(&client_id, note_spec)?);
+            }
+            clients.push(client_id);
+        }
+        for event in &spec.audit_events {
+            seeder.audit(event.event_type, event.resource_type, &event.resource_id, event.outcome)?;
+        }
+
+        let clients = clients.iter().map(|id| vault.get_client(id)).collect::<Result<Vec<Client>, _>>()?;
+        let notes = notes.iter().map(|id| vault.get_note(id)).collect::<Result<Vec<Note>, _>>()?;
+        Ok(Fixture { vault, spec, clients, notes })
+    }
+}
+
+/// Vault key of the fixture built from `seed`
+pub fn fixture_key(seed: &str) -> VaultKey {
+    let digest = hex::decode(crypto::hash_sha256(format!("evidify-fixture-key|{}", seed).as_bytes()))
+        .expect("hash_sha256 returns hex");
+    let mut key = [0u8; 32];
+    key.copy_from_slice(&digest);
+    VaultKey(key)
+}
+
+/// Deterministic id: a v4-shaped UUID hashed from the seed, row kind and
+/// a running counter
+pub fn fixture_id(seed: &str, kind: &str, n: u64) -> String {
+    let digest = hex::decode(crypto::hash_sha256(format!("{}|{}|{}", seed, kind, n).as_bytes()))
+        .expect("hash_sha256 returns hex");
+    let mut bytes = [0u8; 16];
+    bytes.copy_from_slice(&digest[..16]);
+    uuid::Builder::from_random_bytes(bytes).into_uuid().to_string()
+}
+
+/// Writes fixture rows with the fixture clock and ids
+struct Seeder<'a> {
+    conn: &'a Connection,
+    seed: &'a str,
+    now: i64,
+    next: u64,
+}
+
+impl Seeder<'_> {
+    fn tick(&mut self, kind: &str) -> (String, i64) {
+        self.next += 1;
+        self.now += CLOCK_STEP_MS;
+        (fixture_id(self.seed, kind, self.next), self.now)
+    }
+
+    fn insert_client(&mut self, display_name: &str) -> Result<String, VaultError> {
+        let (id, now) = self.tick("client");
+        self.conn.execute(
+            "INSERT INTO clients (id, display_name, status, session_count, created_at, updated_at)
+             VALUES (?1, ?2, 'active', 0, ?3, ?3)",
+            params![&id, display_name, now],
+        )?;
+        self.audit(AuditEventType::ClientCreated, AuditResourceType::Client, &id, AuditOutcome::Success)?;
+        Ok(id)
+    }
+
+    fn insert_note(&mut self, client_id: &str, spec: &NoteFixture) -> Result<String, VaultError> {
+        let (id, now) = self.tick("note");
+        let content = Vault::sanitize_note_content(&spec.raw_input);
+        let content_hash = crypto::hash_sha256(content.as_bytes());
+        let word_count = content.split_whitespace().count() as i32;
+
+        self.conn.execute(
+            "INSERT INTO notes (id, client_id, session_date, note_type, raw_input, word_count,
+             status, content_hash, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, 'draft', ?7, ?8, ?8)",
+            params![&id, client_id, &spec.session_date, spec.note_type.to_string(), &content, word_count, &content_hash, now],
+        )?;
+        self.conn.execute(
+            "UPDATE clients SET session_count = session_count + 1, updated_at = ?1 WHERE id = ?2",
+            params![now, client_id],
+        )?;
+        self.audit(AuditEventType::NoteCreated, AuditResourceType::Note, &id, AuditOutcome::Success)?;
+
+        if spec.signed {
+            let (_, signed_at) = self.tick("sign");
+            self.conn.execute(
+                "UPDATE notes SET status = 'signed', attestations = '[]', signed_at = ?1, updated_at = ?1
+                 WHERE id = ?2",
+                params![signed_at, &id],
+            )?;
+            self.audit(AuditEventType::NoteSigned, AuditResourceType::Note, &id, AuditOutcome::Success)?;
+        }
+        Ok(id)
+    }
+
+    fn audit(
+        &mut self,
+        event_type: AuditEventType,
+        resource_type: AuditResourceType,
+        resource_id: &str,
+        outcome: AuditOutcome,
+    ) -> Result<AuditEntry, VaultError> {
+        let (id, now) = self.tick("audit");
+        audit::append_fixture_entry(self.conn, id, now, event_type, resource_type, resource_id, outcome)
+            .map_err(|e| VaultError::Internal(format!("Fixture audit entry: {}", e)))
+    }
+}
+
+// ============================================
+// Fixture
+// ============================================
+
+/// An unlocked in-memory vault and the rows seeded into it, in spec order
+pub struct Fixture {
+    pub vault: Vault,
+    pub spec: FixtureSpec,
+    pub clients: Vec<Client>,
+    pub notes: Vec<Note>,
+}
+
+impl Fixture {
+    pub fn audit_entries(&self) -> Result<Vec<AuditEntry>, VaultError> {
+        audit::get_entries_from(self.vault.conn()?, 1)
+            .map_err(|e| VaultError::Internal(e.to_string()))
+    }
+
+    /// Pretty JSON of the whole audit chain. Regenerate a golden file by
+    /// writing this out; compare against it to catch changes to how
+    /// entries are chained or hashed.
+    pub fn golden_audit_chain(&self) -> Result<String, VaultError> {
+        serde_json::to_string_pretty(&self.audit_entries()?)
+            .map_err(|e| VaultError::Serialization(e.to_string()))
+    }
+}
+
+impl Vault {
+    /// Unlocked vault on an in-memory SQLCipher database keyed from `seed`,
+    /// with the full schema and migrations applied. There is no keychain
+    /// entry and no data directory; nothing touches disk.
+    pub fn open_in_memory(seed: &str) -> Result<Vault, VaultError> {
+        let vault_key = fixture_key(seed);
+
+        let conn = Connection::open_in_memory()?;
+        conn.pragma_update(None, "key", format!("x'{}'", vault_key.as_hex()))?;
+
+        let mut vault = Vault::new(PathBuf::new());
+        vault.init_schema(&conn)?;
+        vault.conn = Some(conn);
+        vault.vault_key = Some(vault_key);
+        Ok(vault)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec() -> FixtureBuilder {
+        FixtureBuilder::new("golden")
+            .client("Client A")
+            .signed_note("2024-03-01", NoteType::Progress, "Discussed sleep and work stress.")
+            .note("2024-03-08", NoteType::Progress, "Draft follow-up.")
+            .client("Client B")
+            .audit_event(AuditEventType::SearchExecuted, AuditResourceType::Client, "search", AuditOutcome::Success)
+    }
+
+    #[test]
+    fn test_fixture_is_deterministic() {
+        let a = spec().build().unwrap();
+        let b = spec().build().unwrap();
+
+        assert_eq!(a.clients.len(), 2);
+        assert_eq!(a.notes.len(), 2);
+        assert_eq!(a.clients[0].session_count, 2);
+        assert!(a.notes[0].signed_at.is_some());
+        assert_eq!(a.clients[0].id, b.clients[0].id);
+        assert_ne!(a.clients[0].id, FixtureBuilder::new("other").client("Client A").build().unwrap().clients[0].id);
+
+        // client, note, note signed, note, client, search
+        let entries = a.audit_entries().unwrap();
+        assert_eq!(entries.len(), 6);
+        audit::verify_segment(&entries).unwrap();
+        assert!(audit::verify_chain(a.vault.conn().unwrap()).unwrap());
+        assert_eq!(a.golden_audit_chain().unwrap(), b.golden_audit_chain().unwrap());
+    }
+
+    #[test]
+    fn test_fixture_spec_round_trips_through_json() {
+        let fixture = spec().build().unwrap();
+        let json = serde_json::to_string(&fixture.spec).unwrap();
+        let rebuilt = FixtureBuilder::from_json(&json).unwrap().build().unwrap();
+        assert_eq!(rebuilt.golden_audit_chain().unwrap(), fixture.golden_audit_chain().unwrap());
+    }
+}
@@ -83,35 +83,14 @@ impl VoiceCapture {
     }
     
     /// Get available Whisper models in the models directory
-    pub fn list_models(models_dir: &PathBuf) -> Vec<WhisperModelInfo> {
-        let mut models = Vec::new();
-        
-        // Standard Whisper model files
-        let model_files = [
-            ("ggml-tiny.bin", "tiny", 75),
-            ("ggml-tiny.en.bin", "tiny.en", 75),
-            ("ggml-base.bin", "base", 142),
-            ("ggml-base.en.bin", "base.en", 142),
-            ("ggml-small.bin", "small", 466),
-            ("ggml-small.en.bin", "small.en", 466),
-            ("ggml-medium.bin", "medium", 1500),
-            ("ggml-medium.en.bin", "medium.en", 1500),
-            ("ggml-large-v3.bin", "large-v3", 2900),
-        ];
-        
-        for (filename, name, size_mb) in model_files {
-            let path = models_dir.join(filename);
-            if path.exists() {
-                models.push(WhisperModelInfo {
-                    name: name.to_string(),
-                    path: path.to_string_lossy().to_string(),
-                    size_mb,
-                    multilingual: !name.ends_with(".en"),
-                });
-            }
-        }
-        
-        models
+    pub fn list_models(models_dir: &Path) -> Vec<WhisperModelInfo> {
+        WHISPER_MANIFEST
+            .iter()
+            .filter_map(|spec| {
+                let path = models_dir.join(spec.filename);
+                path.exists().then(|| spec.info_at(&path))
+            })
+            .collect()
     }
     
     /// Start recording and transcription
@@ -184,6 +163,221 @@ pub struct WhisperModelInfo {
     pub path: String,
     pub size_mb: u32,
     pub multilingual: bool,
+    /// Approximate peak RAM while transcribing
+    pub ram_mb: u32,
+}
+
+// ============================================
+// Model Manifest & Recommendation
+// ============================================
+
+/// Static facts about one ggml Whisper model
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct WhisperModelSpec {
+    pub name: &'static str,
+    pub filename: &'static str,
+    /// Download size
+    pub size_mb: u32,
+    /// Approximate peak RAM while transcribing (whisper.cpp)
+    pub ram_mb: u32,
+    /// Speed relative to large-v3 (large-v3 = 1.0)
+    pub relative_speed: f32,
+    /// Accuracy rank, higher is better
+    pub quality: u8,
+    pub multilingual: bool,
+}
+
+impl WhisperModelSpec {
+    fn info_at(&self, path: &Path) -> WhisperModelInfo {
+        WhisperModelInfo {
+            name: self.name.to_string(),
+            path: path.to_string_lossy().to_string(),
+            size_mb: self.size_mb,
+            multilingual: self.multilingual,
+            ram_mb: self.ram_mb,
+        }
+    }
+}
+
+pub const WHISPER_MANIFEST: &[WhisperModelSpec] = &[
+    WhisperModelSpec { name: "tiny", filename: "ggml-tiny.bin", size_mb: 75, ram_mb: 390, relative_speed: 32.0, quality: 1, multilingual: true },
+    WhisperModelSpec { name: "tiny.en", filename: "ggml-tiny.en.bin", size_mb: 75, ram_mb: 390, relative_speed: 32.0, quality: 1, multilingual: false },
+    WhisperModelSpec { name: "base", filename: "ggml-base.bin", size_mb: 142, ram_mb: 500, relative_speed: 16.0, quality: 2, multilingual: true },
+    WhisperModelSpec { name: "base.en", filename: "ggml-base.en.bin", size_mb: 142, ram_mb: 500, relative_speed: 16.0, quality: 2, multilingual: false },
+    WhisperModelSpec { name: "small", filename: "ggml-small.bin", size_mb: 466, ram_mb: 1000, relative_speed: 6.0, quality: 3, multilingual: true },
+    WhisperModelSpec { name: "small.en", filename: "ggml-small.en.bin", size_mb: 466, ram_mb: 1000, relative_speed: 6.0, quality: 3, multilingual: false },
+    WhisperModelSpec { name: "medium", filename: "ggml-medium.bin", size_mb: 1500, ram_mb: 2600, relative_speed: 2.0, quality: 4, multilingual: true },
+    WhisperModelSpec { name: "medium.en", filename: "ggml-medium.en.bin", size_mb: 1500, ram_mb: 2600, relative_speed: 2.0, quality: 4, multilingual: false },
+    WhisperModelSpec { name: "large-v3", filename: "ggml-large-v3.bin", size_mb: 2900, ram_mb: 4700, relative_speed: 1.0, quality: 5, multilingual: true },
+];
+
+/// Share of physical RAM transcription may use; the rest is left for the
+/// OS, the app itself and a local LLM that is often loaded at the same time
+const WHISPER_RAM_BUDGET: f64 = 0.4;
+
+/// large-v3 on 8 CPU cores runs at roughly half real time
+const LARGE_V3_BASELINE_RTF: f32 = 0.5;
+
+/// Minimum speed (x real time) for a model to be recommended; a 50-minute
+/// session should transcribe in well under half an hour
+const MIN_REALTIME_FACTOR: f32 = 2.0;
+
+/// What the machine can offer a Whisper model
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HardwareProfile {
+    pub total_ram_mb: Option<u64>,
+    pub cpu_cores: u32,
+    /// "metal", "cuda" or None
+    pub gpu: Option<String>,
+}
+
+/// Probe RAM, CPU cores and GPU acceleration. RAM comes from the OS
+/// (/proc/meminfo, sysctl, or CIM on Windows); None if it can't be read.
+pub fn probe_hardware() -> HardwareProfile {
+    let cpu_cores = std::thread::available_parallelism()
+        .map(|n| n.get() as u32)
+        .unwrap_or(1);
+    
+    let gpu = if cfg!(all(target_os = "macos", target_arch = "aarch64")) {
+        Some("metal".to_string())
+    } else if Command::new("nvidia-smi").arg("-L").output().map(|o| o.status.success()).unwrap_or(false) {
+        Some("cuda".to_string())
+    } else {
+        None
+    };
+    
+    HardwareProfile {
+        total_ram_mb: probe_total_ram_mb(),
+        cpu_cores,
+        gpu,
+    }
+}
+
+fn probe_total_ram_mb() -> Option<u64> {
+    if cfg!(target_os = "linux") {
+        let meminfo = std::fs::read_to_string("/proc/meminfo").ok()?;
+        let kb: u64 = meminfo
+            .lines()
+            .find(|l| l.starts_with("MemTotal:"))?
+            .split_whitespace()
+            .nth(1)?
+            .parse()
+            .ok()?;
+        Some(kb / 1024)
+    } else if cfg!(target_os = "macos") {
+        let output = Command::new("sysctl").args(["-n", "hw.memsize"]).output().ok()?;
+        let bytes: u64 = String::from_utf8_lossy(&output.stdout).trim().parse().ok()?;
+        Some(bytes / (1024 * 1024))
+    } else if cfg!(target_os = "windows") {
+        let output = Command::new("powershell")
+            .args(["-NoProfile", "-Command", "(Get-CimInstance Win32_ComputerSystem).TotalPhysicalMemory"])
+            .output()
+            .ok()?;
+        let bytes: u64 = String::from_utf8_lossy(&output.stdout).trim().parse().ok()?;
+        Some(bytes / (1024 * 1024))
+    } else {
+        None
+    }
+}
+
+/// Expected transcription speed (x real time) of a model on this hardware
+pub fn estimated_realtime_factor(spec: &WhisperModelSpec, hardware: &HardwareProfile) -> f32 {
+    let cpu_scale = hardware.cpu_cores.clamp(1, 16) as f32 / 8.0;
+    let gpu_scale = if hardware.gpu.is_some() { 4.0 } else { 1.0 };
+    LARGE_V3_BASELINE_RTF * spec.relative_speed * cpu_scale * gpu_scale
+}
+
+/// One manifest entry as shown in the model picker
+#[derive(Debug, Clone, Serialize)]
+pub struct WhisperCatalogEntry {
+    #[serde(flatten)]
+    pub spec: WhisperModelSpec,
+    pub installed: bool,
+    pub path: Option<String>,
+    pub estimated_realtime_factor: f32,
+    pub fits_in_memory: bool,
+    /// Why the model isn't suitable here, if it isn't
+    pub warning: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WhisperModelCatalog {
+    pub hardware: HardwareProfile,
+    pub models: Vec<WhisperCatalogEntry>,
+    pub recommended: String,
+    pub recommendation_reason: String,
+}
+
+/// Best-quality model that fits the RAM budget and runs fast enough.
+/// Unknown RAM is treated as 8 GB so we never push large-v3 blind.
+pub fn recommend_model(hardware: &HardwareProfile, english_only: bool) -> (&'static WhisperModelSpec, String) {
+    let ram_mb = hardware.total_ram_mb.unwrap_or(8 * 1024);
+    let budget_mb = (ram_mb as f64 * WHISPER_RAM_BUDGET) as u32;
+    
+    let suitable = WHISPER_MANIFEST
+        .iter()
+        .filter(|spec| spec.multilingual != english_only)
+        .filter(|spec| spec.ram_mb <= budget_mb)
+        .filter(|spec| estimated_realtime_factor(spec, hardware) >= MIN_REALTIME_FACTOR)
+        .max_by_key(|spec| spec.quality);
+    
+    match suitable {
+        Some(spec) => {
+            let reason = format!(
+                "Best accuracy that fits in {} MB of RAM and transcribes at ~{:.1}x real time on {} cores{}",
+                budget_mb,
+                estimated_realtime_factor(spec, hardware),
+                hardware.cpu_cores,
+                hardware.gpu.as_deref().map(|g| format!(" with {} acceleration", g)).unwrap_or_default()
+            );
+            (spec, reason)
+        }
+        None => {
+            // Slow or memory-starved machine: smallest model still works
+            let tiny = if english_only { &WHISPER_MANIFEST[1] } else { &WHISPER_MANIFEST[0] };
+            (tiny, "Limited RAM/CPU: the smallest model is the only one expected to run acceptably".to_string())
+        }
+    }
+}
+
+/// Full manifest annotated for this machine plus a recommendation
+pub fn build_model_catalog(models_dir: &Path, hardware: HardwareProfile, english_only: bool) -> WhisperModelCatalog {
+    let budget_mb = (hardware.total_ram_mb.unwrap_or(8 * 1024) as f64 * WHISPER_RAM_BUDGET) as u32;
+    
+    let models = WHISPER_MANIFEST
+        .iter()
+        .map(|spec| {
+            let path = models_dir.join(spec.filename);
+            let installed = path.exists();
+            let rtf = estimated_realtime_factor(spec, &hardware);
+            let fits_in_memory = spec.ram_mb <= budget_mb;
+            let warning = if !fits_in_memory {
+                Some(format!("Needs ~{} MB RAM; this machine can spare ~{} MB", spec.ram_mb, budget_mb))
+            } else if rtf < MIN_REALTIME_FACTOR {
+                Some(format!("Expected to run at only ~{:.1}x real time here", rtf))
+            } else {
+                None
+            };
+            
+            WhisperCatalogEntry {
+                spec: *spec,
+                installed,
+                path: installed.then(|| path.to_string_lossy().to_string()),
+                estimated_realtime_factor: rtf,
+                fits_in_memory,
+                warning,
+            }
+        })
+        .collect();
+    
+    let (recommended, recommendation_reason) = recommend_model(&hardware, english_only);
+    
+    WhisperModelCatalog {
+        hardware,
+        models,
+        recommended: recommended.name.to_string(),
+        recommendation_reason,
+    }
 }
 
 /// Whisper transcription context
@@ -281,6 +475,9 @@ pub struct WhisperConfig {
     pub threads: u32,
     /// Translate to English (for non-English audio)
     pub translate: bool,
+    /// Denoise/loudness cleanup before transcription (off when None)
+    #[serde(default)]
+    pub preprocessing: Option<crate::audio_preprocess::PreprocessOptions>,
 }
 
 impl Default for WhisperConfig {
@@ -294,6 +491,7 @@ impl Default for WhisperConfig {
             language: "en".to_string(),
             threads: 4,
             translate: false,
+            preprocessing: None,
         }
     }
 }
@@ -354,34 +552,7 @@ pub fn list_available_models() -> Vec<WhisperModelInfo> {
         return vec![];
     }
     
-    let model_specs = [
-        ("ggml-tiny.bin", "tiny", 75, true),
-        ("ggml-tiny.en.bin", "tiny.en", 75, false),
-        ("ggml-base.bin", "base", 142, true),
-        ("ggml-base.en.bin", "base.en", 142, false),
-        ("ggml-small.bin", "small", 466, true),
-        ("ggml-small.en.bin", "small.en", 466, false),
-        ("ggml-medium.bin", "medium", 1500, true),
-        ("ggml-medium.en.bin", "medium.en", 1500, false),
-        ("ggml-large-v3.bin", "large-v3", 2900, true),
-    ];
-    
-    model_specs
-        .iter()
-        .filter_map(|(filename, name, size_mb, multilingual)| {
-            let path = model_dir.join(filename);
-            if path.exists() {
-                Some(WhisperModelInfo {
-                    name: name.to_string(),
-                    path: path.to_string_lossy().to_string(),
-                    size_mb: *size_mb,
-                    multilingual: *multilingual,
-                })
-            } else {
-                None
-            }
-        })
-        .collect()
+    VoiceCapture::list_models(&model_dir)
 }
 
 /// Convert audio file to 16kHz WAV (required by Whisper)
@@ -441,6 +612,20 @@ pub fn transcribe_file(
         convert_to_wav(audio_path)?
     };
     
+    // Never overwrite the caller's file; preprocess into a temp copy
+    let wav_path = match &config.preprocessing {
+        Some(options) => {
+            let processed = audio_path.with_extension("preprocessed.wav");
+            let result = crate::audio_preprocess::preprocess_wav(&wav_path, &processed, options);
+            if wav_path != audio_path {
+                let _ = std::fs::remove_file(&wav_path);
+            }
+            result.map_err(VoiceError::ConversionFailed)?;
+            processed
+        }
+        None => wav_path,
+    };
+    
     // Build command
     let mut cmd = Command::new(&whisper_cmd);
     cmd.args([
@@ -594,7 +779,7 @@ pub fn get_voice_status() -> VoiceStatus {
         whisper_command: whisper_result.ok(),
         models_available: models.iter().map(|m| m.name.clone()).collect(),
         models_directory: get_models_directory().to_string_lossy().to_string(),
-        recommended_model: "ggml-base.en.bin".to_string(),
+        recommended_model: recommend_model(&probe_hardware(), true).0.filename.to_string(),
         ffmpeg_installed: ffmpeg_ok,
     }
 }
@@ -697,6 +882,21 @@ mod tests {
         assert_eq!(resampled.len(), 16000);
     }
     
+    #[test]
+    fn test_model_recommendation() {
+        let laptop = HardwareProfile { total_ram_mb: Some(8 * 1024), cpu_cores: 8, gpu: None };
+        let (spec, _) = recommend_model(&laptop, true);
+        assert_eq!(spec.name, "small.en");
+        
+        let workstation = HardwareProfile { total_ram_mb: Some(32 * 1024), cpu_cores: 10, gpu: Some("metal".to_string()) };
+        let (spec, _) = recommend_model(&workstation, false);
+        assert_eq!(spec.name, "large-v3");
+        
+        // 8 GB never gets large-v3, even with a GPU
+        let gpu_laptop = HardwareProfile { gpu: Some("cuda".to_string()), ..laptop };
+        assert_ne!(recommend_model(&gpu_laptop, false).0.name, "large-v3");
+    }
+    
     #[test]
     fn test_stereo_to_mono() {
         let stereo = vec![0.5, 0.5, 1.0, 0.0, -0.5, -0.5];
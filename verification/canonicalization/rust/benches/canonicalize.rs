@@ -0,0 +1,104 @@
+//! Canonicalization throughput on audit-pack shaped documents.
+//!
+//! Run with `cargo bench`. Pack sizes cover a single session export up to
+//! a multi-year chart; the per-size results show whether cost stays linear.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use evidify_canonicalization::{
+    canonical_digest, canonical_sha256, canonicalize_json, canonicalize_to_writer, into_canonical,
+    sha256_hex, try_canonical_bytes, Algorithm,
+};
+use serde_json::{json, Value};
+
+const PACK_SIZES: [usize; 3] = [100, 1_000, 10_000];
+
+/// An audit pack with `entries` chained log entries and one finding per
+/// twenty entries, keys deliberately out of order.
+fn audit_pack(entries: usize) -> Value {
+    let log: Vec<Value> = (0..entries)
+        .map(|i| {
+            json!({
+                "sequence": i,
+                "timestamp": 1_704_067_200_000_i64 + (i as i64) * 60_000,
+                "event_type": if i % 3 == 0 { "note_signed" } else { "note_viewed" },
+                "resource_type": "note",
+                "resource_id": format!("note-{:06}", i / 4),
+                "outcome": "success",
+                "detection_ids": [format!("det-{}", i), format!("det-{}", i + 1)],
+                "previous_hash": sha256_hex(format!("prev-{}", i).as_bytes()),
+                "entry_hash": sha256_hex(format!("entry-{}", i).as_bytes()),
+            })
+        })
+        .collect();
+    let findings: Vec<Value> = (0..entries / 20)
+        .map(|i| {
+            json!({
+                "severity": "WARN",
+                "message": format!("Note note-{:06} signed {} hours after session", i, 48 + i % 24),
+                "gate_id": "GATE-004",
+                "anchors": [{"type": "audit_entry", "sequence": i * 20}],
+            })
+        })
+        .collect();
+    json!({
+        "pack_version": "1.0",
+        "manifest": {"generator": "bench", "client_id": "client-1", "entry_count": entries},
+        "entries": log,
+        "findings": findings,
+    })
+}
+
+fn bench_canonicalize(c: &mut Criterion) {
+    let mut group = c.benchmark_group("canonicalize");
+    for size in PACK_SIZES {
+        let pack = audit_pack(size);
+        group.throughput(Throughput::Bytes(try_canonical_bytes(&pack).unwrap().len() as u64));
+
+        group.bench_with_input(BenchmarkId::new("canonicalize_json", size), &pack, |b, pack| {
+            b.iter(|| canonicalize_json(black_box(pack)))
+        });
+        group.bench_with_input(BenchmarkId::new("into_canonical", size), &pack, |b, pack| {
+            b.iter_batched(|| pack.clone(), into_canonical, criterion::BatchSize::LargeInput)
+        });
+        // What canonical_bytes used to do: build the canonical copy, then
+        // serialize it. The gap to canonical_bytes is the cost of the copy.
+        group.bench_with_input(BenchmarkId::new("copy_then_serialize", size), &pack, |b, pack| {
+            b.iter(|| serde_json::to_vec(&canonicalize_json(black_box(pack))).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("canonical_bytes", size), &pack, |b, pack| {
+            b.iter(|| try_canonical_bytes(black_box(pack)).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("canonicalize_to_writer", size), &pack, |b, pack| {
+            b.iter(|| canonicalize_to_writer(black_box(pack), std::io::sink()))
+        });
+        group.bench_with_input(BenchmarkId::new("canonical_sha256", size), &pack, |b, pack| {
+            b.iter(|| canonical_sha256(black_box(pack)))
+        });
+    }
+    group.finish();
+}
+
+fn bench_hash(c: &mut Criterion) {
+    let mut group = c.benchmark_group("hash");
+    for size in PACK_SIZES {
+        let bytes = try_canonical_bytes(&audit_pack(size)).unwrap();
+        group.throughput(Throughput::Bytes(bytes.len() as u64));
+        group.bench_with_input(BenchmarkId::new("sha256_hex", size), &bytes, |b, bytes| {
+            b.iter(|| sha256_hex(black_box(bytes)))
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("canonical_digest");
+    let pack = audit_pack(1_000);
+    group.throughput(Throughput::Bytes(try_canonical_bytes(&pack).unwrap().len() as u64));
+    for algorithm in [Algorithm::Sha256, Algorithm::Sha512, Algorithm::Blake3] {
+        group.bench_with_input(BenchmarkId::from_parameter(algorithm), &pack, |b, pack| {
+            b.iter(|| canonical_digest(black_box(pack), algorithm))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_canonicalize, bench_hash);
+criterion_main!(benches);
@@ -0,0 +1,171 @@
+//! Sorting arrays whose order carries no meaning.
+//!
+//! Canonicalization keeps arrays in the order it is given. For lists such
+//! as findings, where producers emit the same set in different orders,
+//! that makes equal documents hash differently unless every producer
+//! sorts the same way. An [`ArraySort`] names such an array by a JSON
+//! Pointer (`*` standing for every member or element at that step) and
+//! the pointer, relative to each element, of the key to sort by.
+//!
+//! Keys compare by type (null, boolean, number, string, array, object),
+//! then numbers numerically, strings by UTF-8 bytes and the rest by their
+//! canonical bytes. Elements with equal keys are ordered by their own
+//! canonical bytes, so the result does not depend on the input order.
+//! Arrays nested inside the elements are sorted first.
+
+use serde_json::Value;
+use std::cmp::Ordering;
+
+use crate::pointer::{parse_index, resolve_pointer, unescape};
+use crate::{canonicalize_json, try_canonical_bytes, CanonicalizeError};
+
+/// Sort the array(s) at `path` by the value at `key` in each element.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArraySort {
+    path: String,
+    tokens: Vec<String>,
+    key: String,
+}
+
+impl ArraySort {
+    /// `path` and `key` are JSON Pointers; `path` may use `*` as a token
+    /// for "every member or element", and `key` is resolved inside each
+    /// element (`""` sorts by the element itself).
+    pub fn new(path: &str, key: &str) -> Result<Self, CanonicalizeError> {
+        match (tokens(path), tokens(key)) {
+            (Some(tokens), Some(_)) => Ok(ArraySort { path: path.to_string(), tokens, key: key.to_string() }),
+            _ => Err(CanonicalizeError::InvalidSortSpec(format!("{} by {}", path, key))),
+        }
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+}
+
+/// Sort the arrays `sorts` name, in place. A path that does not exist in
+/// `value` is skipped; one that leads to something other than an array,
+/// or an element without the sort key, is an error.
+pub fn sort_arrays(value: &mut Value, sorts: &[ArraySort]) -> Result<(), CanonicalizeError> {
+    // Deepest paths first, so tie-breaks see sorted inner arrays
+    let mut ordered: Vec<&ArraySort> = sorts.iter().collect();
+    ordered.sort_by(|a, b| b.tokens.len().cmp(&a.tokens.len()));
+    for sort in ordered {
+        apply(value, &sort.tokens, &sort.key, &mut String::new())?;
+    }
+    Ok(())
+}
+
+/// [`canonicalize_json`] after [`sort_arrays`].
+pub fn canonicalize_json_with_array_sorts(v: &Value, sorts: &[ArraySort]) -> Result<Value, CanonicalizeError> {
+    let mut sorted = v.clone();
+    sort_arrays(&mut sorted, sorts)?;
+    Ok(canonicalize_json(&sorted))
+}
+
+/// Canonical SHA-256 after [`sort_arrays`].
+pub fn canonical_sha256_with_array_sorts(v: &Value, sorts: &[ArraySort]) -> Result<String, CanonicalizeError> {
+    let mut sorted = v.clone();
+    sort_arrays(&mut sorted, sorts)?;
+    Ok(crate::canonical_sha256(&sorted))
+}
+
+/// Unescaped tokens of a JSON Pointer, or None if it is malformed
+fn tokens(pointer: &str) -> Option<Vec<String>> {
+    if pointer.is_empty() {
+        return Some(Vec::new());
+    }
+    pointer.strip_prefix('/')?.split('/').map(|raw| unescape(raw, pointer).ok()).collect()
+}
+
+fn escape(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+fn apply(value: &mut Value, tokens: &[String], key: &str, at: &mut String) -> Result<(), CanonicalizeError> {
+    let Some((token, rest)) = tokens.split_first() else {
+        return match value {
+            Value::Array(items) => sort_items(items, key, at),
+            _ => Err(CanonicalizeError::SortPathNotArray(at.clone())),
+        };
+    };
+
+    let len = at.len();
+    let result = match (value, token.as_str()) {
+        (Value::Object(map), "*") => map.iter_mut().try_for_each(|(k, child)| {
+            at.truncate(len);
+            at.push('/');
+            at.push_str(&escape(k));
+            apply(child, rest, key, at)
+        }),
+        (Value::Array(items), "*") => items.iter_mut().enumerate().try_for_each(|(i, child)| {
+            at.truncate(len);
+            at.push_str(&format!("/{}", i));
+            apply(child, rest, key, at)
+        }),
+        (Value::Object(map), _) => match map.get_mut(token) {
+            Some(child) => {
+                at.push('/');
+                at.push_str(&escape(token));
+                apply(child, rest, key, at)
+            }
+            None => Ok(()),
+        },
+        (Value::Array(items), _) => match parse_index(token).and_then(|i| items.get_mut(i)) {
+            Some(child) => {
+                at.push('/');
+                at.push_str(token);
+                apply(child, rest, key, at)
+            }
+            None => Ok(()),
+        },
+        _ => Ok(()),
+    };
+    at.truncate(len);
+    result
+}
+
+fn sort_items(items: &mut Vec<Value>, key: &str, at: &str) -> Result<(), CanonicalizeError> {
+    let mut keyed = Vec::with_capacity(items.len());
+    for (index, item) in items.drain(..).enumerate() {
+        let sort_key = resolve_pointer(&item, key)
+            .map_err(|_| CanonicalizeError::MissingSortKey { array: at.to_string(), index })?
+            .clone();
+        let tie = try_canonical_bytes(&item)?;
+        keyed.push((sort_key, tie, item));
+    }
+    keyed.sort_by(|a, b| compare_keys(&a.0, &b.0).then_with(|| a.1.cmp(&b.1)));
+    items.extend(keyed.into_iter().map(|(_, _, item)| item));
+    Ok(())
+}
+
+fn type_rank(v: &Value) -> u8 {
+    match v {
+        Value::Null => 0,
+        Value::Bool(_) => 1,
+        Value::Number(_) => 2,
+        Value::String(_) => 3,
+        Value::Array(_) => 4,
+        Value::Object(_) => 5,
+    }
+}
+
+fn compare_keys(a: &Value, b: &Value) -> Ordering {
+    match (a, b) {
+        (Value::Bool(x), Value::Bool(y)) => x.cmp(y),
+        (Value::Number(x), Value::Number(y)) => match (x.as_i64(), y.as_i64(), x.as_u64(), y.as_u64()) {
+            (Some(x), Some(y), _, _) => x.cmp(&y),
+            (_, _, Some(x), Some(y)) => x.cmp(&y),
+            _ => x.as_f64().unwrap_or(f64::NAN).total_cmp(&y.as_f64().unwrap_or(f64::NAN)),
+        },
+        (Value::String(x), Value::String(y)) => x.cmp(y),
+        (Value::Array(_), Value::Array(_)) | (Value::Object(_), Value::Object(_)) => {
+            try_canonical_bytes(a).unwrap_or_default().cmp(&try_canonical_bytes(b).unwrap_or_default())
+        }
+        _ => type_rank(a).cmp(&type_rank(b)),
+    }
+}
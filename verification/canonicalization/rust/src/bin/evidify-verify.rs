@@ -0,0 +1,93 @@
+//! evidify-verify: check an exported audit pack offline.
+//!
+//! ```text
+//! evidify-verify <pack-dir> [--json] [--allow-unhashed]
+//! ```
+//!
+//! Manifest entries that carry no hash fail; `--allow-unhashed` downgrades
+//! them to warnings, for packs from producers that did not record hashes.
+//! `--strict` is accepted for older scripts and is the default.
+//!
+//! Verification is integrity-only: packs are not signed. The manifest's
+//! SHA-256 is printed so it can be compared with one the sender provided.
+//!
+//! Exit codes: 0 all checks pass (warnings allowed), 1 a check failed,
+//! 2 the pack could not be read or the arguments are wrong.
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use evidify_canonicalization::{verify_pack_with, PackReport, VerifyOptions, VERSION};
+
+const USAGE: &str = "usage: evidify-verify <pack-dir> [--json] [--allow-unhashed]";
+
+fn print_text(report: &PackReport) {
+    println!("evidify-verify {}", VERSION);
+    if let Some(id) = &report.pack_id {
+        println!("Pack: {}", id);
+    }
+    println!("Manifest SHA-256: {}", report.manifest_sha256);
+    println!("(integrity only: the pack is not signed; compare this hash with the sender's)");
+    println!();
+    let width = report.checks.iter().map(|c| c.name.len()).max().unwrap_or(0);
+    for check in &report.checks {
+        println!("{}  {:width$}  {}", check.status, check.name, check.detail, width = width);
+    }
+    println!();
+    println!("VERIFICATION: {}", report.status());
+}
+
+fn main() -> ExitCode {
+    let mut dir: Option<PathBuf> = None;
+    let mut json = false;
+    let mut options = VerifyOptions::default();
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "--json" => json = true,
+            "--strict" => options.strict = true,
+            "--allow-unhashed" => options.strict = false,
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return ExitCode::SUCCESS;
+            }
+            "--version" => {
+                println!("evidify-verify {}", VERSION);
+                return ExitCode::SUCCESS;
+            }
+            _ if arg.starts_with('-') || dir.is_some() => {
+                eprintln!("{}", USAGE);
+                return ExitCode::from(2);
+            }
+            _ => dir = Some(PathBuf::from(arg)),
+        }
+    }
+    let Some(dir) = dir else {
+        eprintln!("{}", USAGE);
+        return ExitCode::from(2);
+    };
+
+    let report = match verify_pack_with(&dir, &options) {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("evidify-verify: {}", e);
+            return ExitCode::from(2);
+        }
+    };
+    if json {
+        match serde_json::to_string_pretty(&report) {
+            Ok(out) => println!("{}", out),
+            Err(e) => {
+                eprintln!("evidify-verify: {}", e);
+                return ExitCode::from(2);
+            }
+        }
+    } else {
+        print_text(&report);
+    }
+
+    if report.passed() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::from(1)
+    }
+}
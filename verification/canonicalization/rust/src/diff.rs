@@ -0,0 +1,136 @@
+//! Structural diff of two documents' canonical forms.
+//!
+//! A failed verification only says the hashes differ. This walks both
+//! documents the way canonicalization does — object keys in sorted order,
+//! arrays by position — and reports each place they part ways as a JSON
+//! Pointer (RFC 6901), so a reviewer sees what changed instead of
+//! comparing two large blobs by eye.
+
+use crate::strict::pointer_token;
+use serde_json::Value;
+
+/// How a location differs between the two documents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffKind {
+    /// Present only in the second document.
+    Added,
+    /// Present only in the first document.
+    Removed,
+    /// Present in both with different values (or different types).
+    Changed,
+}
+
+/// One differing location. `left` / `right` hold the value at `path` in
+/// the first / second document, `None` where it is absent.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Difference {
+    pub path: String,
+    pub kind: DiffKind,
+    pub left: Option<Value>,
+    pub right: Option<Value>,
+}
+
+/// Differences between the canonical forms of `a` and `b`, in canonical
+/// order. Empty exactly when both have the same canonical bytes.
+///
+/// Differences are reported at the deepest location where they occur: a
+/// changed field inside an object is one entry for that field, not one
+/// for every enclosing object. An array that grew or shrank reports the
+/// extra indices as added or removed; arrays are compared by position,
+/// so an insertion near the front shows up as changes to every later
+/// element.
+pub fn canonical_diff(a: &Value, b: &Value) -> Vec<Difference> {
+    let mut out = Vec::new();
+    let mut path = String::new();
+    diff_value(a, b, &mut path, &mut out);
+    out
+}
+
+fn diff_value(a: &Value, b: &Value, path: &mut String, out: &mut Vec<Difference>) {
+    match (a, b) {
+        (Value::Object(left), Value::Object(right)) => {
+            let mut keys: Vec<&String> = left.keys().chain(right.keys()).collect();
+            keys.sort_unstable();
+            keys.dedup();
+            for key in keys {
+                let len = path.len();
+                path.push('/');
+                path.push_str(&pointer_token(key));
+                match (left.get(key), right.get(key)) {
+                    (Some(l), Some(r)) => diff_value(l, r, path, out),
+                    (l, r) => out.push(Difference {
+                        path: path.clone(),
+                        kind: if l.is_some() { DiffKind::Removed } else { DiffKind::Added },
+                        left: l.cloned(),
+                        right: r.cloned(),
+                    }),
+                }
+                path.truncate(len);
+            }
+        }
+        (Value::Array(left), Value::Array(right)) => {
+            for i in 0..left.len().max(right.len()) {
+                let len = path.len();
+                path.push('/');
+                path.push_str(&i.to_string());
+                match (left.get(i), right.get(i)) {
+                    (Some(l), Some(r)) => diff_value(l, r, path, out),
+                    (l, r) => out.push(Difference {
+                        path: path.clone(),
+                        kind: if l.is_some() { DiffKind::Removed } else { DiffKind::Added },
+                        left: l.cloned(),
+                        right: r.cloned(),
+                    }),
+                }
+                path.truncate(len);
+            }
+        }
+        // Leaves (or a type change): `Value` equality matches canonical
+        // bytes here, since numbers compare by their serialized form
+        // (1 and 1.0 differ) and strings by content
+        (l, r) => {
+            if l != r {
+                out.push(Difference {
+                    path: path.clone(),
+                    kind: DiffKind::Changed,
+                    left: Some(l.clone()),
+                    right: Some(r.clone()),
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_canonical_diff_paths() {
+        let a = json!({"b": 1, "a": {"x": [1, 2, 3], "k/~": "old"}, "gone": true, "t": {"n": 1}});
+        let b = json!({"a": {"k/~": "new", "x": [1, 5]}, "b": 1.0, "new": null, "t": [1]});
+
+        let diff = canonical_diff(&a, &b);
+        let summary: Vec<(&str, DiffKind)> = diff.iter().map(|d| (d.path.as_str(), d.kind)).collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("/a/k~1~0", DiffKind::Changed),
+                ("/a/x/1", DiffKind::Changed),
+                ("/a/x/2", DiffKind::Removed),
+                ("/b", DiffKind::Changed),
+                ("/gone", DiffKind::Removed),
+                ("/new", DiffKind::Added),
+                ("/t", DiffKind::Changed),
+            ]
+        );
+        assert_eq!(diff[2].left, Some(json!(3)));
+        assert_eq!(diff[2].right, None);
+        assert_eq!(diff[5].right, Some(Value::Null));
+
+        // Key order does not matter; canonical form is the same
+        assert!(canonical_diff(&json!({"a": 1, "b": [true]}), &json!({"b": [true], "a": 1})).is_empty());
+        assert_eq!(canonical_diff(&json!(1), &json!("1"))[0].path, "");
+    }
+}
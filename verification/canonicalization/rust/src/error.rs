@@ -0,0 +1,95 @@
+//! Errors from the fallible (`try_*` and `*_with_options`) entry points.
+//!
+//! The original API panics on inputs it never expected to see, which is
+//! fine in a CLI and fatal in a long-running verification service. These
+//! functions report the same conditions as values instead.
+
+use std::fmt;
+use std::io;
+
+#[derive(Debug)]
+pub enum CanonicalizeError {
+    /// A namespace passed to [`try_uuidv5`](crate::try_uuidv5) is not a
+    /// UUID (32 hex digits, hyphens optional).
+    InvalidNamespace(String),
+    /// Two keys of one object are identical after NFC normalization.
+    NormalizedKeyCollision(String),
+    /// A number is NaN or infinite under
+    /// [`FloatPolicy::RejectNonFinite`](crate::FloatPolicy::RejectNonFinite).
+    NonFiniteNumber(String),
+    /// A number is not an integer under
+    /// [`FloatPolicy::IntegerOnly`](crate::FloatPolicy::IntegerOnly).
+    NonIntegerNumber(String),
+    /// An [`ArraySort`](crate::ArraySort) path or key is not a JSON
+    /// Pointer.
+    InvalidSortSpec(String),
+    /// An [`ArraySort`](crate::ArraySort) path leads to something other
+    /// than an array (the concrete path is given).
+    SortPathNotArray(String),
+    /// An element of the array at `array` has no value at the sort key.
+    MissingSortKey { array: String, index: usize },
+    /// A member named in [`TimestampScope::Fields`](crate::TimestampScope::Fields)
+    /// holds a string that is not an RFC 3339 timestamp (its path is given).
+    InvalidTimestamp(String),
+    /// A value's `Serialize` implementation failed, or produced something
+    /// JSON cannot hold (a map key that is not a string or number, an
+    /// integer beyond 64 bits).
+    Serialize(String),
+    /// The output writer failed.
+    Io(io::Error),
+}
+
+impl fmt::Display for CanonicalizeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CanonicalizeError::InvalidNamespace(ns) => write!(f, "namespace is not a UUID: {:?}", ns),
+            CanonicalizeError::NormalizedKeyCollision(key) => {
+                write!(f, "object keys collide after NFC normalization: {:?}", key)
+            }
+            CanonicalizeError::NonFiniteNumber(n) => write!(f, "number is not finite: {}", n),
+            CanonicalizeError::NonIntegerNumber(n) => write!(f, "number is not an integer: {}", n),
+            CanonicalizeError::InvalidSortSpec(spec) => write!(f, "invalid array sort: {}", spec),
+            CanonicalizeError::SortPathNotArray(path) => write!(f, "not an array at {:?}", path),
+            CanonicalizeError::MissingSortKey { array, index } => {
+                write!(f, "element {} of the array at {:?} has no sort key", index, array)
+            }
+            CanonicalizeError::InvalidTimestamp(path) => write!(f, "not an RFC 3339 timestamp at {:?}", path),
+            CanonicalizeError::Serialize(msg) => write!(f, "cannot serialize: {}", msg),
+            CanonicalizeError::Io(e) => write!(f, "write failed: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for CanonicalizeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CanonicalizeError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// Writer-based functions return `io::Result`; a canonicalization failure
+/// travels inside the `io::Error` and comes back out here.
+impl From<io::Error> for CanonicalizeError {
+    fn from(e: io::Error) -> Self {
+        if !e.get_ref().is_some_and(|inner| inner.is::<CanonicalizeError>()) {
+            return CanonicalizeError::Io(e);
+        }
+        let kind = e.kind();
+        match e.into_inner().map(|inner| inner.downcast::<CanonicalizeError>()) {
+            Some(Ok(ours)) => *ours,
+            Some(Err(other)) => CanonicalizeError::Io(io::Error::new(kind, other)),
+            None => CanonicalizeError::Io(kind.into()),
+        }
+    }
+}
+
+impl From<CanonicalizeError> for io::Error {
+    fn from(e: CanonicalizeError) -> Self {
+        match e {
+            CanonicalizeError::Io(e) => e,
+            other => io::Error::new(io::ErrorKind::InvalidData, other),
+        }
+    }
+}
@@ -0,0 +1,112 @@
+//! Incremental canonical hashing.
+//!
+//! Chained audit entries hash `previous_hash || canonical(entry)`.
+//! [`CanonicalHasher`] absorbs the pieces in order, canonicalizing JSON
+//! values on the way in, so callers never build the concatenated bytes:
+//! `CanonicalHasher::new().str(&previous_hash).json(&entry)?.finalize_hex()`.
+
+use serde_json::Value;
+use std::io::{self, Write};
+
+use crate::digest::Hasher;
+use crate::{digest_hex, write_value, Algorithm, CanonicalizeError, CanonicalizeOptions};
+
+/// Hashes a sequence of raw bytes, strings and canonicalized JSON values
+/// as if they were concatenated. Nothing separates the pieces; include
+/// fixed-width fields or explicit separators if they can be ambiguous.
+pub struct CanonicalHasher {
+    hasher: Hasher,
+    options: CanonicalizeOptions,
+}
+
+impl Default for CanonicalHasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CanonicalHasher {
+    /// SHA-256 under default options.
+    pub fn new() -> Self {
+        Self::with_algorithm(Algorithm::Sha256)
+    }
+
+    pub fn with_algorithm(algorithm: Algorithm) -> Self {
+        CanonicalHasher { hasher: algorithm.hasher(), options: CanonicalizeOptions::default() }
+    }
+
+    /// Canonicalize later [`json`](Self::json) values under `options`.
+    pub fn options(mut self, options: CanonicalizeOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn bytes(mut self, bytes: &[u8]) -> Self {
+        self.hasher.update(bytes);
+        self
+    }
+
+    /// The string's UTF-8 bytes, as is (not JSON-quoted).
+    pub fn str(self, s: &str) -> Self {
+        self.bytes(s.as_bytes())
+    }
+
+    /// The canonical form of `v`. Fails only where
+    /// [`canonical_bytes_with_options`](crate::canonical_bytes_with_options)
+    /// would; never under default options.
+    pub fn json(mut self, v: &Value) -> Result<Self, CanonicalizeError> {
+        let options = self.options;
+        write_value(v, &mut self, &options)?;
+        Ok(self)
+    }
+
+    /// Raw digest.
+    pub fn finalize(self) -> Vec<u8> {
+        self.hasher.finalize()
+    }
+
+    /// Digest as lowercase hex.
+    pub fn finalize_hex(self) -> String {
+        digest_hex(&self.finalize())
+    }
+}
+
+impl Write for CanonicalHasher {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.hasher.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{sha256_hex, try_canonical_bytes};
+    use serde_json::json;
+
+    #[test]
+    fn test_canonical_hasher() {
+        let previous = sha256_hex(b"entry 0");
+        let entry = json!({"sequence": 1, "event_type": "note_signed", "resource_id": "note-1"});
+        let mut concatenated = previous.as_bytes().to_vec();
+        concatenated.extend(try_canonical_bytes(&entry).unwrap());
+
+        let chained = CanonicalHasher::new().str(&previous).json(&entry).unwrap().finalize_hex();
+        assert_eq!(chained, sha256_hex(&concatenated));
+        assert_eq!(CanonicalHasher::new().bytes(&concatenated).finalize_hex(), chained);
+
+        let blake3 = CanonicalHasher::with_algorithm(Algorithm::Blake3).json(&entry).unwrap().finalize();
+        assert_eq!(blake3, crate::canonical_digest_bytes(&entry, Algorithm::Blake3));
+
+        let nfc = CanonicalizeOptions { nfc_normalize_strings: true, ..Default::default() };
+        let colliding = json!({"\u{e9}": 1, "e\u{301}": 2});
+        assert!(matches!(
+            CanonicalHasher::new().options(nfc).json(&colliding),
+            Err(CanonicalizeError::NormalizedKeyCollision(_))
+        ));
+    }
+}
@@ -0,0 +1,362 @@
+//! Canonical JSON Lines.
+//!
+//! Findings leave as JSONL, one canonical record per line, each line ending
+//! in `\n` (including the last). Each record hash is the record's
+//! [`canonical_sha256`](crate::canonical_sha256), over the line without its
+//! newline. The chain hash covers the whole file in order:
+//!
+//! ```text
+//! chain_0 = JSONL_CHAIN_GENESIS
+//! chain_i = SHA-256(chain_{i-1} || record_sha256_i)   (both as lowercase hex text)
+//! ```
+//!
+//! so dropping, reordering or editing any line changes the final chain hash,
+//! and a single record can still be checked against its own hash.
+//!
+//! Audit exports and SIEM spools need the hashes in the file itself, so a
+//! reader does not have to be handed them separately. The framed form
+//! ([`canonical_jsonl_writer`], [`CanonicalJsonlReader`]) carries each
+//! record with its hash, and ends with a trailer holding the record count
+//! and the chain hash above:
+//!
+//! ```text
+//! {"record":{"a":1},"sha256":"<record_sha256_1>"}
+//! ...
+//! {"trailer":{"chain_sha256":"<chain_n>","records":n}}
+//! ```
+//!
+//! Every line is itself canonical JSON. A file without its trailer was cut
+//! short and does not verify.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::io::{self, BufRead, Write};
+
+use crate::{
+    canonical_sha256, canonicalize_to_writer_with_digest, digest_hex, parse_strict, Algorithm, Canonicalize,
+    CanonicalizeError,
+};
+
+/// Chain value before the first record.
+pub const JSONL_CHAIN_GENESIS: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// Hashes of a canonical JSONL file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonlDigest {
+    /// Canonical SHA-256 of each record, in file order.
+    pub record_sha256: Vec<String>,
+    /// Chain hash after the last record; [`JSONL_CHAIN_GENESIS`] when
+    /// there are none.
+    pub chain_sha256: String,
+}
+
+/// `chain_i` from `chain_{i-1}` and the record's hash.
+pub fn jsonl_chain_step(previous: &str, record_sha256: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(previous.as_bytes());
+    hasher.update(record_sha256.as_bytes());
+    digest_hex(&hasher.finalize())
+}
+
+/// Write `records` to `writer` as canonical JSONL and hash them in the
+/// same pass.
+pub fn write_canonical_jsonl<'a>(
+    records: impl IntoIterator<Item = &'a Value>,
+    mut writer: impl Write,
+) -> io::Result<JsonlDigest> {
+    let mut record_sha256 = Vec::new();
+    let mut chain_sha256 = JSONL_CHAIN_GENESIS.to_string();
+    for record in records {
+        let digest = canonicalize_to_writer_with_digest(record, &mut writer, Algorithm::Sha256)?;
+        writer.write_all(b"\n")?;
+        let hash = digest_hex(&digest);
+        chain_sha256 = jsonl_chain_step(&chain_sha256, &hash);
+        record_sha256.push(hash);
+    }
+    writer.flush()?;
+    Ok(JsonlDigest { record_sha256, chain_sha256 })
+}
+
+/// Hashes [`write_canonical_jsonl`] would return, without the output.
+pub fn canonical_jsonl_digest<'a>(records: impl IntoIterator<Item = &'a Value>) -> JsonlDigest {
+    write_canonical_jsonl(records, io::sink()).expect("hashing into a sink cannot fail")
+}
+
+// ============================================
+// Framed JSONL: per-line hashes and a trailer
+// ============================================
+
+/// Last line of a framed file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JsonlTrailer {
+    /// Chain hash after the last record.
+    pub chain_sha256: String,
+    pub records: u64,
+}
+
+/// Writes framed canonical JSONL; see the module docs.
+pub struct CanonicalJsonlWriter<W: Write> {
+    inner: W,
+    chain_sha256: String,
+    records: u64,
+}
+
+/// Start a framed file on `writer`. Call
+/// [`finish`](CanonicalJsonlWriter::finish) to write the trailer; a file
+/// without one does not verify.
+pub fn canonical_jsonl_writer<W: Write>(writer: W) -> CanonicalJsonlWriter<W> {
+    CanonicalJsonlWriter { inner: writer, chain_sha256: JSONL_CHAIN_GENESIS.to_string(), records: 0 }
+}
+
+impl<W: Write> CanonicalJsonlWriter<W> {
+    /// Write one record line; returns the record's canonical SHA-256.
+    pub fn write_record<T: Serialize + ?Sized>(&mut self, record: &T) -> Result<String, CanonicalizeError> {
+        let bytes = record.canonical_bytes()?;
+        let hash = digest_hex(&Sha256::digest(&bytes));
+        self.inner.write_all(b"{\"record\":")?;
+        self.inner.write_all(&bytes)?;
+        writeln!(self.inner, ",\"sha256\":\"{}\"}}", hash)?;
+        self.chain_sha256 = jsonl_chain_step(&self.chain_sha256, &hash);
+        self.records += 1;
+        Ok(hash)
+    }
+
+    /// Chain hash so far.
+    pub fn chain_sha256(&self) -> &str {
+        &self.chain_sha256
+    }
+
+    pub fn records(&self) -> u64 {
+        self.records
+    }
+
+    /// Write the trailer and flush; returns the writer and the trailer.
+    pub fn finish(mut self) -> Result<(W, JsonlTrailer), CanonicalizeError> {
+        let trailer = JsonlTrailer { chain_sha256: self.chain_sha256, records: self.records };
+        self.inner.write_all(b"{\"trailer\":")?;
+        trailer.write_canonical(&mut self.inner, &Default::default())?;
+        self.inner.write_all(b"}\n")?;
+        self.inner.flush()?;
+        Ok((self.inner, trailer))
+    }
+}
+
+/// Why a framed file did not verify. `line` is 1-based.
+#[derive(Debug)]
+pub enum JsonlError {
+    Io(io::Error),
+    /// Not JSON, a duplicate key, or neither a record nor a trailer.
+    Malformed { line: usize, reason: String },
+    /// The line is valid but not written canonically.
+    NotCanonical { line: usize },
+    /// The record does not hash to the `sha256` beside it.
+    RecordHash { line: usize },
+    /// The trailer's count or chain hash disagrees with the records.
+    Trailer { line: usize, expected: JsonlTrailer, found: JsonlTrailer },
+    /// Anything other than blank lines after the trailer.
+    AfterTrailer { line: usize },
+    /// The file ends without a trailer.
+    MissingTrailer,
+}
+
+impl fmt::Display for JsonlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JsonlError::Io(e) => write!(f, "read failed: {}", e),
+            JsonlError::Malformed { line, reason } => write!(f, "line {}: {}", line, reason),
+            JsonlError::NotCanonical { line } => write!(f, "line {}: not canonical JSON", line),
+            JsonlError::RecordHash { line } => write!(f, "line {}: record does not match its sha256", line),
+            JsonlError::Trailer { line, expected, found } => write!(
+                f,
+                "line {}: trailer says {} records with chain {}, file has {} with chain {}",
+                line, found.records, found.chain_sha256, expected.records, expected.chain_sha256
+            ),
+            JsonlError::AfterTrailer { line } => write!(f, "line {}: content after the trailer", line),
+            JsonlError::MissingTrailer => write!(f, "no trailer; the file may be truncated"),
+        }
+    }
+}
+
+impl std::error::Error for JsonlError {}
+
+impl From<io::Error> for JsonlError {
+    fn from(e: io::Error) -> Self {
+        JsonlError::Io(e)
+    }
+}
+
+/// Reads a framed file, yielding each record once its line checks out.
+/// The trailer is checked when it is reached; iteration ends there, or
+/// with an error at the first problem.
+pub struct CanonicalJsonlReader<R> {
+    lines: io::Lines<R>,
+    line: usize,
+    chain_sha256: String,
+    records: u64,
+    trailer: Option<JsonlTrailer>,
+    failed: bool,
+}
+
+impl<R: BufRead> CanonicalJsonlReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            lines: reader.lines(),
+            line: 0,
+            chain_sha256: JSONL_CHAIN_GENESIS.to_string(),
+            records: 0,
+            trailer: None,
+            failed: false,
+        }
+    }
+
+    /// The verified trailer, once the reader has reached it.
+    pub fn trailer(&self) -> Option<&JsonlTrailer> {
+        self.trailer.as_ref()
+    }
+
+    fn malformed(&self, reason: impl Into<String>) -> JsonlError {
+        JsonlError::Malformed { line: self.line, reason: reason.into() }
+    }
+
+    fn read_line(&mut self, text: &str) -> Result<Option<Value>, JsonlError> {
+        let mut value = parse_strict(text).map_err(|e| self.malformed(e.to_string()))?;
+        let canonical = value.canonical_bytes().map_err(|e| self.malformed(e.to_string()))?;
+        if canonical != text.as_bytes() {
+            return Err(JsonlError::NotCanonical { line: self.line });
+        }
+        let Some(map) = value.as_object_mut() else {
+            return Err(self.malformed("not an object"));
+        };
+
+        if map.len() == 1 && map.contains_key("trailer") {
+            let found: JsonlTrailer = serde_json::from_value(map.remove("trailer").unwrap_or_default())
+                .map_err(|e| self.malformed(format!("bad trailer: {}", e)))?;
+            let expected = JsonlTrailer { chain_sha256: self.chain_sha256.clone(), records: self.records };
+            if found != expected {
+                return Err(JsonlError::Trailer { line: self.line, expected, found });
+            }
+            self.trailer = Some(found);
+            return Ok(None);
+        }
+
+        let (Some(record), Some(Value::String(hash)), 2) = (map.get("record"), map.get("sha256"), map.len()) else {
+            return Err(self.malformed("neither a record nor a trailer"));
+        };
+        if canonical_sha256(record) != *hash {
+            return Err(JsonlError::RecordHash { line: self.line });
+        }
+        self.chain_sha256 = jsonl_chain_step(&self.chain_sha256, hash);
+        self.records += 1;
+        Ok(map.remove("record"))
+    }
+
+    fn next_record(&mut self) -> Option<Result<Value, JsonlError>> {
+        loop {
+            let text = match self.lines.next() {
+                Some(Ok(text)) => text,
+                Some(Err(e)) => return Some(Err(e.into())),
+                None if self.trailer.is_some() => return None,
+                None => return Some(Err(JsonlError::MissingTrailer)),
+            };
+            self.line += 1;
+            if self.trailer.is_some() {
+                if text.trim().is_empty() {
+                    continue;
+                }
+                return Some(Err(JsonlError::AfterTrailer { line: self.line }));
+            }
+            match self.read_line(&text) {
+                Ok(Some(record)) => return Some(Ok(record)),
+                Ok(None) => continue,
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for CanonicalJsonlReader<R> {
+    type Item = Result<Value, JsonlError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let next = self.next_record();
+        self.failed = matches!(next, Some(Err(_)));
+        next
+    }
+}
+
+/// Check a whole framed file without keeping the records.
+pub fn verify_canonical_jsonl(reader: impl BufRead) -> Result<JsonlTrailer, JsonlError> {
+    let mut reader = CanonicalJsonlReader::new(reader);
+    for record in &mut reader {
+        record?;
+    }
+    reader.trailer.ok_or(JsonlError::MissingTrailer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_canonical_jsonl() {
+        let records = [json!({"b": 2, "a": 1}), json!({"id": "F-2"})];
+        let mut out = Vec::new();
+        let digest = write_canonical_jsonl(&records, &mut out).unwrap();
+
+        assert_eq!(String::from_utf8(out).unwrap(), "{\"a\":1,\"b\":2}\n{\"id\":\"F-2\"}\n");
+        assert_eq!(digest.record_sha256[0], crate::canonical_sha256(&records[0]));
+        let chain = jsonl_chain_step(&jsonl_chain_step(JSONL_CHAIN_GENESIS, &digest.record_sha256[0]), &digest.record_sha256[1]);
+        assert_eq!(digest.chain_sha256, chain);
+        assert_eq!(canonical_jsonl_digest(&records), digest);
+
+        // Order matters to the chain, not to the record hashes
+        let swapped = canonical_jsonl_digest([&records[1], &records[0]]);
+        assert_ne!(swapped.chain_sha256, digest.chain_sha256);
+        assert_eq!(swapped.record_sha256[1], digest.record_sha256[0]);
+
+        assert_eq!(canonical_jsonl_digest(&[]).chain_sha256, JSONL_CHAIN_GENESIS);
+    }
+
+    #[test]
+    fn test_framed_jsonl() {
+        let records = [json!({"b": 2, "a": 1}), json!({"id": "F-2"})];
+        let mut writer = canonical_jsonl_writer(Vec::new());
+        for record in &records {
+            writer.write_record(record).unwrap();
+        }
+        let (out, trailer) = writer.finish().unwrap();
+        let text = String::from_utf8(out).unwrap();
+
+        // Same hashes as the plain form
+        let plain = canonical_jsonl_digest(&records);
+        assert_eq!(trailer, JsonlTrailer { chain_sha256: plain.chain_sha256.clone(), records: 2 });
+        assert_eq!(
+            text.lines().next().unwrap(),
+            format!("{{\"record\":{{\"a\":1,\"b\":2}},\"sha256\":\"{}\"}}", plain.record_sha256[0])
+        );
+
+        let read: Vec<Value> = CanonicalJsonlReader::new(text.as_bytes()).collect::<Result<_, _>>().unwrap();
+        assert_eq!(read, records);
+        assert_eq!(verify_canonical_jsonl(text.as_bytes()).unwrap(), trailer);
+
+        let lines: Vec<&str> = text.lines().collect();
+        let verify = |lines: &[&str]| verify_canonical_jsonl(lines.join("\n").as_bytes());
+        assert!(matches!(verify(&[lines[1], lines[0], lines[2]]), Err(JsonlError::Trailer { line: 3, .. })));
+        assert!(matches!(verify(&lines[..2]), Err(JsonlError::MissingTrailer)));
+        assert!(matches!(verify(&[lines[0], lines[2]]), Err(JsonlError::Trailer { .. })));
+        let edited = lines[0].replace("\"a\":1", "\"a\":7");
+        assert!(matches!(verify(&[&edited, lines[1], lines[2]]), Err(JsonlError::RecordHash { line: 1 })));
+        let spaced = lines[0].replacen(':', ": ", 1);
+        assert!(matches!(verify(&[&spaced, lines[1], lines[2]]), Err(JsonlError::NotCanonical { line: 1 })));
+        assert!(matches!(verify(&[lines[0], lines[1], lines[2], lines[0]]), Err(JsonlError::AfterTrailer { line: 4 })));
+
+        let (_, empty) = canonical_jsonl_writer(Vec::new()).finish().unwrap();
+        assert_eq!(empty.chain_sha256, JSONL_CHAIN_GENESIS);
+    }
+}
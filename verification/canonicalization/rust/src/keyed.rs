@@ -0,0 +1,96 @@
+//! Keyed (HMAC-SHA256) hashing.
+//!
+//! Salted hashes such as the audit log's `path_hash` are meant to be
+//! unlinkable without the salt: anyone holding the key can recompute and
+//! match them, nobody else can confirm a guess. Computing them here, as
+//! HMAC rather than `SHA-256(salt || data)`, keeps every module on the same
+//! construction and the same canonical bytes.
+
+use hmac::{Hmac, Mac};
+use serde_json::Value;
+use sha2::Sha256;
+use std::io::{self, Write};
+
+use crate::{canonicalize_to_writer, digest_hex, VerifyError};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Feeds canonical output into the MAC.
+struct MacWriter<'a>(&'a mut HmacSha256);
+
+impl Write for MacWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn mac(key: &[u8]) -> HmacSha256 {
+    HmacSha256::new_from_slice(key).expect("HMAC accepts keys of any length")
+}
+
+fn canonical_mac(key: &[u8], v: &Value) -> HmacSha256 {
+    let mut mac = mac(key);
+    canonicalize_to_writer(v, MacWriter(&mut mac)).expect("hashing into a MAC cannot fail");
+    mac
+}
+
+/// HMAC-SHA256 of `bytes` under `key`, as lowercase hex.
+pub fn hmac_sha256_hex(key: &[u8], bytes: &[u8]) -> String {
+    let mut mac = mac(key);
+    mac.update(bytes);
+    digest_hex(&mac.finalize().into_bytes())
+}
+
+/// HMAC-SHA256 of `v`'s canonical form under `key`, as lowercase hex.
+/// Equal to [`hmac_sha256_hex`] over
+/// [`try_canonical_bytes`](crate::try_canonical_bytes), without building
+/// the byte buffer.
+pub fn canonical_hmac_sha256(key: &[u8], v: &Value) -> String {
+    digest_hex(&canonical_mac(key, v).finalize().into_bytes())
+}
+
+/// Check `v`'s keyed hash against `expected_hex` (either case, surrounding
+/// whitespace ignored) in constant time.
+pub fn verify_canonical_hmac_sha256(key: &[u8], v: &Value, expected_hex: &str) -> Result<(), VerifyError> {
+    let expected_hex = expected_hex.trim();
+    if expected_hex.len() != 64 {
+        return Err(VerifyError::InvalidLength { expected: 64, found: expected_hex.len() });
+    }
+    let expected = hex::decode(expected_hex).map_err(|_| VerifyError::InvalidHex)?;
+    canonical_mac(key, v).verify_slice(&expected).map_err(|_| VerifyError::Mismatch)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_hmac_sha256() {
+        // RFC 4231 test case 2
+        assert_eq!(
+            hmac_sha256_hex(b"Jefe", b"what do ya want for nothing?"),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+
+        let doc = json!({"path": "/Users/a/Exports", "kind": "export"});
+        let reordered = json!({"kind": "export", "path": "/Users/a/Exports"});
+        let keyed = canonical_hmac_sha256(b"salt", &doc);
+        assert_eq!(keyed, canonical_hmac_sha256(b"salt", &reordered));
+        assert_eq!(keyed, hmac_sha256_hex(b"salt", &crate::try_canonical_bytes(&doc).unwrap()));
+        assert_ne!(keyed, canonical_hmac_sha256(b"other salt", &doc));
+        assert_ne!(keyed, crate::canonical_sha256(&doc));
+
+        assert_eq!(verify_canonical_hmac_sha256(b"salt", &doc, &keyed.to_uppercase()), Ok(()));
+        assert_eq!(verify_canonical_hmac_sha256(b"other salt", &doc, &keyed), Err(VerifyError::Mismatch));
+        assert_eq!(
+            verify_canonical_hmac_sha256(b"salt", &doc, &keyed[..10]),
+            Err(VerifyError::InvalidLength { expected: 64, found: 10 })
+        );
+    }
+}
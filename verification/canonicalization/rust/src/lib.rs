@@ -5,62 +5,340 @@
 
 use serde_json::Value;
 use sha2::{Digest, Sha256};
+use std::borrow::Cow;
+use std::io::{self, Write};
+
+mod array_sort;
+mod diff;
+mod digest;
+mod error;
+mod finding_id;
+mod hasher;
+mod jsonl;
+mod keyed;
+mod options;
+mod pack;
+mod pointer;
+mod ser;
+mod signature;
+mod strict;
+mod timestamp;
+mod verify;
+
+pub use array_sort::{canonical_sha256_with_array_sorts, canonicalize_json_with_array_sorts, sort_arrays, ArraySort};
+pub use diff::{canonical_diff, DiffKind, Difference};
+pub use digest::{Algorithm, UnknownAlgorithm};
+pub use error::CanonicalizeError;
+pub use finding_id::{FindingIdBuilder, FindingIdEncoding};
+pub use hasher::CanonicalHasher;
+pub use jsonl::{
+    canonical_jsonl_digest, canonical_jsonl_writer, jsonl_chain_step, verify_canonical_jsonl, write_canonical_jsonl,
+    CanonicalJsonlReader, CanonicalJsonlWriter, JsonlDigest, JsonlError, JsonlTrailer, JSONL_CHAIN_GENESIS,
+};
+pub use keyed::{canonical_hmac_sha256, hmac_sha256_hex, verify_canonical_hmac_sha256};
+pub use options::{CanonicalizeOptions, FloatPolicy, KeyOrder};
+pub use pack::{verify_pack, verify_pack_with, CheckStatus, PackCheck, PackError, PackReport, VerifyOptions};
+pub use pointer::{canonical_digest_at, canonical_sha256_at, resolve_pointer, PointerError};
+pub use ser::Canonicalize;
+pub use signature::{
+    key_id, sign_envelope, verify_envelope, EnvelopeError, SignatureEnvelope, SigningKey, VerifyingKey,
+};
+pub use strict::{parse_strict, parse_strict_slice, StrictParseError};
+pub use timestamp::{
+    canonical_sha256_with_timestamps, canonicalize_json_with_timestamps, normalize_timestamp, normalize_timestamps,
+    TimestampScope,
+};
+pub use verify::{verify_canonical_digest, verify_canonical_sha256, VerifyError};
+
+/// Version of this implementation. Producers record it next to their
+/// hashes so a verifier can tell when it is checking output from a
+/// different release.
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 
 /// Recursively canonicalize a JSON value.
 ///
 /// - Objects: keys sorted lexicographically
-/// - Arrays: preserved in original order (must be pre-sorted upstream)
+/// - Arrays: preserved in original order (sort upstream, or see
+///   [`canonicalize_json_with_array_sorts`])
 /// - Primitives: unchanged
+///
+/// Builds the copy in one pass from the borrowed tree. To serialize or
+/// hash, use [`canonical_bytes`], [`canonical_sha256`] or
+/// [`canonicalize_to_writer`], which write straight from the borrowed
+/// value.
+///
+/// A `Value` always serializes its keys in UTF-8 byte order, so
+/// [`KeyOrder::Utf16CodeUnits`] applies only to the byte and digest
+/// functions that take [`CanonicalizeOptions`].
 pub fn canonicalize_json(v: &Value) -> Value {
     match v {
         Value::Object(map) => {
-            let mut keys: Vec<&String> = map.keys().collect();
-            keys.sort();
+            let mut entries: Vec<(&String, &Value)> = map.iter().collect();
+            entries.sort_unstable_by(|a, b| a.0.cmp(b.0));
+            Value::Object(entries.into_iter().map(|(k, v)| (k.clone(), canonicalize_json(v))).collect())
+        }
+        Value::Array(arr) => Value::Array(arr.iter().map(canonicalize_json).collect()),
+        leaf => leaf.clone(),
+    }
+}
+
+/// [`canonicalize_json`] for a value the caller no longer needs; moves
+/// keys and leaves instead of copying them.
+pub fn into_canonical(v: Value) -> Value {
+    match v {
+        Value::Object(map) => {
+            let mut entries: Vec<(String, Value)> = map.into_iter().collect();
+            entries.sort_unstable_by(|a, b| a.0.cmp(&b.0));
 
             let mut out = serde_json::Map::new();
-            for k in keys {
-                out.insert(k.clone(), canonicalize_json(&map[k]));
+            for (k, v) in entries {
+                out.insert(k, into_canonical(v));
             }
             Value::Object(out)
         }
+        Value::Array(arr) => Value::Array(arr.into_iter().map(into_canonical).collect()),
+        other => other,
+    }
+}
+
+/// Stream the canonical form of `v` into `writer`.
+///
+/// Nothing proportional to the document is allocated: keys are sorted one
+/// object at a time and bytes go straight to the writer, so a
+/// multi-hundred-MB pack can be written to a file or hashed without a
+/// canonical copy or output buffer. Leaves go through serde_json, so the
+/// bytes are identical to [`canonical_bytes`]. Wrap unbuffered writers
+/// (files, sockets) in a `BufWriter`; output arrives in small pieces.
+pub fn canonicalize_to_writer(v: &Value, writer: impl Write) -> io::Result<()> {
+    canonicalize_to_writer_with_options(v, writer, &CanonicalizeOptions::default())
+}
+
+/// [`canonicalize_to_writer`] under `options`. With
+/// [`nfc_normalize_strings`](CanonicalizeOptions::nfc_normalize_strings),
+/// fails with [`io::ErrorKind::InvalidData`] if two keys of one object
+/// normalize to the same string; likewise for a number the
+/// [`float_policy`](CanonicalizeOptions::float_policy) refuses. Convert
+/// the error with `CanonicalizeError::from` to see which.
+pub fn canonicalize_to_writer_with_options(
+    v: &Value,
+    mut writer: impl Write,
+    options: &CanonicalizeOptions,
+) -> io::Result<()> {
+    write_value(v, &mut writer, options)
+}
+
+/// Stream the canonical form of `v` into `writer` and return its SHA-256,
+/// in one pass.
+pub fn canonicalize_to_writer_with_sha256(v: &Value, writer: impl Write) -> io::Result<String> {
+    canonicalize_to_writer_with_digest(v, writer, Algorithm::Sha256).map(|d| digest_hex(&d))
+}
+
+/// Stream the canonical form of `v` into `writer` and return its raw
+/// digest under `algorithm`, in one pass.
+pub fn canonicalize_to_writer_with_digest(
+    v: &Value,
+    writer: impl Write,
+    algorithm: Algorithm,
+) -> io::Result<Vec<u8>> {
+    canonicalize_to_writer_with_digest_and_options(v, writer, algorithm, &CanonicalizeOptions::default())
+}
+
+/// [`canonicalize_to_writer_with_digest`] under `options`.
+pub fn canonicalize_to_writer_with_digest_and_options(
+    v: &Value,
+    writer: impl Write,
+    algorithm: Algorithm,
+    options: &CanonicalizeOptions,
+) -> io::Result<Vec<u8>> {
+    let mut tee = HashingWriter { inner: writer, hasher: algorithm.hasher() };
+    write_value(v, &mut tee, options)?;
+    tee.inner.flush()?;
+    Ok(tee.hasher.finalize())
+}
+
+fn write_value<W: Write>(v: &Value, out: &mut W, options: &CanonicalizeOptions) -> io::Result<()> {
+    match v {
+        Value::Object(map) if options.writes_keys_verbatim() && keys_in_byte_order(map) => {
+            // serde_json's default map already iterates in canonical order;
+            // write from it without collecting the entries
+            out.write_all(b"{")?;
+            for (i, (k, v)) in map.iter().enumerate() {
+                if i > 0 {
+                    out.write_all(b",")?;
+                }
+                serde_json::to_writer(&mut *out, k)?;
+                out.write_all(b":")?;
+                write_value(v, out, options)?;
+            }
+            out.write_all(b"}")
+        }
+        Value::Object(map) => {
+            let mut entries: Vec<(Cow<str>, &Value)> =
+                map.iter().map(|(k, v)| (options.string(k), v)).collect();
+            entries.sort_unstable_by(|a, b| options.key_order.compare(&a.0, &b.0));
+            if options.nfc_normalize_strings {
+                if let Some(pair) = entries.windows(2).find(|pair| pair[0].0 == pair[1].0) {
+                    return Err(options::normalized_key_collision(&pair[0].0));
+                }
+            }
+
+            out.write_all(b"{")?;
+            for (i, (k, v)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    out.write_all(b",")?;
+                }
+                serde_json::to_writer(&mut *out, &*k)?;
+                out.write_all(b":")?;
+                write_value(v, out, options)?;
+            }
+            out.write_all(b"}")
+        }
         Value::Array(arr) => {
-            Value::Array(arr.iter().map(canonicalize_json).collect())
+            out.write_all(b"[")?;
+            for (i, item) in arr.iter().enumerate() {
+                if i > 0 {
+                    out.write_all(b",")?;
+                }
+                write_value(item, out, options)?;
+            }
+            out.write_all(b"]")
         }
-        _ => v.clone(),
+        Value::String(s) => Ok(serde_json::to_writer(&mut *out, &*options.string(s))?),
+        Value::Number(n) => {
+            options.check_number(n)?;
+            Ok(serde_json::to_writer(&mut *out, n)?)
+        }
+        leaf => Ok(serde_json::to_writer(&mut *out, leaf)?),
     }
 }
 
+/// Whether `map` iterates in UTF-8 byte order: always with serde_json's
+/// default `BTreeMap`, only by chance with `preserve_order`.
+fn keys_in_byte_order(map: &serde_json::Map<String, Value>) -> bool {
+    map.keys().zip(map.keys().skip(1)).all(|(a, b)| a < b)
+}
+
 /// Serialize a JSON value to canonical bytes (minified, sorted keys).
+#[deprecated(since = "1.1.0", note = "panics on failure; use `try_canonical_bytes`")]
 pub fn canonical_bytes(v: &Value) -> Vec<u8> {
-    let canon = canonicalize_json(v);
-    serde_json::to_vec(&canon).expect("serialize canonical json")
+    try_canonical_bytes(v).expect("default options cannot fail")
+}
+
+/// Serialize a JSON value to canonical bytes (minified, sorted keys).
+pub fn try_canonical_bytes(v: &Value) -> Result<Vec<u8>, CanonicalizeError> {
+    canonical_bytes_with_options(v, &CanonicalizeOptions::default())
+}
+
+/// [`try_canonical_bytes`] under `options`.
+pub fn canonical_bytes_with_options(
+    v: &Value,
+    options: &CanonicalizeOptions,
+) -> Result<Vec<u8>, CanonicalizeError> {
+    let mut out = Vec::with_capacity(encoded_len_hint(v));
+    write_value(v, &mut out, options)?;
+    Ok(out)
+}
+
+/// Rough serialized size, so the output buffer is allocated once for
+/// typical documents instead of doubling through every size.
+fn encoded_len_hint(v: &Value) -> usize {
+    match v {
+        Value::Object(map) => {
+            2 + map.iter().map(|(k, v)| k.len() + 4 + encoded_len_hint(v)).sum::<usize>()
+        }
+        Value::Array(arr) => 2 + arr.iter().map(|v| 1 + encoded_len_hint(v)).sum::<usize>(),
+        Value::String(s) => s.len() + 2,
+        Value::Number(_) => 8,
+        Value::Bool(_) | Value::Null => 5,
+    }
+}
+
+const HEX_DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+/// Lowercase hex of a digest via a 16-entry lookup table into a buffer
+/// sized up front. Plain scalar code; it only avoids `hex::encode`'s
+/// per-call iterator and reallocation.
+fn digest_hex(digest: &[u8]) -> String {
+    let mut out = String::with_capacity(digest.len() * 2);
+    for &b in digest {
+        out.push(HEX_DIGITS[(b >> 4) as usize] as char);
+        out.push(HEX_DIGITS[(b & 0x0f) as usize] as char);
+    }
+    out
 }
 
 /// Compute SHA-256 hash of bytes, returning lowercase hex string.
 pub fn sha256_hex(bytes: &[u8]) -> String {
-    let mut hasher = Sha256::new();
-    hasher.update(bytes);
-    hex::encode(hasher.finalize())
+    digest_hex(&Sha256::digest(bytes))
+}
+
+/// Passes writes through to `inner` and hashes exactly the bytes it
+/// accepted.
+struct HashingWriter<W> {
+    inner: W,
+    hasher: digest::Hasher,
+}
+
+impl<W: Write> Write for HashingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.hasher.update(&buf[..n]);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
 }
 
 /// Compute canonical SHA-256 of a JSON value.
 pub fn canonical_sha256(v: &Value) -> String {
-    sha256_hex(&canonical_bytes(v))
+    canonical_digest(v, Algorithm::Sha256)
+}
+
+/// Canonical digest of a JSON value under `algorithm`, as lowercase hex.
+pub fn canonical_digest(v: &Value, algorithm: Algorithm) -> String {
+    digest_hex(&canonical_digest_bytes(v, algorithm))
+}
+
+/// Canonical digest of a JSON value under `algorithm`, as raw bytes
+/// ([`Algorithm::output_len`] long).
+pub fn canonical_digest_bytes(v: &Value, algorithm: Algorithm) -> Vec<u8> {
+    canonicalize_to_writer_with_digest(v, io::sink(), algorithm)
+        .expect("hashing into a sink cannot fail")
+}
+
+/// [`canonical_digest`] under `options`, as lowercase hex.
+pub fn canonical_digest_with_options(
+    v: &Value,
+    algorithm: Algorithm,
+    options: &CanonicalizeOptions,
+) -> Result<String, CanonicalizeError> {
+    let digest = canonicalize_to_writer_with_digest_and_options(v, io::sink(), algorithm, options)?;
+    Ok(digest_hex(&digest))
 }
 
 /// Generate UUIDv5 from namespace and name.
+#[deprecated(since = "1.1.0", note = "panics on a malformed namespace; use `try_uuidv5`")]
 pub fn uuidv5(namespace: &str, name: &str) -> String {
+    try_uuidv5(namespace, name).expect("namespace is a UUID")
+}
+
+/// Generate UUIDv5 from namespace and name. Fails if `namespace` is not
+/// 32 hex digits once hyphens are removed.
+pub fn try_uuidv5(namespace: &str, name: &str) -> Result<String, CanonicalizeError> {
     use sha1::{Sha1, Digest as Sha1Digest};
     
     // Parse namespace UUID (remove hyphens, decode hex)
     let namespace_hex = namespace.replace("-", "");
-    let namespace_bytes: Vec<u8> = (0..16)
-        .map(|i| u8::from_str_radix(&namespace_hex[i*2..i*2+2], 16).unwrap())
-        .collect();
+    let namespace_bytes: [u8; 16] = hex::decode(&namespace_hex)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| CanonicalizeError::InvalidNamespace(namespace.to_string()))?;
     
     // Hash namespace + name
     let mut hasher = Sha1::new();
-    hasher.update(&namespace_bytes);
+    hasher.update(namespace_bytes);
     hasher.update(name.as_bytes());
     let hash = hasher.finalize();
     
@@ -73,20 +351,25 @@ pub fn uuidv5(namespace: &str, name: &str) -> String {
     bytes[8] = (bytes[8] & 0x3f) | 0x80;
     
     // Format as UUID
-    format!(
+    Ok(format!(
         "{:08x}-{:04x}-{:04x}-{:04x}-{:012x}",
         u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
         u16::from_be_bytes([bytes[4], bytes[5]]),
         u16::from_be_bytes([bytes[6], bytes[7]]),
         u16::from_be_bytes([bytes[8], bytes[9]]),
         u64::from_be_bytes([0, 0, bytes[10], bytes[11], bytes[12], bytes[13], bytes[14], bytes[15]])
-    )
+    ))
 }
 
 /// Evidify namespace for finding IDs
 pub const EVIDIFY_NAMESPACE: &str = "6ba7b810-9dad-11d1-80b4-00c04fd430c8";
 
 /// Generate stable finding ID from components.
+///
+/// The components are joined with `|`, so a component containing a pipe
+/// can collide with a different split. New IDs should come from
+/// [`FindingIdBuilder`]; this function produces the same IDs as its
+/// [`FindingIdEncoding::Legacy`] mode.
 pub fn generate_finding_id(
     gate_id: &str,
     code: &str,
@@ -100,7 +383,7 @@ pub fn generate_finding_id(
         "{}|{}|{}|{}|{}|{}|{}",
         gate_id, code, sub_code, severity, message, object_type, object_id
     );
-    uuidv5(EVIDIFY_NAMESPACE, &input)
+    try_uuidv5(EVIDIFY_NAMESPACE, &input).expect("EVIDIFY_NAMESPACE is a UUID")
 }
 
 #[cfg(test)]
@@ -125,6 +408,154 @@ mod tests {
         assert_eq!(hash, "a02e9e11544fe80a264bc0e2ef6c8c1e1d08ae02e26d2e1fd3ed61d17b9f4880");
     }
 
+    #[test]
+    fn test_streaming_matches_tree_canonicalization() {
+        let input = json!({
+            "z": [{"b": "tab\tquote\"", "a": null}, 1.5, -0, 1e300],
+            "\u{e9}": {"y": true, "x": "caf\u{e9} \u{1F600}"},
+            "A": []
+        });
+        let expected = serde_json::to_vec(&canonicalize_json(&input)).unwrap();
+
+        assert_eq!(try_canonical_bytes(&input).unwrap(), expected);
+        assert_eq!(serde_json::to_vec(&into_canonical(input.clone())).unwrap(), expected);
+        assert_eq!(canonical_sha256(&input), hex::encode(Sha256::digest(&expected)));
+    }
+
+    #[test]
+    fn test_canonicalize_to_writer_tees_into_hash() {
+        let input = json!({"b": [1, {"d": 2, "c": 3}], "a": "x"});
+        let mut file = io::BufWriter::new(Vec::new());
+        let hash = canonicalize_to_writer_with_sha256(&input, &mut file).unwrap();
+        let written = file.into_inner().unwrap();
+
+        assert_eq!(written, br#"{"a":"x","b":[1,{"c":3,"d":2}]}"#);
+        assert_eq!(hash, canonical_sha256(&input));
+
+        let mut plain = Vec::new();
+        canonicalize_to_writer(&input, &mut plain).unwrap();
+        assert_eq!(plain, written);
+    }
+
+    #[test]
+    fn test_canonical_digest_algorithms() {
+        let input = json!({"c": 3, "a": 1, "b": 2});
+        let canonical = br#"{"a":1,"b":2,"c":3}"#;
+
+        assert_eq!(canonical_digest(&input, Algorithm::Sha256), canonical_sha256(&input));
+        assert_eq!(
+            canonical_digest(&input, Algorithm::Sha512),
+            "6bbcda7073a1c4b821ca129f24b9aa8878709ea68180e0ca623ed08864793da8\
+             587000523e64b31ebf7db84577828946ade2aadbca1f0d53fa114759d4c9bc59"
+        );
+        assert_eq!(
+            canonical_digest_bytes(&input, Algorithm::Blake3),
+            blake3::hash(canonical).as_bytes().to_vec()
+        );
+        for algorithm in [Algorithm::Sha256, Algorithm::Sha512, Algorithm::Blake3] {
+            assert_eq!(canonical_digest_bytes(&input, algorithm).len(), algorithm.output_len());
+            assert_eq!(algorithm.name().parse::<Algorithm>(), Ok(algorithm));
+        }
+        assert_eq!("SHA-512".parse::<Algorithm>(), Ok(Algorithm::Sha512));
+        assert!("md5".parse::<Algorithm>().is_err());
+    }
+
+    #[test]
+    fn test_nfc_normalization_option() {
+        let composed = json!({"caf\u{e9}": "Jos\u{e9}", "n": ["\u{c5}"]});
+        let decomposed = json!({"cafe\u{301}": "Jose\u{301}", "n": ["A\u{30a}"]});
+        let nfc = CanonicalizeOptions { nfc_normalize_strings: true, ..Default::default() };
+
+        assert_ne!(try_canonical_bytes(&composed).unwrap(), try_canonical_bytes(&decomposed).unwrap());
+        assert_eq!(
+            canonical_bytes_with_options(&decomposed, &nfc).unwrap(),
+            try_canonical_bytes(&composed).unwrap()
+        );
+        assert_eq!(
+            canonical_digest_with_options(&decomposed, Algorithm::Sha256, &nfc).unwrap(),
+            canonical_sha256(&composed)
+        );
+
+        // Normalization happens before sorting: "\u{e9}" sorts after "f",
+        // "e\u{301}" would not
+        let order = json!({"e\u{301}": 1, "f": 2});
+        assert_eq!(canonical_bytes_with_options(&order, &nfc).unwrap(), "{\"f\":2,\"\u{e9}\":1}".as_bytes());
+
+        let colliding = json!({"\u{e9}": 1, "e\u{301}": 2});
+        let err = canonical_bytes_with_options(&colliding, &nfc).unwrap_err();
+        assert!(matches!(err, CanonicalizeError::NormalizedKeyCollision(_)));
+        let mut sink = Vec::new();
+        let io_err = canonicalize_to_writer_with_options(&colliding, &mut sink, &nfc).unwrap_err();
+        assert_eq!(io_err.kind(), io::ErrorKind::InvalidData);
+        assert!(matches!(CanonicalizeError::from(io_err), CanonicalizeError::NormalizedKeyCollision(_)));
+    }
+
+    #[test]
+    fn test_float_policy() {
+        let doc = json!({"count": 3, "delta": -2, "big": u64::MAX, "score": 0.5});
+        let integers = json!({"count": 3, "delta": -2, "big": u64::MAX});
+
+        let reject = CanonicalizeOptions { float_policy: FloatPolicy::RejectNonFinite, ..Default::default() };
+        assert_eq!(canonical_bytes_with_options(&doc, &reject).unwrap(), try_canonical_bytes(&doc).unwrap());
+
+        let integer_only = CanonicalizeOptions { float_policy: FloatPolicy::IntegerOnly, ..Default::default() };
+        assert_eq!(
+            canonical_bytes_with_options(&integers, &integer_only).unwrap(),
+            try_canonical_bytes(&integers).unwrap()
+        );
+        match canonical_bytes_with_options(&json!({"a": [1, 1.0]}), &integer_only) {
+            Err(CanonicalizeError::NonIntegerNumber(n)) => assert_eq!(n, "1.0"),
+            other => panic!("expected NonIntegerNumber, got {:?}", other),
+        }
+        assert!(matches!(
+            canonical_digest_with_options(&doc, Algorithm::Sha256, &integer_only),
+            Err(CanonicalizeError::NonIntegerNumber(_))
+        ));
+    }
+
+    #[test]
+    fn test_reject_non_finite_numbers() {
+        let reject = CanonicalizeOptions { float_policy: FloatPolicy::RejectNonFinite, ..Default::default() };
+        let finite = json!({"max": f64::MAX, "tiny": 5e-324, "neg": -0.25});
+        assert_eq!(canonical_bytes_with_options(&finite, &reject).unwrap(), try_canonical_bytes(&finite).unwrap());
+
+        // A literal beyond f64 only parses when arbitrary_precision is on
+        // somewhere in the build; then it must be refused, not written back
+        match serde_json::from_str::<Value>("{\"x\": 1e400}") {
+            Ok(overflow) => match canonical_bytes_with_options(&overflow, &reject) {
+                Err(CanonicalizeError::NonFiniteNumber(n)) => assert_eq!(n, "1e400"),
+                other => panic!("expected NonFiniteNumber, got {:?}", other),
+            },
+            Err(e) => assert!(e.to_string().contains("number out of range")),
+        }
+    }
+
+    #[test]
+    fn test_utf16_key_order() {
+        // U+FF61 sorts before U+1F600 as UTF-8 bytes (EF.. < F0..), after
+        // it as UTF-16 code units (FF61 > D83D)
+        let doc = json!({"\u{FF61}": 1, "\u{1F600}": 2, "a": 3});
+        let utf16 = CanonicalizeOptions { key_order: KeyOrder::Utf16CodeUnits, ..Default::default() };
+        assert_eq!(try_canonical_bytes(&doc).unwrap(), "{\"a\":3,\"\u{FF61}\":1,\"\u{1F600}\":2}".as_bytes());
+        assert_eq!(
+            canonical_bytes_with_options(&doc, &utf16).unwrap(),
+            "{\"a\":3,\"\u{1F600}\":2,\"\u{FF61}\":1}".as_bytes()
+        );
+
+        // Keys within the BMP below U+E000 sort the same either way
+        let plain = json!({"b": 1, "\u{e9}": 2, "A": 3});
+        assert_eq!(canonical_bytes_with_options(&plain, &utf16).unwrap(), try_canonical_bytes(&plain).unwrap());
+    }
+
+    #[test]
+    fn test_try_uuidv5_rejects_malformed_namespace() {
+        let id = try_uuidv5(EVIDIFY_NAMESPACE, "name").unwrap();
+        assert_eq!(try_uuidv5(&EVIDIFY_NAMESPACE.replace('-', ""), "name").unwrap(), id);
+        for bad in ["", "6ba7b810", "zzzzzzzz-9dad-11d1-80b4-00c04fd430c8", "6ba7b810-9dad-11d1-80b4-00c04fd430c8ff", "\u{e9}\u{e9}\u{e9}\u{e9}\u{e9}\u{e9}\u{e9}\u{e9}\u{e9}\u{e9}\u{e9}\u{e9}\u{e9}\u{e9}\u{e9}\u{e9}"] {
+            assert!(matches!(try_uuidv5(bad, "name"), Err(CanonicalizeError::InvalidNamespace(_))), "{:?}", bad);
+        }
+    }
+
     #[test]
     fn test_finding_id_generation() {
         let id = generate_finding_id(
@@ -139,4 +570,179 @@ mod tests {
         // This should match TypeScript implementation
         assert_eq!(id, "4502e9ae-cd37-5c9d-88fe-06f3a8ef5937");
     }
+
+    #[test]
+    fn test_canonical_sha256_at() {
+        let doc = json!({"report": {"sections": [{}, {}, {}, {"claims": {"b": 2, "a": 1}}]}, "a/b": {"~": true}});
+        let claims = canonical_sha256_at(&doc, "/report/sections/3/claims").unwrap();
+        assert_eq!(claims, canonical_sha256(&json!({"a": 1, "b": 2})));
+        assert_eq!(canonical_sha256_at(&doc, "").unwrap(), canonical_sha256(&doc));
+        assert_eq!(resolve_pointer(&doc, "/a~1b/~0").unwrap(), &json!(true));
+
+        assert_eq!(
+            canonical_sha256_at(&doc, "/report/summary/text"),
+            Err(PointerError::MissingKey { path: "/report/summary".into() })
+        );
+        assert_eq!(
+            resolve_pointer(&doc, "/report/sections/4"),
+            Err(PointerError::IndexOutOfRange { path: "/report/sections/4".into(), len: 4 })
+        );
+        for bad in ["/report/sections/03", "/report/sections/-", "/report/sections/x"] {
+            assert_eq!(resolve_pointer(&doc, bad), Err(PointerError::InvalidIndex { path: bad.into() }));
+        }
+        assert_eq!(
+            resolve_pointer(&doc, "/a~1b/~0/deeper"),
+            Err(PointerError::NotAContainer { path: "/a~1b/~0/deeper".into() })
+        );
+        assert!(matches!(resolve_pointer(&doc, "report"), Err(PointerError::Syntax(_))));
+        assert!(matches!(resolve_pointer(&doc, "/a~2b"), Err(PointerError::Syntax(_))));
+    }
+
+    #[test]
+    fn test_array_sorts() {
+        let a = json!({"findings": [
+            {"finding_id": "f-2", "anchors": ["b", "a"]},
+            {"finding_id": "f-10", "anchors": []},
+            {"finding_id": "f-1", "anchors": ["c"]}
+        ], "rank": [3, 10, -1]});
+        let b = json!({"rank": [-1, 10, 3], "findings": [
+            {"finding_id": "f-1", "anchors": ["c"]},
+            {"anchors": [], "finding_id": "f-10"},
+            {"finding_id": "f-2", "anchors": ["a", "b"]}
+        ]});
+        let sorts = [
+            ArraySort::new("/findings", "/finding_id").unwrap(),
+            ArraySort::new("/findings/*/anchors", "").unwrap(),
+            ArraySort::new("/rank", "").unwrap(),
+            ArraySort::new("/absent/*", "").unwrap(),
+        ];
+        assert_ne!(canonical_sha256(&a), canonical_sha256(&b));
+        assert_eq!(canonical_sha256_with_array_sorts(&a, &sorts).unwrap(), canonical_sha256_with_array_sorts(&b, &sorts).unwrap());
+
+        let sorted = canonicalize_json_with_array_sorts(&a, &sorts).unwrap();
+        assert_eq!(sorted["rank"], json!([-1, 3, 10]));
+        let ids: Vec<&str> = sorted["findings"].as_array().unwrap().iter().map(|f| f["finding_id"].as_str().unwrap()).collect();
+        assert_eq!(ids, ["f-1", "f-10", "f-2"]);
+        assert_eq!(sorted["findings"][2]["anchors"], json!(["a", "b"]));
+
+        let missing = json!({"findings": [{"finding_id": "f-1"}, {"id": "f-2"}]});
+        assert!(matches!(
+            canonical_sha256_with_array_sorts(&missing, &sorts[..1]),
+            Err(CanonicalizeError::MissingSortKey { ref array, index: 1 }) if array == "/findings"
+        ));
+        let scalar = json!({"findings": {"x": 1}});
+        assert!(matches!(sort_arrays(&mut scalar.clone(), &sorts[..1]), Err(CanonicalizeError::SortPathNotArray(_))));
+        assert!(matches!(ArraySort::new("findings", "/id"), Err(CanonicalizeError::InvalidSortSpec(_))));
+        assert!(matches!(ArraySort::new("/findings", "/a~2"), Err(CanonicalizeError::InvalidSortSpec(_))));
+    }
+
+    #[test]
+    fn test_timestamp_normalization() {
+        for (input, expected) in [
+            ("2024-01-02T03:04:05Z", "2024-01-02T03:04:05Z"),
+            ("2024-01-02T03:04:05.000+00:00", "2024-01-02T03:04:05Z"),
+            ("2024-01-01t22:04:05.120-05:00", "2024-01-02T03:04:05.12Z"),
+            ("2024-03-01 00:30:00+01:00", "2024-02-29T23:30:00Z"),
+            ("1999-12-31T23:59:59.999999-00:30", "2000-01-01T00:29:59.999999Z"),
+        ] {
+            assert_eq!(normalize_timestamp(input).as_deref(), Some(expected), "{}", input);
+        }
+        for bad in ["2024-01-02", "2024-02-30T00:00:00Z", "2024-01-02T03:04:60Z", "2024-01-02T03:04:05", "2024-01-02T03:04:05.Z", "2024-01-02T03:04:05+0100", "meeting at 2024-01-02T03:04:05Z"] {
+            assert_eq!(normalize_timestamp(bad), None, "{}", bad);
+        }
+
+        let a = json!({"exported_at": "2024-01-02T03:04:05Z", "events": [{"at": "2024-01-02T04:04:05+01:00", "note": "2024-01-02"}]});
+        let b = json!({"exported_at": "2024-01-02T03:04:05.000+00:00", "events": [{"at": "2024-01-02T03:04:05Z", "note": "2024-01-02"}]});
+        assert_eq!(
+            canonical_sha256_with_timestamps(&a, &TimestampScope::AllStrings).unwrap(),
+            canonical_sha256_with_timestamps(&b, &TimestampScope::AllStrings).unwrap()
+        );
+
+        let fields = TimestampScope::Fields(vec!["exported_at".into()]);
+        let mut only_named = b.clone();
+        assert_eq!(normalize_timestamps(&mut only_named, &fields).unwrap(), 1);
+        assert_eq!(only_named["events"][0]["at"], "2024-01-02T03:04:05Z");
+        let mut bad = json!({"items": [{"exported_at": "yesterday"}]});
+        assert!(matches!(
+            normalize_timestamps(&mut bad, &fields),
+            Err(CanonicalizeError::InvalidTimestamp(ref path)) if path == "/items/0/exported_at"
+        ));
+    }
+
+    #[test]
+    fn test_finding_id_builder() {
+        let finding = FindingIdBuilder::new()
+            .gate_id("GATE-001")
+            .code("OPINION_NO_BASIS")
+            .sub_code("NO_SUPPORTING_ANCHORS")
+            .severity("BLOCK")
+            .message("Opinion OPN-001 has no supporting anchors in audit log")
+            .object("opinion", "OPN-001");
+        let legacy = finding.clone().encoding(FindingIdEncoding::Legacy).build();
+        assert_eq!(legacy, "4502e9ae-cd37-5c9d-88fe-06f3a8ef5937");
+        assert_ne!(finding.build(), legacy);
+
+        // A pipe moved between fields collides in legacy mode only
+        let a = FindingIdBuilder::new().message("a|b").object("c", "id");
+        let b = FindingIdBuilder::new().message("a").object("b|c", "id");
+        assert_eq!(a.clone().encoding(FindingIdEncoding::Legacy).build(), b.clone().encoding(FindingIdEncoding::Legacy).build());
+        assert_ne!(a.build(), b.build());
+        assert_eq!(a.name(), "v2|0:0:0:0:3:a|b1:c2:id");
+    }
+
+    #[test]
+    fn test_canonicalize_trait() {
+        use serde::Serialize;
+        use std::collections::{BTreeMap, HashMap};
+
+        #[derive(Serialize)]
+        enum Severity { Block, Warn }
+
+        #[derive(Serialize)]
+        struct Anchor { sequence: u64, r#type: &'static str }
+
+        #[derive(Serialize)]
+        struct Finding {
+            severity: Severity,
+            message: String,
+            anchors: Vec<Anchor>,
+            score: f64,
+            ratio: f32,
+            counts: HashMap<u32, i64>,
+            missing: Option<String>,
+            kind: Kind,
+            #[serde(flatten)]
+            extra: BTreeMap<String, Value>,
+        }
+
+        #[derive(Serialize)]
+        enum Kind { Gate { id: String, b: (i8, char) } }
+
+        let finding = Finding {
+            severity: Severity::Block,
+            message: "Opinion \"OPN-001\" has no basis\n".to_string(),
+            anchors: vec![Anchor { sequence: 3, r#type: "audit_entry" }],
+            score: 0.1,
+            ratio: 0.3,
+            counts: HashMap::from([(10, -1), (9, 2)]),
+            missing: None,
+            kind: Kind::Gate { id: "GATE-004".to_string(), b: (-1, 'x') },
+            extra: BTreeMap::from([("zeta".to_string(), json!({"b": [1, {"d": 1, "c": 2}], "a": null}))]),
+        };
+        let value = serde_json::to_value(&finding).unwrap();
+        assert_eq!(finding.canonical_bytes().unwrap(), try_canonical_bytes(&value).unwrap());
+        assert_eq!(finding.canonical_sha256().unwrap(), canonical_sha256(&value));
+        assert_eq!(finding.canonical_digest(Algorithm::Blake3).unwrap(), canonical_digest(&value, Algorithm::Blake3));
+        assert_eq!(value.canonical_bytes().unwrap(), try_canonical_bytes(&value).unwrap());
+        assert_eq!(Severity::Warn.canonical_bytes().unwrap(), b"\"Warn\"");
+
+        let nfc = CanonicalizeOptions { nfc_normalize_strings: true, ..Default::default() };
+        let colliding = BTreeMap::from([("\u{e9}", 1), ("e\u{301}", 2)]);
+        assert!(matches!(colliding.canonical_bytes_with_options(&nfc), Err(CanonicalizeError::NormalizedKeyCollision(_))));
+        let allow = CanonicalizeOptions { float_policy: FloatPolicy::Allow, ..Default::default() };
+        assert!(matches!(f64::NAN.canonical_bytes(), Err(CanonicalizeError::NonFiniteNumber(_))));
+        assert!(matches!(f64::INFINITY.canonical_sha256(), Err(CanonicalizeError::NonFiniteNumber(_))));
+        assert_eq!(f64::NAN.canonical_bytes_with_options(&allow).unwrap(), b"null");
+        assert!(matches!(HashMap::from([((1, 2), 3)]).canonical_bytes(), Err(CanonicalizeError::Serialize(_))));
+    }
 }
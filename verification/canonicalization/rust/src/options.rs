@@ -0,0 +1,111 @@
+//! Options that change the canonical form.
+//!
+//! The defaults reproduce the plain canonical form the TypeScript verifier
+//! implements; every option is off unless a producer and its verifiers
+//! agree to turn it on. The exception is non-finite numbers, refused by
+//! default: that never changes the bytes of a document that canonicalizes.
+
+use serde_json::Number;
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::io;
+use unicode_normalization::{is_nfc, UnicodeNormalization};
+
+use crate::CanonicalizeError;
+
+/// Settings for the `*_with_options` canonicalization functions.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CanonicalizeOptions {
+    /// Normalize object keys and string values to Unicode NFC before keys
+    /// are sorted and anything is written. Without it, `"é"` typed as one
+    /// code point and as `e` + combining accent hash differently even
+    /// though they render the same; inputs from different keyboards,
+    /// platforms or copy-paste paths then fail to verify.
+    pub nfc_normalize_strings: bool,
+    /// Which numbers are accepted; see [`FloatPolicy`].
+    pub float_policy: FloatPolicy,
+    /// How object keys are ordered; see [`KeyOrder`].
+    pub key_order: KeyOrder,
+}
+
+/// Order of object keys in the canonical form. The two orders agree
+/// unless a key holds a character above U+FFFF and another, at the same
+/// position, one in U+E000..=U+FFFF; then the result differs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum KeyOrder {
+    /// Compare keys byte by byte in UTF-8 (Rust's `str` ordering, and
+    /// code point order).
+    #[default]
+    Utf8Bytes,
+    /// Compare keys by UTF-16 code units, as JavaScript's default
+    /// `Array.prototype.sort` does. Use it to match hashes from the
+    /// TypeScript implementation on documents with such keys.
+    Utf16CodeUnits,
+}
+
+impl KeyOrder {
+    pub(crate) fn compare(&self, a: &str, b: &str) -> Ordering {
+        match self {
+            KeyOrder::Utf8Bytes => a.cmp(b),
+            KeyOrder::Utf16CodeUnits => a.encode_utf16().cmp(b.encode_utf16()),
+        }
+    }
+}
+
+/// Which JSON numbers canonicalization accepts. Anything refused fails the
+/// call instead of being written.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FloatPolicy {
+    /// Write every number as serde_json holds it; a non-finite float from
+    /// a typed value is written as `null`, so it hashes the same as `null`.
+    Allow,
+    /// Refuse numbers that are NaN or ±Infinity as an f64 (the default).
+    /// Plain serde_json cannot hold one, but with `arbitrary_precision`
+    /// enabled anywhere in the build a literal like `1e400` parses and is
+    /// written back verbatim, and a verifier reading numbers as doubles
+    /// then hashes something else. Typed values can hold NaN directly.
+    #[default]
+    RejectNonFinite,
+    /// Refuse anything but integers in the i64/u64 range, including
+    /// integral floats such as `1.0`. For documents that carry amounts and
+    /// timestamps only, where a float means the producer is wrong.
+    IntegerOnly,
+}
+
+impl CanonicalizeOptions {
+    /// Keys are written unchanged and in UTF-8 byte order.
+    pub(crate) fn writes_keys_verbatim(&self) -> bool {
+        !self.nfc_normalize_strings && self.key_order == KeyOrder::Utf8Bytes
+    }
+
+    /// `s` as it should be written; borrowed unless normalization changes it.
+    pub(crate) fn string<'a>(&self, s: &'a str) -> Cow<'a, str> {
+        if self.nfc_normalize_strings && !is_nfc(s) {
+            Cow::Owned(s.nfc().collect())
+        } else {
+            Cow::Borrowed(s)
+        }
+    }
+
+    /// Fails if `n` is refused by [`float_policy`](Self::float_policy).
+    pub(crate) fn check_number(&self, n: &Number) -> io::Result<()> {
+        let integer = n.is_i64() || n.is_u64();
+        let refused = match self.float_policy {
+            FloatPolicy::Allow => None,
+            FloatPolicy::RejectNonFinite if integer => None,
+            FloatPolicy::RejectNonFinite => match n.as_f64() {
+                Some(f) if f.is_finite() => None,
+                _ => Some(CanonicalizeError::NonFiniteNumber(n.to_string())),
+            },
+            FloatPolicy::IntegerOnly if integer => None,
+            FloatPolicy::IntegerOnly => Some(CanonicalizeError::NonIntegerNumber(n.to_string())),
+        };
+        refused.map_or(Ok(()), |e| Err(e.into()))
+    }
+}
+
+/// Two keys of one object became identical after normalization; the
+/// document has no single canonical form under these options.
+pub(crate) fn normalized_key_collision(key: &str) -> io::Error {
+    CanonicalizeError::NormalizedKeyCollision(key.to_string()).into()
+}
@@ -0,0 +1,568 @@
+//! Offline verification of an exported audit pack.
+//!
+//! A pack is a folder laid out as in the export folder layout contract:
+//! `manifest.json` at the root listing every artifact and its SHA-256,
+//! `audit/audit.log` (one event per line, each chained to the one before),
+//! `audit/audit_digest.json` summarizing the chain, and
+//! `verification/gate_report.canon.json` carrying its own canonical hash.
+//! [`verify_pack`] recomputes all of it from the files alone, so whoever
+//! receives a pack can check it without Evidify installed.
+//!
+//! The checks are integrity-only. A pack is not signed, so a passing
+//! report shows the files agree with each other, not who produced them:
+//! anyone able to rewrite the pack can recompute every hash. To tie a pack
+//! to its sender, compare [`PackReport::manifest_sha256`] with the value
+//! the sender gave you separately.
+//!
+//! Event chain hashes are SHA-256 over
+//! `{"seq":..,"timestamp":..,"action":..,"details":..,"prev_hash":..}`
+//! written the way the producer wrote it, `details` byte for byte as it
+//! appears in the log; they are not canonical hashes. The first event's
+//! `prev_hash` is 64 zeros.
+
+use serde::{Deserialize, Serialize};
+use serde_json::value::RawValue;
+use serde_json::Value;
+use std::fmt;
+use std::path::{Component, Path, PathBuf};
+
+use crate::{canonical_sha256, parse_strict_slice, sha256_hex, verify_canonical_sha256};
+
+pub const MANIFEST_FILE: &str = "manifest.json";
+pub const MANIFEST_SCHEMA_VERSION: &str = "evidify.manifest.v1";
+pub const AUDIT_LOG_FILE: &str = "audit/audit.log";
+pub const AUDIT_DIGEST_FILE: &str = "audit/audit_digest.json";
+pub const GATE_REPORT_FILE: &str = "verification/gate_report.canon.json";
+pub const GATE_META_FILE: &str = "verification/gate_report.meta.json";
+
+/// Gate report layout and hashing scheme this verifier implements. A pack
+/// recording others was produced by a different release; it is still
+/// checked, with a warning.
+pub const GATE_VERSION: &str = "1.1";
+pub const HASH_ALGORITHM: &str = "sentinel-based-preimage-v1.1";
+
+/// Files every pack must contain, besides the manifest
+pub const REQUIRED_FILES: &[&str] = &[
+    "canonical/canonical.json",
+    AUDIT_LOG_FILE,
+    AUDIT_DIGEST_FILE,
+    GATE_REPORT_FILE,
+    GATE_META_FILE,
+];
+
+/// `prev_hash` of the first audit event, and the placeholder the gate
+/// report's own hash is computed over
+pub const HASH_SENTINEL: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum CheckStatus {
+    Pass,
+    /// Nothing was found wrong, but something could not be checked.
+    Warn,
+    Fail,
+}
+
+impl fmt::Display for CheckStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            CheckStatus::Pass => "PASS",
+            CheckStatus::Warn => "WARN",
+            CheckStatus::Fail => "FAIL",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PackCheck {
+    pub name: String,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PackReport {
+    pub pack_id: Option<String>,
+    /// SHA-256 of `manifest.json` as read, for comparing out of band
+    pub manifest_sha256: String,
+    pub checks: Vec<PackCheck>,
+}
+
+impl PackReport {
+    /// The worst status of any check.
+    pub fn status(&self) -> CheckStatus {
+        self.checks.iter().map(|c| c.status).max().unwrap_or(CheckStatus::Pass)
+    }
+
+    /// No check failed (warnings allowed).
+    pub fn passed(&self) -> bool {
+        self.status() != CheckStatus::Fail
+    }
+
+    fn push(&mut self, name: impl Into<String>, status: CheckStatus, detail: impl Into<String>) {
+        self.checks.push(PackCheck { name: name.into(), status, detail: detail.into() });
+    }
+}
+
+/// How strictly [`verify_pack_with`] judges a pack.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VerifyOptions {
+    /// Fail manifest entries that carry no hash instead of warning. Such an
+    /// artifact is only checked for presence, so its contents are unverified.
+    /// On by default; turn it off only for packs from older producers.
+    pub strict: bool,
+}
+
+impl Default for VerifyOptions {
+    fn default() -> Self {
+        VerifyOptions { strict: true }
+    }
+}
+
+/// The pack could not be checked at all.
+#[derive(Debug)]
+pub enum PackError {
+    /// `manifest.json` is missing, unreadable or not a manifest.
+    Manifest(String),
+}
+
+impl fmt::Display for PackError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PackError::Manifest(msg) => write!(f, "cannot read {}: {}", MANIFEST_FILE, msg),
+        }
+    }
+}
+
+impl std::error::Error for PackError {}
+
+/// One artifact listed in the manifest. Older packs list bare paths.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum ManifestFile {
+    Hashed { path: String, sha256: Option<String> },
+    Path(String),
+}
+
+#[derive(Debug, Deserialize)]
+struct Manifest {
+    schema_version: Option<String>,
+    pack_id: Option<String>,
+    files: Vec<ManifestFile>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AuditEvent<'a> {
+    seq: u64,
+    timestamp: String,
+    action: String,
+    #[serde(borrow)]
+    details: &'a RawValue,
+    prev_hash: String,
+    chain_hash: String,
+}
+
+/// Verify the pack in `dir` with default (strict) options. Problems with the
+/// pack's contents are reported as failed checks; only an unusable
+/// manifest is an error.
+pub fn verify_pack(dir: &Path) -> Result<PackReport, PackError> {
+    verify_pack_with(dir, &VerifyOptions::default())
+}
+
+/// [`verify_pack`] with explicit options.
+pub fn verify_pack_with(dir: &Path, options: &VerifyOptions) -> Result<PackReport, PackError> {
+    let manifest_bytes = std::fs::read(dir.join(MANIFEST_FILE)).map_err(|e| PackError::Manifest(e.to_string()))?;
+    let manifest_value = parse_strict_slice(&manifest_bytes).map_err(|e| PackError::Manifest(e.to_string()))?;
+    let manifest: Manifest = serde_json::from_value(manifest_value).map_err(|e| PackError::Manifest(e.to_string()))?;
+
+    let mut report = PackReport {
+        pack_id: manifest.pack_id.clone(),
+        manifest_sha256: sha256_hex(&manifest_bytes),
+        checks: Vec::new(),
+    };
+    match manifest.schema_version.as_deref() {
+        Some(MANIFEST_SCHEMA_VERSION) => report.push("manifest", CheckStatus::Pass, MANIFEST_SCHEMA_VERSION),
+        Some(other) => report.push("manifest", CheckStatus::Warn, format!("unknown schema version {:?}", other)),
+        None => report.push("manifest", CheckStatus::Warn, "no schema version"),
+    }
+
+    let missing: Vec<&str> = REQUIRED_FILES.iter().copied().filter(|f| !dir.join(f).is_file()).collect();
+    if missing.is_empty() {
+        report.push("required files", CheckStatus::Pass, format!("{} present", REQUIRED_FILES.len()));
+    } else {
+        report.push("required files", CheckStatus::Fail, format!("missing {}", missing.join(", ")));
+    }
+
+    let mut listed = Vec::new();
+    for file in &manifest.files {
+        let (path, expected) = match file {
+            ManifestFile::Hashed { path, sha256 } => (path, sha256.as_deref()),
+            ManifestFile::Path(path) => (path, None),
+        };
+        listed.push(path.clone());
+        check_artifact(dir, path, expected, options, &mut report);
+    }
+    let unlisted: Vec<String> = pack_files(dir)
+        .into_iter()
+        .filter(|f| f != MANIFEST_FILE && !listed.contains(f))
+        .collect();
+    if !unlisted.is_empty() {
+        report.push("unlisted files", CheckStatus::Warn, unlisted.join(", "));
+    }
+
+    check_producer(dir, &mut report);
+    let head = check_audit_chain(dir, &mut report);
+    check_audit_digest(dir, head.as_ref(), &mut report);
+    check_gate_report(dir, head.as_ref(), &mut report);
+    Ok(report)
+}
+
+/// A manifest path that stays inside the pack
+fn pack_path(dir: &Path, path: &str) -> Option<PathBuf> {
+    let relative = Path::new(path);
+    relative
+        .components()
+        .all(|c| matches!(c, Component::Normal(_)))
+        .then(|| dir.join(relative))
+}
+
+fn check_artifact(dir: &Path, path: &str, expected: Option<&str>, options: &VerifyOptions, report: &mut PackReport) {
+    let name = format!("artifact {}", path);
+    let Some(full) = pack_path(dir, path) else {
+        return report.push(name, CheckStatus::Fail, "path leaves the pack folder");
+    };
+    let bytes = match std::fs::read(&full) {
+        Ok(bytes) => bytes,
+        Err(e) => return report.push(name, CheckStatus::Fail, format!("cannot read: {}", e)),
+    };
+    let Some(expected) = expected else {
+        return if options.strict {
+            report.push(name, CheckStatus::Fail, "listed without a hash; contents cannot be verified")
+        } else {
+            report.push(name, CheckStatus::Warn, "listed without a hash; presence checked only")
+        };
+    };
+    let expected = expected.trim().to_ascii_lowercase();
+
+    if sha256_hex(&bytes) == expected {
+        return report.push(name, CheckStatus::Pass, "sha256 of file bytes matches");
+    }
+    // JSON artifacts may be recorded by their canonical hash, so
+    // re-indenting the file does not count as a change
+    if path.ends_with(".json") {
+        if let Ok(value) = parse_strict_slice(&bytes) {
+            if verify_canonical_sha256(&value, &expected).is_ok() {
+                return report.push(name, CheckStatus::Pass, "canonical sha256 matches");
+            }
+        }
+    }
+    report.push(name, CheckStatus::Fail, format!("sha256 does not match manifest ({})", expected));
+}
+
+/// Relative paths of every file under `dir`, with `/` separators
+fn pack_files(dir: &Path) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut pending = vec![PathBuf::new()];
+    while let Some(relative) = pending.pop() {
+        let Ok(entries) = std::fs::read_dir(dir.join(&relative)) else { continue };
+        for entry in entries.flatten() {
+            let child = relative.join(entry.file_name());
+            match entry.file_type() {
+                Ok(t) if t.is_dir() => pending.push(child),
+                Ok(_) => out.push(child.to_string_lossy().replace('\\', "/")),
+                Err(_) => {}
+            }
+        }
+    }
+    out.sort();
+    out
+}
+
+/// The chain hash of an event as its producer computed it
+fn event_chain_hash(event: &AuditEvent) -> Result<String, serde_json::Error> {
+    let preimage = format!(
+        "{{\"seq\":{},\"timestamp\":{},\"action\":{},\"details\":{},\"prev_hash\":{}}}",
+        event.seq,
+        serde_json::to_string(&event.timestamp)?,
+        serde_json::to_string(&event.action)?,
+        event.details.get(),
+        serde_json::to_string(&event.prev_hash)?,
+    );
+    Ok(sha256_hex(preimage.as_bytes()))
+}
+
+/// End of a verified chain
+struct ChainHead {
+    event_count: u64,
+    chain_hash: String,
+}
+
+fn check_audit_chain(dir: &Path, report: &mut PackReport) -> Option<ChainHead> {
+    let name = "audit chain";
+    let log = match std::fs::read_to_string(dir.join(AUDIT_LOG_FILE)) {
+        Ok(log) => log,
+        Err(e) => {
+            report.push(name, CheckStatus::Fail, format!("cannot read {}: {}", AUDIT_LOG_FILE, e));
+            return None;
+        }
+    };
+
+    let mut prev = HASH_SENTINEL.to_string();
+    let mut count = 0u64;
+    for (i, line) in log.lines().enumerate().filter(|(_, l)| !l.trim().is_empty()) {
+        let line_no = i + 1;
+        let fail = |report: &mut PackReport, why: String| {
+            report.push(name, CheckStatus::Fail, format!("line {}: {}", line_no, why));
+        };
+        if let Err(e) = parse_strict_slice(line.as_bytes()) {
+            fail(report, e.to_string());
+            return None;
+        }
+        let event: AuditEvent = match serde_json::from_str(line) {
+            Ok(event) => event,
+            Err(e) => {
+                fail(report, format!("not an audit event: {}", e));
+                return None;
+            }
+        };
+        if event.seq != count {
+            fail(report, format!("seq {} where {} was expected", event.seq, count));
+            return None;
+        }
+        if event.prev_hash != prev {
+            fail(report, "prev_hash does not match the previous event".to_string());
+            return None;
+        }
+        match event_chain_hash(&event) {
+            Ok(hash) if hash == event.chain_hash => {}
+            Ok(_) => {
+                fail(report, "chain_hash does not match the event".to_string());
+                return None;
+            }
+            Err(e) => {
+                fail(report, e.to_string());
+                return None;
+            }
+        }
+        prev = event.chain_hash;
+        count += 1;
+    }
+
+    if count == 0 {
+        report.push(name, CheckStatus::Fail, "no events");
+        return None;
+    }
+    report.push(name, CheckStatus::Pass, format!("{} events, head {}", count, prev));
+    Some(ChainHead { event_count: count, chain_hash: prev })
+}
+
+fn read_json(dir: &Path, file: &str) -> Result<Value, String> {
+    let bytes = std::fs::read(dir.join(file)).map_err(|e| format!("cannot read {}: {}", file, e))?;
+    parse_strict_slice(&bytes).map_err(|e| format!("{}: {}", file, e))
+}
+
+/// Warn when the pack was produced by a release whose gate report layout
+/// or hashing differs from this verifier's
+fn check_producer(dir: &Path, report: &mut PackReport) {
+    let name = "producer version";
+    let meta = match read_json(dir, GATE_META_FILE) {
+        Ok(meta) => meta,
+        Err(e) => return report.push(name, CheckStatus::Warn, e),
+    };
+    let field = |key: &str| meta.get(key).and_then(Value::as_str);
+    let engine = field("engine_version").unwrap_or("unknown");
+
+    let mut skew = Vec::new();
+    for (key, ours) in [("gate_version", GATE_VERSION), ("hash_algorithm", HASH_ALGORITHM)] {
+        match field(key) {
+            Some(theirs) if theirs == ours => {}
+            Some(theirs) => skew.push(format!("{} {} (verifier implements {})", key, theirs, ours)),
+            None => skew.push(format!("no {} recorded", key)),
+        }
+    }
+    if skew.is_empty() {
+        report.push(name, CheckStatus::Pass, format!("engine {}, gate {}", engine, GATE_VERSION));
+    } else {
+        report.push(name, CheckStatus::Warn, format!("engine {}: {}", engine, skew.join("; ")));
+    }
+}
+
+fn check_audit_digest(dir: &Path, head: Option<&ChainHead>, report: &mut PackReport) {
+    let name = "audit digest";
+    let digest = match read_json(dir, AUDIT_DIGEST_FILE) {
+        Ok(digest) => digest,
+        Err(e) => return report.push(name, CheckStatus::Fail, e),
+    };
+    let Some(head) = head else {
+        return report.push(name, CheckStatus::Fail, "audit chain did not verify");
+    };
+    // Some producers nest the summary under "expected_digest"
+    let summary = digest.get("expected_digest").unwrap_or(&digest);
+    let count = summary.get("event_count").and_then(Value::as_u64);
+    let final_hash = summary.get("final_chain_hash").and_then(Value::as_str);
+
+    let mut problems = Vec::new();
+    if count != Some(head.event_count) {
+        problems.push(format!("event_count {:?}, log has {}", count, head.event_count));
+    }
+    if final_hash != Some(head.chain_hash.as_str()) {
+        problems.push("final_chain_hash does not match the log".to_string());
+    }
+    if problems.is_empty() {
+        report.push(name, CheckStatus::Pass, "matches the audit log");
+    } else {
+        report.push(name, CheckStatus::Fail, problems.join("; "));
+    }
+}
+
+fn check_gate_report(dir: &Path, head: Option<&ChainHead>, report: &mut PackReport) {
+    let gate_report = match read_json(dir, GATE_REPORT_FILE) {
+        Ok(gate_report) => gate_report,
+        Err(e) => return report.push("gate report hash", CheckStatus::Fail, e),
+    };
+
+    let embedded = gate_report.pointer("/inputs_digest/canonical_sha256").and_then(Value::as_str);
+    match embedded {
+        None => report.push("gate report hash", CheckStatus::Fail, "no inputs_digest.canonical_sha256"),
+        Some(embedded) => {
+            let mut preimage = gate_report.clone();
+            preimage["inputs_digest"]["canonical_sha256"] = Value::String(HASH_SENTINEL.to_string());
+            match verify_canonical_sha256(&preimage, embedded) {
+                Ok(()) => report.push("gate report hash", CheckStatus::Pass, embedded),
+                Err(e) => report.push(
+                    "gate report hash",
+                    CheckStatus::Fail,
+                    format!("{} (recomputed {})", e, canonical_sha256(&preimage)),
+                ),
+            }
+        }
+    }
+
+    if let Some(recorded) = gate_report.pointer("/inputs_digest/audit_head_sha256").and_then(Value::as_str) {
+        match head {
+            Some(head) if head.chain_hash == recorded => {
+                report.push("gate report audit head", CheckStatus::Pass, "matches the audit log")
+            }
+            Some(_) => report.push("gate report audit head", CheckStatus::Fail, "does not match the audit log"),
+            None => report.push("gate report audit head", CheckStatus::Fail, "audit chain did not verify"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn write(dir: &Path, path: &str, contents: &str) {
+        let full = dir.join(path);
+        std::fs::create_dir_all(full.parent().unwrap()).unwrap();
+        std::fs::write(full, contents).unwrap();
+    }
+
+    /// A pack shaped like the headless CLI's output, with hashed manifest entries
+    fn build_pack(dir: &Path) {
+        let mut prev = HASH_SENTINEL.to_string();
+        let mut log = String::new();
+        for (seq, action) in ["CASE_CREATED", "EVIDENCE_INGESTED"].iter().enumerate() {
+            let details = r#"{"z":1,"a":"b"}"#;
+            let preimage = format!(
+                r#"{{"seq":{},"timestamp":"2026-01-01T00:00:00.000Z","action":"{}","details":{},"prev_hash":"{}"}}"#,
+                seq, action, details, prev
+            );
+            let hash = sha256_hex(preimage.as_bytes());
+            log.push_str(&format!("{},\"chain_hash\":\"{}\"}}\n", &preimage[..preimage.len() - 1], hash));
+            prev = hash;
+        }
+        write(dir, AUDIT_LOG_FILE, &log);
+        write(dir, AUDIT_DIGEST_FILE, &json!({"expected_digest": {"event_count": 2, "final_chain_hash": prev}}).to_string());
+
+        let mut gate = json!({"summary": {"status": "PASS"},
+            "inputs_digest": {"canonical_sha256": HASH_SENTINEL, "audit_head_sha256": prev}});
+        gate["inputs_digest"]["canonical_sha256"] = json!(canonical_sha256(&gate));
+        write(dir, GATE_REPORT_FILE, &serde_json::to_string_pretty(&gate).unwrap());
+        write(dir, "canonical/canonical.json", r#"{"b": 1, "a": 2}"#);
+        write(dir, GATE_META_FILE, &json!({"engine_version": "1.1.0", "gate_version": GATE_VERSION,
+            "hash_algorithm": HASH_ALGORITHM}).to_string());
+
+        let files: Vec<Value> = REQUIRED_FILES
+            .iter()
+            .map(|f| json!({"path": f, "sha256": sha256_hex(&std::fs::read(dir.join(f)).unwrap())}))
+            .collect();
+        let manifest = json!({"schema_version": MANIFEST_SCHEMA_VERSION, "pack_id": "CC-001", "files": files});
+        write(dir, MANIFEST_FILE, &manifest.to_string());
+    }
+
+    #[test]
+    fn test_verify_pack() {
+        let dir = std::env::temp_dir().join(format!("evidify-verify-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        build_pack(&dir);
+
+        let report = verify_pack(&dir).unwrap();
+        assert_eq!(report.status(), CheckStatus::Pass, "{:#?}", report);
+        assert_eq!(report.pack_id.as_deref(), Some("CC-001"));
+        assert_eq!(report.manifest_sha256, sha256_hex(&std::fs::read(dir.join(MANIFEST_FILE)).unwrap()));
+
+        // A pack from a release with another hashing scheme is checked, with a warning
+        let skewed = std::env::temp_dir().join(format!("evidify-verify-skew-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&skewed);
+        build_pack(&skewed);
+        write(&skewed, GATE_META_FILE, &json!({"engine_version": "2.0.0", "gate_version": "2.0",
+            "hash_algorithm": HASH_ALGORITHM}).to_string());
+        let report = verify_pack_with(&skewed, &VerifyOptions { strict: false }).unwrap();
+        let producer = report.checks.iter().find(|c| c.name == "producer version").unwrap();
+        assert_eq!(producer.status, CheckStatus::Warn);
+        assert!(producer.detail.contains("gate_version 2.0"), "{}", producer.detail);
+        std::fs::remove_dir_all(&skewed).unwrap();
+
+        // Re-indenting a JSON artifact still matches by canonical hash
+        let mut manifest: Value = serde_json::from_slice(&std::fs::read(dir.join(MANIFEST_FILE)).unwrap()).unwrap();
+        manifest["files"][0]["sha256"] = json!(canonical_sha256(&json!({"a": 2, "b": 1})));
+        write(&dir, MANIFEST_FILE, &manifest.to_string());
+        assert!(verify_pack(&dir).unwrap().passed());
+
+        // Editing an event breaks the chain and everything anchored to it
+        let log = std::fs::read_to_string(dir.join(AUDIT_LOG_FILE)).unwrap();
+        write(&dir, AUDIT_LOG_FILE, &log.replacen("EVIDENCE_INGESTED", "EVIDENCE_REMOVED", 1));
+        let report = verify_pack(&dir).unwrap();
+        let failed: Vec<&str> =
+            report.checks.iter().filter(|c| c.status == CheckStatus::Fail).map(|c| c.name.as_str()).collect();
+        assert_eq!(
+            failed,
+            ["artifact audit/audit.log", "audit chain", "audit digest", "gate report audit head"]
+        );
+
+        manifest["files"] = json!(["../outside.json"]);
+        write(&dir, MANIFEST_FILE, &manifest.to_string());
+        let report = verify_pack(&dir).unwrap();
+        assert!(report.checks.iter().any(|c| c.detail == "path leaves the pack folder"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(matches!(verify_pack(&dir), Err(PackError::Manifest(_))));
+    }
+
+    #[test]
+    fn test_unhashed_manifest_fails_strict() {
+        let dir = std::env::temp_dir().join(format!("evidify-verify-strict-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        build_pack(&dir);
+        // Manifest as evidify-cli.cjs wrote it before recording hashes
+        let manifest = json!({"pack_id": "CC-001", "scenario": "PASS",
+            "created_at": "2026-01-01T00:00:00.000Z", "files": REQUIRED_FILES});
+        write(&dir, MANIFEST_FILE, &serde_json::to_string_pretty(&manifest).unwrap());
+
+        let lenient = verify_pack_with(&dir, &VerifyOptions { strict: false }).unwrap();
+        assert_eq!(lenient.status(), CheckStatus::Warn, "{:#?}", lenient);
+
+        // Strict is the default
+        let strict = verify_pack(&dir).unwrap();
+        let failed: Vec<&str> =
+            strict.checks.iter().filter(|c| c.status == CheckStatus::Fail).map(|c| c.name.as_str()).collect();
+        assert_eq!(failed.len(), REQUIRED_FILES.len());
+        assert!(failed.contains(&"artifact canonical/canonical.json"));
+        assert!(!strict.passed());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
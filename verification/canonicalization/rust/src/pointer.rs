@@ -0,0 +1,108 @@
+//! Hashing a subtree addressed by a JSON Pointer (RFC 6901).
+//!
+//! Gates often care about one part of a document, such as
+//! `/report/sections/3/claims`. `serde_json::Value::pointer` only says
+//! whether the path resolved; these functions say which step failed and
+//! why, so a gate can report a missing section rather than a bare "None".
+
+use serde_json::Value;
+use std::fmt;
+
+use crate::{canonical_digest, Algorithm};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PointerError {
+    /// The pointer is neither empty nor starts with `/`, or has a `~` not
+    /// followed by `0` or `1`.
+    Syntax(String),
+    /// An object has no member with this key. `path` is the pointer up to
+    /// and including the failing token.
+    MissingKey { path: String },
+    /// An array token is not a decimal index (`-` and leading zeros
+    /// included).
+    InvalidIndex { path: String },
+    /// An array index is past the end.
+    IndexOutOfRange { path: String, len: usize },
+    /// The pointer continues below a string, number, boolean or null.
+    NotAContainer { path: String },
+}
+
+impl fmt::Display for PointerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PointerError::Syntax(pointer) => write!(f, "not a JSON Pointer: {:?}", pointer),
+            PointerError::MissingKey { path } => write!(f, "no member at {}", path),
+            PointerError::InvalidIndex { path } => write!(f, "not an array index at {}", path),
+            PointerError::IndexOutOfRange { path, len } => {
+                write!(f, "index out of range at {} (array has {} elements)", path, len)
+            }
+            PointerError::NotAContainer { path } => write!(f, "cannot descend into a scalar at {}", path),
+        }
+    }
+}
+
+impl std::error::Error for PointerError {}
+
+pub(crate) fn unescape(token: &str, pointer: &str) -> Result<String, PointerError> {
+    let mut out = String::with_capacity(token.len());
+    let mut chars = token.chars();
+    while let Some(c) = chars.next() {
+        if c != '~' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('0') => out.push('~'),
+            Some('1') => out.push('/'),
+            _ => return Err(PointerError::Syntax(pointer.to_string())),
+        }
+    }
+    Ok(out)
+}
+
+pub(crate) fn parse_index(token: &str) -> Option<usize> {
+    let canonical = token == "0" || (!token.starts_with('0') && !token.is_empty());
+    if canonical && token.bytes().all(|b| b.is_ascii_digit()) {
+        token.parse().ok()
+    } else {
+        None
+    }
+}
+
+/// Resolve `pointer` in `value`. The empty pointer is the whole document.
+pub fn resolve_pointer<'a>(value: &'a Value, pointer: &str) -> Result<&'a Value, PointerError> {
+    if pointer.is_empty() {
+        return Ok(value);
+    }
+    let Some(rest) = pointer.strip_prefix('/') else {
+        return Err(PointerError::Syntax(pointer.to_string()));
+    };
+
+    let mut current = value;
+    let mut consumed = 0;
+    for raw in rest.split('/') {
+        consumed += 1 + raw.len();
+        let path = || pointer[..consumed].to_string();
+        let token = unescape(raw, pointer)?;
+        current = match current {
+            Value::Object(map) => map.get(&token).ok_or_else(|| PointerError::MissingKey { path: path() })?,
+            Value::Array(arr) => {
+                let index = parse_index(&token).ok_or_else(|| PointerError::InvalidIndex { path: path() })?;
+                arr.get(index)
+                    .ok_or_else(|| PointerError::IndexOutOfRange { path: path(), len: arr.len() })?
+            }
+            _ => return Err(PointerError::NotAContainer { path: path() }),
+        };
+    }
+    Ok(current)
+}
+
+/// Canonical SHA-256 of the subtree at `pointer`.
+pub fn canonical_sha256_at(value: &Value, pointer: &str) -> Result<String, PointerError> {
+    canonical_digest_at(value, pointer, Algorithm::Sha256)
+}
+
+/// [`canonical_sha256_at`] under `algorithm`.
+pub fn canonical_digest_at(value: &Value, pointer: &str, algorithm: Algorithm) -> Result<String, PointerError> {
+    resolve_pointer(value, pointer).map(|subtree| canonical_digest(subtree, algorithm))
+}
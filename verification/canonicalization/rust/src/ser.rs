@@ -0,0 +1,456 @@
+//! Canonical serialization of typed values.
+//!
+//! Hashing a finding or report struct used to mean
+//! `canonical_sha256(&serde_json::to_value(&finding)?)`: a full `Value`
+//! tree built only to be walked once and dropped. [`Canonicalize`] is
+//! implemented for every `Serialize` type and writes canonical bytes
+//! straight from the struct. Objects are still buffered one at a time so
+//! their keys can be sorted; nothing else is.
+//!
+//! The bytes are the same as going through `to_value`: map keys are
+//! converted the way serde_json converts them, duplicate keys (from
+//! `#[serde(flatten)]`) keep the last value. A non-finite float, which
+//! `to_value` turns into `null`, is an error unless the
+//! [`FloatPolicy`](crate::FloatPolicy) is `Allow`; then it is written as
+//! `null` too.
+
+use serde::ser::{self, Serialize, SerializeMap as _, Serializer as _};
+use serde_json::Value;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+
+use crate::{digest_hex, write_value, Algorithm, CanonicalizeError, CanonicalizeOptions, FloatPolicy, HashingWriter};
+
+/// Canonical bytes and digests of any `Serialize` value, without going
+/// through `serde_json::Value`.
+pub trait Canonicalize {
+    /// Canonical bytes (minified, sorted keys).
+    fn canonical_bytes(&self) -> Result<Vec<u8>, CanonicalizeError>;
+
+    /// [`canonical_bytes`](Self::canonical_bytes) under `options`.
+    fn canonical_bytes_with_options(&self, options: &CanonicalizeOptions) -> Result<Vec<u8>, CanonicalizeError>;
+
+    /// Stream the canonical form into `writer` under `options`.
+    fn write_canonical<W: Write>(&self, writer: W, options: &CanonicalizeOptions) -> Result<(), CanonicalizeError>;
+
+    /// Canonical digest under `algorithm`, as lowercase hex.
+    fn canonical_digest(&self, algorithm: Algorithm) -> Result<String, CanonicalizeError>;
+
+    /// Canonical SHA-256, as lowercase hex.
+    fn canonical_sha256(&self) -> Result<String, CanonicalizeError> {
+        self.canonical_digest(Algorithm::Sha256)
+    }
+}
+
+impl<T: Serialize + ?Sized> Canonicalize for T {
+    fn canonical_bytes(&self) -> Result<Vec<u8>, CanonicalizeError> {
+        self.canonical_bytes_with_options(&CanonicalizeOptions::default())
+    }
+
+    fn canonical_bytes_with_options(&self, options: &CanonicalizeOptions) -> Result<Vec<u8>, CanonicalizeError> {
+        let mut out = Vec::new();
+        self.write_canonical(&mut out, options)?;
+        Ok(out)
+    }
+
+    fn write_canonical<W: Write>(&self, writer: W, options: &CanonicalizeOptions) -> Result<(), CanonicalizeError> {
+        self.serialize(&mut CanonicalWriter { out: writer, options })
+    }
+
+    fn canonical_digest(&self, algorithm: Algorithm) -> Result<String, CanonicalizeError> {
+        let mut tee = HashingWriter { inner: io::sink(), hasher: algorithm.hasher() };
+        self.write_canonical(&mut tee, &CanonicalizeOptions::default())?;
+        Ok(digest_hex(&tee.hasher.finalize()))
+    }
+}
+
+impl ser::Error for CanonicalizeError {
+    fn custom<M: std::fmt::Display>(msg: M) -> Self {
+        CanonicalizeError::Serialize(msg.to_string())
+    }
+}
+
+fn json_error(e: serde_json::Error) -> CanonicalizeError {
+    CanonicalizeError::Serialize(e.to_string())
+}
+
+/// A map key as serde_json would write it: strings as they are, numbers
+/// and booleans as their text, anything else refused.
+fn key_string<K: Serialize + ?Sized>(key: &K) -> Result<String, CanonicalizeError> {
+    let mut map = serde_json::value::Serializer.serialize_map(Some(1)).map_err(json_error)?;
+    map.serialize_entry(key, &()).map_err(json_error)?;
+    match map.end().map_err(json_error)? {
+        Value::Object(entries) => Ok(entries.into_iter().next().map(|(k, _)| k).unwrap_or_default()),
+        _ => unreachable!("a map serializes to an object"),
+    }
+}
+
+struct CanonicalWriter<'o, W> {
+    out: W,
+    options: &'o CanonicalizeOptions,
+}
+
+impl<W: Write> CanonicalWriter<'_, W> {
+    fn raw(&mut self, bytes: &[u8]) -> Result<(), CanonicalizeError> {
+        Ok(self.out.write_all(bytes)?)
+    }
+
+    fn string(&mut self, s: &str) -> Result<(), CanonicalizeError> {
+        serde_json::to_writer(&mut self.out, &*self.options.string(s)).map_err(io::Error::from)?;
+        Ok(())
+    }
+
+    /// Numbers, booleans and byte strings, written from the `Value`
+    /// serde_json would have made of them
+    fn leaf(&mut self, v: Result<Value, serde_json::Error>) -> Result<(), CanonicalizeError> {
+        Ok(write_value(&v.map_err(json_error)?, &mut self.out, self.options)?)
+    }
+
+    fn float(&mut self, f: f64, v: Value) -> Result<(), CanonicalizeError> {
+        if !f.is_finite() && self.options.float_policy != FloatPolicy::Allow {
+            return Err(CanonicalizeError::NonFiniteNumber(f.to_string()));
+        }
+        self.leaf(Ok(v))
+    }
+
+    /// Canonical bytes of one object member's value
+    fn member<T: Serialize + ?Sized>(&self, value: &T) -> Result<Vec<u8>, CanonicalizeError> {
+        let mut child = CanonicalWriter { out: Vec::new(), options: self.options };
+        value.serialize(&mut child)?;
+        Ok(child.out)
+    }
+}
+
+impl<'a, 'o, W: Write> ser::Serializer for &'a mut CanonicalWriter<'o, W> {
+    type Ok = ();
+    type Error = CanonicalizeError;
+    type SerializeSeq = Seq<'a, 'o, W>;
+    type SerializeTuple = Seq<'a, 'o, W>;
+    type SerializeTupleStruct = Seq<'a, 'o, W>;
+    type SerializeTupleVariant = Seq<'a, 'o, W>;
+    type SerializeMap = Object<'a, 'o, W>;
+    type SerializeStruct = Object<'a, 'o, W>;
+    type SerializeStructVariant = Object<'a, 'o, W>;
+
+    fn serialize_bool(self, v: bool) -> Result<(), CanonicalizeError> {
+        self.raw(if v { b"true" } else { b"false" })
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<(), CanonicalizeError> {
+        self.leaf(Ok(Value::from(v)))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<(), CanonicalizeError> {
+        self.leaf(Ok(Value::from(v)))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<(), CanonicalizeError> {
+        self.leaf(Ok(Value::from(v)))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<(), CanonicalizeError> {
+        self.leaf(Ok(Value::from(v)))
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<(), CanonicalizeError> {
+        self.leaf(serde_json::value::Serializer.serialize_i128(v))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<(), CanonicalizeError> {
+        self.leaf(Ok(Value::from(v)))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<(), CanonicalizeError> {
+        self.leaf(Ok(Value::from(v)))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<(), CanonicalizeError> {
+        self.leaf(Ok(Value::from(v)))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<(), CanonicalizeError> {
+        self.leaf(Ok(Value::from(v)))
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<(), CanonicalizeError> {
+        self.leaf(serde_json::value::Serializer.serialize_u128(v))
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<(), CanonicalizeError> {
+        self.float(v as f64, Value::from(v))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<(), CanonicalizeError> {
+        self.float(v, Value::from(v))
+    }
+
+    fn serialize_char(self, v: char) -> Result<(), CanonicalizeError> {
+        self.string(v.encode_utf8(&mut [0; 4]))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<(), CanonicalizeError> {
+        self.string(v)
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<(), CanonicalizeError> {
+        self.leaf(serde_json::value::Serializer.serialize_bytes(v))
+    }
+
+    fn serialize_none(self) -> Result<(), CanonicalizeError> {
+        self.raw(b"null")
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<(), CanonicalizeError> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), CanonicalizeError> {
+        self.raw(b"null")
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<(), CanonicalizeError> {
+        self.raw(b"null")
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+    ) -> Result<(), CanonicalizeError> {
+        self.string(variant)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<(), CanonicalizeError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<(), CanonicalizeError> {
+        self.raw(b"{")?;
+        self.string(variant)?;
+        self.raw(b":")?;
+        value.serialize(&mut *self)?;
+        self.raw(b"}")
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Seq<'a, 'o, W>, CanonicalizeError> {
+        self.raw(b"[")?;
+        Ok(Seq { writer: self, first: true, close: b"]" })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Seq<'a, 'o, W>, CanonicalizeError> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, len: usize) -> Result<Seq<'a, 'o, W>, CanonicalizeError> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Seq<'a, 'o, W>, CanonicalizeError> {
+        self.raw(b"{")?;
+        self.string(variant)?;
+        self.raw(b":[")?;
+        Ok(Seq { writer: self, first: true, close: b"]}" })
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Object<'a, 'o, W>, CanonicalizeError> {
+        Ok(Object { writer: self, members: BTreeMap::new(), key: None, close: b"" })
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<Object<'a, 'o, W>, CanonicalizeError> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Object<'a, 'o, W>, CanonicalizeError> {
+        self.raw(b"{")?;
+        self.string(variant)?;
+        self.raw(b":")?;
+        Ok(Object { writer: self, members: BTreeMap::new(), key: None, close: b"}" })
+    }
+}
+
+/// Arrays, tuples and tuple variants: written as they arrive
+struct Seq<'a, 'o, W> {
+    writer: &'a mut CanonicalWriter<'o, W>,
+    first: bool,
+    close: &'static [u8],
+}
+
+impl<W: Write> Seq<'_, '_, W> {
+    fn element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), CanonicalizeError> {
+        if !self.first {
+            self.writer.raw(b",")?;
+        }
+        self.first = false;
+        value.serialize(&mut *self.writer)
+    }
+
+    fn finish(self) -> Result<(), CanonicalizeError> {
+        self.writer.raw(self.close)
+    }
+}
+
+impl<W: Write> ser::SerializeSeq for Seq<'_, '_, W> {
+    type Ok = ();
+    type Error = CanonicalizeError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), CanonicalizeError> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), CanonicalizeError> {
+        self.finish()
+    }
+}
+
+impl<W: Write> ser::SerializeTuple for Seq<'_, '_, W> {
+    type Ok = ();
+    type Error = CanonicalizeError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), CanonicalizeError> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), CanonicalizeError> {
+        self.finish()
+    }
+}
+
+impl<W: Write> ser::SerializeTupleStruct for Seq<'_, '_, W> {
+    type Ok = ();
+    type Error = CanonicalizeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), CanonicalizeError> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), CanonicalizeError> {
+        self.finish()
+    }
+}
+
+impl<W: Write> ser::SerializeTupleVariant for Seq<'_, '_, W> {
+    type Ok = ();
+    type Error = CanonicalizeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), CanonicalizeError> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), CanonicalizeError> {
+        self.finish()
+    }
+}
+
+/// Maps, structs and struct variants: members are buffered, then written
+/// in key order when the object ends
+struct Object<'a, 'o, W> {
+    writer: &'a mut CanonicalWriter<'o, W>,
+    members: BTreeMap<String, Vec<u8>>,
+    key: Option<String>,
+    close: &'static [u8],
+}
+
+impl<W: Write> Object<'_, '_, W> {
+    fn member<T: Serialize + ?Sized>(&mut self, key: String, value: &T) -> Result<(), CanonicalizeError> {
+        let bytes = self.writer.member(value)?;
+        self.members.insert(key, bytes);
+        Ok(())
+    }
+
+    fn finish(self) -> Result<(), CanonicalizeError> {
+        let Object { writer, members, close, .. } = self;
+        let options = writer.options;
+        let mut entries: Vec<(Cow<str>, &Vec<u8>)> =
+            members.iter().map(|(k, v)| (options.string(k), v)).collect();
+        if !options.writes_keys_verbatim() {
+            entries.sort_by(|a, b| options.key_order.compare(&a.0, &b.0));
+        }
+        if options.nfc_normalize_strings {
+            if let Some(pair) = entries.windows(2).find(|pair| pair[0].0 == pair[1].0) {
+                return Err(CanonicalizeError::NormalizedKeyCollision(pair[0].0.to_string()));
+            }
+        }
+
+        writer.raw(b"{")?;
+        for (i, (k, v)) in entries.into_iter().enumerate() {
+            if i > 0 {
+                writer.raw(b",")?;
+            }
+            serde_json::to_writer(&mut writer.out, &*k).map_err(io::Error::from)?;
+            writer.raw(b":")?;
+            writer.raw(v)?;
+        }
+        writer.raw(b"}")?;
+        writer.raw(close)
+    }
+}
+
+impl<W: Write> ser::SerializeMap for Object<'_, '_, W> {
+    type Ok = ();
+    type Error = CanonicalizeError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), CanonicalizeError> {
+        self.key = Some(key_string(key)?);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), CanonicalizeError> {
+        let key = self.key.take()
+            .ok_or_else(|| CanonicalizeError::Serialize("map value without a key".to_string()))?;
+        self.member(key, value)
+    }
+
+    fn end(self) -> Result<(), CanonicalizeError> {
+        self.finish()
+    }
+}
+
+impl<W: Write> ser::SerializeStruct for Object<'_, '_, W> {
+    type Ok = ();
+    type Error = CanonicalizeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result<(), CanonicalizeError> {
+        self.member(key.to_string(), value)
+    }
+
+    fn end(self) -> Result<(), CanonicalizeError> {
+        self.finish()
+    }
+}
+
+impl<W: Write> ser::SerializeStructVariant for Object<'_, '_, W> {
+    type Ok = ();
+    type Error = CanonicalizeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result<(), CanonicalizeError> {
+        self.member(key.to_string(), value)
+    }
+
+    fn end(self) -> Result<(), CanonicalizeError> {
+        self.finish()
+    }
+}
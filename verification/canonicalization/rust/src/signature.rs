@@ -0,0 +1,175 @@
+//! Detached Ed25519 signatures over canonical hashes.
+//!
+//! A hash shows a document hasn't changed; it doesn't show who vouched
+//! for it. A [`SignatureEnvelope`] travels next to an exported document
+//! and binds its `canonical_sha256` to a signing key and a time. What is
+//! signed is the canonical JSON of the envelope without its signature, so
+//! the key id, timestamp and algorithm names can't be swapped after the
+//! fact either. Anyone holding the public key can check it with
+//! [`verify_envelope`]; nothing else from the producer is needed.
+
+use ed25519_dalek::{Signature, Signer, Verifier};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+
+pub use ed25519_dalek::{SigningKey, VerifyingKey};
+
+use crate::{canonical_sha256, sha256_hex, try_canonical_bytes, verify_canonical_sha256};
+
+pub const ENVELOPE_VERSION: u32 = 1;
+pub const SIGNATURE_ALGORITHM: &str = "Ed25519";
+pub const HASH_ALGORITHM: &str = "sha256";
+
+/// Detached signature over a document's canonical SHA-256.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignatureEnvelope {
+    pub version: u32,
+    pub algorithm: String,
+    pub hash_algorithm: String,
+    /// [`key_id`] of the signing key
+    pub key_id: String,
+    /// `canonical_sha256` of the signed document
+    pub payload_sha256: String,
+    /// RFC 3339 time of signing, as supplied by the signer
+    pub signed_at: String,
+    /// Lowercase hex of the 64-byte Ed25519 signature
+    pub signature: String,
+}
+
+/// Why [`verify_envelope`] rejected a document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EnvelopeError {
+    UnsupportedVersion(u32),
+    UnsupportedAlgorithm(String),
+    /// The envelope names a different key than the one supplied
+    KeyMismatch { expected: String, found: String },
+    /// The document's canonical hash is not the one that was signed
+    PayloadMismatch { expected: String, found: String },
+    MalformedSignature,
+    InvalidSignature,
+}
+
+impl fmt::Display for EnvelopeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EnvelopeError::UnsupportedVersion(v) => write!(f, "unsupported envelope version {}", v),
+            EnvelopeError::UnsupportedAlgorithm(a) => write!(f, "unsupported algorithm {:?}", a),
+            EnvelopeError::KeyMismatch { expected, found } => {
+                write!(f, "envelope was signed by key {} but key {} was supplied", found, expected)
+            }
+            EnvelopeError::PayloadMismatch { expected, found } => {
+                write!(f, "document hash {} does not match signed hash {}", found, expected)
+            }
+            EnvelopeError::MalformedSignature => write!(f, "signature is not 64 bytes of hex"),
+            EnvelopeError::InvalidSignature => write!(f, "signature does not verify"),
+        }
+    }
+}
+
+impl std::error::Error for EnvelopeError {}
+
+/// Stable identifier for a public key: the first 16 bytes of the SHA-256
+/// of its 32 raw bytes, as hex.
+pub fn key_id(key: &VerifyingKey) -> String {
+    sha256_hex(key.as_bytes())[..32].to_string()
+}
+
+/// Bytes actually signed: the canonical JSON of every field but `signature`.
+fn signing_input(envelope: &SignatureEnvelope) -> Vec<u8> {
+    let mut fields = serde_json::to_value(envelope).expect("envelope serializes");
+    if let Value::Object(ref mut map) = fields {
+        map.remove("signature");
+    }
+    try_canonical_bytes(&fields).expect("envelope fields canonicalize")
+}
+
+/// Sign `document`'s canonical SHA-256. `signed_at` is recorded as given
+/// (RFC 3339 expected); this crate has no clock of its own.
+pub fn sign_envelope(document: &Value, key: &SigningKey, signed_at: &str) -> SignatureEnvelope {
+    let mut envelope = SignatureEnvelope {
+        version: ENVELOPE_VERSION,
+        algorithm: SIGNATURE_ALGORITHM.to_string(),
+        hash_algorithm: HASH_ALGORITHM.to_string(),
+        key_id: key_id(&key.verifying_key()),
+        payload_sha256: canonical_sha256(document),
+        signed_at: signed_at.to_string(),
+        signature: String::new(),
+    };
+    envelope.signature = hex::encode(key.sign(&signing_input(&envelope)).to_bytes());
+    envelope
+}
+
+/// Check that `envelope` is a valid signature by `key` over `document`.
+pub fn verify_envelope(
+    document: &Value,
+    envelope: &SignatureEnvelope,
+    key: &VerifyingKey,
+) -> Result<(), EnvelopeError> {
+    if envelope.version != ENVELOPE_VERSION {
+        return Err(EnvelopeError::UnsupportedVersion(envelope.version));
+    }
+    if envelope.algorithm != SIGNATURE_ALGORITHM {
+        return Err(EnvelopeError::UnsupportedAlgorithm(envelope.algorithm.clone()));
+    }
+    if envelope.hash_algorithm != HASH_ALGORITHM {
+        return Err(EnvelopeError::UnsupportedAlgorithm(envelope.hash_algorithm.clone()));
+    }
+
+    let expected_key = key_id(key);
+    if envelope.key_id != expected_key {
+        return Err(EnvelopeError::KeyMismatch { expected: expected_key, found: envelope.key_id.clone() });
+    }
+    if verify_canonical_sha256(document, &envelope.payload_sha256).is_err() {
+        return Err(EnvelopeError::PayloadMismatch {
+            expected: envelope.payload_sha256.clone(),
+            found: canonical_sha256(document),
+        });
+    }
+
+    let bytes: [u8; 64] = hex::decode(&envelope.signature)
+        .ok()
+        .and_then(|b| b.try_into().ok())
+        .ok_or(EnvelopeError::MalformedSignature)?;
+    key.verify(&signing_input(envelope), &Signature::from_bytes(&bytes))
+        .map_err(|_| EnvelopeError::InvalidSignature)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn key(seed: u8) -> SigningKey {
+        SigningKey::from_bytes(&[seed; 32])
+    }
+
+    #[test]
+    fn test_envelope_round_trip_and_tampering() {
+        let findings = json!({"findings": [{"id": "F-1", "severity": "BLOCK"}], "pack": "P-1"});
+        let signer = key(7);
+        let public = signer.verifying_key();
+        let envelope = sign_envelope(&findings, &signer, "2026-01-12T09:30:00Z");
+
+        assert_eq!(envelope.payload_sha256, canonical_sha256(&findings));
+        assert_eq!(verify_envelope(&findings, &envelope, &public), Ok(()));
+
+        // Key order in the document doesn't matter; content does
+        let reordered = json!({"pack": "P-1", "findings": [{"severity": "BLOCK", "id": "F-1"}]});
+        assert_eq!(verify_envelope(&reordered, &envelope, &public), Ok(()));
+        let edited = json!({"findings": [{"id": "F-1", "severity": "WARN"}], "pack": "P-1"});
+        assert!(matches!(
+            verify_envelope(&edited, &envelope, &public),
+            Err(EnvelopeError::PayloadMismatch { .. })
+        ));
+
+        let mut backdated = envelope.clone();
+        backdated.signed_at = "2025-01-12T09:30:00Z".to_string();
+        assert_eq!(verify_envelope(&findings, &backdated, &public), Err(EnvelopeError::InvalidSignature));
+
+        assert!(matches!(
+            verify_envelope(&findings, &envelope, &key(8).verifying_key()),
+            Err(EnvelopeError::KeyMismatch { .. })
+        ));
+    }
+}
@@ -0,0 +1,178 @@
+//! Strict JSON parsing for externally supplied documents.
+//!
+//! `serde_json::from_str` keeps the last value when an object repeats a
+//! key, so `{"amount": 1, "amount": 100}` parses and hashes as if the
+//! first entry never existed. A verifier and a producer can then disagree
+//! about what a signed document says. Strict mode rejects such documents
+//! and reports where the duplicate is.
+
+use serde::de::{self, DeserializeSeed, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde_json::{Map, Number, Value};
+use std::cell::RefCell;
+use std::fmt;
+
+/// Why a document was rejected by [`parse_strict`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StrictParseError {
+    /// An object contains `key` more than once. `path` is the JSON Pointer
+    /// (RFC 6901) of that object; `""` is the document root.
+    DuplicateKey { path: String, key: String },
+    /// Not valid JSON.
+    Syntax(String),
+}
+
+impl fmt::Display for StrictParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StrictParseError::DuplicateKey { path, key } => {
+                let at = if path.is_empty() { "/" } else { path.as_str() };
+                write!(f, "duplicate key {:?} in object at {}", key, at)
+            }
+            StrictParseError::Syntax(msg) => write!(f, "invalid JSON: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for StrictParseError {}
+
+/// Parse JSON text, rejecting objects with duplicate keys.
+pub fn parse_strict(input: &str) -> Result<Value, StrictParseError> {
+    parse_strict_slice(input.as_bytes())
+}
+
+/// Parse JSON bytes, rejecting objects with duplicate keys.
+pub fn parse_strict_slice(input: &[u8]) -> Result<Value, StrictParseError> {
+    let duplicate = RefCell::new(None);
+    let mut de = serde_json::Deserializer::from_slice(input);
+
+    let result = StrictValue { path: String::new(), duplicate: &duplicate }
+        .deserialize(&mut de)
+        .and_then(|v| de.end().map(|_| v));
+
+    match (result, duplicate.into_inner()) {
+        (_, Some(err)) => Err(err),
+        (Ok(v), None) => Ok(v),
+        (Err(e), None) => Err(StrictParseError::Syntax(e.to_string())),
+    }
+}
+
+/// Escape a key as a JSON Pointer reference token.
+pub(crate) fn pointer_token(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+/// Seed that builds a `Value` while tracking its JSON Pointer path.
+/// The duplicate is recorded out of band so the caller gets the
+/// structured error rather than serde's stringified one.
+struct StrictValue<'a> {
+    path: String,
+    duplicate: &'a RefCell<Option<StrictParseError>>,
+}
+
+impl<'de, 'a> DeserializeSeed<'de> for StrictValue<'a> {
+    type Value = Value;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Value, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de, 'a> Visitor<'de> for StrictValue<'a> {
+    type Value = Value;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("any JSON value")
+    }
+
+    fn visit_bool<E>(self, v: bool) -> Result<Value, E> {
+        Ok(Value::Bool(v))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Value, E> {
+        Ok(Value::Number(v.into()))
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Value, E> {
+        Ok(Value::Number(v.into()))
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Value, E> {
+        Number::from_f64(v)
+            .map(Value::Number)
+            .ok_or_else(|| E::custom("non-finite number"))
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Value, E> {
+        Ok(Value::String(v.to_owned()))
+    }
+
+    fn visit_string<E>(self, v: String) -> Result<Value, E> {
+        Ok(Value::String(v))
+    }
+
+    fn visit_unit<E>(self) -> Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Value, A::Error> {
+        let mut out = Vec::new();
+        loop {
+            let seed = StrictValue {
+                path: format!("{}/{}", self.path, out.len()),
+                duplicate: self.duplicate,
+            };
+            match seq.next_element_seed(seed)? {
+                Some(v) => out.push(v),
+                None => return Ok(Value::Array(out)),
+            }
+        }
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Value, A::Error> {
+        let mut out = Map::new();
+        while let Some(key) = map.next_key::<String>()? {
+            if out.contains_key(&key) {
+                *self.duplicate.borrow_mut() = Some(StrictParseError::DuplicateKey {
+                    path: self.path.clone(),
+                    key: key.clone(),
+                });
+                return Err(de::Error::custom(format!("duplicate key {:?}", key)));
+            }
+            let seed = StrictValue {
+                path: format!("{}/{}", self.path, pointer_token(&key)),
+                duplicate: self.duplicate,
+            };
+            let value = map.next_value_seed(seed)?;
+            out.insert(key, value);
+        }
+        Ok(Value::Object(out))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_duplicate_key_rejected_with_path() {
+        let err = parse_strict(r#"{"a": {"items": [{"id": 1, "id": 2}]}}"#).unwrap_err();
+        assert_eq!(
+            err,
+            StrictParseError::DuplicateKey { path: "/a/items/0".to_string(), key: "id".to_string() }
+        );
+
+        let err = parse_strict(r#"{"x/y": 1, "x/y": 1}"#).unwrap_err();
+        assert_eq!(err, StrictParseError::DuplicateKey { path: String::new(), key: "x/y".to_string() });
+        assert_eq!(err.to_string(), r#"duplicate key "x/y" in object at /"#);
+    }
+
+    #[test]
+    fn test_strict_matches_serde_json_on_valid_input() {
+        let input = r#"{"b": [1, -2, 3.5, null, true], "a": {"a~b": "x", "c/d": {}}}"#;
+        let expected: Value = serde_json::from_str(input).unwrap();
+        assert_eq!(parse_strict(input).unwrap(), expected);
+
+        assert!(matches!(parse_strict(r#"{"a": 1"#), Err(StrictParseError::Syntax(_))));
+        assert!(matches!(parse_strict(r#"{"a": 1} x"#), Err(StrictParseError::Syntax(_))));
+    }
+}
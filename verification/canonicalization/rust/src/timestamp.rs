@@ -0,0 +1,185 @@
+//! Rewriting RFC 3339 timestamps to one canonical UTC form.
+//!
+//! `2024-01-02T03:04:05Z`, `2024-01-02T03:04:05.000+00:00` and
+//! `2024-01-01T22:04:05-05:00` are the same instant but different strings,
+//! so documents that carry them hash differently. This pass rewrites them
+//! all to `2024-01-02T03:04:05Z`: UTC, `T` and `Z` upper case, and the
+//! fractional seconds kept with trailing zeros dropped (omitted when
+//! zero). Nothing is rounded, so no precision is lost.
+//!
+//! Run it before hashing, over either every string that parses as a
+//! timestamp or only the members named in a [`TimestampScope::Fields`].
+
+use serde_json::Value;
+
+use crate::{canonicalize_json, CanonicalizeError};
+
+/// Which strings [`normalize_timestamps`] rewrites.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TimestampScope {
+    /// Every string value, anywhere, that parses as an RFC 3339 timestamp.
+    /// Other strings are left alone.
+    AllStrings,
+    /// String values of object members with these names. Such a value
+    /// that does not parse is an error; nulls and non-strings are left
+    /// alone.
+    Fields(Vec<String>),
+}
+
+/// `s` in canonical UTC form, or None if it is not an RFC 3339 date-time
+/// (a space is accepted in place of `T`; leap seconds are not).
+pub fn normalize_timestamp(s: &str) -> Option<String> {
+    let b = s.as_bytes();
+    if b.len() < 20 || b[4] != b'-' || b[7] != b'-' || !matches!(b[10], b'T' | b't' | b' ')
+        || b[13] != b':' || b[16] != b':'
+    {
+        return None;
+    }
+    let (year, month, day) = (digits(b, 0, 4)?, digits(b, 5, 2)?, digits(b, 8, 2)?);
+    let (hour, minute, second) = (digits(b, 11, 2)?, digits(b, 14, 2)?, digits(b, 17, 2)?);
+    if !(1..=12).contains(&month) || day == 0 || day > days_in_month(year, month) || hour > 23 || minute > 59 || second > 59 {
+        return None;
+    }
+
+    let mut pos = 19;
+    let mut fraction = "";
+    if b[pos] == b'.' {
+        let len = b[pos + 1..].iter().take_while(|c| c.is_ascii_digit()).count();
+        if len == 0 {
+            return None;
+        }
+        fraction = s[pos + 1..pos + 1 + len].trim_end_matches('0');
+        pos += 1 + len;
+    }
+    let offset_minutes = match b.get(pos..)? {
+        [b'Z' | b'z'] => 0,
+        [sign @ (b'+' | b'-'), _, _, b':', _, _] => {
+            let (h, m) = (digits(b, pos + 1, 2)?, digits(b, pos + 4, 2)?);
+            if h > 23 || m > 59 {
+                return None;
+            }
+            let minutes = (h * 60 + m) as i64;
+            if *sign == b'-' { -minutes } else { minutes }
+        }
+        _ => return None,
+    };
+
+    let local = days_from_civil(year, month, day) * 86_400 + (hour * 3600 + minute * 60 + second) as i64;
+    let utc = local - offset_minutes * 60;
+    let (year, month, day) = civil_from_days(utc.div_euclid(86_400));
+    if !(0..=9999).contains(&year) {
+        return None;
+    }
+    let secs = utc.rem_euclid(86_400);
+    let fraction = if fraction.is_empty() { String::new() } else { format!(".{}", fraction) };
+    Some(format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}{}Z",
+        year, month, day, secs / 3600, secs % 3600 / 60, secs % 60, fraction
+    ))
+}
+
+/// Rewrite timestamps in `value` in place; returns how many changed.
+pub fn normalize_timestamps(value: &mut Value, scope: &TimestampScope) -> Result<usize, CanonicalizeError> {
+    let mut changed = 0;
+    walk(value, scope, false, &mut String::new(), &mut changed)?;
+    Ok(changed)
+}
+
+/// [`canonicalize_json`] after [`normalize_timestamps`].
+pub fn canonicalize_json_with_timestamps(v: &Value, scope: &TimestampScope) -> Result<Value, CanonicalizeError> {
+    let mut normalized = v.clone();
+    normalize_timestamps(&mut normalized, scope)?;
+    Ok(canonicalize_json(&normalized))
+}
+
+/// Canonical SHA-256 after [`normalize_timestamps`].
+pub fn canonical_sha256_with_timestamps(v: &Value, scope: &TimestampScope) -> Result<String, CanonicalizeError> {
+    let mut normalized = v.clone();
+    normalize_timestamps(&mut normalized, scope)?;
+    Ok(crate::canonical_sha256(&normalized))
+}
+
+fn walk(
+    value: &mut Value,
+    scope: &TimestampScope,
+    named: bool,
+    at: &mut String,
+    changed: &mut usize,
+) -> Result<(), CanonicalizeError> {
+    let len = at.len();
+    match value {
+        Value::String(s) => {
+            let required = named && matches!(scope, TimestampScope::Fields(_));
+            if !required && *scope != TimestampScope::AllStrings {
+                return Ok(());
+            }
+            match normalize_timestamp(s) {
+                Some(normalized) => {
+                    if normalized != *s {
+                        *s = normalized;
+                        *changed += 1;
+                    }
+                }
+                None if required => return Err(CanonicalizeError::InvalidTimestamp(at.clone())),
+                None => {}
+            }
+        }
+        Value::Object(map) => {
+            for (key, child) in map.iter_mut() {
+                let named = matches!(scope, TimestampScope::Fields(fields) if fields.iter().any(|f| f == key));
+                at.truncate(len);
+                at.push('/');
+                at.push_str(&key.replace('~', "~0").replace('/', "~1"));
+                walk(child, scope, named, at, changed)?;
+            }
+        }
+        Value::Array(items) => {
+            for (i, child) in items.iter_mut().enumerate() {
+                at.truncate(len);
+                at.push_str(&format!("/{}", i));
+                walk(child, scope, false, at, changed)?;
+            }
+        }
+        _ => {}
+    }
+    at.truncate(len);
+    Ok(())
+}
+
+fn digits(b: &[u8], start: usize, len: usize) -> Option<u32> {
+    let slice = b.get(start..start + len)?;
+    slice.iter().try_fold(0u32, |n, c| c.is_ascii_digit().then(|| n * 10 + (c - b'0') as u32))
+}
+
+fn days_in_month(year: u32, month: u32) -> u32 {
+    match month {
+        2 if year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400)) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Days since 1970-01-01 (proleptic Gregorian; Howard Hinnant's algorithm)
+fn days_from_civil(year: u32, month: u32, day: u32) -> i64 {
+    let y = year as i64 - if month <= 2 { 1 } else { 0 };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let m = month as i64;
+    let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
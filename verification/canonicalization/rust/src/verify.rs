@@ -0,0 +1,87 @@
+//! Checking a document against a recorded canonical digest.
+//!
+//! Recomputing the hash and comparing strings rejects uppercase hex and
+//! stops at the first differing character, which leaks how much of the
+//! digest matched. These helpers validate and decode the expected digest
+//! first, then compare bytes in constant time.
+
+use serde_json::Value;
+use std::fmt;
+use subtle::ConstantTimeEq;
+
+use crate::{canonical_digest_bytes, Algorithm};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VerifyError {
+    /// The expected digest has the wrong number of hex digits for the
+    /// algorithm.
+    InvalidLength { expected: usize, found: usize },
+    /// The expected digest is not hex.
+    InvalidHex,
+    /// The document's canonical digest is different.
+    Mismatch,
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerifyError::InvalidLength { expected, found } => {
+                write!(f, "expected digest has {} hex digits, not {}", found, expected)
+            }
+            VerifyError::InvalidHex => write!(f, "expected digest is not hex"),
+            VerifyError::Mismatch => write!(f, "canonical digest does not match"),
+        }
+    }
+}
+
+impl std::error::Error for VerifyError {}
+
+/// Check `value`'s canonical SHA-256 against `expected_hex` (either case,
+/// surrounding whitespace ignored).
+pub fn verify_canonical_sha256(value: &Value, expected_hex: &str) -> Result<(), VerifyError> {
+    verify_canonical_digest(value, Algorithm::Sha256, expected_hex)
+}
+
+/// [`verify_canonical_sha256`] under `algorithm`.
+pub fn verify_canonical_digest(value: &Value, algorithm: Algorithm, expected_hex: &str) -> Result<(), VerifyError> {
+    let expected_hex = expected_hex.trim();
+    if expected_hex.len() != algorithm.output_len() * 2 {
+        return Err(VerifyError::InvalidLength { expected: algorithm.output_len() * 2, found: expected_hex.len() });
+    }
+    let expected = hex::decode(expected_hex).map_err(|_| VerifyError::InvalidHex)?;
+    let actual = canonical_digest_bytes(value, algorithm);
+    if bool::from(actual.ct_eq(&expected)) {
+        Ok(())
+    } else {
+        Err(VerifyError::Mismatch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::canonical_digest;
+    use serde_json::json;
+
+    #[test]
+    fn test_verify_canonical_digest() {
+        let doc = json!({"b": 2, "a": 1});
+        let hash = canonical_digest(&doc, Algorithm::Sha256);
+
+        assert_eq!(verify_canonical_sha256(&doc, &hash), Ok(()));
+        assert_eq!(verify_canonical_sha256(&doc, &format!(" {}\n", hash.to_uppercase())), Ok(()));
+        assert_eq!(verify_canonical_sha256(&json!({"a": 2}), &hash), Err(VerifyError::Mismatch));
+        assert_eq!(
+            verify_canonical_sha256(&doc, &hash[..63]),
+            Err(VerifyError::InvalidLength { expected: 64, found: 63 })
+        );
+        assert_eq!(verify_canonical_sha256(&doc, &"zz".repeat(32)), Err(VerifyError::InvalidHex));
+
+        let blake3 = canonical_digest(&doc, Algorithm::Blake3);
+        assert_eq!(verify_canonical_digest(&doc, Algorithm::Blake3, &blake3), Ok(()));
+        assert!(matches!(
+            verify_canonical_digest(&doc, Algorithm::Sha512, &hash),
+            Err(VerifyError::InvalidLength { expected: 128, .. })
+        ));
+    }
+}
@@ -707,19 +707,25 @@ function runPack(packId, scenario, outputDir) {
     }, null, 2)
   );
   
+  // Record each artifact's SHA-256 so the pack can be verified offline
+  const packFiles = [
+    'canonical/canonical.json',
+    'audit/audit.log',
+    'audit/audit_digest.json',
+    'verification/gate_report.canon.json',
+    'verification/gate_report.meta.json'
+  ];
   fs.writeFileSync(
     path.join(outputDir, 'manifest.json'),
     JSON.stringify({
+      schema_version: 'evidify.manifest.v1',
       pack_id: packId,
       scenario,
       created_at: '2026-01-01T00:00:00.000Z',  // Fixed for determinism
-      files: [
-        'canonical/canonical.json',
-        'audit/audit.log',
-        'audit/audit_digest.json',
-        'verification/gate_report.canon.json',
-        'verification/gate_report.meta.json'
-      ]
+      files: packFiles.map(f => ({
+        path: f,
+        sha256: sha256(fs.readFileSync(path.join(outputDir, f), 'utf8'))
+      }))
     }, null, 2)
   );
   